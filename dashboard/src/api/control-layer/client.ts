@@ -18,6 +18,7 @@ import type {
   GroupUpdateRequest,
   ModelUpdateRequest,
   ModelCreate,
+  ModelCloneRequest,
   EndpointCreateRequest,
   EndpointUpdateRequest,
   EndpointValidateRequest,
@@ -450,6 +451,21 @@ const modelApi = {
     return response.json();
   },
 
+  async clone(id: string, data: ModelCloneRequest): Promise<Model> {
+    const response = await fetch(`/admin/api/v1/models/${id}/clone`, {
+      method: "POST",
+      headers: { "Content-Type": "application/json" },
+      body: JSON.stringify(data),
+    });
+
+    if (!response.ok) {
+      const errorText = await response.text();
+      throw new Error(errorText || `Failed to clone model: ${response.status}`);
+    }
+
+    return response.json();
+  },
+
   async delete(id: string): Promise<void> {
     const response = await fetch(`/admin/api/v1/models/${id}`, {
       method: "DELETE",
@@ -6,16 +6,24 @@ export interface PaginatedResponse<T> {
   total_count: number;
   skip: number;
   limit: number;
+  // Present when using cursor pagination and another page follows
+  next_cursor?: string;
 }
 
-export type ModelType = "CHAT" | "EMBEDDINGS" | "RERANKER";
+export type ModelType = "CHAT" | "EMBEDDINGS" | "RERANKER" | "MODERATION";
 export type ModelDisplayCategory = "generation" | "embedding" | "ocr";
 
 // Virtual model types (virtual models route requests across multiple hosted models)
 export type LoadBalancingStrategy = "weighted_random" | "priority";
+export type SystemPromptMode = "prepend" | "merge";
+export type NLimitMode = "reject" | "clamp";
+export type CreditExhaustionMode = "complete" | "abort";
+export type PromptLengthUnit = "tokens" | "characters";
 
 export type JitterStrategy = "none" | "full";
 
+export type Modality = "text" | "image" | "audio";
+
 export type ReasoningEffort =
   | "none"
   | "minimal"
@@ -118,7 +126,9 @@ export type TariffApiKeyPurpose = "realtime" | "batch" | "playground";
 
 export type TrafficRoutingAction =
   | { type: "deny" }
-  | { type: "redirect"; target: string };
+  | { type: "redirect"; target: string }
+  | { type: "sanitize"; enabled: boolean }
+  | { type: "prefer_region"; region: string };
 
 export interface TrafficRoutingRule {
   api_key_purpose: ApiKeyPurpose;
@@ -177,6 +187,7 @@ export interface ModelProbeStatus {
   interval_seconds?: number;
   last_check?: string; // ISO 8601 timestamp
   last_success?: boolean;
+  healthy?: boolean; // Debounced by the probe's thresholds
   uptime_percentage?: number; // Last 24h uptime
 }
 
@@ -314,6 +325,10 @@ export interface Model {
   burst_size?: number | null; // Global rate limiting: burst capacity
   capacity?: number | null; // Maximum concurrent requests allowed
   batch_capacity?: number | null; // Maximum concurrent batch requests allowed
+  per_key_capacity?: number | null; // Maximum concurrent requests a single API key may hold
+  max_cost_per_request?: string | null; // Most a single request may cost, in credits
+  input_modalities?: Modality[] | null; // Content the model accepts (null = not checked)
+  output_modalities?: Modality[] | null; // Output the model produces (null = not checked)
   throughput?: number | null; // Throughput in requests/second for batch SLA capacity calculations
   groups?: Group[]; // array of group IDs - only present when include=groups
   metrics?: ModelMetrics; // only present when include=metrics
@@ -327,6 +342,20 @@ export interface Model {
   components?: ModelComponent[]; // only present when include=components
   sanitize_responses?: boolean | null; // only present for virtual models
   trusted?: boolean; // Mark provider as trusted in strict mode (bypasses error sanitization)
+  allow_public?: boolean; // Usable by every user without group membership
+  require_approval?: boolean; // Users also need an approved access request
+  rewrite_response_model?: boolean; // Responses report the requested alias as their model
+  disable_logging?: boolean; // Request and response bodies are kept out of request logs
+  warmup?: boolean; // Send a warmup request when the model becomes active
+  active?: boolean; // false = deactivated: requests are rejected, config kept
+  system_prompt?: string | null; // Injected into chat requests before forwarding
+  system_prompt_mode?: SystemPromptMode; // How the prompt combines with a client's system message
+  max_n?: number | null; // Most completions (`n`) one request may ask for
+  max_n_mode?: NLimitMode; // Whether a request above max_n is rejected or clamped
+  credit_exhaustion_mode?: CreditExhaustionMode; // What happens to a stream when the caller runs out of credits
+  max_overdraft?: string | null; // Credits a completing stream may charge below zero
+  max_prompt_length?: number | null; // Longest prompt a request may send, in max_prompt_length_unit
+  max_prompt_length_unit?: PromptLengthUnit; // Whether max_prompt_length counts tokens or characters
   open_responses_adapter?: boolean; // Enable adapter that converts /v1/responses to /v1/chat/completions
   reasoning_translation_overrides?: ReasoningTranslationOverrides | null;
   traffic_routing_rules?: TrafficRoutingRule[] | null;
@@ -348,8 +377,25 @@ export interface StandardModelCreate {
   burst_size?: number;
   capacity?: number;
   batch_capacity?: number;
+  per_key_capacity?: number;
+  max_cost_per_request?: string;
+  input_modalities?: Modality[];
+  output_modalities?: Modality[];
   throughput?: number;
   trusted?: boolean;
+  allow_public?: boolean;
+  require_approval?: boolean;
+  rewrite_response_model?: boolean;
+  disable_logging?: boolean;
+  warmup?: boolean;
+  system_prompt?: string;
+  system_prompt_mode?: SystemPromptMode;
+  max_n?: number;
+  max_n_mode?: NLimitMode;
+  credit_exhaustion_mode?: CreditExhaustionMode;
+  max_overdraft?: string;
+  max_prompt_length?: number;
+  max_prompt_length_unit?: PromptLengthUnit;
   open_responses_adapter?: boolean;
   reasoning_translation_overrides?: ReasoningTranslationOverrides;
   traffic_routing_rules?: TrafficRoutingRule[];
@@ -370,6 +416,10 @@ export interface VirtualModelCreate {
   burst_size?: number;
   capacity?: number;
   batch_capacity?: number;
+  per_key_capacity?: number;
+  max_cost_per_request?: string;
+  input_modalities?: Modality[];
+  output_modalities?: Modality[];
   throughput?: number;
   lb_strategy?: LoadBalancingStrategy;
   fallback_enabled?: boolean;
@@ -385,6 +435,19 @@ export interface VirtualModelCreate {
   backoff_jitter?: JitterStrategy;
   backoff_max_total_ms?: number | null;
   sanitize_responses?: boolean;
+  allow_public?: boolean;
+  require_approval?: boolean;
+  rewrite_response_model?: boolean;
+  disable_logging?: boolean;
+  warmup?: boolean;
+  system_prompt?: string;
+  system_prompt_mode?: SystemPromptMode;
+  max_n?: number;
+  max_n_mode?: NLimitMode;
+  credit_exhaustion_mode?: CreditExhaustionMode;
+  max_overdraft?: string;
+  max_prompt_length?: number;
+  max_prompt_length_unit?: PromptLengthUnit;
   traffic_routing_rules?: TrafficRoutingRule[];
   allowed_batch_completion_windows?: string[];
 }
@@ -394,6 +457,12 @@ export type CompositeModelCreate = VirtualModelCreate;
 
 export type ModelCreate = StandardModelCreate | VirtualModelCreate;
 
+export interface ModelCloneRequest {
+  alias: string;
+  display_name?: string; // defaults to the source model's
+  copy_groups?: boolean; // also grant the clone to the source's groups
+}
+
 export interface Endpoint {
   id: string; // UUID
   name: string;
@@ -407,6 +476,62 @@ export interface Endpoint {
   auth_header_name: string;
   auth_header_prefix: string;
   reasoning_translation?: ReasoningTranslationConfig | null;
+  protocol?: EndpointProtocol; // "openai" when absent
+  bedrock?: BedrockEndpointInfo; // Present for Bedrock endpoints; the secret is never returned
+  region?: string; // Region label for region-aware routing
+  body_transform?: BodyTransformConfig; // Declarative request/response body edits
+  auto_sync_interval_seconds?: number; // Background model sync interval; absent when auto-sync is off
+  alias_template?: string; // Alias template for synced models, e.g. "{name}"
+  max_concurrency?: number; // Requests in flight across all of the endpoint's models
+  tls?: EndpointTlsInfo; // Custom TLS settings; the client key is never returned
+  additional_api_key_count?: number; // Keys rotated between with the main key; the keys are never returned
+  api_key_rotation?: ApiKeyRotation;
+}
+
+// How requests to an endpoint are authenticated
+export type EndpointProtocol = "openai" | "bedrock";
+
+// How requests pick between an endpoint's API keys
+export type ApiKeyRotation = "round_robin" | "least_recently_used";
+
+// Field edits applied to JSON bodies; paths are JSON pointers like "/messages"
+export type BodyTransformOp =
+  | { op: "set"; path: string; value: unknown }
+  | { op: "set_default"; path: string; value: unknown }
+  | { op: "remove"; path: string }
+  | { op: "rename"; from: string; to: string }
+  | { op: "prepend"; path: string; value: unknown };
+
+export interface BodyTransformConfig {
+  request?: BodyTransformOp[]; // Applied before forwarding
+  response?: BodyTransformOp[]; // Applied to non-streaming JSON responses
+  tool_calling?: ToolCallingStyle; // Function calling shape the provider accepts
+}
+
+// "tools" = tools/tool_choice, "functions" = legacy functions/function_call
+export type ToolCallingStyle = "tools" | "functions";
+
+export interface BedrockCredentials {
+  region: string; // e.g. "us-east-1"
+  access_key_id: string;
+  secret_access_key: string;
+}
+
+export interface BedrockEndpointInfo {
+  region: string;
+  access_key_id: string;
+}
+
+// PEM-encoded TLS settings for providers behind a private CA or requiring mutual TLS
+export interface EndpointTlsSettings {
+  ca_bundle?: string; // Trusted in addition to the system roots
+  client_cert?: string; // Set together with client_key
+  client_key?: string; // PKCS#8 private key
+}
+
+export interface EndpointTlsInfo {
+  ca_bundle?: string;
+  client_cert?: string;
 }
 
 export interface EndpointSyncResponse {
@@ -455,6 +580,7 @@ export interface User {
   has_auto_topup_payment_method: boolean; // Whether user has a saved payment method for auto top-up
   auto_topup_monthly_limit: number | null; // Monthly spending limit for auto top-ups (null = no limit)
   zero_data_retention: boolean; // Account-wide zero-data-retention flag
+  timezone?: string; // IANA timezone that spending caps and the auto top-up monthly limit follow (default UTC)
   user_type?: "individual" | "organization"; // User type
   organizations?: OrganizationSummary[]; // only present when include=organizations or for current user
   active_organization_id?: string; // only present for /users/current
@@ -483,6 +609,9 @@ export interface ApiKey {
   spend?: string | null; // Spend counted against the cap in the current window (decimal string; null when uncapped)
   total_spend?: string | null; // Lifetime tracked spend for the cap scope (null when uncapped)
   resets_at?: string | null; // ISO 8601: next calendar reset (null for one-off caps / uncapped)
+  allowed_model_ids?: string[] | null; // Key may only use these models; null = no allowlist
+  denied_model_ids?: string[] | null; // Key may never use these models; null = no denylist
+  max_priority?: number; // Highest X-Priority (0-9) the key's requests may ask for
   // Note: actual key value only returned on creation
 }
 
@@ -536,7 +665,7 @@ export interface ModelsQuery {
   search?: string; // Search query to filter models by alias or model_name
   is_composite?: boolean; // Filter by composite/virtual model status (true = virtual, false = hosted)
   provider?: string; // Filter by provider name (case-insensitive exact match)
-  model_type?: ModelType; // Filter by model type (CHAT, EMBEDDINGS, RERANKER)
+  model_type?: ModelType; // Filter by model type (CHAT, EMBEDDINGS, RERANKER, MODERATION)
   capability?: string; // Filter to models with this capability
   sort?: ModelSortField; // Sort field (default: created_at)
   sort_direction?: SortDirection; // Sort direction (default depends on sort field)
@@ -575,6 +704,7 @@ export interface UserCreateRequest {
 export interface GroupCreateRequest {
   name: string;
   description?: string;
+  template_id?: string; // Access template whose models the group is granted
 }
 
 export interface ApiKeyCreateRequest {
@@ -585,6 +715,9 @@ export interface ApiKeyCreateRequest {
   burst_size?: number | null;
   spend_limit?: string | null; // Spending cap in credits (decimal string)
   spend_limit_interval?: SpendLimitInterval | null; // Requires spend_limit; null = one-off
+  allowed_model_ids?: string[] | null; // Narrows group access; never grants more
+  denied_model_ids?: string[] | null;
+  max_priority?: number; // 0-9; above 0 requires PlatformManager
 }
 
 // PATCH /users/{id}/api-keys/{keyId}. Cap fields are tri-state: omit the field
@@ -595,6 +728,9 @@ export interface ApiKeyUpdateRequest {
   spend_limit?: string | null;
   spend_limit_interval?: SpendLimitInterval | null;
   reset_window?: boolean; // Re-arm the cap now: zero the counted window spend
+  allowed_model_ids?: string[] | null;
+  denied_model_ids?: string[] | null;
+  max_priority?: number;
 }
 
 export interface ApiKeysQuery {
@@ -613,6 +749,7 @@ export interface UserUpdateRequest {
   auto_topup_threshold?: number | null; // Set a threshold to enable, null to disable
   auto_topup_monthly_limit?: number | null; // Set a limit to cap, null to remove limit
   zero_data_retention?: boolean; // Users may update this for their own account
+  timezone?: string; // IANA timezone name, e.g. "America/Los_Angeles"
 }
 
 export interface GroupUpdateRequest {
@@ -630,6 +767,10 @@ export interface ModelUpdateRequest {
   burst_size?: number | null;
   capacity?: number | null;
   batch_capacity?: number | null;
+  per_key_capacity?: number | null;
+  max_cost_per_request?: string | null;
+  input_modalities?: Modality[] | null;
+  output_modalities?: Modality[] | null;
   throughput?: number | null;
   tariffs?: TariffDefinition[];
   // Composite model fields
@@ -649,6 +790,19 @@ export interface ModelUpdateRequest {
   backoff_max_total_ms?: number | null;
   sanitize_responses?: boolean | null;
   trusted?: boolean | null;
+  allow_public?: boolean | null;
+  require_approval?: boolean | null;
+  rewrite_response_model?: boolean | null;
+  disable_logging?: boolean | null;
+  warmup?: boolean | null;
+  system_prompt?: string | null;
+  system_prompt_mode?: SystemPromptMode | null;
+  max_n?: number | null;
+  max_n_mode?: NLimitMode | null;
+  credit_exhaustion_mode?: CreditExhaustionMode | null;
+  max_overdraft?: string | null;
+  max_prompt_length?: number | null;
+  max_prompt_length_unit?: PromptLengthUnit | null;
   open_responses_adapter?: boolean | null;
   reasoning_translation_overrides?: ReasoningTranslationOverrides | null;
   traffic_routing_rules?: TrafficRoutingRule[] | null;
@@ -669,6 +823,16 @@ export interface EndpointCreateRequest {
   sync?: boolean; // Whether to sync models during creation (defaults to true)
   skip_fetch?: boolean; // Create deployments directly from model_filter without fetching (defaults to false)
   reasoning_translation?: ReasoningTranslationConfig;
+  protocol?: EndpointProtocol; // Defaults to "openai"
+  bedrock?: BedrockCredentials; // Required when protocol is "bedrock"; model_filter lists the Bedrock model IDs
+  region?: string; // Region label for region-aware routing
+  body_transform?: BodyTransformConfig;
+  auto_sync_interval_seconds?: number; // Minimum 60
+  alias_template?: string; // {model} = full model id, {name} = id without provider prefix
+  max_concurrency?: number; // Minimum 1
+  tls?: EndpointTlsSettings;
+  additional_api_keys?: string[]; // Rotated between with api_key
+  api_key_rotation?: ApiKeyRotation; // Defaults to "round_robin"
 }
 
 export interface EndpointUpdateRequest {
@@ -681,6 +845,15 @@ export interface EndpointUpdateRequest {
   auth_header_name?: string;
   auth_header_prefix?: string;
   reasoning_translation?: ReasoningTranslationConfig | null;
+  bedrock?: BedrockCredentials; // Rotate the credentials of a Bedrock endpoint
+  region?: string | null; // null clears the region label
+  body_transform?: BodyTransformConfig | null; // null clears the transform
+  auto_sync_interval_seconds?: number | null; // null disables auto-sync
+  alias_template?: string | null; // null clears the template
+  max_concurrency?: number | null; // null removes the limit
+  tls?: EndpointTlsSettings | null; // Replaces all TLS settings; null clears them
+  additional_api_keys?: string[]; // Replaces the rotated keys; [] stops rotating
+  api_key_rotation?: ApiKeyRotation;
 }
 
 export type EndpointValidateRequest =
@@ -1089,6 +1262,10 @@ export interface Transaction {
   transaction_type: TransactionType;
   batch_id?: string; // Batch ID (present when this is a grouped batch of multiple usage transactions)
   amount: number; // Amount in dollars
+  /** `amount` rounded half-to-even to the configured display precision */
+  display_amount?: number;
+  /** ISO 4217 currency code, e.g. "USD" */
+  currency?: string;
   source_id: string;
   description?: string;
   created_at: string; // ISO 8601 timestamp
@@ -1110,6 +1287,8 @@ export interface TransactionsListResponse {
   /** Current user balance when skip=0, or balance at the pagination point when skip>0.
    * Frontend can compute each row's balance by subtracting signed amounts from this value. */
   page_start_balance: number;
+  /** ISO 4217 currency code, e.g. "USD" */
+  currency?: string;
 }
 
 export interface TransactionsQuery {
@@ -1133,6 +1312,19 @@ export interface AddFundsRequest {
 export type AddFundsResponse = Transaction;
 
 // Probe types
+export interface JsonPathAssertion {
+  path: string;
+  equals?: any;
+}
+
+export interface ProbeAssertions {
+  expected_status?: number | null;
+  json_paths?: JsonPathAssertion[];
+  max_response_time_ms?: number | null;
+}
+
+export type ProbeType = "liveness" | "chat_completion";
+
 export interface Probe {
   id: string;
   name: string;
@@ -1142,6 +1334,12 @@ export interface Probe {
   http_method: string;
   request_path?: string | null;
   request_body?: Record<string, any> | null;
+  assertions?: ProbeAssertions | null;
+  probe_type: ProbeType;
+  expected_model?: string | null;
+  failure_threshold: number;
+  success_threshold: number;
+  recovery_cooldown_seconds: number;
   created_at: string;
   updated_at: string;
 }
@@ -1153,6 +1351,12 @@ export interface CreateProbeRequest {
   http_method?: string;
   request_path?: string | null;
   request_body?: Record<string, any> | null;
+  assertions?: ProbeAssertions | null;
+  probe_type?: ProbeType;
+  expected_model?: string | null;
+  failure_threshold?: number;
+  success_threshold?: number;
+  recovery_cooldown_seconds?: number;
 }
 
 export interface ProbeResult {
@@ -1714,6 +1918,7 @@ export interface OrganizationUpdateRequest {
   batch_notifications_enabled?: boolean;
   low_balance_threshold?: number | null;
   zero_data_retention?: boolean; // Account-wide zero-data-retention flag (admin-only)
+  timezone?: string; // IANA timezone name, e.g. "Europe/Berlin"
 }
 
 export interface InviteMemberRequest {
@@ -23,6 +23,7 @@ const MODEL_TYPE_LABELS: Record<string, string> = {
   CHAT: "Generation",
   EMBEDDINGS: "Embedding",
   RERANKER: "Reranker",
+  MODERATION: "Moderation",
 };
 
 function ModelPricing({ model }: { model: Model }) {
@@ -349,6 +349,15 @@ const ModelInfo: React.FC = () => {
           },
         };
       }
+      if (rule.action.type === "prefer_region") {
+        return {
+          ...rule,
+          action: {
+            type: "prefer_region" as const,
+            region: rule.action.region.trim(),
+          },
+        };
+      }
       return rule;
     });
 
@@ -362,6 +371,17 @@ const ModelInfo: React.FC = () => {
       return;
     }
 
+    if (
+      normalizedTrafficRules.some(
+        (rule) =>
+          rule.action.type === "prefer_region" &&
+          rule.action.region.length === 0,
+      )
+    ) {
+      setSettingsError("Prefer region rules must include a region");
+      return;
+    }
+
     try {
       // For standard (single-provider) models, the backoff toggle implicitly
       // controls fallback + with_replacement too — otherwise SelectIter
@@ -1528,13 +1548,28 @@ const ModelInfo: React.FC = () => {
                               </div>
                               <div className="md:col-span-3">
                                 <Select
-                                  value={rule.action.type}
+                                  value={
+                                    rule.action.type === "sanitize"
+                                      ? rule.action.enabled
+                                        ? "sanitize"
+                                        : "raw"
+                                      : rule.action.type
+                                  }
                                   onValueChange={(value) =>
                                     setUpdateData((prev) => ({
                                       ...prev,
                                       traffic_routing_rules:
                                         prev.traffic_routing_rules.map((r, i) => {
                                           if (i !== index) return r;
+                                          if (value === "sanitize" || value === "raw") {
+                                            return {
+                                              ...r,
+                                              action: {
+                                                type: "sanitize",
+                                                enabled: value === "sanitize",
+                                              },
+                                            };
+                                          }
                                           if (value === "redirect") {
                                             return {
                                               ...r,
@@ -1547,6 +1582,18 @@ const ModelInfo: React.FC = () => {
                                               },
                                             };
                                           }
+                                          if (value === "prefer_region") {
+                                            return {
+                                              ...r,
+                                              action: {
+                                                type: "prefer_region",
+                                                region:
+                                                  r.action.type === "prefer_region"
+                                                    ? r.action.region
+                                                    : "",
+                                              },
+                                            };
+                                          }
                                           return {
                                             ...r,
                                             action: { type: "deny" },
@@ -1563,6 +1610,15 @@ const ModelInfo: React.FC = () => {
                                     <SelectItem value="redirect">
                                       Redirect
                                     </SelectItem>
+                                    <SelectItem value="sanitize">
+                                      Sanitize responses
+                                    </SelectItem>
+                                    <SelectItem value="raw">
+                                      Raw responses
+                                    </SelectItem>
+                                    <SelectItem value="prefer_region">
+                                      Prefer region
+                                    </SelectItem>
                                   </SelectContent>
                                 </Select>
                               </div>
@@ -1597,6 +1653,35 @@ const ModelInfo: React.FC = () => {
                                       model.id !== modelId
                                     }
                                   />
+                                ) : rule.action.type === "sanitize" ? (
+                                  <p className="text-xs text-muted-foreground h-10 flex items-center">
+                                    {rule.action.enabled
+                                      ? "Sanitize responses for this purpose"
+                                      : "Return upstream responses unmodified"}
+                                  </p>
+                                ) : rule.action.type === "prefer_region" ? (
+                                  <Input
+                                    value={rule.action.region}
+                                    onChange={(e) =>
+                                      setUpdateData((prev) => ({
+                                        ...prev,
+                                        traffic_routing_rules:
+                                          prev.traffic_routing_rules.map((r, i) =>
+                                            i === index
+                                              ? {
+                                                  ...r,
+                                                  action: {
+                                                    type: "prefer_region",
+                                                    region: e.target.value,
+                                                  },
+                                                }
+                                              : r,
+                                          ),
+                                      }))
+                                    }
+                                    placeholder="Endpoint region, e.g. eu-west"
+                                    className="w-full font-mono text-xs"
+                                  />
                                 ) : (
                                   <p className="text-xs text-muted-foreground h-10 flex items-center">
                                     Return 403 Forbidden
@@ -2465,6 +2550,19 @@ const ModelInfo: React.FC = () => {
                                       <span className="text-muted-foreground">→</span>
                                       {rule.action.type === "deny" ? (
                                         <span className="font-medium">deny</span>
+                                      ) : rule.action.type === "sanitize" ? (
+                                        <span className="font-medium">
+                                          {rule.action.enabled
+                                            ? "sanitize responses"
+                                            : "raw responses"}
+                                        </span>
+                                      ) : rule.action.type === "prefer_region" ? (
+                                        <span className="font-medium">
+                                          prefer region
+                                          <span className="ml-1 font-mono text-xs">
+                                            {rule.action.region}
+                                          </span>
+                                        </span>
                                       ) : (
                                         <span className="font-medium">
                                           redirect to
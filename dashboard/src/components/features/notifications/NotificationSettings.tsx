@@ -120,26 +120,43 @@ const PLATFORM_EVENT_TYPE_OPTIONS = [
   }
 }`,
   },
-  {
-    value: "api_key.created",
-    label: "API key created",
-    example: `{
-  "type": "api_key.created",
+];
+
+// Platform events that own-scoped webhooks can also subscribe to, for the
+// owner's keys. Own-scoped webhooks only receive them when listed explicitly.
+const API_KEY_EVENT_TYPE_OPTIONS = (
+  [
+    ["api_key.created", "API key created"],
+    ["api_key.rotated", "API key rotated"],
+    ["api_key.deleted", "API key deleted"],
+  ] as const
+).map(([value, label]) => ({
+  value,
+  label,
+  example: `{
+  "type": "${value}",
   "timestamp": "2025-01-15T10:30:00Z",
   "data": {
     "api_key_id": "key_abc123",
     "user_id": "usr_abc123",
     "created_by": "usr_def456",
-    "name": "My API Key"
+    "name": "My API Key",
+    "purpose": "realtime"
   }
 }`,
-  },
-];
+}));
 
 function eventTypeOptionsForScope(scope: WebhookScope) {
   return scope === "platform"
-    ? PLATFORM_EVENT_TYPE_OPTIONS
-    : OWN_EVENT_TYPE_OPTIONS;
+    ? [...PLATFORM_EVENT_TYPE_OPTIONS, ...API_KEY_EVENT_TYPE_OPTIONS]
+    : [...OWN_EVENT_TYPE_OPTIONS, ...API_KEY_EVENT_TYPE_OPTIONS];
+}
+
+// The event types a webhook without an event filter receives
+function defaultEventTypesForScope(scope: WebhookScope) {
+  return scope === "platform"
+    ? eventTypeOptionsForScope(scope).map((o) => o.value)
+    : OWN_EVENT_TYPE_OPTIONS.map((o) => o.value);
 }
 
 interface NotificationSettingsProps {
@@ -235,7 +252,7 @@ export const NotificationSettings: React.FC<NotificationSettingsProps> = ({
     setWebhookScope("own");
     setWebhookUrl("");
     setWebhookDescription("");
-    setWebhookEventTypes(OWN_EVENT_TYPE_OPTIONS.map((o) => o.value));
+    setWebhookEventTypes(defaultEventTypesForScope("own"));
     setWebhookSecret(null);
     setWebhookError("");
     setWebhookDialogOpen(true);
@@ -247,11 +264,10 @@ export const NotificationSettings: React.FC<NotificationSettingsProps> = ({
     setWebhookScope(scope);
     setWebhookUrl(webhook.url);
     setWebhookDescription(webhook.description || "");
-    const options = eventTypeOptionsForScope(scope);
     setWebhookEventTypes(
       webhook.event_types && webhook.event_types.length > 0
         ? webhook.event_types
-        : options.map((o) => o.value),
+        : defaultEventTypesForScope(scope),
     );
     setWebhookSecret(null);
     setWebhookError("");
@@ -268,8 +284,7 @@ export const NotificationSettings: React.FC<NotificationSettingsProps> = ({
 
   const handleScopeChange = (scope: WebhookScope) => {
     setWebhookScope(scope);
-    const options = eventTypeOptionsForScope(scope);
-    setWebhookEventTypes(options.map((o) => o.value));
+    setWebhookEventTypes(defaultEventTypesForScope(scope));
   };
 
   const handleWebhookSave = async () => {
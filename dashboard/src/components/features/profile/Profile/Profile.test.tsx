@@ -378,12 +378,15 @@ describe("Webhooks", () => {
       ),
     ).toBeInTheDocument();
 
-    // Event types are checked by default
+    // Batch events are checked by default; API key events are opt-in
     const checkboxes = within(dialog).getAllByRole("checkbox");
-    expect(checkboxes).toHaveLength(2);
-    checkboxes.forEach((cb) => {
+    expect(checkboxes).toHaveLength(5);
+    checkboxes.slice(0, 2).forEach((cb) => {
       expect(cb).toHaveAttribute("data-state", "checked");
     });
+    checkboxes.slice(2).forEach((cb) => {
+      expect(cb).toHaveAttribute("data-state", "unchecked");
+    });
 
     // Try to create with empty URL -> validation error
     await user.click(
@@ -606,6 +609,7 @@ describe("Webhook Scope", () => {
     expect(within(dialog).getByText("Batch completed")).toBeInTheDocument();
     expect(within(dialog).getByText("Batch failed")).toBeInTheDocument();
     expect(within(dialog).queryByText("User created")).not.toBeInTheDocument();
+    expect(within(dialog).getByText("API key created")).toBeInTheDocument();
 
     // Switch to platform scope
     await user.click(within(dialog).getByRole("tab", { name: "Platform" }));
@@ -206,6 +206,7 @@ export const CreateVirtualModelModal: React.FC<
                 <SelectItem value="CHAT">Chat</SelectItem>
                 <SelectItem value="EMBEDDINGS">Embeddings</SelectItem>
                 <SelectItem value="RERANKER">Reranker</SelectItem>
+                <SelectItem value="MODERATION">Moderation</SelectItem>
               </SelectContent>
             </Select>
           </div>
@@ -1,6 +1,40 @@
 /// Build script - the frontend is built before compiling release images.
 /// Rust-only checks can reuse the already generated static directory.
+///
+/// Also records build metadata for the `/version` endpoint. The git SHA comes from
+/// `DWCTL_GIT_SHA` when set (container builds have no `.git`), else from `git rev-parse`.
 fn main() {
     // Tell Cargo to rerun this build script if the static directory changes
     println!("cargo:rerun-if-changed=static");
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+    println!("cargo:rerun-if-changed=../.git/refs/heads");
+    println!("cargo:rerun-if-env-changed=DWCTL_GIT_SHA");
+
+    let git_sha = std::env::var("DWCTL_GIT_SHA")
+        .ok()
+        .filter(|sha| !sha.trim().is_empty())
+        .or_else(|| {
+            std::process::Command::new("git")
+                .args(["rev-parse", "HEAD"])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .and_then(|output| String::from_utf8(output.stdout).ok())
+        })
+        .map(|sha| sha.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=DWCTL_GIT_SHA={git_sha}");
+
+    // Honour SOURCE_DATE_EPOCH for reproducible builds
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    let build_epoch = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0)
+        });
+    println!("cargo:rustc-env=DWCTL_BUILD_EPOCH={build_epoch}");
 }
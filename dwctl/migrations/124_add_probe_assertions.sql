@@ -0,0 +1,6 @@
+-- Content assertions evaluated against each probe response: an expected status
+-- code, JSON Pointer checks on the body, and a latency budget. NULL keeps the
+-- original liveness behaviour (any 2xx with a non-error JSON body passes).
+ALTER TABLE probes ADD COLUMN assertions JSONB;
+
+COMMENT ON COLUMN probes.assertions IS 'Assertions a probe response must satisfy (expected_status, json_paths, max_response_time_ms)';
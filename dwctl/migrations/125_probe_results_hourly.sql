@@ -0,0 +1,25 @@
+-- Hourly rollups of probe results older than the raw retention window.
+--
+-- The leader-only retention job moves raw probe_results rows past the cutoff
+-- into one row per (probe, hour) and deletes the originals, so probe_results
+-- stays bounded while long-range statistics keep working. Raw rows and buckets
+-- never overlap: a result is either still raw or already folded into its hour.
+--
+-- Latency columns cover successful executions only, matching how statistics
+-- are computed from raw results. avg_response_time_ms is weighted by
+-- success_count when buckets are merged.
+CREATE TABLE probe_results_hourly (
+    probe_id UUID NOT NULL REFERENCES probes(id) ON DELETE CASCADE,
+    bucket_start TIMESTAMPTZ NOT NULL,
+    total_count BIGINT NOT NULL,
+    success_count BIGINT NOT NULL,
+    min_response_time_ms INTEGER,
+    max_response_time_ms INTEGER,
+    avg_response_time_ms DOUBLE PRECISION,
+    last_execution_at TIMESTAMPTZ NOT NULL,
+    last_success_at TIMESTAMPTZ,
+    last_failure_at TIMESTAMPTZ,
+    PRIMARY KEY (probe_id, bucket_start)
+);
+
+CREATE INDEX idx_probe_results_hourly_bucket_start ON probe_results_hourly(bucket_start);
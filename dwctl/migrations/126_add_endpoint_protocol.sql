@@ -0,0 +1,17 @@
+-- Endpoint protocol: how requests to the endpoint are authenticated.
+-- 'openai' endpoints send api_key as a bearer (or custom) header; 'bedrock'
+-- endpoints sign each request with AWS SigV4 using the credentials below.
+-- The secret access key is encrypted with the connections encryption key.
+
+ALTER TABLE inference_endpoints
+    ADD COLUMN protocol TEXT NOT NULL DEFAULT 'openai'
+        CHECK (protocol IN ('openai', 'bedrock')),
+    ADD COLUMN aws_region TEXT,
+    ADD COLUMN aws_access_key_id TEXT,
+    ADD COLUMN aws_secret_access_key_encrypted BYTEA;
+
+ALTER TABLE inference_endpoints
+    ADD CONSTRAINT inference_endpoints_bedrock_credentials CHECK (
+        protocol <> 'bedrock'
+        OR (aws_region IS NOT NULL AND aws_access_key_id IS NOT NULL AND aws_secret_access_key_encrypted IS NOT NULL)
+    );
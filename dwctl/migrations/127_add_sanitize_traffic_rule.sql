@@ -0,0 +1,15 @@
+-- Traffic rules can override a model's sanitize_responses setting per API key
+-- purpose. 'sanitize' rules carry the override in sanitize_responses; deny and
+-- redirect rules leave it NULL.
+
+ALTER TABLE model_traffic_rules
+    ADD COLUMN sanitize_responses BOOLEAN;
+
+ALTER TABLE model_traffic_rules DROP CONSTRAINT valid_action;
+
+ALTER TABLE model_traffic_rules
+    ADD CONSTRAINT valid_action CHECK (
+        (action = 'deny' AND redirect_target_id IS NULL AND sanitize_responses IS NULL) OR
+        (action = 'redirect' AND redirect_target_id IS NOT NULL AND sanitize_responses IS NULL) OR
+        (action = 'sanitize' AND redirect_target_id IS NULL AND sanitize_responses IS NOT NULL)
+    );
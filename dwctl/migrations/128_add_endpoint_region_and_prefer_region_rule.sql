@@ -0,0 +1,20 @@
+-- Region labels for multi-region routing. An endpoint's region is attached to
+-- the providers it hosts; requests prefer providers in the region named by a
+-- client hint header or a 'prefer_region' traffic rule, falling back to the
+-- other regions when none are available.
+
+ALTER TABLE inference_endpoints
+    ADD COLUMN region TEXT;
+
+ALTER TABLE model_traffic_rules
+    ADD COLUMN prefer_region TEXT;
+
+ALTER TABLE model_traffic_rules DROP CONSTRAINT valid_action;
+
+ALTER TABLE model_traffic_rules
+    ADD CONSTRAINT valid_action CHECK (
+        (action = 'deny' AND redirect_target_id IS NULL AND sanitize_responses IS NULL AND prefer_region IS NULL) OR
+        (action = 'redirect' AND redirect_target_id IS NOT NULL AND sanitize_responses IS NULL AND prefer_region IS NULL) OR
+        (action = 'sanitize' AND redirect_target_id IS NULL AND sanitize_responses IS NOT NULL AND prefer_region IS NULL) OR
+        (action = 'prefer_region' AND redirect_target_id IS NULL AND sanitize_responses IS NULL AND prefer_region IS NOT NULL)
+    );
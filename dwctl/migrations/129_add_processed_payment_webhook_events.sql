@@ -0,0 +1,9 @@
+-- Payment provider webhook events that have been handled, keyed on the
+-- provider's event id. Providers retry deliveries until they see a 2xx, so a
+-- redelivered event is acknowledged without being processed a second time.
+
+CREATE TABLE processed_payment_webhook_events (
+    event_id TEXT PRIMARY KEY,
+    event_type TEXT NOT NULL,
+    processed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,6 @@
+-- Fair sharing of a deployment's concurrency capacity: the most concurrent
+-- requests any one API key may hold against the model, so a single caller
+-- can't occupy every slot. NULL means no per-key cap.
+
+ALTER TABLE deployed_models
+ADD COLUMN per_key_capacity INTEGER DEFAULT NULL CHECK (per_key_capacity > 0);
@@ -0,0 +1,5 @@
+-- Declarative request/response body transforms applied to the models hosted
+-- on an endpoint (field set/remove/rename and array prepend operations).
+
+ALTER TABLE inference_endpoints
+    ADD COLUMN body_transform JSONB;
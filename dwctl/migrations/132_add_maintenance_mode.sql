@@ -0,0 +1,14 @@
+-- Platform-wide maintenance mode. While enabled, the AI proxy (/ai/v1/*)
+-- rejects requests with a 503 and Retry-After; the admin API keeps working.
+-- A single row, so the setting survives restarts and is shared by replicas.
+
+CREATE TABLE maintenance_mode (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    message TEXT,
+    retry_after_seconds INTEGER NOT NULL DEFAULT 300 CHECK (retry_after_seconds > 0),
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO maintenance_mode (id) VALUES (TRUE);
@@ -0,0 +1,11 @@
+-- Probe type: 'liveness' keeps the original behaviour (a type-derived or custom
+-- request that passes on any 2xx non-error JSON body). 'chat_completion' sends a
+-- tiny canned chat request and also requires a well-formed completion with
+-- non-empty content, optionally from an expected model.
+ALTER TABLE probes
+    ADD COLUMN probe_type TEXT NOT NULL DEFAULT 'liveness'
+        CONSTRAINT probes_probe_type_check CHECK (probe_type IN ('liveness', 'chat_completion')),
+    ADD COLUMN expected_model TEXT;
+
+COMMENT ON COLUMN probes.probe_type IS 'liveness (any healthy JSON response) or chat_completion (validated chat completion)';
+COMMENT ON COLUMN probes.expected_model IS 'For chat_completion probes, the model the response must report';
@@ -0,0 +1,56 @@
+-- Per-user timezone for calendar-aligned quota windows.
+--
+-- API-key spending caps (migration 123) and the monthly auto top-up limit
+-- used UTC calendar boundaries. Customers outside UTC want a 'daily' cap to
+-- reset at their local midnight, so each user now carries an IANA timezone
+-- name (validated against pg_timezone_names when set through the API) and
+-- the window helpers take the cap owner's timezone. Existing users default
+-- to UTC, which reproduces the previous behaviour exactly.
+
+ALTER TABLE users ADD COLUMN timezone TEXT NOT NULL DEFAULT 'UTC';
+
+-- The helpers gain a timezone argument. Drop the UTC-only versions rather
+-- than overloading them so a caller that was missed fails loudly instead of
+-- silently disagreeing with the others about window membership.
+DROP FUNCTION api_key_cap_window_current(timestamptz, text);
+DROP FUNCTION api_key_cap_window_resets_at(text);
+
+CREATE FUNCTION api_key_cap_window_current(started_at timestamptz, cap_interval text, tz text)
+RETURNS boolean
+LANGUAGE sql
+STABLE
+AS $$
+  SELECT CASE
+    -- No checkpoint yet: nothing has been folded, so there is no current window.
+    WHEN started_at IS NULL THEN false
+    -- One-off cap: the window never expires.
+    WHEN cap_interval IS NULL THEN true
+    WHEN cap_interval = 'daily'
+      THEN date_trunc('day',   started_at AT TIME ZONE tz) = date_trunc('day',   now() AT TIME ZONE tz)
+    WHEN cap_interval = 'weekly'
+      THEN date_trunc('week',  started_at AT TIME ZONE tz) = date_trunc('week',  now() AT TIME ZONE tz)
+    WHEN cap_interval = 'monthly'
+      THEN date_trunc('month', started_at AT TIME ZONE tz) = date_trunc('month', now() AT TIME ZONE tz)
+    -- Unknown intervals are prevented by the CHECK constraint on api_keys;
+    -- treat defensively as one-off.
+    ELSE true
+  END
+$$;
+
+-- Next calendar boundary in the owner's timezone. Adding the interval to the
+-- local timestamp keeps the boundary at local midnight across DST changes.
+CREATE FUNCTION api_key_cap_window_resets_at(cap_interval text, tz text)
+RETURNS timestamptz
+LANGUAGE sql
+STABLE
+AS $$
+  SELECT CASE
+    WHEN cap_interval = 'daily'
+      THEN (date_trunc('day',   now() AT TIME ZONE tz) + interval '1 day')   AT TIME ZONE tz
+    WHEN cap_interval = 'weekly'
+      THEN (date_trunc('week',  now() AT TIME ZONE tz) + interval '1 week')  AT TIME ZONE tz
+    WHEN cap_interval = 'monthly'
+      THEN (date_trunc('month', now() AT TIME ZONE tz) + interval '1 month') AT TIME ZONE tz
+    ELSE NULL
+  END
+$$;
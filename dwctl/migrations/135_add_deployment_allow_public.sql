@@ -0,0 +1,10 @@
+-- Explicit public access for deployments.
+--
+-- Public access used to be expressed only by assigning a deployment to the
+-- nil-UUID "everyone" group, which is implicit and easy to get wrong. The
+-- allow_public flag states it directly: every user can reach the model
+-- without group membership. Assignments to the everyone group still grant
+-- access for compatibility, so existing deployments are not backfilled;
+-- removing such an assignment keeps restricting access as it always has.
+
+ALTER TABLE deployed_models ADD COLUMN allow_public BOOLEAN NOT NULL DEFAULT FALSE;
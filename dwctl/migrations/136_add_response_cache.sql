@@ -0,0 +1,23 @@
+-- Response caching for deterministic chat completions.
+--
+-- A *cache, not a ledger*: a hit is served without an upstream call and without
+-- being logged or billed, so losing an entry only costs one more (billed) call.
+--
+-- Scope (deployment_id, principal_id, request_hash) keys each entry:
+--   - deployment_id = the deployment the alias resolved to. Deleting the
+--                     deployment drops its entries.
+--   - principal_id  = the billing principal (api_keys.user_id), so one account's
+--                     completions are never served to another.
+--   - request_hash  = SHA-256 of the request body with object keys sorted.
+CREATE TABLE response_cache_entries (
+    deployment_id UUID        NOT NULL REFERENCES deployed_models(id) ON DELETE CASCADE,
+    principal_id  UUID        NOT NULL,
+    request_hash  BYTEA       NOT NULL,
+    response_body BYTEA       NOT NULL,   -- exact upstream bytes, replayed verbatim on a hit
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT now(),
+    expires_at    TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (deployment_id, principal_id, request_hash)
+);
+
+-- Expired entries are swept on the write path.
+CREATE INDEX idx_response_cache_entries_expires_at ON response_cache_entries (expires_at);
@@ -0,0 +1,7 @@
+-- Per-request cost guard: the most a single request to the model may cost, in
+-- credits. Requests whose worst case (max tokens at the output price) exceeds
+-- it are rejected before forwarding, and streams are cut off once their
+-- accrued cost crosses it. NULL means no limit.
+
+ALTER TABLE deployed_models
+ADD COLUMN max_cost_per_request DECIMAL(12, 8) DEFAULT NULL CHECK (max_cost_per_request > 0);
@@ -0,0 +1,9 @@
+-- Report the requested alias as the response `model`.
+--
+-- Upstreams name the model that served a request, which for an aliased or
+-- composite deployment is the underlying model rather than the alias the
+-- client asked for. When rewrite_response_model is set, onwards replaces the
+-- `model` field of success responses with the alias without otherwise
+-- sanitizing them. Strict mode always does this, regardless of the flag.
+
+ALTER TABLE deployed_models ADD COLUMN rewrite_response_model BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,15 @@
+-- Periodic model list synchronization per endpoint.
+--
+-- When auto_sync_interval_seconds is set, a background job on the leader
+-- re-syncs the endpoint's models once the interval has elapsed since its last
+-- automatic sync. The last run is tracked in its own table so that auto-syncs
+-- do not touch inference_endpoints, whose updates bump updated_at and fire a
+-- config-change notification.
+
+ALTER TABLE inference_endpoints
+    ADD COLUMN auto_sync_interval_seconds INTEGER CHECK (auto_sync_interval_seconds > 0);
+
+CREATE TABLE inference_endpoint_auto_syncs (
+    endpoint_id UUID PRIMARY KEY REFERENCES inference_endpoints(id) ON DELETE CASCADE,
+    last_synced_at TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,32 @@
+-- Notify when a probe result flips its deployment's health, so the admin
+-- config event stream can report health changes without polling.
+--
+-- Uses its own channel: onwards doesn't route on probe results, so these
+-- must not trigger an onwards config reload. Only flips (or a probe's first
+-- result) notify; steady-state results are silent.
+
+CREATE OR REPLACE FUNCTION notify_probe_health_change() RETURNS trigger AS $$
+DECLARE
+    previous_success boolean;
+BEGIN
+    SELECT success INTO previous_success
+    FROM probe_results
+    WHERE probe_id = NEW.probe_id
+      AND id <> NEW.id
+      AND executed_at <= NEW.executed_at
+    ORDER BY executed_at DESC
+    LIMIT 1;
+
+    IF previous_success IS DISTINCT FROM NEW.success THEN
+        PERFORM pg_notify('probe_health_changed', json_build_object(
+            'probe_id', NEW.probe_id,
+            'success', NEW.success
+        )::text);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER probe_results_health_notify
+AFTER INSERT ON probe_results
+FOR EACH ROW EXECUTE FUNCTION notify_probe_health_change();
@@ -0,0 +1,64 @@
+-- Per-probe health thresholds and debounced health state.
+--
+-- A probe's health used to be its latest result. Now the scheduler runs each
+-- result through a small state machine: a healthy probe turns unhealthy after
+-- failure_threshold consecutive failures, and an unhealthy one recovers after
+-- success_threshold consecutive successes once recovery_cooldown_seconds have
+-- passed since it went unhealthy. The defaults (1, 1, 0) keep the old
+-- behaviour.
+
+ALTER TABLE probes
+    ADD COLUMN failure_threshold INTEGER NOT NULL DEFAULT 1,
+    ADD COLUMN success_threshold INTEGER NOT NULL DEFAULT 1,
+    ADD COLUMN recovery_cooldown_seconds INTEGER NOT NULL DEFAULT 0,
+    ADD CONSTRAINT probes_failure_threshold_positive CHECK (failure_threshold >= 1),
+    ADD CONSTRAINT probes_success_threshold_positive CHECK (success_threshold >= 1),
+    ADD CONSTRAINT probes_recovery_cooldown_non_negative CHECK (recovery_cooldown_seconds >= 0);
+
+-- Kept out of the probes table so per-result updates don't fire the
+-- probe_changes trigger the scheduler listens on.
+CREATE TABLE probe_health (
+    probe_id UUID PRIMARY KEY REFERENCES probes(id) ON DELETE CASCADE,
+    healthy BOOLEAN NOT NULL,
+    consecutive_successes INTEGER NOT NULL DEFAULT 0,
+    consecutive_failures INTEGER NOT NULL DEFAULT 0,
+    -- When `healthy` last changed; the recovery cooldown counts from here.
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Seed existing probes from their latest result, matching the old behaviour.
+INSERT INTO probe_health (probe_id, healthy, consecutive_successes, consecutive_failures, changed_at)
+SELECT
+    p.id,
+    r.success,
+    CASE WHEN r.success THEN 1 ELSE 0 END,
+    CASE WHEN r.success THEN 0 ELSE 1 END,
+    r.executed_at
+FROM probes p
+JOIN LATERAL (
+    SELECT success, executed_at
+    FROM probe_results
+    WHERE probe_id = p.id
+    ORDER BY executed_at DESC
+    LIMIT 1
+) r ON true;
+
+-- Health changes are now decided by the state machine, so notify from
+-- probe_health instead of from raw results.
+DROP TRIGGER probe_results_health_notify ON probe_results;
+
+CREATE OR REPLACE FUNCTION notify_probe_health_change() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'INSERT' OR OLD.healthy IS DISTINCT FROM NEW.healthy THEN
+        PERFORM pg_notify('probe_health_changed', json_build_object(
+            'probe_id', NEW.probe_id,
+            'healthy', NEW.healthy
+        )::text);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER probe_health_notify
+AFTER INSERT OR UPDATE ON probe_health
+FOR EACH ROW EXECUTE FUNCTION notify_probe_health_change();
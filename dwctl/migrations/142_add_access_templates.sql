@@ -0,0 +1,26 @@
+-- Access templates: named sets of deployments ("tiers") that can be applied to
+-- a group, at creation or later. Applying a template grants the group access to
+-- the template's deployments through deployment_groups; the template itself
+-- plays no part in routing, so these tables don't notify the config sync.
+
+CREATE TABLE access_templates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR NOT NULL UNIQUE,
+    description TEXT,
+    created_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER update_access_templates_updated_at
+    BEFORE UPDATE ON access_templates
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE TABLE access_template_deployments (
+    template_id UUID NOT NULL REFERENCES access_templates(id) ON DELETE CASCADE,
+    deployment_id UUID NOT NULL REFERENCES deployed_models(id) ON DELETE CASCADE,
+    PRIMARY KEY (template_id, deployment_id)
+);
+
+CREATE INDEX idx_access_template_deployments_deployment_id ON access_template_deployments(deployment_id);
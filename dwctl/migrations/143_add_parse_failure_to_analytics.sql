@@ -0,0 +1,4 @@
+-- Set when a successful upstream response couldn't be parsed (e.g. truncated
+-- JSON), so its usage is unknown. Such rows carry zero tokens, or an estimate
+-- when analytics.estimate_tokens_on_parse_failure is enabled.
+ALTER TABLE http_analytics ADD COLUMN parse_failure BOOLEAN NOT NULL DEFAULT false;
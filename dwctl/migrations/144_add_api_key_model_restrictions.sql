@@ -0,0 +1,55 @@
+-- Per-API-key model restrictions, applied on top of group-derived access.
+--
+-- allowed_model_ids: when non-NULL, the key may only use these deployments.
+-- denied_model_ids:  the key may never use these deployments.
+-- NULL means "no restriction". Restrictions live on the scope root (the
+-- visible key); cap-scope child keys inherit their parent's lists through
+-- COALESCE(parent_api_key_id, id), the same as spending caps. Neither list can
+-- grant access the key's owner doesn't already have through their groups.
+ALTER TABLE api_keys
+  ADD COLUMN allowed_model_ids UUID[] NULL,
+  ADD COLUMN denied_model_ids  UUID[] NULL;
+
+-- Extend the scoped api_keys UPDATE-notify (migration 122) with the new
+-- columns: editing a key's restrictions changes which deployments it appears
+-- under in the onwards key set.
+CREATE OR REPLACE FUNCTION notify_api_keys_config_change() RETURNS trigger AS $$
+DECLARE
+    relevant_change boolean := false;
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        relevant_change := EXISTS (SELECT 1 FROM new_rows);
+    ELSIF TG_OP = 'DELETE' THEN
+        relevant_change := EXISTS (SELECT 1 FROM old_rows);
+    ELSIF TG_OP = 'UPDATE' THEN
+        -- Only columns the sync query reads matter; metadata-only updates
+        -- (name, description, last_used) must not reload the cache. Joined on the
+        -- immutable primary key.
+        relevant_change := EXISTS (
+            SELECT 1
+            FROM new_rows n
+            JOIN old_rows o ON o.id = n.id
+            WHERE o.secret               IS DISTINCT FROM n.secret
+               OR o.purpose              IS DISTINCT FROM n.purpose
+               OR o.user_id              IS DISTINCT FROM n.user_id
+               OR o.requests_per_second  IS DISTINCT FROM n.requests_per_second
+               OR o.burst_size           IS DISTINCT FROM n.burst_size
+               OR o.is_deleted           IS DISTINCT FROM n.is_deleted
+               OR o.hidden               IS DISTINCT FROM n.hidden
+               OR o.spend_limit          IS DISTINCT FROM n.spend_limit
+               OR o.spend_limit_interval IS DISTINCT FROM n.spend_limit_interval
+               OR o.parent_api_key_id    IS DISTINCT FROM n.parent_api_key_id
+               OR o.allowed_model_ids    IS DISTINCT FROM n.allowed_model_ids
+               OR o.denied_model_ids     IS DISTINCT FROM n.denied_model_ids
+        );
+    END IF;
+
+    IF relevant_change THEN
+        -- Match notify_config_change()'s payload format (migration 049) so the
+        -- cache-sync lag metric keeps attributing reloads to the api_keys table.
+        PERFORM pg_notify('auth_config_changed',
+            'api_keys:' || (extract(epoch FROM clock_timestamp()) * 1000000)::bigint::text);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
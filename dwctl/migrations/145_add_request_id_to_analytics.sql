@@ -0,0 +1,5 @@
+-- The X-Request-Id returned to the client, either supplied with the request or
+-- generated by dwctl, so a client-reported id can be traced to its row.
+ALTER TABLE http_analytics ADD COLUMN request_id TEXT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_analytics_request_id ON http_analytics (request_id) WHERE request_id IS NOT NULL;
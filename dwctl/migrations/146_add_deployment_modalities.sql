@@ -0,0 +1,8 @@
+-- Modalities a model accepts and produces ('text', 'image', 'audio').
+-- Requests carrying content of an undeclared input modality, or asking for an
+-- undeclared output modality, are rejected with a 422 before forwarding.
+-- NULL means undeclared, and nothing is checked.
+
+ALTER TABLE deployed_models
+ADD COLUMN input_modalities TEXT[] DEFAULT NULL,
+ADD COLUMN output_modalities TEXT[] DEFAULT NULL;
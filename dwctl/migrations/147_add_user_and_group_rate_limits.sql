@@ -0,0 +1,33 @@
+-- Rate and concurrency limits for a user's API keys, set by an admin on the
+-- user (an override) or on a group (a default for its members).
+--
+-- Each key of the user resolves its limits as: user override > the key's own
+-- rate limit > the highest default among the user's groups > the platform
+-- tier from config. NULL columns fall through to the next level. Limits apply
+-- to each key separately, across all models.
+CREATE TABLE user_rate_limits (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    requests_per_second REAL NULL CHECK (requests_per_second > 0),
+    burst_size INTEGER NULL CHECK (burst_size > 0),
+    concurrency_limit INTEGER NULL CHECK (concurrency_limit > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE group_rate_limits (
+    group_id UUID PRIMARY KEY REFERENCES groups(id) ON DELETE CASCADE,
+    requests_per_second REAL NULL CHECK (requests_per_second > 0),
+    burst_size INTEGER NULL CHECK (burst_size > 0),
+    concurrency_limit INTEGER NULL CHECK (concurrency_limit > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- The limits are part of the onwards key definitions (uses existing function from 049)
+CREATE TRIGGER user_rate_limits_notify
+    AFTER INSERT OR UPDATE OR DELETE ON user_rate_limits
+    FOR EACH STATEMENT EXECUTE FUNCTION notify_config_change();
+
+CREATE TRIGGER group_rate_limits_notify
+    AFTER INSERT OR UPDATE OR DELETE ON group_rate_limits
+    FOR EACH STATEMENT EXECUTE FUNCTION notify_config_change();
@@ -0,0 +1,21 @@
+-- Tombstones for batches removed by the batch retention job.
+--
+-- The job deletes a batch's files and soft-deletes it in fusillade, after which
+-- fusillade purges its request rows. The batch object as it was at archive
+-- time is kept here so the batch API can still return it, marked as archived.
+CREATE TABLE archived_batches (
+    batch_id UUID PRIMARY KEY,
+    -- The fusillade owner (a user or organization ID), for access checks
+    created_by TEXT NOT NULL,
+    completion_window TEXT NOT NULL,
+    batch_created_at TIMESTAMPTZ NOT NULL,
+    archived_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Directory the batch's output and error files were exported to, if any
+    cold_storage_path TEXT NULL,
+    -- The batch response at archive time
+    snapshot JSONB NOT NULL
+);
+
+-- Newest-first listing per owner, keyset-paginated on (batch_created_at, batch_id)
+CREATE INDEX idx_archived_batches_owner_created
+    ON archived_batches (created_by, batch_created_at DESC, batch_id DESC);
@@ -0,0 +1,9 @@
+-- Keep request and response bodies of a deployment out of request logs.
+--
+-- When disable_logging is set, the request logging layer stores only the
+-- metadata of requests to the deployment (method, path, status, timing), even
+-- when request logging is enabled globally. Analytics and billing still see
+-- every request. For virtual models the flag on the virtual model or on any of
+-- its components applies.
+
+ALTER TABLE deployed_models ADD COLUMN disable_logging BOOLEAN NOT NULL DEFAULT FALSE;
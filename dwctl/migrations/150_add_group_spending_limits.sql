@@ -0,0 +1,32 @@
+-- Spending limits on groups, set by an admin.
+--
+-- A group's members share one spend window: every billed request of a member
+-- counts towards the group's window, and once the window's spend reaches the
+-- limit every API key of every member loses access to priced models until the
+-- window resets (free models stay usable, as with API-key spending caps).
+-- Windows follow the API-key caps (migrations 122/123): NULL interval = one-off
+-- (spend since the limit was set), otherwise CALENDAR-ALIGNED daily / weekly /
+-- monthly windows. Groups have no timezone, so windows are aligned in UTC.
+CREATE TABLE group_spending_limits (
+    group_id UUID PRIMARY KEY REFERENCES groups(id) ON DELETE CASCADE,
+    spending_limit DECIMAL(20, 9) NOT NULL CHECK (spending_limit > 0),
+    spending_limit_interval TEXT NULL CHECK (spending_limit_interval IN ('daily', 'weekly', 'monthly')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Spend folded by the request-logging batcher for each limited group, with
+-- the same lazy rollover as api_key_spend_checkpoints. Kept apart from the
+-- limits so the fold doesn't fire the config-change trigger on every flush;
+-- the batcher notifies only when a group crosses its limit.
+CREATE TABLE group_spend_checkpoints (
+    group_id UUID PRIMARY KEY REFERENCES groups(id) ON DELETE CASCADE,
+    window_spend DECIMAL(20, 9) NOT NULL DEFAULT 0,
+    window_started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- The limits gate the onwards key set (uses existing function from 049)
+CREATE TRIGGER group_spending_limits_notify
+    AFTER INSERT OR UPDATE OR DELETE ON group_spending_limits
+    FOR EACH STATEMENT EXECUTE FUNCTION notify_config_change();
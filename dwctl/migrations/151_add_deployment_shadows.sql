@@ -0,0 +1,22 @@
+-- Shadow traffic for deployments, set by an admin.
+--
+-- A sampled fraction of a model's requests is copied to another model (the
+-- shadow) and the copies' responses are discarded, so a new endpoint can be
+-- tried on live traffic without callers seeing it. Copies are sent by onwards
+-- after the primary request is admitted and never pass back through dwctl's
+-- logging or billing. One shadow per model; deleting either model (hard
+-- delete) drops the row, and the sync skips shadows whose model is
+-- soft-deleted.
+CREATE TABLE deployment_shadows (
+    deployment_id UUID PRIMARY KEY REFERENCES deployed_models(id) ON DELETE CASCADE,
+    shadow_deployment_id UUID NOT NULL REFERENCES deployed_models(id) ON DELETE CASCADE,
+    fraction DOUBLE PRECISION NOT NULL CHECK (fraction > 0 AND fraction <= 1),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (shadow_deployment_id <> deployment_id)
+);
+
+-- Shadows are part of the onwards routing config (uses existing function from 049)
+CREATE TRIGGER deployment_shadows_notify
+    AFTER INSERT OR UPDATE OR DELETE ON deployment_shadows
+    FOR EACH STATEMENT EXECUTE FUNCTION notify_config_change();
@@ -0,0 +1,9 @@
+-- Alias template for models discovered on an endpoint.
+--
+-- Providers such as OpenRouter list their models as `provider/model-name`.
+-- When set, aliases of deployments created by syncing the endpoint are built
+-- from this template instead of the raw model id: `{model}` is the full id
+-- and `{name}` the id without its provider prefix. Explicit alias mappings
+-- still win, and existing deployments keep their aliases.
+ALTER TABLE inference_endpoints
+    ADD COLUMN alias_template TEXT CHECK (alias_template <> '');
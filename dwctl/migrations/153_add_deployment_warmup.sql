@@ -0,0 +1,10 @@
+-- Warm a deployment up when it becomes active.
+--
+-- When warmup is set, the leader sends the deployment a minimal request
+-- through the AI proxy as soon as it is created or reactivated, and again
+-- every background_services.deployment_warmup.interval if one is configured,
+-- so that connections are established and cold models are loaded before the
+-- first real request. Warmup failures are logged and never block the
+-- deployment.
+
+ALTER TABLE deployed_models ADD COLUMN warmup BOOLEAN NOT NULL DEFAULT FALSE;
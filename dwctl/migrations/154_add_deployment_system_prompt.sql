@@ -0,0 +1,12 @@
+-- System prompt injected into a deployment's chat requests.
+--
+-- When system_prompt is set, the proxy adds it to the messages of every chat
+-- request for the deployment before forwarding. system_prompt_mode decides
+-- what happens when the client already sent a system message: 'prepend'
+-- inserts the prompt as a separate system message ahead of it, 'merge' adds
+-- the prompt to the start of the client's first system message.
+
+ALTER TABLE deployed_models
+    ADD COLUMN system_prompt TEXT,
+    ADD COLUMN system_prompt_mode TEXT NOT NULL DEFAULT 'prepend'
+        CHECK (system_prompt_mode IN ('prepend', 'merge'));
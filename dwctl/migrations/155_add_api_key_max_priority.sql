@@ -0,0 +1,8 @@
+-- Highest request priority an API key may ask for with the X-Priority header.
+-- When a deployment is at capacity and request queueing is enabled, queued
+-- requests are admitted highest priority first. Keys default to 0, the
+-- priority every request without the header gets; raising it is restricted to
+-- PlatformManagers in the API.
+ALTER TABLE api_keys
+    ADD COLUMN max_priority SMALLINT NOT NULL DEFAULT 0
+        CHECK (max_priority BETWEEN 0 AND 9);
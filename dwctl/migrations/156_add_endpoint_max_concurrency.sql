@@ -0,0 +1,8 @@
+-- Concurrency limit for an inference endpoint, shared by all its deployments.
+--
+-- One endpoint (e.g. a provider account) often hosts several deployments and
+-- caps concurrent requests across all of them. When set, the proxy allows at
+-- most this many in-flight requests to the endpoint in total, on top of each
+-- deployment's own capacity.
+ALTER TABLE inference_endpoints
+    ADD COLUMN max_concurrency INTEGER CHECK (max_concurrency > 0);
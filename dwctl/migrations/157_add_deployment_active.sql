@@ -0,0 +1,10 @@
+-- Let admins take a deployment out of service without deleting it.
+--
+-- A deployment with active = false is left out of the proxy's routing table,
+-- so requests for it get a 404 (or a 503 "model disabled", with
+-- onwards.deactivated_models set to "unavailable"). Its configuration, group
+-- access and pricing are kept, and setting active back to true restores it.
+-- This is separate from status, which endpoint syncs set when a model
+-- disappears from (or returns to) the upstream's model list.
+
+ALTER TABLE deployed_models ADD COLUMN active BOOLEAN NOT NULL DEFAULT TRUE;
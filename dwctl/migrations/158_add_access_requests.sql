@@ -0,0 +1,35 @@
+-- Access requests for deployments that need an admin's approval.
+--
+-- With require_approval set, group membership or public access alone no
+-- longer lets a user use the deployment: they also need an approved row here.
+-- Users ask for access (pending), and an admin approves or denies it. One row
+-- per user and deployment; asking again after a denial puts it back to
+-- pending. The system user and batch escalation are not gated.
+
+ALTER TABLE deployed_models ADD COLUMN require_approval BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE access_requests (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    deployment_id UUID NOT NULL REFERENCES deployed_models(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'denied')),
+    reason TEXT,
+    reviewed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    reviewed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (deployment_id, user_id)
+);
+
+CREATE INDEX idx_access_requests_user_id ON access_requests(user_id);
+
+CREATE TRIGGER update_access_requests_updated_at
+    BEFORE UPDATE ON access_requests
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- Approvals change which keys can reach a model, so the onwards config sync
+-- has to reload (uses existing function from 049)
+CREATE TRIGGER access_requests_notify
+    AFTER INSERT OR UPDATE OR DELETE ON access_requests
+    FOR EACH STATEMENT EXECUTE FUNCTION notify_config_change();
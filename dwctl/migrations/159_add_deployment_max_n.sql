@@ -0,0 +1,11 @@
+-- Cap on the number of completions (n) a request to a deployment may ask for.
+--
+-- Each of the n completions is generated and billed separately, so a large n
+-- multiplies a request's cost. When max_n is set, the proxy handles requests
+-- asking for more according to max_n_mode: 'reject' returns a 400, 'clamp'
+-- lowers n to max_n before forwarding. NULL means no cap.
+
+ALTER TABLE deployed_models
+    ADD COLUMN max_n INTEGER CHECK (max_n > 0),
+    ADD COLUMN max_n_mode TEXT NOT NULL DEFAULT 'reject'
+        CHECK (max_n_mode IN ('reject', 'clamp'));
@@ -0,0 +1,25 @@
+-- Add LISTEN/NOTIFY triggers for API key rotation and deletion webhook events.
+-- Creation is already covered by api_keys_webhook_notify (migration 087).
+-- The notification poller reads the key back, so the secret never appears
+-- in a notification payload.
+
+CREATE OR REPLACE FUNCTION notify_webhook_event_action() RETURNS trigger AS $$
+BEGIN
+    -- Payload format: "table_name.action:record_id", with the action passed as the trigger argument
+    PERFORM pg_notify('webhook_event', TG_TABLE_NAME || '.' || TG_ARGV[0] || ':' || NEW.id::text);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER api_keys_rotated_webhook_notify
+    AFTER UPDATE OF secret ON api_keys
+    FOR EACH ROW
+    WHEN (OLD.secret IS DISTINCT FROM NEW.secret)
+    EXECUTE FUNCTION notify_webhook_event_action('rotated');
+
+-- API keys are soft-deleted by setting is_deleted
+CREATE TRIGGER api_keys_deleted_webhook_notify
+    AFTER UPDATE OF is_deleted ON api_keys
+    FOR EACH ROW
+    WHEN (NEW.is_deleted AND NOT OLD.is_deleted)
+    EXECUTE FUNCTION notify_webhook_event_action('deleted');
@@ -0,0 +1,13 @@
+-- What happens when a streamed response runs the caller out of credits.
+--
+-- Requests are only admitted with a positive balance, but a long stream can
+-- cost more than the balance left when it started. With
+-- credit_exhaustion_mode 'complete' (the default) the stream runs to the end
+-- and the balance goes negative; when max_overdraft is set, the stream is
+-- ended once it would take the balance more than that far below zero. With
+-- 'abort' the stream is ended once its cost exceeds the balance.
+
+ALTER TABLE deployed_models
+    ADD COLUMN credit_exhaustion_mode TEXT NOT NULL DEFAULT 'complete'
+        CHECK (credit_exhaustion_mode IN ('complete', 'abort')),
+    ADD COLUMN max_overdraft DECIMAL(12, 8) DEFAULT NULL CHECK (max_overdraft >= 0);
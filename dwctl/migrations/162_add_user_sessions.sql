@@ -0,0 +1,17 @@
+-- Server-side record of native login sessions, so they can be listed and revoked.
+--
+-- Each session JWT carries the id of its row here (the `sid` claim), and
+-- cookie authentication rejects a token whose row is revoked, expired or gone.
+-- Tokens issued before this migration have no `sid` and stop authenticating,
+-- so users signed in with a password have to log in again once.
+
+CREATE TABLE user_sessions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    user_agent TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_user_sessions_user_id ON user_sessions(user_id);
@@ -0,0 +1,12 @@
+-- Per-user override of the API key purposes a user may create keys with.
+--
+-- Without a row here, the purposes come from `auth.api_key_purposes.by_role`
+-- in the config. Batch and playground keys are created by the system, so only
+-- realtime and platform can be granted.
+
+CREATE TABLE user_api_key_purposes (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    allowed_purposes TEXT[] NOT NULL CHECK (allowed_purposes <@ ARRAY['realtime', 'platform']::TEXT[]),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,13 @@
+-- Cap on the length of the prompt a request to a deployment may send.
+--
+-- A guardrail against abuse and oversized context, separate from the
+-- provider's context window. When max_prompt_length is set, requests with a
+-- longer prompt are rejected with a 422 before they are forwarded.
+-- max_prompt_length_unit says what is counted: 'tokens' (counted by
+-- tokenizer-svc where it maps the model, otherwise estimated from the
+-- character count) or 'characters'. NULL means no limit.
+
+ALTER TABLE deployed_models
+    ADD COLUMN max_prompt_length INTEGER CHECK (max_prompt_length > 0),
+    ADD COLUMN max_prompt_length_unit TEXT NOT NULL DEFAULT 'tokens'
+        CHECK (max_prompt_length_unit IN ('tokens', 'characters'));
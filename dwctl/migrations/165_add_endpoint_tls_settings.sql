@@ -0,0 +1,13 @@
+-- Per-endpoint TLS settings for providers behind a private CA or requiring
+-- mutual TLS. The CA bundle and client certificate are PEM text; the client
+-- private key is encrypted with the connections encryption key.
+
+ALTER TABLE inference_endpoints
+    ADD COLUMN tls_ca_bundle TEXT,
+    ADD COLUMN tls_client_cert TEXT,
+    ADD COLUMN tls_client_key_encrypted BYTEA;
+
+ALTER TABLE inference_endpoints
+    ADD CONSTRAINT inference_endpoints_tls_client_identity CHECK (
+        (tls_client_cert IS NULL) = (tls_client_key_encrypted IS NULL)
+    );
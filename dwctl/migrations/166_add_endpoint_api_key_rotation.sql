@@ -0,0 +1,8 @@
+-- Extra upstream API keys for an endpoint, rotated between with api_key to
+-- spread requests over per-key provider rate limits. Like api_key, each may
+-- be a secret reference.
+
+ALTER TABLE inference_endpoints
+    ADD COLUMN additional_api_keys TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN api_key_rotation TEXT NOT NULL DEFAULT 'round_robin'
+        CHECK (api_key_rotation IN ('round_robin', 'least_recently_used'));
@@ -0,0 +1,402 @@
+//! HTTP handlers for access requests.
+//!
+//! A model with `require_approval` set can only be used by users who have an
+//! approved access request, on top of the usual group or public access. Users
+//! who can see such a model in the catalog ask for access here, and admins
+//! approve or deny the request. Approvals are read by the onwards config sync,
+//! so an approved user's keys can reach the model once the sync has run.
+//!
+//! An organization's keys are gated on the organization's own approval. Its
+//! owners and admins ask for that with the organization active; the request is
+//! then filed for the organization rather than for them.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use sqlx_pool_router::PoolProvider;
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::api::models::access_requests::{AccessRequestCreate, AccessRequestResponse, AccessRequestReview, AccessRequestsQuery};
+use crate::api::models::users::CurrentUser;
+use crate::auth::permissions::{RequiresPermission, can_manage_org_resource, operation, resource};
+use crate::db::handlers::{AccessRequests, Deployments, Repository};
+use crate::db::models::access_requests::AccessRequestStatus;
+use crate::errors::{Error, Result};
+use crate::types::{DeploymentId, Operation, Permission, Resource};
+
+fn model_not_found(id: DeploymentId) -> Error {
+    Error::NotFound {
+        resource: "Model".to_string(),
+        id: id.to_string(),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/models/{id}/access-requests",
+    tag = "models",
+    summary = "Request access to a model",
+    description = "Ask for access to a model that requires approval. The request stays pending until an \
+                   admin approves or denies it. Asking again after a denial reopens the request; asking \
+                   again while it is pending or approved returns it unchanged. With an organization active \
+                   the request is for the organization and its keys, and only its owners and admins may make it.",
+    params(("id" = uuid::Uuid, Path, description = "Deployment ID")),
+    request_body = AccessRequestCreate,
+    responses(
+        (status = 201, description = "Access request", body = AccessRequestResponse),
+        (status = 400, description = "The model doesn't require approval"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden (requesting for an organization the user doesn't manage)"),
+        (status = 404, description = "Model not found, or not available to the user"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn create_access_request<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(id): Path<DeploymentId>,
+    current_user: CurrentUser,
+    Json(body): Json<AccessRequestCreate>,
+) -> Result<(StatusCode, Json<AccessRequestResponse>)> {
+    let mut tx = state.db.write().begin().await.map_err(|e| Error::Database(e.into()))?;
+
+    // Organization keys are gated on the organization's approval, which its managers ask for
+    let requester = match current_user.active_organization {
+        Some(org_id) => {
+            if !can_manage_org_resource(&current_user, org_id, &mut tx).await? {
+                return Err(Error::InsufficientPermissions {
+                    required: Permission::Allow(Resource::Organizations, Operation::UpdateOwn),
+                    action: Operation::UpdateOwn,
+                    resource: format!("Access requests for organization {org_id}"),
+                });
+            }
+            org_id
+        }
+        None => current_user.id,
+    };
+
+    let mut deployments = Deployments::new(&mut tx);
+    let model = deployments
+        .get_by_id(id)
+        .await?
+        .filter(|model| !model.deleted)
+        .ok_or_else(|| model_not_found(id))?;
+    // Only users who could use the model but for the approval may ask for it
+    if deployments.check_user_access(&model.alias, requester).await?.is_none() {
+        return Err(model_not_found(id));
+    }
+    if !model.require_approval {
+        return Err(Error::BadRequest {
+            message: format!("Model {} doesn't require approval", model.alias),
+        });
+    }
+
+    let request = AccessRequests::new(&mut tx).request(id, requester, body.reason.as_deref()).await?;
+    tx.commit().await.map_err(|e| Error::Database(e.into()))?;
+    Ok((StatusCode::CREATED, Json(request.into())))
+}
+
+#[utoipa::path(
+    get,
+    path = "/models/{id}/access-requests",
+    tag = "models",
+    summary = "List a model's access requests",
+    params(("id" = uuid::Uuid, Path, description = "Deployment ID"), AccessRequestsQuery),
+    responses(
+        (status = 200, description = "Access requests, newest first", body = [AccessRequestResponse]),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden (requires model-management access)"),
+        (status = 404, description = "Model not found"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn list_access_requests<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(id): Path<DeploymentId>,
+    Query(query): Query<AccessRequestsQuery>,
+    _user: RequiresPermission<resource::Models, operation::ReadAll>,
+) -> Result<Json<Vec<AccessRequestResponse>>> {
+    let mut conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    if Deployments::new(&mut conn).get_by_id(id).await?.is_none() {
+        return Err(model_not_found(id));
+    }
+    let requests = AccessRequests::new(&mut conn).list_for_deployment(id, query.status).await?;
+    Ok(Json(requests.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/models/{id}/access-requests/{request_id}",
+    tag = "models",
+    summary = "Approve or deny an access request",
+    description = "Approving a request lets the user use the model; denying it (or later denying an \
+                   approved request) blocks them again. Changes reach the AI proxy with the next config sync.",
+    params(
+        ("id" = uuid::Uuid, Path, description = "Deployment ID"),
+        ("request_id" = uuid::Uuid, Path, description = "Access request ID"),
+    ),
+    request_body = AccessRequestReview,
+    responses(
+        (status = 200, description = "Reviewed access request", body = AccessRequestResponse),
+        (status = 400, description = "Status is not approved or denied"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden (requires model-management access)"),
+        (status = 404, description = "Access request not found for this model"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn review_access_request<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path((id, request_id)): Path<(DeploymentId, Uuid)>,
+    user: RequiresPermission<resource::Models, operation::UpdateAll>,
+    Json(review): Json<AccessRequestReview>,
+) -> Result<Json<AccessRequestResponse>> {
+    if review.status == AccessRequestStatus::Pending {
+        return Err(Error::BadRequest {
+            message: "status must be approved or denied".to_string(),
+        });
+    }
+    let not_found = || Error::NotFound {
+        resource: "Access request".to_string(),
+        id: request_id.to_string(),
+    };
+
+    let mut tx = state.db.write().begin().await.map_err(|e| Error::Database(e.into()))?;
+    let mut requests = AccessRequests::new(&mut tx);
+    match requests.get(request_id).await? {
+        Some(existing) if existing.deployment_id == id => {}
+        _ => return Err(not_found()),
+    }
+    let reviewed = requests
+        .review(request_id, review.status, user.current_user.id)
+        .await?
+        .ok_or_else(not_found)?;
+    tx.commit().await.map_err(|e| Error::Database(e.into()))?;
+    Ok(Json(reviewed.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::models::users::{Role, UserResponse};
+    use crate::test::utils::{
+        add_auth_headers, add_deployment_to_group, add_org_member, add_user_to_group, create_test_admin_user, create_test_app,
+        create_test_deployment, create_test_group, create_test_org, create_test_user,
+    };
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    /// A model gated by approval, in a group the returned user belongs to
+    async fn gated_model_setup(pool: &PgPool) -> (UserResponse, UserResponse, DeploymentId, String) {
+        let admin = create_test_admin_user(pool, Role::PlatformManager).await;
+        let user = create_test_user(pool, Role::StandardUser).await;
+        let group = create_test_group(pool).await;
+        add_user_to_group(pool, user.id, group.id).await;
+        let deployment = create_test_deployment(pool, admin.id, "gated-model", "gated").await;
+        add_deployment_to_group(pool, deployment.id, group.id, admin.id).await;
+        sqlx::query!("UPDATE deployed_models SET require_approval = TRUE WHERE id = $1", deployment.id)
+            .execute(pool)
+            .await
+            .unwrap();
+        (admin, user, deployment.id, deployment.alias)
+    }
+
+    async fn user_can_use(pool: &PgPool, user: &UserResponse, alias: &str) -> bool {
+        crate::error_enrichment::check_user_has_model_access(pool.clone(), user.id, alias)
+            .await
+            .unwrap()
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_approved_request_grants_access(pool: PgPool) {
+        let (app, _) = create_test_app(pool.clone(), false).await;
+        let (admin, user, model_id, alias) = gated_model_setup(&pool).await;
+        let requests_path = format!("/admin/api/v1/models/{model_id}/access-requests");
+
+        // Group membership alone doesn't grant access to a gated model
+        assert!(!user_can_use(&pool, &user, &alias).await);
+
+        let response = app
+            .post(&requests_path)
+            .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
+            .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+            .json(&json!({"reason": "Quarterly audit"}))
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        let request: AccessRequestResponse = response.json();
+        assert_eq!(request.status, AccessRequestStatus::Pending);
+        assert_eq!(request.reason.as_deref(), Some("Quarterly audit"));
+        assert!(!user_can_use(&pool, &user, &alias).await);
+
+        // Users can't review their own requests
+        let review_path = format!("{requests_path}/{}", request.id);
+        let response = app
+            .patch(&review_path)
+            .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
+            .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+            .json(&json!({"status": "approved"}))
+            .await;
+        response.assert_status_forbidden();
+
+        let response = app
+            .get(&format!("{requests_path}?status=pending"))
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .await;
+        response.assert_status_ok();
+        let pending: Vec<AccessRequestResponse> = response.json();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].user_email, user.email);
+
+        let response = app
+            .patch(&review_path)
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&json!({"status": "approved"}))
+            .await;
+        response.assert_status_ok();
+        let approved: AccessRequestResponse = response.json();
+        assert_eq!(approved.status, AccessRequestStatus::Approved);
+        assert_eq!(approved.reviewed_by, Some(admin.id));
+        assert!(user_can_use(&pool, &user, &alias).await);
+
+        // Asking again once approved leaves the approval in place
+        let response = app
+            .post(&requests_path)
+            .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
+            .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+            .json(&json!({}))
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        assert_eq!(response.json::<AccessRequestResponse>().status, AccessRequestStatus::Approved);
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_denied_request_leaves_model_blocked(pool: PgPool) {
+        let (app, _) = create_test_app(pool.clone(), false).await;
+        let (admin, user, model_id, alias) = gated_model_setup(&pool).await;
+        let requests_path = format!("/admin/api/v1/models/{model_id}/access-requests");
+
+        let response = app
+            .post(&requests_path)
+            .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
+            .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+            .json(&json!({}))
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        let request: AccessRequestResponse = response.json();
+        let review_path = format!("{requests_path}/{}", request.id);
+
+        let response = app
+            .patch(&review_path)
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&json!({"status": "pending"}))
+            .await;
+        response.assert_status_bad_request();
+
+        let response = app
+            .patch(&review_path)
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&json!({"status": "denied"}))
+            .await;
+        response.assert_status_ok();
+        assert_eq!(response.json::<AccessRequestResponse>().status, AccessRequestStatus::Denied);
+        assert!(!user_can_use(&pool, &user, &alias).await);
+
+        // Asking again reopens the request, still without access
+        let response = app
+            .post(&requests_path)
+            .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
+            .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+            .json(&json!({"reason": "Second try"}))
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        let reopened: AccessRequestResponse = response.json();
+        assert_eq!(reopened.id, request.id);
+        assert_eq!(reopened.status, AccessRequestStatus::Pending);
+        assert_eq!(reopened.reviewed_by, None);
+        assert!(!user_can_use(&pool, &user, &alias).await);
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_request_requires_gated_visible_model(pool: PgPool) {
+        let (app, _) = create_test_app(pool.clone(), false).await;
+        let (admin, _user, model_id, _) = gated_model_setup(&pool).await;
+        let outsider = create_test_user(&pool, Role::StandardUser).await;
+        let ungated = create_test_deployment(&pool, admin.id, "open-model", "open").await;
+        sqlx::query!("UPDATE deployed_models SET allow_public = TRUE WHERE id = $1", ungated.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // Not in the model's group
+        let response = app
+            .post(&format!("/admin/api/v1/models/{model_id}/access-requests"))
+            .add_header(&add_auth_headers(&outsider)[0].0, &add_auth_headers(&outsider)[0].1)
+            .add_header(&add_auth_headers(&outsider)[1].0, &add_auth_headers(&outsider)[1].1)
+            .json(&json!({}))
+            .await;
+        response.assert_status_not_found();
+
+        // Models without require_approval have nothing to request
+        let response = app
+            .post(&format!("/admin/api/v1/models/{}/access-requests", ungated.id))
+            .add_header(&add_auth_headers(&outsider)[0].0, &add_auth_headers(&outsider)[0].1)
+            .add_header(&add_auth_headers(&outsider)[1].0, &add_auth_headers(&outsider)[1].1)
+            .json(&json!({}))
+            .await;
+        response.assert_status_bad_request();
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_org_managers_request_access_for_the_org(pool: PgPool) {
+        let (app, _) = create_test_app(pool.clone(), false).await;
+        let (admin, owner, model_id, alias) = gated_model_setup(&pool).await;
+        let member = create_test_user(&pool, Role::StandardUser).await;
+        let org = create_test_org(&pool, owner.id).await;
+        add_org_member(&pool, org.id, member.id, "member").await;
+        let org_group = create_test_group(&pool).await;
+        add_user_to_group(&pool, org.id, org_group.id).await;
+        add_deployment_to_group(&pool, model_id, org_group.id, admin.id).await;
+        let requests_path = format!("/admin/api/v1/models/{model_id}/access-requests");
+        let request_for_org = |user: &UserResponse| {
+            let headers = add_auth_headers(user);
+            app.post(&requests_path)
+                .add_header(&headers[0].0, &headers[0].1)
+                .add_header(&headers[1].0, &headers[1].1)
+                .add_header("x-organization-id", org.id.to_string())
+                .json(&json!({"reason": "Team project"}))
+        };
+
+        // Plain members can't ask on the organization's behalf
+        request_for_org(&member).await.assert_status_forbidden();
+
+        let response = request_for_org(&owner).await;
+        response.assert_status(StatusCode::CREATED);
+        let request: AccessRequestResponse = response.json();
+        assert_eq!(request.user_id, org.id);
+
+        let response = app
+            .patch(&format!("{requests_path}/{}", request.id))
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&json!({"status": "approved"}))
+            .await;
+        response.assert_status_ok();
+
+        // The approval covers the organization's keys, not its owner's own
+        assert!(user_can_use(&pool, &org, &alias).await);
+        assert!(!user_can_use(&pool, &owner, &alias).await);
+    }
+}
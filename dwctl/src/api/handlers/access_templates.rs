@@ -0,0 +1,445 @@
+//! HTTP handlers for access templates.
+//!
+//! An access template is a named set of deployments (a "tier") that can be
+//! applied to a group, either when the group is created or later. Applying a
+//! template only adds access: it grants the group the template's deployments
+//! and leaves any other access the group has alone. The grants go through
+//! `deployment_groups`, so the onwards config sync picks them up as usual.
+
+use sqlx::PgConnection;
+use sqlx_pool_router::PoolProvider;
+
+use crate::api::models::access_templates::{AccessTemplateCreate, AccessTemplateResponse, AccessTemplateUpdate};
+use crate::api::models::groups::BatchMembershipResponse;
+use crate::auth::permissions::{RequiresPermission, operation, resource};
+use crate::db::handlers::{AccessTemplates, Groups};
+use crate::db::models::access_templates::{AccessTemplateCreateDBRequest, AccessTemplateUpdateDBRequest};
+use crate::db::models::groups::MembershipAddOutcome;
+use crate::errors::{Error, Result};
+use crate::{
+    AppState,
+    types::{DeploymentId, GroupId, UserId},
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use uuid::Uuid;
+
+fn template_not_found(id: Uuid) -> Error {
+    Error::NotFound {
+        resource: "AccessTemplate".to_string(),
+        id: id.to_string(),
+    }
+}
+
+/// Grant a group the deployments in a template. Existing access is kept.
+pub(crate) async fn apply_template_to_group(
+    conn: &mut PgConnection,
+    group_id: GroupId,
+    template_id: Uuid,
+    granted_by: UserId,
+) -> Result<Vec<(DeploymentId, MembershipAddOutcome)>> {
+    let template = AccessTemplates::new(&mut *conn)
+        .get_by_id(template_id)
+        .await?
+        .ok_or_else(|| template_not_found(template_id))?;
+
+    let outcomes = Groups::new(&mut *conn)
+        .add_deployments_to_group(group_id, &template.deployment_ids, granted_by)
+        .await?;
+    Ok(outcomes)
+}
+
+#[utoipa::path(
+    get,
+    path = "/access-templates",
+    tag = "groups",
+    summary = "List access templates",
+    responses(
+        (status = 200, description = "List of access templates", body = Vec<AccessTemplateResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("BearerAuth" = []),
+        ("CookieAuth" = []),
+        ("X-Doubleword-User" = [])
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn list_access_templates<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    _: RequiresPermission<resource::Groups, operation::ReadAll>,
+) -> Result<Json<Vec<AccessTemplateResponse>>> {
+    let mut conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    let templates = AccessTemplates::new(&mut conn).list().await?;
+    Ok(Json(templates.into_iter().map(AccessTemplateResponse::from).collect()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/access-templates",
+    tag = "groups",
+    summary = "Create access template",
+    description = "Create a named set of model deployments that can be applied to groups. \
+        Unknown deployment IDs are rejected.",
+    request_body = AccessTemplateCreate,
+    responses(
+        (status = 201, description = "Access template created", body = AccessTemplateResponse),
+        (status = 400, description = "Unknown deployment ID"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 409, description = "A template with this name already exists"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("BearerAuth" = []),
+        ("CookieAuth" = []),
+        ("X-Doubleword-User" = [])
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn create_access_template<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    current_user: RequiresPermission<resource::Groups, operation::CreateAll>,
+    Json(body): Json<AccessTemplateCreate>,
+) -> Result<(StatusCode, Json<AccessTemplateResponse>)> {
+    let mut tx = state.db.write().begin().await.map_err(|e| Error::Database(e.into()))?;
+    let template = AccessTemplates::new(&mut tx)
+        .create(&AccessTemplateCreateDBRequest {
+            name: body.name,
+            description: body.description,
+            deployment_ids: body.deployment_ids,
+            created_by: current_user.id,
+        })
+        .await?;
+    tx.commit().await.map_err(|e| Error::Database(e.into()))?;
+
+    Ok((StatusCode::CREATED, Json(AccessTemplateResponse::from(template))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/access-templates/{id}",
+    tag = "groups",
+    summary = "Get access template",
+    params(
+        ("id" = Uuid, Path, description = "Access template ID"),
+    ),
+    responses(
+        (status = 200, description = "Access template details", body = AccessTemplateResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("BearerAuth" = []),
+        ("CookieAuth" = []),
+        ("X-Doubleword-User" = [])
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_access_template<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(id): Path<Uuid>,
+    _: RequiresPermission<resource::Groups, operation::ReadAll>,
+) -> Result<Json<AccessTemplateResponse>> {
+    let mut conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    let template = AccessTemplates::new(&mut conn)
+        .get_by_id(id)
+        .await?
+        .ok_or_else(|| template_not_found(id))?;
+    Ok(Json(AccessTemplateResponse::from(template)))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/access-templates/{id}",
+    tag = "groups",
+    summary = "Update access template",
+    description = "Update a template's name, description or deployments. `deployment_ids` replaces \
+        the template's deployments. Groups the template was already applied to keep their access.",
+    params(
+        ("id" = Uuid, Path, description = "Access template ID"),
+    ),
+    request_body = AccessTemplateUpdate,
+    responses(
+        (status = 200, description = "Access template updated", body = AccessTemplateResponse),
+        (status = 400, description = "Unknown deployment ID"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found"),
+        (status = 409, description = "A template with this name already exists"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("BearerAuth" = []),
+        ("CookieAuth" = []),
+        ("X-Doubleword-User" = [])
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn update_access_template<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(id): Path<Uuid>,
+    _: RequiresPermission<resource::Groups, operation::UpdateAll>,
+    Json(body): Json<AccessTemplateUpdate>,
+) -> Result<Json<AccessTemplateResponse>> {
+    let mut tx = state.db.write().begin().await.map_err(|e| Error::Database(e.into()))?;
+    let template = AccessTemplates::new(&mut tx)
+        .update(
+            id,
+            &AccessTemplateUpdateDBRequest {
+                name: body.name,
+                description: body.description,
+                deployment_ids: body.deployment_ids,
+            },
+        )
+        .await?;
+    tx.commit().await.map_err(|e| Error::Database(e.into()))?;
+
+    Ok(Json(AccessTemplateResponse::from(template)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/access-templates/{id}",
+    tag = "groups",
+    summary = "Delete access template",
+    description = "Delete a template. Groups it was applied to keep their access.",
+    params(
+        ("id" = Uuid, Path, description = "Access template ID"),
+    ),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("BearerAuth" = []),
+        ("CookieAuth" = []),
+        ("X-Doubleword-User" = [])
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn delete_access_template<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(id): Path<Uuid>,
+    _: RequiresPermission<resource::Groups, operation::DeleteAll>,
+) -> Result<StatusCode> {
+    let mut conn = state.db.write().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    if AccessTemplates::new(&mut conn).delete(id).await? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(template_not_found(id))
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/groups/{group_id}/apply-template/{template_id}",
+    tag = "groups",
+    summary = "Apply access template to group",
+    description = "Grant a group access to every model in an access template. Access the group \
+        already has is kept, including to models outside the template. Each of the template's \
+        models gets a result: `added` or `already_member`.",
+    params(
+        ("group_id" = uuid::Uuid, Path, description = "Group ID"),
+        ("template_id" = uuid::Uuid, Path, description = "Access template ID"),
+    ),
+    responses(
+        (status = 200, description = "Per-model results", body = BatchMembershipResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Group or template not found"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("BearerAuth" = []),
+        ("CookieAuth" = []),
+        ("X-Doubleword-User" = [])
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn apply_access_template<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path((group_id, template_id)): Path<(GroupId, Uuid)>,
+    current_user: RequiresPermission<resource::Groups, operation::UpdateAll>,
+) -> Result<Json<BatchMembershipResponse>> {
+    let mut tx = state.db.write().begin().await.map_err(|e| Error::Database(e.into()))?;
+    let outcomes = apply_template_to_group(&mut tx, group_id, template_id, current_user.id).await?;
+    tx.commit().await.map_err(|e| Error::Database(e.into()))?;
+
+    Ok(Json(outcomes.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::models::{access_templates::AccessTemplateResponse, groups::GroupResponse, users::Role},
+        db::{
+            handlers::{Groups, Repository},
+            models::groups::GroupCreateDBRequest,
+        },
+        test::utils::*,
+    };
+    use axum::http::StatusCode;
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_create_access_template(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let admin = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let standard_user = create_test_user(&pool, Role::StandardUser).await;
+        let model_a = create_test_deployment(&pool, admin.id, "tier-model-a", "tier-alias-a").await;
+        let model_b = create_test_deployment(&pool, admin.id, "tier-model-b", "tier-alias-b").await;
+
+        let response = app
+            .post("/admin/api/v1/access-templates")
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&json!({ "name": "Standard tier", "deployment_ids": [model_a.id, model_b.id] }))
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        let template: AccessTemplateResponse = response.json();
+        assert_eq!(template.name, "Standard tier");
+        let mut expected = vec![model_a.id, model_b.id];
+        expected.sort();
+        assert_eq!(template.deployment_ids, expected);
+
+        // Names are unique
+        let response = app
+            .post("/admin/api/v1/access-templates")
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&json!({ "name": "Standard tier" }))
+            .await;
+        response.assert_status(StatusCode::CONFLICT);
+
+        // Unknown deployments are rejected
+        let response = app
+            .post("/admin/api/v1/access-templates")
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&json!({ "name": "Broken tier", "deployment_ids": [uuid::Uuid::new_v4()] }))
+            .await;
+        response.assert_status_bad_request();
+
+        // Replacing the deployments
+        let response = app
+            .patch(&format!("/admin/api/v1/access-templates/{}", template.id))
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&json!({ "deployment_ids": [model_b.id] }))
+            .await;
+        response.assert_status_ok();
+        let template: AccessTemplateResponse = response.json();
+        assert_eq!(template.deployment_ids, vec![model_b.id]);
+
+        let response = app
+            .get("/admin/api/v1/access-templates")
+            .add_header(&add_auth_headers(&standard_user)[0].0, &add_auth_headers(&standard_user)[0].1)
+            .add_header(&add_auth_headers(&standard_user)[1].0, &add_auth_headers(&standard_user)[1].1)
+            .await;
+        response.assert_status_forbidden();
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_apply_access_template_to_group(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let admin = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let standard_user = create_test_user(&pool, Role::StandardUser).await;
+        let model_a = create_test_deployment(&pool, admin.id, "apply-model-a", "apply-alias-a").await;
+        let model_b = create_test_deployment(&pool, admin.id, "apply-model-b", "apply-alias-b").await;
+        let other = create_test_deployment(&pool, admin.id, "apply-model-other", "apply-alias-other").await;
+
+        let response = app
+            .post("/admin/api/v1/access-templates")
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&json!({ "name": "Pro tier", "deployment_ids": [model_a.id, model_b.id] }))
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        let template: AccessTemplateResponse = response.json();
+
+        // A group that already has one of the template's models and one outside it
+        let mut pool_conn = pool.acquire().await.unwrap();
+        let mut group_repo = Groups::new(&mut pool_conn);
+        let group = group_repo
+            .create(&GroupCreateDBRequest {
+                name: "Existing Group".to_string(),
+                description: None,
+                created_by: admin.id,
+            })
+            .await
+            .unwrap();
+        group_repo.add_deployment_to_group(model_a.id, group.id, admin.id).await.unwrap();
+        group_repo.add_deployment_to_group(other.id, group.id, admin.id).await.unwrap();
+
+        let apply_path = format!("/admin/api/v1/groups/{}/apply-template/{}", group.id, template.id);
+        let response = app
+            .post(&apply_path)
+            .add_header(&add_auth_headers(&standard_user)[0].0, &add_auth_headers(&standard_user)[0].1)
+            .add_header(&add_auth_headers(&standard_user)[1].0, &add_auth_headers(&standard_user)[1].1)
+            .await;
+        response.assert_status_forbidden();
+
+        let response = app
+            .post(&apply_path)
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .await;
+        response.assert_status_ok();
+        let body: serde_json::Value = response.json();
+        let statuses: std::collections::HashMap<String, String> = body["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| (r["id"].as_str().unwrap().to_string(), r["status"].as_str().unwrap().to_string()))
+            .collect();
+        assert_eq!(statuses[&model_a.id.to_string()], "already_member");
+        assert_eq!(statuses[&model_b.id.to_string()], "added");
+        assert_eq!(statuses.len(), 2);
+
+        // Applying adds access without removing any
+        let deployment_ids = group_repo.get_group_deployments(group.id).await.unwrap();
+        assert!(deployment_ids.contains(&model_a.id));
+        assert!(deployment_ids.contains(&model_b.id));
+        assert!(deployment_ids.contains(&other.id));
+
+        let response = app
+            .post(&format!(
+                "/admin/api/v1/groups/{}/apply-template/{}",
+                group.id,
+                uuid::Uuid::new_v4()
+            ))
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .await;
+        response.assert_status_not_found();
+
+        // Groups can be created from a template
+        let response = app
+            .post("/admin/api/v1/groups")
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&json!({ "name": "New Pro Group", "template_id": template.id }))
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        let new_group: GroupResponse = response.json();
+        let mut deployment_ids = group_repo.get_group_deployments(new_group.id).await.unwrap();
+        deployment_ids.sort();
+        let mut expected = vec![model_a.id, model_b.id];
+        expected.sort();
+        assert_eq!(deployment_ids, expected);
+    }
+}
@@ -6,12 +6,17 @@ use axum::{
     http::{HeaderMap, StatusCode, header},
     response::{IntoResponse, Response},
 };
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sqlx::{QueryBuilder, Row};
 use sqlx_pool_router::PoolProvider;
 
-use crate::{AppState, db::handlers::Deployments, reasoning::SupportedReasoningEfforts};
+use crate::{
+    AppState,
+    db::handlers::{Deployments, Tariffs},
+    reasoning::SupportedReasoningEfforts,
+};
 
 const EVERYONE_GROUP_ID: uuid::Uuid = uuid::Uuid::nil();
 
@@ -26,6 +31,7 @@ pub struct ModelsListQuery {
     group: Option<String>,
     available_for_realtime: Option<String>,
     include_reasoning_capabilities: Option<String>,
+    include_pricing: Option<String>,
 }
 
 enum ModelsListQueryError {
@@ -60,6 +66,21 @@ struct ModelObject {
     owned_by: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     supported_reasoning_efforts: Option<SupportedReasoningEfforts>,
+    /// Non-chat capabilities, e.g. `["rerank"]` for models served at `/v1/rerank` or
+    /// `["moderation"]` for models served at `/v1/moderations`.
+    /// Omitted for chat and embedding models so they keep the OpenAI model shape.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    capabilities: Option<Vec<String>>,
+    /// Per-token prices the presented key pays for this model. Returned only when requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pricing: Option<ModelPricing>,
+}
+
+/// The tariff that applies to the presented key's purpose.
+#[derive(Serialize)]
+struct ModelPricing {
+    input_price_per_token: Decimal,
+    output_price_per_token: Decimal,
 }
 
 fn openai_error(status: StatusCode, message: &str, error_type: &str, code: &str) -> Response {
@@ -126,6 +147,9 @@ fn parse_optional_bool(param: &'static str, value: Option<&str>) -> Result<Optio
 /// This intentionally does not filter by credit balance. Credit balance controls
 /// dispatch eligibility in the onwards key sync; model discovery should reflect
 /// access grants so users can still see what would be available after top-up.
+///
+/// With `include_pricing=true`, each model carries the tariff the presented
+/// key pays, resolved for the key's purpose as usage billing resolves it.
 pub async fn list_ai_models<P: PoolProvider>(
     State(state): State<AppState<P>>,
     Query(query): Query<ModelsListQuery>,
@@ -147,6 +171,9 @@ pub async fn list_ai_models<P: PoolProvider>(
         parse_optional_bool("include_reasoning_capabilities", query.include_reasoning_capabilities.as_deref())
             .map_err(ModelsListQueryError::into_response)?
             .unwrap_or(false);
+    let include_pricing = parse_optional_bool("include_pricing", query.include_pricing.as_deref())
+        .map_err(ModelsListQueryError::into_response)?
+        .unwrap_or(false);
 
     let mut conn = state
         .db
@@ -155,9 +182,9 @@ pub async fn list_ai_models<P: PoolProvider>(
         .await
         .map_err(|e| database_error("acquire_read_connection", e))?;
 
-    let user_id = sqlx::query_scalar::<_, uuid::Uuid>(
+    let key = sqlx::query_as::<_, (uuid::Uuid, String)>(
         r#"
-        SELECT ak.user_id
+        SELECT ak.user_id, ak.purpose
         FROM api_keys ak
         INNER JOIN users u ON u.id = ak.user_id
         WHERE ak.secret = $1
@@ -172,7 +199,7 @@ pub async fn list_ai_models<P: PoolProvider>(
     .await
     .map_err(|e| database_error("lookup_api_key", e))?;
 
-    let Some(user_id) = user_id else {
+    let Some((user_id, purpose)) = key else {
         return Err(openai_error(
             StatusCode::UNAUTHORIZED,
             "Invalid API key",
@@ -185,13 +212,16 @@ pub async fn list_ai_models<P: PoolProvider>(
         r#"
         SELECT DISTINCT
             dm.alias,
+            dm.type AS model_type,
             EXTRACT(EPOCH FROM dm.created_at)::BIGINT AS created
         FROM deployed_models dm
-        INNER JOIN deployment_groups dg ON dg.deployment_id = dm.id
+        LEFT JOIN deployment_groups dg ON dg.deployment_id = dm.id
         WHERE dm.deleted = FALSE
           AND dm.status = 'active'
+          AND dm.active = TRUE
           AND (
-              dg.group_id = "#,
+              dm.allow_public
+              OR dg.group_id = "#,
     );
     models_query.push_bind(EVERYONE_GROUP_ID);
     models_query.push(
@@ -207,7 +237,17 @@ pub async fn list_ai_models<P: PoolProvider>(
               )
         "#,
     );
-    models_query.push(")");
+    // Gated models also need an approved access request
+    models_query.push(
+        r#")
+          AND (
+              NOT dm.require_approval
+              OR EXISTS (
+                  SELECT 1 FROM access_requests ar
+                  WHERE ar.deployment_id = dm.id AND ar.status = 'approved' AND ar.user_id = "#,
+    );
+    models_query.push_bind(user_id);
+    models_query.push("))");
 
     if !group_ids.is_empty() {
         models_query.push(" AND dg.group_id = ANY(");
@@ -255,6 +295,18 @@ pub async fn list_ai_models<P: PoolProvider>(
         Default::default()
     };
 
+    // Pricing is looked up only for the models listed, so models the key
+    // can't access never reveal theirs.
+    let pricing = if include_pricing {
+        let aliases = rows.iter().map(|row| row.get("alias")).collect::<Vec<String>>();
+        Tariffs::new(&mut conn)
+            .get_current_pricing_by_alias(&aliases, &purpose)
+            .await
+            .map_err(|e| database_error("load_pricing", e))?
+    } else {
+        Default::default()
+    };
+
     Ok(Json(ModelsListResponse {
         object: "list".to_string(),
         data: rows
@@ -264,12 +316,25 @@ pub async fn list_ai_models<P: PoolProvider>(
                 let supported_reasoning_efforts = include_reasoning_capabilities
                     .then(|| reasoning_policies.get(&id).and_then(|policy| policy.supported_efforts()))
                     .flatten();
+                let capabilities = match row.get::<Option<String>, _>("model_type").as_deref() {
+                    Some("RERANKER") => Some(vec!["rerank".to_string()]),
+                    Some("MODERATION") => Some(vec!["moderation".to_string()]),
+                    _ => None,
+                };
+                let pricing = pricing
+                    .get(&id)
+                    .map(|&(input_price_per_token, output_price_per_token)| ModelPricing {
+                        input_price_per_token,
+                        output_price_per_token,
+                    });
                 ModelObject {
                     id,
                     object: "model".to_string(),
                     created: row.get::<Option<i64>, _>("created").unwrap_or_default(),
                     owned_by: "None".to_string(),
                     supported_reasoning_efforts,
+                    capabilities,
+                    pricing,
                 }
             })
             .collect(),
@@ -1,22 +1,26 @@
 //! HTTP handlers for API key management endpoints.
 
-use crate::api::models::api_keys::ListApiKeysQuery;
+use crate::api::models::api_keys::{
+    ApiKeyPurposesResponse, ApiKeyPurposesUpdate, ApiKeyUsageQuery, ApiKeyUsageResponse, ListApiKeysQuery, SystemApiKeyResponse,
+    SystemApiKeyUpdate,
+};
 use crate::{
     AppState,
     api::models::{
         api_keys::{ApiKeyCreate, ApiKeyInfoResponse, ApiKeyResponse, ApiKeyUpdate},
         pagination::PaginatedResponse,
-        users::CurrentUser,
+        users::{CurrentUser, Role},
     },
     auth::permissions::{
-        can_create_all_resources, can_create_own_resource, can_delete_all_resources, can_delete_own_resource, can_read_all_resources,
-        can_read_own_resource, can_update_all_resources, can_update_own_resource, is_org_member,
+        ApiKeyAccess, RequiresPermission, api_key_access, can_read_all_resources, can_read_own_resource, operation, resource,
     },
-    db::handlers::{Repository, api_keys::ApiKeyFilter, api_keys::ApiKeys},
+    config::ApiKeyPurposesConfig,
+    db::handlers::{Repository, Users, analytics::get_api_key_model_breakdown_for_range, api_keys::ApiKeyFilter, api_keys::ApiKeys},
     db::models::api_keys::{ApiKeyCreateDBRequest, ApiKeyPurpose, ApiKeyUpdateDBRequest},
     errors::{Error, Result},
-    types::{ApiKeyId, Operation, Permission, Resource, UserIdOrCurrent},
+    types::{ApiKeyId, Operation, Permission, Resource, UserId, UserIdOrCurrent},
 };
+use chrono::Utc;
 use rust_decimal::Decimal;
 use sqlx_pool_router::PoolProvider;
 
@@ -25,11 +29,27 @@ use axum::{
     http::StatusCode,
     response::Json,
 };
-use sqlx::Acquire;
+use sqlx::{Acquire, PgConnection};
 
-/// Valid spend-cap reset periods (calendar-aligned UTC; see migration 122/123).
+/// Valid spend-cap reset periods (calendar-aligned in the owner's timezone; see migrations 122/123/134).
 const VALID_CAP_INTERVALS: [&str; 3] = ["daily", "weekly", "monthly"];
 
+/// Longest window accepted by the per-key usage endpoint, matching `/usage`.
+const MAX_USAGE_WINDOW_DAYS: i64 = 180;
+
+/// The error for a user with no [`ApiKeyAccess`] to target_user_id's keys for
+/// the operation whose two scopes are `all` and `own`.
+fn insufficient_api_key_permissions((all, own): (Operation, Operation), target_user_id: UserId) -> Error {
+    Error::InsufficientPermissions {
+        required: Permission::Any(vec![
+            Permission::Allow(Resource::ApiKeys, all),
+            Permission::Allow(Resource::ApiKeys, own),
+        ]),
+        action: own,
+        resource: format!("API keys for user {target_user_id}"),
+    }
+}
+
 /// Validate a (spend_limit, spend_limit_interval) pair as submitted via the
 /// API. Mirrors the DB CHECK constraints so users get a 400 with a message
 /// instead of a 500 from a constraint violation.
@@ -44,7 +64,7 @@ fn validate_cap_fields(spend_limit: Option<&Decimal>, spend_limit_interval: Opti
     if let Some(interval) = spend_limit_interval {
         if !VALID_CAP_INTERVALS.contains(&interval) {
             return Err(Error::BadRequest {
-                message: format!("spend_limit_interval must be one of {VALID_CAP_INTERVALS:?} (calendar-aligned UTC windows)"),
+                message: format!("spend_limit_interval must be one of {VALID_CAP_INTERVALS:?} (calendar-aligned windows)"),
             });
         }
         if spend_limit.is_none() {
@@ -56,6 +76,64 @@ fn validate_cap_fields(spend_limit: Option<&Decimal>, spend_limit_interval: Opti
     Ok(())
 }
 
+/// Validate a requested `max_priority`. Priorities let a key's requests jump
+/// ahead of other callers' queued requests, so only callers who can manage
+/// every key (PlatformManagers) may grant one above the default of 0.
+fn validate_max_priority(max_priority: Option<i16>, can_manage_all: bool) -> Result<()> {
+    let Some(max_priority) = max_priority else {
+        return Ok(());
+    };
+    if !(0..=onwards::priority::MAX_PRIORITY as i16).contains(&max_priority) {
+        return Err(Error::BadRequest {
+            message: format!("max_priority must be between 0 and {}", onwards::priority::MAX_PRIORITY),
+        });
+    }
+    if max_priority > 0 && !can_manage_all {
+        return Err(Error::InsufficientPermissions {
+            required: Permission::Allow(Resource::ApiKeys, Operation::UpdateAll),
+            action: Operation::UpdateAll,
+            resource: "API keys with max_priority (requires PlatformManager)".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Purposes users may create keys with; batch and playground keys are
+/// created by the system
+const CREATABLE_PURPOSES: [ApiKeyPurpose; 2] = [ApiKeyPurpose::Realtime, ApiKeyPurpose::Platform];
+
+/// Purposes allowed to any of `roles` by `auth.api_key_purposes.by_role`. A
+/// role without an entry allows every purpose.
+fn purposes_for_roles(config: &ApiKeyPurposesConfig, roles: &[Role]) -> Vec<ApiKeyPurpose> {
+    CREATABLE_PURPOSES
+        .into_iter()
+        .filter(|purpose| {
+            roles
+                .iter()
+                .any(|role| config.by_role.get(role).is_none_or(|allowed| allowed.contains(purpose)))
+        })
+        .collect()
+}
+
+/// Purposes `owner_id` may have keys created with: an admin's override for
+/// the user if there is one, otherwise what their roles allow. Also returns
+/// whether an override applied.
+pub(crate) async fn allowed_api_key_purposes(
+    conn: &mut PgConnection,
+    config: &ApiKeyPurposesConfig,
+    owner_id: UserId,
+) -> Result<(Vec<ApiKeyPurpose>, bool)> {
+    if let Some(allowed) = ApiKeys::new(conn).get_allowed_purposes(owner_id).await? {
+        return Ok((allowed, true));
+    }
+    let roles = Users::new(conn)
+        .get_by_id(owner_id)
+        .await?
+        .map(|owner| owner.roles)
+        .unwrap_or_default();
+    Ok((purposes_for_roles(config, &roles), false))
+}
+
 /// Create an API key for the current user or a specified user.
 /// This returns `ApiKeyResponse`, which contains the actual API key.
 ///
@@ -73,7 +151,8 @@ fn validate_cap_fields(spend_limit: Option<&Decimal>, spend_limit_interval: Opti
         (status = 201, description = "API key created successfully", body = ApiKeyResponse),
         (status = 400, description = "Bad request - invalid API key data"),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden - can only manage own API keys unless admin"),
+        (status = 403, description = "Forbidden - can only manage own API keys unless admin, or the purpose isn't allowed for the user"),
+        (status = 409, description = "Conflict - the user already has the maximum number of active API keys"),
         (status = 500, description = "Internal server error"),
     ),
     security(
@@ -87,7 +166,7 @@ pub async fn create_user_api_key<P: PoolProvider>(
     State(state): State<AppState<P>>,
     Path(user_id): Path<UserIdOrCurrent>,
     current_user: CurrentUser,
-    Json(data): Json<ApiKeyCreate>,
+    Json(mut data): Json<ApiKeyCreate>,
 ) -> Result<(StatusCode, Json<ApiKeyResponse>)> {
     // Validate input data
     if data.name.trim().is_empty() {
@@ -102,26 +181,20 @@ pub async fn create_user_api_key<P: PoolProvider>(
         UserIdOrCurrent::Id(uuid) => uuid,
     };
 
-    // Check permissions: CreateAll, CreateOwn, or org membership
-    let can_create_all = can_create_all_resources(&current_user, Resource::ApiKeys);
-    let can_create_own = can_create_own_resource(&current_user, Resource::ApiKeys, target_user_id);
+    // Check permissions: CreateAll, CreateOwn, org membership, or delegated group management
+    let access = api_key_access(
+        &current_user,
+        (Operation::CreateAll, Operation::CreateOwn),
+        state.current_config().auth.api_key_manager_role.as_ref(),
+        target_user_id,
+        state.db.read(),
+    )
+    .await?
+    .ok_or_else(|| insufficient_api_key_permissions((Operation::CreateAll, Operation::CreateOwn), target_user_id))?;
+    let can_create_all = access == ApiKeyAccess::All;
+    let delegated = access == ApiKeyAccess::Delegated;
 
-    if !can_create_all && !can_create_own {
-        let mut conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
-        let member = is_org_member(&current_user, target_user_id, &mut conn)
-            .await
-            .map_err(Error::Database)?;
-        if !member {
-            return Err(Error::InsufficientPermissions {
-                required: Permission::Any(vec![
-                    Permission::Allow(Resource::ApiKeys, Operation::CreateAll),
-                    Permission::Allow(Resource::ApiKeys, Operation::CreateOwn),
-                ]),
-                action: Operation::CreateOwn,
-                resource: format!("API keys for user {target_user_id}"),
-            });
-        }
-    }
+    validate_max_priority(data.max_priority, can_create_all)?;
 
     // Only PlatformManagers can specify member_id to attribute a key to another org member
     if data.member_id.is_some() && !can_create_all {
@@ -132,9 +205,12 @@ pub async fn create_user_api_key<P: PoolProvider>(
         });
     }
 
-    // Validate purpose: restrict batch/playground to system use only (purpose defaults to Realtime via serde)
-    match &data.purpose {
-        crate::db::models::api_keys::ApiKeyPurpose::Batch | crate::db::models::api_keys::ApiKeyPurpose::Playground => {
+    // Validate purpose: restrict batch/playground to system use only. An
+    // omitted purpose takes the configured default.
+    let purposes_config = state.current_config().auth.api_key_purposes.clone();
+    let purpose = data.purpose.clone().unwrap_or_else(|| purposes_config.default.clone());
+    match &purpose {
+        ApiKeyPurpose::Batch | ApiKeyPurpose::Playground => {
             return Err(Error::BadRequest {
                 message:
                     "Cannot manually create API keys with 'batch' or 'playground' purpose. These are reserved for internal system use."
@@ -143,9 +219,29 @@ pub async fn create_user_api_key<P: PoolProvider>(
         }
         _ => {}
     }
+    // Delegated managers only hand out inference keys: a platform key would
+    // give them the member's admin API access
+    if delegated && !matches!(purpose, ApiKeyPurpose::Realtime | ApiKeyPurpose::Batch | ApiKeyPurpose::Playground) {
+        return Err(Error::InsufficientPermissions {
+            required: Permission::Allow(Resource::ApiKeys, Operation::CreateAll),
+            action: Operation::CreateAll,
+            resource: format!("{purpose:?} API keys for user {target_user_id}"),
+        });
+    }
 
     let mut pool_conn = state.db.write().acquire().await.map_err(|e| Error::Database(e.into()))?;
 
+    // The purpose must be allowed for the key's owner, whoever creates it
+    let (allowed_purposes, _) = allowed_api_key_purposes(&mut pool_conn, &purposes_config, target_user_id).await?;
+    if !allowed_purposes.contains(&purpose) {
+        return Err(Error::InsufficientPermissions {
+            required: Permission::Granted,
+            action: Operation::CreateOwn,
+            resource: format!("{purpose:?} API keys for user {target_user_id}"),
+        });
+    }
+    data.purpose = Some(purpose);
+
     // Check if target is an organization
     let target_is_org = {
         let mut org_repo = crate::db::handlers::Organizations::new(&mut pool_conn);
@@ -173,10 +269,10 @@ pub async fn create_user_api_key<P: PoolProvider>(
 
     // Determine created_by based on target type:
     // - Organization target: attribute to specified member_id, or current user
-    // - Individual target (PM creating on behalf): attribute to the target user
-    //   so the key is visible to them
+    // - Individual target (PM or delegated manager creating on behalf): attribute
+    //   to the target user so the key is visible to them
     // - Self: attribute to current user
-    let pm_creating_for_other = can_create_all && target_user_id != current_user.id;
+    let pm_creating_for_other = (can_create_all || delegated) && target_user_id != current_user.id;
     let created_by = if target_is_org {
         data.member_id.unwrap_or(current_user.id)
     } else if pm_creating_for_other {
@@ -185,8 +281,52 @@ pub async fn create_user_api_key<P: PoolProvider>(
         current_user.id
     };
 
-    let mut repo = ApiKeys::new(&mut pool_conn);
+    let mut tx = pool_conn.begin().await.map_err(|e| Error::Database(e.into()))?;
+
+    // Enforce the cap on active keys of the key's owner (`limits.api_keys`),
+    // tiered by whether the owner is a platform manager. The owner stays locked
+    // until the key is committed, so concurrent creations can't both pass it.
+    let key_limits = state.current_config().limits.api_keys.clone();
+    if key_limits.max_active_per_user.is_some() || key_limits.platform_manager_max_active_per_user.is_some() {
+        ApiKeys::new(&mut tx).lock_owner(target_user_id).await?;
+        let owner_is_platform_manager = if target_user_id == current_user.id {
+            current_user.is_admin || current_user.roles.contains(&Role::PlatformManager)
+        } else {
+            let mut users_repo = Users::new(&mut tx);
+            users_repo
+                .get_by_id(target_user_id)
+                .await?
+                .is_some_and(|owner| owner.is_admin || owner.roles.contains(&Role::PlatformManager))
+        };
+        let max_active = if owner_is_platform_manager {
+            key_limits.platform_manager_max_active_per_user
+        } else {
+            key_limits.max_active_per_user
+        };
+        if let Some(max_active) = max_active {
+            let active = ApiKeys::new(&mut tx)
+                .count(&ApiKeyFilter {
+                    skip: 0,
+                    limit: i64::MAX,
+                    user_id: Some(target_user_id),
+                    created_by: None,
+                })
+                .await?;
+            if active >= i64::from(max_active) {
+                return Err(Error::Conflict {
+                    message: format!(
+                        "User {target_user_id} already has the maximum of {max_active} active API keys. \
+                         Delete or rotate an existing key before creating a new one."
+                    ),
+                    conflicts: None,
+                });
+            }
+        }
+    }
+
+    let mut repo = ApiKeys::new(&mut tx);
     let has_cap = data.spend_limit.is_some();
+    let has_model_restrictions = data.allowed_model_ids.is_some() || data.denied_model_ids.is_some();
     let db_request = ApiKeyCreateDBRequest::new(target_user_id, created_by, data);
 
     let api_key = repo.create(&db_request).await?;
@@ -194,14 +334,18 @@ pub async fn create_user_api_key<P: PoolProvider>(
     // Capped keys need their cap scope provisioned up front: the hidden batch
     // child (so batch/flex traffic executes inside the scope, and is in
     // onwards' key set before the first request fires) and a zeroed spend
-    // window (the cap counts from now).
-    if has_cap {
+    // window (the cap counts from now). Model-restricted keys need the child
+    // too, or their batch/flex work would run on the unrestricted shared key.
+    if has_cap || has_model_restrictions {
         repo.get_or_create_child_hidden_key(api_key.id).await?;
+    }
+    if has_cap {
         repo.reset_spend_window(api_key.id).await?;
     }
 
     let key_id = api_key.id;
     let spend_states = repo.get_spend_states(&[key_id]).await?;
+    tx.commit().await.map_err(|e| Error::Database(e.into()))?;
 
     // api_key.created webhook deliveries are created by the notification poller
     // via PG LISTEN/NOTIFY on the api_keys table.
@@ -249,29 +393,20 @@ pub async fn list_user_api_keys<P: PoolProvider>(
         UserIdOrCurrent::Id(uuid) => uuid,
     };
 
-    // Check permissions: ReadAll, ReadOwn, or org membership
-    let can_read_all = can_read_all_resources(&current_user, Resource::ApiKeys);
-    let can_read_own = can_read_own_resource(&current_user, Resource::ApiKeys, target_user_id);
-
-    if !can_read_all && !can_read_own {
-        let mut conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
-        let member = is_org_member(&current_user, target_user_id, &mut conn)
-            .await
-            .map_err(Error::Database)?;
-        if !member {
-            return Err(Error::InsufficientPermissions {
-                required: Permission::Any(vec![
-                    Permission::Allow(Resource::ApiKeys, Operation::ReadAll),
-                    Permission::Allow(Resource::ApiKeys, Operation::ReadOwn),
-                ]),
-                action: Operation::ReadOwn,
-                resource: format!("API keys for user {target_user_id}"),
-            });
-        }
-    }
+    // Check permissions: ReadAll, ReadOwn, org membership, or delegated group management
+    let access = api_key_access(
+        &current_user,
+        (Operation::ReadAll, Operation::ReadOwn),
+        state.current_config().auth.api_key_manager_role.as_ref(),
+        target_user_id,
+        state.db.read(),
+    )
+    .await?
+    .ok_or_else(|| insufficient_api_key_permissions((Operation::ReadAll, Operation::ReadOwn), target_user_id))?;
 
-    // PlatformManagers (ReadAll) see all keys for a user; everyone else is scoped to created_by.
-    let skip_created_by_filter = can_read_all;
+    // PlatformManagers (ReadAll) and delegated managers see all keys for a user;
+    // everyone else is scoped to created_by.
+    let skip_created_by_filter = access != ApiKeyAccess::Own;
 
     // Use read replica for this read-only operation
     let mut pool_conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
@@ -346,28 +481,18 @@ pub async fn get_user_api_key<P: PoolProvider>(
         UserIdOrCurrent::Id(uuid) => uuid,
     };
 
-    // Check permissions: ReadAll, ReadOwn, or org membership
-    let can_read_all = can_read_all_resources(&current_user, Resource::ApiKeys);
-    let can_read_own = can_read_own_resource(&current_user, Resource::ApiKeys, target_user_id);
-
-    if !can_read_all && !can_read_own {
-        let mut conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
-        let member = is_org_member(&current_user, target_user_id, &mut conn)
-            .await
-            .map_err(Error::Database)?;
-        if !member {
-            return Err(Error::InsufficientPermissions {
-                required: Permission::Any(vec![
-                    Permission::Allow(Resource::ApiKeys, Operation::ReadAll),
-                    Permission::Allow(Resource::ApiKeys, Operation::ReadOwn),
-                ]),
-                action: Operation::ReadOwn,
-                resource: format!("API keys for user {target_user_id}"),
-            });
-        }
-    }
+    // Check permissions: ReadAll, ReadOwn, org membership, or delegated group management
+    let access = api_key_access(
+        &current_user,
+        (Operation::ReadAll, Operation::ReadOwn),
+        state.current_config().auth.api_key_manager_role.as_ref(),
+        target_user_id,
+        state.db.read(),
+    )
+    .await?
+    .ok_or_else(|| insufficient_api_key_permissions((Operation::ReadAll, Operation::ReadOwn), target_user_id))?;
 
-    let skip_created_by_filter = can_read_all;
+    let skip_created_by_filter = access != ApiKeyAccess::Own;
 
     // Use read replica for this read-only operation
     let mut pool_conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
@@ -390,6 +515,101 @@ pub async fn get_user_api_key<P: PoolProvider>(
     Ok(Json(ApiKeyInfoResponse::from(api_key).with_spend_state(spend_states.get(&key_id))))
 }
 
+/// Get usage for a specific API key over a time window.
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/api-keys/{id}/usage",
+    tag = "api_keys",
+    summary = "Get API key usage",
+    description = "Successful requests, tokens and credits spent through a specific API key over a window \
+                   (default: the last 30 days, at most 180 days)",
+    params(
+        ("user_id" = String, Path, description = "User ID (UUID) or 'current' for current user"),
+        ("id" = uuid::Uuid, Path, description = "API key ID"),
+        ApiKeyUsageQuery,
+    ),
+    responses(
+        (status = 200, description = "API key usage", body = ApiKeyUsageResponse),
+        (status = 400, description = "Invalid window"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - can only view own API keys unless admin"),
+        (status = 404, description = "API key not found"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("BearerAuth" = []),
+        ("CookieAuth" = []),
+        ("X-Doubleword-User" = [])
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_user_api_key_usage<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path((user_id, api_key_id)): Path<(UserIdOrCurrent, ApiKeyId)>,
+    Query(query): Query<ApiKeyUsageQuery>,
+    // Can't use RequiresPermission here because we need conditional logic for own vs other users
+    current_user: CurrentUser,
+) -> Result<Json<ApiKeyUsageResponse>> {
+    let target_user_id = match user_id {
+        UserIdOrCurrent::Current(_) => current_user.id,
+        UserIdOrCurrent::Id(uuid) => uuid,
+    };
+
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query.from.unwrap_or_else(|| to - chrono::Duration::days(30));
+    if from > to {
+        return Err(Error::BadRequest {
+            message: "from must not be after to".to_string(),
+        });
+    }
+    if to - from > chrono::Duration::days(MAX_USAGE_WINDOW_DAYS) {
+        return Err(Error::BadRequest {
+            message: format!("The usage window must not exceed {MAX_USAGE_WINDOW_DAYS} days"),
+        });
+    }
+
+    // Same visibility rules as get_user_api_key
+    let access = api_key_access(
+        &current_user,
+        (Operation::ReadAll, Operation::ReadOwn),
+        state.current_config().auth.api_key_manager_role.as_ref(),
+        target_user_id,
+        state.db.read(),
+    )
+    .await?
+    .ok_or_else(|| insufficient_api_key_permissions((Operation::ReadAll, Operation::ReadOwn), target_user_id))?;
+
+    {
+        let mut pool_conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
+        ApiKeys::new(&mut pool_conn)
+            .get_by_id(api_key_id)
+            .await?
+            .filter(|key| key.user_id == target_user_id)
+            .filter(|key| access != ApiKeyAccess::Own || key.created_by == current_user.id)
+            .ok_or_else(|| Error::NotFound {
+                resource: "API key".to_string(),
+                id: api_key_id.to_string(),
+            })?;
+    }
+
+    let by_model = get_api_key_model_breakdown_for_range(state.db.read(), api_key_id, from, to).await?;
+
+    let total_cost = by_model
+        .iter()
+        .fold(Decimal::ZERO, |acc, e| acc + e.cost.parse::<Decimal>().unwrap_or(Decimal::ZERO));
+
+    Ok(Json(ApiKeyUsageResponse {
+        api_key_id,
+        from,
+        to,
+        total_request_count: by_model.iter().map(|e| e.request_count).sum(),
+        total_input_tokens: by_model.iter().map(|e| e.input_tokens).sum(),
+        total_output_tokens: by_model.iter().map(|e| e.output_tokens).sum(),
+        total_cost: total_cost.to_string(),
+        by_model,
+    }))
+}
+
 /// Update a specific API key: metadata, rate limits, and the spending cap.
 #[utoipa::path(
     patch,
@@ -432,34 +652,26 @@ pub async fn update_user_api_key<P: PoolProvider>(
         UserIdOrCurrent::Id(uuid) => uuid,
     };
 
-    // Check permissions: UpdateAll, UpdateOwn, or org membership — the same
-    // shape as create/delete.
-    let can_update_all = can_update_all_resources(&current_user, Resource::ApiKeys);
-    let can_update_own = can_update_own_resource(&current_user, Resource::ApiKeys, target_user_id);
-
-    if !can_update_all && !can_update_own {
-        let mut conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
-        let member = is_org_member(&current_user, target_user_id, &mut conn)
-            .await
-            .map_err(Error::Database)?;
-        if !member {
-            return Err(Error::InsufficientPermissions {
-                required: Permission::Any(vec![
-                    Permission::Allow(Resource::ApiKeys, Operation::UpdateAll),
-                    Permission::Allow(Resource::ApiKeys, Operation::UpdateOwn),
-                ]),
-                action: Operation::UpdateOwn,
-                resource: format!("API keys for user {target_user_id}"),
-            });
-        }
-    }
+    // Check permissions: UpdateAll, UpdateOwn, org membership, or delegated group
+    // management — the same shape as create/delete.
+    let access = api_key_access(
+        &current_user,
+        (Operation::UpdateAll, Operation::UpdateOwn),
+        state.current_config().auth.api_key_manager_role.as_ref(),
+        target_user_id,
+        state.db.read(),
+    )
+    .await?
+    .ok_or_else(|| insufficient_api_key_permissions((Operation::UpdateAll, Operation::UpdateOwn), target_user_id))?;
+    let can_update_all = access == ApiKeyAccess::All;
 
     // NOTE(org-perms follow-up): updates use the same creator-or-PM scoping
     // as every other key operation — caps are a pure extension on existing
     // behavior. Tightening org-key cap edits to org owners/admins (so members
     // can't raise their own budget) is deliberately deferred to a dedicated
     // org-permissions PR, together with the org key-visibility model.
-    let skip_created_by_filter = can_update_all;
+    let skip_created_by_filter = access != ApiKeyAccess::Own;
+    validate_max_priority(data.max_priority, can_update_all)?;
 
     let mut tx = state.db.write().begin().await.map_err(|e| Error::Database(e.into()))?;
     let mut repo = ApiKeys::new(tx.acquire().await.map_err(|e| Error::Database(e.into()))?);
@@ -504,8 +716,15 @@ pub async fn update_user_api_key<P: PoolProvider>(
         });
     }
 
-    // Generic metadata/rate-limit fields via the existing repository update.
-    if data.name.is_some() || data.description.is_some() || data.requests_per_second.is_some() || data.burst_size.is_some() {
+    // Generic metadata, rate-limit and model-restriction fields via the existing repository update.
+    if data.name.is_some()
+        || data.description.is_some()
+        || data.requests_per_second.is_some()
+        || data.burst_size.is_some()
+        || data.allowed_model_ids.is_some()
+        || data.denied_model_ids.is_some()
+        || data.max_priority.is_some()
+    {
         if let Some(name) = &data.name
             && name.trim().is_empty()
         {
@@ -520,6 +739,9 @@ pub async fn update_user_api_key<P: PoolProvider>(
                 description: data.description.clone(),
                 requests_per_second: data.requests_per_second,
                 burst_size: data.burst_size,
+                allowed_model_ids: data.allowed_model_ids.clone(),
+                denied_model_ids: data.denied_model_ids.clone(),
+                max_priority: data.max_priority,
             },
         )
         .await?;
@@ -541,6 +763,11 @@ pub async fn update_user_api_key<P: PoolProvider>(
         // Idempotent — re-capping reuses the existing child.
         repo.get_or_create_child_hidden_key(api_key_id).await?;
     }
+    if matches!(data.allowed_model_ids, Some(Some(_))) || matches!(data.denied_model_ids, Some(Some(_))) {
+        // Restrictions live on the scope root, so batch/flex work needs to run
+        // on the child to inherit them (see create_user_api_key).
+        repo.get_or_create_child_hidden_key(api_key_id).await?;
+    }
     if newly_capped || interval_changed || reset_window {
         repo.reset_spend_window(api_key_id).await?;
     }
@@ -594,28 +821,18 @@ pub async fn delete_user_api_key<P: PoolProvider>(
         UserIdOrCurrent::Id(uuid) => uuid,
     };
 
-    // Check permissions: DeleteAll, DeleteOwn, or org membership
-    let can_delete_all = can_delete_all_resources(&current_user, Resource::ApiKeys);
-    let can_delete_own = can_delete_own_resource(&current_user, Resource::ApiKeys, target_user_id);
-
-    if !can_delete_all && !can_delete_own {
-        let mut conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
-        let member = is_org_member(&current_user, target_user_id, &mut conn)
-            .await
-            .map_err(Error::Database)?;
-        if !member {
-            return Err(Error::InsufficientPermissions {
-                required: Permission::Any(vec![
-                    Permission::Allow(Resource::ApiKeys, Operation::DeleteAll),
-                    Permission::Allow(Resource::ApiKeys, Operation::DeleteOwn),
-                ]),
-                action: Operation::DeleteOwn,
-                resource: format!("API keys for user {target_user_id}"),
-            });
-        }
-    }
+    // Check permissions: DeleteAll, DeleteOwn, org membership, or delegated group management
+    let access = api_key_access(
+        &current_user,
+        (Operation::DeleteAll, Operation::DeleteOwn),
+        state.current_config().auth.api_key_manager_role.as_ref(),
+        target_user_id,
+        state.db.read(),
+    )
+    .await?
+    .ok_or_else(|| insufficient_api_key_permissions((Operation::DeleteAll, Operation::DeleteOwn), target_user_id))?;
 
-    let skip_created_by_filter = can_delete_all;
+    let skip_created_by_filter = access != ApiKeyAccess::Own;
 
     let mut tx = state.db.write().begin().await.map_err(|e| Error::Database(e.into()))?;
     let mut repo = ApiKeys::new(tx.acquire().await.map_err(|e| Error::Database(e.into()))?);
@@ -643,11 +860,204 @@ pub async fn delete_user_api_key<P: PoolProvider>(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Replace the system API key's secret
+///
+/// The system API key is used by the control layer's own background services
+/// (probes, warmups) and by internal tools calling the admin API. Its secret is
+/// generated when the database is first seeded, or taken from `system_api_key`
+/// in the config; this sets it on an existing install.
+#[utoipa::path(
+    put,
+    path = "/system/api-key",
+    tag = "api_keys",
+    summary = "Set system API key",
+    description = "Replace the system API key's secret with the given one, or with a newly generated one when `secret` is omitted. \
+                   Requests using the old secret are rejected once the proxy configuration has reloaded.",
+    request_body = SystemApiKeyUpdate,
+    responses(
+        (status = 200, description = "The system API key's new secret", body = SystemApiKeyResponse),
+        (status = 400, description = "Bad request - secret too short or contains whitespace"),
+        (status = 403, description = "Insufficient permissions"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("BearerAuth" = []),
+        ("CookieAuth" = []),
+        ("X-Doubleword-User" = [])
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn update_system_api_key<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    _: RequiresPermission<resource::System, operation::UpdateAll>,
+    Json(data): Json<SystemApiKeyUpdate>,
+) -> Result<Json<SystemApiKeyResponse>> {
+    let secret = match data.secret {
+        Some(secret) if !crate::crypto::is_valid_api_key_secret(&secret) => {
+            return Err(Error::BadRequest {
+                message: format!(
+                    "secret must be at least {} characters with no whitespace",
+                    crate::crypto::MIN_API_KEY_SECRET_LENGTH
+                ),
+            });
+        }
+        Some(secret) => secret,
+        None => crate::crypto::generate_api_key(),
+    };
+
+    let mut conn = state.db.write().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    ApiKeys::new(&mut conn).set_system_key_secret(&secret).await?;
+    tracing::info!("System API key secret replaced");
+
+    Ok(Json(SystemApiKeyResponse { key: secret }))
+}
+
+/// 404 unless the user exists and isn't deleted
+async fn ensure_user_exists(conn: &mut PgConnection, id: UserId) -> Result<()> {
+    match Users::new(conn).get_by_id(id).await? {
+        Some(_) => Ok(()),
+        None => Err(Error::NotFound {
+            resource: "User".to_string(),
+            id: id.to_string(),
+        }),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/api-key-purposes",
+    tag = "api_keys",
+    summary = "Get a user's allowed API key purposes",
+    description = "The purposes the user may create API keys with, from an admin's override or else \
+                   their roles, and the purpose used when none is given. Users can see their own; \
+                   admins can see anyone's.",
+    params(("user_id" = String, Path, description = "User ID (UUID) or 'current' for current user")),
+    responses(
+        (status = 200, description = "Allowed purposes", body = ApiKeyPurposesResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "User not found"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_user_api_key_purposes<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(user_id): Path<UserIdOrCurrent>,
+    current_user: CurrentUser,
+) -> Result<Json<ApiKeyPurposesResponse>> {
+    let target_user_id = match user_id {
+        UserIdOrCurrent::Current(_) => current_user.id,
+        UserIdOrCurrent::Id(uuid) => uuid,
+    };
+    if !can_read_all_resources(&current_user, Resource::Users) && !can_read_own_resource(&current_user, Resource::Users, target_user_id) {
+        return Err(Error::InsufficientPermissions {
+            required: Permission::Any(vec![
+                Permission::Allow(Resource::Users, Operation::ReadAll),
+                Permission::Allow(Resource::Users, Operation::ReadOwn),
+            ]),
+            action: Operation::ReadAll,
+            resource: format!("API key purposes for user {target_user_id}"),
+        });
+    }
+
+    let mut conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    ensure_user_exists(&mut conn, target_user_id).await?;
+    let config = state.current_config().auth.api_key_purposes.clone();
+    let (allowed, user_override) = allowed_api_key_purposes(&mut conn, &config, target_user_id).await?;
+    Ok(Json(ApiKeyPurposesResponse {
+        allowed,
+        default: config.default,
+        user_override,
+    }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/users/{user_id}/api-key-purposes",
+    tag = "api_keys",
+    summary = "Set a user's allowed API key purposes",
+    description = "Set the purposes the user may create API keys with, replacing what their roles \
+                   allow. Existing keys are not affected.",
+    params(("user_id" = uuid::Uuid, Path, description = "User ID")),
+    request_body = ApiKeyPurposesUpdate,
+    responses(
+        (status = 200, description = "Override set", body = ApiKeyPurposesResponse),
+        (status = 400, description = "Purposes other than 'realtime' and 'platform'"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "User not found"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn set_user_api_key_purposes<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(user_id): Path<UserId>,
+    _: RequiresPermission<resource::Users, operation::UpdateAll>,
+    Json(update): Json<ApiKeyPurposesUpdate>,
+) -> Result<Json<ApiKeyPurposesResponse>> {
+    if !update.allowed.iter().all(|purpose| CREATABLE_PURPOSES.contains(purpose)) {
+        return Err(Error::BadRequest {
+            message: "allowed may only contain 'realtime' and 'platform'".to_string(),
+        });
+    }
+    let allowed: Vec<_> = CREATABLE_PURPOSES
+        .into_iter()
+        .filter(|purpose| update.allowed.contains(purpose))
+        .collect();
+
+    let mut conn = state.db.write().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    ensure_user_exists(&mut conn, user_id).await?;
+    ApiKeys::new(&mut conn).set_allowed_purposes(user_id, &allowed).await?;
+    Ok(Json(ApiKeyPurposesResponse {
+        allowed,
+        default: state.current_config().auth.api_key_purposes.default.clone(),
+        user_override: true,
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/users/{user_id}/api-key-purposes",
+    tag = "api_keys",
+    summary = "Remove a user's allowed API key purposes override",
+    description = "The user goes back to the purposes their roles allow.",
+    params(("user_id" = uuid::Uuid, Path, description = "User ID")),
+    responses(
+        (status = 204, description = "Override removed"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "User not found, or no override set"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn delete_user_api_key_purposes<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(user_id): Path<UserId>,
+    _: RequiresPermission<resource::Users, operation::UpdateAll>,
+) -> Result<StatusCode> {
+    let mut conn = state.db.write().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    ensure_user_exists(&mut conn, user_id).await?;
+    if ApiKeys::new(&mut conn).delete_allowed_purposes(user_id).await? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(Error::NotFound {
+            resource: "API key purposes override".to_string(),
+            id: user_id.to_string(),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::api::models::api_keys::{ApiKeyInfoResponse, ApiKeyResponse};
+    use super::purposes_for_roles;
+    use crate::api::models::api_keys::{ApiKeyInfoResponse, ApiKeyPurposesResponse, ApiKeyResponse, SystemApiKeyResponse};
     use crate::api::models::pagination::PaginatedResponse;
     use crate::api::models::users::Role;
+    use crate::config::ApiKeyPurposesConfig;
+    use crate::db::models::api_keys::ApiKeyPurpose;
     use crate::test::utils::*;
     use serde_json::json;
     use sqlx::PgPool;
@@ -667,6 +1077,20 @@ mod tests {
             .await
     }
 
+    /// GET helper: fetch a key of the given user with its spend state.
+    async fn get_key_info(
+        app: &axum_test::TestServer,
+        user: &crate::api::models::users::UserResponse,
+        key_id: crate::types::ApiKeyId,
+    ) -> ApiKeyInfoResponse {
+        let auth = add_auth_headers(user);
+        app.get(&format!("/admin/api/v1/users/current/api-keys/{key_id}"))
+            .add_header(&auth[0].0, &auth[0].1)
+            .add_header(&auth[1].0, &auth[1].1)
+            .await
+            .json()
+    }
+
     fn window_spend_of(resp: &ApiKeyInfoResponse) -> rust_decimal::Decimal {
         resp.spend.expect("capped key should carry a spend value")
     }
@@ -763,6 +1187,77 @@ mod tests {
         ok.assert_status(axum::http::StatusCode::CREATED);
     }
 
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_daily_cap_resets_at_owner_local_midnight(pool: PgPool) {
+        use rust_decimal::Decimal;
+
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let auth = add_auth_headers(&user);
+        let set_timezone = |timezone: &'static str| {
+            app.patch(&format!("/admin/api/v1/users/{}", user.id))
+                .json(&json!({ "timezone": timezone }))
+                .add_header(&auth[0].0, &auth[0].1)
+                .add_header(&auth[1].0, &auth[1].1)
+        };
+        set_timezone("Mars/Olympus_Mons")
+            .await
+            .assert_status(axum::http::StatusCode::BAD_REQUEST);
+        let response = set_timezone("America/Los_Angeles").await;
+        response.assert_status_ok();
+        assert_eq!(
+            response.json::<crate::api::models::users::UserResponse>().timezone,
+            "America/Los_Angeles"
+        );
+
+        let key = create_test_api_key_for_user(&pool, user.id).await;
+        let resp = patch_key(&app, &user, key.id, json!({"spend_limit": "50", "spend_limit_interval": "daily"})).await;
+        resp.assert_status_ok();
+        let body: ApiKeyInfoResponse = resp.json();
+
+        // The advertised reset is the next midnight in Los Angeles, not UTC midnight.
+        let resets_at = body.resets_at.expect("daily cap advertises its next reset");
+        let is_local_midnight: bool = sqlx::query_scalar("SELECT ($1::timestamptz AT TIME ZONE 'America/Los_Angeles')::time = '00:00'")
+            .bind(resets_at)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(is_local_midnight, "daily cap should reset at local midnight, got {resets_at}");
+        assert_ne!(
+            resets_at.time(),
+            chrono::NaiveTime::MIN,
+            "daily cap should not reset at UTC midnight"
+        );
+
+        // Spend counted just before local midnight belongs to yesterday's window...
+        let local_midnight: chrono::DateTime<chrono::Utc> =
+            sqlx::query_scalar("SELECT date_trunc('day', now() AT TIME ZONE 'America/Los_Angeles') AT TIME ZONE 'America/Los_Angeles'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        sqlx::query("UPDATE api_key_spend_checkpoints SET window_spend = 50, window_started_at = $2 WHERE api_key_id = $1")
+            .bind(key.id)
+            .bind(local_midnight - chrono::Duration::minutes(1))
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(
+            window_spend_of(&get_key_info(&app, &user, key.id).await),
+            Decimal::ZERO,
+            "window should roll over at local midnight"
+        );
+
+        // ...while spend counted since local midnight is still today's.
+        sqlx::query("UPDATE api_key_spend_checkpoints SET window_started_at = $2 WHERE api_key_id = $1")
+            .bind(key.id)
+            .bind(local_midnight)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(window_spend_of(&get_key_info(&app, &user, key.id).await), Decimal::from(50));
+    }
+
     #[sqlx::test]
     #[test_log::test]
     async fn test_patch_api_key_spend_cap_matrix(pool: PgPool) {
@@ -971,7 +1466,7 @@ mod tests {
 
         // Under the cap: both scope keys are in the paid pool.
         let tiers = RateLimitTiersConfig::default();
-        let targets = crate::sync::onwards_config::load_targets_from_db(&pool, &[], false, &tiers)
+        let targets = crate::sync::onwards_config::load_targets_from_db(&pool, &[], false, &tiers, None, None)
             .await
             .unwrap();
         let has_key = |targets: &onwards::target::Targets, secret: &str| {
@@ -991,7 +1486,7 @@ mod tests {
             .execute(&pool)
             .await
             .unwrap();
-        let targets = crate::sync::onwards_config::load_targets_from_db(&pool, &[], false, &tiers)
+        let targets = crate::sync::onwards_config::load_targets_from_db(&pool, &[], false, &tiers, None, None)
             .await
             .unwrap();
         assert!(!has_key(&targets, &created.key), "exhausted root must leave the paid pool");
@@ -1010,7 +1505,14 @@ mod tests {
                 }),
             )
             .layer(axum::middleware::from_fn_with_state(
-                pool.clone(),
+                crate::error_enrichment::ErrorEnrichmentState {
+                    pool: pool.clone(),
+                    config: {
+                        let mut config = crate::test::utils::create_test_config();
+                        config.onwards.unauthorized_models = crate::config::UnauthorizedModelResponse::Forbidden;
+                        config.into()
+                    },
+                },
                 crate::error_enrichment::error_enrichment_middleware,
             ));
         let proxy = axum_test::TestServer::new(router).unwrap();
@@ -1103,6 +1605,162 @@ mod tests {
         response.assert_status_forbidden();
     }
 
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_delegated_manager_manages_group_member_keys(pool: PgPool) {
+        let mut config = create_test_config();
+        config.auth.api_key_manager_role = Some(Role::RequestViewer);
+        let (app, _bg_services) = create_test_app_with_config(pool.clone(), config, false).await;
+        let manager = create_test_user_with_roles(&pool, vec![Role::StandardUser, Role::RequestViewer]).await;
+        let member = create_test_user(&pool, Role::StandardUser).await;
+        let group = create_test_group(&pool).await;
+        add_user_to_group(&pool, manager.id, group.id).await;
+        add_user_to_group(&pool, member.id, group.id).await;
+        let manager_headers = add_auth_headers(&manager);
+        let member_headers = add_auth_headers(&member);
+
+        let response = app
+            .post(&format!("/admin/api/v1/users/{}/api-keys", member.id))
+            .add_header(&manager_headers[0].0, &manager_headers[0].1)
+            .add_header(&manager_headers[1].0, &manager_headers[1].1)
+            .json(&json!({ "name": "Delegated Key", "purpose": "realtime" }))
+            .await;
+        response.assert_status(axum::http::StatusCode::CREATED);
+        let api_key: ApiKeyResponse = response.json();
+
+        // The key is attributed to the member, so both of them see it
+        for headers in [&member_headers, &manager_headers] {
+            let response = app
+                .get(&format!("/admin/api/v1/users/{}/api-keys", member.id))
+                .add_header(&headers[0].0, &headers[0].1)
+                .add_header(&headers[1].0, &headers[1].1)
+                .await;
+            response.assert_status_ok();
+            let keys: PaginatedResponse<ApiKeyInfoResponse> = response.json();
+            assert!(keys.data.iter().any(|key| key.id == api_key.id));
+        }
+
+        let response = app
+            .delete(&format!("/admin/api/v1/users/{}/api-keys/{}", member.id, api_key.id))
+            .add_header(&manager_headers[0].0, &manager_headers[0].1)
+            .add_header(&manager_headers[1].0, &manager_headers[1].1)
+            .await;
+        response.assert_status(axum::http::StatusCode::NO_CONTENT);
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_delegated_manager_denied_for_non_member(pool: PgPool) {
+        let mut config = create_test_config();
+        config.auth.api_key_manager_role = Some(Role::RequestViewer);
+        let (app, _bg_services) = create_test_app_with_config(pool.clone(), config, false).await;
+        let manager = create_test_user_with_roles(&pool, vec![Role::StandardUser, Role::RequestViewer]).await;
+        let outsider = create_test_user(&pool, Role::StandardUser).await;
+        let teammate = create_test_user(&pool, Role::StandardUser).await;
+        let group = create_test_group(&pool).await;
+        add_user_to_group(&pool, manager.id, group.id).await;
+        add_user_to_group(&pool, teammate.id, group.id).await;
+        let data = json!({ "name": "Delegated Key", "purpose": "realtime" });
+
+        // Not in any of the manager's groups
+        let manager_headers = add_auth_headers(&manager);
+        let response = app
+            .post(&format!("/admin/api/v1/users/{}/api-keys", outsider.id))
+            .add_header(&manager_headers[0].0, &manager_headers[0].1)
+            .add_header(&manager_headers[1].0, &manager_headers[1].1)
+            .json(&data)
+            .await;
+        response.assert_status_forbidden();
+        let response = app
+            .get(&format!("/admin/api/v1/users/{}/api-keys", outsider.id))
+            .add_header(&manager_headers[0].0, &manager_headers[0].1)
+            .add_header(&manager_headers[1].0, &manager_headers[1].1)
+            .await;
+        response.assert_status_forbidden();
+
+        // Sharing a group isn't enough without the configured role
+        let teammate_headers = add_auth_headers(&teammate);
+        let response = app
+            .post(&format!("/admin/api/v1/users/{}/api-keys", manager.id))
+            .add_header(&teammate_headers[0].0, &teammate_headers[0].1)
+            .add_header(&teammate_headers[1].0, &teammate_headers[1].1)
+            .json(&data)
+            .await;
+        response.assert_status_forbidden();
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_delegated_manager_denied_for_more_privileged_member(pool: PgPool) {
+        let mut config = create_test_config();
+        config.auth.api_key_manager_role = Some(Role::RequestViewer);
+        let (app, _bg_services) = create_test_app_with_config(pool.clone(), config, false).await;
+        let manager = create_test_user_with_roles(&pool, vec![Role::StandardUser, Role::RequestViewer]).await;
+        let platform_manager = create_test_user(&pool, Role::PlatformManager).await;
+        let admin = create_test_admin_user(&pool, Role::StandardUser).await;
+        let group = create_test_group(&pool).await;
+        add_user_to_group(&pool, manager.id, group.id).await;
+        add_user_to_group(&pool, platform_manager.id, group.id).await;
+        add_user_to_group(&pool, admin.id, group.id).await;
+        let manager_headers = add_auth_headers(&manager);
+
+        // A role the manager lacks, or admin, puts the member out of reach
+        for target in [&platform_manager, &admin] {
+            let existing = create_test_api_key_for_user(&pool, target.id).await;
+            for purpose in ["realtime", "platform"] {
+                let response = app
+                    .post(&format!("/admin/api/v1/users/{}/api-keys", target.id))
+                    .add_header(&manager_headers[0].0, &manager_headers[0].1)
+                    .add_header(&manager_headers[1].0, &manager_headers[1].1)
+                    .json(&json!({ "name": "Delegated Key", "purpose": purpose }))
+                    .await;
+                response.assert_status_forbidden();
+            }
+            let response = app
+                .get(&format!("/admin/api/v1/users/{}/api-keys", target.id))
+                .add_header(&manager_headers[0].0, &manager_headers[0].1)
+                .add_header(&manager_headers[1].0, &manager_headers[1].1)
+                .await;
+            response.assert_status_forbidden();
+            let response = app
+                .delete(&format!("/admin/api/v1/users/{}/api-keys/{}", target.id, existing.id))
+                .add_header(&manager_headers[0].0, &manager_headers[0].1)
+                .add_header(&manager_headers[1].0, &manager_headers[1].1)
+                .await;
+            response.assert_status_forbidden();
+        }
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_delegated_manager_creates_only_inference_keys(pool: PgPool) {
+        let mut config = create_test_config();
+        config.auth.api_key_manager_role = Some(Role::RequestViewer);
+        let (app, _bg_services) = create_test_app_with_config(pool.clone(), config, false).await;
+        let manager = create_test_user_with_roles(&pool, vec![Role::StandardUser, Role::RequestViewer]).await;
+        let member = create_test_user(&pool, Role::StandardUser).await;
+        let group = create_test_group(&pool).await;
+        add_user_to_group(&pool, manager.id, group.id).await;
+        add_user_to_group(&pool, member.id, group.id).await;
+        let manager_headers = add_auth_headers(&manager);
+
+        let response = app
+            .post(&format!("/admin/api/v1/users/{}/api-keys", member.id))
+            .add_header(&manager_headers[0].0, &manager_headers[0].1)
+            .add_header(&manager_headers[1].0, &manager_headers[1].1)
+            .json(&json!({ "name": "Delegated Platform Key", "purpose": "platform" }))
+            .await;
+        response.assert_status_forbidden();
+
+        let response = app
+            .post(&format!("/admin/api/v1/users/{}/api-keys", member.id))
+            .add_header(&manager_headers[0].0, &manager_headers[0].1)
+            .add_header(&manager_headers[1].0, &manager_headers[1].1)
+            .json(&json!({ "name": "Delegated Key", "purpose": "realtime" }))
+            .await;
+        response.assert_status(axum::http::StatusCode::CREATED);
+    }
+
     #[sqlx::test]
     #[test_log::test]
     async fn test_list_user_api_keys(pool: PgPool) {
@@ -2143,6 +2801,60 @@ mod tests {
         response.assert_status_forbidden();
     }
 
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_only_pm_can_raise_max_priority(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let pm = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let alice = create_test_user(&pool, Role::StandardUser).await;
+
+        // A standard user can't give their own key a raised priority
+        let response = app
+            .post("/admin/api/v1/users/current/api-keys")
+            .add_header(&add_auth_headers(&alice)[0].0, &add_auth_headers(&alice)[0].1)
+            .add_header(&add_auth_headers(&alice)[1].0, &add_auth_headers(&alice)[1].1)
+            .json(&json!({"name": "Interactive", "max_priority": 5}))
+            .await;
+        response.assert_status_forbidden();
+
+        let response = app
+            .post("/admin/api/v1/users/current/api-keys")
+            .add_header(&add_auth_headers(&alice)[0].0, &add_auth_headers(&alice)[0].1)
+            .add_header(&add_auth_headers(&alice)[1].0, &add_auth_headers(&alice)[1].1)
+            .json(&json!({"name": "Interactive"}))
+            .await;
+        response.assert_status(axum::http::StatusCode::CREATED);
+        let key: ApiKeyResponse = response.json();
+        assert_eq!(key.max_priority, 0);
+
+        let response = app
+            .patch(&format!("/admin/api/v1/users/{}/api-keys/{}", alice.id, key.id))
+            .add_header(&add_auth_headers(&alice)[0].0, &add_auth_headers(&alice)[0].1)
+            .add_header(&add_auth_headers(&alice)[1].0, &add_auth_headers(&alice)[1].1)
+            .json(&json!({"max_priority": 5}))
+            .await;
+        response.assert_status_forbidden();
+
+        // A PlatformManager can, within 0-9
+        let response = app
+            .patch(&format!("/admin/api/v1/users/{}/api-keys/{}", alice.id, key.id))
+            .add_header(&add_auth_headers(&pm)[0].0, &add_auth_headers(&pm)[0].1)
+            .add_header(&add_auth_headers(&pm)[1].0, &add_auth_headers(&pm)[1].1)
+            .json(&json!({"max_priority": 10}))
+            .await;
+        response.assert_status_bad_request();
+
+        let response = app
+            .patch(&format!("/admin/api/v1/users/{}/api-keys/{}", alice.id, key.id))
+            .add_header(&add_auth_headers(&pm)[0].0, &add_auth_headers(&pm)[0].1)
+            .add_header(&add_auth_headers(&pm)[1].0, &add_auth_headers(&pm)[1].1)
+            .json(&json!({"max_priority": 5}))
+            .await;
+        response.assert_status_ok();
+        let updated: ApiKeyInfoResponse = response.json();
+        assert_eq!(updated.max_priority, 5);
+    }
+
     #[sqlx::test]
     #[test_log::test]
     async fn test_pm_creates_key_for_individual_user_created_by_is_target(pool: PgPool) {
@@ -2166,4 +2878,395 @@ mod tests {
         assert_eq!(key.created_by, alice.id, "created_by should be the target user for individual keys");
         assert_eq!(key.user_id, alice.id, "user_id should be the target user");
     }
+
+    /// Insert an `http_analytics` row attributed to a user's API key.
+    async fn insert_key_analytics(
+        pool: &PgPool,
+        user_id: uuid::Uuid,
+        api_key_id: crate::types::ApiKeyId,
+        timestamp: chrono::DateTime<chrono::Utc>,
+        status_code: i32,
+        (model, prompt_tokens, completion_tokens, cost): (&str, i64, i64, &str),
+    ) {
+        sqlx::query(
+            "INSERT INTO http_analytics (
+                instance_id, correlation_id, timestamp, uri, method, status_code, duration_ms,
+                model, prompt_tokens, completion_tokens, total_tokens, total_cost, user_id, api_key_id
+            ) VALUES ($1, 1, $2, '/ai/v1/chat/completions', 'POST', $3, 100, $4, $5, $6, $7, $8::numeric, $9, $10)",
+        )
+        .bind(uuid::Uuid::new_v4())
+        .bind(timestamp)
+        .bind(status_code)
+        .bind(model)
+        .bind(prompt_tokens)
+        .bind(completion_tokens)
+        .bind(prompt_tokens + completion_tokens)
+        .bind(cost)
+        .bind(user_id)
+        .bind(api_key_id)
+        .execute(pool)
+        .await
+        .expect("Failed to insert analytics row");
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_api_key_usage_totals_sum_to_user_usage(pool: PgPool) {
+        use chrono::{Duration, SecondsFormat, Utc};
+        use rust_decimal::Decimal;
+
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let other = create_test_user(&pool, Role::StandardUser).await;
+        let auth = add_auth_headers(&user);
+
+        let mut keys = Vec::new();
+        for name in ["Key A", "Key B"] {
+            let response = app
+                .post("/admin/api/v1/users/current/api-keys")
+                .json(&json!({ "name": name, "purpose": "realtime" }))
+                .add_header(&auth[0].0, &auth[0].1)
+                .add_header(&auth[1].0, &auth[1].1)
+                .await;
+            response.assert_status(axum::http::StatusCode::CREATED);
+            keys.push(response.json::<ApiKeyResponse>().id);
+        }
+
+        let recent = Utc::now() - Duration::minutes(5);
+        insert_key_analytics(&pool, user.id, keys[0], recent, 200, ("gpt-4", 100, 50, "1.5")).await;
+        insert_key_analytics(&pool, user.id, keys[0], recent, 200, ("small-model", 10, 5, "0.25")).await;
+        insert_key_analytics(&pool, user.id, keys[1], recent, 200, ("gpt-4", 200, 100, "3")).await;
+        // Failed requests and requests outside the window are not counted
+        insert_key_analytics(&pool, user.id, keys[0], recent, 500, ("gpt-4", 1000, 0, "10")).await;
+        insert_key_analytics(&pool, user.id, keys[1], recent - Duration::days(3), 200, ("gpt-4", 1000, 0, "10")).await;
+        crate::db::handlers::analytics::refresh_user_model_usage_daily(&pool)
+            .await
+            .expect("Failed to refresh usage rollup");
+
+        let from = (Utc::now() - Duration::hours(1)).to_rfc3339_opts(SecondsFormat::Secs, true);
+        let to = (Utc::now() + Duration::minutes(1)).to_rfc3339_opts(SecondsFormat::Secs, true);
+
+        let mut key_usage = Vec::new();
+        for key_id in &keys {
+            let response = app
+                .get(&format!("/admin/api/v1/users/current/api-keys/{key_id}/usage?from={from}&to={to}"))
+                .add_header(&auth[0].0, &auth[0].1)
+                .add_header(&auth[1].0, &auth[1].1)
+                .await;
+            response.assert_status_ok();
+            key_usage.push(response.json::<serde_json::Value>());
+        }
+        assert_eq!(key_usage[0]["total_request_count"], 2);
+        assert_eq!(key_usage[0]["by_model"].as_array().unwrap().len(), 2);
+        assert_eq!(key_usage[1]["total_request_count"], 1);
+        assert_eq!(key_usage[1]["total_input_tokens"], 200);
+
+        let response = app
+            .get(&format!("/admin/api/v1/usage?start_date={from}&end_date={to}"))
+            .add_header(&auth[0].0, &auth[0].1)
+            .add_header(&auth[1].0, &auth[1].1)
+            .await;
+        response.assert_status_ok();
+        let user_usage: serde_json::Value = response.json();
+
+        let sum_i64 = |field: &str| key_usage.iter().map(|usage| usage[field].as_i64().unwrap()).sum::<i64>();
+        assert_eq!(sum_i64("total_request_count"), user_usage["total_request_count"].as_i64().unwrap());
+        assert_eq!(sum_i64("total_input_tokens"), user_usage["total_input_tokens"].as_i64().unwrap());
+        assert_eq!(sum_i64("total_output_tokens"), user_usage["total_output_tokens"].as_i64().unwrap());
+        let cost = |usage: &serde_json::Value| usage["total_cost"].as_str().unwrap().parse::<Decimal>().unwrap();
+        let key_cost: Decimal = key_usage.iter().map(cost).sum();
+        assert_eq!(key_cost, Decimal::new(475, 2));
+        assert_eq!(key_cost, cost(&user_usage));
+
+        // Other users cannot read the key's usage
+        let other_auth = add_auth_headers(&other);
+        app.get(&format!("/admin/api/v1/users/{}/api-keys/{}/usage", user.id, keys[0]))
+            .add_header(&other_auth[0].0, &other_auth[0].1)
+            .add_header(&other_auth[1].0, &other_auth[1].1)
+            .await
+            .assert_status_forbidden();
+        app.get(&format!("/admin/api/v1/users/current/api-keys/{}/usage", keys[0]))
+            .add_header(&other_auth[0].0, &other_auth[0].1)
+            .add_header(&other_auth[1].0, &other_auth[1].1)
+            .await
+            .assert_status_not_found();
+
+        // An inverted window is rejected
+        app.get(&format!(
+            "/admin/api/v1/users/current/api-keys/{}/usage?from={to}&to={from}",
+            keys[0]
+        ))
+        .add_header(&auth[0].0, &auth[0].1)
+        .add_header(&auth[1].0, &auth[1].1)
+        .await
+        .assert_status_bad_request();
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_create_api_key_rejected_at_active_key_cap(pool: PgPool) {
+        let mut config = create_test_config();
+        config.limits.api_keys.max_active_per_user = Some(2);
+        config.limits.api_keys.platform_manager_max_active_per_user = Some(3);
+        let (app, _bg_services) = create_test_app_with_config(pool.clone(), config, false).await;
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let manager = create_test_admin_user(&pool, Role::PlatformManager).await;
+
+        let create_key = |creator: &crate::api::models::users::UserResponse, owner: String, name: &str| {
+            let auth = add_auth_headers(creator);
+            app.post(&format!("/admin/api/v1/users/{owner}/api-keys"))
+                .json(&json!({"name": name}))
+                .add_header(&auth[0].0, &auth[0].1)
+                .add_header(&auth[1].0, &auth[1].1)
+        };
+
+        let mut created = Vec::new();
+        for i in 0..2 {
+            let response = create_key(&user, "current".to_string(), &format!("key {i}")).await;
+            response.assert_status(axum::http::StatusCode::CREATED);
+            created.push(response.json::<ApiKeyResponse>());
+        }
+        let response = create_key(&user, "current".to_string(), "one too many").await;
+        response.assert_status(axum::http::StatusCode::CONFLICT);
+        assert!(response.text().contains("maximum of 2 active API keys"));
+
+        // The cap follows the key's owner, not whoever creates it
+        create_key(&manager, user.id.to_string(), "on behalf")
+            .await
+            .assert_status(axum::http::StatusCode::CONFLICT);
+
+        // Deleting a key frees a slot
+        let auth = add_auth_headers(&user);
+        app.delete(&format!("/admin/api/v1/users/current/api-keys/{}", created[0].id))
+            .add_header(&auth[0].0, &auth[0].1)
+            .add_header(&auth[1].0, &auth[1].1)
+            .await
+            .assert_status(axum::http::StatusCode::NO_CONTENT);
+        create_key(&user, "current".to_string(), "replacement")
+            .await
+            .assert_status(axum::http::StatusCode::CREATED);
+
+        // Platform managers get their own, higher cap
+        for i in 0..3 {
+            create_key(&manager, "current".to_string(), &format!("manager key {i}"))
+                .await
+                .assert_status(axum::http::StatusCode::CREATED);
+        }
+        create_key(&manager, "current".to_string(), "manager one too many")
+            .await
+            .assert_status(axum::http::StatusCode::CONFLICT);
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_concurrent_key_creations_respect_cap(pool: PgPool) {
+        let mut config = create_test_config();
+        config.limits.api_keys.max_active_per_user = Some(1);
+        let (app, _bg_services) = create_test_app_with_config(pool.clone(), config, false).await;
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let auth = add_auth_headers(&user);
+
+        let creations = (0..5).map(|i| {
+            app.post("/admin/api/v1/users/current/api-keys")
+                .json(&json!({"name": format!("racing key {i}")}))
+                .add_header(&auth[0].0, &auth[0].1)
+                .add_header(&auth[1].0, &auth[1].1)
+                .into_future()
+        });
+        let statuses: Vec<_> = futures::future::join_all(creations)
+            .await
+            .iter()
+            .map(|response| response.status_code())
+            .collect();
+        assert_eq!(
+            statuses.iter().filter(|status| **status == axum::http::StatusCode::CREATED).count(),
+            1,
+            "{statuses:?}"
+        );
+        assert!(
+            statuses
+                .iter()
+                .all(|status| matches!(*status, axum::http::StatusCode::CREATED | axum::http::StatusCode::CONFLICT)),
+            "{statuses:?}"
+        );
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_platform_managers_exempt_from_unset_key_cap(pool: PgPool) {
+        let mut config = create_test_config();
+        config.limits.api_keys.max_active_per_user = Some(1);
+        let (app, _bg_services) = create_test_app_with_config(pool.clone(), config, false).await;
+        let manager = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let auth = add_auth_headers(&manager);
+
+        for i in 0..3 {
+            app.post("/admin/api/v1/users/current/api-keys")
+                .json(&json!({"name": format!("manager key {i}")}))
+                .add_header(&auth[0].0, &auth[0].1)
+                .add_header(&auth[1].0, &auth[1].1)
+                .await
+                .assert_status(axum::http::StatusCode::CREATED);
+        }
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_update_system_api_key(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let manager = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let system_secret = || async {
+            sqlx::query_scalar!("SELECT secret FROM api_keys WHERE id = '00000000-0000-0000-0000-000000000000'")
+                .fetch_one(&pool)
+                .await
+                .unwrap()
+        };
+        let update = |caller: &crate::api::models::users::UserResponse, body: serde_json::Value| {
+            let auth = add_auth_headers(caller);
+            app.put("/admin/api/v1/system/api-key")
+                .json(&body)
+                .add_header(&auth[0].0, &auth[0].1)
+                .add_header(&auth[1].0, &auth[1].1)
+        };
+
+        let secret = "sk-configured-system-key-0123456789abcdef";
+        let response = update(&manager, json!({"secret": secret})).await;
+        response.assert_status_ok();
+        assert_eq!(response.json::<SystemApiKeyResponse>().key, secret);
+        assert_eq!(system_secret().await, secret);
+
+        // Omitting the secret generates a new one
+        let response = update(&manager, json!({})).await;
+        response.assert_status_ok();
+        let generated = response.json::<SystemApiKeyResponse>().key;
+        assert_ne!(generated, secret);
+        assert_eq!(system_secret().await, generated);
+
+        // Short or whitespace-containing secrets are rejected
+        update(&manager, json!({"secret": "too-short"}))
+            .await
+            .assert_status(axum::http::StatusCode::BAD_REQUEST);
+        update(&manager, json!({"secret": "sk-configured system key 0123456789abcdef"}))
+            .await
+            .assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+        update(&user, json!({"secret": secret}))
+            .await
+            .assert_status(axum::http::StatusCode::FORBIDDEN);
+        assert_eq!(system_secret().await, generated);
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_user_restricted_to_realtime_cannot_create_platform_key(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let manager = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let request = |method: axum::http::Method, caller: &crate::api::models::users::UserResponse, path: String| {
+            let auth = add_auth_headers(caller);
+            app.method(method, &format!("/admin/api/v1/users/{path}"))
+                .add_header(&auth[0].0, &auth[0].1)
+                .add_header(&auth[1].0, &auth[1].1)
+        };
+        let purposes_path = format!("{}/api-key-purposes", user.id);
+        let keys_path = format!("{}/api-keys", user.id);
+
+        // Only admins can restrict a user
+        request(axum::http::Method::PUT, &user, purposes_path.clone())
+            .json(&json!({"allowed": ["realtime", "platform"]}))
+            .await
+            .assert_status_forbidden();
+        request(axum::http::Method::PUT, &manager, purposes_path.clone())
+            .json(&json!({"allowed": ["batch"]}))
+            .await
+            .assert_status(axum::http::StatusCode::BAD_REQUEST);
+        request(axum::http::Method::PUT, &manager, purposes_path.clone())
+            .json(&json!({"allowed": ["realtime"]}))
+            .await
+            .assert_status_ok();
+
+        let response = request(axum::http::Method::GET, &user, "current/api-key-purposes".to_string()).await;
+        response.assert_status_ok();
+        let purposes: ApiKeyPurposesResponse = response.json();
+        assert_eq!(purposes.allowed, vec![ApiKeyPurpose::Realtime]);
+        assert_eq!(purposes.default, ApiKeyPurpose::Realtime);
+        assert!(purposes.user_override);
+
+        request(axum::http::Method::POST, &user, keys_path.clone())
+            .json(&json!({"name": "platform", "purpose": "platform"}))
+            .await
+            .assert_status_forbidden();
+        request(axum::http::Method::POST, &user, keys_path.clone())
+            .json(&json!({"name": "realtime", "purpose": "realtime"}))
+            .await
+            .assert_status(axum::http::StatusCode::CREATED);
+
+        // The restriction follows the key's owner, not whoever creates it
+        request(axum::http::Method::POST, &manager, keys_path.clone())
+            .json(&json!({"name": "on behalf", "purpose": "platform"}))
+            .await
+            .assert_status_forbidden();
+
+        // Removing the override goes back to what the user's roles allow
+        request(axum::http::Method::DELETE, &manager, purposes_path.clone())
+            .await
+            .assert_status(axum::http::StatusCode::NO_CONTENT);
+        request(axum::http::Method::DELETE, &manager, purposes_path)
+            .await
+            .assert_status_not_found();
+        request(axum::http::Method::POST, &user, keys_path)
+            .json(&json!({"name": "platform", "purpose": "platform"}))
+            .await
+            .assert_status(axum::http::StatusCode::CREATED);
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_configured_default_and_role_purposes(pool: PgPool) {
+        let mut config = create_test_config();
+        config.auth.api_key_purposes = ApiKeyPurposesConfig {
+            default: ApiKeyPurpose::Platform,
+            by_role: [
+                (Role::StandardUser, vec![ApiKeyPurpose::Realtime]),
+                (Role::PlatformManager, vec![ApiKeyPurpose::Realtime, ApiKeyPurpose::Platform]),
+            ]
+            .into(),
+        };
+        let (app, _bg_services) = create_test_app_with_config(pool.clone(), config, false).await;
+        let manager = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let create_key = |caller: &crate::api::models::users::UserResponse, body: serde_json::Value| {
+            let auth = add_auth_headers(caller);
+            app.post("/admin/api/v1/users/current/api-keys")
+                .json(&body)
+                .add_header(&auth[0].0, &auth[0].1)
+                .add_header(&auth[1].0, &auth[1].1)
+        };
+
+        // The default purpose is applied when the request doesn't give one
+        let response = create_key(&manager, json!({"name": "no purpose"})).await;
+        response.assert_status(axum::http::StatusCode::CREATED);
+        assert_eq!(response.json::<ApiKeyResponse>().purpose, ApiKeyPurpose::Platform);
+
+        create_key(&user, json!({"name": "no purpose"})).await.assert_status_forbidden();
+        let response = create_key(&user, json!({"name": "realtime", "purpose": "realtime"})).await;
+        response.assert_status(axum::http::StatusCode::CREATED);
+        assert_eq!(response.json::<ApiKeyResponse>().purpose, ApiKeyPurpose::Realtime);
+    }
+
+    #[test]
+    fn test_purposes_for_roles_is_union_of_roles() {
+        let config = ApiKeyPurposesConfig {
+            default: ApiKeyPurpose::Realtime,
+            by_role: [(Role::StandardUser, vec![ApiKeyPurpose::Realtime]), (Role::BillingManager, vec![])].into(),
+        };
+        assert_eq!(purposes_for_roles(&config, &[Role::StandardUser]), vec![ApiKeyPurpose::Realtime]);
+        assert_eq!(purposes_for_roles(&config, &[Role::BillingManager]), vec![]);
+        // Roles without an entry allow every purpose
+        assert_eq!(
+            purposes_for_roles(&config, &[Role::StandardUser, Role::RequestViewer]),
+            vec![ApiKeyPurpose::Realtime, ApiKeyPurpose::Platform]
+        );
+    }
 }
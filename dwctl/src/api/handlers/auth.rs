@@ -3,6 +3,7 @@
 use axum::{
     Json,
     extract::{Path, State},
+    http::{HeaderMap, header},
 };
 use uuid::Uuid;
 
@@ -19,13 +20,14 @@ use crate::{
     },
     auth::{password, session},
     db::{
-        handlers::{Deployments, PasswordResetTokens, Repository, Users, api_keys::ApiKeys, credits::Credits},
+        handlers::{Deployments, PasswordResetTokens, Repository, Sessions, Users, api_keys::ApiKeys, credits::Credits},
         models::{
             api_keys::ApiKeyPurpose, credits::CreditTransactionCreateDBRequest, deployments::ModelStatus, users::UserCreateDBRequest,
         },
     },
     email::EmailService,
     errors::Error,
+    types::{Operation, Permission, UserId},
 };
 
 /// Get registration information
@@ -73,6 +75,7 @@ pub async fn get_registration_info<P: PoolProvider>(State(state): State<AppState
 #[tracing::instrument(skip_all)]
 pub async fn register<P: PoolProvider>(
     State(state): State<AppState<P>>,
+    headers: HeaderMap,
     Json(request): Json<RegisterRequest>,
 ) -> Result<RegisterResponse, Error> {
     let config = state.current_config();
@@ -179,7 +182,8 @@ pub async fn register<P: PoolProvider>(
     let current_user = CurrentUser::from(created_user);
 
     // Create session token
-    let token = session::create_session_token(&current_user, &config)?;
+    let mut conn = state.db.write().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    let token = session::start_session(&mut conn, &current_user, user_agent(&headers), &config).await?;
 
     // Set session cookie
     let cookie = create_session_cookie(&token, &config);
@@ -215,6 +219,7 @@ pub async fn create_sample_files_for_new_user<P: PoolProvider>(state: &AppState<
     let mut deployments_repo = Deployments::new(&mut conn);
     let filter = DeploymentFilter::new(0, i64::MAX)
         .with_accessible_to(user_id)
+        .with_approved_only()
         .with_statuses(vec![ModelStatus::Active])
         .with_deleted(false);
     let accessible_deployments = deployments_repo.list(&filter).await.map_err(Error::Database)?;
@@ -284,7 +289,11 @@ pub async fn get_login_info<P: PoolProvider>(State(state): State<AppState<P>>) -
     )
 )]
 #[tracing::instrument(skip_all)]
-pub async fn login<P: PoolProvider>(State(state): State<AppState<P>>, Json(request): Json<LoginRequest>) -> Result<LoginResponse, Error> {
+pub async fn login<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    headers: HeaderMap,
+    Json(request): Json<LoginRequest>,
+) -> Result<LoginResponse, Error> {
     let config = state.current_config();
     // Check if native auth is enabled
     if !config.auth.native.enabled {
@@ -324,11 +333,23 @@ pub async fn login<P: PoolProvider>(State(state): State<AppState<P>>, Json(reque
         });
     }
 
+    // Transparently upgrade hashes computed with weaker Argon2 parameters than configured
+    let password_config = &config.auth.native.password;
+    let argon2_params = password::Argon2Params {
+        memory_kib: password_config.argon2_memory_kib,
+        iterations: password_config.argon2_iterations,
+        parallelism: password_config.argon2_parallelism,
+    };
+    if password::needs_rehash(password_hash, argon2_params) {
+        upgrade_password_hash(&state, user.id, password_hash, &request.password, argon2_params).await;
+    }
+
     let user_response = UserResponse::from(user.clone());
     let current_user = CurrentUser::from(user);
 
     // Create session token
-    let token = session::create_session_token(&current_user, &config)?;
+    let mut conn = state.db.write().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    let token = session::start_session(&mut conn, &current_user, user_agent(&headers), &config).await?;
 
     // Set session cookie
     let cookie = create_session_cookie(&token, &config);
@@ -341,21 +362,71 @@ pub async fn login<P: PoolProvider>(State(state): State<AppState<P>>, Json(reque
     Ok(LoginResponse { auth_response, cookie })
 }
 
+/// Re-hash a just-verified password with the configured parameters. Failures are logged
+/// rather than returned: the login itself has already succeeded.
+async fn upgrade_password_hash<P: PoolProvider>(
+    state: &AppState<P>,
+    user_id: UserId,
+    current_hash: &str,
+    password: &str,
+    params: password::Argon2Params,
+) {
+    let password = password.to_string();
+    let new_hash = match tokio::task::spawn_blocking(move || password::hash_string_with_params(&password, Some(params))).await {
+        Ok(Ok(hash)) => hash,
+        Ok(Err(e)) => {
+            tracing::warn!(error = %e, "Failed to re-hash password with current parameters");
+            return;
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "Password re-hash task failed");
+            return;
+        }
+    };
+
+    let result = async {
+        let mut conn = state.db.write().acquire().await.map_err(|e| Error::Database(e.into()))?;
+        Ok::<_, Error>(
+            Users::new(&mut conn)
+                .replace_password_hash(user_id, current_hash, &new_hash)
+                .await?,
+        )
+    }
+    .await;
+
+    match result {
+        Ok(true) => tracing::info!(user_id = %user_id, "Upgraded password hash to current Argon2 parameters"),
+        Ok(false) => tracing::debug!(user_id = %user_id, "Password changed during login; skipped hash upgrade"),
+        Err(e) => tracing::warn!(user_id = %user_id, error = %e, "Failed to store upgraded password hash"),
+    }
+}
+
 /// Logout (clear session)
 #[utoipa::path(
     post,
     path = "/authentication/logout",
     tag = "authentication",
     summary = "End session",
-    description = "Log out the current user by clearing the session cookie. After calling this \
-        endpoint, subsequent requests will require re-authentication.",
+    description = "Log out the current user by revoking their session and clearing the session cookie. \
+        After calling this endpoint, subsequent requests will require re-authentication.",
     responses(
         (status = 200, description = "Logout successful", body = AuthSuccessResponse),
     )
 )]
 #[tracing::instrument(skip_all)]
-pub async fn logout<P: PoolProvider>(State(state): State<AppState<P>>) -> Result<LogoutResponse, Error> {
+pub async fn logout<P: PoolProvider>(State(state): State<AppState<P>>, headers: HeaderMap) -> Result<LogoutResponse, Error> {
     let config = state.current_config();
+
+    // Revoke the session, so a copy of the cookie can't be used either
+    if let Some(claims) = headers
+        .get(header::COOKIE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|cookies| session::session_from_cookies(cookies, &config))
+    {
+        let mut conn = state.db.write().acquire().await.map_err(|e| Error::Database(e.into()))?;
+        Sessions::new(&mut conn).revoke(claims.user_id(), claims.sid).await?;
+    }
+
     let session_config = &config.auth.native.session;
     let secure = if session_config.cookie_secure { "; Secure" } else { "" };
 
@@ -515,6 +586,7 @@ pub async fn confirm_password_reset<P: PoolProvider>(
         auto_topup_threshold: None,
         auto_topup_monthly_limit: None,
         zero_data_retention: None,
+        timezone: None,
     };
 
     let mut tx = state.db.write().begin().await.unwrap();
@@ -652,6 +724,7 @@ pub async fn change_password<P: PoolProvider>(
         auto_topup_threshold: None,
         auto_topup_monthly_limit: None,
         zero_data_retention: None,
+        timezone: None,
     };
 
     user_repo.update(current_user.id, &update_request).await?;
@@ -661,6 +734,11 @@ pub async fn change_password<P: PoolProvider>(
     }))
 }
 
+/// The request's User-Agent, recorded with a new session so users can tell their sessions apart
+fn user_agent(headers: &HeaderMap) -> Option<&str> {
+    headers.get(header::USER_AGENT).and_then(|value| value.to_str().ok())
+}
+
 /// Helper function to create a session cookie
 fn create_session_cookie(token: &str, config: &crate::config::Config) -> String {
     let session_config = &config.auth.native.session;
@@ -796,6 +874,24 @@ pub async fn cli_callback<P: PoolProvider>(
     // Create API keys + auth code in a single transaction
     let mut tx = state.db.write().begin().await.map_err(|e| Error::Database(e.into()))?;
 
+    // Both keys' purposes must be allowed for the account they're created for
+    let (allowed_purposes, _) = crate::api::handlers::api_keys::allowed_api_key_purposes(
+        &mut tx,
+        &state.current_config().auth.api_key_purposes,
+        ctx.target_user_id,
+    )
+    .await?;
+    if let Some(purpose) = [ApiKeyPurpose::Realtime, ApiKeyPurpose::Platform]
+        .into_iter()
+        .find(|purpose| !allowed_purposes.contains(purpose))
+    {
+        return Err(Error::InsufficientPermissions {
+            required: Permission::Granted,
+            action: Operation::CreateOwn,
+            resource: format!("{purpose:?} API keys for user {}", ctx.target_user_id),
+        });
+    }
+
     // Include a timestamp in key names to avoid unique constraint conflicts
     // when a user logs in from multiple machines or re-logs on the same machine.
     let timestamp = chrono::Utc::now().format("%Y%m%d-%H%M%S");
@@ -812,6 +908,9 @@ pub async fn cli_callback<P: PoolProvider>(
             created_by: user_id,
             spend_limit: None,
             spend_limit_interval: None,
+            allowed_model_ids: None,
+            denied_model_ids: None,
+            max_priority: 0,
         })
         .await
         .map_err(Error::Database)?
@@ -829,6 +928,9 @@ pub async fn cli_callback<P: PoolProvider>(
             created_by: user_id,
             spend_limit: None,
             spend_limit_interval: None,
+            allowed_model_ids: None,
+            denied_model_ids: None,
+            max_priority: 0,
         })
         .await
         .map_err(Error::Database)?
@@ -1308,6 +1410,70 @@ mod tests {
         assert_eq!(body.message, "Login successful");
     }
 
+    #[sqlx::test]
+    async fn test_login_upgrades_weak_password_hash(pool: PgPool) {
+        let mut config = create_test_config();
+        config.auth.native.enabled = true;
+        config.auth.native.password.argon2_memory_kib = 256;
+        config.auth.native.password.argon2_iterations = 2;
+
+        let state = crate::test::utils::create_test_app_state_with_config(pool.clone(), config).await;
+
+        // Stored hash predates the cost increase
+        let weak_params = password::Argon2Params {
+            memory_kib: 128,
+            iterations: 1,
+            parallelism: 1,
+        };
+        let weak_hash = password::hash_string_with_params("testpassword", Some(weak_params)).unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        let created_user = Users::new(&mut conn)
+            .create(&UserCreateDBRequest {
+                username: "upgradeuser".to_string(),
+                email: "upgrade@example.com".to_string(),
+                display_name: None,
+                avatar_url: None,
+                is_admin: false,
+                roles: vec![Role::StandardUser],
+                auth_source: "native".to_string(),
+                password_hash: Some(weak_hash.clone()),
+                external_user_id: None,
+            })
+            .await
+            .unwrap();
+        drop(conn);
+
+        let app = axum::Router::new()
+            .route("/auth/login", axum::routing::post(login))
+            .with_state(state);
+        let server = TestServer::new(app).unwrap();
+
+        let request = LoginRequest {
+            email: "upgrade@example.com".to_string(),
+            password: "testpassword".to_string(),
+        };
+        server.post("/auth/login").json(&request).await.assert_status_ok();
+
+        let stored: Option<String> = sqlx::query_scalar("SELECT password_hash FROM users WHERE id = $1")
+            .bind(created_user.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let stored = stored.expect("password hash kept");
+        assert_ne!(stored, weak_hash, "weak hash should be replaced");
+        assert!(stored.contains("m=256,t=2,p=1"), "hash should use current params: {stored}");
+        assert!(password::verify_string("testpassword", &stored).unwrap());
+
+        // Logging in again leaves an up-to-date hash untouched
+        server.post("/auth/login").json(&request).await.assert_status_ok();
+        let unchanged: Option<String> = sqlx::query_scalar("SELECT password_hash FROM users WHERE id = $1")
+            .bind(created_user.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(unchanged.as_deref(), Some(stored.as_str()));
+    }
+
     #[sqlx::test]
     async fn test_login_disabled(pool: PgPool) {
         let mut config = create_test_config();
@@ -2593,6 +2759,9 @@ mod tests {
                 created_by: user.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             })
             .await
             .unwrap();
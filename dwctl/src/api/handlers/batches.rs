@@ -15,8 +15,12 @@ use crate::api::models::batches::{
 use crate::api::models::users::CurrentUser;
 use crate::auth::permissions::{RequiresPermission, can_read_all_resources, has_permission, operation, resource};
 use crate::db::handlers::deployments::BatchModelInfo;
-use crate::db::handlers::{BatchTemplates, Connections, Credits, Deployments, Users, api_keys::ApiKeys, repository::Repository};
+use crate::db::handlers::{
+    ArchivedBatches, BatchTemplates, Connections, Credits, Deployments, GroupSpendingLimits, Users, api_keys::ApiKeys,
+    repository::Repository,
+};
 use crate::db::models::api_keys::ApiKeyPurpose;
+use crate::db::models::archived_batches::{ArchivedBatchDBResponse, ArchivedBatchFilter};
 use crate::errors::{Error, Result};
 use crate::types::{Operation, Resource};
 use axum::{
@@ -230,6 +234,16 @@ fn is_batch_owner(current_user: &CurrentUser, created_by: &str) -> bool {
     false
 }
 
+/// Build the response for a batch removed by the retention job from its
+/// archive-time snapshot, marked with `dwext.archived_at`.
+fn to_archived_batch_response(archived: ArchivedBatchDBResponse) -> Result<BatchResponse> {
+    let mut response: BatchResponse = serde_json::from_value(archived.snapshot).map_err(|e| Error::Internal {
+        operation: format!("decode archived batch {}: {}", archived.batch_id, e),
+    })?;
+    response.dwext.get_or_insert_with(Default::default).archived_at = Some(archived.archived_at.timestamp());
+    Ok(response)
+}
+
 /// Enqueue a background job to transition in-flight child requests to
 /// the given target state. Best-effort: logs a warning on failure so the
 /// caller (cancel/delete handler) can still return a success response.
@@ -255,7 +269,7 @@ async fn enqueue_cascade_batch_state<P: PoolProvider>(state: &AppState<P>, batch
 ///
 /// If `creator_email` is provided, it will be injected into the metadata as `created_by_email`.
 /// This is used to populate the email without storing it in the batch metadata (PII concern).
-fn to_batch_response_with_email(batch: fusillade::Batch, creator_email: Option<&str>) -> BatchResponse {
+pub(crate) fn to_batch_response_with_email(batch: fusillade::Batch, creator_email: Option<&str>) -> BatchResponse {
     to_batch_response_enriched(batch, creator_email, None)
 }
 
@@ -285,6 +299,7 @@ fn to_batch_response_enriched(batch: fusillade::Batch, creator_email: Option<&st
             .or_else(|| raw_metadata.get("dw_source_name").cloned()),
         source_file: raw_metadata.get("dw_external_key").cloned(),
         sync_id: raw_metadata.get("dw_sync_id").cloned(),
+        archived_at: None,
     };
 
     // Build user-facing metadata: filter out internal dw_* keys.
@@ -620,6 +635,15 @@ pub async fn create_batch<P: PoolProvider>(
                 message: "The API key used for this batch has reached its spending cap. Raise or remove the cap, or wait for the cap window to reset, then resubmit.".to_string(),
             });
         }
+        // Likewise if a group of the batch's owner has reached its spending
+        // limit: the proxy withdraws all of their keys from priced models.
+        if let Some((group_name, _)) = GroupSpendingLimits::new(&mut conn).find_exhausted_for_user(target_user_id).await? {
+            return Err(Error::SpendCapExceeded {
+                message: format!(
+                    "The group '{group_name}' has reached its spending limit. Ask an administrator to raise it, or wait for the limit window to reset, then resubmit."
+                ),
+            });
+        }
         // Resolve the creditor's verified flag on the same connection (the org in
         // org context, else the user) for the volume cap below — no extra acquire.
         let verified = Users::new(&mut conn).is_verified(target_user_id).await?;
@@ -654,6 +678,37 @@ pub async fn create_batch<P: PoolProvider>(
     )
     .await?;
 
+    // Keep one user from monopolising the daemon with a single huge batch or
+    // many concurrent ones. The active-batch count is best-effort: concurrent
+    // submissions can race past it by a batch or two.
+    let max_requests = config.batches.max_requests_per_batch;
+    if max_requests > 0 && total_requests > i64::try_from(max_requests).unwrap_or(i64::MAX) {
+        return Err(Error::PayloadTooLarge {
+            message: format!(
+                "Input file contains {total_requests} requests, but a batch can contain at most {max_requests}. \
+                 Split the file into smaller batches."
+            ),
+        });
+    }
+    let max_active = config.batches.max_active_batches_per_user;
+    if max_active > 0 {
+        let active = state
+            .request_manager
+            .count_owner_active_batches(&target_user_id.to_string(), true)
+            .await
+            .map_err(|e| Error::Internal {
+                operation: format!("count active batches: {e}"),
+            })?;
+        if active >= i64::try_from(max_active).unwrap_or(i64::MAX) {
+            return Err(Error::TooManyRequests {
+                message: format!(
+                    "You can have at most {max_active} active batches at once. \
+                     Wait for one to finish or cancel it before creating another."
+                ),
+            });
+        }
+    }
+
     let batch_input = fusillade::BatchInput {
         file_id: fusillade::FileId(file_id),
         endpoint: req.endpoint.clone(),
@@ -933,7 +988,9 @@ async fn release_capacity_reservations<P: PoolProvider>(state: &AppState<P>, res
     summary = "Retrieve batch",
     description = "Retrieve the current status and details of a batch.
 
-Poll this endpoint to monitor progress. Results are streamed to `output_file_id` as they complete — you can start downloading results before the batch finishes.",
+Poll this endpoint to monitor progress. Results are streamed to `output_file_id` as they complete — you can start downloading results before the batch finishes.
+
+Batches removed by the retention policy are still returned, with `dwext.archived_at` set. Their files and results have been deleted.",
     responses(
         (status = 200, description = "Batch details including status, progress counts, and output file IDs.", body = BatchResponse),
         (status = 404, description = "Batch not found or you don't have access to it."),
@@ -953,17 +1010,23 @@ pub async fn get_batch<P: PoolProvider>(
         message: "Invalid batch ID format".to_string(),
     })?;
 
-    let batch = state
-        .request_manager
-        .get_batch(fusillade::BatchId(batch_id))
-        .await
-        .map_err(|_| Error::NotFound {
-            resource: "Batch".to_string(),
-            id: batch_id_str.clone(),
-        })?;
+    let can_read_all = can_read_all_resources(&current_user, Resource::Batches);
+    let Ok(batch) = state.request_manager.get_batch(fusillade::BatchId(batch_id)).await else {
+        // Batches removed by the retention job are served from their archive snapshot
+        let mut read_conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
+        let archived = ArchivedBatches::new(&mut read_conn)
+            .get(batch_id)
+            .await
+            .map_err(Error::Database)?
+            .filter(|archived| can_read_all || is_batch_owner(&current_user, &archived.created_by))
+            .ok_or_else(|| Error::NotFound {
+                resource: "Batch".to_string(),
+                id: batch_id_str.clone(),
+            })?;
+        return Ok(Json(to_archived_batch_response(archived)?));
+    };
 
     // Check ownership: users without ReadAll permission can only see their own batches (or org batches)
-    let can_read_all = can_read_all_resources(&current_user, Resource::Batches);
     if !can_read_all && !is_batch_owner(&current_user, &batch.created_by) {
         return Err(Error::NotFound {
             resource: "Batch".to_string(),
@@ -1645,6 +1708,66 @@ pub async fn retry_specific_requests<P: PoolProvider>(
     Ok(Json(to_batch_response_with_email(batch, creator_email.as_deref())))
 }
 
+/// List batches removed by the retention job, newest first, from their archive snapshots.
+async fn list_archived_batches<P: PoolProvider>(
+    state: &AppState<P>,
+    query: &ListBatchesQuery,
+    created_by: Option<String>,
+    limit: i64,
+) -> Result<BatchListResponse> {
+    let archived = {
+        let mut read_conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
+        ArchivedBatches::new(&mut read_conn)
+            .list(&ArchivedBatchFilter {
+                created_by,
+                created_after: query.created_after,
+                created_before: query.created_before,
+                completion_windows: parse_completion_window_filter(query.completion_window.as_deref()),
+                after: query.pagination.after.as_deref().and_then(|after| Uuid::parse_str(after).ok()),
+                limit: limit + 1,
+            })
+            .await
+            .map_err(Error::Database)?
+    };
+
+    let has_more = archived.len() > limit as usize;
+    let archived: Vec<_> = archived.into_iter().take(limit as usize).collect();
+
+    // Analytics rows outlive the batch's requests, so they can still be included
+    let include_analytics = query
+        .include
+        .as_deref()
+        .is_some_and(|include| include.split(',').any(|s| s.trim() == "analytics"));
+    let mut analytics_map: HashMap<Uuid, BatchAnalytics> = if include_analytics && !archived.is_empty() {
+        let batch_ids: Vec<Uuid> = archived.iter().map(|a| a.batch_id).collect();
+        crate::db::handlers::analytics::get_batches_analytics_bulk(state.db.read(), &batch_ids)
+            .await
+            .map_err(|e| Error::Internal {
+                operation: format!("fetch bulk batch analytics: {}", e),
+            })?
+    } else {
+        HashMap::new()
+    };
+
+    let data = archived
+        .into_iter()
+        .map(|archived| {
+            let batch_id = archived.batch_id;
+            let mut response = to_archived_batch_response(archived)?;
+            response.analytics = analytics_map.remove(&batch_id);
+            Ok(response)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(BatchListResponse {
+        object_type: ListObjectType::List,
+        first_id: data.first().map(|b| b.id.clone()),
+        last_id: data.last().map(|b| b.id.clone()),
+        has_more,
+        data,
+    })
+}
+
 #[utoipa::path(
     get,
     path = "/batches",
@@ -1652,7 +1775,9 @@ pub async fn retry_specific_requests<P: PoolProvider>(
     summary = "List batches",
     description = "Returns a paginated list of your batches, newest first.
 
-Use cursor-based pagination: pass `last_id` from the response as the `after` parameter to fetch the next page.",
+Use cursor-based pagination: pass `last_id` from the response as the `after` parameter to fetch the next page.
+
+Batches removed by the retention policy are not included. Pass `archived=true` to list them instead.",
     responses(
         (status = 200, description = "List of batches. Check `has_more` to determine if additional pages exist.", body = BatchListResponse),
         (status = 500, description = "An unexpected error occurred. Retry the request or contact support if the issue persists.")
@@ -1689,6 +1814,15 @@ pub async fn list_batches<P: PoolProvider>(
         Some(current_user.id.to_string())
     };
 
+    if query.archived {
+        if query.search.is_some() || query.member_id.is_some() || query.status.is_some() {
+            return Err(Error::BadRequest {
+                message: "search, member_id and status filters cannot be combined with archived=true".to_string(),
+            });
+        }
+        return list_archived_batches(&state, &query, created_by, limit).await.map(Json);
+    }
+
     // Translate member_id to api_key_ids for fusillade filtering.
     // Uses a short-lived connection so we don't hold it across the fusillade call.
     //
@@ -3758,7 +3892,25 @@ mod tests {
         user: &crate::api::models::users::UserResponse,
         completion_window: &str,
     ) -> axum_test::TestResponse {
-        let jsonl = r#"{"custom_id":"r1","method":"POST","url":"/v1/chat/completions","body":{"model":"gpt-4","messages":[{"role":"user","content":"Hello"}]}}"#;
+        submit_request_batch(app, user, completion_window, 1).await
+    }
+
+    /// Upload a JSONL file with `n` requests and create a batch from it,
+    /// returning the batch-creation response.
+    async fn submit_request_batch(
+        app: &axum_test::TestServer,
+        user: &crate::api::models::users::UserResponse,
+        completion_window: &str,
+        n: usize,
+    ) -> axum_test::TestResponse {
+        let jsonl = (1..=n)
+            .map(|i| {
+                format!(
+                    r#"{{"custom_id":"r{i}","method":"POST","url":"/v1/chat/completions","body":{{"model":"gpt-4","messages":[{{"role":"user","content":"Hello"}}]}}}}"#
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
         let file_part = axum_test::multipart::Part::bytes(jsonl.as_bytes()).file_name("test.jsonl");
         let multipart = axum_test::multipart::MultipartForm::new()
             .add_part("file", file_part)
@@ -3840,6 +3992,9 @@ mod tests {
                 created_by: user.id,
                 spend_limit: Some(rust_decimal::Decimal::from(10)),
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             })
             .await
             .unwrap();
@@ -3936,6 +4091,70 @@ mod tests {
         submit_one_request_batch(&app, &user, "1h").await.assert_status(StatusCode::CREATED);
     }
 
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_create_batch_rejects_oversized_file(pool: PgPool) {
+        let mut config = create_test_config();
+        config.batches.max_requests_per_batch = 2;
+        config.batches.default_throughput = 100.0;
+
+        let (app, _bg_services) = create_test_app_with_config(pool.clone(), config, false).await;
+        let user = setup_batch_user(&pool).await;
+
+        submit_request_batch(&app, &user, "24h", 2).await.assert_status(StatusCode::CREATED);
+
+        let resp = submit_request_batch(&app, &user, "24h", 3).await;
+        resp.assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+        let body = resp.text();
+        assert!(
+            body.contains("3 requests") && body.contains("at most 2"),
+            "expected the size and the limit in the message, got: {body}"
+        );
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_create_batch_rejects_over_active_batch_limit(pool: PgPool) {
+        let mut config = create_test_config();
+        config.batches.max_active_batches_per_user = 2;
+        config.batches.default_throughput = 100.0;
+
+        let (app, _bg_services) = create_test_app_with_config(pool.clone(), config, false).await;
+        let user = setup_batch_user(&pool).await;
+        let other_user = setup_batch_user(&pool).await;
+
+        let first = submit_one_request_batch(&app, &user, "24h").await;
+        first.assert_status(StatusCode::CREATED);
+        submit_one_request_batch(&app, &user, "24h")
+            .await
+            .assert_status(StatusCode::CREATED);
+
+        let resp = submit_one_request_batch(&app, &user, "24h").await;
+        resp.assert_status(StatusCode::TOO_MANY_REQUESTS);
+        let body = resp.text();
+        assert!(
+            body.contains("2 active batches"),
+            "expected the active batch limit in the message, got: {body}"
+        );
+
+        // The limit is per user: others are unaffected.
+        submit_one_request_batch(&app, &other_user, "24h")
+            .await
+            .assert_status(StatusCode::CREATED);
+
+        // Cancelling a batch frees a slot.
+        let batch_id = first.json::<serde_json::Value>()["id"].as_str().unwrap().to_string();
+        let auth = add_auth_headers(&user);
+        app.post(&format!("/ai/v1/batches/{batch_id}/cancel"))
+            .add_header(&auth[0].0, &auth[0].1)
+            .add_header(&auth[1].0, &auth[1].1)
+            .await
+            .assert_status_ok();
+        submit_one_request_batch(&app, &user, "24h")
+            .await
+            .assert_status(StatusCode::CREATED);
+    }
+
     #[sqlx::test]
     #[test_log::test]
     async fn test_create_batch_rejected_with_negative_balance(pool: PgPool) {
@@ -0,0 +1,1055 @@
+//! HTTP handlers for exporting and importing the platform configuration as YAML.
+//!
+//! Export reads endpoints, deployments and groups into a [`PlatformSnapshot`].
+//! Import validates a snapshot and then converges the database onto it in a
+//! single transaction: missing entities are created, entities that differ are
+//! updated and entities absent from the snapshot are removed. The current state
+//! is read back in snapshot form and compared entry by entry, so re-importing
+//! an unchanged export writes nothing.
+//!
+//! Removal follows the admin API: deployments are hidden (soft-deleted), while
+//! endpoints and native groups are deleted. The Everyone group and groups
+//! synced from SSO are never deleted.
+
+use std::collections::{HashMap, HashSet};
+
+use axum::{Json, extract::State, http::header, response::IntoResponse};
+use sqlx::PgConnection;
+use sqlx_pool_router::PoolProvider;
+
+use crate::{
+    AppState,
+    api::{
+        handlers::{
+            deployments::{
+                replace_tariffs, validate_backoff, validate_max_n, validate_max_prompt_length, validate_metadata,
+                validate_reasoning_translation_overrides,
+            },
+            inference_endpoints::{
+                validate_alias_template, validate_auto_sync_interval, validate_body_transform, validate_max_concurrency,
+                validate_reasoning_translation, validate_region,
+            },
+        },
+        models::{
+            config_snapshot::{
+                CONFIG_SNAPSHOT_VERSION, ComponentSnapshot, ConfigImportResponse, DeploymentSnapshot, EndpointSnapshot, GroupSnapshot,
+                PlatformSnapshot,
+            },
+            deployments::{DeployedModelResponse, TariffDefinition},
+        },
+    },
+    auth::permissions::{RequiresPermission, operation, resource},
+    db::{
+        handlers::{
+            Deployments, Groups, InferenceEndpoints, Repository, Tariffs, deployments::DeploymentFilter, groups::GroupFilter,
+            inference_endpoints::InferenceEndpointFilter,
+        },
+        models::{
+            deployments::{DeploymentCreateDBRequest, DeploymentDBResponse, DeploymentUpdateDBRequest},
+            groups::{GroupCreateDBRequest, GroupDBResponse, GroupUpdateDBRequest},
+            inference_endpoints::{
+                EndpointProtocol, InferenceEndpointCreateDBRequest, InferenceEndpointDBResponse, InferenceEndpointUpdateDBRequest,
+            },
+        },
+    },
+    errors::{Error, Result},
+    reasoning::ReasoningTranslationOverrides,
+    types::{DeploymentId, GroupId, InferenceEndpointId, UserId},
+};
+
+const YAML_CONTENT_TYPE: &str = "application/yaml";
+
+fn bad_request(message: impl Into<String>) -> Error {
+    Error::BadRequest { message: message.into() }
+}
+
+/// Prefix a validation error with the entity it came from.
+fn in_context(kind: &'static str, name: &str) -> impl FnOnce(Error) -> Error {
+    let name = name.to_string();
+    move |error| match error {
+        Error::BadRequest { message } => bad_request(format!("{kind} '{name}': {message}")),
+        other => other,
+    }
+}
+
+/// The database state an import converges from.
+struct CurrentState {
+    endpoints: HashMap<String, InferenceEndpointDBResponse>,
+    /// Every deployment by alias, including hidden ones, since aliases stay reserved
+    deployments: HashMap<String, DeploymentDBResponse>,
+    groups: Vec<GroupDBResponse>,
+    group_deployments: HashMap<GroupId, Vec<DeploymentId>>,
+    /// The visible configuration in snapshot form
+    snapshot: PlatformSnapshot,
+}
+
+fn endpoint_snapshot(endpoint: &InferenceEndpointDBResponse) -> EndpointSnapshot {
+    EndpointSnapshot {
+        name: endpoint.name.clone(),
+        description: endpoint.description.clone(),
+        url: endpoint.url.clone(),
+        protocol: endpoint.protocol,
+        model_filter: endpoint.model_filter.clone(),
+        auth_header_name: endpoint.auth_header_name.clone(),
+        auth_header_prefix: endpoint.auth_header_prefix.clone(),
+        reasoning_translation: endpoint.reasoning_translation.clone(),
+        region: endpoint.region.clone(),
+        body_transform: endpoint.body_transform.clone(),
+        auto_sync_interval_seconds: endpoint.auto_sync_interval_seconds,
+        alias_template: endpoint.alias_template.clone(),
+        max_concurrency: endpoint.max_concurrency,
+    }
+}
+
+fn deployment_snapshot(
+    deployment: &DeploymentDBResponse,
+    endpoint: Option<String>,
+    tariffs: Vec<TariffDefinition>,
+    components: Vec<ComponentSnapshot>,
+) -> DeploymentSnapshot {
+    // The API response already decodes the fallback columns and catalog metadata
+    let response = DeployedModelResponse::from(deployment.clone());
+    DeploymentSnapshot {
+        alias: deployment.alias.clone(),
+        model_name: deployment.model_name.clone(),
+        endpoint,
+        display_name: deployment.display_name.clone(),
+        description: deployment.description.clone(),
+        model_type: deployment.model_type.clone(),
+        capabilities: deployment.capabilities.clone(),
+        requests_per_second: deployment.requests_per_second,
+        burst_size: deployment.burst_size,
+        capacity: deployment.capacity,
+        per_key_capacity: deployment.per_key_capacity,
+        max_cost_per_request: deployment.max_cost_per_request,
+        input_modalities: deployment.input_modalities.clone(),
+        output_modalities: deployment.output_modalities.clone(),
+        batch_capacity: deployment.batch_capacity,
+        throughput: deployment.throughput,
+        composite: deployment.is_composite,
+        lb_strategy: response.lb_strategy,
+        fallback: response.fallback.unwrap_or_default(),
+        sanitize_responses: deployment.sanitize_responses,
+        trusted: deployment.trusted,
+        allow_public: deployment.allow_public,
+        require_approval: deployment.require_approval,
+        rewrite_response_model: deployment.rewrite_response_model,
+        disable_logging: deployment.disable_logging,
+        warmup: deployment.warmup,
+        system_prompt: deployment.system_prompt.clone(),
+        system_prompt_mode: deployment.system_prompt_mode,
+        max_n: deployment.max_n,
+        max_n_mode: deployment.max_n_mode,
+        credit_exhaustion_mode: deployment.credit_exhaustion_mode,
+        max_overdraft: deployment.max_overdraft,
+        max_prompt_length: deployment.max_prompt_length,
+        max_prompt_length_unit: deployment.max_prompt_length_unit,
+        open_responses_adapter: deployment.open_responses_adapter,
+        reasoning_translation_overrides: response
+            .reasoning_translation_overrides
+            .filter(|overrides| *overrides != ReasoningTranslationOverrides::default()),
+        allowed_batch_completion_windows: deployment.allowed_batch_completion_windows.clone(),
+        metadata: response.metadata,
+        tariffs,
+        components,
+    }
+}
+
+async fn load_current_state(conn: &mut PgConnection) -> Result<CurrentState> {
+    let endpoints = InferenceEndpoints::new(&mut *conn)
+        .list(&InferenceEndpointFilter::new(0, i64::MAX))
+        .await?;
+    let deployments = Deployments::new(&mut *conn).list(&DeploymentFilter::new(0, i64::MAX)).await?;
+    let groups = Groups::new(&mut *conn).list(&GroupFilter::new(0, i64::MAX)).await?;
+
+    let endpoint_names: HashMap<InferenceEndpointId, String> = endpoints.iter().map(|e| (e.id, e.name.clone())).collect();
+    let visible: Vec<&DeploymentDBResponse> = deployments.iter().filter(|d| !d.deleted).collect();
+    let visible_aliases: HashMap<DeploymentId, String> = visible.iter().map(|d| (d.id, d.alias.clone())).collect();
+
+    let composite_ids = visible.iter().filter(|d| d.is_composite).map(|d| d.id).collect();
+    let mut components = Deployments::new(&mut *conn).get_components_bulk(composite_ids).await?;
+
+    let mut deployment_snapshots = Vec::with_capacity(visible.len());
+    for deployment in &visible {
+        let tariffs = Tariffs::new(&mut *conn)
+            .list_current_by_model(deployment.id)
+            .await?
+            .into_iter()
+            .map(|t| TariffDefinition {
+                name: t.name,
+                input_price_per_token: t.input_price_per_token,
+                output_price_per_token: t.output_price_per_token,
+                api_key_purpose: t.api_key_purpose,
+                completion_window: t.completion_window,
+            })
+            .collect();
+        let components = components
+            .remove(&deployment.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|c| ComponentSnapshot {
+                alias: c.model_alias,
+                weight: c.weight,
+                enabled: c.enabled,
+                sort_order: c.sort_order,
+            })
+            .collect();
+        let endpoint = deployment.hosted_on.and_then(|id| endpoint_names.get(&id).cloned());
+        deployment_snapshots.push(deployment_snapshot(deployment, endpoint, tariffs, components));
+    }
+
+    let group_ids: Vec<GroupId> = groups.iter().map(|g| g.id).collect();
+    let group_deployments = Groups::new(&mut *conn).get_groups_deployments_bulk(&group_ids).await?;
+    let group_snapshots = groups
+        .iter()
+        .map(|group| GroupSnapshot {
+            name: group.name.clone(),
+            description: group.description.clone(),
+            deployments: group_deployments
+                .get(&group.id)
+                .into_iter()
+                .flatten()
+                .filter_map(|id| visible_aliases.get(id).cloned())
+                .collect(),
+        })
+        .collect();
+
+    let mut snapshot = PlatformSnapshot {
+        version: CONFIG_SNAPSHOT_VERSION,
+        endpoints: endpoints.iter().map(endpoint_snapshot).collect(),
+        deployments: deployment_snapshots,
+        groups: group_snapshots,
+    };
+    snapshot.normalize();
+
+    Ok(CurrentState {
+        endpoints: endpoints.into_iter().map(|e| (e.name.clone(), e)).collect(),
+        deployments: deployments.into_iter().map(|d| (d.alias.clone(), d)).collect(),
+        groups,
+        group_deployments,
+        snapshot,
+    })
+}
+
+/// Check a snapshot is self-consistent and its values are ones the admin API would accept.
+fn validate_snapshot(snapshot: &mut PlatformSnapshot, allowed_completion_windows: &[String]) -> Result<()> {
+    if snapshot.version != CONFIG_SNAPSHOT_VERSION {
+        return Err(bad_request(format!(
+            "Unsupported snapshot version {} (expected {CONFIG_SNAPSHOT_VERSION})",
+            snapshot.version
+        )));
+    }
+
+    let mut endpoint_names = HashSet::new();
+    for endpoint in &mut snapshot.endpoints {
+        if endpoint.name.trim().is_empty() {
+            return Err(bad_request("Endpoint names must not be empty"));
+        }
+        if !endpoint_names.insert(endpoint.name.clone()) {
+            return Err(bad_request(format!("Endpoint '{}' is listed more than once", endpoint.name)));
+        }
+        let context = || in_context("endpoint", &endpoint.name);
+        validate_reasoning_translation(endpoint.reasoning_translation.as_ref()).map_err(context())?;
+        validate_body_transform(endpoint.body_transform.as_ref()).map_err(context())?;
+        validate_auto_sync_interval(endpoint.auto_sync_interval_seconds).map_err(context())?;
+        validate_alias_template(endpoint.alias_template.as_deref()).map_err(context())?;
+        validate_max_concurrency(endpoint.max_concurrency).map_err(context())?;
+        endpoint.region = validate_region(endpoint.region.take()).map_err(context())?;
+    }
+
+    let composites: HashMap<&str, bool> = snapshot.deployments.iter().map(|d| (d.alias.as_str(), d.composite)).collect();
+    if composites.len() != snapshot.deployments.len() {
+        let mut seen = HashSet::new();
+        let duplicate = snapshot
+            .deployments
+            .iter()
+            .find(|d| !seen.insert(&d.alias))
+            .map(|d| d.alias.as_str());
+        return Err(bad_request(format!(
+            "Deployment '{}' is listed more than once",
+            duplicate.unwrap_or_default()
+        )));
+    }
+    for deployment in &snapshot.deployments {
+        let context = || in_context("deployment", &deployment.alias);
+        if deployment.alias.trim().is_empty() || deployment.model_name.trim().is_empty() {
+            return Err(bad_request("Model name and alias must not be empty or whitespace"));
+        }
+        match (&deployment.endpoint, deployment.composite) {
+            (Some(_), true) => return Err(context()(bad_request("composite models must not name an endpoint"))),
+            (None, false) => return Err(context()(bad_request("standard models must name an endpoint"))),
+            (Some(endpoint), false) if !endpoint_names.contains(endpoint) => {
+                return Err(context()(bad_request(format!("unknown endpoint '{endpoint}'"))));
+            }
+            _ => {}
+        }
+        if !deployment.composite && !deployment.components.is_empty() {
+            return Err(context()(bad_request("only composite models have components")));
+        }
+        if deployment.composite && deployment.reasoning_translation_overrides.is_some() {
+            return Err(context()(bad_request(
+                "reasoning_translation_overrides is only supported for standard models",
+            )));
+        }
+        if let Some(t) = deployment.throughput
+            && t <= 0.0
+        {
+            return Err(context()(bad_request(format!("throughput must be positive (> 0), got {t}"))));
+        }
+        for window in deployment.allowed_batch_completion_windows.iter().flatten() {
+            if !allowed_completion_windows.contains(window) {
+                return Err(context()(bad_request(format!(
+                    "Invalid batch completion window '{}'. Configured windows: {}",
+                    window,
+                    allowed_completion_windows.join(", ")
+                ))));
+            }
+        }
+        if let Some(metadata) = &deployment.metadata {
+            validate_metadata(metadata).map_err(context())?;
+        }
+        validate_reasoning_translation_overrides(deployment.reasoning_translation_overrides.as_ref()).map_err(context())?;
+        validate_max_n(deployment.max_n).map_err(context())?;
+        validate_max_prompt_length(deployment.max_prompt_length).map_err(context())?;
+        let backoff = deployment.fallback.backoff.as_ref();
+        validate_backoff(
+            backoff.map(|b| b.initial_ms),
+            backoff.map(|b| b.max_ms),
+            backoff.map(|b| b.factor),
+            deployment.fallback.max_total_backoff_ms,
+        )
+        .map_err(context())?;
+
+        let mut component_aliases = HashSet::new();
+        for component in &deployment.components {
+            match composites.get(component.alias.as_str()) {
+                None => return Err(context()(bad_request(format!("unknown component '{}'", component.alias)))),
+                Some(true) => {
+                    return Err(context()(bad_request(format!(
+                        "component '{}' is itself a composite model",
+                        component.alias
+                    ))));
+                }
+                Some(false) => {}
+            }
+            if !component_aliases.insert(component.alias.as_str()) {
+                return Err(context()(bad_request(format!(
+                    "component '{}' is listed more than once",
+                    component.alias
+                ))));
+            }
+            if !(1..=100).contains(&component.weight) {
+                return Err(context()(bad_request(format!(
+                    "component '{}' weight must be between 1 and 100",
+                    component.alias
+                ))));
+            }
+        }
+    }
+
+    let mut group_names = HashSet::new();
+    for group in &snapshot.groups {
+        if group.name.trim().is_empty() {
+            return Err(bad_request("Group names must not be empty"));
+        }
+        if !group_names.insert(group.name.as_str()) {
+            return Err(bad_request(format!("Group '{}' is listed more than once", group.name)));
+        }
+        let mut aliases = HashSet::new();
+        for alias in &group.deployments {
+            if !composites.contains_key(alias.as_str()) {
+                return Err(in_context("group", &group.name)(bad_request(format!(
+                    "unknown deployment '{alias}'"
+                ))));
+            }
+            if !aliases.insert(alias.as_str()) {
+                return Err(in_context("group", &group.name)(bad_request(format!(
+                    "deployment '{alias}' is listed more than once"
+                ))));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check the snapshot can be applied on top of the current state without losing data.
+fn validate_against_current(snapshot: &PlatformSnapshot, current: &CurrentState) -> Result<()> {
+    for endpoint in &snapshot.endpoints {
+        match current.endpoints.get(&endpoint.name) {
+            Some(existing) if existing.protocol != endpoint.protocol => {
+                return Err(in_context("endpoint", &endpoint.name)(bad_request(format!(
+                    "the protocol of an existing endpoint cannot be changed (currently {})",
+                    existing.protocol.as_str()
+                ))));
+            }
+            None if endpoint.protocol == EndpointProtocol::Bedrock => {
+                return Err(in_context("endpoint", &endpoint.name)(bad_request(
+                    "Bedrock endpoints must be created with their credentials before import",
+                )));
+            }
+            _ => {}
+        }
+    }
+
+    let endpoint_names: HashMap<InferenceEndpointId, &str> = current.endpoints.values().map(|e| (e.id, e.name.as_str())).collect();
+    for deployment in &snapshot.deployments {
+        let Some(existing) = current.deployments.get(&deployment.alias) else {
+            continue;
+        };
+        if existing.is_composite != deployment.composite {
+            return Err(in_context("deployment", &deployment.alias)(bad_request(
+                "an existing model cannot be switched between standard and composite",
+            )));
+        }
+        let existing_endpoint = existing.hosted_on.and_then(|id| endpoint_names.get(&id).copied());
+        if !deployment.composite && existing_endpoint != deployment.endpoint.as_deref() {
+            return Err(in_context("deployment", &deployment.alias)(bad_request(format!(
+                "the model is hosted on endpoint '{}' and cannot be moved to another endpoint",
+                existing_endpoint.unwrap_or_default()
+            ))));
+        }
+    }
+
+    for group in &snapshot.groups {
+        if current.groups.iter().filter(|g| g.name == group.name).count() > 1 {
+            return Err(in_context("group", &group.name)(bad_request(
+                "more than one existing group has this name",
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn endpoint_create_request(endpoint: &EndpointSnapshot, created_by: UserId) -> InferenceEndpointCreateDBRequest {
+    InferenceEndpointCreateDBRequest {
+        created_by,
+        name: endpoint.name.clone(),
+        description: endpoint.description.clone(),
+        url: endpoint.url.clone(),
+        api_key: None,
+        model_filter: endpoint.model_filter.clone(),
+        auth_header_name: Some(endpoint.auth_header_name.clone()),
+        auth_header_prefix: Some(endpoint.auth_header_prefix.clone()),
+        reasoning_translation: endpoint.reasoning_translation.clone(),
+        bedrock: None,
+        region: endpoint.region.clone(),
+        body_transform: endpoint.body_transform.clone(),
+        auto_sync_interval_seconds: endpoint.auto_sync_interval_seconds,
+        alias_template: endpoint.alias_template.clone(),
+        max_concurrency: endpoint.max_concurrency,
+        tls: None,
+        additional_api_keys: Vec::new(),
+        api_key_rotation: None,
+    }
+}
+
+/// Update everything but the name and credentials, which are left as they are.
+fn endpoint_update_request(endpoint: &EndpointSnapshot) -> InferenceEndpointUpdateDBRequest {
+    InferenceEndpointUpdateDBRequest {
+        name: None,
+        description: endpoint.description.clone(),
+        url: Some(endpoint.url.clone()),
+        api_key: None,
+        model_filter: Some(endpoint.model_filter.clone()),
+        auth_header_name: Some(endpoint.auth_header_name.clone()),
+        auth_header_prefix: Some(endpoint.auth_header_prefix.clone()),
+        reasoning_translation: Some(endpoint.reasoning_translation.clone()),
+        bedrock: None,
+        region: Some(endpoint.region.clone()),
+        body_transform: Some(endpoint.body_transform.clone()),
+        auto_sync_interval_seconds: Some(endpoint.auto_sync_interval_seconds),
+        alias_template: Some(endpoint.alias_template.clone()),
+        max_concurrency: Some(endpoint.max_concurrency),
+        tls: None,
+        additional_api_keys: None,
+        api_key_rotation: None,
+    }
+}
+
+fn deployment_create_request(
+    deployment: &DeploymentSnapshot,
+    hosted_on: Option<InferenceEndpointId>,
+    created_by: UserId,
+) -> DeploymentCreateDBRequest {
+    let fallback = &deployment.fallback;
+    let backoff = fallback.backoff.as_ref();
+    DeploymentCreateDBRequest::builder()
+        .created_by(created_by)
+        .model_name(deployment.model_name.clone())
+        .alias(deployment.alias.clone())
+        .maybe_display_name(deployment.display_name.clone())
+        .maybe_description(deployment.description.clone())
+        .maybe_model_type(deployment.model_type.clone())
+        .maybe_capabilities(deployment.capabilities.clone())
+        .maybe_hosted_on(hosted_on)
+        .maybe_requests_per_second(deployment.requests_per_second)
+        .maybe_burst_size(deployment.burst_size)
+        .maybe_capacity(deployment.capacity)
+        .maybe_per_key_capacity(deployment.per_key_capacity)
+        .maybe_max_cost_per_request(deployment.max_cost_per_request)
+        .maybe_input_modalities(deployment.input_modalities.clone())
+        .maybe_output_modalities(deployment.output_modalities.clone())
+        .maybe_batch_capacity(deployment.batch_capacity)
+        .maybe_throughput(deployment.throughput)
+        .is_composite(deployment.composite)
+        .maybe_lb_strategy(deployment.lb_strategy)
+        .fallback_enabled(fallback.enabled)
+        .fallback_on_rate_limit(fallback.on_rate_limit)
+        .fallback_on_status(fallback.on_status.clone())
+        .fallback_with_replacement(fallback.with_replacement)
+        .maybe_fallback_max_attempts(fallback.max_attempts)
+        .backoff_enabled(backoff.is_some())
+        .maybe_backoff_initial_ms(backoff.map(|b| b.initial_ms))
+        .maybe_backoff_max_ms(backoff.map(|b| b.max_ms))
+        .maybe_backoff_factor(backoff.map(|b| b.factor))
+        .maybe_backoff_jitter(backoff.map(|b| b.jitter.as_db_str().to_string()))
+        .maybe_backoff_max_total_ms(fallback.max_total_backoff_ms)
+        .sanitize_responses(deployment.sanitize_responses)
+        .trusted(deployment.trusted)
+        .allow_public(deployment.allow_public)
+        .require_approval(deployment.require_approval)
+        .rewrite_response_model(deployment.rewrite_response_model)
+        .disable_logging(deployment.disable_logging)
+        .warmup(deployment.warmup)
+        .maybe_system_prompt(deployment.system_prompt.clone())
+        .system_prompt_mode(deployment.system_prompt_mode)
+        .maybe_max_n(deployment.max_n)
+        .max_n_mode(deployment.max_n_mode)
+        .credit_exhaustion_mode(deployment.credit_exhaustion_mode)
+        .maybe_max_overdraft(deployment.max_overdraft)
+        .maybe_max_prompt_length(deployment.max_prompt_length)
+        .max_prompt_length_unit(deployment.max_prompt_length_unit)
+        .open_responses_adapter(deployment.open_responses_adapter)
+        .maybe_reasoning_translation_overrides(deployment.reasoning_translation_overrides.clone())
+        .maybe_allowed_batch_completion_windows(deployment.allowed_batch_completion_windows.clone())
+        .maybe_metadata(deployment.metadata.clone())
+        .build()
+}
+
+/// Update every field the snapshot carries, and unhide the model if it was hidden.
+fn deployment_update_request(deployment: &DeploymentSnapshot) -> DeploymentUpdateDBRequest {
+    let fallback = &deployment.fallback;
+    let backoff = fallback.backoff.as_ref();
+    DeploymentUpdateDBRequest::builder()
+        .model_name(deployment.model_name.clone())
+        .maybe_display_name(deployment.display_name.clone())
+        .description(deployment.description.clone())
+        .model_type(deployment.model_type.clone())
+        .capabilities(deployment.capabilities.clone())
+        .deleted(false)
+        .requests_per_second(deployment.requests_per_second)
+        .burst_size(deployment.burst_size)
+        .capacity(deployment.capacity)
+        .per_key_capacity(deployment.per_key_capacity)
+        .max_cost_per_request(deployment.max_cost_per_request)
+        .input_modalities(deployment.input_modalities.clone())
+        .output_modalities(deployment.output_modalities.clone())
+        .batch_capacity(deployment.batch_capacity)
+        .throughput(deployment.throughput)
+        .maybe_lb_strategy(deployment.lb_strategy)
+        .fallback_enabled(fallback.enabled)
+        .fallback_on_rate_limit(fallback.on_rate_limit)
+        .fallback_on_status(fallback.on_status.clone())
+        .fallback_with_replacement(fallback.with_replacement)
+        .fallback_max_attempts(fallback.max_attempts)
+        .backoff_enabled(backoff.is_some())
+        .maybe_backoff_initial_ms(backoff.map(|b| b.initial_ms))
+        .maybe_backoff_max_ms(backoff.map(|b| b.max_ms))
+        .maybe_backoff_factor(backoff.map(|b| b.factor))
+        .maybe_backoff_jitter(backoff.map(|b| b.jitter.as_db_str().to_string()))
+        .backoff_max_total_ms(fallback.max_total_backoff_ms)
+        .sanitize_responses(deployment.sanitize_responses)
+        .trusted(deployment.trusted)
+        .allow_public(deployment.allow_public)
+        .require_approval(deployment.require_approval)
+        .rewrite_response_model(deployment.rewrite_response_model)
+        .disable_logging(deployment.disable_logging)
+        .warmup(deployment.warmup)
+        .system_prompt(deployment.system_prompt.clone())
+        .system_prompt_mode(deployment.system_prompt_mode)
+        .max_n(deployment.max_n)
+        .max_n_mode(deployment.max_n_mode)
+        .credit_exhaustion_mode(deployment.credit_exhaustion_mode)
+        .max_overdraft(deployment.max_overdraft)
+        .max_prompt_length(deployment.max_prompt_length)
+        .max_prompt_length_unit(deployment.max_prompt_length_unit)
+        .open_responses_adapter(deployment.open_responses_adapter)
+        .maybe_reasoning_translation_overrides((!deployment.composite).then(|| deployment.reasoning_translation_overrides.clone()))
+        .allowed_batch_completion_windows(deployment.allowed_batch_completion_windows.clone())
+        .metadata(deployment.metadata.clone().unwrap_or_default())
+        .build()
+}
+
+/// Converge the database onto a validated snapshot. Run inside a transaction.
+async fn apply_snapshot(conn: &mut PgConnection, mut snapshot: PlatformSnapshot, imported_by: UserId) -> Result<ConfigImportResponse> {
+    snapshot.normalize();
+    let current = load_current_state(&mut *conn).await?;
+    validate_against_current(&snapshot, &current)?;
+    let mut changes = ConfigImportResponse::default();
+
+    // Endpoints first, so deployments can be placed on them
+    let current_endpoints: HashMap<&str, &EndpointSnapshot> = current.snapshot.endpoints.iter().map(|e| (e.name.as_str(), e)).collect();
+    let mut endpoint_ids = HashMap::new();
+    for endpoint in &snapshot.endpoints {
+        let mut repo = InferenceEndpoints::new(&mut *conn);
+        match current.endpoints.get(&endpoint.name) {
+            Some(existing) => {
+                endpoint_ids.insert(endpoint.name.as_str(), existing.id);
+                if current_endpoints.get(endpoint.name.as_str()) == Some(&endpoint) {
+                    changes.endpoints.unchanged += 1;
+                } else {
+                    repo.update(existing.id, &endpoint_update_request(endpoint)).await?;
+                    changes.endpoints.updated += 1;
+                }
+            }
+            None => {
+                let created = repo.create(&endpoint_create_request(endpoint, imported_by)).await?;
+                endpoint_ids.insert(endpoint.name.as_str(), created.id);
+                changes.endpoints.created += 1;
+            }
+        }
+    }
+
+    // Deployments and their tariffs; components once every alias resolves
+    let current_deployments: HashMap<&str, &DeploymentSnapshot> =
+        current.snapshot.deployments.iter().map(|d| (d.alias.as_str(), d)).collect();
+    let mut deployment_ids: HashMap<&str, DeploymentId> = HashMap::new();
+    let mut changed_composites = Vec::new();
+    for deployment in &snapshot.deployments {
+        let id = match current.deployments.get(&deployment.alias) {
+            Some(existing) if !existing.deleted && current_deployments.get(deployment.alias.as_str()) == Some(&deployment) => {
+                deployment_ids.insert(deployment.alias.as_str(), existing.id);
+                changes.deployments.unchanged += 1;
+                continue;
+            }
+            Some(existing) => {
+                Deployments::new(&mut *conn)
+                    .update(existing.id, &deployment_update_request(deployment))
+                    .await
+                    .map_err(|e| in_context("deployment", &deployment.alias)(e.into()))?;
+                if existing.deleted {
+                    changes.deployments.created += 1;
+                } else {
+                    changes.deployments.updated += 1;
+                }
+                existing.id
+            }
+            None => {
+                let hosted_on = deployment.endpoint.as_deref().map(|name| endpoint_ids[name]);
+                let created = Deployments::new(&mut *conn)
+                    .create(&deployment_create_request(deployment, hosted_on, imported_by))
+                    .await
+                    .map_err(|e| in_context("deployment", &deployment.alias)(e.into()))?;
+                changes.deployments.created += 1;
+                created.id
+            }
+        };
+        deployment_ids.insert(deployment.alias.as_str(), id);
+        replace_tariffs(&mut *conn, id, deployment.tariffs.clone())
+            .await
+            .map_err(in_context("deployment", &deployment.alias))?;
+        if deployment.composite {
+            changed_composites.push((id, &deployment.components));
+        }
+    }
+    for (composite_id, components) in changed_composites {
+        let components = components
+            .iter()
+            .map(|c| (deployment_ids[c.alias.as_str()], c.weight, c.enabled, c.sort_order))
+            .collect();
+        Deployments::new(&mut *conn).set_components(composite_id, components).await?;
+    }
+    for existing in current.deployments.values() {
+        if !existing.deleted && !deployment_ids.contains_key(existing.alias.as_str()) {
+            Deployments::new(&mut *conn)
+                .update(existing.id, &DeploymentUpdateDBRequest::visibility_update(true))
+                .await?;
+            changes.deployments.deleted += 1;
+        }
+    }
+
+    // Groups and the deployments they grant
+    let current_groups: HashMap<&str, &GroupSnapshot> = current.snapshot.groups.iter().map(|g| (g.name.as_str(), g)).collect();
+    let mut kept_groups = HashSet::new();
+    for group in &snapshot.groups {
+        let desired: Vec<DeploymentId> = group.deployments.iter().map(|alias| deployment_ids[alias.as_str()]).collect();
+        let mut repo = Groups::new(&mut *conn);
+        match current.groups.iter().find(|g| g.name == group.name) {
+            Some(existing) => {
+                kept_groups.insert(existing.id);
+                if current_groups.get(group.name.as_str()) == Some(&group) {
+                    changes.groups.unchanged += 1;
+                    continue;
+                }
+                // The Everyone group's name and description are fixed
+                if !existing.id.is_nil() && existing.description != group.description {
+                    repo.update(
+                        existing.id,
+                        &GroupUpdateDBRequest {
+                            name: None,
+                            description: group.description.clone(),
+                        },
+                    )
+                    .await?;
+                }
+                let granted = current.group_deployments.get(&existing.id).cloned().unwrap_or_default();
+                for deployment_id in granted.iter().filter(|id| !desired.contains(*id)) {
+                    repo.remove_deployment_from_group(*deployment_id, existing.id).await?;
+                }
+                let added: Vec<DeploymentId> = desired.iter().filter(|id| !granted.contains(*id)).copied().collect();
+                if !added.is_empty() {
+                    repo.add_deployments_to_group(existing.id, &added, imported_by).await?;
+                }
+                changes.groups.updated += 1;
+            }
+            None => {
+                let created = repo
+                    .create(&GroupCreateDBRequest {
+                        name: group.name.clone(),
+                        description: group.description.clone(),
+                        created_by: imported_by,
+                    })
+                    .await?;
+                if !desired.is_empty() {
+                    repo.add_deployments_to_group(created.id, &desired, imported_by).await?;
+                }
+                changes.groups.created += 1;
+            }
+        }
+    }
+    for existing in &current.groups {
+        if !kept_groups.contains(&existing.id) && !existing.id.is_nil() && existing.source == "native" {
+            Groups::new(&mut *conn).delete(existing.id).await?;
+            changes.groups.deleted += 1;
+        }
+    }
+
+    // Endpoints last: deleting one also deletes the deployments it hosts
+    for existing in current.endpoints.values() {
+        if !endpoint_ids.contains_key(existing.name.as_str()) {
+            InferenceEndpoints::new(&mut *conn).delete(existing.id).await?;
+            changes.endpoints.deleted += 1;
+        }
+    }
+
+    Ok(changes)
+}
+
+#[utoipa::path(
+    get,
+    path = "/config/export",
+    tag = "config",
+    summary = "Export platform configuration",
+    description = "Export inference endpoints, deployed models (with current tariffs and composite components) and groups \
+        (with the models they grant) as a YAML snapshot. Entities reference each other by name or alias. \
+        Endpoint API keys, Bedrock credentials and group memberships are not included.",
+    responses(
+        (status = 200, description = "Configuration snapshot", body = PlatformSnapshot, content_type = "application/yaml"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Insufficient permissions"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("BearerAuth" = []),
+        ("CookieAuth" = []),
+        ("X-Doubleword-User" = [])
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn export_config<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    _: RequiresPermission<resource::System, operation::ReadAll>,
+) -> Result<impl IntoResponse> {
+    let mut conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    let current = load_current_state(&mut conn).await?;
+    let yaml = serde_yaml::to_string(&current.snapshot).map_err(|e| Error::Internal {
+        operation: format!("serialize config snapshot: {e}"),
+    })?;
+    Ok(([(header::CONTENT_TYPE, YAML_CONTENT_TYPE)], yaml))
+}
+
+#[utoipa::path(
+    post,
+    path = "/config/import",
+    tag = "config",
+    summary = "Import platform configuration",
+    description = "Apply a YAML snapshot produced by the export endpoint. The whole snapshot is validated first and then \
+        applied in one transaction: missing endpoints, models and groups are created, changed ones are updated, \
+        and ones absent from the snapshot are removed (models are hidden, endpoints and native groups are deleted). \
+        Importing the same snapshot twice makes no further changes. New endpoints are created without an API key; \
+        existing credentials are kept.",
+    request_body(content = PlatformSnapshot, content_type = "application/yaml"),
+    responses(
+        (status = 200, description = "Snapshot applied", body = ConfigImportResponse),
+        (status = 400, description = "Invalid snapshot"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Insufficient permissions"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("BearerAuth" = []),
+        ("CookieAuth" = []),
+        ("X-Doubleword-User" = [])
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn import_config<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    current_user: RequiresPermission<resource::System, operation::UpdateAll>,
+    body: String,
+) -> Result<Json<ConfigImportResponse>> {
+    let mut snapshot: PlatformSnapshot = serde_yaml::from_str(&body).map_err(|e| bad_request(format!("Invalid config snapshot: {e}")))?;
+    let allowed_completion_windows = state.current_config().batches.allowed_completion_windows.clone();
+    validate_snapshot(&mut snapshot, &allowed_completion_windows)?;
+
+    let mut tx = state.db.write().begin().await.map_err(|e| Error::Database(e.into()))?;
+    let changes = apply_snapshot(&mut tx, snapshot, current_user.id).await?;
+    tx.commit().await.map_err(|e| Error::Database(e.into()))?;
+
+    tracing::info!(user_id = %current_user.id, ?changes, "configuration snapshot imported");
+    Ok(Json(changes))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::models::{
+            config_snapshot::{ComponentSnapshot, ConfigImportResponse, GroupSnapshot, ImportChangeCounts, PlatformSnapshot},
+            deployments::TariffDefinition,
+            users::{Role, UserResponse},
+        },
+        db::{
+            handlers::{Deployments, InferenceEndpoints, Repository, Tariffs},
+            models::{api_keys::ApiKeyPurpose, deployments::LoadBalancingStrategy, tariffs::TariffCreateDBRequest},
+        },
+        test::utils::*,
+    };
+    use rust_decimal::Decimal;
+    use sqlx::PgPool;
+    use std::str::FromStr;
+
+    async fn export(app: &axum_test::TestServer, admin: &UserResponse) -> (String, PlatformSnapshot) {
+        let response = app
+            .get("/admin/api/v1/config/export")
+            .add_header(&add_auth_headers(admin)[0].0, &add_auth_headers(admin)[0].1)
+            .add_header(&add_auth_headers(admin)[1].0, &add_auth_headers(admin)[1].1)
+            .await;
+        response.assert_status_ok();
+        let yaml = response.text();
+        let snapshot = serde_yaml::from_str(&yaml).expect("export should be a valid snapshot");
+        (yaml, snapshot)
+    }
+
+    async fn import(app: &axum_test::TestServer, admin: &UserResponse, yaml: String) -> axum_test::TestResponse {
+        app.post("/admin/api/v1/config/import")
+            .add_header(&add_auth_headers(admin)[0].0, &add_auth_headers(admin)[0].1)
+            .add_header(&add_auth_headers(admin)[1].0, &add_auth_headers(admin)[1].1)
+            .text(yaml)
+            .await
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_export_mutate_import_round_trip(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let admin = create_test_admin_user(&pool, Role::PlatformManager).await;
+
+        let endpoint_id = create_test_endpoint(&pool, "snapshot-endpoint", admin.id).await;
+        sqlx::query("UPDATE inference_endpoints SET api_key = 'sk-snapshot-secret' WHERE id = $1")
+            .bind(endpoint_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let kept = create_test_deployment(&pool, admin.id, "kept-model", "kept-model").await;
+        let dropped = create_test_deployment(&pool, admin.id, "dropped-model", "dropped-model").await;
+        let group = create_test_group(&pool).await;
+        add_deployment_to_group(&pool, kept.id, group.id, admin.id).await;
+        add_deployment_to_group(&pool, dropped.id, group.id, admin.id).await;
+        {
+            let mut conn = pool.acquire().await.unwrap();
+            Tariffs::new(&mut conn)
+                .create(&TariffCreateDBRequest {
+                    deployed_model_id: kept.id,
+                    name: "realtime".to_string(),
+                    input_price_per_token: Decimal::from_str("0.000001").unwrap(),
+                    output_price_per_token: Decimal::from_str("0.000002").unwrap(),
+                    api_key_purpose: Some(ApiKeyPurpose::Realtime),
+                    completion_window: None,
+                    valid_from: None,
+                })
+                .await
+                .unwrap();
+        }
+
+        let (yaml, mut snapshot) = export(&app, &admin).await;
+        assert!(!yaml.contains("sk-snapshot-secret"), "secrets must not be exported");
+        assert!(snapshot.endpoints.iter().any(|e| e.name == "snapshot-endpoint"));
+        let exported_group = snapshot.groups.iter().find(|g| g.name == group.name).unwrap();
+        assert_eq!(
+            exported_group.deployments,
+            vec!["dropped-model".to_string(), "kept-model".to_string()]
+        );
+
+        // Re-importing an unchanged export writes nothing
+        let response = import(&app, &admin, yaml).await;
+        response.assert_status_ok();
+        let changes: ConfigImportResponse = response.json();
+        assert_eq!(changes.endpoints.created + changes.endpoints.updated + changes.endpoints.deleted, 0);
+        assert_eq!(
+            changes.deployments.created + changes.deployments.updated + changes.deployments.deleted,
+            0
+        );
+        assert_eq!(changes.groups.created + changes.groups.updated + changes.groups.deleted, 0);
+
+        // Mutate: retune one model, drop another, add a standard and a composite model, and a new group
+        let kept_snapshot = snapshot.deployments.iter_mut().find(|d| d.alias == "kept-model").unwrap();
+        kept_snapshot.requests_per_second = Some(25.0);
+        kept_snapshot.tariffs = vec![TariffDefinition {
+            name: "realtime".to_string(),
+            input_price_per_token: Decimal::from_str("0.000003").unwrap(),
+            output_price_per_token: Decimal::from_str("0.000004").unwrap(),
+            api_key_purpose: Some(ApiKeyPurpose::Realtime),
+            completion_window: None,
+        }];
+        let mut added = kept_snapshot.clone();
+        added.alias = "added-model".to_string();
+        added.model_name = "added-model".to_string();
+        added.endpoint = Some("snapshot-endpoint".to_string());
+        added.tariffs.clear();
+        let mut composite = added.clone();
+        composite.alias = "added-composite".to_string();
+        composite.model_name = "added-composite".to_string();
+        composite.endpoint = None;
+        composite.composite = true;
+        composite.lb_strategy = Some(LoadBalancingStrategy::WeightedRandom);
+        composite.reasoning_translation_overrides = None;
+        composite.components = vec![
+            ComponentSnapshot {
+                alias: "kept-model".to_string(),
+                weight: 70,
+                enabled: true,
+                sort_order: 0,
+            },
+            ComponentSnapshot {
+                alias: "added-model".to_string(),
+                weight: 30,
+                enabled: true,
+                sort_order: 1,
+            },
+        ];
+        snapshot.deployments.retain(|d| d.alias != "dropped-model");
+        snapshot.deployments.push(added);
+        snapshot.deployments.push(composite);
+        for group_snapshot in &mut snapshot.groups {
+            group_snapshot.deployments.retain(|alias| alias != "dropped-model");
+        }
+        snapshot.groups.push(GroupSnapshot {
+            name: "snapshot-group".to_string(),
+            description: Some("Created by import".to_string()),
+            deployments: vec!["added-composite".to_string()],
+        });
+
+        let response = import(&app, &admin, serde_yaml::to_string(&snapshot).unwrap()).await;
+        response.assert_status_ok();
+        let changes: ConfigImportResponse = response.json();
+        assert_eq!(changes.endpoints.created + changes.endpoints.updated + changes.endpoints.deleted, 0);
+        assert_eq!(
+            (
+                changes.deployments.created,
+                changes.deployments.updated,
+                changes.deployments.deleted
+            ),
+            (2, 1, 1)
+        );
+        assert_eq!((changes.groups.created, changes.groups.updated), (1, 1));
+
+        // The database converged on the imported snapshot
+        snapshot.normalize();
+        let (_, exported) = export(&app, &admin).await;
+        assert_eq!(exported, snapshot);
+
+        let mut conn = pool.acquire().await.unwrap();
+        let endpoint = InferenceEndpoints::new(&mut conn).get_by_id(endpoint_id).await.unwrap().unwrap();
+        assert_eq!(endpoint.api_key.as_deref(), Some("sk-snapshot-secret"), "credentials are kept");
+        let dropped = Deployments::new(&mut conn).get_by_id(dropped.id).await.unwrap().unwrap();
+        assert!(dropped.deleted, "models missing from the snapshot are hidden");
+        drop(conn);
+
+        // And converging again is a no-op
+        let response = import(&app, &admin, serde_yaml::to_string(&snapshot).unwrap()).await;
+        response.assert_status_ok();
+        let changes: ConfigImportResponse = response.json();
+        assert_eq!(changes.deployments.unchanged as usize, snapshot.deployments.len());
+        assert_eq!(
+            changes.deployments,
+            ImportChangeCounts {
+                unchanged: changes.deployments.unchanged,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            changes.groups,
+            ImportChangeCounts {
+                unchanged: changes.groups.unchanged,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_import_rejects_invalid_snapshot_without_changes(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let admin = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let standard_user = create_test_user(&pool, Role::StandardUser).await;
+        create_test_deployment(&pool, admin.id, "existing-model", "existing-model").await;
+
+        let (yaml, mut snapshot) = export(&app, &admin).await;
+
+        let response = app
+            .get("/admin/api/v1/config/export")
+            .add_header(&add_auth_headers(&standard_user)[0].0, &add_auth_headers(&standard_user)[0].1)
+            .add_header(&add_auth_headers(&standard_user)[1].0, &add_auth_headers(&standard_user)[1].1)
+            .await;
+        response.assert_status_forbidden();
+        let response = import(&app, &standard_user, yaml.clone()).await;
+        response.assert_status_forbidden();
+
+        // A group granting a model that is not in the snapshot
+        snapshot.groups.push(GroupSnapshot {
+            name: "broken-group".to_string(),
+            description: None,
+            deployments: vec!["missing-model".to_string()],
+        });
+        snapshot.deployments.retain(|d| d.alias != "existing-model");
+        let response = import(&app, &admin, serde_yaml::to_string(&snapshot).unwrap()).await;
+        response.assert_status_bad_request();
+        assert!(response.text().contains("missing-model"));
+
+        // A model moved to another endpoint is rejected after reading the database, before any write
+        create_test_endpoint(&pool, "other-endpoint", admin.id).await;
+        let (_, mut snapshot) = export(&app, &admin).await;
+        for deployment in &mut snapshot.deployments {
+            if deployment.alias == "existing-model" {
+                deployment.endpoint = Some("other-endpoint".to_string());
+            }
+        }
+        snapshot.groups.push(GroupSnapshot {
+            name: "not-created".to_string(),
+            description: None,
+            deployments: vec![],
+        });
+        let response = import(&app, &admin, serde_yaml::to_string(&snapshot).unwrap()).await;
+        response.assert_status_bad_request();
+
+        let (after, _) = export(&app, &admin).await;
+        assert!(after.contains("existing-model"));
+        assert!(!after.contains("not-created"));
+
+        // Malformed YAML
+        let response = import(&app, &admin, "version: [".to_string()).await;
+        response.assert_status_bad_request();
+    }
+}
@@ -0,0 +1,242 @@
+//! Admin endpoints for deployment **shadow traffic**: copy a sampled fraction of a
+//! model's requests to another model to try it on live traffic. Thin wrappers over
+//! [`crate::db::handlers::DeploymentShadows`].
+//!
+//! The copies are sent by onwards once the primary request has been admitted, and
+//! their responses are discarded. They never pass back through request logging, so
+//! they don't appear in `http_analytics` and aren't billed; onwards reports them in
+//! its `onwards_shadow_*` metrics instead.
+
+use axum::Json;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use sqlx::PgConnection;
+use sqlx_pool_router::PoolProvider;
+
+use crate::AppState;
+use crate::api::models::deployment_shadows::{DeploymentShadowResponse, DeploymentShadowUpdate};
+use crate::auth::permissions::{RequiresPermission, operation, resource};
+use crate::db::handlers::DeploymentShadows;
+use crate::db::models::deployment_shadows::DeploymentShadowDBRequest;
+use crate::errors::{Error, Result};
+use crate::types::DeploymentId;
+
+async fn model_exists(conn: &mut PgConnection, id: DeploymentId) -> Result<bool> {
+    sqlx::query_scalar!(
+        r#"SELECT EXISTS(SELECT 1 FROM deployed_models WHERE id = $1 AND deleted = false) AS "exists!""#,
+        id,
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| Error::Database(e.into()))
+}
+
+/// 404 unless the model exists and isn't soft-deleted
+async fn ensure_model_exists(conn: &mut PgConnection, id: DeploymentId) -> Result<()> {
+    if model_exists(conn, id).await? {
+        Ok(())
+    } else {
+        Err(Error::NotFound {
+            resource: "Model".to_string(),
+            id: id.to_string(),
+        })
+    }
+}
+
+/// Mirror the table's CHECK constraints with a clean 400, and reject a shadow
+/// model that doesn't exist (rather than an opaque FK violation).
+async fn validate(conn: &mut PgConnection, id: DeploymentId, update: &DeploymentShadowUpdate) -> Result<()> {
+    if !(update.fraction > 0.0 && update.fraction <= 1.0) {
+        return Err(Error::BadRequest {
+            message: "fraction must be greater than 0 and at most 1".to_string(),
+        });
+    }
+    if update.shadow_model_id == id {
+        return Err(Error::BadRequest {
+            message: "A model cannot shadow itself".to_string(),
+        });
+    }
+    if !model_exists(conn, update.shadow_model_id).await? {
+        return Err(Error::BadRequest {
+            message: format!("Shadow model {} not found", update.shadow_model_id),
+        });
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/models/{id}/shadow",
+    tag = "models",
+    summary = "Get a model's shadow",
+    params(("id" = uuid::Uuid, Path, description = "Deployment ID")),
+    responses(
+        (status = 200, description = "The model's shadow", body = DeploymentShadowResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden (requires model-management access)"),
+        (status = 404, description = "Model not found, or no shadow set"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_deployment_shadow<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(id): Path<DeploymentId>,
+    _user: RequiresPermission<resource::Models, operation::ReadAll>,
+) -> Result<Json<DeploymentShadowResponse>> {
+    let mut conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    ensure_model_exists(&mut conn, id).await?;
+    match DeploymentShadows::new(&mut conn).get(id).await? {
+        Some(shadow) => Ok(Json(shadow.into())),
+        None => Err(Error::NotFound {
+            resource: "Model shadow".to_string(),
+            id: id.to_string(),
+        }),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/models/{id}/shadow",
+    tag = "models",
+    summary = "Set a model's shadow",
+    description = "Copy a sampled fraction of the model's requests to another model. Callers only ever \
+                   see the model's own response; the copies' responses are discarded, and the copies \
+                   aren't logged or billed. Replaces any existing shadow.",
+    params(("id" = uuid::Uuid, Path, description = "Deployment ID")),
+    request_body = DeploymentShadowUpdate,
+    responses(
+        (status = 200, description = "Shadow set", body = DeploymentShadowResponse),
+        (status = 400, description = "Invalid fraction or shadow model"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden (requires model-management access)"),
+        (status = 404, description = "Model not found"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn set_deployment_shadow<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(id): Path<DeploymentId>,
+    _user: RequiresPermission<resource::Models, operation::UpdateAll>,
+    Json(update): Json<DeploymentShadowUpdate>,
+) -> Result<Json<DeploymentShadowResponse>> {
+    let mut tx = state.db.write().begin().await.map_err(|e| Error::Database(e.into()))?;
+    ensure_model_exists(&mut tx, id).await?;
+    validate(&mut tx, id, &update).await?;
+    let shadow = DeploymentShadows::new(&mut tx)
+        .set(id, &DeploymentShadowDBRequest::from(update))
+        .await?;
+    tx.commit().await.map_err(|e| Error::Database(e.into()))?;
+    Ok(Json(shadow.into()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/models/{id}/shadow",
+    tag = "models",
+    summary = "Remove a model's shadow",
+    params(("id" = uuid::Uuid, Path, description = "Deployment ID")),
+    responses(
+        (status = 204, description = "Shadow removed (or none was set)"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden (requires model-management access)"),
+        (status = 404, description = "Model not found"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn delete_deployment_shadow<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(id): Path<DeploymentId>,
+    _user: RequiresPermission<resource::Models, operation::UpdateAll>,
+) -> Result<StatusCode> {
+    let mut tx = state.db.write().begin().await.map_err(|e| Error::Database(e.into()))?;
+    ensure_model_exists(&mut tx, id).await?;
+    DeploymentShadows::new(&mut tx).delete(id).await?;
+    tx.commit().await.map_err(|e| Error::Database(e.into()))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::models::users::Role;
+    use crate::test::utils::{add_auth_headers, create_test_admin_user, create_test_app, create_test_deployment, create_test_user};
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_deployment_shadow_round_trips(pool: PgPool) {
+        let (app, _) = create_test_app(pool.clone(), false).await;
+        let admin = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let primary = create_test_deployment(&pool, admin.id, "primary-model", "primary").await;
+        let candidate = create_test_deployment(&pool, admin.id, "candidate-model", "candidate").await;
+        let shadow_path = format!("/admin/api/v1/models/{}/shadow", primary.id);
+
+        let response = app
+            .get(&shadow_path)
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .await;
+        response.assert_status_not_found();
+
+        let response = app
+            .put(&shadow_path)
+            .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
+            .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+            .json(&json!({"shadow_model_id": candidate.id, "fraction": 0.5}))
+            .await;
+        response.assert_status_forbidden();
+
+        for body in [
+            json!({"shadow_model_id": candidate.id, "fraction": 0.0}),
+            json!({"shadow_model_id": candidate.id, "fraction": 1.5}),
+            json!({"shadow_model_id": primary.id, "fraction": 0.5}),
+            json!({"shadow_model_id": uuid::Uuid::new_v4(), "fraction": 0.5}),
+        ] {
+            let response = app
+                .put(&shadow_path)
+                .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+                .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+                .json(&body)
+                .await;
+            response.assert_status_bad_request();
+        }
+
+        let response = app
+            .put(&shadow_path)
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&json!({"shadow_model_id": candidate.id, "fraction": 0.25}))
+            .await;
+        response.assert_status_ok();
+        let shadow: DeploymentShadowResponse = response.json();
+        assert_eq!(shadow.shadow_model_id, candidate.id);
+        assert_eq!(shadow.shadow_alias, "candidate");
+        assert_eq!(shadow.fraction, 0.25);
+
+        let response = app
+            .get(&shadow_path)
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .await;
+        response.assert_status_ok();
+        assert_eq!(response.json::<DeploymentShadowResponse>().fraction, 0.25);
+
+        let response = app
+            .delete(&shadow_path)
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .await;
+        response.assert_status(StatusCode::NO_CONTENT);
+        let response = app
+            .get(&shadow_path)
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .await;
+        response.assert_status_not_found();
+    }
+}
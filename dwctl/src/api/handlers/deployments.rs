@@ -2,7 +2,8 @@
 
 use sqlx_pool_router::PoolProvider;
 
-use crate::api::models::deployments::{ModelFacets, ModelListResponse, TrafficRoutingAction, TrafficRoutingRule};
+use crate::api::models::deployments::{ModelFacets, ModelListResponse, TariffDefinition, TrafficRoutingAction, TrafficRoutingRule};
+use crate::api::models::pagination::take_cursor_page;
 use crate::db::models::deployments::{
     LoadBalancingStrategy, MODEL_CATALOG_METADATA_MAX_BYTES, MODEL_CATALOG_METADATA_MAX_EXTRA_KEYS, ModelCatalogMetadata, TrafficRuleAction,
 };
@@ -11,30 +12,35 @@ use crate::{
     AppState,
     api::models::{
         deployments::{
-            ComponentEndpointSummary, ComponentModelSummary, DeployedModelCreate, DeployedModelResponse, DeployedModelUpdate,
-            GetModelQuery, ListModelsQuery, ModelComponentResponse, enrichment::DeployedModelEnricher,
+            ComponentEndpointSummary, ComponentModelSummary, DeployedModelClone, DeployedModelCreate, DeployedModelResponse,
+            DeployedModelUpdate, GetModelQuery, ListModelsQuery, ModelComponentResponse, RequestCostEstimate, RequestCostEstimateQuery,
+            ResolvedModelConfig, enrichment::DeployedModelEnricher,
         },
         users::CurrentUser,
     },
     auth::permissions::{RequiresPermission, can_read_all_resources, has_permission, operation, resource},
+    config::AliasNormalization,
     db::{
-        handlers::{Deployments, InferenceEndpoints, Repository, Tariffs, deployments::DeploymentFilter},
+        handlers::{Deployments, Groups, InferenceEndpoints, Repository, Tariffs, deployments::DeploymentFilter},
         models::{
             api_keys::ApiKeyPurpose,
-            deployments::{DeploymentComponentDBResponse, DeploymentCreateDBRequest, DeploymentUpdateDBRequest, ModelStatus, ModelType},
+            deployments::{
+                DeploymentComponentDBResponse, DeploymentCreateDBRequest, DeploymentUpdateDBRequest, ModelStatus, ModelType,
+                TrafficRuleDBRow,
+            },
         },
     },
     errors::{Error, Result},
     reasoning::ReasoningTranslationOverrides,
-    types::{DeploymentId, Resource},
+    types::{DeploymentId, Operation, Permission, Resource},
 };
 use axum::{
     extract::{Path, Query, State},
     response::Json,
 };
-use sqlx::Acquire;
+use sqlx::{Acquire, PgConnection};
 
-fn validate_reasoning_translation_overrides(overrides: Option<&ReasoningTranslationOverrides>) -> Result<()> {
+pub(crate) fn validate_reasoning_translation_overrides(overrides: Option<&ReasoningTranslationOverrides>) -> Result<()> {
     if let Some(overrides) = overrides {
         overrides.validate().map_err(|error| Error::BadRequest {
             message: error.to_string(),
@@ -51,7 +57,7 @@ fn validate_reasoning_translation_overrides(overrides: Option<&ReasoningTranslat
 /// update are not asserted here — the DB CHECK constraints in migration 098
 /// are the backstop for those rare cases. The goal of this function is a
 /// friendly API-layer error for the common single-field mistake.
-fn validate_backoff(initial_ms: Option<i32>, max_ms: Option<i32>, factor: Option<f64>, max_total_ms: Option<i32>) -> Result<()> {
+pub(crate) fn validate_backoff(initial_ms: Option<i32>, max_ms: Option<i32>, factor: Option<f64>, max_total_ms: Option<i32>) -> Result<()> {
     if let Some(initial) = initial_ms
         && initial < 1
     {
@@ -90,8 +96,44 @@ fn validate_backoff(initial_ms: Option<i32>, max_ms: Option<i32>, factor: Option
     Ok(())
 }
 
+/// Validate that a cap on completions per request (`max_n`) allows at least one.
+pub(crate) fn validate_max_n(max_n: Option<i32>) -> Result<()> {
+    if let Some(max_n) = max_n
+        && max_n < 1
+    {
+        return Err(Error::BadRequest {
+            message: format!("max_n must be >= 1 (got {})", max_n),
+        });
+    }
+    Ok(())
+}
+
+/// Validate that a stream overdraft limit (`max_overdraft`) isn't negative.
+pub(crate) fn validate_max_overdraft(max_overdraft: Option<rust_decimal::Decimal>) -> Result<()> {
+    if let Some(max_overdraft) = max_overdraft
+        && max_overdraft < rust_decimal::Decimal::ZERO
+    {
+        return Err(Error::BadRequest {
+            message: format!("max_overdraft must be >= 0 (got {})", max_overdraft),
+        });
+    }
+    Ok(())
+}
+
+/// Validate that a prompt length limit (`max_prompt_length`) allows at least one token or character.
+pub(crate) fn validate_max_prompt_length(max_prompt_length: Option<i32>) -> Result<()> {
+    if let Some(max_prompt_length) = max_prompt_length
+        && max_prompt_length < 1
+    {
+        return Err(Error::BadRequest {
+            message: format!("max_prompt_length must be >= 1 (got {})", max_prompt_length),
+        });
+    }
+    Ok(())
+}
+
 /// Validate that model catalog metadata is within size and key count limits.
-fn validate_metadata(metadata: &ModelCatalogMetadata) -> Result<()> {
+pub(crate) fn validate_metadata(metadata: &ModelCatalogMetadata) -> Result<()> {
     let size = serde_json::to_vec(metadata).map(|v| v.len()).unwrap_or(0);
     if size > MODEL_CATALOG_METADATA_MAX_BYTES {
         return Err(Error::BadRequest {
@@ -115,6 +157,86 @@ fn validate_metadata(metadata: &ModelCatalogMetadata) -> Result<()> {
     Ok(())
 }
 
+/// Make `tariff_defs` the current tariffs of a deployment.
+///
+/// Tariffs that are already current and unchanged are kept; the rest are closed
+/// rather than deleted, so historical pricing stays intact.
+pub(crate) async fn replace_tariffs(
+    conn: &mut PgConnection,
+    deployment_id: DeploymentId,
+    tariff_defs: Vec<TariffDefinition>,
+) -> Result<()> {
+    let mut tariffs_repo = Tariffs::new(conn);
+
+    // Fetch current tariffs to compare
+    let current_tariffs = tariffs_repo.list_current_by_model(deployment_id).await?;
+
+    // Helper function to check if a tariff matches the definition
+    let tariff_matches = |existing: &crate::db::models::tariffs::ModelTariff, def: &TariffDefinition| {
+        existing.name == def.name
+            && existing.input_price_per_token == def.input_price_per_token
+            && existing.output_price_per_token == def.output_price_per_token
+            && existing.api_key_purpose == def.api_key_purpose
+            && existing.completion_window == def.completion_window
+    };
+
+    // Collect IDs of tariffs to close (those not in the new set or have changed)
+    let tariffs_to_close: Vec<uuid::Uuid> = current_tariffs
+        .iter()
+        .filter(|existing| !tariff_defs.iter().any(|def| tariff_matches(existing, def)))
+        .map(|t| t.id)
+        .collect();
+
+    // Batch close tariffs in a single query
+    if !tariffs_to_close.is_empty() {
+        tariffs_repo.close_tariffs_batch(&tariffs_to_close).await?;
+    }
+
+    // Create new or changed tariffs (skip those that already exist unchanged)
+    for tariff_def in tariff_defs {
+        // Skip if this tariff already exists with the same values
+        if current_tariffs.iter().any(|existing| tariff_matches(existing, &tariff_def)) {
+            continue;
+        }
+
+        let tariff_request = TariffCreateDBRequest {
+            deployed_model_id: deployment_id,
+            name: tariff_def.name,
+            input_price_per_token: tariff_def.input_price_per_token,
+            output_price_per_token: tariff_def.output_price_per_token,
+            api_key_purpose: tariff_def.api_key_purpose,
+            completion_window: tariff_def.completion_window,
+            valid_from: None, // Use NOW()
+        };
+        tariffs_repo.create(&tariff_request).await?;
+    }
+
+    Ok(())
+}
+
+/// Reject an alias that would match another deployment's alias under `onwards.alias_normalization`.
+///
+/// Exact duplicates are left to the database's uniqueness constraint.
+async fn check_normalized_alias_collision(
+    mode: AliasNormalization,
+    alias: &str,
+    exclude: Option<DeploymentId>,
+    repo: &mut Deployments<'_>,
+) -> Result<()> {
+    let Some(normalized) = mode.normalize(alias) else {
+        return Ok(());
+    };
+    for (id, existing) in repo.list_active_aliases().await? {
+        if Some(id) != exclude && existing != alias && mode.normalize(&existing).as_deref() == Some(normalized.as_str()) {
+            return Err(Error::Conflict {
+                message: format!("Alias '{alias}' collides with existing alias '{existing}' under alias normalization"),
+                conflicts: None,
+            });
+        }
+    }
+    Ok(())
+}
+
 /// Resolve API traffic routing rules to DB-layer actions (alias strings → UUIDs).
 /// Validates no self-redirects, no empty targets, and that redirect targets exist.
 async fn resolve_traffic_rules(
@@ -142,6 +264,15 @@ async fn resolve_traffic_rules(
                 })?;
                 TrafficRuleAction::Redirect(target_id)
             }
+            TrafficRoutingAction::Sanitize { enabled } => TrafficRuleAction::Sanitize(*enabled),
+            TrafficRoutingAction::PreferRegion { region } => {
+                if region.trim().is_empty() {
+                    return Err(Error::BadRequest {
+                        message: "Preferred region must not be empty".to_string(),
+                    });
+                }
+                TrafficRuleAction::PreferRegion(region.trim().to_string())
+            }
         };
         resolved.push((rule.api_key_purpose.clone(), action));
     }
@@ -164,6 +295,7 @@ fn db_component_to_response(c: DeploymentComponentDBResponse) -> ModelComponentR
                 "CHAT" => Some(ModelType::Chat),
                 "EMBEDDINGS" => Some(ModelType::Embeddings),
                 "RERANKER" => Some(ModelType::Reranker),
+                "MODERATION" => Some(ModelType::Moderation),
                 _ => None,
             }),
             endpoint: c.endpoint_id.map(|id| ComponentEndpointSummary {
@@ -183,12 +315,12 @@ fn db_component_to_response(c: DeploymentComponentDBResponse) -> ModelComponentR
     summary = "List deployed models",
     description = "List all deployed models, optionally filtered by endpoint, group, or availability",
     params(
-        ("endpoint" = Option<i32>, Query, description = "Filter by inference endpoint ID"),
+        ("endpoint" = Option<String>, Query, description = "Filter by inference endpoint ID (also accepted as `endpoint_id`)"),
         ("group" = Option<String>, Query, description = "Filter by group IDs (comma-separated UUIDs)"),
         ("accessible" = Option<bool>, Query, description = "Filter to only models the current user can access (defaults to false for admins, true for users)"),
         ("include" = Option<String>, Query, description = "Include additional data (comma-separated: 'groups', 'metrics', 'status', 'pricing', 'endpoints', 'facets', 'reasoning_capabilities'). Only platform managers can include groups. Status shows probe monitoring information. Pricing shows simple customer rates for regular users, full pricing structure including current active tariffs for users with Pricing::ReadAll permission. Endpoints includes full inference endpoint details. Facets returns distinct providers, capabilities, and model types for filter dropdowns. Reasoning capabilities shows efforts supported by every provider behind each model."),
         ("provider" = Option<String>, Query, description = "Filter by provider name (case-insensitive exact match against metadata.provider)"),
-        ("model_type" = Option<String>, Query, description = "Filter by model type (CHAT, EMBEDDINGS, RERANKER)"),
+        ("model_type" = Option<String>, Query, description = "Filter by model type (CHAT, EMBEDDINGS, RERANKER, MODERATION)"),
         ("capability" = Option<String>, Query, description = "Filter by capability (returns models that have this capability)"),
         ("available_for_realtime" = Option<bool>, Query, description = "Filter by realtime availability. true returns models without a realtime deny rule; false returns models with one."),
         ("sort" = Option<String>, Query, description = "Sort field: created_at (default), alias, intelligence_index, released_at, context_window, provider, price_from"),
@@ -197,7 +329,9 @@ fn db_component_to_response(c: DeploymentComponentDBResponse) -> ModelComponentR
         ("inactive" = Option<bool>, Query, description = "Show inactive models when true (admin only)"),
         ("limit" = Option<i64>, Query, description = "Maximum number of items to return (default: 10, max: 100)"),
         ("skip" = Option<i64>, Query, description = "Number of items to skip (default: 0)"),
+        ("cursor" = Option<String>, Query, description = "Keyset pagination cursor: empty for the first page, then `next_cursor` from the previous response. Orders by id; cannot be combined with `sort`."),
         ("search" = Option<String>, Query, description = "Search query to filter models by alias, model_name, or endpoint name (case-insensitive substring match)"),
+        ("alias_contains" = Option<String>, Query, description = "Filter to models whose alias contains this value (case-insensitive)"),
         ("is_composite" = Option<bool>, Query, description = "Filter by composite/virtual model status (true = virtual models only, false = hosted models only)"),
     ),
     responses(
@@ -244,9 +378,20 @@ pub async fn list_deployed_models<P: PoolProvider>(
     // Get deployments with the filter
     let mut repo = Deployments::new(&mut conn);
 
-    // Build the filter with pagination parameters
-    let (skip, limit) = query.pagination.params();
-    let mut filter = DeploymentFilter::new(skip, limit);
+    // Build the filter with pagination parameters. In cursor mode, fetch one extra row to
+    // tell whether another page follows.
+    let cursor = query.pagination.cursor()?;
+    if cursor.is_some() && query.sort.is_some() {
+        return Err(Error::BadRequest {
+            message: "Cursor pagination orders models by id and cannot be combined with sort".to_string(),
+        });
+    }
+    let limit = query.pagination.limit();
+    let skip = if cursor.is_some() { 0 } else { query.pagination.skip() };
+    let mut filter = match cursor {
+        Some(cursor) => DeploymentFilter::new(0, limit + 1).with_cursor(cursor),
+        None => DeploymentFilter::new(skip, limit),
+    };
 
     if let Some(endpoint_id) = query.endpoint {
         filter = filter.with_endpoint(endpoint_id);
@@ -318,6 +463,12 @@ pub async fn list_deployed_models<P: PoolProvider>(
         filter = filter.with_search(search.trim().to_string());
     }
 
+    if let Some(alias) = query.alias_contains.as_ref()
+        && !alias.trim().is_empty()
+    {
+        filter = filter.with_alias_contains(alias.trim().to_string());
+    }
+
     // Apply is_composite filter if specified
     if let Some(is_composite) = query.is_composite {
         filter = filter.with_composite(is_composite);
@@ -392,7 +543,12 @@ pub async fn list_deployed_models<P: PoolProvider>(
 
     // Get total count before applying pagination
     let total_count = repo.count(&filter).await?;
-    let filtered_models = repo.list(&filter).await?;
+    let mut filtered_models = repo.list(&filter).await?;
+    let next_cursor = if cursor.is_some() {
+        take_cursor_page(&mut filtered_models, limit, |m| m.id)
+    } else {
+        None
+    };
 
     // Convert to API responses and add provider_pricing based on permissions and includes
     let mut models: Vec<DeployedModelResponse> = filtered_models
@@ -481,6 +637,7 @@ pub async fn list_deployed_models<P: PoolProvider>(
         total_count,
         skip,
         limit,
+        next_cursor,
         facets,
     }))
 }
@@ -498,6 +655,7 @@ pub async fn list_deployed_models<P: PoolProvider>(
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Forbidden - admin access required"),
         (status = 404, description = "Inference endpoint not found"),
+        (status = 409, description = "Alias collides with an existing alias under alias normalization"),
         (status = 500, description = "Internal server error"),
     ),
     security(
@@ -510,7 +668,7 @@ pub async fn list_deployed_models<P: PoolProvider>(
 pub async fn create_deployed_model<P: PoolProvider>(
     State(state): State<AppState<P>>,
     current_user: RequiresPermission<resource::Models, operation::CreateAll>,
-    Json(create): Json<DeployedModelCreate>,
+    Json(mut create): Json<DeployedModelCreate>,
 ) -> Result<Json<DeployedModelResponse>> {
     // Extract common fields and variant-specific data
     let (model_name, alias, hosted_on, tariffs, throughput) = match &create {
@@ -545,6 +703,19 @@ pub async fn create_deployed_model<P: PoolProvider>(
         });
     }
 
+    validate_max_n(match &create {
+        DeployedModelCreate::Standard(s) => s.max_n,
+        DeployedModelCreate::Composite(c) => c.max_n,
+    })?;
+    validate_max_overdraft(match &create {
+        DeployedModelCreate::Standard(s) => s.max_overdraft,
+        DeployedModelCreate::Composite(c) => c.max_overdraft,
+    })?;
+    validate_max_prompt_length(match &create {
+        DeployedModelCreate::Standard(s) => s.max_prompt_length,
+        DeployedModelCreate::Composite(c) => c.max_prompt_length,
+    })?;
+
     // Validate allowed batch completion windows against global config
     let batch_windows = match &create {
         DeployedModelCreate::Standard(s) => &s.allowed_batch_completion_windows,
@@ -590,6 +761,16 @@ pub async fn create_deployed_model<P: PoolProvider>(
     };
     validate_backoff(Some(b_initial), Some(b_max), Some(b_factor), b_total)?;
 
+    // Fill rate limits the request leaves unset from the platform defaults
+    let defaults = state.current_config().limits.deployments.clone();
+    let (requests_per_second, burst_size, capacity) = match &mut create {
+        DeployedModelCreate::Standard(s) => (&mut s.requests_per_second, &mut s.burst_size, &mut s.capacity),
+        DeployedModelCreate::Composite(c) => (&mut c.requests_per_second, &mut c.burst_size, &mut c.capacity),
+    };
+    *requests_per_second = requests_per_second.or(defaults.requests_per_second);
+    *burst_size = burst_size.or(defaults.burst_size);
+    *capacity = capacity.or(defaults.capacity);
+
     let mut tx = state.db.write().begin().await.map_err(|e| Error::Database(e.into()))?;
 
     // Validate endpoint exists (only for standard models)
@@ -603,6 +784,12 @@ pub async fn create_deployed_model<P: PoolProvider>(
         }
     }
 
+    let alias_normalization = state.current_config().onwards.alias_normalization;
+    if alias_normalization != AliasNormalization::Exact {
+        let mut repo = Deployments::new(tx.acquire().await.map_err(|e| Error::Database(e.into()))?);
+        check_normalized_alias_collision(alias_normalization, alias, None, &mut repo).await?;
+    }
+
     // Resolve traffic routing rules (alias → UUID) before creating
     let traffic_rules_input = match &create {
         DeployedModelCreate::Standard(s) => &s.traffic_routing_rules,
@@ -671,6 +858,7 @@ pub async fn create_deployed_model<P: PoolProvider>(
         (status = 400, description = "Bad request - invalid model data"),
         (status = 401, description = "Unauthorized"),
         (status = 404, description = "Inference endpoint or deployment not found"),
+        (status = 409, description = "Alias collides with an existing alias under alias normalization"),
         (status = 500, description = "Internal server error"),
     ),
     security(
@@ -718,6 +906,9 @@ pub async fn update_deployed_model<P: PoolProvider>(
         validate_metadata(m)?;
     }
     validate_reasoning_translation_overrides(update.reasoning_translation_overrides.as_ref().and_then(Option::as_ref))?;
+    validate_max_n(update.max_n.flatten())?;
+    validate_max_overdraft(update.max_overdraft.flatten())?;
+    validate_max_prompt_length(update.max_prompt_length.flatten())?;
 
     let mut tx = state.db.write().begin().await.map_err(|e| Error::Database(e.into()))?;
 
@@ -800,6 +991,14 @@ pub async fn update_deployed_model<P: PoolProvider>(
         }
     }
 
+    let alias_normalization = state.current_config().onwards.alias_normalization;
+    if let Some(alias) = update.alias.as_deref()
+        && alias_normalization != AliasNormalization::Exact
+    {
+        let mut repo = Deployments::new(tx.acquire().await.map_err(|e| Error::Database(e.into()))?);
+        check_normalized_alias_collision(alias_normalization, alias, Some(deployment_id), &mut repo).await?;
+    }
+
     // Resolve traffic routing rules if provided
     let resolved_rules = match &update.traffic_routing_rules {
         Some(Some(rules)) => {
@@ -843,51 +1042,7 @@ pub async fn update_deployed_model<P: PoolProvider>(
     // Handle tariff replacement if provided
     if let Some(tariff_defs) = tariffs {
         let tariff_conn = tx.acquire().await.map_err(|e| Error::Database(e.into()))?;
-        let mut tariffs_repo = Tariffs::new(tariff_conn);
-
-        // Fetch current tariffs to compare
-        let current_tariffs = tariffs_repo.list_current_by_model(deployment_id).await?;
-
-        // Helper function to check if a tariff matches the definition
-        let tariff_matches = |existing: &crate::db::models::tariffs::ModelTariff,
-                              def: &crate::api::models::deployments::TariffDefinition| {
-            existing.name == def.name
-                && existing.input_price_per_token == def.input_price_per_token
-                && existing.output_price_per_token == def.output_price_per_token
-                && existing.api_key_purpose == def.api_key_purpose
-                && existing.completion_window == def.completion_window
-        };
-
-        // Collect IDs of tariffs to close (those not in the new set or have changed)
-        let tariffs_to_close: Vec<uuid::Uuid> = current_tariffs
-            .iter()
-            .filter(|existing| !tariff_defs.iter().any(|def| tariff_matches(existing, def)))
-            .map(|t| t.id)
-            .collect();
-
-        // Batch close tariffs in a single query
-        if !tariffs_to_close.is_empty() {
-            tariffs_repo.close_tariffs_batch(&tariffs_to_close).await?;
-        }
-
-        // Create new or changed tariffs (skip those that already exist unchanged)
-        for tariff_def in tariff_defs {
-            // Skip if this tariff already exists with the same values
-            if current_tariffs.iter().any(|existing| tariff_matches(existing, &tariff_def)) {
-                continue;
-            }
-
-            let tariff_request = TariffCreateDBRequest {
-                deployed_model_id: deployment_id,
-                name: tariff_def.name,
-                input_price_per_token: tariff_def.input_price_per_token,
-                output_price_per_token: tariff_def.output_price_per_token,
-                api_key_purpose: tariff_def.api_key_purpose,
-                completion_window: tariff_def.completion_window,
-                valid_from: None, // Use NOW()
-            };
-            tariffs_repo.create(&tariff_request).await?;
-        }
+        replace_tariffs(tariff_conn, deployment_id, tariff_defs).await?;
     }
 
     tx.commit().await.map_err(|e| Error::Database(e.into()))?;
@@ -1109,6 +1264,416 @@ pub async fn delete_deployed_model<P: PoolProvider>(
     Ok(Json(deployment_id.to_string()))
 }
 
+#[utoipa::path(
+    patch,
+    path = "/models/{id}/activate",
+    tag = "models",
+    summary = "Activate deployed model",
+    description = "Put a deactivated model back into service. Requests for it are routed again once the proxy's config has synced.",
+    params(
+        ("id" = uuid::Uuid, Path, description = "Deployment ID to activate"),
+    ),
+    responses(
+        (status = 200, description = "Deployed model activated", body = DeployedModelResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - admin access required"),
+        (status = 404, description = "Deployment not found"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("BearerAuth" = []),
+        ("CookieAuth" = []),
+        ("X-Doubleword-User" = [])
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn activate_deployed_model<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(deployment_id): Path<DeploymentId>,
+    _: RequiresPermission<resource::Models, operation::UpdateAll>,
+) -> Result<Json<DeployedModelResponse>> {
+    set_deployment_active(&state, deployment_id, true).await
+}
+
+#[utoipa::path(
+    patch,
+    path = "/models/{id}/deactivate",
+    tag = "models",
+    summary = "Deactivate deployed model",
+    description = "Take a model out of service without deleting it. Requests for it get a 404, or a 503 when \
+        `onwards.deactivated_models` is `unavailable`. Its configuration, access and pricing are kept for reactivation.",
+    params(
+        ("id" = uuid::Uuid, Path, description = "Deployment ID to deactivate"),
+    ),
+    responses(
+        (status = 200, description = "Deployed model deactivated", body = DeployedModelResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - admin access required"),
+        (status = 404, description = "Deployment not found"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("BearerAuth" = []),
+        ("CookieAuth" = []),
+        ("X-Doubleword-User" = [])
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn deactivate_deployed_model<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(deployment_id): Path<DeploymentId>,
+    _: RequiresPermission<resource::Models, operation::UpdateAll>,
+) -> Result<Json<DeployedModelResponse>> {
+    set_deployment_active(&state, deployment_id, false).await
+}
+
+async fn set_deployment_active<P: PoolProvider>(
+    state: &AppState<P>,
+    deployment_id: DeploymentId,
+    active: bool,
+) -> Result<Json<DeployedModelResponse>> {
+    let mut pool_conn = state.db.write().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    let mut repo = Deployments::new(&mut pool_conn);
+
+    let not_found = || Error::NotFound {
+        resource: "Deployment".to_string(),
+        id: deployment_id.to_string(),
+    };
+    if !repo.set_active(deployment_id, active).await? {
+        return Err(not_found());
+    }
+    state.deployment_settings.invalidate();
+    let deployment = repo.get_by_id(deployment_id).await?.ok_or_else(not_found)?;
+    Ok(Json(DeployedModelResponse::from(deployment)))
+}
+
+/// Convert stored traffic rules back into the form they are written in
+fn traffic_rule_actions(rows: Vec<TrafficRuleDBRow>) -> Vec<(ApiKeyPurpose, TrafficRuleAction)> {
+    rows.into_iter()
+        .filter_map(|row| {
+            let purpose: ApiKeyPurpose = serde_json::from_value(serde_json::Value::String(row.api_key_purpose)).ok()?;
+            let action = match row.action.as_str() {
+                "deny" => TrafficRuleAction::Deny,
+                "redirect" => TrafficRuleAction::Redirect(row.redirect_target_id?),
+                "sanitize" => TrafficRuleAction::Sanitize(row.sanitize_responses?),
+                "prefer_region" => TrafficRuleAction::PreferRegion(row.prefer_region?),
+                _ => return None,
+            };
+            Some((purpose, action))
+        })
+        .collect()
+}
+
+#[utoipa::path(
+    post,
+    path = "/models/{id}/clone",
+    tag = "models",
+    summary = "Clone deployed model",
+    description = "Create a new deployment with the same configuration as an existing one under a new alias. \
+        Tariffs, traffic routing rules and, for composite models, components are copied. \
+        Group assignments are copied when `copy_groups` is set, which also requires permission to update groups.",
+    params(
+        ("id" = uuid::Uuid, Path, description = "Deployment ID to clone"),
+    ),
+    request_body = DeployedModelClone,
+    responses(
+        (status = 200, description = "Deployed model cloned", body = DeployedModelResponse),
+        (status = 400, description = "Bad request - empty or duplicate alias"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - admin access required"),
+        (status = 404, description = "Deployment not found"),
+        (status = 409, description = "Alias collides with an existing alias under alias normalization"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("BearerAuth" = []),
+        ("CookieAuth" = []),
+        ("X-Doubleword-User" = [])
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn clone_deployed_model<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(deployment_id): Path<DeploymentId>,
+    current_user: RequiresPermission<resource::Models, operation::CreateAll>,
+    Json(request): Json<DeployedModelClone>,
+) -> Result<Json<DeployedModelResponse>> {
+    let alias = request.alias.trim();
+    if alias.is_empty() {
+        return Err(Error::BadRequest {
+            message: "Alias must not be empty or whitespace".to_string(),
+        });
+    }
+    if request.copy_groups && !has_permission(&current_user, Resource::Groups, Operation::UpdateAll) {
+        return Err(Error::InsufficientPermissions {
+            required: Permission::Allow(Resource::Groups, Operation::UpdateAll),
+            action: Operation::UpdateAll,
+            resource: "group assignments of the cloned model".to_string(),
+        });
+    }
+
+    let mut tx = state.db.write().begin().await.map_err(|e| Error::Database(e.into()))?;
+
+    let mut repo = Deployments::new(tx.acquire().await.map_err(|e| Error::Database(e.into()))?);
+    let source = repo
+        .get_by_id(deployment_id)
+        .await?
+        .filter(|source| !source.deleted)
+        .ok_or_else(|| Error::NotFound {
+            resource: "Deployment".to_string(),
+            id: deployment_id.to_string(),
+        })?;
+
+    let alias_normalization = state.current_config().onwards.alias_normalization;
+    if alias_normalization != AliasNormalization::Exact {
+        check_normalized_alias_collision(alias_normalization, alias, None, &mut repo).await?;
+    }
+
+    let db_request = DeploymentCreateDBRequest::copy_of(&source, alias.to_string(), request.display_name, current_user.id);
+    let model = repo.create(&db_request).await?;
+
+    let rules = traffic_rule_actions(repo.get_traffic_rules(source.id).await?);
+    if !rules.is_empty() {
+        repo.set_traffic_rules(model.id, &rules).await?;
+    }
+
+    let components = repo
+        .get_components(source.id)
+        .await?
+        .into_iter()
+        .map(|c| (c.deployed_model_id, c.weight, c.enabled, c.sort_order))
+        .collect();
+    let components = repo.set_components(model.id, components).await?;
+
+    let tariffs = Tariffs::new(tx.acquire().await.map_err(|e| Error::Database(e.into()))?)
+        .list_current_by_model(source.id)
+        .await?
+        .into_iter()
+        .map(|t| TariffDefinition {
+            name: t.name,
+            input_price_per_token: t.input_price_per_token,
+            output_price_per_token: t.output_price_per_token,
+            api_key_purpose: t.api_key_purpose,
+            completion_window: t.completion_window,
+        })
+        .collect();
+    replace_tariffs(tx.acquire().await.map_err(|e| Error::Database(e.into()))?, model.id, tariffs).await?;
+
+    if request.copy_groups {
+        let mut groups = Groups::new(tx.acquire().await.map_err(|e| Error::Database(e.into()))?);
+        for group_id in groups.get_deployment_groups(source.id).await? {
+            groups.add_deployment_to_group(model.id, group_id, current_user.id).await?;
+        }
+    }
+
+    let rules = Deployments::new(tx.acquire().await.map_err(|e| Error::Database(e.into()))?)
+        .get_traffic_rules(model.id)
+        .await?;
+    tx.commit().await.map_err(|e| Error::Database(e.into()))?;
+
+    let mut response = DeployedModelResponse::from(model).with_traffic_rules(rules);
+    if source.is_composite {
+        response = response.with_components(components.into_iter().map(db_component_to_response).collect());
+    }
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    get,
+    path = "/models/{id}/resolved-config",
+    tag = "models",
+    summary = "Preview resolved routing config",
+    description = "Build the onwards routing config for a model from the database, as the proxy's config sync would, \
+        without reading the proxy's live cache. Upstream credentials are masked and authorized API keys are only counted.",
+    params(
+        ("id" = uuid::Uuid, Path, description = "Deployment ID"),
+    ),
+    responses(
+        (status = 200, description = "Resolved routing config", body = ResolvedModelConfig),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Insufficient permissions"),
+        (status = 404, description = "Deployment not found"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("BearerAuth" = []),
+        ("CookieAuth" = []),
+        ("X-Doubleword-User" = [])
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_resolved_config<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(deployment_id): Path<DeploymentId>,
+    _: RequiresPermission<resource::Models, operation::UpdateAll>,
+) -> Result<Json<ResolvedModelConfig>> {
+    let deployment = {
+        let mut pool_conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
+        let mut repo = Deployments::new(&mut pool_conn);
+        repo.get_by_id(deployment_id).await?.ok_or_else(|| Error::NotFound {
+            resource: "Deployment".to_string(),
+            id: deployment_id.to_string(),
+        })?
+    };
+
+    // Same inputs as the config sync started in `lib.rs`
+    let config = state.config.snapshot();
+    let escalation_models: Vec<String> = config
+        .background_services
+        .batch_daemon
+        .model_escalations
+        .values()
+        .map(|e| e.escalation_model.clone())
+        .collect();
+    let secrets = crate::secrets::SecretStore::new(&config.secrets);
+    let mut onwards_config = crate::sync::onwards_config::load_config_file_from_db(
+        state.db.read(),
+        &escalation_models,
+        config.onwards.strict_mode,
+        &config.auth.rate_limits,
+        state.connections_encryption_key.as_deref(),
+        Some(&secrets),
+    )
+    .await
+    .map_err(|e| Error::Internal {
+        operation: format!("resolve onwards config: {e}"),
+    })?;
+
+    let spec = onwards_config.targets.remove(&deployment.alias);
+    let resolved = ResolvedModelConfig::new(deployment.id, deployment.alias, spec).map_err(|e| Error::Internal {
+        operation: format!("serialize resolved config: {e}"),
+    })?;
+    Ok(Json(resolved))
+}
+
+#[utoipa::path(
+    post,
+    path = "/models/{id}/estimate-cost",
+    tag = "models",
+    summary = "Estimate request cost",
+    description = "Estimate what a request to the model would cost at its current tariff, without forwarding it. \
+        The body is an inference request such as a chat completion. Prompt tokens are counted by the tokenizer when \
+        cached-input pricing is enabled and estimated from the prompt's length otherwise. \
+        The typical cost assumes completions of the model's recent average length, at most the request's token cap; \
+        the worst case assumes every completion asked for with `n` reaches the cap.",
+    params(
+        ("id" = uuid::Uuid, Path, description = "Deployment ID"),
+        RequestCostEstimateQuery,
+    ),
+    request_body(content = inline(Object), description = "Inference request to estimate, e.g. a chat completion"),
+    responses(
+        (status = 200, description = "Cost estimate", body = RequestCostEstimate),
+        (status = 400, description = "Bad request - the body isn't a JSON object"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Insufficient permissions"),
+        (status = 404, description = "Deployment not found"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("BearerAuth" = []),
+        ("CookieAuth" = []),
+        ("X-Doubleword-User" = [])
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn estimate_request_cost<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(deployment_id): Path<DeploymentId>,
+    Query(query): Query<RequestCostEstimateQuery>,
+    _: RequiresPermission<resource::Models, operation::ReadAll>,
+    Json(body): Json<serde_json::Value>,
+) -> Result<Json<RequestCostEstimate>> {
+    if !body.is_object() {
+        return Err(Error::BadRequest {
+            message: "Request body must be a JSON object".to_string(),
+        });
+    }
+
+    let mut conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    let deployment = Deployments::new(&mut conn)
+        .get_by_id(deployment_id)
+        .await?
+        .filter(|deployment| !deployment.deleted)
+        .ok_or_else(|| Error::NotFound {
+            resource: "Deployment".to_string(),
+            id: deployment_id.to_string(),
+        })?;
+
+    // Tokens are counted as the prompt length limit counts them
+    let tokenizer = {
+        let config = state.current_config();
+        config
+            .cache
+            .enabled
+            .then(|| crate::prompt_cache::TokenizerClient::new(config.cache.tokenizer_url.clone()))
+    };
+    let (_, segments) = crate::inference::prompt_length::prompt_segments(&body);
+    let (input_tokens, input_tokens_estimated) =
+        crate::inference::prompt_length::count_tokens(tokenizer.as_ref(), &deployment.alias, &segments).await;
+
+    let completions = crate::inference::cost_guard::requested_completions(&body, deployment.max_n.and_then(|n| u64::try_from(n).ok()));
+    let (typical_per_completion, max_per_completion) = if matches!(deployment.model_type, Some(ModelType::Embeddings)) {
+        (0, Some(0))
+    } else {
+        let token_cap = crate::inference::cost_guard::requested_token_cap(&body).map(|(_, cap)| cap);
+        let context_window = deployment.metadata.get("context_window").and_then(|window| window.as_u64());
+        let max_per_completion = token_cap.or_else(|| context_window.map(|window| window.saturating_sub(input_tokens)));
+        let average = sqlx::query_scalar!(
+            r#"
+            SELECT AVG(completion_tokens)::BIGINT
+            FROM (
+                SELECT completion_tokens
+                FROM http_analytics
+                WHERE model = $1
+                  AND completion_tokens IS NOT NULL
+                  AND status_code = 200
+                ORDER BY timestamp DESC
+                LIMIT 100
+            ) recent_responses
+            "#,
+            deployment.alias
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| Error::Database(e.into()))?
+        .and_then(|average| u64::try_from(average).ok());
+        // Without history, assume a reply as long as the prompt
+        let typical = average.unwrap_or(input_tokens);
+        (max_per_completion.map_or(typical, |max| typical.min(max)), max_per_completion)
+    };
+
+    let (input_price_per_token, output_price_per_token) = Tariffs::new(&mut conn)
+        .get_pricing_at_timestamp_with_fallback(
+            deployment.id,
+            query.api_key_purpose.as_ref(),
+            &ApiKeyPurpose::Realtime,
+            chrono::Utc::now(),
+            query.completion_window.as_deref(),
+        )
+        .await?
+        .unwrap_or_default();
+
+    let typical_output_tokens = typical_per_completion.saturating_mul(completions);
+    let max_output_tokens = max_per_completion.map(|max| max.saturating_mul(completions));
+    let cost_of = |output_tokens: u64| {
+        input_price_per_token.saturating_mul(rust_decimal::Decimal::from(input_tokens))
+            + output_price_per_token.saturating_mul(rust_decimal::Decimal::from(output_tokens))
+    };
+    Ok(Json(RequestCostEstimate {
+        id: deployment.id,
+        alias: deployment.alias,
+        input_tokens,
+        input_tokens_estimated,
+        completions,
+        typical_output_tokens,
+        max_output_tokens,
+        input_price_per_token,
+        output_price_per_token,
+        typical_cost: cost_of(typical_output_tokens),
+        worst_case_cost: max_output_tokens.map(cost_of),
+    }))
+}
+
 // ===== Composite Model Component Handlers =====
 
 use crate::api::models::deployments::{ModelComponentCreate, ModelComponentUpdate};
@@ -1378,7 +1943,7 @@ mod tests {
     use crate::{
         api::{
             handlers::deployments::DeployedModelResponse,
-            models::{pagination::PaginatedResponse, users::Role},
+            models::{deployments::RequestCostEstimate, pagination::PaginatedResponse, users::Role},
         },
         db::{
             handlers::{Deployments, Groups, Repository},
@@ -1496,57 +2061,191 @@ mod tests {
             .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
             .await;
 
-        response.assert_status_not_found(); // Returns 404 without deleted=true
+        response.assert_status_not_found(); // Returns 404 without deleted=true
+
+        // But admin should be able to see it with deleted=true
+        let response = app
+            .get(&format!("/admin/api/v1/models/{deployment_id}?deleted=true"))
+            .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
+            .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+            .await;
+
+        response.assert_status_ok(); // Admin can see deleted model with deleted=true
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_composite_model_patch_rejects_reasoning_translation_overrides(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let user = create_test_admin_user(&pool, Role::PlatformManager).await;
+
+        let response = app
+            .post("/admin/api/v1/models")
+            .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
+            .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+            .json(&json!({
+                "type": "composite",
+                "model_name": "reasoning-composite",
+                "alias": "reasoning-composite"
+            }))
+            .await;
+        response.assert_status_ok();
+        let model: DeployedModelResponse = response.json();
+
+        let response = app
+            .patch(&format!("/admin/api/v1/models/{}", model.id))
+            .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
+            .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+            .json(&json!({
+                "reasoning_translation_overrides": {
+                    "chat_completions": { "mode": "disabled" }
+                }
+            }))
+            .await;
+
+        response.assert_status_bad_request();
+
+        let stored =
+            sqlx::query_scalar::<_, Option<serde_json::Value>>("SELECT reasoning_translation_overrides FROM deployed_models WHERE id = $1")
+                .bind(model.id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(stored, None);
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_resolved_config_for_composite_model(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let manager = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let manager_headers = add_auth_headers(&manager);
+
+        let endpoint_id = create_test_endpoint(&pool, "resolved-endpoint", manager.id).await;
+        sqlx::query("UPDATE inference_endpoints SET api_key = 'sk-upstream-secret' WHERE id = $1")
+            .bind(endpoint_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let primary_id = create_test_model(&pool, "primary-model", "resolved-primary", endpoint_id, manager.id).await;
+        let backup_id = create_test_model(&pool, "backup-model", "resolved-backup", endpoint_id, manager.id).await;
+
+        let response = app
+            .post("/admin/api/v1/models")
+            .add_header(&manager_headers[0].0, &manager_headers[0].1)
+            .add_header(&manager_headers[1].0, &manager_headers[1].1)
+            .json(&json!({
+                "type": "composite",
+                "model_name": "resolved-composite",
+                "alias": "resolved-composite",
+                "lb_strategy": "priority"
+            }))
+            .await;
+        response.assert_status_ok();
+        let composite: DeployedModelResponse = response.json();
+
+        for component_id in [primary_id, backup_id] {
+            app.post(&format!("/admin/api/v1/models/{}/components/{component_id}", composite.id))
+                .add_header(&manager_headers[0].0, &manager_headers[0].1)
+                .add_header(&manager_headers[1].0, &manager_headers[1].1)
+                .json(&json!({ "weight": 50 }))
+                .await
+                .assert_status_ok();
+        }
 
-        // But admin should be able to see it with deleted=true
         let response = app
-            .get(&format!("/admin/api/v1/models/{deployment_id}?deleted=true"))
-            .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
-            .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+            .get(&format!("/admin/api/v1/models/{}/resolved-config", composite.id))
+            .add_header(&manager_headers[0].0, &manager_headers[0].1)
+            .add_header(&manager_headers[1].0, &manager_headers[1].1)
             .await;
+        response.assert_status_ok();
+        assert!(!response.text().contains("sk-upstream-secret"));
+
+        let resolved: serde_json::Value = response.json();
+        assert_eq!(resolved["alias"], "resolved-composite");
+        let target = &resolved["target"];
+        assert_eq!(target["strategy"], "priority");
+        assert!(target.get("keys").is_none_or(|keys| keys.is_null()));
+
+        let providers = target["providers"].as_array().unwrap();
+        let names: Vec<&str> = providers.iter().map(|p| p["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["resolved-primary", "resolved-backup"]);
+        for provider in providers {
+            assert_eq!(provider["onwards_key"], crate::api::models::deployments::MASKED_SECRET);
+        }
 
-        response.assert_status_ok(); // Admin can see deleted model with deleted=true
+        // Other roles can't read it
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let user_headers = add_auth_headers(&user);
+        app.get(&format!("/admin/api/v1/models/{}/resolved-config", composite.id))
+            .add_header(&user_headers[0].0, &user_headers[0].1)
+            .add_header(&user_headers[1].0, &user_headers[1].1)
+            .await
+            .assert_status_forbidden();
     }
 
     #[sqlx::test]
     #[test_log::test]
-    async fn test_composite_model_patch_rejects_reasoning_translation_overrides(pool: PgPool) {
-        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+    async fn test_create_deployment_applies_default_limits(pool: PgPool) {
+        let mut config = create_test_config();
+        config.limits.deployments.requests_per_second = Some(5.0);
+        config.limits.deployments.burst_size = Some(10);
+        config.limits.deployments.capacity = Some(1);
+        let (app, _bg_services) = create_test_app_with_config(pool.clone(), config, false).await;
         let user = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let endpoint_id = get_test_endpoint_id(&pool).await;
 
         let response = app
             .post("/admin/api/v1/models")
             .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
             .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
             .json(&json!({
-                "type": "composite",
-                "model_name": "reasoning-composite",
-                "alias": "reasoning-composite"
+                "type": "standard",
+                "model_name": "defaulted-model",
+                "alias": "defaulted-model",
+                "hosted_on": endpoint_id
             }))
             .await;
         response.assert_status_ok();
         let model: DeployedModelResponse = response.json();
+        assert_eq!(model.requests_per_second, Some(5.0));
+        assert_eq!(model.burst_size, Some(10));
+        assert_eq!(model.capacity, Some(1));
 
+        // Explicit values win over the defaults
         let response = app
-            .patch(&format!("/admin/api/v1/models/{}", model.id))
+            .post("/admin/api/v1/models")
             .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
             .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
             .json(&json!({
-                "reasoning_translation_overrides": {
-                    "chat_completions": { "mode": "disabled" }
-                }
+                "type": "standard",
+                "model_name": "explicit-model",
+                "alias": "explicit-model",
+                "hosted_on": endpoint_id,
+                "capacity": 4
             }))
             .await;
-
-        response.assert_status_bad_request();
-
-        let stored =
-            sqlx::query_scalar::<_, Option<serde_json::Value>>("SELECT reasoning_translation_overrides FROM deployed_models WHERE id = $1")
-                .bind(model.id)
-                .fetch_one(&pool)
-                .await
-                .unwrap();
-        assert_eq!(stored, None);
+        response.assert_status_ok();
+        let explicit: DeployedModelResponse = response.json();
+        assert_eq!(explicit.capacity, Some(4));
+        assert_eq!(explicit.requests_per_second, Some(5.0));
+
+        // The defaults reach the onwards config like any other limit
+        let targets = crate::sync::onwards_config::load_targets_from_db(
+            &pool,
+            &[],
+            false,
+            &crate::config::RateLimitTiersConfig::default(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let target = targets.targets.get("defaulted-model").expect("defaulted-model should be loaded");
+        let provider_pool = target.value();
+        assert!(provider_pool.first_target().unwrap().limiter.is_some(), "rate limit should be set");
+        let _guard = provider_pool.select().expect("first request should get a slot");
+        assert!(provider_pool.select().is_none(), "capacity of 1 should be enforced");
     }
 
     #[sqlx::test]
@@ -3281,6 +3980,61 @@ mod tests {
         assert!(get_model_by_id(other_group_deployment.id, &realtime_models).is_none());
     }
 
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_list_models_with_alias_endpoint_and_capability_filters(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let admin_user = create_test_admin_user(&pool, Role::PlatformManager).await;
+
+        let other_endpoint = create_test_endpoint(&pool, "filter-endpoint", admin_user.id).await;
+        let chat_a = create_test_deployment(&pool, admin_user.id, "filter-chat-a-model", "filter-chat-a")
+            .await
+            .id;
+        let chat_b = create_test_model(&pool, "filter-chat-b-model", "filter-chat-b", other_endpoint, admin_user.id).await;
+        let plain = create_test_model(&pool, "filter-plain-model", "filter-plain", other_endpoint, admin_user.id).await;
+        sqlx::query("UPDATE deployed_models SET capabilities = ARRAY['vision'] WHERE id = ANY($1)")
+            .bind(vec![chat_a, chat_b])
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let list = |query: String| {
+            let request = app
+                .get(&format!("/admin/api/v1/models?limit=100&{query}"))
+                .add_header(&add_auth_headers(&admin_user)[0].0, &add_auth_headers(&admin_user)[0].1)
+                .add_header(&add_auth_headers(&admin_user)[1].0, &add_auth_headers(&admin_user)[1].1);
+            async move {
+                let response = request.await;
+                response.assert_status_ok();
+                let page: PaginatedResponse<DeployedModelResponse> = response.json();
+                assert_eq!(page.total_count, page.data.len() as i64, "count must honour the same filters");
+                let mut ids: Vec<_> = page.data.into_iter().map(|m| m.id).collect();
+                ids.sort();
+                ids
+            }
+        };
+        let sorted = |mut ids: Vec<DeploymentId>| {
+            ids.sort();
+            ids
+        };
+
+        // Each filter on its own
+        assert_eq!(list("alias_contains=FILTER-CHAT".to_string()).await, sorted(vec![chat_a, chat_b]));
+        assert_eq!(list(format!("endpoint_id={other_endpoint}")).await, sorted(vec![chat_b, plain]));
+        assert_eq!(list("capability=vision".to_string()).await, sorted(vec![chat_a, chat_b]));
+
+        // Filters combine with AND
+        assert_eq!(
+            list(format!("alias_contains=filter&endpoint_id={other_endpoint}&capability=vision")).await,
+            vec![chat_b]
+        );
+        assert!(
+            list(format!("alias_contains=plain&capability=vision&endpoint_id={other_endpoint}"))
+                .await
+                .is_empty()
+        );
+    }
+
     // ===== Traffic Routing Rules Tests =====
 
     #[sqlx::test]
@@ -3631,6 +4385,44 @@ mod tests {
         response.assert_status(axum::http::StatusCode::BAD_REQUEST);
     }
 
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_update_model_prefer_region_traffic_rule(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let admin_user = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let model = create_test_deployment(&pool, admin_user.id, "region-model", "region-alias").await;
+
+        let response = app
+            .patch(&format!("/admin/api/v1/models/{}", model.id))
+            .add_header(&add_auth_headers(&admin_user)[0].0, &add_auth_headers(&admin_user)[0].1)
+            .add_header(&add_auth_headers(&admin_user)[1].0, &add_auth_headers(&admin_user)[1].1)
+            .json(&json!({
+                "traffic_routing_rules": [
+                    { "api_key_purpose": "realtime", "action": { "type": "prefer_region", "region": "eu-west" } }
+                ]
+            }))
+            .await;
+        response.assert_status_ok();
+        let updated: DeployedModelResponse = response.json();
+        let rules = updated.traffic_routing_rules.expect("traffic rules should be set");
+        match &rules[0].action {
+            crate::api::models::deployments::TrafficRoutingAction::PreferRegion { region } => assert_eq!(region, "eu-west"),
+            _ => panic!("expected prefer_region action"),
+        }
+
+        let response = app
+            .patch(&format!("/admin/api/v1/models/{}", model.id))
+            .add_header(&add_auth_headers(&admin_user)[0].0, &add_auth_headers(&admin_user)[0].1)
+            .add_header(&add_auth_headers(&admin_user)[1].0, &add_auth_headers(&admin_user)[1].1)
+            .json(&json!({
+                "traffic_routing_rules": [
+                    { "api_key_purpose": "realtime", "action": { "type": "prefer_region", "region": " " } }
+                ]
+            }))
+            .await;
+        response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+    }
+
     #[sqlx::test]
     #[test_log::test]
     async fn test_delete_redirect_target_cascades(pool: PgPool) {
@@ -3680,4 +4472,472 @@ mod tests {
             "traffic rules should be cleared after cascade delete of redirect target"
         );
     }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_normalized_alias_collision_rejected(pool: PgPool) {
+        let mut config = create_test_config();
+        config.onwards.alias_normalization = crate::config::AliasNormalization::CaseInsensitive;
+        let (app, _bg_services) = create_test_app_with_config(pool.clone(), config, false).await;
+        let admin_user = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let headers = add_auth_headers(&admin_user);
+        let test_endpoint_id = get_test_endpoint_id(&pool).await;
+
+        let create = |model_name: &str, alias: &str| {
+            json!({
+                "type": "standard",
+                "model_name": model_name,
+                "alias": alias,
+                "hosted_on": test_endpoint_id.to_string()
+            })
+        };
+
+        let response = app
+            .post("/admin/api/v1/models")
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .json(&create("norm-model-a", "gpt-4-norm"))
+            .await;
+        response.assert_status_ok();
+        let first: DeployedModelResponse = response.json();
+
+        let response = app
+            .post("/admin/api/v1/models")
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .json(&create("norm-model-b", "GPT-4-Norm"))
+            .await;
+        response.assert_status(axum::http::StatusCode::CONFLICT);
+
+        let response = app
+            .post("/admin/api/v1/models")
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .json(&create("norm-model-b", "other-alias"))
+            .await;
+        response.assert_status_ok();
+        let second: DeployedModelResponse = response.json();
+
+        // Renaming into a collision is rejected, but re-casing a model's own alias is allowed
+        app.patch(&format!("/admin/api/v1/models/{}", second.id))
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .json(&json!({ "alias": "Gpt-4-Norm" }))
+            .await
+            .assert_status(axum::http::StatusCode::CONFLICT);
+        app.patch(&format!("/admin/api/v1/models/{}", first.id))
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .json(&json!({ "alias": "GPT-4-NORM" }))
+            .await
+            .assert_status_ok();
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_differently_cased_aliases_allowed_without_normalization(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let admin_user = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let headers = add_auth_headers(&admin_user);
+        let test_endpoint_id = get_test_endpoint_id(&pool).await;
+
+        for (model_name, alias) in [("exact-model-a", "gpt-4-exact"), ("exact-model-b", "GPT-4-Exact")] {
+            app.post("/admin/api/v1/models")
+                .add_header(&headers[0].0, &headers[0].1)
+                .add_header(&headers[1].0, &headers[1].1)
+                .json(&json!({
+                    "type": "standard",
+                    "model_name": model_name,
+                    "alias": alias,
+                    "hosted_on": test_endpoint_id.to_string()
+                }))
+                .await
+                .assert_status_ok();
+        }
+    }
+
+    /// Deploy `alias` on a mock upstream, grant it to everyone and return an
+    /// admin's auth headers, the deployment and a user's realtime API key.
+    async fn deploy_proxied_model(
+        app: &axum_test::TestServer,
+        bg_services: &crate::BackgroundServices,
+        pool: &PgPool,
+        mock_server: &wiremock::MockServer,
+        alias: &str,
+    ) -> (Vec<(String, String)>, DeployedModelResponse, String) {
+        use wiremock::{
+            Mock, ResponseTemplate,
+            matchers::{method, path},
+        };
+
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "chatcmpl-1", "object": "chat.completion", "created": 1, "model": alias,
+                "choices": [ { "index": 0, "message": { "role": "assistant", "content": "Hello" }, "finish_reason": "stop" } ],
+                "usage": { "prompt_tokens": 5, "completion_tokens": 1, "total_tokens": 6 }
+            })))
+            .mount(mock_server)
+            .await;
+
+        let proxied = setup_proxied_model(
+            app,
+            bg_services,
+            pool,
+            mock_server,
+            json!({ "sync": false }),
+            json!({ "alias": alias }),
+        )
+        .await;
+        let model = serde_json::from_value(proxied.model).expect("Failed to parse deployment");
+        (proxied.admin_headers, model, proxied.api_key)
+    }
+
+    fn chat_body(alias: &str) -> serde_json::Value {
+        json!({ "model": alias, "messages": [{ "role": "user", "content": "hi" }] })
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_deactivated_model_stops_serving_until_reactivated(pool: PgPool) {
+        let mock_server = wiremock::MockServer::start().await;
+        let (app, bg_services) = create_test_app(pool.clone(), false).await;
+        let (headers, model, api_key) = deploy_proxied_model(&app, &bg_services, &pool, &mock_server, "pausable-model").await;
+        assert_eq!(model.active, Some(true));
+
+        await_chat_status(&app, &api_key, &chat_body("pausable-model"), 200).await;
+
+        let response = app
+            .patch(&format!("/admin/api/v1/models/{}/deactivate", model.id))
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .await;
+        response.assert_status_ok();
+        let deactivated: DeployedModelResponse = response.json();
+        assert_eq!(deactivated.active, Some(false));
+        // Configuration is kept
+        assert_eq!(deactivated.alias, "pausable-model");
+        assert_eq!(deactivated.hosted_on, model.hosted_on);
+
+        bg_services.sync_onwards_config(&pool).await.expect("Failed to sync onwards config");
+        let body: serde_json::Value = await_chat_status(&app, &api_key, &chat_body("pausable-model"), 404).await.json();
+        assert_eq!(body["error"]["code"], "model_not_found");
+        let served = mock_server.received_requests().await.unwrap().len();
+
+        // Deactivated models are left out of the model list
+        let models: serde_json::Value = app
+            .get("/ai/v1/models")
+            .add_header("authorization", format!("Bearer {api_key}"))
+            .await
+            .json();
+        assert!(!models["data"].as_array().unwrap().iter().any(|m| m["id"] == "pausable-model"));
+
+        let response = app
+            .patch(&format!("/admin/api/v1/models/{}/activate", model.id))
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .await;
+        response.assert_status_ok();
+        assert_eq!(response.json::<DeployedModelResponse>().active, Some(true));
+
+        bg_services.sync_onwards_config(&pool).await.expect("Failed to sync onwards config");
+        let body: serde_json::Value = await_chat_status(&app, &api_key, &chat_body("pausable-model"), 200).await.json();
+        assert_eq!(body["choices"][0]["message"]["content"], "Hello");
+        assert_eq!(mock_server.received_requests().await.unwrap().len(), served + 1);
+
+        app.patch(&format!("/admin/api/v1/models/{}/deactivate", uuid::Uuid::new_v4()))
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .await
+            .assert_status_not_found();
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_deactivated_model_unavailable_response(pool: PgPool) {
+        let mut config = create_test_config();
+        config.onwards.deactivated_models = crate::config::DeactivatedModelResponse::Unavailable;
+        let mock_server = wiremock::MockServer::start().await;
+        let (app, bg_services) = create_test_app_with_config(pool.clone(), config, false).await;
+        let (headers, model, api_key) = deploy_proxied_model(&app, &bg_services, &pool, &mock_server, "paused-model").await;
+
+        app.patch(&format!("/admin/api/v1/models/{}/deactivate", model.id))
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .await
+            .assert_status_ok();
+        bg_services.sync_onwards_config(&pool).await.expect("Failed to sync onwards config");
+
+        let body: serde_json::Value = await_chat_status(&app, &api_key, &chat_body("paused-model"), 503).await.json();
+        assert_eq!(body["error"]["code"], "model_disabled");
+        assert_eq!(body["error"]["type"], "service_unavailable");
+        assert!(mock_server.received_requests().await.unwrap().is_empty());
+
+        // Non-admins can't toggle models
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let user_headers = add_auth_headers(&user);
+        app.patch(&format!("/admin/api/v1/models/{}/activate", model.id))
+            .add_header(&user_headers[0].0, &user_headers[0].1)
+            .add_header(&user_headers[1].0, &user_headers[1].1)
+            .await
+            .assert_status_forbidden();
+    }
+
+    /// Fetch a model with everything a clone copies, minus the fields identifying it
+    async fn model_config(app: &axum_test::TestServer, headers: &[(String, String)], id: DeploymentId) -> serde_json::Value {
+        let response = app
+            .get(&format!("/admin/api/v1/models/{id}?include=groups,pricing,components"))
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .await;
+        response.assert_status_ok();
+        let mut model: serde_json::Value = response.json();
+        let model_fields = model.as_object_mut().unwrap();
+        for field in ["id", "alias", "created_by", "created_at", "updated_at"] {
+            model_fields.remove(field);
+        }
+        if let Some(tariffs) = model_fields.get_mut("tariffs").and_then(|t| t.as_array_mut()) {
+            for tariff in tariffs.iter_mut() {
+                let tariff = tariff.as_object_mut().unwrap();
+                for field in ["id", "deployed_model_id", "valid_from"] {
+                    tariff.remove(field);
+                }
+            }
+            tariffs.sort_by_key(|t| t["name"].as_str().unwrap().to_string());
+        }
+        if let Some(components) = model_fields.get_mut("components").and_then(|c| c.as_array_mut()) {
+            for component in components {
+                component.as_object_mut().unwrap().remove("created_at");
+            }
+        }
+        model
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_clone_standard_deployment(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let admin_user = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let headers = add_auth_headers(&admin_user);
+        let endpoint_id = get_test_endpoint_id(&pool).await;
+        create_test_deployment(&pool, admin_user.id, "clone-target", "clone-target-alias").await;
+
+        let response = app
+            .post("/admin/api/v1/models")
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .json(&json!({
+                "type": "standard",
+                "model_name": "clone-source",
+                "alias": "clone-source-alias",
+                "display_name": "Clone Source",
+                "hosted_on": endpoint_id,
+                "capabilities": ["vision"],
+                "requests_per_second": 5.0,
+                "burst_size": 10,
+                "capacity": 20,
+                "max_prompt_length": 1000,
+                "system_prompt": "Be brief.",
+                "traffic_routing_rules": [
+                    { "api_key_purpose": "batch", "action": { "type": "redirect", "target": "clone-target-alias" } }
+                ],
+                "tariffs": [
+                    { "name": "realtime", "input_price_per_token": "0.001", "output_price_per_token": "0.003", "api_key_purpose": "realtime" },
+                    { "name": "default", "input_price_per_token": "0.002", "output_price_per_token": "0.004" }
+                ]
+            }))
+            .await;
+        response.assert_status_ok();
+        let source: DeployedModelResponse = response.json();
+
+        let group = create_test_group(&pool).await;
+        let mut conn = pool.acquire().await.unwrap();
+        Groups::new(&mut conn)
+            .add_deployment_to_group(source.id, group.id, admin_user.id)
+            .await
+            .unwrap();
+
+        let response = app
+            .post(&format!("/admin/api/v1/models/{}/clone", source.id))
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .json(&json!({ "alias": "clone-copy-alias", "copy_groups": true }))
+            .await;
+        response.assert_status_ok();
+        let clone: DeployedModelResponse = response.json();
+        assert_ne!(clone.id, source.id);
+        assert_eq!(clone.alias, "clone-copy-alias");
+        assert_eq!(clone.model_name, "clone-source");
+        assert_eq!(clone.traffic_routing_rules.map(|rules| rules.len()), Some(1));
+
+        let source_config = model_config(&app, &headers, source.id).await;
+        assert_eq!(source_config["tariffs"].as_array().unwrap().len(), 2);
+        assert_eq!(source_config["groups"].as_array().unwrap().len(), 1);
+        assert_eq!(model_config(&app, &headers, clone.id).await, source_config);
+
+        // Without copy_groups the clone isn't added to any group
+        let response = app
+            .post(&format!("/admin/api/v1/models/{}/clone", source.id))
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .json(&json!({ "alias": "clone-ungrouped-alias", "display_name": "Ungrouped" }))
+            .await;
+        response.assert_status_ok();
+        let ungrouped: DeployedModelResponse = response.json();
+        let ungrouped_config = model_config(&app, &headers, ungrouped.id).await;
+        assert_eq!(ungrouped_config["display_name"], "Ungrouped");
+        assert!(ungrouped_config["groups"].as_array().is_none_or(|groups| groups.is_empty()));
+
+        // The alias must be new
+        app.post(&format!("/admin/api/v1/models/{}/clone", source.id))
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .json(&json!({ "alias": "clone-copy-alias" }))
+            .await
+            .assert_status_not_ok();
+
+        // Non-admins can't clone
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let user_headers = add_auth_headers(&user);
+        app.post(&format!("/admin/api/v1/models/{}/clone", source.id))
+            .add_header(&user_headers[0].0, &user_headers[0].1)
+            .add_header(&user_headers[1].0, &user_headers[1].1)
+            .json(&json!({ "alias": "clone-forbidden-alias" }))
+            .await
+            .assert_status_forbidden();
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_clone_composite_deployment(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let admin_user = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let headers = add_auth_headers(&admin_user);
+        let endpoint_id = create_test_endpoint(&pool, "clone-endpoint", admin_user.id).await;
+        let primary_id = create_test_model(&pool, "primary-model", "clone-primary", endpoint_id, admin_user.id).await;
+        let backup_id = create_test_model(&pool, "backup-model", "clone-backup", endpoint_id, admin_user.id).await;
+
+        let response = app
+            .post("/admin/api/v1/models")
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .json(&json!({
+                "type": "composite",
+                "model_name": "clone-composite",
+                "alias": "clone-composite",
+                "lb_strategy": "priority",
+                "fallback_enabled": true,
+                "fallback_on_status": [429, 503],
+                "per_key_capacity": 4,
+                "tariffs": [
+                    { "name": "default", "input_price_per_token": "0.002", "output_price_per_token": "0.004" }
+                ]
+            }))
+            .await;
+        response.assert_status_ok();
+        let source: DeployedModelResponse = response.json();
+
+        for (component_id, weight) in [(primary_id, 70), (backup_id, 30)] {
+            app.post(&format!("/admin/api/v1/models/{}/components/{component_id}", source.id))
+                .add_header(&headers[0].0, &headers[0].1)
+                .add_header(&headers[1].0, &headers[1].1)
+                .json(&json!({ "weight": weight }))
+                .await
+                .assert_status_ok();
+        }
+
+        let response = app
+            .post(&format!("/admin/api/v1/models/{}/clone", source.id))
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .json(&json!({ "alias": "clone-composite-copy" }))
+            .await;
+        response.assert_status_ok();
+        let clone: DeployedModelResponse = response.json();
+        assert_eq!(clone.is_composite, Some(true));
+        assert_eq!(clone.components.map(|components| components.len()), Some(2));
+
+        let source_config = model_config(&app, &headers, source.id).await;
+        let components: Vec<&str> = source_config["components"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["model"]["alias"].as_str().unwrap())
+            .collect();
+        assert_eq!(components, ["clone-primary", "clone-backup"]);
+        assert_eq!(model_config(&app, &headers, clone.id).await, source_config);
+
+        // Cloning doesn't touch the source's components
+        let mut conn = pool.acquire().await.unwrap();
+        assert_eq!(Deployments::new(&mut conn).get_components(source.id).await.unwrap().len(), 2);
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_estimate_request_cost_from_tariff(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let admin_user = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let headers = add_auth_headers(&admin_user);
+        let endpoint_id = get_test_endpoint_id(&pool).await;
+
+        let response = app
+            .post("/admin/api/v1/models")
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .json(&json!({
+                "type": "standard",
+                "model_name": "estimate-model",
+                "alias": "estimate-model",
+                "hosted_on": endpoint_id,
+                "tariffs": [
+                    { "name": "realtime", "input_price_per_token": "0.001", "output_price_per_token": "0.003", "api_key_purpose": "realtime" }
+                ]
+            }))
+            .await;
+        response.assert_status_ok();
+        let model: DeployedModelResponse = response.json();
+
+        // 400 characters, estimated at four per token without the tokenizer
+        let messages = json!([{ "role": "user", "content": "x".repeat(400) }]);
+        let response = app
+            .post(&format!("/admin/api/v1/models/{}/estimate-cost", model.id))
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .json(&json!({ "model": "estimate-model", "messages": messages, "max_tokens": 200, "n": 2 }))
+            .await;
+        response.assert_status_ok();
+        let estimate: RequestCostEstimate = response.json();
+        assert_eq!(estimate.input_tokens, 100);
+        assert!(estimate.input_tokens_estimated);
+        assert_eq!(estimate.completions, 2);
+        // Without history a completion is assumed as long as the prompt
+        assert_eq!(estimate.typical_output_tokens, 200);
+        assert_eq!(estimate.max_output_tokens, Some(400));
+        // 100 * 0.001 + 200 * 0.003 and 100 * 0.001 + 400 * 0.003
+        assert_eq!(estimate.typical_cost, "0.7".parse().unwrap());
+        assert_eq!(estimate.worst_case_cost, Some("1.3".parse().unwrap()));
+
+        // Nothing bounds the output of a request without a token cap
+        let estimate: RequestCostEstimate = app
+            .post(&format!("/admin/api/v1/models/{}/estimate-cost", model.id))
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .json(&json!({ "model": "estimate-model", "messages": messages }))
+            .await
+            .json();
+        assert_eq!(estimate.typical_output_tokens, 100);
+        assert_eq!(estimate.typical_cost, "0.4".parse().unwrap());
+        assert_eq!(estimate.max_output_tokens, None);
+        assert_eq!(estimate.worst_case_cost, None);
+
+        // Only admins can estimate
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let user_headers = add_auth_headers(&user);
+        app.post(&format!("/admin/api/v1/models/{}/estimate-cost", model.id))
+            .add_header(&user_headers[0].0, &user_headers[0].1)
+            .add_header(&user_headers[1].0, &user_headers[1].1)
+            .json(&json!({ "messages": messages }))
+            .await
+            .assert_status_forbidden();
+    }
 }
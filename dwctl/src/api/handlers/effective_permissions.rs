@@ -0,0 +1,264 @@
+//! Self-service view of what the current user can do.
+//!
+//! Brings together the user's roles, the models their API keys can reach, their
+//! credit balance and the limits their keys get, so a client doesn't have to
+//! find out by trial and error. Model access and limits are resolved the way
+//! the onwards config sync resolves them for the user's keys.
+
+use axum::{Json, extract::State};
+use rust_decimal::prelude::ToPrimitive;
+use sqlx_pool_router::PoolProvider;
+
+use crate::AppState;
+use crate::api::models::effective_permissions::{AccessibleModel, EffectivePermissionsResponse, EffectiveRateLimits, RateLimitSource};
+use crate::api::models::users::CurrentUser;
+use crate::auth::permissions;
+use crate::config::RateLimitTiersConfig;
+use crate::db::handlers::{Credits, Deployments, RateLimits, Repository, deployments::DeploymentFilter};
+use crate::db::models::api_keys::ApiKeyPurpose;
+use crate::db::models::deployments::ModelStatus;
+use crate::db::models::rate_limits::OwnerRateLimitsDBResponse;
+use crate::errors::{Error, Result};
+use crate::types::{Operation, Resource};
+
+/// Key purposes that can call models, with their names in traffic rules
+const INFERENCE_PURPOSES: [(ApiKeyPurpose, &str); 3] = [
+    (ApiKeyPurpose::Realtime, "realtime"),
+    (ApiKeyPurpose::Batch, "batch"),
+    (ApiKeyPurpose::Playground, "playground"),
+];
+
+/// Resolve the limits for a key without its own rate limit: user override,
+/// then the highest group default, then the tier. Concurrency has no tier.
+fn resolve_rate_limits(owner: &OwnerRateLimitsDBResponse, tiers: &RateLimitTiersConfig) -> EffectiveRateLimits {
+    let tier = if owner.verified { tiers.verified } else { tiers.unverified };
+    let (requests_per_second, burst_size, rate_source) = match (owner.user_requests_per_second, owner.group_requests_per_second, tier) {
+        (Some(rps), _, _) => (Some(rps), owner.user_burst_size, Some(RateLimitSource::UserOverride)),
+        (None, Some(rps), _) => (Some(rps), owner.group_burst_size, Some(RateLimitSource::GroupDefault)),
+        (None, None, Some(tier)) => (Some(tier.requests_per_second), tier.burst_size, Some(RateLimitSource::Tier)),
+        (None, None, None) => (None, None, None),
+    };
+    let (concurrency_limit, concurrency_source) = match (owner.user_concurrency_limit, owner.group_concurrency_limit) {
+        (Some(limit), _) => (Some(limit), Some(RateLimitSource::UserOverride)),
+        (None, Some(limit)) => (Some(limit), Some(RateLimitSource::GroupDefault)),
+        (None, None) => (None, None),
+    };
+    EffectiveRateLimits {
+        requests_per_second,
+        burst_size,
+        concurrency_limit,
+        rate_source,
+        concurrency_source,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/me/permissions",
+    tag = "users",
+    summary = "Get the current user's effective permissions",
+    description = "The current user's roles, the models their API keys can call (with the key purposes \
+                   allowed on each), their credit balance and the limits applied to their keys. Works \
+                   with any authentication method.",
+    responses(
+        (status = 200, description = "Effective permissions", body = EffectivePermissionsResponse),
+        (status = 401, description = "Unauthorized"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_effective_permissions<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    current_user: CurrentUser,
+) -> Result<Json<EffectivePermissionsResponse>> {
+    let mut conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
+
+    let filter = DeploymentFilter::new(0, i64::MAX)
+        .with_accessible_to(current_user.id)
+        .with_approved_only()
+        .with_statuses(vec![ModelStatus::Active])
+        .with_deleted(false);
+    let mut deployments_repo = Deployments::new(&mut conn);
+    let deployments: Vec<_> = deployments_repo
+        .list(&filter)
+        .await?
+        .into_iter()
+        .filter(|deployment| deployment.active)
+        .collect();
+    let ids: Vec<_> = deployments.iter().map(|deployment| deployment.id).collect();
+    let rules = deployments_repo.get_traffic_rules_bulk(&ids).await?;
+
+    let mut models: Vec<AccessibleModel> = deployments
+        .into_iter()
+        .map(|deployment| {
+            let denied: Vec<&str> = rules
+                .get(&deployment.id)
+                .into_iter()
+                .flatten()
+                .filter(|rule| rule.action == "deny")
+                .map(|rule| rule.api_key_purpose.as_str())
+                .collect();
+            let purposes = INFERENCE_PURPOSES
+                .iter()
+                .filter(|(_, name)| !denied.contains(name))
+                .map(|(purpose, _)| purpose.clone())
+                .collect();
+            AccessibleModel {
+                id: deployment.id,
+                alias: deployment.alias,
+                model_type: deployment.model_type,
+                purposes,
+            }
+        })
+        .collect();
+    models.sort_by(|a, b| a.alias.cmp(&b.alias));
+
+    let credit_balance = if permissions::has_permission(&current_user, Resource::Credits, Operation::ReadOwn)
+        || permissions::has_permission(&current_user, Resource::Credits, Operation::ReadAll)
+    {
+        let balance = Credits::new(&mut conn).get_user_balance(current_user.id).await?;
+        Some(balance.to_f64().unwrap_or_default())
+    } else {
+        None
+    };
+
+    let owner = RateLimits::new(&mut conn).get_for_owner(current_user.id).await?.unwrap_or_default();
+    let rate_limits = resolve_rate_limits(&owner, &state.current_config().auth.rate_limits);
+
+    Ok(Json(EffectivePermissionsResponse {
+        user_id: current_user.id,
+        is_admin: current_user.is_admin,
+        roles: current_user.roles,
+        models,
+        credit_balance,
+        rate_limits,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::models::users::Role;
+    use crate::config::RateLimitTierConfig;
+    use crate::test::utils::{
+        add_auth_headers, add_deployment_to_group, add_user_to_group, create_test_admin_user, create_test_api_key_for_user,
+        create_test_app, create_test_deployment, create_test_group, create_test_user,
+    };
+    use sqlx::PgPool;
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_models_match_group_derived_access(pool: PgPool) {
+        let (app, _) = create_test_app(pool.clone(), false).await;
+        let admin = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let group = create_test_group(&pool).await;
+        add_user_to_group(&pool, user.id, group.id).await;
+
+        let grouped = create_test_deployment(&pool, admin.id, "grouped-model", "grouped").await;
+        add_deployment_to_group(&pool, grouped.id, group.id, admin.id).await;
+        sqlx::query!(
+            "INSERT INTO model_traffic_rules (deployed_model_id, api_key_purpose, action) VALUES ($1, 'batch', 'deny')",
+            grouped.id
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let public = create_test_deployment(&pool, admin.id, "public-model", "public").await;
+        sqlx::query!("UPDATE deployed_models SET allow_public = TRUE WHERE id = $1", public.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // In the user's group, but not usable until an access request is approved
+        let gated = create_test_deployment(&pool, admin.id, "gated-model", "gated").await;
+        add_deployment_to_group(&pool, gated.id, group.id, admin.id).await;
+        sqlx::query!("UPDATE deployed_models SET require_approval = TRUE WHERE id = $1", gated.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // Not in any of the user's groups
+        let other_group = create_test_group(&pool).await;
+        let ungrouped = create_test_deployment(&pool, admin.id, "ungrouped-model", "ungrouped").await;
+        add_deployment_to_group(&pool, ungrouped.id, other_group.id, admin.id).await;
+
+        let mut request = app.get("/admin/api/v1/me/permissions");
+        for (key, value) in add_auth_headers(&user) {
+            request = request.add_header(&key, &value);
+        }
+        let response = request.await;
+        response.assert_status_ok();
+        let permissions: EffectivePermissionsResponse = response.json();
+
+        assert_eq!(permissions.user_id, user.id);
+        assert_eq!(permissions.roles, vec![Role::StandardUser]);
+        let aliases: Vec<_> = permissions.models.iter().map(|model| model.alias.as_str()).collect();
+        assert_eq!(aliases, vec!["grouped", "public"]);
+        assert_eq!(
+            permissions.models[0].purposes,
+            vec![ApiKeyPurpose::Realtime, ApiKeyPurpose::Playground]
+        );
+        assert_eq!(
+            permissions.models[1].purposes,
+            vec![ApiKeyPurpose::Realtime, ApiKeyPurpose::Batch, ApiKeyPurpose::Playground]
+        );
+
+        // An API key sees the same view as the other auth methods
+        let key = create_test_api_key_for_user(&pool, user.id).await;
+        sqlx::query!("UPDATE api_keys SET purpose = 'platform' WHERE id = $1", key.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let response = app
+            .get("/admin/api/v1/me/permissions")
+            .add_header("authorization", &format!("Bearer {}", key.secret))
+            .await;
+        response.assert_status_ok();
+        let via_key: EffectivePermissionsResponse = response.json();
+        let key_aliases: Vec<_> = via_key.models.iter().map(|model| model.alias.as_str()).collect();
+        assert_eq!(key_aliases, aliases);
+    }
+
+    #[test]
+    fn test_rate_limit_precedence() {
+        let tiers = RateLimitTiersConfig {
+            verified: Some(RateLimitTierConfig {
+                requests_per_second: 50.0,
+                burst_size: Some(100),
+            }),
+            unverified: Some(RateLimitTierConfig {
+                requests_per_second: 1.0,
+                burst_size: None,
+            }),
+        };
+
+        let limits = resolve_rate_limits(&OwnerRateLimitsDBResponse::default(), &tiers);
+        assert_eq!(limits.requests_per_second, Some(1.0));
+        assert_eq!(limits.rate_source, Some(RateLimitSource::Tier));
+        assert_eq!(limits.concurrency_limit, None);
+
+        let group = OwnerRateLimitsDBResponse {
+            verified: true,
+            group_requests_per_second: Some(10.0),
+            group_burst_size: Some(20),
+            group_concurrency_limit: Some(4),
+            ..Default::default()
+        };
+        let limits = resolve_rate_limits(&group, &tiers);
+        assert_eq!((limits.requests_per_second, limits.burst_size), (Some(10.0), Some(20)));
+        assert_eq!(limits.rate_source, Some(RateLimitSource::GroupDefault));
+        assert_eq!(limits.concurrency_source, Some(RateLimitSource::GroupDefault));
+
+        let user = OwnerRateLimitsDBResponse {
+            user_requests_per_second: Some(5.0),
+            user_concurrency_limit: Some(2),
+            ..group
+        };
+        let limits = resolve_rate_limits(&user, &tiers);
+        assert_eq!((limits.requests_per_second, limits.burst_size), (Some(5.0), None));
+        assert_eq!(limits.rate_source, Some(RateLimitSource::UserOverride));
+        assert_eq!(limits.concurrency_limit, Some(2));
+    }
+}
@@ -0,0 +1,81 @@
+//! HTTP handler for the configuration change event stream.
+
+use axum::{
+    extract::State,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::stream::{self, Stream};
+use sqlx_pool_router::PoolProvider;
+
+use crate::{
+    AppState,
+    auth::permissions::{RequiresPermission, operation, resource},
+    errors::{Error, Result},
+    sync::config_events,
+};
+
+/// Stream configuration change events
+///
+/// Server-sent events describing changes to deployments and endpoints. The first
+/// event is a `snapshot` of the current deployments and endpoints. It is followed by
+/// `deployment.created`, `deployment.updated`, `deployment.deleted`, `endpoint.created`,
+/// `endpoint.updated`, `endpoint.deleted` and `endpoint.health_changed` events as
+/// changes happen. Each event's data is JSON.
+///
+/// The stream may close, e.g. when the server restarts or the client falls behind.
+/// Reconnect to get a fresh snapshot; changes made while disconnected are not
+/// replayed. Each replica serves a limited number of streams at once.
+#[utoipa::path(
+    get,
+    path = "/events",
+    tag = "events",
+    summary = "Stream configuration change events",
+    responses(
+        (status = 200, description = "Server-sent event stream", content_type = "text/event-stream"),
+        (status = 403, description = "Insufficient permissions"),
+        (status = 429, description = "Too many event streams are open"),
+    ),
+    security(
+        ("BearerAuth" = []),
+        ("CookieAuth" = []),
+        ("X-Doubleword-User" = [])
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn stream_config_events<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    _: RequiresPermission<resource::System, operation::ReadAll>,
+) -> Result<Sse<impl Stream<Item = std::result::Result<Event, axum::Error>>>> {
+    // LISTEN must be on the primary: notifications aren't replicated.
+    let events = state.config_events.subscribe(state.db.write()).await.map_err(|e| match e {
+        config_events::SubscribeError::TooManySubscribers => Error::TooManyRequests { message: e.to_string() },
+        config_events::SubscribeError::Database(e) => Error::Database(e.into()),
+    })?;
+    let stream = stream::unfold(events, |mut events| async move {
+        let event = events.recv().await?;
+        Some((event.to_sse_event(), events))
+    });
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::models::users::Role;
+    use crate::test::utils::{add_auth_headers, create_test_app, create_test_user};
+    use sqlx::PgPool;
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_events_requires_admin(pool: PgPool) {
+        let (server, _bg_services) = create_test_app(pool.clone(), false).await;
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let user_headers = add_auth_headers(&user);
+
+        server
+            .get("/admin/api/v1/events")
+            .add_header(&user_headers[0].0, &user_headers[0].1)
+            .add_header(&user_headers[1].0, &user_headers[1].1)
+            .await
+            .assert_status_forbidden();
+    }
+}
@@ -8,8 +8,8 @@
 use sqlx_pool_router::PoolProvider;
 
 use crate::api::models::files::{
-    FileContentQuery, FileCostEstimate, FileCostEstimateQuery, FileDeleteResponse, FileListResponse, FileResponse, ListFilesQuery,
-    ListObject, ObjectType, Purpose,
+    FileContentQuery, FileCostEstimate, FileCostEstimateQuery, FileDeleteResponse, FileListResponse, FileResponse, FileValidationMode,
+    FileValidationReport, LineValidationError, ListFilesQuery, ListObject, ObjectType, Purpose, UploadFileQuery,
 };
 use crate::api::models::users::CurrentUser;
 use crate::auth::permissions::{RequiresPermission, can_read_all_resources, operation, resource};
@@ -280,6 +280,8 @@ struct FileStreamConfig {
     max_request_body_size: u64,
     /// Channel buffer size for streaming
     buffer_size: usize,
+    /// Whether invalid lines reject the file or are skipped and reported
+    validation_mode: FileValidationMode,
     /// Optional image normaliser. When `Some`, each parsed template's body is
     /// walked for `image_url` fields containing HTTP(S) URLs (per the user's
     /// mode); each is ingested into the content store and replaced with an
@@ -308,6 +310,7 @@ impl std::fmt::Debug for FileStreamConfig {
             .field("max_requests_per_file", &self.max_requests_per_file)
             .field("max_request_body_size", &self.max_request_body_size)
             .field("buffer_size", &self.buffer_size)
+            .field("validation_mode", &self.validation_mode)
             .field("normalizer_enabled", &self.normalizer.is_some())
             .field("normalizer_mode", &self.normalizer_mode)
             .field("image_access_enabled", &self.access_pool.is_some())
@@ -502,6 +505,25 @@ fn map_batch_normalize_error(e: BatchNormalizeError, line: u64) -> FileUploadErr
 }
 
 impl FileUploadError {
+    /// The line and reason, for errors caused by a single bad line that a
+    /// lenient upload can skip. `None` for errors about the whole upload or
+    /// the service (size limits, invalid UTF-8, image store outages).
+    fn line_error(&self) -> Option<LineValidationError> {
+        let (line, message) = match self {
+            FileUploadError::InvalidJson { line, error } => (*line, format!("Invalid JSON: {error}")),
+            FileUploadError::ModelAccessDenied { model, line } => (
+                *line,
+                format!("Model '{model}' has not been configured or is not available to user"),
+            ),
+            FileUploadError::ValidationError { line, message } | FileUploadError::UnprocessableValidationError { line, message } => {
+                (*line, message.clone())
+            }
+            FileUploadError::ImageUnfetchable { line, message } => (*line, format!("referenced image could not be retrieved ({message})")),
+            _ => return None,
+        };
+        Some(LineValidationError { line, message })
+    }
+
     /// Convert to the appropriate HTTP error type
     fn into_http_error(self) -> Error {
         match self {
@@ -622,11 +644,13 @@ fn is_multer_length_limit(err: &multer::Error) -> bool {
     }
 }
 
-/// Result from create_file_stream: the stream and an error slot.
-/// If the stream aborts, check the error slot for the typed error.
+/// Result from create_file_stream: the stream, an error slot and a report slot.
+/// If the stream aborts, check the error slot for the typed error. Once the
+/// stream completes, the report slot holds the per-line validation report.
 type FileStreamResult = (
     Pin<Box<dyn Stream<Item = fusillade::FileStreamItem> + Send>>,
     Arc<Mutex<Option<FileUploadError>>>,
+    Arc<Mutex<Option<FileValidationReport>>>,
 );
 
 fn resolve_upload_stream_result(
@@ -682,6 +706,62 @@ fn map_request_validation_error(error: &Error, line: u64) -> FileUploadError {
     }
 }
 
+/// Most per-line errors listed in a lenient upload's report; later ones are
+/// still counted in `invalid_lines`.
+const MAX_REPORTED_LINE_ERRORS: usize = 1000;
+
+/// Parse, validate and normalise one non-blank line of an uploaded file into
+/// a request template. `line` is the 1-based line number used in errors.
+async fn parse_file_line(
+    trimmed: &str,
+    line: u64,
+    req_ctx: &FileRequestContext,
+    config: &FileStreamConfig,
+) -> std::result::Result<fusillade::RequestTemplateInput, FileUploadError> {
+    // Parse JSON line as OpenAI Batch format, then transform to internal
+    let openai_req = serde_json::from_str::<OpenAIBatchRequest>(trimmed).map_err(|e| FileUploadError::InvalidJson {
+        line,
+        error: e.to_string(),
+    })?;
+
+    // Transform to internal format (includes model access validation)
+    let mut template = openai_req
+        .to_internal(
+            &req_ctx.endpoint,
+            req_ctx.api_key.clone(),
+            &req_ctx.accessible_models,
+            &req_ctx.allowed_url_paths,
+        )
+        .map_err(|e| map_request_validation_error(&e, line))?;
+
+    // Normalise image URLs in the per-template body before the size cap
+    // check, since substitution replaces (potentially large) HTTP URLs with
+    // short opaque tokens — bodies usually shrink.
+    normalize_template_body_in_place(
+        config.normalizer.as_ref(),
+        config.normalizer_mode,
+        &mut template,
+        config.access_pool.as_ref(),
+        config.access_attribution,
+    )
+    .await
+    .map_err(|e| map_batch_normalize_error(e, line))?;
+
+    // Check per-request body size limit (0 = unlimited)
+    if config.max_request_body_size > 0 && template.body.len() as u64 > config.max_request_body_size {
+        return Err(FileUploadError::ValidationError {
+            line,
+            message: format!(
+                "Request body is {} bytes, which exceeds the maximum allowed size of {} bytes",
+                template.body.len(),
+                config.max_request_body_size
+            ),
+        });
+    }
+
+    Ok(template)
+}
+
 /// Helper function to create a stream of FileStreamItem from multipart upload
 /// This handles the entire multipart parsing inside the stream
 #[tracing::instrument(skip(multipart, req_ctx), fields(config.max_file_size, config.max_requests_per_file, uploaded_by = ?uploaded_by, endpoint = %req_ctx.endpoint, config.buffer_size))]
@@ -692,26 +772,29 @@ fn create_file_stream(
     req_ctx: FileRequestContext,
     api_key_id: Option<uuid::Uuid>,
 ) -> FileStreamResult {
-    let FileRequestContext {
-        endpoint,
-        api_key,
-        accessible_models,
-        allowed_url_paths,
-    } = req_ctx;
-    let normalizer = config.normalizer.clone();
-    let normalizer_mode = config.normalizer_mode;
-    let access_pool = config.access_pool.clone();
-    let access_attribution = config.access_attribution;
     let (tx, rx) = mpsc::channel(config.buffer_size);
     // std::sync::Mutex is appropriate here because:
     // 1. Lock is held only briefly (no await points while locked)
     // 2. No contention (only writer is spawned task, only reader is error handler)
     let error_slot: Arc<Mutex<Option<FileUploadError>>> = Arc::new(Mutex::new(None));
     let error_slot_clone = Arc::clone(&error_slot);
+    let report_slot: Arc<Mutex<Option<FileValidationReport>>> = Arc::new(Mutex::new(None));
+    let report_slot_clone = Arc::clone(&report_slot);
 
     tokio::spawn(async move {
         let mut total_size = 0u64;
+        // Templates accepted so far
         let mut line_count = 0u64;
+        // Physical lines read so far (including blank and skipped ones), for error messages
+        let mut line_number = 0u64;
+        let mut report = FileValidationReport {
+            mode: config.validation_mode,
+            valid_lines: 0,
+            invalid_lines: 0,
+            errors: Vec::new(),
+        };
+        // First skipped line's error, reported if no line is valid
+        let mut first_line_error: Option<FileUploadError> = None;
         let mut incomplete_line = String::with_capacity(1024);
         let mut incomplete_utf8_bytes = Vec::with_capacity(4);
         let mut metadata = fusillade::FileMetadata {
@@ -734,6 +817,33 @@ fn create_file_stream(
             }};
         }
 
+        /// Send a parsed template; in lenient mode, record and skip a bad
+        /// line instead of aborting
+        macro_rules! accept_line {
+            ($result:expr) => {{
+                match $result {
+                    Ok(template) => {
+                        line_count += 1;
+                        if tx.send(fusillade::FileStreamItem::Template(template)).await.is_err() {
+                            return;
+                        }
+                    }
+                    Err(upload_err) => match upload_err.line_error() {
+                        Some(line_error) if config.validation_mode == FileValidationMode::Lenient => {
+                            report.invalid_lines += 1;
+                            if report.errors.len() < MAX_REPORTED_LINE_ERRORS {
+                                report.errors.push(line_error);
+                            }
+                            if first_line_error.is_none() {
+                                first_line_error = Some(upload_err);
+                            }
+                        }
+                        _ => abort!(upload_err),
+                    },
+                }
+            }};
+        }
+
         // Parse multipart fields
         loop {
             let field = match multipart.next_field().await {
@@ -833,12 +943,12 @@ fn create_file_stream(
                                             let byte_offset = (total_size - chunk_size) as i64 + valid_up_to as i64;
                                             tracing::error!(
                                                 "UTF-8 parsing error on/near line {}, byte offset {}",
-                                                line_count + 1,
+                                                line_number + 1,
                                                 byte_offset
                                             );
 
                                             abort!(FileUploadError::InvalidUtf8 {
-                                                line: line_count + 1,
+                                                line: line_number + 1,
                                                 byte_offset,
                                                 error: e.to_string(),
                                             });
@@ -864,6 +974,7 @@ fn create_file_stream(
                                 } else {
                                     format!("{}{}", incomplete_line, chunk_str)
                                 };
+                                incomplete_line.clear();
 
                                 let mut lines = text_to_process.lines().peekable();
                                 let ends_with_newline = chunk_str.ends_with('\n');
@@ -879,6 +990,7 @@ fn create_file_stream(
                                         break;
                                     }
 
+                                    line_number += 1;
                                     let trimmed = line.trim();
                                     if trimmed.is_empty() {
                                         continue;
@@ -892,63 +1004,7 @@ fn create_file_stream(
                                         });
                                     }
 
-                                    // Parse JSON line as OpenAI Batch format, then transform to internal
-                                    match serde_json::from_str::<OpenAIBatchRequest>(trimmed) {
-                                        Ok(openai_req) => {
-                                            // Transform to internal format (includes model access validation)
-                                            match openai_req.to_internal(&endpoint, api_key.clone(), &accessible_models, &allowed_url_paths)
-                                            {
-                                                Ok(mut template) => {
-                                                    // Normalise image URLs in the per-template body
-                                                    // before the size cap check, since substitution
-                                                    // replaces (potentially large) HTTP URLs with
-                                                    // short opaque tokens — bodies usually shrink.
-                                                    if let Err(e) = normalize_template_body_in_place(
-                                                        normalizer.as_ref(),
-                                                        normalizer_mode,
-                                                        &mut template,
-                                                        access_pool.as_ref(),
-                                                        access_attribution,
-                                                    )
-                                                    .await
-                                                    {
-                                                        abort!(map_batch_normalize_error(e, line_count + 1));
-                                                    }
-
-                                                    // Check per-request body size limit (0 = unlimited)
-                                                    if config.max_request_body_size > 0
-                                                        && template.body.len() as u64 > config.max_request_body_size
-                                                    {
-                                                        abort!(FileUploadError::ValidationError {
-                                                            line: line_count + 1,
-                                                            message: format!(
-                                                                "Request body is {} bytes, which exceeds the maximum allowed size of {} bytes",
-                                                                template.body.len(),
-                                                                config.max_request_body_size
-                                                            ),
-                                                        });
-                                                    }
-
-                                                    line_count += 1;
-                                                    incomplete_line.clear();
-                                                    if tx.send(fusillade::FileStreamItem::Template(template)).await.is_err() {
-                                                        return;
-                                                    }
-                                                }
-                                                Err(e) => {
-                                                    // Map the Error back to FileUploadError
-                                                    let upload_err = map_request_validation_error(&e, line_count + 1);
-                                                    abort!(upload_err);
-                                                }
-                                            }
-                                        }
-                                        Err(e) => {
-                                            abort!(FileUploadError::InvalidJson {
-                                                line: line_count + 1,
-                                                error: e.to_string(),
-                                            });
-                                        }
-                                    }
+                                    accept_line!(parse_file_line(trimmed, line_number, &req_ctx, &config).await);
                                 }
                             }
                             Ok(None) => {
@@ -976,6 +1032,7 @@ fn create_file_stream(
 
                     // Process any remaining incomplete line at end of file
                     if !incomplete_line.is_empty() {
+                        line_number += 1;
                         let trimmed = incomplete_line.trim();
                         if !trimmed.is_empty() {
                             // Check request count limit
@@ -986,61 +1043,16 @@ fn create_file_stream(
                                 });
                             }
 
-                            match serde_json::from_str::<OpenAIBatchRequest>(trimmed) {
-                                Ok(openai_req) => {
-                                    match openai_req.to_internal(&endpoint, api_key.clone(), &accessible_models, &allowed_url_paths) {
-                                        Ok(mut template) => {
-                                            // Normalise image URLs in the trailing line as well.
-                                            if let Err(e) = normalize_template_body_in_place(
-                                                normalizer.as_ref(),
-                                                normalizer_mode,
-                                                &mut template,
-                                                access_pool.as_ref(),
-                                                access_attribution,
-                                            )
-                                            .await
-                                            {
-                                                abort!(map_batch_normalize_error(e, line_count + 1));
-                                            }
-
-                                            // Check per-request body size limit (0 = unlimited)
-                                            if config.max_request_body_size > 0 && template.body.len() as u64 > config.max_request_body_size
-                                            {
-                                                abort!(FileUploadError::ValidationError {
-                                                    line: line_count + 1,
-                                                    message: format!(
-                                                        "Request body is {} bytes, which exceeds the maximum allowed size of {} bytes",
-                                                        template.body.len(),
-                                                        config.max_request_body_size
-                                                    ),
-                                                });
-                                            }
-
-                                            line_count += 1;
-                                            if tx.send(fusillade::FileStreamItem::Template(template)).await.is_err() {
-                                                return;
-                                            }
-                                        }
-                                        Err(e) => {
-                                            let upload_err = map_request_validation_error(&e, line_count + 1);
-                                            abort!(upload_err);
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    abort!(FileUploadError::InvalidJson {
-                                        line: line_count + 1,
-                                        error: e.to_string(),
-                                    });
-                                }
-                            }
+                            accept_line!(parse_file_line(trimmed, line_number, &req_ctx, &config).await);
                         }
                     }
 
-                    // Check if file is empty (no templates parsed)
+                    // Check if file is empty (no templates parsed). If every
+                    // line was skipped, report why the first one was rejected.
                     if line_count == 0 {
-                        abort!(FileUploadError::EmptyFile);
+                        abort!(first_line_error.take().unwrap_or(FileUploadError::EmptyFile));
                     }
+                    report.valid_lines = line_count;
 
                     metadata.size_bytes = match i64::try_from(total_size) {
                         Ok(size) => Some(size),
@@ -1064,11 +1076,16 @@ fn create_file_stream(
             abort!(FileUploadError::NoFile);
         }
 
+        match report_slot_clone.lock() {
+            Ok(mut guard) => *guard = Some(report),
+            Err(poisoned) => *poisoned.into_inner() = Some(report),
+        }
+
         // Send final metadata with all fields (including any that came after the file)
         let _ = tx.send(fusillade::FileStreamItem::Metadata(metadata.clone())).await;
     });
 
-    (Box::pin(ReceiverStream::new(rx)), error_slot)
+    (Box::pin(ReceiverStream::new(rx)), error_slot, report_slot)
 }
 
 #[utoipa::path(
@@ -1078,7 +1095,10 @@ fn create_file_stream(
     summary = "Upload file",
     description = "Upload a JSONL file for batch processing.
 
-Each line must be a valid JSON object containing `custom_id`, `method`, `url`, and `body` fields. The `model` field in the body must reference a model your API key has access to.",
+Each line must be a valid JSON object containing `custom_id`, `method`, `url`, and `body` fields. The `model` field in the body must reference a model your API key has access to.
+
+By default (`validation=strict`) the first invalid line rejects the whole file. With `validation=lenient`, invalid lines are skipped and listed in the response's `validation` report, and the file is created from the valid lines. A lenient upload with no valid lines is still rejected.",
+    params(UploadFileQuery),
     request_body(
         content_type = "multipart/form-data",
         description = "Multipart form with `file` (the JSONL file) and `purpose` (must be `batch`)."
@@ -1098,6 +1118,7 @@ Each line must be a valid JSON object containing `custom_id`, `method`, `url`, a
 pub async fn upload_file<P: PoolProvider>(
     State(state): State<AppState<P>>,
     current_user: RequiresPermission<resource::Files, operation::CreateOwn>,
+    Query(query): Query<UploadFileQuery>,
     request: axum::http::Request<axum::body::Body>,
 ) -> Result<(StatusCode, Json<FileResponse>)> {
     // Acquire upload permit (if limiter is configured)
@@ -1156,6 +1177,7 @@ pub async fn upload_file<P: PoolProvider>(
         max_requests_per_file: config.limits.files.max_requests_per_file,
         max_request_body_size: config.limits.requests.max_body_size,
         buffer_size: config.batches.files.upload_buffer_size,
+        validation_mode: query.validation.unwrap_or(config.batches.files.validation_mode),
         normalizer,
         normalizer_mode,
         access_pool: Some(state.db.write().clone()),
@@ -1188,6 +1210,7 @@ pub async fn upload_file<P: PoolProvider>(
     let mut deployments_repo = Deployments::new(&mut conn);
     let filter = DeploymentFilter::new(0, i64::MAX)
         .with_accessible_to(target_user_id)
+        .with_approved_only()
         .with_statuses(vec![ModelStatus::Active])
         .with_deleted(false);
     let accessible_deployments = deployments_repo.list(&filter).await.map_err(Error::Database)?;
@@ -1214,7 +1237,7 @@ pub async fn upload_file<P: PoolProvider>(
     drop(conn);
 
     // Create a stream that parses the multipart upload and yields FileStreamItems
-    let (file_stream, error_slot) = create_file_stream(
+    let (file_stream, error_slot, report_slot) = create_file_stream(
         multipart,
         stream_config,
         uploaded_by,
@@ -1254,6 +1277,13 @@ pub async fn upload_file<P: PoolProvider>(
 
     tracing::debug!("File {} uploaded successfully", created_file_id);
 
+    // Strict uploads keep the plain OpenAI response shape: every line was valid
+    let validation = match report_slot.lock() {
+        Ok(mut guard) => guard.take(),
+        Err(poisoned) => poisoned.into_inner().take(),
+    }
+    .filter(|report| report.mode == FileValidationMode::Lenient);
+
     // Build response using the fusillade file
     // We use the primary pool to avoid transaction or read lags if using replicas
     let file = state
@@ -1290,6 +1320,7 @@ pub async fn upload_file<P: PoolProvider>(
             context_type: None,
             source: file.source_connection_id.map(|_| "sync".to_string()),
             source_name: None, // Upload response — no connection lookup needed
+            validation,
         }),
     ))
 }
@@ -1525,6 +1556,7 @@ pub async fn list_files<P: PoolProvider>(
                 source_name: f
                     .source_connection_id
                     .and_then(|id| connection_info.get(&id).map(|(name, _)| name.clone())),
+                validation: None,
             }
         })
         .collect();
@@ -1659,6 +1691,7 @@ pub async fn get_file<P: PoolProvider>(
         } else {
             None
         },
+        validation: None,
     }))
 }
 
@@ -2235,6 +2268,80 @@ mod tests {
         assert!(missing_budget_limit.text().contains("max_completion_tokens is not set"));
     }
 
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_upload_validation_modes(pool: PgPool) {
+        use crate::api::models::files::FileValidationMode;
+
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let user = create_test_user_with_roles(&pool, vec![Role::StandardUser, Role::BatchAPIUser]).await;
+        let group = create_test_group(&pool).await;
+        add_user_to_group(&pool, user.id, group.id).await;
+        let deployment = create_test_deployment(&pool, user.id, "gpt-4-model", "gpt-4").await;
+        add_deployment_to_group(&pool, deployment.id, group.id, user.id).await;
+
+        // Lines 2 (bad JSON), 4 (inaccessible model) and 6 (bad custom_id) are
+        // invalid; line 5 is blank but still counts towards line numbers.
+        let jsonl_content = r#"{"custom_id":"request-1","method":"POST","url":"/v1/chat/completions","body":{"model":"gpt-4","messages":[]}}
+{"custom_id":"request-2",
+{"custom_id":"request-3","method":"POST","url":"/v1/chat/completions","body":{"model":"gpt-4","messages":[]}}
+{"custom_id":"request-4","method":"POST","url":"/v1/chat/completions","body":{"model":"no-such-model","messages":[]}}
+
+{"custom_id":"bad\nid","method":"POST","url":"/v1/chat/completions","body":{"model":"gpt-4","messages":[]}}
+{"custom_id":"request-7","method":"POST","url":"/v1/chat/completions","body":{"model":"gpt-4","messages":[]}}"#;
+
+        let upload = |validation: &'static str, content: &'static str| {
+            app.post(&format!("/ai/v1/files?validation={validation}"))
+                .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
+                .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+                .multipart(axum_test::multipart::MultipartForm::new().add_text("purpose", "batch").add_part(
+                    "file",
+                    axum_test::multipart::Part::bytes(content.as_bytes().to_vec()).file_name("mixed.jsonl"),
+                ))
+        };
+
+        // Strict (the default) rejects the file at the first bad line
+        let response = upload("strict", jsonl_content).await;
+        response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+        assert!(response.text().contains("line 2"));
+        let response = upload_batch_jsonl(&app, &user, jsonl_content).await;
+        response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+        // Lenient keeps the valid lines and reports the rest
+        let response = upload("lenient", jsonl_content).await;
+        response.assert_status(axum::http::StatusCode::CREATED);
+        let file: FileResponse = response.json();
+        let report = file.validation.expect("lenient uploads carry a validation report");
+        assert_eq!(report.mode, FileValidationMode::Lenient);
+        assert_eq!(report.valid_lines, 3);
+        assert_eq!(report.invalid_lines, 3);
+        assert_eq!(report.errors.iter().map(|e| e.line).collect::<Vec<_>>(), vec![2, 4, 6]);
+        assert!(report.errors[1].message.contains("no-such-model"));
+
+        let content = app
+            .get(&format!("/ai/v1/files/{}/content", file.id))
+            .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
+            .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+            .await;
+        content.assert_status_ok();
+        let custom_ids: Vec<String> = content
+            .text()
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<serde_json::Value>(line).unwrap()["custom_id"]
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(custom_ids, vec!["request-1", "request-3", "request-7"]);
+
+        // A lenient upload with nothing valid still fails, naming the first bad line
+        let response = upload("lenient", "not json\n{\"custom_id\":\"x\"}\n").await;
+        response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+        assert!(response.text().contains("line 1"));
+    }
+
     #[sqlx::test]
     #[test_log::test]
     async fn test_upload_and_download_file_content(pool: PgPool) {
@@ -3359,6 +3466,167 @@ mod tests {
         );
     }
 
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_batch_output_and_error_files_match_openai_format(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let user = create_test_user_with_roles(&pool, vec![Role::StandardUser, Role::BatchAPIUser]).await;
+        let headers = add_auth_headers(&user);
+        let group = create_test_group(&pool).await;
+        add_user_to_group(&pool, user.id, group.id).await;
+        let deployment = create_test_deployment(&pool, user.id, "gpt-4", "gpt-4").await;
+        add_deployment_to_group(&pool, deployment.id, group.id, user.id).await;
+
+        let jsonl_content = ["ok", "rejected", "timed-out"]
+            .iter()
+            .map(|custom_id| {
+                format!(
+                    r#"{{"custom_id":"{custom_id}","method":"POST","url":"/v1/chat/completions","body":{{"model":"gpt-4","messages":[{{"role":"user","content":"Test"}}]}}}}"#
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let file_part = axum_test::multipart::Part::bytes(jsonl_content.into_bytes()).file_name("test.jsonl");
+        let file: FileResponse = app
+            .post("/ai/v1/files")
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .multipart(
+                axum_test::multipart::MultipartForm::new()
+                    .add_text("purpose", "batch")
+                    .add_part("file", file_part),
+            )
+            .await
+            .json();
+        let batch: serde_json::Value = app
+            .post("/ai/v1/batches")
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .json(&serde_json::json!({
+                "input_file_id": file.id,
+                "endpoint": "/v1/chat/completions",
+                "completion_window": "24h"
+            }))
+            .await
+            .json();
+        let batch_id = batch["id"].as_str().expect("Should have id");
+        let batch_uuid = Uuid::parse_str(batch_id.strip_prefix("batch_").unwrap_or(batch_id)).expect("Valid batch UUID");
+
+        for attempt in 0..200 {
+            let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM fusillade.requests WHERE batch_id = $1")
+                .bind(batch_uuid)
+                .fetch_one(&pool)
+                .await
+                .expect("Failed to count requests");
+            if count == 3 {
+                break;
+            }
+            assert!(
+                attempt < 199,
+                "Timed out waiting for requests to be populated for batch {batch_uuid}"
+            );
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+
+        // One success, one upstream error response and one request that never got a response
+        sqlx::query(
+            r#"
+            UPDATE fusillade.requests
+            SET state = 'completed', response_status = 200, response_body = '{"id":"chatcmpl-1","choices":[]}', completed_at = NOW()
+            WHERE batch_id = $1 AND custom_id = 'ok'
+            "#,
+        )
+        .bind(batch_uuid)
+        .execute(&pool)
+        .await
+        .expect("Failed to complete request");
+        for (custom_id, error) in [
+            (
+                "rejected",
+                serde_json::json!({
+                    "type": "NonRetriableHttpStatus",
+                    "details": { "status": 400, "body": r#"{"error":{"message":"Invalid messages"}}"# }
+                }),
+            ),
+            (
+                "timed-out",
+                serde_json::json!({ "type": "Timeout", "details": { "error": "deadline exceeded" } }),
+            ),
+        ] {
+            sqlx::query(
+                "UPDATE fusillade.requests SET state = 'failed', error = $3, failed_at = NOW() WHERE batch_id = $1 AND custom_id = $2",
+            )
+            .bind(batch_uuid)
+            .bind(custom_id)
+            .bind(error.to_string())
+            .execute(&pool)
+            .await
+            .expect("Failed to fail request");
+        }
+
+        let batch: serde_json::Value = app
+            .get(&format!("/ai/v1/batches/{batch_id}"))
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .await
+            .json();
+        let download = |file_id: String| {
+            let app = &app;
+            let headers = &headers;
+            async move {
+                let response = app
+                    .get(&format!("/ai/v1/files/{file_id}/content"))
+                    .add_header(&headers[0].0, &headers[0].1)
+                    .add_header(&headers[1].0, &headers[1].1)
+                    .await;
+                response.assert_status_ok();
+                response
+                    .text()
+                    .lines()
+                    .map(|line| serde_json::from_str::<serde_json::Value>(line).expect("Each line is JSON"))
+                    .collect::<Vec<_>>()
+            }
+        };
+        let keys = |line: &serde_json::Value| {
+            let mut keys = line.as_object().unwrap().keys().cloned().collect::<Vec<_>>();
+            keys.sort();
+            keys
+        };
+
+        let output = download(batch["output_file_id"].as_str().unwrap().to_string()).await;
+        assert_eq!(output.len(), 1);
+        assert_eq!(keys(&output[0]), ["custom_id", "error", "id", "response"]);
+        assert!(output[0]["id"].as_str().unwrap().starts_with("batch_req_"));
+        assert_eq!(output[0]["custom_id"], "ok");
+        assert_eq!(keys(&output[0]["response"]), ["body", "request_id", "status_code"]);
+        assert_eq!(output[0]["response"]["status_code"], 200);
+        assert!(output[0]["response"]["request_id"].is_string());
+        assert_eq!(output[0]["response"]["body"]["id"], "chatcmpl-1");
+        assert!(output[0]["error"].is_null());
+
+        let mut errors = download(batch["error_file_id"].as_str().unwrap().to_string()).await;
+        errors.sort_by_key(|line| line["custom_id"].as_str().unwrap().to_string());
+        assert_eq!(errors.len(), 2);
+        for line in &errors {
+            assert_eq!(keys(line), ["custom_id", "error", "id", "response"]);
+            assert!(line["id"].as_str().unwrap().starts_with("batch_req_"));
+        }
+        // An upstream error response is reported as the response, as OpenAI does
+        let rejected = &errors[0];
+        assert_eq!(rejected["custom_id"], "rejected");
+        assert_eq!(rejected["response"]["status_code"], 400);
+        assert!(rejected["response"]["request_id"].is_string());
+        assert_eq!(rejected["response"]["body"]["error"]["message"], "Invalid messages");
+        assert!(rejected["error"].is_null());
+        // A request that never got a response has a null response and an error
+        let timed_out = &errors[1];
+        assert_eq!(timed_out["custom_id"], "timed-out");
+        assert!(timed_out["response"].is_null());
+        assert_eq!(keys(&timed_out["error"]), ["code", "message"]);
+        assert_eq!(timed_out["error"]["code"], "timeout");
+        assert_eq!(timed_out["error"]["message"], "Request timed out: deadline exceeded");
+    }
+
     #[tokio::test]
     async fn test_upload_rate_limiting_rejects_when_queue_full() {
         use crate::config::FileLimitsConfig;
@@ -0,0 +1,270 @@
+//! Admin endpoints for per-group spending limits. Thin wrappers over
+//! [`crate::db::handlers::GroupSpendingLimits`].
+//!
+//! Every billed request of a group's members counts towards the group's
+//! window. Once the window's spend reaches the limit, the onwards config sync
+//! withdraws every member's API keys from priced models until the window
+//! resets or the limit is raised or removed.
+
+use axum::Json;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use rust_decimal::Decimal;
+use sqlx::PgConnection;
+use sqlx_pool_router::PoolProvider;
+
+use crate::AppState;
+use crate::api::models::group_spending_limits::{GroupSpendingLimitUpdate, GroupUsageResponse};
+use crate::auth::permissions::{RequiresPermission, operation, resource};
+use crate::db::handlers::{GroupSpendingLimits, Groups, Repository};
+use crate::db::models::group_spending_limits::GroupSpendingLimitDBRequest;
+use crate::errors::{Error, Result};
+use crate::types::GroupId;
+
+/// Valid window reset periods (calendar-aligned in UTC; see migration 150).
+const VALID_LIMIT_INTERVALS: [&str; 3] = ["daily", "weekly", "monthly"];
+
+/// 404 unless the group exists
+async fn ensure_group_exists(conn: &mut PgConnection, id: GroupId) -> Result<()> {
+    match Groups::new(conn).get_by_id(id).await? {
+        Some(_) => Ok(()),
+        None => Err(Error::NotFound {
+            resource: "Group".to_string(),
+            id: id.to_string(),
+        }),
+    }
+}
+
+/// Mirror the table's CHECK constraints with a clean 400. The Everyone group
+/// can't carry a limit: it would cut off every user on the platform.
+fn validate(group_id: GroupId, update: &GroupSpendingLimitUpdate) -> Result<()> {
+    if group_id.is_nil() {
+        return Err(Error::BadRequest {
+            message: "The Everyone group cannot have a spending limit".to_string(),
+        });
+    }
+    if update.spending_limit <= Decimal::ZERO {
+        return Err(Error::BadRequest {
+            message: "spending_limit must be greater than zero".to_string(),
+        });
+    }
+    if let Some(interval) = update.spending_limit_interval.as_deref()
+        && !VALID_LIMIT_INTERVALS.contains(&interval)
+    {
+        return Err(Error::BadRequest {
+            message: format!("spending_limit_interval must be one of {VALID_LIMIT_INTERVALS:?} (calendar-aligned windows)"),
+        });
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/groups/{group_id}/usage",
+    tag = "groups",
+    summary = "Get a group's spend against its spending limit",
+    params(("group_id" = uuid::Uuid, Path, description = "Group ID")),
+    responses(
+        (status = 200, description = "The group's limit and current window spend", body = GroupUsageResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Group not found, or no spending limit set"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_group_usage<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(group_id): Path<GroupId>,
+    _: RequiresPermission<resource::Groups, operation::ReadAll>,
+) -> Result<Json<GroupUsageResponse>> {
+    let mut conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    ensure_group_exists(&mut conn, group_id).await?;
+    match GroupSpendingLimits::new(&mut conn).get(group_id).await? {
+        Some(limit) => Ok(Json(limit.into())),
+        None => Err(Error::NotFound {
+            resource: "Group spending limit".to_string(),
+            id: group_id.to_string(),
+        }),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/groups/{group_id}/spending-limit",
+    tag = "groups",
+    summary = "Set a group's spending limit",
+    description = "Cap what the group's members can spend together per window. Once the window's spend \
+                   reaches the limit, every member's API keys lose access to priced models until the window \
+                   resets. A new limit or a new interval starts a fresh window; changing only the amount \
+                   keeps the spend already counted.",
+    params(("group_id" = uuid::Uuid, Path, description = "Group ID")),
+    request_body = GroupSpendingLimitUpdate,
+    responses(
+        (status = 200, description = "Limit set", body = GroupUsageResponse),
+        (status = 400, description = "Invalid limit"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Group not found"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn set_group_spending_limit<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(group_id): Path<GroupId>,
+    _: RequiresPermission<resource::Groups, operation::UpdateAll>,
+    Json(update): Json<GroupSpendingLimitUpdate>,
+) -> Result<Json<GroupUsageResponse>> {
+    validate(group_id, &update)?;
+    let mut tx = state.db.write().begin().await.map_err(|e| Error::Database(e.into()))?;
+    ensure_group_exists(&mut tx, group_id).await?;
+    let limit = GroupSpendingLimits::new(&mut tx)
+        .set(group_id, &GroupSpendingLimitDBRequest::from(update))
+        .await?;
+    tx.commit().await.map_err(|e| Error::Database(e.into()))?;
+    Ok(Json(limit.into()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/groups/{group_id}/spending-limit",
+    tag = "groups",
+    summary = "Remove a group's spending limit",
+    params(("group_id" = uuid::Uuid, Path, description = "Group ID")),
+    responses(
+        (status = 204, description = "Limit removed (or none was set)"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Group not found"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn delete_group_spending_limit<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(group_id): Path<GroupId>,
+    _: RequiresPermission<resource::Groups, operation::UpdateAll>,
+) -> Result<StatusCode> {
+    let mut tx = state.db.write().begin().await.map_err(|e| Error::Database(e.into()))?;
+    ensure_group_exists(&mut tx, group_id).await?;
+    GroupSpendingLimits::new(&mut tx).delete(group_id).await?;
+    tx.commit().await.map_err(|e| Error::Database(e.into()))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::models::users::Role;
+    use crate::test::utils::{add_auth_headers, create_test_admin_user, create_test_app, create_test_group, create_test_user};
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_group_spending_limit_round_trips(pool: PgPool) {
+        let (app, _) = create_test_app(pool.clone(), false).await;
+        let admin = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let group = create_test_group(&pool).await;
+        let limit_path = format!("/admin/api/v1/groups/{}/spending-limit", group.id);
+        let usage_path = format!("/admin/api/v1/groups/{}/usage", group.id);
+
+        let response = app
+            .get(&usage_path)
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .await;
+        response.assert_status_not_found();
+
+        // Members can't lift their own group's limit
+        let response = app
+            .put(&limit_path)
+            .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
+            .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+            .json(&json!({"spending_limit": "100"}))
+            .await;
+        response.assert_status_forbidden();
+
+        for body in [
+            json!({"spending_limit": "0"}),
+            json!({"spending_limit": "100", "spending_limit_interval": "hourly"}),
+        ] {
+            let response = app
+                .put(&limit_path)
+                .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+                .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+                .json(&body)
+                .await;
+            response.assert_status_bad_request();
+        }
+
+        let response = app
+            .put(&limit_path)
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&json!({"spending_limit": "100", "spending_limit_interval": "monthly"}))
+            .await;
+        response.assert_status_ok();
+        let usage: GroupUsageResponse = response.json();
+        assert_eq!(usage.spending_limit, Decimal::from(100));
+        assert_eq!(usage.spent, Decimal::ZERO);
+        assert_eq!(usage.remaining, Decimal::from(100));
+        assert!(!usage.limit_reached);
+        assert!(usage.resets_at.is_some(), "windowed limit advertises its next reset");
+
+        sqlx::query("UPDATE group_spend_checkpoints SET window_spend = 30 WHERE group_id = $1")
+            .bind(group.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // Changing only the amount keeps the spend already counted
+        let response = app
+            .put(&limit_path)
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&json!({"spending_limit": "20", "spending_limit_interval": "monthly"}))
+            .await;
+        response.assert_status_ok();
+        let usage: GroupUsageResponse = response.json();
+        assert_eq!(usage.spent, Decimal::from(30));
+        assert_eq!(usage.remaining, Decimal::ZERO);
+        assert!(usage.limit_reached);
+
+        // A new interval starts a fresh window
+        let response = app
+            .put(&limit_path)
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&json!({"spending_limit": "20"}))
+            .await;
+        response.assert_status_ok();
+        let usage: GroupUsageResponse = response.json();
+        assert_eq!(usage.spent, Decimal::ZERO);
+        assert_eq!(usage.resets_at, None);
+
+        let response = app
+            .delete(&limit_path)
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .await;
+        response.assert_status(StatusCode::NO_CONTENT);
+        let response = app
+            .get(&usage_path)
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .await;
+        response.assert_status_not_found();
+
+        // The Everyone group can't be limited
+        let response = app
+            .put(&format!("/admin/api/v1/groups/{}/spending-limit", uuid::Uuid::nil()))
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&json!({"spending_limit": "100"}))
+            .await;
+        response.assert_status_bad_request();
+    }
+}
@@ -2,9 +2,12 @@
 
 use sqlx_pool_router::PoolProvider;
 
+use crate::api::handlers::access_templates::apply_template_to_group;
 use crate::api::models::deployments::DeployedModelResponse;
-use crate::api::models::groups::{GroupCreate, GroupResponse, GroupUpdate, ListGroupsQuery};
-use crate::api::models::pagination::PaginatedResponse;
+use crate::api::models::groups::{
+    BatchMembershipRequest, BatchMembershipResponse, GroupCreate, GroupResponse, GroupUpdate, ListGroupsQuery,
+};
+use crate::api::models::pagination::{PaginatedResponse, take_cursor_page};
 use crate::api::models::users::{CurrentUser, UserResponse};
 use crate::auth::permissions::{RequiresPermission, can_read_all_resources, can_read_own_resource, operation, resource};
 use crate::db::handlers::{Deployments, Groups, Repository, Users, groups::GroupFilter};
@@ -37,6 +40,7 @@ use axum::{
     params(
         ("skip" = Option<i64>, Query, description = "Number of groups to skip"),
         ("limit" = Option<i64>, Query, description = "Maximum number of groups to return"),
+        ("cursor" = Option<String>, Query, description = "Keyset pagination cursor: empty for the first page, then `next_cursor` from the previous response. Orders by id and ignores `skip`."),
         ("search" = Option<String>, Query, description = "Search query to filter groups by name or description (case-insensitive substring match)"),
     ),
     security(
@@ -54,16 +58,21 @@ pub async fn list_groups<P: PoolProvider>(
     // Use read replica for this read-only operation
     let mut conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
 
-    let groups;
+    let cursor = query.pagination.cursor()?;
+    let mut groups;
     let total_count;
     let skip;
     let limit;
     {
         let mut repo = Groups::new(&mut conn);
-        skip = query.pagination.skip();
+        skip = if cursor.is_some() { 0 } else { query.pagination.skip() };
         limit = query.pagination.limit();
 
-        let mut filter = GroupFilter::new(skip, limit);
+        // In cursor mode, fetch one extra row to tell whether another page follows
+        let mut filter = match cursor {
+            Some(cursor) => GroupFilter::new(0, limit + 1).with_cursor(cursor),
+            None => GroupFilter::new(skip, limit),
+        };
 
         // Apply search filter if specified
         if let Some(search) = query.search.as_ref()
@@ -75,6 +84,11 @@ pub async fn list_groups<P: PoolProvider>(
         groups = repo.list(&filter).await?;
         total_count = repo.count(&filter).await?;
     }
+    let next_cursor = if cursor.is_some() {
+        take_cursor_page(&mut groups, limit, |g| g.id)
+    } else {
+        None
+    };
 
     // Parse include parameter
     let includes: Vec<&str> = query
@@ -176,7 +190,7 @@ pub async fn list_groups<P: PoolProvider>(
         response_groups = groups.into_iter().map(GroupResponse::from).collect();
     }
 
-    let paginated_response = PaginatedResponse::new(response_groups, total_count, skip, limit);
+    let paginated_response = PaginatedResponse::new(response_groups, total_count, skip, limit).with_next_cursor(next_cursor);
     Ok(Json(paginated_response))
 }
 
@@ -186,12 +200,14 @@ pub async fn list_groups<P: PoolProvider>(
     tag = "groups",
     summary = "Create group",
     description = "Create a new group for organizing users and controlling model access. After \
-        creation, use the membership endpoints to add users and grant access to model deployments.",
+        creation, use the membership endpoints to add users and grant access to model deployments. \
+        Pass `template_id` to grant the group an access template's models straight away.",
     request_body = GroupCreate,
     responses(
         (status = 201, description = "Group created successfully", body = GroupResponse),
         (status = 400, description = "Invalid request"),
         (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Access template not found"),
         (status = 500, description = "Internal server error")
     ),
     security(
@@ -206,11 +222,16 @@ pub async fn create_group<P: PoolProvider>(
     current_user: RequiresPermission<resource::Groups, operation::CreateAll>,
     Json(create): Json<GroupCreate>,
 ) -> Result<(StatusCode, Json<GroupResponse>)> {
-    let mut pool_conn = state.db.write().acquire().await.map_err(|e| Error::Database(e.into()))?;
-    let mut repo = Groups::new(&mut pool_conn);
+    let template_id = create.template_id;
     let request = GroupCreateDBRequest::new(current_user.id, create);
 
-    let group = repo.create(&request).await?;
+    let mut tx = state.db.write().begin().await.map_err(|e| Error::Database(e.into()))?;
+    let group = Groups::new(&mut tx).create(&request).await?;
+    if let Some(template_id) = template_id {
+        apply_template_to_group(&mut tx, group.id, template_id, current_user.id).await?;
+    }
+    tx.commit().await.map_err(|e| Error::Database(e.into()))?;
+
     Ok((StatusCode::CREATED, Json(GroupResponse::from(group))))
 }
 
@@ -360,6 +381,65 @@ pub async fn add_user_to_group<P: PoolProvider>(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Maximum number of IDs accepted by a batch membership request
+const MAX_BATCH_MEMBERSHIP_IDS: usize = 1000;
+
+/// Validate a batch membership request, returning its distinct IDs in request order
+fn batch_membership_ids(request: BatchMembershipRequest) -> Result<Vec<uuid::Uuid>> {
+    if request.ids.is_empty() {
+        return Err(Error::BadRequest {
+            message: "ids must not be empty".to_string(),
+        });
+    }
+    if request.ids.len() > MAX_BATCH_MEMBERSHIP_IDS {
+        return Err(Error::BadRequest {
+            message: format!("At most {MAX_BATCH_MEMBERSHIP_IDS} ids can be added in one request"),
+        });
+    }
+    let mut seen = std::collections::HashSet::new();
+    Ok(request.ids.into_iter().filter(|id| seen.insert(*id)).collect())
+}
+
+#[utoipa::path(
+    post,
+    path = "/groups/{group_id}/users:batch",
+    tag = "groups",
+    summary = "Add users to group in bulk",
+    description = "Add many users to a group in one transaction. Adding a user who is already a member \
+        is a no-op. Each requested ID gets a result: `added`, `already_member`, or `not_found`.",
+    request_body = BatchMembershipRequest,
+    responses(
+        (status = 200, description = "Per-user results", body = BatchMembershipResponse),
+        (status = 400, description = "No IDs, or too many IDs"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Group not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    params(
+        ("group_id" = uuid::Uuid, Path, description = "Group ID")
+    ),
+    security(
+        ("BearerAuth" = []),
+        ("CookieAuth" = []),
+        ("X-Doubleword-User" = [])
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn add_users_to_group<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(group_id): Path<GroupId>,
+    _: RequiresPermission<resource::Groups, operation::UpdateAll>,
+    Json(request): Json<BatchMembershipRequest>,
+) -> Result<Json<BatchMembershipResponse>> {
+    let user_ids = batch_membership_ids(request)?;
+
+    let mut tx = state.db.write().begin().await.map_err(|e| Error::Database(e.into()))?;
+    let outcomes = Groups::new(&mut tx).add_users_to_group(group_id, &user_ids).await?;
+    tx.commit().await.map_err(|e| Error::Database(e.into()))?;
+
+    Ok(Json(outcomes.into()))
+}
+
 #[utoipa::path(
     delete,
     path = "/groups/{group_id}/users/{user_id}",
@@ -591,6 +671,49 @@ pub async fn add_deployment_to_group<P: PoolProvider>(
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(
+    post,
+    path = "/groups/{group_id}/models:batch",
+    tag = "models",
+    summary = "Grant group access to models in bulk",
+    description = "Grant a group access to many model deployments in one transaction. Granting access \
+        the group already has is a no-op. Each requested ID gets a result: `added`, `already_member`, \
+        or `not_found`.",
+    request_body = BatchMembershipRequest,
+    responses(
+        (status = 200, description = "Per-model results", body = BatchMembershipResponse),
+        (status = 400, description = "No IDs, or too many IDs"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Group not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    params(
+        ("group_id" = uuid::Uuid, Path, description = "Group ID")
+    ),
+    security(
+        ("BearerAuth" = []),
+        ("CookieAuth" = []),
+        ("X-Doubleword-User" = [])
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn add_deployments_to_group<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(group_id): Path<GroupId>,
+    current_user: RequiresPermission<resource::Groups, operation::UpdateAll>,
+    Json(request): Json<BatchMembershipRequest>,
+) -> Result<Json<BatchMembershipResponse>> {
+    let deployment_ids = batch_membership_ids(request)?;
+
+    let mut tx = state.db.write().begin().await.map_err(|e| Error::Database(e.into()))?;
+    let outcomes = Groups::new(&mut tx)
+        .add_deployments_to_group(group_id, &deployment_ids, current_user.id)
+        .await?;
+    tx.commit().await.map_err(|e| Error::Database(e.into()))?;
+
+    Ok(Json(outcomes.into()))
+}
+
 #[utoipa::path(
     delete,
     path = "/groups/{group_id}/models/{deployment_id}",
@@ -770,6 +893,62 @@ mod tests {
         assert_eq!(paginated_response.data.len(), 6); // Should return all 6 groups (5 test groups + Everyone group)
     }
 
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_list_groups_with_cursor_pagination(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let user = create_test_admin_user(&pool, Role::PlatformManager).await;
+
+        let mut pool_conn = pool.acquire().await.unwrap();
+        let mut group_repo = Groups::new(&mut pool_conn);
+        for i in 0..6 {
+            let group_create = GroupCreateDBRequest {
+                name: format!("Cursor Group {i}"),
+                description: None,
+                created_by: user.id,
+            };
+            group_repo.create(&group_create).await.expect("Failed to create test group");
+        }
+
+        // 7 groups (6 + Everyone) in pages of 3: 3, 3, then 1 with no next cursor
+        let mut seen: Vec<GroupId> = Vec::new();
+        let mut cursor = String::new();
+        let mut page_sizes = Vec::new();
+        loop {
+            let response = app
+                .get(&format!("/admin/api/v1/groups?limit=3&cursor={cursor}"))
+                .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
+                .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+                .await;
+            response.assert_status_ok();
+            let page: PaginatedResponse<GroupResponse> = response.json();
+            assert_eq!(page.total_count, 7);
+            page_sizes.push(page.data.len());
+            seen.extend(page.data.iter().map(|g| g.id));
+            match page.next_cursor {
+                Some(next) => cursor = next,
+                None => break,
+            }
+        }
+
+        assert_eq!(page_sizes, vec![3, 3, 1]);
+        assert!(seen.windows(2).all(|w| w[0] < w[1]), "cursor pages must be strictly ordered by id");
+        assert_eq!(
+            seen.iter().collect::<HashSet<_>>().len(),
+            7,
+            "pages must be disjoint and cover every group"
+        );
+
+        // Offset pagination is unchanged and carries no cursor
+        let response = app
+            .get("/admin/api/v1/groups?limit=3")
+            .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
+            .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+            .await;
+        let page: PaginatedResponse<GroupResponse> = response.json();
+        assert!(page.next_cursor.is_none());
+    }
+
     #[sqlx::test]
     #[test_log::test]
     async fn test_add_user_to_group(pool: PgPool) {
@@ -808,6 +987,124 @@ mod tests {
         assert!(user_ids.contains(&user2.id));
     }
 
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_add_users_to_group_batch_with_mixed_members(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let admin = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let existing = create_test_user(&pool, Role::StandardUser).await;
+        let new_user = create_test_user(&pool, Role::StandardUser).await;
+        let missing = uuid::Uuid::new_v4();
+
+        let mut pool_conn = pool.acquire().await.unwrap();
+        let mut group_repo = Groups::new(&mut pool_conn);
+        let group = group_repo
+            .create(&GroupCreateDBRequest {
+                name: "Batch Group".to_string(),
+                description: None,
+                created_by: admin.id,
+            })
+            .await
+            .expect("Failed to create test group");
+        group_repo.add_user_to_group(existing.id, group.id).await.unwrap();
+
+        let mut listener = sqlx::postgres::PgListener::connect_with(&pool).await.unwrap();
+        listener.listen("auth_config_changed").await.unwrap();
+
+        let response = app
+            .post(&format!("/admin/api/v1/groups/{}/users:batch", group.id))
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&json!({ "ids": [new_user.id, existing.id, missing, new_user.id] }))
+            .await;
+
+        response.assert_status_ok();
+        let body: serde_json::Value = response.json();
+        assert_eq!(
+            body["results"],
+            json!([
+                { "id": new_user.id, "status": "added" },
+                { "id": existing.id, "status": "already_member" },
+                { "id": missing, "status": "not_found" },
+            ])
+        );
+
+        let member_ids = group_repo.get_group_users(group.id).await.unwrap();
+        assert!(member_ids.contains(&existing.id));
+        assert!(member_ids.contains(&new_user.id));
+
+        // The whole batch is one statement, so onwards is notified once
+        let notification = tokio::time::timeout(std::time::Duration::from_secs(5), listener.recv())
+            .await
+            .expect("Timed out waiting for notification")
+            .unwrap();
+        assert!(notification.payload().starts_with("user_groups:"));
+        let extra = tokio::time::timeout(std::time::Duration::from_millis(500), listener.recv()).await;
+        assert!(extra.is_err(), "expected a single notification for the batch");
+
+        // Repeating the batch is a no-op
+        let response = app
+            .post(&format!("/admin/api/v1/groups/{}/users:batch", group.id))
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&json!({ "ids": [new_user.id, existing.id] }))
+            .await;
+        response.assert_status_ok();
+        let body: serde_json::Value = response.json();
+        assert!(body["results"].as_array().unwrap().iter().all(|r| r["status"] == "already_member"));
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_add_users_to_group_batch_validation(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let admin = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let standard = create_test_user(&pool, Role::StandardUser).await;
+
+        let mut pool_conn = pool.acquire().await.unwrap();
+        let group = Groups::new(&mut pool_conn)
+            .create(&GroupCreateDBRequest {
+                name: "Batch Group".to_string(),
+                description: None,
+                created_by: admin.id,
+            })
+            .await
+            .expect("Failed to create test group");
+
+        let post = |path: String, user: &crate::api::models::users::UserResponse, ids: serde_json::Value| {
+            let headers = add_auth_headers(user);
+            app.post(&path)
+                .add_header(&headers[0].0, &headers[0].1)
+                .add_header(&headers[1].0, &headers[1].1)
+                .json(&json!({ "ids": ids }))
+        };
+
+        post(format!("/admin/api/v1/groups/{}/users:batch", group.id), &admin, json!([]))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+
+        let too_many: Vec<uuid::Uuid> = (0..1001).map(|_| uuid::Uuid::new_v4()).collect();
+        post(format!("/admin/api/v1/groups/{}/users:batch", group.id), &admin, json!(too_many))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+
+        post(
+            format!("/admin/api/v1/groups/{}/users:batch", uuid::Uuid::new_v4()),
+            &admin,
+            json!([standard.id]),
+        )
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+        post(
+            format!("/admin/api/v1/groups/{}/users:batch", group.id),
+            &standard,
+            json!([standard.id]),
+        )
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    }
+
     #[sqlx::test]
     #[test_log::test]
     async fn test_remove_user_from_group(pool: PgPool) {
@@ -1123,6 +1420,50 @@ mod tests {
         assert!(groups.contains(&group.id));
     }
 
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_add_deployments_to_group_batch_with_mixed_members(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let admin = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let existing = create_test_deployment(&pool, admin.id, "batch-model-1", "batch-alias-1").await;
+        let new_model = create_test_deployment(&pool, admin.id, "batch-model-2", "batch-alias-2").await;
+        let missing = uuid::Uuid::new_v4();
+
+        let mut pool_conn = pool.acquire().await.unwrap();
+        let mut group_repo = Groups::new(&mut pool_conn);
+        let group = group_repo
+            .create(&GroupCreateDBRequest {
+                name: "Batch Group".to_string(),
+                description: None,
+                created_by: admin.id,
+            })
+            .await
+            .expect("Failed to create test group");
+        group_repo.add_deployment_to_group(existing.id, group.id, admin.id).await.unwrap();
+
+        let response = app
+            .post(&format!("/admin/api/v1/groups/{}/models:batch", group.id))
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&json!({ "ids": [existing.id, missing, new_model.id] }))
+            .await;
+
+        response.assert_status_ok();
+        let body: serde_json::Value = response.json();
+        assert_eq!(
+            body["results"],
+            json!([
+                { "id": existing.id, "status": "already_member" },
+                { "id": missing, "status": "not_found" },
+                { "id": new_model.id, "status": "added" },
+            ])
+        );
+
+        let deployment_ids = group_repo.get_group_deployments(group.id).await.unwrap();
+        assert!(deployment_ids.contains(&existing.id));
+        assert!(deployment_ids.contains(&new_model.id));
+    }
+
     #[sqlx::test]
     #[test_log::test]
     async fn test_remove_deployment_from_group_api(pool: PgPool) {
@@ -255,12 +255,15 @@ mod tests {
         let create = ApiKeyCreate {
             name: format!("Org key {}", uuid::Uuid::new_v4().simple()),
             description: None,
-            purpose: ApiKeyPurpose::Realtime,
+            purpose: Some(ApiKeyPurpose::Realtime),
             requests_per_second: None,
             burst_size: None,
             member_id: None,
             spend_limit: None,
             spend_limit_interval: None,
+            allowed_model_ids: None,
+            denied_model_ids: None,
+            max_priority: None,
         };
         let req = ApiKeyCreateDBRequest::new(org_id, member_id, create);
         ApiKeys::new(&mut conn).create(&req).await.unwrap().secret
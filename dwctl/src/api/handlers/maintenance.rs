@@ -0,0 +1,222 @@
+//! HTTP handlers for maintenance mode.
+//!
+//! Enabling maintenance mode makes the AI proxy (`/ai/v1/*`) reject requests
+//! with a 503; see [`crate::inference::maintenance`].
+
+use axum::{Json, extract::State};
+use sqlx_pool_router::PoolProvider;
+
+use crate::{
+    AppState,
+    api::models::maintenance::{DEFAULT_RETRY_AFTER_SECONDS, MaintenanceModeResponse, MaintenanceModeUpdate},
+    auth::permissions::{RequiresPermission, operation, resource},
+    errors::{Error, Result},
+    inference::maintenance,
+};
+
+/// Get the current maintenance mode state
+#[utoipa::path(
+    get,
+    path = "/maintenance",
+    tag = "maintenance",
+    summary = "Get maintenance mode",
+    responses(
+        (status = 200, description = "Current maintenance mode state", body = MaintenanceModeResponse),
+        (status = 403, description = "Insufficient permissions"),
+    ),
+    security(
+        ("BearerAuth" = []),
+        ("CookieAuth" = []),
+        ("X-Doubleword-User" = [])
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_maintenance_mode<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    _: RequiresPermission<resource::System, operation::ReadAll>,
+) -> Result<Json<MaintenanceModeResponse>> {
+    let status = maintenance::load(state.db.read()).await.map_err(|e| Error::Database(e.into()))?;
+    Ok(Json(status.into()))
+}
+
+/// Enable or disable maintenance mode
+///
+/// While enabled, every `/ai/v1/*` request is rejected with a 503 and a `Retry-After`
+/// header. The admin API and health endpoints are unaffected. The state is persisted,
+/// so it survives restarts and applies to all replicas.
+#[utoipa::path(
+    put,
+    path = "/maintenance",
+    tag = "maintenance",
+    summary = "Set maintenance mode",
+    request_body = MaintenanceModeUpdate,
+    responses(
+        (status = 200, description = "Maintenance mode updated", body = MaintenanceModeResponse),
+        (status = 400, description = "Invalid retry_after_seconds"),
+        (status = 403, description = "Insufficient permissions"),
+    ),
+    security(
+        ("BearerAuth" = []),
+        ("CookieAuth" = []),
+        ("X-Doubleword-User" = [])
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn set_maintenance_mode<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    current_user: RequiresPermission<resource::System, operation::UpdateAll>,
+    Json(update): Json<MaintenanceModeUpdate>,
+) -> Result<Json<MaintenanceModeResponse>> {
+    let retry_after_seconds = update.retry_after_seconds.unwrap_or(DEFAULT_RETRY_AFTER_SECONDS);
+    if retry_after_seconds <= 0 {
+        return Err(Error::BadRequest {
+            message: "retry_after_seconds must be positive".to_string(),
+        });
+    }
+    let message = update.message.as_deref().map(str::trim).filter(|message| !message.is_empty());
+
+    let status = maintenance::store(state.db.write(), update.enabled, message, retry_after_seconds, current_user.id)
+        .await
+        .map_err(|e| Error::Database(e.into()))?;
+    tracing::info!(enabled = status.enabled, user_id = %current_user.id, "maintenance mode updated");
+    state.maintenance.set(status.clone());
+
+    Ok(Json(status.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::utils::{add_auth_headers, await_chat_status, create_test_config, setup_proxied_model};
+    use sqlx::PgPool;
+
+    async fn app(pool: &PgPool) -> (axum_test::TestServer, crate::BackgroundServices) {
+        let mut config = create_test_config();
+        config.background_services.onwards_sync.enabled = true;
+        crate::Application::new_with_pool(config, Some(pool.clone()), None)
+            .await
+            .expect("Failed to create application")
+            .into_test_server()
+    }
+
+    fn chat_body() -> serde_json::Value {
+        serde_json::json!({ "model": "maintenance-model", "messages": [{ "role": "user", "content": "hi" }] })
+    }
+
+    async fn chat(server: &axum_test::TestServer, api_key: &str) -> axum_test::TestResponse {
+        server
+            .post("/ai/v1/chat/completions")
+            .add_header("authorization", format!("Bearer {api_key}"))
+            .json(&chat_body())
+            .await
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_maintenance_mode_blocks_ai_api_but_not_admin_api(pool: PgPool) {
+        let mock_server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/v1/chat/completions"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "chatcmpl-1", "object": "chat.completion", "created": 1, "model": "maintenance-model",
+                "choices": [ { "index": 0, "message": { "role": "assistant", "content": "Hello" }, "finish_reason": "stop" } ],
+                "usage": { "prompt_tokens": 5, "completion_tokens": 1, "total_tokens": 6 }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let (server, bg_services) = app(&pool).await;
+        let proxied = setup_proxied_model(
+            &server,
+            &bg_services,
+            &pool,
+            &mock_server,
+            serde_json::json!({}),
+            serde_json::json!({ "alias": "maintenance-model" }),
+        )
+        .await;
+        let (admin, admin_headers, api_key) = (proxied.admin, proxied.admin_headers, proxied.api_key);
+        let user_headers = add_auth_headers(&proxied.user);
+        await_chat_status(&server, &api_key, &chat_body(), 200).await;
+
+        // Off by default
+        let resp = server
+            .get("/admin/api/v1/maintenance")
+            .add_header(&admin_headers[0].0, &admin_headers[0].1)
+            .add_header(&admin_headers[1].0, &admin_headers[1].1)
+            .await;
+        resp.assert_status_ok();
+        assert_eq!(resp.json::<serde_json::Value>()["enabled"], false);
+
+        // Standard users cannot toggle it
+        server
+            .put("/admin/api/v1/maintenance")
+            .add_header(&user_headers[0].0, &user_headers[0].1)
+            .add_header(&user_headers[1].0, &user_headers[1].1)
+            .json(&serde_json::json!({ "enabled": true }))
+            .await
+            .assert_status_forbidden();
+
+        server
+            .put("/admin/api/v1/maintenance")
+            .add_header(&admin_headers[0].0, &admin_headers[0].1)
+            .add_header(&admin_headers[1].0, &admin_headers[1].1)
+            .json(&serde_json::json!({ "enabled": true, "retry_after_seconds": 0 }))
+            .await
+            .assert_status_bad_request();
+
+        let resp = server
+            .put("/admin/api/v1/maintenance")
+            .add_header(&admin_headers[0].0, &admin_headers[0].1)
+            .add_header(&admin_headers[1].0, &admin_headers[1].1)
+            .json(&serde_json::json!({ "enabled": true, "message": "Upgrading", "retry_after_seconds": 120 }))
+            .await;
+        resp.assert_status_ok();
+        let status: serde_json::Value = resp.json();
+        assert_eq!(status["enabled"], true);
+        assert_eq!(status["updated_by"], admin.id.to_string());
+
+        // The AI API is rejected
+        let resp = chat(&server, &api_key).await;
+        assert_eq!(resp.status_code(), 503, "{}", resp.text());
+        assert_eq!(resp.header("retry-after"), "120");
+        let body: serde_json::Value = resp.json();
+        assert_eq!(body["error"]["code"], "maintenance_mode");
+        assert_eq!(body["error"]["message"], "Upgrading");
+        assert_eq!(server.get("/ai/v1/models").await.status_code(), 503);
+
+        // The admin API and health check are not
+        server
+            .get("/admin/api/v1/models")
+            .add_header(&admin_headers[0].0, &admin_headers[0].1)
+            .add_header(&admin_headers[1].0, &admin_headers[1].1)
+            .await
+            .assert_status_ok();
+        server
+            .get("/admin/api/v1/users/current")
+            .add_header(&user_headers[0].0, &user_headers[0].1)
+            .add_header(&user_headers[1].0, &user_headers[1].1)
+            .await
+            .assert_status_ok();
+        server.get("/healthz").await.assert_status_ok();
+
+        bg_services.shutdown().await;
+
+        // The state survives a restart
+        let (server, bg_services) = app(&pool).await;
+        let resp = chat(&server, &api_key).await;
+        assert_eq!(resp.status_code(), 503, "{}", resp.text());
+
+        server
+            .put("/admin/api/v1/maintenance")
+            .add_header(&admin_headers[0].0, &admin_headers[0].1)
+            .add_header(&admin_headers[1].0, &admin_headers[1].1)
+            .json(&serde_json::json!({ "enabled": false }))
+            .await
+            .assert_status_ok();
+
+        bg_services.sync_onwards_config(&pool).await.expect("Failed to sync onwards config");
+        await_chat_status(&server, &api_key, &chat_body(), 200).await;
+
+        bg_services.shutdown().await;
+    }
+}
@@ -4,20 +4,28 @@
 //!
 //! # Handler Modules
 //!
+//! - [`access_requests`]: Requests for access to models that require approval
+//! - [`access_templates`]: Access templates (named sets of models) and applying them to groups
 //! - [`api_keys`]: API key creation, listing, and deletion for users
 //! - [`auth`]: Authentication, login, registration, and password management
 //! - [`batches`]: Batch request creation, monitoring, and cancellation
 //! - [`config`]: Application configuration retrieval
+//! - [`config_snapshot`]: YAML export and import of endpoints, deployments and groups
 //! - [`deployments`]: Model deployment CRUD operations and group assignments
+//! - [`effective_permissions`]: The current user's roles, accessible models, balance and rate limits
+//! - [`events`]: Server-sent stream of deployment and endpoint changes
 //! - [`files`]: File upload, download, and management for batch processing
 //! - [`groups`]: Group management, user memberships, and model access
 //! - [`inference_endpoints`]: Inference endpoint CRUD and synchronization
+//! - [`maintenance`]: Platform-wide maintenance mode for the AI proxy
 //! - [`payments`]: Payment processing and checkout session creation
 //! - [`probes`]: Health probe configuration, execution, and result retrieval
 //! - [`requests`]: Request logging, analytics, and aggregation
+//! - [`sessions`]: Listing and revoking users' login sessions
 //! - [`static_assets`]: Frontend asset serving and SPA routing
 //! - [`transactions`]: Credit transaction creation and history
 //! - [`users`]: User CRUD operations and profile management
+//! - [`version`]: Build and schema version information
 //!
 //! # Authentication
 //!
@@ -31,6 +39,8 @@
 //! appropriate HTTP status codes and JSON error responses. See [`crate::errors`]
 //! for details on error types and HTTP status mappings.
 
+pub mod access_requests;
+pub mod access_templates;
 pub mod ai_models;
 pub mod api_keys;
 pub mod auth;
@@ -38,20 +48,28 @@ pub mod batch_requests;
 pub mod batches;
 pub mod cache_pricing;
 pub mod config;
+pub mod config_snapshot;
 pub mod connections;
 pub mod daemons;
+pub mod deployment_shadows;
 pub mod deployments;
+pub mod effective_permissions;
+pub mod events;
 pub mod files;
+pub mod group_spending_limits;
 pub mod groups;
 pub mod images;
 pub mod inference_endpoints;
+pub mod maintenance;
 pub mod openapi_docs;
 pub mod organizations;
 pub mod payments;
 pub mod probes;
 pub mod provider_display_configs;
 pub mod queue;
+pub mod rate_limits;
 pub mod requests;
+pub mod sessions;
 pub mod sla_capacity;
 pub mod static_assets;
 pub mod support;
@@ -59,4 +77,5 @@ pub mod tool_sources;
 pub mod transactions;
 pub mod unverified_volume;
 pub mod users;
+pub mod version;
 pub mod webhooks;
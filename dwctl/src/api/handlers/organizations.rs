@@ -305,17 +305,12 @@ pub async fn list_organizations<P: PoolProvider>(
         let orgs = repo.list(&filter).await?;
         let total_count = repo.count(&filter).await?;
 
-        let data = orgs
+        let data: Vec<OrganizationResponse> = orgs
             .into_iter()
             .map(|o| OrganizationResponse::from_user(UserResponse::from(o)))
             .collect();
 
-        Ok(Json(PaginatedResponse {
-            data,
-            total_count,
-            skip,
-            limit,
-        }))
+        Ok(Json(PaginatedResponse::new(data, total_count, skip, limit)))
     } else {
         // Standard users: list only their organizations
         let mut pool_conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
@@ -338,12 +333,7 @@ pub async fn list_organizations<P: PoolProvider>(
 
         let total_count = data.len() as i64;
 
-        Ok(Json(PaginatedResponse {
-            data,
-            total_count,
-            skip: 0,
-            limit: total_count,
-        }))
+        Ok(Json(PaginatedResponse::new(data, total_count, 0, total_count)))
     }
 }
 
@@ -502,6 +492,13 @@ pub async fn update_organization<P: PoolProvider>(
             id: id.to_string(),
         });
     }
+    if let Some(timezone) = &data.timezone
+        && !users_repo.is_valid_timezone(timezone).await?
+    {
+        return Err(Error::BadRequest {
+            message: format!("Unknown timezone '{timezone}'; use an IANA name such as 'Europe/Berlin'"),
+        });
+    }
 
     // If an email change is requested, validate the format, check the domain
     // is mail-deliverable, and route it through the double-opt-in verification
@@ -654,6 +651,7 @@ pub async fn update_organization<P: PoolProvider>(
         batch_notifications_enabled: data.batch_notifications_enabled,
         low_balance_threshold: data.low_balance_threshold,
         zero_data_retention: data.zero_data_retention,
+        timezone: data.timezone,
     };
     debug_assert!(
         db_request.email.is_none(),
@@ -1746,6 +1744,7 @@ pub async fn confirm_email_change<P: PoolProvider>(
             batch_notifications_enabled: None,
             low_balance_threshold: None,
             zero_data_retention: None,
+            timezone: None,
         };
         org_repo.update(pending.organization_id, &update).await?;
         // The `confirm_*_email_side` UPDATE above already locked this row, so
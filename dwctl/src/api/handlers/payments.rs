@@ -334,10 +334,10 @@ pub async fn process_payment<P: PoolProvider>(
     path = "/webhooks/payments",
     tag = "payments",
     summary = "Payment webhook",
-    description = "Receives webhook events from payment providers (Stripe, PayPal, etc.) and processes them.",
+    description = "Receives webhook events from payment providers (Stripe, PayPal, etc.), verifies their signature against the configured webhook secret, and processes them. Events are deduplicated on the provider event id.",
     responses(
-        (status = 200, description = "Webhook processed successfully"),
-        (status = 400, description = "Invalid webhook signature or malformed data"),
+        (status = 200, description = "Webhook processed successfully, or already processed (duplicate event id)"),
+        (status = 400, description = "Missing or invalid webhook signature, or malformed data"),
         (status = 501, description = "Payment provider not configured or doesn't support webhooks"),
     ),
 )]
@@ -372,8 +372,19 @@ pub async fn webhook_handler<P: PoolProvider>(
 
     tracing::trace!("Received webhook event: {}", event.event_type);
 
-    // Process the webhook event
-    match provider.process_webhook_event(state.db.write(), &event, &config.credits).await {
+    handle_webhook_event(state.db.write(), provider.as_ref(), &event, &config.credits).await
+}
+
+/// Process a validated webhook event once, and pick the status that tells the
+/// provider whether to retry it. Redelivered events (provider retries) are
+/// acknowledged without reprocessing.
+async fn handle_webhook_event(
+    db_pool: &sqlx::PgPool,
+    provider: &dyn payment_providers::PaymentProvider,
+    event: &payment_providers::WebhookEvent,
+    credits_config: &crate::config::CreditsConfig,
+) -> StatusCode {
+    match payment_providers::process_webhook_event_once(db_pool, provider, event, credits_config).await {
         Ok(()) => {
             tracing::trace!("Successfully processed webhook event: {}", event.event_type);
             StatusCode::OK
@@ -667,6 +678,7 @@ pub async fn process_auto_topup<P: PoolProvider>(
         auto_topup_threshold: Some(Some(body.threshold)),
         auto_topup_monthly_limit: Some(body.monthly_limit),
         zero_data_retention: None,
+        timezone: None,
     };
 
     let mut conn = state.db.write().acquire().await.map_err(|e| {
@@ -839,6 +851,7 @@ pub async fn enable_auto_topup<P: PoolProvider>(
                 auto_topup_threshold: Some(Some(body.threshold)),
                 auto_topup_monthly_limit: Some(body.monthly_limit),
                 zero_data_retention: None,
+                timezone: None,
             };
 
             Users::new(&mut conn).update(target.id, &update).await.map_err(|e| {
@@ -914,6 +927,7 @@ pub async fn disable_auto_topup<P: PoolProvider>(State(state): State<AppState<P>
         auto_topup_threshold: Some(None),
         auto_topup_monthly_limit: Some(None),
         zero_data_retention: None,
+        timezone: None,
     };
 
     Users::new(&mut conn).update(target.id, &update).await.map_err(|e| match e {
@@ -1657,4 +1671,280 @@ mod tests {
         assert_eq!(user_row.auto_topup_amount, None);
         assert_eq!(user_row.auto_topup_threshold, None);
     }
+
+    const TEST_WEBHOOK_SECRET: &str = "whsec_test_secret";
+
+    fn stripe_webhook_server(state: AppState<crate::test::utils::TestDbPools>) -> TestServer {
+        let app = Router::new().route("/webhooks/payments", post(webhook_handler)).with_state(state);
+        TestServer::new(app).unwrap()
+    }
+
+    /// A `checkout.session.completed` event for the fixture session, as Stripe would send it.
+    fn checkout_completed_event(event_id: &str) -> String {
+        let session: serde_json::Value =
+            serde_json::from_str(include_str!("../../payment_providers/test_fixtures/checkout_session_with_tax.json")).unwrap();
+        serde_json::json!({
+            "id": event_id,
+            "object": "event",
+            "api_version": "2025-09-30.clover",
+            "created": chrono::Utc::now().timestamp(),
+            "data": { "object": session },
+            "livemode": false,
+            "pending_webhooks": 1,
+            "request": { "id": null, "idempotency_key": null },
+            "type": "checkout.session.completed"
+        })
+        .to_string()
+    }
+
+    /// Build a `Stripe-Signature` header value (`t=<timestamp>,v1=<hex hmac>`) for `body`.
+    fn stripe_signature(secret: &str, body: &str) -> String {
+        use hmac::{Hmac, KeyInit, Mac};
+        let timestamp = chrono::Utc::now().timestamp();
+        let mut mac = Hmac::<sha2::Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("{timestamp}.{body}").as_bytes());
+        format!("t={timestamp},v1={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    async fn stripe_state(pool: &PgPool) -> AppState<crate::test::utils::TestDbPools> {
+        let mut config = create_test_config();
+        config.payment = Some(PaymentConfig::Stripe(crate::config::StripeConfig {
+            api_key: "sk_test_fake".to_string(),
+            price_id: "price_fake".to_string(),
+            webhook_secret: TEST_WEBHOOK_SECRET.to_string(),
+            enable_invoice_creation: false,
+            auto_topup_terms_of_service_text: None,
+            tax_code: None,
+        }));
+        crate::test::utils::create_test_app_state_with_config(pool.clone(), config).await
+    }
+
+    /// Record the fixture session's purchase so processing takes the fast path
+    /// instead of calling the Stripe API.
+    async fn seed_fixture_purchase(pool: &PgPool) -> String {
+        let user = crate::test::utils::create_test_user(pool, crate::api::models::users::Role::StandardUser).await;
+        let session: serde_json::Value =
+            serde_json::from_str(include_str!("../../payment_providers/test_fixtures/checkout_session_with_tax.json")).unwrap();
+        let session_id = session["id"].as_str().unwrap().to_string();
+        let mut conn = pool.acquire().await.unwrap();
+        crate::db::handlers::Credits::new(&mut conn)
+            .create_transaction(&crate::db::models::credits::CreditTransactionCreateDBRequest {
+                user_id: user.id,
+                transaction_type: crate::db::models::credits::CreditTransactionType::Purchase,
+                amount: Decimal::new(25, 0),
+                source_id: session_id.clone(),
+                description: Some("Stripe payment".to_string()),
+                fusillade_batch_id: None,
+                api_key_id: None,
+            })
+            .await
+            .unwrap();
+        session_id
+    }
+
+    async fn processed_event_count(pool: &PgPool, event_id: &str) -> i64 {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!" FROM processed_payment_webhook_events WHERE event_id = $1"#,
+            event_id
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test]
+    async fn test_webhook_valid_signature(pool: PgPool) {
+        let server = stripe_webhook_server(stripe_state(&pool).await);
+        seed_fixture_purchase(&pool).await;
+
+        let body = checkout_completed_event("evt_test_valid");
+        let response = server
+            .post("/webhooks/payments")
+            .add_header("stripe-signature", stripe_signature(TEST_WEBHOOK_SECRET, &body))
+            .text(body)
+            .await;
+
+        response.assert_status(StatusCode::OK);
+        assert_eq!(processed_event_count(&pool, "evt_test_valid").await, 1);
+    }
+
+    #[sqlx::test]
+    async fn test_webhook_invalid_signature_rejected(pool: PgPool) {
+        let server = stripe_webhook_server(stripe_state(&pool).await);
+        let body = checkout_completed_event("evt_test_forged");
+
+        // Signed with the wrong secret
+        let response = server
+            .post("/webhooks/payments")
+            .add_header("stripe-signature", stripe_signature("whsec_wrong_secret", &body))
+            .text(body.clone())
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+
+        // Body tampered with after signing
+        let signature = stripe_signature(TEST_WEBHOOK_SECRET, &body);
+        let response = server
+            .post("/webhooks/payments")
+            .add_header("stripe-signature", signature)
+            .text(body.replace("evt_test_forged", "evt_test_forged2"))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+
+        // No signature at all
+        let response = server.post("/webhooks/payments").text(body).await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+
+        assert_eq!(processed_event_count(&pool, "evt_test_forged").await, 0);
+        assert_eq!(processed_event_count(&pool, "evt_test_forged2").await, 0);
+    }
+
+    async fn purchase_count(pool: &PgPool, user_id: crate::types::UserId) -> i64 {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!" FROM credits_transactions WHERE user_id = $1 AND transaction_type = 'purchase'"#,
+            user_id
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    /// Provider that credits its user on every webhook it processes, with no
+    /// idempotency of its own, so only the event-id dedup prevents a double credit.
+    struct CreditingProvider {
+        user_id: crate::types::UserId,
+    }
+
+    #[async_trait::async_trait]
+    impl payment_providers::PaymentProvider for CreditingProvider {
+        async fn create_checkout_session(
+            &self,
+            _payer: &payment_providers::CheckoutPayer,
+            _creditee_id: Option<&str>,
+            _cancel_url: &str,
+            _success_url: &str,
+        ) -> payment_providers::Result<String> {
+            unimplemented!()
+        }
+
+        async fn get_payment_session(&self, _session_id: &str) -> payment_providers::Result<payment_providers::PaymentSession> {
+            unimplemented!()
+        }
+
+        async fn process_payment_session(
+            &self,
+            _db_pool: &PgPool,
+            _session_id: &str,
+            _credits_config: &crate::config::CreditsConfig,
+        ) -> payment_providers::Result<()> {
+            unimplemented!()
+        }
+
+        async fn validate_webhook(
+            &self,
+            _headers: &axum::http::HeaderMap,
+            _body: &str,
+        ) -> payment_providers::Result<Option<payment_providers::WebhookEvent>> {
+            unimplemented!()
+        }
+
+        async fn process_webhook_event(
+            &self,
+            conn: &mut sqlx::PgConnection,
+            _event: &payment_providers::WebhookEvent,
+            _credits_config: &crate::config::CreditsConfig,
+        ) -> payment_providers::Result<()> {
+            crate::db::handlers::Credits::new(conn)
+                .create_transaction(&crate::db::models::credits::CreditTransactionCreateDBRequest {
+                    user_id: self.user_id,
+                    transaction_type: crate::db::models::credits::CreditTransactionType::Purchase,
+                    amount: Decimal::new(25, 0),
+                    source_id: uuid::Uuid::new_v4().to_string(),
+                    description: Some("Webhook credit".to_string()),
+                    fusillade_batch_id: None,
+                    api_key_id: None,
+                })
+                .await?;
+            Ok(())
+        }
+
+        async fn create_billing_portal_session(&self, _customer_id: &str, _return_url: &str) -> payment_providers::Result<String> {
+            unimplemented!()
+        }
+
+        async fn create_auto_topup_checkout_session(
+            &self,
+            _payer: &payment_providers::CheckoutPayer,
+            _cancel_url: &str,
+            _success_url: &str,
+        ) -> payment_providers::Result<String> {
+            unimplemented!()
+        }
+
+        async fn process_auto_topup_session(
+            &self,
+            _db_pool: &PgPool,
+            _session_id: &str,
+        ) -> payment_providers::Result<payment_providers::AutoTopupSetupResult> {
+            unimplemented!()
+        }
+
+        async fn charge_auto_topup(
+            &self,
+            _amount_cents: i64,
+            _customer_id: &str,
+            _payment_method_id: &str,
+            _idempotency_key: &str,
+        ) -> payment_providers::Result<String> {
+            unimplemented!()
+        }
+
+        async fn get_default_payment_method(&self, _customer_id: &str) -> payment_providers::Result<Option<String>> {
+            unimplemented!()
+        }
+
+        async fn customer_has_address(&self, _customer_id: &str) -> payment_providers::Result<bool> {
+            unimplemented!()
+        }
+
+        async fn create_customer(&self, _email: &str, _name: Option<&str>) -> payment_providers::Result<String> {
+            unimplemented!()
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_webhook_duplicate_event_not_reprocessed(pool: PgPool) {
+        let user = crate::test::utils::create_test_user(&pool, crate::api::models::users::Role::StandardUser).await;
+        let provider = CreditingProvider { user_id: user.id };
+        let credits_config = crate::config::CreditsConfig::default();
+        let event = payment_providers::WebhookEvent {
+            event_id: Some("evt_test_duplicate".to_string()),
+            event_type: "checkout.session.completed".to_string(),
+            session_id: Some("cs_test_duplicate".to_string()),
+        };
+
+        // Concurrent deliveries, then a later redelivery
+        let (first, second) = tokio::join!(
+            handle_webhook_event(&pool, &provider, &event, &credits_config),
+            handle_webhook_event(&pool, &provider, &event, &credits_config),
+        );
+        assert_eq!((first, second), (StatusCode::OK, StatusCode::OK));
+        assert_eq!(
+            handle_webhook_event(&pool, &provider, &event, &credits_config).await,
+            StatusCode::OK
+        );
+
+        assert_eq!(processed_event_count(&pool, "evt_test_duplicate").await, 1);
+        assert_eq!(purchase_count(&pool, user.id).await, 1, "Redelivered event must not credit twice");
+
+        // A different event is processed
+        let other = payment_providers::WebhookEvent {
+            event_id: Some("evt_test_other".to_string()),
+            ..event.clone()
+        };
+        assert_eq!(
+            handle_webhook_event(&pool, &provider, &other, &credits_config).await,
+            StatusCode::OK
+        );
+        assert_eq!(purchase_count(&pool, user.id).await, 2);
+    }
 }
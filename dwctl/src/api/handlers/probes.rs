@@ -5,7 +5,7 @@ use crate::api::models::probes::{
     CreateProbe, ProbeStatistics, ProbesQuery, ResultsQuery, StatsQuery, TestProbeRequest, UpdateProbeRequest,
 };
 use crate::auth::permissions::{RequiresPermission, operation, resource};
-use crate::db::models::probes::{Probe, ProbeResult};
+use crate::db::models::probes::{Probe, ProbeAssertions, ProbeResult};
 use crate::errors::Error;
 use crate::probes::db::ProbeManager;
 use axum::{
@@ -15,6 +15,37 @@ use axum::{
 };
 use uuid::Uuid;
 
+fn validate_assertions(assertions: Option<&ProbeAssertions>) -> Result<(), Error> {
+    match assertions {
+        Some(assertions) => assertions.validate().map_err(|message| Error::BadRequest { message }),
+        None => Ok(()),
+    }
+}
+
+fn validate_health_thresholds(
+    failure_threshold: Option<i32>,
+    success_threshold: Option<i32>,
+    recovery_cooldown_seconds: Option<i32>,
+) -> Result<(), Error> {
+    for (name, value) in [("failure_threshold", failure_threshold), ("success_threshold", success_threshold)] {
+        if let Some(value) = value
+            && value < 1
+        {
+            return Err(Error::BadRequest {
+                message: format!("{name} must be at least 1, got {value}"),
+            });
+        }
+    }
+    if let Some(cooldown) = recovery_cooldown_seconds
+        && cooldown < 0
+    {
+        return Err(Error::BadRequest {
+            message: format!("recovery_cooldown_seconds must not be negative, got {cooldown}"),
+        });
+    }
+    Ok(())
+}
+
 #[utoipa::path(
     post,
     path = "/probes",
@@ -42,6 +73,8 @@ pub async fn create_probe(
     _: RequiresPermission<resource::Probes, operation::CreateAll>,
     Json(probe): Json<CreateProbe>,
 ) -> Result<(StatusCode, Json<Probe>), Error> {
+    validate_assertions(probe.assertions.as_ref())?;
+    validate_health_thresholds(probe.failure_threshold, probe.success_threshold, probe.recovery_cooldown_seconds)?;
     let created = ProbeManager::create_probe(&state.db, probe).await?;
     Ok((StatusCode::CREATED, Json(created)))
 }
@@ -239,6 +272,8 @@ pub async fn update_probe(
     Path(id): Path<Uuid>,
     Json(update): Json<UpdateProbeRequest>,
 ) -> Result<Json<Probe>, Error> {
+    validate_assertions(update.assertions.as_ref().and_then(Option::as_ref))?;
+    validate_health_thresholds(update.failure_threshold, update.success_threshold, update.recovery_cooldown_seconds)?;
     let probe = ProbeManager::update_probe(&state.db, id, update).await?;
     Ok(Json(probe))
 }
@@ -287,6 +322,7 @@ pub async fn execute_probe(
     ),
     responses(
         (status = 200, description = "Probe test executed successfully", body = ProbeResult),
+        (status = 400, description = "Bad request - invalid assertions"),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Forbidden - admin access required"),
         (status = 404, description = "Deployment not found"),
@@ -306,13 +342,10 @@ pub async fn test_probe(
     Json(request): Json<Option<TestProbeRequest>>,
 ) -> Result<(StatusCode, Json<ProbeResult>), Error> {
     let config = state.current_config();
-    let (http_method, request_path, request_body) = if let Some(req) = request {
-        (req.http_method, req.request_path, req.request_body)
-    } else {
-        (None, None, None)
-    };
+    let request = request.unwrap_or_default();
+    validate_assertions(request.assertions.as_ref())?;
 
-    let result = ProbeManager::test_probe(&state.db, deployment_id, &config, http_method, request_path, request_body).await?;
+    let result = ProbeManager::test_probe(&state.db, deployment_id, &config, request).await?;
     Ok((StatusCode::OK, Json(result)))
 }
 
@@ -450,6 +483,96 @@ mod tests {
         assert!(probe.active);
     }
 
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_create_probe_with_assertions(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let user = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let deployment_id = setup_test_deployment(&pool, user.id).await;
+        let headers = add_auth_headers(&user);
+
+        let assertions = serde_json::json!({
+            "expected_status": 200,
+            "json_paths": [{"path": "/choices/0/message/content", "equals": "pong"}],
+            "max_response_time_ms": 5000
+        });
+        let response = app
+            .post("/admin/api/v1/probes")
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .json(&serde_json::json!({
+                "name": "Asserting Probe",
+                "deployment_id": deployment_id,
+                "interval_seconds": 60,
+                "assertions": assertions
+            }))
+            .await;
+
+        response.assert_status(axum::http::StatusCode::CREATED);
+        let probe: serde_json::Value = response.json();
+        assert_eq!(probe["assertions"], assertions);
+
+        let invalid = app
+            .post("/admin/api/v1/probes")
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .json(&serde_json::json!({
+                "name": "Invalid Probe",
+                "deployment_id": deployment_id,
+                "interval_seconds": 60,
+                "assertions": {"json_paths": [{"path": "choices.0"}]}
+            }))
+            .await;
+        invalid.assert_status(axum::http::StatusCode::BAD_REQUEST);
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_create_probe_with_health_thresholds(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let user = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let deployment_id = setup_test_deployment(&pool, user.id).await;
+        let headers = add_auth_headers(&user);
+
+        let response = app
+            .post("/admin/api/v1/probes")
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .json(&serde_json::json!({
+                "name": "Debounced Probe",
+                "deployment_id": deployment_id,
+                "interval_seconds": 60,
+                "failure_threshold": 3,
+                "success_threshold": 2,
+                "recovery_cooldown_seconds": 300
+            }))
+            .await;
+        response.assert_status(axum::http::StatusCode::CREATED);
+        let probe: Probe = response.json();
+        assert_eq!(probe.failure_threshold, 3);
+        assert_eq!(probe.success_threshold, 2);
+        assert_eq!(probe.recovery_cooldown_seconds, 300);
+
+        let response = app
+            .patch(&format!("/admin/api/v1/probes/{}", probe.id))
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .json(&serde_json::json!({ "success_threshold": 0 }))
+            .await;
+        response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+        let response = app
+            .patch(&format!("/admin/api/v1/probes/{}", probe.id))
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .json(&serde_json::json!({ "recovery_cooldown_seconds": 60 }))
+            .await;
+        response.assert_status_ok();
+        let probe: Probe = response.json();
+        assert_eq!(probe.failure_threshold, 3, "omitted fields are unchanged");
+        assert_eq!(probe.recovery_cooldown_seconds, 60);
+    }
+
     #[sqlx::test]
     #[test_log::test]
     async fn test_create_probe_unauthorized(pool: PgPool) {
@@ -492,6 +615,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -506,6 +635,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -538,6 +673,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -587,6 +728,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -609,6 +756,61 @@ mod tests {
         assert_eq!(probe.interval_seconds, 120);
     }
 
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_update_probe_clears_assertions(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let user = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let deployment_id = setup_test_deployment(&pool, user.id).await;
+        let assertions = ProbeAssertions {
+            expected_status: Some(200),
+            json_paths: vec![],
+            max_response_time_ms: Some(5000),
+        };
+
+        let created = ProbeManager::create_probe(
+            &pool,
+            CreateProbe {
+                name: "Asserting Probe".to_string(),
+                deployment_id,
+                interval_seconds: 60,
+                http_method: "POST".to_string(),
+                request_path: None,
+                request_body: None,
+                assertions: Some(assertions.clone()),
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        // Omitting assertions leaves them unchanged
+        let response = app
+            .patch(&format!("/admin/api/v1/probes/{}", created.id))
+            .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
+            .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+            .json(&serde_json::json!({ "interval_seconds": 120 }))
+            .await;
+        response.assert_status_ok();
+        assert_eq!(response.json::<Probe>().assertions, Some(assertions));
+
+        // An explicit null removes them
+        let response = app
+            .patch(&format!("/admin/api/v1/probes/{}", created.id))
+            .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
+            .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+            .json(&serde_json::json!({ "assertions": null }))
+            .await;
+        response.assert_status_ok();
+        let probe: Probe = response.json();
+        assert_eq!(probe.assertions, None);
+        assert_eq!(probe.interval_seconds, 120);
+    }
+
     #[sqlx::test]
     #[test_log::test]
     async fn test_activate_probe(pool: PgPool) {
@@ -625,6 +827,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -660,6 +868,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -692,6 +906,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -731,6 +951,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -763,6 +989,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
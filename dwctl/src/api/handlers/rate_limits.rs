@@ -0,0 +1,393 @@
+//! Admin endpoints for per-user rate-limit overrides and per-group rate-limit
+//! defaults. Thin wrappers over [`crate::db::handlers::RateLimits`].
+//!
+//! Both are resolved into each API key's limits by the onwards config sync:
+//! user override > the key's own rate limit > group default > platform tier.
+//! Writes NOTIFY the sync, so changes take effect on the next reload.
+
+use axum::Json;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use sqlx::PgConnection;
+use sqlx_pool_router::PoolProvider;
+
+use crate::AppState;
+use crate::api::models::rate_limits::{RateLimitsResponse, RateLimitsUpdate};
+use crate::auth::permissions::{RequiresPermission, operation, resource};
+use crate::db::handlers::{Groups, RateLimits, Repository, Users};
+use crate::db::models::rate_limits::RateLimitsDBRequest;
+use crate::errors::{Error, Result};
+use crate::types::{GroupId, UserId};
+
+/// 404 unless the user exists and isn't deleted (the system user is hidden too)
+async fn ensure_user_exists(conn: &mut PgConnection, id: UserId) -> Result<()> {
+    match Users::new(conn).get_by_id(id).await? {
+        Some(_) => Ok(()),
+        None => Err(Error::NotFound {
+            resource: "User".to_string(),
+            id: id.to_string(),
+        }),
+    }
+}
+
+/// 404 unless the group exists
+async fn ensure_group_exists(conn: &mut PgConnection, id: GroupId) -> Result<()> {
+    match Groups::new(conn).get_by_id(id).await? {
+        Some(_) => Ok(()),
+        None => Err(Error::NotFound {
+            resource: "Group".to_string(),
+            id: id.to_string(),
+        }),
+    }
+}
+
+/// Every limit that is set must be positive (and the rate finite), matching
+/// the table's CHECK constraints with a clean 400. A burst size only applies
+/// with the rate at its own level, so it can't be set alone.
+fn validate(update: &RateLimitsUpdate) -> Result<()> {
+    if matches!(update.requests_per_second, Some(rps) if !rps.is_finite() || rps <= 0.0) {
+        return Err(Error::BadRequest {
+            message: "requests_per_second must be positive".to_string(),
+        });
+    }
+    if update.burst_size.is_some() && update.requests_per_second.is_none() {
+        return Err(Error::BadRequest {
+            message: "burst_size requires requests_per_second".to_string(),
+        });
+    }
+    for (name, value) in [("burst_size", update.burst_size), ("concurrency_limit", update.concurrency_limit)] {
+        if matches!(value, Some(v) if v <= 0) {
+            return Err(Error::BadRequest {
+                message: format!("{name} must be positive"),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/rate-limits",
+    tag = "users",
+    summary = "Get a user's rate-limit override",
+    params(("user_id" = uuid::Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "The user's override", body = RateLimitsResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "User not found, or no override set"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_user_rate_limits<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(user_id): Path<UserId>,
+    _: RequiresPermission<resource::Users, operation::ReadAll>,
+) -> Result<Json<RateLimitsResponse>> {
+    let mut conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    ensure_user_exists(&mut conn, user_id).await?;
+    match RateLimits::new(&mut conn).get_for_user(user_id).await? {
+        Some(limits) => Ok(Json(limits.into())),
+        None => Err(Error::NotFound {
+            resource: "Rate limit override".to_string(),
+            id: user_id.to_string(),
+        }),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/users/{user_id}/rate-limits",
+    tag = "users",
+    summary = "Set a user's rate-limit override",
+    description = "Set limits for each of the user's API keys that take precedence over the key's own \
+                   rate limit, the user's group defaults and the platform tier. Replaces any existing \
+                   override; omitted fields fall through to the next level.",
+    params(("user_id" = uuid::Uuid, Path, description = "User ID")),
+    request_body = RateLimitsUpdate,
+    responses(
+        (status = 200, description = "Override set", body = RateLimitsResponse),
+        (status = 400, description = "Invalid limits"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "User not found"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn set_user_rate_limits<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(user_id): Path<UserId>,
+    _: RequiresPermission<resource::Users, operation::UpdateAll>,
+    Json(update): Json<RateLimitsUpdate>,
+) -> Result<Json<RateLimitsResponse>> {
+    validate(&update)?;
+    let mut conn = state.db.write().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    ensure_user_exists(&mut conn, user_id).await?;
+    let limits = RateLimits::new(&mut conn)
+        .set_for_user(user_id, &RateLimitsDBRequest::from(update))
+        .await?;
+    Ok(Json(limits.into()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/users/{user_id}/rate-limits",
+    tag = "users",
+    summary = "Remove a user's rate-limit override",
+    params(("user_id" = uuid::Uuid, Path, description = "User ID")),
+    responses(
+        (status = 204, description = "Override removed (or none was set)"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "User not found"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn delete_user_rate_limits<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(user_id): Path<UserId>,
+    _: RequiresPermission<resource::Users, operation::UpdateAll>,
+) -> Result<StatusCode> {
+    let mut conn = state.db.write().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    ensure_user_exists(&mut conn, user_id).await?;
+    RateLimits::new(&mut conn).delete_for_user(user_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/groups/{group_id}/rate-limits",
+    tag = "groups",
+    summary = "Get a group's default rate limits",
+    params(("group_id" = uuid::Uuid, Path, description = "Group ID")),
+    responses(
+        (status = 200, description = "The group's defaults", body = RateLimitsResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Group not found, or no defaults set"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_group_rate_limits<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(group_id): Path<GroupId>,
+    _: RequiresPermission<resource::Groups, operation::ReadAll>,
+) -> Result<Json<RateLimitsResponse>> {
+    let mut conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    ensure_group_exists(&mut conn, group_id).await?;
+    match RateLimits::new(&mut conn).get_for_group(group_id).await? {
+        Some(limits) => Ok(Json(limits.into())),
+        None => Err(Error::NotFound {
+            resource: "Group rate limits".to_string(),
+            id: group_id.to_string(),
+        }),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/groups/{group_id}/rate-limits",
+    tag = "groups",
+    summary = "Set a group's default rate limits",
+    description = "Set default limits for the API keys of the group's members. A key's own rate limit \
+                   and a user override take precedence. A user in several groups gets the highest \
+                   default for each limit. Replaces any existing defaults.",
+    params(("group_id" = uuid::Uuid, Path, description = "Group ID")),
+    request_body = RateLimitsUpdate,
+    responses(
+        (status = 200, description = "Defaults set", body = RateLimitsResponse),
+        (status = 400, description = "Invalid limits"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Group not found"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn set_group_rate_limits<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(group_id): Path<GroupId>,
+    _: RequiresPermission<resource::Groups, operation::UpdateAll>,
+    Json(update): Json<RateLimitsUpdate>,
+) -> Result<Json<RateLimitsResponse>> {
+    validate(&update)?;
+    let mut conn = state.db.write().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    ensure_group_exists(&mut conn, group_id).await?;
+    let limits = RateLimits::new(&mut conn)
+        .set_for_group(group_id, &RateLimitsDBRequest::from(update))
+        .await?;
+    Ok(Json(limits.into()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/groups/{group_id}/rate-limits",
+    tag = "groups",
+    summary = "Remove a group's default rate limits",
+    params(("group_id" = uuid::Uuid, Path, description = "Group ID")),
+    responses(
+        (status = 204, description = "Defaults removed (or none were set)"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Group not found"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn delete_group_rate_limits<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(group_id): Path<GroupId>,
+    _: RequiresPermission<resource::Groups, operation::UpdateAll>,
+) -> Result<StatusCode> {
+    let mut conn = state.db.write().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    ensure_group_exists(&mut conn, group_id).await?;
+    RateLimits::new(&mut conn).delete_for_group(group_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::models::users::Role;
+    use crate::test::utils::{add_auth_headers, create_test_admin_user, create_test_app, create_test_group, create_test_user};
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    #[test]
+    fn validate_rejects_non_positive_limits() {
+        assert!(validate(&RateLimitsUpdate::default()).is_ok());
+        for update in [
+            RateLimitsUpdate {
+                requests_per_second: Some(0.0),
+                ..Default::default()
+            },
+            RateLimitsUpdate {
+                requests_per_second: Some(f32::NAN),
+                ..Default::default()
+            },
+            RateLimitsUpdate {
+                requests_per_second: Some(1.0),
+                burst_size: Some(0),
+                ..Default::default()
+            },
+            RateLimitsUpdate {
+                burst_size: Some(10),
+                ..Default::default()
+            },
+            RateLimitsUpdate {
+                concurrency_limit: Some(-1),
+                ..Default::default()
+            },
+        ] {
+            assert!(validate(&update).is_err(), "{update:?} should be rejected");
+        }
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_user_override_round_trips(pool: PgPool) {
+        let (app, _) = create_test_app(pool.clone(), false).await;
+        let admin = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let path = format!("/admin/api/v1/users/{}/rate-limits", user.id);
+
+        let response = app
+            .get(&path)
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .await;
+        response.assert_status_not_found();
+
+        let response = app
+            .put(&path)
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&json!({"requests_per_second": 50.0, "concurrency_limit": 20}))
+            .await;
+        response.assert_status_ok();
+        let limits: RateLimitsResponse = response.json();
+        assert_eq!(limits.requests_per_second, Some(50.0));
+        assert_eq!(limits.burst_size, None);
+        assert_eq!(limits.concurrency_limit, Some(20));
+
+        // A second PUT replaces the override rather than merging into it
+        let response = app
+            .put(&path)
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&json!({"requests_per_second": 5.0, "burst_size": 10}))
+            .await;
+        response.assert_status_ok();
+        let limits: RateLimitsResponse = response.json();
+        assert_eq!(limits.requests_per_second, Some(5.0));
+        assert_eq!(limits.burst_size, Some(10));
+        assert_eq!(limits.concurrency_limit, None);
+
+        let response = app
+            .delete(&path)
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .await;
+        response.assert_status(StatusCode::NO_CONTENT);
+        let response = app
+            .get(&path)
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .await;
+        response.assert_status_not_found();
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_rate_limits_require_admin_and_existing_target(pool: PgPool) {
+        let (app, _) = create_test_app(pool.clone(), false).await;
+        let admin = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let group = create_test_group(&pool).await;
+        let body = json!({"requests_per_second": 50.0});
+
+        // Users can't raise their own limits
+        let response = app
+            .put(&format!("/admin/api/v1/users/{}/rate-limits", user.id))
+            .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
+            .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+            .json(&body)
+            .await;
+        response.assert_status_forbidden();
+        let response = app
+            .put(&format!("/admin/api/v1/groups/{}/rate-limits", group.id))
+            .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
+            .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+            .json(&body)
+            .await;
+        response.assert_status_forbidden();
+
+        let response = app
+            .put(&format!("/admin/api/v1/users/{}/rate-limits", uuid::Uuid::new_v4()))
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&body)
+            .await;
+        response.assert_status_not_found();
+
+        let response = app
+            .put(&format!("/admin/api/v1/groups/{}/rate-limits", group.id))
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&json!({"requests_per_second": 5.0, "burst_size": 0}))
+            .await;
+        response.assert_status_bad_request();
+
+        let response = app
+            .put(&format!("/admin/api/v1/groups/{}/rate-limits", group.id))
+            .add_header(&add_auth_headers(&admin)[0].0, &add_auth_headers(&admin)[0].1)
+            .add_header(&add_auth_headers(&admin)[1].0, &add_auth_headers(&admin)[1].1)
+            .json(&body)
+            .await;
+        response.assert_status_ok();
+    }
+}
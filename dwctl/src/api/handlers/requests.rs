@@ -86,7 +86,7 @@ pub async fn list_requests<P: PoolProvider>(
     };
 
     // Query the http_analytics table - use read replica for analytics
-    let entries = list_http_analytics(state.db.read(), skip, limit, query.order_desc.unwrap_or(true), filter).await?;
+    let entries = list_http_analytics(state.analytics_pool(), skip, limit, query.order_desc.unwrap_or(true), filter).await?;
 
     Ok(Json(ListAnalyticsResponse { entries }))
 }
@@ -117,8 +117,8 @@ pub async fn aggregate_requests<P: PoolProvider>(
     let time_range_end = query.timestamp_before.unwrap_or(now);
     let model_filter = query.model.as_deref();
 
-    // Get aggregated analytics data from http_analytics table - use read replica for analytics
-    let response = get_requests_aggregate(state.db.read(), time_range_start, time_range_end, model_filter).await?;
+    // Get aggregated analytics data from http_analytics table - use the analytics replica
+    let response = get_requests_aggregate(state.analytics_pool(), time_range_start, time_range_end, model_filter).await?;
 
     Ok(Json(response))
 }
@@ -163,8 +163,8 @@ pub async fn aggregate_by_user<P: PoolProvider>(
     let end_date = query.end_date.unwrap_or_else(Utc::now);
     let start_date = query.start_date.unwrap_or_else(|| end_date - Duration::hours(24));
 
-    // Get usage data from http_analytics table - use read replica for analytics
-    let usage_data = get_model_user_usage(state.db.read(), &model_alias, start_date, end_date).await?;
+    // Get usage data from http_analytics table - use the analytics replica
+    let usage_data = get_model_user_usage(state.analytics_pool(), &model_alias, start_date, end_date).await?;
 
     Ok(Json(usage_data))
 }
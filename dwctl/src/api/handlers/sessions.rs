@@ -0,0 +1,318 @@
+//! HTTP handlers for listing and revoking users' login sessions.
+//!
+//! Sessions are created by native login and registration, and tracked in
+//! `user_sessions` so they can be ended before their JWT expires (see
+//! [`crate::auth::session`]). Users can see and revoke their own sessions;
+//! admins can do so for anyone, e.g. when an account is compromised. SSO and
+//! proxy-header logins have no sessions here.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use sqlx::PgConnection;
+use sqlx_pool_router::PoolProvider;
+
+use crate::{
+    AppState,
+    api::models::{
+        sessions::{RevokeSessionsResponse, SessionPathParams, SessionResponse, UserSessionsPathParams},
+        users::CurrentUser,
+    },
+    auth::permissions::{can_read_all_resources, can_read_own_resource, can_update_all_resources, can_update_own_resource},
+    db::handlers::{Repository, Sessions, Users},
+    errors::{Error, Result},
+    types::{Operation, Permission, Resource, UserId, UserIdOrCurrent},
+};
+
+fn target_user(user_id: UserIdOrCurrent, current_user: &CurrentUser) -> UserId {
+    match user_id {
+        UserIdOrCurrent::Current(_) => current_user.id,
+        UserIdOrCurrent::Id(id) => id,
+    }
+}
+
+/// 404 unless the user exists and isn't deleted
+async fn ensure_user_exists(conn: &mut PgConnection, id: UserId) -> Result<()> {
+    match Users::new(conn).get_by_id(id).await? {
+        Some(_) => Ok(()),
+        None => Err(Error::NotFound {
+            resource: "User".to_string(),
+            id: id.to_string(),
+        }),
+    }
+}
+
+/// Revoking a session needs permission to update the user
+fn ensure_can_revoke(current_user: &CurrentUser, target_user_id: UserId) -> Result<()> {
+    if can_update_all_resources(current_user, Resource::Users) || can_update_own_resource(current_user, Resource::Users, target_user_id) {
+        return Ok(());
+    }
+    Err(Error::InsufficientPermissions {
+        required: Permission::Any(vec![
+            Permission::Allow(Resource::Users, Operation::UpdateAll),
+            Permission::Allow(Resource::Users, Operation::UpdateOwn),
+        ]),
+        action: Operation::UpdateAll,
+        resource: format!("sessions for user {target_user_id}"),
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/sessions",
+    tag = "users",
+    summary = "List a user's sessions",
+    description = "List the user's active login sessions, newest first. Users can list their own \
+                   sessions; admins can list anyone's.",
+    params(("user_id" = uuid::Uuid, Path, description = "User ID, or `current`")),
+    responses(
+        (status = 200, description = "Active sessions", body = [SessionResponse]),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "User not found"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn list_user_sessions<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(params): Path<UserSessionsPathParams>,
+    current_user: CurrentUser,
+) -> Result<Json<Vec<SessionResponse>>> {
+    let target_user_id = target_user(params.user_id, &current_user);
+    if !can_read_all_resources(&current_user, Resource::Users) && !can_read_own_resource(&current_user, Resource::Users, target_user_id) {
+        return Err(Error::InsufficientPermissions {
+            required: Permission::Any(vec![
+                Permission::Allow(Resource::Users, Operation::ReadAll),
+                Permission::Allow(Resource::Users, Operation::ReadOwn),
+            ]),
+            action: Operation::ReadAll,
+            resource: format!("sessions for user {target_user_id}"),
+        });
+    }
+
+    let mut conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    ensure_user_exists(&mut conn, target_user_id).await?;
+    let sessions = Sessions::new(&mut conn).list_active(target_user_id).await?;
+    Ok(Json(sessions.into_iter().map(SessionResponse::from).collect()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/users/{user_id}/sessions",
+    tag = "users",
+    summary = "Revoke all of a user's sessions",
+    description = "Log the user out everywhere: each of their active sessions stops authenticating \
+                   immediately. API keys are not affected.",
+    params(("user_id" = uuid::Uuid, Path, description = "User ID, or `current`")),
+    responses(
+        (status = 200, description = "Sessions revoked", body = RevokeSessionsResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "User not found"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn revoke_user_sessions<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(params): Path<UserSessionsPathParams>,
+    current_user: CurrentUser,
+) -> Result<Json<RevokeSessionsResponse>> {
+    let target_user_id = target_user(params.user_id, &current_user);
+    ensure_can_revoke(&current_user, target_user_id)?;
+
+    let mut conn = state.db.write().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    ensure_user_exists(&mut conn, target_user_id).await?;
+    let revoked = Sessions::new(&mut conn).revoke_all(target_user_id).await?;
+    Ok(Json(RevokeSessionsResponse { revoked }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/users/{user_id}/sessions/{session_id}",
+    tag = "users",
+    summary = "Revoke a session",
+    description = "End one of the user's active sessions; it stops authenticating immediately.",
+    params(
+        ("user_id" = uuid::Uuid, Path, description = "User ID, or `current`"),
+        ("session_id" = uuid::Uuid, Path, description = "Session ID"),
+    ),
+    responses(
+        (status = 204, description = "Session revoked"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "No such active session"),
+    ),
+    security(("BearerAuth" = []), ("CookieAuth" = []), ("X-Doubleword-User" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn revoke_user_session<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Path(params): Path<SessionPathParams>,
+    current_user: CurrentUser,
+) -> Result<StatusCode> {
+    let target_user_id = target_user(params.user_id, &current_user);
+    ensure_can_revoke(&current_user, target_user_id)?;
+
+    let mut conn = state.db.write().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    if !Sessions::new(&mut conn).revoke(target_user_id, params.session_id).await? {
+        return Err(Error::NotFound {
+            resource: "Session".to_string(),
+            id: params.session_id.to_string(),
+        });
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::models::users::Role;
+    use crate::auth::session;
+    use crate::config::Config;
+    use crate::test::utils::{add_auth_headers, create_test_admin_user, create_test_app_with_config, create_test_config, create_test_user};
+    use axum_test::TestServer;
+    use sqlx::PgPool;
+
+    fn native_auth_config() -> Config {
+        let mut config = create_test_config();
+        config.auth.native.enabled = true;
+        config
+    }
+
+    /// Start a session for the user as if they had logged in, returning its cookie
+    async fn log_in(pool: &PgPool, config: &Config, user_id: UserId, user_agent: &str) -> String {
+        let mut conn = pool.acquire().await.unwrap();
+        let user = CurrentUser::from(Users::new(&mut conn).get_by_id(user_id).await.unwrap().unwrap());
+        let token = session::start_session(&mut conn, &user, Some(user_agent), config).await.unwrap();
+        format!("{}={token}", config.auth.native.session.cookie_name)
+    }
+
+    async fn list_sessions(server: &TestServer, cookie: &str) -> Vec<SessionResponse> {
+        let response = server
+            .get("/admin/api/v1/users/current/sessions")
+            .add_header("cookie", cookie)
+            .await;
+        response.assert_status_ok();
+        response.json()
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_user_lists_and_revokes_own_session(pool: PgPool) {
+        let config = native_auth_config();
+        let (server, _bg) = create_test_app_with_config(pool.clone(), config.clone(), false).await;
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let laptop = log_in(&pool, &config, user.id, "laptop").await;
+        let phone = log_in(&pool, &config, user.id, "phone").await;
+
+        let sessions = list_sessions(&server, &laptop).await;
+        let agents: Vec<_> = sessions.iter().map(|session| session.user_agent.as_deref()).collect();
+        assert_eq!(agents, vec![Some("phone"), Some("laptop")]);
+
+        let response = server
+            .delete(&format!("/admin/api/v1/users/{}/sessions/{}", user.id, sessions[0].id))
+            .add_header("cookie", &laptop)
+            .await;
+        response.assert_status(StatusCode::NO_CONTENT);
+
+        // The revoked session no longer authenticates; the other one still does
+        server
+            .get("/admin/api/v1/users/current")
+            .add_header("cookie", &phone)
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+        let sessions = list_sessions(&server, &laptop).await;
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].user_agent.as_deref(), Some("laptop"));
+
+        // An unknown session is a 404
+        let response = server
+            .delete(&format!("/admin/api/v1/users/current/sessions/{}", uuid::Uuid::new_v4()))
+            .add_header("cookie", &laptop)
+            .await;
+        response.assert_status_not_found();
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_admin_revokes_all_of_a_users_sessions(pool: PgPool) {
+        let config = native_auth_config();
+        let (server, _bg) = create_test_app_with_config(pool.clone(), config.clone(), false).await;
+        let admin = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let other = create_test_user(&pool, Role::StandardUser).await;
+        let cookies = [
+            log_in(&pool, &config, user.id, "laptop").await,
+            log_in(&pool, &config, user.id, "phone").await,
+        ];
+        let other_cookie = log_in(&pool, &config, other.id, "laptop").await;
+
+        // Another user can't see or revoke them
+        let path = format!("/admin/api/v1/users/{}/sessions", user.id);
+        server
+            .get(&path)
+            .add_header("cookie", &other_cookie)
+            .await
+            .assert_status_forbidden();
+        server
+            .delete(&path)
+            .add_header("cookie", &other_cookie)
+            .await
+            .assert_status_forbidden();
+
+        let mut request = server.get(&path);
+        for (key, value) in add_auth_headers(&admin) {
+            request = request.add_header(&key, &value);
+        }
+        let sessions: Vec<SessionResponse> = request.await.json();
+        assert_eq!(sessions.len(), 2);
+
+        let mut request = server.delete(&path);
+        for (key, value) in add_auth_headers(&admin) {
+            request = request.add_header(&key, &value);
+        }
+        let response = request.await;
+        response.assert_status_ok();
+        assert_eq!(response.json::<RevokeSessionsResponse>().revoked, 2);
+
+        for cookie in &cookies {
+            server
+                .get("/admin/api/v1/users/current")
+                .add_header("cookie", cookie)
+                .await
+                .assert_status(StatusCode::UNAUTHORIZED);
+        }
+        // Other users' sessions are untouched
+        server
+            .get("/admin/api/v1/users/current")
+            .add_header("cookie", &other_cookie)
+            .await
+            .assert_status_ok();
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_logout_revokes_session(pool: PgPool) {
+        let config = native_auth_config();
+        let (server, _bg) = create_test_app_with_config(pool.clone(), config.clone(), false).await;
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let cookie = log_in(&pool, &config, user.id, "laptop").await;
+
+        server
+            .post("/authentication/logout")
+            .add_header("cookie", &cookie)
+            .await
+            .assert_status_ok();
+
+        // A copy of the cookie kept after logging out doesn't authenticate
+        server
+            .get("/admin/api/v1/users/current")
+            .add_header("cookie", &cookie)
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+    }
+}
@@ -6,23 +6,26 @@ use crate::{
     AppState,
     api::models::{
         transactions::{
-            CreditTransactionCreate, CreditTransactionResponse, ListTransactionsQuery, TransactionFilters, TransactionListResponse,
+            CreditTransactionCreate, CreditTransactionResponse, ExportTransactionsQuery, ListTransactionsQuery, TransactionExportFormat,
+            TransactionFilters, TransactionListResponse,
         },
         users::CurrentUser,
     },
     auth::permissions::{self, RequiresPermission, operation, resource},
     db::{
         handlers::Credits,
-        models::credits::{CreditTransactionCreateDBRequest, CreditTransactionType},
+        models::credits::{CreditLedgerEntry, CreditTransactionCreateDBRequest, CreditTransactionType},
     },
     errors::{Error, Result},
     types::{Operation, Permission, Resource, UserId},
 };
 use axum::{
+    body::{Body, Bytes},
     extract::{Path, Query, State},
-    http::StatusCode,
-    response::Json,
+    http::{HeaderValue, StatusCode, header},
+    response::{Json, Response},
 };
+use futures::StreamExt;
 
 use rust_decimal::Decimal;
 use uuid::Uuid;
@@ -78,7 +81,11 @@ pub async fn create_transaction<P: PoolProvider>(
 
     let transaction = repo.create_transaction(&db_request).await?;
 
-    Ok((StatusCode::CREATED, Json(CreditTransactionResponse::from(transaction))))
+    let config = state.current_config();
+    Ok((
+        StatusCode::CREATED,
+        Json(CreditTransactionResponse::from_db(transaction, &config.credits)),
+    ))
 }
 
 /// Get a specific transaction by ID
@@ -141,7 +148,8 @@ pub async fn get_transaction<P: PoolProvider>(
         }
     };
 
-    Ok(Json(CreditTransactionResponse::from(transaction)))
+    let config = state.current_config();
+    Ok(Json(CreditTransactionResponse::from_db(transaction, &config.credits)))
 }
 
 /// List credit transactions
@@ -214,12 +222,15 @@ pub async fn list_transactions<P: PoolProvider>(
         (_, None) => Some(current_user.id),
     };
 
-    // Use write pool for strong consistency - balance calculations require up-to-date data
-    let mut pool_conn = state.db.write().acquire().await.map_err(|e| Error::Database(e.into()))?;
+    // Listings scan whole ledgers, so read them from the analytics replica to keep
+    // them off the primary. Balances are as of the replica's last replayed write;
+    // the balance checkpoint and transactions it sums replicate together.
+    let mut pool_conn = state.analytics_pool().acquire().await.map_err(|e| Error::Database(e.into()))?;
     let mut repo = Credits::new(&mut pool_conn);
 
     // Parse filters from query
     let filters = query.to_filters();
+    let config = state.current_config();
 
     // Batch grouping only works with a user filter (requires per-user batch_aggregates table)
     let grouping_enabled = query.group_batches.unwrap_or(false) && filter_user_id.is_some();
@@ -233,14 +244,26 @@ pub async fn list_transactions<P: PoolProvider>(
         repo.list_transactions_with_batches(user_id, skip, limit + 1, &filters)
             .await?
             .into_iter()
-            .map(|twc| CreditTransactionResponse::from_db_with_metadata(twc.transaction, twc.batch_id, twc.service_tier, twc.batch_count))
+            .map(|twc| {
+                CreditTransactionResponse::from_db_with_metadata(
+                    twc.transaction,
+                    twc.batch_id,
+                    twc.service_tier,
+                    twc.batch_count,
+                    &config.credits,
+                )
+            })
             .collect()
     } else if let Some(user_id) = filter_user_id {
         let txs = repo.list_user_transactions(user_id, skip, limit + 1, &filters).await?;
-        txs.into_iter().map(CreditTransactionResponse::from).collect()
+        txs.into_iter()
+            .map(|tx| CreditTransactionResponse::from_db(tx, &config.credits))
+            .collect()
     } else {
         let txs = repo.list_all_transactions(skip, limit + 1, &filters).await?;
-        txs.into_iter().map(CreditTransactionResponse::from).collect()
+        txs.into_iter()
+            .map(|tx| CreditTransactionResponse::from_db(tx, &config.credits))
+            .collect()
     };
     let has_more = transactions.len() as i64 > limit;
     transactions.truncate(limit as usize);
@@ -262,9 +285,131 @@ pub async fn list_transactions<P: PoolProvider>(
         data: transactions,
         has_more,
         page_start_balance,
+        currency: config.credits.currency.clone(),
     }))
 }
 
+/// Columns of a CSV ledger export, in order
+const LEDGER_CSV_HEADER: &str =
+    "id,created_at,user_id,transaction_type,amount,signed_amount,balance,currency,source_id,description,service_tier\n";
+
+/// Export the transactions ledger
+#[utoipa::path(
+    get,
+    path = "/transactions/export",
+    tag = "transactions",
+    summary = "Export the transactions ledger",
+    description = "Stream every transaction matching the filters, oldest first, as CSV for accounting reconciliation. Each row carries the user's balance after it, computed over their whole ledger, so filtering by date or type never shifts the balances and a user's last row matches their current balance when no later transactions are filtered out. BillingManager/PlatformManager only.",
+    params(
+        ExportTransactionsQuery
+    ),
+    responses(
+        (status = 200, description = "The matching ledger as CSV", content_type = "text/csv", body = String),
+        (status = 400, description = "Bad request - invalid filter or unsupported format"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - requires BillingManager or PlatformManager role"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("BearerAuth" = []),
+        ("CookieAuth" = []),
+        ("X-Doubleword-User" = [])
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn export_transactions<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    Query(query): Query<ExportTransactionsQuery>,
+    _perm: RequiresPermission<resource::Credits, operation::ReadAll>,
+) -> Result<Response> {
+    if let (Some(from), Some(to)) = (query.from, query.to)
+        && from > to
+    {
+        return Err(Error::BadRequest {
+            message: "'from' must not be after 'to'".to_string(),
+        });
+    }
+    // CSV is the only format so far; deserializing the query rejects others
+    let TransactionExportFormat::Csv = query.format.unwrap_or_default();
+
+    let filters = query.to_filters();
+    let user_id = query.user_id;
+    let currency = state.current_config().credits.currency.clone();
+
+    // Read from the analytics replica, as list_transactions does, so the
+    // balances agree with the checkpoint the transactions list reads
+    let mut conn = state.analytics_pool().acquire().await.map_err(|e| Error::Database(e.into()))?;
+
+    // Rows are written as the database returns them, so the export is never
+    // held in memory. An error mid-stream aborts the body, which the client
+    // sees as a truncated download rather than a short ledger.
+    let body_stream = async_stream::stream! {
+        yield Ok::<_, std::io::Error>(Bytes::from_static(LEDGER_CSV_HEADER.as_bytes()));
+
+        let mut repo = Credits::new(&mut conn);
+        let mut rows = repo.stream_ledger(user_id, &filters);
+        while let Some(row) = rows.next().await {
+            match row {
+                Ok(entry) => yield Ok(Bytes::from(ledger_csv_row(&entry, &currency))),
+                Err(e) => {
+                    tracing::error!(error = %e, "Transactions export failed mid-stream");
+                    yield Err(std::io::Error::other(e.to_string()));
+                    return;
+                }
+            }
+        }
+    };
+
+    let mut response = Response::new(Body::from_stream(body_stream));
+    response
+        .headers_mut()
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static("text/csv; charset=utf-8"));
+    response.headers_mut().insert(
+        header::CONTENT_DISPOSITION,
+        HeaderValue::from_static("attachment; filename=\"transactions.csv\""),
+    );
+    Ok(response)
+}
+
+/// Format one ledger row as a CSV line matching `LEDGER_CSV_HEADER`
+fn ledger_csv_row(entry: &CreditLedgerEntry, currency: &str) -> String {
+    let signed_amount = match entry.transaction_type {
+        CreditTransactionType::AdminGrant | CreditTransactionType::Purchase => entry.amount,
+        CreditTransactionType::AdminRemoval | CreditTransactionType::Usage => -entry.amount,
+    };
+    let transaction_type = match entry.transaction_type {
+        CreditTransactionType::Purchase => "purchase",
+        CreditTransactionType::AdminGrant => "admin_grant",
+        CreditTransactionType::AdminRemoval => "admin_removal",
+        CreditTransactionType::Usage => "usage",
+    };
+    let fields = [
+        entry.id.to_string(),
+        entry.created_at.to_rfc3339(),
+        entry.user_id.to_string(),
+        transaction_type.to_string(),
+        entry.amount.to_string(),
+        signed_amount.to_string(),
+        entry.balance.to_string(),
+        csv_field(currency),
+        csv_field(&entry.source_id),
+        csv_field(entry.description.as_deref().unwrap_or_default()),
+        csv_field(entry.service_tier.as_deref().unwrap_or_default()),
+    ];
+    let mut line = fields.join(",");
+    line.push('\n');
+    line
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
 /// Calculate the balance at the start of a page for pagination purposes.
 /// - Returns the balance that should be shown after the first transaction on the page
 /// - When end_date filter is set, calculates balance at that point in time
@@ -1222,6 +1367,42 @@ mod tests {
         // for balance context when listing transactions
     }
 
+    // Test: Amounts keep full precision, with the configured currency and a rounded display amount alongside
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_transaction_responses_include_currency_and_display_amount(pool: PgPool) {
+        let mut config = create_test_config();
+        config.credits.currency = "EUR".to_string();
+        config.credits.decimal_places = 2;
+        let (app, _bg_services) = create_test_app_with_config(pool.clone(), config, false).await;
+        let user = create_test_user(&pool, Role::StandardUser).await;
+
+        // Exactly halfway between 0.12 and 0.13: banker's rounding goes to the even neighbour
+        let transaction_id = create_initial_credit_transaction(&pool, user.id, "0.125").await;
+
+        let response = app
+            .get(&format!("/admin/api/v1/transactions/{}", transaction_id))
+            .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
+            .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+            .await;
+        response.assert_status_ok();
+        let transaction: CreditTransactionResponse = response.json();
+        assert_eq!(transaction.amount, Decimal::from_str("0.125").unwrap());
+        assert_eq!(transaction.display_amount, Decimal::from_str("0.12").unwrap());
+        assert_eq!(transaction.currency, "EUR");
+
+        let response = app
+            .get("/admin/api/v1/transactions")
+            .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
+            .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+            .await;
+        response.assert_status_ok();
+        let list: TransactionListResponse = response.json();
+        assert_eq!(list.currency, "EUR");
+        assert_eq!(list.page_start_balance, Decimal::from_str("0.125").unwrap());
+        assert!(list.data.iter().all(|t| t.currency == "EUR"));
+    }
+
     // Test: Batch grouping aggregates correctly with mixed transaction types
     #[sqlx::test]
     #[test_log::test]
@@ -2044,4 +2225,130 @@ mod tests {
             "Should have 1 transaction in the earlier filtered range"
         );
     }
+
+    async fn create_ledger_transaction(pool: &PgPool, user_id: UserId, transaction_type: CreditTransactionType, amount: &str) {
+        let mut conn = pool.acquire().await.expect("Failed to acquire connection");
+        CreditsHandler::new(&mut conn)
+            .create_transaction(&CreditTransactionCreateDBRequest {
+                user_id,
+                transaction_type,
+                amount: Decimal::from_str(amount).unwrap(),
+                source_id: Uuid::new_v4().to_string(),
+                description: Some("ledger test".to_string()),
+                fusillade_batch_id: None,
+                api_key_id: None,
+            })
+            .await
+            .expect("Failed to create transaction");
+    }
+
+    /// Parse an export into (id, user_id, transaction_type, signed_amount, balance) rows
+    fn parse_ledger_csv(csv: &str) -> Vec<(Uuid, UserId, String, Decimal, Decimal)> {
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some(LEDGER_CSV_HEADER.trim_end()));
+        lines
+            .map(|line| {
+                let fields: Vec<&str> = line.split(',').collect();
+                assert_eq!(fields.len(), 11, "unexpected row: {line}");
+                (
+                    Uuid::from_str(fields[0]).unwrap(),
+                    UserId::from_str(fields[2]).unwrap(),
+                    fields[3].to_string(),
+                    Decimal::from_str(fields[5]).unwrap(),
+                    Decimal::from_str(fields[6]).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    // Test: the export's running balances reconcile with the transactions endpoint
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_export_balances_reconcile_with_transactions_list(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let billing_manager = create_test_user(&pool, Role::BillingManager).await;
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let other_user = create_test_user(&pool, Role::StandardUser).await;
+
+        create_initial_credit_transaction(&pool, user.id, "100.0").await;
+        create_initial_credit_transaction(&pool, other_user.id, "7.5").await;
+        create_ledger_transaction(&pool, user.id, CreditTransactionType::Usage, "12.5").await;
+        create_ledger_transaction(&pool, user.id, CreditTransactionType::Purchase, "30.0").await;
+        create_ledger_transaction(&pool, other_user.id, CreditTransactionType::Usage, "0.5").await;
+        create_ledger_transaction(&pool, user.id, CreditTransactionType::Usage, "0.000001234").await;
+        create_ledger_transaction(&pool, user.id, CreditTransactionType::AdminRemoval, "2.0").await;
+
+        let get = |path: String| {
+            app.get(&path)
+                .add_header(&add_auth_headers(&billing_manager)[0].0, &add_auth_headers(&billing_manager)[0].1)
+                .add_header(&add_auth_headers(&billing_manager)[1].0, &add_auth_headers(&billing_manager)[1].1)
+        };
+
+        let response = get(format!("/admin/api/v1/transactions/export?user_id={}&format=csv", user.id)).await;
+        response.assert_status_ok();
+        assert_eq!(response.header("content-type"), "text/csv; charset=utf-8");
+        let exported = parse_ledger_csv(&response.text());
+        assert_eq!(exported.len(), 5);
+        assert!(exported.iter().all(|row| row.1 == user.id));
+
+        // Each balance is the previous one plus the row's signed amount
+        let mut running = Decimal::ZERO;
+        for (_, _, _, signed_amount, balance) in &exported {
+            running += signed_amount;
+            assert_eq!(*balance, running);
+        }
+
+        // The transactions endpoint lists newest first from page_start_balance;
+        // walking it back must give the same balance for every row
+        let response = get(format!("/admin/api/v1/transactions?user_id={}&limit=100", user.id)).await;
+        response.assert_status_ok();
+        let listed: TransactionListResponse = response.json();
+        assert_eq!(listed.page_start_balance, Decimal::from_str("115.499998766").unwrap());
+        assert_eq!(exported.last().unwrap().4, listed.page_start_balance);
+        let mut balance = listed.page_start_balance;
+        for (tx, exported_row) in listed.data.iter().zip(exported.iter().rev()) {
+            assert_eq!(tx.id, exported_row.0);
+            assert_eq!(balance, exported_row.4, "balance after {} differs", tx.id);
+            balance -= exported_row.3;
+        }
+        assert_eq!(balance, Decimal::ZERO);
+
+        // Filtering by type picks rows without shifting their balances
+        let response = get(format!("/admin/api/v1/transactions/export?user_id={}&type=usage", user.id)).await;
+        response.assert_status_ok();
+        let usage_only = parse_ledger_csv(&response.text());
+        assert_eq!(usage_only.len(), 2);
+        for row in &usage_only {
+            assert_eq!(row.2, "usage");
+            let full_row = exported.iter().find(|r| r.0 == row.0).unwrap();
+            assert_eq!(row.4, full_row.4);
+        }
+
+        // Without a user filter every user's rows carry their own running balance
+        let response = get("/admin/api/v1/transactions/export".to_string()).await;
+        response.assert_status_ok();
+        let everyone = parse_ledger_csv(&response.text());
+        assert_eq!(everyone.iter().filter(|row| row.1 == user.id).count(), 5);
+        let other_rows: Vec<_> = everyone.iter().filter(|row| row.1 == other_user.id).collect();
+        assert_eq!(other_rows.len(), 2);
+        assert_eq!(other_rows.last().unwrap().4, Decimal::from_str("7.0").unwrap());
+
+        // Unsupported formats and non-billing users are rejected
+        get("/admin/api/v1/transactions/export?format=xlsx".to_string())
+            .await
+            .assert_status(axum::http::StatusCode::BAD_REQUEST);
+        let response = app
+            .get("/admin/api/v1/transactions/export")
+            .add_header(&add_auth_headers(&user)[0].0, &add_auth_headers(&user)[0].1)
+            .add_header(&add_auth_headers(&user)[1].0, &add_auth_headers(&user)[1].1)
+            .await;
+        response.assert_status(axum::http::StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn test_csv_field_quotes_delimiters() {
+        assert_eq!(csv_field("gpt-4o"), "gpt-4o");
+        assert_eq!(csv_field("a, \"b\""), "\"a, \"\"b\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
 }
@@ -6,7 +6,7 @@ use crate::{
     AppState,
     api::models::{
         groups::GroupResponse,
-        pagination::PaginatedResponse,
+        pagination::{PaginatedResponse, take_cursor_page},
         users::{CurrentUser, GetUserQuery, ListUsersQuery, UserCreate, UserResponse, UserUpdate},
     },
     auth::permissions::{self as permissions, RequiresPermission, can_read_all_resources, can_read_own_resource, operation, resource},
@@ -36,8 +36,13 @@ use tracing::error;
     params(
         ("skip" = Option<i64>, Query, description = "Number of users to skip"),
         ("limit" = Option<i64>, Query, description = "Maximum number of users to return"),
+        ("cursor" = Option<String>, Query, description = "Keyset pagination cursor: empty for the first page, then `next_cursor` from the previous response. Orders by id and ignores `skip`."),
         ("include" = Option<String>, Query, description = "Comma-separated list of related entities to include (e.g., 'groups', 'billing')"),
         ("search" = Option<String>, Query, description = "Search query to filter users by display_name, username, or email (case-insensitive substring match)"),
+        ("email_contains" = Option<String>, Query, description = "Filter to users whose email contains this value (case-insensitive)"),
+        ("role" = Option<crate::api::models::users::Role>, Query, description = "Filter to users holding this role"),
+        ("auth_source" = Option<String>, Query, description = "Filter by authentication source (e.g. 'native', 'proxy-header')"),
+        ("is_admin" = Option<bool>, Query, description = "Filter on the legacy admin flag"),
     ),
     responses(
         (status = 200, description = "List of users", body = [UserResponse]),
@@ -60,24 +65,50 @@ pub async fn list_users<P: PoolProvider>(
 ) -> Result<Json<PaginatedResponse<UserResponse>>> {
     // Use read replica for this read-only operation
     let mut conn = state.db.read().acquire().await.map_err(|e| Error::Database(e.into()))?;
-    let skip = query.pagination.skip();
+    let cursor = query.pagination.cursor()?;
+    let skip = if cursor.is_some() { 0 } else { query.pagination.skip() };
     let limit = query.pagination.limit();
 
-    // Build filter with search if provided
-    let mut filter = UserFilter::new(skip, limit);
+    // Build filter with search if provided. In cursor mode, fetch one extra row to tell
+    // whether another page follows.
+    let mut filter = match cursor {
+        Some(cursor) => UserFilter::new(0, limit + 1).with_cursor(cursor),
+        None => UserFilter::new(skip, limit),
+    };
     if let Some(search) = query.search.as_ref()
         && !search.trim().is_empty()
     {
         filter = filter.with_search(search.trim().to_string());
     }
+    if let Some(email) = query.email_contains.as_ref()
+        && !email.trim().is_empty()
+    {
+        filter = filter.with_email_contains(email.trim().to_string());
+    }
+    if let Some(role) = query.role.clone() {
+        filter = filter.with_role(role);
+    }
+    if let Some(auth_source) = query.auth_source.as_ref()
+        && !auth_source.trim().is_empty()
+    {
+        filter = filter.with_auth_source(auth_source.trim().to_string());
+    }
+    if let Some(is_admin) = query.is_admin {
+        filter = filter.with_is_admin(is_admin);
+    }
 
-    let users;
+    let mut users;
     let total_count;
     {
         let mut repo = Users::new(&mut conn);
         users = repo.list(&filter).await?;
         total_count = repo.count(&filter).await?;
     }
+    let next_cursor = if cursor.is_some() {
+        take_cursor_page(&mut users, limit, |u| u.id)
+    } else {
+        None
+    };
     // Parse include parameter
     let includes: Vec<&str> = query
         .include
@@ -150,7 +181,7 @@ pub async fn list_users<P: PoolProvider>(
         response_users.push(response_user);
     }
 
-    let paginated_response = PaginatedResponse::new(response_users, total_count, skip, limit);
+    let paginated_response = PaginatedResponse::new(response_users, total_count, skip, limit).with_next_cursor(next_cursor);
     Ok(Json(paginated_response))
 }
 
@@ -433,6 +464,13 @@ pub async fn update_user<P: PoolProvider>(
     let mut conn = state.db.write().acquire().await.expect("Failed to acquire database connection");
 
     let mut repo = Users::new(&mut conn);
+    if let Some(timezone) = &user_data.timezone
+        && !repo.is_valid_timezone(timezone).await?
+    {
+        return Err(Error::BadRequest {
+            message: format!("Unknown timezone '{timezone}'; use an IANA name such as 'America/Los_Angeles'"),
+        });
+    }
     let db_request = UserUpdateDBRequest::new(user_data);
 
     let user = repo.update(user_id, &db_request).await?;
@@ -777,6 +815,113 @@ mod tests {
         assert_eq!(paginated.limit, MAX_LIMIT); // Limit should be clamped
     }
 
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_list_users_with_filters(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let admin_user = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let viewer = create_test_user(&pool, Role::RequestViewer).await;
+        let native = create_test_user(&pool, Role::StandardUser).await;
+        for (id, email, auth_source) in [
+            (viewer.id, "viewer.filter@corp.example", "proxy-header"),
+            (native.id, "native.filter@corp.example", "native"),
+        ] {
+            sqlx::query("UPDATE users SET email = $1, auth_source = $2 WHERE id = $3")
+                .bind(email)
+                .bind(auth_source)
+                .bind(id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let list = |query: &'static str| {
+            let request = app
+                .get(&format!("/admin/api/v1/users?limit=100&{query}"))
+                .add_header(&add_auth_headers(&admin_user)[0].0, &add_auth_headers(&admin_user)[0].1)
+                .add_header(&add_auth_headers(&admin_user)[1].0, &add_auth_headers(&admin_user)[1].1);
+            async move {
+                let response = request.await;
+                response.assert_status_ok();
+                let page: PaginatedResponse<UserResponse> = response.json();
+                assert_eq!(page.total_count, page.data.len() as i64, "count must honour the same filters");
+                page.data.into_iter().map(|u| u.id).collect::<Vec<_>>()
+            }
+        };
+
+        // Each filter on its own
+        let ids = list("email_contains=FILTER@CORP").await;
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&viewer.id) && ids.contains(&native.id));
+
+        let ids = list("role=RequestViewer").await;
+        assert!(ids.contains(&viewer.id));
+        assert!(!ids.contains(&native.id) && !ids.contains(&admin_user.id));
+
+        let ids = list("auth_source=native").await;
+        assert!(ids.contains(&native.id));
+        assert!(!ids.contains(&viewer.id) && !ids.contains(&admin_user.id));
+
+        let ids = list("is_admin=true").await;
+        assert!(ids.contains(&admin_user.id));
+        assert!(!ids.contains(&viewer.id) && !ids.contains(&native.id));
+
+        // Filters combine with AND
+        assert_eq!(list("email_contains=filter@corp&auth_source=native").await, vec![native.id]);
+        assert_eq!(list("email_contains=filter@corp&role=RequestViewer").await, vec![viewer.id]);
+        assert!(list("email_contains=filter@corp&is_admin=true").await.is_empty());
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_list_users_with_cursor_pagination(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+        let admin_user = create_test_admin_user(&pool, Role::PlatformManager).await;
+        for _ in 0..6 {
+            create_test_user(&pool, Role::StandardUser).await;
+        }
+
+        // Walk every page, following next_cursor until it runs out
+        let mut seen = Vec::new();
+        let mut cursor = String::new();
+        let mut pages = 0;
+        loop {
+            let response = app
+                .get(&format!("/admin/api/v1/users?limit=3&cursor={cursor}"))
+                .add_header(&add_auth_headers(&admin_user)[0].0, &add_auth_headers(&admin_user)[0].1)
+                .add_header(&add_auth_headers(&admin_user)[1].0, &add_auth_headers(&admin_user)[1].1)
+                .await;
+            response.assert_status_ok();
+            let page: PaginatedResponse<UserResponse> = response.json();
+            assert!(page.data.len() <= 3);
+            seen.extend(page.data.iter().map(|u| u.id));
+            pages += 1;
+            match page.next_cursor {
+                Some(next) => cursor = next,
+                None => break,
+            }
+        }
+
+        // Pages are disjoint, ordered by id, and together cover every user
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM users WHERE id != '00000000-0000-0000-0000-000000000000' AND is_deleted = false AND user_type = 'individual'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(seen.len() as i64, total);
+        assert!(seen.windows(2).all(|w| w[0] < w[1]), "cursor pages must be strictly ordered by id");
+        assert_eq!(pages, (total as usize).div_ceil(3));
+
+        // Malformed cursors are rejected
+        let response = app
+            .get("/admin/api/v1/users?cursor=not-a-cursor")
+            .add_header(&add_auth_headers(&admin_user)[0].0, &add_auth_headers(&admin_user)[0].1)
+            .add_header(&add_auth_headers(&admin_user)[1].0, &add_auth_headers(&admin_user)[1].1)
+            .await;
+        response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+    }
+
     #[sqlx::test]
     #[test_log::test]
     async fn test_get_other_user_as_admin(pool: PgPool) {
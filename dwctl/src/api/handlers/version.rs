@@ -0,0 +1,103 @@
+//! HTTP handler for build and schema version information.
+
+use axum::{Json, extract::State};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx_pool_router::PoolProvider;
+use utoipa::ToSchema;
+
+use crate::{AppState, errors::Error};
+
+/// Build and schema version of the running instance
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct VersionResponse {
+    /// Crate version (e.g. "8.101.0")
+    pub version: String,
+    /// Git commit the binary was built from, or "unknown"
+    pub git_sha: String,
+    /// When the binary was built
+    pub build_timestamp: DateTime<Utc>,
+    /// Cargo features compiled into the binary
+    pub features: Vec<String>,
+    /// Latest database migration applied to the main database
+    pub schema_version: Option<i64>,
+    /// Latest migration bundled with this binary. Differs from `schema_version` while migrations are pending.
+    pub latest_migration: Option<i64>,
+}
+
+/// Cargo features compiled into this binary
+fn enabled_features() -> Vec<String> {
+    let mut features = Vec::new();
+    if cfg!(feature = "embedded-db") {
+        features.push("embedded-db".to_string());
+    }
+    features
+}
+
+/// Build timestamp recorded by the build script
+fn build_timestamp() -> DateTime<Utc> {
+    env!("DWCTL_BUILD_EPOCH")
+        .parse::<i64>()
+        .ok()
+        .and_then(|epoch| DateTime::from_timestamp(epoch, 0))
+        .unwrap_or_default()
+}
+
+/// Get version and build information. Unauthenticated, like `/healthz`, so it can be used
+/// by support tooling and deploy checks.
+#[tracing::instrument(skip_all)]
+pub async fn get_version<P: PoolProvider>(State(state): State<AppState<P>>) -> Result<Json<VersionResponse>, Error> {
+    let schema_version: Option<i64> = sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations WHERE success")
+        .fetch_one(state.db.read())
+        .await
+        .map_err(|e| Error::Database(e.into()))?;
+
+    let latest_migration = crate::migrator().iter().map(|migration| migration.version).max();
+
+    Ok(Json(VersionResponse {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        git_sha: env!("DWCTL_GIT_SHA").to_string(),
+        build_timestamp: build_timestamp(),
+        features: enabled_features(),
+        schema_version,
+        latest_migration,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VersionResponse;
+    use crate::test::utils::create_test_app;
+    use sqlx::PgPool;
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_version_endpoint_reports_build_and_schema(pool: PgPool) {
+        let (app, _bg_services) = create_test_app(pool.clone(), false).await;
+
+        let response = app.get("/version").await;
+        response.assert_status_ok();
+        let body: serde_json::Value = response.json();
+        for field in [
+            "version",
+            "git_sha",
+            "build_timestamp",
+            "features",
+            "schema_version",
+            "latest_migration",
+        ] {
+            assert!(body.get(field).is_some(), "missing field {field}: {body}");
+        }
+
+        let version: VersionResponse = response.json();
+        assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+        assert!(!version.version.is_empty());
+        assert!(!version.git_sha.is_empty());
+        assert!(version.build_timestamp.timestamp() > 0);
+        assert_eq!(version.features.contains(&"embedded-db".to_string()), cfg!(feature = "embedded-db"));
+
+        // Tests run fully migrated, so the applied schema matches the bundled migrations
+        assert!(version.schema_version.is_some());
+        assert_eq!(version.schema_version, version.latest_migration);
+    }
+}
@@ -178,7 +178,7 @@ pub async fn create_webhook<P: PoolProvider>(
         for event_type in event_types {
             let parsed = event_type.parse::<WebhookEventType>().map_err(|_| Error::BadRequest {
                 message: format!(
-                    "Invalid event type: '{}'. Valid types are: batch.completed, batch.failed, user.created, batch.created, api_key.created",
+                    "Invalid event type: '{}'. Valid types are: batch.completed, batch.failed, user.created, batch.created, api_key.created, api_key.rotated, api_key.deleted",
                     event_type,
                 ),
             })?;
@@ -188,7 +188,7 @@ pub async fn create_webhook<P: PoolProvider>(
             } else {
                 WebhookScope::Own
             };
-            if parsed.scope() != expected_scope {
+            if !parsed.subscribable_from(expected_scope) {
                 return Err(Error::BadRequest {
                     message: format!(
                         "Event type '{}' belongs to scope '{:?}', but webhook scope is '{}'",
@@ -375,7 +375,7 @@ pub async fn update_webhook<P: PoolProvider>(
             if event_type.parse::<WebhookEventType>().is_err() {
                 return Err(Error::BadRequest {
                     message: format!(
-                        "Invalid event type: {}. Valid types are: batch.completed, batch.failed, user.created, batch.created, api_key.created",
+                        "Invalid event type: {}. Valid types are: batch.completed, batch.failed, user.created, batch.created, api_key.created, api_key.rotated, api_key.deleted",
                         event_type
                     ),
                 });
@@ -0,0 +1,73 @@
+//! API request/response models for access requests.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::db::models::access_requests::{AccessRequestDBResponse, AccessRequestStatus};
+use crate::types::{DeploymentId, UserId};
+
+/// POST body — ask for access to a model that requires approval
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AccessRequestCreate {
+    /// Why access is needed, shown to the admins reviewing the request
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// PATCH body — approve or deny a request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AccessRequestReview {
+    /// `approved` or `denied`
+    pub status: AccessRequestStatus,
+}
+
+/// Query parameters for listing a model's access requests
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct AccessRequestsQuery {
+    /// Only return requests with this status
+    pub status: Option<AccessRequestStatus>,
+}
+
+/// A user's request for access to a model
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccessRequestResponse {
+    #[schema(value_type = String, format = "uuid")]
+    pub id: Uuid,
+    #[schema(value_type = String, format = "uuid")]
+    pub model_id: DeploymentId,
+    #[schema(value_type = String, format = "uuid")]
+    pub user_id: UserId,
+    pub user_email: String,
+    pub status: AccessRequestStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Admin who last approved or denied the request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, format = "uuid")]
+    pub reviewed_by: Option<UserId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<AccessRequestDBResponse> for AccessRequestResponse {
+    fn from(db: AccessRequestDBResponse) -> Self {
+        Self {
+            id: db.id,
+            model_id: db.deployment_id,
+            user_id: db.user_id,
+            user_email: db.user_email,
+            status: db.status,
+            reason: db.reason,
+            reviewed_by: db.reviewed_by,
+            reviewed_at: db.reviewed_at,
+            created_at: db.created_at,
+            updated_at: db.updated_at,
+        }
+    }
+}
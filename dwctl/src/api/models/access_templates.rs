@@ -0,0 +1,73 @@
+//! API request/response models for access templates.
+
+use crate::db::models::access_templates::AccessTemplateDBResponse;
+use crate::types::{DeploymentId, UserId};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Request body for creating an access template.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccessTemplateCreate {
+    /// Unique display name for the template
+    #[schema(example = "Standard tier")]
+    pub name: String,
+    /// Optional description of who the template is for
+    #[schema(example = "Models available to all paying customers")]
+    pub description: Option<String>,
+    /// Deployments granted to a group when the template is applied
+    #[serde(default)]
+    #[schema(value_type = Vec<String>, format = "uuid")]
+    pub deployment_ids: Vec<DeploymentId>,
+}
+
+/// Request body for updating an access template. All fields are optional.
+/// Changing a template does not affect groups it was already applied to.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccessTemplateUpdate {
+    /// New display name
+    pub name: Option<String>,
+    /// New description — pass `null` to clear
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    pub description: Option<Option<String>>,
+    /// Replacement set of deployments
+    #[schema(value_type = Option<Vec<String>>, format = "uuid")]
+    pub deployment_ids: Option<Vec<DeploymentId>>,
+}
+
+/// Access template details returned by the API.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccessTemplateResponse {
+    /// Unique identifier
+    #[schema(value_type = String, format = "uuid")]
+    pub id: Uuid,
+    /// Display name
+    pub name: String,
+    /// Description of who the template is for
+    pub description: Option<String>,
+    /// Deployments in the template (deleted deployments are omitted)
+    #[schema(value_type = Vec<String>, format = "uuid")]
+    pub deployment_ids: Vec<DeploymentId>,
+    /// User who created the template
+    #[schema(value_type = String, format = "uuid")]
+    pub created_by: UserId,
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<AccessTemplateDBResponse> for AccessTemplateResponse {
+    fn from(db: AccessTemplateDBResponse) -> Self {
+        Self {
+            id: db.id,
+            name: db.name,
+            description: db.description,
+            deployment_ids: db.deployment_ids,
+            created_by: db.created_by,
+            created_at: db.created_at,
+            updated_at: db.updated_at,
+        }
+    }
+}
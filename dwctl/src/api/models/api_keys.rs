@@ -8,20 +8,17 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 
-/// Default API key purpose when not specified
-fn default_api_key_purpose() -> ApiKeyPurpose {
-    ApiKeyPurpose::Realtime
-}
-
 // API Key request models.
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ApiKeyCreate {
     pub name: String,
     pub description: Option<String>,
-    /// Purpose of the API key. Defaults to 'realtime' if not specified.
-    /// 'platform' keys require PlatformManager role. 'batch' and 'playground' are reserved for internal system use.
-    #[serde(default = "default_api_key_purpose")]
-    pub purpose: ApiKeyPurpose,
+    /// Purpose of the API key. Defaults to `auth.api_key_purposes.default`
+    /// ('realtime' unless configured) if not specified. Must be one of the
+    /// purposes allowed for the key's owner. 'batch' and 'playground' are
+    /// reserved for internal system use.
+    #[serde(default)]
+    pub purpose: Option<ApiKeyPurpose>,
     /// Per-API-key rate limit: requests per second (null = no limit)
     pub requests_per_second: Option<f32>,
     /// Per-API-key rate limit: maximum burst size (null = no limit)
@@ -38,10 +35,25 @@ pub struct ApiKeyCreate {
     #[schema(value_type = Option<String>)]
     pub spend_limit: Option<Decimal>,
     /// Cap reset period: null = one-off (spend since the cap was set), or
-    /// 'daily' / 'weekly' / 'monthly' on CALENDAR-ALIGNED UTC boundaries (not
-    /// rolling windows). Requires spend_limit.
+    /// 'daily' / 'weekly' / 'monthly' on CALENDAR-ALIGNED boundaries in the
+    /// key owner's timezone (not rolling windows). Requires spend_limit.
     #[serde(default)]
     pub spend_limit_interval: Option<String>,
+    /// Restrict the key to these models (deployment IDs). Narrows the access the
+    /// key's owner already has through their groups; it never grants more.
+    /// Null = no allowlist.
+    #[serde(default)]
+    #[schema(value_type = Option<Vec<String>>, format = "uuid")]
+    pub allowed_model_ids: Option<Vec<DeploymentId>>,
+    /// Models (deployment IDs) the key may never use, even if the owner's
+    /// groups grant them. Null = no denylist.
+    #[serde(default)]
+    #[schema(value_type = Option<Vec<String>>, format = "uuid")]
+    pub denied_model_ids: Option<Vec<DeploymentId>>,
+    /// Highest priority (0-9) requests made with this key may ask for with the
+    /// `X-Priority` header. Defaults to 0; values above 0 require PlatformManager.
+    #[serde(default)]
+    pub max_priority: Option<i16>,
 }
 
 // API Key update.
@@ -69,6 +81,20 @@ pub struct ApiKeyUpdate {
     /// may reset them (this does not grant credits).
     #[serde(default)]
     pub reset_window: Option<bool>,
+    /// Model allowlist (see ApiKeyCreate.allowed_model_ids). Absent =
+    /// unchanged; explicit null = remove it; a list = replace it.
+    #[serde(default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Vec<String>>, format = "uuid")]
+    pub allowed_model_ids: Option<Option<Vec<DeploymentId>>>,
+    /// Model denylist (see ApiKeyCreate.denied_model_ids). Absent =
+    /// unchanged; explicit null = remove it; a list = replace it.
+    #[serde(default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Vec<String>>, format = "uuid")]
+    pub denied_model_ids: Option<Option<Vec<DeploymentId>>>,
+    /// Highest request priority (see ApiKeyCreate.max_priority). Absent =
+    /// unchanged. Values above 0 require PlatformManager.
+    #[serde(default)]
+    pub max_priority: Option<i16>,
 }
 
 // API Key response models
@@ -89,6 +115,14 @@ pub struct ApiKeyResponse {
     pub last_used: Option<DateTime<Utc>>,
     #[schema(value_type = Vec<String>)]
     pub model_access: Vec<DeploymentId>,
+    /// Models this key is restricted to (null = no allowlist)
+    #[schema(value_type = Option<Vec<String>>)]
+    pub allowed_model_ids: Option<Vec<DeploymentId>>,
+    /// Models this key may never use (null = no denylist)
+    #[schema(value_type = Option<Vec<String>>)]
+    pub denied_model_ids: Option<Vec<DeploymentId>>,
+    /// Highest priority requests made with this key may ask for
+    pub max_priority: i16,
     /// Per-API-key rate limit: requests per second (null = no limit)
     pub requests_per_second: Option<f32>,
     /// Per-API-key rate limit: maximum burst size (null = no limit)
@@ -96,7 +130,7 @@ pub struct ApiKeyResponse {
     /// Spending cap in credits (null = no cap)
     #[schema(value_type = Option<String>)]
     pub spend_limit: Option<Decimal>,
-    /// Cap reset period: null = one-off, else daily/weekly/monthly (calendar-aligned UTC)
+    /// Cap reset period: null = one-off, else daily/weekly/monthly (calendar-aligned in the owner's timezone)
     pub spend_limit_interval: Option<String>,
     /// Spend counted against the cap in the current window (null when never used / uncapped)
     #[schema(value_type = Option<String>)]
@@ -124,6 +158,14 @@ pub struct ApiKeyInfoResponse {
     pub last_used: Option<DateTime<Utc>>,
     #[schema(value_type = Vec<String>)]
     pub model_access: Vec<DeploymentId>,
+    /// Models this key is restricted to (null = no allowlist)
+    #[schema(value_type = Option<Vec<String>>)]
+    pub allowed_model_ids: Option<Vec<DeploymentId>>,
+    /// Models this key may never use (null = no denylist)
+    #[schema(value_type = Option<Vec<String>>)]
+    pub denied_model_ids: Option<Vec<DeploymentId>>,
+    /// Highest priority requests made with this key may ask for
+    pub max_priority: i16,
     /// Per-API-key rate limit: requests per second (null = no limit)
     pub requests_per_second: Option<f32>,
     /// Per-API-key rate limit: maximum burst size (null = no limit)
@@ -131,7 +173,7 @@ pub struct ApiKeyInfoResponse {
     /// Spending cap in credits (null = no cap)
     #[schema(value_type = Option<String>)]
     pub spend_limit: Option<Decimal>,
-    /// Cap reset period: null = one-off, else daily/weekly/monthly (calendar-aligned UTC)
+    /// Cap reset period: null = one-off, else daily/weekly/monthly (calendar-aligned in the owner's timezone)
     pub spend_limit_interval: Option<String>,
     /// Spend counted against the cap in the current window (null when never used / uncapped)
     #[schema(value_type = Option<String>)]
@@ -143,6 +185,40 @@ pub struct ApiKeyInfoResponse {
     pub resets_at: Option<DateTime<Utc>>,
 }
 
+/// New secret for the system API key.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct SystemApiKeyUpdate {
+    /// The secret to use: at least 32 characters with no whitespace. Omit it
+    /// to generate a new random secret.
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+/// The system API key's secret after an update.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SystemApiKeyResponse {
+    pub key: String,
+}
+
+/// PUT body — set the purposes a user may create API keys with, replacing
+/// what their roles allow.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKeyPurposesUpdate {
+    /// 'realtime' and/or 'platform'. Empty stops the user creating keys.
+    pub allowed: Vec<ApiKeyPurpose>,
+}
+
+/// The purposes a user may create API keys with
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyPurposesResponse {
+    pub allowed: Vec<ApiKeyPurpose>,
+    /// Purpose given to a key created without one
+    pub default: ApiKeyPurpose,
+    /// Whether `allowed` is an admin's override rather than what the user's roles allow
+    pub user_override: bool,
+}
+
 #[derive(Debug, Deserialize, IntoParams, ToSchema)]
 pub struct ListApiKeysQuery {
     /// Pagination parameters
@@ -151,6 +227,33 @@ pub struct ListApiKeysQuery {
     pub pagination: Pagination,
 }
 
+/// Time window for a per-key usage breakdown.
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct ApiKeyUsageQuery {
+    /// Start of the window (inclusive). Defaults to 30 days before `to`.
+    pub from: Option<DateTime<Utc>>,
+    /// End of the window (inclusive). Defaults to now.
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Successful requests, tokens and credits spent through a single API key.
+///
+/// Includes traffic on the key's hidden batch child, if it has one, so batch and
+/// flex usage of a capped key is attributed to it.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyUsageResponse {
+    #[schema(value_type = String, format = "uuid")]
+    pub api_key_id: ApiKeyId,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub total_request_count: i64,
+    pub total_input_tokens: i64,
+    pub total_output_tokens: i64,
+    /// Credits spent, as a decimal string
+    pub total_cost: String,
+    pub by_model: Vec<super::requests::ModelBreakdownEntry>,
+}
+
 impl From<ApiKeyDBResponse> for ApiKeyResponse {
     fn from(db: ApiKeyDBResponse) -> Self {
         Self {
@@ -164,6 +267,9 @@ impl From<ApiKeyDBResponse> for ApiKeyResponse {
             created_at: db.created_at,
             last_used: db.last_used,
             model_access: db.model_access,
+            allowed_model_ids: db.allowed_model_ids,
+            denied_model_ids: db.denied_model_ids,
+            max_priority: db.max_priority,
             requests_per_second: db.requests_per_second,
             burst_size: db.burst_size,
             spend_limit: db.spend_limit,
@@ -201,6 +307,9 @@ impl From<ApiKeyDBResponse> for ApiKeyInfoResponse {
             created_at: db.created_at,
             last_used: db.last_used,
             model_access: db.model_access,
+            allowed_model_ids: db.allowed_model_ids,
+            denied_model_ids: db.denied_model_ids,
+            max_priority: db.max_priority,
             requests_per_second: db.requests_per_second,
             burst_size: db.burst_size,
             spend_limit: db.spend_limit,
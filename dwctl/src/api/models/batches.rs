@@ -349,6 +349,13 @@ pub struct ListBatchesQuery {
     /// rows don't pollute the Batches view.
     #[param(example = "24h,1h")]
     pub completion_window: Option<String>,
+
+    /// When true, list batches removed by the retention policy instead of live ones.
+    /// Archived batches have `dwext.archived_at` set. Can be combined with pagination,
+    /// `include`, `created_after`, `created_before` and `completion_window`, but not with
+    /// `search`, `member_id` or `status`. Default: false.
+    #[serde(default)]
+    pub archived: bool,
 }
 
 /// Query parameters for batch results
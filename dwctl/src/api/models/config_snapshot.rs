@@ -0,0 +1,239 @@
+//! API models for platform configuration snapshots.
+//!
+//! A snapshot is the declarative form of the platform's routing configuration:
+//! inference endpoints, deployed models (with their tariffs and composite
+//! components) and groups with the models they grant. Entities reference each
+//! other by name or alias rather than ID, so a snapshot exported from one
+//! instance can be applied to another. Endpoint API keys and Bedrock credentials
+//! are never part of a snapshot.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use url::Url;
+use utoipa::ToSchema;
+
+use crate::api::models::deployments::TariffDefinition;
+use crate::body_transform::BodyTransformConfig;
+use crate::db::models::deployments::{
+    CreditExhaustionMode, FallbackConfig, LoadBalancingStrategy, Modality, ModelCatalogMetadata, ModelType, NLimitMode, PromptLengthUnit,
+    SystemPromptMode,
+};
+use crate::db::models::inference_endpoints::EndpointProtocol;
+use crate::reasoning::{ReasoningTranslationConfig, ReasoningTranslationOverrides};
+
+/// Snapshot format version written by export and required by import.
+pub const CONFIG_SNAPSHOT_VERSION: u32 = 1;
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_auth_header_name() -> String {
+    "Authorization".to_string()
+}
+
+fn default_auth_header_prefix() -> String {
+    "Bearer ".to_string()
+}
+
+/// The platform's endpoints, deployments and groups.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PlatformSnapshot {
+    /// Snapshot format version (currently 1)
+    pub version: u32,
+    #[serde(default)]
+    pub endpoints: Vec<EndpointSnapshot>,
+    #[serde(default)]
+    pub deployments: Vec<DeploymentSnapshot>,
+    #[serde(default)]
+    pub groups: Vec<GroupSnapshot>,
+}
+
+impl PlatformSnapshot {
+    /// Sort entries and nested lists into a canonical order, so two snapshots of
+    /// the same configuration compare equal.
+    pub fn normalize(&mut self) {
+        self.endpoints.sort_by(|a, b| a.name.cmp(&b.name));
+        self.deployments.sort_by(|a, b| a.alias.cmp(&b.alias));
+        for deployment in &mut self.deployments {
+            deployment
+                .tariffs
+                .sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.completion_window.cmp(&b.completion_window)));
+            deployment
+                .components
+                .sort_by(|a, b| a.sort_order.cmp(&b.sort_order).then_with(|| a.alias.cmp(&b.alias)));
+        }
+        self.groups.sort_by(|a, b| a.name.cmp(&b.name));
+        for group in &mut self.groups {
+            group.deployments.sort();
+        }
+    }
+}
+
+/// An inference endpoint, identified by its name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EndpointSnapshot {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[schema(value_type = String, format = "uri")]
+    pub url: Url,
+    /// Bedrock endpoints can be updated by import but not created, since their credentials are not in the snapshot
+    #[serde(default)]
+    pub protocol: EndpointProtocol,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_filter: Option<Vec<String>>,
+    #[serde(default = "default_auth_header_name")]
+    pub auth_header_name: String,
+    #[serde(default = "default_auth_header_prefix")]
+    pub auth_header_prefix: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_translation: Option<ReasoningTranslationConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_transform: Option<BodyTransformConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_sync_interval_seconds: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias_template: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrency: Option<i32>,
+}
+
+/// A deployed model, identified by its alias.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DeploymentSnapshot {
+    pub alias: String,
+    pub model_name: String,
+    /// Name of the endpoint hosting the model; omitted for composite models
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_type: Option<ModelType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_per_second: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst_size: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capacity: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_key_capacity: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub max_cost_per_request: Option<Decimal>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_modalities: Option<Vec<Modality>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_modalities: Option<Vec<Modality>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_capacity: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub throughput: Option<f32>,
+    /// Whether this is a composite model routing across `components`
+    #[serde(default)]
+    pub composite: bool,
+    /// Load balancing strategy (composite models only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lb_strategy: Option<LoadBalancingStrategy>,
+    #[serde(default = "FallbackConfig::new")]
+    pub fallback: FallbackConfig,
+    #[serde(default)]
+    pub sanitize_responses: bool,
+    #[serde(default)]
+    pub trusted: bool,
+    #[serde(default)]
+    pub allow_public: bool,
+    #[serde(default)]
+    pub require_approval: bool,
+    #[serde(default)]
+    pub rewrite_response_model: bool,
+    #[serde(default)]
+    pub disable_logging: bool,
+    #[serde(default)]
+    pub warmup: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    #[serde(default)]
+    pub system_prompt_mode: SystemPromptMode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_n: Option<i32>,
+    #[serde(default)]
+    pub max_n_mode: NLimitMode,
+    #[serde(default)]
+    pub credit_exhaustion_mode: CreditExhaustionMode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub max_overdraft: Option<Decimal>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_prompt_length: Option<i32>,
+    #[serde(default)]
+    pub max_prompt_length_unit: PromptLengthUnit,
+    #[serde(default = "default_true")]
+    pub open_responses_adapter: bool,
+    /// Reasoning translation overrides (standard models only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_translation_overrides: Option<ReasoningTranslationOverrides>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_batch_completion_windows: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ModelCatalogMetadata>,
+    /// Current tariffs. Tariffs that are removed are closed, not deleted.
+    #[serde(default)]
+    pub tariffs: Vec<TariffDefinition>,
+    /// Components of a composite model
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<ComponentSnapshot>,
+}
+
+/// A component of a composite model, referencing a standard model by alias.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ComponentSnapshot {
+    pub alias: String,
+    /// Relative weight for load balancing (1-100)
+    pub weight: i32,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Position for priority routing (lower = higher priority)
+    #[serde(default)]
+    pub sort_order: i32,
+}
+
+/// A group and the deployments it grants access to. User membership is not part of a snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GroupSnapshot {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Aliases of the deployments the group grants
+    #[serde(default)]
+    pub deployments: Vec<String>,
+}
+
+/// Number of entities of one kind that an import touched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ImportChangeCounts {
+    pub created: u32,
+    pub updated: u32,
+    pub deleted: u32,
+    pub unchanged: u32,
+}
+
+/// Result of importing a configuration snapshot.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ConfigImportResponse {
+    pub endpoints: ImportChangeCounts,
+    pub deployments: ImportChangeCounts,
+    pub groups: ImportChangeCounts,
+}
@@ -0,0 +1,53 @@
+//! API request/response models for deployment shadow traffic.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::db::models::deployment_shadows::{DeploymentShadowDBRequest, DeploymentShadowDBResponse};
+use crate::types::DeploymentId;
+
+/// PUT body — set a model's shadow, replacing any existing one
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DeploymentShadowUpdate {
+    /// Model that receives the copies
+    #[schema(value_type = String, format = "uuid")]
+    pub shadow_model_id: DeploymentId,
+    /// Fraction of the model's requests to copy, in (0, 1]
+    #[schema(example = 0.1)]
+    pub fraction: f64,
+}
+
+impl From<DeploymentShadowUpdate> for DeploymentShadowDBRequest {
+    fn from(update: DeploymentShadowUpdate) -> Self {
+        Self {
+            shadow_deployment_id: update.shadow_model_id,
+            fraction: update.fraction,
+        }
+    }
+}
+
+/// A model's shadow: where sampled copies of its requests are sent
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DeploymentShadowResponse {
+    #[schema(value_type = String, format = "uuid")]
+    pub shadow_model_id: DeploymentId,
+    /// Alias of the shadow model
+    pub shadow_alias: String,
+    pub fraction: f64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<DeploymentShadowDBResponse> for DeploymentShadowResponse {
+    fn from(db: DeploymentShadowDBResponse) -> Self {
+        Self {
+            shadow_model_id: db.shadow_deployment_id,
+            shadow_alias: db.shadow_alias,
+            fraction: db.fraction,
+            created_at: db.created_at,
+            updated_at: db.updated_at,
+        }
+    }
+}
@@ -50,7 +50,15 @@ pub struct DeployedModelEnricher<'a> {
     pub can_read_composite_info: bool,
 }
 
-type ProbeStatusTuple = (Option<Uuid>, bool, Option<i32>, Option<DateTime<Utc>>, Option<bool>, Option<f64>);
+type ProbeStatusTuple = (
+    Option<Uuid>,
+    bool,
+    Option<i32>,
+    Option<DateTime<Utc>>,
+    Option<bool>,
+    Option<bool>,
+    Option<f64>,
+);
 
 impl<'a> DeployedModelEnricher<'a> {
     /// Enriches multiple models in bulk with requested additional data.
@@ -325,13 +333,16 @@ impl<'a> DeployedModelEnricher<'a> {
         status_map: &Option<HashMap<DeploymentId, ProbeStatusTuple>>,
     ) -> DeployedModelResponse {
         if let Some(statuses) = status_map {
-            if let Some((probe_id, active, interval_seconds, last_check, last_success, uptime_percentage)) = statuses.get(&model.id) {
+            if let Some((probe_id, active, interval_seconds, last_check, last_success, healthy, uptime_percentage)) =
+                statuses.get(&model.id)
+            {
                 let status = ModelProbeStatus {
                     probe_id: *probe_id,
                     active: *active,
                     interval_seconds: *interval_seconds,
                     last_check: *last_check,
                     last_success: *last_success,
+                    healthy: *healthy,
                     uptime_percentage: *uptime_percentage,
                 };
                 model = model.with_status(status);
@@ -343,6 +354,7 @@ impl<'a> DeployedModelEnricher<'a> {
                     interval_seconds: None,
                     last_check: None,
                     last_success: None,
+                    healthy: None,
                     uptime_percentage: None,
                 };
                 model = model.with_status(status);
@@ -409,6 +421,7 @@ impl<'a> DeployedModelEnricher<'a> {
                     "CHAT" => Some(ModelType::Chat),
                     "EMBEDDINGS" => Some(ModelType::Embeddings),
                     "RERANKER" => Some(ModelType::Reranker),
+                    "MODERATION" => Some(ModelType::Moderation),
                     _ => None,
                 }),
                 endpoint: c.endpoint_id.map(|id| ComponentEndpointSummary {
@@ -450,6 +463,10 @@ mod tests {
             burst_size: Some(200),
             capacity: None,
             batch_capacity: None,
+            per_key_capacity: None,
+            max_cost_per_request: None,
+            input_modalities: None,
+            output_modalities: None,
             throughput: None,
             groups: None,
             metrics: None,
@@ -464,6 +481,20 @@ mod tests {
             components: None,
             sanitize_responses: None,
             trusted: None,
+            allow_public: None,
+            require_approval: None,
+            rewrite_response_model: None,
+            disable_logging: None,
+            warmup: None,
+            active: None,
+            system_prompt: None,
+            system_prompt_mode: None,
+            max_n: None,
+            max_n_mode: None,
+            credit_exhaustion_mode: None,
+            max_overdraft: None,
+            max_prompt_length: None,
+            max_prompt_length_unit: None,
             open_responses_adapter: None,
             reasoning_translation_overrides: None,
             supported_reasoning_efforts: None,
@@ -650,6 +681,16 @@ mod tests {
                 auth_header_name: "Authorization".to_string(),
                 auth_header_prefix: "Bearer ".to_string(),
                 reasoning_translation: None,
+                protocol: Default::default(),
+                bedrock: None,
+                region: None,
+                body_transform: None,
+                auto_sync_interval_seconds: None,
+                alias_template: None,
+                max_concurrency: None,
+                tls: None,
+                additional_api_key_count: 0,
+                api_key_rotation: Default::default(),
                 created_by: Uuid::new_v4(),
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
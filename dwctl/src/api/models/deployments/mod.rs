@@ -6,8 +6,9 @@ use super::pagination::Pagination;
 use crate::api::models::groups::GroupResponse;
 use crate::db::models::api_keys::ApiKeyPurpose;
 use crate::db::models::deployments::{
-    BackoffConfig, DeploymentDBResponse, FallbackConfig, JitterStrategy, LoadBalancingStrategy, ModelCatalogMetadata, ModelType,
-    ProviderPricing, ProviderPricingUpdate, TrafficRuleDBRow,
+    BackoffConfig, CreditExhaustionMode, DeploymentDBResponse, FallbackConfig, JitterStrategy, LoadBalancingStrategy, Modality,
+    ModelCatalogMetadata, ModelType, NLimitMode, PromptLengthUnit, ProviderPricing, ProviderPricingUpdate, SystemPromptMode,
+    TrafficRuleDBRow,
 };
 use crate::reasoning::{ReasoningTranslationOverrides, SupportedReasoningEfforts};
 use crate::types::{DeploymentId, InferenceEndpointId, UserId};
@@ -67,6 +68,9 @@ pub struct ModelListResponse {
     pub skip: i64,
     /// Maximum items returned per page
     pub limit: i64,
+    /// Cursor for the next page when using cursor pagination (absent on the last page)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
     /// Filter facets (only included when include=facets)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub facets: Option<ModelFacets>,
@@ -79,7 +83,8 @@ pub struct ListModelsQuery {
     #[serde(flatten)]
     #[param(inline)]
     pub pagination: Pagination,
-    /// Filter by inference endpoint ID
+    /// Filter by inference endpoint ID (also accepted as `endpoint_id`)
+    #[serde(alias = "endpoint_id")]
     #[param(value_type = Option<String>, format = "uuid")]
     #[schema(value_type = Option<String>, format = "uuid")]
     pub endpoint: Option<InferenceEndpointId>,
@@ -95,11 +100,13 @@ pub struct ListModelsQuery {
     pub accessible: Option<bool>,
     /// Search query to filter models by alias or model_name (case-insensitive substring match)
     pub search: Option<String>,
+    /// Filter to models whose alias contains this value (case-insensitive)
+    pub alias_contains: Option<String>,
     /// Filter by composite/virtual model status (true = composite only, false = non-composite only)
     pub is_composite: Option<bool>,
     /// Filter by provider name (case-insensitive exact match against metadata.provider)
     pub provider: Option<String>,
-    /// Filter by model type (CHAT, EMBEDDINGS, RERANKER)
+    /// Filter by model type (CHAT, EMBEDDINGS, RERANKER, MODERATION)
     pub model_type: Option<ModelType>,
     /// Filter by capability (returns models that have this capability)
     pub capability: Option<String>,
@@ -147,7 +154,7 @@ pub struct ModelMetrics {
 }
 
 /// Tariff definition for model creation/update
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct TariffDefinition {
     /// Tariff name (e.g., "Standard Pricing", "Premium Tier")
     pub name: String,
@@ -186,6 +193,17 @@ pub enum TrafficRoutingAction {
         /// The model alias to redirect traffic to
         target: String,
     },
+    /// Serve the request, overriding the model's `sanitize_responses` setting
+    Sanitize {
+        /// Whether responses are sanitized for this traffic kind
+        enabled: bool,
+    },
+    /// Serve the request from components whose endpoint is in this region,
+    /// falling back cross-region. A client region hint header takes precedence.
+    PreferRegion {
+        /// The endpoint region label to prefer
+        region: String,
+    },
 }
 
 /// The data required to create a new model (standard or composite).
@@ -200,6 +218,25 @@ pub enum DeployedModelCreate {
 
 /// Data for creating a standard model (backed by a single endpoint)
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "type": "standard",
+    "model_name": "meta-llama/Llama-3.1-8B-Instruct",
+    "alias": "llama-3.1-8b",
+    "display_name": "Llama 3.1 8B Instruct",
+    "hosted_on": "7c9e6679-7425-40de-944b-e07fc1f90ae7",
+    "description": "Fast general-purpose chat model",
+    "model_type": "CHAT",
+    "capabilities": ["text"],
+    "requests_per_second": 10.0,
+    "burst_size": 20,
+    "capacity": 32,
+    "tariffs": [{
+        "name": "Realtime",
+        "input_price_per_token": "0.0000002",
+        "output_price_per_token": "0.0000006",
+        "api_key_purpose": "realtime"
+    }]
+}))]
 pub struct StandardModelCreate {
     /// The actual model identifier (e.g., "gpt-4", "claude-3-sonnet")
     pub model_name: String,
@@ -222,6 +259,19 @@ pub struct StandardModelCreate {
     pub burst_size: Option<i32>,
     /// Maximum number of concurrent requests allowed for this model (null = no limit)
     pub capacity: Option<i32>,
+    /// Maximum concurrent requests any single API key may hold against this model, so one caller can't take all of `capacity` (null = no per-key limit)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_key_capacity: Option<i32>,
+    /// Most a single request may cost in credits, judged from `max_tokens` and the output price before forwarding and from accrued tokens while streaming (null = no limit)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub max_cost_per_request: Option<rust_decimal::Decimal>,
+    /// Modalities the model accepts (text, image, audio); requests with content of any other modality are rejected with a 422 (null = undeclared, not checked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_modalities: Option<Vec<Modality>>,
+    /// Modalities the model produces; requests asking for any other output modality are rejected with a 422 (null = undeclared, not checked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_modalities: Option<Vec<Modality>>,
     /// Maximum number of concurrent batch requests allowed for this model (null = defaults to capacity or no limit)
     pub batch_capacity: Option<i32>,
     /// Throughput in requests/second for batch capacity calculations (null = use config default)
@@ -236,6 +286,46 @@ pub struct StandardModelCreate {
     /// Whether to mark provider as trusted in strict mode (defaults to false, used when strict_mode=true)
     #[serde(default)]
     pub trusted: Option<bool>,
+    /// Whether every user may use this model without being in one of its groups (defaults to false)
+    #[serde(default)]
+    pub allow_public: Option<bool>,
+    /// Whether users need an approved access request before they can use this model (defaults to false)
+    #[serde(default)]
+    pub require_approval: Option<bool>,
+    /// Whether to rewrite the response `model` field to the requested alias (defaults to false, used when strict_mode=false)
+    #[serde(default)]
+    pub rewrite_response_model: Option<bool>,
+    /// Whether to keep request and response bodies out of request logs (defaults to false)
+    #[serde(default)]
+    pub disable_logging: Option<bool>,
+    /// Whether to send a warmup request when the model becomes active (defaults to false)
+    #[serde(default)]
+    pub warmup: Option<bool>,
+    /// System prompt injected into the model's chat requests before forwarding (null = none)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// How the system prompt is combined with a system message the client sent (defaults to prepend)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt_mode: Option<SystemPromptMode>,
+    /// Largest number of completions (`n`) a request may ask for (null = no cap)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_n: Option<i32>,
+    /// How requests asking for more than max_n completions are handled (defaults to reject)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_n_mode: Option<NLimitMode>,
+    /// What happens when a stream costs more than the caller's remaining credits (defaults to complete)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credit_exhaustion_mode: Option<CreditExhaustionMode>,
+    /// How far below zero a stream may take the caller's balance in complete mode, in credits (null = no limit)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub max_overdraft: Option<rust_decimal::Decimal>,
+    /// Longest prompt a request may send, in max_prompt_length_unit (null = no limit)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_prompt_length: Option<i32>,
+    /// What max_prompt_length counts (defaults to tokens)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_prompt_length_unit: Option<PromptLengthUnit>,
     /// Whether to enable the open_responses adapter that converts /v1/responses to /v1/chat/completions (defaults to true)
     #[serde(default)]
     pub open_responses_adapter: Option<bool>,
@@ -292,6 +382,19 @@ pub struct CompositeModelCreate {
     pub burst_size: Option<i32>,
     /// Maximum number of concurrent requests allowed for this model (null = no limit)
     pub capacity: Option<i32>,
+    /// Maximum concurrent requests any single API key may hold against this model, so one caller can't take all of `capacity` (null = no per-key limit)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_key_capacity: Option<i32>,
+    /// Most a single request may cost in credits, judged from `max_tokens` and the output price before forwarding and from accrued tokens while streaming (null = no limit)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub max_cost_per_request: Option<rust_decimal::Decimal>,
+    /// Modalities the model accepts (text, image, audio); requests with content of any other modality are rejected with a 422 (null = undeclared, not checked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_modalities: Option<Vec<Modality>>,
+    /// Modalities the model produces; requests asking for any other output modality are rejected with a 422 (null = undeclared, not checked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_modalities: Option<Vec<Modality>>,
     /// Maximum number of concurrent batch requests allowed for this model (null = defaults to capacity or no limit)
     pub batch_capacity: Option<i32>,
     /// Throughput in requests/second for batch capacity calculations (null = use config default)
@@ -345,6 +448,46 @@ pub struct CompositeModelCreate {
     /// Whether to mark provider as trusted in strict mode (defaults to false, used when strict_mode=true)
     #[serde(default)]
     pub trusted: Option<bool>,
+    /// Whether every user may use this model without being in one of its groups (defaults to false)
+    #[serde(default)]
+    pub allow_public: Option<bool>,
+    /// Whether users need an approved access request before they can use this model (defaults to false)
+    #[serde(default)]
+    pub require_approval: Option<bool>,
+    /// Whether to rewrite the response `model` field to the requested alias (defaults to false, used when strict_mode=false)
+    #[serde(default)]
+    pub rewrite_response_model: Option<bool>,
+    /// Whether to keep request and response bodies out of request logs (defaults to false)
+    #[serde(default)]
+    pub disable_logging: Option<bool>,
+    /// Whether to send a warmup request when the model becomes active (defaults to false)
+    #[serde(default)]
+    pub warmup: Option<bool>,
+    /// System prompt injected into the model's chat requests before forwarding (null = none)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// How the system prompt is combined with a system message the client sent (defaults to prepend)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt_mode: Option<SystemPromptMode>,
+    /// Largest number of completions (`n`) a request may ask for (null = no cap)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_n: Option<i32>,
+    /// How requests asking for more than max_n completions are handled (defaults to reject)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_n_mode: Option<NLimitMode>,
+    /// What happens when a stream costs more than the caller's remaining credits (defaults to complete)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credit_exhaustion_mode: Option<CreditExhaustionMode>,
+    /// How far below zero a stream may take the caller's balance in complete mode, in credits (null = no limit)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub max_overdraft: Option<rust_decimal::Decimal>,
+    /// Longest prompt a request may send, in max_prompt_length_unit (null = no limit)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_prompt_length: Option<i32>,
+    /// What max_prompt_length counts (defaults to tokens)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_prompt_length_unit: Option<PromptLengthUnit>,
     /// Whether to enable the open_responses adapter that converts /v1/responses to /v1/chat/completions (defaults to true)
     #[serde(default)]
     pub open_responses_adapter: Option<bool>,
@@ -383,6 +526,13 @@ fn default_backoff_factor() -> f64 {
 
 /// The data required to update a specific model.
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "display_name": "Llama 3.1 8B Instruct (v2)",
+    "description": "Fast general-purpose chat model",
+    "requests_per_second": 20.0,
+    "burst_size": null,
+    "capacity": 64
+}))]
 pub struct DeployedModelUpdate {
     pub alias: Option<String>,
     /// Human-readable display name for the model catalog
@@ -399,6 +549,19 @@ pub struct DeployedModelUpdate {
     /// Maximum concurrent requests (null = no change, Some(None) = remove limit, Some(Some(n)) = set limit)
     #[serde(default, skip_serializing_if = "Option::is_none", with = "double_option")]
     pub capacity: Option<Option<i32>>,
+    /// Maximum concurrent requests per API key (null = no change, Some(None) = remove limit, Some(Some(n)) = set limit)
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "double_option")]
+    pub per_key_capacity: Option<Option<i32>>,
+    /// Maximum cost of a single request in credits (null = no change, Some(None) = remove limit, Some(Some(n)) = set limit)
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "double_option")]
+    #[schema(value_type = Option<String>)]
+    pub max_cost_per_request: Option<Option<rust_decimal::Decimal>>,
+    /// Modalities the model accepts (null = no change, Some(None) = undeclare, Some(Some(list)) = set)
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "double_option")]
+    pub input_modalities: Option<Option<Vec<Modality>>>,
+    /// Modalities the model produces (null = no change, Some(None) = undeclare, Some(Some(list)) = set)
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "double_option")]
+    pub output_modalities: Option<Option<Vec<Modality>>>,
     /// Maximum concurrent batch requests (null = no change, Some(None) = remove limit, Some(Some(n)) = set limit)
     #[serde(default, skip_serializing_if = "Option::is_none", with = "double_option")]
     pub batch_capacity: Option<Option<i32>>,
@@ -455,6 +618,48 @@ pub struct DeployedModelUpdate {
     /// Whether to mark provider as trusted in strict mode (null = no change, used when strict_mode=true)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub trusted: Option<bool>,
+    /// Whether every user may use this model without group membership (null = no change).
+    /// Turning it off also removes the model from the public "everyone" group.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_public: Option<bool>,
+    /// Whether users need an approved access request before they can use this model (null = no change).
+    /// Turning it on doesn't revoke anyone's approval; approvals from an earlier period still count.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_approval: Option<bool>,
+    /// Whether to rewrite the response `model` field to the requested alias (null = no change, used when strict_mode=false)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rewrite_response_model: Option<bool>,
+    /// Whether to keep request and response bodies out of request logs (null = no change)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disable_logging: Option<bool>,
+    /// Whether to send a warmup request when the model becomes active (null = no change)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warmup: Option<bool>,
+    /// System prompt injected into chat requests (null = no change, Some(None) = remove, Some(Some(prompt)) = set)
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "double_option")]
+    pub system_prompt: Option<Option<String>>,
+    /// How the system prompt is combined with a client's system message (null = no change)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt_mode: Option<SystemPromptMode>,
+    /// Cap on completions per request (null = no change, Some(None) = remove, Some(Some(n)) = set)
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "double_option")]
+    pub max_n: Option<Option<i32>>,
+    /// How requests asking for more than max_n completions are handled (null = no change)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_n_mode: Option<NLimitMode>,
+    /// What happens when a stream costs more than the caller's remaining credits (null = no change)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credit_exhaustion_mode: Option<CreditExhaustionMode>,
+    /// Overdraft limit for streams (null = no change, Some(None) = remove, Some(Some(x)) = set)
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "double_option")]
+    #[schema(value_type = Option<String>)]
+    pub max_overdraft: Option<Option<rust_decimal::Decimal>>,
+    /// Prompt length limit (null = no change, Some(None) = remove, Some(Some(n)) = set)
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "double_option")]
+    pub max_prompt_length: Option<Option<i32>>,
+    /// What max_prompt_length counts (null = no change)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_prompt_length_unit: Option<PromptLengthUnit>,
     /// Whether to enable the open_responses adapter (null = no change)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub open_responses_adapter: Option<bool>,
@@ -489,11 +694,31 @@ pub struct ModelProbeStatus {
     pub interval_seconds: Option<i32>,
     pub last_check: Option<DateTime<Utc>>,
     pub last_success: Option<bool>,
+    /// Health after applying the probe's failure/success thresholds and recovery cooldown
+    pub healthy: Option<bool>,
     pub uptime_percentage: Option<f64>,
 }
 
 /// API response for a deployed model
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "id": "3fa85f64-5717-4562-b3fc-2c963f66afa6",
+    "model_name": "meta-llama/Llama-3.1-8B-Instruct",
+    "alias": "llama-3.1-8b",
+    "display_name": "Llama 3.1 8B Instruct",
+    "description": "Fast general-purpose chat model",
+    "model_type": "CHAT",
+    "capabilities": ["text"],
+    "created_by": "550e8400-e29b-41d4-a716-446655440000",
+    "hosted_on": "7c9e6679-7425-40de-944b-e07fc1f90ae7",
+    "created_at": "2025-01-15T10:30:00Z",
+    "updated_at": "2025-01-20T14:45:00Z",
+    "requests_per_second": 10.0,
+    "burst_size": 20,
+    "capacity": 32,
+    "is_composite": false,
+    "sanitize_responses": true
+}))]
 pub struct DeployedModelResponse {
     #[schema(value_type = String, format = "uuid")]
     pub id: DeploymentId,
@@ -523,6 +748,19 @@ pub struct DeployedModelResponse {
     /// Maximum number of concurrent requests allowed for this model (null = no limit)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub capacity: Option<i32>,
+    /// Maximum concurrent requests any single API key may hold against this model (null = no per-key limit)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_key_capacity: Option<i32>,
+    /// Most a single request to this model may cost, in credits (null = no limit)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub max_cost_per_request: Option<rust_decimal::Decimal>,
+    /// Modalities the model accepts (null = undeclared)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_modalities: Option<Vec<Modality>>,
+    /// Modalities the model produces (null = undeclared)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_modalities: Option<Vec<Modality>>,
     /// Maximum number of concurrent batch requests allowed for this model (null = defaults to capacity or no limit)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub batch_capacity: Option<i32>,
@@ -570,6 +808,49 @@ pub struct DeployedModelResponse {
     /// Whether to mark provider as trusted in strict mode (used when strict_mode=true)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub trusted: Option<bool>,
+    /// Whether every user may use this model without group membership
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_public: Option<bool>,
+    /// Whether users need an approved access request before they can use this model
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_approval: Option<bool>,
+    /// Whether the response `model` field is rewritten to the requested alias (used when strict_mode=false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rewrite_response_model: Option<bool>,
+    /// Whether request and response bodies are kept out of request logs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disable_logging: Option<bool>,
+    /// Whether a warmup request is sent when the model becomes active
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warmup: Option<bool>,
+    /// Whether the model serves requests (false after it is deactivated)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active: Option<bool>,
+    /// System prompt injected into the model's chat requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// How the system prompt is combined with a client's system message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt_mode: Option<SystemPromptMode>,
+    /// Largest number of completions (`n`) a request may ask for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_n: Option<i32>,
+    /// How requests asking for more than max_n completions are handled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_n_mode: Option<NLimitMode>,
+    /// What happens when a stream costs more than the caller's remaining credits
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credit_exhaustion_mode: Option<CreditExhaustionMode>,
+    /// How far below zero a stream may take the caller's balance in complete mode, in credits
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub max_overdraft: Option<rust_decimal::Decimal>,
+    /// Longest prompt a request may send, in max_prompt_length_unit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_prompt_length: Option<i32>,
+    /// What max_prompt_length counts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_prompt_length_unit: Option<PromptLengthUnit>,
     /// Whether the open_responses adapter is enabled (converts /v1/responses to /v1/chat/completions)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub open_responses_adapter: Option<bool>,
@@ -629,6 +910,10 @@ impl From<DeploymentDBResponse> for DeployedModelResponse {
             requests_per_second: db.requests_per_second,
             burst_size: db.burst_size,
             capacity: db.capacity,
+            per_key_capacity: db.per_key_capacity,
+            max_cost_per_request: db.max_cost_per_request,
+            input_modalities: db.input_modalities,
+            output_modalities: db.output_modalities,
             batch_capacity: db.batch_capacity,
             throughput: db.throughput,
             groups: None,           // By default, relationships are not included
@@ -644,6 +929,20 @@ impl From<DeploymentDBResponse> for DeployedModelResponse {
             components: None, // By default, components are not included
             sanitize_responses: Some(db.sanitize_responses),
             trusted: Some(db.trusted),
+            allow_public: Some(db.allow_public),
+            require_approval: Some(db.require_approval),
+            rewrite_response_model: Some(db.rewrite_response_model),
+            disable_logging: Some(db.disable_logging),
+            warmup: Some(db.warmup),
+            active: Some(db.active),
+            system_prompt: db.system_prompt,
+            system_prompt_mode: Some(db.system_prompt_mode),
+            max_n: db.max_n,
+            max_n_mode: Some(db.max_n_mode),
+            credit_exhaustion_mode: Some(db.credit_exhaustion_mode),
+            max_overdraft: db.max_overdraft,
+            max_prompt_length: db.max_prompt_length,
+            max_prompt_length_unit: Some(db.max_prompt_length_unit),
             open_responses_adapter: Some(db.open_responses_adapter),
             reasoning_translation_overrides: if db.is_composite {
                 None
@@ -702,6 +1001,7 @@ impl DeployedModelResponse {
     /// Mask capacity information (sets to None for users without permission)
     pub fn mask_capacity(mut self) -> Self {
         self.capacity = None;
+        self.per_key_capacity = None;
         self.batch_capacity = None;
         self.throughput = None;
         self
@@ -726,6 +1026,18 @@ impl DeployedModelResponse {
     pub fn mask_response_config(mut self) -> Self {
         self.sanitize_responses = None;
         self.trusted = None;
+        self.rewrite_response_model = None;
+        self.disable_logging = None;
+        self.warmup = None;
+        self.active = None;
+        self.system_prompt = None;
+        self.system_prompt_mode = None;
+        self.max_n = None;
+        self.max_n_mode = None;
+        self.credit_exhaustion_mode = None;
+        self.max_overdraft = None;
+        self.max_prompt_length = None;
+        self.max_prompt_length_unit = None;
         self.open_responses_adapter = None;
         self.reasoning_translation_overrides = None;
         self
@@ -755,8 +1067,8 @@ impl DeployedModelResponse {
     ///
     /// Tariffs with `api_key_purpose = None` (the implicit fallback price)
     /// are kept regardless of deny rules — they aren't bound to a specific
-    /// purpose. Redirect rules are also ignored: those re-route access, not
-    /// block it.
+    /// purpose. Redirect, sanitize and prefer-region rules are also ignored: those change
+    /// how traffic is served, not whether it is allowed.
     pub fn filter_denied_purpose_tariffs(mut self) -> Self {
         if let (Some(rules), Some(tariffs)) = (self.traffic_routing_rules.as_ref(), self.tariffs.as_mut()) {
             // Denied purpose set is small (at most one per ApiKeyPurpose
@@ -810,6 +1122,10 @@ impl DeployedModelResponse {
                             "redirect" => TrafficRoutingAction::Redirect {
                                 target: r.redirect_target_alias.unwrap_or_default(),
                             },
+                            "sanitize" => TrafficRoutingAction::Sanitize {
+                                enabled: r.sanitize_responses?,
+                            },
+                            "prefer_region" => TrafficRoutingAction::PreferRegion { region: r.prefer_region? },
                             _ => return None,
                         };
                         Some(TrafficRoutingRule {
@@ -824,6 +1140,54 @@ impl DeployedModelResponse {
     }
 }
 
+/// Request to clone a deployed model under a new alias
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DeployedModelClone {
+    /// Alias of the new deployment
+    pub alias: String,
+    /// Display name of the new deployment (defaults to the source's)
+    pub display_name: Option<String>,
+    /// Also add the new deployment to every group the source belongs to
+    #[serde(default)]
+    pub copy_groups: bool,
+}
+
+/// Query parameters for estimating the cost of a request to a model
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct RequestCostEstimateQuery {
+    /// API key purpose whose tariff prices the request (falls back to realtime, then the default tariff)
+    pub api_key_purpose: Option<ApiKeyPurpose>,
+    /// Completion window of the batch tariff, e.g. "24h"
+    pub completion_window: Option<String>,
+}
+
+/// Estimated cost of a request to a model, at its current tariff
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RequestCostEstimate {
+    #[schema(value_type = String, format = "uuid")]
+    pub id: DeploymentId,
+    pub alias: String,
+    pub input_tokens: u64,
+    /// Whether input_tokens was estimated from the prompt's length rather than counted by the tokenizer
+    pub input_tokens_estimated: bool,
+    /// Completions the request asks for: its `n`, at most the model's `max_n`
+    pub completions: u64,
+    /// Output tokens of every completion at the model's recent average length
+    pub typical_output_tokens: u64,
+    /// Output tokens if every completion reaches the request's token cap, or
+    /// fills the model's context window without one. `null` when neither bounds it.
+    pub max_output_tokens: Option<u64>,
+    #[schema(value_type = String)]
+    pub input_price_per_token: rust_decimal::Decimal,
+    #[schema(value_type = String)]
+    pub output_price_per_token: rust_decimal::Decimal,
+    #[schema(value_type = String)]
+    pub typical_cost: rust_decimal::Decimal,
+    /// Cost at max_output_tokens; `null` when the output isn't bounded
+    #[schema(value_type = Option<String>)]
+    pub worst_case_cost: Option<rust_decimal::Decimal>,
+}
+
 // ===== Composite Model Component Types =====
 
 /// Request to add a component to a composite model
@@ -896,6 +1260,98 @@ pub struct ComponentEndpointSummary {
     pub name: String,
 }
 
+/// Placeholder for credentials in [`ResolvedModelConfig`].
+pub const MASKED_SECRET: &str = "********";
+
+/// The onwards routing config a model resolves to, as the proxy would load it
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ResolvedModelConfig {
+    #[schema(value_type = String, format = "uuid")]
+    pub id: DeploymentId,
+    pub alias: String,
+    /// Number of API keys allowed to call the model
+    pub authorized_key_count: usize,
+    /// The onwards target (providers, strategy, fallback, rate limits and routing
+    /// rules), with upstream credentials masked and the authorized keys left out.
+    /// `null` when the proxy doesn't route the model, e.g. because it is deleted
+    /// or its endpoint's credentials can't be resolved.
+    pub target: Option<serde_json::Value>,
+}
+
+impl ResolvedModelConfig {
+    pub fn new(id: DeploymentId, alias: String, spec: Option<onwards::target::TargetSpecOrList>) -> serde_json::Result<Self> {
+        use onwards::target::TargetSpecOrList;
+
+        fn mask(
+            onwards_key: &mut Option<String>,
+            sigv4: &mut Option<onwards::sigv4::SigV4Config>,
+            tls: &mut Option<onwards::tls::TlsConfig>,
+            key_rotation: &mut Option<onwards::key_rotation::KeyRotationConfig>,
+        ) {
+            if let Some(key) = onwards_key {
+                *key = MASKED_SECRET.to_string();
+            }
+            if let Some(sigv4) = sigv4 {
+                sigv4.secret_access_key = MASKED_SECRET.to_string();
+            }
+            if let Some(client_key) = tls.as_mut().and_then(|tls| tls.client_key.as_mut()) {
+                *client_key = MASKED_SECRET.to_string();
+            }
+            for key in key_rotation.iter_mut().flat_map(|rotation| rotation.keys.iter_mut()) {
+                *key = MASKED_SECRET.to_string();
+            }
+        }
+
+        let mut authorized_key_count = 0;
+        let target = match spec {
+            Some(mut spec) => {
+                match &mut spec {
+                    TargetSpecOrList::Pool(pool) => {
+                        authorized_key_count = pool.keys.take().map_or(0, |keys| keys.len());
+                        for provider in &mut pool.providers {
+                            mask(
+                                &mut provider.onwards_key,
+                                &mut provider.sigv4,
+                                &mut provider.tls,
+                                &mut provider.key_rotation,
+                            );
+                        }
+                    }
+                    TargetSpecOrList::List(targets) => {
+                        for target in targets {
+                            authorized_key_count = authorized_key_count.max(target.keys.take().map_or(0, |keys| keys.len()));
+                            mask(
+                                &mut target.onwards_key,
+                                &mut target.sigv4,
+                                &mut target.tls,
+                                &mut target.key_rotation,
+                            );
+                        }
+                    }
+                    TargetSpecOrList::Single(target) => {
+                        authorized_key_count = target.keys.take().map_or(0, |keys| keys.len());
+                        mask(
+                            &mut target.onwards_key,
+                            &mut target.sigv4,
+                            &mut target.tls,
+                            &mut target.key_rotation,
+                        );
+                    }
+                }
+                Some(serde_json::to_value(spec)?)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            id,
+            alias,
+            authorized_key_count,
+            target,
+        })
+    }
+}
+
 /// Response for a composite model component
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ModelComponentResponse {
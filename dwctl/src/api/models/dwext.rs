@@ -33,6 +33,11 @@ pub struct BatchDwExtResponse {
     /// Sync operation ID that created this batch (when source = "sync").
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub sync_id: Option<String>,
+
+    /// When the batch was archived by the retention policy (Unix timestamp).
+    /// Its files and per-request results have been deleted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived_at: Option<i64>,
 }
 
 impl BatchDwExtResponse {
@@ -43,5 +48,6 @@ impl BatchDwExtResponse {
             && self.source_id.is_none()
             && self.source_file.is_none()
             && self.sync_id.is_none()
+            && self.archived_at.is_none()
     }
 }
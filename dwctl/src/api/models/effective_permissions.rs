@@ -0,0 +1,65 @@
+//! API response models for the current user's effective permissions.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::models::users::Role;
+use crate::db::models::api_keys::ApiKeyPurpose;
+use crate::db::models::deployments::ModelType;
+use crate::types::{DeploymentId, UserId};
+
+/// A model the user's API keys can call
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccessibleModel {
+    #[schema(value_type = String, format = "uuid")]
+    pub id: DeploymentId,
+    pub alias: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_type: Option<ModelType>,
+    /// Key purposes that may call the model (those not denied by a traffic rule)
+    pub purposes: Vec<ApiKeyPurpose>,
+}
+
+/// Which level a limit comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitSource {
+    /// An admin's override for this user
+    UserOverride,
+    /// The highest default among the user's groups
+    GroupDefault,
+    /// The platform's verified or unverified tier
+    Tier,
+}
+
+/// Limits applied to each of the user's API keys. A key with its own rate
+/// limit uses that instead of a group default or tier; a user override
+/// applies regardless. `null` means unlimited.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct EffectiveRateLimits {
+    pub requests_per_second: Option<f32>,
+    pub burst_size: Option<i32>,
+    pub concurrency_limit: Option<i32>,
+    /// Where `requests_per_second` and `burst_size` come from
+    pub rate_source: Option<RateLimitSource>,
+    /// Where `concurrency_limit` comes from
+    pub concurrency_source: Option<RateLimitSource>,
+}
+
+/// What the current user can do: their roles, the models their keys can
+/// call, their balance and their rate limits
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EffectivePermissionsResponse {
+    #[schema(value_type = String, format = "uuid")]
+    pub user_id: UserId,
+    /// Whether this user has legacy admin privileges
+    pub is_admin: bool,
+    pub roles: Vec<Role>,
+    /// Models reachable through group membership or public access, excluding
+    /// models that require approval the user hasn't been granted
+    pub models: Vec<AccessibleModel>,
+    /// Current credit balance (omitted without permission to read own credits)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credit_balance: Option<f64>,
+    pub rate_limits: EffectiveRateLimits,
+}
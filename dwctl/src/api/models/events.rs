@@ -0,0 +1,96 @@
+//! API models for the configuration change event stream.
+
+use axum::response::sse::Event;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::types::{DeploymentId, InferenceEndpointId};
+
+/// A deployed model as reported on the event stream.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct DeploymentSummary {
+    #[schema(value_type = String, format = "uuid")]
+    pub id: DeploymentId,
+    pub alias: String,
+    pub model_name: String,
+    /// Endpoint hosting the model; `null` for composite models.
+    #[schema(value_type = Option<String>, format = "uuid")]
+    pub endpoint_id: Option<InferenceEndpointId>,
+    /// Health of the model's active probe (see the probe's thresholds); `null` when it has no result yet.
+    pub healthy: Option<bool>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// An inference endpoint as reported on the event stream.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct EndpointSummary {
+    #[schema(value_type = String, format = "uuid")]
+    pub id: InferenceEndpointId,
+    pub name: String,
+    pub url: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Current deployments and endpoints, sent when a client connects.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ConfigSnapshot {
+    pub deployments: Vec<DeploymentSummary>,
+    pub endpoints: Vec<EndpointSummary>,
+}
+
+/// A model's probe turned healthy or unhealthy.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct EndpointHealthChange {
+    #[schema(value_type = Option<String>, format = "uuid")]
+    pub endpoint_id: Option<InferenceEndpointId>,
+    #[schema(value_type = String, format = "uuid")]
+    pub deployment_id: DeploymentId,
+    pub alias: String,
+    pub healthy: Option<bool>,
+}
+
+/// One event on `GET /events`. The SSE event name is given by [`ConfigEvent::name`]
+/// and the data is the variant's payload as JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigEvent {
+    Snapshot(ConfigSnapshot),
+    DeploymentCreated(DeploymentSummary),
+    DeploymentUpdated(DeploymentSummary),
+    DeploymentDeleted(DeploymentSummary),
+    EndpointCreated(EndpointSummary),
+    EndpointUpdated(EndpointSummary),
+    EndpointDeleted(EndpointSummary),
+    EndpointHealthChanged(EndpointHealthChange),
+}
+
+impl ConfigEvent {
+    /// SSE event name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Snapshot(_) => "snapshot",
+            Self::DeploymentCreated(_) => "deployment.created",
+            Self::DeploymentUpdated(_) => "deployment.updated",
+            Self::DeploymentDeleted(_) => "deployment.deleted",
+            Self::EndpointCreated(_) => "endpoint.created",
+            Self::EndpointUpdated(_) => "endpoint.updated",
+            Self::EndpointDeleted(_) => "endpoint.deleted",
+            Self::EndpointHealthChanged(_) => "endpoint.health_changed",
+        }
+    }
+
+    /// Render as an SSE event.
+    pub fn to_sse_event(&self) -> Result<Event, axum::Error> {
+        let event = Event::default().event(self.name());
+        match self {
+            Self::Snapshot(snapshot) => event.json_data(snapshot),
+            Self::DeploymentCreated(deployment) | Self::DeploymentUpdated(deployment) | Self::DeploymentDeleted(deployment) => {
+                event.json_data(deployment)
+            }
+            Self::EndpointCreated(endpoint) | Self::EndpointUpdated(endpoint) | Self::EndpointDeleted(endpoint) => {
+                event.json_data(endpoint)
+            }
+            Self::EndpointHealthChanged(change) => event.json_data(change),
+        }
+    }
+}
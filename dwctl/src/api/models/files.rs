@@ -56,6 +56,48 @@ pub struct FileCostEstimateQuery {
     pub completion_window: Option<String>,
 }
 
+/// How a batch file upload treats lines that fail validation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FileValidationMode {
+    /// Reject the whole file on the first invalid line
+    #[default]
+    Strict,
+    /// Accept the valid lines and report the invalid ones
+    Lenient,
+}
+
+/// Query parameters for file upload
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct UploadFileQuery {
+    /// How to treat invalid lines: `strict` rejects the file, `lenient` skips
+    /// them and lists them in the response's `validation` report. Defaults to
+    /// the server's configured mode (normally `strict`).
+    pub validation: Option<FileValidationMode>,
+}
+
+/// A line of an uploaded file that failed validation
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LineValidationError {
+    /// 1-based line number in the file
+    #[schema(example = 3)]
+    pub line: u64,
+    #[schema(example = "Model 'gpt-5' has not been configured or is not available to user")]
+    pub message: String,
+}
+
+/// Per-line outcome of validating an uploaded file
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct FileValidationReport {
+    pub mode: FileValidationMode,
+    /// Lines accepted as requests
+    pub valid_lines: u64,
+    /// Lines skipped because they failed validation (lenient mode only)
+    pub invalid_lines: u64,
+    /// The skipped lines in file order, up to the first 1000; `invalid_lines` has the full count
+    pub errors: Vec<LineValidationError>,
+}
+
 /// File object response (OpenAI-compatible)
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[schema(example = json!({
@@ -100,6 +142,10 @@ pub struct FileResponse {
     /// Name of the source connection (e.g. "prod-s3-inputs")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source_name: Option<String>,
+
+    /// Per-line validation report, returned by lenient uploads only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validation: Option<FileValidationReport>,
 }
 
 /// Object type - always "file"
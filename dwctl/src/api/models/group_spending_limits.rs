@@ -0,0 +1,66 @@
+//! API request/response models for per-group spending limits.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::db::models::group_spending_limits::{GroupSpendingLimitDBRequest, GroupSpendingLimitDBResponse};
+
+/// PUT body — set a group's spending limit, replacing any existing one
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GroupSpendingLimitUpdate {
+    /// Credits the group's members can spend together per window
+    #[schema(value_type = String, example = "500")]
+    pub spending_limit: Decimal,
+    /// Window reset period: null = one-off (spend since the limit was set), or
+    /// 'daily' / 'weekly' / 'monthly' on CALENDAR-ALIGNED boundaries in UTC
+    #[serde(default)]
+    #[schema(example = "monthly")]
+    pub spending_limit_interval: Option<String>,
+}
+
+impl From<GroupSpendingLimitUpdate> for GroupSpendingLimitDBRequest {
+    fn from(update: GroupSpendingLimitUpdate) -> Self {
+        Self {
+            spending_limit: update.spending_limit,
+            spending_limit_interval: update.spending_limit_interval,
+        }
+    }
+}
+
+/// A group's spending limit and what its members have spent against it
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GroupUsageResponse {
+    #[schema(value_type = String)]
+    pub spending_limit: Decimal,
+    pub spending_limit_interval: Option<String>,
+    /// Spend by the group's members in the current window
+    #[schema(value_type = String)]
+    pub spent: Decimal,
+    /// Credits left in the current window; 0 once the limit is reached
+    #[schema(value_type = String)]
+    pub remaining: Decimal,
+    /// Whether the members' keys are currently cut off from priced models
+    pub limit_reached: bool,
+    /// Next window reset; null for one-off limits
+    pub resets_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<GroupSpendingLimitDBResponse> for GroupUsageResponse {
+    fn from(db: GroupSpendingLimitDBResponse) -> Self {
+        Self {
+            remaining: (db.spending_limit - db.spent).max(Decimal::ZERO),
+            limit_reached: db.spent >= db.spending_limit,
+            spending_limit: db.spending_limit,
+            spending_limit_interval: db.spending_limit_interval,
+            spent: db.spent,
+            resets_at: db.resets_at,
+            created_at: db.created_at,
+            updated_at: db.updated_at,
+        }
+    }
+}
@@ -3,7 +3,7 @@
 use super::pagination::Pagination;
 use crate::api::models::deployments::DeployedModelResponse;
 use crate::api::models::users::UserResponse;
-use crate::db::models::groups::GroupDBResponse;
+use crate::db::models::groups::{GroupDBResponse, MembershipAddOutcome};
 use crate::types::{GroupId, UserId};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -33,6 +33,10 @@ pub struct GroupCreate {
     /// Optional description of the group's purpose
     #[schema(example = "Backend and frontend engineers")]
     pub description: Option<String>,
+    /// Access template whose models the new group is granted
+    #[serde(default)]
+    #[schema(value_type = Option<String>, format = "uuid")]
+    pub template_id: Option<uuid::Uuid>,
 }
 
 /// Request body for updating an existing group. All fields are optional;
@@ -112,3 +116,63 @@ impl GroupResponse {
         self
     }
 }
+
+/// Request body for adding many users or models to a group at once.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BatchMembershipRequest {
+    /// User IDs (for `users:batch`) or deployment IDs (for `models:batch`) to add.
+    /// Duplicates are ignored.
+    #[schema(value_type = Vec<String>, format = "uuid")]
+    pub ids: Vec<uuid::Uuid>,
+}
+
+/// Outcome of adding one member in a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchMembershipStatus {
+    /// Added to the group
+    Added,
+    /// Already in the group; nothing changed
+    AlreadyMember,
+    /// No such user or model
+    NotFound,
+}
+
+impl From<MembershipAddOutcome> for BatchMembershipStatus {
+    fn from(outcome: MembershipAddOutcome) -> Self {
+        match outcome {
+            MembershipAddOutcome::Added => Self::Added,
+            MembershipAddOutcome::AlreadyMember => Self::AlreadyMember,
+            MembershipAddOutcome::NotFound => Self::NotFound,
+        }
+    }
+}
+
+/// Per-item result of a batch membership operation
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BatchMembershipResult {
+    /// The requested user or deployment ID
+    #[schema(value_type = String, format = "uuid")]
+    pub id: uuid::Uuid,
+    pub status: BatchMembershipStatus,
+}
+
+/// Response for a batch membership operation, with one result per distinct requested ID in request order.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BatchMembershipResponse {
+    pub results: Vec<BatchMembershipResult>,
+}
+
+impl From<Vec<(uuid::Uuid, MembershipAddOutcome)>> for BatchMembershipResponse {
+    fn from(outcomes: Vec<(uuid::Uuid, MembershipAddOutcome)>) -> Self {
+        Self {
+            results: outcomes
+                .into_iter()
+                .map(|(id, outcome)| BatchMembershipResult {
+                    id,
+                    status: outcome.into(),
+                })
+                .collect(),
+        }
+    }
+}
@@ -1,7 +1,8 @@
 //! API request/response models for inference endpoints.
 
 use super::pagination::Pagination;
-use crate::db::models::inference_endpoints::InferenceEndpointDBResponse;
+use crate::body_transform::BodyTransformConfig;
+use crate::db::models::inference_endpoints::{ApiKeyRotation, EndpointProtocol, InferenceEndpointDBResponse};
 use crate::reasoning::ReasoningTranslationConfig;
 use crate::types::{InferenceEndpointId, UserId};
 use chrono::{DateTime, Utc};
@@ -108,8 +109,67 @@ pub struct ListEndpointsQuery {
     pub pagination: Pagination,
 }
 
+/// Trailing window that endpoint statistics are computed over
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum StatisticsWindow {
+    #[serde(rename = "1h")]
+    OneHour,
+    #[default]
+    #[serde(rename = "24h")]
+    OneDay,
+    #[serde(rename = "7d")]
+    SevenDays,
+    #[serde(rename = "30d")]
+    ThirtyDays,
+}
+
+impl StatisticsWindow {
+    pub fn duration(self) -> chrono::Duration {
+        match self {
+            Self::OneHour => chrono::Duration::hours(1),
+            Self::OneDay => chrono::Duration::days(1),
+            Self::SevenDays => chrono::Duration::days(7),
+            Self::ThirtyDays => chrono::Duration::days(30),
+        }
+    }
+}
+
+/// Query parameters for endpoint statistics
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct EndpointStatisticsQuery {
+    /// Window to aggregate over: `1h`, `24h` (default), `7d` or `30d`
+    #[serde(default)]
+    pub window: StatisticsWindow,
+}
+
+/// Latency and error statistics for real traffic to an endpoint's models
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EndpointStatistics {
+    pub endpoint_id: InferenceEndpointId,
+    pub window: StatisticsWindow,
+    /// Requests to models hosted on the endpoint within the window
+    pub request_count: i64,
+    /// Requests that returned a 5xx status
+    pub error_count: i64,
+    /// `error_count / request_count`, or 0 when there were no requests
+    pub error_rate: f64,
+    /// Latency percentiles in milliseconds, null when there were no requests
+    pub p50_latency_ms: Option<f64>,
+    pub p90_latency_ms: Option<f64>,
+    pub p99_latency_ms: Option<f64>,
+}
+
 // Request models
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "name": "OpenAI",
+    "description": "Production OpenAI account",
+    "url": "https://api.openai.com/v1",
+    "api_key": "sk-...",
+    "model_filter": ["gpt-4o", "gpt-4o-mini"],
+    "alias_mapping": {"gpt-4o": "openai-gpt-4o"},
+    "sync": true
+}))]
 pub struct InferenceEndpointCreate {
     pub name: String,
     pub description: Option<String>,
@@ -135,6 +195,90 @@ pub struct InferenceEndpointCreate {
     /// Default provider mapping for canonical reasoning controls.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub reasoning_translation: Option<ReasoningTranslationConfig>,
+    /// How requests to the endpoint are authenticated (defaults to "openai")
+    #[serde(default)]
+    pub protocol: EndpointProtocol,
+    /// AWS credentials, required when protocol is "bedrock". Bedrock endpoints
+    /// serve the model IDs listed in model_filter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bedrock: Option<BedrockCredentials>,
+    /// Region label (e.g. "eu-west"). Requests carrying a matching region hint
+    /// prefer this endpoint's components, falling back cross-region.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    /// Declarative request/response body edits for quirky upstreams.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_transform: Option<BodyTransformConfig>,
+    /// Re-sync the endpoint's models in the background this often (minimum 60).
+    /// Omit to only sync on demand.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_sync_interval_seconds: Option<i32>,
+    /// Template for the aliases of models created by syncing the endpoint, e.g.
+    /// "{name}" to strip provider prefixes like "bytedance-seed/". `{model}` is
+    /// the full model id and `{name}` the id without its prefix. Omit to use
+    /// the model id. Entries in alias_mapping take precedence.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias_template: Option<String>,
+    /// Most concurrent requests allowed across all of the endpoint's
+    /// deployments, e.g. to respect a provider account limit. Omit for no
+    /// endpoint-wide limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrency: Option<i32>,
+    /// Custom CA bundle and client certificate for connecting to the endpoint,
+    /// e.g. a self-hosted provider behind a private CA or requiring mutual TLS.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<EndpointTlsSettings>,
+    /// More keys for the endpoint. Requests rotate between these and api_key,
+    /// and a key answered with a 401 or 429 is left out for a while.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub additional_api_keys: Vec<String>,
+    /// How requests pick between the keys (defaults to "round_robin")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_rotation: Option<ApiKeyRotation>,
+}
+
+/// AWS credentials used to SigV4-sign requests to a Bedrock endpoint
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct BedrockCredentials {
+    /// AWS region, e.g. "us-east-1"
+    pub region: String,
+    pub access_key_id: String,
+    /// Stored encrypted; never returned by the API
+    pub secret_access_key: String,
+}
+
+// Manual Debug so the secret is never logged
+impl std::fmt::Debug for BedrockCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BedrockCredentials")
+            .field("region", &self.region)
+            .field("access_key_id", &self.access_key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// TLS settings for connecting to an endpoint. Certificates and keys are PEM text.
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct EndpointTlsSettings {
+    /// CA certificates to trust in addition to the system roots
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<String>,
+    /// Client certificate chain presented for mutual TLS; requires client_key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_cert: Option<String>,
+    /// PKCS#8 private key for client_cert. Stored encrypted; never returned by the API
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<String>,
+}
+
+// Manual Debug so the private key is never logged
+impl std::fmt::Debug for EndpointTlsSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EndpointTlsSettings")
+            .field("ca_bundle", &self.ca_bundle)
+            .field("client_cert", &self.client_cert)
+            .finish_non_exhaustive()
+    }
 }
 
 fn default_sync() -> bool {
@@ -142,6 +286,10 @@ fn default_sync() -> bool {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "description": "Production OpenAI account (rotated key)",
+    "api_key": "sk-..."
+}))]
 pub struct InferenceEndpointUpdate {
     pub name: Option<String>,
     pub description: Option<String>,
@@ -157,6 +305,35 @@ pub struct InferenceEndpointUpdate {
     /// Endpoint reasoning default (omitted = unchanged, null = clear).
     #[serde(default, skip_serializing_if = "Option::is_none", with = "double_option")]
     pub reasoning_translation: Option<Option<ReasoningTranslationConfig>>,
+    /// Replace the AWS credentials of a Bedrock endpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bedrock: Option<BedrockCredentials>,
+    /// Region label (omitted = unchanged, null = clear).
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "double_option")]
+    pub region: Option<Option<String>>,
+    /// Body transform (omitted = unchanged, null = clear).
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "double_option")]
+    pub body_transform: Option<Option<BodyTransformConfig>>,
+    /// Background model sync interval in seconds (omitted = unchanged, null = disable).
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "double_option")]
+    pub auto_sync_interval_seconds: Option<Option<i32>>,
+    /// Alias template for newly synced models (omitted = unchanged, null = clear).
+    /// Existing deployments keep their aliases.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "double_option")]
+    pub alias_template: Option<Option<String>>,
+    /// Endpoint-wide concurrency limit (omitted = unchanged, null = remove the limit).
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "double_option")]
+    pub max_concurrency: Option<Option<i32>>,
+    /// Replace the endpoint's TLS settings (omitted = unchanged, null = clear).
+    /// The client key must be sent again to keep the client certificate.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "double_option")]
+    pub tls: Option<Option<EndpointTlsSettings>>,
+    /// Replace the keys rotated between with api_key (omitted = unchanged, [] = stop rotating).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub additional_api_keys: Option<Vec<String>>,
+    /// How requests pick between the keys
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_rotation: Option<ApiKeyRotation>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -183,8 +360,62 @@ pub struct InferenceEndpointValidateResponse {
     pub error: Option<String>,
 }
 
+/// Which inference API a credential test calls
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialTestKind {
+    /// A one-token `/chat/completions` request
+    #[default]
+    Chat,
+    /// A one-word `/embeddings` request
+    Embeddings,
+}
+
+/// Credentials to test with a real inference request. Nothing is saved.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InferenceEndpointCredentialTest {
+    /// Base URL of the endpoint, e.g. "https://api.openai.com/v1"
+    pub url: String,
+    pub api_key: Option<String>,
+    /// The name of the authorization header (defaults to "Authorization")
+    pub auth_header_name: Option<String>,
+    /// The prefix for the authorization header value (defaults to "Bearer " with trailing space)
+    pub auth_header_prefix: Option<String>,
+    /// Upstream model to call
+    pub model: String,
+    /// API to call (defaults to chat)
+    #[serde(default)]
+    pub kind: CredentialTestKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InferenceEndpointCredentialTestResponse {
+    pub status: String, // "success" | "error"
+    /// Time until the upstream responded, in milliseconds
+    pub latency_ms: u64,
+    /// HTTP status returned by the upstream; absent if it couldn't be reached
+    pub status_code: Option<u16>,
+    /// Model named in the upstream's response
+    pub model: Option<String>,
+    pub error: Option<String>,
+}
+
 // Response model
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "id": "7c9e6679-7425-40de-944b-e07fc1f90ae7",
+    "name": "OpenAI",
+    "description": "Production OpenAI account",
+    "url": "https://api.openai.com/v1",
+    "model_filter": ["gpt-4o", "gpt-4o-mini"],
+    "requires_api_key": true,
+    "auth_header_name": "Authorization",
+    "auth_header_prefix": "Bearer ",
+    "protocol": "openai",
+    "created_by": "550e8400-e29b-41d4-a716-446655440000",
+    "created_at": "2025-01-15T10:30:00Z",
+    "updated_at": "2025-01-15T10:30:00Z"
+}))]
 pub struct InferenceEndpointResponse {
     #[schema(value_type = String, format = "uuid")]
     pub id: InferenceEndpointId,
@@ -197,12 +428,51 @@ pub struct InferenceEndpointResponse {
     pub auth_header_prefix: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reasoning_translation: Option<ReasoningTranslationConfig>,
+    pub protocol: EndpointProtocol,
+    /// AWS region and access key ID of a Bedrock endpoint (the secret is never returned)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bedrock: Option<BedrockEndpointInfo>,
+    /// Region label used for region-aware routing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    /// Request/response body edits applied to this endpoint's models
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body_transform: Option<BodyTransformConfig>,
+    /// Interval in seconds at which the endpoint's models are re-synced in the background
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_sync_interval_seconds: Option<i32>,
+    /// Template for the aliases of models created by syncing the endpoint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias_template: Option<String>,
+    /// Most concurrent requests allowed across all of the endpoint's deployments
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_concurrency: Option<i32>,
+    /// CA bundle and client certificate used to connect (the client key is never returned)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<EndpointTlsInfo>,
+    /// Number of keys rotated between with the main key (the keys are never returned)
+    pub additional_api_key_count: usize,
+    pub api_key_rotation: ApiKeyRotation,
     #[schema(value_type = String, format = "uuid")]
     pub created_by: UserId,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BedrockEndpointInfo {
+    pub region: String,
+    pub access_key_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EndpointTlsInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_cert: Option<String>,
+}
+
 impl From<InferenceEndpointDBResponse> for InferenceEndpointResponse {
     fn from(db: InferenceEndpointDBResponse) -> Self {
         Self {
@@ -211,10 +481,26 @@ impl From<InferenceEndpointDBResponse> for InferenceEndpointResponse {
             description: db.description,
             url: db.url.to_string(),
             model_filter: db.model_filter,
-            requires_api_key: db.api_key.is_some() && !db.api_key.as_ref().unwrap().is_empty(),
+            requires_api_key: db.api_key.as_ref().is_some_and(|key| !key.is_empty()) || !db.additional_api_keys.is_empty(),
             auth_header_name: db.auth_header_name,
             auth_header_prefix: db.auth_header_prefix,
             reasoning_translation: db.reasoning_translation,
+            protocol: db.protocol,
+            bedrock: db.bedrock.map(|b| BedrockEndpointInfo {
+                region: b.region,
+                access_key_id: b.access_key_id,
+            }),
+            region: db.region,
+            body_transform: db.body_transform,
+            auto_sync_interval_seconds: db.auto_sync_interval_seconds,
+            alias_template: db.alias_template,
+            max_concurrency: db.max_concurrency,
+            tls: db.tls.map(|tls| EndpointTlsInfo {
+                ca_bundle: tls.ca_bundle,
+                client_cert: tls.client_cert,
+            }),
+            additional_api_key_count: db.additional_api_keys.len(),
+            api_key_rotation: db.api_key_rotation,
             created_by: db.created_by,
             created_at: db.created_at,
             updated_at: db.updated_at,
@@ -0,0 +1,49 @@
+//! API request/response models for maintenance mode.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::inference::maintenance::MaintenanceStatus;
+use crate::types::UserId;
+
+/// Default `Retry-After` returned to AI API clients, in seconds.
+pub const DEFAULT_RETRY_AFTER_SECONDS: i32 = 300;
+
+/// Request body for enabling or disabling maintenance mode.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MaintenanceModeUpdate {
+    /// Whether `/ai/v1/*` requests are rejected with a 503.
+    pub enabled: bool,
+    /// Message returned to AI API clients. A generic message is used when omitted.
+    #[serde(default)]
+    pub message: Option<String>,
+    /// Value of the `Retry-After` header, in seconds. Defaults to 300.
+    #[serde(default)]
+    pub retry_after_seconds: Option<i32>,
+}
+
+/// Current maintenance mode state.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MaintenanceModeResponse {
+    pub enabled: bool,
+    pub message: Option<String>,
+    pub retry_after_seconds: i32,
+    /// User who last changed the state, if any.
+    #[schema(value_type = Option<String>, format = "uuid")]
+    pub updated_by: Option<UserId>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<MaintenanceStatus> for MaintenanceModeResponse {
+    fn from(status: MaintenanceStatus) -> Self {
+        Self {
+            enabled: status.enabled,
+            message: status.message,
+            retry_after_seconds: status.retry_after_seconds,
+            updated_by: status.updated_by,
+            updated_at: status.updated_at,
+        }
+    }
+}
@@ -2,23 +2,33 @@
 //!
 //! This module contains the data structures used for HTTP request deserialization
 //! and response serialization. These models define the public API contract.
+pub mod access_requests;
+pub mod access_templates;
 pub mod api_keys;
 pub mod auth;
 pub mod batch_requests;
 pub mod batches;
 pub mod cache_pricing;
+pub mod config_snapshot;
 pub mod connections;
 pub mod daemons;
+pub mod deployment_shadows;
 pub mod deployments;
 pub mod dwext;
+pub mod effective_permissions;
+pub mod events;
 pub mod files;
+pub mod group_spending_limits;
 pub mod groups;
 pub mod inference_endpoints;
+pub mod maintenance;
 pub mod organizations;
 pub mod pagination;
 pub mod probes;
 pub mod provider_display_configs;
+pub mod rate_limits;
 pub mod requests;
+pub mod sessions;
 pub mod tariffs;
 pub mod tool_sources;
 pub mod transactions;
@@ -43,6 +43,9 @@ pub struct OrganizationUpdate {
     /// Account-wide zero-data-retention flag. Admin-only: only callers with
     /// UpdateAll on organizations may set this. Omit to leave unchanged.
     pub zero_data_retention: Option<bool>,
+    /// IANA timezone name (e.g. `Europe/Berlin`) whose calendar day, week and
+    /// month the organization's API key spending caps follow. Omit to leave unchanged.
+    pub timezone: Option<String>,
 }
 
 /// Full organization details returned by the API.
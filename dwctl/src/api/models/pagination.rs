@@ -2,10 +2,15 @@
 //!
 //! This module provides standardized pagination for all admin API endpoints.
 //! All endpoints use offset-based pagination with `skip` and `limit` parameters.
+//! The user, group and model listings additionally accept a `cursor` for keyset
+//! pagination, which stays stable while rows are inserted or deleted between pages.
 
 use serde::{Deserialize, Serialize};
 use serde_with::{DisplayFromStr, serde_as};
 use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::errors::Error;
 
 /// Default number of items to return per page.
 pub const DEFAULT_LIMIT: i64 = 10;
@@ -34,6 +39,20 @@ pub struct Pagination {
     #[param(default = 10, minimum = 1, maximum = 100)]
     #[serde_as(as = "Option<DisplayFromStr>")]
     pub limit: Option<i64>,
+
+    /// Cursor for keyset pagination, on endpoints that support it. When present, results are
+    /// ordered by id and `skip` is ignored. Pass an empty value for the first page, then the
+    /// `next_cursor` of each response.
+    pub cursor: Option<String>,
+}
+
+/// Position within an id-ordered listing for keyset pagination.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdCursor {
+    /// The first page
+    Start,
+    /// Rows whose id sorts strictly after this one
+    After(Uuid),
 }
 
 impl Pagination {
@@ -55,6 +74,30 @@ impl Pagination {
     pub fn params(&self) -> (i64, i64) {
         (self.skip(), self.limit())
     }
+
+    /// Parse the keyset cursor, if the request asked for cursor pagination.
+    pub fn cursor(&self) -> Result<Option<IdCursor>, Error> {
+        match self.cursor.as_deref().map(str::trim) {
+            None => Ok(None),
+            Some("") => Ok(Some(IdCursor::Start)),
+            Some(cursor) => Uuid::parse_str(cursor)
+                .map(|id| Some(IdCursor::After(id)))
+                .map_err(|_| Error::BadRequest {
+                    message: format!("Invalid pagination cursor '{cursor}'"),
+                }),
+        }
+    }
+}
+
+/// Trim a cursor-mode page fetched with one extra row (the N+1 pattern) back to `limit`,
+/// returning the cursor for the next page if there is one.
+pub fn take_cursor_page<T>(items: &mut Vec<T>, limit: i64, id: impl Fn(&T) -> Uuid) -> Option<String> {
+    let limit = limit.max(0) as usize;
+    if items.len() <= limit {
+        return None;
+    }
+    items.truncate(limit);
+    items.last().map(|item| id(item).to_string())
 }
 
 /// Generic paginated response wrapper for list endpoints.
@@ -71,6 +114,9 @@ pub struct PaginatedResponse<T: ToSchema> {
     pub skip: i64,
     /// Maximum items returned per page
     pub limit: i64,
+    /// Cursor for the next page when using cursor pagination (absent on the last page)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 impl<T: ToSchema> PaginatedResponse<T> {
@@ -81,8 +127,15 @@ impl<T: ToSchema> PaginatedResponse<T> {
             total_count,
             skip,
             limit,
+            next_cursor: None,
         }
     }
+
+    /// Attach the cursor for the next page
+    pub fn with_next_cursor(mut self, next_cursor: Option<String>) -> Self {
+        self.next_cursor = next_cursor;
+        self
+    }
 }
 
 /// Default limit for cursor-based pagination (OpenAI batch API compatible).
@@ -141,6 +194,7 @@ mod tests {
         let p = Pagination {
             skip: None,
             limit: Some(0),
+            cursor: None,
         };
         assert_eq!(p.limit(), 1);
 
@@ -148,6 +202,7 @@ mod tests {
         let p = Pagination {
             skip: None,
             limit: Some(-5),
+            cursor: None,
         };
         assert_eq!(p.limit(), 1);
 
@@ -155,6 +210,7 @@ mod tests {
         let p = Pagination {
             skip: None,
             limit: Some(1000),
+            cursor: None,
         };
         assert_eq!(p.limit(), MAX_LIMIT);
 
@@ -162,6 +218,7 @@ mod tests {
         let p = Pagination {
             skip: None,
             limit: Some(50),
+            cursor: None,
         };
         assert_eq!(p.limit(), 50);
     }
@@ -172,6 +229,7 @@ mod tests {
         let p = Pagination {
             skip: Some(-10),
             limit: None,
+            cursor: None,
         };
         assert_eq!(p.skip(), 0);
 
@@ -179,6 +237,7 @@ mod tests {
         let p = Pagination {
             skip: Some(100),
             limit: None,
+            cursor: None,
         };
         assert_eq!(p.skip(), 100);
     }
@@ -188,10 +247,42 @@ mod tests {
         let p = Pagination {
             skip: Some(20),
             limit: Some(50),
+            cursor: None,
         };
         assert_eq!(p.params(), (20, 50));
     }
 
+    #[test]
+    fn test_keyset_cursor_parsing() {
+        let id = Uuid::new_v4();
+        let p: Pagination = serde_urlencoded::from_str("limit=5").unwrap();
+        assert_eq!(p.cursor().unwrap(), None);
+
+        let p: Pagination = serde_urlencoded::from_str("limit=5&cursor=").unwrap();
+        assert_eq!(p.cursor().unwrap(), Some(IdCursor::Start));
+
+        let p: Pagination = serde_urlencoded::from_str(&format!("limit=5&cursor={id}")).unwrap();
+        assert_eq!(p.cursor().unwrap(), Some(IdCursor::After(id)));
+
+        let p: Pagination = serde_urlencoded::from_str("cursor=not-a-cursor").unwrap();
+        assert!(matches!(p.cursor(), Err(Error::BadRequest { .. })));
+    }
+
+    #[test]
+    fn test_take_cursor_page() {
+        let ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+
+        // An extra row means there is another page, starting after the last kept row
+        let mut page = ids.clone();
+        assert_eq!(take_cursor_page(&mut page, 2, |id| *id), Some(ids[1].to_string()));
+        assert_eq!(page, ids[..2]);
+
+        // No extra row means this is the last page
+        let mut page = ids.clone();
+        assert_eq!(take_cursor_page(&mut page, 3, |id| *id), None);
+        assert_eq!(page, ids);
+    }
+
     #[test]
     fn test_cursor_default_values() {
         let p = CursorPagination::default();
@@ -1,7 +1,9 @@
 //! API request/response models for health probes.
 
+use crate::db::models::probes::{ProbeAssertions, ProbeType};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_with::rust::double_option;
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
@@ -25,14 +27,38 @@ pub struct CreateProbe {
     pub request_path: Option<String>,
     /// JSON body to send with the probe request
     pub request_body: Option<serde_json::Value>,
+    /// Assertions the response must satisfy for the probe to pass
+    #[serde(default)]
+    pub assertions: Option<ProbeAssertions>,
+    /// What the probe sends and how its response is judged (defaults to `liveness`)
+    #[serde(default)]
+    pub probe_type: ProbeType,
+    /// For `chat_completion` probes, the `model` the response must report
+    pub expected_model: Option<String>,
+    /// Consecutive failures that mark a healthy probe unhealthy (defaults to 1)
+    #[serde(default)]
+    pub failure_threshold: Option<i32>,
+    /// Consecutive successes that mark an unhealthy probe healthy again (defaults to 1)
+    #[serde(default)]
+    pub success_threshold: Option<i32>,
+    /// Minimum seconds a probe stays unhealthy before it can recover (defaults to 0)
+    #[serde(default)]
+    pub recovery_cooldown_seconds: Option<i32>,
 }
 
+/// Default consecutive failures that mark a probe unhealthy.
+pub const DEFAULT_FAILURE_THRESHOLD: i32 = 1;
+/// Default consecutive successes that mark a probe healthy again.
+pub const DEFAULT_SUCCESS_THRESHOLD: i32 = 1;
+/// Default seconds a probe stays unhealthy before it can recover.
+pub const DEFAULT_RECOVERY_COOLDOWN_SECONDS: i32 = 0;
+
 fn default_http_method() -> String {
     "POST".to_string()
 }
 
 /// Request payload for testing a probe configuration
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
 pub struct TestProbeRequest {
     /// HTTP method to use for the test request
     pub http_method: Option<String>,
@@ -40,6 +66,14 @@ pub struct TestProbeRequest {
     pub request_path: Option<String>,
     /// JSON body to send with the test request
     pub request_body: Option<serde_json::Value>,
+    /// Assertions the test response must satisfy
+    #[serde(default)]
+    pub assertions: Option<ProbeAssertions>,
+    /// Probe type to test (defaults to `liveness`)
+    #[serde(default)]
+    pub probe_type: ProbeType,
+    /// For `chat_completion` probes, the `model` the response must report
+    pub expected_model: Option<String>,
 }
 
 /// Query parameters for filtering probes
@@ -88,12 +122,27 @@ pub struct UpdateProbeRequest {
     pub request_path: Option<String>,
     /// Update the request body
     pub request_body: Option<serde_json::Value>,
+    /// Update the response assertions. Set to null to remove them; omit to
+    /// leave them unchanged.
+    #[serde(default, with = "double_option")]
+    pub assertions: Option<Option<ProbeAssertions>>,
+    /// Update the probe type
+    pub probe_type: Option<ProbeType>,
+    /// Update the model `chat_completion` responses must report
+    pub expected_model: Option<String>,
+    /// Update the consecutive failures that mark the probe unhealthy
+    pub failure_threshold: Option<i32>,
+    /// Update the consecutive successes that mark the probe healthy again
+    pub success_threshold: Option<i32>,
+    /// Update the minimum seconds the probe stays unhealthy before it can recover
+    pub recovery_cooldown_seconds: Option<i32>,
 }
 
 /// Aggregated statistics for a probe over a time period.
 ///
 /// Statistics are calculated from stored probe results and include
-/// percentile metrics for response times.
+/// percentile metrics for response times. Ranges reaching past the raw
+/// retention window also include hourly aggregates of downsampled results.
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ProbeStatistics {
     /// Total number of probe executions
@@ -110,7 +159,8 @@ pub struct ProbeStatistics {
     pub min_response_time_ms: Option<i32>,
     /// Maximum response time in milliseconds
     pub max_response_time_ms: Option<i32>,
-    /// 50th percentile (median) response time
+    /// 50th percentile (median) response time. Percentiles are computed from
+    /// raw results only and exclude downsampled history.
     pub p50_response_time_ms: Option<f64>,
     /// 95th percentile response time
     pub p95_response_time_ms: Option<f64>,
@@ -0,0 +1,57 @@
+//! API request/response models for user rate-limit overrides and group
+//! rate-limit defaults.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::db::models::rate_limits::{RateLimitsDBRequest, RateLimitsDBResponse};
+
+/// PUT body — set a user's or group's limits, replacing any existing ones.
+/// An omitted or `null` field falls through to the next level of precedence.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitsUpdate {
+    /// Sustained requests per second for each of the user's API keys
+    #[schema(example = 50.0)]
+    pub requests_per_second: Option<f32>,
+    /// Requests a key can make at once before the sustained rate applies.
+    /// Requires `requests_per_second`.
+    #[schema(example = 100)]
+    pub burst_size: Option<i32>,
+    /// Concurrent requests for each of the user's API keys
+    #[schema(example = 20)]
+    pub concurrency_limit: Option<i32>,
+}
+
+impl From<RateLimitsUpdate> for RateLimitsDBRequest {
+    fn from(update: RateLimitsUpdate) -> Self {
+        Self {
+            requests_per_second: update.requests_per_second,
+            burst_size: update.burst_size,
+            concurrency_limit: update.concurrency_limit,
+        }
+    }
+}
+
+/// A user's or group's limits
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RateLimitsResponse {
+    pub requests_per_second: Option<f32>,
+    pub burst_size: Option<i32>,
+    pub concurrency_limit: Option<i32>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<RateLimitsDBResponse> for RateLimitsResponse {
+    fn from(db: RateLimitsDBResponse) -> Self {
+        Self {
+            requests_per_second: db.requests_per_second,
+            burst_size: db.burst_size,
+            concurrency_limit: db.concurrency_limit,
+            created_at: db.created_at,
+            updated_at: db.updated_at,
+        }
+    }
+}
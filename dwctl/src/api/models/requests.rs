@@ -32,6 +32,8 @@ pub enum ApiAiResponse {
     Embeddings(serde_json::Value),
     Responses(serde_json::Value),
     ResponsesStream(serde_json::Value),
+    Rerank(serde_json::Value),
+    Moderation(serde_json::Value),
     Other(serde_json::Value),
 }
 
@@ -59,6 +61,8 @@ impl From<&AiResponse> for ApiAiResponse {
             AiResponse::Base64Embeddings(resp) => ApiAiResponse::Embeddings(serde_json::to_value(resp).unwrap_or_default()),
             AiResponse::Responses(resp) => ApiAiResponse::Responses(serde_json::to_value(resp).unwrap_or_default()),
             AiResponse::ResponsesStream(events) => ApiAiResponse::ResponsesStream(serde_json::to_value(events).unwrap_or_default()),
+            AiResponse::Rerank(resp) => ApiAiResponse::Rerank(serde_json::to_value(resp).unwrap_or_default()),
+            AiResponse::Moderation(resp) => ApiAiResponse::Moderation(serde_json::to_value(resp).unwrap_or_default()),
             AiResponse::Other(val) => ApiAiResponse::Other(val.clone()),
         }
     }
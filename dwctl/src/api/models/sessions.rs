@@ -0,0 +1,51 @@
+//! API request/response models for users' login sessions.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::db::models::sessions::SessionDBResponse;
+use crate::types::UserIdOrCurrent;
+
+/// Path parameters for a user's sessions
+#[derive(Debug, Deserialize)]
+pub struct UserSessionsPathParams {
+    pub user_id: UserIdOrCurrent,
+}
+
+/// Path parameters for one of a user's sessions
+#[derive(Debug, Deserialize)]
+pub struct SessionPathParams {
+    pub user_id: UserIdOrCurrent,
+    pub session_id: Uuid,
+}
+
+/// An active login session
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SessionResponse {
+    #[schema(value_type = String, format = "uuid")]
+    pub id: Uuid,
+    /// The User-Agent of the browser that logged in
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl From<SessionDBResponse> for SessionResponse {
+    fn from(session: SessionDBResponse) -> Self {
+        Self {
+            id: session.id,
+            user_agent: session.user_agent,
+            created_at: session.created_at,
+            expires_at: session.expires_at,
+        }
+    }
+}
+
+/// Result of revoking all of a user's sessions
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RevokeSessionsResponse {
+    /// How many active sessions were revoked
+    pub revoked: u64,
+}
@@ -25,7 +25,7 @@ pub struct TariffResponse {
     #[schema(value_type = String)]
     pub output_price_per_token: Decimal,
     /// Optional API key purpose this tariff applies to (realtime, batch, playground)
-    /// If null, tariff is not automatically applied
+    /// If null, this is the model's default tariff, applied to purposes without their own
     pub api_key_purpose: Option<ApiKeyPurpose>,
     /// Optional completion window for batch tariffs (e.g., "24h", "1h")
     /// Only applicable when api_key_purpose is Batch
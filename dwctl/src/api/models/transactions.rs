@@ -2,6 +2,7 @@
 
 use super::pagination::Pagination;
 use crate::{
+    config::CreditsConfig,
     db::models::credits::{CreditTransactionDBResponse, CreditTransactionType},
     types::UserId,
 };
@@ -30,6 +31,13 @@ impl From<&TransactionType> for CreditTransactionType {
 
 // Request models
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "user_id": "550e8400-e29b-41d4-a716-446655440000",
+    "transaction_type": "admin_grant",
+    "amount": "25.00",
+    "source_id": "550e8400-e29b-41d4-a716-446655440000",
+    "description": "Onboarding credit"
+}))]
 pub struct CreditTransactionCreate {
     /// User ID (required - UUID format)
     #[schema(value_type = String, format = "uuid")]
@@ -47,6 +55,19 @@ pub struct CreditTransactionCreate {
 
 // Response models
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "id": "9b2d5c1e-8f3a-4e6b-a1d7-3c5e9f2b4a68",
+    "user_id": "550e8400-e29b-41d4-a716-446655440000",
+    "transaction_type": "usage",
+    "batch_id": null,
+    "amount": "0.012345000000000",
+    "display_amount": "0.01",
+    "currency": "USD",
+    "source_id": "f47ac10b-58cc-4372-a567-0e02b2c3d479",
+    "description": "llama-3.1-8b",
+    "created_at": "2025-01-20T14:45:00Z",
+    "service_tier": "realtime"
+}))]
 pub struct CreditTransactionResponse {
     /// Transaction ID
     #[schema(value_type = String, format = "uuid")]
@@ -62,6 +83,11 @@ pub struct CreditTransactionResponse {
     /// Amount of credits (returned as string to preserve precision)
     #[schema(value_type = String)]
     pub amount: Decimal,
+    /// `amount` rounded half-to-even to the configured `credits.decimal_places`, for display
+    #[schema(value_type = String)]
+    pub display_amount: Decimal,
+    /// ISO 4217 currency code credits are denominated in
+    pub currency: String,
     /// Source ID
     pub source_id: String,
     /// Description
@@ -80,6 +106,23 @@ pub struct CreditTransactionResponse {
 /// Paginated response for transaction listing with balance context.
 /// Mirrors PaginatedResponse structure with additional balance field.
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "data": [{
+        "id": "9b2d5c1e-8f3a-4e6b-a1d7-3c5e9f2b4a68",
+        "user_id": "550e8400-e29b-41d4-a716-446655440000",
+        "transaction_type": "admin_grant",
+        "batch_id": null,
+        "amount": "25.000000000000000",
+        "display_amount": "25.00",
+        "currency": "USD",
+        "source_id": "550e8400-e29b-41d4-a716-446655440000",
+        "description": "Onboarding credit",
+        "created_at": "2025-01-15T10:30:00Z"
+    }],
+    "has_more": false,
+    "page_start_balance": "25.000000000000000",
+    "currency": "USD"
+}))]
 pub struct TransactionListResponse {
     /// The transactions for the current page
     pub data: Vec<CreditTransactionResponse>,
@@ -92,6 +135,8 @@ pub struct TransactionListResponse {
     /// by subtracting signed amounts from this value.
     #[schema(value_type = String)]
     pub page_start_balance: Decimal,
+    /// ISO 4217 currency code credits are denominated in
+    pub currency: String,
 }
 
 /// Query parameters for listing transactions
@@ -142,18 +187,7 @@ pub struct TransactionFilters {
 impl ListTransactionsQuery {
     /// Parse query parameters into TransactionFilters struct
     pub fn to_filters(&self) -> TransactionFilters {
-        let transaction_types = self.transaction_types.as_ref().map(|types_str| {
-            types_str
-                .split(',')
-                .filter_map(|t| match t.trim() {
-                    "admin_grant" => Some(CreditTransactionType::AdminGrant),
-                    "admin_removal" => Some(CreditTransactionType::AdminRemoval),
-                    "usage" => Some(CreditTransactionType::Usage),
-                    "purchase" => Some(CreditTransactionType::Purchase),
-                    _ => None,
-                })
-                .collect()
-        });
+        let transaction_types = self.transaction_types.as_deref().map(parse_transaction_types);
 
         TransactionFilters {
             search: self.search.clone(),
@@ -164,18 +198,78 @@ impl ListTransactionsQuery {
     }
 }
 
+/// Query parameters for exporting the transactions ledger
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ExportTransactionsQuery {
+    /// Only export transactions created on or after this date/time (ISO 8601 format)
+    #[param(value_type = Option<String>, format = "date-time")]
+    pub from: Option<DateTime<Utc>>,
+
+    /// Only export transactions created on or before this date/time (ISO 8601 format)
+    #[param(value_type = Option<String>, format = "date-time")]
+    pub to: Option<DateTime<Utc>>,
+
+    /// Only export this user's transactions (all users by default)
+    #[param(value_type = Option<String>, format = "uuid")]
+    pub user_id: Option<UserId>,
+
+    /// Only export these transaction types (comma-separated: "admin_grant,purchase" or "usage,admin_removal")
+    #[serde(rename = "type")]
+    pub transaction_types: Option<String>,
+
+    /// Export format. Only `csv` is supported
+    #[param(inline)]
+    pub format: Option<TransactionExportFormat>,
+}
+
+/// File format of a transactions ledger export
+#[derive(Debug, Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionExportFormat {
+    #[default]
+    Csv,
+}
+
+impl ExportTransactionsQuery {
+    /// Parse query parameters into TransactionFilters struct
+    pub fn to_filters(&self) -> TransactionFilters {
+        TransactionFilters {
+            search: None,
+            transaction_types: self.transaction_types.as_deref().map(parse_transaction_types),
+            start_date: self.from,
+            end_date: self.to,
+        }
+    }
+}
+
+/// Parse a comma-separated list of transaction types, skipping unknown ones
+fn parse_transaction_types(types: &str) -> Vec<CreditTransactionType> {
+    types
+        .split(',')
+        .filter_map(|t| match t.trim() {
+            "admin_grant" => Some(CreditTransactionType::AdminGrant),
+            "admin_removal" => Some(CreditTransactionType::AdminRemoval),
+            "usage" => Some(CreditTransactionType::Usage),
+            "purchase" => Some(CreditTransactionType::Purchase),
+            _ => None,
+        })
+        .collect()
+}
+
 // Conversions
 impl CreditTransactionResponse {
     /// Convert from DB response with optional batch_id (without batch-grouping metadata).
     /// Carries the denormalized `service_tier` from the ledger row so the non-grouped
     /// transactions lists expose the tier just like the grouped path (COR-514).
-    pub fn from_db_with_batch_id(db: CreditTransactionDBResponse, batch_id: Option<Uuid>) -> Self {
+    pub fn from_db_with_batch_id(db: CreditTransactionDBResponse, batch_id: Option<Uuid>, credits: &CreditsConfig) -> Self {
         Self {
             id: db.id,
             user_id: db.user_id,
             transaction_type: db.transaction_type,
             batch_id,
             amount: db.amount,
+            display_amount: credits.round(db.amount),
+            currency: credits.currency.clone(),
             source_id: db.source_id,
             description: db.description,
             created_at: db.created_at,
@@ -190,6 +284,7 @@ impl CreditTransactionResponse {
         batch_id: Option<Uuid>,
         service_tier: Option<String>,
         batch_count: i32,
+        credits: &CreditsConfig,
     ) -> Self {
         // Only include batch_request_count for actual batches (count > 1)
         let batch_request_count = if batch_count > 1 { Some(batch_count) } else { None };
@@ -200,6 +295,8 @@ impl CreditTransactionResponse {
             transaction_type: db.transaction_type,
             batch_id,
             amount: db.amount,
+            display_amount: credits.round(db.amount),
+            currency: credits.currency.clone(),
             source_id: db.source_id,
             description: db.description,
             created_at: db.created_at,
@@ -207,10 +304,9 @@ impl CreditTransactionResponse {
             batch_request_count,
         }
     }
-}
 
-impl From<CreditTransactionDBResponse> for CreditTransactionResponse {
-    fn from(db: CreditTransactionDBResponse) -> Self {
-        Self::from_db_with_batch_id(db, None)
+    /// Convert from DB response, tagging the amount with the configured currency and precision
+    pub fn from_db(db: CreditTransactionDBResponse, credits: &CreditsConfig) -> Self {
+        Self::from_db_with_batch_id(db, None, credits)
     }
 }
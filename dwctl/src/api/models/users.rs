@@ -13,7 +13,7 @@ use utoipa::{IntoParams, ToSchema};
 ///
 /// Roles are additive - a user can have multiple roles, and their effective
 /// permissions are the union of all role permissions.
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Hash, ToSchema)]
 #[sqlx(type_name = "user_role", rename_all = "UPPERCASE")]
 pub enum Role {
     /// Full administrative access: manage users, groups, deployments, and endpoints
@@ -32,6 +32,13 @@ pub enum Role {
 
 /// Request body for creating a new user.
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "username": "jsmith",
+    "email": "john.smith@example.com",
+    "display_name": "John Smith",
+    "avatar_url": "https://example.com/avatars/jsmith.png",
+    "roles": ["StandardUser", "BatchAPIUser"]
+}))]
 pub struct UserCreate {
     /// Unique username for login (must be unique across the system)
     #[schema(example = "jsmith")]
@@ -52,6 +59,12 @@ pub struct UserCreate {
 /// Request body for updating an existing user. All fields are optional;
 /// only provided fields will be updated.
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "display_name": "John Smith Jr.",
+    "roles": ["StandardUser", "BatchAPIUser", "RequestViewer"],
+    "batch_notifications_enabled": true,
+    "low_balance_threshold": 2.0
+}))]
 pub struct UserUpdate {
     /// New display name (null to keep unchanged)
     #[schema(example = "John Smith Jr.")]
@@ -84,10 +97,40 @@ pub struct UserUpdate {
     /// account; callers with UpdateAll on users may set it for any account.
     /// Omit to leave unchanged.
     pub zero_data_retention: Option<bool>,
+    /// IANA timezone name (e.g. `America/Los_Angeles`) whose calendar day, week
+    /// and month API key spending caps and the auto top-up monthly limit follow.
+    /// Omit to leave unchanged.
+    #[schema(example = "America/Los_Angeles")]
+    pub timezone: Option<String>,
 }
 
 /// Full user details returned by the API.
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "id": "550e8400-e29b-41d4-a716-446655440000",
+    "username": "jsmith",
+    "email": "john.smith@example.com",
+    "display_name": "John Smith",
+    "avatar_url": "https://example.com/avatars/jsmith.png",
+    "is_admin": false,
+    "roles": ["StandardUser", "BatchAPIUser"],
+    "created_at": "2025-01-15T10:30:00Z",
+    "updated_at": "2025-01-20T14:45:00Z",
+    "last_login": "2025-01-20T14:45:00Z",
+    "auth_source": "native",
+    "external_user_id": null,
+    "credit_balance": 42.5,
+    "has_payment_provider_id": true,
+    "batch_notifications_enabled": true,
+    "low_balance_threshold": 2.0,
+    "auto_topup_amount": null,
+    "auto_topup_threshold": null,
+    "has_auto_topup_payment_method": false,
+    "auto_topup_monthly_limit": null,
+    "user_type": "individual",
+    "zero_data_retention": false,
+    "timezone": "UTC"
+}))]
 pub struct UserResponse {
     /// Unique identifier for the user
     #[schema(value_type = String, format = "uuid")]
@@ -146,6 +189,8 @@ pub struct UserResponse {
     pub user_type: String,
     /// Account-wide zero-data-retention flag.
     pub zero_data_retention: bool,
+    /// IANA timezone name for calendar-aligned quota windows. Defaults to `UTC`.
+    pub timezone: String,
     /// Organizations this user belongs to (only included if `include=organizations` is specified)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub organizations: Option<Vec<super::organizations::OrganizationSummary>>,
@@ -171,6 +216,18 @@ pub struct ListUsersQuery {
 
     /// Search query to filter users by display_name, username, or email (case-insensitive substring match)
     pub search: Option<String>,
+
+    /// Filter to users whose email contains this value (case-insensitive)
+    pub email_contains: Option<String>,
+
+    /// Filter to users holding this role
+    pub role: Option<Role>,
+
+    /// Filter by authentication source (e.g. "native", "proxy-header")
+    pub auth_source: Option<String>,
+
+    /// Filter on the legacy admin flag
+    pub is_admin: Option<bool>,
 }
 
 /// The currently authenticated user's information.
@@ -251,6 +308,7 @@ impl From<UserDBResponse> for UserResponse {
             auto_topup_monthly_limit: db.auto_topup_monthly_limit,
             user_type: db.user_type,
             zero_data_retention: db.zero_data_retention,
+            timezone: db.timezone,
             organizations: None,
             active_organization_id: None,
             onboarding_redirect_url: None,
@@ -5,7 +5,7 @@ use crate::{
     AppState,
     api::models::users::{CurrentUser, Role},
     auth::session,
-    db::handlers::{Repository, Users},
+    db::handlers::{Repository, Sessions, Users},
     errors::{Error, Result},
 };
 use axum::{extract::FromRequestParts, http::request::Parts};
@@ -20,7 +20,7 @@ type AuthSuccess = (CurrentUser, Option<DateTime<Utc>>);
 /// Returns:
 /// - None: No JWT cookie present
 /// - Some(Ok((user, last_login))): Valid JWT found, user fetched from DB with current data
-/// - Some(Err(error)): JWT cookie present but invalid/malformed, or user not found/deleted
+/// - Some(Err(error)): JWT cookie present but invalid/malformed, session revoked or expired, or user not found/deleted
 #[instrument(skip(parts, config, db))]
 async fn try_jwt_session_auth(
     parts: &axum::http::request::Parts,
@@ -44,20 +44,33 @@ async fn try_jwt_session_auth(
         if let Some((name, value)) = cookie.split_once('=')
             && name == cookie_name
         {
-            // Verify the JWT and extract user ID
-            let user_id = match session::verify_session_token(value, config) {
-                Ok(id) => id,
+            // Verify the JWT and extract the user and session IDs
+            let claims = match session::verify_session_token(value, config) {
+                Ok(claims) => claims,
                 Err(_) => {
                     // Invalid/expired token, continue checking other cookies
                     continue;
                 }
             };
+            let user_id = claims.user_id();
 
-            // Fetch fresh user data from database
             let mut conn = match db.acquire().await {
                 Ok(conn) => conn,
                 Err(e) => return Some(Err(DbError::from(e).into())),
             };
+
+            // The session must not have been revoked
+            match Sessions::new(&mut conn).is_active(claims.sid, user_id).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    return Some(Err(Error::Unauthenticated {
+                        message: Some("Session has expired or been revoked".to_string()),
+                    }));
+                }
+                Err(e) => return Some(Err(Error::Database(e))),
+            }
+
+            // Fetch fresh user data from database
             let mut user_repo = Users::new(&mut conn);
 
             let user = match user_repo.get_by_id(user_id).await {
@@ -106,6 +119,20 @@ async fn try_proxy_header_auth<P: sqlx_pool_router::PoolProvider + Clone + Send
     let config = state.current_config();
     let db: &PgPool = state.db.write();
     tracing::trace!("Trying proxy header auth, config: {:?}", config.auth.proxy_header);
+
+    // Only honour identity headers from trusted proxies, otherwise any client able to
+    // reach dwctl directly could impersonate a user
+    let peer = parts
+        .extensions
+        .get::<axum::extract::ConnectInfo<std::net::SocketAddr>>()
+        .map(|connect_info| connect_info.0.ip());
+    if !config.auth.proxy_header.is_trusted_source(peer, &parts.headers) {
+        if parts.headers.contains_key(&config.auth.proxy_header.header_name) {
+            tracing::warn!(?peer, "Ignoring proxy identity headers from untrusted source");
+        }
+        return None;
+    }
+
     // Extract external_user_id from header_name (required)
     let external_user_id = parts
         .headers
@@ -589,6 +616,11 @@ impl<P: sqlx_pool_router::PoolProvider + Clone + Send + Sync> FromRequestParts<c
 
     #[instrument(skip(parts, state))]
     async fn from_request_parts(parts: &mut Parts, state: &crate::AppState<P>) -> Result<Self> {
+        // Already authenticated by the admin API rate limit middleware
+        if let Some(user) = parts.extensions.get::<CurrentUser>() {
+            return Ok(user.clone());
+        }
+
         // Try all authentication methods and accumulate results
         // Each method returns Option<Result<AuthSuccess>>:
         // - None means the auth method is not applicable (no credentials present)
@@ -1079,6 +1111,98 @@ mod tests {
         assert_eq!(result.unwrap_err().status_code(), axum::http::StatusCode::UNAUTHORIZED);
     }
 
+    #[sqlx::test]
+    async fn test_proxy_header_auth_with_custom_header_names(pool: PgPool) {
+        // oauth2-proxy style headers
+        let mut config = create_test_config();
+        config.auth.proxy_header.header_name = "x-forwarded-user".to_string();
+        config.auth.proxy_header.email_header_name = "x-auth-request-email".to_string();
+        let state = crate::test::utils::create_test_app_state_with_config(pool.clone(), config).await;
+
+        let request = axum::http::Request::builder()
+            .uri("http://localhost/test")
+            .header("X-Forwarded-User", "oidc|alice")
+            .header("X-Auth-Request-Email", "alice@example.com")
+            .body(())
+            .unwrap();
+        let (mut parts, _) = request.into_parts();
+        let current_user = CurrentUser::from_request_parts(&mut parts, &state).await.unwrap();
+        assert_eq!(current_user.email, "alice@example.com");
+        assert_eq!(current_user.username, "oidc|alice");
+
+        // The default header names are no longer honoured
+        let mut parts = create_test_parts_with_auth("oidc|bob", "bob@example.com");
+        let result = CurrentUser::from_request_parts(&mut parts, &state).await;
+        assert_eq!(result.unwrap_err().status_code(), axum::http::StatusCode::UNAUTHORIZED);
+    }
+
+    #[sqlx::test]
+    async fn test_proxy_header_auth_rejects_untrusted_source(pool: PgPool) {
+        let mut config = create_test_config();
+        config.auth.proxy_header.trusted_proxies = vec!["10.0.0.0/8".parse().unwrap()];
+        let state = crate::test::utils::create_test_app_state_with_config(pool.clone(), config).await;
+
+        let parts_from = |peer: Option<&str>| {
+            let mut parts = create_test_parts_with_auth("github|mallory", "mallory@example.com");
+            if let Some(peer) = peer {
+                parts
+                    .extensions
+                    .insert(axum::extract::ConnectInfo::<std::net::SocketAddr>(peer.parse().unwrap()));
+            }
+            parts
+        };
+
+        // Direct client outside the allowlist, and a request with no known peer
+        for peer in [Some("203.0.113.7:51234"), None] {
+            let mut parts = parts_from(peer);
+            let result = CurrentUser::from_request_parts(&mut parts, &state).await;
+            assert_eq!(
+                result.unwrap_err().status_code(),
+                axum::http::StatusCode::UNAUTHORIZED,
+                "peer {peer:?} should not be trusted"
+            );
+        }
+        let mut conn = pool.acquire().await.unwrap();
+        let mut users_repo = Users::new(&mut conn);
+        assert!(users_repo.get_user_by_email("mallory@example.com").await.unwrap().is_none());
+
+        // The trusted proxy is accepted
+        let mut parts = parts_from(Some("10.1.2.3:443"));
+        let current_user = CurrentUser::from_request_parts(&mut parts, &state).await.unwrap();
+        assert_eq!(current_user.email, "mallory@example.com");
+    }
+
+    #[sqlx::test]
+    async fn test_proxy_header_auth_ignores_spoofed_headers_without_shared_secret(pool: PgPool) {
+        let mut config = create_test_config();
+        config.auth.proxy_header.shared_secret = Some("proxy-secret".to_string());
+        let state = crate::test::utils::create_test_app_state_with_config(pool.clone(), config).await;
+
+        let victim = crate::test::utils::create_test_user(&pool, Role::StandardUser).await;
+        let victim_external_id = victim.external_user_id.as_ref().unwrap();
+        let parts_with_secret = |secret: Option<&str>| {
+            let mut parts = create_test_parts_with_auth(victim_external_id, &victim.email);
+            parts.extensions.insert(axum::extract::ConnectInfo::<std::net::SocketAddr>(
+                "203.0.113.7:51234".parse().unwrap(),
+            ));
+            if let Some(secret) = secret {
+                parts.headers.insert("x-doubleword-proxy-secret", secret.parse().unwrap());
+            }
+            parts
+        };
+
+        // Spoofed identity headers without the proxy's secret are ignored
+        for secret in [None, Some("guessed-secret")] {
+            let mut parts = parts_with_secret(secret);
+            let result = CurrentUser::from_request_parts(&mut parts, &state).await;
+            assert_eq!(result.unwrap_err().status_code(), axum::http::StatusCode::UNAUTHORIZED);
+        }
+
+        let mut parts = parts_with_secret(Some("proxy-secret"));
+        let current_user = CurrentUser::from_request_parts(&mut parts, &state).await.unwrap();
+        assert_eq!(current_user.id, victim.id);
+    }
+
     #[sqlx::test]
     async fn test_existing_user_email_update(pool: PgPool) {
         let config = create_test_config();
@@ -1273,7 +1397,9 @@ mod tests {
             active_organization: None,
             api_key_id: None,
         };
-        let jwt_token = session::create_session_token(&current_user, &config).unwrap();
+        let jwt_token = session::start_session(&mut pool.acquire().await.unwrap(), &current_user, None, &config)
+            .await
+            .unwrap();
 
         let state = crate::test::utils::create_test_app_state_with_config(pool.clone(), config.clone()).await;
 
@@ -1305,6 +1431,7 @@ mod tests {
             auto_topup_threshold: None,
             auto_topup_monthly_limit: None,
             zero_data_retention: None,
+            timezone: None,
         };
         users_repo.update(user.id, &update).await.unwrap();
 
@@ -1346,7 +1473,9 @@ mod tests {
             active_organization: None,
             api_key_id: None,
         };
-        let jwt_token = session::create_session_token(&current_user, &config).unwrap();
+        let jwt_token = session::start_session(&mut pool.acquire().await.unwrap(), &current_user, None, &config)
+            .await
+            .unwrap();
 
         let state = crate::test::utils::create_test_app_state_with_config(pool.clone(), config.clone()).await;
 
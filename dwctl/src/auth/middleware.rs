@@ -7,11 +7,12 @@ use crate::{
 };
 use anyhow::Context;
 use axum::{
+    Json,
     body::Body,
     extract::{FromRequestParts, Request, State},
-    http::{HeaderValue, Uri},
+    http::{HeaderValue, StatusCode, Uri, header::RETRY_AFTER},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
 use tracing::{debug, trace};
 
@@ -33,6 +34,8 @@ pub(crate) async fn admin_ai_proxy<P: sqlx_pool_router::PoolProvider + Clone>(
     // Extract user using the same auth methods as other endpoints
     let (mut parts, body) = request.into_parts();
     let current_user = CurrentUser::from_request_parts(&mut parts, &state).await?;
+    // The proxy's identity headers have done their job; don't forward them
+    state.current_config().auth.proxy_header.strip_headers(&mut parts.headers);
 
     // Reconstruct request for further processing
     request = Request::from_parts(parts, body);
@@ -113,6 +116,55 @@ pub async fn admin_ai_proxy_middleware<P: sqlx_pool_router::PoolProvider + Clone
     Ok(next.run(request).await)
 }
 
+/// Middleware that removes the trusted proxy's identity and shared-secret headers from
+/// inference requests before they are proxied. Inference authenticates by API key, so
+/// nothing past this point reads them, and a provider must never see them.
+pub async fn strip_proxy_headers_middleware<P: sqlx_pool_router::PoolProvider + Clone>(
+    State(state): State<crate::AppState<P>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    state.current_config().auth.proxy_header.strip_headers(request.headers_mut());
+    next.run(request).await
+}
+
+/// Middleware that applies the per-user admin API rate limit (`limits.admin_api`).
+///
+/// Authenticates the request up front and stores the [`CurrentUser`] in the
+/// request extensions, where the handler's extractor picks it up instead of
+/// authenticating again. Requests that fail to authenticate are passed through
+/// for the handler to reject.
+pub async fn admin_api_rate_limit_middleware<P: sqlx_pool_router::PoolProvider + Clone + Send + Sync>(
+    State(state): State<crate::AppState<P>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(limiter) = state.limiters.admin_api.clone() else {
+        return next.run(request).await;
+    };
+
+    let (mut parts, body) = request.into_parts();
+    if let Ok(user) = CurrentUser::from_request_parts(&mut parts, &state).await {
+        if let Err(wait) = limiter.check(&user) {
+            debug!(user_id = %user.id, "Admin API rate limit exceeded");
+            let retry_after_seconds = wait.as_secs_f64().ceil().max(1.0) as u64;
+            let body = serde_json::json!({
+                "error": "too_many_requests",
+                "message": "Too many requests. Please slow down and retry later.",
+                "retry_after_seconds": retry_after_seconds
+            });
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(RETRY_AFTER, HeaderValue::from(retry_after_seconds))],
+                Json(body),
+            )
+                .into_response();
+        }
+        parts.extensions.insert(user);
+    }
+    next.run(Request::from_parts(parts, body)).await
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
@@ -131,7 +183,7 @@ mod tests {
                 deployments::DeploymentCreateDBRequest, groups::GroupCreateDBRequest, inference_endpoints::InferenceEndpointCreateDBRequest,
             },
         },
-        test::utils::{create_test_config, create_test_user},
+        test::utils::{add_auth_headers, await_chat_status, create_test_admin_user, create_test_config, create_test_user},
     };
 
     #[sqlx::test]
@@ -151,6 +203,15 @@ mod tests {
                 auth_header_prefix: None,
                 reasoning_translation: None,
                 created_by: user.id,
+                bedrock: None,
+                region: None,
+                body_transform: None,
+                auto_sync_interval_seconds: None,
+                alias_template: None,
+                max_concurrency: None,
+                tls: None,
+                additional_api_keys: Vec::new(),
+                api_key_rotation: None,
             })
             .await
             .expect("Failed to create test inference endpoint");
@@ -207,6 +268,15 @@ mod tests {
                 auth_header_prefix: None,
                 reasoning_translation: None,
                 created_by: user.id,
+                bedrock: None,
+                region: None,
+                body_transform: None,
+                auto_sync_interval_seconds: None,
+                alias_template: None,
+                max_concurrency: None,
+                tls: None,
+                additional_api_keys: Vec::new(),
+                api_key_rotation: None,
             })
             .await
             .expect("Failed to create test inference endpoint");
@@ -234,6 +304,7 @@ mod tests {
                 GroupCreate {
                     name: "a group".to_string(),
                     description: Some("A test group".to_string()),
+                    template_id: None,
                 },
             ))
             .await
@@ -375,6 +446,15 @@ mod tests {
                 auth_header_prefix: None,
                 reasoning_translation: None,
                 created_by: user.id,
+                bedrock: None,
+                region: None,
+                body_transform: None,
+                auto_sync_interval_seconds: None,
+                alias_template: None,
+                max_concurrency: None,
+                tls: None,
+                additional_api_keys: Vec::new(),
+                api_key_rotation: None,
             })
             .await
             .expect("Failed to create test inference endpoint");
@@ -444,6 +524,15 @@ mod tests {
                 auth_header_prefix: None,
                 reasoning_translation: None,
                 created_by: user.id,
+                bedrock: None,
+                region: None,
+                body_transform: None,
+                auto_sync_interval_seconds: None,
+                alias_template: None,
+                max_concurrency: None,
+                tls: None,
+                additional_api_keys: Vec::new(),
+                api_key_rotation: None,
             })
             .await
             .expect("Failed to create test inference endpoint");
@@ -471,6 +560,7 @@ mod tests {
                 GroupCreate {
                     name: "jwt group".to_string(),
                     description: Some("A test group for JWT".to_string()),
+                    template_id: None,
                 },
             ))
             .await
@@ -499,13 +589,16 @@ mod tests {
             active_organization: None,
             api_key_id: None,
         };
-        let jwt_token = session::create_session_token(&current_user, &config).unwrap();
+        let jwt_token = session::start_session(&mut pool.acquire().await.unwrap(), &current_user, None, &config)
+            .await
+            .unwrap();
 
         let mut state = crate::test::utils::create_test_app_state_with_config(pool.clone(), config.clone()).await;
         state = crate::AppState {
             db: state.db,
             config: state.config,
             outlet_db: None,
+            analytics_db: None,
             metrics_recorder: None,
             is_leader: false,
             request_manager: state.request_manager,
@@ -516,6 +609,10 @@ mod tests {
             response_step_manager: state.response_step_manager,
             image_normalizer: state.image_normalizer.clone(),
             keystore: state.keystore.clone(),
+            maintenance: state.maintenance.clone(),
+            aliases: state.aliases.clone(),
+            deployment_settings: state.deployment_settings.clone(),
+            config_events: state.config_events.clone(),
         };
 
         let request = axum::http::Request::builder()
@@ -557,6 +654,15 @@ mod tests {
                 auth_header_prefix: None,
                 reasoning_translation: None,
                 created_by: jwt_user.id,
+                bedrock: None,
+                region: None,
+                body_transform: None,
+                auto_sync_interval_seconds: None,
+                alias_template: None,
+                max_concurrency: None,
+                tls: None,
+                additional_api_keys: Vec::new(),
+                api_key_rotation: None,
             })
             .await
             .expect("Failed to create test inference endpoint");
@@ -584,6 +690,7 @@ mod tests {
                 GroupCreate {
                     name: "priority group".to_string(),
                     description: Some("A test group for auth priority".to_string()),
+                    template_id: None,
                 },
             ))
             .await
@@ -613,13 +720,16 @@ mod tests {
             active_organization: None,
             api_key_id: None,
         };
-        let jwt_token = session::create_session_token(&current_user, &config).unwrap();
+        let jwt_token = session::start_session(&mut pool.acquire().await.unwrap(), &current_user, None, &config)
+            .await
+            .unwrap();
 
         let mut state = crate::test::utils::create_test_app_state_with_config(pool.clone(), config.clone()).await;
         state = crate::AppState {
             db: state.db,
             config: state.config,
             outlet_db: None,
+            analytics_db: None,
             metrics_recorder: None,
             is_leader: false,
             request_manager: state.request_manager,
@@ -630,6 +740,10 @@ mod tests {
             response_step_manager: state.response_step_manager,
             image_normalizer: state.image_normalizer.clone(),
             keystore: state.keystore.clone(),
+            maintenance: state.maintenance.clone(),
+            aliases: state.aliases.clone(),
+            deployment_settings: state.deployment_settings.clone(),
+            config_events: state.config_events.clone(),
         };
 
         let header_external_user_id = header_user.external_user_id.as_ref().unwrap_or(&header_user.username);
@@ -674,6 +788,15 @@ mod tests {
                 auth_header_prefix: None,
                 reasoning_translation: None,
                 created_by: user.id,
+                bedrock: None,
+                region: None,
+                body_transform: None,
+                auto_sync_interval_seconds: None,
+                alias_template: None,
+                max_concurrency: None,
+                tls: None,
+                additional_api_keys: Vec::new(),
+                api_key_rotation: None,
             })
             .await
             .expect("Failed to create test inference endpoint");
@@ -701,6 +824,7 @@ mod tests {
                 GroupCreate {
                     name: "disabled auth group".to_string(),
                     description: Some("A test group for disabled auth".to_string()),
+                    template_id: None,
                 },
             ))
             .await
@@ -729,13 +853,16 @@ mod tests {
             active_organization: None,
             api_key_id: None,
         };
-        let jwt_token = session::create_session_token(&current_user, &config).unwrap();
+        let jwt_token = session::start_session(&mut pool.acquire().await.unwrap(), &current_user, None, &config)
+            .await
+            .unwrap();
 
         let mut state = crate::test::utils::create_test_app_state_with_config(pool.clone(), config.clone()).await;
         state = crate::AppState {
             db: state.db,
             config: state.config,
             outlet_db: None,
+            analytics_db: None,
             metrics_recorder: None,
             is_leader: false,
             request_manager: state.request_manager,
@@ -746,6 +873,10 @@ mod tests {
             response_step_manager: state.response_step_manager,
             image_normalizer: state.image_normalizer.clone(),
             keystore: state.keystore.clone(),
+            maintenance: state.maintenance.clone(),
+            aliases: state.aliases.clone(),
+            deployment_settings: state.deployment_settings.clone(),
+            config_events: state.config_events.clone(),
         };
 
         // Request with JWT cookie - should be ignored since native auth is disabled
@@ -789,6 +920,15 @@ mod tests {
                 auth_header_prefix: None,
                 reasoning_translation: None,
                 created_by: Uuid::nil(), // Use nil for system creation
+                bedrock: None,
+                region: None,
+                body_transform: None,
+                auto_sync_interval_seconds: None,
+                alias_template: None,
+                max_concurrency: None,
+                tls: None,
+                additional_api_keys: Vec::new(),
+                api_key_rotation: None,
             })
             .await
             .expect("Failed to create test inference endpoint");
@@ -873,6 +1013,15 @@ mod tests {
                 auth_header_prefix: None,
                 reasoning_translation: None,
                 created_by: user.id,
+                bedrock: None,
+                region: None,
+                body_transform: None,
+                auto_sync_interval_seconds: None,
+                alias_template: None,
+                max_concurrency: None,
+                tls: None,
+                additional_api_keys: Vec::new(),
+                api_key_rotation: None,
             })
             .await
             .expect("Failed to create test inference endpoint");
@@ -898,6 +1047,7 @@ mod tests {
                 GroupCreate {
                     name: "fallback group".to_string(),
                     description: Some("A test group for auth fallback".to_string()),
+                    template_id: None,
                 },
             ))
             .await
@@ -919,6 +1069,7 @@ mod tests {
             db: state.db,
             config: state.config,
             outlet_db: None,
+            analytics_db: None,
             metrics_recorder: None,
             is_leader: false,
             request_manager: state.request_manager,
@@ -929,6 +1080,10 @@ mod tests {
             response_step_manager: state.response_step_manager,
             image_normalizer: state.image_normalizer.clone(),
             keystore: state.keystore.clone(),
+            maintenance: state.maintenance.clone(),
+            aliases: state.aliases.clone(),
+            deployment_settings: state.deployment_settings.clone(),
+            config_events: state.config_events.clone(),
         };
 
         let external_user_id = user.external_user_id.as_ref().unwrap_or(&user.username);
@@ -973,6 +1128,15 @@ mod tests {
                 auth_header_prefix: None,
                 reasoning_translation: None,
                 created_by: user.id,
+                bedrock: None,
+                region: None,
+                body_transform: None,
+                auto_sync_interval_seconds: None,
+                alias_template: None,
+                max_concurrency: None,
+                tls: None,
+                additional_api_keys: Vec::new(),
+                api_key_rotation: None,
             })
             .await
             .expect("Failed to create test inference endpoint");
@@ -1000,6 +1164,7 @@ mod tests {
                 GroupCreate {
                     name: "playground group".to_string(),
                     description: Some("A test group for playground".to_string()),
+                    template_id: None,
                 },
             ))
             .await
@@ -1028,13 +1193,16 @@ mod tests {
             active_organization: None,
             api_key_id: None,
         };
-        let jwt_token = session::create_session_token(&current_user, &config).unwrap();
+        let jwt_token = session::start_session(&mut pool.acquire().await.unwrap(), &current_user, None, &config)
+            .await
+            .unwrap();
 
         let mut state = crate::test::utils::create_test_app_state_with_config(pool.clone(), config.clone()).await;
         state = crate::AppState {
             db: state.db,
             config: state.config,
             outlet_db: None,
+            analytics_db: None,
             metrics_recorder: None,
             is_leader: false,
             request_manager: state.request_manager,
@@ -1045,6 +1213,10 @@ mod tests {
             response_step_manager: state.response_step_manager,
             image_normalizer: state.image_normalizer.clone(),
             keystore: state.keystore.clone(),
+            maintenance: state.maintenance.clone(),
+            aliases: state.aliases.clone(),
+            deployment_settings: state.deployment_settings.clone(),
+            config_events: state.config_events.clone(),
         };
 
         let request = axum::http::Request::builder()
@@ -1066,4 +1238,139 @@ mod tests {
         assert_eq!(request.uri().path(), "/ai/v1/chat/completions");
         assert!(request.headers().get("authorization").is_some());
     }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_admin_api_rate_limit_per_user(pool: PgPool) {
+        use crate::test::utils::create_test_app_with_config;
+
+        let mut config = create_test_config();
+        config.limits.admin_api.requests_per_second = Some(0.01);
+        config.limits.admin_api.burst_size = Some(3);
+        config.limits.admin_api.platform_manager_requests_per_second = Some(0.01);
+        config.limits.admin_api.platform_manager_burst_size = Some(6);
+        let (app, _bg_services) = create_test_app_with_config(pool.clone(), config, false).await;
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let other_user = create_test_user(&pool, Role::StandardUser).await;
+        let admin = create_test_admin_user(&pool, Role::PlatformManager).await;
+
+        let get_current = |user: &crate::api::models::users::UserResponse| {
+            let headers = add_auth_headers(user);
+            app.get("/admin/api/v1/users/current")
+                .add_header(&headers[0].0, &headers[0].1)
+                .add_header(&headers[1].0, &headers[1].1)
+        };
+
+        for _ in 0..3 {
+            get_current(&user).await.assert_status_ok();
+        }
+        let response = get_current(&user).await;
+        response.assert_status(axum::http::StatusCode::TOO_MANY_REQUESTS);
+        let retry_after: u64 = response.header("retry-after").to_str().unwrap().parse().unwrap();
+        assert!((1..=100).contains(&retry_after));
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["retry_after_seconds"], retry_after);
+
+        // Other users have their own budget, and platform managers a larger one
+        get_current(&other_user).await.assert_status_ok();
+        for _ in 0..6 {
+            get_current(&admin).await.assert_status_ok();
+        }
+        get_current(&admin).await.assert_status(axum::http::StatusCode::TOO_MANY_REQUESTS);
+
+        // Unauthenticated requests and routes outside the admin API are not limited
+        app.get("/admin/api/v1/users/current").await.assert_status_unauthorized();
+        app.get("/healthz").await.assert_status_ok();
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_proxy_headers_never_reach_upstream(pool: PgPool) {
+        let mock_server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/v1/chat/completions"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(json!({
+                "id": "chatcmpl-1", "object": "chat.completion", "created": 1, "model": "strip-model",
+                "choices": [ { "index": 0, "message": { "role": "assistant", "content": "Hello" }, "finish_reason": "stop" } ],
+                "usage": { "prompt_tokens": 5, "completion_tokens": 1, "total_tokens": 6 }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let mut config = create_test_config();
+        config.background_services.onwards_sync.enabled = true;
+        config.auth.proxy_header.shared_secret = Some("proxy-secret".to_string());
+        let secret_header = config.auth.proxy_header.shared_secret_header_name.clone();
+        let (server, bg_services) = crate::Application::new_with_pool(config, Some(pool.clone()), None)
+            .await
+            .expect("Failed to create application")
+            .into_test_server();
+        let admin = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let mut admin_headers = add_auth_headers(&admin);
+        admin_headers.push((secret_header.clone(), "proxy-secret".to_string()));
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let mut user_headers = add_auth_headers(&user);
+        user_headers.push((secret_header.clone(), "proxy-secret".to_string()));
+        let with_headers = |mut request: axum_test::TestRequest, headers: &[(String, String)]| {
+            for (name, value) in headers {
+                request = request.add_header(name, value);
+            }
+            request
+        };
+
+        let endpoint: serde_json::Value = with_headers(server.post("/admin/api/v1/endpoints"), &admin_headers)
+            .json(&json!({ "name": "strip", "url": format!("{}/v1", mock_server.uri()) }))
+            .await
+            .json();
+        let model: serde_json::Value = with_headers(server.post("/admin/api/v1/models"), &admin_headers)
+            .json(&json!({
+                "type": "standard",
+                "model_name": "strip-model",
+                "alias": "strip-model",
+                "hosted_on": endpoint["id"],
+            }))
+            .await
+            .json();
+        with_headers(
+            server.post(&format!(
+                "/admin/api/v1/groups/00000000-0000-0000-0000-000000000000/models/{}",
+                model["id"].as_str().unwrap()
+            )),
+            &admin_headers,
+        )
+        .await;
+        let key: serde_json::Value = with_headers(server.post(&format!("/admin/api/v1/users/{}/api-keys", user.id)), &admin_headers)
+            .json(&json!({ "purpose": "realtime", "name": "strip key" }))
+            .await
+            .json();
+        let api_key = key["key"].as_str().unwrap().to_string();
+
+        bg_services.sync_onwards_config(&pool).await.expect("Failed to sync onwards config");
+
+        let body = json!({ "model": "strip-model", "messages": [{ "role": "user", "content": "hi" }] });
+        await_chat_status(&server, &api_key, &body, 200).await;
+
+        // A playground request authenticated by the proxy headers, and an API key
+        // request that carries them anyway
+        with_headers(server.post("/admin/api/v1/ai/v1/chat/completions"), &user_headers)
+            .json(&body)
+            .await
+            .assert_status_ok();
+        with_headers(server.post("/ai/v1/chat/completions"), &user_headers)
+            .add_header("authorization", format!("Bearer {api_key}"))
+            .json(&body)
+            .await
+            .assert_status_ok();
+
+        let received = mock_server.received_requests().await.unwrap();
+        assert_eq!(received.len(), 3);
+        let proxy_header = crate::config::ProxyHeaderAuthConfig::default();
+        for request in received {
+            for name in [&secret_header, &proxy_header.header_name, &proxy_header.email_header_name] {
+                assert!(!request.headers.contains_key(name.as_str()), "{name} reached the upstream");
+            }
+        }
+
+        bg_services.shutdown().await;
+    }
 }
@@ -76,6 +76,25 @@ pub fn verify_string(input: &str, hash: &str) -> Result<bool, Error> {
     Ok(argon2.verify_password(input.as_bytes(), &parsed_hash).is_ok())
 }
 
+/// Whether a stored hash should be recomputed with `params` after a successful login.
+///
+/// True when the hash's Argon2 cost (memory, iterations or parallelism) is below `params`,
+/// or it wasn't produced by Argon2id v0x13. Hashes that can't be parsed are left alone.
+pub fn needs_rehash(hash: &str, params: Argon2Params) -> bool {
+    let Ok(parsed) = PasswordHash::new(hash) else {
+        return false;
+    };
+
+    if parsed.algorithm != Algorithm::Argon2id.ident() || parsed.version != Some(u32::from(Version::V0x13)) {
+        return true;
+    }
+
+    match Params::try_from(&parsed) {
+        Ok(stored) => stored.m_cost() < params.memory_kib || stored.t_cost() < params.iterations || stored.p_cost() < params.parallelism,
+        Err(_) => false,
+    }
+}
+
 /// Generate a secure random token for password reset
 pub fn generate_reset_token() -> String {
     // Generate 32 bytes (256 bits) of cryptographically secure random data
@@ -132,6 +151,34 @@ mod tests {
         assert!(verify_string(input, &hash2).unwrap());
     }
 
+    #[test]
+    fn test_needs_rehash() {
+        let weak = Argon2Params {
+            memory_kib: 1024,
+            iterations: 1,
+            parallelism: 1,
+        };
+        let current = Argon2Params {
+            memory_kib: 2048,
+            iterations: 2,
+            parallelism: 1,
+        };
+        let weak_hash = hash_string_with_params("pw", Some(weak)).unwrap();
+        let current_hash = hash_string_with_params("pw", Some(current)).unwrap();
+
+        assert!(needs_rehash(&weak_hash, current));
+        assert!(!needs_rehash(&current_hash, current));
+
+        // A hash stronger than the config is never downgraded
+        assert!(!needs_rehash(&current_hash, weak));
+
+        // Raising any single cost parameter triggers an upgrade
+        assert!(needs_rehash(&current_hash, Argon2Params { parallelism: 2, ..current }));
+
+        // Unparseable hashes are left alone
+        assert!(!needs_rehash("not-a-phc-string", current));
+    }
+
     #[test]
     fn test_generate_reset_token() {
         let token1 = generate_reset_token();
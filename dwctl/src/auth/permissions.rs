@@ -460,6 +460,75 @@ pub async fn is_org_member(
     Ok(repo.get_user_org_role(current_user.id, target_user_id).await?.is_some())
 }
 
+/// Check if the current user manages another user's API keys by delegation.
+/// Returns true if current_user holds `manager_role` (`auth.api_key_manager_role`)
+/// and shares a group other than Everyone with target_user_id, and the target
+/// is neither an admin nor holds a role current_user lacks (so delegation never
+/// reaches someone more privileged).
+pub async fn can_manage_group_member_api_keys(
+    current_user: &CurrentUser,
+    manager_role: Option<&Role>,
+    target_user_id: UserId,
+    db: &mut sqlx::PgConnection,
+) -> std::result::Result<bool, crate::db::errors::DbError> {
+    let Some(role) = manager_role else {
+        return Ok(false);
+    };
+    if !current_user.roles.contains(role) || current_user.id == target_user_id {
+        return Ok(false);
+    }
+    let target = {
+        use crate::db::handlers::Repository as _;
+        let mut users = crate::db::handlers::Users::new(&mut *db);
+        users.get_by_id(target_user_id).await?
+    };
+    let Some(target) = target else {
+        return Ok(false);
+    };
+    if target.is_admin || target.roles.iter().any(|role| !current_user.roles.contains(role)) {
+        return Ok(false);
+    }
+    let mut repo = crate::db::handlers::Groups::new(db);
+    repo.shares_group_with(current_user.id, target_user_id).await
+}
+
+/// How a user reaches another user's API keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiKeyAccess {
+    /// The operation's All permission: every user's keys
+    All,
+    /// Their own keys, or an organization's they belong to: only the keys they created
+    Own,
+    /// Delegated group management (`auth.api_key_manager_role`): all of the owner's keys
+    Delegated,
+}
+
+/// How current_user may perform an API key operation (`all` and `own` being
+/// its two scopes) on target_user_id's keys, or None if they may not. The
+/// database is only consulted for org membership and delegation.
+pub async fn api_key_access(
+    current_user: &CurrentUser,
+    (all, own): (Operation, Operation),
+    manager_role: Option<&Role>,
+    target_user_id: UserId,
+    db: &sqlx::PgPool,
+) -> std::result::Result<Option<ApiKeyAccess>, crate::db::errors::DbError> {
+    if can_perform_all_operation(current_user, Resource::ApiKeys, all) {
+        return Ok(Some(ApiKeyAccess::All));
+    }
+    if can_perform_own_operation(current_user, Resource::ApiKeys, own, target_user_id) {
+        return Ok(Some(ApiKeyAccess::Own));
+    }
+    let mut conn = db.acquire().await?;
+    if is_org_member(current_user, target_user_id, &mut conn).await? {
+        return Ok(Some(ApiKeyAccess::Own));
+    }
+    if can_manage_group_member_api_keys(current_user, manager_role, target_user_id, &mut conn).await? {
+        return Ok(Some(ApiKeyAccess::Delegated));
+    }
+    Ok(None)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,27 +1,35 @@
 //! JWT session token creation and verification.
+//!
+//! Every token names a row in `user_sessions` (its `sid` claim), which is what
+//! lets a session be listed and revoked before the token expires. Cookie
+//! authentication checks that the row is still active.
 
 use chrono::Utc;
 use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
 use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use uuid::Uuid;
 
-use crate::{api::models::users::CurrentUser, config::Config, errors::Error, types::UserId};
+use crate::{api::models::users::CurrentUser, config::Config, db::handlers::Sessions, errors::Error, types::UserId};
 
 /// JWT session claims
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SessionClaims {
-    pub sub: UserId, // Subject (user ID) - this is all we store
+    pub sub: UserId, // Subject (user ID)
+    pub sid: Uuid,   // Session ID (row in user_sessions)
     pub exp: i64,    // Expiration time
     pub iat: i64,    // Issued at
 }
 
 impl SessionClaims {
     /// Create new session claims for a user
-    pub fn new(user: &CurrentUser, config: &Config) -> Self {
+    pub fn new(user: &CurrentUser, session_id: Uuid, config: &Config) -> Self {
         let now = Utc::now();
         let exp = now + config.auth.security.jwt_expiry;
 
         Self {
             sub: user.id,
+            sid: session_id,
             exp: exp.timestamp(),
             iat: now.timestamp(),
         }
@@ -33,9 +41,21 @@ impl SessionClaims {
     }
 }
 
+/// Record a new session for a user and create its JWT token
+pub async fn start_session(
+    conn: &mut PgConnection,
+    user: &CurrentUser,
+    user_agent: Option<&str>,
+    config: &Config,
+) -> Result<String, Error> {
+    let expires_at = Utc::now() + config.auth.security.jwt_expiry;
+    let session = Sessions::new(conn).create(user.id, user_agent, expires_at).await?;
+    create_session_token(user, session.id, config)
+}
+
 /// Create a JWT token for a user session
-pub fn create_session_token(user: &CurrentUser, config: &Config) -> Result<String, Error> {
-    let claims = SessionClaims::new(user, config);
+pub fn create_session_token(user: &CurrentUser, session_id: Uuid, config: &Config) -> Result<String, Error> {
+    let claims = SessionClaims::new(user, session_id, config);
     let secret_key = config.secret_key.as_ref().ok_or_else(|| Error::Internal {
         operation: "JWT sessions: secret_key is required".to_string(),
     })?;
@@ -46,8 +66,9 @@ pub fn create_session_token(user: &CurrentUser, config: &Config) -> Result<Strin
     })
 }
 
-/// Verify and decode a JWT session token, returning just the user ID
-pub fn verify_session_token(token: &str, config: &Config) -> Result<UserId, Error> {
+/// Verify and decode a JWT session token. This checks the signature and expiry
+/// only; whether the session has been revoked is up to the caller.
+pub fn verify_session_token(token: &str, config: &Config) -> Result<SessionClaims, Error> {
     let secret_key = config.secret_key.as_ref().ok_or_else(|| Error::Internal {
         operation: "JWT sessions: secret_key is required".to_string(),
     })?;
@@ -90,7 +111,19 @@ pub fn verify_session_token(token: &str, config: &Config) -> Result<UserId, Erro
         },
     })?;
 
-    Ok(token_data.claims.user_id())
+    Ok(token_data.claims)
+}
+
+/// The claims of the first valid session token among a request's cookies
+pub fn session_from_cookies(cookie_header: &str, config: &Config) -> Option<SessionClaims> {
+    let cookie_name = &config.auth.native.session.cookie_name;
+    cookie_header.split(';').find_map(|cookie| {
+        let (name, value) = cookie.trim().split_once('=')?;
+        if name != cookie_name {
+            return None;
+        }
+        verify_session_token(value, config).ok()
+    })
 }
 
 #[cfg(test)]
@@ -139,13 +172,16 @@ mod tests {
         let config = create_test_config();
         let user = create_test_user();
 
+        let session_id = Uuid::new_v4();
+
         // Create token
-        let token = create_session_token(&user, &config).unwrap();
+        let token = create_session_token(&user, session_id, &config).unwrap();
         assert!(!token.is_empty());
 
-        // Verify token - should return user ID only
-        let user_id = verify_session_token(&token, &config).unwrap();
-        assert_eq!(user_id, user.id);
+        // Verify token - should return the user and session IDs
+        let claims = verify_session_token(&token, &config).unwrap();
+        assert_eq!(claims.user_id(), user.id);
+        assert_eq!(claims.sid, session_id);
     }
 
     #[test]
@@ -162,7 +198,7 @@ mod tests {
         let user = create_test_user();
 
         // Create token with one secret
-        let token = create_session_token(&user, &config).unwrap();
+        let token = create_session_token(&user, Uuid::new_v4(), &config).unwrap();
 
         // Try to verify with different secret
         config.secret_key = Some("different-secret".to_string());
@@ -181,6 +217,7 @@ mod tests {
         let now = Utc::now();
         let claims = SessionClaims {
             sub: user.id,
+            sid: Uuid::new_v4(),
             exp: (now - chrono::Duration::seconds(3600)).timestamp(), // 1 hour ago
             iat: now.timestamp(),
         };
@@ -215,18 +252,18 @@ mod tests {
     }
 
     #[test]
-    fn test_jwt_only_contains_user_id() {
+    fn test_jwt_only_contains_user_and_session_ids() {
         let config = create_test_config();
         let user = create_test_user();
 
-        let token = create_session_token(&user, &config).unwrap();
+        let token = create_session_token(&user, Uuid::new_v4(), &config).unwrap();
 
         // Decode without verifying to inspect claims
         let secret_key = config.secret_key.as_ref().unwrap();
         let key = DecodingKey::from_secret(secret_key.as_bytes());
         let token_data = decode::<SessionClaims>(&token, &key, &Validation::default()).unwrap();
 
-        // Verify only user ID is stored (no email, username, roles, etc)
+        // Verify only the user and session IDs are stored (no email, username, roles, etc)
         assert_eq!(token_data.claims.sub, user.id);
         // Claims should have exp and iat too
         assert!(token_data.claims.exp > 0);
@@ -0,0 +1,143 @@
+//! Administrative contract for per-endpoint request and response body transforms.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+use utoipa::ToSchema;
+
+/// Field operations applied to request and response bodies for an endpoint's models.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BodyTransformConfig {
+    /// Applied in order to JSON request bodies before they are forwarded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub request: Vec<BodyTransformOp>,
+    /// Applied in order to non-streaming JSON 2xx response bodies.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub response: Vec<BodyTransformOp>,
+    /// Function calling shape the provider accepts. Chat requests using the other
+    /// shape are translated before the request operations, and responses translated back.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calling: Option<ToolCallingStyle>,
+}
+
+/// How a chat completions request declares callable functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCallingStyle {
+    /// `tools` and `tool_choice`, with `tool_calls` in responses.
+    Tools,
+    /// Legacy `functions` and `function_call`.
+    Functions,
+}
+
+/// A declarative edit to a JSON body. Paths are absolute JSON pointers such as `/messages`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "op", rename_all = "snake_case", deny_unknown_fields)]
+pub enum BodyTransformOp {
+    /// Set `path` to `value`, replacing any existing value.
+    Set { path: String, value: Value },
+    /// Set `path` to `value` only when the field is absent.
+    SetDefault { path: String, value: Value },
+    /// Remove the field at `path` if present.
+    Remove { path: String },
+    /// Move the field at `from` to `to` if present.
+    Rename { from: String, to: String },
+    /// Insert `value` at the start of the array at `path` if present (e.g. a system message).
+    Prepend { path: String, value: Value },
+}
+
+impl BodyTransformConfig {
+    /// Validate using the same implementation that applies transforms at runtime.
+    pub fn validate(&self) -> Result<(), onwards::body_transform::BodyTransformError> {
+        onwards::body_transform::BodyTransformConfig::from(self.clone()).validate()
+    }
+}
+
+/// Parse an endpoint's stored transform, ignoring (with a warning) anything invalid.
+pub(crate) fn parse_body_transform(value: Option<Value>, model_alias: &str) -> Option<BodyTransformConfig> {
+    let value = value?;
+    match serde_json::from_value::<BodyTransformConfig>(value) {
+        Ok(config) => match config.validate() {
+            Ok(()) => Some(config),
+            Err(error) => {
+                warn!(model_alias, %error, "ignoring invalid endpoint body transform");
+                None
+            }
+        },
+        Err(error) => {
+            warn!(model_alias, %error, "ignoring malformed endpoint body transform");
+            None
+        }
+    }
+}
+
+impl From<BodyTransformOp> for onwards::body_transform::BodyTransformOp {
+    fn from(value: BodyTransformOp) -> Self {
+        match value {
+            BodyTransformOp::Set { path, value } => Self::Set { path, value },
+            BodyTransformOp::SetDefault { path, value } => Self::SetDefault { path, value },
+            BodyTransformOp::Remove { path } => Self::Remove { path },
+            BodyTransformOp::Rename { from, to } => Self::Rename { from, to },
+            BodyTransformOp::Prepend { path, value } => Self::Prepend { path, value },
+        }
+    }
+}
+
+impl From<ToolCallingStyle> for onwards::tool_calling::ToolCallingStyle {
+    fn from(value: ToolCallingStyle) -> Self {
+        match value {
+            ToolCallingStyle::Tools => Self::Tools,
+            ToolCallingStyle::Functions => Self::Functions,
+        }
+    }
+}
+
+impl From<BodyTransformConfig> for onwards::body_transform::BodyTransformConfig {
+    fn from(value: BodyTransformConfig) -> Self {
+        Self {
+            request: value.request.into_iter().map(Into::into).collect(),
+            response: value.response.into_iter().map(Into::into).collect(),
+            tool_calling: value.tool_calling.map(Into::into),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn converts_to_onwards_config() {
+        let config: BodyTransformConfig = serde_json::from_value(json!({
+            "request": [
+                { "op": "rename", "from": "/max_completion_tokens", "to": "/max_tokens" },
+                { "op": "prepend", "path": "/messages", "value": { "role": "system", "content": "Be brief." } }
+            ],
+            "response": [{ "op": "remove", "path": "/provider_metadata" }],
+            "tool_calling": "tools"
+        }))
+        .unwrap();
+
+        config.validate().unwrap();
+
+        let onwards_config = onwards::body_transform::BodyTransformConfig::from(config);
+        assert_eq!(onwards_config.request.len(), 2);
+        assert_eq!(onwards_config.tool_calling, Some(onwards::tool_calling::ToolCallingStyle::Tools));
+        assert_eq!(
+            onwards_config.response[0],
+            onwards::body_transform::BodyTransformOp::Remove {
+                path: "/provider_metadata".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_stored_transform_is_ignored() {
+        assert!(parse_body_transform(Some(json!({ "request": [{ "op": "remove", "path": "model" }] })), "m").is_none());
+        assert!(parse_body_transform(Some(json!({ "request": [{ "op": "eval", "code": "1" }] })), "m").is_none());
+        assert!(parse_body_transform(None, "m").is_none());
+        assert!(parse_body_transform(Some(json!({ "request": [{ "op": "remove", "path": "/a" }] })), "m").is_some());
+    }
+}
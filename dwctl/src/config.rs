@@ -18,7 +18,7 @@
 //! ## Usage
 //!
 //! ```no_run
-//! use clap::Parser;
+//! use clap::{Parser, Subcommand};
 //! use dwctl::config::{Args, Config};
 //!
 //! # fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -71,17 +71,21 @@ use figment::{
     Figment,
     providers::{Env, Format, Yaml},
 };
+use ipnet::IpNet;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     ffi::OsString,
+    net::IpAddr,
     path::{Path, PathBuf},
     sync::Arc,
     time::Duration,
 };
 use url::Url;
 
+use crate::api::models::files::FileValidationMode;
 use crate::api::models::users::Role;
+use crate::db::models::api_keys::ApiKeyPurpose;
 use crate::errors::Error;
 use crate::sample_files::SampleFilesConfig;
 
@@ -100,13 +104,36 @@ pub struct Args {
     /// Useful for CI/CD pipelines to catch config errors before deployment.
     #[arg(long)]
     pub validate: bool,
+
+    /// Start without applying database migrations, for deployments that run
+    /// `dwctl migrate` separately. Startup fails if any are still pending.
+    #[arg(long)]
+    pub skip_migrations: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// CLI subcommands. Without one, dwctl starts the server.
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Load and validate the configuration, print a report and exit without
+    /// binding a port or running migrations. Exits non-zero if any check fails.
+    ValidateConfig {
+        /// Also check that the configured database (and replica) accept connections
+        #[arg(long)]
+        check_database: bool,
+    },
+    /// Apply all pending database migrations (dwctl, fusillade and, when
+    /// request logging is enabled, outlet) and exit without starting the server.
+    Migrate,
 }
 
 /// Main application configuration.
 ///
 /// This is the root configuration structure loaded from YAML and environment variables.
 /// All fields have sensible defaults defined in the `Default` implementation.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Clone, Deserialize, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct Config {
     /// HTTP server host to bind to (e.g., "0.0.0.0" for all interfaces)
@@ -127,14 +154,23 @@ pub struct Config {
     pub database: DatabaseConfig,
     /// Threshold in milliseconds for logging slow SQL statements (default: 1000ms)
     pub slow_statement_threshold_ms: u64,
+    /// Don't apply database migrations on startup; they are run separately with
+    /// `dwctl migrate`. Startup fails if any are still pending. Also set by
+    /// `--skip-migrations`.
+    pub skip_migrations: bool,
     /// Email address for the initial admin user (created on first startup)
     pub admin_email: String,
     /// Password for the initial admin user (optional, can be set via environment)
     pub admin_password: Option<String>,
+    /// Secret for the system API key, set when the database is first seeded
+    /// (optional, can be set via environment). Generated at random when unset.
+    pub system_api_key: Option<String>,
     /// Secret key for JWT signing and encryption (required for production)
     pub secret_key: Option<String>,
     /// Model sources for syncing available models
     pub model_sources: Vec<ModelSource>,
+    /// Retries for fetching models when endpoints are validated and synchronized
+    pub endpoint_sync: EndpointSyncConfig,
     /// Frontend metadata displayed in the UI
     pub metadata: Metadata,
     /// Payment provider configuration (Stripe, PayPal, etc.)
@@ -166,6 +202,8 @@ pub struct Config {
     pub analytics: AnalyticsConfig,
     /// Enable OpenTelemetry OTLP export for distributed tracing
     pub enable_otel_export: bool,
+    /// Log output format and per-module verbosity
+    pub log: LogConfig,
     /// Credit system configuration
     pub credits: CreditsConfig,
     /// Sample file generation configuration for new users
@@ -205,6 +243,10 @@ pub struct Config {
     /// (the default) leaves ZDR disabled.
     #[serde(default)]
     pub keystore: Option<crate::keystore::KeystoreConfig>,
+    /// External secret references (`env:`, `file:`, `vault:`) for inference
+    /// endpoint API keys. Inline keys need no configuration.
+    #[serde(default)]
+    pub secrets: crate::secrets::SecretsConfig,
     /// OpenAPI spec exposure controls. Defaults disable the Admin spec
     /// (which describes internal management endpoints) and enable the
     /// AI spec (which mirrors the publicly-documented OpenAI surface).
@@ -217,6 +259,54 @@ pub struct Config {
     pub cache: CacheConfig,
 }
 
+// Manual Debug so the admin password, system API key and secret key never
+// reach the logged config.
+impl std::fmt::Debug for Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Config")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("dashboard_url", &self.dashboard_url)
+            .field("database_url", &self.database_url)
+            .field("database_replica_url", &self.database_replica_url)
+            .field("database", &self.database)
+            .field("slow_statement_threshold_ms", &self.slow_statement_threshold_ms)
+            .field("skip_migrations", &self.skip_migrations)
+            .field("admin_email", &self.admin_email)
+            .field("admin_password", &self.admin_password.as_ref().map(|_| "<redacted>"))
+            .field("system_api_key", &self.system_api_key.as_ref().map(|_| "<redacted>"))
+            .field("secret_key", &self.secret_key.as_ref().map(|_| "<redacted>"))
+            .field("model_sources", &self.model_sources)
+            .field("endpoint_sync", &self.endpoint_sync)
+            .field("metadata", &self.metadata)
+            .field("payment", &self.payment)
+            .field("auth", &self.auth)
+            .field("batches", &self.batches)
+            .field("background_services", &self.background_services)
+            .field("enable_metrics", &self.enable_metrics)
+            .field("enable_request_logging", &self.enable_request_logging)
+            .field("enable_analytics", &self.enable_analytics)
+            .field("analytics", &self.analytics)
+            .field("enable_otel_export", &self.enable_otel_export)
+            .field("log", &self.log)
+            .field("credits", &self.credits)
+            .field("sample_files", &self.sample_files)
+            .field("limits", &self.limits)
+            .field("email", &self.email)
+            .field("onwards", &self.onwards)
+            .field("onboarding_url", &self.onboarding_url)
+            .field("support_email", &self.support_email)
+            .field("connections", &self.connections)
+            .field("responses", &self.responses)
+            .field("image_normalizer", &self.image_normalizer)
+            .field("keystore", &self.keystore)
+            .field("secrets", &self.secrets)
+            .field("openapi", &self.openapi)
+            .field("cache", &self.cache)
+            .finish()
+    }
+}
+
 /// Controls exposure of the OpenAPI specs and Scalar doc UIs.
 ///
 /// Both surfaces are mounted by default but always require
@@ -415,6 +505,13 @@ pub enum DatabaseConfig {
         /// Whether to persist data between restarts (default: false/ephemeral)
         #[serde(default)]
         persistent: bool,
+        /// PostgreSQL version to run, as a major (`"15"`) or exact (`"15.8.0"`) version
+        /// (default: the bundled version, 16). Other versions are downloaded on first start.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        version: Option<String>,
+        /// Extensions created in the database on startup (e.g. `["pg_trgm"]`)
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        extensions: Vec<String>,
         /// Main database connection pool settings for primary (and replica if not specified)
         #[serde(default)]
         pool: PoolSettings,
@@ -447,6 +544,15 @@ pub enum DatabaseConfig {
         /// If not specified, uses the same settings as `pool`
         #[serde(default, skip_serializing_if = "Option::is_none")]
         replica_pool: Option<PoolSettings>,
+        /// Optional dedicated replica for heavy analytics reads (request
+        /// aggregates, transaction listings and exports). If not specified,
+        /// these use the read replica, or the primary without one.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        analytics_replica_url: Option<String>,
+        /// Optional pool settings for the analytics replica
+        /// If not specified, uses the replica pool settings
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        analytics_pool: Option<PoolSettings>,
         /// Fusillade batch processing database configuration
         #[serde(default = "default_fusillade_component")]
         fusillade: ComponentDb,
@@ -468,6 +574,8 @@ impl Default for DatabaseConfig {
             DatabaseConfig::Embedded {
                 data_dir: None,
                 persistent: false,
+                version: None,
+                extensions: Vec::new(),
                 pool: PoolSettings::default(),
                 replica_pool: None,
                 fusillade: default_fusillade_component(),
@@ -482,6 +590,8 @@ impl Default for DatabaseConfig {
                 replica_url: None,
                 pool: PoolSettings::default(),
                 replica_pool: None,
+                analytics_replica_url: None,
+                analytics_pool: None,
                 fusillade: default_fusillade_component(),
                 outlet: default_outlet_component(),
                 underway_pool: default_underway_pool(),
@@ -512,6 +622,26 @@ impl DatabaseConfig {
         }
     }
 
+    /// Get the analytics replica URL if configured
+    pub fn analytics_replica_url(&self) -> Option<&str> {
+        match self {
+            DatabaseConfig::External { analytics_replica_url, .. } => analytics_replica_url.as_deref(),
+            DatabaseConfig::Embedded { .. } => None,
+        }
+    }
+
+    /// Get the analytics replica pool settings
+    /// Returns the analytics_pool if specified, otherwise the main replica pool settings
+    pub fn analytics_pool_settings(&self) -> &PoolSettings {
+        match self {
+            DatabaseConfig::External {
+                analytics_pool: Some(analytics_pool),
+                ..
+            } => analytics_pool,
+            _ => self.main_replica_pool_settings(),
+        }
+    }
+
     /// Get embedded data directory if configured
     pub fn embedded_data_dir(&self) -> Option<PathBuf> {
         match self {
@@ -528,6 +658,22 @@ impl DatabaseConfig {
         }
     }
 
+    /// Get the embedded PostgreSQL version if configured
+    pub fn embedded_version(&self) -> Option<&str> {
+        match self {
+            DatabaseConfig::Embedded { version, .. } => version.as_deref(),
+            DatabaseConfig::External { .. } => None,
+        }
+    }
+
+    /// Get the extensions to create in the embedded database
+    pub fn embedded_extensions(&self) -> &[String] {
+        match self {
+            DatabaseConfig::Embedded { extensions, .. } => extensions,
+            DatabaseConfig::External { .. } => &[],
+        }
+    }
+
     /// Get the main database primary pool settings
     pub fn main_pool_settings(&self) -> &PoolSettings {
         match self {
@@ -678,6 +824,28 @@ pub struct DefaultModel {
     pub add_to_everyone_group: bool,
 }
 
+/// Retry policy for inference endpoint model discovery and synchronization.
+///
+/// Timeouts, connection failures and 408/429/5xx responses are retried with
+/// exponential backoff; other errors (e.g. 401, malformed model lists) fail immediately.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EndpointSyncConfig {
+    /// Attempts made after the first one fails with a transient error (default: 2). Set to `0` to disable retries.
+    pub max_retries: u32,
+    /// Delay before the first retry in milliseconds (default: 500ms). Doubles on each further retry.
+    pub retry_initial_delay_milliseconds: u64,
+}
+
+impl Default for EndpointSyncConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            retry_initial_delay_milliseconds: 500,
+        }
+    }
+}
+
 /// Authentication configuration for all supported auth methods.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -696,6 +864,15 @@ pub struct AuthConfig {
     /// `verified` flag. Only used when the api_key has no explicit per-key
     /// override. Leaving either tier as `None` means "no limit for that tier".
     pub rate_limits: RateLimitTiersConfig,
+    /// Role that may manage API keys for the other members of its groups
+    /// (the Everyone group excluded), like a PlatformManager can for any user.
+    /// Members who are admins or hold a role the manager lacks are excluded,
+    /// and delegated managers can only create inference keys. Unset (the
+    /// default) disables delegated key management.
+    pub api_key_manager_role: Option<Role>,
+    /// Which purposes users may create API keys with, and the purpose used
+    /// when a request doesn't give one
+    pub api_key_purposes: ApiKeyPurposesConfig,
 }
 
 impl Default for AuthConfig {
@@ -706,6 +883,29 @@ impl Default for AuthConfig {
             security: SecurityConfig::default(),
             default_user_roles: vec![Role::StandardUser],
             rate_limits: RateLimitTiersConfig::default(),
+            api_key_manager_role: None,
+            api_key_purposes: ApiKeyPurposesConfig::default(),
+        }
+    }
+}
+
+/// API key purposes users may create. A per-user override set through the
+/// API replaces these; otherwise a user may use any purpose allowed to one of
+/// their roles. Roles without an entry may use every purpose.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiKeyPurposesConfig {
+    /// Purpose given to a key created without one
+    pub default: ApiKeyPurpose,
+    /// Purposes each role may create keys with
+    pub by_role: HashMap<Role, Vec<ApiKeyPurpose>>,
+}
+
+impl Default for ApiKeyPurposesConfig {
+    fn default() -> Self {
+        Self {
+            default: ApiKeyPurpose::Realtime,
+            by_role: HashMap::new(),
         }
     }
 }
@@ -748,7 +948,7 @@ pub struct NativeAuthConfig {
 ///
 /// This authentication method reads user identity from HTTP headers set by an upstream
 /// proxy (e.g., SSO proxy). Enables integration with external authentication systems.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Clone, Deserialize, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct ProxyHeaderAuthConfig {
     /// Enable proxy header authentication
@@ -790,6 +990,101 @@ pub struct ProxyHeaderAuthConfig {
     /// a new user with email taken from 'email_header_name',
     /// and groups taken from groups_field_name.
     pub auto_create_users: bool,
+    /// Source addresses allowed to set the identity headers, as CIDRs or bare IPs
+    /// (e.g. `10.0.0.0/8`, `192.168.1.10`).
+    #[serde(deserialize_with = "deserialize_trusted_proxies")]
+    pub trusted_proxies: Vec<IpNet>,
+    /// Secret the proxy sends in `shared_secret_header_name` to prove it set the
+    /// identity headers. Useful when the proxy's address isn't stable.
+    pub shared_secret: Option<String>,
+    /// HTTP header name carrying `shared_secret`
+    pub shared_secret_header_name: String,
+}
+
+// Manual Debug so the shared secret never reaches the logged config.
+impl std::fmt::Debug for ProxyHeaderAuthConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProxyHeaderAuthConfig")
+            .field("enabled", &self.enabled)
+            .field("header_name", &self.header_name)
+            .field("email_header_name", &self.email_header_name)
+            .field("groups_field_name", &self.groups_field_name)
+            .field("import_idp_groups", &self.import_idp_groups)
+            .field("blacklisted_sso_groups", &self.blacklisted_sso_groups)
+            .field("provider_field_name", &self.provider_field_name)
+            .field("auto_create_users", &self.auto_create_users)
+            .field("trusted_proxies", &self.trusted_proxies)
+            .field("shared_secret", &self.shared_secret.as_ref().map(|_| "<redacted>"))
+            .field("shared_secret_header_name", &self.shared_secret_header_name)
+            .finish()
+    }
+}
+
+impl ProxyHeaderAuthConfig {
+    /// Whether `trusted_proxies` or `shared_secret` restricts who may send identity headers
+    pub fn has_trust_controls(&self) -> bool {
+        !self.trusted_proxies.is_empty() || self.shared_secret.is_some()
+    }
+
+    /// Whether identity headers on a request should be honoured. A request is trusted if
+    /// it comes from a `trusted_proxies` peer or carries the shared secret. With neither
+    /// configured every request is trusted, so the proxy must be the only route to dwctl.
+    /// `peer` is `None` when the connection address is unknown.
+    pub fn is_trusted_source(&self, peer: Option<IpAddr>, headers: &axum::http::HeaderMap) -> bool {
+        if !self.has_trust_controls() {
+            return true;
+        }
+
+        // Normalise IPv4-mapped IPv6 peers (dual-stack listeners) so IPv4 ranges match
+        if let Some(peer) = peer.map(|ip| ip.to_canonical())
+            && self.trusted_proxies.iter().any(|net| net.contains(&peer))
+        {
+            return true;
+        }
+
+        match (&self.shared_secret, headers.get(&self.shared_secret_header_name)) {
+            (Some(secret), Some(presented)) => constant_time_eq(secret.as_bytes(), presented.as_bytes()),
+            _ => false,
+        }
+    }
+
+    /// Remove the identity and shared-secret headers, which must never reach a provider
+    pub fn strip_headers(&self, headers: &mut axum::http::HeaderMap) {
+        for name in [
+            &self.header_name,
+            &self.email_header_name,
+            &self.groups_field_name,
+            &self.provider_field_name,
+            &self.shared_secret_header_name,
+        ] {
+            headers.remove(name.as_str());
+        }
+    }
+}
+
+/// Compare secrets without short-circuiting on the first differing byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Parse trusted proxy entries, accepting bare addresses as single-host networks
+fn deserialize_trusted_proxies<'de, D>(deserializer: D) -> Result<Vec<IpNet>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+
+    let entries: Vec<String> = Vec::deserialize(deserializer)?;
+    entries
+        .iter()
+        .map(|entry| {
+            let entry = entry.trim();
+            entry
+                .parse::<IpNet>()
+                .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from))
+                .map_err(|_| D::Error::custom(format!("trusted_proxies entry '{}' is not an IP address or CIDR", entry)))
+        })
+        .collect()
 }
 
 /// Session cookie configuration.
@@ -955,6 +1250,9 @@ pub struct FilesConfig {
     pub download_buffer_size: usize,
     /// Number of templates to insert in each batch during file upload (default: 5000)
     pub batch_insert_size: usize,
+    /// How uploads treat invalid lines when they don't choose with the
+    /// `validation` query parameter (default: strict)
+    pub validation_mode: FileValidationMode,
 }
 
 impl Default for FilesConfig {
@@ -966,6 +1264,7 @@ impl Default for FilesConfig {
             upload_buffer_size: 100,
             download_buffer_size: 100,
             batch_insert_size: 5000,
+            validation_mode: FileValidationMode::default(),
         }
     }
 }
@@ -981,6 +1280,65 @@ pub struct LimitsConfig {
     pub files: FileLimitsConfig,
     /// Request limits (per-request body size within batch files)
     pub requests: RequestLimitsConfig,
+    /// Rate limits given to new deployments that don't set their own
+    pub deployments: DeploymentLimitsConfig,
+    /// Per-user rate limits on the admin API
+    pub admin_api: AdminApiLimitsConfig,
+    /// Per-user caps on active API keys
+    pub api_keys: ApiKeyLimitsConfig,
+}
+
+/// Per-user caps on active (non-deleted) API keys.
+///
+/// Creating a key beyond the cap of the user who will own it fails with HTTP
+/// 409. Hidden system keys are not counted. Each field left unset keeps that
+/// tier unlimited.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiKeyLimitsConfig {
+    /// Maximum active API keys per user
+    pub max_active_per_user: Option<u32>,
+    /// Maximum active API keys for platform managers, replacing
+    /// `max_active_per_user` for them
+    pub platform_manager_max_active_per_user: Option<u32>,
+}
+
+/// Per-user rate limits on the admin API (`/admin/api/v1/*`).
+///
+/// Each authenticated user gets their own token bucket; requests over the limit
+/// receive HTTP 429 with a `Retry-After` header. Unauthenticated requests and
+/// routes outside the admin API (health, version, metrics, payment webhooks)
+/// are not limited. Each field left unset keeps that tier unlimited.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdminApiLimitsConfig {
+    /// Sustained requests per second for each user
+    pub requests_per_second: Option<f32>,
+    /// Requests a user may make at once before the sustained rate applies.
+    /// Default: `requests_per_second` rounded up
+    pub burst_size: Option<u32>,
+    /// Sustained requests per second for platform managers, replacing
+    /// `requests_per_second` for them
+    pub platform_manager_requests_per_second: Option<f32>,
+    /// Burst size for platform managers.
+    /// Default: `platform_manager_requests_per_second` rounded up
+    pub platform_manager_burst_size: Option<u32>,
+}
+
+/// Default rate limits for new deployments.
+///
+/// Applied when a deployment is created without an explicit value for the
+/// field. Each field left unset here keeps the previous unlimited default.
+/// Existing deployments are never changed.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DeploymentLimitsConfig {
+    /// Default maximum requests per second
+    pub requests_per_second: Option<f32>,
+    /// Default burst size for the rate limiter
+    pub burst_size: Option<i32>,
+    /// Default maximum concurrent requests
+    pub capacity: Option<i32>,
 }
 
 /// Request limits configuration.
@@ -1017,6 +1375,268 @@ pub struct OnwardsConfig {
     /// When false (default), all requests are passed through transparently.
     /// When true, only known OpenAI API paths are accepted and validated.
     pub strict_mode: bool,
+    /// AI API paths rejected platform-wide with a 404, regardless of model or
+    /// strict mode (e.g. `["/v1/completions"]` to block the legacy completions
+    /// endpoint). Each entry also disables the paths beneath it.
+    pub disabled_paths: Vec<String>,
+    /// How requested model names are matched against model aliases. With
+    /// anything other than `exact`, a request for `GPT-4` is routed (and billed)
+    /// as `gpt-4`, and creating aliases that would become indistinguishable is
+    /// rejected.
+    pub alias_normalization: AliasNormalization,
+    /// Response to requests for a deactivated model: `not_found` (the default)
+    /// answers as if the model didn't exist, `unavailable` returns a 503
+    /// saying it is disabled.
+    pub deactivated_models: DeactivatedModelResponse,
+    /// Response to requests for a model the API key's user has no group access
+    /// to: `not_found` (the default) answers as if the model didn't exist, so
+    /// callers can't discover models they can't use; `forbidden` returns a 403
+    /// saying access is denied.
+    pub unauthorized_models: UnauthorizedModelResponse,
+    /// Extra request headers an API key is accepted from (e.g. `["api-key", "x-api-key"]`),
+    /// for clients whose SDKs can't send `Authorization: Bearer`. The key is
+    /// moved into `Authorization: Bearer` and authenticated exactly like one
+    /// sent there; a request that already has an `Authorization` header keeps it.
+    pub api_key_headers: Vec<String>,
+    /// Replay of identical deterministic chat completions. See [`ResponseCacheConfig`].
+    pub response_cache: ResponseCacheConfig,
+    /// Heartbeats on idle streaming responses. See [`StreamKeepaliveConfig`].
+    pub stream_keepalive: StreamKeepaliveConfig,
+    /// Connections from the proxy to inference endpoints. See [`UpstreamHttpConfig`].
+    pub upstream_http: UpstreamHttpConfig,
+    /// Failing fast on endpoints that are down. See [`CircuitBreakerConfig`].
+    pub circuit_breaker: CircuitBreakerConfig,
+    /// Waiting for capacity on deployments at their concurrency limit. See [`RequestQueueConfig`].
+    pub request_queue: RequestQueueConfig,
+}
+
+/// Response caching for deterministic chat completions.
+///
+/// When enabled, a non-streaming `/v1/chat/completions` request whose `temperature`
+/// is at most `max_temperature` is looked up by a hash of its body (object keys
+/// sorted), scoped to the deployment and the API key's owner. A hit returns the
+/// stored response without an upstream call, request log or charge. Clients opt
+/// out per request with `Cache-Control: no-cache` or `no-store`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ResponseCacheConfig {
+    /// Enable response caching (default: false)
+    pub enabled: bool,
+    /// How long a cached response is served before the next request goes upstream again (default: 1h)
+    #[serde(with = "humantime_serde")]
+    pub ttl: Duration,
+    /// Highest `temperature` that is cached (default: 0). A request without a
+    /// `temperature` uses the OpenAI default of 1.
+    pub max_temperature: f64,
+}
+
+impl Default for ResponseCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl: Duration::from_secs(3600),
+            max_temperature: 0.0,
+        }
+    }
+}
+
+/// Keepalive comments for slow streaming responses.
+///
+/// When enabled, a `: keepalive` SSE comment is written to a streaming response
+/// that has sent nothing for `interval`, so load balancers that drop idle
+/// connections keep it open. Heartbeats are only written between events and
+/// are never logged or billed.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StreamKeepaliveConfig {
+    /// Enable keepalive comments (default: false)
+    pub enabled: bool,
+    /// How long a stream may be silent before a heartbeat is sent (default: 15s)
+    #[serde(with = "humantime_serde")]
+    pub interval: Duration,
+}
+
+impl Default for StreamKeepaliveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: Duration::from_secs(15),
+        }
+    }
+}
+
+/// HTTP connections from the AI proxy to inference endpoints.
+///
+/// Applies to every endpoint. Passed to onwards as its `http_pool` config.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UpstreamHttpConfig {
+    /// Idle connections kept open per endpoint host (default: 100)
+    pub max_idle_per_host: usize,
+    /// How long an idle connection is kept open (default: 90s)
+    #[serde(with = "humantime_serde")]
+    pub idle_timeout: Duration,
+    /// Open connections as HTTP/2 without negotiating it first (default: false).
+    /// Every endpoint must then accept HTTP/2 this way, e.g. cleartext h2c.
+    pub http2_prior_knowledge: bool,
+    /// Request gzip-compressed responses from endpoints when the client sent no
+    /// `Accept-Encoding`, decompressing them before they are logged or returned (default: false)
+    pub gzip: bool,
+}
+
+impl Default for UpstreamHttpConfig {
+    fn default() -> Self {
+        Self {
+            max_idle_per_host: 100,
+            idle_timeout: Duration::from_secs(90),
+            http2_prior_knowledge: false,
+            gzip: false,
+        }
+    }
+}
+
+impl From<&UpstreamHttpConfig> for onwards::target::HttpPoolConfig {
+    fn from(config: &UpstreamHttpConfig) -> Self {
+        Self {
+            max_idle_per_host: config.max_idle_per_host,
+            idle_timeout_secs: config.idle_timeout.as_secs(),
+            http2_prior_knowledge: config.http2_prior_knowledge,
+            gzip: config.gzip,
+        }
+    }
+}
+
+/// Circuit breaking for inference endpoints.
+///
+/// When enabled, an endpoint that fails `failure_threshold` times in a row
+/// (connection errors, timeouts or 5xx responses) within `window` is skipped
+/// for `cooldown`: virtual models fall back to their other components at once,
+/// and other requests get a 503 without reaching the endpoint. After the
+/// cooldown a single request is let through to check whether it has recovered.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CircuitBreakerConfig {
+    /// Enable circuit breaking (default: false)
+    pub enabled: bool,
+    /// Consecutive failures that open the breaker (default: 5)
+    pub failure_threshold: u32,
+    /// Failures further apart than this are not counted together (default: 30s)
+    #[serde(with = "humantime_serde")]
+    pub window: Duration,
+    /// How long an open breaker skips the endpoint (default: 30s)
+    #[serde(with = "humantime_serde")]
+    pub cooldown: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            failure_threshold: 5,
+            window: Duration::from_secs(30),
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+impl From<&CircuitBreakerConfig> for onwards::circuit_breaker::CircuitBreakerConfig {
+    fn from(config: &CircuitBreakerConfig) -> Self {
+        Self {
+            failure_threshold: config.failure_threshold,
+            window_secs: config.window.as_secs(),
+            cooldown_secs: config.cooldown.as_secs(),
+        }
+    }
+}
+
+/// Queueing for deployments at their concurrency limit.
+///
+/// When enabled, a request to a deployment with no free concurrency slot
+/// waits up to `max_wait` for one instead of getting a 429 at once. Waiting
+/// requests are let in highest `X-Priority` first, up to the API key's
+/// `max_priority`; a request's priority rises by one for every `aging` it has
+/// waited, so low-priority requests are not starved.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RequestQueueConfig {
+    /// Enable queueing (default: false)
+    pub enabled: bool,
+    /// Longest a request waits for a slot before it gets a 429 (default: 30s)
+    #[serde(with = "humantime_serde")]
+    pub max_wait: Duration,
+    /// Wait after which a queued request's priority rises by one (default: 1s)
+    #[serde(with = "humantime_serde")]
+    pub aging: Duration,
+}
+
+impl Default for RequestQueueConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_wait: Duration::from_secs(30),
+            aging: Duration::from_secs(1),
+        }
+    }
+}
+
+impl From<&RequestQueueConfig> for onwards::priority::QueueConfig {
+    fn from(config: &RequestQueueConfig) -> Self {
+        Self {
+            max_wait: config.max_wait,
+            aging: config.aging,
+        }
+    }
+}
+
+/// How requested model names are matched against model aliases.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AliasNormalization {
+    /// Names must match an alias exactly
+    #[default]
+    Exact,
+    /// Ignore case, so `GPT-4` matches `gpt-4`
+    CaseInsensitive,
+    /// Ignore case, `-`, `_` and whitespace, so `gpt4` and `GPT_4` match `gpt-4`
+    CaseAndSeparators,
+}
+
+impl AliasNormalization {
+    /// The key two names share when they match, or `None` in `exact` mode.
+    pub fn normalize(self, name: &str) -> Option<String> {
+        match self {
+            Self::Exact => None,
+            Self::CaseInsensitive => Some(name.to_lowercase()),
+            Self::CaseAndSeparators => Some(
+                name.chars()
+                    .filter(|c| !matches!(c, '-' | '_') && !c.is_whitespace())
+                    .flat_map(char::to_lowercase)
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// How requests for a deactivated model are answered.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeactivatedModelResponse {
+    /// 404 `model_not_found`, as for a model that doesn't exist
+    #[default]
+    NotFound,
+    /// 503 `model_disabled`
+    Unavailable,
+}
+
+/// How requests for a model the caller has no access to are answered.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UnauthorizedModelResponse {
+    /// 404 `model_not_found`, as for a model that doesn't exist
+    #[default]
+    NotFound,
+    /// 403 saying the caller has no access to the model
+    Forbidden,
 }
 
 /// Cached-input pricing — the dwctl-owned cache tower layer. All cache configuration lives
@@ -1294,6 +1914,15 @@ pub struct BatchConfig {
     /// Verified creditors are never limited. Set to 0 to disable the cap.
     /// Default: 1000.
     pub unverified_requests_per_completion_hour: usize,
+    /// Maximum number of requests in a single batch. Batches created from a
+    /// larger input file are rejected with 413. Set to 0 to disable.
+    /// Default: 0.
+    pub max_requests_per_batch: usize,
+    /// Maximum number of active (not yet completed, failed, cancelled or
+    /// expired) batches a user or organization can have at once. Further
+    /// batches are rejected with 429 until one finishes. Set to 0 to disable.
+    /// Default: 0.
+    pub max_active_batches_per_user: usize,
 
     /// Include committed pending/claimed/processing requests in batch admission capacity checks.
     /// When false, admission capacity checks only include active in-flight reservations.
@@ -1438,6 +2067,8 @@ impl Default for BatchConfig {
             reservation_ttl_secs: default_reservation_ttl_secs(),
             priority_decay_window_secs: None,
             unverified_requests_per_completion_hour: 1000,
+            max_requests_per_batch: 0,
+            max_active_batches_per_user: 0,
             pending_capacity_counts_enabled: false,
         }
     }
@@ -2014,6 +2645,43 @@ impl DaemonConfig {
     }
 }
 
+/// Log output configuration.
+///
+/// The filter uses `RUST_LOG` directive syntax, so a single module can be made
+/// more or less verbose without touching the rest, e.g.
+/// `info,dwctl::sync::onwards_config=debug`. When the `RUST_LOG` environment
+/// variable is set it takes precedence over `filter`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogConfig {
+    /// Output format (default: compact)
+    pub format: LogFormat,
+    /// Filter directives, `RUST_LOG` syntax (default: "info")
+    pub filter: String,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            format: LogFormat::Compact,
+            filter: "info".to_string(),
+        }
+    }
+}
+
+/// Log line format.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Single-line human-readable output
+    #[default]
+    Compact,
+    /// Multi-line human-readable output, for local development
+    Pretty,
+    /// One JSON object per line, for log aggregation
+    Json,
+}
+
 /// Controls when the batch processing daemon runs.
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -2084,12 +2752,22 @@ pub struct BackgroundServicesConfig {
     pub usage_refresh: UsageRefreshConfig,
     /// Configuration for probe scheduler service
     pub probe_scheduler: ProbeSchedulerConfig,
+    /// Configuration for periodic model syncs of endpoints with an auto-sync interval
+    pub endpoint_auto_sync: EndpointAutoSyncConfig,
+    /// Configuration for warmup requests to deployments with `warmup` set
+    pub deployment_warmup: DeploymentWarmupConfig,
+    /// Configuration for periodic reconciliation of balance checkpoints against the ledger
+    pub balance_checkpoints: BalanceCheckpointConfig,
+    /// Configuration for archiving and deleting old finished batches
+    pub batch_retention: BatchRetentionConfig,
     /// Configuration for batch processing daemon
     pub batch_daemon: DaemonConfig,
     /// Leader election configuration for multi-instance deployments
     pub leader_election: LeaderElectionConfig,
     /// Configuration for database pool metrics sampling
     pub pool_metrics: PoolMetricsSamplerConfig,
+    /// Configuration for batch queue depth metrics sampling
+    pub batch_queue_metrics: BatchQueueMetricsConfig,
     /// Configuration for batch completion notifications (email + webhooks)
     pub notifications: NotificationsConfig,
     /// Configuration for connection sync workers (file ingestion, batch activation)
@@ -2118,6 +2796,30 @@ impl Default for PoolMetricsSamplerConfig {
     }
 }
 
+/// Batch queue metrics sampling configuration.
+///
+/// Controls how often batch queue depth (pending and in-progress requests per
+/// model) is read from fusillade and exported as gauges. Only runs when
+/// `enable_metrics` is set.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BatchQueueMetricsConfig {
+    /// Enable the batch queue metrics sampler (default: true)
+    pub enabled: bool,
+    /// How often to sample queue depth (default: 15s)
+    #[serde(with = "humantime_serde")]
+    pub sample_interval: Duration,
+}
+
+impl Default for BatchQueueMetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            sample_interval: Duration::from_secs(15),
+        }
+    }
+}
+
 /// Onwards configuration sync service configuration.
 ///
 /// This service syncs database configuration changes to the onwards routing layer via PostgreSQL LISTEN/NOTIFY.
@@ -2141,6 +2843,17 @@ pub struct OnwardsSyncConfig {
     /// removes protection against missed notifications and is generally not recommended
     /// in production environments.
     pub fallback_interval_milliseconds: u64,
+    /// Delay before the first LISTEN reconnect attempt after the connection drops, in milliseconds
+    /// (default: 100ms). Doubles on each consecutive failure, with jitter, and resets on success.
+    pub reconnect_initial_delay_milliseconds: u64,
+    /// Maximum delay between LISTEN reconnect attempts in milliseconds (default: 30000ms = 30 seconds)
+    pub reconnect_max_delay_milliseconds: u64,
+    /// Drift check interval in milliseconds (default: 600000ms = 10 minutes)
+    ///
+    /// The leader compares its live routing table with one freshly built from the database,
+    /// and on a mismatch logs it, counts it in `dwctl_onwards_config_drift_total` and forces
+    /// every replica to resync. Each check is a full routing-table load. Set to `0` to disable.
+    pub drift_check_interval_milliseconds: u64,
 }
 
 impl Default for OnwardsSyncConfig {
@@ -2148,6 +2861,9 @@ impl Default for OnwardsSyncConfig {
         Self {
             enabled: true,
             fallback_interval_milliseconds: 300_000, // 5 minutes (NOTIFY handles real changes; this is only a missed-notification safety net)
+            reconnect_initial_delay_milliseconds: 100,
+            reconnect_max_delay_milliseconds: 30_000,
+            drift_check_interval_milliseconds: 600_000,
         }
     }
 }
@@ -2197,47 +2913,216 @@ pub struct ProbeSchedulerConfig {
     /// Enable probe scheduler service (default: true)
     /// When leader election is enabled, the probe scheduler only runs on the elected leader
     pub enabled: bool,
+    /// Retention and hourly downsampling of probe results
+    pub retention: ProbeRetentionConfig,
 }
 
 impl Default for ProbeSchedulerConfig {
     fn default() -> Self {
-        Self { enabled: true }
+        Self {
+            enabled: true,
+            retention: ProbeRetentionConfig::default(),
+        }
     }
 }
 
-/// Webhook delivery service configuration.
+/// Probe result retention configuration.
 ///
-/// The webhook service delivers Standard Webhooks-compliant notifications
-/// for batch terminal state events (completed, failed, cancelled).
+/// Raw probe results older than `raw_retention` are rolled up into hourly
+/// aggregates (min/max/avg latency and success counts), which are kept for
+/// `aggregate_retention`. Runs on the leader alongside the probe scheduler.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default, deny_unknown_fields)]
-pub struct WebhookConfig {
-    /// Enable webhook delivery service (default: true)
+pub struct ProbeRetentionConfig {
+    /// Enable downsampling and pruning of probe results (default: true)
     pub enabled: bool,
-    /// HTTP timeout for webhook deliveries in seconds (default: 30)
-    pub timeout_secs: u64,
-    /// Retry backoff schedule in seconds. Each entry is the delay before the
-    /// corresponding attempt. The length of this list is the maximum number of
-    /// attempts — once exhausted, the delivery is marked as exhausted.
-    ///
-    /// Default: [0, 5, 300, 1800, 7200, 28800, 86400]
-    ///          (immediate, 5s, 5m, 30m, 2h, 8h, 24h)
-    pub retry_schedule_secs: Vec<i64>,
-    /// Number of consecutive failures before disabling a webhook (default: 10)
-    pub circuit_breaker_threshold: i32,
-    /// Maximum deliveries to claim from the database per tick (default: 50)
-    pub claim_batch_size: i64,
-    /// Maximum concurrent outbound HTTP requests (default: 20)
-    pub max_concurrent_sends: usize,
-    /// Internal channel buffer capacity for send requests and results (default: 200)
-    pub channel_capacity: usize,
+    /// How long raw probe results are kept before being downsampled (default: 7 days).
+    /// Must be at least 24h, since uptime percentages are computed from raw results.
+    #[serde(with = "humantime_serde")]
+    pub raw_retention: Duration,
+    /// How long hourly aggregates are kept (default: 365 days)
+    #[serde(with = "humantime_serde")]
+    pub aggregate_retention: Duration,
+    /// How often the retention job runs (default: 1 hour)
+    #[serde(with = "humantime_serde")]
+    pub run_interval: Duration,
 }
 
-impl Default for WebhookConfig {
+impl Default for ProbeRetentionConfig {
     fn default() -> Self {
         Self {
             enabled: true,
-            timeout_secs: 30,
+            raw_retention: Duration::from_secs(7 * 24 * 60 * 60),
+            aggregate_retention: Duration::from_secs(365 * 24 * 60 * 60),
+            run_interval: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+/// Endpoint auto-sync configuration.
+///
+/// Endpoints with `auto_sync_interval_seconds` set have their model lists
+/// re-synced once that interval has elapsed since their last automatic sync.
+/// This job checks for due endpoints every `check_interval`. Runs on the leader.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EndpointAutoSyncConfig {
+    /// Enable automatic endpoint syncs (default: true)
+    pub enabled: bool,
+    /// How often to check for endpoints that are due a sync (default: 30s)
+    #[serde(with = "humantime_serde")]
+    pub check_interval: Duration,
+}
+
+impl Default for EndpointAutoSyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            check_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Deployment warmup configuration.
+///
+/// Deployments with `warmup` set are sent a minimal request through the AI
+/// proxy shortly after they become active, and again every `interval` if one
+/// is set, so the first real request doesn't pay for connection setup or a
+/// cold model. This job checks for deployments to warm every `check_interval`.
+/// Runs on the leader.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DeploymentWarmupConfig {
+    /// Enable deployment warmup (default: true)
+    pub enabled: bool,
+    /// How often to look for deployments to warm (default: 10s)
+    #[serde(with = "humantime_serde")]
+    pub check_interval: Duration,
+    /// How long a deployment must have been active before its first warmup,
+    /// giving the proxy time to load it (default: 5s)
+    #[serde(with = "humantime_serde")]
+    pub activation_delay: Duration,
+    /// Re-warm active deployments this long after their last warmup (default: unset, warm on activation only)
+    #[serde(with = "humantime_serde")]
+    pub interval: Option<Duration>,
+    /// Minimum time between two warmup requests (default: 1s)
+    #[serde(with = "humantime_serde")]
+    pub min_request_gap: Duration,
+}
+
+impl Default for DeploymentWarmupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            check_interval: Duration::from_secs(10),
+            activation_delay: Duration::from_secs(5),
+            interval: None,
+            min_request_gap: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Balance checkpoint reconciliation configuration.
+///
+/// Every writer folds its ledger rows into `user_balance_checkpoints` as it
+/// writes them, so checkpoints only drift when something bypasses the writers.
+/// This job re-derives the balance of every user with ledger activity in the
+/// last `lookback` from their full ledger history and heals any checkpoint that
+/// disagrees. Runs on the leader.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BalanceCheckpointConfig {
+    /// Enable balance checkpoint reconciliation (default: true)
+    pub enabled: bool,
+    /// How often the job runs (default: 5 minutes)
+    #[serde(with = "humantime_serde")]
+    pub run_interval: Duration,
+    /// Users with ledger rows newer than this are reconciled on each run (default: 15 minutes).
+    /// Must be at least `run_interval`, so that every active user is checked.
+    #[serde(with = "humantime_serde")]
+    pub lookback: Duration,
+}
+
+impl Default for BalanceCheckpointConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            run_interval: Duration::from_secs(5 * 60),
+            lookback: Duration::from_secs(15 * 60),
+        }
+    }
+}
+
+/// Batch retention configuration.
+///
+/// Finished (completed, failed or cancelled) batches created more than `max_age`
+/// ago are archived: their output and error files are optionally exported to
+/// `cold_storage_path`, their files are deleted, and the batch is soft-deleted
+/// so fusillade purges its requests. The batch API keeps returning the batch,
+/// marked as archived. Runs on the leader. Off by default.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BatchRetentionConfig {
+    /// Enable batch retention (default: false)
+    pub enabled: bool,
+    /// Batches created longer ago than this are archived (default: 90 days)
+    #[serde(with = "humantime_serde")]
+    pub max_age: Duration,
+    /// How often the job runs (default: 1 hour)
+    #[serde(with = "humantime_serde")]
+    pub run_interval: Duration,
+    /// Maximum batches archived per run (default: 100)
+    pub batches_per_run: i64,
+    /// Directory to export each batch's output and error files to before they
+    /// are deleted. Unset (the default) deletes them without a copy.
+    pub cold_storage_path: Option<PathBuf>,
+}
+
+impl Default for BatchRetentionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_age: Duration::from_secs(90 * 24 * 60 * 60),
+            run_interval: Duration::from_secs(60 * 60),
+            batches_per_run: 100,
+            cold_storage_path: None,
+        }
+    }
+}
+
+/// Webhook delivery service configuration.
+///
+/// The webhook service delivers Standard Webhooks-compliant notifications
+/// for batch terminal state events (completed, failed, cancelled).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WebhookConfig {
+    /// Enable webhook delivery service (default: true)
+    pub enabled: bool,
+    /// HTTP timeout for webhook deliveries in seconds (default: 30)
+    pub timeout_secs: u64,
+    /// Retry backoff schedule in seconds. Each entry is the delay before the
+    /// corresponding attempt. The length of this list is the maximum number of
+    /// attempts — once exhausted, the delivery is marked as exhausted.
+    ///
+    /// Default: [0, 5, 300, 1800, 7200, 28800, 86400]
+    ///          (immediate, 5s, 5m, 30m, 2h, 8h, 24h)
+    pub retry_schedule_secs: Vec<i64>,
+    /// Number of consecutive failures before disabling a webhook (default: 10)
+    pub circuit_breaker_threshold: i32,
+    /// Maximum deliveries to claim from the database per tick (default: 50)
+    pub claim_batch_size: i64,
+    /// Maximum concurrent outbound HTTP requests (default: 20)
+    pub max_concurrent_sends: usize,
+    /// Internal channel buffer capacity for send requests and results (default: 200)
+    pub channel_capacity: usize,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            timeout_secs: 30,
             retry_schedule_secs: vec![0, 5, 300, 1800, 7200, 28800, 86400],
             circuit_breaker_threshold: 10,
             claim_batch_size: 50,
@@ -2281,6 +3166,27 @@ where
     Url::parse(&s).map_err(serde::de::Error::custom)
 }
 
+/// Largest `credits.decimal_places` accepted; matches the scale of the ledger's amount columns.
+const MAX_CREDIT_DECIMAL_PLACES: u32 = 15;
+
+/// PostgreSQL major versions the embedded database can run (those `postgresql_embedded` ships binaries for).
+const EMBEDDED_POSTGRES_MAJOR_VERSIONS: std::ops::RangeInclusive<u64> = 13..=17;
+
+/// Whether `version` is a `major[.minor[.patch]]` version of a supported embedded PostgreSQL major.
+fn is_supported_embedded_postgres_version(version: &str) -> bool {
+    let parts: Vec<&str> = version.split('.').collect();
+    if parts.len() > 3
+        || parts
+            .iter()
+            .any(|part| part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return false;
+    }
+    parts[0]
+        .parse()
+        .is_ok_and(|major| EMBEDDED_POSTGRES_MAJOR_VERSIONS.contains(&major))
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default, deny_unknown_fields)]
 /// Credit system configuration.
@@ -2295,6 +3201,21 @@ pub struct CreditsConfig {
     /// `purchase`), so existing paying customers are never matched.
     #[serde(default)]
     pub first_payment_match_up_to: rust_decimal::Decimal,
+    /// ISO 4217 code of the currency credits are denominated in (default: "USD").
+    /// Reported alongside amounts in transaction responses.
+    pub currency: String,
+    /// Decimal places amounts are rounded to at display boundaries (default: 2).
+    /// The ledger itself keeps full precision; see [`CreditsConfig::round`].
+    pub decimal_places: u32,
+}
+
+impl CreditsConfig {
+    /// Round an amount to the configured precision using banker's rounding (half to even),
+    /// so that rounding errors don't systematically favour either side across many rows.
+    /// Only for presenting amounts: never feed the result back into the ledger.
+    pub fn round(&self, amount: rust_decimal::Decimal) -> rust_decimal::Decimal {
+        amount.round_dp_with_strategy(self.decimal_places, rust_decimal::RoundingStrategy::MidpointNearestEven)
+    }
 }
 
 impl Default for CreditsConfig {
@@ -2304,6 +3225,8 @@ impl Default for CreditsConfig {
             initial_credits_for_standard_users: rust_decimal::Decimal::ZERO,
             // Default to 0 (first-payment match promotion disabled)
             first_payment_match_up_to: rust_decimal::Decimal::ZERO,
+            currency: "USD".to_string(),
+            decimal_places: 2,
         }
     }
 }
@@ -2336,6 +3259,23 @@ pub struct AnalyticsConfig {
     /// which needs no global rate limit. Kept so existing config files still
     /// parse.
     pub balance_notification_interval_milliseconds: u64,
+    /// When a successful response can't be parsed (e.g. truncated JSON), its
+    /// usage is unknown. By default the request is recorded with zero tokens and
+    /// not billed; when enabled, tokens are estimated at ~4 bytes per token of
+    /// request and response body and billed as usual. Either way the record is
+    /// flagged with `parse_failure`.
+    /// Default: false
+    pub estimate_tokens_on_parse_failure: bool,
+    /// Fraction of successful (2xx) requests, between 0 and 1, whose detailed
+    /// analytics row is written. The choice is deterministic per request.
+    /// Every request is still billed; batched requests are always logged.
+    /// Default: 1.0
+    pub success_sample_rate: f64,
+    /// Fraction of failed (non-2xx) requests whose analytics row is written.
+    /// Default: 1.0
+    pub error_sample_rate: f64,
+    /// External destination that also receives each request's analytics event
+    pub sink: AnalyticsSinkConfig,
 }
 
 impl Default for AnalyticsConfig {
@@ -2345,10 +3285,82 @@ impl Default for AnalyticsConfig {
             max_retries: 3,
             retry_base_delay_ms: 100,
             balance_notification_interval_milliseconds: 5000,
+            estimate_tokens_on_parse_failure: false,
+            success_sample_rate: 1.0,
+            error_sample_rate: 1.0,
+            sink: AnalyticsSinkConfig::default(),
         }
     }
 }
 
+/// Publishing analytics events to a webhook or Kafka topic, alongside Postgres.
+///
+/// The sink receives an event for every request, including those sampled out
+/// of `http_analytics`, so setting both sample rates to 0 sends the detailed
+/// data only to the sink. Events are queued in a bounded buffer and delivered
+/// in batches by a background task; when the buffer is full, new events are
+/// dropped (counted in `dwctl_analytics_sink_dropped_total`) rather than
+/// slowing down analytics or the proxy.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnalyticsSinkConfig {
+    /// Where to send events. Unset (the default) disables the sink.
+    pub destination: Option<AnalyticsSinkDestination>,
+    /// Events held waiting for delivery before new ones are dropped.
+    /// Default: 10000
+    pub buffer_size: usize,
+    /// Most events sent in one request.
+    /// Default: 100
+    pub batch_size: usize,
+    /// Retries for a failed delivery before its batch is dropped.
+    /// Default: 3
+    pub max_retries: u32,
+    /// Base delay for exponential backoff between retries.
+    /// Default: 500ms
+    #[serde(with = "humantime_serde")]
+    pub retry_base_delay: Duration,
+    /// Timeout for each delivery request.
+    /// Default: 10s
+    #[serde(with = "humantime_serde")]
+    pub timeout: Duration,
+}
+
+impl Default for AnalyticsSinkConfig {
+    fn default() -> Self {
+        Self {
+            destination: None,
+            buffer_size: 10_000,
+            batch_size: 100,
+            max_retries: 3,
+            retry_base_delay: Duration::from_millis(500),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Where analytics events are published.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+pub enum AnalyticsSinkDestination {
+    /// POST each batch to a URL as a JSON array of events
+    Webhook {
+        url: Url,
+        /// Headers sent with every request, e.g. `Authorization`
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+    /// Produce each event to a Kafka topic through a Kafka REST Proxy (v2 API).
+    /// Events are keyed by user ID.
+    Kafka {
+        /// Base URL of the REST proxy
+        rest_proxy_url: Url,
+        topic: String,
+        /// Headers sent with every request, e.g. `Authorization`
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+}
+
 /// External data source connections configuration.
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -2459,10 +3471,13 @@ impl Default for Config {
             database_replica_url: None,
             database: DatabaseConfig::default(),
             slow_statement_threshold_ms: 1000,
+            skip_migrations: false,
             admin_email: "test@doubleword.ai".to_string(),
             admin_password: Some("hunter2".to_string()),
+            system_api_key: None,
             secret_key: None,
             model_sources: vec![],
+            endpoint_sync: EndpointSyncConfig::default(),
             metadata: Metadata::default(),
             payment: None,
             auth: AuthConfig::default(),
@@ -2473,6 +3488,7 @@ impl Default for Config {
             enable_analytics: true,
             analytics: AnalyticsConfig::default(),
             enable_otel_export: false,
+            log: LogConfig::default(),
             credits: CreditsConfig::default(),
             sample_files: SampleFilesConfig::default(),
             limits: LimitsConfig::default(),
@@ -2484,6 +3500,7 @@ impl Default for Config {
             responses: ResponsesConfig::default(),
             image_normalizer: crate::image_normalizer::ImageNormalizerConfig::default(),
             keystore: None,
+            secrets: crate::secrets::SecretsConfig::default(),
             openapi: OpenApiConfig::default(),
             cache: CacheConfig::default(),
         }
@@ -2525,6 +3542,9 @@ impl Default for ProxyHeaderAuthConfig {
             auto_create_users: true,
             blacklisted_sso_groups: Vec::new(),
             import_idp_groups: false,
+            trusted_proxies: Vec::new(),
+            shared_secret: None,
+            shared_secret_header_name: "x-doubleword-proxy-secret".to_string(),
         }
     }
 }
@@ -2626,6 +3646,8 @@ impl Config {
         Self::load(&Args {
             config: path.as_ref().to_path_buf(),
             validate: false,
+            skip_migrations: false,
+            command: None,
         })
     }
 
@@ -2645,6 +3667,14 @@ impl Config {
                 DatabaseConfig::External { replica_pool, .. } => replica_pool.clone(),
                 DatabaseConfig::Embedded { replica_pool, .. } => replica_pool.clone(),
             };
+            let (analytics_replica_url, analytics_pool) = match &config.database {
+                DatabaseConfig::External {
+                    analytics_replica_url,
+                    analytics_pool,
+                    ..
+                } => (analytics_replica_url.clone(), analytics_pool.clone()),
+                DatabaseConfig::Embedded { .. } => (None, None),
+            };
 
             // Check if replica_url was set via environment variable
             let replica_url = config.database_replica_url.take();
@@ -2654,6 +3684,8 @@ impl Config {
                 replica_url,
                 pool,
                 replica_pool: original_replica_pool, // Always preserve original replica_pool if it existed
+                analytics_replica_url,
+                analytics_pool,
                 fusillade,
                 outlet,
                 underway_pool,
@@ -2678,6 +3710,10 @@ impl Config {
             config.auth.native.session.cookie_domain = None;
         }
 
+        if args.skip_migrations {
+            config.skip_migrations = true;
+        }
+
         config.validate().map_err(|e| figment::Error::from(e.to_string()))?;
         Ok(config)
     }
@@ -2717,6 +3753,28 @@ impl Config {
             }
         }
 
+        if self
+            .system_api_key
+            .as_deref()
+            .is_some_and(|secret| !crate::crypto::is_valid_api_key_secret(secret))
+        {
+            return Err(Error::Internal {
+                operation: format!(
+                    "Config validation: system_api_key must be at least {} characters with no whitespace",
+                    crate::crypto::MIN_API_KEY_SECRET_LENGTH
+                ),
+            });
+        }
+
+        // Batch and playground keys are created by the system, never by users
+        let purposes = &self.auth.api_key_purposes;
+        let creatable = |purpose: &ApiKeyPurpose| matches!(purpose, ApiKeyPurpose::Realtime | ApiKeyPurpose::Platform);
+        if !creatable(&purposes.default) || !purposes.by_role.values().flatten().all(creatable) {
+            return Err(Error::Internal {
+                operation: "Config validation: auth.api_key_purposes may only contain \"realtime\" and \"platform\"".to_string(),
+            });
+        }
+
         // Cached-input pricing needs a tokenizer-svc URL to count cache-prefix tokens.
         // Without it, every cacheable request silently degrades to no caching — fail fast
         // at startup instead, so an operator who flips the flag gets a clear error.
@@ -2781,6 +3839,174 @@ impl Config {
             });
         }
 
+        // Validate proxy header names, which would otherwise silently never match
+        if self.auth.proxy_header.enabled {
+            let proxy_header = &self.auth.proxy_header;
+            for (field, name) in [
+                ("header_name", &proxy_header.header_name),
+                ("email_header_name", &proxy_header.email_header_name),
+                ("groups_field_name", &proxy_header.groups_field_name),
+                ("provider_field_name", &proxy_header.provider_field_name),
+                ("shared_secret_header_name", &proxy_header.shared_secret_header_name),
+            ] {
+                if axum::http::HeaderName::from_bytes(name.as_bytes()).is_err() {
+                    return Err(Error::Internal {
+                        operation: format!("Config validation: auth.proxy_header.{field} '{name}' is not a valid HTTP header name"),
+                    });
+                }
+            }
+
+            if proxy_header.shared_secret.as_deref().is_some_and(|secret| secret.trim().is_empty()) {
+                return Err(Error::Internal {
+                    operation: "Config validation: auth.proxy_header.shared_secret must not be empty".to_string(),
+                });
+            }
+        }
+
+        // Validate credit currency and display precision
+        if self.credits.currency.len() != 3 || !self.credits.currency.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(Error::Internal {
+                operation: format!(
+                    "Config validation: credits.currency '{}' must be a three-letter uppercase ISO 4217 code (e.g. \"USD\")",
+                    self.credits.currency
+                ),
+            });
+        }
+        if self.credits.decimal_places > MAX_CREDIT_DECIMAL_PLACES {
+            return Err(Error::Internal {
+                operation: format!(
+                    "Config validation: credits.decimal_places ({}) cannot be greater than {MAX_CREDIT_DECIMAL_PLACES}",
+                    self.credits.decimal_places
+                ),
+            });
+        }
+
+        for path in &self.onwards.disabled_paths {
+            if !path.starts_with("/v1/") || path.trim_end_matches('/') == "/v1" {
+                return Err(Error::Internal {
+                    operation: format!(
+                        "Config validation: onwards.disabled_paths entry '{path}' must be an AI API path starting with /v1/"
+                    ),
+                });
+            }
+        }
+
+        if let Some(version) = self.database.embedded_version()
+            && !is_supported_embedded_postgres_version(version)
+        {
+            return Err(Error::Internal {
+                operation: format!(
+                    "Config validation: database.version '{version}' is not a supported embedded PostgreSQL version \
+                     (use a major version from {} to {}, optionally with minor and patch)",
+                    EMBEDDED_POSTGRES_MAJOR_VERSIONS.start(),
+                    EMBEDDED_POSTGRES_MAJOR_VERSIONS.end()
+                ),
+            });
+        }
+        for extension in self.database.embedded_extensions() {
+            if extension.is_empty() || !extension.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-') {
+                return Err(Error::Internal {
+                    operation: format!("Config validation: database.extensions entry '{extension}' is not a valid extension name"),
+                });
+            }
+        }
+
+        let response_cache = &self.onwards.response_cache;
+        if response_cache.ttl.is_zero() || !response_cache.max_temperature.is_finite() || response_cache.max_temperature < 0.0 {
+            return Err(Error::Internal {
+                operation: "Config validation: onwards.response_cache.ttl must be positive and max_temperature must not be negative"
+                    .to_string(),
+            });
+        }
+
+        if self.onwards.stream_keepalive.interval < Duration::from_secs(1) {
+            return Err(Error::Internal {
+                operation: "Config validation: onwards.stream_keepalive.interval must be at least 1s".to_string(),
+            });
+        }
+
+        let analytics = &self.analytics;
+        if ![analytics.success_sample_rate, analytics.error_sample_rate]
+            .iter()
+            .all(|rate| (0.0..=1.0).contains(rate))
+        {
+            return Err(Error::Internal {
+                operation: "Config validation: analytics.success_sample_rate and analytics.error_sample_rate must be between 0 and 1"
+                    .to_string(),
+            });
+        }
+        if analytics.sink.buffer_size == 0 || analytics.sink.batch_size == 0 {
+            return Err(Error::Internal {
+                operation: "Config validation: analytics.sink.buffer_size and analytics.sink.batch_size must be at least 1".to_string(),
+            });
+        }
+
+        let circuit_breaker = &self.onwards.circuit_breaker;
+        if circuit_breaker.failure_threshold < 1
+            || circuit_breaker.window < Duration::from_secs(1)
+            || circuit_breaker.cooldown < Duration::from_secs(1)
+        {
+            return Err(Error::Internal {
+                operation:
+                    "Config validation: onwards.circuit_breaker.failure_threshold must be positive and window and cooldown at least 1s"
+                        .to_string(),
+            });
+        }
+
+        let request_queue = &self.onwards.request_queue;
+        if request_queue.enabled && (request_queue.max_wait.is_zero() || request_queue.aging.is_zero()) {
+            return Err(Error::Internal {
+                operation: "Config validation: onwards.request_queue.max_wait and aging must be positive".to_string(),
+            });
+        }
+
+        let deployment_limits = &self.limits.deployments;
+        if deployment_limits
+            .requests_per_second
+            .is_some_and(|rps| !rps.is_finite() || rps <= 0.0)
+            || deployment_limits.burst_size.is_some_and(|burst| burst < 1)
+            || deployment_limits.capacity.is_some_and(|capacity| capacity < 1)
+        {
+            return Err(Error::Internal {
+                operation: "Config validation: limits.deployments values must be positive".to_string(),
+            });
+        }
+
+        let admin_api_limits = &self.limits.admin_api;
+        if [
+            admin_api_limits.requests_per_second,
+            admin_api_limits.platform_manager_requests_per_second,
+        ]
+        .iter()
+        .flatten()
+        .any(|rps| !rps.is_finite() || *rps <= 0.0)
+            || [admin_api_limits.burst_size, admin_api_limits.platform_manager_burst_size].contains(&Some(0))
+        {
+            return Err(Error::Internal {
+                operation: "Config validation: limits.admin_api values must be positive".to_string(),
+            });
+        }
+
+        let api_key_limits = &self.limits.api_keys;
+        if [
+            api_key_limits.max_active_per_user,
+            api_key_limits.platform_manager_max_active_per_user,
+        ]
+        .contains(&Some(0))
+        {
+            return Err(Error::Internal {
+                operation: "Config validation: limits.api_keys values must be positive".to_string(),
+            });
+        }
+
+        for name in &self.onwards.api_key_headers {
+            if axum::http::HeaderName::from_bytes(name.as_bytes()).is_err() {
+                return Err(Error::Internal {
+                    operation: format!("Config validation: onwards.api_key_headers entry '{name}' is not a valid HTTP header name"),
+                });
+            }
+        }
+
         // Validate cookie_domain if set — must produce a valid Set-Cookie header fragment
         if let Some(ref domain) = self.auth.native.session.cookie_domain {
             let invalid = domain.is_empty() || domain.chars().any(|c| c.is_whitespace() || c.is_control()) || domain.contains(';');
@@ -2823,6 +4049,29 @@ impl Config {
             });
         }
 
+        // Model sources are keyed by name and polled over HTTP
+        let mut source_names = HashSet::new();
+        for source in &self.model_sources {
+            if source.name.trim().is_empty() {
+                return Err(Error::Internal {
+                    operation: format!("Config validation: model source with url '{}' has an empty name.", source.url),
+                });
+            }
+            if !source_names.insert(source.name.as_str()) {
+                return Err(Error::Internal {
+                    operation: format!("Config validation: model source name '{}' is used more than once.", source.name),
+                });
+            }
+            if !matches!(source.url.scheme(), "http" | "https") {
+                return Err(Error::Internal {
+                    operation: format!(
+                        "Config validation: model source '{}' url must use http or https, got '{}'.",
+                        source.name, source.url
+                    ),
+                });
+            }
+        }
+
         if self.background_services.batch_daemon.upload_chunk_bytes == 0 {
             return Err(Error::Internal {
                 operation: "Config validation: upload_chunk_bytes cannot be 0. Set a positive integer value (default: 65536).".to_string(),
@@ -2905,51 +4154,138 @@ impl Config {
             // Validate expiry times are positive and in sensible order
             if self.batches.files.min_expiry_seconds <= 0 {
                 return Err(Error::Internal {
-                    operation: "Config validation: min_expiry_seconds must be positive (default: 3600 = 1 hour).".to_string(),
+                    operation: "Config validation: min_expiry_seconds must be positive (default: 3600 = 1 hour).".to_string(),
+                });
+            }
+
+            if self.batches.files.default_expiry_seconds <= 0 {
+                return Err(Error::Internal {
+                    operation: "Config validation: default_expiry_seconds must be positive (default: 86400 = 24 hours).".to_string(),
+                });
+            }
+
+            if self.batches.files.max_expiry_seconds <= 0 {
+                return Err(Error::Internal {
+                    operation: "Config validation: max_expiry_seconds must be positive (default: 2592000 = 30 days).".to_string(),
+                });
+            }
+
+            // Validate expiry times are in correct order
+            if self.batches.files.min_expiry_seconds > self.batches.files.default_expiry_seconds {
+                return Err(Error::Internal {
+                    operation: format!(
+                        "Config validation: min_expiry_seconds ({}) cannot be greater than default_expiry_seconds ({})",
+                        self.batches.files.min_expiry_seconds, self.batches.files.default_expiry_seconds
+                    ),
+                });
+            }
+
+            if self.batches.files.default_expiry_seconds > self.batches.files.max_expiry_seconds {
+                return Err(Error::Internal {
+                    operation: format!(
+                        "Config validation: default_expiry_seconds ({}) cannot be greater than max_expiry_seconds ({})",
+                        self.batches.files.default_expiry_seconds, self.batches.files.max_expiry_seconds
+                    ),
+                });
+            }
+
+            if self.batches.files.min_expiry_seconds > self.batches.files.max_expiry_seconds {
+                return Err(Error::Internal {
+                    operation: format!(
+                        "Config validation: min_expiry_seconds ({}) cannot be greater than max_expiry_seconds ({})",
+                        self.batches.files.min_expiry_seconds, self.batches.files.max_expiry_seconds
+                    ),
+                });
+            }
+        }
+
+        let retention = &self.background_services.probe_scheduler.retention;
+        if retention.enabled {
+            if retention.raw_retention < Duration::from_secs(24 * 60 * 60) {
+                return Err(Error::Internal {
+                    operation: format!(
+                        "Config validation: probe_scheduler.retention.raw_retention ({}) must be at least 24h, \
+                         since uptime percentages are computed from raw probe results.",
+                        humantime::format_duration(retention.raw_retention)
+                    ),
+                });
+            }
+            if retention.aggregate_retention < retention.raw_retention {
+                return Err(Error::Internal {
+                    operation: "Config validation: probe_scheduler.retention.aggregate_retention cannot be shorter than raw_retention."
+                        .to_string(),
+                });
+            }
+            if retention.run_interval.is_zero() {
+                return Err(Error::Internal {
+                    operation: "Config validation: probe_scheduler.retention.run_interval must be positive.".to_string(),
+                });
+            }
+        }
+
+        let auto_sync = &self.background_services.endpoint_auto_sync;
+        if auto_sync.enabled && auto_sync.check_interval.is_zero() {
+            return Err(Error::Internal {
+                operation: "Config validation: endpoint_auto_sync.check_interval must be positive.".to_string(),
+            });
+        }
+
+        let warmup = &self.background_services.deployment_warmup;
+        if warmup.enabled {
+            if warmup.check_interval.is_zero() {
+                return Err(Error::Internal {
+                    operation: "Config validation: deployment_warmup.check_interval must be positive.".to_string(),
+                });
+            }
+            if warmup.interval.is_some_and(|interval| interval.is_zero()) {
+                return Err(Error::Internal {
+                    operation: "Config validation: deployment_warmup.interval must be positive.".to_string(),
                 });
             }
+        }
 
-            if self.batches.files.default_expiry_seconds <= 0 {
+        let checkpoints = &self.background_services.balance_checkpoints;
+        if checkpoints.enabled {
+            if checkpoints.run_interval.is_zero() {
                 return Err(Error::Internal {
-                    operation: "Config validation: default_expiry_seconds must be positive (default: 86400 = 24 hours).".to_string(),
+                    operation: "Config validation: balance_checkpoints.run_interval must be positive.".to_string(),
                 });
             }
-
-            if self.batches.files.max_expiry_seconds <= 0 {
+            if checkpoints.lookback < checkpoints.run_interval {
                 return Err(Error::Internal {
-                    operation: "Config validation: max_expiry_seconds must be positive (default: 2592000 = 30 days).".to_string(),
+                    operation: "Config validation: balance_checkpoints.lookback cannot be shorter than run_interval.".to_string(),
                 });
             }
+        }
 
-            // Validate expiry times are in correct order
-            if self.batches.files.min_expiry_seconds > self.batches.files.default_expiry_seconds {
+        let batch_retention = &self.background_services.batch_retention;
+        if batch_retention.enabled {
+            if batch_retention.max_age < Duration::from_secs(24 * 60 * 60) {
                 return Err(Error::Internal {
                     operation: format!(
-                        "Config validation: min_expiry_seconds ({}) cannot be greater than default_expiry_seconds ({})",
-                        self.batches.files.min_expiry_seconds, self.batches.files.default_expiry_seconds
+                        "Config validation: batch_retention.max_age ({}) must be at least 24h.",
+                        humantime::format_duration(batch_retention.max_age)
                     ),
                 });
             }
-
-            if self.batches.files.default_expiry_seconds > self.batches.files.max_expiry_seconds {
+            if batch_retention.run_interval.is_zero() {
                 return Err(Error::Internal {
-                    operation: format!(
-                        "Config validation: default_expiry_seconds ({}) cannot be greater than max_expiry_seconds ({})",
-                        self.batches.files.default_expiry_seconds, self.batches.files.max_expiry_seconds
-                    ),
+                    operation: "Config validation: batch_retention.run_interval must be positive.".to_string(),
                 });
             }
-
-            if self.batches.files.min_expiry_seconds > self.batches.files.max_expiry_seconds {
+            if batch_retention.batches_per_run <= 0 {
                 return Err(Error::Internal {
-                    operation: format!(
-                        "Config validation: min_expiry_seconds ({}) cannot be greater than max_expiry_seconds ({})",
-                        self.batches.files.min_expiry_seconds, self.batches.files.max_expiry_seconds
-                    ),
+                    operation: "Config validation: batch_retention.batches_per_run must be positive.".to_string(),
                 });
             }
         }
 
+        if let Err(e) = crate::telemetry::build_env_filter(&self.log.filter) {
+            return Err(Error::Internal {
+                operation: format!("Config validation: invalid log.filter '{}': {e}", self.log.filter),
+            });
+        }
+
         Ok(())
     }
 
@@ -3000,6 +4336,8 @@ model_sources:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
 
             let config = Config::load(&args)?;
@@ -3040,6 +4378,8 @@ auth:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let config = Config::load(&args)?;
 
@@ -3058,6 +4398,8 @@ auth:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let config = Config::load(&args)?;
 
@@ -3086,6 +4428,8 @@ metadata:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
 
             let config = Config::load(&args)?;
@@ -3126,6 +4470,8 @@ auth:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
 
             let config = Config::load(&args)?;
@@ -3180,6 +4526,384 @@ auth:
         assert!(result.unwrap_err().to_string().contains("No authentication methods"));
     }
 
+    #[test]
+    fn test_config_validation_invalid_proxy_header_name() {
+        let mut config = Config::default();
+        config.auth.native.enabled = false;
+        config.auth.proxy_header.enabled = true;
+        config.auth.proxy_header.email_header_name = "X Auth Request Email".to_string();
+
+        let result = config.validate();
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("email_header_name"));
+    }
+
+    #[test]
+    fn test_proxy_header_trusted_proxies() {
+        Jail::expect_with(|jail| {
+            jail.create_file(
+                "test.yaml",
+                r#"
+auth:
+  proxy_header:
+    enabled: true
+    trusted_proxies: ["10.0.0.0/8", "192.168.1.10", "fd00::/8"]
+"#,
+            )?;
+
+            let args = Args {
+                config: "test.yaml".into(),
+                validate: false,
+                skip_migrations: false,
+                command: None,
+            };
+            let proxy_header = Config::load(&args)?.auth.proxy_header;
+
+            let headers = axum::http::HeaderMap::new();
+            assert_eq!(proxy_header.trusted_proxies.len(), 3);
+            assert!(proxy_header.is_trusted_source(Some("10.1.2.3".parse().unwrap()), &headers));
+            assert!(proxy_header.is_trusted_source(Some("192.168.1.10".parse().unwrap()), &headers));
+            assert!(proxy_header.is_trusted_source(Some("::ffff:10.1.2.3".parse().unwrap()), &headers));
+            assert!(proxy_header.is_trusted_source(Some("fd12::1".parse().unwrap()), &headers));
+            assert!(!proxy_header.is_trusted_source(Some("192.168.1.11".parse().unwrap()), &headers));
+            assert!(!proxy_header.is_trusted_source(None, &headers));
+
+            // No trust controls trusts every source
+            assert!(ProxyHeaderAuthConfig::default().is_trusted_source(None, &headers));
+
+            jail.create_file("bad.yaml", "auth:\n  proxy_header:\n    trusted_proxies: [\"not-an-ip\"]\n")?;
+            let args = Args {
+                config: "bad.yaml".into(),
+                validate: false,
+                skip_migrations: false,
+                command: None,
+            };
+            assert!(Config::load(&args).is_err());
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_proxy_header_shared_secret() {
+        let proxy_header = ProxyHeaderAuthConfig {
+            shared_secret: Some("proxy-secret".to_string()),
+            ..Default::default()
+        };
+        let untrusted_peer = Some("203.0.113.7".parse().unwrap());
+
+        let mut headers = axum::http::HeaderMap::new();
+        assert!(!proxy_header.is_trusted_source(untrusted_peer, &headers));
+
+        headers.insert("x-doubleword-proxy-secret", "wrong-secret".parse().unwrap());
+        assert!(!proxy_header.is_trusted_source(untrusted_peer, &headers));
+
+        headers.insert("x-doubleword-proxy-secret", "proxy-secret".parse().unwrap());
+        assert!(proxy_header.is_trusted_source(untrusted_peer, &headers));
+        assert!(proxy_header.is_trusted_source(None, &headers));
+
+        // The secret is redacted from the config as logged
+        let logged = format!("{proxy_header:?}");
+        assert!(!logged.contains("proxy-secret"), "{logged}");
+        assert!(logged.contains("<redacted>"), "{logged}");
+    }
+
+    #[test]
+    fn test_credits_round_half_to_even() {
+        let credits = CreditsConfig::default();
+        let round = |s: &str| credits.round(s.parse().unwrap()).to_string();
+        assert_eq!(round("0.125"), "0.12");
+        assert_eq!(round("0.135"), "0.14");
+        assert_eq!(round("-0.125"), "-0.12");
+        assert_eq!(round("0.1251"), "0.13");
+        assert_eq!(round("7"), "7");
+
+        let credits = CreditsConfig {
+            decimal_places: 4,
+            ..Default::default()
+        };
+        assert_eq!(credits.round("0.00012345".parse().unwrap()).to_string(), "0.0001");
+        assert_eq!(credits.round("2.50005".parse().unwrap()).to_string(), "2.5000");
+    }
+
+    #[test]
+    fn test_config_validation_invalid_credits_currency_and_precision() {
+        let mut config = Config::default();
+        config.secret_key = Some("test-secret-key".to_string());
+        config.credits.currency = "usd".to_string();
+        let result = config.validate();
+        assert!(result.unwrap_err().to_string().contains("credits.currency"));
+
+        let mut config = Config::default();
+        config.secret_key = Some("test-secret-key".to_string());
+        config.credits.decimal_places = 16;
+        let result = config.validate();
+        assert!(result.unwrap_err().to_string().contains("credits.decimal_places"));
+    }
+
+    #[test]
+    fn test_config_validation_disabled_paths() {
+        for (path, valid) in [
+            ("/v1/completions", true),
+            ("completions", false),
+            ("/v1/", false),
+            ("/ai/v1/completions", false),
+        ] {
+            let mut config = Config::default();
+            config.secret_key = Some("test-secret-key".to_string());
+            config.onwards.disabled_paths = vec![path.to_string()];
+            assert_eq!(config.validate().is_ok(), valid, "{path}");
+        }
+    }
+
+    #[test]
+    fn test_config_validation_api_key_headers() {
+        for (name, valid) in [("api-key", true), ("X-API-Key", true), ("api key", false), ("", false)] {
+            let mut config = Config::default();
+            config.secret_key = Some("test-secret-key".to_string());
+            config.onwards.api_key_headers = vec![name.to_string()];
+            assert_eq!(config.validate().is_ok(), valid, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_config_validation_embedded_database() {
+        let embedded = |version: Option<&str>, extensions: &[&str]| {
+            let mut config = Config::default();
+            config.secret_key = Some("test-secret-key".to_string());
+            config.database = DatabaseConfig::Embedded {
+                data_dir: None,
+                persistent: false,
+                version: version.map(str::to_string),
+                extensions: extensions.iter().map(|e| e.to_string()).collect(),
+                pool: PoolSettings::default(),
+                replica_pool: None,
+                fusillade: default_fusillade_component(),
+                outlet: default_outlet_component(),
+                underway_pool: default_underway_pool(),
+            };
+            config.validate()
+        };
+
+        for version in ["15", "16.4", "17.2.0", "13"] {
+            assert!(embedded(Some(version), &[]).is_ok(), "{version}");
+        }
+        for version in ["12", "99", "16.x", "16.4.0.1", "", "v16"] {
+            assert!(
+                embedded(Some(version), &[]).unwrap_err().to_string().contains("database.version"),
+                "{version}"
+            );
+        }
+
+        assert!(embedded(None, &["pg_trgm", "uuid-ossp"]).is_ok());
+        assert!(
+            embedded(None, &["pg_trgm\"; DROP"])
+                .unwrap_err()
+                .to_string()
+                .contains("database.extensions")
+        );
+    }
+
+    #[test]
+    fn test_config_validation_model_sources() {
+        let source = |name: &str, url: &str| ModelSource {
+            name: name.to_string(),
+            url: Url::parse(url).unwrap(),
+            api_key: None,
+            sync_interval: Duration::from_secs(10),
+            default_models: None,
+        };
+        let mut config = Config::default();
+        config.secret_key = Some("test-secret-key".to_string());
+        config.model_sources = vec![
+            source("openai", "https://api.openai.com"),
+            source("internal", "http://internal:8080"),
+        ];
+        assert!(config.validate().is_ok());
+
+        config.model_sources.push(source("openai", "https://api.openai.com/v2"));
+        assert!(config.validate().unwrap_err().to_string().contains("used more than once"));
+
+        config.model_sources = vec![source(" ", "https://api.openai.com")];
+        assert!(config.validate().unwrap_err().to_string().contains("empty name"));
+
+        config.model_sources = vec![source("files", "file:///models")];
+        assert!(config.validate().unwrap_err().to_string().contains("http or https"));
+    }
+
+    #[test]
+    fn test_validate_config_subcommand_parses() {
+        let args = Args::try_parse_from(["dwctl", "-f", "prod.yaml", "validate-config", "--check-database"]).unwrap();
+        assert_eq!(args.config, PathBuf::from("prod.yaml"));
+        assert_eq!(args.command, Some(Command::ValidateConfig { check_database: true }));
+
+        let args = Args::try_parse_from(["dwctl"]).unwrap();
+        assert_eq!(args.command, None);
+    }
+
+    #[test]
+    fn test_migrate_subcommand_and_skip_migrations_flag_parse() {
+        let args = Args::try_parse_from(["dwctl", "migrate"]).unwrap();
+        assert_eq!(args.command, Some(Command::Migrate));
+        assert!(!args.skip_migrations);
+
+        let args = Args::try_parse_from(["dwctl", "--skip-migrations"]).unwrap();
+        assert_eq!(args.command, None);
+        assert!(args.skip_migrations);
+    }
+
+    #[test]
+    fn test_config_validation_response_cache() {
+        let mut config = Config::default();
+        config.secret_key = Some("test-secret-key".to_string());
+        config.onwards.response_cache.enabled = true;
+        config.onwards.response_cache.max_temperature = 0.5;
+        assert!(config.validate().is_ok());
+
+        config.onwards.response_cache.ttl = Duration::ZERO;
+        assert!(config.validate().unwrap_err().to_string().contains("onwards.response_cache"));
+
+        config.onwards.response_cache.ttl = Duration::from_secs(60);
+        config.onwards.response_cache.max_temperature = -1.0;
+        assert!(config.validate().unwrap_err().to_string().contains("onwards.response_cache"));
+    }
+
+    #[test]
+    fn test_config_validation_stream_keepalive() {
+        let mut config = Config::default();
+        config.secret_key = Some("test-secret-key".to_string());
+        config.onwards.stream_keepalive.enabled = true;
+        assert!(config.validate().is_ok());
+
+        config.onwards.stream_keepalive.interval = Duration::from_millis(500);
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("onwards.stream_keepalive.interval")
+        );
+    }
+
+    #[test]
+    fn test_config_validation_analytics_sample_rates() {
+        let mut config = Config::default();
+        config.secret_key = Some("test-secret-key".to_string());
+        config.analytics.success_sample_rate = 0.0;
+        assert!(config.validate().is_ok());
+
+        config.analytics.success_sample_rate = 1.5;
+        assert!(config.validate().unwrap_err().to_string().contains("analytics.success_sample_rate"));
+
+        config.analytics.success_sample_rate = 0.1;
+        config.analytics.error_sample_rate = f64::NAN;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_validation_circuit_breaker() {
+        let mut config = Config::default();
+        config.secret_key = Some("test-secret-key".to_string());
+        config.onwards.circuit_breaker.enabled = true;
+        assert!(config.validate().is_ok());
+
+        config.onwards.circuit_breaker.failure_threshold = 0;
+        assert!(config.validate().unwrap_err().to_string().contains("onwards.circuit_breaker"));
+
+        config.onwards.circuit_breaker.failure_threshold = 3;
+        config.onwards.circuit_breaker.cooldown = Duration::from_millis(500);
+        assert!(config.validate().unwrap_err().to_string().contains("onwards.circuit_breaker"));
+    }
+
+    #[test]
+    fn test_config_validation_system_api_key() {
+        let mut config = Config::default();
+        config.secret_key = Some("test-secret-key".to_string());
+        config.system_api_key = Some("sk-gitops-managed-system-key-0000000001".to_string());
+        assert!(config.validate().is_ok());
+
+        // The key is redacted from the config as logged
+        let logged = format!("{config:#?}");
+        assert!(!logged.contains("sk-gitops-managed-system-key"), "{logged}");
+        assert!(!logged.contains("test-secret-key"), "{logged}");
+
+        config.system_api_key = Some("sk-short".to_string());
+        assert!(config.validate().unwrap_err().to_string().contains("system_api_key"));
+    }
+
+    #[test]
+    fn test_config_validation_request_queue() {
+        let mut config = Config::default();
+        config.secret_key = Some("test-secret-key".to_string());
+        config.onwards.request_queue.enabled = true;
+        assert!(config.validate().is_ok());
+
+        config.onwards.request_queue.max_wait = Duration::ZERO;
+        assert!(config.validate().unwrap_err().to_string().contains("onwards.request_queue"));
+    }
+
+    #[test]
+    fn test_config_validation_endpoint_auto_sync() {
+        let mut config = Config::default();
+        config.secret_key = Some("test-secret-key".to_string());
+        assert!(config.validate().is_ok());
+
+        config.background_services.endpoint_auto_sync.check_interval = Duration::ZERO;
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("endpoint_auto_sync.check_interval")
+        );
+
+        // A disabled job's interval is not checked
+        config.background_services.endpoint_auto_sync.enabled = false;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_config_validation_deployment_limits() {
+        let mut config = Config::default();
+        config.secret_key = Some("test-secret-key".to_string());
+        config.limits.deployments.requests_per_second = Some(10.0);
+        config.limits.deployments.capacity = Some(5);
+        assert!(config.validate().is_ok());
+
+        config.limits.deployments.burst_size = Some(0);
+        assert!(config.validate().unwrap_err().to_string().contains("limits.deployments"));
+    }
+
+    #[test]
+    fn test_config_validation_admin_api_limits() {
+        let mut config = Config::default();
+        config.secret_key = Some("test-secret-key".to_string());
+        config.limits.admin_api.requests_per_second = Some(0.5);
+        config.limits.admin_api.platform_manager_burst_size = Some(50);
+        assert!(config.validate().is_ok());
+
+        config.limits.admin_api.platform_manager_requests_per_second = Some(-1.0);
+        assert!(config.validate().unwrap_err().to_string().contains("limits.admin_api"));
+
+        config.limits.admin_api.platform_manager_requests_per_second = None;
+        config.limits.admin_api.burst_size = Some(0);
+        assert!(config.validate().unwrap_err().to_string().contains("limits.admin_api"));
+    }
+
+    #[test]
+    fn test_config_validation_api_key_limits() {
+        let mut config = Config::default();
+        config.auth.native.enabled = true;
+        config.secret_key = Some("test-secret-key".to_string());
+
+        config.limits.api_keys.max_active_per_user = Some(5);
+        config.limits.api_keys.platform_manager_max_active_per_user = Some(50);
+        assert!(config.validate().is_ok());
+
+        config.limits.api_keys.max_active_per_user = Some(0);
+        assert!(config.validate().unwrap_err().to_string().contains("limits.api_keys"));
+    }
+
     #[test]
     fn test_config_validation_valid_config() {
         let mut config = Config::default();
@@ -3212,6 +4936,8 @@ batches:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
 
             let config = Config::load(&args)?;
@@ -3236,6 +4962,8 @@ secret_key: "test-secret-key"
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
 
             let config = Config::load(&args)?;
@@ -3486,6 +5214,8 @@ batches:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
 
             let config = Config::load(&args)?;
@@ -3510,6 +5240,8 @@ batches:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
 
             let config = Config::load(&args)?;
@@ -3534,6 +5266,8 @@ batches:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
 
             let config = Config::load(&args)?;
@@ -3558,6 +5292,8 @@ batches:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
 
             let result = Config::load(&args);
@@ -3584,6 +5320,8 @@ batches:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
 
             let result = Config::load(&args);
@@ -3610,6 +5348,8 @@ secret_key: "test-secret-key"
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
 
             let config = Config::load(&args)?;
@@ -3634,6 +5374,8 @@ batches:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let result = Config::load(&args);
             assert!(result.is_err());
@@ -3658,6 +5400,8 @@ batches:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let result = Config::load(&args);
             assert!(result.is_err());
@@ -3682,6 +5426,8 @@ batches:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let config = Config::load(&args)?;
             assert_eq!(config.batches.reservation_ttl_secs, 600);
@@ -3723,6 +5469,8 @@ batches:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let config = Config::load(&args)?;
             assert_eq!(config.batches.priority_decay_window_secs, Some(600));
@@ -3746,6 +5494,8 @@ batches:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let result = Config::load(&args);
             assert!(result.is_err());
@@ -3779,6 +5529,8 @@ batches:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let config = Config::load(&args)?;
             assert_eq!(config.batches.relaxation_factor("1h"), 1.0);
@@ -3803,6 +5555,8 @@ batches:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let result = Config::load(&args);
             assert!(result.is_err());
@@ -3827,6 +5581,8 @@ batches:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let result = Config::load(&args);
             assert!(result.is_err());
@@ -3851,6 +5607,8 @@ batches:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let config = Config::load(&args)?;
             assert_eq!(config.batches.relaxation_factor("1h"), 0.0);
@@ -3872,6 +5630,8 @@ batches:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let config = Config::load(&args)?;
             // No relaxation_factors key — all windows default to 1.0
@@ -3901,6 +5661,8 @@ auth:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
 
             let config = Config::load(&args)?;
@@ -3917,6 +5679,8 @@ auth:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
 
             let config = Config::load(&args)?;
@@ -3967,6 +5731,8 @@ secret_key: "test-secret-key"
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
 
             let config = Config::load(&args)?;
@@ -3992,6 +5758,8 @@ background_services:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
 
             let config = Config::load(&args)?;
@@ -4017,6 +5785,8 @@ background_services:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
 
             let result = Config::load(&args);
@@ -4044,6 +5814,8 @@ background_services:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
 
             let result = Config::load(&args);
@@ -4067,6 +5839,8 @@ secret_key: "test-secret-key"
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let config = Config::load(&args)?;
             assert_eq!(config.background_services.batch_daemon.mode, DaemonMode::Both);
@@ -4118,6 +5892,8 @@ background_services:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
 
             let config = Config::load(&args)?;
@@ -4180,6 +5956,8 @@ secret_key: "test-secret-key"
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let config = Config::load(&args)?;
             assert_eq!(config.background_services.batch_daemon.claim_ramp_exponent, 0.56);
@@ -4212,6 +5990,8 @@ secret_key: "test-secret-key"
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let config = Config::load(&args)?;
             assert_eq!(config.background_services.batch_daemon.claim_loop_max_consecutive_failures, 10);
@@ -4244,6 +6024,8 @@ secret_key: "test-secret-key"
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let config = Config::load(&args)?;
             assert_eq!(config.background_services.batch_daemon.claim_query_timeout_ms, 180_000);
@@ -4279,6 +6061,8 @@ background_services:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let result = Config::load(&args);
             assert!(result.is_err());
@@ -4304,6 +6088,8 @@ background_services:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let result = Config::load(&args);
             assert!(result.is_err());
@@ -4333,6 +6119,8 @@ background_services:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
 
             let config = Config::load(&args)?;
@@ -4357,6 +6145,8 @@ background_services:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let result = Config::load(&args);
             assert!(result.is_err());
@@ -4385,6 +6175,8 @@ background_services:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let result = Config::load(&args);
             assert!(result.is_err());
@@ -4413,6 +6205,8 @@ background_services:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let config = Config::load(&args)?;
             assert_eq!(config.background_services.batch_daemon.batch_archive_cancel_grace_secs, 600.0);
@@ -4436,6 +6230,8 @@ background_services:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let result = Config::load(&args);
             assert!(result.is_err());
@@ -4461,6 +6257,8 @@ background_services:
             let args = Args {
                 config: "test.yaml".into(),
                 validate: false,
+                skip_migrations: false,
+                command: None,
             };
             let result = Config::load(&args);
             assert!(result.is_err());
@@ -4473,4 +6271,88 @@ background_services:
             Ok(())
         });
     }
+
+    #[test]
+    fn test_api_key_purposes_yaml_and_validation() {
+        Jail::expect_with(|jail| {
+            jail.create_file(
+                "test.yaml",
+                r#"
+secret_key: "test-secret-key"
+auth:
+  api_key_purposes:
+    default: platform
+    by_role:
+      StandardUser: [realtime]
+      PlatformManager: [realtime, platform]
+"#,
+            )?;
+
+            let args = Args {
+                config: "test.yaml".into(),
+                validate: false,
+                skip_migrations: false,
+                command: None,
+            };
+
+            let mut config = Config::load(&args)?;
+            let purposes = &config.auth.api_key_purposes;
+            assert_eq!(purposes.default, ApiKeyPurpose::Platform);
+            assert_eq!(purposes.by_role[&Role::StandardUser], vec![ApiKeyPurpose::Realtime]);
+            assert_eq!(purposes.by_role.len(), 2);
+
+            config.auth.api_key_purposes.default = ApiKeyPurpose::Batch;
+            let err = config.validate().unwrap_err().to_string();
+            assert!(err.contains("api_key_purposes"), "{err}");
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_analytics_sink_yaml() {
+        Jail::expect_with(|jail| {
+            jail.create_file(
+                "test.yaml",
+                r#"
+secret_key: "test-secret-key"
+analytics:
+  sink:
+    destination:
+      type: kafka
+      rest_proxy_url: "http://kafka-rest:8082"
+      topic: usage
+    buffer_size: 500
+    retry_base_delay: 2s
+"#,
+            )?;
+
+            let args = Args {
+                config: "test.yaml".into(),
+                validate: false,
+                skip_migrations: false,
+                command: None,
+            };
+
+            let config = Config::load(&args)?;
+            let sink = &config.analytics.sink;
+            match &sink.destination {
+                Some(AnalyticsSinkDestination::Kafka {
+                    rest_proxy_url,
+                    topic,
+                    headers,
+                }) => {
+                    assert_eq!(rest_proxy_url.as_str(), "http://kafka-rest:8082/");
+                    assert_eq!(topic, "usage");
+                    assert!(headers.is_empty());
+                }
+                other => panic!("expected a kafka destination, got {other:?}"),
+            }
+            assert_eq!(sink.buffer_size, 500);
+            assert_eq!(sink.batch_size, 100);
+            assert_eq!(sink.retry_base_delay, Duration::from_secs(2));
+
+            Ok(())
+        });
+    }
 }
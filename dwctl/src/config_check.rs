@@ -0,0 +1,228 @@
+//! Offline configuration checks behind `dwctl validate-config`.
+//!
+//! Nothing here binds a port, starts the embedded database or runs migrations, so
+//! the command is safe to run in CI against a production config. Database
+//! connectivity is only probed when explicitly requested.
+
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use sqlx::{Connection, PgConnection};
+
+use crate::Config;
+use crate::config::Args;
+
+/// How long to wait for each database connection before reporting it unreachable.
+const DATABASE_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Ok,
+    Skipped,
+    Failed,
+}
+
+/// Outcome of a single check, with a human-readable explanation.
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+/// The result of validating a configuration file.
+#[derive(Debug, Clone)]
+pub struct ValidationReport {
+    pub config_path: PathBuf,
+    pub checks: Vec<Check>,
+}
+
+impl ValidationReport {
+    fn new(config_path: PathBuf) -> Self {
+        Self {
+            config_path,
+            checks: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, name: &'static str, status: CheckStatus, detail: impl Into<String>) {
+        self.checks.push(Check {
+            name,
+            status,
+            detail: detail.into(),
+        });
+    }
+
+    /// True when no check failed. Skipped checks do not make a config invalid.
+    pub fn is_valid(&self) -> bool {
+        self.checks.iter().all(|check| check.status != CheckStatus::Failed)
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Validating {}", self.config_path.display())?;
+        for check in &self.checks {
+            let status = match check.status {
+                CheckStatus::Ok => "ok",
+                CheckStatus::Skipped => "skipped",
+                CheckStatus::Failed => "FAILED",
+            };
+            writeln!(f, "  [{status:>7}] {}: {}", check.name, check.detail)?;
+        }
+        let failed = self.checks.iter().filter(|check| check.status == CheckStatus::Failed).count();
+        if failed == 0 {
+            writeln!(f, "Configuration is valid.")
+        } else {
+            writeln!(f, "Configuration is invalid: {failed} check(s) failed.")
+        }
+    }
+}
+
+/// Run every check that needs no network access. Returns the loaded config when
+/// it parsed and validated, so callers can run further checks against it.
+pub fn validate(args: &Args) -> (ValidationReport, Option<Config>) {
+    let mut report = ValidationReport::new(args.config.clone());
+
+    let config = match Config::load(args) {
+        Ok(config) => config,
+        Err(e) => {
+            report.push("configuration", CheckStatus::Failed, e.to_string());
+            return (report, None);
+        }
+    };
+    report.push("configuration", CheckStatus::Ok, "parsed and validated");
+
+    let cors = &config.auth.security.cors;
+    match crate::create_cors_layer(cors) {
+        Ok(_) => report.push(
+            "cors",
+            CheckStatus::Ok,
+            format!(
+                "{} allowed origin(s), credentials {}",
+                cors.allowed_origins.len(),
+                cors.allow_credentials
+            ),
+        ),
+        Err(e) => report.push("cors", CheckStatus::Failed, format!("invalid allowed origin: {e}")),
+    }
+
+    if config.model_sources.is_empty() {
+        report.push("model sources", CheckStatus::Ok, "none configured");
+    } else {
+        let names: Vec<&str> = config.model_sources.iter().map(|source| source.name.as_str()).collect();
+        report.push("model sources", CheckStatus::Ok, names.join(", "));
+    }
+
+    (report, Some(config))
+}
+
+/// Check that the configured database and replicas accept connections.
+pub async fn check_database(config: &Config, report: &mut ValidationReport) {
+    let Some(url) = config.database.external_url() else {
+        report.push("database", CheckStatus::Skipped, "embedded database is started by dwctl at runtime");
+        return;
+    };
+    push_connection_check(report, "database", url).await;
+
+    if let Some(replica_url) = config.database.external_replica_url() {
+        push_connection_check(report, "database replica", replica_url).await;
+    }
+    if let Some(analytics_url) = config.database.analytics_replica_url() {
+        push_connection_check(report, "database analytics replica", analytics_url).await;
+    }
+}
+
+async fn push_connection_check(report: &mut ValidationReport, name: &'static str, url: &str) {
+    // Connection errors are reported without the URL, which may embed credentials
+    match tokio::time::timeout(DATABASE_CONNECT_TIMEOUT, PgConnection::connect(url)).await {
+        Ok(Ok(conn)) => {
+            let _ = conn.close().await;
+            report.push(name, CheckStatus::Ok, "reachable");
+        }
+        Ok(Err(e)) => report.push(name, CheckStatus::Failed, format!("unreachable: {e}")),
+        Err(_) => report.push(
+            name,
+            CheckStatus::Failed,
+            format!("unreachable: no connection after {}s", DATABASE_CONNECT_TIMEOUT.as_secs()),
+        ),
+    }
+}
+
+/// Validate the configuration named by `args`, optionally probing the database.
+pub async fn run(args: &Args, check_database_connectivity: bool) -> ValidationReport {
+    let (mut report, config) = validate(args);
+    match config {
+        Some(config) if check_database_connectivity => check_database(&config, &mut report).await,
+        Some(_) => report.push("database", CheckStatus::Skipped, "pass --check-database to test connectivity"),
+        None => {}
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use figment::Jail;
+
+    fn args() -> Args {
+        Args {
+            config: "test.yaml".into(),
+            validate: false,
+            skip_migrations: false,
+            command: None,
+        }
+    }
+
+    #[test]
+    fn valid_config_passes() {
+        Jail::expect_with(|jail| {
+            jail.create_file(
+                "test.yaml",
+                r#"
+secret_key: hello
+model_sources:
+  - name: openai
+    url: https://api.openai.com
+"#,
+            )?;
+
+            let (report, config) = validate(&args());
+            assert!(report.is_valid(), "{report}");
+            assert!(config.is_some());
+            assert!(report.to_string().contains("Configuration is valid."));
+            assert!(report.checks.iter().any(|c| c.name == "model sources" && c.detail == "openai"));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn cors_wildcard_with_credentials_fails() {
+        Jail::expect_with(|jail| {
+            jail.create_file(
+                "test.yaml",
+                r#"
+secret_key: hello
+auth:
+  security:
+    cors:
+      allowed_origins:
+        - "*"
+      allow_credentials: true
+"#,
+            )?;
+
+            let (report, config) = validate(&args());
+            assert!(!report.is_valid());
+            assert!(config.is_none());
+            let failed: Vec<_> = report.checks.iter().filter(|c| c.status == CheckStatus::Failed).collect();
+            assert_eq!(failed.len(), 1);
+            assert!(failed[0].detail.contains("wildcard origin"), "{}", failed[0].detail);
+            assert!(report.to_string().contains("Configuration is invalid: 1 check(s) failed."));
+
+            Ok(())
+        });
+    }
+}
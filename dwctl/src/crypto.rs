@@ -30,6 +30,16 @@ pub fn generate_api_key() -> String {
     format!("sk-{}", general_purpose::URL_SAFE_NO_PAD.encode(key_bytes))
 }
 
+/// Shortest API key secret accepted when one is chosen rather than generated.
+pub const MIN_API_KEY_SECRET_LENGTH: usize = 32;
+
+/// Whether `secret` may be used as a chosen API key secret: at least
+/// [`MIN_API_KEY_SECRET_LENGTH`] characters, all visible ASCII so it can be
+/// sent as-is in an `Authorization` header.
+pub fn is_valid_api_key_secret(secret: &str) -> bool {
+    secret.len() >= MIN_API_KEY_SECRET_LENGTH && secret.bytes().all(|b| b.is_ascii_graphic())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -50,6 +60,14 @@ mod tests {
         assert!(key_part.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
     }
 
+    #[test]
+    fn test_is_valid_api_key_secret() {
+        assert!(is_valid_api_key_secret(&generate_api_key()));
+        assert!(is_valid_api_key_secret("sk-gitops-managed-system-key-0000000001"));
+        assert!(!is_valid_api_key_secret("sk-short"));
+        assert!(!is_valid_api_key_secret("sk-contains a space-and-is-long-enough-000"));
+    }
+
     #[test]
     fn test_generate_api_key_uniqueness() {
         let mut keys = HashSet::new();
@@ -7,10 +7,14 @@
 //!
 //! When built with the `embedded-db` feature, PostgreSQL binaries are bundled
 //! into the binary at compile time. Set POSTGRESQL_VERSION environment variable
-//! during build to specify the version (e.g., "16.4.0").
+//! during build to specify the version (e.g., "16.4.0"). A different version can
+//! be chosen at runtime with `database.version`; its binaries are downloaded on
+//! first start. Extensions listed in `database.extensions` are created on startup.
 
 #[cfg(feature = "embedded-db")]
-use postgresql_embedded::{PostgreSQL, Settings, V16};
+use postgresql_embedded::{PostgreSQL, Settings, V16, VersionReq};
+#[cfg(feature = "embedded-db")]
+use sqlx::{Connection, PgConnection};
 #[cfg(feature = "embedded-db")]
 use std::path::PathBuf;
 #[cfg(feature = "embedded-db")]
@@ -31,10 +35,12 @@ impl EmbeddedDatabase {
     /// # Arguments
     /// * `data_dir` - Directory where PostgreSQL data will be stored (default: `$HOME/.dwctl_data/postgres`)
     /// * `persistent` - Whether to persist data between restarts (default: false/ephemeral)
+    /// * `version` - PostgreSQL version to run, e.g. `"15"` or `"15.8.0"` (default: 16)
+    /// * `extensions` - Extensions to create in the database once it is running
     ///
     /// # Returns
     /// A running EmbeddedDatabase instance with connection string containing the actual port
-    pub async fn start(data_dir: Option<PathBuf>, persistent: bool) -> anyhow::Result<Self> {
+    pub async fn start(data_dir: Option<PathBuf>, persistent: bool, version: Option<&str>, extensions: &[String]) -> anyhow::Result<Self> {
         let data_dir = data_dir.unwrap_or_else(|| {
             // Default to $HOME/.dwctl_data/postgres, fallback to ./dwctl_data/postgres if HOME not available
             if let Some(home) = std::env::home_dir() {
@@ -52,10 +58,17 @@ impl EmbeddedDatabase {
             debug!("Starting ephemeral embedded PostgreSQL");
         }
 
+        // Use PostgreSQL 16 unless configured - set POSTGRESQL_VERSION at build time to bundle a specific version
+        let version = match version {
+            Some(version) => VersionReq::parse(&format!("={version}"))
+                .map_err(|e| anyhow::anyhow!("Invalid embedded PostgreSQL version '{}': {}", version, e))?,
+            None => V16.clone(),
+        };
+
         // Create settings for the embedded PostgreSQL instance
         let settings = Settings {
-            version: V16.clone(), // Use PostgreSQL 16 - set POSTGRESQL_VERSION at build time for specific version
-            port: 0,              // Use ephemeral port (OS will assign)
+            version,
+            port: 0, // Use ephemeral port (OS will assign)
             username: "postgres".to_string(),
             password: "password".to_string(),
             temporary: !persistent, // If persistent=false, temporary=true (ephemeral)
@@ -102,6 +115,21 @@ impl EmbeddedDatabase {
 
         let connection_string = postgres.settings().url(database_name);
 
+        if !extensions.is_empty() {
+            let mut conn = PgConnection::connect(&connection_string)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to connect to embedded PostgreSQL: {}", e))?;
+            for extension in extensions {
+                // Names are validated as [A-Za-z0-9_-] at config load, so quoting is enough
+                sqlx::query(&format!("CREATE EXTENSION IF NOT EXISTS \"{extension}\""))
+                    .execute(&mut conn)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to create extension '{}': {}", extension, e))?;
+                debug!("Created extension '{}'", extension);
+            }
+            conn.close().await.ok();
+        }
+
         info!("Embedded PostgreSQL started successfully on port {}", actual_port);
 
         Ok(Self {
@@ -133,7 +161,12 @@ pub struct EmbeddedDatabase;
 #[cfg(not(feature = "embedded-db"))]
 #[allow(dead_code)]
 impl EmbeddedDatabase {
-    pub async fn start(_data_dir: Option<std::path::PathBuf>, _persistent: bool) -> anyhow::Result<Self> {
+    pub async fn start(
+        _data_dir: Option<std::path::PathBuf>,
+        _persistent: bool,
+        _version: Option<&str>,
+        _extensions: &[String],
+    ) -> anyhow::Result<Self> {
         anyhow::bail!(
             "Embedded database feature is not enabled. \
              Rebuild with --features embedded-db to use this feature."
@@ -152,3 +185,35 @@ impl EmbeddedDatabase {
         Ok(())
     }
 }
+
+#[cfg(all(test, feature = "embedded-db"))]
+mod tests {
+    use super::EmbeddedDatabase;
+    use sqlx::{Connection, PgConnection};
+
+    #[tokio::test]
+    #[ignore = "downloads PostgreSQL 15 binaries"]
+    async fn test_start_with_version_and_extensions() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let db = EmbeddedDatabase::start(Some(data_dir.path().to_path_buf()), false, Some("15"), &["pg_trgm".to_string()])
+            .await
+            .expect("embedded PostgreSQL 15 should start");
+
+        let mut conn = PgConnection::connect(db.connection_string()).await.unwrap();
+        let server_version: String = sqlx::query_scalar("SHOW server_version").fetch_one(&mut conn).await.unwrap();
+        assert!(server_version.starts_with("15."), "{server_version}");
+        let installed: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM pg_extension WHERE extname = 'pg_trgm')")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        assert!(installed);
+        let similarity: f32 = sqlx::query_scalar("SELECT similarity('control', 'controls')")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        assert!(similarity > 0.5);
+        conn.close().await.unwrap();
+
+        db.stop().await.unwrap();
+    }
+}
@@ -0,0 +1,135 @@
+//! Database repository for access requests.
+//!
+//! An approved request is what lets a user use a deployment with
+//! `require_approval` set; the access checks (the onwards config sync, the
+//! `/v1/models` listing, error enrichment) read this table directly. Writes
+//! NOTIFY the config sync (migration 158).
+
+use crate::db::{
+    errors::{DbError, Result},
+    models::access_requests::{AccessRequestDBResponse, AccessRequestStatus},
+};
+use crate::types::{DeploymentId, UserId, abbrev_uuid};
+use sqlx::PgConnection;
+use tracing::instrument;
+use uuid::Uuid;
+
+pub struct AccessRequests<'c> {
+    db: &'c mut PgConnection,
+}
+
+impl<'c> AccessRequests<'c> {
+    pub fn new(db: &'c mut PgConnection) -> Self {
+        Self { db }
+    }
+
+    /// Get an access request by ID
+    #[instrument(skip(self), fields(request_id = %abbrev_uuid(&id)), err)]
+    pub async fn get(&mut self, id: Uuid) -> Result<Option<AccessRequestDBResponse>> {
+        let request = sqlx::query_as!(
+            AccessRequestDBResponse,
+            r#"
+            SELECT ar.id,
+                   ar.deployment_id,
+                   ar.user_id,
+                   u.email AS user_email,
+                   ar.status AS "status: AccessRequestStatus",
+                   ar.reason,
+                   ar.reviewed_by,
+                   ar.reviewed_at,
+                   ar.created_at,
+                   ar.updated_at
+            FROM access_requests ar
+            JOIN users u ON u.id = ar.user_id
+            WHERE ar.id = $1
+            "#,
+            id,
+        )
+        .fetch_optional(&mut *self.db)
+        .await?;
+        Ok(request)
+    }
+
+    /// List a deployment's access requests, optionally only those with `status`, newest first
+    #[instrument(skip(self), fields(deployment_id = %abbrev_uuid(&deployment_id)), err)]
+    pub async fn list_for_deployment(
+        &mut self,
+        deployment_id: DeploymentId,
+        status: Option<AccessRequestStatus>,
+    ) -> Result<Vec<AccessRequestDBResponse>> {
+        let requests = sqlx::query_as!(
+            AccessRequestDBResponse,
+            r#"
+            SELECT ar.id,
+                   ar.deployment_id,
+                   ar.user_id,
+                   u.email AS user_email,
+                   ar.status AS "status: AccessRequestStatus",
+                   ar.reason,
+                   ar.reviewed_by,
+                   ar.reviewed_at,
+                   ar.created_at,
+                   ar.updated_at
+            FROM access_requests ar
+            JOIN users u ON u.id = ar.user_id
+            WHERE ar.deployment_id = $1
+              AND ($2::text IS NULL OR ar.status = $2)
+            ORDER BY ar.created_at DESC, ar.id
+            "#,
+            deployment_id,
+            status.map(AccessRequestStatus::as_str),
+        )
+        .fetch_all(&mut *self.db)
+        .await?;
+        Ok(requests)
+    }
+
+    /// Ask for access on behalf of `user_id`. A denied request is reopened as
+    /// pending; a pending or approved one is left as it is.
+    #[instrument(skip(self, reason), fields(deployment_id = %abbrev_uuid(&deployment_id), user_id = %abbrev_uuid(&user_id)), err)]
+    pub async fn request(&mut self, deployment_id: DeploymentId, user_id: UserId, reason: Option<&str>) -> Result<AccessRequestDBResponse> {
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO access_requests (deployment_id, user_id, reason)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (deployment_id, user_id) DO UPDATE SET
+                status = CASE WHEN access_requests.status = 'denied' THEN 'pending' ELSE access_requests.status END,
+                reason = CASE WHEN access_requests.status = 'denied' THEN EXCLUDED.reason ELSE access_requests.reason END,
+                reviewed_by = CASE WHEN access_requests.status = 'denied' THEN NULL ELSE access_requests.reviewed_by END,
+                reviewed_at = CASE WHEN access_requests.status = 'denied' THEN NULL ELSE access_requests.reviewed_at END
+            RETURNING id
+            "#,
+            deployment_id,
+            user_id,
+            reason,
+        )
+        .fetch_one(&mut *self.db)
+        .await?;
+
+        self.get(id).await?.ok_or(DbError::NotFound)
+    }
+
+    /// Approve or deny a request. Approving a denied request (or denying an
+    /// approved one) is allowed, so a decision can be reversed.
+    #[instrument(skip(self), fields(request_id = %abbrev_uuid(&id), reviewer = %abbrev_uuid(&reviewed_by)), err)]
+    pub async fn review(&mut self, id: Uuid, status: AccessRequestStatus, reviewed_by: UserId) -> Result<Option<AccessRequestDBResponse>> {
+        let updated = sqlx::query_scalar!(
+            r#"
+            UPDATE access_requests
+            SET status = $2, reviewed_by = $3, reviewed_at = NOW()
+            WHERE id = $1
+            RETURNING id
+            "#,
+            id,
+            status.as_str(),
+            reviewed_by,
+        )
+        .fetch_optional(&mut *self.db)
+        .await?;
+
+        match updated {
+            Some(id) => self.get(id).await,
+            None => Ok(None),
+        }
+    }
+}
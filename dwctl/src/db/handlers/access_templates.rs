@@ -0,0 +1,207 @@
+//! Database repository for access templates and their deployments.
+
+use crate::db::{
+    errors::{DbError, Result},
+    models::access_templates::{AccessTemplateCreateDBRequest, AccessTemplateDBResponse, AccessTemplateUpdateDBRequest},
+};
+use crate::types::{DeploymentId, UserId, abbrev_uuid};
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgConnection};
+use tracing::instrument;
+use uuid::Uuid;
+
+/// Internal row struct: an `access_templates` row with its non-deleted deployments aggregated.
+#[derive(Debug, Clone, FromRow)]
+struct AccessTemplateRow {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub deployment_ids: Vec<DeploymentId>,
+    pub created_by: UserId,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<AccessTemplateRow> for AccessTemplateDBResponse {
+    fn from(row: AccessTemplateRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            description: row.description,
+            deployment_ids: row.deployment_ids,
+            created_by: row.created_by,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+pub struct AccessTemplates<'c> {
+    db: &'c mut PgConnection,
+}
+
+impl<'c> AccessTemplates<'c> {
+    pub fn new(db: &'c mut PgConnection) -> Self {
+        Self { db }
+    }
+
+    /// Create a template. Run inside a transaction so the template and its
+    /// deployments are written together.
+    #[instrument(skip(self, request), fields(name = %request.name, count = request.deployment_ids.len()), err)]
+    pub async fn create(&mut self, request: &AccessTemplateCreateDBRequest) -> Result<AccessTemplateDBResponse> {
+        let id = sqlx::query_scalar!(
+            "INSERT INTO access_templates (name, description, created_by) VALUES ($1, $2, $3) RETURNING id",
+            request.name,
+            request.description,
+            request.created_by,
+        )
+        .fetch_one(&mut *self.db)
+        .await?;
+
+        self.replace_deployments(id, &request.deployment_ids).await?;
+        self.get_by_id(id).await?.ok_or(DbError::NotFound)
+    }
+
+    #[instrument(skip(self), fields(id = %abbrev_uuid(&id)), err)]
+    pub async fn get_by_id(&mut self, id: Uuid) -> Result<Option<AccessTemplateDBResponse>> {
+        let row = sqlx::query_as!(
+            AccessTemplateRow,
+            r#"
+            SELECT
+                t.id, t.name, t.description, t.created_by, t.created_at, t.updated_at,
+                COALESCE(
+                    array_agg(atd.deployment_id ORDER BY atd.deployment_id) FILTER (WHERE dm.deleted = false),
+                    '{}'
+                ) as "deployment_ids!"
+            FROM access_templates t
+            LEFT JOIN access_template_deployments atd ON atd.template_id = t.id
+            LEFT JOIN deployed_models dm ON dm.id = atd.deployment_id
+            WHERE t.id = $1
+            GROUP BY t.id
+            "#,
+            id,
+        )
+        .fetch_optional(&mut *self.db)
+        .await?;
+
+        Ok(row.map(AccessTemplateDBResponse::from))
+    }
+
+    #[instrument(skip(self), err)]
+    pub async fn list(&mut self) -> Result<Vec<AccessTemplateDBResponse>> {
+        let rows = sqlx::query_as!(
+            AccessTemplateRow,
+            r#"
+            SELECT
+                t.id, t.name, t.description, t.created_by, t.created_at, t.updated_at,
+                COALESCE(
+                    array_agg(atd.deployment_id ORDER BY atd.deployment_id) FILTER (WHERE dm.deleted = false),
+                    '{}'
+                ) as "deployment_ids!"
+            FROM access_templates t
+            LEFT JOIN access_template_deployments atd ON atd.template_id = t.id
+            LEFT JOIN deployed_models dm ON dm.id = atd.deployment_id
+            GROUP BY t.id
+            ORDER BY t.name
+            "#
+        )
+        .fetch_all(&mut *self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(AccessTemplateDBResponse::from).collect())
+    }
+
+    /// Update a template. Run inside a transaction when replacing deployments.
+    #[instrument(skip(self, request), fields(id = %abbrev_uuid(&id)), err)]
+    pub async fn update(&mut self, id: Uuid, request: &AccessTemplateUpdateDBRequest) -> Result<AccessTemplateDBResponse> {
+        sqlx::query_scalar!(
+            r#"
+            UPDATE access_templates SET
+                name        = COALESCE($2, name),
+                description = CASE WHEN $3 THEN $4 ELSE description END
+            WHERE id = $1
+            RETURNING id
+            "#,
+            id,
+            request.name,
+            request.description.is_some(),
+            request.description.as_ref().and_then(|d| d.as_deref()),
+        )
+        .fetch_optional(&mut *self.db)
+        .await?
+        .ok_or(DbError::NotFound)?;
+
+        if let Some(deployment_ids) = &request.deployment_ids {
+            self.replace_deployments(id, deployment_ids).await?;
+        }
+        self.get_by_id(id).await?.ok_or(DbError::NotFound)
+    }
+
+    #[instrument(skip(self), fields(id = %abbrev_uuid(&id)), err)]
+    pub async fn delete(&mut self, id: Uuid) -> Result<bool> {
+        let result = sqlx::query!("DELETE FROM access_templates WHERE id = $1", id)
+            .execute(&mut *self.db)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Replace a template's deployments. Unknown deployment IDs fail with a
+    /// foreign key violation.
+    async fn replace_deployments(&mut self, id: Uuid, deployment_ids: &[DeploymentId]) -> Result<()> {
+        sqlx::query!("DELETE FROM access_template_deployments WHERE template_id = $1", id)
+            .execute(&mut *self.db)
+            .await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO access_template_deployments (template_id, deployment_id)
+            SELECT $1, deployment_id FROM UNNEST($2::uuid[]) AS deployment_id
+            ON CONFLICT DO NOTHING
+            "#,
+            id,
+            deployment_ids,
+        )
+        .execute(&mut *self.db)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::models::users::Role;
+    use crate::test::utils::{create_test_deployment, create_test_user};
+    use sqlx::PgPool;
+
+    #[sqlx::test]
+    async fn test_template_lists_only_live_deployments(pool: PgPool) {
+        let user = create_test_user(&pool, Role::PlatformManager).await;
+        let kept = create_test_deployment(&pool, user.id, "template-model-1", "template-alias-1").await;
+        let removed = create_test_deployment(&pool, user.id, "template-model-2", "template-alias-2").await;
+
+        let mut tx = pool.begin().await.unwrap();
+        let template = AccessTemplates::new(&mut tx)
+            .create(&AccessTemplateCreateDBRequest {
+                name: "Standard tier".to_string(),
+                description: None,
+                deployment_ids: vec![kept.id, removed.id],
+                created_by: user.id,
+            })
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+        assert_eq!(template.deployment_ids.len(), 2);
+
+        sqlx::query("UPDATE deployed_models SET deleted = true WHERE id = $1")
+            .bind(removed.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let mut conn = pool.acquire().await.unwrap();
+        let template = AccessTemplates::new(&mut conn).get_by_id(template.id).await.unwrap().unwrap();
+        assert_eq!(template.deployment_ids, vec![kept.id]);
+    }
+}
@@ -578,6 +578,44 @@ pub async fn get_model_metrics(db: &PgPool, mut model_aliases: Vec<String>) -> R
     Ok(result)
 }
 
+/// Latency and error aggregates for traffic to deployments on one inference endpoint
+#[derive(Debug, FromRow)]
+pub struct EndpointTrafficStats {
+    pub request_count: i64,
+    pub error_count: i64,
+    pub p50_latency_ms: Option<f64>,
+    pub p90_latency_ms: Option<f64>,
+    pub p99_latency_ms: Option<f64>,
+}
+
+/// Aggregate real traffic since `since` to the models hosted on an endpoint.
+///
+/// Requests are attributed by model alias, so traffic that reached the endpoint
+/// through a composite model is not included. 5xx responses count as errors.
+#[instrument(skip(db), err)]
+pub async fn get_endpoint_traffic_stats(db: &PgPool, endpoint_id: Uuid, since: DateTime<Utc>) -> Result<EndpointTrafficStats> {
+    let stats = sqlx::query_as!(
+        EndpointTrafficStats,
+        r#"
+        SELECT
+            COUNT(*) as "request_count!",
+            COUNT(*) FILTER (WHERE ha.status_code >= 500) as "error_count!",
+            (PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY ha.duration_ms))::float8 as p50_latency_ms,
+            (PERCENTILE_CONT(0.9) WITHIN GROUP (ORDER BY ha.duration_ms))::float8 as p90_latency_ms,
+            (PERCENTILE_CONT(0.99) WITHIN GROUP (ORDER BY ha.duration_ms))::float8 as p99_latency_ms
+        FROM http_analytics ha
+        WHERE ha.timestamp >= $2
+          AND ha.model IN (SELECT dm.alias FROM deployed_models dm WHERE dm.hosted_on = $1)
+        "#,
+        endpoint_id,
+        since
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(stats)
+}
+
 /// Internal implementation of get_model_metrics (not cached)
 async fn get_model_metrics_impl(db: &PgPool, model_aliases: Vec<String>) -> Result<HashMap<String, ModelMetrics>> {
     // Initialize all models with zero metrics (for models with no activity)
@@ -1327,6 +1365,55 @@ pub async fn get_user_batch_count_for_range(pool: &PgPool, user_id: Uuid, start:
     Ok(row)
 }
 
+/// Get per-model breakdown of successful requests made with one API key, read directly
+/// from `http_analytics` (the daily rollup has no key dimension).
+///
+/// Traffic is grouped by cap scope, `COALESCE(parent_api_key_id, id)`, so the key's
+/// hidden batch child (see migration 122) counts towards it.
+#[instrument(skip(pool), err)]
+pub async fn get_api_key_model_breakdown_for_range(
+    pool: &PgPool,
+    api_key_id: Uuid,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<ModelBreakdownEntry>> {
+    let rows = sqlx::query_as!(
+        ModelBreakdownRow,
+        r#"
+        SELECT model,
+               COALESCE(SUM(prompt_tokens), 0)::bigint     AS input_tokens,
+               COALESCE(SUM(completion_tokens), 0)::bigint AS output_tokens,
+               COALESCE(SUM(total_cost), 0)                AS cost,
+               COUNT(*)                                    AS request_count
+        FROM http_analytics
+        WHERE api_key_id IN (SELECT id FROM api_keys WHERE COALESCE(parent_api_key_id, id) = $1)
+          AND model IS NOT NULL
+          AND status_code BETWEEN 200 AND 299
+          AND timestamp >= $2 AND timestamp <= $3
+        GROUP BY model
+        ORDER BY COUNT(*) DESC
+        "#,
+        api_key_id,
+        start,
+        end
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            row.model.map(|model| ModelBreakdownEntry {
+                model,
+                input_tokens: row.input_tokens.unwrap_or(0),
+                output_tokens: row.output_tokens.unwrap_or(0),
+                cost: row.cost.map(|d| d.to_string()).unwrap_or_else(|| "0".to_string()),
+                request_count: row.request_count.unwrap_or(0),
+            })
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
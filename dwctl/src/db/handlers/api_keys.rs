@@ -58,6 +58,9 @@ struct ApiKey {
     pub spend_limit: Option<Decimal>,
     pub spend_limit_interval: Option<String>,
     pub parent_api_key_id: Option<ApiKeyId>,
+    pub allowed_model_ids: Option<Vec<DeploymentId>>,
+    pub denied_model_ids: Option<Vec<DeploymentId>>,
+    pub max_priority: i16,
 }
 
 impl From<(Vec<DeploymentId>, ApiKey)> for ApiKeyDBResponse {
@@ -95,6 +98,9 @@ impl From<(Vec<DeploymentId>, ApiKey)> for ApiKeyDBResponse {
             spend_limit: api_key.spend_limit,
             spend_limit_interval: api_key.spend_limit_interval,
             parent_api_key_id: api_key.parent_api_key_id,
+            allowed_model_ids: api_key.allowed_model_ids,
+            denied_model_ids: api_key.denied_model_ids,
+            max_priority: api_key.max_priority,
         }
     }
 }
@@ -127,8 +133,8 @@ impl<'c> Repository for ApiKeys<'c> {
         let api_key = sqlx::query_as!(
             ApiKey,
             r#"
-            INSERT INTO api_keys (name, description, secret, purpose, user_id, created_by, requests_per_second, burst_size, hidden, spend_limit, spend_limit_interval)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, false, $9, $10)
+            INSERT INTO api_keys (name, description, secret, purpose, user_id, created_by, requests_per_second, burst_size, hidden, spend_limit, spend_limit_interval, allowed_model_ids, denied_model_ids, max_priority)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, false, $9, $10, $11, $12, $13)
             RETURNING *
             "#,
             request.name,
@@ -140,7 +146,10 @@ impl<'c> Repository for ApiKeys<'c> {
             request.requests_per_second,
             request.burst_size,
             request.spend_limit,
-            request.spend_limit_interval
+            request.spend_limit_interval,
+            request.allowed_model_ids.as_deref(),
+            request.denied_model_ids.as_deref(),
+            request.max_priority
         )
         .fetch_one(&mut *self.db)
         .await?;
@@ -152,7 +161,7 @@ impl<'c> Repository for ApiKeys<'c> {
     async fn get_by_id(&mut self, id: Self::Id) -> Result<Option<Self::Response>> {
         let api_key = sqlx::query_as!(
             ApiKey,
-            "SELECT id, name, description, secret, purpose, user_id, created_by, created_at, last_used, requests_per_second, burst_size, hidden, is_deleted, spend_limit, spend_limit_interval, parent_api_key_id FROM api_keys WHERE id = $1 AND is_deleted = false",
+            "SELECT id, name, description, secret, purpose, user_id, created_by, created_at, last_used, requests_per_second, burst_size, hidden, is_deleted, spend_limit, spend_limit_interval, parent_api_key_id, allowed_model_ids, denied_model_ids, max_priority FROM api_keys WHERE id = $1 AND is_deleted = false",
             id
         )
             .fetch_optional(&mut *self.db)
@@ -168,7 +177,7 @@ impl<'c> Repository for ApiKeys<'c> {
     async fn get_bulk(&mut self, ids: Vec<Self::Id>) -> Result<HashMap<Self::Id, Self::Response>> {
         let api_keys = sqlx::query_as!(
             ApiKey,
-            "SELECT id, name, description, secret, purpose, user_id, created_by, created_at, last_used, requests_per_second, burst_size, hidden, is_deleted, spend_limit, spend_limit_interval, parent_api_key_id FROM api_keys WHERE id = ANY($1) AND is_deleted = false",
+            "SELECT id, name, description, secret, purpose, user_id, created_by, created_at, last_used, requests_per_second, burst_size, hidden, is_deleted, spend_limit, spend_limit_interval, parent_api_key_id, allowed_model_ids, denied_model_ids, max_priority FROM api_keys WHERE id = ANY($1) AND is_deleted = false",
             &ids
         )
             .fetch_all(&mut *self.db)
@@ -186,7 +195,7 @@ impl<'c> Repository for ApiKeys<'c> {
     async fn list(&mut self, filter: &Self::Filter) -> Result<Vec<Self::Response>> {
         let api_keys = sqlx::query_as!(
             ApiKey,
-            r#"SELECT id, name, description, secret, purpose, user_id, created_by, created_at, last_used, requests_per_second, burst_size, hidden, is_deleted, spend_limit, spend_limit_interval, parent_api_key_id
+            r#"SELECT id, name, description, secret, purpose, user_id, created_by, created_at, last_used, requests_per_second, burst_size, hidden, is_deleted, spend_limit, spend_limit_interval, parent_api_key_id, allowed_model_ids, denied_model_ids, max_priority
             FROM api_keys
             WHERE hidden = false AND is_deleted = false
               AND ($1::uuid IS NULL OR user_id = $1)
@@ -255,7 +264,10 @@ impl<'c> Repository for ApiKeys<'c> {
                 burst_size = CASE
                     WHEN $5::integer IS NOT NULL THEN $5
                     ELSE burst_size
-                END
+                END,
+                allowed_model_ids = CASE WHEN $6 THEN $7 ELSE allowed_model_ids END,
+                denied_model_ids = CASE WHEN $8 THEN $9 ELSE denied_model_ids END,
+                max_priority = COALESCE($10, max_priority)
             WHERE id = $1
             RETURNING *
             "#,
@@ -263,7 +275,12 @@ impl<'c> Repository for ApiKeys<'c> {
             request.name,
             request.description,
             request.requests_per_second.unwrap_or(None),
-            request.burst_size.unwrap_or(None)
+            request.burst_size.unwrap_or(None),
+            request.allowed_model_ids.is_some(),
+            request.allowed_model_ids.as_ref().and_then(|ids| ids.as_deref()),
+            request.denied_model_ids.is_some(),
+            request.denied_model_ids.as_ref().and_then(|ids| ids.as_deref()),
+            request.max_priority,
         )
         .fetch_optional(&mut *self.db)
         .await?
@@ -295,6 +312,16 @@ impl<'c> ApiKeys<'c> {
         Ok(count.unwrap_or(0))
     }
 
+    /// Lock a key owner's user row until the transaction ends, serializing
+    /// key creations for them (e.g. around `limits.api_keys`).
+    #[instrument(skip(self), err)]
+    pub async fn lock_owner(&mut self, user_id: UserId) -> Result<()> {
+        sqlx::query!("SELECT id FROM users WHERE id = $1 FOR UPDATE", user_id)
+            .fetch_optional(&mut *self.db)
+            .await?;
+        Ok(())
+    }
+
     /// Get the user ID associated with an API key secret
     ///
     /// Joins with users table to verify the key exists and user is valid.
@@ -635,12 +662,13 @@ impl<'c> ApiKeys<'c> {
                    -- mislead. (The row itself is kept for instant re-capping.)
                    CASE WHEN ak.spend_limit IS NULL THEN NULL
                         WHEN ck.api_key_id IS NULL THEN NULL
-                        WHEN api_key_cap_window_current(ck.window_started_at, ak.spend_limit_interval)
+                        WHEN api_key_cap_window_current(ck.window_started_at, ak.spend_limit_interval, owner.timezone)
                         THEN ck.window_spend ELSE 0 END AS spend,
                    CASE WHEN ak.spend_limit IS NULL THEN NULL ELSE ck.total_spend END AS "total_spend?",
                    CASE WHEN ak.spend_limit IS NOT NULL
-                        THEN api_key_cap_window_resets_at(ak.spend_limit_interval) END AS resets_at
+                        THEN api_key_cap_window_resets_at(ak.spend_limit_interval, owner.timezone) END AS resets_at
             FROM api_keys ak
+            JOIN users owner ON owner.id = ak.user_id
             LEFT JOIN api_key_spend_checkpoints ck ON ck.api_key_id = ak.id
             WHERE ak.id = ANY($1)
             "#,
@@ -677,10 +705,11 @@ impl<'c> ApiKeys<'c> {
                 SELECT 1
                 FROM api_keys ak
                 JOIN api_keys root ON root.id = COALESCE(ak.parent_api_key_id, ak.id)
+                JOIN users owner ON owner.id = root.user_id
                 LEFT JOIN api_key_spend_checkpoints ck ON ck.api_key_id = root.id
                 WHERE ak.id = $1
                   AND root.spend_limit IS NOT NULL
-                  AND api_key_cap_window_current(ck.window_started_at, root.spend_limit_interval)
+                  AND api_key_cap_window_current(ck.window_started_at, root.spend_limit_interval, owner.timezone)
                   AND ck.window_spend >= root.spend_limit
             ) AS "exhausted!"
             "#,
@@ -777,10 +806,14 @@ impl<'c> ApiKeys<'c> {
     }
 
     /// Get specific deployment IDs that an API key has access to
+    ///
+    /// Group-derived access narrowed by the key's allow/deny lists (read from the
+    /// cap-scope root), matching the onwards sync query.
     #[instrument(skip(self), fields(api_key_id = %abbrev_uuid(&api_key_id)), err)]
     async fn get_api_key_deployments(&mut self, api_key_id: ApiKeyId) -> Result<Vec<DeploymentId>> {
         let deployment_ids = sqlx::query_scalar!(
             r#"
+            SELECT access.deployment_id FROM (
             SELECT DISTINCT dg.deployment_id
             FROM user_groups ug
             INNER JOIN deployment_groups dg ON ug.group_id = dg.group_id
@@ -794,6 +827,37 @@ impl<'c> ApiKeys<'c> {
             INNER JOIN api_keys ak ON dg.group_id = '00000000-0000-0000-0000-000000000000'
             WHERE ak.id = $1
             AND ak.user_id != '00000000-0000-0000-0000-000000000000'  -- Exclude system user
+
+            UNION
+
+            SELECT dm.id
+            FROM deployed_models dm
+            INNER JOIN api_keys ak ON dm.allow_public
+            WHERE ak.id = $1
+            AND ak.user_id != '00000000-0000-0000-0000-000000000000'  -- Exclude system user
+            ) access (deployment_id)
+            -- Gated models also need the key owner's approved access request
+            WHERE NOT EXISTS (
+                SELECT 1
+                FROM deployed_models dm
+                INNER JOIN api_keys ak ON ak.id = $1
+                WHERE dm.id = access.deployment_id
+                AND dm.require_approval
+                AND NOT EXISTS (
+                    SELECT 1 FROM access_requests ar
+                    WHERE ar.deployment_id = dm.id AND ar.user_id = ak.user_id AND ar.status = 'approved'
+                )
+            )
+            AND NOT EXISTS (
+                SELECT 1
+                FROM api_keys ak
+                JOIN api_keys scope ON scope.id = COALESCE(ak.parent_api_key_id, ak.id)
+                WHERE ak.id = $1
+                AND (
+                    (scope.allowed_model_ids IS NOT NULL AND NOT (access.deployment_id = ANY(scope.allowed_model_ids)))
+                    OR access.deployment_id = ANY(COALESCE(scope.denied_model_ids, '{}'))
+                )
+            )
             "#,
             api_key_id
         )
@@ -829,7 +893,10 @@ impl<'c> ApiKeys<'c> {
                 ak.is_deleted as "is_deleted!",
                 ak.spend_limit,
                 ak.spend_limit_interval,
-                ak.parent_api_key_id
+                ak.parent_api_key_id,
+                ak.allowed_model_ids,
+                ak.denied_model_ids,
+                ak.max_priority as "max_priority!"
             FROM api_keys ak
             WHERE ak.user_id = $2  -- System user has access to all deployments
 
@@ -851,7 +918,10 @@ impl<'c> ApiKeys<'c> {
                 ak.is_deleted as "is_deleted!",
                 ak.spend_limit,
                 ak.spend_limit_interval,
-                ak.parent_api_key_id
+                ak.parent_api_key_id,
+                ak.allowed_model_ids,
+                ak.denied_model_ids,
+                ak.max_priority as "max_priority!"
             FROM api_keys ak
             INNER JOIN user_groups ug ON ak.user_id = ug.user_id
             INNER JOIN deployment_groups dg ON ug.group_id = dg.group_id
@@ -896,11 +966,20 @@ impl<'c> ApiKeys<'c> {
                 ak.is_deleted as "is_deleted!",
                 ak.spend_limit,
                 ak.spend_limit_interval,
-                ak.parent_api_key_id
+                ak.parent_api_key_id,
+                ak.allowed_model_ids,
+                ak.denied_model_ids,
+                ak.max_priority as "max_priority!"
             FROM api_keys ak
-            INNER JOIN deployment_groups dg ON dg.group_id = '00000000-0000-0000-0000-000000000000'
-            INNER JOIN deployed_models dm ON dg.deployment_id = dm.id
-            WHERE dg.deployment_id = $1
+            INNER JOIN deployed_models dm ON dm.id = $1
+            WHERE (
+                dm.allow_public
+                OR EXISTS (
+                    SELECT 1 FROM deployment_groups dg
+                    WHERE dg.deployment_id = dm.id
+                    AND dg.group_id = '00000000-0000-0000-0000-000000000000'
+                )
+            )
             AND ak.user_id != '00000000-0000-0000-0000-000000000000'  -- Exclude system user (already covered above)
             AND (
                 ak.user_id = $2  -- System user always has access
@@ -954,6 +1033,14 @@ impl<'c> ApiKeys<'c> {
                 INNER JOIN deployment_groups dg ON dg.group_id = '00000000-0000-0000-0000-000000000000'
                 WHERE ak.id = ANY($1)
                 AND ak.user_id != '00000000-0000-0000-0000-000000000000'
+
+                UNION
+
+                SELECT ak.id as api_key_id, dm.id as deployment_id
+                FROM api_keys ak
+                INNER JOIN deployed_models dm ON dm.allow_public
+                WHERE ak.id = ANY($1)
+                AND ak.user_id != '00000000-0000-0000-0000-000000000000'
                 "#,
                 &api_key_ids
             )
@@ -1049,6 +1136,70 @@ impl<'c> ApiKeys<'c> {
 
         Ok(result.rows_affected())
     }
+
+    /// Replace the system API key's secret. Onwards picks up the new secret
+    /// through the api_keys change notification.
+    #[instrument(skip_all, err)]
+    pub async fn set_system_key_secret(&mut self, secret: &str) -> Result<()> {
+        let result = sqlx::query!(
+            "UPDATE api_keys SET secret = $1 WHERE id = '00000000-0000-0000-0000-000000000000'",
+            secret
+        )
+        .execute(&mut *self.db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DbError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Get the purposes an admin allowed a user to create keys with, or
+    /// `None` if the user has no override
+    #[instrument(skip(self), fields(user_id = %abbrev_uuid(&user_id)), err)]
+    pub async fn get_allowed_purposes(&mut self, user_id: UserId) -> Result<Option<Vec<ApiKeyPurpose>>> {
+        let allowed = sqlx::query_scalar!("SELECT allowed_purposes FROM user_api_key_purposes WHERE user_id = $1", user_id)
+            .fetch_optional(&mut *self.db)
+            .await?;
+
+        Ok(allowed.map(|names| {
+            names
+                .into_iter()
+                .filter_map(|name| serde_json::from_value(serde_json::Value::String(name)).ok())
+                .collect()
+        }))
+    }
+
+    /// Set the purposes a user may create keys with, replacing any existing override
+    #[instrument(skip(self, purposes), fields(user_id = %abbrev_uuid(&user_id)), err)]
+    pub async fn set_allowed_purposes(&mut self, user_id: UserId, purposes: &[ApiKeyPurpose]) -> Result<()> {
+        let names: Vec<String> = purposes
+            .iter()
+            .filter_map(|purpose| serde_json::to_value(purpose).ok())
+            .filter_map(|value| value.as_str().map(str::to_string))
+            .collect();
+        sqlx::query!(
+            r#"INSERT INTO user_api_key_purposes (user_id, allowed_purposes)
+               VALUES ($1, $2)
+               ON CONFLICT (user_id) DO UPDATE SET
+                   allowed_purposes = EXCLUDED.allowed_purposes,
+                   updated_at = NOW()"#,
+            user_id,
+            &names,
+        )
+        .execute(&mut *self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Remove a user's override. Returns whether one was set.
+    #[instrument(skip(self), fields(user_id = %abbrev_uuid(&user_id)), err)]
+    pub async fn delete_allowed_purposes(&mut self, user_id: UserId) -> Result<bool> {
+        let result = sqlx::query!("DELETE FROM user_api_key_purposes WHERE user_id = $1", user_id)
+            .execute(&mut *self.db)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
 }
 
 #[cfg(test)]
@@ -1106,6 +1257,9 @@ mod tests {
                     created_by: userid,
                     spend_limit: None,
                     spend_limit_interval: None,
+                    allowed_model_ids: None,
+                    denied_model_ids: None,
+                    max_priority: 0,
                 };
 
                 api_key = api_repo.create(&api_key_create).await.unwrap();
@@ -1149,6 +1303,9 @@ mod tests {
                 created_by,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             })
             .await
             .unwrap()
@@ -1309,6 +1466,9 @@ mod tests {
                 created_by: user.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             };
             let key2 = ApiKeyCreateDBRequest {
                 user_id: user.id,
@@ -1320,6 +1480,9 @@ mod tests {
                 created_by: user.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             };
 
             api_repo.create(&key1).await.unwrap();
@@ -1378,6 +1541,9 @@ mod tests {
                 created_by: user.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             };
             api_key = api_repo.create(&api_key_create).await.unwrap();
         }
@@ -1424,6 +1590,9 @@ mod tests {
                 created_by: user.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             };
 
             // Test create via Repository trait
@@ -1446,6 +1615,9 @@ mod tests {
             description: Some("Updated description".to_string()),
             requests_per_second: None,
             burst_size: None,
+            allowed_model_ids: None,
+            denied_model_ids: None,
+            max_priority: None,
         };
         let updated_key = api_repo.update(api_key.id, &update).await.unwrap();
         assert_eq!(updated_key.name, "Updated Key Name");
@@ -1503,7 +1675,7 @@ mod tests {
         }
         // Create inference endpoint for deployments
         let config = crate::test::utils::create_test_config();
-        crate::seed_database(&config.model_sources, &pool).await.unwrap();
+        crate::seed_database(&config.model_sources, None, &pool).await.unwrap();
 
         // Get a valid endpoint ID
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
@@ -1551,6 +1723,9 @@ mod tests {
                 created_by: user.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             };
             api_key = api_key_repo.create(&api_key_create).await.unwrap();
         }
@@ -1626,7 +1801,7 @@ mod tests {
 
         // Create inference endpoint for deployments
         let config = crate::test::utils::create_test_config();
-        crate::seed_database(&config.model_sources, &pool).await.unwrap();
+        crate::seed_database(&config.model_sources, None, &pool).await.unwrap();
 
         {
             let mut group_tx = tx.begin().await.unwrap();
@@ -1684,6 +1859,9 @@ mod tests {
                 created_by: user.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             };
             api_key = api_key_repo.create(&api_key_create).await.unwrap();
         }
@@ -1773,7 +1951,7 @@ mod tests {
         // Create inference endpoint for deployments
         let config = crate::test::utils::create_test_config();
 
-        crate::seed_database(&config.model_sources, &pool).await.unwrap();
+        crate::seed_database(&config.model_sources, None, &pool).await.unwrap();
 
         {
             let mut group_tx = tx.begin().await.unwrap();
@@ -1831,6 +2009,9 @@ mod tests {
                 created_by: user.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             };
             api_key = api_key_repo.create(&api_key_create).await.unwrap();
         }
@@ -1931,7 +2112,7 @@ mod tests {
 
         // Create inference endpoint for deployments
         let config = crate::test::utils::create_test_config();
-        crate::seed_database(&config.model_sources, &pool).await.unwrap();
+        crate::seed_database(&config.model_sources, None, &pool).await.unwrap();
 
         {
             let mut group_tx = tx.begin().await.unwrap();
@@ -2004,6 +2185,9 @@ mod tests {
                 created_by: user1.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             };
             api_key1 = api_key_repo.create(&api_key1_create).await.unwrap();
 
@@ -2017,6 +2201,9 @@ mod tests {
                 created_by: user2.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             };
             api_key2 = api_key_repo.create(&api_key2_create).await.unwrap();
         }
@@ -2122,7 +2309,7 @@ mod tests {
         {
             // Create inference endpoint for deployments
             let config = crate::test::utils::create_test_config();
-            crate::seed_database(&config.model_sources, &pool).await.unwrap();
+            crate::seed_database(&config.model_sources, None, &pool).await.unwrap();
 
             {
                 let mut group_tx = tx.begin().await.unwrap();
@@ -2194,6 +2381,9 @@ mod tests {
                 created_by: user.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             };
             api_key = api_key_repo.create(&api_key_create).await.unwrap();
         }
@@ -2297,7 +2487,7 @@ mod tests {
         }
         // Create inference endpoint for deployments
         let config = crate::test::utils::create_test_config();
-        crate::seed_database(&config.model_sources, &pool).await.unwrap();
+        crate::seed_database(&config.model_sources, None, &pool).await.unwrap();
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
         {
             let mut deployment_repo = Deployments::new(tx.acquire().await.unwrap());
@@ -2339,6 +2529,9 @@ mod tests {
                 created_by: user.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             };
             api_key = api_key_repo.create(&api_key_create).await.unwrap();
         }
@@ -2468,6 +2661,8 @@ mod tests {
                 replica_url: None,
                 pool: Default::default(),
                 replica_pool: None,
+                analytics_replica_url: None,
+                analytics_pool: None,
                 fusillade: crate::config::default_fusillade_component(),
                 outlet: crate::config::default_outlet_component(),
                 underway_pool: crate::config::default_underway_pool(),
@@ -2484,6 +2679,7 @@ mod tests {
                 sync_interval: std::time::Duration::from_secs(3600),
                 default_models: None,
             }],
+            endpoint_sync: Default::default(),
             metadata: crate::config::Metadata {
                 region: Some("Test Region".to_string()),
                 organization: Some("Test Org".to_string()),
@@ -2495,6 +2691,7 @@ mod tests {
             enable_analytics: true,
             analytics: Default::default(),
             enable_otel_export: false,
+            log: Default::default(),
             credits: Default::default(),
             batches: Default::default(),
             background_services: crate::config::BackgroundServicesConfig::default(),
@@ -2507,11 +2704,12 @@ mod tests {
             connections: Default::default(),
             responses: Default::default(),
             image_normalizer: Default::default(),
+            secrets: Default::default(),
             openapi: Default::default(),
             cache: Default::default(),
             keystore: None,
         };
-        crate::seed_database(&config.model_sources, &pool).await.unwrap();
+        crate::seed_database(&config.model_sources, None, &pool).await.unwrap();
 
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
         let mut deployment_create = DeploymentCreateDBRequest::builder()
@@ -2555,6 +2753,9 @@ mod tests {
                 created_by: user.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             };
             api_key = api_key_repo.create(&api_key_create).await.unwrap();
         }
@@ -2642,6 +2843,9 @@ mod tests {
                     created_by: user.id,
                     spend_limit: None,
                     spend_limit_interval: None,
+                    allowed_model_ids: None,
+                    denied_model_ids: None,
+                    max_priority: 0,
                 };
                 api_repo.create(&key_create).await.unwrap();
             }
@@ -2777,6 +2981,9 @@ mod tests {
                 created_by: user1.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             };
             let key2 = ApiKeyCreateDBRequest {
                 user_id: user2.id,
@@ -2788,6 +2995,9 @@ mod tests {
                 created_by: user2.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             };
 
             api_repo.create(&key1).await.unwrap();
@@ -2856,6 +3066,9 @@ mod tests {
             created_by: user.id,
             spend_limit: None,
             spend_limit_interval: None,
+            allowed_model_ids: None,
+            denied_model_ids: None,
+            max_priority: 0,
         };
         let key2_create = ApiKeyCreateDBRequest {
             user_id: user.id,
@@ -2867,6 +3080,9 @@ mod tests {
             created_by: user.id,
             spend_limit: None,
             spend_limit_interval: None,
+            allowed_model_ids: None,
+            denied_model_ids: None,
+            max_priority: 0,
         };
         let key3_create = ApiKeyCreateDBRequest {
             user_id: user.id,
@@ -2878,6 +3094,9 @@ mod tests {
             created_by: user.id,
             spend_limit: None,
             spend_limit_interval: None,
+            allowed_model_ids: None,
+            denied_model_ids: None,
+            max_priority: 0,
         };
 
         let mut api_conn = pool.acquire().await.unwrap();
@@ -2938,6 +3157,9 @@ mod tests {
             created_by: user.id,
             spend_limit: None,
             spend_limit_interval: None,
+            allowed_model_ids: None,
+            denied_model_ids: None,
+            max_priority: 0,
         };
 
         let mut api_conn = pool.acquire().await.unwrap();
@@ -3020,6 +3242,9 @@ mod tests {
             created_by: user.id,
             spend_limit: None,
             spend_limit_interval: None,
+            allowed_model_ids: None,
+            denied_model_ids: None,
+            max_priority: 0,
         };
         let mut api_conn = pool.acquire().await.unwrap();
         let mut api_repo = ApiKeys::new(&mut api_conn);
@@ -3069,7 +3294,7 @@ mod tests {
 
         // Create inference endpoint for deployments
         let config = crate::test::utils::create_test_config();
-        crate::seed_database(&config.model_sources, &pool).await.unwrap();
+        crate::seed_database(&config.model_sources, None, &pool).await.unwrap();
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
 
         let mut tx = pool.begin().await.unwrap();
@@ -3111,6 +3336,9 @@ mod tests {
             created_by: user.id,
             spend_limit: None,
             spend_limit_interval: None,
+            allowed_model_ids: None,
+            denied_model_ids: None,
+            max_priority: 0,
         };
         let key2_create = ApiKeyCreateDBRequest {
             user_id: user.id,
@@ -3122,6 +3350,9 @@ mod tests {
             created_by: user.id,
             spend_limit: None,
             spend_limit_interval: None,
+            allowed_model_ids: None,
+            denied_model_ids: None,
+            max_priority: 0,
         };
 
         let mut api_repo = ApiKeys::new(&mut tx);
@@ -3185,6 +3416,9 @@ mod tests {
             created_by: user1.id,
             spend_limit: None,
             spend_limit_interval: None,
+            allowed_model_ids: None,
+            denied_model_ids: None,
+            max_priority: 0,
         };
         let key2_create = ApiKeyCreateDBRequest {
             user_id: user2.id,
@@ -3196,6 +3430,9 @@ mod tests {
             created_by: user2.id,
             spend_limit: None,
             spend_limit_interval: None,
+            allowed_model_ids: None,
+            denied_model_ids: None,
+            max_priority: 0,
         };
         let mut api_repo = ApiKeys::new(&mut tx);
 
@@ -3268,7 +3505,7 @@ mod tests {
 
         // Seed database and get endpoint ID
         let config = crate::test::utils::create_test_config();
-        crate::seed_database(&config.model_sources, pool).await.unwrap();
+        crate::seed_database(&config.model_sources, None, pool).await.unwrap();
         let test_endpoint_id = get_test_endpoint_id(pool).await;
 
         // Create deployment with pricing (paid model by default for credit tests)
@@ -3380,7 +3617,7 @@ mod tests {
 
         // Seed database and get endpoint ID
         let config = crate::test::utils::create_test_config();
-        crate::seed_database(&config.model_sources, &pool).await.unwrap();
+        crate::seed_database(&config.model_sources, None, &pool).await.unwrap();
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
 
         // Create deployment (paid model via tariffs)
@@ -3443,6 +3680,9 @@ mod tests {
                 purpose: ApiKeyPurpose::Realtime,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             })
             .await
             .unwrap();
@@ -3458,6 +3698,9 @@ mod tests {
                 purpose: ApiKeyPurpose::Realtime,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             })
             .await
             .unwrap();
@@ -3589,6 +3832,9 @@ mod tests {
                     purpose: ApiKeyPurpose::Realtime,
                     spend_limit: None,
                     spend_limit_interval: None,
+                    allowed_model_ids: None,
+                    denied_model_ids: None,
+                    max_priority: 0,
                 })
                 .await
                 .unwrap();
@@ -3685,6 +3931,9 @@ mod tests {
                     purpose: ApiKeyPurpose::Realtime,
                     spend_limit: None,
                     spend_limit_interval: None,
+                    allowed_model_ids: None,
+                    denied_model_ids: None,
+                    max_priority: 0,
                 })
                 .await
                 .unwrap();
@@ -3750,6 +3999,9 @@ mod tests {
                     purpose: ApiKeyPurpose::Realtime,
                     spend_limit: None,
                     spend_limit_interval: None,
+                    allowed_model_ids: None,
+                    denied_model_ids: None,
+                    max_priority: 0,
                 })
                 .await
                 .unwrap();
@@ -3844,6 +4096,9 @@ mod tests {
                     purpose: ApiKeyPurpose::Realtime,
                     spend_limit: None,
                     spend_limit_interval: None,
+                    allowed_model_ids: None,
+                    denied_model_ids: None,
+                    max_priority: 0,
                 })
                 .await
                 .unwrap();
@@ -3945,7 +4200,7 @@ mod tests {
 
         // Seed database and get endpoint ID
         let config = crate::test::utils::create_test_config();
-        crate::seed_database(&config.model_sources, &pool).await.unwrap();
+        crate::seed_database(&config.model_sources, None, &pool).await.unwrap();
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
 
         // Create deployment with NO pricing (free model)
@@ -3988,6 +4243,9 @@ mod tests {
                 purpose: ApiKeyPurpose::Realtime,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             })
             .await
             .unwrap();
@@ -4065,7 +4323,7 @@ mod tests {
 
         // Seed database and get endpoint ID
         let config = crate::test::utils::create_test_config();
-        crate::seed_database(&config.model_sources, &pool).await.unwrap();
+        crate::seed_database(&config.model_sources, None, &pool).await.unwrap();
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
 
         // Create deployment (paid model via tariffs)
@@ -4127,6 +4385,9 @@ mod tests {
                 purpose: ApiKeyPurpose::Realtime,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             })
             .await
             .unwrap();
@@ -4204,7 +4465,7 @@ mod tests {
 
         // Seed database and get endpoint ID
         let config = crate::test::utils::create_test_config();
-        crate::seed_database(&config.model_sources, &pool).await.unwrap();
+        crate::seed_database(&config.model_sources, None, &pool).await.unwrap();
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
 
         // Create deployment with explicit zero pricing (free model)
@@ -4266,6 +4527,9 @@ mod tests {
                 purpose: ApiKeyPurpose::Realtime,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             })
             .await
             .unwrap();
@@ -4329,6 +4593,9 @@ mod tests {
                 created_by: user.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             };
 
             api_key = api_repo.create(&api_key_create).await.unwrap();
@@ -4392,6 +4659,9 @@ mod tests {
                     created_by: user.id,
                     spend_limit: None,
                     spend_limit_interval: None,
+                    allowed_model_ids: None,
+                    denied_model_ids: None,
+                    max_priority: 0,
                 })
                 .await
                 .unwrap();
@@ -4449,6 +4719,9 @@ mod tests {
                     created_by: user.id,
                     spend_limit: None,
                     spend_limit_interval: None,
+                    allowed_model_ids: None,
+                    denied_model_ids: None,
+                    max_priority: 0,
                 })
                 .await
                 .unwrap();
@@ -4464,6 +4737,9 @@ mod tests {
                     created_by: user.id,
                     spend_limit: None,
                     spend_limit_interval: None,
+                    allowed_model_ids: None,
+                    denied_model_ids: None,
+                    max_priority: 0,
                 })
                 .await
                 .unwrap();
@@ -4533,6 +4809,9 @@ mod tests {
                 created_by: member.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             })
             .await
             .unwrap();
@@ -4762,6 +5041,9 @@ mod tests {
                     created_by: member_a.id,
                     spend_limit: None,
                     spend_limit_interval: None,
+                    allowed_model_ids: None,
+                    denied_model_ids: None,
+                    max_priority: 0,
                 })
                 .await
                 .unwrap();
@@ -4776,6 +5058,9 @@ mod tests {
                     created_by: member_a.id,
                     spend_limit: None,
                     spend_limit_interval: None,
+                    allowed_model_ids: None,
+                    denied_model_ids: None,
+                    max_priority: 0,
                 })
                 .await
                 .unwrap();
@@ -4791,6 +5076,9 @@ mod tests {
                     created_by: member_b.id,
                     spend_limit: None,
                     spend_limit_interval: None,
+                    allowed_model_ids: None,
+                    denied_model_ids: None,
+                    max_priority: 0,
                 })
                 .await
                 .unwrap();
@@ -4893,6 +5181,9 @@ mod tests {
                         created_by: member_a.id,
                         spend_limit: None,
                         spend_limit_interval: None,
+                        allowed_model_ids: None,
+                        denied_model_ids: None,
+                        max_priority: 0,
                     })
                     .await
                     .unwrap();
@@ -4908,6 +5199,9 @@ mod tests {
                     created_by: member_b.id,
                     spend_limit: None,
                     spend_limit_interval: None,
+                    allowed_model_ids: None,
+                    denied_model_ids: None,
+                    max_priority: 0,
                 })
                 .await
                 .unwrap();
@@ -0,0 +1,88 @@
+//! Database repository for batches removed by the batch retention job.
+//!
+//! Rows are written by [`crate::sync::batch_retention`] just before it
+//! soft-deletes the batch in fusillade, and read by the batch API so archived
+//! batches can still be retrieved and listed.
+
+use crate::db::{
+    errors::Result,
+    models::archived_batches::{ArchivedBatchCreateDBRequest, ArchivedBatchDBResponse, ArchivedBatchFilter},
+};
+use sqlx::PgConnection;
+use tracing::instrument;
+use uuid::Uuid;
+
+pub struct ArchivedBatches<'c> {
+    db: &'c mut PgConnection,
+}
+
+impl<'c> ArchivedBatches<'c> {
+    pub fn new(db: &'c mut PgConnection) -> Self {
+        Self { db }
+    }
+
+    /// Record an archived batch, replacing any earlier record of it (a retried archive)
+    #[instrument(skip(self, request), fields(batch_id = %request.batch_id), err)]
+    pub async fn create(&mut self, request: &ArchivedBatchCreateDBRequest) -> Result<ArchivedBatchDBResponse> {
+        let archived = sqlx::query_as!(
+            ArchivedBatchDBResponse,
+            r#"INSERT INTO archived_batches (batch_id, created_by, completion_window, batch_created_at, cold_storage_path, snapshot)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT (batch_id) DO UPDATE SET
+                   cold_storage_path = EXCLUDED.cold_storage_path,
+                   snapshot = EXCLUDED.snapshot,
+                   archived_at = NOW()
+               RETURNING batch_id, created_by, completion_window, batch_created_at, archived_at, cold_storage_path, snapshot"#,
+            request.batch_id,
+            request.created_by,
+            request.completion_window,
+            request.batch_created_at,
+            request.cold_storage_path,
+            request.snapshot,
+        )
+        .fetch_one(&mut *self.db)
+        .await?;
+        Ok(archived)
+    }
+
+    /// Get an archived batch, if the batch was archived
+    #[instrument(skip(self), err)]
+    pub async fn get(&mut self, batch_id: Uuid) -> Result<Option<ArchivedBatchDBResponse>> {
+        let archived = sqlx::query_as!(
+            ArchivedBatchDBResponse,
+            r#"SELECT batch_id, created_by, completion_window, batch_created_at, archived_at, cold_storage_path, snapshot
+               FROM archived_batches WHERE batch_id = $1"#,
+            batch_id,
+        )
+        .fetch_optional(&mut *self.db)
+        .await?;
+        Ok(archived)
+    }
+
+    /// List archived batches newest first (by batch creation time)
+    #[instrument(skip(self, filter), fields(limit = filter.limit), err)]
+    pub async fn list(&mut self, filter: &ArchivedBatchFilter) -> Result<Vec<ArchivedBatchDBResponse>> {
+        let archived = sqlx::query_as!(
+            ArchivedBatchDBResponse,
+            r#"SELECT batch_id, created_by, completion_window, batch_created_at, archived_at, cold_storage_path, snapshot
+               FROM archived_batches
+               WHERE ($1::text IS NULL OR created_by = $1)
+                 AND ($2::timestamptz IS NULL OR batch_created_at > $2)
+                 AND ($3::timestamptz IS NULL OR batch_created_at < $3)
+                 AND ($4::text[] IS NULL OR completion_window = ANY($4))
+                 AND ($5::uuid IS NULL OR (batch_created_at, batch_id) <
+                      (SELECT batch_created_at, batch_id FROM archived_batches WHERE batch_id = $5))
+               ORDER BY batch_created_at DESC, batch_id DESC
+               LIMIT $6"#,
+            filter.created_by,
+            filter.created_after,
+            filter.created_before,
+            filter.completion_windows.as_deref(),
+            filter.after,
+            filter.limit,
+        )
+        .fetch_all(&mut *self.db)
+        .await?;
+        Ok(archived)
+    }
+}
@@ -4,16 +4,18 @@ use crate::{
     api::models::transactions::TransactionFilters,
     db::{
         errors::Result,
-        models::credits::{CreditTransactionCreateDBRequest, CreditTransactionDBResponse, CreditTransactionType},
+        models::credits::{CreditLedgerEntry, CreditTransactionCreateDBRequest, CreditTransactionDBResponse, CreditTransactionType},
     },
     types::{UserId, abbrev_uuid},
 };
 use chrono::{DateTime, Utc};
+use futures::{Stream, TryStreamExt};
 
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, PgConnection};
 use std::collections::HashMap;
+use std::pin::Pin;
 use tracing::{instrument, trace};
 use uuid::Uuid;
 
@@ -363,6 +365,80 @@ impl<'c> Credits<'c> {
         Ok(balances_map)
     }
 
+    /// Users with at least one ledger row created at or after `since`.
+    #[instrument(skip(self), err)]
+    pub async fn users_with_transactions_since(&mut self, since: DateTime<Utc>) -> Result<Vec<UserId>> {
+        let user_ids = sqlx::query_scalar!(
+            "SELECT DISTINCT user_id FROM credits_transactions WHERE created_at >= $1 ORDER BY user_id",
+            since
+        )
+        .fetch_all(&mut *self.db)
+        .await?;
+
+        Ok(user_ids)
+    }
+
+    /// Re-derive a user's balance from their full ledger history and heal the
+    /// checkpoint if it disagrees. Returns the correction applied (ledger minus
+    /// checkpoint), or `None` if the checkpoint was already exact.
+    ///
+    /// Must run inside a transaction. The checkpoint row is locked before the
+    /// ledger is summed, in a separate statement: writers insert their ledger
+    /// rows and fold them under the same row lock in one transaction, so once
+    /// the lock is held every committed row is both visible to the sum and
+    /// already in the checkpoint, and rows still in flight are in neither (their
+    /// writer folds them after we commit). Nothing is counted twice or lost.
+    #[instrument(skip(self), fields(user_id = %abbrev_uuid(&user_id)), err)]
+    pub async fn reconcile_balance_checkpoint(&mut self, user_id: UserId) -> Result<Option<Decimal>> {
+        let Some(checkpoint) = sqlx::query_scalar!(
+            "SELECT balance FROM user_balance_checkpoints WHERE user_id = $1 FOR UPDATE",
+            user_id
+        )
+        .fetch_optional(&mut *self.db)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let ledger = sqlx::query!(
+            r#"
+            SELECT
+                COALESCE(SUM(CASE WHEN transaction_type IN ('admin_grant', 'purchase') THEN amount ELSE -amount END), 0) as "balance!",
+                COALESCE(MAX(seq), 0) as "max_seq!"
+            FROM credits_transactions
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_one(&mut *self.db)
+        .await?;
+
+        if ledger.balance == checkpoint {
+            return Ok(None);
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE user_balance_checkpoints
+            SET balance = $2,
+                checkpoint_seq = GREATEST(checkpoint_seq, $3),
+                updated_at = NOW()
+            WHERE user_id = $1
+            "#,
+            user_id,
+            ledger.balance,
+            ledger.max_seq,
+        )
+        .execute(&mut *self.db)
+        .await?;
+
+        if (checkpoint > Decimal::ZERO) != (ledger.balance > Decimal::ZERO) {
+            self.notify_balance_crossing().await?;
+        }
+
+        Ok(Some(ledger.balance - checkpoint))
+    }
+
     /// List transactions for a specific user with pagination and optional filters
     #[instrument(skip(self, filters), fields(user_id = %abbrev_uuid(&user_id), skip = skip, limit = limit), err)]
     pub async fn list_user_transactions(
@@ -444,6 +520,63 @@ impl<'c> Credits<'c> {
         Ok(transactions)
     }
 
+    /// Stream the ledger oldest first, each row carrying its user's balance
+    /// after it. The balance is summed over the user's whole ledger in `seq`
+    /// order, as the balance checkpoint folds rows, so filters only pick which
+    /// rows are returned and never shift the balances; a user's last row
+    /// matches `get_user_balance` when nothing after it is filtered out.
+    pub fn stream_ledger<'a>(
+        &'a mut self,
+        user_id: Option<UserId>,
+        filters: &TransactionFilters,
+    ) -> Pin<Box<dyn Stream<Item = Result<CreditLedgerEntry>> + Send + 'a>> {
+        let transaction_types: Option<Vec<String>> = filters
+            .transaction_types
+            .as_ref()
+            .map(|types| types.iter().map(transaction_type_to_string).collect());
+        let filters = filters.clone();
+
+        Box::pin(async_stream::try_stream! {
+            let mut rows = sqlx::query_as!(
+                CreditLedgerEntry,
+                r#"
+                SELECT
+                    id as "id!",
+                    user_id as "user_id!",
+                    transaction_type as "transaction_type!: CreditTransactionType",
+                    amount as "amount!",
+                    source_id as "source_id!",
+                    description,
+                    created_at as "created_at!",
+                    service_tier,
+                    balance as "balance!"
+                FROM (
+                    SELECT id, user_id, transaction_type, amount, source_id, description, created_at, service_tier, seq,
+                        SUM(CASE WHEN transaction_type IN ('admin_grant', 'purchase') THEN amount ELSE -amount END)
+                            OVER (PARTITION BY user_id ORDER BY seq) AS balance
+                    FROM credits_transactions
+                    WHERE ($1::uuid IS NULL OR user_id = $1)
+                ) ledger
+                WHERE ($2::text IS NULL OR description ILIKE '%' || $2 || '%')
+                  AND ($3::text[] IS NULL OR transaction_type::text = ANY($3))
+                  AND ($4::timestamptz IS NULL OR created_at >= $4)
+                  AND ($5::timestamptz IS NULL OR created_at <= $5)
+                ORDER BY seq ASC
+                "#,
+                user_id,
+                filters.search.as_deref(),
+                transaction_types.as_deref(),
+                filters.start_date,
+                filters.end_date,
+            )
+            .fetch(&mut *self.db);
+
+            while let Some(entry) = rows.try_next().await? {
+                yield entry;
+            }
+        })
+    }
+
     /// Get a single transaction by its ID
     #[instrument(skip(self), err)]
     pub async fn get_transaction_by_id(&mut self, transaction_id: Uuid) -> Result<Option<CreditTransactionDBResponse>> {
@@ -480,16 +613,18 @@ impl<'c> Credits<'c> {
         Ok(result.is_some())
     }
 
-    /// Get the total amount of auto top-up charges for a user in the current calendar month (UTC).
+    /// Get the total amount of auto top-up charges for a user in the current calendar month
+    /// (in the user's timezone).
     #[instrument(skip(self), err)]
     pub async fn get_monthly_auto_topup_spend(&mut self, user_id: UserId) -> Result<rust_decimal::Decimal> {
         let row = sqlx::query!(
             r#"
-            SELECT COALESCE(SUM(amount), 0)::decimal(20, 9) as "total!"
-            FROM credits_transactions
-            WHERE user_id = $1
-              AND source_id LIKE 'auto_topup_%'
-              AND created_at >= date_trunc('month', now() AT TIME ZONE 'UTC') AT TIME ZONE 'UTC'
+            SELECT COALESCE(SUM(ct.amount), 0)::decimal(20, 9) as "total!"
+            FROM credits_transactions ct
+            JOIN users u ON u.id = ct.user_id
+            WHERE ct.user_id = $1
+              AND ct.source_id LIKE 'auto_topup_%'
+              AND ct.created_at >= date_trunc('month', now() AT TIME ZONE u.timezone) AT TIME ZONE u.timezone
             "#,
             user_id
         )
@@ -499,7 +634,8 @@ impl<'c> Credits<'c> {
         Ok(row.total)
     }
 
-    /// Get the total auto top-up spend for multiple users in the current calendar month (UTC).
+    /// Get the total auto top-up spend for multiple users in the current calendar month (in
+    /// each user's timezone).
     /// Returns a map of user_id → total spend. Users with no auto-topup transactions this month
     /// will be absent from the map; callers should treat missing entries as zero.
     #[instrument(skip(self, user_ids), fields(count = user_ids.len()), err)]
@@ -510,12 +646,13 @@ impl<'c> Credits<'c> {
 
         let rows = sqlx::query!(
             r#"
-            SELECT user_id, COALESCE(SUM(amount), 0)::decimal(20, 9) as "total!"
-            FROM credits_transactions
-            WHERE user_id = ANY($1)
-              AND source_id LIKE 'auto_topup_%'
-              AND created_at >= date_trunc('month', now() AT TIME ZONE 'UTC') AT TIME ZONE 'UTC'
-            GROUP BY user_id
+            SELECT ct.user_id, COALESCE(SUM(ct.amount), 0)::decimal(20, 9) as "total!"
+            FROM credits_transactions ct
+            JOIN users u ON u.id = ct.user_id
+            WHERE ct.user_id = ANY($1)
+              AND ct.source_id LIKE 'auto_topup_%'
+              AND ct.created_at >= date_trunc('month', now() AT TIME ZONE u.timezone) AT TIME ZONE u.timezone
+            GROUP BY ct.user_id
             "#,
             user_ids
         )
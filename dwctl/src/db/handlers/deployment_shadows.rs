@@ -0,0 +1,75 @@
+//! Database repository for deployment shadow traffic.
+//!
+//! Shadows are read by the onwards config sync, and writes NOTIFY it
+//! (migration 151).
+
+use crate::db::{
+    errors::{DbError, Result},
+    models::deployment_shadows::{DeploymentShadowDBRequest, DeploymentShadowDBResponse},
+};
+use crate::types::{DeploymentId, abbrev_uuid};
+use sqlx::PgConnection;
+use tracing::instrument;
+
+pub struct DeploymentShadows<'c> {
+    db: &'c mut PgConnection,
+}
+
+impl<'c> DeploymentShadows<'c> {
+    pub fn new(db: &'c mut PgConnection) -> Self {
+        Self { db }
+    }
+
+    /// Get a deployment's shadow, if one is set
+    #[instrument(skip(self), fields(deployment_id = %abbrev_uuid(&deployment_id)), err)]
+    pub async fn get(&mut self, deployment_id: DeploymentId) -> Result<Option<DeploymentShadowDBResponse>> {
+        let shadow = sqlx::query_as!(
+            DeploymentShadowDBResponse,
+            r#"
+            SELECT s.shadow_deployment_id,
+                   dm.alias AS shadow_alias,
+                   s.fraction,
+                   s.created_at,
+                   s.updated_at
+            FROM deployment_shadows s
+            JOIN deployed_models dm ON dm.id = s.shadow_deployment_id
+            WHERE s.deployment_id = $1
+            "#,
+            deployment_id,
+        )
+        .fetch_optional(&mut *self.db)
+        .await?;
+        Ok(shadow)
+    }
+
+    /// Set a deployment's shadow, replacing any existing one
+    #[instrument(skip(self, request), fields(deployment_id = %abbrev_uuid(&deployment_id)), err)]
+    pub async fn set(&mut self, deployment_id: DeploymentId, request: &DeploymentShadowDBRequest) -> Result<DeploymentShadowDBResponse> {
+        sqlx::query!(
+            r#"
+            INSERT INTO deployment_shadows (deployment_id, shadow_deployment_id, fraction)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (deployment_id) DO UPDATE SET
+                shadow_deployment_id = EXCLUDED.shadow_deployment_id,
+                fraction = EXCLUDED.fraction,
+                updated_at = NOW()
+            "#,
+            deployment_id,
+            request.shadow_deployment_id,
+            request.fraction,
+        )
+        .execute(&mut *self.db)
+        .await?;
+
+        self.get(deployment_id).await?.ok_or(DbError::NotFound)
+    }
+
+    /// Remove a deployment's shadow. Returns whether one was set.
+    #[instrument(skip(self), fields(deployment_id = %abbrev_uuid(&deployment_id)), err)]
+    pub async fn delete(&mut self, deployment_id: DeploymentId) -> Result<bool> {
+        let result = sqlx::query!("DELETE FROM deployment_shadows WHERE deployment_id = $1", deployment_id)
+            .execute(&mut *self.db)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -1,14 +1,15 @@
 //! Database repository for model deployments.
 
 use crate::api::models::deployments::{ModelSortField, SortDirection};
+use crate::api::models::pagination::IdCursor;
 use crate::db::models::api_keys::ApiKeyPurpose;
 use crate::db::{
     errors::{DbError, Result},
     handlers::repository::Repository,
     models::deployments::{
-        DeploymentComponentCreateDBRequest, DeploymentComponentDBResponse, DeploymentCreateDBRequest, DeploymentDBResponse,
-        DeploymentUpdateDBRequest, LoadBalancingStrategy, ModelStatus, ModelType, ProviderPricing, ProviderPricingFields,
-        TrafficRuleAction, TrafficRuleDBRow,
+        CreditExhaustionMode, DeploymentComponentCreateDBRequest, DeploymentComponentDBResponse, DeploymentCreateDBRequest,
+        DeploymentDBResponse, DeploymentUpdateDBRequest, LoadBalancingStrategy, Modality, ModelStatus, ModelType, NLimitMode,
+        PromptLengthUnit, ProviderPricing, ProviderPricingFields, SystemPromptMode, TrafficRuleAction, TrafficRuleDBRow,
     },
 };
 use crate::reasoning::{ModelReasoningPolicy, resolve_reasoning_translation};
@@ -29,6 +30,20 @@ pub struct BatchModelInfo {
     pub allowed_windows: HashMap<String, Vec<String>>,
 }
 
+/// A deployment's settings for the inference cost guard.
+#[derive(Debug, Clone)]
+pub struct DeploymentCostLimits {
+    pub alias: String,
+    pub max_cost_per_request: Option<Decimal>,
+    /// Highest input price among the deployment's current tariffs (None when unpriced)
+    pub input_price_per_token: Option<Decimal>,
+    /// Highest output price among the deployment's current tariffs (None when unpriced)
+    pub output_price_per_token: Option<Decimal>,
+    pub max_n: Option<i32>,
+    pub credit_exhaustion_mode: CreditExhaustionMode,
+    pub max_overdraft: Option<Decimal>,
+}
+
 /// Filter options for listing deployments
 #[derive(Debug, Clone)]
 pub struct DeploymentFilter {
@@ -38,9 +53,11 @@ pub struct DeploymentFilter {
     pub statuses: Option<Vec<ModelStatus>>,
     pub deleted: Option<bool>, // None = show all, Some(false) = show non-deleted only, Some(true) = show deleted only
     pub accessible_to: Option<UserId>, // None = show all deployments, Some(user_id) = show only deployments accessible to that user
+    pub approved_only: bool,   // With accessible_to: also hide require_approval deployments the user hasn't been approved for
     pub group_ids: Option<Vec<crate::types::GroupId>>, // None = show all, Some(group_ids) = show only models in any of these groups
     pub aliases: Option<Vec<String>>,
     pub search: Option<String>,                // Case-insensitive substring search on alias and model_name
+    pub alias_contains: Option<String>,        // Case-insensitive substring match on alias only
     pub is_composite: Option<bool>,            // None = show all, Some(true) = composite only, Some(false) = non-composite only
     pub provider: Option<String>,              // Filter by metadata provider (case-insensitive exact match)
     pub model_type: Option<ModelType>,         // Filter by model type column
@@ -48,6 +65,7 @@ pub struct DeploymentFilter {
     pub available_for_realtime: Option<bool>,  // Filter by whether realtime traffic is denied
     pub sort_field: Option<ModelSortField>,    // Sort field (default: created_at)
     pub sort_direction: Option<SortDirection>, // Sort direction (default depends on field)
+    pub cursor: Option<IdCursor>,              // Keyset pagination by id; replaces sorting and skip
 }
 
 impl DeploymentFilter {
@@ -59,9 +77,11 @@ impl DeploymentFilter {
             statuses: None,
             deleted: None,       // Default: show all models
             accessible_to: None, // Default: show all deployments
-            group_ids: None,     // Default: show all groups
+            approved_only: false,
+            group_ids: None, // Default: show all groups
             aliases: None,
             search: None,
+            alias_contains: None,
             is_composite: None,           // Default: show all models
             provider: None,               // Default: no provider filter
             model_type: None,             // Default: no type filter
@@ -69,6 +89,7 @@ impl DeploymentFilter {
             available_for_realtime: None, // Default: no realtime availability filter
             sort_field: None,             // Default: created_at
             sort_direction: None,         // Default: depends on field
+            cursor: None,                 // Default: offset pagination
         }
     }
 
@@ -87,6 +108,14 @@ impl DeploymentFilter {
         self
     }
 
+    /// Narrow `accessible_to` to deployments the user can actually use: gated
+    /// deployments stay visible in the catalog so users can ask for access, but
+    /// aren't usable until an access request is approved.
+    pub fn with_approved_only(mut self) -> Self {
+        self.approved_only = true;
+        self
+    }
+
     pub fn with_groups(mut self, group_ids: Vec<crate::types::GroupId>) -> Self {
         self.group_ids = Some(group_ids);
         self
@@ -107,6 +136,11 @@ impl DeploymentFilter {
         self
     }
 
+    pub fn with_alias_contains(mut self, alias: String) -> Self {
+        self.alias_contains = Some(alias);
+        self
+    }
+
     pub fn with_composite(mut self, is_composite: bool) -> Self {
         self.is_composite = Some(is_composite);
         self
@@ -137,6 +171,11 @@ impl DeploymentFilter {
         self.sort_direction = direction;
         self
     }
+
+    pub fn with_cursor(mut self, cursor: IdCursor) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
 }
 
 /// Result of checking user access to a deployment
@@ -170,6 +209,10 @@ struct DeployedModel {
     pub capacity: Option<i32>,
     pub batch_capacity: Option<i32>,
     pub throughput: Option<f32>,
+    pub per_key_capacity: Option<i32>,
+    pub max_cost_per_request: Option<Decimal>,
+    pub input_modalities: Option<Vec<String>>,
+    pub output_modalities: Option<Vec<String>>,
     // Provider pricing (flexible)
     pub downstream_pricing_mode: Option<String>,
     pub downstream_input_price_per_token: Option<Decimal>,
@@ -192,6 +235,20 @@ struct DeployedModel {
     pub backoff_max_total_ms: Option<i32>,
     pub sanitize_responses: bool,
     pub trusted: bool,
+    pub allow_public: bool,
+    pub require_approval: bool,
+    pub rewrite_response_model: bool,
+    pub disable_logging: bool,
+    pub warmup: bool,
+    pub active: bool,
+    pub system_prompt: Option<String>,
+    pub system_prompt_mode: String,
+    pub max_n: Option<i32>,
+    pub max_n_mode: String,
+    pub credit_exhaustion_mode: String,
+    pub max_overdraft: Option<Decimal>,
+    pub max_prompt_length: Option<i32>,
+    pub max_prompt_length_unit: String,
     pub open_responses_adapter: Option<bool>,
     pub reasoning_translation_overrides: Option<serde_json::Value>,
     // Traffic routing
@@ -204,6 +261,15 @@ pub struct Deployments<'c> {
     db: &'c mut PgConnection,
 }
 
+/// Modalities stored as text, skipping any this version doesn't know.
+fn parse_modalities(stored: Option<Vec<String>>) -> Option<Vec<Modality>> {
+    stored.map(|modalities| modalities.iter().filter_map(|m| Modality::try_parse(m)).collect())
+}
+
+fn modality_strs(modalities: Option<&[Modality]>) -> Option<Vec<String>> {
+    modalities.map(|modalities| modalities.iter().map(|m| m.as_str().to_string()).collect())
+}
+
 impl From<(Option<ModelType>, DeployedModel)> for DeploymentDBResponse {
     fn from((model_type, m): (Option<ModelType>, DeployedModel)) -> Self {
         let provider_pricing = ProviderPricing::from_flat_fields(ProviderPricingFields {
@@ -239,6 +305,10 @@ impl From<(Option<ModelType>, DeployedModel)> for DeploymentDBResponse {
             requests_per_second: m.requests_per_second,
             burst_size: m.burst_size,
             capacity: m.capacity,
+            per_key_capacity: m.per_key_capacity,
+            max_cost_per_request: m.max_cost_per_request,
+            input_modalities: parse_modalities(m.input_modalities),
+            output_modalities: parse_modalities(m.output_modalities),
             batch_capacity: m.batch_capacity,
             throughput: m.throughput,
             provider_pricing,
@@ -258,6 +328,20 @@ impl From<(Option<ModelType>, DeployedModel)> for DeploymentDBResponse {
             backoff_max_total_ms: m.backoff_max_total_ms,
             sanitize_responses: m.sanitize_responses,
             trusted: m.trusted,
+            allow_public: m.allow_public,
+            require_approval: m.require_approval,
+            rewrite_response_model: m.rewrite_response_model,
+            disable_logging: m.disable_logging,
+            warmup: m.warmup,
+            active: m.active,
+            system_prompt: m.system_prompt,
+            system_prompt_mode: SystemPromptMode::try_parse(&m.system_prompt_mode).unwrap_or_default(),
+            max_n: m.max_n,
+            max_n_mode: NLimitMode::try_parse(&m.max_n_mode).unwrap_or_default(),
+            credit_exhaustion_mode: CreditExhaustionMode::try_parse(&m.credit_exhaustion_mode).unwrap_or_default(),
+            max_overdraft: m.max_overdraft,
+            max_prompt_length: m.max_prompt_length,
+            max_prompt_length_unit: PromptLengthUnit::try_parse(&m.max_prompt_length_unit).unwrap_or_default(),
             open_responses_adapter: m.open_responses_adapter.unwrap_or(true),
             reasoning_translation_overrides: m.reasoning_translation_overrides.and_then(|value| {
                 serde_json::from_value(value)
@@ -296,6 +380,7 @@ impl<'c> Repository for Deployments<'c> {
             ModelType::Chat => "CHAT",
             ModelType::Embeddings => "EMBEDDINGS",
             ModelType::Reranker => "RERANKER",
+            ModelType::Moderation => "MODERATION",
         });
 
         // Extract provider pricing fields
@@ -309,6 +394,8 @@ impl<'c> Repository for Deployments<'c> {
             .map(serde_json::to_value)
             .transpose()
             .map_err(anyhow::Error::from)?;
+        let input_modalities = modality_strs(request.input_modalities.as_deref());
+        let output_modalities = modality_strs(request.output_modalities.as_deref());
 
         let model = sqlx::query_as!(
             DeployedModel,
@@ -323,9 +410,11 @@ impl<'c> Repository for Deployments<'c> {
                 sanitize_responses, trusted, open_responses_adapter, allowed_batch_completion_windows,
                 metadata,
                 backoff_enabled, backoff_initial_ms, backoff_max_ms, backoff_factor, backoff_jitter, backoff_max_total_ms,
-                reasoning_translation_overrides
+                reasoning_translation_overrides, per_key_capacity, allow_public, max_cost_per_request, rewrite_response_model,
+                input_modalities, output_modalities, disable_logging, warmup, system_prompt, system_prompt_mode, require_approval,
+                max_n, max_n_mode, credit_exhaustion_mode, max_overdraft, max_prompt_length, max_prompt_length_unit
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32, $33, $34, $35, $36, $37, $38, $39)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32, $33, $34, $35, $36, $37, $38, $39, $40, $41, $42, $43, $44, $45, $46, $47, $48, $49, $50, $51, $52, $53, $54, $55, $56)
             RETURNING *
             "#,
             request.model_name.trim(),
@@ -367,6 +456,23 @@ impl<'c> Repository for Deployments<'c> {
             request.backoff_jitter.as_str(),          // $37
             request.backoff_max_total_ms,             // $38
             reasoning_translation_overrides,          // $39
+            request.per_key_capacity,                 // $40
+            request.allow_public,                     // $41
+            request.max_cost_per_request,             // $42
+            request.rewrite_response_model,           // $43
+            input_modalities.as_deref(),              // $44
+            output_modalities.as_deref(),             // $45
+            request.disable_logging,                  // $46
+            request.warmup,                           // $47
+            request.system_prompt.as_deref(),         // $48
+            request.system_prompt_mode.as_str(),      // $49
+            request.require_approval,                 // $50
+            request.max_n,                            // $51
+            request.max_n_mode.as_str(),              // $52
+            request.credit_exhaustion_mode.as_str(),  // $53
+            request.max_overdraft,                    // $54
+            request.max_prompt_length,                // $55
+            request.max_prompt_length_unit.as_str(),  // $56
         )
         .fetch_one(&mut *self.db)
         .await?;
@@ -375,6 +481,7 @@ impl<'c> Repository for Deployments<'c> {
             "CHAT" => Some(ModelType::Chat),
             "EMBEDDINGS" => Some(ModelType::Embeddings),
             "RERANKER" => Some(ModelType::Reranker),
+            "MODERATION" => Some(ModelType::Moderation),
             _ => None,
         });
 
@@ -385,7 +492,7 @@ impl<'c> Repository for Deployments<'c> {
     async fn get_by_id(&mut self, id: Self::Id) -> Result<Option<Self::Response>> {
         let model = sqlx::query_as!(
             DeployedModel,
-            "SELECT id, model_name, alias, display_name, description, type, capabilities, created_by, hosted_on, status, last_sync, deleted, created_at, updated_at, requests_per_second, burst_size, capacity, batch_capacity, throughput, downstream_pricing_mode, downstream_input_price_per_token, downstream_output_price_per_token, downstream_hourly_rate, downstream_input_token_cost_ratio, is_composite, lb_strategy, fallback_enabled, fallback_on_rate_limit, fallback_on_status, fallback_with_replacement, fallback_max_attempts, backoff_enabled, backoff_initial_ms, backoff_max_ms, backoff_factor, backoff_jitter, backoff_max_total_ms, sanitize_responses, trusted, open_responses_adapter, allowed_batch_completion_windows, metadata, reasoning_translation_overrides FROM deployed_models WHERE id = $1",
+            "SELECT id, model_name, alias, display_name, description, type, capabilities, created_by, hosted_on, status, last_sync, deleted, created_at, updated_at, requests_per_second, burst_size, capacity, batch_capacity, throughput, downstream_pricing_mode, downstream_input_price_per_token, downstream_output_price_per_token, downstream_hourly_rate, downstream_input_token_cost_ratio, is_composite, lb_strategy, fallback_enabled, fallback_on_rate_limit, fallback_on_status, fallback_with_replacement, fallback_max_attempts, backoff_enabled, backoff_initial_ms, backoff_max_ms, backoff_factor, backoff_jitter, backoff_max_total_ms, sanitize_responses, trusted, open_responses_adapter, allowed_batch_completion_windows, metadata, reasoning_translation_overrides, per_key_capacity, allow_public, require_approval, max_cost_per_request, rewrite_response_model, input_modalities, output_modalities, disable_logging, warmup, active, system_prompt, system_prompt_mode, max_n, max_n_mode, credit_exhaustion_mode, max_overdraft, max_prompt_length, max_prompt_length_unit FROM deployed_models WHERE id = $1",
             id
         )
             .fetch_optional(&mut *self.db)
@@ -395,6 +502,8 @@ impl<'c> Repository for Deployments<'c> {
             m.r#type.as_ref().and_then(|s| match s.as_str() {
                 "CHAT" => Some(ModelType::Chat),
                 "EMBEDDINGS" => Some(ModelType::Embeddings),
+                "RERANKER" => Some(ModelType::Reranker),
+                "MODERATION" => Some(ModelType::Moderation),
                 _ => None,
             })
         });
@@ -410,7 +519,7 @@ impl<'c> Repository for Deployments<'c> {
 
         let deployments = sqlx::query_as!(
             DeployedModel,
-            "SELECT id, model_name, alias, display_name, description, type, capabilities, created_by, hosted_on, status, last_sync, deleted, created_at, updated_at, requests_per_second, burst_size, capacity, batch_capacity, throughput, downstream_pricing_mode, downstream_input_price_per_token, downstream_output_price_per_token, downstream_hourly_rate, downstream_input_token_cost_ratio, is_composite, lb_strategy, fallback_enabled, fallback_on_rate_limit, fallback_on_status, fallback_with_replacement, fallback_max_attempts, backoff_enabled, backoff_initial_ms, backoff_max_ms, backoff_factor, backoff_jitter, backoff_max_total_ms, sanitize_responses, trusted, open_responses_adapter, allowed_batch_completion_windows, metadata, reasoning_translation_overrides FROM deployed_models WHERE id = ANY($1)",
+            "SELECT id, model_name, alias, display_name, description, type, capabilities, created_by, hosted_on, status, last_sync, deleted, created_at, updated_at, requests_per_second, burst_size, capacity, batch_capacity, throughput, downstream_pricing_mode, downstream_input_price_per_token, downstream_output_price_per_token, downstream_hourly_rate, downstream_input_token_cost_ratio, is_composite, lb_strategy, fallback_enabled, fallback_on_rate_limit, fallback_on_status, fallback_with_replacement, fallback_max_attempts, backoff_enabled, backoff_initial_ms, backoff_max_ms, backoff_factor, backoff_jitter, backoff_max_total_ms, sanitize_responses, trusted, open_responses_adapter, allowed_batch_completion_windows, metadata, reasoning_translation_overrides, per_key_capacity, allow_public, require_approval, max_cost_per_request, rewrite_response_model, input_modalities, output_modalities, disable_logging, warmup, active, system_prompt, system_prompt_mode, max_n, max_n_mode, credit_exhaustion_mode, max_overdraft, max_prompt_length, max_prompt_length_unit FROM deployed_models WHERE id = ANY($1)",
             ids.as_slice()
         )
             .fetch_all(&mut *self.db)
@@ -422,6 +531,8 @@ impl<'c> Repository for Deployments<'c> {
             let model_type = deployment.r#type.as_ref().and_then(|s| match s.as_str() {
                 "CHAT" => Some(ModelType::Chat),
                 "EMBEDDINGS" => Some(ModelType::Embeddings),
+                "RERANKER" => Some(ModelType::Reranker),
+                "MODERATION" => Some(ModelType::Moderation),
                 _ => None,
             });
             result.insert(deployment.id, DeploymentDBResponse::from((model_type, deployment)));
@@ -458,6 +569,7 @@ impl<'c> Repository for Deployments<'c> {
                 ModelType::Chat => "CHAT",
                 ModelType::Embeddings => "EMBEDDINGS",
                 ModelType::Reranker => "RERANKER",
+                ModelType::Moderation => "MODERATION",
             })
         });
 
@@ -480,6 +592,9 @@ impl<'c> Repository for Deployments<'c> {
             .transpose()
             .map_err(anyhow::Error::from)?;
 
+        let input_modalities = modality_strs(request.input_modalities.as_ref().and_then(Option::as_deref));
+        let output_modalities = modality_strs(request.output_modalities.as_ref().and_then(Option::as_deref));
+
         // Info logging for rate limiting
         tracing::info!(
             "Updating deployment {} - requests_per_second: {:?}, burst_size: {:?}",
@@ -537,6 +652,22 @@ impl<'c> Repository for Deployments<'c> {
                 WHEN $20 THEN $21
                 ELSE batch_capacity
             END,
+            per_key_capacity = CASE
+                WHEN $58 THEN $59
+                ELSE per_key_capacity
+            END,
+            max_cost_per_request = CASE
+                WHEN $61 THEN $62
+                ELSE max_cost_per_request
+            END,
+            input_modalities = CASE
+                WHEN $64 THEN $65
+                ELSE input_modalities
+            END,
+            output_modalities = CASE
+                WHEN $66 THEN $67
+                ELSE output_modalities
+            END,
 
             -- Three-state update for throughput
             throughput = CASE
@@ -578,6 +709,31 @@ impl<'c> Repository for Deployments<'c> {
                 ELSE fallback_max_attempts
             END,
             trusted = COALESCE($42, trusted),
+            allow_public = COALESCE($60, allow_public),
+            require_approval = COALESCE($73, require_approval),
+            rewrite_response_model = COALESCE($63, rewrite_response_model),
+            disable_logging = COALESCE($68, disable_logging),
+            warmup = COALESCE($69, warmup),
+            system_prompt = CASE
+                WHEN $70 THEN $71
+                ELSE system_prompt
+            END,
+            system_prompt_mode = COALESCE($72, system_prompt_mode),
+            max_n = CASE
+                WHEN $74 THEN $75
+                ELSE max_n
+            END,
+            max_n_mode = COALESCE($76, max_n_mode),
+            credit_exhaustion_mode = COALESCE($77, credit_exhaustion_mode),
+            max_overdraft = CASE
+                WHEN $78 THEN $79
+                ELSE max_overdraft
+            END,
+            max_prompt_length = CASE
+                WHEN $80 THEN $81
+                ELSE max_prompt_length
+            END,
+            max_prompt_length_unit = COALESCE($82, max_prompt_length_unit),
             open_responses_adapter = COALESCE($43, open_responses_adapter),
 
             -- Batch completion windows
@@ -686,15 +842,52 @@ impl<'c> Repository for Deployments<'c> {
             request.backoff_max_total_ms.as_ref().and_then(|inner| inner.as_ref()), // $55
             request.reasoning_translation_overrides.is_some(),                      // $56
             reasoning_translation_overrides,                                        // $57
+            request.per_key_capacity.is_some() as bool,                             // $58
+            request.per_key_capacity.as_ref().and_then(|inner| inner.as_ref()),     // $59
+            request.allow_public,                                                   // $60
+            request.max_cost_per_request.is_some() as bool,                         // $61
+            request.max_cost_per_request.as_ref().and_then(|inner| inner.as_ref()), // $62
+            request.rewrite_response_model,                                         // $63
+            request.input_modalities.is_some() as bool,                             // $64
+            input_modalities.as_deref(),                                            // $65
+            request.output_modalities.is_some() as bool,                            // $66
+            output_modalities.as_deref(),                                           // $67
+            request.disable_logging,                                                // $68
+            request.warmup,                                                         // $69
+            request.system_prompt.is_some() as bool,                                // $70
+            request.system_prompt.as_ref().and_then(|inner| inner.as_deref()),      // $71
+            request.system_prompt_mode.map(|m| m.as_str()),                         // $72
+            request.require_approval,                                               // $73
+            request.max_n.is_some() as bool,                                        // $74
+            request.max_n.flatten(),                                                // $75
+            request.max_n_mode.map(|m| m.as_str()),                                 // $76
+            request.credit_exhaustion_mode.map(|m| m.as_str()),                     // $77
+            request.max_overdraft.is_some() as bool,                                // $78
+            request.max_overdraft.flatten(),                                        // $79
+            request.max_prompt_length.is_some() as bool,                            // $80
+            request.max_prompt_length.flatten(),                                    // $81
+            request.max_prompt_length_unit.map(|u| u.as_str()),                     // $82
         )
         .fetch_one(&mut *self.db)
         .await?;
 
+        // Turning public access off also withdraws a legacy everyone-group grant,
+        // otherwise the nil-group path would keep the model public.
+        if request.allow_public == Some(false) {
+            sqlx::query!(
+                "DELETE FROM deployment_groups WHERE deployment_id = $1 AND group_id = '00000000-0000-0000-0000-000000000000'::uuid",
+                id
+            )
+            .execute(&mut *self.db)
+            .await?;
+        }
+
         // Convert DB model_type back to enum
         let model_type = model.r#type.as_deref().and_then(|s| match s {
             "CHAT" => Some(ModelType::Chat),
             "EMBEDDINGS" => Some(ModelType::Embeddings),
             "RERANKER" => Some(ModelType::Reranker),
+            "MODERATION" => Some(ModelType::Moderation),
             _ => None,
         });
 
@@ -709,35 +902,46 @@ impl<'c> Repository for Deployments<'c> {
 
         Self::apply_filters(&mut query, filter);
 
-        // Dynamic ordering
-        let (sort_expr, default_dir) = match filter.sort_field {
-            Some(ModelSortField::Alias) => ("dm.alias", "ASC"),
-            Some(ModelSortField::IntelligenceIndex) => ("(dm.metadata->>'intelligence_index')::float8", "DESC"),
-            Some(ModelSortField::ReleasedAt) => ("(dm.metadata->>'released_at')::date", "DESC"),
-            Some(ModelSortField::ContextWindow) => ("(dm.metadata->>'context_window')::bigint", "DESC"),
-            Some(ModelSortField::Provider) => ("dm.metadata->>'provider'", "ASC"),
-            Some(ModelSortField::PriceFrom) => (
-                "(SELECT MIN(mt.input_price_per_token + mt.output_price_per_token) FROM model_tariffs mt WHERE mt.deployed_model_id = dm.id AND mt.valid_until IS NULL AND (mt.input_price_per_token + mt.output_price_per_token) > 0)",
-                "ASC",
-            ),
-            Some(ModelSortField::CreatedAt) | None => ("dm.created_at", "DESC"),
-        };
-        let direction = match filter.sort_direction {
-            Some(SortDirection::Asc) => "ASC",
-            Some(SortDirection::Desc) => "DESC",
-            None => default_dir,
-        };
-        // NULLS LAST for metadata sorts so models without metadata sink to the bottom
-        let nulls_clause =
-            if filter.sort_field.is_some() && !matches!(filter.sort_field, Some(ModelSortField::CreatedAt) | Some(ModelSortField::Alias)) {
+        // Keyset pagination orders by id alone, so pages stay disjoint as models come and go
+        if let Some(cursor) = filter.cursor {
+            if let IdCursor::After(after) = cursor {
+                query.push(" AND dm.id > ");
+                query.push_bind(after);
+            }
+            query.push(" ORDER BY dm.id LIMIT ");
+            query.push_bind(filter.limit);
+        } else {
+            // Dynamic ordering
+            let (sort_expr, default_dir) = match filter.sort_field {
+                Some(ModelSortField::Alias) => ("dm.alias", "ASC"),
+                Some(ModelSortField::IntelligenceIndex) => ("(dm.metadata->>'intelligence_index')::float8", "DESC"),
+                Some(ModelSortField::ReleasedAt) => ("(dm.metadata->>'released_at')::date", "DESC"),
+                Some(ModelSortField::ContextWindow) => ("(dm.metadata->>'context_window')::bigint", "DESC"),
+                Some(ModelSortField::Provider) => ("dm.metadata->>'provider'", "ASC"),
+                Some(ModelSortField::PriceFrom) => (
+                    "(SELECT MIN(mt.input_price_per_token + mt.output_price_per_token) FROM model_tariffs mt WHERE mt.deployed_model_id = dm.id AND mt.valid_until IS NULL AND (mt.input_price_per_token + mt.output_price_per_token) > 0)",
+                    "ASC",
+                ),
+                Some(ModelSortField::CreatedAt) | None => ("dm.created_at", "DESC"),
+            };
+            let direction = match filter.sort_direction {
+                Some(SortDirection::Asc) => "ASC",
+                Some(SortDirection::Desc) => "DESC",
+                None => default_dir,
+            };
+            // NULLS LAST for metadata sorts so models without metadata sink to the bottom
+            let nulls_clause = if filter.sort_field.is_some()
+                && !matches!(filter.sort_field, Some(ModelSortField::CreatedAt) | Some(ModelSortField::Alias))
+            {
                 " NULLS LAST"
             } else {
                 ""
             };
-        query.push(format!(" ORDER BY {sort_expr} {direction}{nulls_clause} LIMIT "));
-        query.push_bind(filter.limit);
-        query.push(" OFFSET ");
-        query.push_bind(filter.skip);
+            query.push(format!(" ORDER BY {sort_expr} {direction}{nulls_clause} LIMIT "));
+            query.push_bind(filter.limit);
+            query.push(" OFFSET ");
+            query.push_bind(filter.skip);
+        }
 
         let models = query.build_query_as::<DeployedModel>().fetch_all(&mut *self.db).await?;
 
@@ -748,6 +952,7 @@ impl<'c> Repository for Deployments<'c> {
                     "CHAT" => Some(ModelType::Chat),
                     "EMBEDDINGS" => Some(ModelType::Embeddings),
                     "RERANKER" => Some(ModelType::Reranker),
+                    "MODERATION" => Some(ModelType::Moderation),
                     _ => None,
                 });
 
@@ -790,14 +995,23 @@ impl<'c> Deployments<'c> {
         }
 
         if let Some(user_id) = filter.accessible_to {
-            query.push(" AND dm.id IN (");
+            query.push(" AND (dm.id IN (");
             query.push("SELECT dg.deployment_id FROM deployment_groups dg WHERE dg.group_id IN (");
             query.push("SELECT ug.group_id FROM user_groups ug WHERE ug.user_id = ");
             query.push_bind(user_id);
             query.push(" UNION SELECT '00000000-0000-0000-0000-000000000000'::uuid WHERE ");
             query.push_bind(user_id);
             query.push(" != '00000000-0000-0000-0000-000000000000'::uuid");
-            query.push("))");
+            query.push(")) OR (dm.allow_public AND ");
+            query.push_bind(user_id);
+            query.push(" != '00000000-0000-0000-0000-000000000000'::uuid))");
+
+            if filter.approved_only {
+                query.push(" AND (NOT dm.require_approval OR EXISTS (");
+                query.push("SELECT 1 FROM access_requests ar WHERE ar.deployment_id = dm.id AND ar.status = 'approved' AND ar.user_id = ");
+                query.push_bind(user_id);
+                query.push("))");
+            }
         }
 
         if let Some(ref group_ids) = filter.group_ids
@@ -820,6 +1034,11 @@ impl<'c> Deployments<'c> {
             query.push(")");
         }
 
+        if let Some(ref alias) = filter.alias_contains {
+            query.push(" AND LOWER(dm.alias) LIKE ");
+            query.push_bind(format!("%{}%", alias.to_lowercase()));
+        }
+
         if let Some(is_composite) = filter.is_composite {
             query.push(" AND dm.is_composite = ");
             query.push_bind(is_composite);
@@ -836,6 +1055,7 @@ impl<'c> Deployments<'c> {
                 ModelType::Chat => "CHAT",
                 ModelType::Embeddings => "EMBEDDINGS",
                 ModelType::Reranker => "RERANKER",
+                ModelType::Moderation => "MODERATION",
             };
             query.push(" AND dm.type = ");
             query.push_bind(type_str.to_string());
@@ -886,8 +1106,10 @@ impl<'c> Deployments<'c> {
             skip: 0,
             limit: i64::MAX,
             search: None,
+            alias_contains: None,
             sort_field: None,
             sort_direction: None,
+            cursor: None,
             ..filter.clone()
         };
         Self::apply_filters(&mut query, &facets_filter);
@@ -923,14 +1145,20 @@ impl<'c> Deployments<'c> {
                 d.alias as deployment_alias,
                 ak.secret as system_api_key
             FROM deployed_models d
-            JOIN deployment_groups dg ON dg.deployment_id = d.id
             JOIN api_keys ak ON ak.id = '00000000-0000-0000-0000-000000000000'::uuid
             WHERE d.alias = $1
-            AND dg.group_id IN (
-                SELECT ug.group_id FROM user_groups ug WHERE ug.user_id = $2
-                UNION
-                SELECT '00000000-0000-0000-0000-000000000000'::uuid
-                WHERE $2 != '00000000-0000-0000-0000-000000000000'::uuid
+            AND (
+                (d.allow_public AND $2 != '00000000-0000-0000-0000-000000000000'::uuid)
+                OR EXISTS (
+                    SELECT 1 FROM deployment_groups dg
+                    WHERE dg.deployment_id = d.id
+                    AND dg.group_id IN (
+                        SELECT ug.group_id FROM user_groups ug WHERE ug.user_id = $2
+                        UNION
+                        SELECT '00000000-0000-0000-0000-000000000000'::uuid
+                        WHERE $2 != '00000000-0000-0000-0000-000000000000'::uuid
+                    )
+                )
             )
             LIMIT 1
             "#,
@@ -1523,6 +1751,161 @@ impl<'c> Deployments<'c> {
         Ok(id)
     }
 
+    /// Aliases of all non-deleted deployments, for `onwards.alias_normalization`.
+    #[instrument(skip(self), err)]
+    pub async fn list_active_aliases(&mut self) -> Result<Vec<(DeploymentId, String)>> {
+        let rows = sqlx::query!("SELECT id, alias FROM deployed_models WHERE deleted = false")
+            .fetch_all(&mut *self.db)
+            .await?;
+
+        Ok(rows.into_iter().map(|row| (row.id, row.alias)).collect())
+    }
+
+    /// Cost settings of non-deleted deployments that set a per-request cost limit
+    /// or limit what a stream may spend of the caller's credits.
+    #[instrument(skip(self), err)]
+    pub async fn list_cost_limits(&mut self) -> Result<Vec<DeploymentCostLimits>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                dm.alias,
+                dm.max_cost_per_request,
+                (
+                    SELECT MAX(mt.input_price_per_token)
+                    FROM model_tariffs mt
+                    WHERE mt.deployed_model_id = dm.id AND mt.valid_until IS NULL
+                ) AS input_price_per_token,
+                (
+                    SELECT MAX(mt.output_price_per_token)
+                    FROM model_tariffs mt
+                    WHERE mt.deployed_model_id = dm.id AND mt.valid_until IS NULL
+                ) AS output_price_per_token,
+                dm.max_n,
+                dm.credit_exhaustion_mode,
+                dm.max_overdraft
+            FROM deployed_models dm
+            WHERE dm.deleted = false
+              AND (dm.max_cost_per_request IS NOT NULL OR dm.credit_exhaustion_mode = 'abort' OR dm.max_overdraft IS NOT NULL)
+            "#
+        )
+        .fetch_all(&mut *self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DeploymentCostLimits {
+                alias: row.alias,
+                max_cost_per_request: row.max_cost_per_request,
+                input_price_per_token: row.input_price_per_token,
+                output_price_per_token: row.output_price_per_token,
+                max_n: row.max_n,
+                credit_exhaustion_mode: CreditExhaustionMode::try_parse(&row.credit_exhaustion_mode).unwrap_or_default(),
+                max_overdraft: row.max_overdraft,
+            })
+            .collect())
+    }
+
+    /// Declared input and output modalities of non-deleted deployments that declare either.
+    #[instrument(skip(self), err)]
+    pub async fn list_modalities(&mut self) -> Result<Vec<(String, Option<Vec<Modality>>, Option<Vec<Modality>>)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT alias, input_modalities, output_modalities
+            FROM deployed_models
+            WHERE deleted = false AND (input_modalities IS NOT NULL OR output_modalities IS NOT NULL)
+            "#
+        )
+        .fetch_all(&mut *self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.alias,
+                    parse_modalities(row.input_modalities),
+                    parse_modalities(row.output_modalities),
+                )
+            })
+            .collect())
+    }
+
+    /// Prompt length limits of non-deleted deployments that set one.
+    #[instrument(skip(self), err)]
+    pub async fn list_prompt_length_limits(&mut self) -> Result<Vec<(String, i32, PromptLengthUnit)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT alias, max_prompt_length AS "max_prompt_length!", max_prompt_length_unit
+            FROM deployed_models
+            WHERE deleted = false AND max_prompt_length IS NOT NULL
+            "#
+        )
+        .fetch_all(&mut *self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.alias,
+                    row.max_prompt_length,
+                    PromptLengthUnit::try_parse(&row.max_prompt_length_unit).unwrap_or_default(),
+                )
+            })
+            .collect())
+    }
+
+    /// Aliases of non-deleted deployments whose request bodies must not be logged:
+    /// those with `disable_logging` set, and virtual models with it set on any component.
+    #[instrument(skip(self), err)]
+    pub async fn list_logging_disabled_aliases(&mut self) -> Result<Vec<String>> {
+        let aliases = sqlx::query_scalar!(
+            r#"
+            SELECT dm.alias
+            FROM deployed_models dm
+            WHERE dm.deleted = false
+              AND (
+                  dm.disable_logging
+                  OR EXISTS (
+                      SELECT 1
+                      FROM deployed_model_components dmc
+                      INNER JOIN deployed_models component ON component.id = dmc.deployed_model_id
+                      WHERE dmc.composite_model_id = dm.id AND component.disable_logging
+                  )
+              )
+            "#
+        )
+        .fetch_all(&mut *self.db)
+        .await?;
+
+        Ok(aliases)
+    }
+
+    /// Take a deployment out of service, or put it back. Returns false if it
+    /// doesn't exist or is deleted.
+    #[instrument(skip(self), fields(deployment_id = %abbrev_uuid(&id)), err)]
+    pub async fn set_active(&mut self, id: DeploymentId, active: bool) -> Result<bool> {
+        let result = sqlx::query!(
+            "UPDATE deployed_models SET active = $2, updated_at = NOW() WHERE id = $1 AND deleted = false",
+            id,
+            active
+        )
+        .execute(&mut *self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Aliases of non-deleted deployments that have been deactivated.
+    #[instrument(skip(self), err)]
+    pub async fn list_deactivated_aliases(&mut self) -> Result<Vec<String>> {
+        let aliases = sqlx::query_scalar!("SELECT alias FROM deployed_models WHERE deleted = false AND active = false")
+            .fetch_all(&mut *self.db)
+            .await?;
+
+        Ok(aliases)
+    }
+
     /// Set traffic routing rules for a model (replace-all pattern).
     #[instrument(skip(self, rules), fields(deployment_id = %abbrev_uuid(&deployed_model_id), count = rules.len()), err)]
     pub async fn set_traffic_rules(&mut self, deployed_model_id: DeploymentId, rules: &[(ApiKeyPurpose, TrafficRuleAction)]) -> Result<()> {
@@ -1537,20 +1920,24 @@ impl<'c> Deployments<'c> {
                 .ok()
                 .and_then(|v| v.as_str().map(String::from))
                 .unwrap_or_default();
-            let (action_str, redirect_target_id) = match action {
-                TrafficRuleAction::Deny => ("deny", None),
-                TrafficRuleAction::Redirect(target_id) => ("redirect", Some(*target_id)),
+            let (action_str, redirect_target_id, sanitize_responses, prefer_region) = match action {
+                TrafficRuleAction::Deny => ("deny", None, None, None),
+                TrafficRuleAction::Redirect(target_id) => ("redirect", Some(*target_id), None, None),
+                TrafficRuleAction::Sanitize(enabled) => ("sanitize", None, Some(*enabled), None),
+                TrafficRuleAction::PreferRegion(region) => ("prefer_region", None, None, Some(region.as_str())),
             };
 
             sqlx::query!(
                 r#"
-                INSERT INTO model_traffic_rules (deployed_model_id, api_key_purpose, action, redirect_target_id)
-                VALUES ($1, $2, $3, $4)
+                INSERT INTO model_traffic_rules (deployed_model_id, api_key_purpose, action, redirect_target_id, sanitize_responses, prefer_region)
+                VALUES ($1, $2, $3, $4, $5, $6)
                 "#,
                 deployed_model_id,
                 purpose_str,
                 action_str,
                 redirect_target_id,
+                sanitize_responses,
+                prefer_region,
             )
             .execute(&mut *self.db)
             .await?;
@@ -1566,7 +1953,7 @@ impl<'c> Deployments<'c> {
             r#"
             SELECT mtr.id, mtr.deployed_model_id, mtr.api_key_purpose, mtr.action,
                    mtr.redirect_target_id, dm.alias as "redirect_target_alias?",
-                   mtr.created_at
+                   mtr.sanitize_responses, mtr.prefer_region, mtr.created_at
             FROM model_traffic_rules mtr
             LEFT JOIN deployed_models dm ON dm.id = mtr.redirect_target_id
             WHERE mtr.deployed_model_id = $1
@@ -1586,6 +1973,8 @@ impl<'c> Deployments<'c> {
                 action: r.action,
                 redirect_target_id: r.redirect_target_id,
                 redirect_target_alias: r.redirect_target_alias,
+                sanitize_responses: r.sanitize_responses,
+                prefer_region: r.prefer_region,
                 created_at: r.created_at,
             })
             .collect())
@@ -1605,7 +1994,7 @@ impl<'c> Deployments<'c> {
             r#"
             SELECT mtr.id, mtr.deployed_model_id, mtr.api_key_purpose, mtr.action,
                    mtr.redirect_target_id, dm.alias as "redirect_target_alias?",
-                   mtr.created_at
+                   mtr.sanitize_responses, mtr.prefer_region, mtr.created_at
             FROM model_traffic_rules mtr
             LEFT JOIN deployed_models dm ON dm.id = mtr.redirect_target_id
             WHERE mtr.deployed_model_id = ANY($1)
@@ -1625,6 +2014,8 @@ impl<'c> Deployments<'c> {
                 action: r.action,
                 redirect_target_id: r.redirect_target_id,
                 redirect_target_alias: r.redirect_target_alias,
+                sanitize_responses: r.sanitize_responses,
+                prefer_region: r.prefer_region,
                 created_at: r.created_at,
             });
         }
@@ -1724,6 +2115,7 @@ mod tests {
                 sync_interval: std::time::Duration::from_secs(3600),
                 default_models: None,
             }],
+            None,
             &pool,
         )
         .await
@@ -1770,6 +2162,7 @@ mod tests {
                 sync_interval: std::time::Duration::from_secs(3600),
                 default_models: None,
             }],
+            None,
             &pool,
         )
         .await
@@ -1816,7 +2209,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let user = create_test_user(&pool).await;
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
@@ -1864,7 +2257,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let user = create_test_user(&pool).await;
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
@@ -1905,7 +2298,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let user = create_test_user(&pool).await;
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
@@ -1962,7 +2355,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let user = create_test_user(&pool).await;
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
@@ -2004,7 +2397,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let user = create_test_user(&pool).await;
 
@@ -2027,6 +2420,11 @@ mod tests {
                 model_create.capabilities = Some(vec!["test-capability".to_string()]);
                 model_create.capacity = Some(150);
                 model_create.batch_capacity = Some(60);
+                model_create.per_key_capacity = Some(10);
+                model_create.max_cost_per_request = Some(Decimal::new(25, 2));
+                model_create.max_overdraft = Some(Decimal::new(5, 1));
+                model_create.max_prompt_length = Some(8000);
+                model_create.input_modalities = Some(vec![Modality::Text, Modality::Image]);
 
                 created_model = repo.create(&model_create).await.unwrap();
 
@@ -2036,6 +2434,11 @@ mod tests {
                     .maybe_capabilities(Some(None))
                     .maybe_capacity(Some(None))
                     .maybe_batch_capacity(Some(None))
+                    .maybe_per_key_capacity(Some(None))
+                    .maybe_max_cost_per_request(Some(None))
+                    .maybe_max_overdraft(Some(None))
+                    .maybe_max_prompt_length(Some(None))
+                    .maybe_input_modalities(Some(None))
                     .build();
 
                 updated_model = repo.update(created_model.id, &update).await.unwrap();
@@ -2046,10 +2449,20 @@ mod tests {
         assert_eq!(created_model.capabilities, Some(vec!["test-capability".to_string()]));
         assert_eq!(created_model.capacity, Some(150));
         assert_eq!(created_model.batch_capacity, Some(60));
+        assert_eq!(created_model.per_key_capacity, Some(10));
+        assert_eq!(created_model.max_cost_per_request, Some(Decimal::new(25, 2)));
+        assert_eq!(created_model.max_overdraft, Some(Decimal::new(5, 1)));
+        assert_eq!(created_model.max_prompt_length, Some(8000));
+        assert_eq!(created_model.input_modalities, Some(vec![Modality::Text, Modality::Image]));
         assert_eq!(updated_model.model_type, None);
         assert_eq!(updated_model.capabilities, None);
         assert_eq!(updated_model.capacity, None);
         assert_eq!(updated_model.batch_capacity, None);
+        assert_eq!(updated_model.per_key_capacity, None);
+        assert_eq!(updated_model.max_cost_per_request, None);
+        assert_eq!(updated_model.max_overdraft, None);
+        assert_eq!(updated_model.max_prompt_length, None);
+        assert_eq!(updated_model.input_modalities, None);
     }
 
     #[sqlx::test]
@@ -2063,7 +2476,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let user = create_test_user(&pool).await;
 
@@ -2104,7 +2517,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let mut pool_conn = pool.acquire().await.unwrap();
         let mut repo = Deployments::new(&mut pool_conn);
@@ -2149,7 +2562,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let mut pool_conn = pool.acquire().await.unwrap();
         let mut repo = Deployments::new(&mut pool_conn);
@@ -2185,7 +2598,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let mut pool_conn = pool.acquire().await.unwrap();
         let mut repo = Deployments::new(&mut pool_conn);
@@ -2225,7 +2638,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let mut pool_conn = pool.acquire().await.unwrap();
         let mut repo = Deployments::new(&mut pool_conn);
@@ -2271,7 +2684,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let mut pool_conn = pool.acquire().await.unwrap();
         let mut repo = Deployments::new(&mut pool_conn);
@@ -2345,7 +2758,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let mut pool_conn = pool.acquire().await.unwrap();
         let mut repo = Deployments::new(&mut pool_conn);
@@ -2402,7 +2815,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let mut pool_conn = pool.acquire().await.unwrap();
         let mut repo = Deployments::new(&mut pool_conn);
@@ -2448,7 +2861,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let mut conn = pool.acquire().await.unwrap();
         let mut repo = Deployments::new(&mut conn);
@@ -2486,7 +2899,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let user = create_test_user(&pool).await;
         let mut conn = pool.acquire().await.unwrap();
@@ -2522,7 +2935,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let mut conn = pool.acquire().await.unwrap();
         let mut repo = Deployments::new(&mut conn);
@@ -2585,7 +2998,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
         let user = create_test_user(&pool).await;
 
         let mut conn = pool.acquire().await.unwrap();
@@ -2633,7 +3046,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
         let user = create_test_user(&pool).await;
 
         let mut conn = pool.acquire().await.unwrap();
@@ -2673,7 +3086,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let mut conn = pool.acquire().await.unwrap();
         let mut repo = Deployments::new(&mut conn);
@@ -2716,7 +3129,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let mut conn = pool.acquire().await.unwrap();
         let mut repo = Deployments::new(&mut conn);
@@ -2737,7 +3150,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
         let mut deploy_conn = pool.acquire().await.unwrap();
         let mut deployment_repo = Deployments::new(&mut deploy_conn);
         let mut group_conn = pool.acquire().await.unwrap();
@@ -2854,7 +3267,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let user = create_test_user(&pool).await;
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
@@ -2971,7 +3384,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let user = create_test_user(&pool).await;
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
@@ -3099,7 +3512,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let mut pool_conn = pool.acquire().await.unwrap();
         let mut repo = Deployments::new(&mut pool_conn);
@@ -3169,7 +3582,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let mut pool_conn = pool.acquire().await.unwrap();
         let mut repo = Deployments::new(&mut pool_conn);
@@ -3225,6 +3638,15 @@ mod tests {
             auth_header_prefix: None,
             reasoning_translation: None,
             created_by: user.id,
+            bedrock: None,
+            region: None,
+            body_transform: None,
+            auto_sync_interval_seconds: None,
+            alias_template: None,
+            max_concurrency: None,
+            tls: None,
+            additional_api_keys: Vec::new(),
+            api_key_rotation: None,
         };
         let endpoint = endpoints_repo.create(&endpoint_create).await.unwrap();
         let test_endpoint_id = endpoint.id;
@@ -3272,6 +3694,15 @@ mod tests {
             auth_header_prefix: None,
             reasoning_translation: None,
             created_by: user.id,
+            bedrock: None,
+            region: None,
+            body_transform: None,
+            auto_sync_interval_seconds: None,
+            alias_template: None,
+            max_concurrency: None,
+            tls: None,
+            additional_api_keys: Vec::new(),
+            api_key_rotation: None,
         };
         let endpoint = endpoints_repo.create(&endpoint_create).await.unwrap();
         let test_endpoint_id = endpoint.id;
@@ -3316,7 +3747,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let user = create_test_user(&pool).await;
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
@@ -3361,7 +3792,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let user = create_test_user(&pool).await;
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
@@ -3405,7 +3836,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let mut pool_conn = pool.acquire().await.unwrap();
         let mut repo = Deployments::new(&mut pool_conn);
@@ -3513,7 +3944,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let user = create_test_user(&pool).await;
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
@@ -3582,7 +4013,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let user = create_test_user(&pool).await;
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
@@ -3669,7 +4100,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let user = create_test_user(&pool).await;
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
@@ -3752,7 +4183,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let user = create_test_user(&pool).await;
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
@@ -3820,7 +4251,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let user = create_test_user(&pool).await;
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
@@ -3866,7 +4297,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let user = create_test_user(&pool).await;
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
@@ -3964,7 +4395,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let mut tx = pool.begin().await.unwrap();
         let mut repo = Deployments::new(tx.acquire().await.unwrap());
@@ -3984,7 +4415,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let user = create_test_user(&pool).await;
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
@@ -4063,7 +4494,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let user = create_test_user(&pool).await;
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
@@ -4142,7 +4573,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let user = create_test_user(&pool).await;
         let test_endpoint_id = get_test_endpoint_id(&pool).await;
@@ -4200,7 +4631,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let mut pool_conn = pool.acquire().await.unwrap();
         let mut repo = Deployments::new(&mut pool_conn);
@@ -4259,7 +4690,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let mut pool_conn = pool.acquire().await.unwrap();
         let mut repo = Deployments::new(&mut pool_conn);
@@ -4316,7 +4747,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let mut pool_conn = pool.acquire().await.unwrap();
         let mut repo = Deployments::new(&mut pool_conn);
@@ -4373,7 +4804,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let mut pool_conn = pool.acquire().await.unwrap();
         let mut repo = Deployments::new(&mut pool_conn);
@@ -4433,7 +4864,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let mut pool_conn = pool.acquire().await.unwrap();
         let mut repo = Deployments::new(&mut pool_conn);
@@ -4497,7 +4928,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         let mut pool_conn = pool.acquire().await.unwrap();
         let mut repo = Deployments::new(&mut pool_conn);
@@ -4545,7 +4976,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, pool).await.unwrap();
+        crate::seed_database(&sources, None, pool).await.unwrap();
 
         let endpoint_id = get_test_endpoint_id(pool).await;
         let mut tx = pool.begin().await.unwrap();
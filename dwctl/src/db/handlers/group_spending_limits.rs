@@ -0,0 +1,157 @@
+//! Database repository for per-group spending limits.
+//!
+//! The limits are read by the onwards config sync, and writes NOTIFY it
+//! (migration 150). The spend they are checked against is folded into
+//! `group_spend_checkpoints` by the request-logging batcher.
+
+use crate::db::{
+    errors::{DbError, Result},
+    models::group_spending_limits::{GroupSpendingLimitDBRequest, GroupSpendingLimitDBResponse},
+};
+use crate::types::{GroupId, UserId, abbrev_uuid};
+use sqlx::PgConnection;
+use tracing::instrument;
+
+pub struct GroupSpendingLimits<'c> {
+    db: &'c mut PgConnection,
+}
+
+impl<'c> GroupSpendingLimits<'c> {
+    pub fn new(db: &'c mut PgConnection) -> Self {
+        Self { db }
+    }
+
+    /// Get a group's limit and current window spend, if a limit is set
+    #[instrument(skip(self), fields(group_id = %abbrev_uuid(&group_id)), err)]
+    pub async fn get(&mut self, group_id: GroupId) -> Result<Option<GroupSpendingLimitDBResponse>> {
+        let limit = sqlx::query_as!(
+            GroupSpendingLimitDBResponse,
+            r#"
+            SELECT l.spending_limit,
+                   l.spending_limit_interval,
+                   CASE WHEN api_key_cap_window_current(ck.window_started_at, l.spending_limit_interval, 'UTC')
+                        THEN ck.window_spend ELSE 0 END AS "spent!",
+                   api_key_cap_window_resets_at(l.spending_limit_interval, 'UTC') AS resets_at,
+                   l.created_at,
+                   l.updated_at
+            FROM group_spending_limits l
+            LEFT JOIN group_spend_checkpoints ck ON ck.group_id = l.group_id
+            WHERE l.group_id = $1
+            "#,
+            group_id,
+        )
+        .fetch_optional(&mut *self.db)
+        .await?;
+        Ok(limit)
+    }
+
+    /// Set a group's limit, replacing any existing one. A new limit, or a
+    /// change of interval, starts a fresh window; changing only the amount
+    /// keeps the spend already counted. Run in a transaction.
+    #[instrument(skip(self, request), fields(group_id = %abbrev_uuid(&group_id)), err)]
+    pub async fn set(&mut self, group_id: GroupId, request: &GroupSpendingLimitDBRequest) -> Result<GroupSpendingLimitDBResponse> {
+        let previous_interval = sqlx::query_scalar!(
+            "SELECT spending_limit_interval FROM group_spending_limits WHERE group_id = $1 FOR UPDATE",
+            group_id,
+        )
+        .fetch_optional(&mut *self.db)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO group_spending_limits (group_id, spending_limit, spending_limit_interval)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (group_id) DO UPDATE SET
+                spending_limit = EXCLUDED.spending_limit,
+                spending_limit_interval = EXCLUDED.spending_limit_interval,
+                updated_at = NOW()
+            "#,
+            group_id,
+            request.spending_limit,
+            request.spending_limit_interval,
+        )
+        .execute(&mut *self.db)
+        .await?;
+
+        if previous_interval.as_ref() != Some(&request.spending_limit_interval) {
+            self.reset_window(group_id).await?;
+        }
+
+        self.get(group_id).await?.ok_or(DbError::NotFound)
+    }
+
+    /// Remove a group's limit and its spend window. Returns whether one was set.
+    #[instrument(skip(self), fields(group_id = %abbrev_uuid(&group_id)), err)]
+    pub async fn delete(&mut self, group_id: GroupId) -> Result<bool> {
+        let result = sqlx::query!("DELETE FROM group_spending_limits WHERE group_id = $1", group_id)
+            .execute(&mut *self.db)
+            .await?;
+        sqlx::query!("DELETE FROM group_spend_checkpoints WHERE group_id = $1", group_id)
+            .execute(&mut *self.db)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Zero a group's window spend and start a fresh window now
+    #[instrument(skip(self), fields(group_id = %abbrev_uuid(&group_id)), err)]
+    pub async fn reset_window(&mut self, group_id: GroupId) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO group_spend_checkpoints (group_id, window_spend, window_started_at)
+            VALUES ($1, 0, NOW())
+            ON CONFLICT (group_id) DO UPDATE SET
+                window_spend = 0,
+                window_started_at = NOW(),
+                updated_at = NOW()
+            "#,
+            group_id,
+        )
+        .execute(&mut *self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// A group of the user whose limit is exhausted for the current window,
+    /// with its name — the same predicate the onwards sync uses to withdraw
+    /// the user's keys from priced models.
+    #[instrument(skip(self), fields(user_id = %abbrev_uuid(&user_id)), err)]
+    pub async fn find_exhausted_for_user(&mut self, user_id: UserId) -> Result<Option<(String, GroupSpendingLimitDBResponse)>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT g.name,
+                   l.spending_limit,
+                   l.spending_limit_interval,
+                   ck.window_spend,
+                   api_key_cap_window_resets_at(l.spending_limit_interval, 'UTC') AS resets_at,
+                   l.created_at,
+                   l.updated_at
+            FROM user_groups ug
+            JOIN groups g ON g.id = ug.group_id
+            JOIN group_spending_limits l ON l.group_id = ug.group_id
+            JOIN group_spend_checkpoints ck ON ck.group_id = ug.group_id
+            WHERE ug.user_id = $1
+              AND api_key_cap_window_current(ck.window_started_at, l.spending_limit_interval, 'UTC')
+              AND ck.window_spend >= l.spending_limit
+            ORDER BY g.name
+            LIMIT 1
+            "#,
+            user_id,
+        )
+        .fetch_optional(&mut *self.db)
+        .await?;
+
+        Ok(row.map(|r| {
+            (
+                r.name,
+                GroupSpendingLimitDBResponse {
+                    spending_limit: r.spending_limit,
+                    spending_limit_interval: r.spending_limit_interval,
+                    spent: r.window_spend,
+                    resets_at: r.resets_at,
+                    created_at: r.created_at,
+                    updated_at: r.updated_at,
+                },
+            )
+        }))
+    }
+}
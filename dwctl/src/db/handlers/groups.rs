@@ -1,9 +1,10 @@
 //! Database repository for groups and memberships.
 
+use crate::api::models::pagination::IdCursor;
 use crate::db::{
     errors::{DbError, Result},
     handlers::repository::Repository,
-    models::groups::{GroupCreateDBRequest, GroupDBResponse, GroupUpdateDBRequest},
+    models::groups::{GroupCreateDBRequest, GroupDBResponse, GroupUpdateDBRequest, MembershipAddOutcome},
 };
 use crate::types::{DeploymentId, GroupId, Operation, UserId, abbrev_uuid};
 use chrono::{DateTime, Utc};
@@ -17,18 +18,29 @@ use uuid::Uuid;
 pub struct GroupFilter {
     pub skip: i64,
     pub limit: i64,
-    pub search: Option<String>, // Case-insensitive substring search on name and description
+    pub search: Option<String>,   // Case-insensitive substring search on name and description
+    pub cursor: Option<IdCursor>, // Keyset pagination by id; replaces name ordering and skip
 }
 
 impl GroupFilter {
     pub fn new(skip: i64, limit: i64) -> Self {
-        Self { skip, limit, search: None }
+        Self {
+            skip,
+            limit,
+            search: None,
+            cursor: None,
+        }
     }
 
     pub fn with_search(mut self, search: String) -> Self {
         self.search = Some(search);
         self
     }
+
+    pub fn with_cursor(mut self, cursor: IdCursor) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
 }
 
 // Database entity model
@@ -177,10 +189,19 @@ impl<'c> Repository for Groups<'c> {
             tracing::info!("Database layer: No search filter in GroupFilter");
         }
 
-        query.push(" ORDER BY name LIMIT ");
-        query.push_bind(filter.limit);
-        query.push(" OFFSET ");
-        query.push_bind(filter.skip);
+        if let Some(cursor) = filter.cursor {
+            if let IdCursor::After(after) = cursor {
+                query.push(" AND id > ");
+                query.push_bind(after);
+            }
+            query.push(" ORDER BY id LIMIT ");
+            query.push_bind(filter.limit);
+        } else {
+            query.push(" ORDER BY name LIMIT ");
+            query.push_bind(filter.limit);
+            query.push(" OFFSET ");
+            query.push_bind(filter.skip);
+        }
 
         let sql = query.sql();
         tracing::info!("Executing SQL: {}", sql);
@@ -257,6 +278,37 @@ impl<'c> Groups<'c> {
         }
     }
 
+    /// Add many users to a group in a single statement, so the config-change trigger
+    /// fires once for the whole batch. Returns each requested user's outcome in request
+    /// order; `user_ids` must not contain duplicates. Missing or deleted users are
+    /// reported rather than failing the batch.
+    #[instrument(skip(self, user_ids), fields(group_id = %abbrev_uuid(&group_id), count = user_ids.len()), err)]
+    pub async fn add_users_to_group(&mut self, group_id: GroupId, user_ids: &[UserId]) -> Result<Vec<(UserId, MembershipAddOutcome)>> {
+        self.ensure_group_exists(group_id).await?;
+
+        let rows = sqlx::query!(
+            r#"
+            WITH valid AS (
+                SELECT id FROM users WHERE id = ANY($2) AND is_deleted = false
+            ),
+            inserted AS (
+                INSERT INTO user_groups (user_id, group_id)
+                SELECT id, $1 FROM valid
+                ON CONFLICT DO NOTHING
+                RETURNING user_id
+            )
+            SELECT v.id, EXISTS (SELECT 1 FROM inserted i WHERE i.user_id = v.id) as "added!"
+            FROM valid v
+            "#,
+            group_id,
+            user_ids
+        )
+        .fetch_all(&mut *self.db)
+        .await?;
+
+        Ok(membership_outcomes(user_ids, rows.into_iter().map(|r| (r.id, r.added))))
+    }
+
     #[instrument(skip(self), fields(user_id = %abbrev_uuid(&user_id), group_id = %abbrev_uuid(&group_id)), err)]
     pub async fn remove_user_from_group(&mut self, user_id: UserId, group_id: GroupId) -> Result<()> {
         let result = sqlx::query!("DELETE FROM user_groups WHERE user_id = $1 AND group_id = $2", user_id, group_id)
@@ -294,6 +346,31 @@ impl<'c> Groups<'c> {
         Ok(groups.into_iter().map(GroupDBResponse::from).collect())
     }
 
+    /// Whether `user_id` and the individual `other_user_id` are both members of
+    /// some group other than Everyone.
+    #[instrument(skip(self), fields(user_id = %abbrev_uuid(&user_id), other_user_id = %abbrev_uuid(&other_user_id)), err)]
+    pub async fn shares_group_with(&mut self, user_id: UserId, other_user_id: UserId) -> Result<bool> {
+        let shared = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1
+                FROM user_groups mine
+                JOIN user_groups theirs ON theirs.group_id = mine.group_id
+                JOIN users u ON u.id = theirs.user_id
+                WHERE mine.user_id = $1
+                  AND theirs.user_id = $2
+                  AND mine.group_id != '00000000-0000-0000-0000-000000000000'
+                  AND u.user_type = 'individual'
+            ) as "shared!"
+            "#,
+            user_id,
+            other_user_id
+        )
+        .fetch_one(&mut *self.db)
+        .await?;
+        Ok(shared)
+    }
+
     #[instrument(skip(self), fields(group_id = %abbrev_uuid(&group_id)), err)]
     pub async fn get_group_users(&mut self, group_id: GroupId) -> Result<Vec<UserId>> {
         if group_id == Uuid::nil() {
@@ -333,6 +410,51 @@ impl<'c> Groups<'c> {
         }
     }
 
+    /// Grant a group access to many deployments in a single statement, so the
+    /// config-change trigger fires once for the whole batch. Returns each requested
+    /// deployment's outcome in request order; `deployment_ids` must not contain
+    /// duplicates. Missing or deleted deployments are reported rather than failing
+    /// the batch.
+    #[instrument(skip(self, deployment_ids), fields(group_id = %abbrev_uuid(&group_id), count = deployment_ids.len()), err)]
+    pub async fn add_deployments_to_group(
+        &mut self,
+        group_id: GroupId,
+        deployment_ids: &[DeploymentId],
+        granted_by: UserId,
+    ) -> Result<Vec<(DeploymentId, MembershipAddOutcome)>> {
+        self.ensure_group_exists(group_id).await?;
+
+        let rows = sqlx::query!(
+            r#"
+            WITH valid AS (
+                SELECT id FROM deployed_models WHERE id = ANY($2) AND deleted = false
+            ),
+            inserted AS (
+                INSERT INTO deployment_groups (deployment_id, group_id, granted_by)
+                SELECT id, $1, $3 FROM valid
+                ON CONFLICT DO NOTHING
+                RETURNING deployment_id
+            )
+            SELECT v.id, EXISTS (SELECT 1 FROM inserted i WHERE i.deployment_id = v.id) as "added!"
+            FROM valid v
+            "#,
+            group_id,
+            deployment_ids,
+            granted_by
+        )
+        .fetch_all(&mut *self.db)
+        .await?;
+
+        Ok(membership_outcomes(deployment_ids, rows.into_iter().map(|r| (r.id, r.added))))
+    }
+
+    async fn ensure_group_exists(&mut self, group_id: GroupId) -> Result<()> {
+        let exists = sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM groups WHERE id = $1) as "exists!""#, group_id)
+            .fetch_one(&mut *self.db)
+            .await?;
+        if exists { Ok(()) } else { Err(DbError::NotFound) }
+    }
+
     #[instrument(skip(self), fields(deployment_id = %abbrev_uuid(&deployment_id), group_id = %abbrev_uuid(&group_id)), err)]
     pub async fn remove_deployment_from_group(&mut self, deployment_id: DeploymentId, group_id: GroupId) -> Result<()> {
         let result = sqlx::query!(
@@ -574,6 +696,22 @@ impl<'c> Groups<'c> {
     }
 }
 
+/// Map requested ids to batch outcomes given `(id, added)` rows for the ids that exist
+fn membership_outcomes(requested: &[Uuid], rows: impl Iterator<Item = (Uuid, bool)>) -> Vec<(Uuid, MembershipAddOutcome)> {
+    let found: std::collections::HashMap<Uuid, bool> = rows.collect();
+    requested
+        .iter()
+        .map(|id| {
+            let outcome = match found.get(id) {
+                Some(true) => MembershipAddOutcome::Added,
+                Some(false) => MembershipAddOutcome::AlreadyMember,
+                None => MembershipAddOutcome::NotFound,
+            };
+            (*id, outcome)
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -626,7 +764,7 @@ mod tests {
 
         // Create inference endpoint for deployments
         let config = crate::test::utils::create_test_config();
-        seed_database(&config.model_sources, pool)
+        seed_database(&config.model_sources, config.system_api_key.as_deref(), pool)
             .await
             .expect("Failed to create inference endpoints");
 
@@ -1154,6 +1292,9 @@ mod tests {
             created_by: test_user_id,
             spend_limit: None,
             spend_limit_interval: None,
+            allowed_model_ids: None,
+            denied_model_ids: None,
+            max_priority: 0,
         };
         let api_key = api_key_repo.create(&api_key_create).await.expect("Failed to create API key");
 
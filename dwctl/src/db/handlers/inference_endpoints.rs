@@ -3,7 +3,8 @@
 use crate::db::errors::{DbError, Result};
 use crate::db::handlers::repository::Repository;
 use crate::db::models::inference_endpoints::{
-    InferenceEndpointCreateDBRequest, InferenceEndpointDBResponse, InferenceEndpointUpdateDBRequest,
+    ApiKeyRotation, BedrockEndpointConfig, EndpointProtocol, EndpointTlsConfig, InferenceEndpointCreateDBRequest,
+    InferenceEndpointDBResponse, InferenceEndpointUpdateDBRequest,
 };
 use crate::types::{InferenceEndpointId, UserId, abbrev_uuid};
 use chrono::{DateTime, Utc};
@@ -39,12 +40,39 @@ struct InferenceEndpoint {
     pub created_by: UserId,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub protocol: String,
+    pub aws_region: Option<String>,
+    pub aws_access_key_id: Option<String>,
+    pub aws_secret_access_key_encrypted: Option<Vec<u8>>,
+    pub region: Option<String>,
+    pub body_transform: Option<serde_json::Value>,
+    pub auto_sync_interval_seconds: Option<i32>,
+    pub alias_template: Option<String>,
+    pub max_concurrency: Option<i32>,
+    pub tls_ca_bundle: Option<String>,
+    pub tls_client_cert: Option<String>,
+    pub tls_client_key_encrypted: Option<Vec<u8>>,
+    pub additional_api_keys: Vec<String>,
+    pub api_key_rotation: String,
 }
 
 impl TryFrom<InferenceEndpoint> for InferenceEndpointDBResponse {
     type Error = anyhow::Error;
 
     fn try_from(src: InferenceEndpoint) -> std::result::Result<Self, Self::Error> {
+        let bedrock = match (src.aws_region, src.aws_access_key_id, src.aws_secret_access_key_encrypted) {
+            (Some(region), Some(access_key_id), Some(secret_access_key_encrypted)) => Some(BedrockEndpointConfig {
+                region,
+                access_key_id,
+                secret_access_key_encrypted,
+            }),
+            _ => None,
+        };
+        let tls = (src.tls_ca_bundle.is_some() || src.tls_client_cert.is_some()).then(|| EndpointTlsConfig {
+            ca_bundle: src.tls_ca_bundle,
+            client_cert: src.tls_client_cert,
+            client_key_encrypted: src.tls_client_key_encrypted,
+        });
         Ok(Self {
             id: src.id,
             name: src.name,
@@ -55,6 +83,16 @@ impl TryFrom<InferenceEndpoint> for InferenceEndpointDBResponse {
             auth_header_name: src.auth_header_name,
             auth_header_prefix: src.auth_header_prefix,
             reasoning_translation: src.reasoning_translation.map(serde_json::from_value).transpose()?,
+            protocol: src.protocol.parse()?,
+            bedrock,
+            region: src.region,
+            body_transform: src.body_transform.map(serde_json::from_value).transpose()?,
+            auto_sync_interval_seconds: src.auto_sync_interval_seconds,
+            alias_template: src.alias_template,
+            max_concurrency: src.max_concurrency,
+            tls,
+            additional_api_keys: src.additional_api_keys,
+            api_key_rotation: src.api_key_rotation.parse()?,
             created_by: src.created_by,
             created_at: src.created_at,
             updated_at: src.updated_at,
@@ -82,12 +120,33 @@ impl<'c> Repository for InferenceEndpoints<'c> {
             .map(serde_json::to_value)
             .transpose()
             .map_err(anyhow::Error::from)?;
+        let body_transform = request
+            .body_transform
+            .as_ref()
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(anyhow::Error::from)?;
+        let bedrock = request.bedrock.as_ref();
+        let tls = request.tls.as_ref();
+        let protocol = if bedrock.is_some() {
+            EndpointProtocol::Bedrock
+        } else {
+            EndpointProtocol::OpenAi
+        };
         // created_at and updated_at use database DEFAULT NOW() for consistency
         let endpoint = sqlx::query_as!(
             InferenceEndpoint,
             r#"
-            INSERT INTO inference_endpoints (name, description, url, api_key, model_filter, auth_header_name, auth_header_prefix, created_by, reasoning_translation)
-            VALUES ($1, $2, $3, $4, $5, COALESCE($6, 'Authorization'), COALESCE($7, 'Bearer '), $8, $9)
+            INSERT INTO inference_endpoints (
+                name, description, url, api_key, model_filter, auth_header_name, auth_header_prefix, created_by, reasoning_translation,
+                protocol, aws_region, aws_access_key_id, aws_secret_access_key_encrypted, region, body_transform, auto_sync_interval_seconds,
+                alias_template, max_concurrency, tls_ca_bundle, tls_client_cert, tls_client_key_encrypted, additional_api_keys,
+                api_key_rotation
+            )
+            VALUES (
+                $1, $2, $3, $4, $5, COALESCE($6, 'Authorization'), COALESCE($7, 'Bearer '), $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18,
+                $19, $20, $21, $22, COALESCE($23, 'round_robin')
+            )
             RETURNING *
             "#,
             request.name,
@@ -98,7 +157,21 @@ impl<'c> Repository for InferenceEndpoints<'c> {
             request.auth_header_name,
             request.auth_header_prefix,
             request.created_by,
-            reasoning_translation
+            reasoning_translation,
+            protocol.as_str(),
+            bedrock.map(|b| b.region.as_str()),
+            bedrock.map(|b| b.access_key_id.as_str()),
+            bedrock.map(|b| b.secret_access_key_encrypted.as_slice()),
+            request.region,
+            body_transform,
+            request.auto_sync_interval_seconds,
+            request.alias_template,
+            request.max_concurrency,
+            tls.and_then(|t| t.ca_bundle.as_deref()),
+            tls.and_then(|t| t.client_cert.as_deref()),
+            tls.and_then(|t| t.client_key_encrypted.as_deref()),
+            &request.additional_api_keys,
+            request.api_key_rotation.map(|r| r.as_str())
         )
         .fetch_one(&mut *self.db)
         .await?;
@@ -143,6 +216,20 @@ impl<'c> Repository for InferenceEndpoints<'c> {
                 created_by: row.created_by,
                 created_at: row.created_at,
                 updated_at: row.updated_at,
+                protocol: row.protocol,
+                aws_region: row.aws_region,
+                aws_access_key_id: row.aws_access_key_id,
+                aws_secret_access_key_encrypted: row.aws_secret_access_key_encrypted,
+                region: row.region,
+                body_transform: row.body_transform,
+                auto_sync_interval_seconds: row.auto_sync_interval_seconds,
+                alias_template: row.alias_template,
+                max_concurrency: row.max_concurrency,
+                tls_ca_bundle: row.tls_ca_bundle,
+                tls_client_cert: row.tls_client_cert,
+                tls_client_key_encrypted: row.tls_client_key_encrypted,
+                additional_api_keys: row.additional_api_keys,
+                api_key_rotation: row.api_key_rotation,
             })
             .collect();
 
@@ -172,6 +259,14 @@ impl<'c> Repository for InferenceEndpoints<'c> {
             .map(serde_json::to_value)
             .transpose()
             .map_err(anyhow::Error::from)?;
+        let body_transform = request
+            .body_transform
+            .as_ref()
+            .and_then(Option::as_ref)
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(anyhow::Error::from)?;
+        let tls = request.tls.as_ref().and_then(Option::as_ref);
         // Atomic update with conditional field updates
         let endpoint = sqlx::query_as!(
             InferenceEndpoint,
@@ -197,6 +292,43 @@ impl<'c> Repository for InferenceEndpoints<'c> {
                     WHEN $9 THEN $10
                     ELSE reasoning_translation
                 END,
+                aws_region = COALESCE($11, aws_region),
+                aws_access_key_id = COALESCE($12, aws_access_key_id),
+                aws_secret_access_key_encrypted = COALESCE($13, aws_secret_access_key_encrypted),
+                region = CASE
+                    WHEN $14 THEN $15
+                    ELSE region
+                END,
+                body_transform = CASE
+                    WHEN $16 THEN $17
+                    ELSE body_transform
+                END,
+                auto_sync_interval_seconds = CASE
+                    WHEN $18 THEN $19
+                    ELSE auto_sync_interval_seconds
+                END,
+                alias_template = CASE
+                    WHEN $20 THEN $21
+                    ELSE alias_template
+                END,
+                max_concurrency = CASE
+                    WHEN $22 THEN $23
+                    ELSE max_concurrency
+                END,
+                tls_ca_bundle = CASE
+                    WHEN $24 THEN $25
+                    ELSE tls_ca_bundle
+                END,
+                tls_client_cert = CASE
+                    WHEN $24 THEN $26
+                    ELSE tls_client_cert
+                END,
+                tls_client_key_encrypted = CASE
+                    WHEN $24 THEN $27
+                    ELSE tls_client_key_encrypted
+                END,
+                additional_api_keys = COALESCE($28, additional_api_keys),
+                api_key_rotation = COALESCE($29, api_key_rotation),
                 updated_at = NOW()
             WHERE id = $1
             RETURNING *
@@ -210,7 +342,26 @@ impl<'c> Repository for InferenceEndpoints<'c> {
             request.auth_header_name,
             request.auth_header_prefix,
             request.reasoning_translation.is_some(),
-            reasoning_translation
+            reasoning_translation,
+            request.bedrock.as_ref().map(|b| b.region.as_str()),
+            request.bedrock.as_ref().map(|b| b.access_key_id.as_str()),
+            request.bedrock.as_ref().map(|b| b.secret_access_key_encrypted.as_slice()),
+            request.region.is_some(),
+            request.region.as_ref().and_then(|opt| opt.as_deref()),
+            request.body_transform.is_some(),
+            body_transform,
+            request.auto_sync_interval_seconds.is_some(),
+            request.auto_sync_interval_seconds.flatten(),
+            request.alias_template.is_some(),
+            request.alias_template.as_ref().and_then(|opt| opt.as_deref()),
+            request.max_concurrency.is_some(),
+            request.max_concurrency.flatten(),
+            request.tls.is_some(),
+            tls.and_then(|t| t.ca_bundle.as_deref()),
+            tls.and_then(|t| t.client_cert.as_deref()),
+            tls.and_then(|t| t.client_key_encrypted.as_deref()),
+            request.additional_api_keys.as_deref(),
+            request.api_key_rotation.map(|r| r.as_str())
         )
         .fetch_optional(&mut *self.db)
         .await?
@@ -239,6 +390,29 @@ impl<'c> InferenceEndpoints<'c> {
         Self { db }
     }
 
+    /// Claim the endpoints whose auto-sync interval has elapsed since their last
+    /// automatic sync (or that have never been auto-synced), recording now as
+    /// their last sync time. Returns the claimed endpoint IDs.
+    #[instrument(skip(self), err)]
+    pub async fn claim_due_auto_syncs(&mut self) -> Result<Vec<InferenceEndpointId>> {
+        let ids = sqlx::query_scalar!(
+            r#"
+            INSERT INTO inference_endpoint_auto_syncs (endpoint_id, last_synced_at)
+            SELECT e.id, NOW()
+            FROM inference_endpoints e
+            LEFT JOIN inference_endpoint_auto_syncs s ON s.endpoint_id = e.id
+            WHERE e.auto_sync_interval_seconds IS NOT NULL
+              AND (s.last_synced_at IS NULL OR s.last_synced_at + make_interval(secs => e.auto_sync_interval_seconds) <= NOW())
+            ON CONFLICT (endpoint_id) DO UPDATE SET last_synced_at = EXCLUDED.last_synced_at
+            RETURNING endpoint_id
+            "#
+        )
+        .fetch_all(&mut *self.db)
+        .await?;
+
+        Ok(ids)
+    }
+
     /// Returns the ID of the default inference endpoint
     pub fn default_endpoint_id() -> InferenceEndpointId {
         // Use a deterministic UUID for tests
@@ -287,6 +461,15 @@ mod tests {
             auth_header_name: None,
             auth_header_prefix: None,
             reasoning_translation: None,
+            bedrock: None,
+            region: None,
+            body_transform: None,
+            auto_sync_interval_seconds: None,
+            alias_template: None,
+            max_concurrency: None,
+            tls: None,
+            additional_api_keys: Vec::new(),
+            api_key_rotation: None,
             created_by,
         }
     }
@@ -354,6 +537,15 @@ mod tests {
                     auth_header_name: None,
                     auth_header_prefix: None,
                     reasoning_translation: Some(None),
+                    bedrock: None,
+                    region: None,
+                    body_transform: None,
+                    auto_sync_interval_seconds: None,
+                    alias_template: None,
+                    max_concurrency: None,
+                    tls: None,
+                    additional_api_keys: None,
+                    api_key_rotation: None,
                 },
             )
             .await
@@ -487,6 +679,66 @@ mod tests {
         assert!(!deleted);
     }
 
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_claim_due_auto_syncs(pool: PgPool) {
+        let user = create_test_user(&pool).await;
+        let mut conn = pool.acquire().await.unwrap();
+        let mut repo = InferenceEndpoints::new(&mut conn);
+
+        let manual = repo.create(&create_test_endpoint_request(user.id, "manual")).await.unwrap();
+        let mut request = create_test_endpoint_request(user.id, "auto");
+        request.auto_sync_interval_seconds = Some(300);
+        let auto = repo.create(&request).await.unwrap();
+        assert_eq!(auto.auto_sync_interval_seconds, Some(300));
+
+        // Never synced: due straight away. Endpoints without an interval are never claimed.
+        assert_eq!(repo.claim_due_auto_syncs().await.unwrap(), vec![auto.id]);
+        // Claiming records the sync, so it is not due again until the interval passes
+        assert!(repo.claim_due_auto_syncs().await.unwrap().is_empty());
+
+        sqlx::query!(
+            "UPDATE inference_endpoint_auto_syncs SET last_synced_at = NOW() - INTERVAL '301 seconds' WHERE endpoint_id = $1",
+            auto.id
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert_eq!(repo.claim_due_auto_syncs().await.unwrap(), vec![auto.id]);
+
+        // Disabling auto-sync stops the endpoint being claimed
+        let mut update = InferenceEndpointUpdateDBRequest {
+            name: None,
+            description: None,
+            url: None,
+            api_key: None,
+            model_filter: None,
+            auth_header_name: None,
+            auth_header_prefix: None,
+            reasoning_translation: None,
+            bedrock: None,
+            region: None,
+            body_transform: None,
+            auto_sync_interval_seconds: Some(None),
+            alias_template: None,
+            max_concurrency: None,
+            tls: None,
+            additional_api_keys: None,
+            api_key_rotation: None,
+        };
+        let updated = repo.update(auto.id, &update).await.unwrap();
+        assert_eq!(updated.auto_sync_interval_seconds, None);
+        sqlx::query!("UPDATE inference_endpoint_auto_syncs SET last_synced_at = NOW() - INTERVAL '1 day'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(repo.claim_due_auto_syncs().await.unwrap().is_empty());
+
+        update.auto_sync_interval_seconds = Some(Some(60));
+        repo.update(manual.id, &update).await.unwrap();
+        assert_eq!(repo.claim_due_auto_syncs().await.unwrap(), vec![manual.id]);
+    }
+
     #[sqlx::test]
     #[test_log::test]
     async fn test_apply_update_all_fields(pool: PgPool) {
@@ -508,6 +760,15 @@ mod tests {
             auth_header_name: None,
             auth_header_prefix: None,
             reasoning_translation: None,
+            bedrock: None,
+            region: None,
+            body_transform: None,
+            auto_sync_interval_seconds: None,
+            alias_template: None,
+            max_concurrency: None,
+            tls: None,
+            additional_api_keys: None,
+            api_key_rotation: None,
         };
 
         // Apply update
@@ -553,6 +814,15 @@ mod tests {
             auth_header_name: None,
             auth_header_prefix: None,
             reasoning_translation: None,
+            bedrock: None,
+            region: None,
+            body_transform: None,
+            auto_sync_interval_seconds: None,
+            alias_template: None,
+            max_concurrency: None,
+            tls: None,
+            additional_api_keys: None,
+            api_key_rotation: None,
         };
 
         // Apply update
@@ -601,6 +871,33 @@ mod tests {
         if let Some(reasoning_translation) = update_request.reasoning_translation {
             original.reasoning_translation = reasoning_translation;
         }
+        if let Some(bedrock) = update_request.bedrock {
+            original.bedrock = Some(bedrock);
+        }
+        if let Some(region) = update_request.region {
+            original.region = region;
+        }
+        if let Some(body_transform) = update_request.body_transform {
+            original.body_transform = body_transform;
+        }
+        if let Some(auto_sync_interval_seconds) = update_request.auto_sync_interval_seconds {
+            original.auto_sync_interval_seconds = auto_sync_interval_seconds;
+        }
+        if let Some(alias_template) = update_request.alias_template {
+            original.alias_template = alias_template;
+        }
+        if let Some(max_concurrency) = update_request.max_concurrency {
+            original.max_concurrency = max_concurrency;
+        }
+        if let Some(tls) = update_request.tls {
+            original.tls = tls;
+        }
+        if let Some(additional_api_keys) = update_request.additional_api_keys {
+            original.additional_api_keys = additional_api_keys;
+        }
+        if let Some(api_key_rotation) = update_request.api_key_rotation {
+            original.api_key_rotation = api_key_rotation;
+        }
 
         // Always update the timestamp like COALESCE would with NOW()
         original.updated_at = chrono::Utc::now();
@@ -624,6 +921,16 @@ mod tests {
             created_by: uuid::Uuid::new_v4(),
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
+            protocol: EndpointProtocol::OpenAi,
+            bedrock: None,
+            region: None,
+            body_transform: None,
+            auto_sync_interval_seconds: None,
+            alias_template: None,
+            max_concurrency: None,
+            tls: None,
+            additional_api_keys: Vec::new(),
+            api_key_rotation: ApiKeyRotation::RoundRobin,
         };
 
         // Test ApplyUpdate trait directly
@@ -636,6 +943,15 @@ mod tests {
             auth_header_name: None,
             auth_header_prefix: None,
             reasoning_translation: None,
+            bedrock: None,
+            region: None,
+            body_transform: None,
+            auto_sync_interval_seconds: None,
+            alias_template: None,
+            max_concurrency: None,
+            tls: None,
+            additional_api_keys: None,
+            api_key_rotation: None,
         };
 
         let updated_response = mock_coalesce_update(update_request, original_response.clone());
@@ -675,6 +991,16 @@ mod tests {
             created_by: uuid::Uuid::new_v4(),
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now() - chrono::Duration::seconds(1),
+            protocol: EndpointProtocol::OpenAi,
+            bedrock: None,
+            region: None,
+            body_transform: None,
+            auto_sync_interval_seconds: None,
+            alias_template: None,
+            max_concurrency: None,
+            tls: None,
+            additional_api_keys: Vec::new(),
+            api_key_rotation: ApiKeyRotation::RoundRobin,
         };
 
         // Test ApplyUpdate with empty update (all None fields)
@@ -687,6 +1013,15 @@ mod tests {
             auth_header_name: None,
             auth_header_prefix: None,
             reasoning_translation: None,
+            bedrock: None,
+            region: None,
+            body_transform: None,
+            auto_sync_interval_seconds: None,
+            alias_template: None,
+            max_concurrency: None,
+            tls: None,
+            additional_api_keys: None,
+            api_key_rotation: None,
         };
 
         let updated_response = mock_coalesce_update(update_request, original_response.clone());
@@ -721,6 +1056,15 @@ mod tests {
             auth_header_name: None,
             auth_header_prefix: None,
             reasoning_translation: None,
+            bedrock: None,
+            region: None,
+            body_transform: None,
+            auto_sync_interval_seconds: None,
+            alias_template: None,
+            max_concurrency: None,
+            tls: None,
+            additional_api_keys: None,
+            api_key_rotation: None,
         };
 
         let result = repo.update(fake_id, &update_request).await;
@@ -20,6 +20,7 @@
 //! - [`InferenceEndpoints`]: Backend inference endpoint management
 //! - [`Credits`]: Credit balance tracking and transactions
 //! - [`PasswordResetTokens`]: Password reset token lifecycle
+//! - [`Sessions`]: Native login sessions and their revocation
 //! - [`analytics`]: Request logging and analytics queries
 //! - [`api_keys`]: API key management (not re-exported)
 //!
@@ -57,37 +58,51 @@
 //! - `list()`: List records with pagination
 //! - `delete()`: Delete a record by ID
 
+pub mod access_requests;
+pub mod access_templates;
 pub mod analytics;
 pub mod api_keys;
+pub mod archived_batches;
 pub mod batch_templates;
 pub mod cache_tariffs;
 pub mod capacity_reservations;
 pub mod connections;
 pub mod credits;
+pub mod deployment_shadows;
 pub mod deployments;
+pub mod group_spending_limits;
 pub mod groups;
 pub mod inference_endpoints;
 pub mod organizations;
 pub mod password_reset_tokens;
 pub mod provider_display_configs;
+pub mod rate_limits;
 pub mod repository;
+pub mod sessions;
 pub mod tariffs;
 pub mod tool_sources;
 pub mod users;
 pub mod webhooks;
 
+pub use access_requests::AccessRequests;
+pub use access_templates::AccessTemplates;
+pub use archived_batches::ArchivedBatches;
 pub use batch_templates::BatchTemplates;
 pub use cache_tariffs::{ActiveTariff, CacheTariffOverrides, CacheTariffs};
 pub use capacity_reservations::BatchCapacityReservations;
 pub use connections::{Connections, SyncEntries, SyncOperations};
 pub use credits::Credits;
+pub use deployment_shadows::DeploymentShadows;
 pub use deployments::Deployments;
+pub use group_spending_limits::GroupSpendingLimits;
 pub use groups::Groups;
 pub use inference_endpoints::InferenceEndpoints;
 pub use organizations::Organizations;
 pub use password_reset_tokens::PasswordResetTokens;
 pub use provider_display_configs::ProviderDisplayConfigs;
+pub use rate_limits::RateLimits;
 pub use repository::Repository;
+pub use sessions::Sessions;
 pub use tariffs::Tariffs;
 pub use tool_sources::ToolSources;
 pub use users::Users;
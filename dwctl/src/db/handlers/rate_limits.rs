@@ -0,0 +1,153 @@
+//! Database repository for user rate-limit overrides and group rate-limit defaults.
+//!
+//! Both tables are read by the onwards config sync, and writes NOTIFY it
+//! (migration 147), so changes reach the proxy on the next reload.
+
+use crate::db::{
+    errors::Result,
+    models::rate_limits::{OwnerRateLimitsDBResponse, RateLimitsDBRequest, RateLimitsDBResponse},
+};
+use crate::types::{GroupId, UserId, abbrev_uuid};
+use sqlx::PgConnection;
+use tracing::instrument;
+
+pub struct RateLimits<'c> {
+    db: &'c mut PgConnection,
+}
+
+impl<'c> RateLimits<'c> {
+    pub fn new(db: &'c mut PgConnection) -> Self {
+        Self { db }
+    }
+
+    /// Get a user's override, if one is set
+    #[instrument(skip(self), fields(user_id = %abbrev_uuid(&user_id)), err)]
+    pub async fn get_for_user(&mut self, user_id: UserId) -> Result<Option<RateLimitsDBResponse>> {
+        let limits = sqlx::query_as!(
+            RateLimitsDBResponse,
+            r#"SELECT requests_per_second, burst_size, concurrency_limit, created_at, updated_at
+               FROM user_rate_limits WHERE user_id = $1"#,
+            user_id,
+        )
+        .fetch_optional(&mut *self.db)
+        .await?;
+        Ok(limits)
+    }
+
+    /// Set a user's override, replacing any existing one
+    #[instrument(skip(self, request), fields(user_id = %abbrev_uuid(&user_id)), err)]
+    pub async fn set_for_user(&mut self, user_id: UserId, request: &RateLimitsDBRequest) -> Result<RateLimitsDBResponse> {
+        let limits = sqlx::query_as!(
+            RateLimitsDBResponse,
+            r#"INSERT INTO user_rate_limits (user_id, requests_per_second, burst_size, concurrency_limit)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT (user_id) DO UPDATE SET
+                   requests_per_second = EXCLUDED.requests_per_second,
+                   burst_size = EXCLUDED.burst_size,
+                   concurrency_limit = EXCLUDED.concurrency_limit,
+                   updated_at = NOW()
+               RETURNING requests_per_second, burst_size, concurrency_limit, created_at, updated_at"#,
+            user_id,
+            request.requests_per_second,
+            request.burst_size,
+            request.concurrency_limit,
+        )
+        .fetch_one(&mut *self.db)
+        .await?;
+        Ok(limits)
+    }
+
+    /// Remove a user's override. Returns whether one was set.
+    #[instrument(skip(self), fields(user_id = %abbrev_uuid(&user_id)), err)]
+    pub async fn delete_for_user(&mut self, user_id: UserId) -> Result<bool> {
+        let result = sqlx::query!("DELETE FROM user_rate_limits WHERE user_id = $1", user_id)
+            .execute(&mut *self.db)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get the limits an admin set on a user and their groups, the way the
+    /// config sync reads them: defaults on the Everyone group apply to every
+    /// user. `None` if the user doesn't exist.
+    #[instrument(skip(self), fields(user_id = %abbrev_uuid(&user_id)), err)]
+    pub async fn get_for_owner(&mut self, user_id: UserId) -> Result<Option<OwnerRateLimitsDBResponse>> {
+        let limits = sqlx::query_as!(
+            OwnerRateLimitsDBResponse,
+            r#"
+            SELECT
+                u.verified,
+                ul.requests_per_second as "user_requests_per_second?",
+                ul.burst_size as "user_burst_size?",
+                ul.concurrency_limit as "user_concurrency_limit?",
+                gl.requests_per_second as group_requests_per_second,
+                gl.burst_size as group_burst_size,
+                gl.concurrency_limit as group_concurrency_limit
+            FROM users u
+            LEFT JOIN user_rate_limits ul ON ul.user_id = u.id
+            CROSS JOIN LATERAL (
+                SELECT
+                    MAX(g.requests_per_second) as requests_per_second,
+                    MAX(g.burst_size) as burst_size,
+                    MAX(g.concurrency_limit) as concurrency_limit
+                FROM group_rate_limits g
+                WHERE g.group_id = '00000000-0000-0000-0000-000000000000'
+                   OR EXISTS (
+                       SELECT 1 FROM user_groups ug
+                       WHERE ug.group_id = g.group_id AND ug.user_id = u.id
+                   )
+            ) gl
+            WHERE u.id = $1
+            "#,
+            user_id,
+        )
+        .fetch_optional(&mut *self.db)
+        .await?;
+        Ok(limits)
+    }
+
+    /// Get a group's default, if one is set
+    #[instrument(skip(self), fields(group_id = %abbrev_uuid(&group_id)), err)]
+    pub async fn get_for_group(&mut self, group_id: GroupId) -> Result<Option<RateLimitsDBResponse>> {
+        let limits = sqlx::query_as!(
+            RateLimitsDBResponse,
+            r#"SELECT requests_per_second, burst_size, concurrency_limit, created_at, updated_at
+               FROM group_rate_limits WHERE group_id = $1"#,
+            group_id,
+        )
+        .fetch_optional(&mut *self.db)
+        .await?;
+        Ok(limits)
+    }
+
+    /// Set a group's default, replacing any existing one
+    #[instrument(skip(self, request), fields(group_id = %abbrev_uuid(&group_id)), err)]
+    pub async fn set_for_group(&mut self, group_id: GroupId, request: &RateLimitsDBRequest) -> Result<RateLimitsDBResponse> {
+        let limits = sqlx::query_as!(
+            RateLimitsDBResponse,
+            r#"INSERT INTO group_rate_limits (group_id, requests_per_second, burst_size, concurrency_limit)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT (group_id) DO UPDATE SET
+                   requests_per_second = EXCLUDED.requests_per_second,
+                   burst_size = EXCLUDED.burst_size,
+                   concurrency_limit = EXCLUDED.concurrency_limit,
+                   updated_at = NOW()
+               RETURNING requests_per_second, burst_size, concurrency_limit, created_at, updated_at"#,
+            group_id,
+            request.requests_per_second,
+            request.burst_size,
+            request.concurrency_limit,
+        )
+        .fetch_one(&mut *self.db)
+        .await?;
+        Ok(limits)
+    }
+
+    /// Remove a group's default. Returns whether one was set.
+    #[instrument(skip(self), fields(group_id = %abbrev_uuid(&group_id)), err)]
+    pub async fn delete_for_group(&mut self, group_id: GroupId) -> Result<bool> {
+        let result = sqlx::query!("DELETE FROM group_rate_limits WHERE group_id = $1", group_id)
+            .execute(&mut *self.db)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
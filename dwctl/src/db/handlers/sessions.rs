@@ -0,0 +1,112 @@
+//! Database repository for native login sessions.
+//!
+//! A session is active until it expires or is revoked. Cookie authentication
+//! checks [`Sessions::is_active`] on every request, so revoking a session
+//! logs it out immediately.
+
+use crate::db::{errors::Result, models::sessions::SessionDBResponse};
+use crate::types::{UserId, abbrev_uuid};
+use chrono::{DateTime, Utc};
+use sqlx::PgConnection;
+use tracing::instrument;
+use uuid::Uuid;
+
+pub struct Sessions<'c> {
+    db: &'c mut PgConnection,
+}
+
+impl<'c> Sessions<'c> {
+    pub fn new(db: &'c mut PgConnection) -> Self {
+        Self { db }
+    }
+
+    /// Record a new session for a user, clearing out their sessions that have already ended
+    #[instrument(skip(self, user_agent), fields(user_id = %abbrev_uuid(&user_id)), err)]
+    pub async fn create(&mut self, user_id: UserId, user_agent: Option<&str>, expires_at: DateTime<Utc>) -> Result<SessionDBResponse> {
+        sqlx::query!(
+            "DELETE FROM user_sessions WHERE user_id = $1 AND (expires_at <= NOW() OR revoked_at IS NOT NULL)",
+            user_id
+        )
+        .execute(&mut *self.db)
+        .await?;
+
+        let session = sqlx::query_as!(
+            SessionDBResponse,
+            r#"
+            INSERT INTO user_sessions (user_id, user_agent, expires_at)
+            VALUES ($1, $2, $3)
+            RETURNING id, user_id, user_agent, created_at, expires_at, revoked_at
+            "#,
+            user_id,
+            user_agent,
+            expires_at,
+        )
+        .fetch_one(&mut *self.db)
+        .await?;
+        Ok(session)
+    }
+
+    /// Whether a session exists for the user and hasn't expired or been revoked
+    #[instrument(skip(self), fields(session_id = %abbrev_uuid(&id)), err)]
+    pub async fn is_active(&mut self, id: Uuid, user_id: UserId) -> Result<bool> {
+        let active = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM user_sessions
+                WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL AND expires_at > NOW()
+            ) AS "active!"
+            "#,
+            id,
+            user_id,
+        )
+        .fetch_one(&mut *self.db)
+        .await?;
+        Ok(active)
+    }
+
+    /// List a user's active sessions, newest first
+    #[instrument(skip(self), fields(user_id = %abbrev_uuid(&user_id)), err)]
+    pub async fn list_active(&mut self, user_id: UserId) -> Result<Vec<SessionDBResponse>> {
+        let sessions = sqlx::query_as!(
+            SessionDBResponse,
+            r#"
+            SELECT id, user_id, user_agent, created_at, expires_at, revoked_at
+            FROM user_sessions
+            WHERE user_id = $1 AND revoked_at IS NULL AND expires_at > NOW()
+            ORDER BY created_at DESC
+            "#,
+            user_id,
+        )
+        .fetch_all(&mut *self.db)
+        .await?;
+        Ok(sessions)
+    }
+
+    /// Revoke one of a user's active sessions. Returns false if there was no such session.
+    #[instrument(skip(self), fields(user_id = %abbrev_uuid(&user_id), session_id = %abbrev_uuid(&id)), err)]
+    pub async fn revoke(&mut self, user_id: UserId, id: Uuid) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE user_sessions SET revoked_at = NOW()
+            WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL AND expires_at > NOW()
+            "#,
+            id,
+            user_id,
+        )
+        .execute(&mut *self.db)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Revoke all of a user's active sessions, returning how many were revoked
+    #[instrument(skip(self), fields(user_id = %abbrev_uuid(&user_id)), err)]
+    pub async fn revoke_all(&mut self, user_id: UserId) -> Result<u64> {
+        let result = sqlx::query!(
+            "UPDATE user_sessions SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL AND expires_at > NOW()",
+            user_id,
+        )
+        .execute(&mut *self.db)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -10,6 +10,7 @@ use crate::{
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use sqlx::PgConnection;
+use std::collections::HashMap;
 use tracing::instrument;
 use uuid::Uuid;
 
@@ -118,10 +119,50 @@ impl<'c> Tariffs<'c> {
         Ok(tariffs)
     }
 
+    /// Get the current pricing a key of the given purpose pays for each of the given model aliases
+    ///
+    /// Resolves like usage billing for a request without a completion window: the generic
+    /// tariff for the purpose, then the generic realtime tariff, then the model's default
+    /// tariff. Aliases with none of these are omitted.
+    #[instrument(skip(self, aliases), fields(count = aliases.len()), err)]
+    pub async fn get_current_pricing_by_alias(
+        &mut self,
+        aliases: &[String],
+        api_key_purpose: &str,
+    ) -> Result<HashMap<String, (Decimal, Decimal)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT DISTINCT ON (dm.alias)
+                dm.alias, mt.input_price_per_token, mt.output_price_per_token
+            FROM deployed_models dm
+            JOIN model_tariffs mt ON mt.deployed_model_id = dm.id
+            WHERE dm.alias = ANY($1)
+              AND dm.deleted = FALSE
+              AND mt.valid_until IS NULL
+              AND mt.valid_from <= NOW()
+              AND mt.completion_window IS NULL
+              AND (mt.api_key_purpose = $2 OR mt.api_key_purpose = 'realtime' OR mt.api_key_purpose IS NULL)
+            ORDER BY dm.alias,
+                CASE WHEN mt.api_key_purpose = $2 THEN 0 WHEN mt.api_key_purpose = 'realtime' THEN 1 ELSE 2 END,
+                mt.valid_from DESC
+            "#,
+            aliases,
+            api_key_purpose
+        )
+        .fetch_all(&mut *self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| (r.alias, (r.input_price_per_token, r.output_price_per_token)))
+            .collect())
+    }
+
     /// Get pricing with fallback support
     ///
     /// Tries to get pricing for the preferred API key purpose, falling back to the
-    /// fallback purpose if the preferred one is not found.
+    /// fallback purpose if the preferred one is not found, and then to the model's
+    /// default tariff (one without a purpose).
     ///
     /// # Arguments
     /// * `deployed_model_id` - The model to get pricing for
@@ -131,7 +172,7 @@ impl<'c> Tariffs<'c> {
     ///
     /// # Returns
     /// * `Ok(Some((input_price, output_price)))` - Found pricing
-    /// * `Ok(None)` - Neither preferred nor fallback tariff found
+    /// * `Ok(None)` - No preferred, fallback or default tariff found
     #[instrument(skip(self), err)]
     pub async fn get_pricing_at_timestamp_with_fallback(
         &mut self,
@@ -151,8 +192,42 @@ impl<'c> Tariffs<'c> {
         }
 
         // Fall back to fallback purpose (completion_window not relevant for fallback)
-        self.get_pricing_at_timestamp(deployed_model_id, fallback_purpose, timestamp, None)
-            .await
+        if let Some(pricing) = self
+            .get_pricing_at_timestamp(deployed_model_id, fallback_purpose, timestamp, None)
+            .await?
+        {
+            return Ok(Some(pricing));
+        }
+
+        self.get_default_pricing_at_timestamp(deployed_model_id, timestamp).await
+    }
+
+    /// Get the pricing of the model's default tariff (one without an API key purpose)
+    /// that was valid at a given timestamp
+    #[instrument(skip(self), err)]
+    pub async fn get_default_pricing_at_timestamp(
+        &mut self,
+        deployed_model_id: DeploymentId,
+        timestamp: DateTime<Utc>,
+    ) -> Result<Option<(Decimal, Decimal)>> {
+        let result = sqlx::query!(
+            r#"
+            SELECT input_price_per_token, output_price_per_token
+            FROM model_tariffs
+            WHERE deployed_model_id = $1
+              AND api_key_purpose IS NULL
+              AND valid_from <= $2
+              AND (valid_until IS NULL OR valid_until > $2)
+            ORDER BY valid_from DESC
+            LIMIT 1
+            "#,
+            deployed_model_id,
+            timestamp
+        )
+        .fetch_optional(&mut *self.db)
+        .await?;
+
+        Ok(result.map(|r| (r.input_price_per_token, r.output_price_per_token)))
     }
 
     /// Get the pricing for a specific API key purpose that was valid at a given timestamp
@@ -283,7 +358,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         // Create a test user
         let user = crate::test::utils::create_test_user(&pool, crate::api::models::users::Role::StandardUser).await;
@@ -359,7 +434,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         // Create a test user
         let user = crate::test::utils::create_test_user(&pool, crate::api::models::users::Role::StandardUser).await;
@@ -417,7 +492,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         // Create a test user
         let user = crate::test::utils::create_test_user(&pool, crate::api::models::users::Role::StandardUser).await;
@@ -475,7 +550,7 @@ mod tests {
             sync_interval: std::time::Duration::from_secs(3600),
             default_models: None,
         }];
-        crate::seed_database(&sources, &pool).await.unwrap();
+        crate::seed_database(&sources, None, &pool).await.unwrap();
 
         // Create a test user
         let user = crate::test::utils::create_test_user(&pool, crate::api::models::users::Role::StandardUser).await;
@@ -2,7 +2,7 @@
 
 use crate::types::{UserId, abbrev_uuid};
 use crate::{
-    api::models::users::Role,
+    api::models::{pagination::IdCursor, users::Role},
     db::{
         errors::{DbError, Result},
         handlers::{Groups, api_keys::ApiKeys, repository::Repository},
@@ -25,6 +25,11 @@ pub struct UserFilter {
     pub limit: i64,
     pub search: Option<String>, // Case-insensitive substring search on display_name, username, and email
     pub user_type: String,
+    pub email_contains: Option<String>, // Case-insensitive substring match on email
+    pub role: Option<Role>,             // Users holding this role
+    pub auth_source: Option<String>,    // Exact match on auth_source (e.g. "native", "proxy-header")
+    pub is_admin: Option<bool>,         // Filter on the legacy admin flag
+    pub cursor: Option<IdCursor>,       // Keyset pagination by id; replaces created_at ordering and skip
 }
 
 impl UserFilter {
@@ -34,6 +39,11 @@ impl UserFilter {
             limit,
             search: None,
             user_type: "individual".to_string(),
+            email_contains: None,
+            role: None,
+            auth_source: None,
+            is_admin: None,
+            cursor: None,
         }
     }
 
@@ -43,6 +53,11 @@ impl UserFilter {
             limit,
             search: None,
             user_type: "organization".to_string(),
+            email_contains: None,
+            role: None,
+            auth_source: None,
+            is_admin: None,
+            cursor: None,
         }
     }
 
@@ -50,6 +65,31 @@ impl UserFilter {
         self.search = Some(search);
         self
     }
+
+    pub fn with_email_contains(mut self, email: String) -> Self {
+        self.email_contains = Some(email);
+        self
+    }
+
+    pub fn with_role(mut self, role: Role) -> Self {
+        self.role = Some(role);
+        self
+    }
+
+    pub fn with_auth_source(mut self, auth_source: String) -> Self {
+        self.auth_source = Some(auth_source);
+        self
+    }
+
+    pub fn with_is_admin(mut self, is_admin: bool) -> Self {
+        self.is_admin = Some(is_admin);
+        self
+    }
+
+    pub fn with_cursor(mut self, cursor: IdCursor) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
 }
 
 /// User eligible for auto top-up (has threshold + amount + payment provider configured).
@@ -112,6 +152,7 @@ struct User {
     pub user_type: String,
     pub verified: bool,
     pub zero_data_retention: bool,
+    pub timezone: String,
 }
 
 pub struct Users<'c> {
@@ -144,6 +185,7 @@ impl From<(Vec<Role>, User)> for UserDBResponse {
             auto_topup_monthly_limit: user.auto_topup_monthly_limit,
             user_type: user.user_type,
             zero_data_retention: user.zero_data_retention,
+            timezone: user.timezone,
         }
     }
 }
@@ -243,11 +285,12 @@ impl<'c> Repository for Users<'c> {
                 u.user_type,
                 u.verified,
                 u.zero_data_retention,
+                u.timezone,
                 ARRAY_AGG(ur.role) FILTER (WHERE ur.role IS NOT NULL) as "roles: Vec<Role>"
             FROM users u
             LEFT JOIN user_roles ur ON ur.user_id = u.id
             WHERE u.id = $1 AND u.id != '00000000-0000-0000-0000-000000000000' AND u.is_deleted = false
-            GROUP BY u.id, u.username, u.email, u.display_name, u.avatar_url, u.auth_source, u.created_at, u.updated_at, u.last_login, u.is_admin, u.password_hash, u.external_user_id, u.payment_provider_id, u.is_deleted, u.is_internal, u.batch_notifications_enabled, u.first_batch_email_sent, u.low_balance_notification_sent, u.low_balance_threshold, u.auto_topup_amount, u.auto_topup_threshold, u.auto_topup_monthly_limit, u.auto_topup_limit_notification_sent, u.user_type, u.verified, u.zero_data_retention
+            GROUP BY u.id, u.username, u.email, u.display_name, u.avatar_url, u.auth_source, u.created_at, u.updated_at, u.last_login, u.is_admin, u.password_hash, u.external_user_id, u.payment_provider_id, u.is_deleted, u.is_internal, u.batch_notifications_enabled, u.first_batch_email_sent, u.low_balance_notification_sent, u.low_balance_threshold, u.auto_topup_amount, u.auto_topup_threshold, u.auto_topup_monthly_limit, u.auto_topup_limit_notification_sent, u.user_type, u.verified, u.zero_data_retention, u.timezone
             "#,
             id
         )
@@ -282,6 +325,7 @@ impl<'c> Repository for Users<'c> {
                 user_type: row.user_type,
                 verified: row.verified,
                 zero_data_retention: row.zero_data_retention,
+                timezone: row.timezone,
             };
 
             let roles = row.roles.unwrap_or_default();
@@ -328,11 +372,12 @@ impl<'c> Repository for Users<'c> {
                 u.user_type,
                 u.verified,
                 u.zero_data_retention,
+                u.timezone,
                 ARRAY_AGG(ur.role) FILTER (WHERE ur.role IS NOT NULL) as "roles: Vec<Role>"
             FROM users u
             LEFT JOIN user_roles ur ON ur.user_id = u.id
             WHERE u.id = ANY($1) AND u.id != '00000000-0000-0000-0000-000000000000' AND u.is_deleted = false
-            GROUP BY u.id, u.username, u.email, u.display_name, u.avatar_url, u.auth_source, u.created_at, u.updated_at, u.last_login, u.is_admin, u.password_hash, u.external_user_id, u.payment_provider_id, u.is_deleted, u.is_internal, u.batch_notifications_enabled, u.first_batch_email_sent, u.low_balance_notification_sent, u.low_balance_threshold, u.auto_topup_amount, u.auto_topup_threshold, u.auto_topup_monthly_limit, u.auto_topup_limit_notification_sent, u.user_type, u.verified, u.zero_data_retention
+            GROUP BY u.id, u.username, u.email, u.display_name, u.avatar_url, u.auth_source, u.created_at, u.updated_at, u.last_login, u.is_admin, u.password_hash, u.external_user_id, u.payment_provider_id, u.is_deleted, u.is_internal, u.batch_notifications_enabled, u.first_batch_email_sent, u.low_balance_notification_sent, u.low_balance_threshold, u.auto_topup_amount, u.auto_topup_threshold, u.auto_topup_monthly_limit, u.auto_topup_limit_notification_sent, u.user_type, u.verified, u.zero_data_retention, u.timezone
             "#,
             ids.as_slice()
         )
@@ -369,6 +414,7 @@ impl<'c> Repository for Users<'c> {
                 user_type: row.user_type,
                 verified: row.verified,
                 zero_data_retention: row.zero_data_retention,
+                timezone: row.timezone,
             };
 
             let roles = row.roles.unwrap_or_default();
@@ -382,28 +428,23 @@ impl<'c> Repository for Users<'c> {
     async fn list(&mut self, filter: &Self::Filter) -> Result<Vec<Self::Response>> {
         use sqlx::QueryBuilder;
 
-        let mut query = QueryBuilder::new(
-            "SELECT * FROM users WHERE id != '00000000-0000-0000-0000-000000000000' AND is_deleted = false AND user_type = ",
-        );
-        query.push_bind(filter.user_type.clone());
+        let mut query = QueryBuilder::new("SELECT * FROM users WHERE 1=1");
+        Self::apply_filters(&mut query, filter);
 
-        // Add search filter if specified (case-insensitive substring match on display_name, username, or email)
-        if let Some(ref search) = filter.search {
-            let search_pattern = format!("%{}%", search.to_lowercase());
-            query.push(" AND (LOWER(COALESCE(display_name, '')) LIKE ");
-            query.push_bind(search_pattern.clone());
-            query.push(" OR LOWER(username) LIKE ");
-            query.push_bind(search_pattern.clone());
-            query.push(" OR LOWER(email) LIKE ");
-            query.push_bind(search_pattern);
-            query.push(")");
+        if let Some(cursor) = filter.cursor {
+            if let IdCursor::After(after) = cursor {
+                query.push(" AND id > ");
+                query.push_bind(after);
+            }
+            query.push(" ORDER BY id LIMIT ");
+            query.push_bind(filter.limit);
+        } else {
+            query.push(" ORDER BY created_at DESC LIMIT ");
+            query.push_bind(filter.limit);
+            query.push(" OFFSET ");
+            query.push_bind(filter.skip);
         }
 
-        query.push(" ORDER BY created_at DESC LIMIT ");
-        query.push_bind(filter.limit);
-        query.push(" OFFSET ");
-        query.push_bind(filter.skip);
-
         let users = query.build_query_as::<User>().fetch_all(&mut *self.db).await?;
 
         let mut tx = self.db.begin().await?;
@@ -514,6 +555,7 @@ impl<'c> Repository for Users<'c> {
                     ELSE auto_topup_limit_notification_sent
                 END,
                 zero_data_retention = COALESCE($14, zero_data_retention),
+                timezone = COALESCE($15, timezone),
                 updated_at = NOW()
             WHERE id = $1
             RETURNING *
@@ -532,6 +574,7 @@ impl<'c> Repository for Users<'c> {
                 request.auto_topup_monthly_limit.is_some() as bool,
                 request.auto_topup_monthly_limit.flatten(),
                 request.zero_data_retention,
+                request.timezone,
             )
             .fetch_optional(&mut *tx)
             .await?
@@ -577,13 +620,9 @@ impl<'c> Users<'c> {
         Self { db }
     }
 
-    #[instrument(skip(self, filter), fields(search = filter.search), err)]
-    pub async fn count(&mut self, filter: &UserFilter) -> Result<i64> {
-        use sqlx::QueryBuilder;
-
-        let mut query = QueryBuilder::new(
-            "SELECT COUNT(*) FROM users WHERE id != '00000000-0000-0000-0000-000000000000' AND is_deleted = false AND user_type = ",
-        );
+    /// Apply shared filter clauses to a query builder (used by both list and count)
+    fn apply_filters(query: &mut sqlx::QueryBuilder<'_, sqlx::Postgres>, filter: &UserFilter) {
+        query.push(" AND id != '00000000-0000-0000-0000-000000000000' AND is_deleted = false AND user_type = ");
         query.push_bind(filter.user_type.clone());
 
         // Add search filter if specified (case-insensitive substring match on display_name, username, or email)
@@ -598,10 +637,55 @@ impl<'c> Users<'c> {
             query.push(")");
         }
 
+        if let Some(ref email) = filter.email_contains {
+            query.push(" AND LOWER(email) LIKE ");
+            query.push_bind(format!("%{}%", email.to_lowercase()));
+        }
+
+        if let Some(ref role) = filter.role {
+            query.push(" AND EXISTS (SELECT 1 FROM user_roles ur WHERE ur.user_id = users.id AND ur.role = ");
+            query.push_bind(role.clone());
+            query.push(")");
+        }
+
+        if let Some(ref auth_source) = filter.auth_source {
+            query.push(" AND auth_source = ");
+            query.push_bind(auth_source.clone());
+        }
+
+        if let Some(is_admin) = filter.is_admin {
+            query.push(" AND is_admin = ");
+            query.push_bind(is_admin);
+        }
+    }
+
+    #[instrument(skip(self, filter), fields(search = filter.search), err)]
+    pub async fn count(&mut self, filter: &UserFilter) -> Result<i64> {
+        use sqlx::QueryBuilder;
+
+        let mut query = QueryBuilder::new("SELECT COUNT(*) FROM users WHERE 1=1");
+        Self::apply_filters(&mut query, filter);
+
         let count: (i64,) = query.build_query_as().fetch_one(&mut *self.db).await?;
         Ok(count.0)
     }
 
+    /// Replace a user's password hash only if it still equals `current_hash`, so a password
+    /// changed concurrently is never overwritten. Returns whether the hash was replaced.
+    #[instrument(skip(self, current_hash, new_hash), fields(user_id = %abbrev_uuid(&user_id)), err)]
+    pub async fn replace_password_hash(&mut self, user_id: UserId, current_hash: &str, new_hash: &str) -> Result<bool> {
+        let result = sqlx::query!(
+            "UPDATE users SET password_hash = $1 WHERE id = $2 AND password_hash = $3",
+            new_hash,
+            user_id,
+            current_hash
+        )
+        .execute(&mut *self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     #[instrument(skip(self, email), err)]
     pub async fn get_user_by_email(&mut self, email: &str) -> Result<Option<UserDBResponse>> {
         let user = sqlx::query_as!(
@@ -650,6 +734,19 @@ impl<'c> Users<'c> {
         }
     }
 
+    /// Whether `name` is a timezone Postgres recognises, so it is safe to use
+    /// in the `AT TIME ZONE` quota window calculations.
+    #[instrument(skip(self), err)]
+    pub async fn is_valid_timezone(&mut self, name: &str) -> Result<bool> {
+        let valid = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM pg_timezone_names WHERE name = $1) AS "valid!""#,
+            name
+        )
+        .fetch_one(&mut *self.db)
+        .await?;
+        Ok(valid)
+    }
+
     /// Update a user's email address
     #[instrument(skip(self, email), fields(user_id = %abbrev_uuid(&user_id)), err)]
     async fn update_user_email(&mut self, user_id: UserId, email: &str) -> Result<()> {
@@ -1052,6 +1149,9 @@ mod tests {
                 created_by: user.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             })
             .await
             .unwrap();
@@ -1160,6 +1260,7 @@ mod tests {
             auto_topup_threshold: None,
             auto_topup_monthly_limit: None,
             zero_data_retention: None,
+            timezone: None,
         };
 
         let updated_user = repo.update(created_user.id, &update_request).await.unwrap();
@@ -1182,6 +1283,7 @@ mod tests {
             auto_topup_threshold: None,
             auto_topup_monthly_limit: None,
             zero_data_retention: None,
+            timezone: None,
         };
 
         let updated_user = repo.update(created_user.id, &update_request).await.unwrap();
@@ -1218,6 +1320,7 @@ mod tests {
                 auto_topup_threshold: None,
                 auto_topup_monthly_limit: None,
                 zero_data_retention: None,
+                timezone: None,
             };
             repo.update(user.id, &update).await.unwrap();
         }
@@ -1467,6 +1570,7 @@ mod tests {
             auto_topup_threshold: None,
             auto_topup_monthly_limit: None,
             zero_data_retention: None,
+            timezone: None,
         };
         let updated = users.update(user_id, &update).await.unwrap();
         assert!(!updated.low_balance_notification_sent);
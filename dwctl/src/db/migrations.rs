@@ -0,0 +1,145 @@
+//! Applying and checking database migrations.
+//!
+//! On startup dwctl applies its own migrations and those of the components it
+//! embeds (fusillade, outlet) with [`run`]. Operators who apply migrations as a
+//! separate CI/CD step (`dwctl migrate`) start the server with
+//! `--skip-migrations`, in which case [`verify`] only checks that nothing is
+//! left to apply.
+//!
+//! Both report failures with what went wrong and how to fix it, rather than
+//! sqlx's bare error.
+
+use std::collections::HashSet;
+
+use anyhow::anyhow;
+use sqlx::PgPool;
+use sqlx::migrate::{MigrateError, Migrator};
+use tracing::{info, warn};
+
+/// Versions recorded as applied, or none if the migrations table doesn't exist yet.
+async fn applied_versions(pool: &PgPool) -> Result<HashSet<i64>, sqlx::Error> {
+    let exists: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+        .fetch_one(pool)
+        .await?;
+    if !exists {
+        return Ok(HashSet::new());
+    }
+    let versions: Vec<i64> = sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success")
+        .fetch_all(pool)
+        .await?;
+    Ok(versions.into_iter().collect())
+}
+
+/// Versions in `migrator` that haven't been applied, in order.
+fn pending_versions(migrator: &Migrator, applied: &HashSet<i64>) -> Vec<i64> {
+    migrator
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration() && !applied.contains(&migration.version))
+        .map(|migration| migration.version)
+        .collect()
+}
+
+fn format_versions(versions: &[i64]) -> String {
+    versions.iter().map(i64::to_string).collect::<Vec<_>>().join(", ")
+}
+
+/// Apply `component`'s pending migrations.
+///
+/// Pending migrations numbered below the latest applied one (usually from
+/// branches merged in a different order than they were numbered) are still
+/// applied, with a warning naming them.
+pub async fn run(component: &str, migrator: &Migrator, pool: &PgPool) -> anyhow::Result<()> {
+    let applied = applied_versions(pool)
+        .await
+        .map_err(|e| anyhow!("Failed to read applied {component} migrations: {e}"))?;
+    let pending = pending_versions(migrator, &applied);
+
+    if let Some(&latest_applied) = applied.iter().max() {
+        let out_of_order: Vec<i64> = pending.iter().copied().filter(|&version| version < latest_applied).collect();
+        if !out_of_order.is_empty() {
+            warn!(
+                component,
+                latest_applied,
+                "Applying {component} migrations {} out of order: they are older than the latest applied migration. \
+                 Check they weren't renumbered or skipped by an earlier release.",
+                format_versions(&out_of_order)
+            );
+        }
+    }
+    if !pending.is_empty() {
+        info!(component, count = pending.len(), "Applying database migrations");
+    }
+
+    migrator.run(pool).await.map_err(|error| explain(component, error))
+}
+
+/// Check that `component` has no pending migrations, for when they are applied separately.
+pub async fn verify(component: &str, migrator: &Migrator, pool: &PgPool) -> anyhow::Result<()> {
+    let applied = applied_versions(pool)
+        .await
+        .map_err(|e| anyhow!("Failed to read applied {component} migrations: {e}"))?;
+    let pending = pending_versions(migrator, &applied);
+    if pending.is_empty() {
+        return Ok(());
+    }
+    Err(anyhow!(
+        "Migrations are skipped (--skip-migrations) but the {component} database is missing {} migration(s): {}. \
+         Run `dwctl migrate` with this release before starting it.",
+        pending.len(),
+        format_versions(&pending)
+    ))
+}
+
+/// Turn a migration failure into an error saying what went wrong and how to fix it.
+fn explain(component: &str, error: MigrateError) -> anyhow::Error {
+    let reason = match &error {
+        MigrateError::VersionMismatch(version) => format!(
+            "migration {version} has been modified since it was applied (its checksum no longer matches the database). \
+             Applied migrations must not change: restore the original file and put the change in a new migration."
+        ),
+        MigrateError::VersionMissing(version) => format!(
+            "the database has migration {version} applied but this release doesn't include it, so it was migrated by a \
+             newer or different build. Run a release that includes it, or start this one with --skip-migrations if it \
+             is compatible with the newer schema."
+        ),
+        MigrateError::Dirty(version) => format!(
+            "migration {version} is only partially applied. Repair the schema by hand, delete the migration's row from \
+             _sqlx_migrations and restart."
+        ),
+        MigrateError::ExecuteMigration(source, version) => {
+            format!("migration {version} failed: {source}. Fix the cause and restart to retry it.")
+        }
+        _ => return anyhow::Error::new(error).context(format!("Failed to run {component} migrations")),
+    };
+    anyhow!("Failed to run {component} migrations: {reason}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain_checksum_mismatch() {
+        let message = explain("dwctl", MigrateError::VersionMismatch(42)).to_string();
+        assert!(message.contains("dwctl migrations"));
+        assert!(message.contains("migration 42 has been modified"));
+        assert!(message.contains("new migration"));
+    }
+
+    #[test]
+    fn test_explain_migration_from_newer_release() {
+        let message = explain("fusillade", MigrateError::VersionMissing(7)).to_string();
+        assert!(message.contains("fusillade migrations"));
+        assert!(message.contains("--skip-migrations"));
+    }
+
+    #[test]
+    fn test_pending_versions() {
+        let migrator = crate::migrator();
+        let versions: Vec<i64> = migrator.iter().map(|migration| migration.version).collect();
+        assert_eq!(pending_versions(&migrator, &HashSet::new()), versions);
+
+        let applied: HashSet<i64> = versions.iter().copied().filter(|&version| version != versions[1]).collect();
+        assert_eq!(pending_versions(&migrator, &applied), vec![versions[1]]);
+    }
+}
@@ -90,6 +90,7 @@
 pub mod embedded;
 pub mod errors;
 pub mod handlers;
+pub mod migrations;
 pub mod models;
 pub mod pools;
 
@@ -0,0 +1,42 @@
+//! Database models for access requests to deployments that require approval.
+
+use crate::types::{DeploymentId, UserId};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Where an access request stands. Only `approved` grants access.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema, sqlx::Type)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AccessRequestStatus {
+    Pending,
+    Approved,
+    Denied,
+}
+
+impl AccessRequestStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Approved => "approved",
+            Self::Denied => "denied",
+        }
+    }
+}
+
+/// An access request, with the requesting user's email
+#[derive(Debug, Clone)]
+pub struct AccessRequestDBResponse {
+    pub id: Uuid,
+    pub deployment_id: DeploymentId,
+    pub user_id: UserId,
+    pub user_email: String,
+    pub status: AccessRequestStatus,
+    pub reason: Option<String>,
+    pub reviewed_by: Option<UserId>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,36 @@
+//! Database models for access templates.
+
+use crate::types::{DeploymentId, UserId};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Database response for an access template, with its deployments.
+#[derive(Debug, Clone)]
+pub struct AccessTemplateDBResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    /// Non-deleted deployments in the template
+    pub deployment_ids: Vec<DeploymentId>,
+    pub created_by: UserId,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Database request for creating an access template
+#[derive(Debug, Clone)]
+pub struct AccessTemplateCreateDBRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub deployment_ids: Vec<DeploymentId>,
+    pub created_by: UserId,
+}
+
+/// Database request for updating an access template. `deployment_ids`, when
+/// given, replaces the template's deployments.
+#[derive(Debug, Clone)]
+pub struct AccessTemplateUpdateDBRequest {
+    pub name: Option<String>,
+    pub description: Option<Option<String>>,
+    pub deployment_ids: Option<Vec<DeploymentId>>,
+}
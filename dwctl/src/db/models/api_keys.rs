@@ -64,6 +64,11 @@ pub struct ApiKeyCreateDBRequest {
     /// also mint the cap-scope child key (handler responsibility).
     pub spend_limit: Option<Decimal>,
     pub spend_limit_interval: Option<String>,
+    /// Per-key model restrictions; see migration 144. None = unrestricted.
+    pub allowed_model_ids: Option<Vec<DeploymentId>>,
+    pub denied_model_ids: Option<Vec<DeploymentId>>,
+    /// Highest request priority the key may ask for; see migration 155.
+    pub max_priority: i16,
 }
 
 impl ApiKeyCreateDBRequest {
@@ -72,12 +77,15 @@ impl ApiKeyCreateDBRequest {
             user_id,
             name: create.name,
             description: create.description,
-            purpose: create.purpose,
+            purpose: create.purpose.unwrap_or(ApiKeyPurpose::Realtime),
             requests_per_second: create.requests_per_second,
             burst_size: create.burst_size,
             created_by,
             spend_limit: create.spend_limit,
             spend_limit_interval: create.spend_limit_interval,
+            allowed_model_ids: create.allowed_model_ids,
+            denied_model_ids: create.denied_model_ids,
+            max_priority: create.max_priority.unwrap_or(0),
         }
     }
 }
@@ -89,6 +97,10 @@ pub struct ApiKeyUpdateDBRequest {
     pub description: Option<String>,
     pub requests_per_second: Option<Option<f32>>,
     pub burst_size: Option<Option<i32>>,
+    /// Outer None = leave unchanged, Some(None) = remove the restriction
+    pub allowed_model_ids: Option<Option<Vec<DeploymentId>>>,
+    pub denied_model_ids: Option<Option<Vec<DeploymentId>>>,
+    pub max_priority: Option<i16>,
 }
 
 /// Database response for an API key
@@ -114,6 +126,12 @@ pub struct ApiKeyDBResponse {
     /// Set only on hidden cap-scope child keys; see migration 122. Spend
     /// accounting/enforcement group by COALESCE(parent_api_key_id, id).
     pub parent_api_key_id: Option<ApiKeyId>,
+    /// When set, the key may only use these deployments (on top of group access).
+    pub allowed_model_ids: Option<Vec<DeploymentId>>,
+    /// Deployments the key may never use, even if its owner's groups grant them.
+    pub denied_model_ids: Option<Vec<DeploymentId>>,
+    /// Highest request priority (0-9) the key may ask for with `X-Priority`.
+    pub max_priority: i16,
 }
 
 /// Spend display state for one cap scope (read from `api_key_spend_checkpoints`
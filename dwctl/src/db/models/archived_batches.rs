@@ -0,0 +1,41 @@
+//! Database models for batches removed by the batch retention job.
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Database request for recording an archived batch
+#[derive(Debug, Clone)]
+pub struct ArchivedBatchCreateDBRequest {
+    pub batch_id: Uuid,
+    pub created_by: String,
+    pub completion_window: String,
+    pub batch_created_at: DateTime<Utc>,
+    pub cold_storage_path: Option<String>,
+    pub snapshot: serde_json::Value,
+}
+
+/// Database response for an archived batch
+#[derive(Debug, Clone)]
+pub struct ArchivedBatchDBResponse {
+    pub batch_id: Uuid,
+    pub created_by: String,
+    pub completion_window: String,
+    pub batch_created_at: DateTime<Utc>,
+    pub archived_at: DateTime<Utc>,
+    pub cold_storage_path: Option<String>,
+    pub snapshot: serde_json::Value,
+}
+
+/// Filter for listing archived batches, newest first
+#[derive(Debug, Clone, Default)]
+pub struct ArchivedBatchFilter {
+    /// Only batches with this owner; `None` lists every owner's
+    pub created_by: Option<String>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    /// Only batches with one of these completion windows; `None` disables the filter
+    pub completion_windows: Option<Vec<String>>,
+    /// Cursor: the archived batch to start after
+    pub after: Option<Uuid>,
+    pub limit: i64,
+}
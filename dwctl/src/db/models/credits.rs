@@ -65,3 +65,19 @@ pub struct CreditTransactionDBResponse {
     /// was backfilled.
     pub service_tier: Option<String>,
 }
+
+/// A ledger row with its user's running balance, for exports
+#[derive(Debug, Clone)]
+pub struct CreditLedgerEntry {
+    pub id: Uuid,
+    pub user_id: UserId,
+    pub transaction_type: CreditTransactionType,
+    pub amount: Decimal,
+    pub source_id: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub service_tier: Option<String>,
+    /// The user's balance after this transaction, summed over their whole
+    /// ledger in `seq` order (the order the balance checkpoint folds rows in)
+    pub balance: Decimal,
+}
@@ -0,0 +1,21 @@
+//! Database models for deployment shadow traffic.
+
+use crate::types::DeploymentId;
+use chrono::{DateTime, Utc};
+
+/// Database request for setting a deployment's shadow. Replaces any existing shadow.
+#[derive(Debug, Clone)]
+pub struct DeploymentShadowDBRequest {
+    pub shadow_deployment_id: DeploymentId,
+    pub fraction: f64,
+}
+
+/// A deployment's shadow, with the shadow model's alias
+#[derive(Debug, Clone)]
+pub struct DeploymentShadowDBResponse {
+    pub shadow_deployment_id: DeploymentId,
+    pub shadow_alias: String,
+    pub fraction: f64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -182,6 +182,7 @@ pub enum ModelType {
     Chat,
     Embeddings,
     Reranker,
+    Moderation,
 }
 
 impl ModelType {
@@ -200,6 +201,9 @@ impl ModelType {
             "mxbai-rerank",
         ];
 
+        // Moderation (safety classifier) model patterns
+        let moderation_patterns = ["moderation", "llama-guard", "shieldgemma", "granite-guardian"];
+
         // Embedding model patterns
         let embedding_patterns = [
             "embed",
@@ -217,6 +221,11 @@ impl ModelType {
             return Self::Reranker;
         }
 
+        // Check if model name contains any moderation patterns
+        if moderation_patterns.iter().any(|pattern| name_lower.contains(pattern)) {
+            return Self::Moderation;
+        }
+
         // Check if model name contains any embedding patterns
         if embedding_patterns.iter().any(|pattern| name_lower.contains(pattern)) {
             return Self::Embeddings;
@@ -329,6 +338,146 @@ impl LoadBalancingStrategy {
     }
 }
 
+/// How a deployment's system prompt is combined with a system message the client sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemPromptMode {
+    /// Insert the prompt as a separate system message before the client's (default)
+    #[default]
+    Prepend,
+    /// Add the prompt to the start of the client's first system message
+    Merge,
+}
+
+impl SystemPromptMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Prepend => "prepend",
+            Self::Merge => "merge",
+        }
+    }
+
+    pub fn try_parse(s: &str) -> Option<Self> {
+        match s {
+            "prepend" => Some(Self::Prepend),
+            "merge" => Some(Self::Merge),
+            _ => None,
+        }
+    }
+}
+
+/// How a request asking for more completions (`n`) than a deployment's `max_n` is handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NLimitMode {
+    /// Reject the request with a 400 (default)
+    #[default]
+    Reject,
+    /// Lower `n` to `max_n` and forward the request
+    Clamp,
+}
+
+impl NLimitMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Reject => "reject",
+            Self::Clamp => "clamp",
+        }
+    }
+
+    pub fn try_parse(s: &str) -> Option<Self> {
+        match s {
+            "reject" => Some(Self::Reject),
+            "clamp" => Some(Self::Clamp),
+            _ => None,
+        }
+    }
+}
+
+/// What happens when a streamed response costs more than the caller's remaining credits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CreditExhaustionMode {
+    /// Let the stream finish, charging into overdraft (up to `max_overdraft` when set) (default)
+    #[default]
+    Complete,
+    /// End the stream with an error event once its cost exceeds the balance
+    Abort,
+}
+
+impl CreditExhaustionMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Complete => "complete",
+            Self::Abort => "abort",
+        }
+    }
+
+    pub fn try_parse(s: &str) -> Option<Self> {
+        match s {
+            "complete" => Some(Self::Complete),
+            "abort" => Some(Self::Abort),
+            _ => None,
+        }
+    }
+}
+
+/// What a deployment's `max_prompt_length` counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptLengthUnit {
+    /// Tokens, counted by tokenizer-svc when it maps the model and estimated from characters otherwise (default)
+    #[default]
+    Tokens,
+    /// Characters of prompt text
+    Characters,
+}
+
+impl PromptLengthUnit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Tokens => "tokens",
+            Self::Characters => "characters",
+        }
+    }
+
+    pub fn try_parse(s: &str) -> Option<Self> {
+        match s {
+            "tokens" => Some(Self::Tokens),
+            "characters" => Some(Self::Characters),
+            _ => None,
+        }
+    }
+}
+
+/// Kind of content a model accepts or produces
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Modality {
+    Text,
+    Image,
+    Audio,
+}
+
+impl Modality {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Image => "image",
+            Self::Audio => "audio",
+        }
+    }
+
+    pub fn try_parse(s: &str) -> Option<Self> {
+        match s {
+            "text" => Some(Self::Text),
+            "image" => Some(Self::Image),
+            "audio" => Some(Self::Audio),
+            _ => None,
+        }
+    }
+}
+
 fn default_true() -> bool {
     true
 }
@@ -338,7 +487,7 @@ fn default_fallback_status_codes() -> Vec<i32> {
 }
 
 /// Fallback configuration for composite models
-#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct FallbackConfig {
     /// Whether fallback is enabled (default: true)
     #[serde(default = "default_true")]
@@ -403,7 +552,7 @@ impl JitterStrategy {
 
 /// Exponential backoff between fallback attempts. Mirrors
 /// `onwards::target::BackoffConfig`.
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct BackoffConfig {
     /// Delay before the first retry, in milliseconds.
     pub initial_ms: i32,
@@ -477,6 +626,12 @@ pub struct DeploymentCreateDBRequest {
     pub requests_per_second: Option<f32>,
     pub burst_size: Option<i32>,
     pub capacity: Option<i32>,
+    pub per_key_capacity: Option<i32>,
+    pub max_cost_per_request: Option<Decimal>,
+    /// Modalities the model accepts (None = undeclared, not checked)
+    pub input_modalities: Option<Vec<Modality>>,
+    /// Modalities the model produces (None = undeclared, not checked)
+    pub output_modalities: Option<Vec<Modality>>,
     pub batch_capacity: Option<i32>,
     pub throughput: Option<f32>,
     // Provider/downstream pricing
@@ -512,6 +667,41 @@ pub struct DeploymentCreateDBRequest {
     /// Whether to mark provider as trusted in strict mode (bypasses sanitization)
     #[builder(default = false)]
     pub trusted: bool,
+    /// Whether every user may use the model without group membership
+    #[builder(default = false)]
+    pub allow_public: bool,
+    /// Whether users need an approved access request before they can use the model
+    #[builder(default = false)]
+    pub require_approval: bool,
+    /// Whether responses report the requested alias as their `model`
+    #[builder(default = false)]
+    pub rewrite_response_model: bool,
+    /// Whether request and response bodies are kept out of request logs
+    #[builder(default = false)]
+    pub disable_logging: bool,
+    /// Whether a warmup request is sent when the deployment becomes active
+    #[builder(default = false)]
+    pub warmup: bool,
+    /// System prompt injected into the deployment's chat requests
+    pub system_prompt: Option<String>,
+    /// How the system prompt is combined with a client's system message
+    #[builder(default)]
+    pub system_prompt_mode: SystemPromptMode,
+    /// Largest number of completions (`n`) a request may ask for
+    pub max_n: Option<i32>,
+    /// How requests asking for more than `max_n` completions are handled
+    #[builder(default)]
+    pub max_n_mode: NLimitMode,
+    /// What happens when a stream costs more than the caller's remaining credits
+    #[builder(default)]
+    pub credit_exhaustion_mode: CreditExhaustionMode,
+    /// How far below zero a stream may take the caller's balance in complete mode
+    pub max_overdraft: Option<Decimal>,
+    /// Longest prompt a request may send, in `max_prompt_length_unit`
+    pub max_prompt_length: Option<i32>,
+    /// What `max_prompt_length` counts
+    #[builder(default)]
+    pub max_prompt_length_unit: PromptLengthUnit,
     /// Whether to enable the open_responses adapter (converts /v1/responses to /v1/chat/completions)
     #[builder(default = true)]
     pub open_responses_adapter: bool,
@@ -551,6 +741,10 @@ impl DeploymentCreateDBRequest {
                     .maybe_requests_per_second(standard.requests_per_second)
                     .maybe_burst_size(standard.burst_size)
                     .maybe_capacity(standard.capacity)
+                    .maybe_per_key_capacity(standard.per_key_capacity)
+                    .maybe_max_cost_per_request(standard.max_cost_per_request)
+                    .maybe_input_modalities(standard.input_modalities)
+                    .maybe_output_modalities(standard.output_modalities)
                     .maybe_batch_capacity(standard.batch_capacity)
                     .maybe_throughput(standard.throughput)
                     .maybe_provider_pricing(standard.provider_pricing)
@@ -566,6 +760,19 @@ impl DeploymentCreateDBRequest {
                     .maybe_backoff_max_total_ms(standard.backoff_max_total_ms)
                     .sanitize_responses(standard.sanitize_responses.unwrap_or(false))
                     .trusted(standard.trusted.unwrap_or(false))
+                    .allow_public(standard.allow_public.unwrap_or(false))
+                    .require_approval(standard.require_approval.unwrap_or(false))
+                    .rewrite_response_model(standard.rewrite_response_model.unwrap_or(false))
+                    .disable_logging(standard.disable_logging.unwrap_or(false))
+                    .warmup(standard.warmup.unwrap_or(false))
+                    .maybe_system_prompt(standard.system_prompt)
+                    .system_prompt_mode(standard.system_prompt_mode.unwrap_or_default())
+                    .maybe_max_n(standard.max_n)
+                    .max_n_mode(standard.max_n_mode.unwrap_or_default())
+                    .credit_exhaustion_mode(standard.credit_exhaustion_mode.unwrap_or_default())
+                    .maybe_max_overdraft(standard.max_overdraft)
+                    .maybe_max_prompt_length(standard.max_prompt_length)
+                    .max_prompt_length_unit(standard.max_prompt_length_unit.unwrap_or_default())
                     .open_responses_adapter(standard.open_responses_adapter.unwrap_or(true))
                     .maybe_reasoning_translation_overrides(standard.reasoning_translation_overrides)
                     .maybe_allowed_batch_completion_windows(standard.allowed_batch_completion_windows)
@@ -583,6 +790,10 @@ impl DeploymentCreateDBRequest {
                 .maybe_requests_per_second(composite.requests_per_second)
                 .maybe_burst_size(composite.burst_size)
                 .maybe_capacity(composite.capacity)
+                .maybe_per_key_capacity(composite.per_key_capacity)
+                .maybe_max_cost_per_request(composite.max_cost_per_request)
+                .maybe_input_modalities(composite.input_modalities)
+                .maybe_output_modalities(composite.output_modalities)
                 .maybe_batch_capacity(composite.batch_capacity)
                 .maybe_throughput(composite.throughput)
                 .is_composite(true)
@@ -600,12 +811,81 @@ impl DeploymentCreateDBRequest {
                 .maybe_backoff_max_total_ms(composite.backoff_max_total_ms)
                 .sanitize_responses(composite.sanitize_responses)
                 .trusted(composite.trusted.unwrap_or(false))
+                .allow_public(composite.allow_public.unwrap_or(false))
+                .require_approval(composite.require_approval.unwrap_or(false))
+                .rewrite_response_model(composite.rewrite_response_model.unwrap_or(false))
+                .disable_logging(composite.disable_logging.unwrap_or(false))
+                .warmup(composite.warmup.unwrap_or(false))
+                .maybe_system_prompt(composite.system_prompt)
+                .system_prompt_mode(composite.system_prompt_mode.unwrap_or_default())
+                .maybe_max_n(composite.max_n)
+                .max_n_mode(composite.max_n_mode.unwrap_or_default())
+                .credit_exhaustion_mode(composite.credit_exhaustion_mode.unwrap_or_default())
+                .maybe_max_overdraft(composite.max_overdraft)
+                .maybe_max_prompt_length(composite.max_prompt_length)
+                .max_prompt_length_unit(composite.max_prompt_length_unit.unwrap_or_default())
                 .open_responses_adapter(composite.open_responses_adapter.unwrap_or(true))
                 .maybe_allowed_batch_completion_windows(composite.allowed_batch_completion_windows)
                 .maybe_metadata(composite.metadata)
                 .build(),
         }
     }
+
+    /// Creates a request for a new deployment configured like `source`, under a new alias
+    pub fn copy_of(source: &DeploymentDBResponse, alias: String, display_name: Option<String>, created_by: UserId) -> Self {
+        Self::builder()
+            .created_by(created_by)
+            .model_name(source.model_name.clone())
+            .alias(alias)
+            .maybe_display_name(display_name.or_else(|| source.display_name.clone()))
+            .maybe_description(source.description.clone())
+            .maybe_model_type(source.model_type.clone())
+            .maybe_capabilities(source.capabilities.clone())
+            .maybe_hosted_on(source.hosted_on)
+            .maybe_requests_per_second(source.requests_per_second)
+            .maybe_burst_size(source.burst_size)
+            .maybe_capacity(source.capacity)
+            .maybe_per_key_capacity(source.per_key_capacity)
+            .maybe_max_cost_per_request(source.max_cost_per_request)
+            .maybe_input_modalities(source.input_modalities.clone())
+            .maybe_output_modalities(source.output_modalities.clone())
+            .maybe_batch_capacity(source.batch_capacity)
+            .maybe_throughput(source.throughput)
+            .maybe_provider_pricing(source.provider_pricing.clone())
+            .is_composite(source.is_composite)
+            .lb_strategy(source.lb_strategy)
+            .fallback_enabled(source.fallback_enabled)
+            .fallback_on_rate_limit(source.fallback_on_rate_limit)
+            .fallback_on_status(source.fallback_on_status.clone())
+            .fallback_with_replacement(source.fallback_with_replacement)
+            .maybe_fallback_max_attempts(source.fallback_max_attempts)
+            .backoff_enabled(source.backoff_enabled)
+            .backoff_initial_ms(source.backoff_initial_ms)
+            .backoff_max_ms(source.backoff_max_ms)
+            .backoff_factor(source.backoff_factor)
+            .backoff_jitter(source.backoff_jitter.clone())
+            .maybe_backoff_max_total_ms(source.backoff_max_total_ms)
+            .sanitize_responses(source.sanitize_responses)
+            .trusted(source.trusted)
+            .allow_public(source.allow_public)
+            .require_approval(source.require_approval)
+            .rewrite_response_model(source.rewrite_response_model)
+            .disable_logging(source.disable_logging)
+            .warmup(source.warmup)
+            .maybe_system_prompt(source.system_prompt.clone())
+            .system_prompt_mode(source.system_prompt_mode)
+            .maybe_max_n(source.max_n)
+            .max_n_mode(source.max_n_mode)
+            .credit_exhaustion_mode(source.credit_exhaustion_mode)
+            .maybe_max_overdraft(source.max_overdraft)
+            .maybe_max_prompt_length(source.max_prompt_length)
+            .max_prompt_length_unit(source.max_prompt_length_unit)
+            .open_responses_adapter(source.open_responses_adapter)
+            .maybe_reasoning_translation_overrides(source.reasoning_translation_overrides.clone())
+            .maybe_allowed_batch_completion_windows(source.allowed_batch_completion_windows.clone())
+            .maybe_metadata(serde_json::from_value(source.metadata.clone()).ok())
+            .build()
+    }
 }
 
 /// Database request for updating a deployment
@@ -623,6 +903,10 @@ pub struct DeploymentUpdateDBRequest {
     pub requests_per_second: Option<Option<f32>>,
     pub burst_size: Option<Option<i32>>,
     pub capacity: Option<Option<i32>>,
+    pub per_key_capacity: Option<Option<i32>>,
+    pub max_cost_per_request: Option<Option<Decimal>>,
+    pub input_modalities: Option<Option<Vec<Modality>>>,
+    pub output_modalities: Option<Option<Vec<Modality>>>,
     pub batch_capacity: Option<Option<i32>>,
     pub throughput: Option<Option<f32>>,
     // Provider pricing updates
@@ -648,6 +932,32 @@ pub struct DeploymentUpdateDBRequest {
     pub sanitize_responses: Option<bool>,
     /// Whether to mark provider as trusted in strict mode (bypasses sanitization)
     pub trusted: Option<bool>,
+    /// Whether every user may use the model without group membership
+    pub allow_public: Option<bool>,
+    /// Whether users need an approved access request before they can use the model
+    pub require_approval: Option<bool>,
+    /// Whether responses report the requested alias as their `model`
+    pub rewrite_response_model: Option<bool>,
+    /// Whether request and response bodies are kept out of request logs
+    pub disable_logging: Option<bool>,
+    /// Whether a warmup request is sent when the deployment becomes active
+    pub warmup: Option<bool>,
+    /// None leaves the system prompt unchanged; Some(None) removes it.
+    pub system_prompt: Option<Option<String>>,
+    /// How the system prompt is combined with a client's system message
+    pub system_prompt_mode: Option<SystemPromptMode>,
+    /// None leaves the cap unchanged; Some(None) removes it.
+    pub max_n: Option<Option<i32>>,
+    /// How requests asking for more than `max_n` completions are handled
+    pub max_n_mode: Option<NLimitMode>,
+    /// What happens when a stream costs more than the caller's remaining credits
+    pub credit_exhaustion_mode: Option<CreditExhaustionMode>,
+    /// None leaves the overdraft limit unchanged; Some(None) removes it.
+    pub max_overdraft: Option<Option<Decimal>>,
+    /// None leaves the prompt length limit unchanged; Some(None) removes it.
+    pub max_prompt_length: Option<Option<i32>>,
+    /// What `max_prompt_length` counts
+    pub max_prompt_length_unit: Option<PromptLengthUnit>,
     /// Whether to enable the open_responses adapter (converts /v1/responses to /v1/chat/completions)
     pub open_responses_adapter: Option<bool>,
     /// None leaves overrides unchanged; Some(None) inherits both endpoint defaults.
@@ -669,6 +979,10 @@ impl From<DeployedModelUpdate> for DeploymentUpdateDBRequest {
             .maybe_requests_per_second(update.requests_per_second)
             .maybe_burst_size(update.burst_size)
             .maybe_capacity(update.capacity)
+            .maybe_per_key_capacity(update.per_key_capacity)
+            .maybe_max_cost_per_request(update.max_cost_per_request)
+            .maybe_input_modalities(update.input_modalities)
+            .maybe_output_modalities(update.output_modalities)
             .maybe_batch_capacity(update.batch_capacity)
             .maybe_throughput(update.throughput)
             .maybe_provider_pricing(update.provider_pricing)
@@ -686,6 +1000,19 @@ impl From<DeployedModelUpdate> for DeploymentUpdateDBRequest {
             .maybe_backoff_max_total_ms(update.backoff_max_total_ms)
             .maybe_sanitize_responses(update.sanitize_responses)
             .maybe_trusted(update.trusted)
+            .maybe_allow_public(update.allow_public)
+            .maybe_require_approval(update.require_approval)
+            .maybe_rewrite_response_model(update.rewrite_response_model)
+            .maybe_disable_logging(update.disable_logging)
+            .maybe_warmup(update.warmup)
+            .maybe_system_prompt(update.system_prompt)
+            .maybe_system_prompt_mode(update.system_prompt_mode)
+            .maybe_max_n(update.max_n)
+            .maybe_max_n_mode(update.max_n_mode)
+            .maybe_credit_exhaustion_mode(update.credit_exhaustion_mode)
+            .maybe_max_overdraft(update.max_overdraft)
+            .maybe_max_prompt_length(update.max_prompt_length)
+            .maybe_max_prompt_length_unit(update.max_prompt_length_unit)
             .maybe_open_responses_adapter(update.open_responses_adapter)
             .maybe_reasoning_translation_overrides(update.reasoning_translation_overrides)
             .maybe_allowed_batch_completion_windows(update.allowed_batch_completion_windows)
@@ -733,6 +1060,14 @@ pub struct DeploymentDBResponse {
     pub requests_per_second: Option<f32>,
     pub burst_size: Option<i32>,
     pub capacity: Option<i32>,
+    /// Maximum concurrent requests any single API key may hold against this model
+    pub per_key_capacity: Option<i32>,
+    /// Most a single request may cost, in credits; enforced by the inference cost guard
+    pub max_cost_per_request: Option<Decimal>,
+    /// Modalities the model accepts; enforced by the inference modality check
+    pub input_modalities: Option<Vec<Modality>>,
+    /// Modalities the model produces; enforced by the inference modality check
+    pub output_modalities: Option<Vec<Modality>>,
     pub batch_capacity: Option<i32>,
     /// Throughput in requests/second for batch capacity calculations
     pub throughput: Option<f32>,
@@ -761,6 +1096,36 @@ pub struct DeploymentDBResponse {
     pub sanitize_responses: bool,
     /// Whether to mark provider as trusted in strict mode (bypasses sanitization)
     pub trusted: bool,
+    /// Whether every user may use the model without group membership
+    pub allow_public: bool,
+    /// Whether users need an approved access request before they can use the model
+    /// (see `access_requests`). Doesn't apply to system or batch-escalation access.
+    pub require_approval: bool,
+    /// Whether responses report the requested alias as their `model`
+    pub rewrite_response_model: bool,
+    /// Whether request and response bodies are kept out of request logs
+    pub disable_logging: bool,
+    /// Whether a warmup request is sent when the deployment becomes active
+    pub warmup: bool,
+    /// Whether the deployment serves requests. Inactive deployments keep their
+    /// configuration but are left out of the proxy until reactivated.
+    pub active: bool,
+    /// System prompt injected into the deployment's chat requests
+    pub system_prompt: Option<String>,
+    /// How the system prompt is combined with a client's system message
+    pub system_prompt_mode: SystemPromptMode,
+    /// Largest number of completions (`n`) a request may ask for
+    pub max_n: Option<i32>,
+    /// How requests asking for more than `max_n` completions are handled
+    pub max_n_mode: NLimitMode,
+    /// What happens when a stream costs more than the caller's remaining credits; enforced by the inference cost guard
+    pub credit_exhaustion_mode: CreditExhaustionMode,
+    /// How far below zero a stream may take the caller's balance in complete mode (None = no limit)
+    pub max_overdraft: Option<Decimal>,
+    /// Longest prompt a request may send (None = no limit); enforced by the inference prompt length check
+    pub max_prompt_length: Option<i32>,
+    /// What `max_prompt_length` counts
+    pub max_prompt_length_unit: PromptLengthUnit,
     /// Whether the open_responses adapter is enabled (converts /v1/responses to /v1/chat/completions)
     pub open_responses_adapter: bool,
     pub reasoning_translation_overrides: Option<ReasoningTranslationOverrides>,
@@ -775,6 +1140,10 @@ pub struct DeploymentDBResponse {
 pub enum TrafficRuleAction {
     Deny,
     Redirect(DeploymentId),
+    /// Override the model's sanitize_responses setting
+    Sanitize(bool),
+    /// Prefer providers in the named region, falling back cross-region
+    PreferRegion(String),
 }
 
 /// Row returned from model_traffic_rules table (with joined redirect target alias)
@@ -787,6 +1156,10 @@ pub struct TrafficRuleDBRow {
     pub redirect_target_id: Option<DeploymentId>,
     /// Populated via LEFT JOIN on deployed_models
     pub redirect_target_alias: Option<String>,
+    /// Set for 'sanitize' rules
+    pub sanitize_responses: Option<bool>,
+    /// Set for 'prefer_region' rules
+    pub prefer_region: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
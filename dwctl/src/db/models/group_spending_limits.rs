@@ -0,0 +1,25 @@
+//! Database models for per-group spending limits.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+/// Database request for setting a group's spending limit. Replaces any existing limit.
+#[derive(Debug, Clone)]
+pub struct GroupSpendingLimitDBRequest {
+    pub spending_limit: Decimal,
+    pub spending_limit_interval: Option<String>,
+}
+
+/// A group's spending limit and its current window's spend
+#[derive(Debug, Clone)]
+pub struct GroupSpendingLimitDBResponse {
+    pub spending_limit: Decimal,
+    pub spending_limit_interval: Option<String>,
+    /// Spend counted in the current window; 0 once the window has rolled but
+    /// no request has been folded into the new one yet.
+    pub spent: Decimal,
+    /// Next calendar boundary for windowed limits; `None` for one-off limits.
+    pub resets_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -49,3 +49,14 @@ pub struct GroupDBResponse {
     pub updated_at: DateTime<Utc>,
     pub source: String,
 }
+
+/// Outcome of adding one member in a batch membership operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MembershipAddOutcome {
+    /// The member was added to the group
+    Added,
+    /// The member was already in the group; nothing changed
+    AlreadyMember,
+    /// No such (non-deleted) user or deployment
+    NotFound,
+}
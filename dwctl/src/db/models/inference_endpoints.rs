@@ -1,9 +1,99 @@
 //! Database models for inference endpoints.
 
+use crate::body_transform::BodyTransformConfig;
 use crate::reasoning::ReasoningTranslationConfig;
 use crate::types::{InferenceEndpointId, UserId};
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use url::Url;
+use utoipa::ToSchema;
+
+/// How requests to an endpoint are authenticated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EndpointProtocol {
+    /// OpenAI-compatible API authenticated with `api_key` in a header
+    #[default]
+    #[serde(rename = "openai")]
+    OpenAi,
+    /// AWS Bedrock's OpenAI-compatible API, authenticated with SigV4
+    Bedrock,
+}
+
+impl EndpointProtocol {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EndpointProtocol::OpenAi => "openai",
+            EndpointProtocol::Bedrock => "bedrock",
+        }
+    }
+}
+
+impl std::str::FromStr for EndpointProtocol {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "openai" => Ok(EndpointProtocol::OpenAi),
+            "bedrock" => Ok(EndpointProtocol::Bedrock),
+            other => Err(anyhow::anyhow!("unknown endpoint protocol: {other}")),
+        }
+    }
+}
+
+/// How requests pick between an endpoint's upstream API keys
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyRotation {
+    /// Each key in turn
+    #[default]
+    RoundRobin,
+    /// The key used longest ago
+    LeastRecentlyUsed,
+}
+
+impl ApiKeyRotation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyRotation::RoundRobin => "round_robin",
+            ApiKeyRotation::LeastRecentlyUsed => "least_recently_used",
+        }
+    }
+}
+
+impl std::str::FromStr for ApiKeyRotation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "round_robin" => Ok(ApiKeyRotation::RoundRobin),
+            "least_recently_used" => Ok(ApiKeyRotation::LeastRecentlyUsed),
+            other => Err(anyhow::anyhow!("unknown API key rotation: {other}")),
+        }
+    }
+}
+
+/// AWS credentials for a Bedrock endpoint. The secret is stored encrypted with
+/// the connections encryption key and only decrypted when building onwards config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BedrockEndpointConfig {
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key_encrypted: Vec<u8>,
+}
+
+/// TLS settings for connecting to an endpoint behind a private CA or requiring
+/// mutual TLS. The client key is stored encrypted with the connections
+/// encryption key; the certificates are PEM text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EndpointTlsConfig {
+    /// Extra root certificates to trust, in addition to the system roots
+    pub ca_bundle: Option<String>,
+    /// Client certificate chain presented for mutual TLS
+    pub client_cert: Option<String>,
+    /// PKCS#8 private key for `client_cert`; set exactly when it is
+    pub client_key_encrypted: Option<Vec<u8>>,
+}
 
 /// Database request for creating a new inference endpoint
 #[derive(Debug, Clone)]
@@ -17,6 +107,24 @@ pub struct InferenceEndpointCreateDBRequest {
     pub auth_header_name: Option<String>,
     pub auth_header_prefix: Option<String>,
     pub reasoning_translation: Option<ReasoningTranslationConfig>,
+    /// Set for Bedrock endpoints, which are stored with the Bedrock protocol
+    pub bedrock: Option<BedrockEndpointConfig>,
+    /// Region label used for region-aware routing between model components
+    pub region: Option<String>,
+    /// Declarative request/response body edits for this endpoint's models
+    pub body_transform: Option<BodyTransformConfig>,
+    /// Re-sync the endpoint's models this often; None disables auto-sync
+    pub auto_sync_interval_seconds: Option<i32>,
+    /// Template for aliases of models created by sync; None uses the model id
+    pub alias_template: Option<String>,
+    /// Concurrent requests allowed across all the endpoint's deployments; None is unlimited
+    pub max_concurrency: Option<i32>,
+    /// Custom CA and client certificate for connecting to the endpoint
+    pub tls: Option<EndpointTlsConfig>,
+    /// Keys rotated between with `api_key`
+    pub additional_api_keys: Vec<String>,
+    /// None uses round-robin
+    pub api_key_rotation: Option<ApiKeyRotation>,
 }
 
 /// Database request for updating an inference endpoint
@@ -31,6 +139,24 @@ pub struct InferenceEndpointUpdateDBRequest {
     pub auth_header_prefix: Option<String>,
     /// None leaves the value unchanged; Some(None) clears it.
     pub reasoning_translation: Option<Option<ReasoningTranslationConfig>>,
+    /// Replace the Bedrock credentials (only valid on Bedrock endpoints)
+    pub bedrock: Option<BedrockEndpointConfig>,
+    /// None leaves the value unchanged; Some(None) clears it.
+    pub region: Option<Option<String>>,
+    /// None leaves the value unchanged; Some(None) clears it.
+    pub body_transform: Option<Option<BodyTransformConfig>>,
+    /// None leaves the value unchanged; Some(None) disables auto-sync.
+    pub auto_sync_interval_seconds: Option<Option<i32>>,
+    /// None leaves the value unchanged; Some(None) clears it.
+    pub alias_template: Option<Option<String>>,
+    /// None leaves the value unchanged; Some(None) removes the limit.
+    pub max_concurrency: Option<Option<i32>>,
+    /// None leaves the value unchanged; Some replaces all TLS settings and
+    /// Some(None) clears them.
+    pub tls: Option<Option<EndpointTlsConfig>>,
+    /// None leaves the keys unchanged; an empty list removes them.
+    pub additional_api_keys: Option<Vec<String>>,
+    pub api_key_rotation: Option<ApiKeyRotation>,
 }
 
 /// Database response for an inference endpoint
@@ -45,6 +171,16 @@ pub struct InferenceEndpointDBResponse {
     pub auth_header_name: String,
     pub auth_header_prefix: String,
     pub reasoning_translation: Option<ReasoningTranslationConfig>,
+    pub protocol: EndpointProtocol,
+    pub bedrock: Option<BedrockEndpointConfig>,
+    pub region: Option<String>,
+    pub body_transform: Option<BodyTransformConfig>,
+    pub auto_sync_interval_seconds: Option<i32>,
+    pub alias_template: Option<String>,
+    pub max_concurrency: Option<i32>,
+    pub tls: Option<EndpointTlsConfig>,
+    pub additional_api_keys: Vec<String>,
+    pub api_key_rotation: ApiKeyRotation,
     pub created_by: UserId,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
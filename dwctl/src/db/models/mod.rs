@@ -20,16 +20,23 @@
 //! - [`groups`]: Group definitions and user-group memberships
 //! - [`deployments`]: Model deployment configurations and routing
 //! - [`inference_endpoints`]: Backend inference service endpoints
+//! - [`deployment_shadows`]: Shadow traffic copied from one deployment to another
 //!
 //! ## Access Control
 //!
+//! - [`access_templates`]: Named sets of deployments that can be granted to groups
+//! - [`access_requests`]: Users' requests for access to deployments that require approval
 //! - [`api_keys`]: API keys for programmatic access
 //! - [`password_reset_tokens`]: Time-limited password reset tokens
+//! - [`rate_limits`]: Per-user rate-limit overrides and per-group defaults
+//! - [`sessions`]: Native login sessions
+//! - [`group_spending_limits`]: Per-group spending limits
 //!
 //! ## Operations
 //!
 //! - [`credits`]: Credit balance tracking and transaction history
 //! - [`probes`]: Health probe definitions and execution results
+//! - [`archived_batches`]: Batches removed by the batch retention job
 //!
 //! # Conversion to API Models
 //!
@@ -60,16 +67,23 @@
 //! }
 //! ```
 
+pub mod access_requests;
+pub mod access_templates;
 pub mod api_keys;
+pub mod archived_batches;
 pub mod connections;
 pub mod credits;
+pub mod deployment_shadows;
 pub mod deployments;
+pub mod group_spending_limits;
 pub mod groups;
 pub mod inference_endpoints;
 pub mod organizations;
 pub mod password_reset_tokens;
 pub mod probes;
 pub mod provider_display_configs;
+pub mod rate_limits;
+pub mod sessions;
 pub mod tariffs;
 pub mod tool_sources;
 pub mod users;
@@ -33,6 +33,8 @@ pub struct OrganizationUpdateDBRequest {
     pub low_balance_threshold: Option<Option<f32>>,
     /// Account-wide zero-data-retention flag. `None` = don't change.
     pub zero_data_retention: Option<bool>,
+    /// IANA timezone name for calendar-aligned quota windows. `None` = don't change.
+    pub timezone: Option<String>,
 }
 
 /// Database response for an organization membership
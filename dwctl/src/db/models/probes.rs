@@ -31,6 +31,19 @@ pub struct Probe {
     pub request_path: Option<String>,
     /// JSON body to send with the probe request
     pub request_body: Option<serde_json::Value>,
+    /// Assertions the response must satisfy for the probe to pass
+    #[sqlx(json(nullable))]
+    pub assertions: Option<ProbeAssertions>,
+    /// What the probe sends and how its response is judged
+    pub probe_type: ProbeType,
+    /// For `chat_completion` probes, the `model` the response must report
+    pub expected_model: Option<String>,
+    /// Consecutive failures that mark a healthy probe unhealthy
+    pub failure_threshold: i32,
+    /// Consecutive successes that mark an unhealthy probe healthy again
+    pub success_threshold: i32,
+    /// Minimum seconds a probe stays unhealthy before it can recover
+    pub recovery_cooldown_seconds: i32,
     /// When the probe was created
     #[schema(value_type = String, format = "date-time")]
     pub created_at: DateTime<Utc>,
@@ -39,6 +52,73 @@ pub struct Probe {
     pub updated_at: DateTime<Utc>,
 }
 
+/// What a probe sends and how its response is judged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema, sqlx::Type)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ProbeType {
+    /// The model type's default request (or the configured custom request),
+    /// passing on any 2xx response with a non-error JSON body.
+    #[default]
+    Liveness,
+    /// A tiny canned chat completion that must return a well-formed completion
+    /// with non-empty content. Ignores the custom request settings.
+    ChatCompletion,
+}
+
+/// Assertions checked against a probe response.
+///
+/// Without assertions a probe passes on any 2xx response with a non-error JSON
+/// body. Every configured assertion must hold for the probe to pass; the first
+/// one that fails is recorded in the result.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ProbeAssertions {
+    /// Exact HTTP status code the response must have. Replaces the default 2xx check.
+    pub expected_status: Option<i32>,
+    /// JSON Pointer checks against the response body
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub json_paths: Vec<JsonPathAssertion>,
+    /// Maximum acceptable response time in milliseconds
+    pub max_response_time_ms: Option<i32>,
+}
+
+/// A check on one field of the response body.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonPathAssertion {
+    /// JSON Pointer (RFC 6901) to the field, e.g. `/choices/0/message/content`
+    pub path: String,
+    /// Value the field must equal. When omitted the field only has to exist.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equals: Option<serde_json::Value>,
+}
+
+impl ProbeAssertions {
+    /// Check the assertions are well-formed, returning a message suitable for a 400.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(status) = self.expected_status
+            && !(100..=599).contains(&status)
+        {
+            return Err(format!("expected_status must be a valid HTTP status code, got {status}"));
+        }
+        if let Some(budget) = self.max_response_time_ms
+            && budget <= 0
+        {
+            return Err(format!("max_response_time_ms must be positive, got {budget}"));
+        }
+        for assertion in &self.json_paths {
+            if !assertion.path.is_empty() && !assertion.path.starts_with('/') {
+                return Err(format!(
+                    "json_paths entry '{}' must be a JSON Pointer starting with '/'",
+                    assertion.path
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
 /// A stored result from executing a probe.
 ///
 /// Results are persisted to the database and used to calculate statistics
@@ -68,6 +148,22 @@ pub struct ProbeResult {
     pub metadata: Option<serde_json::Value>,
 }
 
+/// Debounced health of a probe, updated by the scheduler after each result.
+///
+/// See [`crate::probes::health`] for how results move a probe between states.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ProbeHealth {
+    /// Whether the probe is currently considered healthy
+    pub healthy: bool,
+    /// Successes in a row, reset by a failure
+    pub consecutive_successes: i32,
+    /// Failures in a row, reset by a success
+    pub consecutive_failures: i32,
+    /// When `healthy` last changed
+    #[schema(value_type = String, format = "date-time")]
+    pub changed_at: DateTime<Utc>,
+}
+
 /// In-memory representation of a probe execution before it's stored.
 ///
 /// This is the result of running a probe, which gets converted to a
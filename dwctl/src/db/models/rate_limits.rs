@@ -0,0 +1,35 @@
+//! Database models for user rate-limit overrides and group rate-limit defaults.
+
+use chrono::{DateTime, Utc};
+
+/// Database request for setting a user's or group's limits. Replaces any
+/// existing limits; `None` leaves that limit to the next level.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimitsDBRequest {
+    pub requests_per_second: Option<f32>,
+    pub burst_size: Option<i32>,
+    pub concurrency_limit: Option<i32>,
+}
+
+/// The admin-set limits that apply to a user's API keys: the user's override
+/// and, for each limit, the highest default among their groups
+#[derive(Debug, Clone, Default)]
+pub struct OwnerRateLimitsDBResponse {
+    pub verified: bool,
+    pub user_requests_per_second: Option<f32>,
+    pub user_burst_size: Option<i32>,
+    pub user_concurrency_limit: Option<i32>,
+    pub group_requests_per_second: Option<f32>,
+    pub group_burst_size: Option<i32>,
+    pub group_concurrency_limit: Option<i32>,
+}
+
+/// Database response for a user's or group's limits
+#[derive(Debug, Clone)]
+pub struct RateLimitsDBResponse {
+    pub requests_per_second: Option<f32>,
+    pub burst_size: Option<i32>,
+    pub concurrency_limit: Option<i32>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,16 @@
+//! Database models for native login sessions.
+
+use crate::types::UserId;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// A login session; its id is the `sid` claim of the session JWT
+#[derive(Debug, Clone)]
+pub struct SessionDBResponse {
+    pub id: Uuid,
+    pub user_id: UserId,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
@@ -18,7 +18,7 @@ pub struct ModelTariff {
     pub valid_from: DateTime<Utc>,
     pub valid_until: Option<DateTime<Utc>>,
     /// Optional API key purpose this tariff applies to
-    /// If None, tariff is not automatically applied (legacy/manual pricing)
+    /// If None, this is the model's default tariff, applied to purposes without their own
     /// If Some(purpose), tariff applies when model is accessed via that purpose
     pub api_key_purpose: Option<ApiKeyPurpose>,
     /// Optional completion window (priority) for batch tariffs (e.g., "24h", "1h")
@@ -56,6 +56,8 @@ pub struct UserUpdateDBRequest {
     pub auto_topup_monthly_limit: Option<Option<f32>>,
     /// Account-wide zero-data-retention flag. `None` = don't change.
     pub zero_data_retention: Option<bool>,
+    /// IANA timezone name for calendar-aligned quota windows. `None` = don't change.
+    pub timezone: Option<String>,
 }
 
 impl UserUpdateDBRequest {
@@ -71,6 +73,7 @@ impl UserUpdateDBRequest {
             auto_topup_threshold: update.auto_topup_threshold,
             auto_topup_monthly_limit: update.auto_topup_monthly_limit,
             zero_data_retention: update.zero_data_retention,
+            timezone: update.timezone,
         }
     }
 }
@@ -107,4 +110,6 @@ pub struct UserDBResponse {
     pub user_type: String,
     /// Account-wide zero-data-retention flag.
     pub zero_data_retention: bool,
+    /// IANA timezone name for calendar-aligned quota windows.
+    pub timezone: String,
 }
@@ -36,7 +36,8 @@ impl Webhook {
     /// Enforces scope matching: an own-scoped webhook only receives own-scope events,
     /// a platform-scoped webhook only receives platform-scope events. This means
     /// `event_types = null` (accept all) is scoped — it never leaks events across
-    /// scope boundaries.
+    /// scope boundaries. The exception is API key events, which an own-scoped
+    /// webhook receives only when it lists them in `event_types`.
     pub fn accepts_event(&self, event_type: WebhookEventType) -> bool {
         if !self.enabled {
             return false;
@@ -48,13 +49,13 @@ impl Webhook {
         } else {
             crate::webhooks::WebhookScope::Own
         };
-        if event_type.scope() != webhook_scope {
+        if !event_type.subscribable_from(webhook_scope) {
             return false;
         }
 
         // If event_types is null, accept all events within this scope
         let Some(ref types) = self.event_types else {
-            return true;
+            return event_type.scope() == webhook_scope;
         };
 
         if let Some(arr) = types.as_array() {
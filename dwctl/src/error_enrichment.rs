@@ -21,20 +21,40 @@
 //!    - Shows current balance
 //! 2. **403 Forbidden - Model Access Denied**: User is not a member of a group with access to the requested model
 //!    - Shows which model was requested
+//!    - With `onwards.unauthorized_models: not_found` (the default) this is
+//!      answered with the same 404 `model_not_found` as an unknown model, so
+//!      callers can't discover models they have no access to. So is every
+//!      other 401/403 for a model the key can't use: a missing, invalid or
+//!      non-inference key, a model outside the key's allow/deny lists, or a
+//!      routing rule denying the key's purpose
 //! 3. **403 Forbidden - Modality Blocked**: A traffic routing rule denies the API key's
 //!    purpose (realtime/batch/playground) for the requested model
 //!    - Shows which modality and model are blocked
+//! 4. **402 Payment Required - Group Spending Limit**: A group of the key's user
+//!    has spent its limit for the current window
+//!    - Shows the group, its limit and the spend
+//!
+//! ## Reason Codes
+//!
+//! Every 403/404 this middleware inspects, and every response it rewrites,
+//! carries an [`ERROR_REASON_HEADER`] naming the cause (see [`reason`]). The
+//! body formats are unchanged, so existing clients are unaffected. A 403 that
+//! none of the database checks explain is reported as
+//! [`reason::KEY_NOT_IN_CACHE`]: the key is valid but the proxy's key set
+//! doesn't include it yet (e.g. it was created moments ago).
 
 use crate::{
+    config::UnauthorizedModelResponse,
     db::errors::DbError,
-    db::handlers::{Credits, api_keys::ApiKeys},
+    db::handlers::{Credits, GroupSpendingLimits, api_keys::ApiKeys},
+    db::models::group_spending_limits::GroupSpendingLimitDBResponse,
     errors::Error,
     types::UserId,
 };
 use axum::{
     body::Body,
     extract::State,
-    http::{Request, Response, StatusCode},
+    http::{HeaderValue, Request, Response, StatusCode},
     middleware::Next,
     response::IntoResponse,
 };
@@ -43,6 +63,78 @@ use serde::Deserialize;
 use sqlx::PgPool;
 use tracing::{debug, instrument};
 
+/// Response header naming why the AI proxy rejected a request.
+pub const ERROR_REASON_HEADER: &str = "x-dwctl-error-reason";
+
+/// Values of the [`ERROR_REASON_HEADER`].
+pub mod reason {
+    /// The key was used for something other than inference (e.g. a `platform` key).
+    pub const NON_INFERENCE_KEY: &str = "non_inference_key";
+    /// The key's user is not in a group with access to the model.
+    pub const NO_GROUP_ACCESS: &str = "no_group_access";
+    /// A traffic routing rule denies the key's purpose for the model.
+    pub const MODALITY_BLOCKED: &str = "modality_blocked";
+    /// The key's user has no credits left.
+    pub const INSUFFICIENT_CREDITS: &str = "insufficient_credits";
+    /// The key's spending cap is exhausted for the current window.
+    pub const SPEND_CAP_EXCEEDED: &str = "spend_cap_exceeded";
+    /// The spending cap window has rolled and the key is being reinstated.
+    pub const SPEND_CAP_RESET_PENDING: &str = "spend_cap_reset_pending";
+    /// A group of the key's user has reached its spending limit.
+    pub const GROUP_SPENDING_LIMIT_EXCEEDED: &str = "group_spending_limit_exceeded";
+    /// The key is valid and nothing in the database explains the rejection,
+    /// so the proxy's key set doesn't include it yet.
+    pub const KEY_NOT_IN_CACHE: &str = "key_not_in_cache";
+    /// The key does not exist or has been deleted.
+    pub const INVALID_API_KEY: &str = "invalid_api_key";
+    /// The proxy has no model with the requested name.
+    pub const MODEL_UNKNOWN: &str = "model_unknown";
+}
+
+/// Tag a response with a reason code.
+fn with_reason(mut response: Response<Body>, reason: &'static str) -> Response<Body> {
+    response.headers_mut().insert(ERROR_REASON_HEADER, HeaderValue::from_static(reason));
+    response
+}
+
+/// State for [`error_enrichment_middleware`].
+#[derive(Clone)]
+pub struct ErrorEnrichmentState {
+    pub pool: PgPool,
+    /// Read per request for how a request for a model the key's user has no
+    /// access to is answered (`onwards.unauthorized_models`), so reloads apply.
+    pub config: crate::SharedConfig,
+}
+
+/// The response to a request for `model` from a caller with no access to it:
+/// a 404 indistinguishable from an unknown model, or a 403 naming the model.
+pub fn unauthorized_model_response(model: &str, mode: UnauthorizedModelResponse) -> Response<Body> {
+    match mode {
+        UnauthorizedModelResponse::NotFound => with_reason(model_not_found(model), reason::MODEL_UNKNOWN),
+        UnauthorizedModelResponse::Forbidden => {
+            let response = Error::ModelAccessDenied {
+                model_name: model.to_string(),
+                message: format!("You do not have access to '{model}'. Please contact your administrator to request access."),
+            }
+            .into_response();
+            with_reason(response, reason::NO_GROUP_ACCESS)
+        }
+    }
+}
+
+/// 404 `model_not_found`, with the body onwards sends for a model it doesn't know.
+pub fn model_not_found(model: &str) -> Response<Body> {
+    let body = serde_json::json!({
+        "error": {
+            "message": format!("The model `{model}` does not exist or you do not have access to it."),
+            "type": "invalid_request_error",
+            "param": null,
+            "code": "model_not_found",
+        }
+    });
+    (StatusCode::NOT_FOUND, axum::Json(body)).into_response()
+}
+
 /// Request body structure for extracting model name
 #[derive(Debug, Deserialize)]
 struct ChatRequest {
@@ -54,12 +146,20 @@ struct ChatRequest {
 /// Currently handles:
 /// - 403 Forbidden errors (likely insufficient credits) → enriched with balance
 /// - 403 Forbidden errors (likely model access denied) → enriched with model name
+/// - With `onwards.unauthorized_models: not_found`, any 401/403 for a model the
+///   key can't use → 404 `model_not_found`, as for an unknown model
 /// - 403 Forbidden errors (likely modality blocked by routing rule) → enriched with modality + model
 /// - 403 Forbidden errors (spending cap exhausted) → rewritten to 402 with cap details
 /// - 403 Forbidden errors (cap window rolled, reinstatement pending) → retriable 429
 ///   plus a demand-driven config resync so the retry succeeds within seconds
+/// - 403 Forbidden errors (a group's spending limit reached) → rewritten to 402
+///   with the group and its limit
+/// - 403 Forbidden errors nothing above explains → tagged `key_not_in_cache`
+///   (or `invalid_api_key` when the key doesn't exist)
+/// - 404 Not Found errors for a named model → tagged `model_unknown`
 #[instrument(name = "dwctl.error_enrichment", skip_all, fields(http.request.method = %request.method(), url.path = %request.uri().path(), url.query = request.uri().query().unwrap_or("")))]
-pub async fn error_enrichment_middleware(State(pool): State<PgPool>, request: Request<Body>, next: Next) -> Response<Body> {
+pub async fn error_enrichment_middleware(State(state): State<ErrorEnrichmentState>, request: Request<Body>, next: Next) -> Response<Body> {
+    let pool = state.pool;
     // Extract API key from request headers before passing to onwards
     let api_key = request
         .headers()
@@ -87,6 +187,23 @@ pub async fn error_enrichment_middleware(State(pool): State<PgPool>, request: Re
     // Let the request proceed through onwards
     let response = next.run(reconstructed).await;
 
+    // Onwards 404s an inference request only when no target matches the model.
+    if response.status() == StatusCode::NOT_FOUND && model_name.is_some() {
+        return with_reason(response, reason::MODEL_UNKNOWN);
+    }
+
+    // Onwards checks the model before the key, so any rejection of a key that
+    // can't use the model would tell an existing model from an unknown one.
+    // Answer them all like an unknown model. On a lookup error we can't tell,
+    // so fall through.
+    if matches!(response.status(), StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN)
+        && let Some(model) = &model_name
+        && state.config.snapshot().onwards.unauthorized_models == UnauthorizedModelResponse::NotFound
+        && let Ok(false) = key_may_use_model(pool.clone(), api_key.as_deref(), model).await
+    {
+        return unauthorized_model_response(model, UnauthorizedModelResponse::NotFound);
+    }
+
     // Only enrich 403 errors when we have an API key
     // Note: This middleware is applied only to the onwards router (AI proxy paths),
     // so no path filtering is needed here
@@ -98,7 +215,8 @@ pub async fn error_enrichment_middleware(State(pool): State<PgPool>, request: Re
         // One lookup of the key's owner + purpose, reused by the checks below so
         // a 403 does not fan out into several by-secret queries. None for an
         // unknown/invalid token, in which case the checks fall through.
-        let key_info = get_api_key_user_and_purpose(pool.clone(), &key).await.ok().flatten();
+        let key_lookup = get_api_key_user_and_purpose(pool.clone(), &key).await;
+        let key_info = key_lookup.as_ref().ok().cloned().flatten();
 
         // Order matters: the more fundamental the failure, the earlier it runs, so
         // when several conditions could explain the 403 we surface the one that's
@@ -116,6 +234,8 @@ pub async fn error_enrichment_middleware(State(pool): State<PgPool>, request: Re
         //   4. Spending cap — onwards excludes every key of a cap scope whose
         //      window spend reached the limit; only reported when balance is
         //      healthy.
+        //   5. Group spending limit — onwards excludes every key of a group's
+        //      members once the group's window spend reached its limit.
 
         // 0. Non-inference key: explain why an otherwise-valid key was rejected,
         //    rather than leaving onwards' generic "forbidden" body.
@@ -128,11 +248,12 @@ pub async fn error_enrichment_middleware(State(pool): State<PgPool>, request: Re
                     "type": "invalid_request_error"
                 }
             });
-            return Response::builder()
+            let response = Response::builder()
                 .status(StatusCode::FORBIDDEN)
                 .header("content-type", "application/json")
                 .body(Body::from(body.to_string()))
                 .unwrap_or_else(|_| StatusCode::FORBIDDEN.into_response());
+            return with_reason(response, reason::NON_INFERENCE_KEY);
         }
 
         // 1. Model access via group membership
@@ -141,37 +262,32 @@ pub async fn error_enrichment_middleware(State(pool): State<PgPool>, request: Re
             && let Ok(has_access) = check_user_has_model_access(pool.clone(), *user_id, model).await
             && !has_access
         {
-            return Error::ModelAccessDenied {
-                model_name: model.clone(),
-                message: format!(
-                    "You do not have access to '{}'. Please contact your administrator to request access.",
-                    model
-                ),
-            }
-            .into_response();
+            return unauthorized_model_response(model, state.config.snapshot().onwards.unauthorized_models);
         }
 
         // 2. Modality blocked by a traffic routing rule on this model.
         if let Some(model) = &model_name
             && let Ok(Some(purpose)) = check_modality_blocked(pool.clone(), &key, model).await
         {
-            return Error::ModalityAccessDenied {
+            let response = Error::ModalityAccessDenied {
                 model_name: model.clone(),
                 purpose: purpose.clone(),
                 message: modality_blocked_message(&purpose, model),
             }
             .into_response();
+            return with_reason(response, reason::MODALITY_BLOCKED);
         }
 
         // 3. Insufficient balance.
         if let Ok(balance) = get_balance_of_api_key(pool.clone(), &key).await
             && balance <= Decimal::ZERO
         {
-            return Error::InsufficientCredits {
+            let response = Error::InsufficientCredits {
                 current_balance: balance,
                 message: "Account balance too low. Please add credits to continue.".to_string(),
             }
             .into_response();
+            return with_reason(response, reason::INSUFFICIENT_CREDITS);
         }
 
         // 4. Spending cap. Read-only against the same checkpoint state and
@@ -200,11 +316,12 @@ pub async fn error_enrichment_middleware(State(pool): State<PgPool>, request: Re
                         "param": null
                     }
                 });
-                return Response::builder()
+                let response = Response::builder()
                     .status(StatusCode::PAYMENT_REQUIRED)
                     .header("content-type", "application/json")
                     .body(Body::from(body.to_string()))
                     .unwrap_or_else(|_| StatusCode::PAYMENT_REQUIRED.into_response());
+                return with_reason(response, reason::SPEND_CAP_EXCEEDED);
             }
 
             // The window has ROLLED but the sync hasn't readmitted the scope
@@ -234,13 +351,52 @@ pub async fn error_enrichment_middleware(State(pool): State<PgPool>, request: Re
                     "param": null
                 }
             });
-            return Response::builder()
+            let response = Response::builder()
                 .status(StatusCode::TOO_MANY_REQUESTS)
                 .header("content-type", "application/json")
                 .header("retry-after", "5")
                 .body(Body::from(body.to_string()))
                 .unwrap_or_else(|_| StatusCode::TOO_MANY_REQUESTS.into_response());
+            return with_reason(response, reason::SPEND_CAP_RESET_PENDING);
+        }
+
+        // 5. Group spending limit, from the same checkpoint state the sync
+        //    predicate reads.
+        if let Some((user_id, _)) = key_info.as_ref()
+            && let Ok(Some((group_name, limit))) = get_exhausted_group_limit(pool.clone(), *user_id).await
+        {
+            let resets = match limit.resets_at {
+                Some(at) => format!("; resets {}", at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+                None => String::new(),
+            };
+            let body = serde_json::json!({
+                "error": {
+                    "message": format!(
+                        "Your group '{group_name}' has reached its spending limit of ${} (spent ${} this period{resets}). Ask an administrator to raise it to resume.",
+                        limit.spending_limit.round_dp(2),
+                        limit.spent.round_dp(2),
+                    ),
+                    "type": "insufficient_quota",
+                    "code": "group_spending_limit_exceeded",
+                    "param": null
+                }
+            });
+            let response = Response::builder()
+                .status(StatusCode::PAYMENT_REQUIRED)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap_or_else(|_| StatusCode::PAYMENT_REQUIRED.into_response());
+            return with_reason(response, reason::GROUP_SPENDING_LIMIT_EXCEEDED);
         }
+
+        // Nothing in the database explains the 403. A known key is most likely
+        // missing from onwards' key set (not synced yet); an unknown one is
+        // simply invalid. On a lookup error we can't tell, so leave it untagged.
+        return match key_lookup {
+            Ok(Some(_)) => with_reason(response, reason::KEY_NOT_IN_CACHE),
+            Ok(None) => with_reason(response, reason::INVALID_API_KEY),
+            Err(_) => response,
+        };
     }
 
     response
@@ -300,7 +456,7 @@ async fn maybe_fire_boundary_resync(pool: &PgPool) {
 struct SpendCapState {
     limit: Decimal,
     window_spend: Decimal,
-    /// Whether `window_started_at` falls in the current calendar window (UTC).
+    /// Whether `window_started_at` falls in the current calendar window (owner's timezone).
     /// False for an exhausted-but-rolled window = reinstatement pending.
     window_current: bool,
     /// Next calendar boundary for windowed caps; `None` for one-off caps.
@@ -313,10 +469,11 @@ async fn get_spend_cap_state(pool: PgPool, api_key: &str) -> Result<Option<Spend
         r#"
         SELECT root.spend_limit AS "spend_limit!",
                COALESCE(ck.window_spend, 0) AS "window_spend!",
-               api_key_cap_window_current(ck.window_started_at, root.spend_limit_interval) AS "window_current!",
-               api_key_cap_window_resets_at(root.spend_limit_interval) AS resets_at
+               api_key_cap_window_current(ck.window_started_at, root.spend_limit_interval, owner.timezone) AS "window_current!",
+               api_key_cap_window_resets_at(root.spend_limit_interval, owner.timezone) AS resets_at
         FROM api_keys ak
         JOIN api_keys root ON root.id = COALESCE(ak.parent_api_key_id, ak.id)
+        JOIN users owner ON owner.id = root.user_id
         LEFT JOIN api_key_spend_checkpoints ck ON ck.api_key_id = root.id
         WHERE ak.secret = $1 AND ak.is_deleted = false AND root.spend_limit IS NOT NULL
         "#,
@@ -333,6 +490,13 @@ async fn get_spend_cap_state(pool: PgPool, api_key: &str) -> Result<Option<Spend
     }))
 }
 
+/// A group of the user whose spending limit is exhausted for the current window, with its name.
+#[instrument(skip_all, name = "dwctl.get_exhausted_group_limit")]
+async fn get_exhausted_group_limit(pool: PgPool, user_id: UserId) -> Result<Option<(String, GroupSpendingLimitDBResponse)>, DbError> {
+    let mut conn = pool.acquire().await?;
+    GroupSpendingLimits::new(&mut conn).find_exhausted_for_user(user_id).await
+}
+
 /// Render an `api_keys.purpose` value as a user-facing modality label.
 ///
 /// Returns owned `String` so unknown purposes can fall back to a capitalised
@@ -426,6 +590,52 @@ pub async fn check_modality_blocked_for_purpose(pool: PgPool, model_alias: &str,
     Ok(found)
 }
 
+/// Whether `api_key` may call `model` at all, as onwards would enforce it: an
+/// inference-purpose key of a user with access to the model, the model inside
+/// the key's allow/deny lists, and no deny rule for the key's purpose. Credits
+/// and spending limits don't depend on the model, so they aren't checked.
+#[instrument(skip_all, name = "dwctl.key_may_use_model")]
+async fn key_may_use_model(pool: PgPool, api_key: Option<&str>, model: &str) -> Result<bool, DbError> {
+    let Some(api_key) = api_key else {
+        return Ok(false);
+    };
+    let Some((user_id, purpose)) = get_api_key_user_and_purpose(pool.clone(), api_key).await? else {
+        return Ok(false);
+    };
+    // The system key is exempt from the purpose gate, as in the onwards key sync
+    if user_id != uuid::Uuid::nil() && !crate::db::models::api_keys::is_inference_purpose(&purpose) {
+        return Ok(false);
+    }
+
+    // The lists are the scope root's, as in the onwards sync, so cap-scope
+    // child keys inherit their parent's.
+    let mut conn = pool.acquire().await?;
+    let in_scope = sqlx::query_scalar!(
+        r#"
+        SELECT NOT EXISTS (
+            SELECT 1
+            FROM api_keys ak
+            JOIN api_keys scope ON scope.id = COALESCE(ak.parent_api_key_id, ak.id)
+            JOIN deployed_models dm ON dm.alias = $2 AND dm.deleted = false
+            WHERE ak.secret = $1
+              AND (
+                  (scope.allowed_model_ids IS NOT NULL AND NOT (dm.id = ANY(scope.allowed_model_ids)))
+                  OR dm.id = ANY(COALESCE(scope.denied_model_ids, '{}'))
+              )
+        ) AS "in_scope!"
+        "#,
+        api_key,
+        model
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    drop(conn);
+
+    Ok(in_scope
+        && check_user_has_model_access(pool.clone(), user_id, model).await?
+        && check_modality_blocked(pool, api_key, model).await?.is_none())
+}
+
 #[instrument(skip_all, name = "dwctl.get_user_id_of_api_key")]
 pub async fn get_user_id_of_api_key(pool: PgPool, api_key: &str) -> Result<UserId, DbError> {
     let mut conn = pool.acquire().await?;
@@ -469,20 +679,34 @@ pub async fn get_balance_of_api_key(pool: PgPool, api_key: &str) -> Result<Decim
 pub async fn check_user_has_model_access(pool: PgPool, user_id: UserId, model_alias: &str) -> Result<bool, DbError> {
     let mut conn = pool.acquire().await?;
 
-    // Query to check if user has access to this deployment through group membership
+    // Query to check if user has access to this deployment through group membership or public access,
+    // and, for a model that requires approval, an approved access request
     let result = sqlx::query_scalar!(
         r#"
         SELECT EXISTS(
             SELECT 1
             FROM deployed_models d
-            JOIN deployment_groups dg ON dg.deployment_id = d.id
             WHERE d.alias = $1
               AND d.deleted = false
-              AND dg.group_id IN (
-                  SELECT ug.group_id FROM user_groups ug WHERE ug.user_id = $2
-                  UNION
-                  SELECT '00000000-0000-0000-0000-000000000000'::uuid
-                  WHERE $2 != '00000000-0000-0000-0000-000000000000'
+              AND (
+                  (d.allow_public AND $2 != '00000000-0000-0000-0000-000000000000'::uuid)
+                  OR EXISTS (
+                      SELECT 1 FROM deployment_groups dg
+                      WHERE dg.deployment_id = d.id
+                        AND dg.group_id IN (
+                            SELECT ug.group_id FROM user_groups ug WHERE ug.user_id = $2
+                            UNION
+                            SELECT '00000000-0000-0000-0000-000000000000'::uuid
+                            WHERE $2 != '00000000-0000-0000-0000-000000000000'
+                        )
+                  )
+              )
+              AND (
+                  NOT d.require_approval
+                  OR EXISTS (
+                      SELECT 1 FROM access_requests ar
+                      WHERE ar.deployment_id = d.id AND ar.user_id = $2 AND ar.status = 'approved'
+                  )
               )
         ) as "has_access!"
         "#,
@@ -495,21 +719,33 @@ pub async fn check_user_has_model_access(pool: PgPool, user_id: UserId, model_al
     Ok(result)
 }
 
+/// Why [`validate_api_key_model_access`] turned a request down.
+#[derive(Debug)]
+pub struct AccessRejection {
+    /// One of the [`reason`] codes; `None` when the check itself failed.
+    pub reason: Option<&'static str>,
+    /// User-facing error message.
+    pub message: String,
+}
+
 /// Validate that the bearer token's user is allowed to call the specified model.
 ///
 /// Checks both group-based access and modality (traffic routing rule) restrictions.
-/// Returns `Ok(())` if the request should be allowed, or a user-facing error message
-/// if not.
+/// Returns `Ok(())` if the request should be allowed, or the reason and a
+/// user-facing error message if not.
 ///
 /// Used by the inference middleware to fail fast on Flex requests that bypass
 /// `onwards` entirely — without the modality check here, a Batch-purpose key
 /// could send a Flex request and skip a deny rule onwards would have enforced.
-pub async fn validate_api_key_model_access(pool: PgPool, api_key: &str, model: &str) -> Result<(), String> {
+pub async fn validate_api_key_model_access(pool: PgPool, api_key: &str, model: &str) -> Result<(), AccessRejection> {
+    let reject = |reason: Option<&'static str>, message: String| AccessRejection { reason, message };
+
     // One by-secret lookup of the key's owner + purpose, reused below.
     let (user_id, purpose) = get_api_key_user_and_purpose(pool.clone(), api_key)
         .await
-        .map_err(|_| "Invalid API key".to_string())?
-        .ok_or_else(|| "Invalid API key".to_string())?;
+        .ok()
+        .flatten()
+        .ok_or_else(|| reject(Some(reason::INVALID_API_KEY), "Invalid API key".to_string()))?;
 
     // Reject non-inference keys (e.g. `platform`) up front. Flex bypasses
     // onwards entirely, so this is the only place the purpose gate - mirrored
@@ -519,17 +755,20 @@ pub async fn validate_api_key_model_access(pool: PgPool, api_key: &str, model: &
     // for inference, so it is exempt here, mirroring the onwards key-sync
     // exemption.
     if user_id != uuid::Uuid::nil() && !crate::db::models::api_keys::is_inference_purpose(&purpose) {
-        return Err(format!("API keys with purpose '{purpose}' cannot be used for inference requests."));
+        return Err(reject(
+            Some(reason::NON_INFERENCE_KEY),
+            format!("API keys with purpose '{purpose}' cannot be used for inference requests."),
+        ));
     }
 
     let has_access = check_user_has_model_access(pool.clone(), user_id, model)
         .await
-        .map_err(|e| format!("Failed to check model access: {e}"))?;
+        .map_err(|e| reject(None, format!("Failed to check model access: {e}")))?;
 
     if !has_access {
-        return Err(format!(
-            "You do not have access to '{}'. Please contact your administrator to request access.",
-            model
+        return Err(reject(
+            Some(reason::NO_GROUP_ACCESS),
+            format!("You do not have access to '{model}'. Please contact your administrator to request access."),
         ));
     }
 
@@ -540,9 +779,9 @@ pub async fn validate_api_key_model_access(pool: PgPool, api_key: &str, model: &
     // `onwards` enforces when the daemon later dispatches the request.
     if let Some(purpose) = check_modality_blocked_for_purpose(pool, model, FLEX_DISPATCH_PURPOSE)
         .await
-        .map_err(|e| format!("Failed to check modality routing rules: {e}"))?
+        .map_err(|e| reject(None, format!("Failed to check modality routing rules: {e}")))?
     {
-        return Err(modality_blocked_message(&purpose, model));
+        return Err(reject(Some(reason::MODALITY_BLOCKED), modality_blocked_message(&purpose, model)));
     }
 
     Ok(())
@@ -572,6 +811,16 @@ mod tests {
     use crate::{api::models::users::Role, test::utils::create_test_user};
     use rust_decimal::Decimal;
 
+    /// State that answers missing model access with the enriched 403.
+    fn forbidden_state(pool: &PgPool) -> ErrorEnrichmentState {
+        let mut config = crate::test::utils::create_test_config();
+        config.onwards.unauthorized_models = UnauthorizedModelResponse::Forbidden;
+        ErrorEnrichmentState {
+            pool: pool.clone(),
+            config: config.into(),
+        }
+    }
+
     /// Pure unit test: `modality_label` produces user-friendly labels for known
     /// purposes and capitalises unknown values rather than emitting a placeholder.
     #[test]
@@ -612,6 +861,9 @@ mod tests {
                 created_by: user.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             })
             .await
             .unwrap();
@@ -645,7 +897,7 @@ mod tests {
                 }),
             )
             .layer(axum::middleware::from_fn_with_state(
-                pool.clone(),
+                forbidden_state(&pool),
                 crate::error_enrichment::error_enrichment_middleware,
             ));
 
@@ -741,6 +993,9 @@ mod tests {
                 created_by: user.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             })
             .await
             .unwrap();
@@ -785,7 +1040,7 @@ mod tests {
                 }),
             )
             .layer(axum::middleware::from_fn_with_state(
-                pool.clone(),
+                forbidden_state(&pool),
                 crate::error_enrichment::error_enrichment_middleware,
             ));
         let server = axum_test::TestServer::new(router).expect("Failed to create test server");
@@ -944,6 +1199,9 @@ mod tests {
                 created_by: user.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             })
             .await
             .unwrap();
@@ -985,7 +1243,7 @@ mod tests {
                 }),
             )
             .layer(axum::middleware::from_fn_with_state(
-                pool.clone(),
+                forbidden_state(&pool),
                 crate::error_enrichment::error_enrichment_middleware,
             ));
 
@@ -1025,7 +1283,7 @@ mod tests {
                 }),
             )
             .layer(axum::middleware::from_fn_with_state(
-                pool.clone(),
+                forbidden_state(&pool),
                 crate::error_enrichment::error_enrichment_middleware,
             ));
 
@@ -1054,7 +1312,7 @@ mod tests {
                 }),
             )
             .layer(axum::middleware::from_fn_with_state(
-                pool.clone(),
+                forbidden_state(&pool),
                 crate::error_enrichment::error_enrichment_middleware,
             ));
 
@@ -1093,6 +1351,9 @@ mod tests {
                 created_by: user.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             })
             .await
             .unwrap();
@@ -1140,7 +1401,7 @@ mod tests {
                 }),
             )
             .layer(axum::middleware::from_fn_with_state(
-                pool.clone(),
+                forbidden_state(&pool),
                 crate::error_enrichment::error_enrichment_middleware,
             ));
 
@@ -1188,6 +1449,9 @@ mod tests {
                 created_by: user.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             })
             .await
             .unwrap();
@@ -1207,7 +1471,7 @@ mod tests {
         .unwrap();
 
         let result = validate_api_key_model_access(pool.clone(), &api_key.secret, "blocked-model").await;
-        let err = result.expect_err("expected modality-blocked rejection");
+        let err = result.expect_err("expected modality-blocked rejection").message;
         assert!(
             err.contains("Batch") && err.contains("blocked-model") && err.contains("administrator"),
             "expected modality-blocked message, got: {err}"
@@ -1247,6 +1511,9 @@ mod tests {
                 created_by: user.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             })
             .await
             .unwrap();
@@ -1295,6 +1562,9 @@ mod tests {
                 created_by: user.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             })
             .await
             .unwrap();
@@ -1317,6 +1587,8 @@ mod tests {
         let err = validate_api_key_model_access(pool.clone(), &api_key.secret, "no-batch")
             .await
             .expect_err("Flex request must be blocked by a batch deny rule (Flex runs as batch)");
+        assert_eq!(err.reason, Some(super::reason::MODALITY_BLOCKED));
+        let err = err.message;
         assert!(
             err.contains("Batch") && err.contains("no-batch") && err.contains("administrator"),
             "expected batch modality-blocked message, got: {err}"
@@ -1342,12 +1614,17 @@ mod tests {
                 created_by: user.id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             })
             .await
             .unwrap();
 
         let result = validate_api_key_model_access(pool.clone(), &api_key.secret, "any-model").await;
         let err = result.expect_err("expected platform-purpose rejection");
+        assert_eq!(err.reason, Some(super::reason::NON_INFERENCE_KEY));
+        let err = err.message;
         assert!(
             err.contains("platform") && err.contains("inference"),
             "expected non-inference purpose message, got: {err}"
@@ -1372,6 +1649,9 @@ mod tests {
                 created_by: uuid::Uuid::nil(),
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             })
             .await
             .unwrap();
@@ -1379,7 +1659,7 @@ mod tests {
         // The purpose gate must NOT fire for the system (nil) user. A
         // model-access error is acceptable - we only assert the key was not
         // rejected on purpose grounds.
-        if let Err(msg) = validate_api_key_model_access(pool.clone(), &api_key.secret, "any-model").await {
+        if let Err(AccessRejection { message: msg, .. }) = validate_api_key_model_access(pool.clone(), &api_key.secret, "any-model").await {
             assert!(
                 !msg.contains("cannot be used for inference requests"),
                 "system key must be exempt from the purpose gate, got: {msg}"
@@ -1388,6 +1668,333 @@ mod tests {
     }
 
     /// Integration test: Error enrichment middleware passes through when no auth header
+    /// Integration test: each rejection cause is tagged with its own reason code
+    /// in the `x-dwctl-error-reason` header.
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_error_enrichment_middleware_reason_codes(pool: PgPool) {
+        use super::{ERROR_REASON_HEADER, reason};
+        use crate::test::utils::{add_deployment_to_group, add_user_to_group, create_test_group};
+
+        let user = create_test_user(&pool, Role::StandardUser).await;
+
+        let mut api_key_conn = pool.acquire().await.unwrap();
+        let api_key = ApiKeys::new(&mut api_key_conn)
+            .create(&ApiKeyCreateDBRequest {
+                user_id: user.id,
+                name: "Test Key".to_string(),
+                description: None,
+                purpose: ApiKeyPurpose::Realtime,
+                requests_per_second: None,
+                burst_size: None,
+                created_by: user.id,
+                spend_limit: None,
+                spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
+            })
+            .await
+            .unwrap();
+
+        // Onwards rejects unknown models with 404 and everything else with 403;
+        // pick the status from the requested model.
+        let router = axum::Router::new()
+            .route(
+                "/ai/v1/chat/completions",
+                axum::routing::post(|body: String| async move {
+                    let status = if body.contains("missing-model") {
+                        StatusCode::NOT_FOUND
+                    } else {
+                        StatusCode::FORBIDDEN
+                    };
+                    axum::response::Response::builder()
+                        .status(status)
+                        .body(axum::body::Body::from("Rejected"))
+                        .unwrap()
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                forbidden_state(&pool),
+                crate::error_enrichment::error_enrichment_middleware,
+            ));
+        let server = axum_test::TestServer::new(router).expect("Failed to create test server");
+
+        let request = |key: String, model: &'static str| {
+            server
+                .post("/ai/v1/chat/completions")
+                .add_header("authorization", &format!("Bearer {key}"))
+                .json(&serde_json::json!({
+                    "model": model,
+                    "messages": [{"role": "user", "content": "Hello"}]
+                }))
+        };
+
+        // Unknown model.
+        let response = request(api_key.secret.clone(), "missing-model").await;
+        assert_eq!(response.status_code().as_u16(), 404);
+        assert_eq!(response.text(), "Rejected", "404 body is passed through");
+        assert_eq!(response.header(ERROR_REASON_HEADER), reason::MODEL_UNKNOWN);
+
+        // Key that doesn't exist.
+        let response = request("not-a-real-key".to_string(), "test-model").await;
+        assert_eq!(response.status_code().as_u16(), 403);
+        assert_eq!(response.header(ERROR_REASON_HEADER), reason::INVALID_API_KEY);
+
+        // No group grants the user the model.
+        let endpoint_id = crate::test::utils::create_test_endpoint(&pool, "test-endpoint", user.id).await;
+        let deployment_id = crate::test::utils::create_test_model(&pool, "test-model-name", "test-model", endpoint_id, user.id).await;
+        let response = request(api_key.secret.clone(), "test-model").await;
+        assert_eq!(response.status_code().as_u16(), 403);
+        assert_eq!(response.header(ERROR_REASON_HEADER), reason::NO_GROUP_ACCESS);
+
+        // Access granted, but the new user has no credits.
+        let group = create_test_group(&pool).await;
+        add_user_to_group(&pool, user.id, group.id).await;
+        add_deployment_to_group(&pool, deployment_id, group.id, user.id).await;
+        let response = request(api_key.secret.clone(), "test-model").await;
+        assert_eq!(response.status_code().as_u16(), 402);
+        assert_eq!(response.header(ERROR_REASON_HEADER), reason::INSUFFICIENT_CREDITS);
+
+        // Access and credits are fine, so the key must be missing from onwards' key set.
+        let mut credits_conn = pool.acquire().await.unwrap();
+        Credits::new(&mut credits_conn)
+            .create_transaction(&CreditTransactionCreateDBRequest {
+                user_id: user.id,
+                transaction_type: CreditTransactionType::AdminGrant,
+                amount: Decimal::new(5000, 2),
+                source_id: uuid::Uuid::new_v4().to_string(),
+                description: Some("Initial credits".to_string()),
+                fusillade_batch_id: None,
+                api_key_id: None,
+            })
+            .await
+            .unwrap();
+        let response = request(api_key.secret.clone(), "test-model").await;
+        assert_eq!(response.status_code().as_u16(), 403);
+        assert_eq!(response.text(), "Rejected", "unexplained 403 body is passed through");
+        assert_eq!(response.header(ERROR_REASON_HEADER), reason::KEY_NOT_IN_CACHE);
+    }
+
+    /// Integration test: `onwards.unauthorized_models` decides whether a model
+    /// the caller has no access to is hidden behind a 404 or reported with a
+    /// 403; callers with access are unaffected either way. The setting is read
+    /// per request, so a config reload takes effect without a rebuild.
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_error_enrichment_unauthorized_model_modes(pool: PgPool) {
+        use super::{ERROR_REASON_HEADER, reason};
+        use crate::test::utils::{add_deployment_to_group, add_user_to_group, create_test_group};
+
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let mut api_key_conn = pool.acquire().await.unwrap();
+        let api_key = ApiKeys::new(&mut api_key_conn)
+            .create(&ApiKeyCreateDBRequest {
+                user_id: user.id,
+                name: "Test Key".to_string(),
+                description: None,
+                purpose: ApiKeyPurpose::Realtime,
+                requests_per_second: None,
+                burst_size: None,
+                created_by: user.id,
+                spend_limit: None,
+                spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
+            })
+            .await
+            .unwrap();
+        drop(api_key_conn);
+
+        let endpoint_id = crate::test::utils::create_test_endpoint(&pool, "test-endpoint", user.id).await;
+        crate::test::utils::create_test_model(&pool, "private-model-name", "private-model", endpoint_id, user.id).await;
+        let granted_id = crate::test::utils::create_test_model(&pool, "granted-model-name", "granted-model", endpoint_id, user.id).await;
+        let group = create_test_group(&pool).await;
+        add_user_to_group(&pool, user.id, group.id).await;
+        add_deployment_to_group(&pool, granted_id, group.id, user.id).await;
+
+        let config = crate::SharedConfig::new(crate::test::utils::create_test_config());
+        let server = {
+            // Onwards only has the key in the granted model's key set.
+            let router = axum::Router::new()
+                .route(
+                    "/ai/v1/chat/completions",
+                    axum::routing::post(|body: String| async move {
+                        let status = if body.contains("private-model") {
+                            StatusCode::FORBIDDEN
+                        } else {
+                            StatusCode::OK
+                        };
+                        axum::response::Response::builder()
+                            .status(status)
+                            .body(axum::body::Body::from("Proxied"))
+                            .unwrap()
+                    }),
+                )
+                .layer(axum::middleware::from_fn_with_state(
+                    ErrorEnrichmentState {
+                        pool: pool.clone(),
+                        config: config.clone(),
+                    },
+                    crate::error_enrichment::error_enrichment_middleware,
+                ));
+            axum_test::TestServer::new(router).expect("Failed to create test server")
+        };
+        let body = |model: &str| serde_json::json!({"model": model, "messages": [{"role": "user", "content": "Hello"}]});
+        let bearer = format!("Bearer {}", api_key.secret);
+
+        // not_found (the default): the unauthorized caller can't tell the model exists.
+        let response = server
+            .post("/ai/v1/chat/completions")
+            .add_header("authorization", &bearer)
+            .json(&body("private-model"))
+            .await;
+        assert_eq!(response.status_code().as_u16(), 404);
+        assert_eq!(response.header(ERROR_REASON_HEADER), reason::MODEL_UNKNOWN);
+        let json: serde_json::Value = response.json();
+        assert_eq!(json["error"]["code"], "model_not_found");
+        assert_eq!(
+            json["error"]["message"],
+            "The model `private-model` does not exist or you do not have access to it."
+        );
+        let response = server
+            .post("/ai/v1/chat/completions")
+            .add_header("authorization", &bearer)
+            .json(&body("granted-model"))
+            .await;
+        assert_eq!(response.status_code().as_u16(), 200);
+        assert_eq!(response.text(), "Proxied");
+
+        // forbidden, switched on by a reload: the unauthorized caller is told access is denied.
+        let mut reloaded = crate::test::utils::create_test_config();
+        reloaded.onwards.unauthorized_models = UnauthorizedModelResponse::Forbidden;
+        config.store(reloaded);
+        let response = server
+            .post("/ai/v1/chat/completions")
+            .add_header("authorization", &bearer)
+            .json(&body("private-model"))
+            .await;
+        assert_eq!(response.status_code().as_u16(), 403);
+        assert_eq!(response.header(ERROR_REASON_HEADER), reason::NO_GROUP_ACCESS);
+        assert!(response.text().contains("do not have access to 'private-model'"));
+        let response = server
+            .post("/ai/v1/chat/completions")
+            .add_header("authorization", &bearer)
+            .json(&body("granted-model"))
+            .await;
+        assert_eq!(response.status_code().as_u16(), 200);
+        assert_eq!(response.text(), "Proxied");
+    }
+
+    /// Integration test: with `onwards.unauthorized_models: not_found`, every
+    /// rejection of a key that can't use an existing model looks exactly like a
+    /// request for a model that doesn't exist, so the catalog can't be probed.
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_error_enrichment_not_found_hides_model_from_rejected_keys(pool: PgPool) {
+        use super::{ERROR_REASON_HEADER, model_not_found, reason};
+        use crate::test::utils::{add_deployment_to_group, add_user_to_group, create_test_group};
+
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let endpoint_id = crate::test::utils::create_test_endpoint(&pool, "test-endpoint", user.id).await;
+        let deployment_id = crate::test::utils::create_test_model(&pool, "hidden-model-name", "hidden-model", endpoint_id, user.id).await;
+        let group = create_test_group(&pool).await;
+        add_user_to_group(&pool, user.id, group.id).await;
+        add_deployment_to_group(&pool, deployment_id, group.id, user.id).await;
+
+        // A usable key, one whose denylist names the model, and a management key
+        let mut api_key_conn = pool.acquire().await.unwrap();
+        let mut secrets = Vec::new();
+        for (purpose, denied_model_ids) in [
+            (ApiKeyPurpose::Realtime, None),
+            (ApiKeyPurpose::Realtime, Some(vec![deployment_id])),
+            (ApiKeyPurpose::Platform, None),
+        ] {
+            let key = ApiKeys::new(&mut api_key_conn)
+                .create(&ApiKeyCreateDBRequest {
+                    user_id: user.id,
+                    name: "Test Key".to_string(),
+                    description: None,
+                    purpose,
+                    requests_per_second: None,
+                    burst_size: None,
+                    created_by: user.id,
+                    spend_limit: None,
+                    spend_limit_interval: None,
+                    allowed_model_ids: None,
+                    denied_model_ids,
+                    max_priority: 0,
+                })
+                .await
+                .unwrap();
+            secrets.push(key.secret);
+        }
+        drop(api_key_conn);
+        let [usable_key, denied_key, platform_key] = <[String; 3]>::try_from(secrets).unwrap();
+
+        // Like onwards: unknown models 404 before the key is looked at, a missing
+        // key is a 401 and any other rejection a 403.
+        let router = axum::Router::new()
+            .route(
+                "/ai/v1/chat/completions",
+                axum::routing::post(|headers: axum::http::HeaderMap, body: String| async move {
+                    if body.contains("unknown-model") {
+                        return model_not_found("unknown-model");
+                    }
+                    let status = if headers.contains_key("authorization") {
+                        StatusCode::FORBIDDEN
+                    } else {
+                        StatusCode::UNAUTHORIZED
+                    };
+                    axum::response::Response::builder()
+                        .status(status)
+                        .body(axum::body::Body::from("Rejected"))
+                        .unwrap()
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                ErrorEnrichmentState {
+                    pool: pool.clone(),
+                    config: crate::test::utils::create_test_config().into(),
+                },
+                crate::error_enrichment::error_enrichment_middleware,
+            ));
+        let server = axum_test::TestServer::new(router).expect("Failed to create test server");
+        let request = |key: Option<&str>, model: &str| {
+            let request = server
+                .post("/ai/v1/chat/completions")
+                .json(&serde_json::json!({"model": model, "messages": [{"role": "user", "content": "Hello"}]}));
+            match key {
+                Some(key) => request.add_header("authorization", format!("Bearer {key}")),
+                None => request,
+            }
+        };
+
+        let unknown: serde_json::Value = request(Some("not-a-real-key"), "unknown-model").await.json();
+        for key in [Some("not-a-real-key"), None, Some(denied_key.as_str()), Some(platform_key.as_str())] {
+            let response = request(key, "hidden-model").await;
+            assert_eq!(response.status_code().as_u16(), 404, "key {key:?}");
+            assert_eq!(response.header(ERROR_REASON_HEADER), reason::MODEL_UNKNOWN);
+            let body: serde_json::Value = response.json();
+            assert_eq!(
+                body["error"],
+                serde_json::json!({
+                    "message": "The model `hidden-model` does not exist or you do not have access to it.",
+                    "type": unknown["error"]["type"],
+                    "param": null,
+                    "code": unknown["error"]["code"],
+                }),
+                "key {key:?}"
+            );
+        }
+
+        // A key that can use the model still learns why it was rejected.
+        let response = request(Some(&usable_key), "hidden-model").await;
+        assert_eq!(response.status_code().as_u16(), 402);
+        assert_eq!(response.header(ERROR_REASON_HEADER), reason::INSUFFICIENT_CREDITS);
+    }
+
     #[sqlx::test]
     #[test_log::test]
     async fn test_error_enrichment_middleware_without_auth_header(pool: PgPool) {
@@ -1403,7 +2010,7 @@ mod tests {
                 }),
             )
             .layer(axum::middleware::from_fn_with_state(
-                pool.clone(),
+                forbidden_state(&pool),
                 crate::error_enrichment::error_enrichment_middleware,
             ));
 
@@ -0,0 +1,264 @@
+//! Normalized model alias matching (`onwards.alias_normalization`).
+//!
+//! Applied to the whole `/ai/v1` router ahead of request logging, so a request
+//! for `GPT-4` is rewritten to the configured `gpt-4` alias before onwards
+//! routes it and before it is logged and billed. Names that already match an
+//! alias exactly are left alone, and a name whose normalized form matches more
+//! than one alias (possible only if aliases were created before normalization
+//! was enabled) is left for onwards to reject as an unknown model.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{HeaderValue, Method, header::CONTENT_LENGTH, header::CONTENT_TYPE},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sqlx::PgPool;
+use sqlx_pool_router::PoolProvider;
+use tracing::{debug, warn};
+
+use crate::AppState;
+use crate::config::AliasNormalization;
+use crate::db::errors::DbError;
+use crate::db::handlers::Deployments;
+use crate::inference::deployment_settings::MODEL_OVERRIDE_HEADER;
+
+/// How long a replica trusts its cached alias list before re-reading it.
+const CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Aliases indexed by their normalized form under one mode.
+struct Snapshot {
+    fetched_at: Instant,
+    mode: AliasNormalization,
+    exact: HashSet<String>,
+    /// Normalized form -> alias, or `None` when several aliases share it.
+    normalized: HashMap<String, Option<String>>,
+}
+
+impl Snapshot {
+    fn build(mode: AliasNormalization, aliases: impl IntoIterator<Item = String>) -> Self {
+        let mut exact = HashSet::new();
+        let mut normalized: HashMap<String, Option<String>> = HashMap::new();
+        for alias in aliases {
+            if let Some(key) = mode.normalize(&alias) {
+                normalized
+                    .entry(key)
+                    .and_modify(|existing| *existing = None)
+                    .or_insert_with(|| Some(alias.clone()));
+            }
+            exact.insert(alias);
+        }
+        Self {
+            fetched_at: Instant::now(),
+            mode,
+            exact,
+            normalized,
+        }
+    }
+
+    fn resolve(&self, model: &str) -> Option<String> {
+        if self.exact.contains(model) {
+            return None;
+        }
+        self.normalized.get(&self.mode.normalize(model)?).cloned().flatten()
+    }
+}
+
+/// Per-replica cache of deployment aliases for normalized matching.
+#[derive(Clone, Default)]
+pub struct AliasIndex {
+    cached: Arc<RwLock<Option<Arc<Snapshot>>>>,
+}
+
+impl AliasIndex {
+    /// The alias a request for `model` should be routed as, when that differs from `model`.
+    pub async fn resolve(&self, pool: &PgPool, mode: AliasNormalization, model: &str) -> Result<Option<String>, DbError> {
+        let cached = self.cached.read().expect("alias cache poisoned").clone();
+        let snapshot = match cached {
+            Some(snapshot) if snapshot.mode == mode && snapshot.fetched_at.elapsed() < CACHE_TTL => snapshot,
+            _ => {
+                let mut conn = pool.acquire().await?;
+                let aliases = Deployments::new(&mut conn).list_active_aliases().await?;
+                let snapshot = Arc::new(Snapshot::build(mode, aliases.into_iter().map(|(_, alias)| alias)));
+                *self.cached.write().expect("alias cache poisoned") = Some(snapshot.clone());
+                snapshot
+            }
+        };
+        Ok(snapshot.resolve(model))
+    }
+}
+
+/// Resolve `model` through the alias cache, logging (and ignoring) database errors.
+async fn resolve_model<P: PoolProvider>(state: &AppState<P>, mode: AliasNormalization, model: &str) -> Option<String> {
+    match state.aliases.resolve(state.db.read(), mode, model).await {
+        Ok(Some(alias)) => {
+            debug!(requested = %model, %alias, "resolved model by normalized alias");
+            Some(alias)
+        }
+        Ok(None) => None,
+        Err(error) => {
+            warn!(%error, "failed to load model aliases; passing model through unchanged");
+            None
+        }
+    }
+}
+
+/// Rewrite the requested model to its configured alias when it only matches after normalization.
+///
+/// Fails open: if the aliases cannot be read, the request is passed on unchanged.
+pub async fn alias_normalization_middleware<P: PoolProvider>(
+    State(state): State<AppState<P>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let mode = state.current_config().onwards.alias_normalization;
+    if mode == AliasNormalization::Exact || request.method() == Method::GET {
+        return next.run(request).await;
+    }
+
+    if let Some(model) = request
+        .headers()
+        .get(MODEL_OVERRIDE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+        && let Some(alias) = resolve_model(&state, mode, &model).await
+        && let Ok(value) = HeaderValue::from_str(&alias)
+    {
+        request.headers_mut().insert(MODEL_OVERRIDE_HEADER, value);
+    }
+
+    // Only JSON bodies carry a model field; leave uploads and other bodies unread.
+    let is_json = request
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_none_or(|content_type| content_type.contains("json"));
+    if !is_json {
+        return next.run(request).await;
+    }
+
+    let body_bytes = match axum::body::to_bytes(std::mem::take(request.body_mut()), usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(error) => {
+            warn!(%error, "Failed to read request body in alias normalization middleware");
+            return (axum::http::StatusCode::BAD_REQUEST, "Failed to read request body").into_response();
+        }
+    };
+
+    let mut body = match serde_json::from_slice::<serde_json::Value>(&body_bytes) {
+        Ok(body) => body,
+        Err(_) => {
+            *request.body_mut() = Body::from(body_bytes);
+            return next.run(request).await;
+        }
+    };
+    let Some(model) = body.get("model").and_then(|model| model.as_str()).map(str::to_string) else {
+        *request.body_mut() = Body::from(body_bytes);
+        return next.run(request).await;
+    };
+    let Some(alias) = resolve_model(&state, mode, &model).await else {
+        *request.body_mut() = Body::from(body_bytes);
+        return next.run(request).await;
+    };
+
+    body["model"] = serde_json::Value::String(alias);
+    let new_bytes = serde_json::to_vec(&body).expect("JSON value serializes");
+    request.headers_mut().insert(CONTENT_LENGTH, HeaderValue::from(new_bytes.len()));
+    *request.body_mut() = Body::from(new_bytes);
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Snapshot;
+    use crate::config::AliasNormalization;
+    use crate::test::utils::{await_chat_status, create_test_config, setup_proxied_model};
+    use sqlx::PgPool;
+
+    fn snapshot(mode: AliasNormalization) -> Snapshot {
+        Snapshot::build(mode, ["gpt-4", "Llama-3", "dup-a", "Dup-A"].map(str::to_string))
+    }
+
+    #[test]
+    fn test_case_insensitive_resolution() {
+        let snapshot = snapshot(AliasNormalization::CaseInsensitive);
+        assert_eq!(snapshot.resolve("GPT-4").as_deref(), Some("gpt-4"));
+        assert_eq!(snapshot.resolve("llama-3").as_deref(), Some("Llama-3"));
+        // Exact matches and unknown names are left alone
+        assert_eq!(snapshot.resolve("gpt-4"), None);
+        assert_eq!(snapshot.resolve("gpt4"), None);
+        // Ambiguous names are not resolved, but their exact aliases still are
+        assert_eq!(snapshot.resolve("DUP-A"), None);
+        assert_eq!(snapshot.resolve("Dup-A"), None);
+    }
+
+    #[test]
+    fn test_case_and_separator_resolution() {
+        let snapshot = snapshot(AliasNormalization::CaseAndSeparators);
+        assert_eq!(snapshot.resolve("gpt4").as_deref(), Some("gpt-4"));
+        assert_eq!(snapshot.resolve("GPT_4").as_deref(), Some("gpt-4"));
+        assert_eq!(snapshot.resolve("gpt 4").as_deref(), Some("gpt-4"));
+        assert_eq!(snapshot.resolve("gpt-4.1"), None);
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_case_insensitive_request_routes_to_alias(pool: PgPool) {
+        let mock_server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/v1/chat/completions"))
+            .and(wiremock::matchers::body_partial_json(
+                serde_json::json!({ "model": "norm-upstream" }),
+            ))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "chatcmpl-1", "object": "chat.completion", "created": 1, "model": "norm-upstream",
+                "choices": [ { "index": 0, "message": { "role": "assistant", "content": "Hello" }, "finish_reason": "stop" } ],
+                "usage": { "prompt_tokens": 5, "completion_tokens": 1, "total_tokens": 6 }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let mut config = create_test_config();
+        config.background_services.onwards_sync.enabled = true;
+        config.onwards.alias_normalization = AliasNormalization::CaseInsensitive;
+        let (server, bg_services) = crate::Application::new_with_pool(config, Some(pool.clone()), None)
+            .await
+            .expect("Failed to create application")
+            .into_test_server();
+        let api_key = setup_proxied_model(
+            &server,
+            &bg_services,
+            &pool,
+            &mock_server,
+            serde_json::json!({}),
+            serde_json::json!({ "model_name": "norm-upstream", "alias": "gpt-4-norm" }),
+        )
+        .await
+        .api_key;
+
+        // A differently-cased model name routes to the alias
+        await_chat_status(
+            &server,
+            &api_key,
+            &serde_json::json!({ "model": "GPT-4-Norm", "messages": [{ "role": "user", "content": "hi" }] }),
+            200,
+        )
+        .await;
+
+        // A name that doesn't normalize to any alias is still unknown
+        let resp = server
+            .post("/ai/v1/chat/completions")
+            .add_header("authorization", format!("Bearer {api_key}"))
+            .json(&serde_json::json!({ "model": "gpt4-norm", "messages": [{ "role": "user", "content": "hi" }] }))
+            .await;
+        assert_ne!(resp.status_code().as_u16(), 200);
+
+        bg_services.shutdown().await;
+    }
+}
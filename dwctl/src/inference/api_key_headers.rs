@@ -0,0 +1,142 @@
+//! Alternate inbound API key headers (`onwards.api_key_headers`).
+//!
+//! Applied to the whole `/ai/v1` router, so a key sent as `api-key: sk-...`
+//! (or any other configured header) is moved into `Authorization: Bearer`
+//! before onwards authenticates it and before rate limiting, request logging
+//! and billing read it. An `Authorization` header sent by the client always
+//! wins. The configured headers are removed either way so the key is never
+//! forwarded upstream.
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue, header::AUTHORIZATION},
+    middleware::Next,
+    response::Response,
+};
+use sqlx_pool_router::PoolProvider;
+
+use crate::AppState;
+
+/// Move a key sent in one of `names` into `Authorization: Bearer`, and strip `names`.
+fn promote_api_key(headers: &mut HeaderMap, names: &[String]) {
+    for name in names {
+        let Some(value) = headers.remove(name.as_str()) else {
+            continue;
+        };
+        if headers.contains_key(AUTHORIZATION) {
+            continue;
+        }
+        let Some(key) = value.to_str().ok().map(str::trim) else {
+            continue;
+        };
+        // Tolerate clients that send the full `Bearer <key>` form in the alternate header
+        let key = key.strip_prefix("Bearer ").unwrap_or(key);
+        if let Ok(value) = HeaderValue::from_str(&format!("Bearer {key}")) {
+            headers.insert(AUTHORIZATION, value);
+        }
+    }
+}
+
+/// Accept API keys from the headers listed in `onwards.api_key_headers`.
+pub async fn api_key_headers_middleware<P: PoolProvider>(State(state): State<AppState<P>>, mut request: Request, next: Next) -> Response {
+    let config = state.current_config();
+    if !config.onwards.api_key_headers.is_empty() {
+        promote_api_key(request.headers_mut(), &config.onwards.api_key_headers);
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::promote_api_key;
+    use crate::test::utils::{await_chat_status, create_test_config, setup_proxied_model};
+    use axum::http::{HeaderMap, HeaderValue, header::AUTHORIZATION};
+    use sqlx::PgPool;
+
+    #[test]
+    fn test_promote_api_key() {
+        let names = vec!["api-key".to_string(), "X-API-Key".to_string()];
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-key", HeaderValue::from_static("sk-alt"));
+        promote_api_key(&mut headers, &names);
+        assert_eq!(headers.get(AUTHORIZATION).unwrap(), "Bearer sk-alt");
+        assert!(!headers.contains_key("x-api-key"));
+
+        // A Bearer prefix in the alternate header isn't doubled
+        let mut headers = HeaderMap::new();
+        headers.insert("api-key", HeaderValue::from_static("Bearer sk-alt"));
+        promote_api_key(&mut headers, &names);
+        assert_eq!(headers.get(AUTHORIZATION).unwrap(), "Bearer sk-alt");
+
+        // Authorization wins, and the alternate header is still stripped
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer sk-real"));
+        headers.insert("api-key", HeaderValue::from_static("sk-ignored"));
+        promote_api_key(&mut headers, &names);
+        assert_eq!(headers.get(AUTHORIZATION).unwrap(), "Bearer sk-real");
+        assert!(!headers.contains_key("api-key"));
+
+        // Unconfigured headers are left alone
+        let mut headers = HeaderMap::new();
+        headers.insert("x-key", HeaderValue::from_static("sk-other"));
+        promote_api_key(&mut headers, &names);
+        assert!(!headers.contains_key(AUTHORIZATION));
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_authenticate_with_each_configured_header(pool: PgPool) {
+        let mock_server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/v1/chat/completions"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "chatcmpl-1", "object": "chat.completion", "created": 1, "model": "header-model",
+                "choices": [ { "index": 0, "message": { "role": "assistant", "content": "Hello" }, "finish_reason": "stop" } ],
+                "usage": { "prompt_tokens": 5, "completion_tokens": 1, "total_tokens": 6 }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let mut config = create_test_config();
+        config.background_services.onwards_sync.enabled = true;
+        config.onwards.api_key_headers = vec!["api-key".to_string(), "x-api-key".to_string()];
+        let (server, bg_services) = crate::Application::new_with_pool(config, Some(pool.clone()), None)
+            .await
+            .expect("Failed to create application")
+            .into_test_server();
+        let api_key = setup_proxied_model(
+            &server,
+            &bg_services,
+            &pool,
+            &mock_server,
+            serde_json::json!({}),
+            serde_json::json!({ "alias": "header-model" }),
+        )
+        .await
+        .api_key;
+
+        let body = serde_json::json!({ "model": "header-model", "messages": [{ "role": "user", "content": "hi" }] });
+        await_chat_status(&server, &api_key, &body, 200).await;
+        for name in ["api-key", "x-api-key"] {
+            let resp = server.post("/ai/v1/chat/completions").add_header(name, &api_key).json(&body).await;
+            assert_eq!(resp.status_code(), 200, "key sent in {name} should authenticate");
+        }
+
+        // A wrong key in an alternate header is rejected like a wrong Bearer token
+        let resp = server
+            .post("/ai/v1/chat/completions")
+            .add_header("api-key", "sk-not-a-real-key")
+            .json(&body)
+            .await;
+        assert_ne!(resp.status_code().as_u16(), 200);
+
+        // The alternate headers are never forwarded upstream
+        for request in mock_server.received_requests().await.unwrap() {
+            assert!(!request.headers.contains_key("api-key"));
+            assert!(!request.headers.contains_key("x-api-key"));
+        }
+
+        bg_services.shutdown().await;
+    }
+}
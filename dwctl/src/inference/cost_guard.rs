@@ -0,0 +1,724 @@
+//! Per-request cost guard (`max_cost_per_request` and `credit_exhaustion_mode`
+//! on deployments).
+//!
+//! Applied outside the inference middleware and request logging, so a request
+//! is judged before it is queued, forwarded or billed. Its worst-case cost is
+//! the requested token cap (`max_completion_tokens`, `max_tokens` or
+//! `max_output_tokens`) at the model's highest current output price, once for
+//! each completion asked for with `n` (no more than the deployment's `max_n`);
+//! if that exceeds the limit the request is rejected with a 400. Prompt tokens
+//! are not counted, and a request without a token cap is not rejected up front.
+//!
+//! Streamed responses are metered as they pass: the generated output their
+//! events carry is estimated at four bytes a token, as when billing a stream cut
+//! short (or the usage total is used, when the provider reports it). Once the accrued cost crosses the limit the event that crossed it is
+//! delivered, followed by an error event, and the stream ends. Dropping the
+//! upstream body cancels the provider request, and the tokens delivered so far
+//! are billed like any stream cut short.
+//!
+//! The same metering decides what happens when a stream outlasts the caller's
+//! credits. A request is only admitted with a positive balance, so a stream
+//! that costs more than the balance left when it started takes the balance
+//! negative. In `complete` mode (the default) it is allowed to, by at most the
+//! deployment's `max_overdraft` when that is set; in `abort` mode the stream is
+//! ended once its cost exceeds the balance. Either way the stream ends with an
+//! `insufficient_credits` error event. Against the credits a stream is charged
+//! for its prompt too, estimated from the request body at the model's highest
+//! current input price, so a large prompt leaves less of the balance for output.
+//! The balance is read once, when the response starts, so concurrent spending by
+//! the same user isn't seen.
+//!
+//! Limits are read from the per-replica
+//! [`deployment_settings`](crate::inference::deployment_settings) snapshot, so
+//! requests to models without a limit cost no extra queries. Anything that
+//! can't be checked fails open.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use axum::{
+    Json,
+    body::{Body, Bytes, HttpBody},
+    extract::{Request, State},
+    http::{
+        Method, StatusCode,
+        header::{AUTHORIZATION, CONTENT_TYPE},
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use futures::Stream;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use sqlx_pool_router::PoolProvider;
+use tracing::{debug, warn};
+
+use crate::AppState;
+use crate::db::errors::DbError;
+use crate::db::handlers::deployments::DeploymentCostLimits;
+use crate::db::models::deployments::CreditExhaustionMode;
+use crate::inference::deployment_settings::{json_body, model_override, request_body_limit, requested_model};
+use crate::request_logging::token_estimate;
+
+/// Error code reported when a request would cost, or has cost, more than the limit.
+const ERROR_CODE: &str = "max_cost_per_request_exceeded";
+
+/// Error code reported when a stream is ended for costing more than the caller's credits.
+const CREDITS_ERROR_CODE: &str = "insufficient_credits";
+
+/// The system user, which isn't subject to the balance gate.
+const SYSTEM_USER_ID: uuid::Uuid = uuid::Uuid::nil();
+
+/// Request fields capping generated tokens, in order of precedence.
+const TOKEN_CAP_FIELDS: [&str; 3] = ["max_completion_tokens", "max_tokens", "max_output_tokens"];
+
+/// A deployment's per-request limits and the prices they are judged at.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CostLimit {
+    /// The deployment's `max_cost_per_request`
+    pub max_cost: Option<Decimal>,
+    pub input_price_per_token: Decimal,
+    pub output_price_per_token: Decimal,
+    /// The deployment's cap on `n`; requests above it are clamped or rejected by onwards
+    pub max_n: Option<u64>,
+    /// How far below zero a stream may take the caller's balance: zero in abort
+    /// mode, `max_overdraft` in complete mode, None when it isn't limited
+    pub overdraft: Option<Decimal>,
+}
+
+impl CostLimit {
+    /// The limits of a deployment, or None for an unpriced one, which can't cost anything.
+    pub(crate) fn from_row(row: &DeploymentCostLimits) -> Option<Self> {
+        let output_price_per_token = row.output_price_per_token.filter(|price| *price > Decimal::ZERO)?;
+        let overdraft = match row.credit_exhaustion_mode {
+            CreditExhaustionMode::Abort => Some(Decimal::ZERO),
+            CreditExhaustionMode::Complete => row.max_overdraft,
+        };
+        Some(Self {
+            max_cost: row.max_cost_per_request,
+            input_price_per_token: row.input_price_per_token.unwrap_or_default(),
+            output_price_per_token,
+            max_n: row.max_n.and_then(|max_n| u64::try_from(max_n).ok()),
+            overdraft,
+        })
+    }
+
+    fn cost_of(&self, tokens: u64) -> Decimal {
+        self.output_price_per_token.saturating_mul(Decimal::from(tokens))
+    }
+}
+
+/// How many completions a request can generate: its `n`, at most the deployment's cap.
+pub(crate) fn requested_completions(body: &serde_json::Value, max_n: Option<u64>) -> u64 {
+    let n = body.get("n").and_then(|n| n.as_u64()).unwrap_or(1).max(1);
+    max_n.map_or(n, |max_n| n.min(max_n))
+}
+
+/// The token cap a request asks for, with the field it came from.
+pub(crate) fn requested_token_cap(body: &serde_json::Value) -> Option<(&'static str, u64)> {
+    TOKEN_CAP_FIELDS
+        .into_iter()
+        .find_map(|field| body.get(field).and_then(|value| value.as_u64()).map(|cap| (field, cap)))
+}
+
+/// Balance of the user owning `api_key`, or None for unknown keys and the system user.
+async fn key_owner_balance(pool: &PgPool, api_key: &str) -> Result<Option<Decimal>, DbError> {
+    let mut conn = pool.acquire().await?;
+    let balance = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(ub.balance, 0) AS "balance!"
+        FROM api_keys ak
+        LEFT JOIN user_balance_checkpoints ub ON ub.user_id = ak.user_id
+        WHERE ak.secret = $1 AND ak.is_deleted = false AND ak.user_id <> $2
+        "#,
+        api_key,
+        SYSTEM_USER_ID
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+    Ok(balance)
+}
+
+fn error_body(message: String, param: Option<&str>) -> serde_json::Value {
+    serde_json::json!({
+        "error": {
+            "message": message,
+            "type": "invalid_request_error",
+            "param": param,
+            "code": ERROR_CODE,
+        }
+    })
+}
+
+/// Why a stream was ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cutoff {
+    /// The deployment's `max_cost_per_request` was crossed
+    MaxCost,
+    /// The caller's credits, plus any overdraft allowed, ran out
+    Credits,
+}
+
+/// A streamed response body that ends once its accrued cost crosses the limit
+/// or the caller's credits.
+struct CostCappedStream<S> {
+    /// Dropped as soon as the limit is crossed, cancelling the upstream request.
+    inner: Option<S>,
+    limit: CostLimit,
+    /// Credits the stream may spend: the balance plus the overdraft allowed.
+    credits: Option<Decimal>,
+    /// Estimated cost of the prompt, charged against the credits before any output.
+    prompt_cost: Decimal,
+    /// Bytes of an event whose terminating blank line hasn't arrived yet.
+    pending: Vec<u8>,
+    /// Bytes of generated output streamed so far.
+    output_bytes: usize,
+    /// The latest usage total the provider reported, which supersedes the estimate.
+    reported_tokens: Option<u64>,
+    tokens: u64,
+    /// Error event to send after the chunk that crossed the limit.
+    abort_event: Option<Bytes>,
+}
+
+impl<S> CostCappedStream<S> {
+    fn new(inner: S, limit: CostLimit, credits: Option<Decimal>, prompt_cost: Decimal) -> Self {
+        Self {
+            inner: Some(inner),
+            limit,
+            credits,
+            prompt_cost,
+            pending: Vec::new(),
+            output_bytes: 0,
+            reported_tokens: None,
+            tokens: 0,
+            abort_event: None,
+        }
+    }
+
+    /// Meter a chunk of the stream; Some once the accrued cost exceeds the limit or the credits.
+    fn observe(&mut self, chunk: &[u8]) -> Option<Cutoff> {
+        self.pending.extend_from_slice(chunk);
+        while let Some(end) = self.pending.windows(2).position(|window| window == b"\n\n") {
+            let event: Vec<u8> = self.pending.drain(..end + 2).collect();
+            let data: String = String::from_utf8_lossy(&event)
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(str::trim)
+                .collect();
+            let Ok(event) = serde_json::from_str::<serde_json::Value>(&data) else {
+                continue;
+            };
+            match token_estimate::event_usage_output_tokens(&event) {
+                Some(total) => self.reported_tokens = Some(total),
+                None => self.output_bytes += token_estimate::event_output_bytes(&event),
+            }
+        }
+        self.tokens = self
+            .reported_tokens
+            .unwrap_or_else(|| token_estimate::tokens_from_bytes(self.output_bytes));
+        // `max_cost_per_request` limits the output cost alone, as it does up front
+        let cost = self.limit.cost_of(self.tokens);
+        if self.limit.max_cost.is_some_and(|max_cost| cost > max_cost) {
+            Some(Cutoff::MaxCost)
+        } else if self.credits.is_some_and(|credits| self.prompt_cost.saturating_add(cost) > credits) {
+            Some(Cutoff::Credits)
+        } else {
+            None
+        }
+    }
+
+    fn error_event(&self, cutoff: Cutoff) -> Bytes {
+        let output_cost = self.limit.cost_of(self.tokens);
+        let body = match (cutoff, self.limit.max_cost) {
+            (Cutoff::MaxCost, Some(max_cost)) => {
+                let cost = output_cost.normalize();
+                let message = format!(
+                    "Response stopped: its cost reached {cost} credits, above the model's maximum cost per request of {} credits.",
+                    max_cost.normalize()
+                );
+                error_body(message, None)
+            }
+            _ => serde_json::json!({
+                "error": {
+                    "message": format!(
+                        "Response stopped: its cost reached {} credits, more than your remaining credits allow.",
+                        self.prompt_cost.saturating_add(output_cost).normalize()
+                    ),
+                    "type": "insufficient_quota",
+                    "param": null,
+                    "code": CREDITS_ERROR_CODE,
+                }
+            }),
+        };
+        Bytes::from(format!("data: {body}\n\n"))
+    }
+}
+
+impl<S, E> Stream for CostCappedStream<S>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin,
+{
+    type Item = Result<Bytes, E>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(event) = self.abort_event.take() {
+            return Poll::Ready(Some(Ok(event)));
+        }
+        let Some(inner) = self.inner.as_mut() else {
+            return Poll::Ready(None);
+        };
+        let poll = Pin::new(inner).poll_next(cx);
+        if let Poll::Ready(Some(Ok(chunk))) = &poll
+            && let Some(cutoff) = self.observe(chunk)
+        {
+            debug!(tokens = self.tokens, ?cutoff, "Stream crossed its cost limit; cancelling upstream");
+            self.abort_event = Some(self.error_event(cutoff));
+            self.inner = None;
+        }
+        poll
+    }
+}
+
+/// Reject requests whose worst-case cost exceeds the model's `max_cost_per_request`,
+/// and cut off streams whose accrued cost crosses it or the caller's credits.
+///
+/// Fails open: if the limits cannot be read, the request is passed on unchecked.
+pub async fn cost_guard_middleware<P: PoolProvider>(State(state): State<AppState<P>>, request: Request, next: Next) -> Response {
+    if request.method() != Method::POST {
+        return next.run(request).await;
+    }
+    let settings = match state.deployment_settings.get(state.db.read()).await {
+        Ok(settings) if !settings.cost_limits.is_empty() => settings,
+        Ok(_) => return next.run(request).await,
+        Err(error) => {
+            warn!(%error, "Failed to load per-request cost limits; passing request through");
+            return next.run(request).await;
+        }
+    };
+    // Only JSON bodies carry a model and token cap; uploads are left unread.
+    let (request, body) = match json_body(request, request_body_limit(&state.current_config())).await {
+        Ok((request, Some(body))) => (request, body),
+        Ok((request, None)) => return next.run(request).await,
+        Err(response) => return response,
+    };
+    let limit = requested_model(model_override(request.headers()), Some(&*body))
+        .and_then(|model| settings.cost_limits.get(model))
+        .copied();
+    let Some(limit) = limit else {
+        return next.run(request).await;
+    };
+
+    if let Some(max_cost) = limit.max_cost
+        && let Some((field, cap)) = requested_token_cap(&body)
+    {
+        let completions = requested_completions(&body, limit.max_n);
+        let worst_case = limit.cost_of(cap.saturating_mul(completions));
+        if worst_case > max_cost {
+            let (per_completion, lower) = match completions {
+                1 => (String::new(), field.to_string()),
+                n => (format!(" for each of {n} completions"), format!("{field} or n")),
+            };
+            let message = format!(
+                "This request could cost up to {} credits ({field} of {cap}{per_completion} at the model's output price), \
+                 above the model's maximum cost per request of {} credits. Lower {lower}.",
+                worst_case.normalize(),
+                max_cost.normalize()
+            );
+            return (StatusCode::BAD_REQUEST, Json(error_body(message, Some(field)))).into_response();
+        }
+    }
+
+    let streaming = body.get("stream").and_then(|stream| stream.as_bool()).unwrap_or(false);
+    let api_key = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::to_string);
+    // The buffered body's exact length
+    let prompt_bytes = usize::try_from(request.body().size_hint().lower()).unwrap_or(usize::MAX);
+    let response = next.run(request).await;
+    let is_event_stream = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with("text/event-stream"));
+    if !streaming || !response.status().is_success() || !is_event_stream {
+        return response;
+    }
+
+    let credits = match (limit.overdraft, api_key) {
+        (Some(overdraft), Some(api_key)) => match key_owner_balance(state.db.read(), &api_key).await {
+            Ok(balance) => balance.map(|balance| balance + overdraft),
+            Err(error) => {
+                warn!(%error, "Failed to read the caller's balance; streaming without a credit limit");
+                None
+            }
+        },
+        _ => None,
+    };
+    if limit.max_cost.is_none() && credits.is_none() {
+        return response;
+    }
+
+    let prompt_cost = limit
+        .input_price_per_token
+        .saturating_mul(Decimal::from(token_estimate::tokens_from_bytes(prompt_bytes)));
+    let (parts, body) = response.into_parts();
+    let capped = CostCappedStream::new(body.into_data_stream(), limit, credits, prompt_cost);
+    Response::from_parts(parts, Body::from_stream(capped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CostCappedStream, CostLimit, Cutoff, requested_completions, requested_token_cap};
+    use crate::test::utils::{await_chat_status, create_test_config, setup_proxied_model};
+    use futures::StreamExt;
+    use rust_decimal::Decimal;
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    fn chat_chunk(content: &str) -> String {
+        format!(
+            "data: {}\n\n",
+            json!({ "object": "chat.completion.chunk", "choices": [ { "index": 0, "delta": { "content": content } } ] })
+        )
+    }
+
+    #[test]
+    fn test_requested_token_cap() {
+        assert_eq!(requested_token_cap(&json!({ "max_tokens": 10 })), Some(("max_tokens", 10)));
+        assert_eq!(
+            requested_token_cap(&json!({ "max_tokens": 10, "max_completion_tokens": 20 })),
+            Some(("max_completion_tokens", 20))
+        );
+        assert_eq!(
+            requested_token_cap(&json!({ "max_output_tokens": 30 })),
+            Some(("max_output_tokens", 30))
+        );
+        assert_eq!(requested_token_cap(&json!({ "model": "m" })), None);
+    }
+
+    #[test]
+    fn test_requested_completions() {
+        assert_eq!(requested_completions(&json!({ "model": "m" }), None), 1);
+        assert_eq!(requested_completions(&json!({ "n": 0 }), None), 1);
+        assert_eq!(requested_completions(&json!({ "n": 8 }), None), 8);
+        assert_eq!(requested_completions(&json!({ "n": 8 }), Some(3)), 3);
+        assert_eq!(requested_completions(&json!({ "n": 2 }), Some(3)), 2);
+    }
+
+    #[tokio::test]
+    async fn test_stream_counts_deltas_and_usage() {
+        let limit = CostLimit {
+            max_cost: Some(Decimal::new(100, 0)),
+            input_price_per_token: Decimal::ONE,
+            output_price_per_token: Decimal::ONE,
+            max_n: None,
+            overdraft: None,
+        };
+        // An event split across chunks is counted once it completes; role-only deltas don't count.
+        // Twelve bytes of output make three tokens.
+        let chunks: Vec<Result<axum::body::Bytes, std::convert::Infallible>> = vec![
+            Ok(format!("data: {}\n\n", json!({ "choices": [ { "delta": { "role": "assistant" } } ] })).into()),
+            Ok(chat_chunk("Hell")[..20].to_string().into()),
+            Ok(chat_chunk("Hell")[20..].to_string().into()),
+            Ok(format!("data: {}\n\n", json!({ "type": "response.output_text.delta", "delta": "o, world" })).into()),
+        ];
+        let mut stream = CostCappedStream::new(futures::stream::iter(chunks), limit, None, Decimal::ZERO);
+        while stream.next().await.is_some() {}
+        assert_eq!(stream.tokens, 3);
+
+        // A usage total replaces the running count
+        let mut stream = CostCappedStream::new(
+            futures::stream::empty::<Result<axum::body::Bytes, std::convert::Infallible>>(),
+            limit,
+            None,
+            Decimal::ZERO,
+        );
+        assert!(stream.observe(chat_chunk("abcd").as_bytes()).is_none());
+        assert!(
+            stream
+                .observe(
+                    format!(
+                        "data: {}\n\ndata: [DONE]\n\n",
+                        json!({ "choices": [], "usage": { "completion_tokens": 40 } })
+                    )
+                    .as_bytes()
+                )
+                .is_none()
+        );
+        assert_eq!(stream.tokens, 40);
+    }
+
+    #[test]
+    fn test_stream_cutoff_reasons() {
+        let limit = CostLimit {
+            max_cost: Some(Decimal::new(10, 0)),
+            input_price_per_token: Decimal::ONE,
+            output_price_per_token: Decimal::ONE,
+            max_n: None,
+            overdraft: Some(Decimal::ZERO),
+        };
+        let new = |credits, prompt_cost| {
+            CostCappedStream::new(
+                futures::stream::empty::<Result<axum::body::Bytes, std::convert::Infallible>>(),
+                limit,
+                credits,
+                prompt_cost,
+            )
+        };
+
+        // Credits below the per-request limit end the stream first
+        let mut stream = new(Some(Decimal::new(3, 0)), Decimal::ZERO);
+        let cutoffs: Vec<_> = (0..4).map(|_| stream.observe(chat_chunk("abcd").as_bytes())).collect();
+        assert_eq!(cutoffs, vec![None, None, None, Some(Cutoff::Credits)]);
+        let event = String::from_utf8(stream.error_event(Cutoff::Credits).to_vec()).unwrap();
+        assert!(event.contains(r#""code":"insufficient_credits""#), "{event}");
+
+        // The prompt is charged against the credits, leaving less for output
+        let mut stream = new(Some(Decimal::new(10, 0)), Decimal::new(8, 0));
+        let cutoffs: Vec<_> = (0..3).map(|_| stream.observe(chat_chunk("abcd").as_bytes())).collect();
+        assert_eq!(cutoffs, vec![None, None, Some(Cutoff::Credits)]);
+        let event = String::from_utf8(stream.error_event(Cutoff::Credits).to_vec()).unwrap();
+        assert!(event.contains("its cost reached 11 credits"), "{event}");
+
+        // Without a credit limit only the per-request limit applies, to the output alone
+        let mut stream = new(None, Decimal::new(8, 0));
+        assert_eq!(stream.observe(chat_chunk("abcd").repeat(10).as_bytes()), None);
+        assert_eq!(stream.observe(chat_chunk("abcd").as_bytes()), Some(Cutoff::MaxCost));
+    }
+
+    /// A user with `credits` and a key for a model priced at 0.001 credits per input
+    /// and 0.01 per output token, created with the extra `settings`.
+    async fn setup(
+        pool: &PgPool,
+        mock_server: &wiremock::MockServer,
+        settings: serde_json::Value,
+        credits: &str,
+    ) -> (axum_test::TestServer, crate::BackgroundServices, String) {
+        let mut config = create_test_config();
+        config.background_services.onwards_sync.enabled = true;
+        let (server, bg_services) = crate::Application::new_with_pool(config, Some(pool.clone()), None)
+            .await
+            .expect("Failed to create application")
+            .into_test_server();
+        let mut model = json!({
+            "alias": "cost-model",
+            "allow_public": true,
+            "tariffs": [{
+                "name": "realtime",
+                "input_price_per_token": "0.001",
+                "output_price_per_token": "0.01",
+                "api_key_purpose": "realtime"
+            }]
+        });
+        model.as_object_mut().unwrap().extend(settings.as_object().unwrap().clone());
+        let proxied = setup_proxied_model(&server, &bg_services, pool, mock_server, json!({}), model).await;
+        let response = server
+            .post("/admin/api/v1/transactions")
+            .add_header(&proxied.admin_headers[0].0, &proxied.admin_headers[0].1)
+            .add_header(&proxied.admin_headers[1].0, &proxied.admin_headers[1].1)
+            .json(&json!({
+                "user_id": proxied.user.id,
+                "transaction_type": "admin_grant",
+                "amount": credits,
+                "source_id": proxied.admin.id,
+                "description": "Cost guard test credits"
+            }))
+            .await;
+        assert_eq!(response.status_code(), 201, "Failed to grant credits");
+        (server, bg_services, proxied.api_key)
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_preflight_rejects_worst_case_over_limit(pool: PgPool) {
+        let mock_server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/v1/chat/completions"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(json!({
+                "id": "chatcmpl-1", "object": "chat.completion", "created": 1, "model": "cost-model",
+                "choices": [ { "index": 0, "message": { "role": "assistant", "content": "Hi" }, "finish_reason": "stop" } ],
+                "usage": { "prompt_tokens": 5, "completion_tokens": 1, "total_tokens": 6 }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        // 0.01 credits per output token: up to 50 tokens fit in 0.5 credits
+        let (server, _bg, api_key) = setup(&pool, &mock_server, json!({ "max_cost_per_request": "0.5" }), "1000").await;
+        let messages = json!([ { "role": "user", "content": "Hello" } ]);
+
+        await_chat_status(
+            &server,
+            &api_key,
+            &json!({ "model": "cost-model", "messages": messages, "max_tokens": 50 }),
+            200,
+        )
+        .await;
+
+        let rejected = server
+            .post("/ai/v1/chat/completions")
+            .add_header("authorization", format!("Bearer {api_key}"))
+            .json(&json!({ "model": "cost-model", "messages": messages, "max_completion_tokens": 51 }))
+            .await;
+        rejected.assert_status(axum::http::StatusCode::BAD_REQUEST);
+        let error = rejected.json::<serde_json::Value>();
+        assert_eq!(error["error"]["code"], "max_cost_per_request_exceeded");
+        assert_eq!(error["error"]["param"], "max_completion_tokens");
+        assert!(error["error"]["message"].as_str().unwrap().contains("0.51"));
+
+        // Each of n completions can use the whole token cap
+        let rejected = server
+            .post("/ai/v1/chat/completions")
+            .add_header("authorization", format!("Bearer {api_key}"))
+            .json(&json!({ "model": "cost-model", "messages": messages, "max_tokens": 50, "n": 2 }))
+            .await;
+        rejected.assert_status(axum::http::StatusCode::BAD_REQUEST);
+        let message = rejected.json::<serde_json::Value>()["error"]["message"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert!(message.contains("for each of 2 completions"), "{message}");
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_stream_aborted_when_accrued_cost_crosses_limit(pool: PgPool) {
+        let mock_server = wiremock::MockServer::start().await;
+        // Four bytes, so one token, each
+        let events: String = ["one,", " two", " six", " ten", " end"]
+            .iter()
+            .map(|word| chat_chunk(word))
+            .collect();
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/v1/chat/completions"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/event-stream")
+                    .set_body_string(format!("{events}data: [DONE]\n\n")),
+            )
+            .mount(&mock_server)
+            .await;
+        // 0.01 credits per output token: the third token crosses 0.025 credits
+        let (server, _bg, api_key) = setup(&pool, &mock_server, json!({ "max_cost_per_request": "0.025" }), "1000").await;
+
+        let response = await_chat_status(
+            &server,
+            &api_key,
+            &json!({ "model": "cost-model", "messages": [ { "role": "user", "content": "Count" } ], "stream": true }),
+            200,
+        )
+        .await;
+        let body = response.text();
+        assert!(body.contains(r#""content":" six""#), "{body}");
+        assert!(!body.contains(r#""content":" ten""#), "{body}");
+        assert!(!body.contains("[DONE]"), "{body}");
+        assert!(body.trim_end().ends_with("}}"), "{body}");
+        let last_event = body.trim_end().rsplit("\n\n").next().unwrap();
+        let error: serde_json::Value = serde_json::from_str(last_event.strip_prefix("data: ").unwrap()).unwrap();
+        assert_eq!(error["error"]["code"], "max_cost_per_request_exceeded");
+    }
+
+    /// Stream `count` (at most 100) one-token chunks, " t00" to " t{count - 1}", from the mock upstream.
+    async fn mount_long_stream(mock_server: &wiremock::MockServer, count: usize) {
+        let events: String = (0..count).map(|i| chat_chunk(&format!(" t{i:02}"))).collect();
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/v1/chat/completions"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/event-stream")
+                    .set_body_string(format!("{events}data: [DONE]\n\n")),
+            )
+            .mount(mock_server)
+            .await;
+    }
+
+    async fn stream_long_response(server: &axum_test::TestServer, api_key: &str) -> String {
+        let response = await_chat_status(
+            server,
+            api_key,
+            &json!({ "model": "cost-model", "messages": [ { "role": "user", "content": "Count" } ], "stream": true }),
+            200,
+        )
+        .await;
+        response.text()
+    }
+
+    fn assert_ended_for_credits(body: &str) {
+        assert!(!body.contains("[DONE]"), "{body}");
+        let last_event = body.trim_end().rsplit("\n\n").next().unwrap();
+        let error: serde_json::Value = serde_json::from_str(last_event.strip_prefix("data: ").unwrap()).unwrap();
+        assert_eq!(error["error"]["code"], "insufficient_credits");
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_stream_aborted_when_credits_run_out(pool: PgPool) {
+        let mock_server = wiremock::MockServer::start().await;
+        mount_long_stream(&mock_server, 100).await;
+        // The 83-byte request costs 0.02 credits as 20 prompt tokens, and 0.01 credits per output
+        // token: the fourth token costs more than the 0.05 credits left
+        let (server, _bg, api_key) = setup(&pool, &mock_server, json!({ "credit_exhaustion_mode": "abort" }), "0.05").await;
+
+        let body = stream_long_response(&server, &api_key).await;
+        assert!(body.contains(r#""content":" t03""#), "{body}");
+        assert!(!body.contains(r#""content":" t04""#), "{body}");
+        assert_ended_for_credits(&body);
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_stream_large_prompt_counts_against_credits(pool: PgPool) {
+        let mock_server = wiremock::MockServer::start().await;
+        mount_long_stream(&mock_server, 100).await;
+        // 100 output tokens alone would cost exactly the 1 credit left, but the
+        // prompt of over 8000 bytes costs another 2, so the first token crosses it
+        let (server, _bg, api_key) = setup(&pool, &mock_server, json!({ "credit_exhaustion_mode": "abort" }), "1").await;
+
+        let response = await_chat_status(
+            &server,
+            &api_key,
+            &json!({ "model": "cost-model", "messages": [ { "role": "user", "content": "x".repeat(8000) } ], "stream": true }),
+            200,
+        )
+        .await;
+        let body = response.text();
+        assert!(body.contains(r#""content":" t00""#), "{body}");
+        assert!(!body.contains(r#""content":" t01""#), "{body}");
+        assert_ended_for_credits(&body);
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_stream_completes_into_overdraft_up_to_limit(pool: PgPool) {
+        let mock_server = wiremock::MockServer::start().await;
+        mount_long_stream(&mock_server, 100).await;
+        // 0.05 credits left and 0.1 of overdraft: after 0.02 credits of prompt, the fourteenth
+        // token crosses 0.15 credits
+        let (server, _bg, api_key) = setup(
+            &pool,
+            &mock_server,
+            json!({ "credit_exhaustion_mode": "complete", "max_overdraft": "0.1" }),
+            "0.05",
+        )
+        .await;
+
+        let body = stream_long_response(&server, &api_key).await;
+        assert!(body.contains(r#""content":" t13""#), "{body}");
+        assert!(!body.contains(r#""content":" t14""#), "{body}");
+        assert_ended_for_credits(&body);
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_stream_completes_without_overdraft_limit(pool: PgPool) {
+        let mock_server = wiremock::MockServer::start().await;
+        mount_long_stream(&mock_server, 100).await;
+        let (server, _bg, api_key) = setup(&pool, &mock_server, json!({ "credit_exhaustion_mode": "complete" }), "0.05").await;
+
+        let body = stream_long_response(&server, &api_key).await;
+        assert!(body.contains(r#""content":" t99""#), "{body}");
+        assert!(body.contains("data: [DONE]"), "{body}");
+    }
+}
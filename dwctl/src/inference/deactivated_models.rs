@@ -0,0 +1,78 @@
+//! Requests for deactivated deployments (`/models/{id}/deactivate`).
+//!
+//! A deactivated deployment is left out of the onwards config, so a realtime
+//! request for it already gets onwards' 404. This middleware answers before
+//! anything else can serve or accept the request: a cached response, or a
+//! flex request queued for the batch daemon. With
+//! `onwards.deactivated_models: unavailable` it answers with a 503
+//! `model_disabled` instead of the 404, so clients can tell the model will be
+//! back.
+//!
+//! Deactivated aliases are read from the per-replica
+//! [`deployment_settings`](crate::inference::deployment_settings) snapshot, so
+//! while no model is deactivated requests cost no extra queries.
+//! Anything that can't be checked fails open.
+
+use axum::{
+    Json,
+    extract::{Request, State},
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sqlx_pool_router::PoolProvider;
+use tracing::warn;
+
+use crate::AppState;
+use crate::config::DeactivatedModelResponse;
+use crate::inference::deployment_settings::{json_body, model_override, request_body_limit, requested_model};
+
+/// The response to a request for the deactivated model `model`.
+fn rejection(model: &str, mode: DeactivatedModelResponse) -> Response {
+    match mode {
+        DeactivatedModelResponse::NotFound => crate::error_enrichment::model_not_found(model),
+        DeactivatedModelResponse::Unavailable => {
+            let body = serde_json::json!({
+                "error": {
+                    "message": format!("The model `{model}` is disabled."),
+                    "type": "service_unavailable",
+                    "param": null,
+                    "code": "model_disabled",
+                }
+            });
+            (StatusCode::SERVICE_UNAVAILABLE, Json(body)).into_response()
+        }
+    }
+}
+
+/// Reject requests for deactivated models with a 404, or a 503 when
+/// `onwards.deactivated_models` is `unavailable`.
+///
+/// Fails open: if the aliases cannot be read, the request is passed on unchecked.
+pub async fn deactivated_model_middleware<P: PoolProvider>(State(state): State<AppState<P>>, request: Request, next: Next) -> Response {
+    if request.method() != Method::POST {
+        return next.run(request).await;
+    }
+    let settings = match state.deployment_settings.get(state.db.read()).await {
+        Ok(settings) if !settings.deactivated.is_empty() => settings,
+        Ok(_) => return next.run(request).await,
+        Err(error) => {
+            warn!(%error, "Failed to load deactivated models; passing request through");
+            return next.run(request).await;
+        }
+    };
+    let config = state.current_config();
+
+    // Only JSON bodies name a model; uploads are left unread.
+    let (request, body) = match json_body(request, request_body_limit(&config)).await {
+        Ok(read) => read,
+        Err(response) => return response,
+    };
+    if let Some(model) = requested_model(model_override(request.headers()), body.as_deref())
+        && settings.deactivated.contains(model)
+    {
+        return rejection(model, config.onwards.deactivated_models);
+    }
+
+    next.run(request).await
+}
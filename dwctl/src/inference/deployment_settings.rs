@@ -0,0 +1,231 @@
+//! Per-deployment settings judged on every `/ai/v1` request, and the request
+//! plumbing the checks share.
+//!
+//! The deactivated model, response cache, modality, prompt length and cost
+//! guard middlewares, and the request logging opt-out, each judge a request by
+//! a setting of the deployment it names. They read those settings from one
+//! per-replica [`DeploymentSettingsIndex`] snapshot refreshed every few seconds,
+//! and find the deployment with [`requested_model`]. A JSON body is buffered and
+//! parsed by [`json_body`] once; the middlewares after the first reuse the parse.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::Request,
+    http::{HeaderMap, StatusCode, header::CONTENT_TYPE},
+    response::{IntoResponse, Response},
+};
+use sqlx::PgPool;
+use tracing::warn;
+
+use crate::config::Config;
+use crate::db::errors::DbError;
+use crate::db::handlers::Deployments;
+use crate::inference::cost_guard::CostLimit;
+use crate::inference::modalities::DeclaredModalities;
+use crate::inference::prompt_length::PromptLengthLimit;
+
+/// How long a replica trusts its cached settings before re-reading them.
+const CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Header onwards reads the model from in preference to the body.
+pub const MODEL_OVERRIDE_HEADER: &str = "model-override";
+
+/// The settings of every deployment that sets one, keyed by alias.
+#[derive(Debug, Default)]
+pub struct DeploymentSettings {
+    /// Aliases of deactivated deployments
+    pub deactivated: HashSet<String>,
+    /// Aliases of deployments with `disable_logging` set
+    pub logging_disabled: HashSet<String>,
+    /// Per-request cost limits of priced deployments
+    pub cost_limits: HashMap<String, CostLimit>,
+    /// Declared input and output modalities
+    pub modalities: HashMap<String, DeclaredModalities>,
+    /// Prompt length limits
+    pub prompt_lengths: HashMap<String, PromptLengthLimit>,
+}
+
+impl DeploymentSettings {
+    async fn load(pool: &PgPool) -> Result<Self, DbError> {
+        let mut conn = pool.acquire().await?;
+        let mut deployments = Deployments::new(&mut conn);
+        let deactivated = deployments.list_deactivated_aliases().await?.into_iter().collect();
+        let logging_disabled = deployments.list_logging_disabled_aliases().await?.into_iter().collect();
+        let cost_limits = deployments
+            .list_cost_limits()
+            .await?
+            .into_iter()
+            .filter_map(|row| Some((row.alias.clone(), CostLimit::from_row(&row)?)))
+            .collect();
+        let modalities = deployments
+            .list_modalities()
+            .await?
+            .into_iter()
+            .map(|(alias, input, output)| (alias, DeclaredModalities { input, output }))
+            .collect();
+        let prompt_lengths = deployments
+            .list_prompt_length_limits()
+            .await?
+            .into_iter()
+            .filter_map(|(alias, max, unit)| {
+                Some((
+                    alias,
+                    PromptLengthLimit {
+                        max: u64::try_from(max).ok()?,
+                        unit,
+                    },
+                ))
+            })
+            .collect();
+        Ok(Self {
+            deactivated,
+            logging_disabled,
+            cost_limits,
+            modalities,
+            prompt_lengths,
+        })
+    }
+}
+
+/// Per-replica cache of [`DeploymentSettings`].
+#[derive(Clone, Default)]
+pub struct DeploymentSettingsIndex {
+    cached: Arc<RwLock<Option<(Instant, Arc<DeploymentSettings>)>>>,
+}
+
+impl DeploymentSettingsIndex {
+    /// Drop the cached settings so the next request re-reads them. Other
+    /// replicas pick the change up within [`CACHE_TTL`].
+    pub fn invalidate(&self) {
+        *self.cached.write().expect("deployment settings cache poisoned") = None;
+    }
+
+    /// The current settings, re-read once the cached ones are older than [`CACHE_TTL`].
+    pub async fn get(&self, pool: &PgPool) -> Result<Arc<DeploymentSettings>, DbError> {
+        let cached = self.cached.read().expect("deployment settings cache poisoned").clone();
+        if let Some((fetched_at, settings)) = cached
+            && fetched_at.elapsed() < CACHE_TTL
+        {
+            return Ok(settings);
+        }
+
+        let settings = Arc::new(DeploymentSettings::load(pool).await?);
+        *self.cached.write().expect("deployment settings cache poisoned") = Some((Instant::now(), settings.clone()));
+        Ok(settings)
+    }
+}
+
+/// The `model-override` header of a request, when it is valid.
+pub fn model_override(headers: &HeaderMap) -> Option<&str> {
+    headers.get(MODEL_OVERRIDE_HEADER).and_then(|value| value.to_str().ok())
+}
+
+/// The model a request is routed on, as onwards resolves it: the
+/// `model-override` header if present, otherwise the body's `model`.
+pub fn requested_model<'a>(model_override: Option<&'a str>, body: Option<&'a serde_json::Value>) -> Option<&'a str> {
+    model_override.or_else(|| body?.get("model")?.as_str())
+}
+
+/// Largest request body the middlewares read (`limits.requests.max_body_size`, 0 = unlimited).
+pub fn request_body_limit(config: &Config) -> usize {
+    match config.limits.requests.max_body_size {
+        0 => usize::MAX,
+        n => usize::try_from(n).unwrap_or(usize::MAX),
+    }
+}
+
+/// A body parsed by [`json_body`], kept in the request extensions with the
+/// buffer it was parsed from.
+#[derive(Clone)]
+struct ParsedBody {
+    bytes: Bytes,
+    json: Option<Arc<serde_json::Value>>,
+}
+
+/// Buffer a JSON request body of at most `limit` bytes and parse it.
+///
+/// Returns the request with its body put back, and the parsed body: None when
+/// it isn't JSON. Uploads are left unread. A body an outer middleware already
+/// parsed is still the same buffer when re-read, so its parse is reused. A body
+/// that can't be read is answered with a 400.
+pub async fn json_body(request: Request, limit: usize) -> Result<(Request, Option<Arc<serde_json::Value>>), Response> {
+    let is_json = request
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_none_or(|content_type| content_type.contains("json"));
+    if !is_json {
+        return Ok((request, None));
+    }
+
+    let (mut parts, body) = request.into_parts();
+    let bytes = axum::body::to_bytes(body, limit).await.map_err(|error| {
+        warn!(%error, "Failed to read request body");
+        (StatusCode::BAD_REQUEST, "Failed to read request body").into_response()
+    })?;
+    let parsed = parts
+        .extensions
+        .get::<ParsedBody>()
+        .filter(|parsed| parsed.bytes.as_ptr() == bytes.as_ptr() && parsed.bytes.len() == bytes.len())
+        .cloned();
+    let json = match parsed {
+        Some(parsed) => parsed.json,
+        None => {
+            let json = serde_json::from_slice::<serde_json::Value>(&bytes).ok().map(Arc::new);
+            parts.extensions.insert(ParsedBody {
+                bytes: bytes.clone(),
+                json: json.clone(),
+            });
+            json
+        }
+    };
+    Ok((Request::from_parts(parts, Body::from(bytes)), json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{json_body, requested_model};
+    use axum::{body::Body, extract::Request, http::header::CONTENT_TYPE};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_requested_model() {
+        let body = json!({ "model": "from-body" });
+        assert_eq!(requested_model(None, Some(&body)), Some("from-body"));
+        assert_eq!(requested_model(Some("override"), Some(&body)), Some("override"));
+        assert_eq!(requested_model(None, Some(&json!({ "model": 1 }))), None);
+        assert_eq!(requested_model(None, None), None);
+    }
+
+    #[tokio::test]
+    async fn test_json_body_parses_once() {
+        let request = Request::post("/v1/chat/completions")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"model":"m"}"#))
+            .unwrap();
+        let (request, first) = json_body(request, usize::MAX).await.unwrap();
+        let (request, second) = json_body(request, usize::MAX).await.unwrap();
+        let (first, second) = (first.unwrap(), second.unwrap());
+        assert!(Arc::ptr_eq(&first, &second), "the second read reuses the first parse");
+        assert_eq!(first["model"], "m");
+
+        // A body rewritten in between is parsed again
+        let (parts, _) = request.into_parts();
+        let request = Request::from_parts(parts, Body::from(r#"{"model":"other"}"#));
+        let (_, rewritten) = json_body(request, usize::MAX).await.unwrap();
+        assert_eq!(rewritten.unwrap()["model"], "other");
+
+        // Uploads are left unread
+        let request = Request::post("/v1/files")
+            .header(CONTENT_TYPE, "multipart/form-data; boundary=b")
+            .body(Body::from("--b"))
+            .unwrap();
+        let (_, upload) = json_body(request, usize::MAX).await.unwrap();
+        assert!(upload.is_none());
+    }
+}
@@ -0,0 +1,123 @@
+//! Platform-wide blocking of AI API paths (`onwards.disabled_paths`).
+//!
+//! Applied to the whole `/ai/v1` router (onwards plus the batch API), so a
+//! disabled path is rejected before model lookup, in strict and non-strict
+//! mode alike. Entries are public paths such as `/v1/completions`; the router
+//! is nested at `/ai/v1`, so requests arrive here with that prefix stripped.
+
+use axum::{
+    Json,
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sqlx_pool_router::PoolProvider;
+
+use crate::AppState;
+
+/// Whether `path` (relative to `/ai/v1`) is `disabled` or lies beneath it.
+fn is_disabled(disabled: &str, path: &str) -> bool {
+    let Some(disabled) = disabled.strip_prefix("/v1") else {
+        return false;
+    };
+    let disabled = disabled.trim_end_matches('/');
+    let path = path.trim_end_matches('/');
+    path.strip_prefix(disabled)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Reject requests to paths listed in `onwards.disabled_paths` with an OpenAI-style 404.
+pub async fn disabled_paths_middleware<P: PoolProvider>(State(state): State<AppState<P>>, request: Request, next: Next) -> Response {
+    let config = state.current_config();
+    let path = request.uri().path();
+    if !config.onwards.disabled_paths.iter().any(|disabled| is_disabled(disabled, path)) {
+        return next.run(request).await;
+    }
+
+    let body = serde_json::json!({
+        "error": {
+            "message": format!("The path /v1{path} is disabled on this platform"),
+            "type": "invalid_request_error",
+            "param": null,
+            "code": "path_disabled",
+        }
+    });
+    (StatusCode::NOT_FOUND, Json(body)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_disabled;
+    use crate::test::utils::{await_chat_status, create_test_config, setup_proxied_model};
+    use sqlx::PgPool;
+
+    #[test]
+    fn test_is_disabled_matches_path_and_subpaths() {
+        assert!(is_disabled("/v1/completions", "/completions"));
+        assert!(is_disabled("/v1/completions/", "/completions"));
+        assert!(is_disabled("/v1/batches", "/batches/batch_123/cancel"));
+        assert!(!is_disabled("/v1/completions", "/chat/completions"));
+        assert!(!is_disabled("/v1/completions", "/completions-extra"));
+        assert!(!is_disabled("/v2/completions", "/completions"));
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_disabled_path_rejected_in_both_modes(pool: PgPool) {
+        let mock_server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/v1/chat/completions"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "chatcmpl-1", "object": "chat.completion", "created": 1, "model": "path-model",
+                "choices": [ { "index": 0, "message": { "role": "assistant", "content": "Hello" }, "finish_reason": "stop" } ],
+                "usage": { "prompt_tokens": 5, "completion_tokens": 1, "total_tokens": 6 }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        for strict_mode in [false, true] {
+            let mut config = create_test_config();
+            config.onwards.strict_mode = strict_mode;
+            config.onwards.disabled_paths = vec!["/v1/completions".to_string()];
+            config.background_services.onwards_sync.enabled = true;
+            let app = crate::Application::new_with_pool(config, Some(pool.clone()), None)
+                .await
+                .expect("Failed to create application");
+            let (server, bg_services) = app.into_test_server();
+
+            let alias = format!("path-model-{strict_mode}");
+            let api_key = setup_proxied_model(
+                &server,
+                &bg_services,
+                &pool,
+                &mock_server,
+                serde_json::json!({}),
+                serde_json::json!({ "model_name": "path-model", "alias": alias }),
+            )
+            .await
+            .api_key;
+
+            // The disabled path is rejected regardless of model
+            let resp = server
+                .post("/ai/v1/completions")
+                .add_header("authorization", format!("Bearer {api_key}"))
+                .json(&serde_json::json!({ "model": alias, "prompt": "hi" }))
+                .await;
+            assert_eq!(resp.status_code(), 404, "strict_mode={strict_mode}: {}", resp.text());
+            let body: serde_json::Value = resp.json();
+            assert_eq!(body["error"]["code"], "path_disabled");
+
+            // Other paths still work
+            await_chat_status(
+                &server,
+                &api_key,
+                &serde_json::json!({ "model": alias, "messages": [{ "role": "user", "content": "hi" }] }),
+                200,
+            )
+            .await;
+
+            bg_services.shutdown().await;
+        }
+    }
+}
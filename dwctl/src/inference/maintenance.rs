@@ -0,0 +1,134 @@
+//! Platform-wide maintenance mode for the AI proxy.
+//!
+//! Persisted in the single-row `maintenance_mode` table and toggled through
+//! `/admin/api/v1/maintenance`. While enabled, every request under `/ai/v1`
+//! is rejected with a 503 and a `Retry-After` header; the admin API, health
+//! and version endpoints are mounted outside that router and keep working.
+//!
+//! Each replica caches the row for [`CACHE_TTL`], so a change made on one
+//! replica takes effect on the others within that window. The replica that
+//! handled the change updates its cache immediately.
+
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use axum::{
+    Json,
+    extract::{Request, State},
+    http::{HeaderValue, StatusCode, header::RETRY_AFTER},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use sqlx_pool_router::PoolProvider;
+use tracing::warn;
+
+use crate::AppState;
+use crate::types::UserId;
+
+/// How long a replica trusts its cached maintenance state before re-reading it.
+pub const CACHE_TTL: Duration = Duration::from_secs(2);
+
+/// Message returned to clients when no custom message is set.
+const DEFAULT_MESSAGE: &str = "The service is temporarily unavailable for maintenance. Please retry later.";
+
+/// The persisted maintenance mode row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaintenanceStatus {
+    pub enabled: bool,
+    pub message: Option<String>,
+    pub retry_after_seconds: i32,
+    pub updated_by: Option<UserId>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Read the current maintenance mode row.
+pub async fn load(pool: &PgPool) -> Result<MaintenanceStatus, sqlx::Error> {
+    sqlx::query_as!(
+        MaintenanceStatus,
+        "SELECT enabled, message, retry_after_seconds, updated_by, updated_at FROM maintenance_mode WHERE id"
+    )
+    .fetch_one(pool)
+    .await
+}
+
+/// Persist a new maintenance mode state, returning the stored row.
+pub async fn store(
+    pool: &PgPool,
+    enabled: bool,
+    message: Option<&str>,
+    retry_after_seconds: i32,
+    updated_by: UserId,
+) -> Result<MaintenanceStatus, sqlx::Error> {
+    sqlx::query_as!(
+        MaintenanceStatus,
+        r#"
+        UPDATE maintenance_mode
+        SET enabled = $1, message = $2, retry_after_seconds = $3, updated_by = $4, updated_at = NOW()
+        WHERE id
+        RETURNING enabled, message, retry_after_seconds, updated_by, updated_at
+        "#,
+        enabled,
+        message,
+        retry_after_seconds,
+        updated_by,
+    )
+    .fetch_one(pool)
+    .await
+}
+
+/// Per-replica cache of the maintenance mode row.
+#[derive(Clone, Default)]
+pub struct MaintenanceMode {
+    cached: Arc<RwLock<Option<(Instant, MaintenanceStatus)>>>,
+}
+
+impl MaintenanceMode {
+    /// The current state, re-read from the database once the cache is older than [`CACHE_TTL`].
+    pub async fn status(&self, pool: &PgPool) -> Result<MaintenanceStatus, sqlx::Error> {
+        if let Some((fetched_at, status)) = self.cached.read().expect("maintenance cache poisoned").as_ref()
+            && fetched_at.elapsed() < CACHE_TTL
+        {
+            return Ok(status.clone());
+        }
+        let status = load(pool).await?;
+        self.set(status.clone());
+        Ok(status)
+    }
+
+    /// Replace the cached state, e.g. after this replica persisted a change.
+    pub fn set(&self, status: MaintenanceStatus) {
+        *self.cached.write().expect("maintenance cache poisoned") = Some((Instant::now(), status));
+    }
+}
+
+/// Reject AI API requests with an OpenAI-style 503 while maintenance mode is enabled.
+///
+/// Fails open: if the state cannot be read, the request is let through.
+pub async fn maintenance_middleware<P: PoolProvider>(State(state): State<AppState<P>>, request: Request, next: Next) -> Response {
+    let status = match state.maintenance.status(state.db.read()).await {
+        Ok(status) => status,
+        Err(error) => {
+            warn!(%error, "failed to read maintenance mode; allowing request");
+            return next.run(request).await;
+        }
+    };
+    if !status.enabled {
+        return next.run(request).await;
+    }
+
+    let body = serde_json::json!({
+        "error": {
+            "message": status.message.as_deref().unwrap_or(DEFAULT_MESSAGE),
+            "type": "service_unavailable",
+            "param": null,
+            "code": "maintenance_mode",
+        }
+    });
+    let mut response = (StatusCode::SERVICE_UNAVAILABLE, Json(body)).into_response();
+    response
+        .headers_mut()
+        .insert(RETRY_AFTER, HeaderValue::from(status.retry_after_seconds));
+    response
+}
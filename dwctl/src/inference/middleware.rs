@@ -68,6 +68,9 @@ pub struct InferenceMiddlewareState<P: PoolProvider + Clone = sqlx_pool_router::
     /// (`config.batches.async_requests.completion_window`, e.g. "1h"). The
     /// unverified cap is measured over a rolling window of this length.
     pub flex_completion_window: String,
+    /// Read per request for how a flex request for a model the key's user has
+    /// no access to is answered (`config.onwards.unauthorized_models`).
+    pub config: crate::SharedConfig,
     /// Encrypted key custody for ZDR flex bodies. `None` disables ZDR.
     pub keystore: Option<crate::keystore::Keystore>,
     /// Per-key ZDR policy map (api key secret to the owning account's
@@ -279,7 +282,12 @@ pub async fn inference_middleware<P: PoolProvider + Clone + Send + Sync + 'stati
     // Flex requests bypass onwards entirely — the daemon processes them later —
     // so we must enforce auth here.
     if matches!(service_tier, ServiceTier::Flex) {
+        let unauthorized_models = state.config.snapshot().onwards.unauthorized_models;
+        let hide_model = unauthorized_models == crate::config::UnauthorizedModelResponse::NotFound;
         match api_key.as_deref() {
+            None if hide_model => {
+                return crate::error_enrichment::unauthorized_model_response(model, unauthorized_models);
+            }
             None => {
                 return Response::builder()
                     .status(StatusCode::UNAUTHORIZED)
@@ -290,12 +298,18 @@ pub async fn inference_middleware<P: PoolProvider + Clone + Send + Sync + 'stati
                     .unwrap();
             }
             Some(key) => {
-                if let Err(msg) = crate::error_enrichment::validate_api_key_model_access(state.dwctl_pool.clone(), key, model).await {
+                if let Err(rejection) = crate::error_enrichment::validate_api_key_model_access(state.dwctl_pool.clone(), key, model).await {
+                    // Answered exactly like the same request on the realtime path.
+                    if rejection.reason == Some(crate::error_enrichment::reason::NO_GROUP_ACCESS)
+                        || (hide_model && rejection.reason.is_some())
+                    {
+                        return crate::error_enrichment::unauthorized_model_response(model, unauthorized_models);
+                    }
                     return Response::builder()
                         .status(StatusCode::FORBIDDEN)
                         .header("content-type", "application/json")
                         .body(Body::from(
-                            serde_json::json!({"error": {"message": msg, "type": "invalid_request_error"}}).to_string(),
+                            serde_json::json!({"error": {"message": rejection.message, "type": "invalid_request_error"}}).to_string(),
                         ))
                         .unwrap();
                 }
@@ -1347,12 +1361,15 @@ mod tests {
                 ApiKeyCreate {
                     name: "Org realtime key".to_string(),
                     description: None,
-                    purpose: ApiKeyPurpose::Realtime,
+                    purpose: Some(ApiKeyPurpose::Realtime),
                     requests_per_second: None,
                     burst_size: None,
                     member_id: None,
                     spend_limit: None,
                     spend_limit_interval: None,
+                    allowed_model_ids: None,
+                    denied_model_ids: None,
+                    max_priority: None,
                 },
             ))
             .await
@@ -11,16 +11,48 @@
 //!   the per-surface response renderers (`detail_to_*_object`).
 //! - **streaming**: inline multi-step (warm-path) streaming/blocking responses.
 //! - **handler**: `GET /ai/v1/responses/{id}` HTTP handler.
+//! - **api_key_headers**: accepts API keys from the headers listed in
+//!   `onwards.api_key_headers` by moving them into `Authorization: Bearer`.
+//! - **alias_normalization**: rewrites models that match an alias only after
+//!   `onwards.alias_normalization` to that alias.
+//! - **disabled_paths**: rejects paths listed in `onwards.disabled_paths`.
+//! - **maintenance**: rejects all requests with a 503 while maintenance mode is on.
+//! - **deployment_settings**: the per-replica cache of deployment settings the
+//!   checks below judge requests by, and the body and model extraction they share.
+//! - **deactivated_models**: rejects requests for deactivated models with a 404,
+//!   or a 503 with `onwards.deactivated_models: unavailable`.
+//! - **response_cache**: replays stored responses to identical deterministic
+//!   chat completions (`onwards.response_cache`) without an upstream call.
+//! - **cost_guard**: rejects requests whose worst-case cost exceeds the model's
+//!   `max_cost_per_request` and cuts off streams that cross it or that run the
+//!   caller out of credits (`credit_exhaustion_mode`).
+//! - **modalities**: rejects content and requested output of a modality the
+//!   model doesn't declare in `input_modalities` / `output_modalities`.
+//! - **prompt_length**: rejects prompts longer than the model's
+//!   `max_prompt_length`, in tokens or characters.
+//! - **stream_keepalive**: writes SSE comment heartbeats on idle streaming
+//!   responses (`onwards.stream_keepalive`).
 //! - **image_normalizer_middleware**: body-rewriting image normalisation shared
 //!   by the chat-completions and responses surfaces.
 //! - **tools**: server-side tool resolution (injection) and execution (executor).
 //! - **engine**: the multi-step Open Responses orchestration loop and the
 //!   daemon-side request processor.
 
+pub mod alias_normalization;
+pub mod api_key_headers;
+pub mod cost_guard;
+pub mod deactivated_models;
+pub mod deployment_settings;
+pub mod disabled_paths;
 pub mod handler;
 pub mod image_normalizer_middleware;
+pub mod maintenance;
 pub mod middleware;
+pub mod modalities;
+pub mod prompt_length;
+pub mod response_cache;
 pub mod store;
+pub mod stream_keepalive;
 pub mod streaming;
 
 pub mod engine;
@@ -0,0 +1,296 @@
+//! Input and output modality checks (`input_modalities` / `output_modalities`
+//! on deployments).
+//!
+//! Applied outside the inference middleware and request logging, so a request
+//! is judged before it is queued, forwarded or billed. Content parts in
+//! `messages` (chat completions) or `input` (responses) are classified as
+//! text, image or audio; a part whose modality the model doesn't accept is
+//! rejected with a 422. A `modalities` list asking for output the model doesn't
+//! produce is rejected the same way. Part types this module doesn't recognise,
+//! such as files, are left for the upstream to judge.
+//!
+//! Declarations are read from the per-replica
+//! [`deployment_settings`](crate::inference::deployment_settings) snapshot, so
+//! requests to models that declare nothing cost no extra queries.
+//! Anything that can't be checked fails open.
+
+use axum::{
+    Json,
+    extract::{Request, State},
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sqlx_pool_router::PoolProvider;
+use tracing::warn;
+
+use crate::AppState;
+use crate::db::models::deployments::Modality;
+use crate::inference::deployment_settings::{json_body, model_override, request_body_limit, requested_model};
+
+/// Error code reported when a request uses a modality the model doesn't support.
+const ERROR_CODE: &str = "unsupported_modality";
+
+/// The modalities a deployment declares; `None` leaves that direction unchecked.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeclaredModalities {
+    pub input: Option<Vec<Modality>>,
+    pub output: Option<Vec<Modality>>,
+}
+
+/// The modality of one content part, by its `type`.
+fn part_modality(part: &serde_json::Value) -> Option<Modality> {
+    match part {
+        serde_json::Value::String(_) => Some(Modality::Text),
+        serde_json::Value::Object(_) => match part.get("type")?.as_str()? {
+            "text" | "input_text" | "output_text" => Some(Modality::Text),
+            "image_url" | "input_image" | "image" => Some(Modality::Image),
+            "input_audio" | "audio" => Some(Modality::Audio),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Every modality used by the content of a request, with the field it appears in.
+fn input_modalities(body: &serde_json::Value) -> Vec<(&'static str, Modality)> {
+    let mut found = Vec::new();
+    for field in ["messages", "input"] {
+        let items = match body.get(field) {
+            Some(serde_json::Value::Array(items)) => items.as_slice(),
+            Some(input @ serde_json::Value::String(_)) => std::slice::from_ref(input),
+            _ => continue,
+        };
+        for item in items {
+            // A message carries its parts in `content`; a bare responses input item is a part itself
+            let parts = match item.get("content") {
+                Some(serde_json::Value::Array(parts)) => parts.as_slice(),
+                Some(content) => std::slice::from_ref(content),
+                None => std::slice::from_ref(item),
+            };
+            found.extend(parts.iter().filter_map(part_modality).map(|modality| (field, modality)));
+        }
+    }
+    found
+}
+
+/// Output modalities the request asks for in `modalities`.
+fn requested_output_modalities(body: &serde_json::Value) -> Vec<Modality> {
+    body.get("modalities")
+        .and_then(|modalities| modalities.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|modality| modality.as_str().and_then(Modality::try_parse))
+        .collect()
+}
+
+/// Why a request doesn't fit the model's declared modalities, with the offending field.
+fn unsupported(model: &str, declared: &DeclaredModalities, body: &serde_json::Value) -> Option<(String, &'static str)> {
+    if let Some(accepted) = &declared.input
+        && let Some((field, modality)) = input_modalities(body)
+            .into_iter()
+            .find(|(_, modality)| !accepted.contains(modality))
+    {
+        return Some((format!("Model '{model}' does not accept {} input.", modality.as_str()), field));
+    }
+    if let Some(produced) = &declared.output
+        && let Some(modality) = requested_output_modalities(body)
+            .into_iter()
+            .find(|modality| !produced.contains(modality))
+    {
+        return Some((
+            format!("Model '{model}' does not produce {} output.", modality.as_str()),
+            "modalities",
+        ));
+    }
+    None
+}
+
+fn error_body(message: String, param: &str) -> serde_json::Value {
+    serde_json::json!({
+        "error": {
+            "message": message,
+            "type": "invalid_request_error",
+            "param": param,
+            "code": ERROR_CODE,
+        }
+    })
+}
+
+/// Reject requests whose content or requested output uses a modality the
+/// model doesn't declare, with a 422.
+///
+/// Fails open: if the declarations cannot be read, the request is passed on unchecked.
+pub async fn modality_middleware<P: PoolProvider>(State(state): State<AppState<P>>, request: Request, next: Next) -> Response {
+    if request.method() != Method::POST {
+        return next.run(request).await;
+    }
+    let settings = match state.deployment_settings.get(state.db.read()).await {
+        Ok(settings) if !settings.modalities.is_empty() => settings,
+        Ok(_) => return next.run(request).await,
+        Err(error) => {
+            warn!(%error, "Failed to load model modalities; passing request through");
+            return next.run(request).await;
+        }
+    };
+    // Only JSON bodies carry content parts; uploads are left unread.
+    let (request, body) = match json_body(request, request_body_limit(&state.current_config())).await {
+        Ok((request, Some(body))) => (request, body),
+        Ok((request, None)) => return next.run(request).await,
+        Err(response) => return response,
+    };
+    if let Some(model) = requested_model(model_override(request.headers()), Some(&*body))
+        && let Some(declared) = settings.modalities.get(model)
+        && let Some((message, param)) = unsupported(model, declared, &body)
+    {
+        return (StatusCode::UNPROCESSABLE_ENTITY, Json(error_body(message, param))).into_response();
+    }
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeclaredModalities, input_modalities, requested_output_modalities, unsupported};
+    use crate::db::models::deployments::Modality;
+    use crate::test::utils::{await_chat_status, create_test_config, setup_proxied_model};
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    fn image_request(model: &str) -> serde_json::Value {
+        json!({
+            "model": model,
+            "messages": [{
+                "role": "user",
+                "content": [
+                    { "type": "text", "text": "What is in this image?" },
+                    { "type": "image_url", "image_url": { "url": "data:image/png;base64,iVBORw0KGgo=" } }
+                ]
+            }]
+        })
+    }
+
+    #[test]
+    fn test_input_modalities_of_chat_and_responses_requests() {
+        assert_eq!(
+            input_modalities(&image_request("m")),
+            vec![("messages", Modality::Text), ("messages", Modality::Image)]
+        );
+        assert_eq!(input_modalities(&json!({ "input": "Hello" })), vec![("input", Modality::Text)]);
+        assert_eq!(
+            input_modalities(&json!({
+                "input": [
+                    { "role": "user", "content": [ { "type": "input_audio", "input_audio": { "data": "", "format": "wav" } } ] },
+                    { "type": "input_image", "image_url": "https://example.com/cat.png" },
+                    { "type": "input_file", "file_id": "file-1" }
+                ]
+            })),
+            vec![("input", Modality::Audio), ("input", Modality::Image)]
+        );
+        assert_eq!(
+            requested_output_modalities(&json!({ "modalities": ["text", "audio"] })),
+            vec![Modality::Text, Modality::Audio]
+        );
+    }
+
+    #[test]
+    fn test_unsupported_reports_first_undeclared_modality() {
+        let text_only = DeclaredModalities {
+            input: Some(vec![Modality::Text]),
+            output: Some(vec![Modality::Text]),
+        };
+        let (message, param) = unsupported("m", &text_only, &image_request("m")).unwrap();
+        assert_eq!(message, "Model 'm' does not accept image input.");
+        assert_eq!(param, "messages");
+
+        let (message, param) = unsupported("m", &text_only, &json!({ "messages": [], "modalities": ["audio"] })).unwrap();
+        assert_eq!(message, "Model 'm' does not produce audio output.");
+        assert_eq!(param, "modalities");
+
+        // Undeclared directions are not checked
+        assert_eq!(unsupported("m", &DeclaredModalities::default(), &image_request("m")), None);
+    }
+
+    /// A user with a key for a text-only model and a vision model served by `mock_server`.
+    async fn setup(pool: &PgPool, mock_server: &wiremock::MockServer) -> (axum_test::TestServer, crate::BackgroundServices, String) {
+        let mut config = create_test_config();
+        config.background_services.onwards_sync.enabled = true;
+        let (server, bg_services) = crate::Application::new_with_pool(config, Some(pool.clone()), None)
+            .await
+            .expect("Failed to create application")
+            .into_test_server();
+        let proxied = setup_proxied_model(
+            &server,
+            &bg_services,
+            pool,
+            mock_server,
+            json!({}),
+            json!({ "alias": "vision-model", "allow_public": true, "input_modalities": ["text", "image"], "output_modalities": ["text"] }),
+        )
+        .await;
+        assert_eq!(proxied.model["input_modalities"], json!(["text", "image"]));
+        let admin_headers = &proxied.admin_headers;
+        let response = server
+            .post("/admin/api/v1/models")
+            .add_header(&admin_headers[0].0, &admin_headers[0].1)
+            .add_header(&admin_headers[1].0, &admin_headers[1].1)
+            .json(&json!({
+                "type": "standard",
+                "model_name": "text-model",
+                "alias": "text-model",
+                "hosted_on": proxied.model["hosted_on"],
+                "allow_public": true,
+                "input_modalities": ["text"],
+                "output_modalities": ["text"]
+            }))
+            .await;
+        assert_eq!(response.status_code(), 200, "Failed to create model");
+        assert_eq!(response.json::<serde_json::Value>()["input_modalities"], json!(["text"]));
+        let response = server
+            .post("/admin/api/v1/transactions")
+            .add_header(&admin_headers[0].0, &admin_headers[0].1)
+            .add_header(&admin_headers[1].0, &admin_headers[1].1)
+            .json(&json!({
+                "user_id": proxied.user.id,
+                "transaction_type": "admin_grant",
+                "amount": 1000,
+                "source_id": proxied.admin.id,
+                "description": "Modality test credits"
+            }))
+            .await;
+        assert_eq!(response.status_code(), 201, "Failed to grant credits");
+
+        bg_services.sync_onwards_config(pool).await.expect("Failed to sync onwards config");
+        (server, bg_services, proxied.api_key)
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_image_part_rejected_for_text_model_and_forwarded_to_vision_model(pool: PgPool) {
+        let mock_server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/v1/chat/completions"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(json!({
+                "id": "chatcmpl-1", "object": "chat.completion", "created": 1, "model": "vision-model",
+                "choices": [ { "index": 0, "message": { "role": "assistant", "content": "A cat" }, "finish_reason": "stop" } ],
+                "usage": { "prompt_tokens": 5, "completion_tokens": 2, "total_tokens": 7 }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let (server, _bg, api_key) = setup(&pool, &mock_server).await;
+
+        await_chat_status(&server, &api_key, &image_request("vision-model"), 200).await;
+
+        let rejected = server
+            .post("/ai/v1/chat/completions")
+            .add_header("authorization", format!("Bearer {api_key}"))
+            .json(&image_request("text-model"))
+            .await;
+        rejected.assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+        let error = rejected.json::<serde_json::Value>();
+        assert_eq!(error["error"]["code"], "unsupported_modality");
+        assert_eq!(error["error"]["param"], "messages");
+        assert_eq!(error["error"]["message"], "Model 'text-model' does not accept image input.");
+    }
+}
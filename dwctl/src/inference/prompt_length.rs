@@ -0,0 +1,345 @@
+//! Prompt length limits (`max_prompt_length` and `max_prompt_length_unit` on
+//! deployments).
+//!
+//! A guardrail of our own against abuse and oversized context, separate from
+//! the provider's context window. Applied outside the inference middleware and
+//! request logging, so a request is judged before it is queued, forwarded or
+//! billed. The prompt is the text of `instructions`, `messages`, `prompt` and
+//! `input`; a prompt longer than the deployment's limit is rejected with a 422.
+//!
+//! A limit in characters counts the characters of that text. A limit in tokens
+//! is counted by tokenizer-svc when cached-input pricing is enabled (so the
+//! service is deployed) and it maps the model. Otherwise, or when the call
+//! fails, tokens are estimated from the character count at
+//! [`CHARS_PER_TOKEN`]. Prompts no longer than the limit in bytes can't exceed
+//! it and are never sent to tokenizer-svc.
+//!
+//! Limits are read from the per-replica
+//! [`deployment_settings`](crate::inference::deployment_settings) snapshot, so
+//! requests to models without a limit cost no extra queries. Anything that
+//! can't be checked fails open.
+
+use axum::{
+    Json,
+    extract::{Request, State},
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sqlx::PgPool;
+use tracing::{debug, warn};
+
+use crate::db::models::deployments::PromptLengthUnit;
+use crate::inference::deployment_settings::{DeploymentSettingsIndex, json_body, model_override, requested_model};
+use crate::prompt_cache::{TokenizerClient, TokenizerError};
+
+/// Error code reported when a prompt is longer than the model allows.
+const ERROR_CODE: &str = "max_prompt_length_exceeded";
+
+/// Characters per token assumed when tokens can't be counted.
+const CHARS_PER_TOKEN: u64 = 4;
+
+/// Request fields holding prompt text, in the order they are reported.
+const PROMPT_FIELDS: [&str; 3] = ["messages", "prompt", "input"];
+
+/// A deployment's prompt length limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PromptLengthLimit {
+    pub max: u64,
+    pub unit: PromptLengthUnit,
+}
+
+/// State for [`prompt_length_middleware`].
+#[derive(Clone)]
+pub struct PromptLengthState {
+    pub pool: PgPool,
+    pub settings: DeploymentSettingsIndex,
+    /// Counts tokens when set; tokens are estimated from characters otherwise
+    pub tokenizer: Option<TokenizerClient>,
+    /// Largest request body read (`limits.requests.max_body_size`, 0 = unlimited)
+    pub body_limit: usize,
+}
+
+/// Collect the text of a prompt value: a string, an array of strings or
+/// content parts, or messages and input items carrying their parts in `content`.
+fn push_text(value: &serde_json::Value, segments: &mut Vec<String>) {
+    match value {
+        serde_json::Value::String(text) => segments.push(text.clone()),
+        serde_json::Value::Array(items) => items.iter().for_each(|item| push_text(item, segments)),
+        serde_json::Value::Object(object) => {
+            if let Some(text) = object.get("text").and_then(|text| text.as_str()) {
+                segments.push(text.to_string());
+            } else if let Some(content) = object.get("content") {
+                push_text(content, segments);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The text of a request's prompt, with the field it is reported against.
+pub(crate) fn prompt_segments(body: &serde_json::Value) -> (Option<&'static str>, Vec<String>) {
+    let mut segments = Vec::new();
+    if let Some(instructions) = body.get("instructions").and_then(|instructions| instructions.as_str()) {
+        segments.push(instructions.to_string());
+    }
+    let mut param = None;
+    for field in PROMPT_FIELDS {
+        if let Some(value) = body.get(field) {
+            param.get_or_insert(field);
+            push_text(value, &mut segments);
+        }
+    }
+    (param, segments)
+}
+
+/// Tokens in `segments`, and whether the count is an estimate.
+pub(crate) async fn count_tokens(tokenizer: Option<&TokenizerClient>, model: &str, segments: &[String]) -> (u64, bool) {
+    if let Some(tokenizer) = tokenizer {
+        match tokenizer.tokenize(model, segments).await {
+            Ok(response) => return (u64::from(response.total), false),
+            Err(TokenizerError::Unmapped(_)) => debug!(model, "No tokenizer for model; estimating prompt tokens"),
+            Err(error) => warn!(%error, model, "Failed to count prompt tokens; estimating them"),
+        }
+    }
+    let characters: u64 = segments.iter().map(|segment| segment.chars().count() as u64).sum();
+    (characters.div_ceil(CHARS_PER_TOKEN), true)
+}
+
+/// The message for a prompt over its limit, if it is.
+async fn check(tokenizer: Option<&TokenizerClient>, model: &str, limit: PromptLengthLimit, segments: &[String]) -> Option<String> {
+    let (length, estimated) = match limit.unit {
+        PromptLengthUnit::Characters => (segments.iter().map(|segment| segment.chars().count() as u64).sum(), false),
+        PromptLengthUnit::Tokens => {
+            // A token covers at least one byte, so a prompt no longer than the limit in bytes is within it
+            if segments.iter().map(|segment| segment.len() as u64).sum::<u64>() <= limit.max {
+                return None;
+            }
+            count_tokens(tokenizer, model, segments).await
+        }
+    };
+    if length <= limit.max {
+        return None;
+    }
+    let unit = limit.unit.as_str();
+    let about = if estimated { "about " } else { "" };
+    Some(format!(
+        "The prompt is {about}{length} {unit} long, above the maximum of {} {unit} for model '{model}'. Shorten it.",
+        limit.max
+    ))
+}
+
+fn error_body(message: String, param: Option<&str>) -> serde_json::Value {
+    serde_json::json!({
+        "error": {
+            "message": message,
+            "type": "invalid_request_error",
+            "param": param,
+            "code": ERROR_CODE,
+        }
+    })
+}
+
+/// Reject requests whose prompt is longer than the model's `max_prompt_length`, with a 422.
+///
+/// Fails open: if the limits cannot be read, the request is passed on unchecked.
+pub async fn prompt_length_middleware(State(state): State<PromptLengthState>, request: Request, next: Next) -> Response {
+    if request.method() != Method::POST {
+        return next.run(request).await;
+    }
+    let settings = match state.settings.get(&state.pool).await {
+        Ok(settings) if !settings.prompt_lengths.is_empty() => settings,
+        Ok(_) => return next.run(request).await,
+        Err(error) => {
+            warn!(%error, "Failed to load prompt length limits; passing request through");
+            return next.run(request).await;
+        }
+    };
+    // Only JSON bodies carry a prompt; uploads are left unread.
+    let (request, body) = match json_body(request, state.body_limit).await {
+        Ok((request, Some(body))) => (request, body),
+        Ok((request, None)) => return next.run(request).await,
+        Err(response) => return response,
+    };
+    if let Some(model) = requested_model(model_override(request.headers()), Some(&*body))
+        && let Some(limit) = settings.prompt_lengths.get(model)
+    {
+        let (param, segments) = prompt_segments(&body);
+        if let Some(message) = check(state.tokenizer.as_ref(), model, *limit, &segments).await {
+            return (StatusCode::UNPROCESSABLE_ENTITY, Json(error_body(message, param))).into_response();
+        }
+    }
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PromptLengthLimit, check, prompt_segments};
+    use crate::db::models::deployments::PromptLengthUnit;
+    use crate::prompt_cache::TokenizerClient;
+    use crate::test::utils::{await_chat_status, create_test_config, setup_proxied_model};
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    fn tokens(max: u64) -> PromptLengthLimit {
+        PromptLengthLimit {
+            max,
+            unit: PromptLengthUnit::Tokens,
+        }
+    }
+
+    #[test]
+    fn test_prompt_segments_of_chat_completions_and_responses_requests() {
+        let (param, segments) = prompt_segments(&json!({
+            "model": "m",
+            "messages": [
+                { "role": "system", "content": "Be brief." },
+                { "role": "user", "content": [
+                    { "type": "text", "text": "What is in this image?" },
+                    { "type": "image_url", "image_url": { "url": "https://example.com/cat.png" } }
+                ] },
+                { "role": "assistant", "content": null, "tool_calls": [] }
+            ]
+        }));
+        assert_eq!(param, Some("messages"));
+        assert_eq!(segments, vec!["Be brief.", "What is in this image?"]);
+
+        let (param, segments) = prompt_segments(&json!({
+            "instructions": "Answer in French.",
+            "input": [
+                { "role": "user", "content": [ { "type": "input_text", "text": "Hello" } ] },
+                { "type": "input_text", "text": "there" }
+            ]
+        }));
+        assert_eq!(param, Some("input"));
+        assert_eq!(segments, vec!["Answer in French.", "Hello", "there"]);
+
+        let (param, segments) = prompt_segments(&json!({ "prompt": ["a", "b"] }));
+        assert_eq!(param, Some("prompt"));
+        assert_eq!(segments, vec!["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn test_limit_without_tokenizer_counts_characters_or_estimates_tokens() {
+        let prompt = vec!["x".repeat(100)];
+        let characters = PromptLengthLimit {
+            max: 100,
+            unit: PromptLengthUnit::Characters,
+        };
+        assert_eq!(check(None, "m", characters, &prompt).await, None);
+        assert_eq!(
+            check(None, "m", PromptLengthLimit { max: 99, ..characters }, &prompt).await,
+            Some("The prompt is 100 characters long, above the maximum of 99 characters for model 'm'. Shorten it.".to_string())
+        );
+
+        // 100 characters at four per token
+        assert_eq!(check(None, "m", tokens(25), &prompt).await, None);
+        assert_eq!(
+            check(None, "m", tokens(24), &prompt).await,
+            Some("The prompt is about 25 tokens long, above the maximum of 24 tokens for model 'm'. Shorten it.".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tokenizer_counts_tokens_and_unmapped_models_fall_back() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/v1/tokenize"))
+            .and(wiremock::matchers::body_partial_json(json!({ "virtual_model": "mapped" })))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(json!({
+                "virtual_model": "mapped",
+                "tokenizer_version": "sha256:abc",
+                "segment_counts": [30],
+                "cumulative": [30],
+                "total": 30
+            })))
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/v1/tokenize"))
+            .respond_with(wiremock::ResponseTemplate::new(422).set_body_json(json!({ "error": "UNMAPPED_MODEL" })))
+            .mount(&server)
+            .await;
+        let tokenizer = TokenizerClient::new(server.uri());
+        let prompt = vec!["x".repeat(100)];
+
+        assert_eq!(check(Some(&tokenizer), "mapped", tokens(30), &prompt).await, None);
+        assert_eq!(
+            check(Some(&tokenizer), "mapped", tokens(29), &prompt).await,
+            Some("The prompt is 30 tokens long, above the maximum of 29 tokens for model 'mapped'. Shorten it.".to_string())
+        );
+        // Without a tokenizer mapping, tokens are estimated
+        assert_eq!(
+            check(Some(&tokenizer), "unmapped", tokens(24), &prompt).await,
+            Some("The prompt is about 25 tokens long, above the maximum of 24 tokens for model 'unmapped'. Shorten it.".to_string())
+        );
+        // A prompt no longer than the limit in bytes isn't sent to the tokenizer
+        assert_eq!(check(Some(&tokenizer), "mapped", tokens(100), &prompt).await, None);
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_prompt_above_limit_rejected_and_below_limit_forwarded(pool: PgPool) {
+        let mock_server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/v1/chat/completions"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(json!({
+                "id": "chatcmpl-1", "object": "chat.completion", "created": 1, "model": "short-model",
+                "choices": [ { "index": 0, "message": { "role": "assistant", "content": "Hi" }, "finish_reason": "stop" } ],
+                "usage": { "prompt_tokens": 5, "completion_tokens": 1, "total_tokens": 6 }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut config = create_test_config();
+        config.background_services.onwards_sync.enabled = true;
+        let (server, bg_services) = crate::Application::new_with_pool(config, Some(pool.clone()), None)
+            .await
+            .expect("Failed to create application")
+            .into_test_server();
+        let proxied = setup_proxied_model(
+            &server,
+            &bg_services,
+            &pool,
+            &mock_server,
+            json!({}),
+            json!({ "alias": "short-model", "allow_public": true, "max_prompt_length": 20, "max_prompt_length_unit": "characters" }),
+        )
+        .await;
+        assert_eq!(proxied.model["max_prompt_length"], 20);
+        assert_eq!(proxied.model["max_prompt_length_unit"], "characters");
+
+        let admin_headers = &proxied.admin_headers;
+        let response = server
+            .post("/admin/api/v1/transactions")
+            .add_header(&admin_headers[0].0, &admin_headers[0].1)
+            .add_header(&admin_headers[1].0, &admin_headers[1].1)
+            .json(&json!({
+                "user_id": proxied.user.id,
+                "transaction_type": "admin_grant",
+                "amount": 1000,
+                "source_id": proxied.admin.id,
+                "description": "Prompt length test credits"
+            }))
+            .await;
+        assert_eq!(response.status_code(), 201, "Failed to grant credits");
+        let api_key = proxied.api_key;
+
+        let request = |content: &str| json!({ "model": "short-model", "messages": [ { "role": "user", "content": content } ] });
+
+        let rejected = server
+            .post("/ai/v1/chat/completions")
+            .add_header("authorization", format!("Bearer {api_key}"))
+            .json(&request("This prompt is well over twenty characters."))
+            .await;
+        rejected.assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+        let error = rejected.json::<serde_json::Value>();
+        assert_eq!(error["error"]["code"], "max_prompt_length_exceeded");
+        assert_eq!(error["error"]["param"], "messages");
+
+        await_chat_status(&server, &api_key, &request("Short prompt."), 200).await;
+    }
+}
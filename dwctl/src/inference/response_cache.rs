@@ -0,0 +1,533 @@
+//! Response caching for deterministic chat completions (`onwards.response_cache`).
+//!
+//! Applied outside request logging on the onwards router, so a cache hit is
+//! answered without an upstream call and is neither logged nor billed. Only
+//! non-streaming, realtime `/chat/completions` requests whose `temperature` is
+//! at most `max_temperature` are cached. Entries are keyed on a SHA-256 of the
+//! request body and scoped to the deployment and the API key's owner; accounts
+//! with zero data retention are never cached. A client skips the cache for one
+//! request with `Cache-Control: no-cache` or `no-store`.
+//!
+//! Onwards never sees a hit, so the key's access to the model is checked here
+//! before one is served. Anything that can't be checked (missing or unknown
+//! key, database errors) fails open to the normal path, which produces the
+//! usual errors.
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{
+        HeaderMap, HeaderValue, Method, StatusCode,
+        header::{CACHE_CONTROL, CONTENT_TYPE},
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use sqlx_pool_router::PoolProvider;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::config::ResponseCacheConfig;
+use crate::db::errors::DbError;
+use crate::error_enrichment::{check_modality_blocked, check_user_has_model_access};
+use crate::inference::deployment_settings::{json_body, model_override, request_body_limit, requested_model};
+
+/// Response header reporting whether a cacheable request was served from the cache.
+pub const CACHE_STATUS_HEADER: &str = "x-dwctl-cache";
+
+/// Temperature a request without one is sampled at (the OpenAI default).
+const DEFAULT_TEMPERATURE: f64 = 1.0;
+
+/// Whether the client asked to bypass the cache for this request.
+fn opted_out(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(CACHE_CONTROL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|directive| matches!(directive.trim().to_ascii_lowercase().as_str(), "no-cache" | "no-store"))
+}
+
+/// Whether a parsed chat completions body is deterministic enough to cache.
+fn is_cacheable(body: &serde_json::Value, config: &ResponseCacheConfig) -> bool {
+    if body.get("stream").and_then(|stream| stream.as_bool()).unwrap_or(false) {
+        return false;
+    }
+    // Flex requests are queued and billed by the batch path
+    if body.get("service_tier").and_then(|tier| tier.as_str()) == Some("flex") {
+        return false;
+    }
+    let temperature = body
+        .get("temperature")
+        .and_then(|temperature| temperature.as_f64())
+        .unwrap_or(DEFAULT_TEMPERATURE);
+    temperature <= config.max_temperature
+}
+
+/// Hash of a request body. `serde_json` keeps object keys sorted, so
+/// re-serializing the parsed body makes key order and whitespace irrelevant.
+fn request_hash(body: &serde_json::Value) -> Vec<u8> {
+    let normalized = serde_json::to_vec(body).expect("JSON value serializes");
+    Sha256::digest(&normalized).to_vec()
+}
+
+/// The owner of `api_key`, if it may be served cached completions of `model`.
+///
+/// Mirrors what onwards would enforce for a realtime call: an inference-purpose
+/// key of a live user with access to the model, the model inside the key's
+/// allow/deny lists, and no deny rule for the key's purpose. `Ok(None)` also
+/// covers zero-data-retention accounts, whose completions must not be stored.
+async fn cache_principal(pool: &PgPool, api_key: &str, model: &str) -> Result<Option<Uuid>, DbError> {
+    let mut conn = pool.acquire().await?;
+    // The lists are the scope root's, as in the onwards sync, so cap-scope
+    // child keys inherit their parent's.
+    let key = sqlx::query!(
+        r#"
+        SELECT ak.user_id, ak.purpose, u.zero_data_retention,
+               NOT EXISTS (
+                   SELECT 1
+                   FROM api_keys scope
+                   JOIN deployed_models dm ON dm.alias = $2 AND dm.deleted = false
+                   WHERE scope.id = COALESCE(ak.parent_api_key_id, ak.id)
+                     AND (
+                         (scope.allowed_model_ids IS NOT NULL AND NOT (dm.id = ANY(scope.allowed_model_ids)))
+                         OR dm.id = ANY(COALESCE(scope.denied_model_ids, '{}'))
+                     )
+               ) AS "model_in_scope!"
+        FROM api_keys ak
+        JOIN users u ON u.id = ak.user_id
+        WHERE ak.secret = $1 AND ak.is_deleted = false AND u.is_deleted = false
+        "#,
+        api_key,
+        model
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+    drop(conn);
+
+    let Some(key) = key else {
+        return Ok(None);
+    };
+    if key.zero_data_retention || !key.model_in_scope || !crate::db::models::api_keys::is_inference_purpose(&key.purpose) {
+        return Ok(None);
+    }
+    if !check_user_has_model_access(pool.clone(), key.user_id, model).await? {
+        return Ok(None);
+    }
+    if check_modality_blocked(pool.clone(), api_key, model).await?.is_some() {
+        return Ok(None);
+    }
+    Ok(Some(key.user_id))
+}
+
+/// The unexpired cached response for `request_hash`, if any.
+async fn lookup(pool: &PgPool, model: &str, principal_id: Uuid, request_hash: &[u8]) -> Result<Option<Vec<u8>>, DbError> {
+    let mut conn = pool.acquire().await?;
+    let body = sqlx::query_scalar!(
+        r#"
+        SELECT e.response_body
+        FROM response_cache_entries e
+        JOIN deployed_models dm ON dm.id = e.deployment_id
+        WHERE dm.alias = $1
+          AND dm.deleted = false
+          AND e.principal_id = $2
+          AND e.request_hash = $3
+          AND e.expires_at > now()
+        "#,
+        model,
+        principal_id,
+        request_hash
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+    Ok(body)
+}
+
+/// Store a response, first sweeping every expired entry.
+async fn store(
+    pool: &PgPool,
+    model: &str,
+    principal_id: Uuid,
+    request_hash: &[u8],
+    response_body: &[u8],
+    expires_at: DateTime<Utc>,
+) -> Result<(), DbError> {
+    let mut conn = pool.acquire().await?;
+    sqlx::query!("DELETE FROM response_cache_entries WHERE expires_at <= now()")
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query!(
+        r#"
+        INSERT INTO response_cache_entries (deployment_id, principal_id, request_hash, response_body, expires_at)
+        SELECT id, $2, $3, $4, $5
+        FROM deployed_models
+        WHERE alias = $1 AND deleted = false
+        ON CONFLICT (deployment_id, principal_id, request_hash) DO UPDATE
+        SET response_body = EXCLUDED.response_body,
+            created_at    = now(),
+            expires_at    = EXCLUDED.expires_at
+        "#,
+        model,
+        principal_id,
+        request_hash,
+        response_body,
+        expires_at
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// Serve deterministic chat completions from the response cache, and cache successful ones.
+///
+/// Fails open: if the cache cannot be read or written, the request is served upstream as usual.
+pub async fn response_cache_middleware<P: PoolProvider>(State(state): State<AppState<P>>, request: Request, next: Next) -> Response {
+    let config = state.current_config();
+    let cache_config = &config.onwards.response_cache;
+    if !cache_config.enabled
+        || request.method() != Method::POST
+        || !request.uri().path().ends_with("/chat/completions")
+        || request.headers().contains_key("x-fusillade-request-id")
+        || opted_out(request.headers())
+    {
+        return next.run(request).await;
+    }
+    let Some(api_key) = request
+        .headers()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::to_string)
+    else {
+        return next.run(request).await;
+    };
+
+    let (request, body) = match json_body(request, request_body_limit(&config)).await {
+        Ok((request, Some(body))) if is_cacheable(&body, cache_config) => (request, body),
+        Ok((request, _)) => return next.run(request).await,
+        Err(response) => return response,
+    };
+    let Some(model) = requested_model(model_override(request.headers()), Some(&*body)).map(str::to_string) else {
+        return next.run(request).await;
+    };
+
+    let pool = state.db.write().clone();
+    let principal_id = match cache_principal(&pool, &api_key, &model).await {
+        Ok(Some(principal_id)) => principal_id,
+        Ok(None) => return next.run(request).await,
+        Err(error) => {
+            warn!(%error, "Failed to authorize response cache lookup; serving upstream");
+            return next.run(request).await;
+        }
+    };
+    let hash = request_hash(&body);
+
+    match lookup(&pool, &model, principal_id, &hash).await {
+        Ok(Some(cached)) => {
+            debug!(%model, "Serving chat completion from response cache");
+            return Response::builder()
+                .status(StatusCode::OK)
+                .header(CONTENT_TYPE, "application/json")
+                .header(CACHE_STATUS_HEADER, "hit")
+                .body(Body::from(cached))
+                .expect("valid cached response");
+        }
+        Ok(None) => {}
+        Err(error) => warn!(%error, "Failed to read response cache; serving upstream"),
+    }
+
+    let response = next.run(request).await;
+    let is_json = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|content_type| content_type.contains("json"));
+    if response.status() != StatusCode::OK || !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let response_bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(error) => {
+            warn!(%error, "Failed to read upstream response body in response cache middleware");
+            return StatusCode::BAD_GATEWAY.into_response();
+        }
+    };
+    let expires_at = chrono::Duration::from_std(cache_config.ttl)
+        .ok()
+        .and_then(|ttl| Utc::now().checked_add_signed(ttl))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC);
+    if let Err(error) = store(&pool, &model, principal_id, &hash, &response_bytes, expires_at).await {
+        warn!(%error, "Failed to write response cache entry");
+    }
+    parts.headers.insert(CACHE_STATUS_HEADER, HeaderValue::from_static("miss"));
+    Response::from_parts(parts, Body::from(response_bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CACHE_STATUS_HEADER, is_cacheable, opted_out, request_hash};
+    use crate::api::models::users::Role;
+    use crate::config::ResponseCacheConfig;
+    use crate::db::handlers::Credits;
+    use crate::test::utils::{add_auth_headers, await_chat_status, create_test_admin_user, create_test_config, setup_proxied_model};
+    use axum::http::{HeaderMap, HeaderValue, header::CACHE_CONTROL};
+    use rust_decimal::Decimal;
+    use serde_json::json;
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_is_cacheable() {
+        let config = ResponseCacheConfig::default();
+        assert!(is_cacheable(&json!({ "model": "m", "temperature": 0 }), &config));
+        assert!(is_cacheable(&json!({ "model": "m", "temperature": 0.0, "stream": false }), &config));
+        assert!(!is_cacheable(&json!({ "model": "m", "temperature": 0.7 }), &config));
+        // A missing temperature samples at the default of 1
+        assert!(!is_cacheable(&json!({ "model": "m" }), &config));
+        assert!(!is_cacheable(&json!({ "model": "m", "temperature": 0, "stream": true }), &config));
+        assert!(!is_cacheable(
+            &json!({ "model": "m", "temperature": 0, "service_tier": "flex" }),
+            &config
+        ));
+
+        let config = ResponseCacheConfig {
+            max_temperature: 1.0,
+            ..ResponseCacheConfig::default()
+        };
+        assert!(is_cacheable(&json!({ "model": "m" }), &config));
+        assert!(!is_cacheable(&json!({ "model": "m", "temperature": 1.2 }), &config));
+    }
+
+    #[test]
+    fn test_opted_out() {
+        let mut headers = HeaderMap::new();
+        assert!(!opted_out(&headers));
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("max-age=0"));
+        assert!(!opted_out(&headers));
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("max-age=0, No-Store"));
+        assert!(opted_out(&headers));
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        assert!(opted_out(&headers));
+    }
+
+    #[test]
+    fn test_request_hash_ignores_key_order_and_whitespace() {
+        let a: serde_json::Value = serde_json::from_str(r#"{"model":"m","temperature":0,"messages":[]}"#).unwrap();
+        let b: serde_json::Value = serde_json::from_str(r#"{ "messages": [], "temperature": 0, "model": "m" }"#).unwrap();
+        let c: serde_json::Value = serde_json::from_str(r#"{"model":"m","temperature":0,"messages":[{"role":"user"}]}"#).unwrap();
+        assert_eq!(request_hash(&a), request_hash(&b));
+        assert_ne!(request_hash(&a), request_hash(&c));
+    }
+
+    /// A test server with response caching and request logging on, one public
+    /// priced model backed by `mock_server`, and a funded user's realtime key.
+    async fn setup(pool: &PgPool, mock_server: &wiremock::MockServer) -> (axum_test::TestServer, crate::BackgroundServices, Uuid, String) {
+        let mut config = create_test_config();
+        config.background_services.onwards_sync.enabled = true;
+        config.enable_request_logging = true;
+        config.onwards.response_cache.enabled = true;
+        let (server, bg_services) = crate::Application::new_with_pool(config, Some(pool.clone()), None)
+            .await
+            .expect("Failed to create application")
+            .into_test_server();
+        let proxied = setup_proxied_model(
+            &server,
+            &bg_services,
+            pool,
+            mock_server,
+            json!({}),
+            json!({
+                "alias": "cached-model",
+                "allow_public": true,
+                "tariffs": [{
+                    "name": "realtime",
+                    "input_price_per_token": "0.001",
+                    "output_price_per_token": "0.003",
+                    "api_key_purpose": "realtime"
+                }]
+            }),
+        )
+        .await;
+        let response = server
+            .post("/admin/api/v1/transactions")
+            .add_header(&proxied.admin_headers[0].0, &proxied.admin_headers[0].1)
+            .add_header(&proxied.admin_headers[1].0, &proxied.admin_headers[1].1)
+            .json(&json!({
+                "user_id": proxied.user.id,
+                "transaction_type": "admin_grant",
+                "amount": 1000,
+                "source_id": proxied.admin.id,
+                "description": "Response cache test credits"
+            }))
+            .await;
+        assert_eq!(response.status_code(), 201, "Failed to grant credits");
+        (server, bg_services, proxied.user.id, proxied.api_key)
+    }
+
+    async fn mount_completion(mock_server: &wiremock::MockServer, expected_calls: u64) {
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/v1/chat/completions"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(json!({
+                "id": "chatcmpl-1", "object": "chat.completion", "created": 1, "model": "cached-model",
+                "choices": [ { "index": 0, "message": { "role": "assistant", "content": "Hello" }, "finish_reason": "stop" } ],
+                "usage": { "prompt_tokens": 5, "completion_tokens": 1, "total_tokens": 6 }
+            })))
+            .expect(expected_calls)
+            .mount(mock_server)
+            .await;
+    }
+
+    /// POST `body` as written, so its key order and whitespace reach the middleware.
+    async fn post_raw(server: &axum_test::TestServer, api_key: &str, body: &str) -> axum_test::TestResponse {
+        server
+            .post("/ai/v1/chat/completions")
+            .add_header("authorization", format!("Bearer {api_key}"))
+            .add_header("content-type", "application/json")
+            .bytes(body.as_bytes().to_vec().into())
+            .await
+    }
+
+    async fn balance(pool: &PgPool, user_id: Uuid) -> Decimal {
+        let mut conn = pool.acquire().await.unwrap();
+        Credits::new(&mut conn).get_user_balance(user_id).await.unwrap()
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_cache_hit_skips_upstream_and_billing(pool: PgPool) {
+        let mock_server = wiremock::MockServer::start().await;
+        mount_completion(&mock_server, 1).await;
+        let (server, bg_services, user_id, api_key) = setup(&pool, &mock_server).await;
+
+        let miss = await_chat_status(
+            &server,
+            &api_key,
+            &json!({ "model": "cached-model", "temperature": 0, "messages": [{ "role": "user", "content": "hi" }] }),
+            200,
+        )
+        .await;
+        assert_eq!(miss.header(CACHE_STATUS_HEADER), "miss");
+
+        // Wait for the miss to be billed
+        let mut charged = None;
+        for _ in 0..100 {
+            let current = balance(&pool, user_id).await;
+            if current < Decimal::from(1000) {
+                charged = Some(current);
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let charged = charged.expect("the upstream call should be billed");
+
+        // Same request with keys reordered: served from the cache
+        let hit = post_raw(
+            &server,
+            &api_key,
+            r#"{ "messages": [{"content": "hi", "role": "user"}], "temperature": 0, "model": "cached-model" }"#,
+        )
+        .await;
+        assert_eq!(hit.status_code(), 200);
+        assert_eq!(hit.header(CACHE_STATUS_HEADER), "hit");
+        assert_eq!(hit.json::<serde_json::Value>(), miss.json::<serde_json::Value>());
+
+        // The hit is neither logged nor billed
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert_eq!(balance(&pool, user_id).await, charged);
+        let logged = sqlx::query_scalar!("SELECT COUNT(*) FROM http_analytics WHERE status_code = 200")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(logged, Some(1));
+
+        bg_services.shutdown().await;
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_key_denied_the_model_is_not_served_from_cache(pool: PgPool) {
+        let mock_server = wiremock::MockServer::start().await;
+        mount_completion(&mock_server, 1).await;
+        let (server, bg_services, user_id, api_key) = setup(&pool, &mock_server).await;
+        let body = r#"{"model":"cached-model","temperature":0,"messages":[{"role":"user","content":"hi"}]}"#;
+
+        let miss = await_chat_status(&server, &api_key, &serde_json::from_str(body).unwrap(), 200).await;
+        assert_eq!(miss.header(CACHE_STATUS_HEADER), "miss");
+        let mut stored = false;
+        for _ in 0..100 {
+            let entries = sqlx::query_scalar!("SELECT COUNT(*) FROM response_cache_entries")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            if entries == Some(1) {
+                stored = true;
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert!(stored, "the miss should be cached");
+
+        // Another key of the same user, denied the model
+        let model_id = sqlx::query_scalar!("SELECT id FROM deployed_models WHERE alias = 'cached-model'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let admin = create_test_admin_user(&pool, Role::PlatformManager).await;
+        let admin_headers = add_auth_headers(&admin);
+        let denied: serde_json::Value = server
+            .post(&format!("/admin/api/v1/users/{user_id}/api-keys"))
+            .add_header(&admin_headers[0].0, &admin_headers[0].1)
+            .add_header(&admin_headers[1].0, &admin_headers[1].1)
+            .json(&json!({ "purpose": "realtime", "name": "denied key", "denied_model_ids": [model_id] }))
+            .await
+            .json();
+        bg_services.sync_onwards_config(&pool).await.expect("Failed to sync onwards config");
+
+        let response = post_raw(&server, denied["key"].as_str().unwrap(), body).await;
+        assert_ne!(response.status_code(), 200);
+        assert!(response.maybe_header(CACHE_STATUS_HEADER).is_none());
+
+        bg_services.shutdown().await;
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_non_deterministic_and_opted_out_requests_are_not_cached(pool: PgPool) {
+        let mock_server = wiremock::MockServer::start().await;
+        mount_completion(&mock_server, 4).await;
+        let (server, bg_services, _, api_key) = setup(&pool, &mock_server).await;
+
+        let sampled = json!({ "model": "cached-model", "temperature": 0.7, "messages": [{ "role": "user", "content": "hi" }] });
+        for _ in 0..2 {
+            let response = await_chat_status(&server, &api_key, &sampled, 200).await;
+            assert!(response.maybe_header(CACHE_STATUS_HEADER).is_none());
+        }
+
+        let deterministic = r#"{"model":"cached-model","temperature":0,"messages":[{"role":"user","content":"hi"}]}"#;
+        for _ in 0..2 {
+            let response = server
+                .post("/ai/v1/chat/completions")
+                .add_header("authorization", format!("Bearer {api_key}"))
+                .add_header("cache-control", "no-store")
+                .add_header("content-type", "application/json")
+                .bytes(deterministic.as_bytes().to_vec().into())
+                .await;
+            assert_eq!(response.status_code(), 200);
+            assert!(response.maybe_header(CACHE_STATUS_HEADER).is_none());
+        }
+
+        let entries = sqlx::query_scalar!("SELECT COUNT(*) FROM response_cache_entries")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(entries, Some(0));
+
+        bg_services.shutdown().await;
+    }
+}
@@ -0,0 +1,197 @@
+//! SSE keepalive comments for slow streaming responses.
+//!
+//! Load balancers and proxies between the client and dwctl often drop a
+//! connection that has carried no bytes for a while, even when the response
+//! is still streaming. With `onwards.stream_keepalive` enabled, a
+//! `: keepalive` comment is written to any `text/event-stream` response that
+//! has been silent for the configured interval.
+//!
+//! Comments are ignored by SSE parsers, and a heartbeat is only written
+//! between events, never inside one. The layer sits outside request logging
+//! and the cost guard, so heartbeats are never logged, billed or counted as
+//! tokens.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Request, State},
+    http::header::CONTENT_TYPE,
+    middleware::Next,
+    response::Response,
+};
+use futures::Stream;
+use sqlx_pool_router::PoolProvider;
+use tokio::time::{Instant, Sleep};
+
+use crate::AppState;
+
+/// The comment written while a stream is idle.
+pub const KEEPALIVE_COMMENT: &[u8] = b": keepalive\n\n";
+
+/// Wraps an SSE byte stream, yielding [`KEEPALIVE_COMMENT`] whenever the inner
+/// stream has produced nothing for `interval` and the last bytes sent ended an
+/// event.
+pub struct KeepaliveStream<S> {
+    inner: S,
+    interval: Duration,
+    idle: Pin<Box<Sleep>>,
+    /// Up to the last four bytes forwarded, to find event boundaries split across chunks
+    tail: Vec<u8>,
+    finished: bool,
+}
+
+impl<S> KeepaliveStream<S> {
+    pub fn new(inner: S, interval: Duration) -> Self {
+        Self {
+            inner,
+            interval,
+            idle: Box::pin(tokio::time::sleep(interval)),
+            tail: Vec::with_capacity(8),
+            finished: false,
+        }
+    }
+
+    /// True before anything was sent, or when the bytes sent so far end an event.
+    fn at_event_boundary(&self) -> bool {
+        self.tail.is_empty() || self.tail.ends_with(b"\n\n") || self.tail.ends_with(b"\r\n\r\n") || self.tail.ends_with(b"\r\r")
+    }
+
+    fn record_sent(&mut self, chunk: &[u8]) {
+        self.tail.extend_from_slice(&chunk[chunk.len().saturating_sub(4)..]);
+        let excess = self.tail.len().saturating_sub(4);
+        self.tail.drain(..excess);
+    }
+
+    fn reset_idle(&mut self) {
+        let deadline = Instant::now() + self.interval;
+        self.idle.as_mut().reset(deadline);
+    }
+}
+
+impl<S, E> Stream for KeepaliveStream<S>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin,
+{
+    type Item = Result<Bytes, E>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        if this.finished {
+            return Poll::Ready(None);
+        }
+
+        match Pin::new(&mut this.inner).poll_next(cx) {
+            Poll::Ready(Some(Ok(chunk))) => {
+                if !chunk.is_empty() {
+                    this.record_sent(&chunk);
+                    this.reset_idle();
+                }
+                return Poll::Ready(Some(Ok(chunk)));
+            }
+            Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+            Poll::Ready(None) => {
+                this.finished = true;
+                return Poll::Ready(None);
+            }
+            Poll::Pending => {}
+        }
+
+        while this.idle.as_mut().poll(cx).is_ready() {
+            this.reset_idle();
+            if this.at_event_boundary() {
+                return Poll::Ready(Some(Ok(Bytes::from_static(KEEPALIVE_COMMENT))));
+            }
+            // Mid-event: wait for the event to finish, re-arming the timer so
+            // a heartbeat goes out if the stream stays quiet after it does
+        }
+        Poll::Pending
+    }
+}
+
+fn is_event_stream(response: &Response) -> bool {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/event-stream"))
+}
+
+/// Add keepalive comments to streaming responses when `onwards.stream_keepalive` is enabled.
+pub async fn stream_keepalive_middleware<P: PoolProvider>(State(state): State<AppState<P>>, request: Request, next: Next) -> Response {
+    let keepalive = state.current_config().onwards.stream_keepalive.clone();
+    let response = next.run(request).await;
+    if !keepalive.enabled || !is_event_stream(&response) {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let stream = KeepaliveStream::new(body.into_data_stream(), keepalive.interval);
+    Response::from_parts(parts, Body::from_stream(stream))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    const INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Collect a stream's chunks as strings.
+    async fn collect<S: Stream<Item = Result<Bytes, std::convert::Infallible>> + Unpin>(stream: S) -> Vec<String> {
+        stream
+            .map(|chunk| String::from_utf8(chunk.unwrap().to_vec()).unwrap())
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_heartbeats_fill_gaps_between_events() {
+        let upstream = Box::pin(async_stream::stream! {
+            yield Ok::<_, std::convert::Infallible>(Bytes::from_static(b"data: {\"n\":1}\n\n"));
+            tokio::time::sleep(INTERVAL * 3 + INTERVAL / 2).await;
+            yield Ok(Bytes::from_static(b"data: {\"n\":2}\n\n"));
+            yield Ok(Bytes::from_static(b"data: [DONE]\n\n"));
+        });
+
+        let chunks = collect(KeepaliveStream::new(upstream, INTERVAL)).await;
+
+        assert_eq!(chunks.first().map(String::as_str), Some("data: {\"n\":1}\n\n"));
+        assert_eq!(chunks.last().map(String::as_str), Some("data: [DONE]\n\n"));
+        let heartbeats = chunks.iter().filter(|c| c.as_bytes() == KEEPALIVE_COMMENT).count();
+        assert!(heartbeats >= 2, "expected heartbeats during the gap, got {chunks:?}");
+        // Heartbeats only appear inside the gap, never after data resumes
+        let resumed = chunks.iter().position(|c| c.contains("\"n\":2")).unwrap();
+        assert!(chunks[resumed..].iter().all(|c| c.as_bytes() != KEEPALIVE_COMMENT));
+    }
+
+    #[tokio::test]
+    async fn test_no_heartbeat_inside_an_event() {
+        let upstream = Box::pin(async_stream::stream! {
+            yield Ok::<_, std::convert::Infallible>(Bytes::from_static(b"data: {\"n\":"));
+            tokio::time::sleep(INTERVAL * 3).await;
+            yield Ok(Bytes::from_static(b"1}\n"));
+            yield Ok(Bytes::from_static(b"\n"));
+        });
+
+        let chunks = collect(KeepaliveStream::new(upstream, INTERVAL)).await;
+
+        assert_eq!(chunks.concat(), "data: {\"n\":1}\n\n", "the event must not be split by a heartbeat");
+    }
+
+    #[tokio::test]
+    async fn test_no_heartbeat_when_stream_is_busy() {
+        let upstream = Box::pin(async_stream::stream! {
+            for n in 0..5 {
+                tokio::time::sleep(INTERVAL / 10).await;
+                yield Ok::<_, std::convert::Infallible>(Bytes::from(format!("data: {{\"n\":{n}}}\n\n")));
+            }
+        });
+
+        let chunks = collect(KeepaliveStream::new(upstream, INTERVAL)).await;
+
+        assert_eq!(chunks.len(), 5, "no heartbeats while data flows: {chunks:?}");
+    }
+}
@@ -99,7 +99,7 @@
 //!     let config = Config::load(&args)?;
 //!
 //!     // Initialize telemetry (structured logging and optional OpenTelemetry)
-//!     let tracer_provider = dwctl::telemetry::init_telemetry(config.enable_otel_export)?;
+//!     let tracer_provider = dwctl::telemetry::init_telemetry(config.enable_otel_export, &config.log)?;
 //!
 //!     // Create and start the application
 //!     let app = Application::new(config, tracer_provider).await?;
@@ -142,7 +142,9 @@ fn install_crypto_provider() {
 
 pub mod api;
 pub mod auth;
+pub mod body_transform;
 pub mod config;
+pub mod config_check;
 mod config_watcher;
 pub mod connections;
 mod crypto;
@@ -165,6 +167,7 @@ pub mod prompt_cache;
 pub mod reasoning;
 mod request_logging;
 pub mod sample_files;
+pub mod secrets;
 mod static_assets;
 mod sync;
 pub mod tasks;
@@ -184,6 +187,7 @@ use crate::{
     },
     auth::password,
     config::{CorsConfig, CorsOrigin},
+    db::errors::DbError,
     db::handlers::{Deployments, Groups, Repository, Users},
     db::models::{deployments::DeploymentCreateDBRequest, users::UserCreateDBRequest},
     metrics::GenAiMetrics,
@@ -192,7 +196,7 @@ use crate::{
 use sqlx_pool_router::{DbPools, PoolProvider};
 
 use anyhow::Context;
-use auth::middleware::admin_ai_proxy_middleware;
+use auth::middleware::{admin_ai_proxy_middleware, admin_api_rate_limit_middleware};
 use axum::extract::{DefaultBodyLimit, Request, State};
 use axum::http::HeaderValue;
 use axum::response::Response;
@@ -286,6 +290,9 @@ where
     /// Outlet database pools for request logging. Always uses DbPools (production type).
     /// In tests, this uses DbPools without read-only enforcement (outlet is write-heavy).
     pub outlet_db: Option<DbPools>,
+    /// Dedicated replica for heavy analytics reads, from `database.analytics_replica_url`.
+    /// Use [`AppState::analytics_pool`] rather than reading this directly.
+    pub analytics_db: Option<PgPool>,
     pub metrics_recorder: Option<GenAiMetrics>,
     #[builder(default = false)]
     pub is_leader: bool,
@@ -313,6 +320,19 @@ where
     /// Encrypted key custody, built from `config.keystore`. `None` means it is
     /// not configured (ZDR flex disabled).
     pub keystore: Option<crate::keystore::Keystore>,
+    /// Cached maintenance mode state, checked on every `/ai/v1` request.
+    #[builder(default)]
+    pub maintenance: crate::inference::maintenance::MaintenanceMode,
+    /// Cached deployment aliases for `onwards.alias_normalization`.
+    #[builder(default)]
+    pub aliases: crate::inference::alias_normalization::AliasIndex,
+    /// Cached per-deployment settings (deactivation, cost and prompt length limits,
+    /// modalities, logging opt-out), checked on every `/ai/v1` request.
+    #[builder(default)]
+    pub deployment_settings: crate::inference::deployment_settings::DeploymentSettingsIndex,
+    /// Configuration change events for `GET /events`, fed by one shared LISTEN connection.
+    #[builder(default)]
+    pub config_events: crate::sync::config_events::ConfigEvents,
 }
 
 impl<P> AppState<P>
@@ -322,6 +342,13 @@ where
     pub fn current_config(&self) -> Arc<Config> {
         self.config.snapshot()
     }
+
+    /// Pool for heavy analytics reads (request aggregates, transaction
+    /// listings and exports): the dedicated analytics replica if configured,
+    /// otherwise the read pool, so they never contend with writes on the primary.
+    pub fn analytics_pool(&self) -> &PgPool {
+        self.analytics_db.as_ref().unwrap_or_else(|| self.db.read())
+    }
 }
 
 /// Get the dwctl database migrator
@@ -523,9 +550,11 @@ pub async fn create_initial_admin_user(
 ///
 /// # Errors
 ///
-/// Returns an error if database operations fail.
+/// Returns an error if database operations fail, including when a default model's alias
+/// collides with an existing deployment. Seeding is atomic: on any failure nothing is
+/// written and `endpoints_seeded` stays false, so the next startup retries.
 #[instrument(skip_all)]
-pub async fn seed_database(sources: &[config::ModelSource], db: &PgPool) -> Result<(), anyhow::Error> {
+pub async fn seed_database(sources: &[config::ModelSource], system_api_key: Option<&str>, db: &PgPool) -> Result<(), anyhow::Error> {
     // Use a transaction to ensure atomicity
     let mut tx = db.begin().await?;
 
@@ -560,9 +589,9 @@ pub async fn seed_database(sources: &[config::ModelSource], db: &PgPool) -> Resu
         .await?
         {
             for model in source.default_models.as_deref().unwrap_or(&[]) {
-                // Insert deployed model if it doesn't already exist
+                // Any failure aborts the whole seed, leaving endpoints_seeded unset for a retry
                 let mut model_repo = Deployments::new(&mut tx);
-                if let Ok(row) = model_repo
+                let row = match model_repo
                     .create(&DeploymentCreateDBRequest::from_api_create(
                         Uuid::nil(),
                         DeployedModelCreate::Standard(StandardModelCreate {
@@ -577,11 +606,28 @@ pub async fn seed_database(sources: &[config::ModelSource], db: &PgPool) -> Resu
                             burst_size: None,
                             capacity: None,
                             batch_capacity: None,
+                            per_key_capacity: None,
+                            max_cost_per_request: None,
+                            input_modalities: None,
+                            output_modalities: None,
                             throughput: None,
                             tariffs: None,
                             provider_pricing: None,
                             sanitize_responses: None,
                             trusted: None,
+                            allow_public: None,
+                            require_approval: None,
+                            rewrite_response_model: None,
+                            disable_logging: None,
+                            warmup: None,
+                            system_prompt: None,
+                            system_prompt_mode: None,
+                            max_n: None,
+                            max_n_mode: None,
+                            credit_exhaustion_mode: None,
+                            max_overdraft: None,
+                            max_prompt_length: None,
+                            max_prompt_length_unit: None,
                             open_responses_adapter: None,
                             reasoning_translation_overrides: None,
                             backoff_enabled: false,
@@ -596,24 +642,44 @@ pub async fn seed_database(sources: &[config::ModelSource], db: &PgPool) -> Resu
                         }),
                     ))
                     .await
-                    && model.add_to_everyone_group
                 {
-                    let mut groups_repo = Groups::new(&mut tx);
-                    if let Err(e) = groups_repo.add_deployment_to_group(row.id, Uuid::nil(), Uuid::nil()).await {
-                        debug!(
-                            "Failed to add deployed model {} to 'everyone' group during seeding: {}",
-                            model.name, e
+                    Ok(row) => row,
+                    Err(DbError::UniqueViolation { constraint, .. }) if constraint.as_deref() == Some("deployed_models_alias_unique") => {
+                        tracing::error!(
+                            source = %source.name,
+                            alias = %model.name,
+                            "Default model alias collides with an existing deployment; seeding aborted"
+                        );
+                        anyhow::bail!(
+                            "Failed to seed model source '{}': alias '{}' is already used by another deployment",
+                            source.name,
+                            model.name
                         );
                     }
+                    Err(e) => {
+                        return Err(anyhow::Error::from(e).context(format!(
+                            "Failed to seed default model '{}' for source '{}'",
+                            model.name, source.name
+                        )));
+                    }
+                };
+
+                if model.add_to_everyone_group {
+                    let mut groups_repo = Groups::new(&mut tx);
+                    groups_repo
+                        .add_deployment_to_group(row.id, Uuid::nil(), Uuid::nil())
+                        .await
+                        .with_context(|| format!("Failed to add seeded model '{}' to the 'everyone' group", model.name))?;
                 }
             }
         }
     }
 
     // Update the system API key secret and ensure it has platform purpose
-    // (required for admin API access used by internal services like scouter)
+    // (required for admin API access used by internal services like scouter).
+    // A configured secret is used as-is, so deployments can know it up front.
     let system_api_key_id = Uuid::nil();
-    let new_secret = crypto::generate_api_key();
+    let new_secret = system_api_key.map_or_else(crypto::generate_api_key, str::to_string);
     sqlx::query!(
         "UPDATE api_keys SET secret = $1, purpose = 'platform' WHERE id = $2",
         new_secret,
@@ -638,15 +704,108 @@ pub async fn seed_database(sources: &[config::ModelSource], db: &PgPool) -> Resu
     Ok(())
 }
 
-/// Setup database connections, run migrations, and initialize data
-/// Returns: (embedded_db, main_pools, fusillade_pools, outlet_pools)
+/// Database pools: (embedded_db, main_pools, fusillade_pools, outlet_pools)
+type DatabasePools = (Option<db::embedded::EmbeddedDatabase>, DbPools, DbPools, Option<DbPools>);
+
+/// Setup database connections, run migrations (or check they have been run,
+/// with `skip_migrations`), and initialize data
 ///
 /// If `pool` is provided, it will be used directly instead of creating a new connection.
 /// This is useful for tests where sqlx::test provides a pool.
-async fn setup_database(
-    config: &Config,
-    pool: Option<PgPool>,
-) -> anyhow::Result<(Option<db::embedded::EmbeddedDatabase>, DbPools, DbPools, Option<DbPools>)> {
+async fn setup_database(config: &Config, pool: Option<PgPool>) -> anyhow::Result<DatabasePools> {
+    let (embedded_db, db_pools, fusillade_pools, outlet_pools) = connect_databases(config, pool).await?;
+
+    if config.skip_migrations {
+        info!("Skipping database migrations (skip_migrations is set)");
+        verify_migrations(&db_pools, &fusillade_pools, outlet_pools.as_ref()).await?;
+    } else {
+        run_migrations(&db_pools, &fusillade_pools, outlet_pools.as_ref()).await?;
+    }
+
+    // Create initial admin user if it doesn't exist (always use primary for writes)
+    let argon2_params = password::Argon2Params {
+        memory_kib: config.auth.native.password.argon2_memory_kib,
+        iterations: config.auth.native.password.argon2_iterations,
+        parallelism: config.auth.native.password.argon2_parallelism,
+    };
+    create_initial_admin_user(&config.admin_email, config.admin_password.as_deref(), argon2_params, &db_pools)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create initial admin user: {}", e))?;
+
+    // Seed database with initial configuration (only runs once)
+    seed_database(&config.model_sources, config.system_api_key.as_deref(), &db_pools).await?;
+
+    Ok((embedded_db, db_pools, fusillade_pools, outlet_pools))
+}
+
+/// Connect to the dedicated analytics replica, if `database.analytics_replica_url` is set
+async fn connect_analytics_pool(config: &Config) -> anyhow::Result<Option<PgPool>> {
+    let Some(url) = config.database.analytics_replica_url() else {
+        return Ok(None);
+    };
+    info!("Setting up analytics replica pool");
+    let settings = config.database.analytics_pool_settings();
+    let slow_threshold = std::time::Duration::from_millis(config.slow_statement_threshold_ms);
+    let connect_opts = PgConnectOptions::from_str(url)?.log_slow_statements(log::LevelFilter::Warn, slow_threshold);
+    let pool = sqlx::postgres::PgPoolOptions::new()
+        .max_connections(settings.max_connections)
+        .min_connections(settings.min_connections)
+        .acquire_timeout(std::time::Duration::from_secs(settings.acquire_timeout_secs))
+        .idle_timeout(if settings.idle_timeout_secs > 0 {
+            Some(std::time::Duration::from_secs(settings.idle_timeout_secs))
+        } else {
+            None
+        })
+        .max_lifetime(if settings.max_lifetime_secs > 0 {
+            Some(std::time::Duration::from_secs(settings.max_lifetime_secs))
+        } else {
+            None
+        })
+        .connect_with(connect_opts)
+        .await?;
+    Ok(Some(pool))
+}
+
+/// Apply all pending migrations: dwctl's, fusillade's, underway's and, when
+/// request logging is enabled, outlet's.
+async fn run_migrations(db_pools: &DbPools, fusillade_pools: &DbPools, outlet_pools: Option<&DbPools>) -> anyhow::Result<()> {
+    db::migrations::run("dwctl", &migrator(), db_pools).await?;
+    db::migrations::run("fusillade", &fusillade_arsenal::migrator(), fusillade_pools).await?;
+
+    // Run underway migrations (background task queue)
+    underway::run_migrations(&**db_pools)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to run underway migrations: {e}"))?;
+
+    if let Some(outlet_pools) = outlet_pools {
+        db::migrations::run("outlet", &outlet_postgres::migrator(), outlet_pools).await?;
+    }
+    Ok(())
+}
+
+/// Check that migrations applied separately (`dwctl migrate`) are up to date.
+/// Underway tracks its own migrations and isn't checked.
+async fn verify_migrations(db_pools: &DbPools, fusillade_pools: &DbPools, outlet_pools: Option<&DbPools>) -> anyhow::Result<()> {
+    db::migrations::verify("dwctl", &migrator(), db_pools).await?;
+    db::migrations::verify("fusillade", &fusillade_arsenal::migrator(), fusillade_pools).await?;
+    if let Some(outlet_pools) = outlet_pools {
+        db::migrations::verify("outlet", &outlet_postgres::migrator(), outlet_pools).await?;
+    }
+    Ok(())
+}
+
+/// Apply all pending database migrations and return, for `dwctl migrate`.
+///
+/// If `pool` is provided, it is used for the main database instead of connecting.
+pub async fn migrate(config: &Config, pool: Option<PgPool>) -> anyhow::Result<()> {
+    let (_embedded_db, db_pools, fusillade_pools, outlet_pools) = connect_databases(config, pool).await?;
+    run_migrations(&db_pools, &fusillade_pools, outlet_pools.as_ref()).await?;
+    info!("Database migrations applied");
+    Ok(())
+}
+
+/// Connect to the main database and create the component pools (and schemas)
+async fn connect_databases(config: &Config, pool: Option<PgPool>) -> anyhow::Result<DatabasePools> {
     let slow_threshold = std::time::Duration::from_millis(config.slow_statement_threshold_ms);
 
     // If a pool is provided (e.g., from tests), create a TestDbPools which will create a read-only replica
@@ -673,7 +832,13 @@ async fn setup_database(
                 #[cfg(feature = "embedded-db")]
                 {
                     let data_dir = config.database.embedded_data_dir();
-                    let embedded_db = db::embedded::EmbeddedDatabase::start(data_dir, persistent).await?;
+                    let embedded_db = db::embedded::EmbeddedDatabase::start(
+                        data_dir,
+                        persistent,
+                        config.database.embedded_version(),
+                        config.database.embedded_extensions(),
+                    )
+                    .await?;
                     let url = embedded_db.connection_string().to_string();
                     (Some(embedded_db), url)
                 }
@@ -712,8 +877,6 @@ async fn setup_database(
         (_embedded_db, pool, None)
     };
 
-    migrator().run(&pool).await?;
-
     // Create replica pool if configured (or use test replica if in test mode)
     let db_pools = if let Some(test_replica) = test_replica_pool {
         info!("Using test replica pool with read-only enforcement");
@@ -854,10 +1017,6 @@ async fn setup_database(
             }
         }
     };
-    fusillade_arsenal::migrator().run(&*fusillade_pools).await?;
-
-    // Run underway migrations (background task queue)
-    underway::run_migrations(&*db_pools).await?;
 
     // Setup outlet schema and pool if request logging is enabled
     let outlet_pools = if config.enable_request_logging {
@@ -932,27 +1091,12 @@ async fn setup_database(
                 }
             }
         };
-        outlet_postgres::migrator().run(&*pools).await?;
-
         Some(pools)
     } else {
         info!("Skipping outlet pool setup (logging disabled)");
         None
     };
 
-    // Create initial admin user if it doesn't exist (always use primary for writes)
-    let argon2_params = password::Argon2Params {
-        memory_kib: config.auth.native.password.argon2_memory_kib,
-        iterations: config.auth.native.password.argon2_iterations,
-        parallelism: config.auth.native.password.argon2_parallelism,
-    };
-    create_initial_admin_user(&config.admin_email, config.admin_password.as_deref(), argon2_params, &db_pools)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to create initial admin user: {}", e))?;
-
-    // Seed database with initial configuration (only runs once)
-    seed_database(&config.model_sources, &db_pools).await?;
-
     Ok((embedded_db, db_pools, fusillade_pools, outlet_pools))
 }
 
@@ -1172,6 +1316,12 @@ pub async fn build_router(
                 .expect("Failed to create PostgresHandler for request logging")
                 .with_request_serializer(parse_ai_request)
                 .with_response_serializer(parse_ai_response);
+            // Requests to deployments with `disable_logging` set are logged without bodies.
+            let postgres_handler = request_logging::opt_out::LoggingOptOutScrubber::new(
+                postgres_handler,
+                state.db.read().clone(),
+                state.deployment_settings.clone(),
+            );
             // TRANSITIONAL (dwctl ZDR): guard the analytics logger so plaintext
             // ZDR bodies (decrypted for the upstream call, captured on the
             // loopback) never land in http_requests / http_responses. The marker
@@ -1234,6 +1384,8 @@ pub async fn build_router(
     // API routes
     let api_routes = Router::new()
         .route("/config", get(api::handlers::config::get_config))
+        .route("/config/export", get(api::handlers::config_snapshot::export_config))
+        .route("/config/import", post(api::handlers::config_snapshot::import_config))
         // CLI login endpoints — under /admin/api/v1/ so they route through the app,
         // not through oauth2-proxy (which intercepts all /authentication/* paths).
         .route("/auth/cli-callback", get(api::handlers::auth::cli_callback))
@@ -1243,6 +1395,11 @@ pub async fn build_router(
         .route("/users/{id}", get(api::handlers::users::get_user))
         .route("/users/{id}", patch(api::handlers::users::update_user))
         .route("/users/{id}", delete(api::handlers::users::delete_user))
+        // Self-service view of what the authenticated user can do
+        .route(
+            "/me/permissions",
+            get(api::handlers::effective_permissions::get_effective_permissions),
+        )
         // API Keys as user sub-resources
         .route("/users/{user_id}/api-keys", get(api::handlers::api_keys::list_user_api_keys))
         .route("/users/{user_id}/api-keys", post(api::handlers::api_keys::create_user_api_key))
@@ -1255,6 +1412,11 @@ pub async fn build_router(
             "/users/{user_id}/api-keys/{id}",
             delete(api::handlers::api_keys::delete_user_api_key),
         )
+        .route(
+            "/users/{user_id}/api-keys/{id}/usage",
+            get(api::handlers::api_keys::get_user_api_key_usage),
+        )
+        .route("/system/api-key", put(api::handlers::api_keys::update_system_api_key))
         // Webhooks as user sub-resources
         .route("/users/{user_id}/webhooks", get(api::handlers::webhooks::list_webhooks))
         .route("/users/{user_id}/webhooks", post(api::handlers::webhooks::create_webhook))
@@ -1278,8 +1440,42 @@ pub async fn build_router(
             "/users/{user_id}/groups/{group_id}",
             delete(api::handlers::groups::remove_group_from_user),
         )
+        // Per-user rate-limit overrides
+        .route(
+            "/users/{user_id}/rate-limits",
+            get(api::handlers::rate_limits::get_user_rate_limits),
+        )
+        .route(
+            "/users/{user_id}/rate-limits",
+            put(api::handlers::rate_limits::set_user_rate_limits),
+        )
+        .route(
+            "/users/{user_id}/rate-limits",
+            delete(api::handlers::rate_limits::delete_user_rate_limits),
+        )
+        // Per-user API key purposes
+        .route(
+            "/users/{user_id}/api-key-purposes",
+            get(api::handlers::api_keys::get_user_api_key_purposes),
+        )
+        .route(
+            "/users/{user_id}/api-key-purposes",
+            put(api::handlers::api_keys::set_user_api_key_purposes),
+        )
+        .route(
+            "/users/{user_id}/api-key-purposes",
+            delete(api::handlers::api_keys::delete_user_api_key_purposes),
+        )
+        // Users' login sessions
+        .route("/users/{user_id}/sessions", get(api::handlers::sessions::list_user_sessions))
+        .route("/users/{user_id}/sessions", delete(api::handlers::sessions::revoke_user_sessions))
+        .route(
+            "/users/{user_id}/sessions/{session_id}",
+            delete(api::handlers::sessions::revoke_user_session),
+        )
         // Transaction management (RESTful credit transactions)
         .route("/transactions", post(api::handlers::transactions::create_transaction))
+        .route("/transactions/export", get(api::handlers::transactions::export_transactions))
         .route("/transactions/{transaction_id}", get(api::handlers::transactions::get_transaction))
         .route("/transactions", get(api::handlers::transactions::list_transactions))
         // Payment processing
@@ -1295,6 +1491,10 @@ pub async fn build_router(
             "/endpoints/validate",
             post(api::handlers::inference_endpoints::validate_inference_endpoint),
         )
+        .route(
+            "/endpoints/test-credentials",
+            post(api::handlers::inference_endpoints::test_endpoint_credentials),
+        )
         .route("/endpoints/{id}", get(api::handlers::inference_endpoints::get_inference_endpoint))
         .route(
             "/endpoints/{id}",
@@ -1308,12 +1508,26 @@ pub async fn build_router(
             "/endpoints/{id}/synchronize",
             post(api::handlers::inference_endpoints::synchronize_endpoint),
         )
+        .route(
+            "/endpoints/{id}/statistics",
+            get(api::handlers::inference_endpoints::get_endpoint_statistics),
+        )
         // Models endpoints
         .route("/models", get(api::handlers::deployments::list_deployed_models))
         .route("/models", post(api::handlers::deployments::create_deployed_model))
         .route("/models/{id}", get(api::handlers::deployments::get_deployed_model))
         .route("/models/{id}", patch(api::handlers::deployments::update_deployed_model))
         .route("/models/{id}", delete(api::handlers::deployments::delete_deployed_model))
+        .route("/models/{id}/activate", patch(api::handlers::deployments::activate_deployed_model))
+        .route(
+            "/models/{id}/deactivate",
+            patch(api::handlers::deployments::deactivate_deployed_model),
+        )
+        .route("/models/{id}/clone", post(api::handlers::deployments::clone_deployed_model))
+        .route(
+            "/models/{id}/estimate-cost",
+            post(api::handlers::deployments::estimate_request_cost),
+        )
         .route("/models/{id}/cache-pricing", get(api::handlers::cache_pricing::get_cache_pricing))
         .route(
             "/models/{id}/cache-pricing",
@@ -1323,6 +1537,24 @@ pub async fn build_router(
             "/models/{id}/cache-pricing",
             delete(api::handlers::cache_pricing::disable_cache_pricing),
         )
+        .route("/models/{id}/shadow", get(api::handlers::deployment_shadows::get_deployment_shadow))
+        .route("/models/{id}/shadow", put(api::handlers::deployment_shadows::set_deployment_shadow))
+        .route(
+            "/models/{id}/shadow",
+            delete(api::handlers::deployment_shadows::delete_deployment_shadow),
+        )
+        .route(
+            "/models/{id}/access-requests",
+            post(api::handlers::access_requests::create_access_request),
+        )
+        .route(
+            "/models/{id}/access-requests",
+            get(api::handlers::access_requests::list_access_requests),
+        )
+        .route(
+            "/models/{id}/access-requests/{request_id}",
+            patch(api::handlers::access_requests::review_access_request),
+        )
         .route(
             "/provider-display-configs",
             get(api::handlers::provider_display_configs::list_provider_display_configs),
@@ -1347,6 +1579,7 @@ pub async fn build_router(
             "/provider-display-configs/{provider_key}/icon",
             get(api::handlers::provider_display_configs::get_provider_display_config_icon),
         )
+        .route("/models/{id}/resolved-config", get(api::handlers::deployments::get_resolved_config))
         // Composite model component management (for models where is_composite=true)
         .route("/models/{id}/components", get(api::handlers::deployments::get_model_components))
         .route(
@@ -1373,6 +1606,7 @@ pub async fn build_router(
         .route("/groups/{id}", delete(api::handlers::groups::delete_group))
         // Group-user relationships
         .route("/groups/{group_id}/users", get(api::handlers::groups::get_group_users))
+        .route("/groups/{group_id}/users:batch", post(api::handlers::groups::add_users_to_group))
         .route("/groups/{group_id}/users/{user_id}", post(api::handlers::groups::add_user_to_group))
         .route(
             "/groups/{group_id}/users/{user_id}",
@@ -1380,6 +1614,10 @@ pub async fn build_router(
         )
         // Group-model relationships
         .route("/groups/{group_id}/models", get(api::handlers::groups::get_group_deployments))
+        .route(
+            "/groups/{group_id}/models:batch",
+            post(api::handlers::groups::add_deployments_to_group),
+        )
         .route(
             "/groups/{group_id}/models/{deployment_id}",
             post(api::handlers::groups::add_deployment_to_group),
@@ -1388,7 +1626,49 @@ pub async fn build_router(
             "/groups/{group_id}/models/{deployment_id}",
             delete(api::handlers::groups::remove_deployment_from_group),
         )
+        // Per-group rate-limit defaults
+        .route(
+            "/groups/{group_id}/rate-limits",
+            get(api::handlers::rate_limits::get_group_rate_limits),
+        )
+        .route(
+            "/groups/{group_id}/rate-limits",
+            put(api::handlers::rate_limits::set_group_rate_limits),
+        )
+        .route(
+            "/groups/{group_id}/rate-limits",
+            delete(api::handlers::rate_limits::delete_group_rate_limits),
+        )
+        // Per-group spending limits
+        .route(
+            "/groups/{group_id}/usage",
+            get(api::handlers::group_spending_limits::get_group_usage),
+        )
+        .route(
+            "/groups/{group_id}/spending-limit",
+            put(api::handlers::group_spending_limits::set_group_spending_limit),
+        )
+        .route(
+            "/groups/{group_id}/spending-limit",
+            delete(api::handlers::group_spending_limits::delete_group_spending_limit),
+        )
         .route("/models/{deployment_id}/groups", get(api::handlers::groups::get_deployment_groups))
+        // Access templates
+        .route("/access-templates", get(api::handlers::access_templates::list_access_templates))
+        .route("/access-templates", post(api::handlers::access_templates::create_access_template))
+        .route("/access-templates/{id}", get(api::handlers::access_templates::get_access_template))
+        .route(
+            "/access-templates/{id}",
+            patch(api::handlers::access_templates::update_access_template),
+        )
+        .route(
+            "/access-templates/{id}",
+            delete(api::handlers::access_templates::delete_access_template),
+        )
+        .route(
+            "/groups/{group_id}/apply-template/{template_id}",
+            post(api::handlers::access_templates::apply_access_template),
+        )
         // Organization management
         .route("/organizations", get(api::handlers::organizations::list_organizations))
         .route("/organizations", post(api::handlers::organizations::create_organization))
@@ -1467,6 +1747,11 @@ pub async fn build_router(
         .route("/probes/{id}/execute", post(api::handlers::probes::execute_probe))
         .route("/probes/{id}/results", get(api::handlers::probes::get_probe_results))
         .route("/probes/{id}/statistics", get(api::handlers::probes::get_statistics))
+        // Maintenance mode
+        .route("/maintenance", get(api::handlers::maintenance::get_maintenance_mode))
+        .route("/maintenance", put(api::handlers::maintenance::set_maintenance_mode))
+        // Configuration change events
+        .route("/events", get(api::handlers::events::stream_config_events))
         // Queue monitoring
         .route(
             "/monitoring/pending-request-counts",
@@ -1535,7 +1820,10 @@ pub async fn build_router(
             get(api::handlers::connections::list_sync_entries),
         );
 
-    let api_routes_with_state = api_routes.with_state(state.clone());
+    // Per-user rate limit; route_layer so unmatched paths fall through to the SPA untouched
+    let api_routes_with_state = api_routes
+        .route_layer(middleware::from_fn_with_state(state.clone(), admin_api_rate_limit_middleware))
+        .with_state(state.clone());
 
     // Batches API routes (files + batches) - conditionally enabled under /ai/v1
     let batches_routes = if config.batches.enabled {
@@ -1598,8 +1886,9 @@ pub async fn build_router(
     // wrapper: on a request it runs first; on the response it runs last. The stack below,
     // outermost → innermost (i.e. reverse of the code order), is:
     //
-    //   translation  →  responses_mw  →  outlet (logging/billing)
-    //                →  cache  →  error_enrichment  →  image_normalizer
+    //   stream_keepalive  →  translation  →  deactivated_models  →  response_cache  →  modalities  →  prompt_length
+    //                →  cost_guard  →  responses_mw
+    //                →  outlet (logging/billing)  →  cache  →  error_enrichment  →  image_normalizer
     //                →  tool_injection  →  models_route  →  onwards
     //
     // Why this order:
@@ -1661,8 +1950,12 @@ pub async fn build_router(
     };
 
     // Apply error enrichment middleware to onwards router (before outlet logging)
+    let error_enrichment_state = error_enrichment::ErrorEnrichmentState {
+        pool: state.db.write().clone(),
+        config: state.config.clone(),
+    };
     let onwards_router = onwards_router.layer(middleware::from_fn_with_state(
-        state.db.write().clone(),
+        error_enrichment_state,
         error_enrichment::error_enrichment_middleware,
     ));
 
@@ -1726,6 +2019,59 @@ pub async fn build_router(
         onwards_router
     };
 
+    // Apply the per-request cost guard outside the inference middleware, so flex
+    // requests are judged before they are queued, and outside request logging, so
+    // a rejected request is never billed. A stream it cuts off is billed for the
+    // tokens delivered.
+    let onwards_router = onwards_router.layer(middleware::from_fn_with_state(
+        state.clone(),
+        crate::inference::cost_guard::cost_guard_middleware,
+    ));
+
+    // Reject prompts longer than the model allows outside the cost guard, for the
+    // same reasons. Tokens are counted by tokenizer-svc when cached-input pricing
+    // is enabled, as the service is only deployed then; otherwise they are estimated.
+    let onwards_router = {
+        let cfg = state.current_config();
+        let prompt_length_state = crate::inference::prompt_length::PromptLengthState {
+            pool: state.db.read().clone(),
+            settings: state.deployment_settings.clone(),
+            tokenizer: cfg
+                .cache
+                .enabled
+                .then(|| crate::prompt_cache::TokenizerClient::new(cfg.cache.tokenizer_url.clone())),
+            body_limit: crate::inference::deployment_settings::request_body_limit(&cfg),
+        };
+        onwards_router.layer(middleware::from_fn_with_state(
+            prompt_length_state,
+            crate::inference::prompt_length::prompt_length_middleware,
+        ))
+    };
+
+    // Reject content the model doesn't accept outside the cost guard, for the same
+    // reasons: nothing is queued, forwarded or billed. Inside translation, so
+    // Anthropic image blocks are judged in their OpenAI form.
+    let onwards_router = onwards_router.layer(middleware::from_fn_with_state(
+        state.clone(),
+        crate::inference::modalities::modality_middleware,
+    ));
+
+    // Apply response caching outside request logging and the inference middleware,
+    // so a cache hit skips the upstream call, the request log and billing. Inside
+    // translation, so translated Anthropic requests are cached in their OpenAI form.
+    let onwards_router = onwards_router.layer(middleware::from_fn_with_state(
+        state.clone(),
+        crate::inference::response_cache::response_cache_middleware,
+    ));
+
+    // Reject requests for deactivated models outside response caching and the
+    // inference middleware, so they are neither served from the cache nor
+    // queued as flex requests.
+    let onwards_router = onwards_router.layer(middleware::from_fn_with_state(
+        state.clone(),
+        crate::inference::deactivated_models::deactivated_model_middleware,
+    ));
+
     // Apply the generic edge protocol-translation middleware as the OUTERMOST
     // layer on the onwards router. On the request path it runs first, so any
     // foreign-protocol request (today: Anthropic `/v1/messages` and `/v1/models`)
@@ -1756,6 +2102,20 @@ pub async fn build_router(
         ))
     };
 
+    // Apply stream keepalive outside translation, so heartbeats are added to the
+    // final client bytes and never reach a translator, the cost guard or billing.
+    let onwards_router = onwards_router.layer(middleware::from_fn_with_state(
+        state.clone(),
+        crate::inference::stream_keepalive::stream_keepalive_middleware,
+    ));
+
+    // Strip the trusted proxy's identity and shared-secret headers first, so no
+    // layer can forward them upstream
+    let onwards_router = onwards_router.layer(middleware::from_fn_with_state(
+        state.clone(),
+        crate::auth::middleware::strip_proxy_headers_middleware,
+    ));
+
     // Build the app with admin API and onwards proxy nested. serve the (restricted) openai spec.
     // Strict mode requires different nesting:
     // - Batches routes (no /v1 prefix) need to be at /ai/v1/files, /ai/v1/batches
@@ -1764,11 +2124,23 @@ pub async fn build_router(
     // - Both batches and onwards can be merged and nested at /ai/v1 (catchall handles everything)
     let mut router = Router::new()
         .route("/healthz", get(|| async { "OK" }))
+        .route("/version", get(api::handlers::version::get_version))
         // Webhook routes (external services, not part of client API docs)
         .route("/webhooks/payments", post(api::handlers::payments::webhook_handler))
         .with_state(state.clone())
         .merge(auth_routes);
 
+    // Disabled paths are rejected ahead of both batches and onwards, whatever the mode
+    let disabled_paths_layer = middleware::from_fn_with_state(state.clone(), crate::inference::disabled_paths::disabled_paths_middleware);
+    // Maintenance mode rejects everything under /ai/v1; admin, health and version routes stay up
+    let maintenance_layer = middleware::from_fn_with_state(state.clone(), crate::inference::maintenance::maintenance_middleware);
+    // Normalized alias matching rewrites the model before onwards, request logging and batching see it
+    let alias_normalization_layer =
+        middleware::from_fn_with_state(state.clone(), crate::inference::alias_normalization::alias_normalization_middleware);
+    // Keys sent in alternate headers are moved into Authorization before anything reads it
+    let api_key_headers_layer =
+        middleware::from_fn_with_state(state.clone(), crate::inference::api_key_headers::api_key_headers_middleware);
+
     // Add AI routes with appropriate nesting based on strict mode
     if strict_mode {
         // Strict mode: combine batches and onwards before nesting so the shared
@@ -1779,7 +2151,14 @@ pub async fn build_router(
         } else {
             onwards_router
         };
-        router = router.nest("/ai/v1", ai_router);
+        router = router.nest(
+            "/ai/v1",
+            ai_router
+                .layer(alias_normalization_layer)
+                .layer(api_key_headers_layer)
+                .layer(disabled_paths_layer)
+                .layer(maintenance_layer),
+        );
     } else {
         // Non-strict mode: merge batches + onwards, nest at /ai/v1
         let ai_router = if let Some(batches) = batches_routes {
@@ -1787,7 +2166,14 @@ pub async fn build_router(
         } else {
             onwards_router
         };
-        router = router.nest("/ai/v1", ai_router);
+        router = router.nest(
+            "/ai/v1",
+            ai_router
+                .layer(alias_normalization_layer)
+                .layer(api_key_headers_layer)
+                .layer(disabled_paths_layer)
+                .layer(maintenance_layer),
+        );
     }
 
     // OpenAPI spec routes. Both surfaces are gated by extractors in the
@@ -1903,113 +2289,117 @@ pub async fn build_router(
     }
 
     // Add tracing layer with OTel-compatible span names and HTTP semantic conventions.
-    // Only trace_id and otel.name are tracing span fields (visible in fmt log output).
+    // Only trace_id, request_id and otel.name are tracing span fields (visible in fmt log output).
     // All other attributes are set via OpenTelemetrySpanExt::set_attribute() so they're
     // exported to the trace backend but don't clutter log lines.
     // Reference: https://opentelemetry.io/docs/specs/semconv/http/http-spans/
-    let router = router.layer(middleware::from_fn(inject_trace_id)).layer(
-        TraceLayer::new_for_http()
-            .make_span_with(|request: &http::Request<_>| {
-                let path = request.uri().path();
-                let route = request
-                    .extensions()
-                    .get::<axum::extract::MatchedPath>()
-                    .map(|mp| mp.as_str().to_owned());
-                let span_name = if let Some(ref route) = route {
-                    format!("{} {}", request.method(), route)
-                } else {
-                    format!("{} {}", request.method(), path)
-                };
-                let api_type = if path.starts_with("/ai/") {
-                    "ai_proxy"
-                } else if path.starts_with("/admin/") {
-                    "admin"
-                } else {
-                    "other"
-                };
-                let span = tracing::info_span!(
-                    "request",
-                    trace_id = tracing::field::Empty,
-                    otel.name = %span_name,
-                );
-
-                // W3C Trace Context propagation (https://www.w3.org/TR/trace-context/)
-                //
-                // When an upstream caller (e.g. fusillade's batch daemon) sends a
-                // request with a `traceparent` header, we parse it and set this
-                // span's parent to the remote span context. This makes the dwctl
-                // request span appear as a child of the caller's span in the trace
-                // backend, producing one continuous trace across service boundaries.
-                //
-                // Without this, dwctl would start a new trace for every incoming
-                // request, breaking the connection between fusillade's
-                // process_request → execute and the dwctl request it dispatches.
-                //
-                // The traceparent header format is: {version}-{trace_id}-{span_id}-{flags}
-                // e.g. "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
-                //
-                // If parsing fails at any point we silently fall through and the
-                // span starts a fresh trace — this is fine for requests that don't
-                // carry trace context (e.g. direct API calls from users).
-                if let Some(traceparent) = request.headers().get("traceparent")
-                    && let Ok(tp) = traceparent.to_str()
-                {
-                    let parts: Vec<&str> = tp.split('-').collect();
-                    if parts.len() == 4
-                        && let (Ok(trace_id), Ok(span_id)) = (
-                            opentelemetry::trace::TraceId::from_hex(parts[1]),
-                            opentelemetry::trace::SpanId::from_hex(parts[2]),
-                        )
+    let router = router
+        .layer(middleware::from_fn(inject_request_id))
+        .layer(middleware::from_fn(inject_trace_id))
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(|request: &http::Request<_>| {
+                    let path = request.uri().path();
+                    let route = request
+                        .extensions()
+                        .get::<axum::extract::MatchedPath>()
+                        .map(|mp| mp.as_str().to_owned());
+                    let span_name = if let Some(ref route) = route {
+                        format!("{} {}", request.method(), route)
+                    } else {
+                        format!("{} {}", request.method(), path)
+                    };
+                    let api_type = if path.starts_with("/ai/") {
+                        "ai_proxy"
+                    } else if path.starts_with("/admin/") {
+                        "admin"
+                    } else {
+                        "other"
+                    };
+                    let span = tracing::info_span!(
+                        "request",
+                        trace_id = tracing::field::Empty,
+                        request_id = tracing::field::Empty,
+                        otel.name = %span_name,
+                    );
+
+                    // W3C Trace Context propagation (https://www.w3.org/TR/trace-context/)
+                    //
+                    // When an upstream caller (e.g. fusillade's batch daemon) sends a
+                    // request with a `traceparent` header, we parse it and set this
+                    // span's parent to the remote span context. This makes the dwctl
+                    // request span appear as a child of the caller's span in the trace
+                    // backend, producing one continuous trace across service boundaries.
+                    //
+                    // Without this, dwctl would start a new trace for every incoming
+                    // request, breaking the connection between fusillade's
+                    // process_request → execute and the dwctl request it dispatches.
+                    //
+                    // The traceparent header format is: {version}-{trace_id}-{span_id}-{flags}
+                    // e.g. "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+                    //
+                    // If parsing fails at any point we silently fall through and the
+                    // span starts a fresh trace — this is fine for requests that don't
+                    // carry trace context (e.g. direct API calls from users).
+                    if let Some(traceparent) = request.headers().get("traceparent")
+                        && let Ok(tp) = traceparent.to_str()
                     {
-                        let flags = u8::from_str_radix(parts[3], 16).unwrap_or(1);
-                        let parent_ctx = opentelemetry::trace::SpanContext::new(
-                            trace_id,
-                            span_id,
-                            opentelemetry::trace::TraceFlags::new(flags),
-                            true, // remote: this span context came from another process
-                            opentelemetry::trace::TraceState::default(),
-                        );
-                        let parent = opentelemetry::Context::new().with_remote_span_context(parent_ctx);
-                        let _ = span.set_parent(parent);
+                        let parts: Vec<&str> = tp.split('-').collect();
+                        if parts.len() == 4
+                            && let (Ok(trace_id), Ok(span_id)) = (
+                                opentelemetry::trace::TraceId::from_hex(parts[1]),
+                                opentelemetry::trace::SpanId::from_hex(parts[2]),
+                            )
+                        {
+                            let flags = u8::from_str_radix(parts[3], 16).unwrap_or(1);
+                            let parent_ctx = opentelemetry::trace::SpanContext::new(
+                                trace_id,
+                                span_id,
+                                opentelemetry::trace::TraceFlags::new(flags),
+                                true, // remote: this span context came from another process
+                                opentelemetry::trace::TraceState::default(),
+                            );
+                            let parent = opentelemetry::Context::new().with_remote_span_context(parent_ctx);
+                            let _ = span.set_parent(parent);
+                        }
                     }
-                }
 
-                span.set_attribute("otel.kind", "Server");
-                span.set_attribute("api.type", api_type.to_string());
-                span.set_attribute("http.request.method", request.method().to_string());
-                span.set_attribute("http.route", route.unwrap_or_default());
-                span.set_attribute("url.path", path.to_string());
-                span.set_attribute("url.query", request.uri().query().unwrap_or("").to_string());
-                span
-            })
-            .on_request(tower_http::trace::DefaultOnRequest::new().level(tracing::Level::TRACE))
-            .on_response(|response: &http::Response<_>, latency: std::time::Duration, span: &tracing::Span| {
-                let status = response.status().as_u16();
-                span.set_attribute("http.response.status_code", i64::from(status));
-                if status >= 500 {
-                    span.set_attribute("otel.status_code", "ERROR");
-                    span.set_attribute("error.type", status.to_string());
-                } else if status >= 400 {
-                    span.set_attribute("error.type", status.to_string());
-                }
-                tracing::info!(
-                    http.response.status_code = status,
-                    latency_ms = latency.as_millis() as u64,
-                    "finished processing request"
-                );
-            })
-            .on_failure(
-                |error: tower_http::classify::ServerErrorsFailureClass, latency: std::time::Duration, span: &tracing::Span| {
-                    span.set_attribute("otel.status_code", "ERROR");
-                    span.set_attribute("error.type", error.to_string());
-                    tracing::error!(
-                        error = %error,
+                    span.set_attribute("otel.kind", "Server");
+                    span.set_attribute("api.type", api_type.to_string());
+                    span.set_attribute("http.request.method", request.method().to_string());
+                    span.set_attribute("http.route", route.unwrap_or_default());
+                    span.set_attribute("url.path", path.to_string());
+                    span.set_attribute("url.query", request.uri().query().unwrap_or("").to_string());
+                    span
+                })
+                .on_request(tower_http::trace::DefaultOnRequest::new().level(tracing::Level::TRACE))
+                .on_response(|response: &http::Response<_>, latency: std::time::Duration, span: &tracing::Span| {
+                    let status = response.status().as_u16();
+                    span.set_attribute("http.response.status_code", i64::from(status));
+                    if status >= 500 {
+                        span.set_attribute("otel.status_code", "ERROR");
+                        span.set_attribute("error.type", status.to_string());
+                    } else if status >= 400 {
+                        span.set_attribute("error.type", status.to_string());
+                    }
+                    tracing::info!(
+                        http.response.status_code = status,
                         latency_ms = latency.as_millis() as u64,
-                        "request failed"
+                        "finished processing request"
                     );
-                },
-            ),
-    );
+                })
+                .on_failure(
+                    |error: tower_http::classify::ServerErrorsFailureClass, latency: std::time::Duration, span: &tracing::Span| {
+                        span.set_attribute("otel.status_code", "ERROR");
+                        span.set_attribute("error.type", error.to_string());
+                        tracing::error!(
+                            error = %error,
+                            latency_ms = latency.as_millis() as u64,
+                            "request failed"
+                        );
+                    },
+                ),
+        );
 
     Ok(router)
 }
@@ -2025,6 +2415,43 @@ async fn inject_trace_id(request: axum::extract::Request, next: middleware::Next
     next.run(request).await
 }
 
+/// Header carrying the id that correlates a request across client, logs and upstream.
+pub(crate) const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Middleware that gives every request an `X-Request-Id`: the client's own if it
+/// sent a usable one, otherwise a new UUID. The id is written into the request
+/// headers, so it is forwarded upstream and stored with the analytics row, and
+/// is recorded on the current span and returned on the response.
+async fn inject_request_id(mut request: axum::extract::Request, next: middleware::Next) -> axum::response::Response {
+    let supplied = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .filter(|value| is_usable_request_id(value))
+        .cloned();
+    let request_id = match supplied {
+        Some(value) => value,
+        None => {
+            let value = HeaderValue::from_str(&Uuid::new_v4().to_string()).expect("a UUID is a valid header value");
+            request.headers_mut().insert(REQUEST_ID_HEADER, value.clone());
+            value
+        }
+    };
+    tracing::Span::current().record("request_id", request_id.to_str().unwrap_or_default());
+
+    let mut response = next.run(request).await;
+    response.headers_mut().insert(REQUEST_ID_HEADER, request_id);
+    response
+}
+
+/// Client ids are echoed into logs and upstream requests, so only short
+/// printable ASCII values are kept; anything else is replaced.
+fn is_usable_request_id(value: &HeaderValue) -> bool {
+    value.len() <= 128
+        && value
+            .to_str()
+            .is_ok_and(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_graphic()))
+}
+
 /// Container for background services and their lifecycle management.
 ///
 /// This struct encapsulates all background tasks that run alongside the HTTP server,
@@ -2084,6 +2511,8 @@ pub struct BackgroundServices {
     connections_encryption_key: Option<Vec<u8>>,
     /// Encrypted key custody, built once at startup. `None` when unconfigured.
     keystore: Option<crate::keystore::Keystore>,
+    /// Resolver for endpoint API key references, shared with the onwards sync.
+    secret_store: crate::secrets::SecretStore,
 }
 
 impl BackgroundServices {
@@ -2268,9 +2697,15 @@ impl BackgroundServices {
 
         // Use the same load function as the automatic sync
         // Note: escalation_models is empty for tests - individual tests can set up their own
-        let new_targets =
-            crate::sync::onwards_config::load_targets_from_db(pool, &[], self.strict_mode, &crate::config::RateLimitTiersConfig::default())
-                .await?;
+        let new_targets = crate::sync::onwards_config::load_targets_from_db(
+            pool,
+            &[],
+            self.strict_mode,
+            &crate::config::RateLimitTiersConfig::default(),
+            self.connections_encryption_key.as_deref(),
+            Some(&self.secret_store),
+        )
+        .await?;
 
         // Send through the watch channel (same as automatic sync)
         sender
@@ -2440,9 +2875,26 @@ async fn setup_background_services(input: BackgroundServicesInput) -> anyhow::Re
     // daemon's concurrency control and the onwards config-sync writer)
     // is now owned by the caller — see the function-level doc.
 
+    // Encryption key for connection credentials and Bedrock endpoint credentials.
+    // Derived before onwards sync, which decrypts Bedrock credentials into its config.
+    let encryption_key = match config.connections.encryption_key.as_deref().or(config.secret_key.as_deref()) {
+        Some(secret) if !secret.trim().is_empty() => Some(encryption::derive_encryption_key(secret.trim())),
+        Some(_) => {
+            tracing::warn!("Encryption key is empty/whitespace — connection features will be unavailable");
+            None
+        }
+        None => {
+            tracing::info!("No encryption key configured for connections (set secret_key or connections.encryption_key)");
+            None
+        }
+    };
+
+    // Endpoint API keys may be references into an external secrets manager
+    let secret_store = crate::secrets::SecretStore::new(&config.secrets);
+
     // Start onwards integration for proxying AI requests (if enabled)
     #[cfg_attr(not(test), allow(unused_variables))]
-    let (initial_targets, onwards_sender) = if config.background_services.onwards_sync.enabled {
+    let (initial_targets, onwards_sender, drift_checker) = if config.background_services.onwards_sync.enabled {
         // Extract escalation model names from batch daemon config
         // Batch API keys automatically get access to these models for completion window escalation
         let escalation_models: Vec<String> = config
@@ -2460,11 +2912,15 @@ async fn setup_background_services(input: BackgroundServicesInput) -> anyhow::Re
             escalation_models,
             config.onwards.strict_mode,
             config.auth.rate_limits.clone(),
+            encryption_key.clone(),
+            secret_store.clone(),
         )
         .await?;
 
         // Clone the sender before moving onwards_config_sync into the spawn (for manual sync)
         let sender = onwards_config_sync.sender();
+        // Compares the live targets (shared with the proxy) against the database; run by the leader
+        let drift_checker = onwards_config_sync.drift_checker(initial_targets.clone());
 
         // Start target updates - this spawns a background task internally and returns immediately
         initial_targets
@@ -2475,15 +2931,17 @@ async fn setup_background_services(input: BackgroundServicesInput) -> anyhow::Re
 
         // Start the onwards configuration listener
         let onwards_shutdown = shutdown_token.clone();
-        let fallback_interval = config.background_services.onwards_sync.fallback_interval_milliseconds;
+        let onwards_sync_config = config.background_services.onwards_sync.clone();
         background_tasks.spawn("onwards-config-sync", async move {
             info!(
                 "Starting onwards configuration listener (fallback sync every {}ms)",
-                fallback_interval
+                onwards_sync_config.fallback_interval_milliseconds
             );
             let sync_config = sync::onwards_config::SyncConfig {
                 status_tx: None,
-                fallback_interval_milliseconds: fallback_interval,
+                fallback_interval_milliseconds: onwards_sync_config.fallback_interval_milliseconds,
+                reconnect_initial_delay_milliseconds: onwards_sync_config.reconnect_initial_delay_milliseconds,
+                reconnect_max_delay_milliseconds: onwards_sync_config.reconnect_max_delay_milliseconds,
             };
             onwards_config_sync
                 .start(sync_config, onwards_shutdown)
@@ -2491,7 +2949,7 @@ async fn setup_background_services(input: BackgroundServicesInput) -> anyhow::Re
                 .context("Onwards configuration listener failed")
         });
 
-        (initial_targets, Some(sender))
+        (initial_targets, Some(sender), Some(drift_checker))
     } else {
         info!("Onwards config sync disabled - AI proxy will not receive config updates");
         // Create empty targets when onwards sync is disabled
@@ -2501,7 +2959,11 @@ async fn setup_background_services(input: BackgroundServicesInput) -> anyhow::Re
             strict_mode: false,
             http_pool: None,
         };
-        (onwards::target::Targets::from_config(empty_config)?, None)
+        (onwards::target::Targets::from_config(empty_config)?, None, None)
+    };
+    let drift_check_interval = match config.background_services.onwards_sync.drift_check_interval_milliseconds {
+        0 => None,
+        ms => Some(std::time::Duration::from_millis(ms)),
     };
 
     // Per-key ZDR policy map: an initial synchronous load ALWAYS runs, so the
@@ -2555,10 +3017,70 @@ async fn setup_background_services(input: BackgroundServicesInput) -> anyhow::Re
                 // Probe scheduler runs until cancelled, then exits normally
                 Ok(())
             });
+
+            let retention = config.background_services.probe_scheduler.retention.clone();
+            if retention.enabled {
+                let retention_pool = pool.clone();
+                let retention_shutdown = shutdown_token.clone();
+                background_tasks.spawn("probe-retention", async move {
+                    probes::retention::run_probe_retention(retention_pool, retention, retention_shutdown).await
+                });
+            }
         } else {
             info!("Probe scheduler disabled by configuration");
         }
 
+        if config.background_services.endpoint_auto_sync.enabled {
+            let auto_sync_pool = pool.clone();
+            let auto_sync_config = config.background_services.endpoint_auto_sync.clone();
+            let auto_sync_retry = config.endpoint_sync.clone();
+            let auto_sync_shutdown = shutdown_token.clone();
+            background_tasks.spawn("endpoint-auto-sync", async move {
+                sync::endpoint_auto_sync::run_endpoint_auto_sync(auto_sync_pool, auto_sync_config, auto_sync_retry, auto_sync_shutdown)
+                    .await
+            });
+        }
+
+        if config.background_services.deployment_warmup.enabled {
+            let warmup_pool = pool.clone();
+            let warmup_config = config.background_services.deployment_warmup.clone();
+            let warmup_proxy_url = format!("http://localhost:{}/ai", config.port);
+            let warmup_shutdown = shutdown_token.clone();
+            background_tasks.spawn("deployment-warmup", async move {
+                probes::warmup::run_deployment_warmup(warmup_pool, warmup_config, warmup_proxy_url, warmup_shutdown).await
+            });
+        }
+
+        if let (Some(checker), Some(interval)) = (drift_checker.clone(), drift_check_interval) {
+            let drift_shutdown = shutdown_token.clone();
+            background_tasks.spawn("onwards-drift-check", async move { checker.run(interval, drift_shutdown).await });
+        }
+
+        if config.background_services.balance_checkpoints.enabled {
+            let checkpoint_pool = pool.clone();
+            let checkpoint_config = config.background_services.balance_checkpoints.clone();
+            let checkpoint_shutdown = shutdown_token.clone();
+            background_tasks.spawn("balance-checkpoints", async move {
+                sync::balance_checkpoints::run_balance_checkpoint_job(checkpoint_pool, checkpoint_config, checkpoint_shutdown).await
+            });
+        }
+
+        if config.background_services.batch_retention.enabled {
+            let retention_pool = pool.clone();
+            let retention_request_manager = request_manager.clone();
+            let retention_config = config.background_services.batch_retention.clone();
+            let retention_shutdown = shutdown_token.clone();
+            background_tasks.spawn("batch-retention", async move {
+                sync::batch_retention::run_batch_retention_job(
+                    retention_pool,
+                    retention_request_manager,
+                    retention_config,
+                    retention_shutdown,
+                )
+                .await
+            });
+        }
+
         // Start the fusillade batch processing daemon based on config
         use crate::config::DaemonEnabled;
         match config.background_services.batch_daemon.enabled {
@@ -2646,6 +3168,7 @@ async fn setup_background_services(input: BackgroundServicesInput) -> anyhow::Re
         let leader_election_scheduler_lose = probe_scheduler.clone();
         let leader_election_request_manager_gain = request_manager.clone();
         let leader_election_postgres_daemon_gain = postgres_daemon.clone();
+        let leader_election_drift_checker = drift_checker.clone();
         let leader_election_config = config.clone();
         let leader_election_flag = is_leader_flag.clone();
 
@@ -2676,6 +3199,7 @@ async fn setup_background_services(input: BackgroundServicesInput) -> anyhow::Re
                     let postgres_daemon = leader_election_postgres_daemon_gain.clone();
                     let daemon_handle = daemon_handle_gain.clone();
                     let leadership_shutdown = leadership_shutdown_gain.clone();
+                    let drift_checker = leader_election_drift_checker.clone();
                     async move {
                         // Wait for the server to be fully up before starting probes
                         tokio::time::sleep(std::time::Duration::from_secs(1)).await;
@@ -2698,10 +3222,81 @@ async fn setup_background_services(input: BackgroundServicesInput) -> anyhow::Re
                                 let use_listen_notify = !cfg!(test);
                                 daemon_scheduler.run_daemon(daemon_session_token, use_listen_notify, 300).await;
                             });
+
+                            let retention = config.background_services.probe_scheduler.retention.clone();
+                            if retention.enabled {
+                                let retention_pool = pool.clone();
+                                let retention_session_token = session_token.clone();
+                                tokio::spawn(async move {
+                                    probes::retention::run_probe_retention(retention_pool, retention, retention_session_token).await
+                                });
+                            }
                         } else {
                             tracing::info!("Probe scheduler disabled by configuration");
                         }
 
+                        if config.background_services.endpoint_auto_sync.enabled {
+                            let auto_sync_pool = pool.clone();
+                            let auto_sync_config = config.background_services.endpoint_auto_sync.clone();
+                            let auto_sync_retry = config.endpoint_sync.clone();
+                            let auto_sync_session_token = session_token.clone();
+                            tokio::spawn(async move {
+                                sync::endpoint_auto_sync::run_endpoint_auto_sync(
+                                    auto_sync_pool,
+                                    auto_sync_config,
+                                    auto_sync_retry,
+                                    auto_sync_session_token,
+                                )
+                                .await
+                            });
+                        }
+
+                        if config.background_services.deployment_warmup.enabled {
+                            let warmup_pool = pool.clone();
+                            let warmup_config = config.background_services.deployment_warmup.clone();
+                            let warmup_proxy_url = format!("http://localhost:{}/ai", config.port);
+                            let warmup_session_token = session_token.clone();
+                            tokio::spawn(async move {
+                                probes::warmup::run_deployment_warmup(warmup_pool, warmup_config, warmup_proxy_url, warmup_session_token)
+                                    .await
+                            });
+                        }
+
+                        if let (Some(checker), Some(interval)) = (drift_checker, drift_check_interval) {
+                            let drift_session_token = session_token.clone();
+                            tokio::spawn(async move { checker.run(interval, drift_session_token).await });
+                        }
+
+                        if config.background_services.balance_checkpoints.enabled {
+                            let checkpoint_pool = pool.clone();
+                            let checkpoint_config = config.background_services.balance_checkpoints.clone();
+                            let checkpoint_session_token = session_token.clone();
+                            tokio::spawn(async move {
+                                sync::balance_checkpoints::run_balance_checkpoint_job(
+                                    checkpoint_pool,
+                                    checkpoint_config,
+                                    checkpoint_session_token,
+                                )
+                                .await
+                            });
+                        }
+
+                        if config.background_services.batch_retention.enabled {
+                            let retention_pool = pool.clone();
+                            let retention_request_manager = request_manager.clone();
+                            let retention_config = config.background_services.batch_retention.clone();
+                            let retention_session_token = session_token.clone();
+                            tokio::spawn(async move {
+                                sync::batch_retention::run_batch_retention_job(
+                                    retention_pool,
+                                    retention_request_manager,
+                                    retention_config,
+                                    retention_session_token,
+                                )
+                                .await
+                            });
+                        }
+
                         let notification_request_manager = request_manager.clone();
 
                         // Start the fusillade batch processing daemon based on config
@@ -2831,6 +3426,22 @@ async fn setup_background_services(input: BackgroundServicesInput) -> anyhow::Re
         });
     }
 
+    // Start batch queue depth sampler if metrics are enabled
+    if config.enable_metrics && config.batches.enabled && config.background_services.batch_queue_metrics.enabled {
+        let windows = config
+            .batches
+            .allowed_completion_windows
+            .iter()
+            .map(|window| (window.clone(), None, api::handlers::sla_capacity::parse_window_to_seconds(window)))
+            .collect::<Vec<_>>();
+        let sampler_request_manager = request_manager.clone();
+        let sample_interval = config.background_services.batch_queue_metrics.sample_interval;
+        let sampler_shutdown = shutdown_token.clone();
+        background_tasks.spawn("batch-queue-metrics-sampler", async move {
+            crate::metrics::run_batch_queue_metrics_sampler(sampler_request_manager, windows, sample_interval, sampler_shutdown).await
+        });
+    }
+
     // Start the usage-refresh daemon: incrementally folds new http_analytics rows into
     // user_model_usage_daily. The analytics batcher (below) nudges it after every flush;
     // this shares an in-process Notify with it rather than round-tripping through Postgres.
@@ -2849,7 +3460,17 @@ async fn setup_background_services(input: BackgroundServicesInput) -> anyhow::Re
     // Start analytics batcher if enabled
     let analytics_sender = if config.enable_analytics {
         let (batcher, sender) = request_logging::AnalyticsBatcher::new(pool.clone(), config.clone(), metrics_recorder);
-        let batcher = batcher.with_usage_refresh_notify(usage_refresh_notify.clone());
+        let mut batcher = batcher.with_usage_refresh_notify(usage_refresh_notify.clone());
+
+        // The sink worker stops after the batcher does, once it has delivered what's left
+        if let Some(destination) = config.analytics.sink.destination.clone() {
+            let (sink, worker) = request_logging::AnalyticsSink::new(&config.analytics.sink, destination)?;
+            batcher = batcher.with_sink(sink);
+            background_tasks.spawn("analytics-sink", async move {
+                worker.run().await;
+                Ok(())
+            });
+        }
 
         let batcher_shutdown = shutdown_token.clone();
         background_tasks.spawn("analytics-batcher", async move {
@@ -2879,18 +3500,6 @@ async fn setup_background_services(input: BackgroundServicesInput) -> anyhow::Re
     };
 
     // Build the underway task runner for background jobs (batch population, sync pipeline, etc.)
-    let encryption_key = match config.connections.encryption_key.as_deref().or(config.secret_key.as_deref()) {
-        Some(secret) if !secret.trim().is_empty() => Some(encryption::derive_encryption_key(secret.trim())),
-        Some(_) => {
-            tracing::warn!("Encryption key is empty/whitespace — connection features will be unavailable");
-            None
-        }
-        None => {
-            tracing::info!("No encryption key configured for connections (set secret_key or connections.encryption_key)");
-            None
-        }
-    };
-
     let task_state = tasks::TaskState {
         request_manager: request_manager.clone(),
         dwctl_pool: pool.clone(),
@@ -2929,6 +3538,7 @@ async fn setup_background_services(input: BackgroundServicesInput) -> anyhow::Re
         drop_guard: Some(drop_guard),
         connections_encryption_key: encryption_key.clone(),
         keystore,
+        secret_store,
         // Application::new_with_pool wires these once the onwards-
         // instance daemon row is registered. Kept Optional here so
         // tests that bypass that wiring still construct cleanly.
@@ -3001,8 +3611,16 @@ impl Application {
     ) -> anyhow::Result<Self> {
         debug!("Starting control layer with configuration: {:#?}", config);
 
+        if config.auth.proxy_header.enabled && !config.auth.proxy_header.has_trust_controls() {
+            warn!(
+                "Proxy header auth is enabled without trusted_proxies or shared_secret; any client that can reach \
+                 dwctl directly can impersonate users"
+            );
+        }
+
         // Setup database connections, run migrations, and initialize data
         let (_embedded_db, db_pools, fusillade_pools, outlet_pools) = setup_database(&config, pool).await?;
+        let analytics_pool = connect_analytics_pool(&config).await?;
 
         // Install Prometheus recorder BEFORE background services start
         // This ensures metrics set during background service initialization are captured
@@ -3309,6 +3927,7 @@ impl Application {
             image_normalizer_enabled: config.image_normalizer.enabled,
             unverified_requests_per_completion_hour: config.batches.unverified_requests_per_completion_hour,
             flex_completion_window: config.batches.async_requests.completion_window.clone(),
+            config: shared_config.clone(),
             keystore: bg_services.keystore.clone(),
             zdr_key_cache: bg_services.zdr_key_cache.clone(),
         };
@@ -3325,13 +3944,27 @@ impl Application {
         // onwards stays cache-agnostic: cached-input pricing now lives entirely in
         // the dwctl cache tower layer (wired in `build_router`, gated on `cache.enabled`).
         // No classifier is injected here.
-        let onwards_app_state = onwards::AppState::with_transform(bg_services.onwards_targets.clone(), body_transform)
+        // The upstream client is built once from the targets' pool config.
+        let mut onwards_targets = bg_services.onwards_targets.clone();
+        onwards_targets.http_pool_config = Some((&config.onwards.upstream_http).into());
+        let mut onwards_app_state = onwards::AppState::with_transform(onwards_targets, body_transform)
             .with_response_transform(onwards::create_openai_sanitizer())
             .with_streaming_header("x-fusillade-stream")
             .with_response_id_header("x-fusillade-request-id")
+            .with_route_to_header("x-dwctl-route-to")
+            .with_region_preference_header("x-region-preference")
+            .with_priority_header("x-priority")
+            .with_deprecation_header("openai-deprecation")
+            .with_queue_wait_header("x-queue-wait-ms")
             .with_tool_executor(Arc::new(tool_executor))
             .with_response_store(response_store.clone() as Arc<dyn onwards::ResponseStore>)
             .with_body_limit(onwards_body_limit);
+        if config.onwards.circuit_breaker.enabled {
+            onwards_app_state = onwards_app_state.with_circuit_breaker((&config.onwards.circuit_breaker).into());
+        }
+        if config.onwards.request_queue.enabled {
+            onwards_app_state = onwards_app_state.with_request_queue((&config.onwards.request_queue).into());
+        }
 
         let onwards_router = if bg_services.onwards_targets.strict_mode {
             tracing::info!("Strict mode enabled - using typed request validation");
@@ -3351,6 +3984,7 @@ impl Application {
             .request_manager(bg_services.request_manager.clone())
             .task_runner(bg_services.task_runner.clone())
             .maybe_outlet_db(outlet_pools.clone())
+            .maybe_analytics_db(analytics_pool)
             .limiters(limiters)
             .maybe_connections_encryption_key(bg_services.connections_encryption_key.clone())
             .maybe_keystore(bg_services.keystore.clone())
@@ -3414,7 +4048,8 @@ impl Application {
             bind_addr, self.config.port
         );
 
-        // Apply middleware before path matching
+        // Apply middleware before path matching. Peer addresses are recorded so proxy
+        // header auth can check `trusted_proxies`.
         let middleware = middleware::from_fn_with_state(self.app_state, admin_ai_proxy_middleware);
         let service = middleware.layer(self.router);
 
@@ -3430,7 +4065,7 @@ impl Application {
 
         // Race the server against background task failures (fail-fast)
         let server_error: Option<anyhow::Error> = tokio::select! {
-            result = axum::serve(listener, service.into_make_service()).with_graceful_shutdown(shutdown) => {
+            result = axum::serve(listener, service.into_make_service_with_connect_info::<std::net::SocketAddr>()).with_graceful_shutdown(shutdown) => {
                 result.err().map(Into::into) // None if server shut down cleanly
             }
             result = self.bg_services.wait_for_failure() => {
@@ -3,14 +3,19 @@
 //! This module provides rate limiting and concurrency control mechanisms
 //! to prevent resource exhaustion under high load.
 
+use std::num::NonZeroU32;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 
+use governor::clock::Clock;
+use governor::{DefaultKeyedRateLimiter, Quota, RateLimiter};
 use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
-use crate::config::{FileLimitsConfig, LimitsConfig};
+use crate::api::models::users::{CurrentUser, Role};
+use crate::config::{AdminApiLimitsConfig, FileLimitsConfig, LimitsConfig};
 use crate::errors::{Error, Result};
+use crate::types::UserId;
 
 /// Overhead allowance for multipart encoding (headers, boundaries, field metadata).
 /// This is added to max_file_size when configuring body limits to account for
@@ -29,6 +34,8 @@ pub const MULTIPART_OVERHEAD: u64 = 10 * 1024; // 10KB
 pub struct Limiters {
     /// Limiter for concurrent file uploads. None means unlimited.
     pub file_uploads: Option<Arc<UploadLimiter>>,
+    /// Per-user admin API rate limiter. None means unlimited.
+    pub admin_api: Option<Arc<AdminApiLimiter>>,
 }
 
 impl Limiters {
@@ -36,10 +43,70 @@ impl Limiters {
     pub fn new(config: &LimitsConfig) -> Self {
         Self {
             file_uploads: UploadLimiter::new(&config.files).map(Arc::new),
+            admin_api: AdminApiLimiter::new(&config.admin_api).map(Arc::new),
         }
     }
 }
 
+/// Rate limits admin API requests per authenticated user.
+///
+/// Platform managers and legacy admins are limited by their own tier, so a
+/// busy operator isn't throttled at the rate set for everyone else. The
+/// limiters keep one small entry per user that has made a request.
+#[derive(Debug)]
+pub struct AdminApiLimiter {
+    /// Limiter for users without platform manager access. None means unlimited.
+    standard: Option<DefaultKeyedRateLimiter<UserId>>,
+    /// Limiter for platform managers. None means unlimited.
+    platform_manager: Option<DefaultKeyedRateLimiter<UserId>>,
+}
+
+impl AdminApiLimiter {
+    /// Creates the limiter from configuration.
+    ///
+    /// Returns `None` when neither tier has a rate (unlimited admin API).
+    pub fn new(config: &AdminApiLimitsConfig) -> Option<Self> {
+        let standard = keyed_limiter(config.requests_per_second, config.burst_size);
+        let platform_manager = keyed_limiter(config.platform_manager_requests_per_second, config.platform_manager_burst_size);
+        if standard.is_none() && platform_manager.is_none() {
+            return None;
+        }
+        Some(Self {
+            standard,
+            platform_manager,
+        })
+    }
+
+    /// Records a request by `user`.
+    ///
+    /// Returns `Err` with the time until the user may make another request
+    /// when they are over their limit.
+    pub fn check(&self, user: &CurrentUser) -> std::result::Result<(), Duration> {
+        let is_platform_manager = user.is_admin || user.roles.contains(&Role::PlatformManager);
+        let limiter = if is_platform_manager {
+            &self.platform_manager
+        } else {
+            &self.standard
+        };
+        let Some(limiter) = limiter else {
+            return Ok(());
+        };
+        limiter
+            .check_key(&user.id)
+            .map_err(|not_until| not_until.wait_time_from(limiter.clock().now()))
+    }
+}
+
+fn keyed_limiter(requests_per_second: Option<f32>, burst_size: Option<u32>) -> Option<DefaultKeyedRateLimiter<UserId>> {
+    let requests_per_second = requests_per_second.filter(|rps| rps.is_finite() && *rps > 0.0)?;
+    let burst = burst_size
+        .and_then(NonZeroU32::new)
+        .or_else(|| NonZeroU32::new(requests_per_second.ceil() as u32))
+        .unwrap_or(NonZeroU32::MIN);
+    let quota = Quota::with_period(Duration::from_secs_f64(1.0 / f64::from(requests_per_second)))?.allow_burst(burst);
+    Some(RateLimiter::keyed(quota))
+}
+
 /// Controls concurrent file upload capacity.
 ///
 /// This limiter implements a bounded queue with configurable concurrency,
@@ -175,6 +242,48 @@ mod tests {
         }
     }
 
+    fn admin_api_user(roles: Vec<Role>) -> CurrentUser {
+        CurrentUser {
+            id: uuid::Uuid::new_v4(),
+            username: "user".to_string(),
+            email: "user@example.com".to_string(),
+            is_admin: false,
+            roles,
+            display_name: None,
+            avatar_url: None,
+            payment_provider_id: None,
+            organizations: vec![],
+            active_organization: None,
+            api_key_id: None,
+        }
+    }
+
+    #[test]
+    fn test_admin_api_limiter_tiers() {
+        assert!(AdminApiLimiter::new(&AdminApiLimitsConfig::default()).is_none());
+
+        let limiter = AdminApiLimiter::new(&AdminApiLimitsConfig {
+            requests_per_second: Some(0.1),
+            burst_size: Some(2),
+            ..Default::default()
+        })
+        .unwrap();
+        let user = admin_api_user(vec![Role::StandardUser]);
+        let other = admin_api_user(vec![Role::StandardUser]);
+        let manager = admin_api_user(vec![Role::PlatformManager]);
+
+        assert!(limiter.check(&user).is_ok());
+        assert!(limiter.check(&user).is_ok());
+        let wait = limiter.check(&user).unwrap_err();
+        assert!(wait > Duration::from_secs(5) && wait <= Duration::from_secs(10));
+
+        // Buckets are per user, and platform managers have their own (unlimited) tier
+        assert!(limiter.check(&other).is_ok());
+        for _ in 0..10 {
+            assert!(limiter.check(&manager).is_ok());
+        }
+    }
+
     #[test]
     fn test_unlimited_returns_none() {
         let config = test_config(0, 20, 60);
@@ -1,5 +1,5 @@
 use clap::Parser;
-use dwctl::{Application, Config, telemetry};
+use dwctl::{Application, Config, config::Command, telemetry};
 
 /// Wait for shutdown signal (SIGTERM or Ctrl+C)
 async fn shutdown_signal() {
@@ -44,23 +44,35 @@ async fn async_main() -> anyhow::Result<()> {
     // Parse CLI args
     let args = dwctl::config::Args::parse();
 
+    // `validate-config` (or the --validate flag) reports on the config and exits
+    // without binding a port or running migrations
+    let validate_only = match args.command {
+        Some(Command::ValidateConfig { check_database }) => Some(check_database),
+        None if args.validate => Some(false),
+        _ => None,
+    };
+    if let Some(check_database) = validate_only {
+        let report = dwctl::config_check::run(&args, check_database).await;
+        print!("{report}");
+        std::process::exit(if report.is_valid() { 0 } else { 1 });
+    }
+
     // Load configuration
     let config = Config::load(&args)?;
 
     // Validate config consistency
     config.batches.validate();
 
-    // If --validate flag is set, exit successfully after config validation
-    if args.validate {
-        println!("Configuration is valid.");
-        return Ok(());
-    }
-
     // Initialize telemetry (tracing + optional OpenTelemetry)
-    let tracer_provider = telemetry::init_telemetry(config.enable_otel_export)?;
+    let tracer_provider = telemetry::init_telemetry(config.enable_otel_export, &config.log)?;
 
     tracing::debug!("{:?}", args);
 
+    // `migrate` applies pending migrations and exits without starting the server
+    if args.command == Some(Command::Migrate) {
+        return dwctl::migrate(&config, None).await;
+    }
+
     // Run the application with graceful shutdown on SIGTERM/Ctrl+C
     let shutdown = shutdown_signal();
     Application::new_with_config_path(config, Some(args.config.clone()), tracer_provider)
@@ -0,0 +1,208 @@
+//! Prometheus gauges for batch queue depth.
+//!
+//! Periodically reads request counts from fusillade storage and publishes them
+//! as gauges so the batch daemon can be autoscaled on queue depth:
+//!
+//! - `dwctl_batch_queue_pending_requests{model, window}` - requests waiting to be claimed
+//! - `dwctl_batch_queue_in_progress_requests{model, window}` - requests claimed or being processed
+//!
+//! `window` buckets requests by deadline ("due within"), using the configured
+//! `batches.allowed_completion_windows` - the same buckets as the
+//! `/admin/api/v1/monitoring/pending-request-counts` endpoint. The priority
+//! tier is excluded since it's served in realtime rather than by the daemon.
+//!
+//! Throughput comes from the daemon itself: `fusillade_requests_completed_total{model, status}`
+//! is incremented on every terminal transition, so completed/failed per minute is
+//! `rate(fusillade_requests_completed_total[1m]) * 60`.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use fusillade::Storage;
+use fusillade::request::ServiceTierFilter;
+use metrics::gauge;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info};
+
+use crate::metrics::errors::component;
+
+const PENDING_GAUGE: &str = "dwctl_batch_queue_pending_requests";
+const IN_PROGRESS_GAUGE: &str = "dwctl_batch_queue_in_progress_requests";
+
+/// Tracks the (model, window) series emitted on the previous sample so series
+/// whose queue has drained are reset to zero rather than left at their last value.
+#[derive(Default)]
+pub struct BatchQueueMetricsState {
+    prev_pending: HashSet<(String, String)>,
+    prev_in_progress: HashSet<(String, String)>,
+}
+
+/// Sample queue depth once and update the gauges.
+pub async fn sample_batch_queue_metrics<S: Storage + ?Sized>(
+    storage: &S,
+    windows: &[(String, Option<i64>, i64)],
+    state: &mut BatchQueueMetricsState,
+) -> fusillade::Result<()> {
+    let tier_filter = ServiceTierFilter::Exclude(vec![Some("priority".to_string())]);
+
+    let pending = storage
+        .get_pending_request_counts_by_model_and_window(windows, &["pending".to_string()], &[], &tier_filter, None, false)
+        .await?;
+    let in_progress = storage
+        .get_pending_request_counts_by_model_and_window(
+            windows,
+            &["claimed".to_string(), "processing".to_string()],
+            &[],
+            &tier_filter,
+            None,
+            false,
+        )
+        .await?;
+
+    state.prev_pending = set_gauges(PENDING_GAUGE, &pending, &state.prev_pending);
+    state.prev_in_progress = set_gauges(IN_PROGRESS_GAUGE, &in_progress, &state.prev_in_progress);
+
+    debug!(
+        models_pending = pending.len(),
+        models_in_progress = in_progress.len(),
+        "Sampled batch queue metrics"
+    );
+    Ok(())
+}
+
+/// Set `name` for every (model, window) in `counts`, zero the series that
+/// disappeared since `prev`, and return the new series set.
+fn set_gauges(
+    name: &'static str,
+    counts: &HashMap<String, HashMap<String, i64>>,
+    prev: &HashSet<(String, String)>,
+) -> HashSet<(String, String)> {
+    let mut current = HashSet::new();
+    for (model, by_window) in counts {
+        for (window, count) in by_window {
+            gauge!(name, "model" => model.clone(), "window" => window.clone()).set(*count as f64);
+            current.insert((model.clone(), window.clone()));
+        }
+    }
+    for (model, window) in prev.difference(&current) {
+        gauge!(name, "model" => model.clone(), "window" => window.clone()).set(0.0);
+    }
+    current
+}
+
+/// Run the batch queue metrics sampler until `shutdown` is cancelled.
+pub async fn run_batch_queue_metrics_sampler<S: Storage + ?Sized>(
+    storage: Arc<S>,
+    windows: Vec<(String, Option<i64>, i64)>,
+    sample_interval: Duration,
+    shutdown: CancellationToken,
+) -> anyhow::Result<()> {
+    info!("Starting batch queue metrics sampler with {:?} interval", sample_interval);
+
+    let mut state = BatchQueueMetricsState::default();
+    let mut interval = tokio::time::interval(sample_interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => {
+                info!("Batch queue metrics sampler shutting down");
+                break;
+            }
+            _ = interval.tick() => {
+                if let Err(e) = sample_batch_queue_metrics(storage.as_ref(), &windows, &mut state).await {
+                    crate::background_error!(component::BATCH_QUEUE_METRICS, "sample", Warning, error = %e, "Failed to sample batch queue metrics");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fusillade::{BatchInput, RequestTemplateInput};
+    use sqlx::PgPool;
+    use sqlx::postgres::PgConnectOptions;
+    use sqlx_pool_router::TestDbPools;
+
+    fn gauge_line<'a>(rendered: &'a str, name: &str, model: &str) -> Option<&'a str> {
+        rendered
+            .lines()
+            .find(|line| line.starts_with(name) && line.contains(&format!("model=\"{model}\"")))
+    }
+
+    #[sqlx::test]
+    async fn test_batch_queue_metrics_appear_after_submitting_batch(pool: PgPool) {
+        let handle = crate::get_or_install_prometheus_handle();
+        // Run the app once so the fusillade schema is migrated.
+        let (_server, _bg) = crate::test::utils::create_test_app(pool.clone(), false).await;
+
+        let base_opts: PgConnectOptions = pool.connect_options().as_ref().clone();
+        let fusillade_pool = sqlx::postgres::PgPoolOptions::new()
+            .max_connections(2)
+            .min_connections(0)
+            .connect_with(base_opts.options([("search_path", "fusillade")]))
+            .await
+            .expect("Failed to create fusillade pool");
+        let fusillade_pools = TestDbPools::new(fusillade_pool).await.expect("TestDbPools");
+        let request_manager = fusillade_arsenal::PostgresRequestManager::new(fusillade_pools, Default::default());
+
+        let model = "batch-queue-metrics-model";
+        let templates = (0..3)
+            .map(|i| RequestTemplateInput {
+                custom_id: Some(format!("req-{i}")),
+                endpoint: "https://api.example.com".to_string(),
+                method: "POST".to_string(),
+                path: "/v1/chat/completions".to_string(),
+                body: r#"{"input":"x"}"#.to_string(),
+                model: model.to_string(),
+                api_key: "key".to_string(),
+            })
+            .collect();
+        let file_id = request_manager
+            .create_file("batch-queue-metrics".to_string(), None, templates)
+            .await
+            .expect("create_file");
+        request_manager
+            .create_batch(BatchInput {
+                file_id,
+                endpoint: "/v1/chat/completions".to_string(),
+                completion_window: "24h".to_string(),
+                metadata: None,
+                created_by: None,
+                api_key_id: None,
+                api_key: None,
+                total_requests: None,
+            })
+            .await
+            .expect("create_batch");
+
+        let windows = vec![("24h".to_string(), None, 86400 + 60)];
+        let mut state = BatchQueueMetricsState::default();
+        sample_batch_queue_metrics(&request_manager, &windows, &mut state)
+            .await
+            .expect("sample should succeed");
+
+        let rendered = handle.render();
+        let pending = gauge_line(&rendered, PENDING_GAUGE, model).unwrap_or_else(|| panic!("missing {PENDING_GAUGE} for {model}"));
+        assert!(pending.contains("window=\"24h\""), "unexpected labels: {pending}");
+        assert!(pending.ends_with(" 3"), "expected 3 pending requests: {pending}");
+
+        // Once the batch is cancelled the series is reset rather than left stale.
+        sqlx::query("UPDATE fusillade.batches SET cancelling_at = NOW()")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sample_batch_queue_metrics(&request_manager, &windows, &mut state)
+            .await
+            .expect("sample should succeed");
+
+        let rendered = handle.render();
+        let pending = gauge_line(&rendered, PENDING_GAUGE, model).expect("series should still be exported");
+        assert!(pending.ends_with(" 0"), "expected drained series to be zeroed: {pending}");
+    }
+}
@@ -369,7 +369,7 @@ mod tests {
     use crate::config::RateLimitTiersConfig;
     use crate::db::handlers::{Deployments, Groups, InferenceEndpoints, Repository, Tariffs};
     use crate::db::models::{
-        deployments::{DeploymentCreateDBRequest, LoadBalancingStrategy},
+        deployments::{DeploymentCreateDBRequest, LoadBalancingStrategy, SystemPromptMode},
         groups::GroupCreateDBRequest,
         inference_endpoints::InferenceEndpointCreateDBRequest,
         tariffs::TariffCreateDBRequest,
@@ -403,6 +403,15 @@ mod tests {
                 auth_header_name: Some("Authorization".to_string()),
                 auth_header_prefix: Some("Bearer ".to_string()),
                 reasoning_translation: None,
+                bedrock: None,
+                region: None,
+                body_transform: None,
+                auto_sync_interval_seconds: None,
+                alias_template: None,
+                max_concurrency: None,
+                tls: None,
+                additional_api_keys: Vec::new(),
+                api_key_rotation: None,
             })
             .await
             .unwrap();
@@ -425,6 +434,10 @@ mod tests {
                 burst_size: None,
                 capacity: Some(50),
                 batch_capacity: Some(10),
+                per_key_capacity: None,
+                max_cost_per_request: None,
+                input_modalities: None,
+                output_modalities: None,
                 throughput: Some(25.0),
                 provider_pricing: None,
                 is_composite: false,
@@ -442,6 +455,19 @@ mod tests {
                 backoff_max_total_ms: None,
                 sanitize_responses: true,
                 trusted: false,
+                allow_public: false,
+                require_approval: false,
+                rewrite_response_model: false,
+                disable_logging: false,
+                warmup: false,
+                system_prompt: None,
+                system_prompt_mode: SystemPromptMode::default(),
+                max_n: None,
+                max_n_mode: Default::default(),
+                credit_exhaustion_mode: Default::default(),
+                max_overdraft: None,
+                max_prompt_length: None,
+                max_prompt_length_unit: Default::default(),
                 open_responses_adapter: true,
                 reasoning_translation_overrides: None,
 
@@ -491,7 +517,7 @@ mod tests {
         tx.commit().await.unwrap();
 
         // Load targets and update metrics
-        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
             .await
             .unwrap();
         super::update_cache_info_metrics(&pool, &targets, &mut state).await.unwrap();
@@ -558,6 +584,15 @@ mod tests {
                 auth_header_name: Some("Authorization".to_string()),
                 auth_header_prefix: Some("Bearer ".to_string()),
                 reasoning_translation: None,
+                bedrock: None,
+                region: None,
+                body_transform: None,
+                auto_sync_interval_seconds: None,
+                alias_template: None,
+                max_concurrency: None,
+                tls: None,
+                additional_api_keys: Vec::new(),
+                api_key_rotation: None,
             })
             .await
             .unwrap();
@@ -580,6 +615,10 @@ mod tests {
                 burst_size: None,
                 capacity: None,
                 batch_capacity: None,
+                per_key_capacity: None,
+                max_cost_per_request: None,
+                input_modalities: None,
+                output_modalities: None,
                 throughput: None,
                 provider_pricing: None,
                 is_composite: false,
@@ -597,6 +636,19 @@ mod tests {
                 backoff_max_total_ms: None,
                 sanitize_responses: true,
                 trusted: false,
+                allow_public: false,
+                require_approval: false,
+                rewrite_response_model: false,
+                disable_logging: false,
+                warmup: false,
+                system_prompt: None,
+                system_prompt_mode: SystemPromptMode::default(),
+                max_n: None,
+                max_n_mode: Default::default(),
+                credit_exhaustion_mode: Default::default(),
+                max_overdraft: None,
+                max_prompt_length: None,
+                max_prompt_length_unit: Default::default(),
                 open_responses_adapter: true,
                 reasoning_translation_overrides: None,
 
@@ -624,6 +676,10 @@ mod tests {
                 burst_size: None,
                 capacity: None,
                 batch_capacity: None,
+                per_key_capacity: None,
+                max_cost_per_request: None,
+                input_modalities: None,
+                output_modalities: None,
                 throughput: None,
                 provider_pricing: None,
                 is_composite: true,
@@ -641,6 +697,19 @@ mod tests {
                 backoff_max_total_ms: None,
                 sanitize_responses: true,
                 trusted: false,
+                allow_public: false,
+                require_approval: false,
+                rewrite_response_model: false,
+                disable_logging: false,
+                warmup: false,
+                system_prompt: None,
+                system_prompt_mode: SystemPromptMode::default(),
+                max_n: None,
+                max_n_mode: Default::default(),
+                credit_exhaustion_mode: Default::default(),
+                max_overdraft: None,
+                max_prompt_length: None,
+                max_prompt_length_unit: Default::default(),
                 open_responses_adapter: true,
                 reasoning_translation_overrides: None,
 
@@ -662,7 +731,7 @@ mod tests {
         .await
         .unwrap();
 
-        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
             .await
             .unwrap();
         super::update_cache_info_metrics(&pool, &targets, &mut state).await.unwrap();
@@ -710,6 +779,15 @@ mod tests {
                 auth_header_name: Some("Authorization".to_string()),
                 auth_header_prefix: Some("Bearer ".to_string()),
                 reasoning_translation: None,
+                bedrock: None,
+                region: None,
+                body_transform: None,
+                auto_sync_interval_seconds: None,
+                alias_template: None,
+                max_concurrency: None,
+                tls: None,
+                additional_api_keys: Vec::new(),
+                api_key_rotation: None,
             })
             .await
             .unwrap();
@@ -731,6 +809,10 @@ mod tests {
             burst_size: None,
             capacity: None,
             batch_capacity: None,
+            per_key_capacity: None,
+            max_cost_per_request: None,
+            input_modalities: None,
+            output_modalities: None,
             throughput: None,
             provider_pricing: None,
             is_composite: false,
@@ -748,6 +830,19 @@ mod tests {
             backoff_max_total_ms: None,
             sanitize_responses: false,
             trusted: false,
+            allow_public: false,
+            require_approval: false,
+            rewrite_response_model: false,
+            disable_logging: false,
+            warmup: false,
+            system_prompt: None,
+            system_prompt_mode: SystemPromptMode::default(),
+            max_n: None,
+            max_n_mode: Default::default(),
+            credit_exhaustion_mode: Default::default(),
+            max_overdraft: None,
+            max_prompt_length: None,
+            max_prompt_length_unit: Default::default(),
             open_responses_adapter: true,
             reasoning_translation_overrides: None,
             allowed_batch_completion_windows: None,
@@ -757,7 +852,7 @@ mod tests {
         .unwrap();
         tx.commit().await.unwrap();
 
-        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
             .await
             .unwrap();
         super::update_cache_info_metrics(&pool, &targets, &mut state).await.unwrap();
@@ -811,6 +906,15 @@ mod tests {
                 auth_header_name: Some("Authorization".to_string()),
                 auth_header_prefix: Some("Bearer ".to_string()),
                 reasoning_translation: None,
+                bedrock: None,
+                region: None,
+                body_transform: None,
+                auto_sync_interval_seconds: None,
+                alias_template: None,
+                max_concurrency: None,
+                tls: None,
+                additional_api_keys: Vec::new(),
+                api_key_rotation: None,
             })
             .await
             .unwrap();
@@ -833,6 +937,10 @@ mod tests {
                 burst_size: None,
                 capacity: None,
                 batch_capacity: None,
+                per_key_capacity: None,
+                max_cost_per_request: None,
+                input_modalities: None,
+                output_modalities: None,
                 throughput: None,
                 provider_pricing: None,
                 is_composite: false,
@@ -850,6 +958,19 @@ mod tests {
                 backoff_max_total_ms: None,
                 sanitize_responses: false,
                 trusted: false,
+                allow_public: false,
+                require_approval: false,
+                rewrite_response_model: false,
+                disable_logging: false,
+                warmup: false,
+                system_prompt: None,
+                system_prompt_mode: SystemPromptMode::default(),
+                max_n: None,
+                max_n_mode: Default::default(),
+                credit_exhaustion_mode: Default::default(),
+                max_overdraft: None,
+                max_prompt_length: None,
+                max_prompt_length_unit: Default::default(),
                 open_responses_adapter: true,
                 reasoning_translation_overrides: None,
 
@@ -883,7 +1004,7 @@ mod tests {
         .unwrap();
 
         // Cycle 1: group is present — populates PREV_GROUPS
-        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
             .await
             .unwrap();
         super::update_cache_info_metrics(&pool, &targets, &mut state).await.unwrap();
@@ -912,7 +1033,7 @@ mod tests {
         .unwrap();
 
         // Cycle 2: group is gone — zeroing should zero the ORIGINAL series
-        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
             .await
             .unwrap();
         super::update_cache_info_metrics(&pool, &targets, &mut state).await.unwrap();
@@ -962,6 +1083,15 @@ mod tests {
                 auth_header_name: Some("Authorization".to_string()),
                 auth_header_prefix: Some("Bearer ".to_string()),
                 reasoning_translation: None,
+                bedrock: None,
+                region: None,
+                body_transform: None,
+                auto_sync_interval_seconds: None,
+                alias_template: None,
+                max_concurrency: None,
+                tls: None,
+                additional_api_keys: Vec::new(),
+                api_key_rotation: None,
             })
             .await
             .unwrap();
@@ -984,6 +1114,10 @@ mod tests {
                 burst_size: None,
                 capacity: None,
                 batch_capacity: None,
+                per_key_capacity: None,
+                max_cost_per_request: None,
+                input_modalities: None,
+                output_modalities: None,
                 throughput: None,
                 provider_pricing: None,
                 is_composite: false,
@@ -1001,6 +1135,19 @@ mod tests {
                 backoff_max_total_ms: None,
                 sanitize_responses: false,
                 trusted: false,
+                allow_public: false,
+                require_approval: false,
+                rewrite_response_model: false,
+                disable_logging: false,
+                warmup: false,
+                system_prompt: None,
+                system_prompt_mode: SystemPromptMode::default(),
+                max_n: None,
+                max_n_mode: Default::default(),
+                credit_exhaustion_mode: Default::default(),
+                max_overdraft: None,
+                max_prompt_length: None,
+                max_prompt_length_unit: Default::default(),
                 open_responses_adapter: true,
                 reasoning_translation_overrides: None,
 
@@ -1028,6 +1175,10 @@ mod tests {
                 burst_size: None,
                 capacity: None,
                 batch_capacity: None,
+                per_key_capacity: None,
+                max_cost_per_request: None,
+                input_modalities: None,
+                output_modalities: None,
                 throughput: None,
                 provider_pricing: None,
                 is_composite: true,
@@ -1045,6 +1196,19 @@ mod tests {
                 backoff_max_total_ms: None,
                 sanitize_responses: false,
                 trusted: false,
+                allow_public: false,
+                require_approval: false,
+                rewrite_response_model: false,
+                disable_logging: false,
+                warmup: false,
+                system_prompt: None,
+                system_prompt_mode: SystemPromptMode::default(),
+                max_n: None,
+                max_n_mode: Default::default(),
+                credit_exhaustion_mode: Default::default(),
+                max_overdraft: None,
+                max_prompt_length: None,
+                max_prompt_length_unit: Default::default(),
                 open_responses_adapter: true,
                 reasoning_translation_overrides: None,
 
@@ -1067,7 +1231,7 @@ mod tests {
         .unwrap();
 
         // Cycle 1: component is present
-        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
             .await
             .unwrap();
         super::update_cache_info_metrics(&pool, &targets, &mut state).await.unwrap();
@@ -1094,7 +1258,7 @@ mod tests {
         .unwrap();
 
         // Cycle 2: component is gone — should zero the original series
-        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
             .await
             .unwrap();
         super::update_cache_info_metrics(&pool, &targets, &mut state).await.unwrap();
@@ -1146,6 +1310,15 @@ mod tests {
                 auth_header_name: Some("Authorization".to_string()),
                 auth_header_prefix: Some("Bearer ".to_string()),
                 reasoning_translation: None,
+                bedrock: None,
+                region: None,
+                body_transform: None,
+                auto_sync_interval_seconds: None,
+                alias_template: None,
+                max_concurrency: None,
+                tls: None,
+                additional_api_keys: Vec::new(),
+                api_key_rotation: None,
             })
             .await
             .unwrap();
@@ -1168,6 +1341,10 @@ mod tests {
                 burst_size: None,
                 capacity: Some(99),
                 batch_capacity: None,
+                per_key_capacity: None,
+                max_cost_per_request: None,
+                input_modalities: None,
+                output_modalities: None,
                 throughput: None,
                 provider_pricing: None,
                 is_composite: false,
@@ -1185,6 +1362,19 @@ mod tests {
                 backoff_max_total_ms: None,
                 sanitize_responses: false,
                 trusted: false,
+                allow_public: false,
+                require_approval: false,
+                rewrite_response_model: false,
+                disable_logging: false,
+                warmup: false,
+                system_prompt: None,
+                system_prompt_mode: SystemPromptMode::default(),
+                max_n: None,
+                max_n_mode: Default::default(),
+                credit_exhaustion_mode: Default::default(),
+                max_overdraft: None,
+                max_prompt_length: None,
+                max_prompt_length_unit: Default::default(),
                 open_responses_adapter: true,
                 reasoning_translation_overrides: None,
 
@@ -1196,7 +1386,7 @@ mod tests {
         tx.commit().await.unwrap();
 
         // Cycle 1: model is active
-        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
             .await
             .unwrap();
         super::update_cache_info_metrics(&pool, &targets, &mut state).await.unwrap();
@@ -1223,7 +1413,7 @@ mod tests {
             .unwrap();
 
         // Cycle 2: model is deleted — gauges should be zeroed
-        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
             .await
             .unwrap();
         super::update_cache_info_metrics(&pool, &targets, &mut state).await.unwrap();
@@ -1279,6 +1469,15 @@ mod tests {
                 auth_header_name: Some("Authorization".to_string()),
                 auth_header_prefix: Some("Bearer ".to_string()),
                 reasoning_translation: None,
+                bedrock: None,
+                region: None,
+                body_transform: None,
+                auto_sync_interval_seconds: None,
+                alias_template: None,
+                max_concurrency: None,
+                tls: None,
+                additional_api_keys: Vec::new(),
+                api_key_rotation: None,
             })
             .await
             .unwrap();
@@ -1301,6 +1500,10 @@ mod tests {
                 burst_size: None,
                 capacity: None,
                 batch_capacity: None,
+                per_key_capacity: None,
+                max_cost_per_request: None,
+                input_modalities: None,
+                output_modalities: None,
                 throughput: None,
                 provider_pricing: None,
                 is_composite: false,
@@ -1318,6 +1521,19 @@ mod tests {
                 backoff_max_total_ms: None,
                 sanitize_responses: false,
                 trusted: false,
+                allow_public: false,
+                require_approval: false,
+                rewrite_response_model: false,
+                disable_logging: false,
+                warmup: false,
+                system_prompt: None,
+                system_prompt_mode: SystemPromptMode::default(),
+                max_n: None,
+                max_n_mode: Default::default(),
+                credit_exhaustion_mode: Default::default(),
+                max_overdraft: None,
+                max_prompt_length: None,
+                max_prompt_length_unit: Default::default(),
                 open_responses_adapter: true,
                 reasoning_translation_overrides: None,
 
@@ -1329,7 +1545,7 @@ mod tests {
         tx.commit().await.unwrap();
 
         // Cycle 1: model exists, is_metered=false (no tariff)
-        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
             .await
             .unwrap();
         super::update_cache_info_metrics(&pool, &targets, &mut state).await.unwrap();
@@ -1359,7 +1575,7 @@ mod tests {
         tx.commit().await.unwrap();
 
         // Cycle 2: same model, but is_metered changed
-        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+        let targets = load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
             .await
             .unwrap();
         super::update_cache_info_metrics(&pool, &targets, &mut state).await.unwrap();
@@ -19,6 +19,11 @@ pub mod component {
     pub const LEADER_ELECTION: &str = "leader_election";
     pub const CONFIG_WATCHER: &str = "config_watcher";
     pub const PROBE_SCHEDULER: &str = "probe_scheduler";
+    pub const PROBE_RETENTION: &str = "probe_retention";
+    pub const ENDPOINT_AUTO_SYNC: &str = "endpoint_auto_sync";
+    pub const DEPLOYMENT_WARMUP: &str = "deployment_warmup";
+    pub const BALANCE_CHECKPOINTS: &str = "balance_checkpoints";
+    pub const BATCH_RETENTION: &str = "batch_retention";
     pub const TASK_WORKER: &str = "task_worker";
     pub const ONWARDS_SYNC: &str = "onwards_sync";
     pub const ZDR_KEY_SYNC: &str = "zdr_key_sync";
@@ -26,10 +31,12 @@ pub mod component {
     pub const ONWARDS_HEARTBEAT: &str = "onwards_heartbeat";
     pub const ANALYTICS: &str = "analytics";
     pub const ANALYTICS_BATCHER: &str = "analytics_batcher";
+    pub const ANALYTICS_SINK: &str = "analytics_sink";
     pub const RESPONSES_WRITER: &str = "responses_writer";
     pub const BATCH_POPULATE: &str = "batch_populate";
     pub const PAYMENTS: &str = "payments";
     pub const USAGE_REFRESH: &str = "usage_refresh";
+    pub const BATCH_QUEUE_METRICS: &str = "batch_queue_metrics";
 }
 
 /// Increment `dwctl_background_errors_total`. `component`/`reason`/`severity` are `&'static str`
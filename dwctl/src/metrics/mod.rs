@@ -6,11 +6,13 @@
 //! Additional metrics (credits, analytics lag) are recorded inline using the `metrics`
 //! facade in the request_logging module.
 
+mod batch_queue;
 mod cache_info;
 pub mod errors;
 mod gen_ai;
 mod recorder;
 
+pub use batch_queue::run_batch_queue_metrics_sampler;
 pub use cache_info::{CacheInfoState, update_cache_info_metrics};
 pub use gen_ai::GenAiMetrics;
 pub(crate) use gen_ai::served_by_host;
@@ -9,6 +9,9 @@
 //! **Reactive events** (triggered via PostgreSQL LISTEN/NOTIFY):
 //! - `user.created`: PG trigger on `users` INSERT
 //! - `api_key.created`: PG trigger on `api_keys` INSERT
+//! - `api_key.rotated` / `api_key.deleted`: PG triggers on `api_keys` secret
+//!   changes and soft deletes. API key events also go to the key owner's own
+//!   webhooks that subscribe to them.
 //!
 //! The webhook dispatcher (claim → sign → send → process results) runs on
 //! each tick when `webhooks.enabled` is true. Email notifications are gated
@@ -45,7 +48,7 @@ use crate::payment_providers::{self, PaymentProvider};
 use crate::webhooks::WebhookDispatcher;
 use crate::webhooks::events::{WebhookEvent, WebhookEventType};
 
-/// PostgreSQL NOTIFY channel for webhook events (user.created, api_key.created).
+/// PostgreSQL NOTIFY channel for webhook events (user.created, api_key.*).
 const WEBHOOK_EVENT_CHANNEL: &str = "webhook_event";
 
 /// Outcome of a completed batch for notification purposes.
@@ -163,7 +166,7 @@ pub async fn run_notification_poller(
         None
     };
 
-    // Set up PG listener for platform webhook events (user.created, api_key.created)
+    // Set up PG listener for platform webhook events (user.created, api_key.*)
     let mut listener = if dispatcher.is_some() {
         match PgListener::connect_with(&dwctl_pool).await {
             Ok(mut l) => match l.listen(WEBHOOK_EVENT_CHANNEL).await {
@@ -258,7 +261,7 @@ pub async fn run_notification_poller(
             }
         };
 
-        // === Step 1: Process platform webhook events (user.created, api_key.created) ===
+        // === Step 1: Process platform webhook events (user.created, api_key.*) ===
         if dispatcher.is_some() && !pending_webhook_events.is_empty() {
             let events = std::mem::take(&mut pending_webhook_events);
             let _ = process_platform_events(&mut conn, &events)
@@ -418,7 +421,8 @@ async fn create_batch_deliveries(
 
 /// Parse a webhook event NOTIFY payload.
 ///
-/// Expected format: `"table_name:record_uuid"`
+/// Expected format: `"table_name:record_uuid"`, or `"table_name.action:record_uuid"`
+/// for events other than inserts (e.g. `"api_keys.deleted:<uuid>"`)
 fn parse_webhook_event_payload(payload: &str) -> Option<(String, Uuid)> {
     let (table, id_str) = payload.split_once(':')?;
     let id = Uuid::parse_str(id_str).ok()?;
@@ -429,19 +433,16 @@ fn parse_webhook_event_payload(payload: &str) -> Option<(String, Uuid)> {
 ///
 /// For each (table, record_id) pair, queries the source table for record details,
 /// builds the webhook event payload, and creates delivery records for all eligible
-/// platform webhooks.
+/// platform webhooks. API key events are also delivered to the key owner's own
+/// webhooks.
 async fn process_platform_events(conn: &mut sqlx::pool::PoolConnection<sqlx::Postgres>, events: &[(String, Uuid)]) -> anyhow::Result<()> {
     let pm_webhooks = {
         let mut repo = Webhooks::new(&mut *conn);
         repo.get_enabled_platform_webhooks().await?
     };
 
-    if pm_webhooks.is_empty() {
-        return Ok(());
-    }
-
     for (table, id) in events {
-        let (event, event_type) = match table.as_str() {
+        let (event, event_type, owner_id) = match table.as_str() {
             "users" => {
                 let row = sqlx::query!(r#"SELECT id, email, auth_source FROM users WHERE id = $1"#, id,)
                     .fetch_optional(&mut **conn)
@@ -455,10 +456,16 @@ async fn process_platform_events(conn: &mut sqlx::pool::PoolConnection<sqlx::Pos
                 (
                     WebhookEvent::user_created(row.id, &row.email, &row.auth_source),
                     WebhookEventType::UserCreated,
+                    None,
                 )
             }
-            "api_keys" => {
-                let row = sqlx::query!(r#"SELECT id, user_id, created_by, name FROM api_keys WHERE id = $1"#, id,)
+            "api_keys" | "api_keys.rotated" | "api_keys.deleted" => {
+                let event_type = match table.as_str() {
+                    "api_keys.rotated" => WebhookEventType::ApiKeyRotated,
+                    "api_keys.deleted" => WebhookEventType::ApiKeyDeleted,
+                    _ => WebhookEventType::ApiKeyCreated,
+                };
+                let row = sqlx::query!(r#"SELECT id, user_id, created_by, name, purpose FROM api_keys WHERE id = $1"#, id,)
                     .fetch_optional(&mut **conn)
                     .await?;
 
@@ -468,8 +475,9 @@ async fn process_platform_events(conn: &mut sqlx::pool::PoolConnection<sqlx::Pos
                 };
 
                 (
-                    WebhookEvent::api_key_created(row.id, row.user_id, row.created_by, &row.name),
-                    WebhookEventType::ApiKeyCreated,
+                    WebhookEvent::api_key(event_type, row.id, row.user_id, row.created_by, &row.name, &row.purpose),
+                    event_type,
+                    Some(row.user_id),
                 )
             }
             _ => {
@@ -481,13 +489,32 @@ async fn process_platform_events(conn: &mut sqlx::pool::PoolConnection<sqlx::Pos
         let payload = serde_json::to_value(&event)?;
 
         let mut repo = Webhooks::new(&mut *conn);
-        for webhook in pm_webhooks.iter().filter(|w| w.accepts_event(event_type)) {
+        let owner_webhooks = match owner_id {
+            Some(owner_id) => repo
+                .get_enabled_webhooks_for_users(vec![owner_id])
+                .await?
+                .remove(&owner_id)
+                .unwrap_or_default(),
+            None => Vec::new(),
+        };
+        // A platform manager's own platform webhooks appear in both lists
+        let mut seen = HashSet::new();
+        let recipients: Vec<_> = pm_webhooks
+            .iter()
+            .chain(owner_webhooks.iter())
+            .filter(|w| w.accepts_event(event_type) && seen.insert(w.id))
+            .collect();
+
+        // A key can be rotated more than once, so rotations aren't deduplicated per key
+        let resource_id = (event_type != WebhookEventType::ApiKeyRotated).then_some(*id);
+
+        for webhook in &recipients {
             let delivery_request = WebhookDeliveryCreateDBRequest {
                 webhook_id: webhook.id,
                 event_id: Uuid::new_v4(),
                 event_type: event_type.to_string(),
                 payload: payload.clone(),
-                resource_id: Some(*id),
+                resource_id,
                 next_attempt_at: None,
             };
 
@@ -506,7 +533,7 @@ async fn process_platform_events(conn: &mut sqlx::pool::PoolConnection<sqlx::Pos
             table = %table,
             resource_id = %id,
             event_type = %event_type,
-            webhooks = pm_webhooks.iter().filter(|w| w.accepts_event(event_type)).count(),
+            webhooks = recipients.len(),
             "Platform webhook event processed"
         );
     }
@@ -1007,10 +1034,94 @@ async fn send_low_balance_notifications(
 mod tests {
     use super::*;
     use crate::api::models::users::Role;
-    use crate::config::{CreditsConfig, DummyConfig};
+    use crate::config::{CreditsConfig, DummyConfig, WebhookConfig};
+    use crate::db::models::webhooks::WebhookCreateDBRequest;
     use crate::payment_providers;
+    use crate::webhooks::signing;
     use rust_decimal::Decimal;
     use sqlx::PgPool;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[sqlx::test]
+    async fn test_api_key_created_webhook_delivered_to_key_owner(pool: PgPool) {
+        let receiver = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&receiver)
+            .await;
+
+        let user = crate::test::utils::create_test_user(&pool, Role::StandardUser).await;
+        let mut conn = pool.acquire().await.unwrap();
+        // Subscribed to creations only, so the rotation below isn't delivered
+        let webhook = Webhooks::new(&mut conn)
+            .create(&WebhookCreateDBRequest {
+                user_id: user.id,
+                url: receiver.uri(),
+                secret: signing::generate_secret(),
+                event_types: Some(vec!["api_key.created".to_string()]),
+                description: None,
+                scope: "own".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let mut listener = PgListener::connect_with(&pool).await.unwrap();
+        listener.listen(WEBHOOK_EVENT_CHANNEL).await.unwrap();
+        let key = crate::test::utils::create_test_api_key_for_user(&pool, user.id).await;
+        sqlx::query!("UPDATE api_keys SET secret = 'rotated-secret' WHERE id = $1", key.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let mut events = Vec::new();
+        while events.len() < 2 {
+            let notification = tokio::time::timeout(Duration::from_secs(5), listener.recv())
+                .await
+                .expect("timed out waiting for webhook event notification")
+                .unwrap();
+            events.extend(parse_webhook_event_payload(notification.payload()).filter(|(_, id)| *id == key.id));
+        }
+        assert_eq!(
+            events,
+            vec![("api_keys".to_string(), key.id), ("api_keys.rotated".to_string(), key.id)]
+        );
+        process_platform_events(&mut conn, &events).await.unwrap();
+
+        let shutdown = CancellationToken::new();
+        let mut dispatcher = WebhookDispatcher::spawn(pool.clone(), &WebhookConfig::default(), shutdown.clone());
+        dispatcher.tick().await;
+        let requests = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let requests = receiver.received_requests().await.unwrap();
+                if !requests.is_empty() {
+                    return requests;
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .expect("timed out waiting for webhook delivery");
+        shutdown.cancel();
+
+        let request = &requests[0];
+        let body = String::from_utf8(request.body.clone()).unwrap();
+        let header = |name: &str| request.headers.get(name).unwrap().to_str().unwrap().to_string();
+        let timestamp: i64 = header("webhook-timestamp").parse().unwrap();
+        assert_eq!(
+            Some(header("webhook-signature")),
+            signing::sign_payload(&header("webhook-id"), timestamp, &body, &webhook.secret)
+        );
+
+        let event: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(event["type"], "api_key.created");
+        assert_eq!(event["data"]["api_key_id"], key.id.to_string());
+        assert_eq!(event["data"]["user_id"], user.id.to_string());
+        assert_eq!(event["data"]["purpose"], "realtime");
+        assert!(event["data"].get("secret").is_none());
+        assert!(!body.contains(&key.secret) && !body.contains("rotated-secret"));
+    }
 
     #[sqlx::test]
     async fn test_process_auto_topups_charges_below_threshold(pool: PgPool) {
@@ -60,26 +60,45 @@ impl Modify for AdminSecurityAddon {
         api::handlers::users::get_user,
         api::handlers::users::update_user,
         api::handlers::users::delete_user,
+        api::handlers::effective_permissions::get_effective_permissions,
         api::handlers::api_keys::list_user_api_keys,
         api::handlers::api_keys::create_user_api_key,
         api::handlers::api_keys::get_user_api_key,
         api::handlers::api_keys::update_user_api_key,
         api::handlers::api_keys::delete_user_api_key,
+        api::handlers::api_keys::get_user_api_key_usage,
+        api::handlers::api_keys::update_system_api_key,
+        api::handlers::api_keys::get_user_api_key_purposes,
+        api::handlers::api_keys::set_user_api_key_purposes,
+        api::handlers::api_keys::delete_user_api_key_purposes,
         api::handlers::inference_endpoints::list_inference_endpoints,
         api::handlers::inference_endpoints::get_inference_endpoint,
         api::handlers::inference_endpoints::create_inference_endpoint,
         api::handlers::inference_endpoints::update_inference_endpoint,
         api::handlers::inference_endpoints::delete_inference_endpoint,
         api::handlers::inference_endpoints::validate_inference_endpoint,
+        api::handlers::inference_endpoints::test_endpoint_credentials,
         api::handlers::inference_endpoints::synchronize_endpoint,
+        api::handlers::inference_endpoints::get_endpoint_statistics,
         api::handlers::deployments::list_deployed_models,
         api::handlers::deployments::create_deployed_model,
         api::handlers::deployments::get_deployed_model,
         api::handlers::deployments::update_deployed_model,
         api::handlers::deployments::delete_deployed_model,
+        api::handlers::deployments::activate_deployed_model,
+        api::handlers::deployments::deactivate_deployed_model,
+        api::handlers::deployments::clone_deployed_model,
         api::handlers::cache_pricing::get_cache_pricing,
         api::handlers::cache_pricing::enable_cache_pricing,
         api::handlers::cache_pricing::disable_cache_pricing,
+        api::handlers::deployment_shadows::get_deployment_shadow,
+        api::handlers::deployment_shadows::set_deployment_shadow,
+        api::handlers::deployment_shadows::delete_deployment_shadow,
+        api::handlers::access_requests::create_access_request,
+        api::handlers::access_requests::list_access_requests,
+        api::handlers::access_requests::review_access_request,
+        api::handlers::deployments::get_resolved_config,
+        api::handlers::deployments::estimate_request_cost,
         api::handlers::deployments::get_model_components,
         api::handlers::deployments::add_model_component,
         api::handlers::deployments::update_model_component,
@@ -96,19 +115,42 @@ impl Modify for AdminSecurityAddon {
         api::handlers::groups::update_group,
         api::handlers::groups::delete_group,
         api::handlers::groups::add_user_to_group,
+        api::handlers::groups::add_users_to_group,
         api::handlers::groups::remove_user_from_group,
         api::handlers::groups::add_group_to_user,
         api::handlers::groups::remove_group_from_user,
         api::handlers::groups::get_group_users,
         api::handlers::groups::get_user_groups,
         api::handlers::groups::add_deployment_to_group,
+        api::handlers::groups::add_deployments_to_group,
         api::handlers::groups::remove_deployment_from_group,
         api::handlers::groups::get_group_deployments,
         api::handlers::groups::get_deployment_groups,
+        api::handlers::rate_limits::get_user_rate_limits,
+        api::handlers::rate_limits::set_user_rate_limits,
+        api::handlers::rate_limits::delete_user_rate_limits,
+        api::handlers::rate_limits::get_group_rate_limits,
+        api::handlers::rate_limits::set_group_rate_limits,
+        api::handlers::rate_limits::delete_group_rate_limits,
+        api::handlers::sessions::list_user_sessions,
+        api::handlers::sessions::revoke_user_sessions,
+        api::handlers::sessions::revoke_user_session,
+        api::handlers::group_spending_limits::get_group_usage,
+        api::handlers::group_spending_limits::set_group_spending_limit,
+        api::handlers::group_spending_limits::delete_group_spending_limit,
+        api::handlers::access_templates::list_access_templates,
+        api::handlers::access_templates::create_access_template,
+        api::handlers::access_templates::get_access_template,
+        api::handlers::access_templates::update_access_template,
+        api::handlers::access_templates::delete_access_template,
+        api::handlers::access_templates::apply_access_template,
         api::handlers::transactions::create_transaction,
         api::handlers::transactions::get_transaction,
         api::handlers::transactions::list_transactions,
+        api::handlers::transactions::export_transactions,
         api::handlers::config::get_config,
+        api::handlers::config_snapshot::export_config,
+        api::handlers::config_snapshot::import_config,
         api::handlers::probes::create_probe,
         api::handlers::probes::list_probes,
         api::handlers::probes::get_probe,
@@ -124,6 +166,9 @@ impl Modify for AdminSecurityAddon {
         api::handlers::requests::aggregate_requests,
         api::handlers::requests::aggregate_by_user,
         api::handlers::queue::get_pending_request_counts,
+        api::handlers::maintenance::get_maintenance_mode,
+        api::handlers::maintenance::set_maintenance_mode,
+        api::handlers::events::stream_config_events,
     ),
     components(
         schemas(
@@ -136,28 +181,62 @@ impl Modify for AdminSecurityAddon {
             api::models::users::UserUpdate,
             api::models::users::UserResponse,
             api::models::users::CurrentUser,
+            api::models::effective_permissions::AccessibleModel,
+            api::models::effective_permissions::EffectivePermissionsResponse,
+            api::models::effective_permissions::EffectiveRateLimits,
+            api::models::effective_permissions::RateLimitSource,
             api::models::users::ListUsersQuery,
             api::models::api_keys::ApiKeyCreate,
             api::models::api_keys::ApiKeyUpdate,
             api::models::api_keys::ListApiKeysQuery,
             api::models::api_keys::ApiKeyResponse,
             api::models::api_keys::ApiKeyInfoResponse,
+            api::models::api_keys::ApiKeyUsageResponse,
+            api::models::api_keys::SystemApiKeyUpdate,
+            api::models::api_keys::SystemApiKeyResponse,
+            api::models::api_keys::ApiKeyPurposesUpdate,
+            api::models::api_keys::ApiKeyPurposesResponse,
             api::models::deployments::DeployedModelCreate,
+            api::models::deployments::StandardModelCreate,
             api::models::deployments::DeployedModelUpdate,
             api::models::deployments::DeployedModelUpdateRequest,
             api::models::deployments::DeployedModelResponse,
+            api::models::deployments::DeployedModelClone,
             api::models::cache_pricing::CachePricingUpdateRequest,
             api::models::cache_pricing::CachePricingResponse,
+            api::models::deployment_shadows::DeploymentShadowUpdate,
+            api::models::deployment_shadows::DeploymentShadowResponse,
+            api::models::access_requests::AccessRequestCreate,
+            api::models::access_requests::AccessRequestReview,
+            api::models::access_requests::AccessRequestResponse,
+            api::models::access_requests::AccessRequestsQuery,
+            crate::db::models::access_requests::AccessRequestStatus,
+            api::models::rate_limits::RateLimitsUpdate,
+            api::models::rate_limits::RateLimitsResponse,
+            api::models::sessions::SessionResponse,
+            api::models::sessions::RevokeSessionsResponse,
+            api::models::group_spending_limits::GroupSpendingLimitUpdate,
+            api::models::group_spending_limits::GroupUsageResponse,
             api::models::deployments::ModelComponentCreate,
             api::models::deployments::ModelComponentUpdate,
             api::models::deployments::ModelComponentResponse,
+            api::models::deployments::ResolvedModelConfig,
+            api::models::deployments::RequestCostEstimate,
             crate::db::models::deployments::LoadBalancingStrategy,
+            crate::db::models::deployments::Modality,
             crate::db::models::deployments::FallbackConfig,
             crate::db::models::deployments::DeploymentComponent,
             api::models::groups::GroupCreate,
             api::models::groups::GroupUpdate,
             api::models::groups::GroupResponse,
+            api::models::groups::BatchMembershipRequest,
+            api::models::groups::BatchMembershipResponse,
+            api::models::groups::BatchMembershipResult,
+            api::models::groups::BatchMembershipStatus,
             api::models::groups::ListGroupsQuery,
+            api::models::access_templates::AccessTemplateCreate,
+            api::models::access_templates::AccessTemplateUpdate,
+            api::models::access_templates::AccessTemplateResponse,
             api::models::deployments::ListModelsQuery,
             api::models::deployments::ModelSortField,
             api::models::deployments::SortDirection,
@@ -170,12 +249,28 @@ impl Modify for AdminSecurityAddon {
             api::models::inference_endpoints::InferenceEndpointUpdate,
             api::models::inference_endpoints::InferenceEndpointValidate,
             api::models::inference_endpoints::InferenceEndpointValidateResponse,
+            api::models::inference_endpoints::InferenceEndpointCredentialTest,
+            api::models::inference_endpoints::InferenceEndpointCredentialTestResponse,
+            api::models::inference_endpoints::CredentialTestKind,
             api::models::inference_endpoints::InferenceEndpointResponse,
+            api::models::inference_endpoints::BedrockCredentials,
+            api::models::inference_endpoints::BedrockEndpointInfo,
+            api::models::inference_endpoints::EndpointTlsSettings,
+            api::models::inference_endpoints::EndpointTlsInfo,
+            crate::db::models::inference_endpoints::EndpointProtocol,
+            crate::db::models::inference_endpoints::ApiKeyRotation,
+            crate::body_transform::BodyTransformConfig,
+            crate::body_transform::BodyTransformOp,
+            crate::body_transform::ToolCallingStyle,
             api::models::inference_endpoints::ListEndpointsQuery,
+            api::models::inference_endpoints::EndpointStatisticsQuery,
+            api::models::inference_endpoints::EndpointStatistics,
+            api::models::inference_endpoints::StatisticsWindow,
             api::models::inference_endpoints::OpenAIModel,
             api::models::inference_endpoints::OpenAIModelsResponse,
             api::models::transactions::CreditTransactionCreate,
             api::models::transactions::CreditTransactionResponse,
+            api::models::transactions::TransactionListResponse,
             crate::db::models::credits::CreditTransactionType,
             sync::endpoint_sync::EndpointSyncResponse,
             api::models::probes::CreateProbe,
@@ -187,6 +282,9 @@ impl Modify for AdminSecurityAddon {
             api::models::probes::ProbeStatistics,
             crate::db::models::probes::Probe,
             crate::db::models::probes::ProbeResult,
+            crate::db::models::probes::ProbeAssertions,
+            crate::db::models::probes::JsonPathAssertion,
+            crate::db::models::probes::ProbeType,
             api::models::requests::ApiAiRequest,
             api::models::requests::ApiAiResponse,
             api::models::requests::AggregateRequestsQuery,
@@ -203,6 +301,19 @@ impl Modify for AdminSecurityAddon {
             api::models::requests::RequestsAggregateResponse,
             api::handlers::config::ConfigResponse,
             api::handlers::config::BatchConfigResponse,
+            api::models::config_snapshot::PlatformSnapshot,
+            api::models::config_snapshot::EndpointSnapshot,
+            api::models::config_snapshot::DeploymentSnapshot,
+            api::models::config_snapshot::ComponentSnapshot,
+            api::models::config_snapshot::GroupSnapshot,
+            api::models::config_snapshot::ConfigImportResponse,
+            api::models::config_snapshot::ImportChangeCounts,
+            api::models::maintenance::MaintenanceModeUpdate,
+            api::models::maintenance::MaintenanceModeResponse,
+            api::models::events::ConfigSnapshot,
+            api::models::events::DeploymentSummary,
+            api::models::events::EndpointSummary,
+            api::models::events::EndpointHealthChange,
         )
     ),
     tags(
@@ -218,6 +329,8 @@ impl Modify for AdminSecurityAddon {
         (name = "probes", description = "Probe monitoring API"),
         (name = "requests", description = "Request logging and analytics API"),
         (name = "monitoring", description = "Queue and system monitoring API"),
+        (name = "maintenance", description = "Maintenance mode for the AI API"),
+        (name = "events", description = "Configuration change event stream"),
     ),
     info(
         title = "Admin API",
@@ -248,4 +361,55 @@ mod tests {
         let json = serde_json::to_string(schema).unwrap();
         assert!(!json.contains("api_key_id"), "api_key_id leaked into CurrentUser schema: {json}");
     }
+
+    /// The example attached to a component schema, as emitted in the spec
+    fn schema_example(spec: &utoipa::openapi::OpenApi, name: &str) -> serde_json::Value {
+        let schema = spec
+            .components
+            .as_ref()
+            .expect("spec has components")
+            .schemas
+            .get(name)
+            .unwrap_or_else(|| panic!("{name} schema present"));
+        let json = serde_json::to_value(schema).unwrap();
+        json.get("example")
+            .or_else(|| json.get("examples").and_then(|examples| examples.get(0)))
+            .cloned()
+            .unwrap_or_else(|| panic!("{name} schema has no example: {json}"))
+    }
+
+    /// Key request/response schemas carry examples, and each example deserializes into
+    /// the handler type it documents, so the examples can't drift from the real API.
+    #[test]
+    fn key_schemas_have_examples_matching_their_types() {
+        fn check<T: serde::de::DeserializeOwned>(spec: &utoipa::openapi::OpenApi, name: &str) {
+            let example = schema_example(spec, name);
+            if let Err(e) = serde_json::from_value::<T>(example.clone()) {
+                panic!("{name} example does not deserialize: {e}\n{example}");
+            }
+        }
+
+        let admin = AdminApiDoc::openapi();
+        check::<api::models::users::UserCreate>(&admin, "UserCreate");
+        check::<api::models::users::UserUpdate>(&admin, "UserUpdate");
+        check::<api::models::users::UserResponse>(&admin, "UserResponse");
+        check::<api::models::deployments::DeployedModelCreate>(&admin, "StandardModelCreate");
+        check::<api::models::deployments::DeployedModelUpdate>(&admin, "DeployedModelUpdate");
+        check::<api::models::deployments::DeployedModelResponse>(&admin, "DeployedModelResponse");
+        check::<api::models::inference_endpoints::InferenceEndpointCreate>(&admin, "InferenceEndpointCreate");
+        check::<api::models::inference_endpoints::InferenceEndpointUpdate>(&admin, "InferenceEndpointUpdate");
+        check::<api::models::inference_endpoints::InferenceEndpointResponse>(&admin, "InferenceEndpointResponse");
+        check::<api::models::transactions::CreditTransactionCreate>(&admin, "CreditTransactionCreate");
+        check::<api::models::transactions::CreditTransactionResponse>(&admin, "CreditTransactionResponse");
+        check::<api::models::transactions::TransactionListResponse>(&admin, "TransactionListResponse");
+
+        let ai = crate::openapi::ai::AiApiDoc::openapi();
+        check::<api::models::batches::CreateBatchRequest>(&ai, "CreateBatchRequest");
+        check::<api::models::batches::BatchResponse>(&ai, "BatchResponse");
+
+        // Examples survive serialization of the whole document served at /admin/docs
+        let spec_json = admin.to_json().unwrap();
+        assert!(spec_json.contains("john.smith@example.com"));
+        assert!(spec_json.contains("meta-llama/Llama-3.1-8B-Instruct"));
+    }
 }
@@ -61,7 +61,7 @@ Set `stream: true` to receive partial responses as server-sent events.",
         (status = 401, description = "Invalid or missing API key. Ensure your `Authorization` header is set to `Bearer YOUR_API_KEY`.", body = extra_types::OpenAIErrorResponse),
         (status = 402, description = "Insufficient credits. Top up your account to continue making requests.", body = extra_types::OpenAIErrorResponse),
         (status = 403, description = "Your API key does not have access to the requested model.", body = extra_types::OpenAIErrorResponse),
-        (status = 404, description = "The specified model does not exist. Use `GET /models` to list available models.", body = extra_types::OpenAIErrorResponse),
+        (status = 404, description = "The specified model does not exist or your API key does not have access to it. Use `GET /models` to list available models.", body = extra_types::OpenAIErrorResponse),
         (status = 429, description = "Rate limit exceeded. Back off and retry after a short delay.", body = extra_types::OpenAIErrorResponse),
         (status = 500, description = "An unexpected error occurred. Retry the request or contact support if the issue persists.", body = extra_types::OpenAIErrorResponse),
     ),
@@ -86,7 +86,7 @@ Input can be a single string or an array of strings. Each input produces one emb
         (status = 401, description = "Invalid or missing API key. Ensure your `Authorization` header is set to `Bearer YOUR_API_KEY`.", body = extra_types::OpenAIErrorResponse),
         (status = 402, description = "Insufficient credits. Top up your account to continue making requests.", body = extra_types::OpenAIErrorResponse),
         (status = 403, description = "Your API key does not have access to the requested model.", body = extra_types::OpenAIErrorResponse),
-        (status = 404, description = "The specified model does not exist. Use `GET /models` to list available models.", body = extra_types::OpenAIErrorResponse),
+        (status = 404, description = "The specified model does not exist or your API key does not have access to it. Use `GET /models` to list available models.", body = extra_types::OpenAIErrorResponse),
         (status = 429, description = "Rate limit exceeded. Back off and retry after a short delay.", body = extra_types::OpenAIErrorResponse),
         (status = 500, description = "An unexpected error occurred. Retry the request or contact support if the issue persists.", body = extra_types::OpenAIErrorResponse),
     ),
@@ -105,11 +105,12 @@ fn embeddings() {}
 
 The response includes model IDs that can be used in chat completion and embedding requests.
 
-By default, this endpoint follows the OpenAI-compatible models-list shape. Doubleword also supports optional query-string extensions for model discovery: `group` filters to comma-separated group UUIDs, `available_for_realtime` filters by whether realtime traffic is allowed for the model, and `include_reasoning_capabilities=true` adds the reasoning efforts supported by every provider behind each model.",
+By default, this endpoint follows the OpenAI-compatible models-list shape. Doubleword also supports optional query-string extensions for model discovery: `group` filters to comma-separated group UUIDs, `available_for_realtime` filters by whether realtime traffic is allowed for the model, `include_reasoning_capabilities=true` adds the reasoning efforts supported by every provider behind each model, and `include_pricing=true` adds the per-token prices your API key pays for each model.",
     params(
         ("group" = Option<String>, Query, description = "Doubleword extension. Filter to models in one or more groups (comma-separated UUIDs). This is intersected with the API key's normal model access."),
         ("available_for_realtime" = Option<bool>, Query, description = "Doubleword extension. true returns models without a realtime deny rule; false returns models with one."),
         ("include_reasoning_capabilities" = Option<bool>, Query, description = "Doubleword extension, disabled by default. true adds supported_reasoning_efforts when reasoning support is known for every provider behind a model."),
+        ("include_pricing" = Option<bool>, Query, description = "Doubleword extension, disabled by default. true adds pricing with the per-token prices your API key pays, for models that have a tariff."),
     ),
     responses(
         (status = 200, description = "List of models your API key can access.", body = extra_types::ModelsListResponse),
@@ -166,7 +167,7 @@ Set `stream: true` to receive partial responses as server-sent events.
         (status = 401, description = "Invalid or missing API key. Ensure your `Authorization` header is set to `Bearer YOUR_API_KEY`.", body = extra_types::OpenAIErrorResponse),
         (status = 402, description = "Insufficient credits. Top up your account to continue making requests.", body = extra_types::OpenAIErrorResponse),
         (status = 403, description = "Your API key does not have access to the requested model.", body = extra_types::OpenAIErrorResponse),
-        (status = 404, description = "The specified model does not exist. Use `GET /models` to list available models.", body = extra_types::OpenAIErrorResponse),
+        (status = 404, description = "The specified model does not exist or your API key does not have access to it. Use `GET /models` to list available models.", body = extra_types::OpenAIErrorResponse),
         (status = 429, description = "Rate limit exceeded. Back off and retry after a short delay.", body = extra_types::OpenAIErrorResponse),
         (status = 500, description = "An unexpected error occurred. Retry the request or contact support if the issue persists.", body = extra_types::OpenAIErrorResponse),
     ),
@@ -230,6 +231,7 @@ fn create_response() {}
             extra_types::EmbeddingUsage,
             extra_types::ModelsListResponse,
             extra_types::ModelObject,
+            extra_types::ModelPricing,
             extra_types::OpenAIErrorResponse,
             extra_types::OpenAIError,
             // Responses API types
@@ -242,6 +244,10 @@ fn create_response() {}
             // File/Batch types
             api::models::files::ListFilesQuery,
             api::models::files::FileResponse,
+            api::models::files::UploadFileQuery,
+            api::models::files::FileValidationMode,
+            api::models::files::FileValidationReport,
+            api::models::files::LineValidationError,
             api::models::files::FileDeleteResponse,
             api::models::files::FileListResponse,
             api::models::files::FileCostEstimate,
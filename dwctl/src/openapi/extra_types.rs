@@ -464,6 +464,27 @@ pub struct ModelObject {
     /// every provider behind this model. Returned only when requested.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub supported_reasoning_efforts: Option<crate::reasoning::SupportedReasoningEfforts>,
+
+    /// Doubleword extension containing the per-token prices your API key pays
+    /// for this model. Returned only when requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pricing: Option<ModelPricing>,
+}
+
+/// Per-token prices for a model, as charged to the requesting API key.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "input_price_per_token": "0.0000001",
+    "output_price_per_token": "0.0000004"
+}))]
+pub struct ModelPricing {
+    /// Price per input token, as a decimal string.
+    #[schema(value_type = String, example = "0.0000001")]
+    pub input_price_per_token: rust_decimal::Decimal,
+
+    /// Price per output token, as a decimal string.
+    #[schema(value_type = String, example = "0.0000004")]
+    pub output_price_per_token: rust_decimal::Decimal,
 }
 
 // ============================================================================
@@ -4,7 +4,7 @@
 //! Useful for testing and development purposes.
 
 use async_trait::async_trait;
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 
 use crate::{
     config::CreditsConfig,
@@ -179,7 +179,7 @@ impl PaymentProvider for DummyProvider {
         Ok(None)
     }
 
-    async fn process_webhook_event(&self, _db_pool: &PgPool, _event: &WebhookEvent, _credits_config: &CreditsConfig) -> Result<()> {
+    async fn process_webhook_event(&self, _conn: &mut PgConnection, _event: &WebhookEvent, _credits_config: &CreditsConfig) -> Result<()> {
         // Dummy provider doesn't use webhooks
         Ok(())
     }
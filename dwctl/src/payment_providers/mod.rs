@@ -6,7 +6,7 @@
 use async_trait::async_trait;
 use axum::http::StatusCode;
 use rust_decimal::Decimal;
-use sqlx::PgPool;
+use sqlx::{Acquire, PgConnection, PgPool};
 
 use crate::{
     UserId,
@@ -113,12 +113,60 @@ pub struct PaymentSession {
 /// Represents a webhook event from a payment provider
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct WebhookEvent {
+    /// Provider's unique id for this event, used to deduplicate redelivered webhooks
+    pub event_id: Option<String>,
     /// Type of event (e.g., "checkout.session.completed")
     pub event_type: String,
     /// Session ID associated with this event, if applicable
     pub session_id: Option<String>,
 }
 
+/// Process a webhook event at most once per provider event id.
+///
+/// The event id is claimed in the transaction the provider processes the event
+/// in, so of two concurrent deliveries one waits for the other's claim and then
+/// skips. A redelivery of a handled event returns `Ok(())` without processing.
+/// A database error rolls the claim back with everything else, so the retry the
+/// caller asks for is processed; any other error keeps the claim, as retrying
+/// wouldn't help, and only the provider's writes are undone.
+pub async fn process_webhook_event_once(
+    db_pool: &PgPool,
+    provider: &dyn PaymentProvider,
+    event: &WebhookEvent,
+    credits_config: &CreditsConfig,
+) -> Result<()> {
+    let mut tx = db_pool.begin().await?;
+    if let Some(event_id) = &event.event_id {
+        let claimed = sqlx::query_scalar!(
+            r#"
+            INSERT INTO processed_payment_webhook_events (event_id, event_type)
+            VALUES ($1, $2)
+            ON CONFLICT (event_id) DO NOTHING
+            RETURNING event_id
+            "#,
+            event_id,
+            event.event_type
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        if claimed.is_none() {
+            tracing::debug!("Webhook event {} already processed, skipping", event_id);
+            return Ok(());
+        }
+    }
+
+    // A savepoint, so a failed write doesn't abort the transaction holding the claim
+    let mut processing = tx.begin().await?;
+    let result = provider.process_webhook_event(&mut *processing, event, credits_config).await;
+    match &result {
+        Ok(()) => processing.commit().await?,
+        Err(PaymentError::Database(_)) => return result,
+        Err(_) => processing.rollback().await?,
+    }
+    tx.commit().await?;
+    result
+}
+
 /// The entity paying for a checkout session (individual user or org).
 pub struct CheckoutPayer {
     pub id: UserId,
@@ -173,12 +221,13 @@ pub trait PaymentProvider: Send + Sync {
 
     /// Process a validated webhook event
     ///
-    /// This is called after validate_webhook succeeds.
+    /// This is called after validate_webhook succeeds, on a transaction that has
+    /// claimed the event id (see [`process_webhook_event_once`]).
     /// Should be idempotent - processing the same event multiple times should be safe.
     ///
     /// `credits_config` is forwarded to payment processing (see
     /// `process_payment_session`).
-    async fn process_webhook_event(&self, db_pool: &PgPool, event: &WebhookEvent, credits_config: &CreditsConfig) -> Result<()>;
+    async fn process_webhook_event(&self, conn: &mut PgConnection, event: &WebhookEvent, credits_config: &CreditsConfig) -> Result<()>;
 
     /// Create a billing portal session for customer self-service
     ///
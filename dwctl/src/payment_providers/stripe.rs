@@ -2,7 +2,7 @@
 
 use async_trait::async_trait;
 use rust_decimal::Decimal;
-use sqlx::PgPool;
+use sqlx::{Acquire, PgConnection, PgPool};
 use std::collections::HashMap;
 use stripe::Client;
 use stripe_billing::billing_portal_session::CreateBillingPortalSession;
@@ -128,6 +128,137 @@ impl StripeProvider {
                 PaymentError::ProviderApi(e.to_string())
             })
     }
+
+    /// Credit a completed checkout session, on `conn` so a webhook can run it in
+    /// the transaction that claims its event id.
+    async fn fulfil_payment_session(
+        &self,
+        conn: &mut PgConnection,
+        session_id: &str,
+        credits_config: &crate::config::CreditsConfig,
+    ) -> Result<()> {
+        // Fast path: Check if we've already processed this payment
+        // This avoids expensive Stripe API calls for duplicate webhook deliveries,
+        // user retries, etc. The unique constraint below handles race conditions.
+        {
+            let mut credits = Credits::new(&mut *conn);
+            if credits.transaction_exists_by_source_id(session_id).await? {
+                tracing::trace!("Transaction for session_id {} already exists, skipping (fast path)", session_id);
+                return Ok(());
+            }
+        }
+
+        // Get payment session details
+        let payment_session = self.get_payment_session(session_id).await?;
+
+        // Verify payment status
+        if !payment_session.is_paid {
+            tracing::trace!("Transaction for session_id {} has not been paid, skipping.", session_id);
+            return Err(PaymentError::PaymentNotCompleted);
+        }
+
+        // Look up creditor user and build description + set creditor stripe ID in db.
+        // This is one block to scope user repo lifetime properly
+        let description = {
+            let mut users = crate::db::handlers::users::Users::new(&mut *conn);
+
+            // Verify creditor user exists before proceeding
+            let creditor_user = users.get_by_id(payment_session.creditor_id).await?;
+            if creditor_user.is_none() {
+                tracing::error!(
+                    "Creditor user {} not found for payment session {}. This indicates a data integrity issue.",
+                    payment_session.creditor_id,
+                    session_id
+                );
+            }
+
+            // Build description with payer information
+            let description = if payment_session.creditor_id == payment_session.creditee_id {
+                // Self-payment
+                "Stripe payment".to_string()
+            } else if let Some(creditor) = creditor_user.as_ref() {
+                let creditor_name = creditor.display_name.as_ref().unwrap_or(&creditor.email);
+                format!("Stripe payment from {}", creditor_name)
+            } else {
+                "Stripe payment".to_string()
+            };
+
+            // Save the customer ID if we don't have one yet, so we can offer the billing portal
+            if let Some(ref provider_id) = payment_session.payment_provider_id
+                && users
+                    .set_payment_provider_id_if_empty(payment_session.creditor_id, provider_id)
+                    .await?
+            {
+                tracing::debug!(
+                    "Saved newly created stripe ID {} for user ID {}",
+                    provider_id,
+                    payment_session.creditor_id
+                );
+            }
+
+            description
+        };
+
+        // Create the credit transaction
+        let request = CreditTransactionCreateDBRequest {
+            user_id: payment_session.creditee_id,
+            transaction_type: CreditTransactionType::Purchase,
+            amount: payment_session.amount,
+            source_id: session_id.to_string(),
+            description: Some(description),
+            fusillade_batch_id: None,
+            api_key_id: None,
+        };
+
+        // Record the purchase first. This is the critical write (real money moved)
+        // and is never made contingent on the secondary effects below.
+        Credits::new(&mut *conn).create_transaction(&request).await?;
+
+        // First-payment match (no-op unless enabled and this is the payee's first
+        // ever payment). The bonus lands on the creditee (whose balance was just
+        // topped up), deliberately the credited account rather than the payer we
+        // verify below, since the promo rewards whoever receives the credits.
+        // Best-effort: a freebie must never undo the recorded purchase, so we log
+        // and continue on failure rather than failing payment processing. Note
+        // such a failure is not retried (the webhook retry's fast-path sees the
+        // purchase already recorded), so the error log is the signal to grant it
+        // manually. It runs in a savepoint, so a failure can't abort a transaction
+        // the purchase is part of.
+        let mut bonus = conn.begin().await?;
+        match Credits::new(&mut *bonus)
+            .grant_first_payment_match(
+                credits_config.first_payment_match_up_to,
+                payment_session.creditee_id,
+                payment_session.amount,
+                session_id,
+            )
+            .await
+        {
+            Ok(()) => bonus.commit().await?,
+            Err(e) => {
+                bonus.rollback().await?;
+                tracing::error!(session_id, creditee_id = %payment_session.creditee_id, error = %e, "First-payment match failed; purchase unaffected, grant manually if needed");
+            }
+        }
+
+        // Real money moved: mark the payer as verified for the onwards rate-limit
+        // tier. `creditor_id` is the resolved billing target (org when paying as an
+        // org, otherwise self), so this naturally verifies whichever entity owns
+        // the keys we care about in the common case. For the rare admin
+        // pay-on-behalf flow (explicit `creditee_id` query param) the payer is
+        // verified rather than the recipient, which we accept as the right
+        // semantic for "this entity can pay".
+        crate::db::handlers::users::Users::new(&mut *conn)
+            .set_verified(payment_session.creditor_id)
+            .await?;
+
+        tracing::debug!(
+            "Successfully fulfilled checkout session {} for user {}",
+            session_id,
+            payment_session.creditee_id
+        );
+        Ok(())
+    }
 }
 
 /// Pre-tax amount (in cents) to credit for a completed checkout session.
@@ -290,125 +421,8 @@ impl PaymentProvider for StripeProvider {
         session_id: &str,
         credits_config: &crate::config::CreditsConfig,
     ) -> Result<()> {
-        // Acquire connection early for idempotency check
         let mut conn = db_pool.acquire().await?;
-
-        // Fast path: Check if we've already processed this payment
-        // This avoids expensive Stripe API calls for duplicate webhook deliveries,
-        // user retries, etc. The unique constraint below handles race conditions.
-        {
-            let mut credits = Credits::new(&mut conn);
-            if credits.transaction_exists_by_source_id(session_id).await? {
-                tracing::trace!("Transaction for session_id {} already exists, skipping (fast path)", session_id);
-                return Ok(());
-            }
-        }
-
-        // Get payment session details
-        let payment_session = self.get_payment_session(session_id).await?;
-
-        // Verify payment status
-        if !payment_session.is_paid {
-            tracing::trace!("Transaction for session_id {} has not been paid, skipping.", session_id);
-            return Err(PaymentError::PaymentNotCompleted);
-        }
-
-        // Look up creditor user and build description + set creditor stripe ID in db.
-        // This is one block to scope user repo lifetime properly
-        let description = {
-            let mut users = crate::db::handlers::users::Users::new(&mut conn);
-
-            // Verify creditor user exists before proceeding
-            let creditor_user = users.get_by_id(payment_session.creditor_id).await?;
-            if creditor_user.is_none() {
-                tracing::error!(
-                    "Creditor user {} not found for payment session {}. This indicates a data integrity issue.",
-                    payment_session.creditor_id,
-                    session_id
-                );
-            }
-
-            // Build description with payer information
-            let description = if payment_session.creditor_id == payment_session.creditee_id {
-                // Self-payment
-                "Stripe payment".to_string()
-            } else if let Some(creditor) = creditor_user.as_ref() {
-                let creditor_name = creditor.display_name.as_ref().unwrap_or(&creditor.email);
-                format!("Stripe payment from {}", creditor_name)
-            } else {
-                "Stripe payment".to_string()
-            };
-
-            // Save the customer ID if we don't have one yet, so we can offer the billing portal
-            if let Some(ref provider_id) = payment_session.payment_provider_id
-                && users
-                    .set_payment_provider_id_if_empty(payment_session.creditor_id, provider_id)
-                    .await?
-            {
-                tracing::debug!(
-                    "Saved newly created stripe ID {} for user ID {}",
-                    provider_id,
-                    payment_session.creditor_id
-                );
-            }
-
-            description
-        };
-
-        // Create the credit transaction
-        let request = CreditTransactionCreateDBRequest {
-            user_id: payment_session.creditee_id,
-            transaction_type: CreditTransactionType::Purchase,
-            amount: payment_session.amount,
-            source_id: session_id.to_string(),
-            description: Some(description),
-            fusillade_batch_id: None,
-            api_key_id: None,
-        };
-
-        // Record the purchase first. This is the critical write (real money moved)
-        // and is never made contingent on the secondary effects below.
-        let mut credits = Credits::new(&mut conn);
-        credits.create_transaction(&request).await?;
-
-        // First-payment match (no-op unless enabled and this is the payee's first
-        // ever payment). The bonus lands on the creditee (whose balance was just
-        // topped up), deliberately the credited account rather than the payer we
-        // verify below, since the promo rewards whoever receives the credits.
-        // Best-effort: a freebie must never undo the recorded purchase, so we log
-        // and continue on failure rather than failing payment processing. Note
-        // such a failure is not retried (the webhook retry's fast-path sees the
-        // purchase already recorded), so the error log is the signal to grant it
-        // manually.
-        if let Err(e) = credits
-            .grant_first_payment_match(
-                credits_config.first_payment_match_up_to,
-                payment_session.creditee_id,
-                payment_session.amount,
-                session_id,
-            )
-            .await
-        {
-            tracing::error!(session_id, creditee_id = %payment_session.creditee_id, error = %e, "First-payment match failed; purchase unaffected, grant manually if needed");
-        }
-
-        // Real money moved: mark the payer as verified for the onwards rate-limit
-        // tier. `creditor_id` is the resolved billing target (org when paying as an
-        // org, otherwise self), so this naturally verifies whichever entity owns
-        // the keys we care about in the common case. For the rare admin
-        // pay-on-behalf flow (explicit `creditee_id` query param) the payer is
-        // verified rather than the recipient, which we accept as the right
-        // semantic for "this entity can pay".
-        crate::db::handlers::users::Users::new(&mut conn)
-            .set_verified(payment_session.creditor_id)
-            .await?;
-
-        tracing::debug!(
-            "Successfully fulfilled checkout session {} for user {}",
-            session_id,
-            payment_session.creditee_id
-        );
-        Ok(())
+        self.fulfil_payment_session(&mut conn, session_id, credits_config).await
     }
 
     async fn validate_webhook(&self, headers: &axum::http::HeaderMap, body: &str) -> Result<Option<WebhookEvent>> {
@@ -442,6 +456,7 @@ impl PaymentProvider for StripeProvider {
         };
 
         let webhook_event = WebhookEvent {
+            event_id: Some(event.id.to_string()),
             event_type: event.type_.to_string(),
             session_id,
         };
@@ -451,7 +466,7 @@ impl PaymentProvider for StripeProvider {
 
     async fn process_webhook_event(
         &self,
-        db_pool: &PgPool,
+        conn: &mut PgConnection,
         event: &WebhookEvent,
         credits_config: &crate::config::CreditsConfig,
     ) -> Result<()> {
@@ -471,8 +486,7 @@ impl PaymentProvider for StripeProvider {
 
         tracing::trace!("Processing webhook event {} for session: {}", event.event_type, session_id);
 
-        // Use the existing process_payment_session method
-        self.process_payment_session(db_pool, session_id, credits_config).await
+        self.fulfil_payment_session(conn, session_id, credits_config).await
     }
 
     async fn create_auto_topup_checkout_session(&self, payer: &CheckoutPayer, cancel_url: &str, success_url: &str) -> Result<String> {
@@ -851,10 +865,12 @@ mod tests {
     fn test_webhook_event_parsing() {
         // Test WebhookEvent structure
         let event = WebhookEvent {
+            event_id: Some("evt_test_123".to_string()),
             event_type: "CheckoutSessionCompleted".to_string(),
             session_id: Some("cs_test_123".to_string()),
         };
 
+        assert_eq!(event.event_id, Some("evt_test_123".to_string()));
         assert_eq!(event.event_type, "CheckoutSessionCompleted");
         assert_eq!(event.session_id, Some("cs_test_123".to_string()));
     }
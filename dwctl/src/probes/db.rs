@@ -6,8 +6,11 @@
 //!
 //! Background scheduling is handled separately by the `ProbeScheduler`.
 
-use crate::api::models::probes::{CreateProbe, ProbeStatistics, UpdateProbeRequest};
-use crate::db::models::probes::{Probe, ProbeExecution, ProbeResult};
+use crate::api::models::probes::{
+    CreateProbe, DEFAULT_FAILURE_THRESHOLD, DEFAULT_RECOVERY_COOLDOWN_SECONDS, DEFAULT_SUCCESS_THRESHOLD, ProbeStatistics,
+    TestProbeRequest, UpdateProbeRequest,
+};
+use crate::db::models::probes::{Probe, ProbeAssertions, ProbeExecution, ProbeHealth, ProbeResult, ProbeType};
 use crate::errors::Error as AppError;
 use crate::probes::executor::{ProbeExecutionContext, ProbeExecutor};
 use chrono::{DateTime, Utc};
@@ -16,13 +19,14 @@ use uuid::Uuid;
 
 /// Deployment status information returned by bulk status queries.
 ///
-/// Tuple contains: (probe_id, active, interval_seconds, last_check, last_success, uptime_24h)
+/// Tuple contains: (probe_id, active, interval_seconds, last_check, last_success, healthy, uptime_24h)
 type DeploymentStatus = (
     Option<Uuid>,
     bool,
     Option<i32>,
     Option<chrono::DateTime<chrono::Utc>>,
     Option<bool>,
+    Option<bool>,
     Option<f64>,
 );
 
@@ -41,8 +45,9 @@ impl ProbeManager {
     pub async fn create_probe(pool: &PgPool, probe: CreateProbe) -> Result<Probe, AppError> {
         let result = sqlx::query_as::<_, Probe>(
             r#"
-            INSERT INTO probes (name, deployment_id, interval_seconds, active, http_method, request_path, request_body)
-            VALUES ($1, $2, $3, true, $4, $5, $6)
+            INSERT INTO probes (name, deployment_id, interval_seconds, active, http_method, request_path, request_body, assertions, probe_type, expected_model,
+                                failure_threshold, success_threshold, recovery_cooldown_seconds)
+            VALUES ($1, $2, $3, true, $4, $5, $6, $7, $8, $9, $10, $11, $12)
             RETURNING *
             "#,
         )
@@ -52,6 +57,12 @@ impl ProbeManager {
         .bind(&probe.http_method)
         .bind(&probe.request_path)
         .bind(&probe.request_body)
+        .bind(probe.assertions.map(sqlx::types::Json))
+        .bind(probe.probe_type)
+        .bind(&probe.expected_model)
+        .bind(probe.failure_threshold.unwrap_or(DEFAULT_FAILURE_THRESHOLD))
+        .bind(probe.success_threshold.unwrap_or(DEFAULT_SUCCESS_THRESHOLD))
+        .bind(probe.recovery_cooldown_seconds.unwrap_or(DEFAULT_RECOVERY_COOLDOWN_SECONDS))
         .fetch_one(pool)
         .await
         .map_err(|e| anyhow::anyhow!("Failed to create probe: {}", e))?;
@@ -107,7 +118,7 @@ impl ProbeManager {
     }
 
     /// Get probe status for multiple deployments (bulk operation)
-    /// Returns a map of deployment_id -> (probe_id, active, interval_seconds, last_check, last_success, uptime_24h)
+    /// Returns a map of deployment_id -> (probe_id, active, interval_seconds, last_check, last_success, healthy, uptime_24h)
     #[tracing::instrument(skip(pool, deployment_ids), fields(count = deployment_ids.len()), err)]
     pub async fn get_deployment_statuses(pool: &PgPool, deployment_ids: &[Uuid]) -> Result<DeploymentStatusMap, AppError> {
         if deployment_ids.is_empty() {
@@ -123,7 +134,8 @@ impl ProbeManager {
                 p.active,
                 p.interval_seconds,
                 pr.executed_at as last_check,
-                pr.success as last_success
+                pr.success as last_success,
+                ph.healthy as "healthy?"
             FROM probes p
             LEFT JOIN LATERAL (
                 SELECT executed_at, success
@@ -132,6 +144,7 @@ impl ProbeManager {
                 ORDER BY executed_at DESC
                 LIMIT 1
             ) pr ON true
+            LEFT JOIN probe_health ph ON ph.probe_id = p.id
             WHERE p.deployment_id = ANY($1)
             "#,
             deployment_ids
@@ -161,13 +174,22 @@ impl ProbeManager {
             let interval_seconds = row.interval_seconds;
             let last_check = row.last_check;
             let last_success = row.last_success;
+            let healthy = row.healthy;
 
             // Get uptime from the bulk-calculated map (only for active probes)
             let uptime_24h = if active { uptime_map.get(&probe_id).copied() } else { None };
 
             result.insert(
                 deployment_id,
-                (Some(probe_id), active, Some(interval_seconds), last_check, last_success, uptime_24h),
+                (
+                    Some(probe_id),
+                    active,
+                    Some(interval_seconds),
+                    last_check,
+                    last_success,
+                    healthy,
+                    uptime_24h,
+                ),
             );
         }
 
@@ -264,7 +286,16 @@ impl ProbeManager {
             SET interval_seconds = COALESCE($2, interval_seconds),
                 http_method = COALESCE($3, http_method),
                 request_path = COALESCE($4, request_path),
-                request_body = COALESCE($5, request_body)
+                request_body = COALESCE($5, request_body),
+                assertions = CASE
+                    WHEN $6 THEN $7
+                    ELSE assertions
+                END,
+                probe_type = COALESCE($8, probe_type),
+                expected_model = COALESCE($9, expected_model),
+                failure_threshold = COALESCE($10, failure_threshold),
+                success_threshold = COALESCE($11, success_threshold),
+                recovery_cooldown_seconds = COALESCE($12, recovery_cooldown_seconds)
             WHERE id = $1
             RETURNING *
             "#,
@@ -274,6 +305,13 @@ impl ProbeManager {
         .bind(update.http_method)
         .bind(update.request_path)
         .bind(update.request_body)
+        .bind(update.assertions.is_some())
+        .bind(update.assertions.flatten().map(sqlx::types::Json))
+        .bind(update.probe_type)
+        .bind(update.expected_model)
+        .bind(update.failure_threshold)
+        .bind(update.success_threshold)
+        .bind(update.recovery_cooldown_seconds)
         .fetch_one(pool)
         .await
         .map_err(|e| anyhow::anyhow!("Failed to update probe: {}", e))?;
@@ -301,9 +339,7 @@ impl ProbeManager {
         pool: &PgPool,
         deployment_id: Uuid,
         config: &crate::config::Config,
-        http_method: Option<String>,
-        request_path: Option<String>,
-        request_body: Option<serde_json::Value>,
+        request: TestProbeRequest,
     ) -> Result<ProbeResult, AppError> {
         // Fetch deployment details - use alias to route through control layer
         let context = sqlx::query!(
@@ -335,6 +371,8 @@ impl ProbeManager {
             Some(t) => match t.to_uppercase().as_str() {
                 "CHAT" => crate::db::models::deployments::ModelType::Chat,
                 "EMBEDDINGS" => crate::db::models::deployments::ModelType::Embeddings,
+                "RERANKER" => crate::db::models::deployments::ModelType::Reranker,
+                "MODERATION" => crate::db::models::deployments::ModelType::Moderation,
                 _ => {
                     return Err(AppError::BadRequest {
                         message: format!("Unknown model type: {}", t),
@@ -350,9 +388,12 @@ impl ProbeManager {
             model_type,
             endpoint_url,
             api_key,
-            http_method: http_method.unwrap_or_else(|| "POST".to_string()),
-            request_path,
-            request_body,
+            http_method: request.http_method.unwrap_or_else(|| "POST".to_string()),
+            request_path: request.request_path,
+            request_body: request.request_body,
+            assertions: request.assertions,
+            probe_type: request.probe_type,
+            expected_model: request.expected_model,
         };
 
         let executor = ProbeExecutor::new();
@@ -388,6 +429,9 @@ impl ProbeManager {
                 p.http_method,
                 p.request_path,
                 p.request_body,
+                p.assertions,
+                p.probe_type as "probe_type: ProbeType",
+                p.expected_model,
                 d.alias,
                 d.type as model_type,
                 ak.secret as system_api_key
@@ -406,6 +450,11 @@ impl ProbeManager {
         let http_method = context.http_method;
         let request_path = context.request_path;
         let request_body = context.request_body;
+        let assertions = context
+            .assertions
+            .map(serde_json::from_value::<ProbeAssertions>)
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("Failed to parse probe assertions: {}", e))?;
         let model_name = context.alias;
         let model_type_str = context.model_type;
         let system_api_key = context.system_api_key;
@@ -419,6 +468,8 @@ impl ProbeManager {
             Some(t) => match t.to_uppercase().as_str() {
                 "CHAT" => crate::db::models::deployments::ModelType::Chat,
                 "EMBEDDINGS" => crate::db::models::deployments::ModelType::Embeddings,
+                "RERANKER" => crate::db::models::deployments::ModelType::Reranker,
+                "MODERATION" => crate::db::models::deployments::ModelType::Moderation,
                 _ => {
                     return Err(AppError::BadRequest {
                         message: format!("Unknown model type: {}", t),
@@ -437,6 +488,9 @@ impl ProbeManager {
             http_method,
             request_path,
             request_body,
+            assertions,
+            probe_type: context.probe_type,
+            expected_model: context.expected_model,
         };
 
         let executor = ProbeExecutor::new();
@@ -471,6 +525,59 @@ impl ProbeManager {
         Ok(result)
     }
 
+    /// Apply a result to the probe's debounced health and return the new health.
+    ///
+    /// Only the scheduler records health, so manual "Run Now" executions don't
+    /// move a probe in or out of health.
+    pub async fn record_health(pool: &PgPool, probe: &Probe, result: &ProbeResult) -> Result<ProbeHealth, AppError> {
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to begin transaction: {}", e))?;
+
+        let current = sqlx::query_as!(
+            ProbeHealth,
+            r#"
+            SELECT healthy, consecutive_successes, consecutive_failures, changed_at
+            FROM probe_health
+            WHERE probe_id = $1
+            FOR UPDATE
+            "#,
+            probe.id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to fetch probe health: {}", e))?;
+
+        let next = crate::probes::health::next_health(current, result.success, &probe.into(), result.executed_at);
+
+        sqlx::query!(
+            r#"
+            INSERT INTO probe_health (probe_id, healthy, consecutive_successes, consecutive_failures, changed_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (probe_id) DO UPDATE
+            SET healthy = EXCLUDED.healthy,
+                consecutive_successes = EXCLUDED.consecutive_successes,
+                consecutive_failures = EXCLUDED.consecutive_failures,
+                changed_at = EXCLUDED.changed_at
+            "#,
+            probe.id,
+            next.healthy,
+            next.consecutive_successes,
+            next.consecutive_failures,
+            next.changed_at
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store probe health: {}", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to commit probe health: {}", e))?;
+
+        Ok(next)
+    }
+
     /// Get probe results with optional filters
     pub async fn get_probe_results(
         pool: &PgPool,
@@ -535,7 +642,8 @@ impl ProbeManager {
     /// Calculate aggregated statistics for a probe over a time period.
     ///
     /// Computes success rates, response time percentiles, and execution counts
-    /// from stored probe results.
+    /// from stored probe results, folding in hourly aggregates for any part of
+    /// the range that has already been downsampled (see [`crate::probes::retention`]).
     pub async fn get_statistics(
         pool: &PgPool,
         probe_id: Uuid,
@@ -662,29 +770,66 @@ impl ProbeManager {
             .map_err(|e| anyhow::anyhow!("Failed to fetch probe statistics: {}", e))?,
         };
 
-        let total = row.total.unwrap_or(0);
-        let successful = row.successful.unwrap_or(0);
-        let failed = row.failed.unwrap_or(0);
+        // Results past the raw retention window live on as hourly buckets. A bucket is
+        // included when its hour overlaps the requested range.
+        let hourly = sqlx::query!(
+            r#"
+                SELECT
+                    SUM(total_count)::int8 as total,
+                    SUM(success_count)::int8 as successful,
+                    (SUM(avg_response_time_ms * success_count) / NULLIF(SUM(success_count) FILTER (WHERE avg_response_time_ms IS NOT NULL), 0))::float8 as avg_time,
+                    MIN(min_response_time_ms) as min_time,
+                    MAX(max_response_time_ms) as max_time,
+                    MAX(last_execution_at) as last_execution,
+                    MAX(last_success_at) as last_success,
+                    MAX(last_failure_at) as last_failure
+                FROM probe_results_hourly
+                WHERE probe_id = $1
+                  AND ($2::timestamptz IS NULL OR bucket_start >= date_trunc('hour', $2::timestamptz))
+                  AND ($3::timestamptz IS NULL OR bucket_start <= $3::timestamptz)
+                "#,
+            probe_id,
+            start_time,
+            end_time
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to fetch aggregated probe statistics: {}", e))?;
+
+        let raw_successful = row.successful.unwrap_or(0);
+        let hourly_successful = hourly.successful.unwrap_or(0);
+
+        let total = row.total.unwrap_or(0) + hourly.total.unwrap_or(0);
+        let successful = raw_successful + hourly_successful;
+        let failed = total - successful;
         let success_rate = if total > 0 {
             (successful as f64 / total as f64) * 100.0
         } else {
             0.0
         };
 
+        let avg_response_time_ms = match (row.avg_time, hourly.avg_time) {
+            (Some(raw), Some(agg)) => {
+                Some((raw * raw_successful as f64 + agg * hourly_successful as f64) / (raw_successful + hourly_successful) as f64)
+            }
+            (raw, agg) => raw.or(agg),
+        };
+
         Ok(ProbeStatistics {
             total_executions: total,
             successful_executions: successful,
             failed_executions: failed,
             success_rate,
-            avg_response_time_ms: row.avg_time,
-            min_response_time_ms: row.min_time,
-            max_response_time_ms: row.max_time,
+            avg_response_time_ms,
+            min_response_time_ms: row.min_time.into_iter().chain(hourly.min_time).min(),
+            max_response_time_ms: row.max_time.into_iter().chain(hourly.max_time).max(),
+            // Percentiles can't be recovered from hourly aggregates, so they cover raw results only
             p50_response_time_ms: row.p50,
             p95_response_time_ms: row.p95,
             p99_response_time_ms: row.p99,
-            last_execution: row.last_execution,
-            last_success: row.last_success,
-            last_failure: row.last_failure,
+            last_execution: row.last_execution.max(hourly.last_execution),
+            last_success: row.last_success.max(hourly.last_success),
+            last_failure: row.last_failure.max(hourly.last_failure),
         })
     }
 }
@@ -736,6 +881,12 @@ mod tests {
             http_method: "POST".to_string(),
             request_path: None,
             request_body: None,
+            assertions: None,
+            probe_type: Default::default(),
+            expected_model: None,
+            failure_threshold: None,
+            success_threshold: None,
+            recovery_cooldown_seconds: None,
         };
 
         let created = ProbeManager::create_probe(&pool, probe_create).await.unwrap();
@@ -765,6 +916,12 @@ mod tests {
                     http_method: "POST".to_string(),
                     request_path: None,
                     request_body: None,
+                    assertions: None,
+                    probe_type: Default::default(),
+                    expected_model: None,
+                    failure_threshold: None,
+                    success_threshold: None,
+                    recovery_cooldown_seconds: None,
                 },
             )
             .await
@@ -791,6 +948,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -805,6 +968,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -831,6 +1000,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -860,6 +1035,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -874,6 +1055,12 @@ mod tests {
                 http_method: None,
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -889,6 +1076,12 @@ mod tests {
                 http_method: None,
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -909,6 +1102,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -934,6 +1133,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -942,12 +1147,91 @@ mod tests {
         let statuses = ProbeManager::get_deployment_statuses(&pool, &[deployment_id]).await.unwrap();
 
         assert_eq!(statuses.len(), 1);
-        let (probe_id, active, interval, _, _, _) = statuses.get(&deployment_id).unwrap();
+        let (probe_id, active, interval, _, _, _, _) = statuses.get(&deployment_id).unwrap();
         assert_eq!(*probe_id, Some(probe.id));
         assert!(*active);
         assert_eq!(*interval, Some(60));
     }
 
+    #[sqlx::test]
+    async fn test_record_health_applies_probe_thresholds(pool: PgPool) {
+        let deployment_id = setup_test_deployment(&pool).await;
+
+        let probe = ProbeManager::create_probe(
+            &pool,
+            CreateProbe {
+                name: "Debounced Probe".to_string(),
+                deployment_id,
+                interval_seconds: 60,
+                http_method: "POST".to_string(),
+                request_path: None,
+                request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: Some(2),
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        // Store a result and feed it through the state machine, as the scheduler does.
+        async fn record(pool: &PgPool, probe: &Probe, success: bool) -> (bool, Option<bool>) {
+            let result = ProbeManager::store_result(
+                pool,
+                ProbeExecution {
+                    probe_id: probe.id,
+                    success,
+                    response_time_ms: 10,
+                    status_code: Some(if success { 200 } else { 500 }),
+                    error_message: None,
+                    response_data: None,
+                    metadata: None,
+                },
+            )
+            .await
+            .unwrap();
+            let health = ProbeManager::record_health(pool, probe, &result).await.unwrap();
+            let statuses = ProbeManager::get_deployment_statuses(pool, &[probe.deployment_id]).await.unwrap();
+            let (_, _, _, _, _, healthy, _) = statuses.get(&probe.deployment_id).unwrap();
+            (health.healthy, *healthy)
+        }
+
+        assert_eq!(record(&pool, &probe, true).await, (true, Some(true)));
+        // One failure is tolerated with failure_threshold = 2...
+        assert_eq!(record(&pool, &probe, false).await, (true, Some(true)));
+        // ...the second marks the probe unhealthy.
+        assert_eq!(record(&pool, &probe, false).await, (false, Some(false)));
+        // success_threshold = 1 and no cooldown: one success recovers it.
+        assert_eq!(record(&pool, &probe, true).await, (true, Some(true)));
+
+        // With a recovery cooldown, a flapping probe stays unhealthy.
+        let probe = ProbeManager::update_probe(
+            &pool,
+            probe.id,
+            UpdateProbeRequest {
+                interval_seconds: None,
+                http_method: None,
+                request_path: None,
+                request_body: None,
+                assertions: None,
+                probe_type: None,
+                expected_model: None,
+                failure_threshold: Some(1),
+                success_threshold: None,
+                recovery_cooldown_seconds: Some(3600),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(record(&pool, &probe, false).await, (false, Some(false)));
+        assert_eq!(record(&pool, &probe, true).await, (false, Some(false)));
+        assert_eq!(record(&pool, &probe, false).await, (false, Some(false)));
+        assert_eq!(record(&pool, &probe, true).await, (false, Some(false)));
+    }
+
     #[sqlx::test]
     async fn test_get_statistics_empty(pool: PgPool) {
         let deployment_id = setup_test_deployment(&pool).await;
@@ -961,6 +1245,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -987,6 +1277,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -1017,6 +1313,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -1042,6 +1344,12 @@ mod tests {
                 http_method: None,
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -3,12 +3,20 @@
 //! This module provides the `ProbeExecutor` which handles the actual HTTP requests
 //! to monitored endpoints. It constructs appropriate payloads for different endpoint
 //! types (chat completions vs embeddings) and measures response times.
+//!
+//! When a probe has [`ProbeAssertions`], the response is additionally checked
+//! against them and the first failing assertion is recorded on the result.
+//!
+//! [`ProbeType::ChatCompletion`] probes always send a tiny canned chat request
+//! and also require a well-formed completion with non-empty content, so an
+//! endpoint that lists a model but cannot serve it fails the probe.
 
 use crate::db::models::deployments::ModelType;
-use crate::db::models::probes::ProbeExecution;
+use crate::db::models::probes::{ProbeAssertions, ProbeExecution, ProbeType};
 use anyhow::Result;
 use reqwest::Client;
-use serde_json::json;
+use serde::Serialize;
+use serde_json::{Value, json};
 use std::time::Instant;
 use uuid::Uuid;
 
@@ -22,8 +30,17 @@ pub struct ProbeExecutionContext {
     pub http_method: String,
     pub request_path: Option<String>,
     pub request_body: Option<serde_json::Value>,
+    pub assertions: Option<ProbeAssertions>,
+    pub probe_type: ProbeType,
+    pub expected_model: Option<String>,
 }
 
+/// Prompt sent by chat completion probes; short enough that the probe costs a handful of tokens.
+const CHAT_PROBE_PROMPT: &str = "Reply with the word OK.";
+
+/// Completion budget for chat completion probes.
+const CHAT_PROBE_MAX_TOKENS: u32 = 5;
+
 /// Executes health check requests against API endpoints.
 ///
 /// The executor maintains an HTTP client and constructs type-appropriate
@@ -64,6 +81,13 @@ impl ProbeExecutor {
                     "documents": ["test document"]
                 }),
             ),
+            ModelType::Moderation => (
+                format!("{}/v1/moderations", endpoint_url.trim_end_matches('/')),
+                json!({
+                    "model": model_name,
+                    "input": "Health check probe"
+                }),
+            ),
         }
     }
 
@@ -74,21 +98,54 @@ impl ProbeExecutor {
     /// `ProbeExecution` regardless of success or failure to ensure
     /// all execution attempts are captured.
     pub async fn execute(&self, context: ProbeExecutionContext) -> Result<ProbeExecution> {
-        let start = Instant::now();
-
-        // Get default config based on model type, then override with custom values if provided
-        let (default_url, default_payload) = Self::get_default_config(&context.model_type, &context.model_name, &context.endpoint_url);
+        let assertions = context.assertions.clone();
+        let probe_type = context.probe_type;
+        let expected_model = context.expected_model.clone();
+        let mut execution = self.send(context).await?;
+        if probe_type == ProbeType::ChatCompletion
+            && execution.success
+            && let Some(failure) = check_chat_completion(&execution, expected_model.as_deref())
+        {
+            execution = fail(execution, failure);
+        }
+        Ok(match assertions {
+            Some(assertions) => apply_assertions(execution, &assertions),
+            None => execution,
+        })
+    }
 
-        let full_url = context
-            .request_path
-            .as_ref()
-            .map(|path| format!("{}{}", context.endpoint_url.trim_end_matches('/'), path))
-            .unwrap_or(default_url);
+    /// Send the probe request and classify the response with the default liveness check.
+    async fn send(&self, context: ProbeExecutionContext) -> Result<ProbeExecution> {
+        let start = Instant::now();
 
-        let payload = context.request_body.clone().unwrap_or(default_payload);
+        // Chat completion probes always send the canned chat request
+        let (full_url, payload, http_method) = match context.probe_type {
+            ProbeType::ChatCompletion => {
+                let url = format!("{}/v1/chat/completions", context.endpoint_url.trim_end_matches('/'));
+                let payload = json!({
+                    "model": context.model_name,
+                    "messages": [{"role": "user", "content": CHAT_PROBE_PROMPT}],
+                    "max_tokens": CHAT_PROBE_MAX_TOKENS,
+                    "temperature": 0
+                });
+                (url, payload, "POST".to_string())
+            }
+            ProbeType::Liveness => {
+                // Get default config based on model type, then override with custom values if provided
+                let (default_url, default_payload) =
+                    Self::get_default_config(&context.model_type, &context.model_name, &context.endpoint_url);
+                let url = context
+                    .request_path
+                    .as_ref()
+                    .map(|path| format!("{}{}", context.endpoint_url.trim_end_matches('/'), path))
+                    .unwrap_or(default_url);
+                let payload = context.request_body.clone().unwrap_or(default_payload);
+                (url, payload, context.http_method.clone())
+            }
+        };
 
         // Build and send request with the configured HTTP method
-        let mut request = match context.http_method.to_uppercase().as_str() {
+        let mut request = match http_method.to_uppercase().as_str() {
             "GET" => self.client.get(&full_url),
             "POST" => self.client.post(&full_url).json(&payload),
             "PUT" => self.client.put(&full_url).json(&payload),
@@ -192,8 +249,354 @@ impl ProbeExecutor {
     }
 }
 
+/// Why a probe response failed its assertions.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "assertion", rename_all = "snake_case")]
+enum AssertionFailure {
+    Status { expected: i32, actual: Option<i32> },
+    JsonPathMissing { path: String },
+    JsonPathMismatch { path: String, expected: Value, actual: Value },
+    Latency { max_response_time_ms: i32, actual_ms: i32 },
+    CompletionContent,
+    Model { expected: String, actual: Option<String> },
+}
+
+impl std::fmt::Display for AssertionFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Status { expected, actual } => match actual {
+                Some(actual) => write!(f, "expected status {expected}, got {actual}"),
+                None => write!(f, "expected status {expected}, got no response"),
+            },
+            Self::JsonPathMissing { path } => write!(f, "expected response field {path} to exist"),
+            Self::JsonPathMismatch { path, expected, actual } => {
+                write!(f, "expected response field {path} to equal {expected}, got {actual}")
+            }
+            Self::Latency {
+                max_response_time_ms,
+                actual_ms,
+            } => write!(f, "expected response within {max_response_time_ms}ms, took {actual_ms}ms"),
+            Self::CompletionContent => write!(f, "expected a chat completion with non-empty message content"),
+            Self::Model { expected, actual } => match actual {
+                Some(actual) => write!(f, "expected model {expected}, got {actual}"),
+                None => write!(f, "expected model {expected}, got none"),
+            },
+        }
+    }
+}
+
+/// Find the first assertion the response fails, checking status, then body, then latency.
+fn check_assertions(assertions: &ProbeAssertions, execution: &ProbeExecution) -> Option<AssertionFailure> {
+    if let Some(expected) = assertions.expected_status
+        && execution.status_code != Some(expected)
+    {
+        return Some(AssertionFailure::Status {
+            expected,
+            actual: execution.status_code,
+        });
+    }
+
+    for assertion in &assertions.json_paths {
+        let Some(actual) = execution.response_data.as_ref().and_then(|data| data.pointer(&assertion.path)) else {
+            return Some(AssertionFailure::JsonPathMissing {
+                path: assertion.path.clone(),
+            });
+        };
+        if let Some(expected) = &assertion.equals
+            && actual != expected
+        {
+            return Some(AssertionFailure::JsonPathMismatch {
+                path: assertion.path.clone(),
+                expected: expected.clone(),
+                actual: actual.clone(),
+            });
+        }
+    }
+
+    if let Some(max_response_time_ms) = assertions.max_response_time_ms
+        && execution.response_time_ms > max_response_time_ms
+    {
+        return Some(AssertionFailure::Latency {
+            max_response_time_ms,
+            actual_ms: execution.response_time_ms,
+        });
+    }
+
+    None
+}
+
+/// Check a successful chat completion probe response is a real completion.
+fn check_chat_completion(execution: &ProbeExecution, expected_model: Option<&str>) -> Option<AssertionFailure> {
+    let data = execution.response_data.as_ref();
+    let content = data
+        .and_then(|data| data.pointer("/choices/0/message/content"))
+        .and_then(Value::as_str);
+    if content.is_none_or(|content| content.trim().is_empty()) {
+        return Some(AssertionFailure::CompletionContent);
+    }
+
+    if let Some(expected) = expected_model {
+        let actual = data.and_then(|data| data.get("model")).and_then(Value::as_str);
+        if actual != Some(expected) {
+            return Some(AssertionFailure::Model {
+                expected: expected.to_string(),
+                actual: actual.map(str::to_string),
+            });
+        }
+    }
+
+    None
+}
+
+/// Mark an execution as failed by `failure`.
+fn fail(mut execution: ProbeExecution, failure: AssertionFailure) -> ProbeExecution {
+    execution.success = false;
+    execution.error_message = Some(format!("Assertion failed: {failure}"));
+    execution.metadata = Some(json!({ "failed_assertion": failure }));
+    execution
+}
+
+/// Apply assertions on top of the default liveness check.
+///
+/// An explicit `expected_status` outside 2xx replaces the liveness check, so a
+/// probe can assert that e.g. an invalid request is rejected with a 400.
+fn apply_assertions(mut execution: ProbeExecution, assertions: &ProbeAssertions) -> ProbeExecution {
+    let liveness_applies = assertions.expected_status.is_none_or(|status| (200..300).contains(&status));
+    if liveness_applies && !execution.success {
+        return execution;
+    }
+
+    match check_assertions(assertions, &execution) {
+        Some(failure) => fail(execution, failure),
+        None => {
+            execution.success = true;
+            execution.error_message = None;
+            execution
+        }
+    }
+}
+
 impl Default for ProbeExecutor {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::probes::JsonPathAssertion;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn chat_server() -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "object": "chat.completion",
+                "model": "probe-model",
+                "choices": [{"index": 0, "message": {"role": "assistant", "content": "pong"}}]
+            })))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn context(endpoint_url: String, assertions: ProbeAssertions) -> ProbeExecutionContext {
+        ProbeExecutionContext {
+            probe_id: Uuid::new_v4(),
+            model_name: "probe-model".to_string(),
+            model_type: ModelType::Chat,
+            endpoint_url,
+            api_key: None,
+            http_method: "POST".to_string(),
+            request_path: None,
+            request_body: None,
+            assertions: Some(assertions),
+            probe_type: ProbeType::Liveness,
+            expected_model: None,
+        }
+    }
+
+    fn chat_completion_context(endpoint_url: String, expected_model: Option<&str>) -> ProbeExecutionContext {
+        ProbeExecutionContext {
+            assertions: None,
+            probe_type: ProbeType::ChatCompletion,
+            expected_model: expected_model.map(str::to_string),
+            ..context(endpoint_url, ProbeAssertions::default())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_probe_passes_when_content_matches() {
+        let server = chat_server().await;
+        let assertions = ProbeAssertions {
+            expected_status: Some(200),
+            json_paths: vec![
+                JsonPathAssertion {
+                    path: "/choices/0/message/content".to_string(),
+                    equals: Some(json!("pong")),
+                },
+                JsonPathAssertion {
+                    path: "/model".to_string(),
+                    equals: None,
+                },
+            ],
+            max_response_time_ms: Some(10_000),
+        };
+
+        let execution = ProbeExecutor::new().execute(context(server.uri(), assertions)).await.unwrap();
+
+        assert!(execution.success, "unexpected failure: {:?}", execution.error_message);
+        assert!(execution.error_message.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_probe_fails_on_missing_json_field() {
+        let server = chat_server().await;
+        let assertions = ProbeAssertions {
+            json_paths: vec![JsonPathAssertion {
+                path: "/usage/total_tokens".to_string(),
+                equals: None,
+            }],
+            ..Default::default()
+        };
+
+        let execution = ProbeExecutor::new().execute(context(server.uri(), assertions)).await.unwrap();
+
+        assert!(!execution.success);
+        assert_eq!(execution.status_code, Some(200));
+        assert_eq!(
+            execution.error_message.as_deref(),
+            Some("Assertion failed: expected response field /usage/total_tokens to exist")
+        );
+        assert_eq!(
+            execution.metadata,
+            Some(json!({"failed_assertion": {"assertion": "json_path_missing", "path": "/usage/total_tokens"}}))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_probe_fails_on_mismatched_value_and_latency() {
+        let server = chat_server().await;
+        let mismatch = ProbeAssertions {
+            json_paths: vec![JsonPathAssertion {
+                path: "/choices/0/message/content".to_string(),
+                equals: Some(json!("ping")),
+            }],
+            ..Default::default()
+        };
+        let execution = ProbeExecutor::new().execute(context(server.uri(), mismatch)).await.unwrap();
+        assert!(!execution.success);
+        assert_eq!(execution.metadata.unwrap()["failed_assertion"]["actual"], "pong");
+
+        let status = ProbeAssertions {
+            expected_status: Some(201),
+            ..Default::default()
+        };
+        let execution = ProbeExecutor::new().execute(context(server.uri(), status)).await.unwrap();
+        assert_eq!(
+            execution.error_message.as_deref(),
+            Some("Assertion failed: expected status 201, got 200")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_probe_expected_error_status_passes() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({"object": "error", "message": "bad input"})))
+            .mount(&server)
+            .await;
+        let assertions = ProbeAssertions {
+            expected_status: Some(400),
+            json_paths: vec![JsonPathAssertion {
+                path: "/object".to_string(),
+                equals: Some(json!("error")),
+            }],
+            ..Default::default()
+        };
+
+        let execution = ProbeExecutor::new().execute(context(server.uri(), assertions)).await.unwrap();
+
+        assert!(execution.success, "unexpected failure: {:?}", execution.error_message);
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_probe_fails_when_endpoint_only_lists_models() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "object": "list",
+                "data": [{"id": "probe-model", "object": "model"}]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(500).set_body_json(json!({"object": "error", "message": "model failed to load"})))
+            .mount(&server)
+            .await;
+
+        // A liveness probe against the model list passes...
+        let listing = ProbeExecutionContext {
+            http_method: "GET".to_string(),
+            request_path: Some("/v1/models".to_string()),
+            assertions: None,
+            ..context(server.uri(), ProbeAssertions::default())
+        };
+        let execution = ProbeExecutor::new().execute(listing).await.unwrap();
+        assert!(execution.success, "unexpected failure: {:?}", execution.error_message);
+
+        // ...but a chat completion probe catches that the model can't be served
+        let execution = ProbeExecutor::new()
+            .execute(chat_completion_context(server.uri(), None))
+            .await
+            .unwrap();
+        assert!(!execution.success);
+        assert_eq!(execution.status_code, Some(500));
+        assert_eq!(execution.error_message.as_deref(), Some("HTTP 500 - model failed to load"));
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_probe_validates_completion() {
+        let server = chat_server().await;
+        let execution = ProbeExecutor::new()
+            .execute(chat_completion_context(server.uri(), Some("probe-model")))
+            .await
+            .unwrap();
+        assert!(execution.success, "unexpected failure: {:?}", execution.error_message);
+
+        let execution = ProbeExecutor::new()
+            .execute(chat_completion_context(server.uri(), Some("other-model")))
+            .await
+            .unwrap();
+        assert!(!execution.success);
+        assert_eq!(
+            execution.error_message.as_deref(),
+            Some("Assertion failed: expected model other-model, got probe-model")
+        );
+
+        let empty = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "object": "chat.completion",
+                "model": "probe-model",
+                "choices": [{"index": 0, "message": {"role": "assistant", "content": ""}}]
+            })))
+            .mount(&empty)
+            .await;
+        let execution = ProbeExecutor::new()
+            .execute(chat_completion_context(empty.uri(), None))
+            .await
+            .unwrap();
+        assert!(!execution.success);
+        assert_eq!(
+            execution.metadata,
+            Some(json!({"failed_assertion": {"assertion": "completion_content"}}))
+        );
+    }
+}
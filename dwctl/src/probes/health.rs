@@ -0,0 +1,150 @@
+//! Debounced probe health.
+//!
+//! Rather than taking a probe's health from its latest result, the scheduler
+//! feeds each result through [`next_health`]:
+//!
+//! - A probe's first result sets its health directly.
+//! - A healthy probe turns unhealthy after `failure_threshold` consecutive failures.
+//! - An unhealthy probe turns healthy after `success_threshold` consecutive
+//!   successes, but not until `recovery_cooldown_seconds` have passed since it
+//!   went unhealthy. A flapping model therefore stays unhealthy through the
+//!   cooldown instead of toggling on every result.
+//!
+//! With the defaults (1, 1, 0) health follows the latest result.
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::db::models::probes::{Probe, ProbeHealth};
+
+/// A probe's health transition settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthThresholds {
+    pub failure_threshold: i32,
+    pub success_threshold: i32,
+    pub recovery_cooldown: Duration,
+}
+
+impl From<&Probe> for HealthThresholds {
+    fn from(probe: &Probe) -> Self {
+        Self {
+            failure_threshold: probe.failure_threshold,
+            success_threshold: probe.success_threshold,
+            recovery_cooldown: Duration::seconds(probe.recovery_cooldown_seconds as i64),
+        }
+    }
+}
+
+/// Apply one result to a probe's health.
+pub fn next_health(current: Option<ProbeHealth>, success: bool, thresholds: &HealthThresholds, now: DateTime<Utc>) -> ProbeHealth {
+    let Some(current) = current else {
+        return ProbeHealth {
+            healthy: success,
+            consecutive_successes: success as i32,
+            consecutive_failures: !success as i32,
+            changed_at: now,
+        };
+    };
+
+    let (consecutive_successes, consecutive_failures) = if success {
+        (current.consecutive_successes.saturating_add(1), 0)
+    } else {
+        (0, current.consecutive_failures.saturating_add(1))
+    };
+
+    let healthy = if current.healthy {
+        consecutive_failures < thresholds.failure_threshold
+    } else {
+        consecutive_successes >= thresholds.success_threshold && now - current.changed_at >= thresholds.recovery_cooldown
+    };
+
+    ProbeHealth {
+        healthy,
+        consecutive_successes,
+        consecutive_failures,
+        changed_at: if healthy == current.healthy { current.changed_at } else { now },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thresholds(failure_threshold: i32, success_threshold: i32, cooldown_secs: i64) -> HealthThresholds {
+        HealthThresholds {
+            failure_threshold,
+            success_threshold,
+            recovery_cooldown: Duration::seconds(cooldown_secs),
+        }
+    }
+
+    /// Feed `results` one minute apart, returning the health after each.
+    fn run(thresholds: &HealthThresholds, results: &[bool]) -> Vec<bool> {
+        let start = Utc::now();
+        let mut health = None;
+        results
+            .iter()
+            .enumerate()
+            .map(|(i, &success)| {
+                let next = next_health(health, success, thresholds, start + Duration::minutes(i as i64));
+                health = Some(next);
+                next.healthy
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_default_thresholds_follow_latest_result() {
+        let defaults = thresholds(1, 1, 0);
+        assert_eq!(
+            run(&defaults, &[true, false, true, true, false]),
+            vec![true, false, true, true, false]
+        );
+    }
+
+    #[test]
+    fn test_transitions_at_configured_thresholds() {
+        let t = thresholds(3, 2, 0);
+
+        // Two failures are tolerated; the third marks the probe unhealthy.
+        assert_eq!(run(&t, &[true, false, false, false]), vec![true, true, true, false]);
+
+        // A success in between resets the failure count.
+        assert_eq!(run(&t, &[true, false, false, true, false, false]), vec![true; 6]);
+
+        // Recovery needs two successes in a row.
+        assert_eq!(run(&t, &[false, true, false, true, true]), vec![false, false, false, false, true]);
+    }
+
+    #[test]
+    fn test_cooldown_suppresses_flapping() {
+        // Results arrive a minute apart; the probe must stay unhealthy for 5 minutes.
+        let t = thresholds(1, 1, 300);
+        let flapping = [true, false, true, false, true, true, true, true, true];
+        assert_eq!(
+            run(&t, &flapping),
+            // Unhealthy at minute 1; the successes at minutes 2 and 4-5 fall inside
+            // the cooldown and are ignored; recovery at minute 6.
+            vec![true, false, false, false, false, false, true, true, true]
+        );
+
+        // Without a cooldown the same results toggle health on every flip.
+        assert_eq!(
+            run(&thresholds(1, 1, 0), &flapping),
+            vec![true, false, true, false, true, true, true, true, true]
+        );
+    }
+
+    #[test]
+    fn test_changed_at_only_moves_on_transition() {
+        let t = thresholds(2, 1, 0);
+        let start = Utc::now();
+        let first = next_health(None, true, &t, start);
+        let second = next_health(Some(first), false, &t, start + Duration::minutes(1));
+        assert!(second.healthy);
+        assert_eq!(second.changed_at, start);
+
+        let third = next_health(Some(second), false, &t, start + Duration::minutes(2));
+        assert!(!third.healthy);
+        assert_eq!(third.changed_at, start + Duration::minutes(2));
+    }
+}
@@ -1,5 +1,8 @@
 pub mod db;
 pub mod executor;
+pub mod health;
+pub mod retention;
 pub mod scheduler;
+pub mod warmup;
 
 pub use scheduler::ProbeScheduler;
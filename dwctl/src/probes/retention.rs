@@ -0,0 +1,347 @@
+//! Retention and downsampling of probe results.
+//!
+//! Probes write one row per execution, so `probe_results` grows without bound. This
+//! module runs on the leader alongside the `ProbeScheduler` and periodically:
+//!
+//! 1. Moves raw results older than `raw_retention` into `probe_results_hourly`, one
+//!    row per probe per hour (counts, min/max/avg latency of successful runs, last
+//!    success/failure). Only whole hours are moved, so a bucket is never split
+//!    between raw and aggregated storage.
+//! 2. Deletes hourly aggregates older than `aggregate_retention`.
+//!
+//! `ProbeManager::get_statistics` reads from both tables, so callers see one
+//! continuous history regardless of where the data lives.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::ProbeRetentionConfig;
+use crate::metrics::errors::component::PROBE_RETENTION;
+
+/// Row counts affected by a single retention pass.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionOutcome {
+    /// Raw results folded into hourly aggregates and deleted
+    pub results_downsampled: u64,
+    /// Hourly aggregates deleted for being past aggregate retention
+    pub aggregates_pruned: u64,
+}
+
+/// Fold raw results from hours that end before `cutoff` into hourly aggregates.
+///
+/// The delete and insert run as one statement, so results are never counted twice
+/// or lost. If a bucket already exists (e.g. a late result for an hour that was
+/// already downsampled) the new rows are merged into it.
+pub async fn downsample_probe_results(pool: &PgPool, cutoff: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+    let rows = sqlx::query_scalar!(
+        r#"
+        WITH moved AS (
+            DELETE FROM probe_results
+            WHERE executed_at < date_trunc('hour', $1::timestamptz)
+            RETURNING probe_id, executed_at, success, response_time_ms
+        ),
+        buckets AS (
+            INSERT INTO probe_results_hourly (
+                probe_id, bucket_start, total_count, success_count,
+                min_response_time_ms, max_response_time_ms, avg_response_time_ms,
+                last_execution_at, last_success_at, last_failure_at
+            )
+            SELECT
+                probe_id,
+                date_trunc('hour', executed_at),
+                COUNT(*),
+                COUNT(*) FILTER (WHERE success = true),
+                MIN(response_time_ms) FILTER (WHERE success = true),
+                MAX(response_time_ms) FILTER (WHERE success = true),
+                (AVG(response_time_ms) FILTER (WHERE success = true))::float8,
+                MAX(executed_at),
+                MAX(executed_at) FILTER (WHERE success = true),
+                MAX(executed_at) FILTER (WHERE success = false)
+            FROM moved
+            GROUP BY probe_id, date_trunc('hour', executed_at)
+            ON CONFLICT (probe_id, bucket_start) DO UPDATE SET
+                avg_response_time_ms = CASE
+                    WHEN probe_results_hourly.avg_response_time_ms IS NULL THEN EXCLUDED.avg_response_time_ms
+                    WHEN EXCLUDED.avg_response_time_ms IS NULL THEN probe_results_hourly.avg_response_time_ms
+                    ELSE (probe_results_hourly.avg_response_time_ms * probe_results_hourly.success_count
+                          + EXCLUDED.avg_response_time_ms * EXCLUDED.success_count)
+                         / NULLIF(probe_results_hourly.success_count + EXCLUDED.success_count, 0)
+                END,
+                total_count = probe_results_hourly.total_count + EXCLUDED.total_count,
+                success_count = probe_results_hourly.success_count + EXCLUDED.success_count,
+                min_response_time_ms = LEAST(probe_results_hourly.min_response_time_ms, EXCLUDED.min_response_time_ms),
+                max_response_time_ms = GREATEST(probe_results_hourly.max_response_time_ms, EXCLUDED.max_response_time_ms),
+                last_execution_at = GREATEST(probe_results_hourly.last_execution_at, EXCLUDED.last_execution_at),
+                last_success_at = GREATEST(probe_results_hourly.last_success_at, EXCLUDED.last_success_at),
+                last_failure_at = GREATEST(probe_results_hourly.last_failure_at, EXCLUDED.last_failure_at)
+        )
+        SELECT COUNT(*) as "count!" FROM moved
+        "#,
+        cutoff
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(rows as u64)
+}
+
+/// Delete hourly aggregates for hours that start before `cutoff`.
+pub async fn prune_probe_aggregates(pool: &PgPool, cutoff: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!("DELETE FROM probe_results_hourly WHERE bucket_start < $1", cutoff)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Run one retention pass relative to the current time.
+pub async fn apply_retention(pool: &PgPool, config: &ProbeRetentionConfig) -> anyhow::Result<RetentionOutcome> {
+    let now = Utc::now();
+    let raw_cutoff = now - chrono::Duration::from_std(config.raw_retention)?;
+    let aggregate_cutoff = now - chrono::Duration::from_std(config.aggregate_retention)?;
+
+    Ok(RetentionOutcome {
+        results_downsampled: downsample_probe_results(pool, raw_cutoff).await?,
+        aggregates_pruned: prune_probe_aggregates(pool, aggregate_cutoff).await?,
+    })
+}
+
+/// Run the retention job every `run_interval` until `shutdown` is cancelled.
+///
+/// Only run this on the leader replica; concurrent passes are safe but wasteful.
+pub async fn run_probe_retention(pool: PgPool, config: ProbeRetentionConfig, shutdown: CancellationToken) -> anyhow::Result<()> {
+    tracing::info!(
+        raw_retention = %humantime::format_duration(config.raw_retention),
+        aggregate_retention = %humantime::format_duration(config.aggregate_retention),
+        "Starting probe result retention job"
+    );
+
+    let mut interval = tokio::time::interval(config.run_interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => {
+                tracing::info!("Probe result retention job shutting down");
+                break;
+            }
+            _ = interval.tick() => {
+                match apply_retention(&pool, &config).await {
+                    Ok(outcome) => tracing::debug!(
+                        results_downsampled = outcome.results_downsampled,
+                        aggregates_pruned = outcome.aggregates_pruned,
+                        "Applied probe result retention"
+                    ),
+                    Err(e) => {
+                        crate::background_error!(PROBE_RETENTION, "apply", Warning, error = %e, "Failed to apply probe result retention");
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::models::probes::CreateProbe;
+    use crate::probes::db::ProbeManager;
+    use chrono::TimeZone;
+    use uuid::Uuid;
+
+    async fn create_probe(pool: &PgPool) -> Uuid {
+        let endpoint_id = sqlx::query_scalar!(
+            "INSERT INTO inference_endpoints (name, url, created_by) VALUES ($1, $2, $3) RETURNING id",
+            format!("retention-endpoint-{}", Uuid::new_v4()),
+            "http://localhost:8080",
+            Uuid::nil()
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap();
+
+        let model_name = format!("retention-model-{}", Uuid::new_v4());
+        let deployment_id = sqlx::query_scalar!(
+            "INSERT INTO deployed_models (model_name, alias, type, hosted_on, created_by) VALUES ($1, $2, $3, $4, $5) RETURNING id",
+            model_name.clone(),
+            model_name,
+            "chat" as _,
+            endpoint_id,
+            Uuid::nil()
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap();
+
+        ProbeManager::create_probe(
+            pool,
+            CreateProbe {
+                name: "Retention Probe".to_string(),
+                deployment_id,
+                interval_seconds: 60,
+                http_method: "POST".to_string(),
+                request_path: None,
+                request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
+            },
+        )
+        .await
+        .unwrap()
+        .id
+    }
+
+    async fn insert_result(pool: &PgPool, probe_id: Uuid, executed_at: DateTime<Utc>, success: bool, response_time_ms: i32) {
+        sqlx::query!(
+            "INSERT INTO probe_results (probe_id, executed_at, success, response_time_ms) VALUES ($1, $2, $3, $4)",
+            probe_id,
+            executed_at,
+            success,
+            response_time_ms
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[sqlx::test]
+    async fn test_downsampling_produces_hourly_aggregates(pool: PgPool) {
+        let probe_id = create_probe(&pool).await;
+
+        let hour_a = Utc.with_ymd_and_hms(2026, 1, 1, 10, 0, 0).unwrap();
+        let hour_b = Utc.with_ymd_and_hms(2026, 1, 1, 11, 0, 0).unwrap();
+        let recent = Utc::now();
+
+        // Hour A: three successes and a failure
+        insert_result(&pool, probe_id, hour_a + chrono::Duration::minutes(5), true, 100).await;
+        insert_result(&pool, probe_id, hour_a + chrono::Duration::minutes(20), true, 200).await;
+        insert_result(&pool, probe_id, hour_a + chrono::Duration::minutes(35), true, 300).await;
+        insert_result(&pool, probe_id, hour_a + chrono::Duration::minutes(50), false, 5000).await;
+        // Hour B: failures only
+        insert_result(&pool, probe_id, hour_b + chrono::Duration::minutes(10), false, 30000).await;
+        // Inside the raw retention window - must be left alone
+        insert_result(&pool, probe_id, recent, true, 50).await;
+
+        let stats_before = ProbeManager::get_statistics(&pool, probe_id, None, None).await.unwrap();
+
+        let moved = downsample_probe_results(&pool, Utc::now() - chrono::Duration::days(7))
+            .await
+            .unwrap();
+        assert_eq!(moved, 5);
+
+        let remaining: i64 = sqlx::query_scalar!(r#"SELECT COUNT(*) as "count!" FROM probe_results WHERE probe_id = $1"#, probe_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, 1, "only the recent result should stay raw");
+
+        let buckets = sqlx::query!(
+            r#"
+            SELECT bucket_start, total_count, success_count, min_response_time_ms, max_response_time_ms,
+                   avg_response_time_ms, last_success_at, last_failure_at
+            FROM probe_results_hourly WHERE probe_id = $1 ORDER BY bucket_start
+            "#,
+            probe_id
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(buckets.len(), 2);
+
+        let a = &buckets[0];
+        assert_eq!(a.bucket_start, hour_a);
+        assert_eq!(a.total_count, 4);
+        assert_eq!(a.success_count, 3);
+        assert_eq!(a.min_response_time_ms, Some(100));
+        assert_eq!(a.max_response_time_ms, Some(300));
+        assert_eq!(a.avg_response_time_ms, Some(200.0));
+        assert_eq!(a.last_success_at, Some(hour_a + chrono::Duration::minutes(35)));
+        assert_eq!(a.last_failure_at, Some(hour_a + chrono::Duration::minutes(50)));
+
+        let b = &buckets[1];
+        assert_eq!(b.bucket_start, hour_b);
+        assert_eq!(b.total_count, 1);
+        assert_eq!(b.success_count, 0);
+        assert_eq!(b.min_response_time_ms, None);
+        assert_eq!(b.avg_response_time_ms, None);
+        assert_eq!(b.last_success_at, None);
+
+        // Statistics over the full range are unchanged by downsampling (bar percentiles)
+        let stats_after = ProbeManager::get_statistics(&pool, probe_id, None, None).await.unwrap();
+        assert_eq!(stats_after.total_executions, stats_before.total_executions);
+        assert_eq!(stats_after.successful_executions, 4);
+        assert_eq!(stats_after.failed_executions, 2);
+        assert_eq!(stats_after.success_rate, stats_before.success_rate);
+        assert_eq!(stats_after.avg_response_time_ms, stats_before.avg_response_time_ms);
+        assert_eq!(stats_after.min_response_time_ms, Some(50));
+        assert_eq!(stats_after.max_response_time_ms, Some(300));
+        assert_eq!(stats_after.last_failure, stats_before.last_failure);
+
+        // A range covering only hour A reads from the aggregates alone
+        let hour_a_stats = ProbeManager::get_statistics(&pool, probe_id, Some(hour_a), Some(hour_a + chrono::Duration::minutes(59)))
+            .await
+            .unwrap();
+        assert_eq!(hour_a_stats.total_executions, 4);
+        assert_eq!(hour_a_stats.success_rate, 75.0);
+        assert_eq!(hour_a_stats.avg_response_time_ms, Some(200.0));
+
+        // Running again is a no-op
+        assert_eq!(
+            downsample_probe_results(&pool, Utc::now() - chrono::Duration::days(7))
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_late_results_merge_into_existing_bucket(pool: PgPool) {
+        let probe_id = create_probe(&pool).await;
+        let hour = Utc.with_ymd_and_hms(2026, 1, 1, 10, 0, 0).unwrap();
+        let cutoff = Utc::now() - chrono::Duration::days(7);
+
+        insert_result(&pool, probe_id, hour + chrono::Duration::minutes(1), true, 100).await;
+        downsample_probe_results(&pool, cutoff).await.unwrap();
+
+        insert_result(&pool, probe_id, hour + chrono::Duration::minutes(2), true, 300).await;
+        insert_result(&pool, probe_id, hour + chrono::Duration::minutes(3), false, 0).await;
+        downsample_probe_results(&pool, cutoff).await.unwrap();
+
+        let bucket = sqlx::query!(
+            "SELECT total_count, success_count, min_response_time_ms, max_response_time_ms, avg_response_time_ms FROM probe_results_hourly WHERE probe_id = $1",
+            probe_id
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(bucket.total_count, 3);
+        assert_eq!(bucket.success_count, 2);
+        assert_eq!(bucket.min_response_time_ms, Some(100));
+        assert_eq!(bucket.max_response_time_ms, Some(300));
+        assert_eq!(bucket.avg_response_time_ms, Some(200.0));
+    }
+
+    #[sqlx::test]
+    async fn test_prune_drops_expired_aggregates(pool: PgPool) {
+        let probe_id = create_probe(&pool).await;
+        let old = Utc::now() - chrono::Duration::days(400);
+        let kept = Utc::now() - chrono::Duration::days(30);
+
+        insert_result(&pool, probe_id, old, true, 100).await;
+        insert_result(&pool, probe_id, kept, true, 100).await;
+
+        let outcome = apply_retention(&pool, &ProbeRetentionConfig::default()).await.unwrap();
+        assert_eq!(outcome.results_downsampled, 2);
+        assert_eq!(outcome.aggregates_pruned, 1);
+
+        let stats = ProbeManager::get_statistics(&pool, probe_id, None, None).await.unwrap();
+        assert_eq!(stats.total_executions, 1);
+    }
+}
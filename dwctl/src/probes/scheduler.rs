@@ -170,6 +170,27 @@ impl ProbeScheduler {
                         } else {
                             tracing::warn!("Probe {} execution failed: {:?}", probe.name, result.error_message);
                         }
+
+                        match ProbeManager::record_health(&pool, &probe, &result).await {
+                            Ok(health) if health.changed_at == result.executed_at => {
+                                tracing::info!(
+                                    "Probe {} is now {}",
+                                    probe.name,
+                                    if health.healthy { "healthy" } else { "unhealthy" }
+                                );
+                            }
+                            Ok(_) => {}
+                            Err(e) => {
+                                crate::background_error!(
+                                    PROBE_SCHEDULER,
+                                    "probe_health",
+                                    Warning,
+                                    "Error recording health for probe {}: {}",
+                                    probe.name,
+                                    e
+                                );
+                            }
+                        }
                     }
                     Err(e) => {
                         crate::background_error!(
@@ -507,6 +528,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -521,6 +548,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -558,6 +591,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -583,6 +622,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
@@ -616,6 +661,12 @@ mod tests {
                     http_method: "POST".to_string(),
                     request_path: None,
                     request_body: None,
+                    assertions: None,
+                    probe_type: Default::default(),
+                    expected_model: None,
+                    failure_threshold: None,
+                    success_threshold: None,
+                    recovery_cooldown_seconds: None,
                 },
             )
             .await
@@ -647,6 +698,12 @@ mod tests {
                 http_method: "POST".to_string(),
                 request_path: None,
                 request_body: None,
+                assertions: None,
+                probe_type: Default::default(),
+                expected_model: None,
+                failure_threshold: None,
+                success_threshold: None,
+                recovery_cooldown_seconds: None,
             },
         )
         .await
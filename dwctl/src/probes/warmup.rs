@@ -0,0 +1,267 @@
+//! Warmup requests for deployments with `warmup` set.
+//!
+//! A cold deployment pays for connection setup (and, on providers that scale
+//! to zero, model loading) on its first real request. The leader sends each
+//! active deployment with `warmup` set a minimal request, the same default
+//! payload as a liveness probe, through the control layer's own AI proxy with
+//! the system API key: once shortly after the deployment is first seen active
+//! (created, reactivated by a sync, or opted in), and again every
+//! `interval` if one is configured.
+//!
+//! Which deployments have been warmed is kept in memory, so a new leader warms
+//! every active deployment once. Requests are spaced by `min_request_gap` so a
+//! leader change or a large sync doesn't send a burst. A failed warmup is
+//! logged and not retried until the deployment is due again; it never affects
+//! the deployment itself.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use sqlx::PgPool;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use crate::config::DeploymentWarmupConfig;
+use crate::db::models::deployments::ModelType;
+use crate::db::models::probes::ProbeType;
+use crate::metrics::errors::component::DEPLOYMENT_WARMUP;
+use crate::probes::executor::{ProbeExecutionContext, ProbeExecutor};
+use crate::types::DeploymentId;
+
+/// A deployment to warm, with the key its request is sent with
+struct WarmupTarget {
+    id: DeploymentId,
+    alias: String,
+    model_type: Option<String>,
+    system_api_key: String,
+}
+
+/// When this leader first saw a deployment active, and when it last warmed it
+struct WarmupState {
+    first_seen: Instant,
+    last_warmed: Option<Instant>,
+}
+
+/// Sends warmup requests to deployments as they become due.
+pub struct DeploymentWarmer {
+    pool: PgPool,
+    config: DeploymentWarmupConfig,
+    /// Base URL of the AI proxy the requests are sent through
+    proxy_url: String,
+    executor: ProbeExecutor,
+    seen: HashMap<DeploymentId, WarmupState>,
+}
+
+impl DeploymentWarmer {
+    pub fn new(pool: PgPool, config: DeploymentWarmupConfig, proxy_url: String) -> Self {
+        Self {
+            pool,
+            config,
+            proxy_url,
+            executor: ProbeExecutor::new(),
+            seen: HashMap::new(),
+        }
+    }
+
+    /// Warm every deployment that is due, returning the IDs of those a request was sent to.
+    pub async fn warm_due(&mut self) -> anyhow::Result<Vec<DeploymentId>> {
+        let targets = sqlx::query_as!(
+            WarmupTarget,
+            r#"
+            SELECT d.id, d.alias, d.type as model_type, ak.secret as system_api_key
+            FROM deployed_models d
+            CROSS JOIN api_keys ak
+            WHERE d.warmup AND d.status = 'active' AND d.deleted = false
+              AND ak.id = '00000000-0000-0000-0000-000000000000'::uuid
+            ORDER BY d.created_at
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        // Forget deployments that are no longer active, so they are warmed
+        // again if they come back
+        self.seen.retain(|id, _| targets.iter().any(|t| t.id == *id));
+
+        let mut warmed = Vec::new();
+        for target in targets {
+            let now = Instant::now();
+            let state = self.seen.entry(target.id).or_insert(WarmupState {
+                first_seen: now,
+                last_warmed: None,
+            });
+            let due = match state.last_warmed {
+                None => now.duration_since(state.first_seen) >= self.config.activation_delay,
+                Some(last) => self.config.interval.is_some_and(|interval| now.duration_since(last) >= interval),
+            };
+            if !due {
+                continue;
+            }
+            state.last_warmed = Some(now);
+
+            if !warmed.is_empty() {
+                tokio::time::sleep(self.config.min_request_gap).await;
+            }
+            self.warm(&target).await;
+            warmed.push(target.id);
+        }
+
+        Ok(warmed)
+    }
+
+    /// Send one warmup request and log its outcome.
+    async fn warm(&self, target: &WarmupTarget) {
+        let model_type = target
+            .model_type
+            .as_deref()
+            .and_then(|t| match t.to_uppercase().as_str() {
+                "CHAT" => Some(ModelType::Chat),
+                "EMBEDDINGS" => Some(ModelType::Embeddings),
+                "RERANKER" => Some(ModelType::Reranker),
+                "MODERATION" => Some(ModelType::Moderation),
+                _ => None,
+            })
+            .unwrap_or_else(|| ModelType::detect_from_name(&target.alias));
+
+        let context = ProbeExecutionContext {
+            probe_id: Uuid::nil(),
+            model_name: target.alias.clone(),
+            model_type,
+            endpoint_url: self.proxy_url.clone(),
+            api_key: Some(target.system_api_key.clone()),
+            http_method: "POST".to_string(),
+            request_path: None,
+            request_body: None,
+            assertions: None,
+            probe_type: ProbeType::Liveness,
+            expected_model: None,
+        };
+
+        match self.executor.execute(context).await {
+            Ok(execution) if execution.success => {
+                tracing::info!(
+                    deployment_id = %target.id,
+                    alias = %target.alias,
+                    response_time_ms = execution.response_time_ms,
+                    "Warmed deployment"
+                );
+            }
+            Ok(execution) => {
+                tracing::warn!(
+                    deployment_id = %target.id,
+                    alias = %target.alias,
+                    status_code = execution.status_code,
+                    error = execution.error_message.as_deref().unwrap_or_default(),
+                    "Deployment warmup request failed"
+                );
+            }
+            Err(e) => {
+                tracing::warn!(deployment_id = %target.id, alias = %target.alias, error = %e, "Deployment warmup request failed");
+            }
+        }
+    }
+}
+
+/// Check for deployments to warm every `check_interval` until `shutdown` is cancelled.
+///
+/// Only run this on the leader replica, so that each deployment is warmed once.
+pub async fn run_deployment_warmup(
+    pool: PgPool,
+    config: DeploymentWarmupConfig,
+    proxy_url: String,
+    shutdown: CancellationToken,
+) -> anyhow::Result<()> {
+    tracing::info!(
+        check_interval = %humantime::format_duration(config.check_interval),
+        "Starting deployment warmup job"
+    );
+
+    let mut interval = tokio::time::interval(config.check_interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let mut warmer = DeploymentWarmer::new(pool, config, proxy_url);
+
+    loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => {
+                tracing::info!("Deployment warmup job shutting down");
+                break;
+            }
+            _ = interval.tick() => {
+                if let Err(e) = warmer.warm_due().await {
+                    crate::background_error!(DEPLOYMENT_WARMUP, "query", Warning, error = %e, "Failed to find deployments to warm");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::models::users::Role;
+    use crate::test::utils::{create_test_deployment, create_test_user};
+    use std::time::Duration;
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn set_deployment(pool: &PgPool, id: DeploymentId, warmup: bool, status: &str) {
+        sqlx::query!(
+            "UPDATE deployed_models SET warmup = $2, status = $3 WHERE id = $1",
+            id,
+            warmup,
+            status
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[sqlx::test]
+    async fn test_warmup_sent_to_newly_activated_deployment(pool: PgPool) {
+        let proxy = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(body_partial_json(serde_json::json!({"model": "warm-model"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"object": "chat.completion"})))
+            .mount(&proxy)
+            .await;
+
+        let user = create_test_user(&pool, Role::PlatformManager).await;
+        let deployment = create_test_deployment(&pool, user.id, "warm-model", "warm-model").await;
+        let cold = create_test_deployment(&pool, user.id, "cold-model", "cold-model").await;
+        set_deployment(&pool, deployment.id, true, "inactive").await;
+
+        let config = DeploymentWarmupConfig {
+            activation_delay: Duration::ZERO,
+            min_request_gap: Duration::ZERO,
+            ..Default::default()
+        };
+        let mut warmer = DeploymentWarmer::new(pool.clone(), config, proxy.uri());
+
+        // Inactive deployments and those without warmup are left alone
+        assert!(warmer.warm_due().await.unwrap().is_empty());
+
+        // Activating the deployment warms it once
+        set_deployment(&pool, deployment.id, true, "active").await;
+        assert_eq!(warmer.warm_due().await.unwrap(), vec![deployment.id]);
+        assert!(warmer.warm_due().await.unwrap().is_empty());
+
+        let requests = proxy.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 1);
+        let auth = requests[0].headers.get("authorization").unwrap().to_str().unwrap();
+        assert!(auth.starts_with("Bearer "), "warmup must use the system API key");
+
+        // Deactivating and reactivating warms it again
+        set_deployment(&pool, deployment.id, true, "inactive").await;
+        assert!(warmer.warm_due().await.unwrap().is_empty());
+        set_deployment(&pool, deployment.id, true, "active").await;
+        assert_eq!(warmer.warm_due().await.unwrap(), vec![deployment.id]);
+        assert_eq!(proxy.received_requests().await.unwrap().len(), 2);
+
+        // A failing warmup is logged, not propagated
+        set_deployment(&pool, cold.id, true, "active").await;
+        assert_eq!(warmer.warm_due().await.unwrap(), vec![cold.id]);
+    }
+}
@@ -49,8 +49,10 @@ use crate::metrics::errors::component::ANALYTICS;
 use crate::request_logging::AiResponse;
 use crate::request_logging::batcher::{AnalyticsSender, RawAnalyticsRecord};
 use crate::request_logging::serializers::{Auth, UsageMetrics, parse_ai_response};
-use crate::request_logging::utils::{extract_header_as_string, extract_header_as_uuid};
+use crate::request_logging::token_estimate;
+use crate::request_logging::utils::{decompress_response_if_needed, extract_header_as_string, extract_header_as_uuid};
 use axum::http::Uri;
+use metrics::counter;
 use outlet::{RequestData, RequestHandler, ResponseData};
 use serde_json::Value;
 use tracing::{Instrument, info_span};
@@ -69,6 +71,19 @@ fn zdr_safe_parse_error(err: &(dyn std::error::Error + Send + Sync + 'static)) -
     }
 }
 
+/// Rough token counts for a response whose usage couldn't be parsed, from the
+/// sizes of the request body and (decompressed) response body.
+fn estimate_tokens(request_data: &RequestData, response_data: &ResponseData) -> (i64, i64) {
+    let prompt_bytes = request_data.body.as_ref().map_or(0, |body| body.len());
+    let completion_bytes = response_data.body.as_ref().map_or(0, |body| {
+        decompress_response_if_needed(body.as_ref(), &response_data.headers).map_or(body.len(), |decoded| decoded.len())
+    });
+    (
+        token_estimate::tokens_from_bytes(prompt_bytes) as i64,
+        token_estimate::tokens_from_bytes(completion_bytes) as i64,
+    )
+}
+
 /// A request handler that sends analytics data to a background batcher.
 ///
 /// This handler implements [`outlet::RequestHandler`] and can be used standalone or composed
@@ -107,6 +122,8 @@ impl AnalyticsHandler {
             path if path.ends_with("/v1/chat/completions") || path.ends_with("/chat/completions") => Some("/v1/chat/completions"),
             path if path.ends_with("/v1/completions") || path.ends_with("/completions") => Some("/v1/completions"),
             path if path.ends_with("/v1/responses") || path.ends_with("/responses") => Some("/v1/responses"),
+            path if path.ends_with("/rerank") => Some("/v1/rerank"),
+            path if path.ends_with("/moderations") => Some("/v1/moderations"),
             _ => None,
         }
     }
@@ -178,7 +195,24 @@ impl RequestHandler for AnalyticsHandler {
             };
 
             // Extract basic metrics - captures status_code, duration, model from request, etc.
-            let metrics = UsageMetrics::extract(self.instance_id, &request_data, &response_data, &metrics_response, &self.config);
+            let mut metrics = UsageMetrics::extract(self.instance_id, &request_data, &response_data, &metrics_response, &self.config);
+
+            // A successful response we couldn't parse still gets logged, flagged so the
+            // missing (or estimated) usage can be found and reconciled later.
+            let parse_failure = parse_result.is_err() && response_data.status.is_success();
+            if parse_failure {
+                counter!(
+                    "dwctl_analytics_parse_failures_total",
+                    "model" => metrics.request_model.clone().unwrap_or_default()
+                )
+                .increment(1);
+                if self.config.analytics.estimate_tokens_on_parse_failure && metrics.total_tokens == 0 {
+                    let (prompt_tokens, completion_tokens) = estimate_tokens(&request_data, &response_data);
+                    metrics.prompt_tokens = prompt_tokens;
+                    metrics.completion_tokens = completion_tokens;
+                    metrics.total_tokens = prompt_tokens + completion_tokens;
+                }
+            }
 
             // Gate on the (possibly reclassified) status from metrics, not the raw upstream
             // status — streams that opened 200 but ended with an embedded error frame have
@@ -254,6 +288,8 @@ impl RequestHandler for AnalyticsHandler {
                 batch_created_at,
                 batch_request_source,
                 trace_id: request_data.trace_id.clone(),
+                request_id: extract_header_as_string(&request_data, crate::REQUEST_ID_HEADER),
+                parse_failure,
             };
 
             // Send to batcher (non-blocking, just puts in channel)
@@ -393,5 +429,61 @@ mod tests {
         assert_eq!(record.correlation_id, 123);
         assert_eq!(record.method, "POST");
         assert!(record.uri.contains("chat/completions"));
+        assert!(!record.parse_failure);
+    }
+
+    #[tokio::test]
+    async fn test_truncated_response_is_logged_with_parse_failure() {
+        let recorder = metrics_exporter_prometheus::PrometheusBuilder::new().build_recorder();
+        let metrics_handle = recorder.handle();
+        // The test runtime is single-threaded, so the handler records into this recorder.
+        let _guard = metrics::set_default_local_recorder(&recorder);
+
+        let request_body = r#"{"model":"truncated-model","messages":[{"role":"user","content":"Hello there"}]}"#;
+        let response_body = r#"{"id":"chatcmpl-1","object":"chat.completion","model":"truncated-model","choices":[{"index":0,"message":{"role":"assistant","content":"Hi! How can I he"#;
+        let request_data = || RequestData {
+            body: Some(axum::body::Bytes::from(request_body)),
+            ..create_test_request_data()
+        };
+        let response_data = || ResponseData {
+            body: Some(axum::body::Bytes::from(response_body)),
+            ..create_test_response_data()
+        };
+
+        // By default the request is logged with zero tokens
+        let (tx, mut rx) = mpsc::channel::<RawAnalyticsRecord>(100);
+        let handler = AnalyticsHandler::new(tx, Uuid::new_v4(), Config::default());
+        handler.handle_response(request_data(), response_data()).await;
+
+        let record = rx.try_recv().expect("Should have received a record");
+        assert!(record.parse_failure);
+        assert_eq!(record.status_code, 200);
+        assert_eq!(record.duration_ms, 100);
+        assert_eq!(record.request_model.as_deref(), Some("truncated-model"));
+        assert_eq!(record.total_tokens, 0);
+
+        let rendered = metrics_handle.render();
+        assert!(
+            rendered.contains(r#"dwctl_analytics_parse_failures_total{model="truncated-model"} 1"#),
+            "missing parse failure metric:\n{rendered}"
+        );
+
+        // With estimation enabled, tokens are estimated from the body sizes
+        let mut config = Config::default();
+        config.analytics.estimate_tokens_on_parse_failure = true;
+        let (tx, mut rx) = mpsc::channel::<RawAnalyticsRecord>(100);
+        let handler = AnalyticsHandler::new(tx, Uuid::new_v4(), config);
+        handler.handle_response(request_data(), response_data()).await;
+
+        let record = rx.try_recv().expect("Should have received a record");
+        assert!(record.parse_failure);
+        assert_eq!(record.prompt_tokens, (request_body.len() / 4) as i64);
+        assert_eq!(record.completion_tokens, (response_body.len() / 4) as i64);
+        assert_eq!(record.total_tokens, record.prompt_tokens + record.completion_tokens);
+        assert!(
+            metrics_handle
+                .render()
+                .contains(r#"dwctl_analytics_parse_failures_total{model="truncated-model"} 2"#)
+        );
     }
 }
@@ -24,6 +24,10 @@
 //!                                              Phase 3: Record metrics
 //! ```
 //!
+//! When an external sink is configured, each enriched record is also
+//! published to it as an [`AnalyticsEvent`] before the write (see
+//! [`super::sink`]).
+//!
 //! # Key Design Decisions
 //!
 //! - **All DB work in batcher**: The handler sends unenriched `RawAnalyticsRecord`s.
@@ -39,6 +43,7 @@ use crate::db::models::api_keys::ApiKeyPurpose;
 use crate::metrics::MetricsRecorder;
 use crate::metrics::errors::component::ANALYTICS_BATCHER;
 use crate::request_logging::serializers::HttpAnalyticsRow;
+use crate::request_logging::sink::{AnalyticsEvent, AnalyticsSink};
 use chrono::{DateTime, Utc};
 use metrics::{counter, histogram};
 use rust_decimal::Decimal;
@@ -109,6 +114,12 @@ pub struct RawAnalyticsRecord {
     // === Tracing ===
     /// OpenTelemetry trace ID for correlation with Tempo
     pub trace_id: Option<String>,
+    /// The `X-Request-Id` returned to the client (supplied by it or generated)
+    pub request_id: Option<String>,
+
+    /// A successful response whose body couldn't be parsed; its tokens are
+    /// zero or estimated (see `AnalyticsConfig::estimate_tokens_on_parse_failure`)
+    pub parse_failure: bool,
 }
 
 /// Enriched data resolved during batch processing
@@ -328,10 +339,16 @@ where
     batch_size: usize,
     max_retries: u32,
     retry_base_delay: std::time::Duration,
+    /// Fractions of successful (2xx) and other requests whose `http_analytics`
+    /// row is written. Billing covers every request regardless.
+    success_sample_rate: f64,
+    error_sample_rate: f64,
     /// In-process wake signal for the usage-refresh daemon. Nudged after every
     /// successful batch write so the daemon folds the just-written rows into
     /// `user_model_usage_daily`. `None` disables the nudge (tests, refresh daemon off).
     usage_refresh_notify: Option<Arc<Notify>>,
+    /// External destination that also receives every enriched record
+    sink: Option<AnalyticsSink>,
 }
 
 impl<M> AnalyticsBatcher<M>
@@ -364,7 +381,10 @@ where
             batch_size,
             max_retries,
             retry_base_delay,
+            success_sample_rate: config.analytics.success_sample_rate,
+            error_sample_rate: config.analytics.error_sample_rate,
             usage_refresh_notify: None,
+            sink: None,
         };
 
         (batcher, sender)
@@ -379,6 +399,14 @@ where
         self
     }
 
+    /// Publish every enriched record to `sink`, whether or not it's sampled
+    /// into `http_analytics`. Off by default.
+    #[must_use]
+    pub fn with_sink(mut self, sink: AnalyticsSink) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+
     /// Runs the batcher's background write loop.
     ///
     /// This should be spawned as a tokio task. The strategy is:
@@ -479,6 +507,13 @@ where
                 }
             };
 
+            // Publish to the sink first, so a slow or failing database doesn't hold events back
+            if let Some(sink) = &self.sink {
+                for record in &enriched {
+                    sink.publish(Self::enriched_to_event(record));
+                }
+            }
+
             // Phase 2: Transactional write with retry
             let mut last_error = None;
             for attempt in 0..=self.max_retries {
@@ -648,7 +683,7 @@ where
             let (provider_name, input_price, output_price) = if let Some(ref model_alias) = raw.request_model {
                 if let Some(model_info) = model_map.get(model_alias) {
                     // Find best matching tariff
-                    let (input, output) = self.find_best_tariff(
+                    let (input, output) = find_best_tariff(
                         &model_info.tariffs,
                         api_key_purpose.as_ref(),
                         raw.batch_completion_window.as_deref(),
@@ -814,15 +849,12 @@ where
                 tariffs: Vec::new(),
             });
 
-            // Add tariff if present
-            if let (Some(purpose), Some(valid_from), Some(input_price), Some(output_price)) = (
-                row.tariff_purpose,
-                row.tariff_valid_from,
-                row.tariff_input_price,
-                row.tariff_output_price,
-            ) {
+            // Add tariff if present (a tariff without a purpose is the model's default)
+            if let (Some(valid_from), Some(input_price), Some(output_price)) =
+                (row.tariff_valid_from, row.tariff_input_price, row.tariff_output_price)
+            {
                 entry.tariffs.push(TariffInfo {
-                    purpose: parse_api_key_purpose(&purpose),
+                    purpose: row.tariff_purpose.as_deref().map(parse_api_key_purpose),
                     effective_from: valid_from,
                     valid_until: row.tariff_valid_until,
                     input_price_per_token: input_price,
@@ -892,56 +924,19 @@ where
         Ok(map)
     }
 
-    /// Find the best matching tariff for a record.
-    ///
-    /// Implements fallback logic:
-    /// 1. Try exact match (purpose + completion_window + timestamp)
-    /// 2. Fall back to generic tariff for that purpose (completion_window = None)
-    /// 3. Fall back to realtime purpose (generic)
-    fn find_best_tariff(
-        &self,
-        tariffs: &[TariffInfo],
-        api_key_purpose: Option<&ApiKeyPurpose>,
-        completion_window: Option<&str>,
-        timestamp: DateTime<Utc>,
-    ) -> (Option<Decimal>, Option<Decimal>) {
-        let purpose = api_key_purpose.unwrap_or(&ApiKeyPurpose::Realtime);
-
-        // Filter tariffs valid at timestamp:
-        // effective_from <= timestamp AND (valid_until IS NULL OR valid_until > timestamp)
-        let valid_tariffs: Vec<_> = tariffs
-            .iter()
-            .filter(|t| t.effective_from <= timestamp && t.valid_until.is_none_or(|valid_until| valid_until > timestamp))
-            .collect();
-
-        // Try exact match with completion_window (for batch tariffs with specific priority)
-        if let Some(cw) = completion_window
-            && let Some(tariff) = valid_tariffs
-                .iter()
-                .find(|t| &t.purpose == purpose && t.completion_window.as_deref() == Some(cw))
-        {
-            return (Some(tariff.input_price_per_token), Some(tariff.output_price_per_token));
+    /// Whether a request's `http_analytics` row is written, per the configured
+    /// sampling rates. Batched requests are always logged: batch progress and
+    /// totals are folded from their rows.
+    fn is_logged(&self, raw: &RawAnalyticsRecord) -> bool {
+        if raw.fusillade_batch_id.is_some() {
+            return true;
         }
-
-        // Try generic tariff for this purpose (completion_window = None)
-        // This ensures we don't accidentally match a different priority tier
-        if let Some(tariff) = valid_tariffs
-            .iter()
-            .find(|t| &t.purpose == purpose && t.completion_window.is_none())
-        {
-            return (Some(tariff.input_price_per_token), Some(tariff.output_price_per_token));
-        }
-
-        // Fall back to generic realtime tariff
-        if purpose != &ApiKeyPurpose::Realtime
-            && let Some(tariff) = valid_tariffs
-                .iter()
-                .find(|t| t.purpose == ApiKeyPurpose::Realtime && t.completion_window.is_none())
-        {
-            return (Some(tariff.input_price_per_token), Some(tariff.output_price_per_token));
-        }
-
-        (None, None)
+        let rate = if (200..=299).contains(&raw.status_code) {
+            self.success_sample_rate
+        } else {
+            self.error_sample_rate
+        };
+        rate >= 1.0 || sample_point(raw.instance_id, raw.correlation_id) < rate
     }
 
     /// Write enriched records to the database in a single transaction.
@@ -949,8 +944,13 @@ where
     async fn write_batch_transactional(&self, records: &[EnrichedRecord]) -> Result<(), sqlx::Error> {
         let mut tx = self.pool.begin().await?;
 
-        // Phase 1: Batch INSERT http_analytics
-        let (analytics_ids, newly_inserted) = self.batch_insert_analytics(&mut tx, records).await?;
+        // Phase 1: Batch INSERT http_analytics for the requests sampled in
+        let logged: Vec<&EnrichedRecord> = records.iter().filter(|r| self.is_logged(&r.raw)).collect();
+        let skipped = records.len() - logged.len();
+        if skipped > 0 {
+            counter!("dwctl_analytics_unsampled_records_total").increment(skipped as u64);
+        }
+        let (analytics_ids, newly_inserted) = self.batch_insert_analytics(&mut tx, &logged).await?;
 
         // Phase 2: Batch INSERT credit_transactions (+ fold batch_aggregates)
         let duplicates = self.batch_insert_credits(&mut tx, records, &analytics_ids, &newly_inserted).await?;
@@ -974,7 +974,7 @@ where
     async fn batch_insert_analytics(
         &self,
         tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
-        records: &[EnrichedRecord],
+        records: &[&EnrichedRecord],
     ) -> Result<(HashMap<(Uuid, i64), i64>, HashSet<i64>), sqlx::Error> {
         if records.is_empty() {
             return Ok((HashMap::new(), HashSet::new()));
@@ -1008,6 +1008,7 @@ where
 
         let mut api_key_ids: Vec<Option<Uuid>> = Vec::with_capacity(records.len());
         let mut trace_ids: Vec<Option<String>> = Vec::with_capacity(records.len());
+        let mut request_ids: Vec<Option<String>> = Vec::with_capacity(records.len());
         let mut cache_read_vec: Vec<i64> = Vec::with_capacity(records.len());
         let mut cache_creation_total_vec: Vec<i64> = Vec::with_capacity(records.len());
         let mut cache_5m_vec: Vec<i64> = Vec::with_capacity(records.len());
@@ -1016,6 +1017,7 @@ where
         let mut total_cost_vec: Vec<Option<Decimal>> = Vec::with_capacity(records.len());
         let mut uncached_cost_vec: Vec<Option<Decimal>> = Vec::with_capacity(records.len());
         let mut served_by_vec: Vec<Option<String>> = Vec::with_capacity(records.len());
+        let mut parse_failures: Vec<bool> = Vec::with_capacity(records.len());
 
         for record in records {
             instance_ids.push(record.raw.instance_id);
@@ -1048,6 +1050,7 @@ where
 
             api_key_ids.push(record.api_key_id);
             trace_ids.push(record.raw.trace_id.clone());
+            request_ids.push(record.raw.request_id.clone());
 
             let c5 = record.raw.cache_creation_5m_input_tokens;
             let c1 = record.raw.cache_creation_1h_input_tokens;
@@ -1061,6 +1064,7 @@ where
             total_cost_vec.push(record.total_cost);
             uncached_cost_vec.push(record.uncached_cost);
             served_by_vec.push(record.raw.served_by.clone());
+            parse_failures.push(record.raw.parse_failure);
         }
 
         let rows = sqlx::query!(
@@ -1073,7 +1077,7 @@ where
                 request_origin, batch_sla, batch_request_source, api_key_id, trace_id,
                 cache_read_input_tokens, cache_creation_input_tokens,
                 cache_creation_5m_input_tokens, cache_creation_1h_input_tokens, cache_creation_24h_input_tokens,
-                total_cost, uncached_cost, served_by
+                total_cost, uncached_cost, served_by, parse_failure, request_id
             )
             SELECT * FROM UNNEST(
                 $1::uuid[], $2::bigint[], $3::timestamptz[], $4::text[], $5::text[], $6::text[],
@@ -1083,7 +1087,7 @@ where
                 $22::text[], $23::text[], $24::text[], $25::uuid[], $26::text[],
                 $27::bigint[], $28::bigint[],
                 $29::bigint[], $30::bigint[], $31::bigint[],
-                $32::numeric[], $33::numeric[], $34::text[]
+                $32::numeric[], $33::numeric[], $34::text[], $35::bool[], $36::text[]
             )
             ON CONFLICT (instance_id, correlation_id)
             DO UPDATE SET
@@ -1114,7 +1118,9 @@ where
                 cache_creation_24h_input_tokens = EXCLUDED.cache_creation_24h_input_tokens,
                 total_cost = EXCLUDED.total_cost,
                 uncached_cost = EXCLUDED.uncached_cost,
-                served_by = EXCLUDED.served_by
+                served_by = EXCLUDED.served_by,
+                parse_failure = EXCLUDED.parse_failure,
+                request_id = EXCLUDED.request_id
             RETURNING id, instance_id, correlation_id, (xmax = 0) AS "newly_inserted!"
             "#,
             &instance_ids,
@@ -1151,6 +1157,8 @@ where
             &total_cost_vec as &[Option<Decimal>],
             &uncached_cost_vec as &[Option<Decimal>],
             &served_by_vec as &[Option<String>],
+            &parse_failures,
+            &request_ids as &[Option<String>],
         )
         .fetch_all(&mut **tx)
         .await?;
@@ -1225,22 +1233,28 @@ where
                 continue;
             }
 
-            // Get analytics_id
-            let Some(&analytics_id) = analytics_ids.get(&(record.raw.instance_id, record.raw.correlation_id)) else {
-                crate::background_error!(
-                    ANALYTICS_BATCHER, "analytics_id_missing", Warning,
-                    instance_id = %record.raw.instance_id,
-                    correlation_id = record.raw.correlation_id,
-                    "Analytics ID not found for credit transaction"
-                );
-                continue;
+            // The analytics row id is the ledger source_id. Requests sampled out of
+            // the analytics log are still billed, keyed by the request itself so
+            // retried flushes stay idempotent.
+            let source_id = match analytics_ids.get(&(record.raw.instance_id, record.raw.correlation_id)) {
+                Some(analytics_id) => analytics_id.to_string(),
+                None if !self.is_logged(&record.raw) => unlogged_source_id(&record.raw),
+                None => {
+                    crate::background_error!(
+                        ANALYTICS_BATCHER, "analytics_id_missing", Warning,
+                        instance_id = %record.raw.instance_id,
+                        correlation_id = record.raw.correlation_id,
+                        "Analytics ID not found for credit transaction"
+                    );
+                    continue;
+                }
             };
 
             let model = record.raw.request_model.clone().unwrap_or_default();
 
             user_ids.push(user_id);
             amounts.push(total_cost);
-            source_ids.push(analytics_id.to_string());
+            source_ids.push(source_id);
             descriptions.push(Some(format!(
                 "API usage: {} ({} input + {} output tokens)",
                 model, record.raw.prompt_tokens, record.raw.completion_tokens
@@ -1430,10 +1444,10 @@ where
             let scope_deltas: Vec<Decimal> = scope_ids.iter().map(|s| scope_folds[s]).collect();
 
             // Main path: the checkpoint row exists (created at cap-set time).
-            // Windows are CALENDAR-ALIGNED (UTC) — `api_key_cap_window_current`
-            // (migration 123, shared with the sync eligibility predicate) says
-            // whether window_started_at falls in the same calendar day/week/
-            // month as now(); a stale window means this is the first billed
+            // Windows are CALENDAR-ALIGNED in the key owner's timezone —
+            // `api_key_cap_window_current` (migrations 123/134, shared with the
+            // sync eligibility predicate) says whether window_started_at falls
+            // in the same calendar day/week/month as now(); a stale window means this is the first billed
             // request past the boundary, so the fold REPLACES window_spend
             // with this delta instead of accumulating (lazy rollover — no
             // scheduled job exists).
@@ -1442,18 +1456,19 @@ where
                 UPDATE api_key_spend_checkpoints ck SET
                     total_spend = ck.total_spend + i.delta,
                     window_spend = CASE
-                        WHEN api_key_cap_window_current(ck.window_started_at, ak.spend_limit_interval)
+                        WHEN api_key_cap_window_current(ck.window_started_at, ak.spend_limit_interval, owner.timezone)
                         THEN ck.window_spend + i.delta
                         ELSE i.delta
                     END,
                     window_started_at = CASE
-                        WHEN api_key_cap_window_current(ck.window_started_at, ak.spend_limit_interval)
+                        WHEN api_key_cap_window_current(ck.window_started_at, ak.spend_limit_interval, owner.timezone)
                         THEN ck.window_started_at
                         ELSE NOW()
                     END,
                     updated_at = NOW()
                 FROM UNNEST($1::uuid[], $2::numeric[]) AS i(api_key_id, delta)
                 JOIN api_keys ak ON ak.id = i.api_key_id
+                JOIN users owner ON owner.id = ak.user_id
                 WHERE ck.api_key_id = i.api_key_id
                 RETURNING ck.api_key_id, ck.window_spend AS "window_spend!", i.delta AS "delta!", ak.spend_limit
                 "#,
@@ -1545,6 +1560,102 @@ where
             }
         }
 
+        // Fold the same per-user amounts into the spend windows of the users'
+        // limited groups (migration 150): a member's spend counts once towards
+        // each limited group they belong to. Rides `folds`, so retries never
+        // double-fold, and is a no-op unless a flushed user is in a group with
+        // a spending limit. Windows roll lazily like the cap checkpoints
+        // above (calendar-aligned in UTC), and limit crossings NOTIFY the
+        // same way.
+        if !folds.is_empty() {
+            let fold_users: Vec<Uuid> = folds.keys().copied().collect();
+            let memberships = sqlx::query!(
+                r#"
+                SELECT ug.user_id, ug.group_id
+                FROM user_groups ug
+                JOIN group_spending_limits gl ON gl.group_id = ug.group_id
+                WHERE ug.user_id = ANY($1)
+                "#,
+                &fold_users,
+            )
+            .fetch_all(&mut **tx)
+            .await?;
+
+            let mut group_folds: HashMap<Uuid, Decimal> = HashMap::new();
+            for membership in &memberships {
+                // User fold deltas are debits (negative); the window counts spend.
+                *group_folds.entry(membership.group_id).or_insert(Decimal::ZERO) -= folds[&membership.user_id].delta;
+            }
+
+            if !group_folds.is_empty() {
+                // Sorted for the same cross-replica deadlock avoidance as the
+                // balance fold.
+                let mut group_ids: Vec<Uuid> = group_folds.keys().copied().collect();
+                group_ids.sort_unstable();
+                let group_deltas: Vec<Decimal> = group_ids.iter().map(|g| group_folds[g]).collect();
+
+                // Upsert: the row normally exists from limit-set time, but a
+                // missing one starts a window now with this delta.
+                let folded = sqlx::query!(
+                    r#"
+                    INSERT INTO group_spend_checkpoints AS ck (group_id, window_spend, window_started_at)
+                    SELECT i.group_id, i.delta, NOW()
+                    FROM UNNEST($1::uuid[], $2::numeric[]) AS i(group_id, delta)
+                    ON CONFLICT (group_id) DO UPDATE SET
+                        window_spend = CASE
+                            WHEN api_key_cap_window_current(
+                                ck.window_started_at,
+                                (SELECT gl.spending_limit_interval FROM group_spending_limits gl WHERE gl.group_id = ck.group_id),
+                                'UTC'
+                            )
+                            THEN ck.window_spend + EXCLUDED.window_spend
+                            ELSE EXCLUDED.window_spend
+                        END,
+                        window_started_at = CASE
+                            WHEN api_key_cap_window_current(
+                                ck.window_started_at,
+                                (SELECT gl.spending_limit_interval FROM group_spending_limits gl WHERE gl.group_id = ck.group_id),
+                                'UTC'
+                            )
+                            THEN ck.window_started_at
+                            ELSE NOW()
+                        END,
+                        updated_at = NOW()
+                    RETURNING ck.group_id, ck.window_spend,
+                        (SELECT gl.spending_limit FROM group_spending_limits gl WHERE gl.group_id = ck.group_id) AS spending_limit
+                    "#,
+                    &group_ids,
+                    &group_deltas,
+                )
+                .fetch_all(&mut **tx)
+                .await?;
+
+                // Edge-trigger, as for key caps: crossed iff this delta moved
+                // the window from below the limit to at/above it.
+                let crossed_limits = folded
+                    .iter()
+                    .filter(|row| {
+                        let delta = group_folds.get(&row.group_id).copied().unwrap_or(Decimal::ZERO);
+                        matches!(row.spending_limit, Some(limit) if row.window_spend >= limit && row.window_spend - delta < limit)
+                    })
+                    .count() as u64;
+
+                if crossed_limits > 0 {
+                    let epoch_micros = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_micros();
+                    let payload = format!("group_spending_limit:{}", epoch_micros);
+                    sqlx::query("SELECT pg_notify($1, $2)")
+                        .bind(ONWARDS_CONFIG_CHANGED_CHANNEL)
+                        .bind(&payload)
+                        .execute(&mut **tx)
+                        .await?;
+                    counter!("dwctl_group_spending_limit_crossings_total").increment(crossed_limits);
+                }
+            }
+        }
+
         // Aggregate this flush's batched rows into batch_aggregates (the
         // grouped view the transactions UI reads). Sorted for the same
         // deadlock-avoidance reason as above.
@@ -1795,6 +1906,40 @@ where
             served_by: record.raw.served_by.clone(),
         }
     }
+
+    /// Convert an enriched record to the event published to the sink.
+    fn enriched_to_event(record: &EnrichedRecord) -> AnalyticsEvent {
+        AnalyticsEvent {
+            instance_id: record.raw.instance_id,
+            correlation_id: record.raw.correlation_id,
+            timestamp: record.raw.timestamp,
+            request_id: record.raw.request_id.clone(),
+            trace_id: record.raw.trace_id.clone(),
+            method: record.raw.method.clone(),
+            uri: record.raw.uri.clone(),
+            request_model: record.raw.request_model.clone(),
+            response_model: record.raw.response_model.clone(),
+            provider_name: record.provider_name.clone(),
+            status_code: record.raw.status_code,
+            duration_ms: record.raw.duration_ms,
+            duration_to_first_byte_ms: record.raw.duration_to_first_byte_ms,
+            prompt_tokens: record.raw.prompt_tokens,
+            completion_tokens: record.raw.completion_tokens,
+            reasoning_tokens: record.raw.reasoning_tokens,
+            total_tokens: record.raw.total_tokens,
+            cache_read_input_tokens: record.raw.cache_read_input_tokens,
+            user_id: record.user_id,
+            api_key_id: record.api_key_id,
+            access_source: record.access_source.clone(),
+            request_origin: compute_request_origin(record.api_key_purpose.as_ref(), record.raw.fusillade_batch_id).to_string(),
+            fusillade_batch_id: record.raw.fusillade_batch_id,
+            fusillade_request_id: record.raw.fusillade_request_id,
+            custom_id: record.raw.custom_id.clone(),
+            batch_completion_window: record.raw.batch_completion_window.clone(),
+            total_cost: record.total_cost,
+            parse_failure: record.raw.parse_failure,
+        }
+    }
 }
 
 /// Model info with tariffs
@@ -1807,7 +1952,8 @@ struct ModelInfo {
 /// Tariff info for pricing lookup
 #[derive(Debug)]
 struct TariffInfo {
-    purpose: ApiKeyPurpose,
+    /// `None` for a default tariff, which applies to any purpose without its own
+    purpose: Option<ApiKeyPurpose>,
     effective_from: DateTime<Utc>,
     valid_until: Option<DateTime<Utc>>,
     input_price_per_token: Decimal,
@@ -1815,7 +1961,82 @@ struct TariffInfo {
     completion_window: Option<String>,
 }
 
+/// Find the best matching tariff for a record.
+///
+/// Implements fallback logic:
+/// 1. Try exact match (purpose + completion_window + timestamp)
+/// 2. Fall back to generic tariff for that purpose (completion_window = None)
+/// 3. Fall back to realtime purpose (generic)
+/// 4. Fall back to the model's default tariff (no purpose)
+fn find_best_tariff(
+    tariffs: &[TariffInfo],
+    api_key_purpose: Option<&ApiKeyPurpose>,
+    completion_window: Option<&str>,
+    timestamp: DateTime<Utc>,
+) -> (Option<Decimal>, Option<Decimal>) {
+    let purpose = api_key_purpose.unwrap_or(&ApiKeyPurpose::Realtime);
+
+    // Filter tariffs valid at timestamp:
+    // effective_from <= timestamp AND (valid_until IS NULL OR valid_until > timestamp)
+    let valid_tariffs: Vec<_> = tariffs
+        .iter()
+        .filter(|t| t.effective_from <= timestamp && t.valid_until.is_none_or(|valid_until| valid_until > timestamp))
+        .collect();
+
+    // Try exact match with completion_window (for batch tariffs with specific priority)
+    if let Some(cw) = completion_window
+        && let Some(tariff) = valid_tariffs
+            .iter()
+            .find(|t| t.purpose.as_ref() == Some(purpose) && t.completion_window.as_deref() == Some(cw))
+    {
+        return (Some(tariff.input_price_per_token), Some(tariff.output_price_per_token));
+    }
+
+    // Try generic tariff for this purpose (completion_window = None)
+    // This ensures we don't accidentally match a different priority tier
+    if let Some(tariff) = valid_tariffs
+        .iter()
+        .find(|t| t.purpose.as_ref() == Some(purpose) && t.completion_window.is_none())
+    {
+        return (Some(tariff.input_price_per_token), Some(tariff.output_price_per_token));
+    }
+
+    // Fall back to generic realtime tariff
+    if purpose != &ApiKeyPurpose::Realtime
+        && let Some(tariff) = valid_tariffs
+            .iter()
+            .find(|t| t.purpose == Some(ApiKeyPurpose::Realtime) && t.completion_window.is_none())
+    {
+        return (Some(tariff.input_price_per_token), Some(tariff.output_price_per_token));
+    }
+
+    // Fall back to the default tariff
+    if let Some(tariff) = valid_tariffs.iter().find(|t| t.purpose.is_none()) {
+        return (Some(tariff.input_price_per_token), Some(tariff.output_price_per_token));
+    }
+
+    (None, None)
+}
+
 /// Parse API key purpose from string
+/// A request's deterministic position in `[0, 1)` for analytics sampling, so
+/// the decision is the same on every flush attempt and reproducible from the
+/// request id.
+fn sample_point(instance_id: Uuid, correlation_id: i64) -> f64 {
+    let (hi, lo) = instance_id.as_u64_pair();
+    // splitmix64 finalizer: spreads sequential correlation ids evenly
+    let mut x = hi ^ lo ^ correlation_id as u64;
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^= x >> 31;
+    (x >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Ledger `source_id` for a billed request that has no `http_analytics` row.
+fn unlogged_source_id(raw: &RawAnalyticsRecord) -> String {
+    format!("unlogged_{}_{}", raw.instance_id, raw.correlation_id)
+}
+
 fn parse_api_key_purpose(s: &str) -> ApiKeyPurpose {
     match s {
         "platform" => ApiKeyPurpose::Platform,
@@ -1914,6 +2135,8 @@ mod tests {
             batch_created_at: None,
             batch_request_source: "".to_string(),
             trace_id: None,
+            request_id: None,
+            parse_failure: false,
         };
 
         assert_eq!(record.correlation_id, 123);
@@ -1962,6 +2185,8 @@ mod tests {
             batch_created_at: None,
             batch_request_source: String::new(),
             trace_id: None,
+            request_id: None,
+            parse_failure: false,
         }
     }
 
@@ -2128,7 +2353,7 @@ mod tests {
         completion_window: Option<&str>,
     ) -> TariffInfo {
         TariffInfo {
-            purpose,
+            purpose: Some(purpose),
             effective_from,
             valid_until,
             input_price_per_token: Decimal::from_str(input_price).unwrap(),
@@ -2137,46 +2362,6 @@ mod tests {
         }
     }
 
-    /// Helper to call find_best_tariff without needing a full batcher
-    fn find_tariff(
-        tariffs: &[TariffInfo],
-        api_key_purpose: Option<&ApiKeyPurpose>,
-        completion_window: Option<&str>,
-        timestamp: DateTime<Utc>,
-    ) -> (Option<Decimal>, Option<Decimal>) {
-        let purpose = api_key_purpose.unwrap_or(&ApiKeyPurpose::Realtime);
-
-        let valid_tariffs: Vec<_> = tariffs
-            .iter()
-            .filter(|t| t.effective_from <= timestamp && t.valid_until.is_none_or(|valid_until| valid_until > timestamp))
-            .collect();
-
-        if let Some(cw) = completion_window
-            && let Some(tariff) = valid_tariffs
-                .iter()
-                .find(|t| &t.purpose == purpose && t.completion_window.as_deref() == Some(cw))
-        {
-            return (Some(tariff.input_price_per_token), Some(tariff.output_price_per_token));
-        }
-
-        if let Some(tariff) = valid_tariffs
-            .iter()
-            .find(|t| &t.purpose == purpose && t.completion_window.is_none())
-        {
-            return (Some(tariff.input_price_per_token), Some(tariff.output_price_per_token));
-        }
-
-        if purpose != &ApiKeyPurpose::Realtime
-            && let Some(tariff) = valid_tariffs
-                .iter()
-                .find(|t| t.purpose == ApiKeyPurpose::Realtime && t.completion_window.is_none())
-        {
-            return (Some(tariff.input_price_per_token), Some(tariff.output_price_per_token));
-        }
-
-        (None, None)
-    }
-
     #[test]
     fn test_find_best_tariff_exact_match() {
         let now = chrono::Utc::now();
@@ -2189,7 +2374,7 @@ mod tests {
             None,
         )];
 
-        let (input, output) = find_tariff(&tariffs, Some(&ApiKeyPurpose::Realtime), None, now);
+        let (input, output) = find_best_tariff(&tariffs, Some(&ApiKeyPurpose::Realtime), None, now);
         assert_eq!(input, Some(Decimal::from_str("0.00010").unwrap()));
         assert_eq!(output, Some(Decimal::from_str("0.00020").unwrap()));
     }
@@ -2217,12 +2402,12 @@ mod tests {
         ];
 
         // Batch purpose should get batch pricing
-        let (input, output) = find_tariff(&tariffs, Some(&ApiKeyPurpose::Batch), None, now);
+        let (input, output) = find_best_tariff(&tariffs, Some(&ApiKeyPurpose::Batch), None, now);
         assert_eq!(input, Some(Decimal::from_str("0.00005").unwrap()));
         assert_eq!(output, Some(Decimal::from_str("0.00010").unwrap()));
 
         // Realtime purpose should get realtime pricing
-        let (input, output) = find_tariff(&tariffs, Some(&ApiKeyPurpose::Realtime), None, now);
+        let (input, output) = find_best_tariff(&tariffs, Some(&ApiKeyPurpose::Realtime), None, now);
         assert_eq!(input, Some(Decimal::from_str("0.00010").unwrap()));
         assert_eq!(output, Some(Decimal::from_str("0.00020").unwrap()));
     }
@@ -2241,11 +2426,49 @@ mod tests {
         )];
 
         // Batch purpose with no batch tariff should fall back to realtime
-        let (input, output) = find_tariff(&tariffs, Some(&ApiKeyPurpose::Batch), None, now);
+        let (input, output) = find_best_tariff(&tariffs, Some(&ApiKeyPurpose::Batch), None, now);
         assert_eq!(input, Some(Decimal::from_str("0.00015").unwrap()));
         assert_eq!(output, Some(Decimal::from_str("0.00030").unwrap()));
     }
 
+    #[test]
+    fn test_find_best_tariff_fallback_to_default() {
+        // A tariff without a purpose applies when neither the key's purpose nor realtime has one
+        let now = chrono::Utc::now();
+        let mut default_tariff = make_tariff(
+            ApiKeyPurpose::Realtime,
+            now - chrono::Duration::days(1),
+            None,
+            "0.00002",
+            "0.00004",
+            None,
+        );
+        default_tariff.purpose = None;
+        let tariffs = vec![
+            default_tariff,
+            make_tariff(
+                ApiKeyPurpose::Batch,
+                now - chrono::Duration::days(1),
+                None,
+                "0.00001",
+                "0.00002",
+                Some("24h"),
+            ),
+        ];
+
+        let (input, output) = find_best_tariff(&tariffs, Some(&ApiKeyPurpose::Realtime), None, now);
+        assert_eq!(input, Some(Decimal::from_str("0.00002").unwrap()));
+        assert_eq!(output, Some(Decimal::from_str("0.00004").unwrap()));
+
+        // A purpose-specific tariff still wins over the default
+        let (input, _) = find_best_tariff(&tariffs, Some(&ApiKeyPurpose::Batch), Some("24h"), now);
+        assert_eq!(input, Some(Decimal::from_str("0.00001").unwrap()));
+
+        // An unknown batch window falls through realtime to the default
+        let (input, _) = find_best_tariff(&tariffs, Some(&ApiKeyPurpose::Batch), Some("1h"), now);
+        assert_eq!(input, Some(Decimal::from_str("0.00002").unwrap()));
+    }
+
     #[test]
     fn test_find_best_tariff_historical_pricing() {
         // Test that expired tariffs are not selected for current requests
@@ -2277,7 +2500,7 @@ mod tests {
         ];
 
         // Current request should use new pricing
-        let (input, output) = find_tariff(&tariffs, Some(&ApiKeyPurpose::Realtime), None, now);
+        let (input, output) = find_best_tariff(&tariffs, Some(&ApiKeyPurpose::Realtime), None, now);
         assert_eq!(
             input,
             Some(Decimal::from_str("0.00010").unwrap()),
@@ -2287,7 +2510,7 @@ mod tests {
 
         // Historical request (20 days ago) should use old pricing
         let historical_time = now - chrono::Duration::days(20);
-        let (input, output) = find_tariff(&tariffs, Some(&ApiKeyPurpose::Realtime), None, historical_time);
+        let (input, output) = find_best_tariff(&tariffs, Some(&ApiKeyPurpose::Realtime), None, historical_time);
         assert_eq!(
             input,
             Some(Decimal::from_str("0.00020").unwrap()),
@@ -2322,7 +2545,7 @@ mod tests {
         ];
 
         // Request with 24h completion window should get the priority-specific pricing
-        let (input, output) = find_tariff(&tariffs, Some(&ApiKeyPurpose::Batch), Some("24h"), now);
+        let (input, output) = find_best_tariff(&tariffs, Some(&ApiKeyPurpose::Batch), Some("24h"), now);
         assert_eq!(
             input,
             Some(Decimal::from_str("0.00005").unwrap()),
@@ -2331,7 +2554,7 @@ mod tests {
         assert_eq!(output, Some(Decimal::from_str("0.00010").unwrap()));
 
         // Request without completion window should get generic batch pricing
-        let (input, output) = find_tariff(&tariffs, Some(&ApiKeyPurpose::Batch), None, now);
+        let (input, output) = find_best_tariff(&tariffs, Some(&ApiKeyPurpose::Batch), None, now);
         assert_eq!(
             input,
             Some(Decimal::from_str("0.00010").unwrap()),
@@ -2375,7 +2598,7 @@ mod tests {
         ];
 
         // Request with unknown "1h" priority should fall back to generic, NOT to 24h or 7d
-        let (input, output) = find_tariff(&tariffs, Some(&ApiKeyPurpose::Batch), Some("1h"), now);
+        let (input, output) = find_best_tariff(&tariffs, Some(&ApiKeyPurpose::Batch), Some("1h"), now);
         assert_eq!(
             input,
             Some(Decimal::from_str("0.00010").unwrap()),
@@ -2389,7 +2612,7 @@ mod tests {
         let now = chrono::Utc::now();
         let tariffs = vec![];
 
-        let (input, output) = find_tariff(&tariffs, Some(&ApiKeyPurpose::Realtime), None, now);
+        let (input, output) = find_best_tariff(&tariffs, Some(&ApiKeyPurpose::Realtime), None, now);
         assert_eq!(input, None);
         assert_eq!(output, None);
     }
@@ -2407,11 +2630,20 @@ mod tests {
             None,
         )];
 
-        let (input, output) = find_tariff(&tariffs, Some(&ApiKeyPurpose::Realtime), None, now);
+        let (input, output) = find_best_tariff(&tariffs, Some(&ApiKeyPurpose::Realtime), None, now);
         assert_eq!(input, None, "Future tariff should not be selected");
         assert_eq!(output, None);
     }
 
+    #[test]
+    fn test_sample_point_is_deterministic_and_uniform() {
+        let instance_id = Uuid::new_v4();
+        assert_eq!(sample_point(instance_id, 42), sample_point(instance_id, 42));
+
+        let sampled = (0..10_000).filter(|id| sample_point(instance_id, *id) < 0.1).count();
+        assert!((800..1200).contains(&sampled), "expected ~10% sampled, got {sampled}");
+    }
+
     use rust_decimal::prelude::FromStr;
 }
 
@@ -2450,6 +2682,15 @@ mod integration_tests {
                 auth_header_name: Some("Authorization".to_string()),
                 auth_header_prefix: Some("Bearer ".to_string()),
                 reasoning_translation: None,
+                bedrock: None,
+                region: None,
+                body_transform: None,
+                auto_sync_interval_seconds: None,
+                alias_template: None,
+                max_concurrency: None,
+                tls: None,
+                additional_api_keys: Vec::new(),
+                api_key_rotation: None,
             })
             .await
             .unwrap();
@@ -2471,6 +2712,10 @@ mod integration_tests {
                 burst_size: None,
                 capacity: None,
                 batch_capacity: None,
+                per_key_capacity: None,
+                max_cost_per_request: None,
+                input_modalities: None,
+                output_modalities: None,
                 throughput: None,
                 provider_pricing: None,
                 is_composite: false,
@@ -2488,6 +2733,19 @@ mod integration_tests {
                 backoff_max_total_ms: None,
                 sanitize_responses: true,
                 trusted: false,
+                allow_public: false,
+                require_approval: false,
+                rewrite_response_model: false,
+                disable_logging: false,
+                warmup: false,
+                system_prompt: None,
+                system_prompt_mode: Default::default(),
+                max_n: None,
+                max_n_mode: Default::default(),
+                credit_exhaustion_mode: Default::default(),
+                max_overdraft: None,
+                max_prompt_length: None,
+                max_prompt_length_unit: Default::default(),
                 open_responses_adapter: true,
                 reasoning_translation_overrides: None,
                 allowed_batch_completion_windows: None,
@@ -2580,6 +2838,9 @@ mod integration_tests {
                 created_by: user_id,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: 0,
             })
             .await
             .unwrap();
@@ -2620,12 +2881,17 @@ mod integration_tests {
             batch_created_at: None,
             batch_request_source: String::new(),
             trace_id: None,
+            request_id: None,
+            parse_failure: false,
         }
     }
 
     /// Run the batcher with given records and wait for completion
     async fn run_batcher_with_records(pool: &PgPool, records: Vec<RawAnalyticsRecord>) {
-        let config = crate::test::utils::create_test_config();
+        run_batcher_with_config(pool, crate::test::utils::create_test_config(), records).await;
+    }
+
+    async fn run_batcher_with_config(pool: &PgPool, config: Config, records: Vec<RawAnalyticsRecord>) {
         let (batcher, sender) = AnalyticsBatcher::<crate::metrics::GenAiMetrics>::new(pool.clone(), config, None);
 
         // Send all records
@@ -2681,6 +2947,58 @@ mod integration_tests {
         assert_eq!(usage_tx.unwrap().amount, expected_cost);
     }
 
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_batcher_sampling_still_logs_errors_and_bills_everything(pool: PgPool) {
+        let model_id = create_test_model(&pool, "gpt-4-test").await;
+        let input_price = Decimal::from_str("0.00001").unwrap();
+        let output_price = Decimal::from_str("0.00003").unwrap();
+        setup_tariff(&pool, model_id, input_price, output_price, ApiKeyPurpose::Realtime).await;
+
+        let initial_balance = Decimal::from_str("10.00").unwrap();
+        let user_id = setup_user_with_balance(&pool, initial_balance).await;
+        let api_key = create_api_key_for_user(&pool, user_id, ApiKeyPurpose::Realtime).await;
+
+        // No successful request is logged, every error is
+        let mut config = crate::test::utils::create_test_config();
+        config.analytics.success_sample_rate = 0.0;
+        config.analytics.error_sample_rate = 1.0;
+
+        let success = create_raw_record("gpt-4-test", Some(api_key.clone()), 1000, 500);
+        let mut error = create_raw_record("gpt-4-test", Some(api_key), 0, 0);
+        error.status_code = 500;
+        let (success_key, error_key) = (
+            (success.instance_id, success.correlation_id),
+            (error.instance_id, error.correlation_id),
+        );
+
+        run_batcher_with_config(&pool, config, vec![success, error]).await;
+
+        let logged: Vec<(Uuid, i64, i32)> =
+            sqlx::query_as("SELECT instance_id, correlation_id, status_code FROM http_analytics WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(logged, vec![(error_key.0, error_key.1, 500)], "only the error should be logged");
+
+        // The unlogged success is still charged in full
+        let mut conn = pool.acquire().await.unwrap();
+        let mut credits = Credits::new(&mut conn);
+        let final_balance = credits.get_user_balance(user_id).await.unwrap();
+        assert_eq!(final_balance, initial_balance - Decimal::from_str("0.025").unwrap());
+
+        let transactions = credits
+            .list_user_transactions(user_id, 0, 10, &TransactionFilters::default())
+            .await
+            .unwrap();
+        let usage_tx = transactions
+            .iter()
+            .find(|tx| tx.transaction_type == CreditTransactionType::Usage)
+            .expect("unlogged request should still be billed");
+        assert_eq!(usage_tx.source_id, format!("unlogged_{}_{}", success_key.0, success_key.1));
+    }
+
     #[sqlx::test]
     #[test_log::test]
     async fn test_batcher_cache_discount_applied(pool: PgPool) {
@@ -2814,6 +3132,77 @@ mod integration_tests {
         assert!(amounts.contains(&expected_realtime_cost), "Should have realtime cost transaction");
     }
 
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_batcher_falls_back_to_default_tariff(pool: PgPool) {
+        use crate::db::handlers::Tariffs;
+        use crate::db::models::tariffs::TariffCreateDBRequest;
+
+        // Setup: a batch tariff plus a default tariff (no purpose), but no realtime tariff
+        let model_id = create_test_model(&pool, "gpt-4-default-tariff").await;
+        setup_tariff(
+            &pool,
+            model_id,
+            Decimal::from_str("0.00005").unwrap(),
+            Decimal::from_str("0.00010").unwrap(),
+            ApiKeyPurpose::Batch,
+        )
+        .await;
+        let mut conn = pool.acquire().await.unwrap();
+        Tariffs::new(&mut conn)
+            .create(&TariffCreateDBRequest {
+                deployed_model_id: model_id,
+                name: "default_tariff".to_string(),
+                api_key_purpose: None,
+                input_price_per_token: Decimal::from_str("0.00020").unwrap(),
+                output_price_per_token: Decimal::from_str("0.00040").unwrap(),
+                valid_from: None,
+                completion_window: None,
+            })
+            .await
+            .unwrap();
+        drop(conn);
+
+        let user_id = setup_user_with_balance(&pool, Decimal::from_str("100.00").unwrap()).await;
+        let batch_key = create_api_key_for_user(&pool, user_id, ApiKeyPurpose::Batch).await;
+        let realtime_key = create_api_key_for_user(&pool, user_id, ApiKeyPurpose::Realtime).await;
+
+        let mut batch_record = create_raw_record("gpt-4-default-tariff", Some(batch_key), 1000, 500);
+        batch_record.batch_completion_window = Some("24h".to_string());
+        let realtime_record = create_raw_record("gpt-4-default-tariff", Some(realtime_key), 1000, 500);
+
+        run_batcher_with_records(&pool, vec![batch_record, realtime_record]).await;
+
+        // Batch: (1000 * 0.00005) + (500 * 0.00010) = 0.10
+        // Realtime, billed at the default: (1000 * 0.00020) + (500 * 0.00040) = 0.40
+        let mut conn = pool.acquire().await.unwrap();
+        let mut credits = Credits::new(&mut conn);
+        let final_balance = credits.get_user_balance(user_id).await.unwrap();
+        assert_eq!(final_balance, Decimal::from_str("99.50").unwrap());
+
+        // Analytics record the tariff that was applied to each request
+        let applied: Vec<(Option<Decimal>, Option<Decimal>)> = sqlx::query_as(
+            "SELECT input_price_per_token, output_price_per_token FROM http_analytics
+             WHERE model = 'gpt-4-default-tariff' ORDER BY input_price_per_token",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            applied,
+            vec![
+                (
+                    Some(Decimal::from_str("0.00005").unwrap()),
+                    Some(Decimal::from_str("0.00010").unwrap())
+                ),
+                (
+                    Some(Decimal::from_str("0.00020").unwrap()),
+                    Some(Decimal::from_str("0.00040").unwrap())
+                ),
+            ]
+        );
+    }
+
     #[sqlx::test]
     #[test_log::test]
     async fn test_batcher_folds_batch_analytics_into_aggregates(pool: PgPool) {
@@ -3043,6 +3432,41 @@ mod integration_tests {
         assert_eq!(tx_count, Some(0), "No credit transactions for unauthenticated requests");
     }
 
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_batcher_records_parse_failure_and_bills_estimate(pool: PgPool) {
+        let model_id = create_test_model(&pool, "gpt-4-parse-failure").await;
+        setup_tariff(
+            &pool,
+            model_id,
+            Decimal::from_str("0.00001").unwrap(),
+            Decimal::from_str("0.00003").unwrap(),
+            ApiKeyPurpose::Realtime,
+        )
+        .await;
+        let user_id = setup_user_with_balance(&pool, Decimal::from_str("10.00").unwrap()).await;
+        let api_key = create_api_key_for_user(&pool, user_id, ApiKeyPurpose::Realtime).await;
+
+        // Estimated tokens from an unparseable response
+        let record = RawAnalyticsRecord {
+            parse_failure: true,
+            ..create_raw_record("gpt-4-parse-failure", Some(api_key), 1000, 500)
+        };
+        run_batcher_with_records(&pool, vec![record]).await;
+
+        let (parse_failure, total_tokens): (bool, i64) =
+            sqlx::query_as("SELECT parse_failure, total_tokens FROM http_analytics WHERE model = 'gpt-4-parse-failure'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(parse_failure);
+        assert_eq!(total_tokens, 1500);
+
+        let mut conn = pool.acquire().await.unwrap();
+        let balance = Credits::new(&mut conn).get_user_balance(user_id).await.unwrap();
+        assert_eq!(balance, Decimal::from_str("9.975").unwrap(), "Estimated usage is still billed");
+    }
+
     /// Test that the batcher sends pg_notify when a user's balance is depleted (crosses zero downward)
     #[sqlx::test]
     #[test_log::test]
@@ -3292,6 +3716,128 @@ mod integration_tests {
         assert_no_cap_notification(&mut listener).await;
     }
 
+    /// Group spending limit: two members' spend folds into one group window,
+    /// crossing the limit notifies once, and the next reload withdraws the
+    /// keys of every member from the priced model.
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_group_spend_crossing_limit_revokes_all_members(pool: PgPool) {
+        use crate::config::RateLimitTiersConfig;
+        use crate::db::handlers::GroupSpendingLimits;
+        use crate::db::models::group_spending_limits::GroupSpendingLimitDBRequest;
+        use crate::sync::onwards_config::load_targets_from_db;
+        use crate::test::utils::{add_deployment_to_group, add_user_to_group, create_test_group};
+        use onwards::auth::ConstantTimeString;
+        use sqlx::postgres::PgListener;
+        use std::time::Duration;
+        use tokio::time::timeout;
+
+        let alias = "gpt-4-group-limit-test";
+        let model_id = create_test_model(&pool, alias).await;
+        setup_tariff(
+            &pool,
+            model_id,
+            Decimal::from_str("0.00001").unwrap(),
+            Decimal::from_str("0.00003").unwrap(),
+            ApiKeyPurpose::Realtime,
+        )
+        .await;
+
+        // Two wealthy members, so no balance crossing interferes.
+        let member_a = setup_user_with_balance(&pool, Decimal::from_str("100").unwrap()).await;
+        let member_b = setup_user_with_balance(&pool, Decimal::from_str("100").unwrap()).await;
+        let secret_a = create_api_key_for_user(&pool, member_a, ApiKeyPurpose::Realtime).await;
+        let secret_b = create_api_key_for_user(&pool, member_b, ApiKeyPurpose::Realtime).await;
+
+        let group = create_test_group(&pool).await;
+        add_user_to_group(&pool, member_a, group.id).await;
+        add_user_to_group(&pool, member_b, group.id).await;
+        add_deployment_to_group(&pool, model_id, group.id, member_a).await;
+
+        // $0.04 limit; each request below costs $0.025.
+        {
+            let mut conn = pool.acquire().await.unwrap();
+            GroupSpendingLimits::new(&mut conn)
+                .set(
+                    group.id,
+                    &GroupSpendingLimitDBRequest {
+                        spending_limit: Decimal::from_str("0.04").unwrap(),
+                        spending_limit_interval: Some("monthly".to_string()),
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        let has_key = |targets: &onwards::target::Targets, secret: &str| {
+            let expected = ConstantTimeString::from(secret.to_string());
+            targets
+                .targets
+                .get(alias)
+                .is_some_and(|p| p.value().keys().is_some_and(|keys| keys.iter().any(|c| c == &expected)))
+        };
+        let tiers = RateLimitTiersConfig::default();
+        let targets = load_targets_from_db(&pool, &[], false, &tiers, None, None).await.unwrap();
+        assert!(
+            has_key(&targets, &secret_a) && has_key(&targets, &secret_b),
+            "under the limit both members have access"
+        );
+
+        let mut listener = PgListener::connect_with(&pool).await.expect("Failed to create listener");
+        listener.listen(ONWARDS_CONFIG_CHANGED_CHANNEL).await.expect("Failed to listen");
+        while timeout(Duration::from_millis(10), listener.try_recv()).await.is_ok() {}
+
+        // Neither member is over the limit alone; together they are.
+        run_batcher_with_records(
+            &pool,
+            vec![
+                create_raw_record(alias, Some(secret_a.clone()), 1000, 500),
+                create_raw_record(alias, Some(secret_b.clone()), 1000, 500),
+            ],
+        )
+        .await;
+
+        let notification = timeout(Duration::from_secs(2), listener.recv())
+            .await
+            .expect("Timeout waiting for group limit notification")
+            .expect("Failed to receive notification");
+        assert!(
+            notification.payload().starts_with("group_spending_limit:"),
+            "Expected group limit payload, got: {}",
+            notification.payload()
+        );
+
+        let usage = {
+            let mut conn = pool.acquire().await.unwrap();
+            GroupSpendingLimits::new(&mut conn).get(group.id).await.unwrap().unwrap()
+        };
+        assert_eq!(usage.spent, Decimal::from_str("0.05").unwrap());
+
+        let targets = load_targets_from_db(&pool, &[], false, &tiers, None, None).await.unwrap();
+        assert!(!has_key(&targets, &secret_a), "member A loses the priced model");
+        assert!(!has_key(&targets, &secret_b), "member B loses the priced model");
+
+        // Raising the limit keeps the counted spend but readmits both members.
+        {
+            let mut conn = pool.acquire().await.unwrap();
+            GroupSpendingLimits::new(&mut conn)
+                .set(
+                    group.id,
+                    &GroupSpendingLimitDBRequest {
+                        spending_limit: Decimal::from(1),
+                        spending_limit_interval: Some("monthly".to_string()),
+                    },
+                )
+                .await
+                .unwrap();
+        }
+        let targets = load_targets_from_db(&pool, &[], false, &tiers, None, None).await.unwrap();
+        assert!(
+            has_key(&targets, &secret_a) && has_key(&targets, &secret_b),
+            "a raised limit readmits the members"
+        );
+    }
+
     #[sqlx::test]
     #[test_log::test]
     async fn test_flush_emits_single_notification_for_multiple_depletions(pool: PgPool) {
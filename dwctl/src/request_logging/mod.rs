@@ -1,10 +1,14 @@
 pub mod analytics_handler;
 pub mod batcher;
 pub mod models;
+pub mod opt_out;
 pub mod serializers;
+pub mod sink;
 pub mod stream_usage;
+pub mod token_estimate;
 mod utils;
 
 pub use analytics_handler::AnalyticsHandler;
 pub use batcher::AnalyticsBatcher;
 pub use models::{AiRequest, AiResponse, ParsedAIRequest};
+pub use sink::AnalyticsSink;
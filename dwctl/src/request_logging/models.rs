@@ -36,6 +36,20 @@ pub struct ResponsesRequest {
     pub stream: Option<bool>,
 }
 
+/// Minimal parsed form of a /v1/rerank request – only the fields needed for analytics.
+#[derive(Debug, Clone)]
+pub struct RerankRequest {
+    pub model: Option<String>,
+    /// Number of documents submitted for scoring; this is the billed unit.
+    pub documents: usize,
+}
+
+/// Minimal parsed form of a /v1/moderations request – only the fields needed for analytics.
+#[derive(Debug, Clone)]
+pub struct ModerationRequest {
+    pub model: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParsedAIRequest {
     pub headers: HashMap<String, String>,
@@ -44,6 +58,38 @@ pub struct ParsedAIRequest {
     /// Skipped during serde because `ResponsesRequest` is a local computation artifact.
     #[serde(skip)]
     pub responses_request: Option<ResponsesRequest>,
+    /// Populated when the request was routed to /v1/rerank.
+    #[serde(skip)]
+    pub rerank_request: Option<RerankRequest>,
+    /// Populated when the request was routed to /v1/moderations.
+    #[serde(skip)]
+    pub moderation_request: Option<ModerationRequest>,
+}
+
+/// Response from a /v1/rerank endpoint (Cohere / Jina / vLLM / TEI shape).
+///
+/// Rerankers don't report usage consistently, so billing is based on the number of
+/// documents scored, taken from the request since `top_n` can truncate `results`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RerankResponse {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub results: Vec<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Value>,
+    #[serde(skip)]
+    pub documents_scored: usize,
+}
+
+/// Response from a /v1/moderations endpoint.
+///
+/// Moderations are billed at a flat rate per request, so only the model and the
+/// classification results are kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModerationResponse {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub results: Vec<Value>,
 }
 
 /// SSE chunk emitted by an upstream provider when it fails mid-stream.
@@ -91,6 +137,13 @@ pub enum AiResponse {
     Responses(Response),
     /// Streaming /v1/responses – SSE events collected until stream end.
     ResponsesStream(Vec<ResponseStreamEvent>),
+    /// /v1/rerank response. Only produced by path-based detection: other endpoints
+    /// (e.g. moderations) also return a `results` array.
+    #[serde(skip_deserializing)]
+    Rerank(RerankResponse),
+    /// /v1/moderations response. Path-based like `Rerank`, which shares the `results` shape.
+    #[serde(skip_deserializing)]
+    Moderation(ModerationResponse),
     Other(Value),
 }
 
@@ -0,0 +1,81 @@
+//! Per-deployment request logging opt-out (`disable_logging` on deployments).
+//!
+//! [`LoggingOptOutScrubber`] wraps the request logging handler (outlet-postgres'
+//! `PostgresHandler`) and blanks the request and response bodies of requests
+//! to opted-out deployments before they are persisted, so only metadata
+//! (method, path, status, timing) reaches `http_requests` / `http_responses`.
+//! Analytics and billing don't persist bodies and are not wrapped.
+//!
+//! The deployment is resolved the way onwards resolves it: the `model-override`
+//! header if present, otherwise the body's `model`. Opted-out aliases are read
+//! from the per-replica
+//! [`deployment_settings`](crate::inference::deployment_settings) snapshot.
+//! Unlike the other per-deployment checks this fails closed: if the snapshot
+//! can't be loaded, no bodies are logged.
+
+use outlet::{RequestData, RequestHandler, ResponseData};
+use sqlx::PgPool;
+use tracing::warn;
+
+use crate::inference::deployment_settings::{DeploymentSettingsIndex, MODEL_OVERRIDE_HEADER, requested_model};
+
+/// The model a captured request was routed on, if it names one.
+fn logged_model(request: &RequestData) -> Option<String> {
+    let model_override = request
+        .headers
+        .get(MODEL_OVERRIDE_HEADER)
+        .and_then(|values| values.first())
+        .and_then(|bytes| std::str::from_utf8(bytes).ok());
+    let body = request
+        .body
+        .as_ref()
+        .filter(|_| model_override.is_none())
+        .and_then(|body| serde_json::from_slice::<serde_json::Value>(body).ok());
+    requested_model(model_override, body.as_ref()).map(str::to_string)
+}
+
+/// A `RequestHandler` that blanks the bodies of requests to deployments with
+/// `disable_logging` set before the handler it wraps persists them.
+#[derive(Clone)]
+pub struct LoggingOptOutScrubber<H> {
+    inner: H,
+    pool: PgPool,
+    settings: DeploymentSettingsIndex,
+}
+
+impl<H> LoggingOptOutScrubber<H> {
+    pub fn new(inner: H, pool: PgPool, settings: DeploymentSettingsIndex) -> Self {
+        Self { inner, pool, settings }
+    }
+
+    /// Whether the request's bodies must be kept out of the log.
+    async fn logging_disabled(&self, request: &RequestData) -> bool {
+        let Some(model) = logged_model(request) else {
+            return false;
+        };
+        match self.settings.get(&self.pool).await {
+            Ok(settings) => settings.logging_disabled.contains(&model),
+            Err(error) => {
+                warn!(%error, "Failed to load logging opt-outs; not logging request bodies");
+                true
+            }
+        }
+    }
+}
+
+impl<H: RequestHandler> RequestHandler for LoggingOptOutScrubber<H> {
+    async fn handle_request(&self, mut data: RequestData) {
+        if self.logging_disabled(&data).await {
+            data.body = None;
+        }
+        self.inner.handle_request(data).await
+    }
+
+    async fn handle_response(&self, mut request_data: RequestData, mut response_data: ResponseData) {
+        if self.logging_disabled(&request_data).await {
+            request_data.body = None;
+            response_data.body = None;
+        }
+        self.inner.handle_response(request_data, response_data).await
+    }
+}
@@ -40,7 +40,10 @@
 //! [outlet]: https://github.com/doublewordai/outlet
 
 use crate::config::Config;
-use crate::request_logging::models::{AiRequest, AiResponse, ChatCompletionChunk, CompletionChunk, ParsedAIRequest, ResponsesRequest};
+use crate::request_logging::models::{
+    AiRequest, AiResponse, ChatCompletionChunk, CompletionChunk, ModerationRequest, ModerationResponse, ParsedAIRequest, RerankRequest,
+    RerankResponse, ResponsesRequest,
+};
 use async_openai::types::responses::ResponseStreamEvent;
 use outlet::{RequestData, ResponseData};
 use outlet_postgres::SerializationError;
@@ -50,7 +53,7 @@ use std::str;
 use tracing::{error, instrument};
 use uuid::Uuid;
 
-use super::utils;
+use super::{token_estimate, utils};
 
 /// Authentication information extracted from request headers
 #[derive(Clone)]
@@ -172,6 +175,9 @@ pub struct UsageMetrics {
 /// - On parse failure, returns error with base64-encoded body for safe PostgreSQL storage
 /// - For `/v1/responses` paths, uses path-based detection to avoid serde disambiguation
 ///   issues with the embeddings variant (both use an `input` field).
+/// - For `/v1/rerank` paths, records the model and number of documents to score.
+/// - For `/v1/moderations` paths, records the model (moderations would otherwise
+///   parse as embeddings, which share the `model` + `input` shape).
 #[instrument(skip_all, name = "dwctl.parse_ai_request")]
 pub fn parse_ai_request(request_data: &RequestData) -> Result<ParsedAIRequest, SerializationError> {
     let headers = request_data
@@ -187,6 +193,8 @@ pub fn parse_ai_request(request_data: &RequestData) -> Result<ParsedAIRequest, S
                 headers,
                 request: AiRequest::Other(Value::Null),
                 responses_request: None,
+                rerank_request: None,
+                moderation_request: None,
             });
         }
     };
@@ -198,6 +206,8 @@ pub fn parse_ai_request(request_data: &RequestData) -> Result<ParsedAIRequest, S
             headers,
             request: AiRequest::Other(Value::Null),
             responses_request: None,
+            rerank_request: None,
+            moderation_request: None,
         });
     }
 
@@ -213,6 +223,54 @@ pub fn parse_ai_request(request_data: &RequestData) -> Result<ParsedAIRequest, S
                     headers,
                     request: AiRequest::Other(value),
                     responses_request: Some(ResponsesRequest { model, stream }),
+                    rerank_request: None,
+                    moderation_request: None,
+                })
+            }
+            Err(e) => {
+                let base64_encoded = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, bytes);
+                Err(SerializationError {
+                    fallback_data: format!("base64:{base64_encoded}"),
+                    error: Box::new(e),
+                })
+            }
+        };
+    }
+
+    // Rerank requests don't match any typed variant; record the document count for billing.
+    if request_data.uri.path().ends_with("/rerank") {
+        return match serde_json::from_str::<Value>(&body_str) {
+            Ok(value) => {
+                let model = value.get("model").and_then(|v| v.as_str()).map(|s| s.to_string());
+                let documents = value.get("documents").and_then(|v| v.as_array()).map_or(0, |docs| docs.len());
+                Ok(ParsedAIRequest {
+                    headers,
+                    request: AiRequest::Other(value),
+                    responses_request: None,
+                    rerank_request: Some(RerankRequest { model, documents }),
+                    moderation_request: None,
+                })
+            }
+            Err(e) => {
+                let base64_encoded = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, bytes);
+                Err(SerializationError {
+                    fallback_data: format!("base64:{base64_encoded}"),
+                    error: Box::new(e),
+                })
+            }
+        };
+    }
+
+    if request_data.uri.path().ends_with("/moderations") {
+        return match serde_json::from_str::<Value>(&body_str) {
+            Ok(value) => {
+                let model = value.get("model").and_then(|v| v.as_str()).map(|s| s.to_string());
+                Ok(ParsedAIRequest {
+                    headers,
+                    request: AiRequest::Other(value),
+                    responses_request: None,
+                    rerank_request: None,
+                    moderation_request: Some(ModerationRequest { model }),
                 })
             }
             Err(e) => {
@@ -230,6 +288,8 @@ pub fn parse_ai_request(request_data: &RequestData) -> Result<ParsedAIRequest, S
             headers,
             request,
             responses_request: None,
+            rerank_request: None,
+            moderation_request: None,
         }),
         Err(e) => {
             // Always base64 encode unparseable content to avoid PostgreSQL issues
@@ -298,6 +358,20 @@ pub fn parse_ai_response(request_data: &RequestData, response_data: &ResponseDat
                     // AiResponse::Other rather than becoming a base64 SerializationError.
                     utils::parse_responses_non_streaming_response(&body_str).or_else(|_| utils::parse_non_streaming_response(&body_str))
                 }
+            } else if let Some(rerank_req) = &parsed_request.rerank_request {
+                // Error bodies don't carry `results`, so fall back to the generic parser.
+                serde_json::from_str::<RerankResponse>(&body_str)
+                    .map(|response| {
+                        AiResponse::Rerank(RerankResponse {
+                            documents_scored: rerank_req.documents,
+                            ..response
+                        })
+                    })
+                    .or_else(|_| utils::parse_non_streaming_response(&body_str))
+            } else if parsed_request.moderation_request.is_some() {
+                serde_json::from_str::<ModerationResponse>(&body_str)
+                    .map(AiResponse::Moderation)
+                    .or_else(|_| utils::parse_non_streaming_response(&body_str))
             } else {
                 match parsed_request.request {
                     AiRequest::ChatCompletions(chat_req) if chat_req.stream.unwrap_or(false) || fusillade_stream => {
@@ -351,6 +425,10 @@ impl UsageMetrics {
             Ok(parsed_request) => {
                 if let Some(responses_req) = parsed_request.responses_request {
                     responses_req.model
+                } else if let Some(rerank_req) = parsed_request.rerank_request {
+                    rerank_req.model
+                } else if let Some(moderation_req) = parsed_request.moderation_request {
+                    moderation_req.model
                 } else {
                     match parsed_request.request {
                         AiRequest::ChatCompletions(req) => Some(req.model),
@@ -374,7 +452,15 @@ impl UsageMetrics {
         };
 
         // Extract token metrics and response model from response
-        let response_metrics = TokenMetrics::from(parsed_response);
+        let mut response_metrics = TokenMetrics::from(parsed_response);
+
+        // A stream cut off before its usage frame never reported its prompt either,
+        // so estimate that from the request body
+        if cut_short_output_tokens(parsed_response).is_some() {
+            let prompt_bytes = request_data.body.as_ref().map_or(0, |body| body.len());
+            response_metrics.prompt_tokens = token_estimate::tokens_from_bytes(prompt_bytes) as i64;
+            response_metrics.total_tokens = response_metrics.prompt_tokens + response_metrics.completion_tokens;
+        }
 
         // The cache split lives in extension fields the typed parse drops, so read it from
         // the raw `usage` object. It only exists on a successful response that carried a
@@ -568,6 +654,82 @@ fn extract_response_reasoning_tokens(usage: &async_openai::types::responses::Res
     usage.output_tokens_details.reasoning_tokens as i64
 }
 
+/// Output tokens delivered by a stream that was cut off before its usage frame,
+/// or None for anything else.
+///
+/// When the client disconnects mid-stream the upstream request is cancelled and
+/// the provider never sends usage, so the response is billed for what was
+/// actually streamed, estimated from the generated output its events carried. A
+/// stream that finished (a `[DONE]` marker, a `finish_reason` on every choice, as
+/// there are several when the request set `n`, or a terminal Responses API
+/// event) but omitted usage is not estimated.
+fn cut_short_output_tokens(response: &AiResponse) -> Option<i64> {
+    let events: Vec<Value> = match response {
+        AiResponse::ChatCompletionsStream(chunks) => {
+            let normal: Vec<_> = chunks
+                .iter()
+                .filter_map(|chunk| match chunk {
+                    ChatCompletionChunk::Normal(c) => Some(c),
+                    _ => None,
+                })
+                .collect();
+            let done = chunks.iter().any(|chunk| matches!(chunk, ChatCompletionChunk::Done));
+            let choices = normal
+                .iter()
+                .flat_map(|c| c.choices.iter())
+                .map(|choice| (choice.index, choice.finish_reason.is_some()));
+            if done || every_choice_finished(choices) || normal.iter().any(|c| c.usage.is_some()) {
+                return None;
+            }
+            normal.into_iter().filter_map(|c| serde_json::to_value(c).ok()).collect()
+        }
+        AiResponse::CompletionsStream(chunks) => {
+            let normal: Vec<_> = chunks
+                .iter()
+                .filter_map(|chunk| match chunk {
+                    CompletionChunk::Normal(c) => Some(c),
+                    _ => None,
+                })
+                .collect();
+            let done = chunks.iter().any(|chunk| matches!(chunk, CompletionChunk::Done));
+            let choices = normal
+                .iter()
+                .flat_map(|c| c.choices.iter())
+                .map(|choice| (choice.index, choice.finish_reason.is_some()));
+            if done || every_choice_finished(choices) || normal.iter().any(|c| c.usage.is_some()) {
+                return None;
+            }
+            normal.into_iter().filter_map(|c| serde_json::to_value(c).ok()).collect()
+        }
+        AiResponse::ResponsesStream(events) => {
+            let events: Vec<Value> = events.iter().filter_map(|e| serde_json::to_value(e).ok()).collect();
+            let finished = events.iter().any(|e| {
+                matches!(
+                    e.get("type").and_then(|kind| kind.as_str()),
+                    Some("response.completed" | "response.failed" | "response.incomplete")
+                )
+            });
+            if finished {
+                return None;
+            }
+            events
+        }
+        _ => return None,
+    };
+    let bytes = events.iter().map(token_estimate::event_output_bytes).sum();
+    Some(token_estimate::tokens_from_bytes(bytes) as i64)
+}
+
+/// Whether a stream's choices, given as `(index, has finish_reason)` per delta,
+/// have all finished. False when no choice was streamed.
+fn every_choice_finished(choices: impl Iterator<Item = (u32, bool)>) -> bool {
+    let mut streaming = std::collections::BTreeMap::new();
+    for (index, finished) in choices {
+        *streaming.entry(index).or_insert(false) |= finished;
+    }
+    !streaming.is_empty() && streaming.values().all(|finished| *finished)
+}
+
 impl From<&AiResponse> for TokenMetrics {
     fn from(response: &AiResponse) -> Self {
         match response {
@@ -627,11 +789,13 @@ impl From<&AiResponse> for TokenMetrics {
                         }
                     }
                 } else {
+                    // No usage frame: bill what was streamed if the client cut the stream short
+                    let completion_tokens = cut_short_output_tokens(response).unwrap_or(0);
                     Self {
                         prompt_tokens: 0,
-                        completion_tokens: 0,
+                        completion_tokens,
                         reasoning_tokens: 0,
-                        total_tokens: 0,
+                        total_tokens: completion_tokens,
                         response_type: "chat_completion_stream".to_string(),
                         response_model: model,
                     }
@@ -669,11 +833,13 @@ impl From<&AiResponse> for TokenMetrics {
                         }
                     }
                 } else {
+                    // No usage frame: bill what was streamed if the client cut the stream short
+                    let completion_tokens = cut_short_output_tokens(response).unwrap_or(0);
                     Self {
                         prompt_tokens: 0,
-                        completion_tokens: 0,
+                        completion_tokens,
                         reasoning_tokens: 0,
-                        total_tokens: 0,
+                        total_tokens: completion_tokens,
                         response_type: "completion_stream".to_string(),
                         response_model: model,
                     }
@@ -765,16 +931,40 @@ impl From<&AiResponse> for TokenMetrics {
                         response_model: model,
                     }
                 } else {
+                    // No usage: bill what was streamed if the client cut the stream short
+                    let completion_tokens = cut_short_output_tokens(response).unwrap_or(0);
                     Self {
                         prompt_tokens: 0,
-                        completion_tokens: 0,
+                        completion_tokens,
                         reasoning_tokens: 0,
-                        total_tokens: 0,
+                        total_tokens: completion_tokens,
                         response_type: "response_stream".to_string(),
                         response_model: model,
                     }
                 }
             }
+            AiResponse::Rerank(response) => {
+                // Billed per document scored: each document counts as one input unit.
+                let documents = response.documents_scored.max(response.results.len()) as i64;
+                Self {
+                    prompt_tokens: documents,
+                    completion_tokens: 0,
+                    reasoning_tokens: 0,
+                    total_tokens: documents,
+                    response_type: "rerank".to_string(),
+                    response_model: response.model.clone(),
+                }
+            }
+            // Billed at a flat rate: each request counts as one input unit, however many
+            // inputs it classifies.
+            AiResponse::Moderation(response) => Self {
+                prompt_tokens: 1,
+                completion_tokens: 0,
+                reasoning_tokens: 0,
+                total_tokens: 1,
+                response_type: "moderation".to_string(),
+                response_model: response.model.clone(),
+            },
             AiResponse::Other(_) => Self {
                 prompt_tokens: 0,
                 completion_tokens: 0,
@@ -1605,6 +1795,84 @@ mod tests {
         assert_eq!(metrics.response_type, "chat_completion_stream");
     }
 
+    #[test]
+    fn test_analytics_metrics_bill_streamed_tokens_for_cancelled_stream() {
+        let request_json = r#"{"model": "gpt-4", "messages": [{"role": "user", "content": "hello"}], "stream": true}"#;
+        let request_data = RequestData {
+            correlation_id: 123,
+            timestamp: SystemTime::now(),
+            method: Method::POST,
+            uri: "/ai/v1/chat/completions".parse::<Uri>().unwrap(),
+            headers: HashMap::new(),
+            body: Some(Bytes::from(request_json)),
+            trace_id: None,
+            span_id: None,
+        };
+        let chunk = |content: &str| {
+            format!(
+                "data: {{\"id\":\"chatcmpl-123\",\"object\":\"chat.completion.chunk\",\"created\":1677652288,\"model\":\"gpt-4\",\"choices\":[{{\"index\":0,\"delta\":{{\"content\":\"{content}\"}}}}]}}\n\n"
+            )
+        };
+        let response_data = |body: String| ResponseData {
+            extensions: Default::default(),
+            correlation_id: 123,
+            timestamp: SystemTime::now(),
+            status: StatusCode::OK,
+            headers: HashMap::new(),
+            body: Some(Bytes::from(body)),
+            duration: Duration::from_millis(100),
+            duration_to_first_byte: Duration::from_millis(50),
+        };
+        let extract = |response_data: &ResponseData| {
+            let parsed = parse_ai_response(&request_data, response_data).unwrap();
+            UsageMetrics::extract(
+                Uuid::new_v4(),
+                &request_data,
+                response_data,
+                &parsed,
+                &crate::test::utils::create_test_config(),
+            )
+        };
+
+        // The client disconnected after 12 bytes of output: no usage frame, no [DONE]. Both
+        // sides are estimated at four bytes a token, the prompt from the request body.
+        let cancelled = response_data(format!("{}{}{}", chunk("Hell"), chunk("o, w"), chunk("orld")));
+        let metrics = extract(&cancelled);
+        assert_eq!(metrics.prompt_tokens, (request_json.len() / 4) as i64);
+        assert_eq!(metrics.completion_tokens, 3);
+        assert_eq!(metrics.total_tokens, metrics.prompt_tokens + 3);
+
+        // A stream that completed without usage is not estimated
+        let completed = response_data(format!("{}{}data: [DONE]\n\n", chunk("Hell"), chunk("o, w")));
+        let metrics = extract(&completed);
+        assert_eq!(metrics.prompt_tokens, 0);
+        assert_eq!(metrics.completion_tokens, 0);
+        assert_eq!(metrics.total_tokens, 0);
+
+        // With n > 1, one choice finishing doesn't mean the stream did
+        let choice = |index: u32, delta: &str, finish_reason: &str| {
+            format!(
+                "data: {{\"id\":\"chatcmpl-123\",\"object\":\"chat.completion.chunk\",\"created\":1677652288,\"model\":\"gpt-4\",\"choices\":[{{\"index\":{index},\"delta\":{delta},\"finish_reason\":{finish_reason}}}]}}\n\n"
+            )
+        };
+        let cancelled = response_data(format!(
+            "{}{}{}{}",
+            choice(0, r#"{"content":"Hi, "}"#, "null"),
+            choice(1, r#"{"content":"Hell"}"#, "null"),
+            choice(0, "{}", r#""stop""#),
+            choice(1, r#"{"content":"o, w"}"#, "null"),
+        ));
+        assert_eq!(extract(&cancelled).completion_tokens, 3);
+        let completed = response_data(format!(
+            "{}{}{}{}",
+            choice(0, r#"{"content":"Hi"}"#, "null"),
+            choice(1, r#"{"content":"Hel"}"#, "null"),
+            choice(0, "{}", r#""stop""#),
+            choice(1, "{}", r#""stop""#),
+        ));
+        assert_eq!(extract(&completed).completion_tokens, 0);
+    }
+
     #[test]
     fn test_analytics_metrics_extract_chat_reasoning_tokens() {
         let instance_id = Uuid::new_v4();
@@ -1960,6 +2228,102 @@ mod tests {
         }
     }
 
+    fn rerank_request_data() -> RequestData {
+        RequestData {
+            correlation_id: 1,
+            timestamp: SystemTime::now(),
+            method: Method::POST,
+            uri: "/v1/rerank".parse::<Uri>().unwrap(),
+            headers: HashMap::new(),
+            body: Some(Bytes::from(
+                r#"{"model":"bge-reranker","query":"capital of France","documents":["Berlin","Paris","Madrid"],"top_n":1}"#,
+            )),
+            trace_id: None,
+            span_id: None,
+        }
+    }
+
+    #[test]
+    fn test_analytics_metrics_extract_rerank_bills_documents_scored() {
+        // top_n truncates `results`, but every submitted document was scored.
+        let request_data = rerank_request_data();
+        let response_data = responses_response_data(
+            r#"{"id":"rerank-1","model":"bge-reranker","results":[{"index":1,"relevance_score":0.99}],"usage":{"total_tokens":31}}"#
+                .to_string(),
+        );
+
+        let parsed_response = parse_ai_response(&request_data, &response_data).unwrap();
+        assert!(matches!(parsed_response, AiResponse::Rerank(_)));
+
+        let metrics = UsageMetrics::extract(
+            Uuid::new_v4(),
+            &request_data,
+            &response_data,
+            &parsed_response,
+            &crate::test::utils::create_test_config(),
+        );
+
+        assert_eq!(metrics.request_model, Some("bge-reranker".to_string()));
+        assert_eq!(metrics.response_model, Some("bge-reranker".to_string()));
+        assert_eq!(metrics.prompt_tokens, 3);
+        assert_eq!(metrics.completion_tokens, 0);
+        assert_eq!(metrics.total_tokens, 3);
+        assert_eq!(metrics.response_type, "rerank");
+    }
+
+    #[test]
+    fn test_parse_ai_response_rerank_error_body_falls_back_to_other() {
+        let request_data = rerank_request_data();
+        let response_data = responses_response_data(r#"{"error":{"message":"model not found"}}"#.to_string());
+
+        let result = parse_ai_response(&request_data, &response_data).unwrap();
+        assert!(matches!(result, AiResponse::Other(_)));
+    }
+
+    #[test]
+    fn test_parse_ai_response_results_body_not_rerank_outside_rerank_path() {
+        // Moderations also return a `results` array; only the /rerank path produces Rerank.
+        let request_data = RequestData {
+            uri: "/v1/moderations".parse::<Uri>().unwrap(),
+            body: Some(Bytes::from(r#"{"model":"omni-moderation-latest","input":"hi"}"#)),
+            ..rerank_request_data()
+        };
+        let response_data = responses_response_data(r#"{"id":"modr-1","model":"omni","results":[{"flagged":false}]}"#.to_string());
+
+        let result = parse_ai_response(&request_data, &response_data).unwrap();
+        assert!(!matches!(result, AiResponse::Rerank(_)));
+    }
+
+    #[test]
+    fn test_analytics_metrics_extract_moderation_bills_one_unit_per_request() {
+        // Two inputs are classified, but moderations are billed per request.
+        let request_data = RequestData {
+            uri: "/v1/moderations".parse::<Uri>().unwrap(),
+            body: Some(Bytes::from(r#"{"model":"llama-guard","input":["first","second"]}"#)),
+            ..rerank_request_data()
+        };
+        let response_data =
+            responses_response_data(r#"{"id":"modr-1","model":"llama-guard","results":[{"flagged":false},{"flagged":true}]}"#.to_string());
+
+        let parsed_response = parse_ai_response(&request_data, &response_data).unwrap();
+        assert!(matches!(parsed_response, AiResponse::Moderation(_)));
+
+        let metrics = UsageMetrics::extract(
+            Uuid::new_v4(),
+            &request_data,
+            &response_data,
+            &parsed_response,
+            &crate::test::utils::create_test_config(),
+        );
+
+        assert_eq!(metrics.request_model, Some("llama-guard".to_string()));
+        assert_eq!(metrics.response_model, Some("llama-guard".to_string()));
+        assert_eq!(metrics.prompt_tokens, 1);
+        assert_eq!(metrics.completion_tokens, 0);
+        assert_eq!(metrics.total_tokens, 1);
+        assert_eq!(metrics.response_type, "moderation");
+    }
+
     #[test]
     fn test_parse_ai_response_responses_streaming() {
         let request_data = responses_request_data(Some(true));
@@ -2036,6 +2400,32 @@ mod tests {
         assert_eq!(metrics.response_type, "response_stream");
     }
 
+    #[test]
+    fn test_analytics_metrics_estimate_cancelled_responses_stream() {
+        let request_data = responses_request_data(Some(true));
+        let delta = |text: &str| {
+            format!(
+                "data: {{\"type\":\"response.output_text.delta\",\"sequence_number\":1,\"item_id\":\"item_1\",\"output_index\":0,\"content_index\":0,\"delta\":\"{text}\"}}\n\n"
+            )
+        };
+        // Cut off before response.completed: 8 bytes of output
+        let response_data = responses_response_data(format!("{}{}", delta("Why did"), delta(" ")));
+
+        let parsed_response = parse_ai_response(&request_data, &response_data).unwrap();
+        let metrics = UsageMetrics::extract(
+            Uuid::new_v4(),
+            &request_data,
+            &response_data,
+            &parsed_response,
+            &crate::test::utils::create_test_config(),
+        );
+
+        let prompt_bytes = request_data.body.as_ref().unwrap().len();
+        assert_eq!(metrics.prompt_tokens, (prompt_bytes / 4) as i64);
+        assert_eq!(metrics.completion_tokens, 2);
+        assert_eq!(metrics.total_tokens, metrics.prompt_tokens + 2);
+    }
+
     fn response_with_body(body: impl Into<Bytes>) -> ResponseData {
         ResponseData {
             extensions: Default::default(),
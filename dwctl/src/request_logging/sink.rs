@@ -0,0 +1,336 @@
+//! Publishing analytics events to an external sink.
+//!
+//! High-volume customers want usage events in their own data lake as well as
+//! in `http_analytics`. When `analytics.sink.destination` is set, the batcher
+//! publishes an [`AnalyticsEvent`] for every request it enriches, and a
+//! [`SinkWorker`] delivers them in batches to a webhook or, through a Kafka
+//! REST Proxy, to a Kafka topic.
+//!
+//! ```text
+//! AnalyticsBatcher ──publish (try_send)──► bounded buffer ──► SinkWorker ──POST──► webhook / REST proxy
+//!                       │                                        │
+//!                       └─ buffer full: drop, count               └─ failed: retry with backoff, then drop
+//! ```
+//!
+//! Publishing never waits: while the worker is retrying a slow or failing
+//! destination the buffer fills, and new events are dropped rather than
+//! holding up the batcher, and with it the proxy. Drops are counted in
+//! `dwctl_analytics_sink_dropped_total` by reason.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use metrics::counter;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::config::{AnalyticsSinkConfig, AnalyticsSinkDestination};
+use crate::metrics::errors::component::ANALYTICS_SINK;
+
+/// Content type of the Kafka REST Proxy v2 API for JSON records
+const KAFKA_JSON_CONTENT_TYPE: &str = "application/vnd.kafka.json.v2+json";
+
+/// One request's analytics, as published to the sink
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalyticsEvent {
+    pub instance_id: Uuid,
+    pub correlation_id: i64,
+    pub timestamp: DateTime<Utc>,
+    /// The `X-Request-Id` returned to the client
+    pub request_id: Option<String>,
+    pub trace_id: Option<String>,
+    pub method: String,
+    pub uri: String,
+    pub request_model: Option<String>,
+    pub response_model: Option<String>,
+    pub provider_name: Option<String>,
+    pub status_code: i32,
+    pub duration_ms: i64,
+    pub duration_to_first_byte_ms: Option<i64>,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub reasoning_tokens: i64,
+    pub total_tokens: i64,
+    pub cache_read_input_tokens: i64,
+    pub user_id: Option<Uuid>,
+    pub api_key_id: Option<Uuid>,
+    /// How the request authenticated: "api_key", "unknown_api_key" or "unauthenticated"
+    pub access_source: String,
+    /// "api", "frontend" or "fusillade"
+    pub request_origin: String,
+    pub fusillade_batch_id: Option<Uuid>,
+    pub fusillade_request_id: Option<Uuid>,
+    pub custom_id: Option<String>,
+    pub batch_completion_window: Option<String>,
+    /// Credits charged for the request; `None` when the model has no pricing
+    pub total_cost: Option<Decimal>,
+    /// The response couldn't be parsed, so its tokens are zero or estimated
+    pub parse_failure: bool,
+}
+
+/// Handle the batcher publishes events through
+#[derive(Debug, Clone)]
+pub struct AnalyticsSink {
+    sender: mpsc::Sender<AnalyticsEvent>,
+}
+
+impl AnalyticsSink {
+    /// Create a sink for `destination` along with the worker that delivers its
+    /// events. The worker stops once every handle has been dropped and the
+    /// buffer is drained.
+    pub fn new(config: &AnalyticsSinkConfig, destination: AnalyticsSinkDestination) -> anyhow::Result<(Self, SinkWorker)> {
+        let (sender, receiver) = mpsc::channel(config.buffer_size);
+        let client = reqwest::Client::builder().timeout(config.timeout).build()?;
+        let worker = SinkWorker {
+            receiver,
+            client,
+            destination,
+            batch_size: config.batch_size,
+            max_retries: config.max_retries,
+            retry_base_delay: config.retry_base_delay,
+        };
+        Ok((Self { sender }, worker))
+    }
+
+    /// Queue an event for delivery, dropping it if the buffer is full
+    pub fn publish(&self, event: AnalyticsEvent) {
+        match self.sender.try_send(event) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                counter!("dwctl_analytics_sink_dropped_total", "reason" => "buffer_full").increment(1);
+            }
+            Err(TrySendError::Closed(_)) => {
+                counter!("dwctl_analytics_sink_dropped_total", "reason" => "closed").increment(1);
+            }
+        }
+    }
+}
+
+/// Background task delivering queued events to the destination
+pub struct SinkWorker {
+    receiver: mpsc::Receiver<AnalyticsEvent>,
+    client: reqwest::Client,
+    destination: AnalyticsSinkDestination,
+    batch_size: usize,
+    max_retries: u32,
+    retry_base_delay: Duration,
+}
+
+impl SinkWorker {
+    /// Deliver events until every [`AnalyticsSink`] is dropped, then deliver
+    /// what's left in the buffer and return.
+    pub async fn run(mut self) {
+        info!(batch_size = self.batch_size, "Analytics sink started");
+        let mut batch = Vec::with_capacity(self.batch_size);
+        while self.receiver.recv_many(&mut batch, self.batch_size).await > 0 {
+            self.deliver(&batch).await;
+            batch.clear();
+        }
+        info!("Analytics sink stopped");
+    }
+
+    /// Send a batch, retrying with exponential backoff before dropping it
+    async fn deliver(&self, events: &[AnalyticsEvent]) {
+        for attempt in 0..=self.max_retries {
+            match self.send(events).await {
+                Ok(()) => {
+                    counter!("dwctl_analytics_sink_delivered_total").increment(events.len() as u64);
+                    debug!(count = events.len(), "Delivered analytics events to sink");
+                    return;
+                }
+                Err(e) if attempt < self.max_retries => {
+                    let delay = self.retry_base_delay * 2u32.pow(attempt);
+                    warn!(
+                        error = %e,
+                        attempt = attempt + 1,
+                        max_retries = self.max_retries,
+                        delay_ms = delay.as_millis() as u64,
+                        "Analytics sink delivery failed, retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => {
+                    crate::background_error!(
+                        ANALYTICS_SINK, "delivery_drop", Error,
+                        error = %e,
+                        count = events.len(),
+                        attempts = self.max_retries + 1,
+                        "Failed to deliver analytics events to sink after all retries, dropping them"
+                    );
+                    counter!("dwctl_analytics_sink_dropped_total", "reason" => "delivery_failed").increment(events.len() as u64);
+                }
+            }
+        }
+    }
+
+    async fn send(&self, events: &[AnalyticsEvent]) -> anyhow::Result<()> {
+        let (request, headers) = match &self.destination {
+            AnalyticsSinkDestination::Webhook { url, headers } => (self.client.post(url.clone()).json(events), headers),
+            AnalyticsSinkDestination::Kafka {
+                rest_proxy_url,
+                topic,
+                headers,
+            } => {
+                let url = format!("{}/topics/{topic}", rest_proxy_url.as_str().trim_end_matches('/'));
+                let records: Vec<_> = events
+                    .iter()
+                    .map(|event| serde_json::json!({ "key": event.user_id, "value": event }))
+                    .collect();
+                let body = serde_json::to_vec(&serde_json::json!({ "records": records }))?;
+                let request = self
+                    .client
+                    .post(url)
+                    .header(reqwest::header::CONTENT_TYPE, KAFKA_JSON_CONTENT_TYPE)
+                    .body(body);
+                (request, headers)
+            }
+        };
+        let request = headers.iter().fold(request, |request, (name, value)| request.header(name, value));
+        request.send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn event(correlation_id: i64) -> AnalyticsEvent {
+        AnalyticsEvent {
+            instance_id: Uuid::nil(),
+            correlation_id,
+            timestamp: Utc::now(),
+            request_id: Some(format!("req-{correlation_id}")),
+            trace_id: None,
+            method: "POST".to_string(),
+            uri: "/ai/v1/chat/completions".to_string(),
+            request_model: Some("model".to_string()),
+            response_model: Some("model".to_string()),
+            provider_name: None,
+            status_code: 200,
+            duration_ms: 120,
+            duration_to_first_byte_ms: Some(40),
+            prompt_tokens: 10,
+            completion_tokens: 5,
+            reasoning_tokens: 0,
+            total_tokens: 15,
+            cache_read_input_tokens: 0,
+            user_id: Some(Uuid::from_u128(7)),
+            api_key_id: None,
+            access_source: "api_key".to_string(),
+            request_origin: "api".to_string(),
+            fusillade_batch_id: None,
+            fusillade_request_id: None,
+            custom_id: None,
+            batch_completion_window: None,
+            total_cost: Some(Decimal::new(25, 4)),
+            parse_failure: false,
+        }
+    }
+
+    fn config(buffer_size: usize) -> AnalyticsSinkConfig {
+        AnalyticsSinkConfig {
+            buffer_size,
+            batch_size: 10,
+            max_retries: 1,
+            retry_base_delay: Duration::from_millis(1),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_webhook_receives_published_events() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/events"))
+            .and(header("authorization", "Bearer sink-token"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let destination = AnalyticsSinkDestination::Webhook {
+            url: format!("{}/events", server.uri()).parse().unwrap(),
+            headers: HashMap::from([("authorization".to_string(), "Bearer sink-token".to_string())]),
+        };
+        let (sink, worker) = AnalyticsSink::new(&config(100), destination).unwrap();
+        let events: Vec<_> = (0..3).map(event).collect();
+        for event in &events {
+            sink.publish(event.clone());
+        }
+        drop(sink);
+        worker.run().await;
+
+        let delivered: Vec<AnalyticsEvent> = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .flat_map(|request| request.body_json::<Vec<AnalyticsEvent>>().unwrap())
+            .collect();
+        assert_eq!(delivered, events);
+    }
+
+    #[tokio::test]
+    async fn test_kafka_records_are_keyed_by_user() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/topics/usage"))
+            .and(header("content-type", KAFKA_JSON_CONTENT_TYPE))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let destination = AnalyticsSinkDestination::Kafka {
+            rest_proxy_url: format!("{}/", server.uri()).parse().unwrap(),
+            topic: "usage".to_string(),
+            headers: HashMap::new(),
+        };
+        let (sink, worker) = AnalyticsSink::new(&config(100), destination).unwrap();
+        sink.publish(event(1));
+        drop(sink);
+        worker.run().await;
+
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 1);
+        let body: serde_json::Value = requests[0].body_json().unwrap();
+        let records = body["records"].as_array().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["key"], Uuid::from_u128(7).to_string());
+        assert_eq!(records[0]["value"]["correlation_id"], 1);
+        assert_eq!(records[0]["value"]["total_cost"], "0.0025");
+    }
+
+    #[tokio::test]
+    async fn test_events_dropped_when_buffer_full_or_delivery_fails() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let destination = AnalyticsSinkDestination::Webhook {
+            url: server.uri().parse().unwrap(),
+            headers: HashMap::new(),
+        };
+        // Nothing is consuming the buffer yet, so only the first two fit
+        let (sink, worker) = AnalyticsSink::new(&config(2), destination).unwrap();
+        for id in 0..5 {
+            sink.publish(event(id));
+        }
+        drop(sink);
+        worker.run().await;
+
+        // One batch of two, tried once and retried once, then dropped
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 2);
+        for request in requests {
+            assert_eq!(request.body_json::<Vec<AnalyticsEvent>>().unwrap().len(), 2);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Token estimates for responses the provider didn't report usage for.
+//!
+//! Roughly four bytes of text make a token, the same heuristic as batch file
+//! cost estimates. Prompts are estimated from the request body; generated
+//! output from the text a stream's events carry, so a stream cut off before its
+//! usage frame is billed (and metered by the cost guard) for what it delivered.
+
+/// Bytes of text assumed to make up one token.
+pub const BYTES_PER_TOKEN: usize = 4;
+
+/// Estimated tokens in `bytes` bytes of text.
+pub fn tokens_from_bytes(bytes: usize) -> u64 {
+    (bytes / BYTES_PER_TOKEN) as u64
+}
+
+/// Output tokens reported by a usage object on a stream event: top-level for chat
+/// and legacy completions, on the response for the Responses API.
+pub fn event_usage_output_tokens(event: &serde_json::Value) -> Option<u64> {
+    let usage = event.get("usage").or_else(|| event.pointer("/response/usage"))?;
+    usage
+        .get("completion_tokens")
+        .or_else(|| usage.get("output_tokens"))
+        .and_then(|tokens| tokens.as_u64())
+}
+
+/// Bytes of generated output carried by one stream event: chat deltas (content,
+/// refusal, reasoning and tool calls), legacy completion text, and Responses API
+/// `*.delta` events.
+pub fn event_output_bytes(event: &serde_json::Value) -> usize {
+    let len = |value: Option<&serde_json::Value>| value.and_then(|value| value.as_str()).map_or(0, str::len);
+
+    if let Some(kind) = event.get("type").and_then(|kind| kind.as_str()) {
+        return if kind.ends_with(".delta") { len(event.get("delta")) } else { 0 };
+    }
+
+    event
+        .get("choices")
+        .and_then(|choices| choices.as_array())
+        .into_iter()
+        .flatten()
+        .map(|choice| match choice.get("delta") {
+            Some(delta) => {
+                let tool_calls = delta
+                    .get("tool_calls")
+                    .and_then(|calls| calls.as_array())
+                    .into_iter()
+                    .flatten()
+                    .map(|call| len(call.pointer("/function/name")) + len(call.pointer("/function/arguments")))
+                    .sum::<usize>();
+                ["content", "refusal", "reasoning_content", "reasoning"]
+                    .into_iter()
+                    .map(|field| len(delta.get(field)))
+                    .sum::<usize>()
+                    + tool_calls
+            }
+            None => len(choice.get("text")),
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{event_output_bytes, event_usage_output_tokens, tokens_from_bytes};
+    use serde_json::json;
+
+    #[test]
+    fn test_event_output_bytes() {
+        let chat = json!({ "choices": [
+            { "index": 0, "delta": { "role": "assistant", "content": "Hello" } },
+            { "index": 1, "delta": { "tool_calls": [ { "index": 0, "function": { "name": "get", "arguments": "{\"a\":1}" } } ] } },
+        ] });
+        assert_eq!(event_output_bytes(&chat), 5 + 3 + 7);
+        assert_eq!(event_output_bytes(&json!({ "choices": [ { "index": 0, "text": "abc" } ] })), 3);
+        assert_eq!(
+            event_output_bytes(&json!({ "type": "response.output_text.delta", "delta": "abcd" })),
+            4
+        );
+        assert_eq!(event_output_bytes(&json!({ "type": "response.created", "response": {} })), 0);
+        assert_eq!(event_output_bytes(&json!({ "choices": [ { "index": 0, "delta": {} } ] })), 0);
+    }
+
+    #[test]
+    fn test_event_usage_output_tokens() {
+        assert_eq!(event_usage_output_tokens(&json!({ "usage": { "completion_tokens": 7 } })), Some(7));
+        assert_eq!(
+            event_usage_output_tokens(&json!({ "type": "response.completed", "response": { "usage": { "output_tokens": 9 } } })),
+            Some(9)
+        );
+        assert_eq!(event_usage_output_tokens(&json!({ "choices": [] })), None);
+        assert_eq!(tokens_from_bytes(9), 2);
+    }
+}
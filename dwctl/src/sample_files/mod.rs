@@ -183,6 +183,10 @@ mod tests {
             burst_size: None,
             capacity: None,
             batch_capacity: None,
+            per_key_capacity: None,
+            max_cost_per_request: None,
+            input_modalities: None,
+            output_modalities: None,
             throughput: None,
             status: crate::db::models::deployments::ModelStatus::Active,
             created_at: chrono::Utc::now(),
@@ -207,6 +211,20 @@ mod tests {
             backoff_max_total_ms: None,
             sanitize_responses: true,
             trusted: false,
+            allow_public: false,
+            require_approval: false,
+            rewrite_response_model: false,
+            disable_logging: false,
+            warmup: false,
+            active: true,
+            system_prompt: None,
+            system_prompt_mode: crate::db::models::deployments::SystemPromptMode::default(),
+            max_n: None,
+            max_n_mode: Default::default(),
+            credit_exhaustion_mode: Default::default(),
+            max_overdraft: None,
+            max_prompt_length: None,
+            max_prompt_length_unit: Default::default(),
             open_responses_adapter: true,
             reasoning_translation_overrides: None,
             allowed_batch_completion_windows: None,
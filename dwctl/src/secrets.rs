@@ -0,0 +1,493 @@
+//! External secret references for inference endpoint API keys.
+//!
+//! `inference_endpoints.api_key` may hold a reference instead of the raw key:
+//!
+//! - `env:NAME` reads the environment variable `NAME`, if `NAME` starts with
+//!   one of [`SecretsConfig::allowed_env_prefixes`]
+//! - `file:/path/to/key` reads the file (trailing whitespace trimmed), if it is
+//!   under one of [`SecretsConfig::allowed_file_dirs`]
+//! - `vault:path#field` reads `field` from the Vault KV v2 secret at `path`
+//!   under the configured mount (`field` defaults to `value`), if `path` can't
+//!   climb out of the mount
+//!
+//! Any other value is an inline key and is used as-is, so existing endpoints
+//! keep working. References are resolved when the onwards config is rebuilt
+//! from the database, and each resolved value is reused for
+//! [`SecretsConfig::cache_ttl_seconds`] before it is read again; the periodic
+//! fallback sync therefore picks up rotated secrets without a restart.
+//!
+//! The `env:` and `file:` allowlists keep endpoint admins from pointing an
+//! endpoint at the server's own secrets (e.g. `env:DATABASE_URL`), which the
+//! sync would then send upstream as the endpoint's key. Both are empty by
+//! default, so those references are refused unless the operator opts in.
+
+use std::fmt;
+use std::path::{Component, Path};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// Secrets backend configuration.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SecretsConfig {
+    /// How long a resolved reference is reused before it is read again, in seconds.
+    pub cache_ttl_seconds: u64,
+    /// Vault server for `vault:` references. Without it those references fail to resolve.
+    pub vault: Option<VaultConfig>,
+    /// Prefixes of the environment variables `env:` references may read, e.g.
+    /// `["PROVIDER_KEY_"]`. Empty (the default) refuses every `env:` reference.
+    pub allowed_env_prefixes: Vec<String>,
+    /// Directories whose files `file:` references may read, e.g.
+    /// `["/run/secrets/providers"]`. Empty (the default) refuses every `file:` reference.
+    pub allowed_file_dirs: Vec<String>,
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self {
+            cache_ttl_seconds: 300,
+            vault: None,
+            allowed_env_prefixes: Vec::new(),
+            allowed_file_dirs: Vec::new(),
+        }
+    }
+}
+
+impl SecretsConfig {
+    /// Check that a stored API key is an inline key or a reference the
+    /// allowlists permit.
+    pub fn check_reference(&self, stored: &str) -> Result<(), SecretError> {
+        match SecretReference::parse(stored) {
+            Some(reference) => self.check(&reference),
+            None => Ok(()),
+        }
+    }
+
+    fn check(&self, reference: &SecretReference) -> Result<(), SecretError> {
+        let allowed = match reference {
+            SecretReference::Env(name) => self.allowed_env_prefixes.iter().any(|prefix| name.starts_with(prefix.as_str())),
+            SecretReference::File(path) => {
+                let path = Path::new(path);
+                // `..` could climb out of an allowed directory
+                path.is_absolute()
+                    && !path.components().any(|component| component == Component::ParentDir)
+                    && self.allowed_file_dirs.iter().any(|dir| path.starts_with(dir))
+            }
+            SecretReference::Vault { path, .. } => return check_vault_path(path),
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(SecretError::NotAllowed(reference.to_string()))
+        }
+    }
+}
+
+/// Check that a `vault:` path names a secret under the configured mount.
+///
+/// The path is spliced into the request URL, so `.` and `..` segments (or
+/// their percent-encoded forms, which URL parsing decodes) could reach another
+/// mount or API; `?` and `\` could rewrite the rest of the URL.
+fn check_vault_path(path: &str) -> Result<(), SecretError> {
+    let valid = path
+        .split('/')
+        .all(|segment| !matches!(segment, "" | "." | "..") && !segment.contains(['%', '?', '\\']));
+    if valid {
+        Ok(())
+    } else {
+        Err(SecretError::InvalidVaultPath(path.to_string()))
+    }
+}
+
+/// Connection to a HashiCorp Vault KV v2 secrets engine.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct VaultConfig {
+    /// Vault server address, e.g. `https://vault.internal:8200`
+    pub address: String,
+    /// Token sent as `X-Vault-Token`. Prefer setting it with `DWCTL_SECRETS__VAULT__TOKEN`.
+    pub token: String,
+    /// Mount point of the KV v2 engine
+    #[serde(default = "default_vault_mount")]
+    pub mount: String,
+}
+
+fn default_vault_mount() -> String {
+    "secret".to_string()
+}
+
+// Manual Debug so the token never reaches the logged config.
+impl fmt::Debug for VaultConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VaultConfig")
+            .field("address", &self.address)
+            .field("token", &"<redacted>")
+            .field("mount", &self.mount)
+            .finish()
+    }
+}
+
+/// Errors resolving a secret reference.
+#[derive(Debug, thiserror::Error)]
+pub enum SecretError {
+    #[error("environment variable {0} is not set")]
+    MissingEnv(String),
+
+    #[error("failed to read {path}: {source}")]
+    File { path: String, source: std::io::Error },
+
+    #[error("vault references require secrets.vault to be configured")]
+    VaultNotConfigured,
+
+    #[error("vault request failed: {0}")]
+    Vault(String),
+
+    #[error("field {field} not found in vault secret {path}")]
+    MissingField { path: String, field: String },
+
+    #[error("no secret store configured")]
+    NoStore,
+
+    #[error("{0} is not allowed by secrets.allowed_env_prefixes or secrets.allowed_file_dirs")]
+    NotAllowed(String),
+
+    #[error("vault path {0} must not contain empty, `.` or `..` segments, or `%`, `?` or `\\`")]
+    InvalidVaultPath(String),
+}
+
+/// A pointer to a secret held outside the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretReference {
+    Env(String),
+    File(String),
+    Vault { path: String, field: String },
+}
+
+impl fmt::Display for SecretReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Env(name) => write!(f, "env:{name}"),
+            Self::File(path) => write!(f, "file:{path}"),
+            Self::Vault { path, field } => write!(f, "vault:{path}#{field}"),
+        }
+    }
+}
+
+impl SecretReference {
+    /// Parse a stored value, returning `None` for inline secrets.
+    pub fn parse(value: &str) -> Option<Self> {
+        if let Some(name) = value.strip_prefix("env:") {
+            return Some(Self::Env(name.to_string()));
+        }
+        if let Some(path) = value.strip_prefix("file:") {
+            return Some(Self::File(path.to_string()));
+        }
+        let reference = value.strip_prefix("vault:")?;
+        let (path, field) = reference.split_once('#').unwrap_or((reference, "value"));
+        Some(Self::Vault {
+            path: path.trim_matches('/').to_string(),
+            field: field.to_string(),
+        })
+    }
+}
+
+/// Reads the secret a reference points at.
+#[async_trait]
+pub trait SecretResolver: Send + Sync {
+    async fn resolve(&self, reference: &SecretReference) -> Result<String, SecretError>;
+}
+
+/// Resolves references against the environment, the filesystem and (if configured) Vault.
+pub struct BackendResolver {
+    client: reqwest::Client,
+    config: SecretsConfig,
+}
+
+impl BackendResolver {
+    pub fn new(config: SecretsConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            config,
+        }
+    }
+
+    async fn read_vault(&self, path: &str, field: &str) -> Result<String, SecretError> {
+        let vault = self.config.vault.as_ref().ok_or(SecretError::VaultNotConfigured)?;
+        let url = format!(
+            "{}/v1/{}/data/{}",
+            vault.address.trim_end_matches('/'),
+            vault.mount.trim_matches('/'),
+            path
+        );
+        let response = self
+            .client
+            .get(&url)
+            .header("X-Vault-Token", &vault.token)
+            .send()
+            .await
+            .map_err(|e| SecretError::Vault(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(SecretError::Vault(format!("{} returned {}", path, response.status())));
+        }
+        let body: serde_json::Value = response.json().await.map_err(|e| SecretError::Vault(e.to_string()))?;
+        body.pointer("/data/data")
+            .and_then(|data| data.get(field))
+            .and_then(|value| value.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| SecretError::MissingField {
+                path: path.to_string(),
+                field: field.to_string(),
+            })
+    }
+}
+
+#[async_trait]
+impl SecretResolver for BackendResolver {
+    async fn resolve(&self, reference: &SecretReference) -> Result<String, SecretError> {
+        // Also checked here for references stored before the allowlists existed
+        self.config.check(reference)?;
+        match reference {
+            SecretReference::Env(name) => std::env::var(name).map_err(|_| SecretError::MissingEnv(name.clone())),
+            SecretReference::File(path) => tokio::fs::read_to_string(path)
+                .await
+                .map(|contents| contents.trim_end().to_string())
+                .map_err(|source| SecretError::File {
+                    path: path.clone(),
+                    source,
+                }),
+            SecretReference::Vault { path, field } => self.read_vault(path, field).await,
+        }
+    }
+}
+
+/// Resolves stored endpoint API keys, caching resolved references. Cheap to clone.
+#[derive(Clone)]
+pub struct SecretStore {
+    resolver: Arc<dyn SecretResolver>,
+    ttl: Duration,
+    cache: Arc<DashMap<String, (Instant, String)>>,
+}
+
+impl SecretStore {
+    pub fn new(config: &SecretsConfig) -> Self {
+        Self::with_resolver(
+            Arc::new(BackendResolver::new(config.clone())),
+            Duration::from_secs(config.cache_ttl_seconds),
+        )
+    }
+
+    pub fn with_resolver(resolver: Arc<dyn SecretResolver>, ttl: Duration) -> Self {
+        Self {
+            resolver,
+            ttl,
+            cache: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// The usable value of a stored API key: inline keys as-is, references resolved.
+    ///
+    /// If a reference that resolved before now fails, the last value is kept
+    /// so a brief backend outage doesn't take endpoints down.
+    pub async fn resolve(&self, stored: &str) -> Result<String, SecretError> {
+        let Some(reference) = SecretReference::parse(stored) else {
+            return Ok(stored.to_string());
+        };
+        let cached = self.cache.get(stored).map(|entry| entry.value().clone());
+        if let Some((resolved_at, value)) = &cached
+            && resolved_at.elapsed() < self.ttl
+        {
+            return Ok(value.clone());
+        }
+        match self.resolver.resolve(&reference).await {
+            Ok(value) => {
+                self.cache.insert(stored.to_string(), (Instant::now(), value.clone()));
+                Ok(value)
+            }
+            Err(error) => match cached {
+                Some((_, value)) => {
+                    warn!(reference = stored, %error, "failed to refresh secret reference; using last resolved value");
+                    Ok(value)
+                }
+                None => Err(error),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Resolves `vault:` references from a fixed value and counts lookups.
+    struct MockResolver {
+        value: std::sync::Mutex<Option<String>>,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl SecretResolver for MockResolver {
+        async fn resolve(&self, reference: &SecretReference) -> Result<String, SecretError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let SecretReference::Vault { path, field } = reference else {
+                panic!("unexpected reference {reference:?}");
+            };
+            self.value.lock().unwrap().clone().ok_or_else(|| SecretError::MissingField {
+                path: path.clone(),
+                field: field.clone(),
+            })
+        }
+    }
+
+    fn mock(value: Option<&str>) -> Arc<MockResolver> {
+        Arc::new(MockResolver {
+            value: std::sync::Mutex::new(value.map(str::to_string)),
+            calls: AtomicUsize::new(0),
+        })
+    }
+
+    #[test]
+    fn test_parse_references() {
+        assert_eq!(SecretReference::parse("sk-inline"), None);
+        assert_eq!(
+            SecretReference::parse("env:OPENAI_KEY"),
+            Some(SecretReference::Env("OPENAI_KEY".to_string()))
+        );
+        assert_eq!(
+            SecretReference::parse("file:/run/secrets/key"),
+            Some(SecretReference::File("/run/secrets/key".to_string()))
+        );
+        assert_eq!(
+            SecretReference::parse("vault:providers/openai#api_key"),
+            Some(SecretReference::Vault {
+                path: "providers/openai".to_string(),
+                field: "api_key".to_string()
+            })
+        );
+        assert_eq!(
+            SecretReference::parse("vault:providers/openai"),
+            Some(SecretReference::Vault {
+                path: "providers/openai".to_string(),
+                field: "value".to_string()
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_references_resolve_and_inline_values_pass_through() {
+        let resolver = mock(Some("sk-from-vault"));
+        let store = SecretStore::with_resolver(resolver.clone(), Duration::from_secs(60));
+
+        assert_eq!(store.resolve("sk-inline").await.unwrap(), "sk-inline");
+        assert_eq!(store.resolve("vault:providers/openai#api_key").await.unwrap(), "sk-from-vault");
+        // Served from the cache until the TTL expires
+        assert_eq!(store.resolve("vault:providers/openai#api_key").await.unwrap(), "sk-from-vault");
+        assert_eq!(resolver.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_expired_references_are_refreshed() {
+        let resolver = mock(Some("sk-old"));
+        let store = SecretStore::with_resolver(resolver.clone(), Duration::ZERO);
+        assert_eq!(store.resolve("vault:providers/openai#api_key").await.unwrap(), "sk-old");
+
+        *resolver.value.lock().unwrap() = Some("sk-rotated".to_string());
+        assert_eq!(store.resolve("vault:providers/openai#api_key").await.unwrap(), "sk-rotated");
+
+        // A failed refresh keeps the last resolved value
+        *resolver.value.lock().unwrap() = None;
+        assert_eq!(store.resolve("vault:providers/openai#api_key").await.unwrap(), "sk-rotated");
+        assert!(store.resolve("vault:providers/other#api_key").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_env_and_file_references() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("key");
+        std::fs::write(&path, "sk-from-file\n").unwrap();
+        let store = SecretStore::new(&SecretsConfig {
+            allowed_env_prefixes: vec!["DWCTL_TEST_".to_string()],
+            allowed_file_dirs: vec![dir.path().display().to_string()],
+            ..SecretsConfig::default()
+        });
+
+        assert_eq!(store.resolve(&format!("file:{}", path.display())).await.unwrap(), "sk-from-file");
+        assert!(matches!(
+            store.resolve("env:DWCTL_TEST_SECRET_THAT_IS_NOT_SET").await,
+            Err(SecretError::MissingEnv(_))
+        ));
+        assert!(matches!(
+            store.resolve("vault:providers/openai#api_key").await,
+            Err(SecretError::VaultNotConfigured)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_references_outside_allowlists_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let allowed = dir.path().join("allowed");
+        std::fs::create_dir(&allowed).unwrap();
+        std::fs::write(dir.path().join("server-secret"), "do-not-send").unwrap();
+        let config = SecretsConfig {
+            allowed_env_prefixes: vec!["PROVIDER_KEY_".to_string()],
+            allowed_file_dirs: vec![allowed.display().to_string()],
+            ..SecretsConfig::default()
+        };
+
+        assert!(config.check_reference("sk-inline").is_ok());
+        assert!(config.check_reference("env:PROVIDER_KEY_OPENAI").is_ok());
+        assert!(config.check_reference(&format!("file:{}/key", allowed.display())).is_ok());
+        assert!(config.check_reference("vault:providers/openai#api_key").is_ok());
+        assert!(config.check_reference("vault:/providers/openai/").is_ok());
+        for refused in [
+            "vault:../../sys/policy#api_key",
+            "vault:providers/../../../auth/token/lookup-self",
+            "vault:providers//openai",
+            "vault:providers/./openai",
+            "vault:providers/%2e%2e/openai",
+            "vault:providers/openai?version=1",
+            "vault:",
+        ] {
+            assert!(
+                matches!(config.check_reference(refused), Err(SecretError::InvalidVaultPath(_))),
+                "{refused} should be refused"
+            );
+        }
+        let escape = format!("file:{}/../server-secret", allowed.display());
+        for refused in ["env:DATABASE_URL", "file:/etc/passwd", "file:relative/key", escape.as_str()] {
+            assert!(
+                matches!(config.check_reference(refused), Err(SecretError::NotAllowed(_))),
+                "{refused} should be refused"
+            );
+        }
+
+        // Stored references are refused at resolve time too
+        let store = SecretStore::new(&config);
+        assert!(matches!(store.resolve(&escape).await, Err(SecretError::NotAllowed(_))));
+        assert!(matches!(
+            store.resolve("vault:../sys/policy").await,
+            Err(SecretError::InvalidVaultPath(_))
+        ));
+        assert!(matches!(
+            SecretStore::new(&SecretsConfig::default()).resolve("env:PATH").await,
+            Err(SecretError::NotAllowed(_))
+        ));
+    }
+
+    #[test]
+    fn test_vault_token_is_redacted_from_debug() {
+        let vault = VaultConfig {
+            address: "https://vault.internal:8200".to_string(),
+            token: "hvs.super-secret".to_string(),
+            mount: default_vault_mount(),
+        };
+        let debug = format!("{vault:?}");
+        assert!(!debug.contains("hvs.super-secret"));
+        assert!(debug.contains("vault.internal"));
+    }
+}
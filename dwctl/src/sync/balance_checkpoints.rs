@@ -0,0 +1,250 @@
+//! Periodic reconciliation of `user_balance_checkpoints` against the ledger.
+//!
+//! The checkpoint table is a total read model: `create_transaction` and the
+//! analytics batcher fold every ledger row into it in the same transaction that
+//! inserts the row, so balance reads are point reads and never sum ledger
+//! history. That only holds while every write goes through those writers.
+//! Manual surgery on `credits_transactions`, or rows written by a binary that
+//! predates write-time folding, leave the checkpoint out of step until someone
+//! re-runs `scripts/backfill_balance_checkpoints.sql`.
+//!
+//! This job runs on the leader and does that heal incrementally: each run takes
+//! the users with ledger rows newer than `lookback`, re-derives each one's
+//! balance from their full ledger (an index-only scan of
+//! `idx_credits_checkpoint_delta`) and corrects any checkpoint that disagrees.
+//! Each user is reconciled in its own short transaction under the checkpoint
+//! row lock the writers also take, so it is safe against live traffic (see
+//! [`Credits::reconcile_balance_checkpoint`]). Users with no recent activity are
+//! left to the backfill script.
+
+use chrono::Utc;
+use metrics::counter;
+use sqlx::PgPool;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::BalanceCheckpointConfig;
+use crate::db::handlers::Credits;
+use crate::metrics::errors::component::BALANCE_CHECKPOINTS;
+
+/// Result of a single reconciliation pass.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReconcileOutcome {
+    /// Users whose checkpoint was compared with the ledger
+    pub users_checked: u64,
+    /// Checkpoints that disagreed with the ledger and were corrected
+    pub checkpoints_healed: u64,
+}
+
+/// Reconcile the checkpoint of every user with ledger activity in the last `lookback`.
+pub async fn reconcile_recent_balances(pool: &PgPool, lookback: std::time::Duration) -> anyhow::Result<ReconcileOutcome> {
+    let since = Utc::now() - chrono::Duration::from_std(lookback)?;
+    let users = {
+        let mut conn = pool.acquire().await?;
+        Credits::new(&mut conn).users_with_transactions_since(since).await?
+    };
+
+    let mut outcome = ReconcileOutcome::default();
+    for user_id in users {
+        let mut tx = pool.begin().await?;
+        let correction = Credits::new(&mut tx).reconcile_balance_checkpoint(user_id).await?;
+        tx.commit().await?;
+
+        outcome.users_checked += 1;
+        if let Some(correction) = correction {
+            tracing::warn!(%user_id, %correction, "Healed balance checkpoint that disagreed with the ledger");
+            counter!("dwctl_balance_checkpoint_corrections_total").increment(1);
+            outcome.checkpoints_healed += 1;
+        }
+    }
+
+    Ok(outcome)
+}
+
+/// Run the reconciliation every `run_interval` until `shutdown` is cancelled.
+///
+/// Only run this on the leader replica; concurrent passes are safe but wasteful.
+pub async fn run_balance_checkpoint_job(pool: PgPool, config: BalanceCheckpointConfig, shutdown: CancellationToken) -> anyhow::Result<()> {
+    tracing::info!(
+        run_interval = %humantime::format_duration(config.run_interval),
+        lookback = %humantime::format_duration(config.lookback),
+        "Starting balance checkpoint reconciliation job"
+    );
+
+    let mut interval = tokio::time::interval(config.run_interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => {
+                tracing::info!("Balance checkpoint reconciliation job shutting down");
+                break;
+            }
+            _ = interval.tick() => {
+                match reconcile_recent_balances(&pool, config.lookback).await {
+                    Ok(outcome) => tracing::debug!(
+                        users_checked = outcome.users_checked,
+                        checkpoints_healed = outcome.checkpoints_healed,
+                        "Reconciled balance checkpoints"
+                    ),
+                    Err(e) => {
+                        crate::background_error!(BALANCE_CHECKPOINTS, "reconcile", Warning, error = %e, "Failed to reconcile balance checkpoints");
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::models::users::Role;
+    use crate::db::models::credits::{CreditTransactionCreateDBRequest, CreditTransactionType};
+    use crate::test::utils::create_test_user;
+    use crate::types::UserId;
+    use rust_decimal::Decimal;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    const LOOKBACK: Duration = Duration::from_secs(15 * 60);
+
+    fn transaction(user_id: UserId, transaction_type: CreditTransactionType, amount: i64) -> CreditTransactionCreateDBRequest {
+        CreditTransactionCreateDBRequest {
+            user_id,
+            transaction_type,
+            amount: Decimal::from(amount),
+            source_id: Uuid::new_v4().to_string(),
+            description: None,
+            fusillade_batch_id: None,
+            api_key_id: None,
+        }
+    }
+
+    async fn checkpoint_balance(pool: &PgPool, user_id: UserId) -> Decimal {
+        sqlx::query_scalar("SELECT balance FROM user_balance_checkpoints WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn ledger_balance(pool: &PgPool, user_id: UserId) -> Decimal {
+        sqlx::query_scalar(
+            "SELECT COALESCE(SUM(CASE WHEN transaction_type IN ('admin_grant', 'purchase') THEN amount ELSE -amount END), 0)
+             FROM credits_transactions WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test]
+    async fn test_reconcile_heals_drifted_checkpoint_to_full_history(pool: PgPool) {
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        let untouched = create_test_user(&pool, Role::StandardUser).await;
+        {
+            let mut conn = pool.acquire().await.unwrap();
+            let mut credits = Credits::new(&mut conn);
+            credits
+                .create_transaction(&transaction(user.id, CreditTransactionType::Purchase, 100))
+                .await
+                .unwrap();
+            credits
+                .create_transaction(&transaction(user.id, CreditTransactionType::Usage, 30))
+                .await
+                .unwrap();
+            credits
+                .create_transaction(&transaction(untouched.id, CreditTransactionType::AdminGrant, 5))
+                .await
+                .unwrap();
+        }
+
+        // Nothing to heal while every write went through the writers
+        let outcome = reconcile_recent_balances(&pool, LOOKBACK).await.unwrap();
+        assert_eq!(outcome.users_checked, 2);
+        assert_eq!(outcome.checkpoints_healed, 0);
+
+        // Simulate a checkpoint left stale by a write that bypassed the fold
+        sqlx::query("UPDATE user_balance_checkpoints SET balance = 12 WHERE user_id = $1")
+            .bind(user.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let outcome = reconcile_recent_balances(&pool, LOOKBACK).await.unwrap();
+        assert_eq!(outcome.checkpoints_healed, 1);
+        assert_eq!(checkpoint_balance(&pool, user.id).await, Decimal::from(70));
+        assert_eq!(checkpoint_balance(&pool, user.id).await, ledger_balance(&pool, user.id).await);
+        assert_eq!(checkpoint_balance(&pool, untouched.id).await, Decimal::from(5));
+    }
+
+    #[sqlx::test]
+    async fn test_reconcile_skips_users_without_recent_activity(pool: PgPool) {
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        {
+            let mut conn = pool.acquire().await.unwrap();
+            Credits::new(&mut conn)
+                .create_transaction(&transaction(user.id, CreditTransactionType::Purchase, 100))
+                .await
+                .unwrap();
+        }
+        sqlx::query("UPDATE user_balance_checkpoints SET balance = 1 WHERE user_id = $1")
+            .bind(user.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let outcome = reconcile_recent_balances(&pool, Duration::ZERO).await.unwrap();
+        assert_eq!(outcome, ReconcileOutcome::default());
+        assert_eq!(checkpoint_balance(&pool, user.id).await, Decimal::from(1));
+    }
+
+    #[sqlx::test]
+    async fn test_reconcile_during_concurrent_transactions_does_not_double_count(pool: PgPool) {
+        let user = create_test_user(&pool, Role::StandardUser).await;
+        {
+            let mut conn = pool.acquire().await.unwrap();
+            Credits::new(&mut conn)
+                .create_transaction(&transaction(user.id, CreditTransactionType::AdminGrant, 1000))
+                .await
+                .unwrap();
+        }
+
+        // 50 grants of 10 and 50 usages of 5, racing repeated reconciliations
+        let user_id = user.id;
+        let mut writers = tokio::task::JoinSet::new();
+        for i in 0..100 {
+            let pool = pool.clone();
+            writers.spawn(async move {
+                let request = if i % 2 == 0 {
+                    transaction(user_id, CreditTransactionType::AdminGrant, 10)
+                } else {
+                    transaction(user_id, CreditTransactionType::Usage, 5)
+                };
+                let mut conn = pool.acquire().await.unwrap();
+                Credits::new(&mut conn).create_transaction(&request).await.unwrap();
+            });
+        }
+        let reconciler = {
+            let pool = pool.clone();
+            tokio::spawn(async move {
+                let mut healed = 0;
+                for _ in 0..20 {
+                    healed += reconcile_recent_balances(&pool, LOOKBACK).await.unwrap().checkpoints_healed;
+                    tokio::task::yield_now().await;
+                }
+                healed
+            })
+        };
+        while let Some(result) = writers.join_next().await {
+            result.unwrap();
+        }
+
+        assert_eq!(reconciler.await.unwrap(), 0, "a correctly folded checkpoint never needs healing");
+        assert_eq!(checkpoint_balance(&pool, user.id).await, Decimal::from(1250));
+        assert_eq!(ledger_balance(&pool, user.id).await, Decimal::from(1250));
+    }
+}
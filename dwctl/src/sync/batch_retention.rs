@@ -0,0 +1,401 @@
+//! Retention of finished batches.
+//!
+//! Batches keep their input, output and error files, and fusillade keeps a row
+//! per request, for as long as the batch exists, so storage grows with every
+//! batch ever run. When enabled, this job runs on the leader and archives
+//! finished (completed, failed or cancelled) batches created more than
+//! `max_age` ago. For each one it:
+//!
+//! 1. Exports the output and error files as JSONL to
+//!    `{cold_storage_path}/{batch_id}/`, if a cold storage path is configured.
+//! 2. Records the batch object in `archived_batches`, so the batch API can
+//!    still return it, marked as archived.
+//! 3. Deletes the output and error files, and the input file unless another
+//!    batch still uses it.
+//! 4. Soft-deletes the batch. Fusillade's purge then removes its requests.
+//!
+//! Every step can be repeated, so a batch that fails part-way is picked up
+//! again on the next run.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::Utc;
+use fusillade::Storage;
+use fusillade_arsenal::PostgresRequestManager;
+use futures::StreamExt;
+use sqlx::PgPool;
+use sqlx_pool_router::DbPools;
+use tokio::io::AsyncWriteExt;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::BatchRetentionConfig;
+use crate::db::handlers::ArchivedBatches;
+use crate::db::models::archived_batches::ArchivedBatchCreateDBRequest;
+use crate::metrics::errors::component::BATCH_RETENTION;
+
+/// Fusillade list filters that together cover every finished batch.
+const FINISHED_STATUSES: [&str; 3] = ["completed", "failed", "cancelled"];
+
+/// Result of a single retention pass.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionOutcome {
+    /// Batches archived and soft-deleted
+    pub batches_archived: u64,
+    /// Input, output and error files deleted
+    pub files_deleted: u64,
+}
+
+/// Archive up to `batches_per_run` finished batches created more than `max_age` ago.
+///
+/// A batch that fails to archive is logged and skipped; the others still are.
+pub async fn archive_expired_batches(
+    pool: &PgPool,
+    request_manager: &PostgresRequestManager<DbPools>,
+    config: &BatchRetentionConfig,
+) -> anyhow::Result<RetentionOutcome> {
+    let cutoff = Utc::now() - chrono::Duration::from_std(config.max_age)?;
+
+    let mut seen = HashSet::new();
+    let mut expired = Vec::new();
+    for status in FINISHED_STATUSES {
+        let remaining = config.batches_per_run - expired.len() as i64;
+        if remaining <= 0 {
+            break;
+        }
+        let batches = request_manager
+            .list_batches(fusillade::ListBatchesFilter {
+                status: Some(status.to_string()),
+                created_before: Some(cutoff),
+                limit: Some(remaining),
+                ..Default::default()
+            })
+            .await?;
+        // The "cancelled" filter also matches batches that are still cancelling
+        expired.extend(
+            batches
+                .into_iter()
+                .filter(|b| (b.completed_at.is_some() || b.failed_at.is_some() || b.cancelled_at.is_some()) && seen.insert(b.id)),
+        );
+    }
+
+    let mut outcome = RetentionOutcome::default();
+    for batch in expired {
+        let batch_id = batch.id;
+        match archive_batch(pool, request_manager, batch, config.cold_storage_path.as_deref()).await {
+            Ok(files_deleted) => {
+                outcome.batches_archived += 1;
+                outcome.files_deleted += files_deleted;
+            }
+            Err(e) => {
+                crate::background_error!(BATCH_RETENTION, "archive_batch", Warning, error = %e, batch_id = %batch_id, "Failed to archive batch");
+            }
+        }
+    }
+
+    Ok(outcome)
+}
+
+/// Archive one batch. Returns the number of files deleted.
+async fn archive_batch(
+    pool: &PgPool,
+    request_manager: &PostgresRequestManager<DbPools>,
+    batch: fusillade::Batch,
+    cold_storage_path: Option<&Path>,
+) -> anyhow::Result<u64> {
+    let batch_id = batch.id;
+
+    let cold_storage_dir = match cold_storage_path {
+        Some(root) => Some(export_batch_files(request_manager, &batch, root).await?),
+        None => None,
+    };
+
+    // The snapshot is what the batch API serves from now on. The output and
+    // error files are about to be deleted, so it must not point at them.
+    let mut snapshot = crate::api::handlers::batches::to_batch_response_with_email(batch.clone(), None);
+    snapshot.output_file_id = None;
+    snapshot.error_file_id = None;
+    {
+        let mut conn = pool.acquire().await?;
+        ArchivedBatches::new(&mut conn)
+            .create(&ArchivedBatchCreateDBRequest {
+                batch_id: batch_id.0,
+                created_by: batch.created_by.clone(),
+                completion_window: batch.completion_window.clone(),
+                batch_created_at: batch.created_at,
+                cold_storage_path: cold_storage_dir.map(|dir| dir.to_string_lossy().into_owned()),
+                snapshot: serde_json::to_value(&snapshot)?,
+            })
+            .await?;
+    }
+
+    let mut files_deleted = 0;
+    for file_id in [batch.output_file_id, batch.error_file_id].into_iter().flatten() {
+        request_manager.delete_file(file_id).await?;
+        files_deleted += 1;
+    }
+    // Deleting a file cancels the batches that use it, so an input file shared
+    // with another batch is left for that batch
+    if let Some(file_id) = batch.file_id {
+        let users = request_manager.list_file_batches(file_id).await?;
+        if users.iter().all(|b| b.batch_id == batch_id) {
+            request_manager.delete_file(file_id).await?;
+            files_deleted += 1;
+        }
+    }
+
+    request_manager.delete_batch(batch_id).await?;
+    tracing::debug!(batch_id = %batch_id, files_deleted, "Archived batch");
+
+    Ok(files_deleted)
+}
+
+/// Write the batch's output and error files to `{root}/{batch_id}/` as
+/// `output.jsonl` and `errors.jsonl`. Returns the directory.
+async fn export_batch_files(
+    request_manager: &PostgresRequestManager<DbPools>,
+    batch: &fusillade::Batch,
+    root: &Path,
+) -> anyhow::Result<PathBuf> {
+    let dir = root.join(batch.id.0.to_string());
+    tokio::fs::create_dir_all(&dir).await?;
+
+    for (file_id, name) in [(batch.output_file_id, "output.jsonl"), (batch.error_file_id, "errors.jsonl")] {
+        let Some(file_id) = file_id else { continue };
+        let mut writer = tokio::io::BufWriter::new(tokio::fs::File::create(dir.join(name)).await?);
+        let mut content = request_manager.get_file_content_stream(file_id, 0, None);
+        while let Some(item) = content.next().await {
+            let line = match item? {
+                fusillade::FileContentItem::Output(output) => serde_json::to_string(&output)?,
+                fusillade::FileContentItem::Error(error) => serde_json::to_string(&error)?,
+                fusillade::FileContentItem::Template(_) => anyhow::bail!("file {file_id} is not a batch output or error file"),
+            };
+            writer.write_all(line.as_bytes()).await?;
+            writer.write_all(b"\n").await?;
+        }
+        writer.flush().await?;
+    }
+
+    Ok(dir)
+}
+
+/// Run the retention job every `run_interval` until `shutdown` is cancelled.
+///
+/// Only run this on the leader replica; concurrent passes would archive the same batches.
+pub async fn run_batch_retention_job(
+    pool: PgPool,
+    request_manager: Arc<PostgresRequestManager<DbPools>>,
+    config: BatchRetentionConfig,
+    shutdown: CancellationToken,
+) -> anyhow::Result<()> {
+    tracing::info!(
+        max_age = %humantime::format_duration(config.max_age),
+        run_interval = %humantime::format_duration(config.run_interval),
+        cold_storage_path = ?config.cold_storage_path,
+        "Starting batch retention job"
+    );
+
+    let mut interval = tokio::time::interval(config.run_interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => {
+                tracing::info!("Batch retention job shutting down");
+                break;
+            }
+            _ = interval.tick() => {
+                match archive_expired_batches(&pool, &request_manager, &config).await {
+                    Ok(outcome) => tracing::debug!(
+                        batches_archived = outcome.batches_archived,
+                        files_deleted = outcome.files_deleted,
+                        "Applied batch retention"
+                    ),
+                    Err(e) => {
+                        crate::background_error!(BATCH_RETENTION, "apply", Warning, error = %e, "Failed to apply batch retention");
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::models::users::Role;
+    use crate::test::utils::*;
+    use axum::http::StatusCode;
+    use uuid::Uuid;
+
+    /// Insert a completed batch with one completed request, created `age_days` ago.
+    /// Returns the batch ID and its input, output and error file IDs.
+    async fn insert_completed_batch(pool: &PgPool, owner: Uuid, age_days: i64) -> (Uuid, [Uuid; 3]) {
+        let batch_id = Uuid::new_v4();
+        let files = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        for (file_id, purpose) in files.iter().zip(["batch", "batch_output", "batch_error"]) {
+            sqlx::query(
+                "INSERT INTO fusillade.files (id, name, status, purpose, created_at, updated_at) VALUES ($1, 'test.jsonl', 'processed', $2, NOW(), NOW())",
+            )
+            .bind(file_id)
+            .bind(purpose)
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+
+        let created_at = Utc::now() - chrono::Duration::days(age_days);
+        sqlx::query(
+            "INSERT INTO fusillade.batches (id, created_by, file_id, output_file_id, error_file_id, endpoint, completion_window, expires_at, created_at,
+                                            requests_started_at, finalizing_at, completed_at, total_requests, completed_requests, counts_frozen_at)
+             VALUES ($1, $2, $3, $4, $5, '/v1/chat/completions', '24h', $6 + interval '24 hours', $6, $6, $6, $6, 1, 1, $6)",
+        )
+        .bind(batch_id)
+        .bind(owner.to_string())
+        .bind(files[0])
+        .bind(files[1])
+        .bind(files[2])
+        .bind(created_at)
+        .execute(pool)
+        .await
+        .unwrap();
+
+        let template_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO fusillade.request_templates (id, file_id, model, api_key, endpoint, path, body, custom_id, method) VALUES ($1, $2, 'test-model', 'test-key', 'http://test', '/v1/chat/completions', '{}', 'req-1', 'POST')",
+        )
+        .bind(template_id)
+        .bind(files[0])
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO fusillade.requests (id, batch_id, template_id, custom_id, model, state, response_status, response_body, created_at, completed_at) VALUES ($1, $2, $3, 'req-1', 'test-model', 'completed', 200, '{\"id\": \"chatcmpl-1\"}', $4, $4)",
+        )
+        .bind(Uuid::new_v4())
+        .bind(batch_id)
+        .bind(template_id)
+        .bind(created_at)
+        .execute(pool)
+        .await
+        .unwrap();
+
+        (batch_id, files)
+    }
+
+    async fn batch_deleted(pool: &PgPool, batch_id: Uuid) -> bool {
+        sqlx::query_scalar::<_, Option<chrono::DateTime<Utc>>>("SELECT deleted_at FROM fusillade.batches WHERE id = $1")
+            .bind(batch_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+            .is_some()
+    }
+
+    async fn live_files(pool: &PgPool, file_ids: &[Uuid]) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM fusillade.files WHERE id = ANY($1) AND deleted_at IS NULL")
+            .bind(file_ids)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_old_batch_is_archived_and_recent_batch_retained(pool: PgPool) {
+        let (app, bg_services) = create_test_app(pool.clone(), false).await;
+        let user = create_test_user_with_roles(&pool, vec![Role::StandardUser, Role::BatchAPIUser]).await;
+        let auth = add_auth_headers(&user);
+
+        let (old_batch, old_files) = insert_completed_batch(&pool, user.id, 100).await;
+        let (recent_batch, recent_files) = insert_completed_batch(&pool, user.id, 1).await;
+
+        let cold_storage = tempfile::tempdir().unwrap();
+        let config = BatchRetentionConfig {
+            enabled: true,
+            cold_storage_path: Some(cold_storage.path().to_path_buf()),
+            ..Default::default()
+        };
+
+        let outcome = archive_expired_batches(&pool, &bg_services.request_manager, &config).await.unwrap();
+        assert_eq!(
+            outcome,
+            RetentionOutcome {
+                batches_archived: 1,
+                files_deleted: 3
+            }
+        );
+
+        // The old batch and its files are gone; the recent one is untouched
+        assert!(batch_deleted(&pool, old_batch).await);
+        assert_eq!(live_files(&pool, &old_files).await, 0);
+        assert!(!batch_deleted(&pool, recent_batch).await);
+        assert_eq!(live_files(&pool, &recent_files).await, 3);
+
+        // Its output was exported to cold storage first
+        let output = std::fs::read_to_string(cold_storage.path().join(old_batch.to_string()).join("output.jsonl")).unwrap();
+        assert_eq!(output.lines().count(), 1);
+        assert!(output.contains("\"custom_id\":\"req-1\""));
+
+        // The API still returns the archived batch, marked as archived
+        let resp = app
+            .get(&format!("/ai/v1/batches/{old_batch}"))
+            .add_header(&auth[0].0, &auth[0].1)
+            .add_header(&auth[1].0, &auth[1].1)
+            .await;
+        resp.assert_status(StatusCode::OK);
+        let archived: serde_json::Value = resp.json();
+        assert_eq!(archived["status"], "completed");
+        assert!(archived["dwext"]["archived_at"].is_i64());
+        assert!(archived["output_file_id"].is_null());
+
+        let resp = app
+            .get(&format!("/ai/v1/batches/{recent_batch}"))
+            .add_header(&auth[0].0, &auth[0].1)
+            .add_header(&auth[1].0, &auth[1].1)
+            .await;
+        resp.assert_status(StatusCode::OK);
+        assert!(resp.json::<serde_json::Value>()["dwext"]["archived_at"].is_null());
+
+        // Live and archived listings are disjoint
+        let listed_ids = |body: serde_json::Value| -> Vec<String> {
+            body["data"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|b| b["id"].as_str().unwrap().to_string())
+                .collect()
+        };
+        let resp = app
+            .get("/ai/v1/batches")
+            .add_header(&auth[0].0, &auth[0].1)
+            .add_header(&auth[1].0, &auth[1].1)
+            .await;
+        resp.assert_status(StatusCode::OK);
+        assert_eq!(listed_ids(resp.json()), vec![recent_batch.to_string()]);
+
+        let resp = app
+            .get("/ai/v1/batches?archived=true")
+            .add_header(&auth[0].0, &auth[0].1)
+            .add_header(&auth[1].0, &auth[1].1)
+            .await;
+        resp.assert_status(StatusCode::OK);
+        assert_eq!(listed_ids(resp.json()), vec![old_batch.to_string()]);
+
+        // Another user can't see the archived batch
+        let other = create_test_user_with_roles(&pool, vec![Role::StandardUser, Role::BatchAPIUser]).await;
+        let other_auth = add_auth_headers(&other);
+        app.get(&format!("/ai/v1/batches/{old_batch}"))
+            .add_header(&other_auth[0].0, &other_auth[0].1)
+            .add_header(&other_auth[1].0, &other_auth[1].1)
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        // Nothing left to archive on the next run
+        let outcome = archive_expired_batches(&pool, &bg_services.request_manager, &config).await.unwrap();
+        assert_eq!(outcome, RetentionOutcome::default());
+    }
+}
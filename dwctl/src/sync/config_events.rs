@@ -0,0 +1,375 @@
+//! Configuration change events for `GET /admin/api/v1/events`.
+//!
+//! A replica's subscribers share one LISTEN connection on the channel that
+//! drives the onwards config sync, plus [`PROBE_HEALTH_CHANGED_CHANNEL`],
+//! opened by the first subscriber and closed once none are left. The triggers
+//! behind those channels fire per statement and don't say which rows changed,
+//! so on each relevant notification the listener reloads the deployments and
+//! endpoints, diffs them against what it last saw and broadcasts the result.
+//!
+//! Notifications sent while the LISTEN connection is down are lost, so every
+//! stream ends when the connection drops, as does the stream of a subscriber
+//! that falls [`EVENT_CHANNEL_BUFFER`] events behind. Clients reconnect and
+//! start again from a fresh snapshot.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use sqlx::PgPool;
+use sqlx::postgres::{PgListener, PgNotification};
+use tokio::sync::{Mutex, broadcast};
+use tracing::{debug, warn};
+
+use crate::api::models::events::{ConfigEvent, ConfigSnapshot, DeploymentSummary, EndpointHealthChange, EndpointSummary};
+use crate::config::ONWARDS_CONFIG_CHANGED_CHANNEL;
+use crate::types::{DeploymentId, InferenceEndpointId};
+
+/// Channel notified when a probe's debounced health changes.
+pub static PROBE_HEALTH_CHANGED_CHANNEL: &str = "probe_health_changed";
+
+/// Tables on [`ONWARDS_CONFIG_CHANGED_CHANNEL`] whose changes produce events.
+const WATCHED_TABLES: &[&str] = &["deployed_models", "inference_endpoints"];
+
+/// Events buffered per subscriber before it is dropped for falling behind.
+const EVENT_CHANNEL_BUFFER: usize = 64;
+
+/// Most event streams a replica serves at once.
+pub const MAX_SUBSCRIBERS: usize = 64;
+
+/// Deployments and endpoints as last seen by a subscriber.
+#[derive(Debug)]
+struct ConfigState {
+    deployments: BTreeMap<DeploymentId, DeploymentSummary>,
+    endpoints: BTreeMap<InferenceEndpointId, EndpointSummary>,
+}
+
+impl ConfigState {
+    fn snapshot(&self) -> ConfigSnapshot {
+        ConfigSnapshot {
+            deployments: self.deployments.values().cloned().collect(),
+            endpoints: self.endpoints.values().cloned().collect(),
+        }
+    }
+}
+
+/// Load the current (non-deleted) deployments, their probe health and the endpoints.
+async fn load_state(pool: &PgPool) -> Result<ConfigState, sqlx::Error> {
+    let deployments = sqlx::query!(
+        r#"
+        SELECT
+            d.id as "id!",
+            d.alias as "alias!",
+            d.model_name as "model_name!",
+            d.hosted_on,
+            d.updated_at as "updated_at!",
+            ph.healthy as "healthy?"
+        FROM deployed_models d
+        LEFT JOIN probes p ON p.deployment_id = d.id AND p.active
+        LEFT JOIN probe_health ph ON ph.probe_id = p.id
+        WHERE d.deleted = false
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let endpoints = sqlx::query!("SELECT id, name, url, updated_at FROM inference_endpoints")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(ConfigState {
+        deployments: deployments
+            .into_iter()
+            .map(|row| {
+                let summary = DeploymentSummary {
+                    id: row.id,
+                    alias: row.alias,
+                    model_name: row.model_name,
+                    endpoint_id: row.hosted_on,
+                    healthy: row.healthy,
+                    updated_at: row.updated_at,
+                };
+                (summary.id, summary)
+            })
+            .collect(),
+        endpoints: endpoints
+            .into_iter()
+            .map(|row| {
+                let summary = EndpointSummary {
+                    id: row.id,
+                    name: row.name,
+                    url: row.url,
+                    updated_at: row.updated_at,
+                };
+                (summary.id, summary)
+            })
+            .collect(),
+    })
+}
+
+/// Events that turn `previous` into `next`. Endpoints are created before the
+/// deployments they host and deleted after them.
+fn diff(previous: &ConfigState, next: &ConfigState) -> Vec<ConfigEvent> {
+    let mut events = Vec::new();
+
+    for (id, endpoint) in &next.endpoints {
+        match previous.endpoints.get(id) {
+            None => events.push(ConfigEvent::EndpointCreated(endpoint.clone())),
+            Some(old) if old != endpoint => events.push(ConfigEvent::EndpointUpdated(endpoint.clone())),
+            Some(_) => {}
+        }
+    }
+
+    for (id, deployment) in &next.deployments {
+        let Some(old) = previous.deployments.get(id) else {
+            events.push(ConfigEvent::DeploymentCreated(deployment.clone()));
+            continue;
+        };
+        // Health is reported separately, so it doesn't count as an update.
+        let ignoring_health = DeploymentSummary {
+            healthy: old.healthy,
+            ..deployment.clone()
+        };
+        if &ignoring_health != old {
+            events.push(ConfigEvent::DeploymentUpdated(deployment.clone()));
+        }
+        if old.healthy != deployment.healthy {
+            events.push(ConfigEvent::EndpointHealthChanged(EndpointHealthChange {
+                endpoint_id: deployment.endpoint_id,
+                deployment_id: deployment.id,
+                alias: deployment.alias.clone(),
+                healthy: deployment.healthy,
+            }));
+        }
+    }
+
+    for (id, deployment) in &previous.deployments {
+        if !next.deployments.contains_key(id) {
+            events.push(ConfigEvent::DeploymentDeleted(deployment.clone()));
+        }
+    }
+
+    for (id, endpoint) in &previous.endpoints {
+        if !next.endpoints.contains_key(id) {
+            events.push(ConfigEvent::EndpointDeleted(endpoint.clone()));
+        }
+    }
+
+    events
+}
+
+/// Whether a notification can change what subscribers see.
+fn is_relevant(notification: &PgNotification) -> bool {
+    if notification.channel() == PROBE_HEALTH_CHANGED_CHANNEL {
+        return true;
+    }
+    // Payload format: "table_name:epoch_microseconds"
+    let table = notification.payload().split(':').next().unwrap_or_default();
+    WATCHED_TABLES.contains(&table)
+}
+
+/// Errors subscribing to configuration change events.
+#[derive(Debug, thiserror::Error)]
+pub enum SubscribeError {
+    #[error("at most {MAX_SUBSCRIBERS} config event streams can be open at once")]
+    TooManySubscribers,
+
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// The shared listener's view of the config and the channel it broadcasts on.
+struct Feed {
+    state: ConfigState,
+    sender: broadcast::Sender<ConfigEvent>,
+}
+
+/// Per-replica source of configuration change events. Cheap to clone.
+#[derive(Clone, Default)]
+pub struct ConfigEvents {
+    feed: Arc<Mutex<Option<Feed>>>,
+}
+
+impl ConfigEvents {
+    /// Subscribe to configuration change events, starting the shared listener
+    /// if there is no other subscriber.
+    pub async fn subscribe(&self, pool: &PgPool) -> Result<Subscription, SubscribeError> {
+        let mut feed = self.feed.lock().await;
+        if feed.is_none() {
+            *feed = Some(self.start(pool).await?);
+        }
+        let feed = feed.as_ref().expect("feed started above");
+        if feed.sender.receiver_count() >= MAX_SUBSCRIBERS {
+            return Err(SubscribeError::TooManySubscribers);
+        }
+        Ok(Subscription {
+            snapshot: Some(feed.state.snapshot()),
+            events: feed.sender.subscribe(),
+        })
+    }
+
+    /// Open the LISTEN connection and spawn the task that broadcasts its events.
+    async fn start(&self, pool: &PgPool) -> Result<Feed, sqlx::Error> {
+        // Listen before loading the snapshot so no change falls between the two.
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener
+            .listen_all([ONWARDS_CONFIG_CHANGED_CHANNEL, PROBE_HEALTH_CHANGED_CHANNEL])
+            .await?;
+        let state = load_state(pool).await?;
+        let (sender, _) = broadcast::channel(EVENT_CHANNEL_BUFFER);
+
+        let shared = self.feed.clone();
+        let pool = pool.clone();
+        tokio::spawn(async move {
+            loop {
+                match listener.try_recv().await {
+                    Ok(Some(notification)) if is_relevant(&notification) => {}
+                    Ok(Some(_)) => continue,
+                    Ok(None) => {
+                        debug!("Config event listener connection lost, closing streams");
+                        break;
+                    }
+                    Err(e) => {
+                        warn!(error = %e, "Config event listener failed, closing streams");
+                        break;
+                    }
+                }
+
+                let next = match load_state(&pool).await {
+                    Ok(next) => next,
+                    Err(e) => {
+                        warn!(error = %e, "Failed to reload config for event streams, closing streams");
+                        break;
+                    }
+                };
+                let mut feed = shared.lock().await;
+                let Some(current) = feed.as_mut() else { return };
+                if current.sender.receiver_count() == 0 {
+                    debug!("No config event subscribers left, closing listener");
+                    *feed = None;
+                    return;
+                }
+                for event in diff(&current.state, &next) {
+                    // Only fails once every subscriber has gone, which the
+                    // next notification notices
+                    let _ = current.sender.send(event);
+                }
+                current.state = next;
+            }
+            // Dropping the sender ends every subscriber's stream
+            *shared.lock().await = None;
+        });
+
+        Ok(Feed { state, sender })
+    }
+}
+
+/// One subscriber's configuration change events.
+pub struct Subscription {
+    snapshot: Option<ConfigSnapshot>,
+    events: broadcast::Receiver<ConfigEvent>,
+}
+
+impl Subscription {
+    /// The next event: a [`ConfigEvent::Snapshot`] first, then changes as they
+    /// happen. `None` once the shared listener has stopped or this subscriber
+    /// fell behind, which ends the subscription.
+    pub async fn recv(&mut self) -> Option<ConfigEvent> {
+        if let Some(snapshot) = self.snapshot.take() {
+            return Some(ConfigEvent::Snapshot(snapshot));
+        }
+        match self.events.recv().await {
+            Ok(event) => Some(event),
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                debug!(skipped, "Config event subscriber fell behind, closing stream");
+                None
+            }
+            Err(broadcast::error::RecvError::Closed) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use sqlx::PgPool;
+
+    use super::{ConfigEvents, MAX_SUBSCRIBERS, SubscribeError, Subscription};
+    use crate::api::models::events::ConfigEvent;
+    use crate::api::models::users::Role;
+    use crate::test::utils::{create_test_endpoint, create_test_model, create_test_user};
+
+    async fn next_event(rx: &mut Subscription) -> ConfigEvent {
+        tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .expect("timed out waiting for a config event")
+            .expect("event stream closed")
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_deployment_creation_produces_event(pool: PgPool) {
+        let user = create_test_user(&pool, Role::PlatformManager).await;
+        let endpoint_id = create_test_endpoint(&pool, "events-endpoint", user.id).await;
+
+        let events = ConfigEvents::default();
+        let mut rx = events.subscribe(&pool).await.unwrap();
+        let ConfigEvent::Snapshot(snapshot) = next_event(&mut rx).await else {
+            panic!("first event must be a snapshot");
+        };
+        assert!(snapshot.endpoints.iter().any(|e| e.id == endpoint_id));
+        assert!(snapshot.deployments.is_empty());
+
+        let deployment_id = create_test_model(&pool, "events-model-name", "events-model", endpoint_id, user.id).await;
+
+        let ConfigEvent::DeploymentCreated(deployment) = next_event(&mut rx).await else {
+            panic!("expected a deployment.created event");
+        };
+        assert_eq!(deployment.id, deployment_id);
+        assert_eq!(deployment.alias, "events-model");
+        assert_eq!(deployment.endpoint_id, Some(endpoint_id));
+        assert_eq!(deployment.healthy, None);
+
+        // Soft-deleting the deployment reports it as deleted.
+        sqlx::query("UPDATE deployed_models SET deleted = true WHERE id = $1")
+            .bind(deployment_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let ConfigEvent::DeploymentDeleted(deployment) = next_event(&mut rx).await else {
+            panic!("expected a deployment.deleted event");
+        };
+        assert_eq!(deployment.id, deployment_id);
+    }
+
+    #[sqlx::test]
+    #[test_log::test]
+    async fn test_subscribers_share_one_listener_up_to_the_cap(pool: PgPool) {
+        let user = create_test_user(&pool, Role::PlatformManager).await;
+        let endpoint_id = create_test_endpoint(&pool, "shared-endpoint", user.id).await;
+
+        let events = ConfigEvents::default();
+        let mut subscriptions = Vec::new();
+        for _ in 0..MAX_SUBSCRIBERS {
+            let mut rx = events.subscribe(&pool).await.unwrap();
+            assert!(matches!(next_event(&mut rx).await, ConfigEvent::Snapshot(_)));
+            subscriptions.push(rx);
+        }
+        assert!(matches!(events.subscribe(&pool).await, Err(SubscribeError::TooManySubscribers)));
+
+        // Every subscriber sees the change; a closed stream frees its place
+        let deployment_id = create_test_model(&pool, "shared-model-name", "shared-model", endpoint_id, user.id).await;
+        for rx in &mut subscriptions {
+            let ConfigEvent::DeploymentCreated(deployment) = next_event(rx).await else {
+                panic!("expected a deployment.created event");
+            };
+            assert_eq!(deployment.id, deployment_id);
+        }
+        subscriptions.pop();
+        let mut rx = events.subscribe(&pool).await.unwrap();
+        let ConfigEvent::Snapshot(snapshot) = next_event(&mut rx).await else {
+            panic!("first event must be a snapshot");
+        };
+        assert!(snapshot.deployments.iter().any(|d| d.id == deployment_id));
+    }
+}
@@ -1,12 +1,13 @@
 //! Model fetching from external sources.
 
 use crate::api::models::inference_endpoints::{AnthropicModelsResponse, OpenAIModelsResponse, OpenRouterModelsResponse};
-use crate::db::models::inference_endpoints::InferenceEndpointDBResponse;
+use crate::config::EndpointSyncConfig;
+use crate::db::models::inference_endpoints::{EndpointTlsConfig, InferenceEndpointDBResponse};
 use anyhow::anyhow;
 use async_trait::async_trait;
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
 use std::time::Duration;
-use tracing::{debug, instrument};
+use tracing::{debug, instrument, warn};
 use url::Url;
 
 #[derive(Debug, Clone)]
@@ -55,6 +56,8 @@ pub struct FetchModelsReqwest {
     auth_header_prefix: String,
     request_timeout: Duration,
     format_override: Option<ModelFormat>,
+    max_retries: u32,
+    retry_initial_delay: Duration,
 }
 
 impl FetchModelsReqwest {
@@ -77,6 +80,61 @@ impl FetchModelsReqwest {
             auth_header_prefix,
             request_timeout,
             format_override,
+            max_retries: 0,
+            retry_initial_delay: Duration::ZERO,
+        }
+    }
+
+    /// Retry transient failures according to `config` (by default a single attempt is made).
+    pub fn with_retry(mut self, config: &EndpointSyncConfig) -> Self {
+        self.max_retries = config.max_retries;
+        self.retry_initial_delay = Duration::from_millis(config.retry_initial_delay_milliseconds);
+        self
+    }
+
+    /// Trust the endpoint's custom CA bundle, if it has one. The client
+    /// certificate isn't presented when listing models.
+    pub fn with_endpoint_tls(mut self, tls: Option<&EndpointTlsConfig>) -> Self {
+        let Some(ca_bundle) = tls.and_then(|tls| tls.ca_bundle.as_deref()) else {
+            return self;
+        };
+        match reqwest::Certificate::from_pem_bundle(ca_bundle.as_bytes()) {
+            Ok(certs) => {
+                self.client = Client::builder()
+                    .timeout(self.request_timeout)
+                    .tls_certs_merge(certs)
+                    .build()
+                    .expect("Failed to create HTTP client");
+            }
+            Err(e) => warn!(error = %e, "Ignoring invalid endpoint CA bundle"),
+        }
+        self
+    }
+}
+
+/// Why a single attempt to list an endpoint's models failed.
+#[derive(Debug)]
+enum FetchError {
+    /// Timeouts, connection failures and 408/429/5xx responses, which may succeed on retry
+    Transient(anyhow::Error),
+    /// Auth failures, other 4xx responses and malformed bodies, which retrying won't fix
+    Permanent(anyhow::Error),
+}
+
+impl FetchError {
+    fn from_reqwest(error: reqwest::Error) -> Self {
+        if error.is_builder() {
+            Self::Permanent(error.into())
+        } else {
+            Self::Transient(error.into())
+        }
+    }
+
+    fn from_status(status: StatusCode, error: anyhow::Error) -> Self {
+        if status.is_server_error() || status == StatusCode::REQUEST_TIMEOUT || status == StatusCode::TOO_MANY_REQUESTS {
+            Self::Transient(error)
+        } else {
+            Self::Permanent(error)
         }
     }
 }
@@ -121,13 +179,38 @@ impl From<&Url> for ModelFormat {
 #[async_trait]
 impl FetchModels for FetchModelsReqwest {
     async fn fetch(&self) -> anyhow::Result<OpenAIModelsResponse> {
+        let mut attempt = 0;
+        loop {
+            match self.fetch_once().await {
+                Ok(models) => return Ok(models),
+                Err(FetchError::Transient(error)) if attempt < self.max_retries => {
+                    let delay = self.retry_initial_delay.saturating_mul(2u32.saturating_pow(attempt));
+                    attempt += 1;
+                    warn!(
+                        url = %self.base_url,
+                        attempt,
+                        max_retries = self.max_retries,
+                        "Failed to fetch models ({:#}); retrying in {:?}",
+                        error,
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(FetchError::Transient(error) | FetchError::Permanent(error)) => return Err(error),
+            }
+        }
+    }
+}
+
+impl FetchModelsReqwest {
+    async fn fetch_once(&self) -> Result<OpenAIModelsResponse, FetchError> {
         debug!("Base URL for fetching models: {}", self.base_url);
         let fmt = self.format_override.clone().unwrap_or_else(|| (&self.base_url).into());
         debug!("Fetching models in format: {:?}", fmt);
 
         let url = ensure_slash(&self.base_url)
             .join("models")
-            .map_err(|e| anyhow::anyhow!("Failed to construct models URL: {}", e))?;
+            .map_err(|e| FetchError::Permanent(anyhow::anyhow!("Failed to construct models URL: {}", e)))?;
 
         debug!("Fetching models from URL: {}", url);
 
@@ -139,18 +222,22 @@ impl FetchModels for FetchModelsReqwest {
                     request = request.header(&self.auth_header_name, format!("{}{}", self.auth_header_prefix, api_key));
                 };
 
-                let response = request.timeout(self.request_timeout).send().await?;
+                let response = request
+                    .timeout(self.request_timeout)
+                    .send()
+                    .await
+                    .map_err(FetchError::from_reqwest)?;
 
                 if !response.status().is_success() {
                     let status = response.status();
                     let body = response.text().await.unwrap_or_default();
                     tracing::error!("Failed to make request to openAI API for models");
                     tracing::error!("Url was: {}", url);
-                    return Err(anyhow!("OpenAI API error: {} - {}", status, body));
+                    return Err(FetchError::from_status(status, anyhow!("OpenAI API error: {} - {}", status, body)));
                 }
 
                 // Get the response body as text first for logging
-                let body_text = response.text().await?;
+                let body_text = response.text().await.map_err(FetchError::from_reqwest)?;
                 tracing::debug!("Models API response body: {}", body_text);
 
                 // Try to parse the JSON
@@ -160,7 +247,7 @@ impl FetchModels for FetchModelsReqwest {
                         tracing::error!("Failed to make request to openAI-compatible API for models");
                         tracing::error!("Failed to parse models response as JSON. Error: {}", e);
                         tracing::error!("Response body was: {}", body_text);
-                        Err(anyhow!("error decoding response body: {}", e))
+                        Err(FetchError::Permanent(anyhow!("error decoding response body: {}", e)))
                     }
                 }
             }
@@ -172,18 +259,22 @@ impl FetchModels for FetchModelsReqwest {
                 // Have to set this
                 request = request.header("anthropic-version", "2023-06-01");
 
-                let response = request.timeout(self.request_timeout).send().await?;
+                let response = request
+                    .timeout(self.request_timeout)
+                    .send()
+                    .await
+                    .map_err(FetchError::from_reqwest)?;
 
                 if !response.status().is_success() {
                     let status = response.status();
                     let body = response.text().await.unwrap_or_default();
                     tracing::error!("Failed to make request to anthropic API for models");
                     tracing::error!("Url was: {}", url);
-                    return Err(anyhow!("Anthropic API error {}: {}", status, body));
+                    return Err(FetchError::from_status(status, anyhow!("Anthropic API error {}: {}", status, body)));
                 }
 
                 // Get the response body as text first for logging
-                let body_text = response.text().await?;
+                let body_text = response.text().await.map_err(FetchError::from_reqwest)?;
                 tracing::debug!("Models API response body: {}", body_text);
 
                 // Try to parse the JSON
@@ -194,7 +285,7 @@ impl FetchModels for FetchModelsReqwest {
                         tracing::error!("Url was: {}", url);
                         tracing::error!("Failed to parse models response as JSON. Error: {}", e);
                         tracing::error!("Response body was: {}", body_text);
-                        Err(anyhow!("error decoding response body: {}", e))
+                        Err(FetchError::Permanent(anyhow!("error decoding response body: {}", e)))
                     }
                 }
             }
@@ -203,18 +294,25 @@ impl FetchModels for FetchModelsReqwest {
                     request = request.header(&self.auth_header_name, format!("{}{}", self.auth_header_prefix, api_key));
                 };
 
-                let response = request.timeout(self.request_timeout).send().await?;
+                let response = request
+                    .timeout(self.request_timeout)
+                    .send()
+                    .await
+                    .map_err(FetchError::from_reqwest)?;
 
                 if !response.status().is_success() {
                     let status = response.status();
                     let body = response.text().await.unwrap_or_default();
                     tracing::error!("Failed to make request to OpenRouter API for models");
                     tracing::error!("Url was: {}", url);
-                    return Err(anyhow!("OpenRouter API error: {} - {}", status, body));
+                    return Err(FetchError::from_status(
+                        status,
+                        anyhow!("OpenRouter API error: {} - {}", status, body),
+                    ));
                 }
 
                 // Get the response body as text first for logging
-                let body_text = response.text().await?;
+                let body_text = response.text().await.map_err(FetchError::from_reqwest)?;
                 tracing::debug!("Models API response body: {}", body_text);
 
                 // Try to parse the JSON
@@ -225,7 +323,7 @@ impl FetchModels for FetchModelsReqwest {
                         tracing::error!("Url was: {}", url);
                         tracing::error!("Failed to parse models response as JSON. Error: {}", e);
                         tracing::error!("Response body was: {}", body_text);
-                        Err(anyhow!("error decoding response body: {}", e))
+                        Err(FetchError::Permanent(anyhow!("error decoding response body: {}", e)))
                     }
                 }
             }
@@ -707,4 +805,102 @@ mod tests {
         assert_eq!(result.data[1].id, "snowflake/mistral-large2");
         assert_eq!(result.data[0].object, "model");
     }
+
+    fn retry_config(uri: &str) -> SyncConfig {
+        SyncConfig {
+            openai_api_key: Some("test-key".to_string()),
+            openai_base_url: uri.parse().unwrap(),
+            auth_header_name: "Authorization".to_string(),
+            auth_header_prefix: "Bearer ".to_string(),
+            request_timeout: Duration::from_secs(30),
+            format_override: None,
+        }
+    }
+
+    fn fast_retries() -> EndpointSyncConfig {
+        EndpointSyncConfig {
+            max_retries: 2,
+            retry_initial_delay_milliseconds: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_retries_transient_failure() {
+        let mock_server = MockServer::start().await;
+
+        // The first attempt hits a 503, the retry succeeds
+        Mock::given(method("GET"))
+            .and(path("/models"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("upstream overloaded"))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "object": "list",
+                "data": [{ "id": "gpt-4", "object": "model", "created": 1234567890, "owned_by": "openai" }]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let fetcher = FetchModelsReqwest::new(retry_config(&mock_server.uri())).with_retry(&fast_retries());
+        let result = fetcher.fetch().await.unwrap();
+
+        assert_eq!(result.data.len(), 1);
+        assert_eq!(result.data[0].id, "gpt-4");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_gives_up_after_max_retries() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/models"))
+            .respond_with(ResponseTemplate::new(502))
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+
+        let fetcher = FetchModelsReqwest::new(retry_config(&mock_server.uri())).with_retry(&fast_retries());
+        let err = fetcher.fetch().await.unwrap_err();
+
+        assert!(err.to_string().contains("502"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_does_not_retry_permanent_failures() {
+        let mock_server = MockServer::start().await;
+
+        // Bad credentials and malformed bodies fail on the first attempt
+        Mock::given(method("GET"))
+            .and(path("/models"))
+            .and(header("Authorization", "Bearer test-key"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("invalid api key"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let fetcher = FetchModelsReqwest::new(retry_config(&mock_server.uri())).with_retry(&fast_retries());
+        assert!(fetcher.fetch().await.unwrap_err().to_string().contains("401"));
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("not valid json"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let fetcher = FetchModelsReqwest::new(retry_config(&mock_server.uri())).with_retry(&fast_retries());
+        assert!(
+            fetcher
+                .fetch()
+                .await
+                .unwrap_err()
+                .to_string()
+                .contains("error decoding response body")
+        );
+    }
 }
@@ -0,0 +1,82 @@
+//! Periodic model synchronization for endpoints with an auto-sync interval.
+//!
+//! Admins opt an endpoint in by setting `auto_sync_interval_seconds`. On each
+//! check the leader claims the endpoints whose interval has elapsed since their
+//! last automatic sync and runs the same [`synchronize_endpoint`] as the manual
+//! sync API: models that appeared upstream are added, models that disappeared
+//! are marked inactive and returning ones are reactivated. Aliases, tariffs and
+//! other admin edits to existing deployments are left untouched.
+//!
+//! A claim records the sync time before the sync runs, so a failed sync is not
+//! retried until the endpoint's next interval.
+
+use sqlx::PgPool;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::{EndpointAutoSyncConfig, EndpointSyncConfig};
+use crate::db::handlers::InferenceEndpoints;
+use crate::metrics::errors::component::ENDPOINT_AUTO_SYNC;
+use crate::sync::endpoint_sync::{EndpointSyncResponse, synchronize_endpoint};
+
+/// Sync every endpoint that is due, returning the results of the syncs that succeeded.
+pub async fn sync_due_endpoints(pool: &PgPool, retry: &EndpointSyncConfig) -> anyhow::Result<Vec<EndpointSyncResponse>> {
+    let due = {
+        let mut conn = pool.acquire().await?;
+        InferenceEndpoints::new(&mut conn).claim_due_auto_syncs().await?
+    };
+
+    let mut results = Vec::with_capacity(due.len());
+    for endpoint_id in due {
+        match synchronize_endpoint(endpoint_id, pool.clone(), retry).await {
+            Ok(result) => {
+                tracing::debug!(
+                    %endpoint_id,
+                    new_models_created = result.new_models_created,
+                    models_reactivated = result.models_reactivated,
+                    models_deactivated = result.models_deactivated,
+                    "Auto-synced endpoint"
+                );
+                results.push(result);
+            }
+            Err(e) => {
+                crate::background_error!(ENDPOINT_AUTO_SYNC, "sync", Warning, %endpoint_id, error = %e, "Failed to auto-sync endpoint");
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// Check for due endpoints every `check_interval` until `shutdown` is cancelled.
+///
+/// Only run this on the leader replica, so that each endpoint is synced once per interval.
+pub async fn run_endpoint_auto_sync(
+    pool: PgPool,
+    config: EndpointAutoSyncConfig,
+    retry: EndpointSyncConfig,
+    shutdown: CancellationToken,
+) -> anyhow::Result<()> {
+    tracing::info!(
+        check_interval = %humantime::format_duration(config.check_interval),
+        "Starting endpoint auto-sync job"
+    );
+
+    let mut interval = tokio::time::interval(config.check_interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => {
+                tracing::info!("Endpoint auto-sync job shutting down");
+                break;
+            }
+            _ = interval.tick() => {
+                if let Err(e) = sync_due_endpoints(&pool, &retry).await {
+                    crate::background_error!(ENDPOINT_AUTO_SYNC, "claim", Warning, error = %e, "Failed to find endpoints due an auto-sync");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,13 +1,14 @@
 //! Inference endpoint health synchronization.
 
 use crate::api::models::inference_endpoints::OpenAIModel;
+use crate::config::EndpointSyncConfig;
 use crate::db::handlers::deployments::DeploymentFilter;
 use crate::db::handlers::repository::Repository;
 use crate::db::handlers::{Deployments, InferenceEndpoints};
 use crate::db::models::deployments::{DeploymentCreateDBRequest, DeploymentDBResponse, DeploymentUpdateDBRequest, ModelStatus};
-use crate::db::models::inference_endpoints::InferenceEndpointDBResponse;
+use crate::db::models::inference_endpoints::{EndpointProtocol, InferenceEndpointDBResponse};
 use crate::errors::AliasConflict;
-use crate::sync::deployments::fetch_models::{FetchModels, FetchModelsReqwest, SyncConfig};
+use crate::sync::deployments::fetch_models::{FetchModels, FetchModelsReqwest, StaticModelsFetcher, SyncConfig};
 use crate::types::{DeploymentId, InferenceEndpointId, UserId};
 use anyhow::Result;
 use chrono::Utc;
@@ -50,9 +51,45 @@ pub struct EndpointSyncResponse {
     pub synced_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// Placeholders an endpoint's `alias_template` may use
+const ALIAS_TEMPLATE_PLACEHOLDERS: [&str; 2] = ["{model}", "{name}"];
+
+/// Build the alias for a model discovered on an endpoint from the endpoint's
+/// alias template: `{model}` is the model id as listed, `{name}` the id without
+/// its provider prefix (everything up to the last `/`). Without a template the
+/// alias is the model id.
+pub fn apply_alias_template(template: Option<&str>, model_id: &str) -> String {
+    let Some(template) = template else {
+        return model_id.to_string();
+    };
+    let name = model_id.rsplit_once('/').map_or(model_id, |(_, name)| name);
+    template.replace("{model}", model_id).replace("{name}", name).trim().to_string()
+}
+
+/// Check an alias template uses at least one placeholder and no unknown ones,
+/// returning why it is invalid otherwise
+pub fn check_alias_template(template: &str) -> std::result::Result<(), String> {
+    if !ALIAS_TEMPLATE_PLACEHOLDERS.iter().any(|p| template.contains(p)) {
+        return Err("must contain {model} or {name}".to_string());
+    }
+    let literal = ALIAS_TEMPLATE_PLACEHOLDERS
+        .iter()
+        .fold(template.to_string(), |t, p| t.replace(p, ""));
+    if literal.contains(['{', '}']) {
+        return Err("only the {model} and {name} placeholders are supported".to_string());
+    }
+    Ok(())
+}
+
 /// Synchronize deployments for a specific inference endpoint
+///
+/// Transient failures fetching the endpoint's models are retried according to `retry`.
 #[instrument]
-pub async fn synchronize_endpoint(endpoint_id: InferenceEndpointId, pool: PgPool) -> Result<EndpointSyncResponse> {
+pub async fn synchronize_endpoint(
+    endpoint_id: InferenceEndpointId,
+    pool: PgPool,
+    retry: &EndpointSyncConfig,
+) -> Result<EndpointSyncResponse> {
     let mut tx = pool.begin().await?;
     let endpoint_info;
     // Automatically synchronize the endpoint after creating
@@ -66,17 +103,20 @@ pub async fn synchronize_endpoint(endpoint_id: InferenceEndpointId, pool: PgPool
             .ok_or_else(|| anyhow::anyhow!("Endpoint not found: {}", endpoint_id))?;
     }
 
-    // Create sync config from endpoint
-    let sync_config = SyncConfig::from_endpoint(&endpoint_info);
-
-    // Create fetcher
-    let fetcher = FetchModelsReqwest::new(sync_config);
-
     // Perform the sync
     let sync_result;
     {
         let mut deployments_repo = Deployments::new(&mut tx);
-        sync_result = sync_endpoint_models(endpoint_info, &mut deployments_repo, fetcher).await
+        sync_result = if endpoint_info.protocol == EndpointProtocol::Bedrock {
+            // Bedrock has no OpenAI-style model listing; serve the configured model IDs
+            let fetcher = StaticModelsFetcher::new(endpoint_info.model_filter.clone().unwrap_or_default());
+            sync_endpoint_models(endpoint_info, &mut deployments_repo, fetcher).await
+        } else {
+            let fetcher = FetchModelsReqwest::new(SyncConfig::from_endpoint(&endpoint_info))
+                .with_retry(retry)
+                .with_endpoint_tls(endpoint_info.tls.as_ref());
+            sync_endpoint_models(endpoint_info, &mut deployments_repo, fetcher).await
+        };
     }
 
     tx.commit()
@@ -316,7 +356,7 @@ where
                 .as_ref()
                 .and_then(|mapping| mapping.get(&model.id))
                 .cloned()
-                .unwrap_or_else(|| model.id.clone());
+                .unwrap_or_else(|| apply_alias_template(endpoint_info.alias_template.as_deref(), &model.id));
 
             match create_deployment_with_alias(deployments_repo, model, &endpoint_info, system_user_id, alias.clone()).await {
                 Ok(_) => {
@@ -347,7 +387,7 @@ where
             .as_ref()
             .and_then(|mapping| mapping.get(&model.id))
             .cloned()
-            .unwrap_or_else(|| model.id.clone());
+            .unwrap_or_else(|| apply_alias_template(endpoint_info.alias_template.as_deref(), &model.id));
         if let Some(existing_model) = seen_aliases.insert(alias.clone(), model.id.clone()) {
             // Found a duplicate alias in this batch
             conflicts.push(AliasConflict {
@@ -468,7 +508,7 @@ where
     let db_request = DeploymentCreateDBRequest::builder()
         .created_by(created_by)
         .model_name(model.id.clone())
-        .alias(model.id.clone()) // Use model ID as alias by default
+        .alias(apply_alias_template(endpoint_info.alias_template.as_deref(), &model.id))
         .maybe_model_type(Some(detected_type))
         .hosted_on(endpoint_info.id)
         .build();
@@ -555,7 +595,7 @@ where
             let alias = alias_mapping
                 .get(model_name)
                 .cloned()
-                .unwrap_or_else(|| model_name.clone())
+                .unwrap_or_else(|| apply_alias_template(endpoint.alias_template.as_deref(), model_name))
                 .trim()
                 .to_string();
             create_aliases.push(alias.clone());
@@ -693,8 +733,9 @@ mod tests {
             models::{
                 deployments::{
                     DeploymentCreateDBRequest, DeploymentDBResponse, DeploymentUpdateDBRequest, LoadBalancingStrategy, ModelStatus,
+                    SystemPromptMode,
                 },
-                inference_endpoints::InferenceEndpointDBResponse,
+                inference_endpoints::{ApiKeyRotation, EndpointProtocol, InferenceEndpointDBResponse},
             },
         },
         sync::{
@@ -739,6 +780,10 @@ mod tests {
                 burst_size: None,
                 capacity: None,
                 batch_capacity: None,
+                per_key_capacity: None,
+                max_cost_per_request: None,
+                input_modalities: None,
+                output_modalities: None,
                 throughput: None,
                 provider_pricing: None,
                 // Composite model fields (regular model = not composite)
@@ -757,6 +802,20 @@ mod tests {
                 backoff_max_total_ms: None,
                 sanitize_responses: true,
                 trusted: false,
+                allow_public: false,
+                require_approval: false,
+                rewrite_response_model: false,
+                disable_logging: false,
+                warmup: false,
+                active: true,
+                system_prompt: None,
+                system_prompt_mode: SystemPromptMode::default(),
+                max_n: None,
+                max_n_mode: Default::default(),
+                credit_exhaustion_mode: Default::default(),
+                max_overdraft: None,
+                max_prompt_length: None,
+                max_prompt_length_unit: Default::default(),
                 open_responses_adapter: true,
                 reasoning_translation_overrides: None,
                 allowed_batch_completion_windows: None,
@@ -942,6 +1001,16 @@ mod tests {
             auth_header_name: "Authorization".to_string(),
             auth_header_prefix: "Bearer ".to_string(),
             reasoning_translation: None,
+            protocol: EndpointProtocol::OpenAi,
+            bedrock: None,
+            region: None,
+            body_transform: None,
+            auto_sync_interval_seconds: None,
+            alias_template: None,
+            max_concurrency: None,
+            tls: None,
+            additional_api_keys: Vec::new(),
+            api_key_rotation: ApiKeyRotation::RoundRobin,
             created_by: uuid::Uuid::nil(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
@@ -1317,4 +1386,71 @@ mod tests {
         let result = sync_endpoint_models_with_aliases(endpoint_info2, &mut repo, fetch_models.clone(), &None).await;
         assert!(matches!(result, Err(crate::sync::endpoint_sync::SyncError::AliasConflicts { .. })));
     }
+
+    #[tokio::test]
+    async fn test_sync_applies_endpoint_alias_template() {
+        let mut repo = MockDeploymentsRepo::new();
+        let fetch_models = MockFetchModels::new();
+        fetch_models.set_models(vec![
+            create_test_model("bytedance-seed/seed-1.6-flash"),
+            create_test_model("openai/gpt-4o-mini"),
+            create_test_model("plain-model"),
+        ]);
+
+        // Explicit mappings win over the template
+        let endpoint_info = InferenceEndpointDBResponse {
+            alias_template: Some("or-{name}".to_string()),
+            ..create_test_endpoint()
+        };
+        let alias_mapping = Some(HashMap::from([("openai/gpt-4o-mini".to_string(), "mini".to_string())]));
+        sync_endpoint_models_with_aliases(endpoint_info, &mut repo, fetch_models.clone(), &alias_mapping)
+            .await
+            .unwrap();
+
+        let mut aliases: Vec<(String, String)> = repo
+            .list(&DeploymentFilter::new(0, 10))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|d| (d.model_name, d.alias))
+            .collect();
+        aliases.sort();
+        assert_eq!(
+            aliases,
+            vec![
+                ("bytedance-seed/seed-1.6-flash".to_string(), "or-seed-1.6-flash".to_string()),
+                ("openai/gpt-4o-mini".to_string(), "mini".to_string()),
+                ("plain-model".to_string(), "or-plain-model".to_string()),
+            ]
+        );
+
+        // Periodic syncs use the template for newly listed models
+        fetch_models.set_models(vec![create_test_model("plain-model"), create_test_model("meta-llama/llama-3-8b")]);
+        let endpoint_info = InferenceEndpointDBResponse {
+            alias_template: Some("{name}".to_string()),
+            ..create_test_endpoint()
+        };
+        sync_endpoint_models(endpoint_info, &mut repo, fetch_models).await.unwrap();
+        let deployments = repo.list(&DeploymentFilter::new(0, 10)).await.unwrap();
+        assert!(
+            deployments
+                .iter()
+                .any(|d| d.model_name == "meta-llama/llama-3-8b" && d.alias == "llama-3-8b")
+        );
+    }
+
+    #[test]
+    fn test_alias_template_placeholders() {
+        use crate::sync::endpoint_sync::{apply_alias_template, check_alias_template};
+
+        assert_eq!(apply_alias_template(None, "a/b/c"), "a/b/c");
+        assert_eq!(apply_alias_template(Some("{name}"), "a/b/c"), "c");
+        assert_eq!(apply_alias_template(Some("{name}"), "plain"), "plain");
+        assert_eq!(apply_alias_template(Some("openrouter/{model}"), "x/y"), "openrouter/x/y");
+
+        assert!(check_alias_template("{name}").is_ok());
+        assert!(check_alias_template("or-{model}").is_ok());
+        assert!(check_alias_template("static").is_err());
+        assert!(check_alias_template("{provider}-{name}").is_err());
+    }
 }
@@ -1,4 +1,8 @@
+pub mod balance_checkpoints;
+pub mod batch_retention;
+pub mod config_events;
 pub mod deployments;
+pub mod endpoint_auto_sync;
 pub mod endpoint_sync;
 pub mod onwards_config;
 pub mod usage_refresh;
@@ -0,0 +1,219 @@
+//! Detecting and repairing drift between the live routing table and the database.
+//!
+//! The config sync keeps each replica's routing table current through
+//! LISTEN/NOTIFY, with the fallback reload as a safety net. Both are blind: a
+//! notification a replica missed, or a sync that crashed part way, goes
+//! unnoticed until the next reload happens to succeed. The drift check runs on
+//! the leader, rebuilds the routing table from the database and compares it
+//! with the live one. A difference is logged, counted in
+//! `dwctl_onwards_config_drift_total`, and repaired: the live table is
+//! replaced, and every replica is told to reload.
+//!
+//! The comparison covers what decides whether a request is routed: each
+//! alias's providers, the keys allowed to call it, and which keys have their
+//! own rate or concurrency limits. Limiter state is not compared.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use dashmap::DashMap;
+use metrics::counter;
+use onwards::auth::KeySet;
+use onwards::load_balancer::ProviderPool;
+use onwards::target::Targets;
+use sqlx::PgPool;
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use super::load_targets_from_db;
+use crate::config::{ONWARDS_CONFIG_CHANGED_CHANNEL, RateLimitTiersConfig};
+use crate::metrics::errors::component::ONWARDS_SYNC;
+use crate::secrets::SecretStore;
+
+/// How long to wait before confirming a difference, so a change that is
+/// still being applied through NOTIFY isn't reported as drift.
+const SETTLE_DELAY: Duration = Duration::from_secs(1);
+
+/// The parts of an alias's pool the drift check compares
+#[derive(Debug, PartialEq, Eq)]
+struct PoolSummary {
+    /// Name, URL, upstream model and weight of each provider, sorted
+    providers: Vec<(Option<String>, String, Option<String>, u32)>,
+    keys: Option<KeySet>,
+}
+
+impl PoolSummary {
+    fn of(pool: &ProviderPool) -> Self {
+        let mut providers: Vec<_> = pool
+            .providers()
+            .iter()
+            .map(|provider| {
+                (
+                    provider.target.name.clone(),
+                    provider.target.url.to_string(),
+                    provider.target.onwards_model.clone(),
+                    provider.weight,
+                )
+            })
+            .collect();
+        providers.sort();
+        Self {
+            providers,
+            keys: pool.keys().cloned(),
+        }
+    }
+}
+
+/// How the live routing table differs from the one built from the database
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Drift {
+    /// Aliases in the database that aren't being routed
+    pub missing: Vec<String>,
+    /// Aliases being routed that the database no longer has
+    pub unexpected: Vec<String>,
+    /// Aliases routed to different providers, or for different keys
+    pub changed: Vec<String>,
+    /// Whether a different set of keys has its own rate or concurrency limit
+    pub key_limits_changed: bool,
+}
+
+impl Drift {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.unexpected.is_empty() && self.changed.is_empty() && !self.key_limits_changed
+    }
+}
+
+fn map_keys<V>(map: &DashMap<String, V>) -> HashSet<String> {
+    map.iter().map(|entry| entry.key().clone()).collect()
+}
+
+/// Compare the live routing table with the expected one.
+pub fn compare(live: &Targets, expected: &Targets) -> Drift {
+    let mut drift = Drift::default();
+    for entry in expected.targets.iter() {
+        match live.targets.get(entry.key()) {
+            None => drift.missing.push(entry.key().clone()),
+            Some(pool) if PoolSummary::of(&pool) != PoolSummary::of(entry.value()) => drift.changed.push(entry.key().clone()),
+            Some(_) => {}
+        }
+    }
+    drift.unexpected = live
+        .targets
+        .iter()
+        .map(|entry| entry.key().clone())
+        .filter(|alias| !expected.targets.contains_key(alias))
+        .collect();
+    drift.missing.sort();
+    drift.unexpected.sort();
+    drift.changed.sort();
+    drift.key_limits_changed = map_keys(&live.key_rate_limiters) != map_keys(&expected.key_rate_limiters)
+        || map_keys(&live.key_concurrency_limiters) != map_keys(&expected.key_concurrency_limiters);
+    drift
+}
+
+/// Checks a replica's live routing table against the database. Created by
+/// [`super::OnwardsConfigSync::drift_checker`], so it builds targets the same
+/// way the sync does.
+#[derive(Clone)]
+pub struct DriftChecker {
+    pub(super) db: PgPool,
+    /// The routing table the proxy is serving from
+    pub(super) live: Targets,
+    pub(super) sender: watch::Sender<Targets>,
+    pub(super) escalation_models: Vec<String>,
+    pub(super) strict_mode: bool,
+    pub(super) rate_limit_tiers: RateLimitTiersConfig,
+    pub(super) endpoint_credentials_key: Option<Vec<u8>>,
+    pub(super) secrets: SecretStore,
+}
+
+impl DriftChecker {
+    async fn load(&self) -> anyhow::Result<Targets> {
+        load_targets_from_db(
+            &self.db,
+            &self.escalation_models,
+            self.strict_mode,
+            &self.rate_limit_tiers,
+            self.endpoint_credentials_key.as_deref(),
+            Some(&self.secrets),
+        )
+        .await
+    }
+
+    /// Compare the live routing table with the database once, repairing any drift.
+    ///
+    /// A difference is only reported if it is still there on a second load
+    /// after [`SETTLE_DELAY`]. Repairing sends the freshly loaded targets to
+    /// this replica's proxy and NOTIFYs the other replicas to reload, as they
+    /// may have missed the same change.
+    pub async fn check(&self) -> anyhow::Result<Drift> {
+        if compare(&self.live, &self.load().await?).is_empty() {
+            return Ok(Drift::default());
+        }
+        tokio::time::sleep(SETTLE_DELAY).await;
+        let expected = self.load().await?;
+        let drift = compare(&self.live, &expected);
+        if drift.is_empty() {
+            return Ok(drift);
+        }
+
+        warn!(
+            missing = ?drift.missing,
+            unexpected = ?drift.unexpected,
+            changed = ?drift.changed,
+            key_limits_changed = drift.key_limits_changed,
+            "Onwards routing table drifted from the database; forcing a resync"
+        );
+        counter!("dwctl_onwards_config_drift_total").increment(1);
+
+        self.sender
+            .send(expected)
+            .map_err(|_| anyhow::anyhow!("Onwards targets receiver dropped"))?;
+        counter!("dwctl_cache_sync_total", "source" => "drift_repair").increment(1);
+
+        let epoch_micros = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros();
+        sqlx::query("SELECT pg_notify($1, $2)")
+            .bind(ONWARDS_CONFIG_CHANGED_CHANNEL)
+            .bind(format!("drift_repair:{epoch_micros}"))
+            .execute(&self.db)
+            .await?;
+
+        Ok(drift)
+    }
+
+    /// Run [`Self::check`] every `interval` until `shutdown` is cancelled.
+    ///
+    /// Only run this on the leader replica; each check reloads the full routing table.
+    pub async fn run(self, interval: Duration, shutdown: CancellationToken) -> anyhow::Result<()> {
+        info!(interval = %humantime::format_duration(interval), "Starting onwards config drift check");
+
+        let mut timer = tokio::time::interval(interval);
+        timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        // The first tick is immediate, and the routing table was just loaded
+        timer.tick().await;
+
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => {
+                    info!("Onwards config drift check shutting down");
+                    break;
+                }
+                _ = timer.tick() => {
+                    match self.check().await {
+                        Ok(drift) if drift.is_empty() => debug!("Onwards routing table matches the database"),
+                        Ok(_) => {}
+                        Err(e) => {
+                            crate::background_error!(ONWARDS_SYNC, "drift_check", Warning, error = %e, "Failed to check onwards config for drift");
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
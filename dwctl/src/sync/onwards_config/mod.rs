@@ -1,14 +1,22 @@
 //! Configuration synchronization to onwards routing layer.
 
+pub mod drift;
+
 use crate::metrics::errors::component::ONWARDS_SYNC;
 use std::{collections::HashMap, num::NonZeroU32, sync::Arc};
 
 use metrics::histogram;
+use onwards::key_rotation::{DEFAULT_COOLDOWN_SECS, KeyRotationConfig, KeyRotationStrategy};
+use onwards::sigv4::SigV4Config;
 use onwards::target::{
-    Auth, BackoffConfig as OnwardsBackoffConfig, ConcurrencyLimitParameters, ConfigFile, FallbackConfig as OnwardsFallbackConfig,
-    JitterStrategy as OnwardsJitterStrategy, KeyDefinition, LoadBalanceStrategy as OnwardsLoadBalanceStrategy, OpenResponsesConfig,
-    PoolSpec, ProviderSpec, RateLimitParameters, RoutingAction, RoutingRule, TargetSpecOrList, Targets, WatchTargetsStream,
+    Auth, BackoffConfig as OnwardsBackoffConfig, ConcurrencyLimitParameters, ConfigFile, EndpointConcurrencyLimit,
+    FallbackConfig as OnwardsFallbackConfig, JitterStrategy as OnwardsJitterStrategy, KeyDefinition, LabeledResponseHeaders,
+    LoadBalanceStrategy as OnwardsLoadBalanceStrategy, NLimitConfig, NLimitMode as OnwardsNLimitMode, OpenResponsesConfig, PoolSpec,
+    ProviderSpec, RateLimitParameters, RoutingAction, RoutingRule, ShadowConfig, SystemPromptConfig,
+    SystemPromptMode as OnwardsSystemPromptMode, TargetSpecOrList, Targets, WatchTargetsStream,
 };
+use onwards::tls::TlsConfig;
+use rust_decimal::Decimal;
 use sqlx::{PgPool, postgres::PgListener};
 use tokio::sync::{mpsc, watch};
 use tokio_util::sync::CancellationToken;
@@ -20,16 +28,27 @@ pub enum SyncStatus {
     Connecting,
     Connected,
     Disconnected,
-    Reconnecting,
+    /// About to reconnect after waiting `delay`
+    Reconnecting {
+        delay: std::time::Duration,
+    },
 }
 
 use crate::{
+    body_transform::{BodyTransformConfig, parse_body_transform},
     config::{ONWARDS_CONFIG_CHANGED_CHANNEL, RateLimitTiersConfig},
-    db::models::deployments::LoadBalancingStrategy,
+    db::models::deployments::{LoadBalancingStrategy, NLimitMode, SystemPromptMode},
+    db::models::inference_endpoints::ApiKeyRotation,
     reasoning::{ReasoningTranslationConfig, resolve_reasoning_translation},
-    types::{ApiKeyId, DeploymentId},
+    secrets::SecretStore,
+    types::{ApiKeyId, DeploymentId, InferenceEndpointId, UserId},
 };
 
+/// Response header reporting the per-token input price the request is billed at
+const INPUT_PRICE_HEADER: &str = "Input-Price-Per-Token";
+/// Response header reporting the per-token output price the request is billed at
+const OUTPUT_PRICE_HEADER: &str = "Output-Price-Per-Token";
+
 /// Parse the NOTIFY payload to extract the timestamp
 /// Payload format: "table_name:epoch_microseconds"
 /// Returns the table name and the elapsed time since the notification was sent
@@ -57,12 +76,24 @@ struct OnwardsTarget {
     requests_per_second: Option<f32>,
     burst_size: Option<i32>,
     capacity: Option<i32>,
+    /// Most concurrent requests a single API key may hold on this model
+    per_key_capacity: Option<i32>,
     sanitize_responses: bool,
+    /// Whether responses report the alias as their `model`
+    rewrite_response_model: bool,
     trusted: bool,
     open_responses_adapter: bool,
     reasoning_translation: Option<ReasoningTranslationConfig>,
     /// Traffic routing rules from the model_traffic_rules table
     routing_rules: Vec<RoutingRule>,
+    /// Pricing response headers per API key purpose, from the model's current tariffs
+    pricing_headers: Vec<LabeledResponseHeaders>,
+    /// Shadow traffic copied to another model, from the deployment_shadows table
+    shadow: Option<ShadowConfig>,
+    /// System prompt injected into chat requests
+    system_prompt: Option<SystemPromptConfig>,
+    /// Cap on the number of completions per request
+    n_limit: Option<NLimitConfig>,
 
     // Fallback / backoff config. Standard (single-provider) models only retry
     // when fallback is on AND `with_replacement` is true (otherwise the
@@ -83,8 +114,20 @@ struct OnwardsTarget {
     // Endpoint info
     endpoint_url: url::Url,
     endpoint_api_key: Option<String>,
+    /// All of the endpoint's keys, when it has more than one to rotate between
+    key_rotation: Option<KeyRotationConfig>,
     auth_header_name: String,
     auth_header_prefix: String,
+    /// SigV4 signing for Bedrock endpoints (replaces `endpoint_api_key`)
+    sigv4: Option<SigV4Config>,
+    /// Custom CA and client certificate configured on the endpoint
+    tls: Option<TlsConfig>,
+    /// Region label of the endpoint, used for region-aware provider selection
+    endpoint_region: Option<String>,
+    /// Concurrency limit shared by every deployment on the endpoint
+    endpoint_concurrency_limit: Option<EndpointConcurrencyLimit>,
+    /// Declarative request/response body edits configured on the endpoint
+    body_transform: Option<BodyTransformConfig>,
 
     // API keys that have access to this deployment
     api_keys: Vec<OnwardsApiKey>,
@@ -105,6 +148,25 @@ struct OnwardsApiKey {
     /// Account-wide zero-data-retention flag on the api_key's owning user.
     /// Surfaced to onwards as a "zdr" key label; onwards does not act on it yet.
     zero_data_retention: bool,
+    /// Highest request priority the key may ask for. Surfaced to onwards as
+    /// a "max_priority" key label when above the default of 0.
+    max_priority: i16,
+    /// Limits an admin set on the owning user or their groups
+    owner_limits: OwnerRateLimits,
+}
+
+/// Rate and concurrency limits an admin set for an API key's owner: their own
+/// override (`user_rate_limits`) and the highest default among their groups
+/// (`group_rate_limits`). Resolved against the key's own limits by
+/// [`resolve_key_limits`].
+#[derive(Debug, Clone, Copy, Default)]
+struct OwnerRateLimits {
+    user_requests_per_second: Option<f32>,
+    user_burst_size: Option<i32>,
+    user_concurrency_limit: Option<i32>,
+    group_requests_per_second: Option<f32>,
+    group_burst_size: Option<i32>,
+    group_concurrency_limit: Option<i32>,
 }
 
 /// Manages the integration between onwards-pilot and the onwards proxy
@@ -124,6 +186,10 @@ pub struct OnwardsConfigSync {
     /// Default rate-limit tiers applied to API keys based on the owning user's
     /// `verified` flag. Used when a key has no per-key override.
     rate_limit_tiers: RateLimitTiersConfig,
+    /// Key for decrypting Bedrock endpoint credentials (the connections encryption key)
+    endpoint_credentials_key: Option<Vec<u8>>,
+    /// Resolves endpoint API keys stored as secret references
+    secrets: SecretStore,
 }
 
 pub struct SyncConfig {
@@ -133,6 +199,12 @@ pub struct SyncConfig {
     /// Provides periodic full syncs independent of LISTEN/NOTIFY to guarantee eventual consistency.
     /// Set to 0 to disable fallback sync (not recommended).
     pub fallback_interval_milliseconds: u64,
+    /// Delay before the first listener reconnect attempt in milliseconds (default: 100ms)
+    ///
+    /// Doubles on each consecutive failure (plus up to 20% jitter) and resets once a connection succeeds.
+    pub reconnect_initial_delay_milliseconds: u64,
+    /// Upper bound on the listener reconnect delay in milliseconds (default: 30000ms = 30 seconds)
+    pub reconnect_max_delay_milliseconds: u64,
 }
 
 impl Default for SyncConfig {
@@ -140,16 +212,87 @@ impl Default for SyncConfig {
         Self {
             status_tx: None,
             fallback_interval_milliseconds: 10000, // 10 seconds
+            reconnect_initial_delay_milliseconds: 100,
+            reconnect_max_delay_milliseconds: 30_000,
         }
     }
 }
 
+/// How long a listener connection has to stay up before reconnect backoff
+/// starts over, so a connection that drops straight after connecting keeps
+/// backing off.
+const HEALTHY_CONNECTION: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Exponential backoff between listener reconnect attempts, so a recovering
+/// database isn't hammered with connection attempts.
+struct ReconnectBackoff {
+    initial: std::time::Duration,
+    max: std::time::Duration,
+    attempt: u32,
+    /// When the current connection was established
+    connected_at: Option<std::time::Instant>,
+}
+
+impl ReconnectBackoff {
+    fn new(config: &SyncConfig) -> Self {
+        let initial = std::time::Duration::from_millis(config.reconnect_initial_delay_milliseconds.max(1));
+        Self {
+            initial,
+            max: std::time::Duration::from_millis(config.reconnect_max_delay_milliseconds).max(initial),
+            attempt: 0,
+            connected_at: None,
+        }
+    }
+
+    /// Delay before the next attempt: `initial * 2^attempt` plus 0..20% positive
+    /// jitter (so pods that lost the same database don't reconnect in lockstep),
+    /// capped at `max`. The sequence starts over if the last connection stayed
+    /// up for [`HEALTHY_CONNECTION`].
+    fn next_delay(&mut self) -> std::time::Duration {
+        use rand::RngExt;
+        if self
+            .connected_at
+            .take()
+            .is_some_and(|connected_at| connected_at.elapsed() >= HEALTHY_CONNECTION)
+        {
+            self.attempt = 0;
+        }
+        let base = self.initial.saturating_mul(1u32 << self.attempt.min(20)).min(self.max);
+        let base_ms = base.as_millis() as u64;
+        let jitter_ms = rand::rng().random_range(0..(base_ms / 5 + 1));
+        self.attempt = self.attempt.saturating_add(1);
+        (base + std::time::Duration::from_millis(jitter_ms)).min(self.max)
+    }
+
+    /// Record that a connection was established at `at`.
+    fn connected(&mut self, at: std::time::Instant) {
+        self.connected_at = Some(at);
+    }
+}
+
+/// Open a LISTEN connection on the onwards config channel.
+async fn connect_listener(db: &PgPool) -> Result<PgListener, sqlx::Error> {
+    let mut listener = PgListener::connect_with(db).await?;
+    listener.listen(ONWARDS_CONFIG_CHANGED_CHANNEL).await?;
+    Ok(listener)
+}
+
 impl OnwardsConfigSync {
     /// Creates a new OnwardsConfigSync and returns it along with initial targets and a WatchTargetsStream
     #[cfg(test)]
     #[instrument(skip(db))]
     pub async fn new(db: PgPool) -> Result<(Self, Targets, WatchTargetsStream), anyhow::Error> {
-        Self::new_with_daemon_limits(db, None, 10, Vec::new(), false, RateLimitTiersConfig::default()).await
+        Self::new_with_daemon_limits(
+            db,
+            None,
+            10,
+            Vec::new(),
+            false,
+            RateLimitTiersConfig::default(),
+            None,
+            SecretStore::new(&crate::secrets::SecretsConfig::default()),
+        )
+        .await
     }
 
     /// Creates a new OnwardsConfigSync with optional daemon capacity limits map and escalation models
@@ -159,7 +302,10 @@ impl OnwardsConfigSync {
     /// `escalation_models` - Model aliases that batch API keys should have automatic access to.
     /// `strict_mode` - Enable strict mode with schema validation (only known OpenAI API paths accepted)
     /// `rate_limit_tiers` - Default rate limits applied per-key based on the owning user's `verified` flag.
-    #[instrument(skip(db, daemon_capacity_limits, escalation_models, rate_limit_tiers))]
+    /// `endpoint_credentials_key` - Key for decrypting Bedrock endpoint credentials; without it Bedrock models are skipped.
+    /// `secrets` - Resolves endpoint API keys stored as secret references.
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip(db, daemon_capacity_limits, escalation_models, rate_limit_tiers, endpoint_credentials_key, secrets))]
     pub async fn new_with_daemon_limits(
         db: PgPool,
         daemon_capacity_limits: Option<Arc<dashmap::DashMap<String, usize>>>,
@@ -167,9 +313,19 @@ impl OnwardsConfigSync {
         escalation_models: Vec<String>,
         strict_mode: bool,
         rate_limit_tiers: RateLimitTiersConfig,
+        endpoint_credentials_key: Option<Vec<u8>>,
+        secrets: SecretStore,
     ) -> Result<(Self, Targets, WatchTargetsStream), anyhow::Error> {
         // Load initial configuration (including composite models)
-        let initial_targets = load_targets_from_db(&db, &escalation_models, strict_mode, &rate_limit_tiers).await?;
+        let initial_targets = load_targets_from_db(
+            &db,
+            &escalation_models,
+            strict_mode,
+            &rate_limit_tiers,
+            endpoint_credentials_key.as_deref(),
+            Some(&secrets),
+        )
+        .await?;
 
         // If daemon limits are provided, populate them
         if let Some(ref limits) = daemon_capacity_limits {
@@ -200,6 +356,8 @@ impl OnwardsConfigSync {
             cache_info_state,
             strict_mode,
             rate_limit_tiers,
+            endpoint_credentials_key,
+            secrets,
         };
         let stream = WatchTargetsStream::new(receiver);
 
@@ -211,6 +369,21 @@ impl OnwardsConfigSync {
         self.sender.clone()
     }
 
+    /// A drift checker comparing `live`, the routing table the proxy serves
+    /// from, with targets built from the database the way this sync builds them
+    pub fn drift_checker(&self, live: Targets) -> drift::DriftChecker {
+        drift::DriftChecker {
+            db: self.db.clone(),
+            live,
+            sender: self.sender.clone(),
+            escalation_models: self.escalation_models.clone(),
+            strict_mode: self.strict_mode,
+            rate_limit_tiers: self.rate_limit_tiers.clone(),
+            endpoint_credentials_key: self.endpoint_credentials_key.clone(),
+            secrets: self.secrets.clone(),
+        }
+    }
+
     /// Starts the background task that listens for database changes and updates the configuration
     #[instrument(skip(self, config, shutdown_token), err)]
     pub async fn start(mut self, config: SyncConfig, shutdown_token: CancellationToken) -> Result<(), anyhow::Error> {
@@ -229,13 +402,45 @@ impl OnwardsConfigSync {
             None
         };
 
+        let mut backoff = ReconnectBackoff::new(&config);
+        let mut reconnecting = false;
+
         'outer: loop {
+            // Back off before every attempt after the first, so a recovering
+            // database isn't hit with a tight reconnect loop
+            if std::mem::replace(&mut reconnecting, true) {
+                let delay = backoff.next_delay();
+                info!("Reconnecting onwards configuration listener in {:?}", delay);
+                if let Some(tx) = &config.status_tx {
+                    tx.send(SyncStatus::Reconnecting { delay }).await?;
+                }
+                tokio::select! {
+                    _ = shutdown_token.cancelled() => break 'outer,
+                    _ = tokio::time::sleep(delay) => {}
+                }
+            }
+
             if let Some(tx) = &config.status_tx {
                 tx.send(SyncStatus::Connecting).await?;
             }
-            let mut listener = PgListener::connect_with(&self.db).await?;
-            // Listen to auth config changes
-            listener.listen(ONWARDS_CONFIG_CHANGED_CHANNEL).await?;
+            let mut listener = match connect_listener(&self.db).await {
+                Ok(listener) => listener,
+                Err(sqlx::Error::PoolClosed) => {
+                    error!("Database pool closed, exiting sync task");
+                    return Err(sqlx::Error::PoolClosed.into());
+                }
+                Err(e) => {
+                    crate::background_error!(
+                        ONWARDS_SYNC,
+                        "listener_connect",
+                        Error,
+                        "Failed to connect onwards config listener: {}",
+                        e
+                    );
+                    continue;
+                }
+            };
+            backoff.connected(std::time::Instant::now());
 
             if let Some(tx) = &config.status_tx {
                 tx.send(SyncStatus::Connected).await?;
@@ -269,11 +474,6 @@ impl OnwardsConfigSync {
                                     debug!("Sending Disconnected status");
                                     tx.send(SyncStatus::Disconnected).await?;
                                 }
-                                // Try to reconnect for other errors
-                                if let Some(tx) = &config.status_tx {
-                                    debug!("Sending Reconnecting status");
-                                    tx.send(SyncStatus::Reconnecting).await?;
-                                }
                                 break;
 
                             },
@@ -320,10 +520,6 @@ impl OnwardsConfigSync {
                                 if let Some(tx) = &config.status_tx {
                                     tx.send(SyncStatus::Disconnected).await?;
                                 }
-                                // Try to reconnect for other errors
-                                if let Some(tx) = &config.status_tx {
-                                    tx.send(SyncStatus::Reconnecting).await?;
-                                }
 
                                 // Check if this is a fatal error that should propagate
                                 if e.to_string().contains("closed pool") || e.to_string().contains("connection closed") {
@@ -384,7 +580,16 @@ impl OnwardsConfigSync {
     /// watch channel is closed (all receivers dropped); Err only for fatal
     /// DB errors (closed pool / connection).
     async fn full_reload(&mut self, source: &'static str) -> Result<bool, anyhow::Error> {
-        let new_targets = match load_targets_from_db(&self.db, &self.escalation_models, self.strict_mode, &self.rate_limit_tiers).await {
+        let new_targets = match load_targets_from_db(
+            &self.db,
+            &self.escalation_models,
+            self.strict_mode,
+            &self.rate_limit_tiers,
+            self.endpoint_credentials_key.as_deref(),
+            Some(&self.secrets),
+        )
+        .await
+        {
             Ok(targets) => targets,
             Err(e) => {
                 crate::background_error!(ONWARDS_SYNC, "load_targets", Error, "Failed to load targets from database: {}", e);
@@ -458,6 +663,8 @@ struct OnwardsCompositeModel {
     requests_per_second: Option<f32>,
     burst_size: Option<i32>,
     capacity: Option<i32>,
+    /// Most concurrent requests a single API key may hold across the composite
+    per_key_capacity: Option<i32>,
     /// Load balancing strategy (weighted_random or priority)
     lb_strategy: LoadBalancingStrategy,
     /// Fallback enabled
@@ -483,6 +690,8 @@ struct OnwardsCompositeModel {
     backoff_max_total_ms: Option<i32>,
     /// Whether to sanitize/filter sensitive data from model responses
     sanitize_responses: bool,
+    /// Whether responses report the composite's alias as their `model`
+    rewrite_response_model: bool,
     /// Whether to mark provider as trusted in strict mode
     #[allow(dead_code)] // Stored in DB but composite-level trust is not yet propagated to onwards
     trusted: bool,
@@ -490,14 +699,29 @@ struct OnwardsCompositeModel {
     open_responses_adapter: bool,
     /// Traffic routing rules from the database
     routing_rules: Vec<RoutingRule>,
+    /// Pricing response headers per API key purpose, from the composite's current tariffs
+    pricing_headers: Vec<LabeledResponseHeaders>,
+    /// Shadow traffic copied to another model, from the deployment_shadows table
+    shadow: Option<ShadowConfig>,
+    /// System prompt injected into chat requests
+    system_prompt: Option<SystemPromptConfig>,
+    /// Cap on the number of completions per request
+    n_limit: Option<NLimitConfig>,
     components: Vec<CompositeModelComponent>,
     // API keys that have access to this composite model
     api_keys: Vec<OnwardsApiKey>,
 }
 
 /// Loads composite models with their components and API keys from the database
-#[tracing::instrument(skip(db, escalation_models))]
-async fn load_composite_models_from_db(db: &PgPool, escalation_models: &[String]) -> Result<Vec<OnwardsCompositeModel>, anyhow::Error> {
+#[tracing::instrument(skip(db, escalation_models, bedrock_signing, endpoint_tls, endpoint_api_keys, owner_limits))]
+async fn load_composite_models_from_db(
+    db: &PgPool,
+    escalation_models: &[String],
+    bedrock_signing: &HashMap<InferenceEndpointId, Option<SigV4Config>>,
+    endpoint_tls: &HashMap<InferenceEndpointId, Option<TlsConfig>>,
+    endpoint_api_keys: &HashMap<InferenceEndpointId, Option<EndpointApiKeys>>,
+    owner_limits: &HashMap<UserId, OwnerRateLimits>,
+) -> Result<Vec<OnwardsCompositeModel>, anyhow::Error> {
     debug!(
         "Loading composite models from database (escalation_models: {:?})",
         escalation_models
@@ -536,18 +760,23 @@ async fn load_composite_models_from_db(db: &PgPool, escalation_models: &[String]
             ie.reasoning_translation as endpoint_reasoning_translation,
             dm.reasoning_translation_overrides as model_reasoning_translation_overrides,
             -- Endpoint info
+            ie.id as endpoint_id,
             ie.url as "endpoint_url!",
-            ie.api_key as endpoint_api_key,
             ie.auth_header_name,
-            ie.auth_header_prefix
+            ie.auth_header_prefix,
+            ie.region as endpoint_region,
+            ie.max_concurrency as endpoint_max_concurrency,
+            ie.body_transform as endpoint_body_transform
         FROM deployed_models cm
         INNER JOIN deployed_model_components dmc ON cm.id = dmc.composite_model_id
         INNER JOIN deployed_models dm ON dmc.deployed_model_id = dm.id
         INNER JOIN inference_endpoints ie ON dm.hosted_on = ie.id
         WHERE cm.is_composite = TRUE
           AND cm.deleted = FALSE
+          AND cm.active = TRUE
           AND dmc.enabled = TRUE
           AND dm.deleted = FALSE
+          AND dm.active = TRUE
         -- Deterministic priority order: sort_order is the failover order onwards
         -- uses (Priority strategy iterates providers in definition order). The
         -- weight/created_at keys break any residual sort_order tie the same way
@@ -569,8 +798,10 @@ async fn load_composite_models_from_db(db: &PgPool, escalation_models: &[String]
             ak.purpose as api_key_purpose,
             ak.requests_per_second,
             ak.burst_size,
+            ak.user_id as api_key_user_id,
             ak.user_verified,
-            ak.user_zero_data_retention
+            ak.user_zero_data_retention,
+            ak.max_priority as "api_key_max_priority!"
         FROM deployed_models cm
         CROSS JOIN LATERAL (
             SELECT DISTINCT
@@ -579,8 +810,10 @@ async fn load_composite_models_from_db(db: &PgPool, escalation_models: &[String]
                 ak.purpose,
                 ak.requests_per_second,
                 ak.burst_size,
+                ak.user_id,
                 u.verified as user_verified,
-                u.zero_data_retention as user_zero_data_retention
+                u.zero_data_retention as user_zero_data_retention,
+                ak.max_priority
             FROM api_keys ak
             JOIN users u ON u.id = ak.user_id
             WHERE (
@@ -593,7 +826,9 @@ async fn load_composite_models_from_db(db: &PgPool, escalation_models: &[String]
                     WHERE dg.deployment_id = cm.id
                       AND ug.user_id = ak.user_id
                 )
-                -- OR composite model is in public group (nil UUID)
+                -- OR composite model is public
+                OR cm.allow_public
+                -- OR composite model is in public group (nil UUID, legacy convention)
                 OR EXISTS (
                     SELECT 1 FROM deployment_groups dg
                     WHERE dg.deployment_id = cm.id
@@ -605,6 +840,22 @@ async fn load_composite_models_from_db(db: &PgPool, escalation_models: &[String]
                     AND cm.alias = ANY($1::text[])
                 )
             )
+            -- Gated models also need an approved access request (system user
+            -- and batch escalation are exempt)
+            AND (
+                NOT cm.require_approval
+                OR ak.user_id = '00000000-0000-0000-0000-000000000000'
+                OR (
+                    ak.purpose = 'batch'
+                    AND cm.alias = ANY($1::text[])
+                )
+                OR EXISTS (
+                    SELECT 1 FROM access_requests ar
+                    WHERE ar.deployment_id = cm.id
+                      AND ar.user_id = ak.user_id
+                      AND ar.status = 'approved'
+                )
+            )
             -- Require positive balance OR free model (system user always passes)
             AND (
                 ak.user_id = '00000000-0000-0000-0000-000000000000'
@@ -630,7 +881,7 @@ async fn load_composite_models_from_db(db: &PgPool, escalation_models: &[String]
             AND ak.is_deleted = false
             -- Spending-cap gate: exclude every key of a cap scope (the capped
             -- root and its hidden batch child alike) once the scope's
-            -- CALENDAR-ALIGNED (UTC, non-rolling) window spend has reached the
+            -- CALENDAR-ALIGNED (owner's timezone, non-rolling) window spend has reached the
             -- root's limit. Guarded so the uncapped majority short-circuits on
             -- the first branch; the subquery is two PK probes. Free models
             -- stay usable on an exhausted scope, mirroring the balance gate's
@@ -643,10 +894,11 @@ async fn load_composite_models_from_db(db: &PgPool, escalation_models: &[String]
                 OR NOT EXISTS (
                     SELECT 1
                     FROM api_keys root
+                    JOIN users owner ON owner.id = root.user_id
                     LEFT JOIN api_key_spend_checkpoints ck ON ck.api_key_id = root.id
                     WHERE root.id = COALESCE(ak.parent_api_key_id, ak.id)
                       AND root.spend_limit IS NOT NULL
-                      AND api_key_cap_window_current(ck.window_started_at, root.spend_limit_interval)
+                      AND api_key_cap_window_current(ck.window_started_at, root.spend_limit_interval, owner.timezone)
                       AND ck.window_spend >= root.spend_limit
                       AND EXISTS (
                           SELECT 1 FROM model_tariffs mt
@@ -656,6 +908,40 @@ async fn load_composite_models_from_db(db: &PgPool, escalation_models: &[String]
                       )
                 )
             )
+            -- Group spending limits (migration 150): once a group's window
+            -- spend reaches its limit, every key of every member is excluded
+            -- from priced models, with the same window function as the key
+            -- caps (aligned in UTC). Groups without a limit never match.
+            AND (
+                ak.user_id = '00000000-0000-0000-0000-000000000000'
+                OR NOT EXISTS (
+                    SELECT 1
+                    FROM user_groups ug
+                    JOIN group_spending_limits gl ON gl.group_id = ug.group_id
+                    JOIN group_spend_checkpoints gck ON gck.group_id = ug.group_id
+                    WHERE ug.user_id = ak.user_id
+                      AND api_key_cap_window_current(gck.window_started_at, gl.spending_limit_interval, 'UTC')
+                      AND gck.window_spend >= gl.spending_limit
+                      AND EXISTS (
+                          SELECT 1 FROM model_tariffs mt
+                          WHERE mt.deployed_model_id = cm.id
+                            AND mt.valid_until IS NULL
+                            AND (mt.input_price_per_token > 0 OR mt.output_price_per_token > 0)
+                      )
+                )
+            )
+            -- Per-key model restrictions (migration 144): narrow the key's
+            -- group-derived access with the scope root's allow/deny lists, so
+            -- a cap-scope child inherits its parent's restrictions. NULL lists
+            -- don't restrict; the system key never carries any.
+            AND NOT EXISTS (
+                SELECT 1 FROM api_keys scope
+                WHERE scope.id = COALESCE(ak.parent_api_key_id, ak.id)
+                  AND (
+                      (scope.allowed_model_ids IS NOT NULL AND NOT (cm.id = ANY(scope.allowed_model_ids)))
+                      OR cm.id = ANY(COALESCE(scope.denied_model_ids, '{}'))
+                  )
+            )
             -- Inference data plane only: platform (management) keys must never
             -- enter onwards' key set. Mirrors is_inference_purpose in
             -- db::models::api_keys (SQL cannot call it). The system key is
@@ -668,6 +954,7 @@ async fn load_composite_models_from_db(db: &PgPool, escalation_models: &[String]
         ) ak
         WHERE cm.is_composite = TRUE
           AND cm.deleted = FALSE
+          AND cm.active = TRUE
         ORDER BY cm.id, ak.id
         "#,
         escalation_models
@@ -686,6 +973,7 @@ async fn load_composite_models_from_db(db: &PgPool, escalation_models: &[String]
             requests_per_second,
             burst_size,
             capacity,
+            per_key_capacity,
             lb_strategy,
             fallback_enabled,
             fallback_on_rate_limit,
@@ -699,11 +987,17 @@ async fn load_composite_models_from_db(db: &PgPool, escalation_models: &[String]
             backoff_jitter,
             backoff_max_total_ms,
             sanitize_responses,
+            rewrite_response_model,
             trusted,
-            open_responses_adapter as "open_responses_adapter?"
+            open_responses_adapter as "open_responses_adapter?",
+            system_prompt,
+            system_prompt_mode,
+            max_n,
+            max_n_mode
         FROM deployed_models
         WHERE is_composite = TRUE
           AND deleted = FALSE
+          AND active = TRUE
         "#
     )
     .fetch_all(db)
@@ -726,6 +1020,7 @@ async fn load_composite_models_from_db(db: &PgPool, escalation_models: &[String]
                 requests_per_second: row.requests_per_second,
                 burst_size: row.burst_size,
                 capacity: row.capacity,
+                per_key_capacity: row.per_key_capacity,
                 lb_strategy,
                 fallback_enabled: row.fallback_enabled.unwrap_or(true),
                 fallback_on_rate_limit: row.fallback_on_rate_limit.unwrap_or(true),
@@ -739,9 +1034,14 @@ async fn load_composite_models_from_db(db: &PgPool, escalation_models: &[String]
                 backoff_jitter: row.backoff_jitter,
                 backoff_max_total_ms: row.backoff_max_total_ms,
                 sanitize_responses: row.sanitize_responses,
+                rewrite_response_model: row.rewrite_response_model,
                 trusted: row.trusted,
                 open_responses_adapter: row.open_responses_adapter.unwrap_or(true),
-                routing_rules: Vec::new(), // Populated from separate query below
+                routing_rules: Vec::new(),   // Populated from separate query below
+                pricing_headers: Vec::new(), // Populated from separate query below
+                shadow: None,                // Populated from separate query below
+                system_prompt: system_prompt_config(row.system_prompt, &row.system_prompt_mode),
+                n_limit: n_limit_config(row.max_n, &row.max_n_mode),
                 components: Vec::new(),
                 api_keys: Vec::new(),
             },
@@ -762,6 +1062,22 @@ async fn load_composite_models_from_db(db: &PgPool, escalation_models: &[String]
             }
         };
 
+        let sigv4 = match bedrock_signing.get(&row.endpoint_id) {
+            Some(None) => continue, // Credentials unusable (already reported); don't forward unsigned
+            Some(Some(sigv4)) => Some(sigv4.clone()),
+            None => None,
+        };
+        let tls = match endpoint_tls.get(&row.endpoint_id) {
+            Some(None) => continue, // Client key unusable (already reported)
+            Some(Some(tls)) => Some(tls.clone()),
+            None => None,
+        };
+        let (endpoint_api_key, key_rotation) = match endpoint_api_keys.get(&row.endpoint_id) {
+            Some(None) => continue, // Secret reference unresolvable (already reported)
+            Some(Some(keys)) => (Some(keys.key.clone()), keys.rotation.clone()),
+            None => (None, None),
+        };
+
         if let Some(composite) = composite_map.get_mut(&row.composite_model_id) {
             composite.components.push(CompositeModelComponent {
                 weight: row.weight,
@@ -771,7 +1087,11 @@ async fn load_composite_models_from_db(db: &PgPool, escalation_models: &[String]
                     requests_per_second: row.deployment_requests_per_second,
                     burst_size: row.deployment_burst_size,
                     capacity: row.deployment_capacity,
+                    // The composite's per-key cap applies across the whole pool
+                    per_key_capacity: None,
                     sanitize_responses: row.deployment_sanitize_responses,
+                    // The composite's setting applies to every provider in the pool
+                    rewrite_response_model: false,
                     trusted: row.deployment_trusted,
                     open_responses_adapter: row.deployment_open_responses_adapter.unwrap_or(true),
                     reasoning_translation: resolve_reasoning_translation(
@@ -779,7 +1099,11 @@ async fn load_composite_models_from_db(db: &PgPool, escalation_models: &[String]
                         row.model_reasoning_translation_overrides,
                         &row.deployment_alias,
                     ),
-                    routing_rules: Vec::new(), // Components don't have their own routing rules
+                    routing_rules: Vec::new(),   // Components don't have their own routing rules
+                    pricing_headers: Vec::new(), // Requests are billed at the composite's tariffs
+                    shadow: None,                // Copies are taken at the composite's level
+                    system_prompt: None,         // The composite's prompt applies to the whole pool
+                    n_limit: None,               // As does the composite's cap on n
                     // Components don't surface their own fallback/backoff —
                     // the composite's PoolSpec.fallback drives retries across
                     // the whole pool.
@@ -795,9 +1119,15 @@ async fn load_composite_models_from_db(db: &PgPool, escalation_models: &[String]
                     backoff_jitter: "full".to_string(),
                     backoff_max_total_ms: None,
                     endpoint_url,
-                    endpoint_api_key: row.endpoint_api_key.clone(),
+                    endpoint_api_key,
+                    key_rotation,
                     auth_header_name: row.auth_header_name.clone(),
                     auth_header_prefix: row.auth_header_prefix.clone(),
+                    sigv4,
+                    tls,
+                    endpoint_region: row.endpoint_region.clone(),
+                    endpoint_concurrency_limit: endpoint_concurrency_limit(row.endpoint_id, row.endpoint_max_concurrency),
+                    body_transform: parse_body_transform(row.endpoint_body_transform, &row.deployment_alias),
                     api_keys: Vec::new(),
                 },
             });
@@ -817,6 +1147,8 @@ async fn load_composite_models_from_db(db: &PgPool, escalation_models: &[String]
                     burst_size: row.burst_size,
                     user_verified: row.user_verified,
                     zero_data_retention: row.user_zero_data_retention,
+                    max_priority: row.api_key_max_priority,
+                    owner_limits: owner_limits.get(&row.api_key_user_id).copied().unwrap_or_default(),
                 });
             }
         }
@@ -843,29 +1175,22 @@ fn convert_composite_to_target_spec(
 ) -> (String, TargetSpecOrList) {
     // Add this composite model's API keys to key_definitions
     for api_key in &composite.api_keys {
-        // The system key (nil UUID) carries internal traffic and is never tiered.
-        let rate_limit = if api_key.id.is_nil() {
-            None
-        } else {
-            resolve_key_rate_limit(
-                api_key.requests_per_second,
-                api_key.burst_size,
-                api_key.user_verified,
-                rate_limit_tiers,
-            )
-        };
+        let (rate_limit, concurrency_limit) = resolve_key_limits(api_key, rate_limit_tiers);
 
         let mut labels = HashMap::from([("purpose".to_string(), api_key.purpose.clone())]);
         // Surface the account's zero-data-retention flag to onwards as a label.
         // Always emitted ("true"/"false"); onwards does not act on it yet.
         labels.insert("zdr".to_string(), api_key.zero_data_retention.to_string());
+        if api_key.max_priority > 0 {
+            labels.insert(onwards::priority::MAX_PRIORITY_LABEL.to_string(), api_key.max_priority.to_string());
+        }
 
         key_definitions.insert(
             api_key.id.to_string(),
             KeyDefinition {
                 key: api_key.secret.clone(),
                 rate_limit,
-                concurrency_limit: None,
+                concurrency_limit,
                 labels,
             },
         );
@@ -905,6 +1230,9 @@ fn convert_composite_to_target_spec(
             max_concurrent_requests: capacity as usize,
         }
     });
+    let per_key_concurrency_limit = composite.per_key_capacity.map(|capacity| ConcurrencyLimitParameters {
+        max_concurrent_requests: capacity.max(1) as usize,
+    });
 
     // Convert our LoadBalancingStrategy to onwards LoadBalanceStrategy
     let strategy = match composite.lb_strategy {
@@ -974,12 +1302,16 @@ fn convert_composite_to_target_spec(
                     target.alias, target.model_name, component.weight, composite.sanitize_responses, target.trusted
                 );
                 ProviderSpec {
+                    // Named by the component's alias so callers can pin a request to
+                    // this component via `x-dwctl-route-to`.
+                    name: Some(target.alias.clone()),
                     url: target.endpoint_url.clone(),
                     onwards_key: target.endpoint_api_key.clone(),
                     onwards_model: Some(target.model_name.clone()),
                     weight: component.weight.max(1) as u32,
                     rate_limit: provider_rate_limit,
                     concurrency_limit: provider_concurrency_limit,
+                    endpoint_concurrency_limit: target.endpoint_concurrency_limit.clone(),
                     upstream_auth_header_name: if target.auth_header_name != "Authorization" {
                         Some(target.auth_header_name.clone())
                     } else {
@@ -994,6 +1326,7 @@ fn convert_composite_to_target_spec(
                     // For composite models, use the composite model's sanitize_responses setting
                     // This ensures the virtual model's toggle controls all providers
                     sanitize_response: composite.sanitize_responses,
+                    rewrite_response_model: composite.rewrite_response_model,
                     open_responses: Some(OpenResponsesConfig {
                         adapter: target.open_responses_adapter,
                     }),
@@ -1008,6 +1341,11 @@ fn convert_composite_to_target_spec(
                     // leaked to them.
                     propagate_trace_context: None,
                     reasoning_translation: target.reasoning_translation.clone().map(Into::into),
+                    sigv4: target.sigv4.clone(),
+                    tls: target.tls.clone(),
+                    key_rotation: target.key_rotation.clone(),
+                    region: target.endpoint_region.clone(),
+                    body_transform: target.body_transform.clone().map(Into::into),
                 }
             }
         })
@@ -1029,6 +1367,7 @@ fn convert_composite_to_target_spec(
         keys,
         rate_limit,
         concurrency_limit,
+        per_key_concurrency_limit,
         fallback,
         strategy,
         providers,
@@ -1039,11 +1378,50 @@ fn convert_composite_to_target_spec(
             adapter: composite.open_responses_adapter,
         }),
         routing_rules: composite.routing_rules.clone(),
+        labeled_response_headers: composite.pricing_headers.clone(),
+        shadow: composite.shadow.clone(),
+        system_prompt: composite.system_prompt.clone(),
+        n_limit: composite.n_limit,
     };
 
     (composite.alias.clone(), TargetSpecOrList::Pool(pool_spec))
 }
 
+/// Resolves the rate and concurrency limits for an API key. Precedence,
+/// highest first: the owner's user override, the key's own rate limit, the
+/// highest default among the owner's groups, then the verified/unverified tier.
+/// A burst size belongs to the rate at its level. Concurrency has no per-key or
+/// tier setting, so it is the user override, then the group default, else
+/// unlimited. The system key (nil UUID) carries internal traffic and is never
+/// limited.
+fn resolve_key_limits(
+    api_key: &OnwardsApiKey,
+    tiers: &RateLimitTiersConfig,
+) -> (Option<RateLimitParameters>, Option<ConcurrencyLimitParameters>) {
+    if api_key.id.is_nil() {
+        return (None, None);
+    }
+
+    let owner = &api_key.owner_limits;
+    let key_has_rate = matches!(api_key.requests_per_second, Some(rps) if rps > 0.0);
+    let (rps, burst) = match (owner.user_requests_per_second, owner.group_requests_per_second) {
+        (Some(rps), _) => (Some(rps), owner.user_burst_size),
+        (None, Some(rps)) if !key_has_rate => (Some(rps), owner.group_burst_size),
+        _ => (api_key.requests_per_second, api_key.burst_size),
+    };
+    let rate_limit = resolve_key_rate_limit(rps, burst, api_key.user_verified, tiers);
+
+    let concurrency_limit = owner
+        .user_concurrency_limit
+        .or(owner.group_concurrency_limit)
+        .filter(|limit| *limit > 0)
+        .map(|limit| ConcurrencyLimitParameters {
+            max_concurrent_requests: limit as usize,
+        });
+
+    (rate_limit, concurrency_limit)
+}
+
 /// Resolves the rate limit for an API key. A non-NULL per-key
 /// `requests_per_second` always wins; otherwise we fall back to the
 /// verified/unverified tier defaults from config, which may themselves be unset
@@ -1091,30 +1469,23 @@ fn convert_to_config_file(
         .map(|target| {
             // Add this target's API keys to key_definitions
             for api_key in &target.api_keys {
-                // The system key (nil UUID) carries internal traffic and is never tiered.
-                let rate_limit = if api_key.id.is_nil() {
-                    None
-                } else {
-                    resolve_key_rate_limit(
-                        api_key.requests_per_second,
-                        api_key.burst_size,
-                        api_key.user_verified,
-                        rate_limit_tiers,
-                    )
-                };
+                let (rate_limit, concurrency_limit) = resolve_key_limits(api_key, rate_limit_tiers);
 
                 // Build labels from API key purpose
                 let mut labels = HashMap::from([("purpose".to_string(), api_key.purpose.clone())]);
                 // Surface the account's zero-data-retention flag as a label.
                 // Always emitted ("true"/"false"); onwards does not act on it yet.
                 labels.insert("zdr".to_string(), api_key.zero_data_retention.to_string());
+                if api_key.max_priority > 0 {
+                    labels.insert(onwards::priority::MAX_PRIORITY_LABEL.to_string(), api_key.max_priority.to_string());
+                }
 
                 key_definitions.insert(
                     api_key.id.to_string(),
                     KeyDefinition {
                         key: api_key.secret.clone(),
                         rate_limit,
-                        concurrency_limit: None,
+                        concurrency_limit,
                         labels,
                     },
                 );
@@ -1155,16 +1526,19 @@ fn convert_to_config_file(
 
             // Build provider spec from target
             let provider = ProviderSpec {
+                name: Some(target.alias.clone()),
                 url: target.endpoint_url.clone(),
                 onwards_key: target.endpoint_api_key.clone(),
                 onwards_model: Some(target.model_name.clone()),
                 rate_limit,
                 concurrency_limit,
+                endpoint_concurrency_limit: target.endpoint_concurrency_limit.clone(),
                 upstream_auth_header_name,
                 upstream_auth_header_prefix,
                 response_headers: None,
                 weight: 1,
                 sanitize_response: target.sanitize_responses,
+                rewrite_response_model: target.rewrite_response_model,
                 open_responses: Some(OpenResponsesConfig {
                     adapter: target.open_responses_adapter,
                 }),
@@ -1175,6 +1549,11 @@ fn convert_to_config_file(
                 // context, third-party providers do not.
                 propagate_trace_context: None,
                 reasoning_translation: target.reasoning_translation.clone().map(Into::into),
+                sigv4: target.sigv4.clone(),
+                tls: target.tls.clone(),
+                key_rotation: target.key_rotation.clone(),
+                region: target.endpoint_region.clone(),
+                body_transform: target.body_transform.clone().map(Into::into),
             };
 
             // Build fallback configuration. For single-provider (standard)
@@ -1212,6 +1591,9 @@ fn convert_to_config_file(
                 keys,
                 rate_limit: None,
                 concurrency_limit: None,
+                per_key_concurrency_limit: target.per_key_capacity.map(|capacity| ConcurrencyLimitParameters {
+                    max_concurrent_requests: capacity.max(1) as usize,
+                }),
                 fallback,
                 strategy: OnwardsLoadBalanceStrategy::default(),
                 providers: vec![provider],
@@ -1220,6 +1602,10 @@ fn convert_to_config_file(
                 sanitize_response: target.sanitize_responses,
                 trusted: false,
                 routing_rules: target.routing_rules,
+                labeled_response_headers: target.pricing_headers,
+                shadow: target.shadow,
+                system_prompt: target.system_prompt,
+                n_limit: target.n_limit,
             };
 
             (target.alias, TargetSpecOrList::Pool(pool_spec))
@@ -1273,10 +1659,43 @@ pub async fn load_targets_from_db(
     escalation_models: &[String],
     strict_mode: bool,
     rate_limit_tiers: &RateLimitTiersConfig,
+    endpoint_credentials_key: Option<&[u8]>,
+    secrets: Option<&SecretStore>,
 ) -> Result<Targets, anyhow::Error> {
+    let config = load_config_file_from_db(
+        db,
+        escalation_models,
+        strict_mode,
+        rate_limit_tiers,
+        endpoint_credentials_key,
+        secrets,
+    )
+    .await?;
+
+    // Convert ConfigFile to Targets
+    Targets::from_config(config)
+}
+
+/// Loads the onwards [`ConfigFile`] that [`load_targets_from_db`] builds its targets from.
+///
+/// Takes the same arguments as [`load_targets_from_db`].
+#[tracing::instrument(skip(db, escalation_models))]
+pub async fn load_config_file_from_db(
+    db: &PgPool,
+    escalation_models: &[String],
+    strict_mode: bool,
+    rate_limit_tiers: &RateLimitTiersConfig,
+    endpoint_credentials_key: Option<&[u8]>,
+    secrets: Option<&SecretStore>,
+) -> Result<ConfigFile, anyhow::Error> {
     let query_start = std::time::Instant::now();
     debug!("Loading onwards targets from database (with composite models)");
 
+    let bedrock_signing = load_bedrock_signing(db, endpoint_credentials_key).await?;
+    let endpoint_tls = load_endpoint_tls(db, endpoint_credentials_key).await?;
+    let endpoint_api_keys = load_endpoint_api_keys(db, secrets).await?;
+    let owner_limits = load_owner_rate_limits(db).await?;
+
     // Load regular deployed models (existing logic)
     // Note: We pass escalation_models to grant batch API keys access to escalation models
     let rows = sqlx::query!(
@@ -1289,9 +1708,15 @@ pub async fn load_targets_from_db(
             dm.requests_per_second as deployment_requests_per_second,
             dm.burst_size as deployment_burst_size,
             dm.capacity,
+            dm.per_key_capacity,
             dm.sanitize_responses,
+            dm.rewrite_response_model,
             dm.trusted,
             dm.open_responses_adapter,
+            dm.system_prompt,
+            dm.system_prompt_mode,
+            dm.max_n,
+            dm.max_n_mode,
             ie.reasoning_translation as endpoint_reasoning_translation,
             dm.reasoning_translation_overrides as model_reasoning_translation_overrides,
             dm.fallback_enabled,
@@ -1307,16 +1732,20 @@ pub async fn load_targets_from_db(
             dm.backoff_max_total_ms,
             ie.id as endpoint_id,
             ie.url as "endpoint_url!",
-            ie.api_key as endpoint_api_key,
             ie.auth_header_name,
             ie.auth_header_prefix,
+            ie.region as endpoint_region,
+            ie.max_concurrency as endpoint_max_concurrency,
+            ie.body_transform as endpoint_body_transform,
             ak.id as "api_key_id?",
             ak.secret as "api_key_secret?",
             ak.purpose as "api_key_purpose?",
             ak.requests_per_second as api_key_requests_per_second,
             ak.burst_size as api_key_burst_size,
+            ak.user_id as "api_key_user_id?",
             ak.user_verified as "api_key_user_verified?",
-            ak.user_zero_data_retention as "api_key_user_zero_data_retention?"
+            ak.user_zero_data_retention as "api_key_user_zero_data_retention?",
+            ak.max_priority as "api_key_max_priority?"
         FROM deployed_models dm
         INNER JOIN inference_endpoints ie ON dm.hosted_on = ie.id
         LEFT JOIN LATERAL (
@@ -1326,8 +1755,10 @@ pub async fn load_targets_from_db(
                 ak.purpose,
                 ak.requests_per_second,
                 ak.burst_size,
+                ak.user_id,
                 u.verified as user_verified,
-                u.zero_data_retention as user_zero_data_retention
+                u.zero_data_retention as user_zero_data_retention,
+                ak.max_priority
             FROM api_keys ak
             JOIN users u ON u.id = ak.user_id
             WHERE (
@@ -1340,7 +1771,9 @@ pub async fn load_targets_from_db(
                     WHERE dg.deployment_id = dm.id
                       AND ug.user_id = ak.user_id
                 )
-                -- OR model is in public group
+                -- OR model is public
+                OR dm.allow_public
+                -- OR model is in public group (nil UUID, legacy convention)
                 OR EXISTS (
                     SELECT 1 FROM deployment_groups dg
                     WHERE dg.deployment_id = dm.id
@@ -1352,6 +1785,22 @@ pub async fn load_targets_from_db(
                     AND dm.alias = ANY($1::text[])
                 )
             )
+            -- Gated models also need an approved access request (system user
+            -- and batch escalation are exempt)
+            AND (
+                NOT dm.require_approval
+                OR ak.user_id = '00000000-0000-0000-0000-000000000000'
+                OR (
+                    ak.purpose = 'batch'
+                    AND dm.alias = ANY($1::text[])
+                )
+                OR EXISTS (
+                    SELECT 1 FROM access_requests ar
+                    WHERE ar.deployment_id = dm.id
+                      AND ar.user_id = ak.user_id
+                      AND ar.status = 'approved'
+                )
+            )
             AND (
                 ak.user_id = '00000000-0000-0000-0000-000000000000'
                 -- Positive balance read directly from the total
@@ -1376,7 +1825,7 @@ pub async fn load_targets_from_db(
             AND ak.is_deleted = false
             -- Spending-cap gate: exclude every key of a cap scope (the capped
             -- root and its hidden batch child alike) once the scope's
-            -- CALENDAR-ALIGNED (UTC, non-rolling) window spend has reached the
+            -- CALENDAR-ALIGNED (owner's timezone, non-rolling) window spend has reached the
             -- root's limit. Guarded so the uncapped majority short-circuits on
             -- the first branch; the subquery is two PK probes. Free models
             -- stay usable on an exhausted scope, mirroring the balance gate's
@@ -1389,10 +1838,11 @@ pub async fn load_targets_from_db(
                 OR NOT EXISTS (
                     SELECT 1
                     FROM api_keys root
+                    JOIN users owner ON owner.id = root.user_id
                     LEFT JOIN api_key_spend_checkpoints ck ON ck.api_key_id = root.id
                     WHERE root.id = COALESCE(ak.parent_api_key_id, ak.id)
                       AND root.spend_limit IS NOT NULL
-                      AND api_key_cap_window_current(ck.window_started_at, root.spend_limit_interval)
+                      AND api_key_cap_window_current(ck.window_started_at, root.spend_limit_interval, owner.timezone)
                       AND ck.window_spend >= root.spend_limit
                       AND EXISTS (
                           SELECT 1 FROM model_tariffs mt
@@ -1402,6 +1852,40 @@ pub async fn load_targets_from_db(
                       )
                 )
             )
+            -- Group spending limits (migration 150): once a group's window
+            -- spend reaches its limit, every key of every member is excluded
+            -- from priced models, with the same window function as the key
+            -- caps (aligned in UTC). Groups without a limit never match.
+            AND (
+                ak.user_id = '00000000-0000-0000-0000-000000000000'
+                OR NOT EXISTS (
+                    SELECT 1
+                    FROM user_groups ug
+                    JOIN group_spending_limits gl ON gl.group_id = ug.group_id
+                    JOIN group_spend_checkpoints gck ON gck.group_id = ug.group_id
+                    WHERE ug.user_id = ak.user_id
+                      AND api_key_cap_window_current(gck.window_started_at, gl.spending_limit_interval, 'UTC')
+                      AND gck.window_spend >= gl.spending_limit
+                      AND EXISTS (
+                          SELECT 1 FROM model_tariffs mt
+                          WHERE mt.deployed_model_id = dm.id
+                            AND mt.valid_until IS NULL
+                            AND (mt.input_price_per_token > 0 OR mt.output_price_per_token > 0)
+                      )
+                )
+            )
+            -- Per-key model restrictions (migration 144): narrow the key's
+            -- group-derived access with the scope root's allow/deny lists, so
+            -- a cap-scope child inherits its parent's restrictions. NULL lists
+            -- don't restrict; the system key never carries any.
+            AND NOT EXISTS (
+                SELECT 1 FROM api_keys scope
+                WHERE scope.id = COALESCE(ak.parent_api_key_id, ak.id)
+                  AND (
+                      (scope.allowed_model_ids IS NOT NULL AND NOT (dm.id = ANY(scope.allowed_model_ids)))
+                      OR dm.id = ANY(COALESCE(scope.denied_model_ids, '{}'))
+                  )
+            )
             -- Inference data plane only: platform (management) keys must never
             -- enter onwards' key set. Mirrors is_inference_purpose in
             -- db::models::api_keys (SQL cannot call it). The system key is
@@ -1413,6 +1897,7 @@ pub async fn load_targets_from_db(
             )
         ) ak ON true
         WHERE dm.deleted = FALSE
+          AND dm.active = TRUE
           AND dm.is_composite = FALSE
         ORDER BY dm.id, ak.id
         "#,
@@ -1431,6 +1916,21 @@ pub async fn load_targets_from_db(
     // Group results into targets
     let mut targets_map: HashMap<DeploymentId, OnwardsTarget> = HashMap::new();
     for row in rows {
+        let sigv4 = match bedrock_signing.get(&row.endpoint_id) {
+            Some(None) => continue, // Credentials unusable (already reported); don't forward unsigned
+            Some(Some(sigv4)) => Some(sigv4.clone()),
+            None => None,
+        };
+        let tls = match endpoint_tls.get(&row.endpoint_id) {
+            Some(None) => continue, // Client key unusable (already reported)
+            Some(Some(tls)) => Some(tls.clone()),
+            None => None,
+        };
+        let (endpoint_api_key, key_rotation) = match endpoint_api_keys.get(&row.endpoint_id) {
+            Some(None) => continue, // Secret reference unresolvable (already reported)
+            Some(Some(keys)) => (Some(keys.key.clone()), keys.rotation.clone()),
+            None => (None, None),
+        };
         let deployment_id = row.deployment_id;
         let target = targets_map.entry(deployment_id).or_insert_with(|| {
             OnwardsTarget {
@@ -1439,7 +1939,9 @@ pub async fn load_targets_from_db(
                 requests_per_second: row.deployment_requests_per_second,
                 burst_size: row.deployment_burst_size,
                 capacity: row.capacity,
+                per_key_capacity: row.per_key_capacity,
                 sanitize_responses: row.sanitize_responses,
+                rewrite_response_model: row.rewrite_response_model,
                 trusted: row.trusted,
                 open_responses_adapter: row.open_responses_adapter.unwrap_or(true),
                 reasoning_translation: resolve_reasoning_translation(
@@ -1447,7 +1949,11 @@ pub async fn load_targets_from_db(
                     row.model_reasoning_translation_overrides.clone(),
                     &row.alias,
                 ),
-                routing_rules: Vec::new(), // Populated from separate query below
+                routing_rules: Vec::new(),   // Populated from separate query below
+                pricing_headers: Vec::new(), // Populated from separate query below
+                shadow: None,                // Populated from separate query below
+                system_prompt: system_prompt_config(row.system_prompt.clone(), &row.system_prompt_mode),
+                n_limit: n_limit_config(row.max_n, &row.max_n_mode),
                 fallback_enabled: row.fallback_enabled.unwrap_or(true),
                 fallback_on_rate_limit: row.fallback_on_rate_limit.unwrap_or(true),
                 fallback_on_status: row.fallback_on_status.clone().unwrap_or_else(|| vec![429, 499, 500, 502, 503, 504]),
@@ -1460,9 +1966,15 @@ pub async fn load_targets_from_db(
                 backoff_jitter: row.backoff_jitter.clone(),
                 backoff_max_total_ms: row.backoff_max_total_ms,
                 endpoint_url: url::Url::parse(&row.endpoint_url).expect("Invalid URL in database"),
-                endpoint_api_key: row.endpoint_api_key.clone(),
+                endpoint_api_key,
+                key_rotation,
                 auth_header_name: row.auth_header_name.clone(),
                 auth_header_prefix: row.auth_header_prefix.clone(),
+                sigv4,
+                tls,
+                endpoint_region: row.endpoint_region.clone(),
+                endpoint_concurrency_limit: endpoint_concurrency_limit(row.endpoint_id, row.endpoint_max_concurrency),
+                body_transform: parse_body_transform(row.endpoint_body_transform.clone(), &row.alias),
                 api_keys: Vec::new(),
             }
         });
@@ -1472,10 +1984,18 @@ pub async fn load_targets_from_db(
         // tie it to the same "row materialised" check as the other api_key columns
         // so a future schema/SQL change can't silently demote keys to the
         // unverified tier.
-        if let (Some(api_key_id), Some(api_key_secret), Some(api_key_purpose), Some(user_verified), Some(zero_data_retention)) = (
+        if let (
+            Some(api_key_id),
+            Some(api_key_secret),
+            Some(api_key_purpose),
+            Some(user_id),
+            Some(user_verified),
+            Some(zero_data_retention),
+        ) = (
             row.api_key_id,
             row.api_key_secret,
             row.api_key_purpose,
+            row.api_key_user_id,
             row.api_key_user_verified,
             row.api_key_user_zero_data_retention,
         ) {
@@ -1487,6 +2007,8 @@ pub async fn load_targets_from_db(
                 burst_size: row.api_key_burst_size,
                 user_verified,
                 zero_data_retention,
+                max_priority: row.api_key_max_priority.unwrap_or(0),
+                owner_limits: owner_limits.get(&user_id).copied().unwrap_or_default(),
             });
         }
     }
@@ -1494,13 +2016,21 @@ pub async fn load_targets_from_db(
     debug!("Loaded {} deployed models", targets_map.len());
 
     // Load composite models (pass escalation_models to grant batch API keys access)
-    let composites = load_composite_models_from_db(db, escalation_models).await?;
+    let composites = load_composite_models_from_db(
+        db,
+        escalation_models,
+        &bedrock_signing,
+        &endpoint_tls,
+        &endpoint_api_keys,
+        &owner_limits,
+    )
+    .await?;
 
     // Load traffic routing rules for all non-deleted models (regular + composite)
     let traffic_rule_rows = sqlx::query!(
         r#"
         SELECT mtr.deployed_model_id, mtr.api_key_purpose, mtr.action,
-               dm.alias as "redirect_target_alias?"
+               dm.alias as "redirect_target_alias?", mtr.sanitize_responses, mtr.prefer_region
         FROM model_traffic_rules mtr
         LEFT JOIN deployed_models dm ON dm.id = mtr.redirect_target_id
         WHERE mtr.deployed_model_id IN (
@@ -1522,37 +2052,436 @@ pub async fn load_targets_from_db(
                 "redirect" => RoutingAction::Redirect {
                     target: rule_row.redirect_target_alias.unwrap_or_default(),
                 },
+                "sanitize" => match rule_row.sanitize_responses {
+                    Some(enabled) => RoutingAction::Sanitize { enabled },
+                    None => continue,
+                },
+                "prefer_region" => match rule_row.prefer_region {
+                    Some(region) => RoutingAction::PreferRegion { region },
+                    None => continue,
+                },
                 _ => continue,
             },
         };
         routing_rules_map.entry(rule_row.deployed_model_id).or_default().push(routing_rule);
     }
 
-    // Attach routing rules to regular targets
+    // Load the current tariffs that apply outside batches, to report the price each
+    // API key purpose is billed at in response headers
+    let tariff_rows = sqlx::query!(
+        r#"
+        SELECT deployed_model_id, api_key_purpose, input_price_per_token, output_price_per_token
+        FROM model_tariffs
+        WHERE valid_until IS NULL
+          AND valid_from <= NOW()
+          AND completion_window IS NULL
+          AND deployed_model_id IN (
+            SELECT id FROM deployed_models WHERE deleted = FALSE
+          )
+        "#
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut tariffs_map: HashMap<DeploymentId, Vec<(Option<String>, Decimal, Decimal)>> = HashMap::new();
+    for row in tariff_rows {
+        tariffs_map.entry(row.deployed_model_id).or_default().push((
+            row.api_key_purpose,
+            row.input_price_per_token,
+            row.output_price_per_token,
+        ));
+    }
+    let mut pricing_headers_map: HashMap<DeploymentId, Vec<LabeledResponseHeaders>> = tariffs_map
+        .into_iter()
+        .map(|(deployment_id, tariffs)| (deployment_id, pricing_headers(&tariffs)))
+        .collect();
+
+    // Load shadow traffic. Shadows whose model is deleted are skipped; a shadow
+    // alias with no API keys is still routable by onwards, since copies don't
+    // authenticate against the shadow pool.
+    let shadow_rows = sqlx::query!(
+        r#"
+        SELECT s.deployment_id, dm.alias, s.fraction
+        FROM deployment_shadows s
+        JOIN deployed_models dm ON dm.id = s.shadow_deployment_id
+        WHERE dm.deleted = FALSE
+        "#
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut shadows_map: HashMap<DeploymentId, ShadowConfig> = shadow_rows
+        .into_iter()
+        .map(|row| {
+            (
+                row.deployment_id,
+                ShadowConfig {
+                    target: row.alias,
+                    fraction: row.fraction,
+                },
+            )
+        })
+        .collect();
+
+    // Attach routing rules, pricing headers and shadows to regular targets
     for (deployment_id, target) in &mut targets_map {
         if let Some(rules) = routing_rules_map.remove(deployment_id) {
             target.routing_rules = rules;
         }
+        if let Some(headers) = pricing_headers_map.remove(deployment_id) {
+            target.pricing_headers = headers;
+        }
+        target.shadow = shadows_map.remove(deployment_id);
     }
 
     let targets: Vec<_> = targets_map.into_values().collect();
 
-    // Attach routing rules to composite models
+    // Attach routing rules, pricing headers and shadows to composite models
     let composites: Vec<_> = composites
         .into_iter()
         .map(|mut c| {
             if let Some(rules) = routing_rules_map.remove(&c.id) {
                 c.routing_rules = rules;
             }
+            if let Some(headers) = pricing_headers_map.remove(&c.id) {
+                c.pricing_headers = headers;
+            }
+            c.shadow = shadows_map.remove(&c.id);
             c
         })
         .collect();
 
     // Convert to ConfigFile format
-    let config = convert_to_config_file(targets, composites, strict_mode, rate_limit_tiers);
+    Ok(convert_to_config_file(targets, composites, strict_mode, rate_limit_tiers))
+}
 
-    // Convert ConfigFile to Targets
-    Targets::from_config(config)
+/// Loads the limits admins set on users and groups, keyed by user. Only users
+/// with a user override or a member of a group with defaults are included. A
+/// user in several groups gets the highest default for each limit. Defaults on
+/// the Everyone group (nil UUID) apply to every user, since membership of it is
+/// implicit.
+async fn load_owner_rate_limits(db: &PgPool) -> Result<HashMap<UserId, OwnerRateLimits>, anyhow::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            u.id as user_id,
+            ul.requests_per_second as "user_requests_per_second?",
+            ul.burst_size as "user_burst_size?",
+            ul.concurrency_limit as "user_concurrency_limit?",
+            gl.requests_per_second as group_requests_per_second,
+            gl.burst_size as group_burst_size,
+            gl.concurrency_limit as group_concurrency_limit
+        FROM users u
+        LEFT JOIN user_rate_limits ul ON ul.user_id = u.id
+        CROSS JOIN LATERAL (
+            SELECT
+                MAX(g.requests_per_second) as requests_per_second,
+                MAX(g.burst_size) as burst_size,
+                MAX(g.concurrency_limit) as concurrency_limit
+            FROM group_rate_limits g
+            WHERE g.group_id = '00000000-0000-0000-0000-000000000000'
+               OR EXISTS (
+                   SELECT 1 FROM user_groups ug
+                   WHERE ug.group_id = g.group_id AND ug.user_id = u.id
+               )
+        ) gl
+        WHERE ul.user_id IS NOT NULL
+           OR gl.requests_per_second IS NOT NULL
+           OR gl.burst_size IS NOT NULL
+           OR gl.concurrency_limit IS NOT NULL
+        "#
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            (
+                row.user_id,
+                OwnerRateLimits {
+                    user_requests_per_second: row.user_requests_per_second,
+                    user_burst_size: row.user_burst_size,
+                    user_concurrency_limit: row.user_concurrency_limit,
+                    group_requests_per_second: row.group_requests_per_second,
+                    group_burst_size: row.group_burst_size,
+                    group_concurrency_limit: row.group_concurrency_limit,
+                },
+            )
+        })
+        .collect())
+}
+
+/// Builds the per-purpose pricing response headers for one model's current tariffs.
+///
+/// Each API key purpose gets the price the analytics batcher bills it at: its own
+/// tariff, else the realtime tariff, else the model's default tariff (no purpose).
+/// Tariffs are `(api_key_purpose, input_price_per_token, output_price_per_token)`.
+fn pricing_headers(tariffs: &[(Option<String>, Decimal, Decimal)]) -> Vec<LabeledResponseHeaders> {
+    let tariff_for = |purpose: Option<&str>| tariffs.iter().find(|(p, _, _)| p.as_deref() == purpose);
+
+    ["realtime", "batch", "playground", "platform"]
+        .into_iter()
+        .filter_map(|purpose| {
+            let (_, input_price, output_price) = tariff_for(Some(purpose))
+                .or_else(|| tariff_for(Some("realtime")))
+                .or_else(|| tariff_for(None))?;
+            Some(LabeledResponseHeaders {
+                match_labels: HashMap::from([("purpose".to_string(), purpose.to_string())]),
+                headers: HashMap::from([
+                    (INPUT_PRICE_HEADER.to_string(), input_price.normalize().to_string()),
+                    (OUTPUT_PRICE_HEADER.to_string(), output_price.normalize().to_string()),
+                ]),
+            })
+        })
+        .collect()
+}
+
+/// Builds onwards' endpoint concurrency limit from an endpoint's
+/// `max_concurrency`. The limit is keyed by endpoint id, so every deployment on
+/// the endpoint shares one counter.
+fn endpoint_concurrency_limit(endpoint_id: InferenceEndpointId, max_concurrency: Option<i32>) -> Option<EndpointConcurrencyLimit> {
+    max_concurrency.map(|max| EndpointConcurrencyLimit {
+        endpoint: endpoint_id.to_string(),
+        max_concurrent_requests: max.max(1) as usize,
+    })
+}
+
+/// Builds onwards' system prompt config from a deployment's `system_prompt`
+/// and `system_prompt_mode` columns. A blank prompt injects nothing.
+fn system_prompt_config(system_prompt: Option<String>, mode: &str) -> Option<SystemPromptConfig> {
+    let content = system_prompt.filter(|prompt| !prompt.trim().is_empty())?;
+    let mode = match SystemPromptMode::try_parse(mode).unwrap_or_default() {
+        SystemPromptMode::Prepend => OnwardsSystemPromptMode::Prepend,
+        SystemPromptMode::Merge => OnwardsSystemPromptMode::Merge,
+    };
+    Some(SystemPromptConfig { content, mode })
+}
+
+/// Builds onwards' cap on completions per request from a deployment's `max_n`
+/// and `max_n_mode` columns.
+fn n_limit_config(max_n: Option<i32>, mode: &str) -> Option<NLimitConfig> {
+    let max = u32::try_from(max_n?).ok().filter(|max| *max > 0)?;
+    let mode = match NLimitMode::try_parse(mode).unwrap_or_default() {
+        NLimitMode::Reject => OnwardsNLimitMode::Reject,
+        NLimitMode::Clamp => OnwardsNLimitMode::Clamp,
+    };
+    Some(NLimitConfig { max, mode })
+}
+
+/// Decrypts the credentials of every Bedrock endpoint into onwards' SigV4 config.
+///
+/// A `None` entry marks a Bedrock endpoint whose credentials can't be used (no
+/// encryption key configured, or decryption failed). Its models are left out of
+/// the onwards config rather than forwarded unsigned.
+async fn load_bedrock_signing(
+    db: &PgPool,
+    endpoint_credentials_key: Option<&[u8]>,
+) -> Result<HashMap<InferenceEndpointId, Option<SigV4Config>>, anyhow::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT id, aws_region, aws_access_key_id, aws_secret_access_key_encrypted
+        FROM inference_endpoints
+        WHERE protocol = 'bedrock'
+        "#
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut signing = HashMap::with_capacity(rows.len());
+    for row in rows {
+        let (Some(region), Some(access_key_id), Some(secret_encrypted)) =
+            (row.aws_region, row.aws_access_key_id, row.aws_secret_access_key_encrypted)
+        else {
+            // Ruled out by the inference_endpoints_bedrock_credentials constraint
+            signing.insert(row.id, None);
+            continue;
+        };
+        let Some(key) = endpoint_credentials_key else {
+            crate::background_error!(
+                ONWARDS_SYNC,
+                "bedrock_credentials",
+                Error,
+                endpoint_id = %row.id,
+                "Bedrock endpoint configured but no encryption key is set; skipping its models"
+            );
+            signing.insert(row.id, None);
+            continue;
+        };
+        let secret = crate::encryption::decrypt(key, &secret_encrypted)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| String::from_utf8(bytes).map_err(anyhow::Error::from));
+        match secret {
+            Ok(secret_access_key) => {
+                signing.insert(
+                    row.id,
+                    Some(SigV4Config {
+                        region,
+                        service: "bedrock".to_string(),
+                        access_key_id,
+                        secret_access_key,
+                    }),
+                );
+            }
+            Err(e) => {
+                crate::background_error!(
+                    ONWARDS_SYNC,
+                    "bedrock_credentials",
+                    Error,
+                    endpoint_id = %row.id,
+                    error = %e,
+                    "Failed to decrypt Bedrock credentials; skipping the endpoint's models"
+                );
+                signing.insert(row.id, None);
+            }
+        }
+    }
+    Ok(signing)
+}
+
+/// Loads the TLS settings of every endpoint that has them, decrypting client keys.
+///
+/// A `None` entry marks an endpoint whose client key can't be used (no
+/// encryption key configured, or decryption failed). Its models are left out of
+/// the onwards config rather than forwarded without the client certificate.
+async fn load_endpoint_tls(
+    db: &PgPool,
+    endpoint_credentials_key: Option<&[u8]>,
+) -> Result<HashMap<InferenceEndpointId, Option<TlsConfig>>, anyhow::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT id, tls_ca_bundle, tls_client_cert, tls_client_key_encrypted
+        FROM inference_endpoints
+        WHERE tls_ca_bundle IS NOT NULL OR tls_client_cert IS NOT NULL
+        "#
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut endpoint_tls = HashMap::with_capacity(rows.len());
+    for row in rows {
+        let client_key = match row.tls_client_key_encrypted {
+            None => None,
+            Some(encrypted) => {
+                let Some(key) = endpoint_credentials_key else {
+                    crate::background_error!(
+                        ONWARDS_SYNC,
+                        "endpoint_tls",
+                        Error,
+                        endpoint_id = %row.id,
+                        "Endpoint has a TLS client certificate but no encryption key is set; skipping its models"
+                    );
+                    endpoint_tls.insert(row.id, None);
+                    continue;
+                };
+                let decrypted = crate::encryption::decrypt(key, &encrypted)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|bytes| String::from_utf8(bytes).map_err(anyhow::Error::from));
+                match decrypted {
+                    Ok(client_key) => Some(client_key),
+                    Err(e) => {
+                        crate::background_error!(
+                            ONWARDS_SYNC,
+                            "endpoint_tls",
+                            Error,
+                            endpoint_id = %row.id,
+                            error = %e,
+                            "Failed to decrypt TLS client key; skipping the endpoint's models"
+                        );
+                        endpoint_tls.insert(row.id, None);
+                        continue;
+                    }
+                }
+            }
+        };
+        endpoint_tls.insert(
+            row.id,
+            Some(TlsConfig {
+                ca_bundle: row.tls_ca_bundle,
+                client_cert: row.tls_client_cert,
+                client_key,
+            }),
+        );
+    }
+    Ok(endpoint_tls)
+}
+
+/// An endpoint's resolved upstream keys
+#[derive(Clone)]
+struct EndpointApiKeys {
+    /// The key sent when the endpoint has only one
+    key: String,
+    rotation: Option<KeyRotationConfig>,
+}
+
+/// Resolves every endpoint's stored API keys, any of which may be a secret reference.
+///
+/// A `None` entry marks an endpoint with a key that references a secret that
+/// can't be resolved (or no secret store was given). Its models are left out of
+/// the onwards config rather than forwarded with the reference as the key.
+async fn load_endpoint_api_keys(
+    db: &PgPool,
+    secrets: Option<&SecretStore>,
+) -> Result<HashMap<InferenceEndpointId, Option<EndpointApiKeys>>, anyhow::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT id, api_key, additional_api_keys, api_key_rotation
+        FROM inference_endpoints
+        WHERE api_key IS NOT NULL OR cardinality(additional_api_keys) > 0
+        "#
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut keys = HashMap::with_capacity(rows.len());
+    for row in rows {
+        let mut resolved = Vec::new();
+        let mut failure = None;
+        for stored in row.api_key.into_iter().chain(row.additional_api_keys) {
+            let key = match secrets {
+                Some(secrets) => secrets.resolve(&stored).await,
+                None if crate::secrets::SecretReference::parse(&stored).is_none() => Ok(stored),
+                None => Err(crate::secrets::SecretError::NoStore),
+            };
+            match key {
+                Ok(key) => resolved.push(key),
+                Err(e) => {
+                    failure = Some(e);
+                    break;
+                }
+            }
+        }
+        if let Some(e) = failure {
+            crate::background_error!(
+                ONWARDS_SYNC,
+                "endpoint_secret",
+                Error,
+                endpoint_id = %row.id,
+                error = %e,
+                "Failed to resolve endpoint API key reference; skipping the endpoint's models"
+            );
+            keys.insert(row.id, None);
+            continue;
+        }
+
+        // Rotation only applies to endpoints with more than one key
+        let rotation = (resolved.len() > 1).then(|| KeyRotationConfig {
+            keys: resolved.clone(),
+            strategy: match row.api_key_rotation.parse() {
+                Ok(ApiKeyRotation::LeastRecentlyUsed) => KeyRotationStrategy::LeastRecentlyUsed,
+                _ => KeyRotationStrategy::RoundRobin,
+            },
+            cooldown_secs: DEFAULT_COOLDOWN_SECS,
+        });
+        keys.insert(
+            row.id,
+            Some(EndpointApiKeys {
+                key: resolved.swap_remove(0),
+                rotation,
+            }),
+        );
+    }
+    Ok(keys)
 }
 
 /// Updates the daemon capacity limits DashMap from deployed_models.
@@ -3,7 +3,10 @@ use std::{str::FromStr, time::Duration};
 use onwards::{
     auth::ConstantTimeString,
     load_balancer::ProviderPool,
-    target::{LoadBalanceStrategy as OnwardsLoadBalanceStrategy, RoutingAction, TargetSpecOrList},
+    target::{
+        EndpointConcurrencyLimit, LoadBalanceStrategy as OnwardsLoadBalanceStrategy, NLimitConfig, NLimitMode, RoutingAction,
+        SystemPromptConfig, SystemPromptMode, TargetSpecOrList,
+    },
 };
 use tokio::{sync::mpsc, time::timeout};
 use tokio_util::sync::CancellationToken;
@@ -34,12 +37,18 @@ fn create_test_target(model_name: &str, alias: &str, endpoint_url: &str) -> Onwa
         requests_per_second: None,
         burst_size: None,
         capacity: None,
+        per_key_capacity: None,
         sanitize_responses: true,
+        rewrite_response_model: false,
         trusted: false,
         open_responses_adapter: true,
         reasoning_translation: None,
         endpoint_url: url::Url::parse(endpoint_url).unwrap(),
         routing_rules: Vec::new(),
+        pricing_headers: Vec::new(),
+        shadow: None,
+        system_prompt: None,
+        n_limit: None,
         fallback_enabled: false,
         fallback_on_rate_limit: false,
         fallback_on_status: Vec::new(),
@@ -54,6 +63,11 @@ fn create_test_target(model_name: &str, alias: &str, endpoint_url: &str) -> Onwa
         endpoint_api_key: None,
         auth_header_name: "Authorization".to_string(),
         auth_header_prefix: "Bearer ".to_string(),
+        sigv4: None,
+        tls: None,
+        key_rotation: None,
+        endpoint_region: None,
+        body_transform: None,
         api_keys: Vec::new(),
     }
 }
@@ -121,6 +135,30 @@ fn test_convert_to_config_file_with_single_target() {
     assert!(config.targets.contains_key("valid-alias"));
 }
 
+#[test]
+fn test_convert_to_config_file_with_per_key_capacity() {
+    let mut target = create_test_target("shared-model", "shared-alias", "https://api.shared.com");
+    target.capacity = Some(8);
+    target.per_key_capacity = Some(2);
+
+    let config = convert_to_config_file(vec![target], vec![], false, &RateLimitTiersConfig::default());
+
+    let TargetSpecOrList::Pool(pool) = &config.targets["shared-alias"] else {
+        panic!("Expected Pool target spec");
+    };
+    let per_key = pool.per_key_concurrency_limit.as_ref().expect("per-key limit should be set");
+    assert_eq!(per_key.max_concurrent_requests, 2);
+    assert_eq!(pool.providers[0].concurrency_limit.as_ref().unwrap().max_concurrent_requests, 8);
+
+    // Without a per-key capacity no per-key limit is configured
+    let target = create_test_target("other-model", "other-alias", "https://api.other.com");
+    let config = convert_to_config_file(vec![target], vec![], false, &RateLimitTiersConfig::default());
+    let TargetSpecOrList::Pool(pool) = &config.targets["other-alias"] else {
+        panic!("Expected Pool target spec");
+    };
+    assert!(pool.per_key_concurrency_limit.is_none());
+}
+
 #[test]
 fn test_parse_notify_payload() {
     // Test valid payload
@@ -159,7 +197,7 @@ fn test_parse_notify_payload() {
 
 #[sqlx::test(fixtures(path = "fixtures", scripts("cache_base")))]
 async fn test_cache_shape_regular_public_and_private_access(pool: sqlx::PgPool) {
-    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
 
@@ -195,6 +233,155 @@ async fn test_cache_shape_regular_public_and_private_access(pool: sqlx::PgPool)
     );
 }
 
+#[sqlx::test(fixtures(path = "fixtures", scripts("cache_base")))]
+async fn test_allow_public_grants_access_without_group_membership(pool: sqlx::PgPool) {
+    use crate::db::handlers::{Deployments, Repository};
+    use crate::db::models::deployments::DeploymentUpdateDBRequest;
+
+    let private_id: uuid::Uuid = "40000000-0000-0000-0000-000000000002".parse().unwrap();
+    let public_id: uuid::Uuid = "40000000-0000-0000-0000-000000000001".parse().unwrap();
+    let tiers = RateLimitTiersConfig::default();
+
+    // The flag alone opens the private model to users outside its group
+    let mut conn = pool.acquire().await.unwrap();
+    Deployments::new(&mut conn)
+        .update(private_id, &DeploymentUpdateDBRequest::builder().allow_public(true).build())
+        .await
+        .unwrap();
+    let targets = super::load_targets_from_db(&pool, &[], false, &tiers, None, None).await.unwrap();
+    let private_pool = targets.targets.get("regular-private").unwrap();
+    assert!(
+        pool_has_key(private_pool.value(), KEY_B_SECRET),
+        "non-member should reach a public model"
+    );
+    assert!(pool_has_key(private_pool.value(), KEY_BATCH_SECRET));
+
+    // Turning it off restores group-only access
+    Deployments::new(&mut conn)
+        .update(private_id, &DeploymentUpdateDBRequest::builder().allow_public(false).build())
+        .await
+        .unwrap();
+    let targets = super::load_targets_from_db(&pool, &[], false, &tiers, None, None).await.unwrap();
+    let private_pool = targets.targets.get("regular-private").unwrap();
+    assert!(!pool_has_key(private_pool.value(), KEY_B_SECRET), "non-member should lose access");
+    assert!(pool_has_key(private_pool.value(), KEY_A_SECRET), "group member keeps access");
+
+    // Turning it off on a model made public through the everyone group also withdraws that grant
+    Deployments::new(&mut conn)
+        .update(public_id, &DeploymentUpdateDBRequest::builder().allow_public(false).build())
+        .await
+        .unwrap();
+    let targets = super::load_targets_from_db(&pool, &[], false, &tiers, None, None).await.unwrap();
+    let public_pool = targets.targets.get("regular-public").unwrap();
+    assert!(!pool_has_key(public_pool.value(), KEY_A_SECRET));
+    assert!(!pool_has_key(public_pool.value(), KEY_B_SECRET));
+    assert!(pool_has_key(public_pool.value(), SYSTEM_KEY_SECRET));
+}
+
+#[sqlx::test(fixtures(path = "fixtures", scripts("cache_base")))]
+async fn test_require_approval_limits_access_to_approved_users(pool: sqlx::PgPool) {
+    use crate::db::handlers::{AccessRequests, Deployments, Repository};
+    use crate::db::models::access_requests::AccessRequestStatus;
+    use crate::db::models::deployments::DeploymentUpdateDBRequest;
+
+    let private_id: uuid::Uuid = "40000000-0000-0000-0000-000000000002".parse().unwrap();
+    let user_a: uuid::Uuid = "00000000-0000-0000-0000-0000000000a1".parse().unwrap();
+    let tiers = RateLimitTiersConfig::default();
+
+    // Group membership no longer suffices once the model requires approval
+    let mut conn = pool.acquire().await.unwrap();
+    Deployments::new(&mut conn)
+        .update(private_id, &DeploymentUpdateDBRequest::builder().require_approval(true).build())
+        .await
+        .unwrap();
+    let escalation = vec!["regular-private".to_string()];
+    let targets = super::load_targets_from_db(&pool, &escalation, false, &tiers, None, None)
+        .await
+        .unwrap();
+    let private_pool = targets.targets.get("regular-private").unwrap();
+    assert!(
+        !pool_has_key(private_pool.value(), KEY_A_SECRET),
+        "unapproved member should lose access"
+    );
+    assert!(pool_has_key(private_pool.value(), SYSTEM_KEY_SECRET));
+    assert!(pool_has_key(private_pool.value(), KEY_BATCH_SECRET), "batch escalation isn't gated");
+
+    // A pending request changes nothing; an approved one restores access
+    let request = AccessRequests::new(&mut conn).request(private_id, user_a, None).await.unwrap();
+    let targets = super::load_targets_from_db(&pool, &[], false, &tiers, None, None).await.unwrap();
+    assert!(!pool_has_key(targets.targets.get("regular-private").unwrap().value(), KEY_A_SECRET));
+
+    AccessRequests::new(&mut conn)
+        .review(request.id, AccessRequestStatus::Approved, user_a)
+        .await
+        .unwrap();
+    let targets = super::load_targets_from_db(&pool, &[], false, &tiers, None, None).await.unwrap();
+    let private_pool = targets.targets.get("regular-private").unwrap();
+    assert!(
+        pool_has_key(private_pool.value(), KEY_A_SECRET),
+        "approved member should regain access"
+    );
+    assert!(
+        !pool_has_key(private_pool.value(), KEY_B_SECRET),
+        "approval doesn't bypass group membership"
+    );
+}
+
+#[sqlx::test(fixtures(path = "fixtures", scripts("cache_base")))]
+async fn test_key_model_denylist_overrides_group_access(pool: sqlx::PgPool) {
+    use crate::db::handlers::{Repository, api_keys::ApiKeys};
+    use crate::db::models::api_keys::ApiKeyUpdateDBRequest;
+
+    let private_id: uuid::Uuid = "40000000-0000-0000-0000-000000000002".parse().unwrap();
+    let public_id: uuid::Uuid = "40000000-0000-0000-0000-000000000001".parse().unwrap();
+    let tiers = RateLimitTiersConfig::default();
+    let key_a_id: uuid::Uuid = sqlx::query_scalar("SELECT id FROM api_keys WHERE secret = $1")
+        .bind(KEY_A_SECRET)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let restrict = |allowed: Option<Vec<uuid::Uuid>>, denied: Option<Vec<uuid::Uuid>>| ApiKeyUpdateDBRequest {
+        name: None,
+        description: None,
+        requests_per_second: None,
+        burst_size: None,
+        allowed_model_ids: Some(allowed),
+        denied_model_ids: Some(denied),
+        max_priority: None,
+    };
+
+    // Key A reaches regular-private through its owner's group; deny it on the key
+    let mut conn = pool.acquire().await.unwrap();
+    let key = ApiKeys::new(&mut conn)
+        .update(key_a_id, &restrict(None, Some(vec![private_id])))
+        .await
+        .unwrap();
+    assert!(!key.model_access.contains(&private_id), "model_access reflects the denylist");
+
+    let targets = super::load_targets_from_db(&pool, &[], false, &tiers, None, None).await.unwrap();
+    let private_pool = targets.targets.get("regular-private").unwrap();
+    assert!(
+        !pool_has_key(private_pool.value(), KEY_A_SECRET),
+        "denied model is withdrawn from the key"
+    );
+    assert!(pool_has_key(private_pool.value(), SYSTEM_KEY_SECRET));
+    let public_pool = targets.targets.get("regular-public").unwrap();
+    assert!(pool_has_key(public_pool.value(), KEY_A_SECRET), "other models are unaffected");
+
+    // An allowlist narrows the key to the listed models only
+    ApiKeys::new(&mut conn)
+        .update(key_a_id, &restrict(Some(vec![private_id]), None))
+        .await
+        .unwrap();
+    let targets = super::load_targets_from_db(&pool, &[], false, &tiers, None, None).await.unwrap();
+    assert!(pool_has_key(targets.targets.get("regular-private").unwrap().value(), KEY_A_SECRET));
+    assert!(!pool_has_key(targets.targets.get("regular-public").unwrap().value(), KEY_A_SECRET));
+
+    // Clearing both restores plain group access
+    let key = ApiKeys::new(&mut conn).update(key_a_id, &restrict(None, None)).await.unwrap();
+    assert!(key.model_access.contains(&private_id) && key.model_access.contains(&public_id));
+}
+
 #[sqlx::test(fixtures(path = "fixtures", scripts("cache_base")))]
 async fn test_endpoint_reasoning_default_reaches_standard_provider(pool: sqlx::PgPool) {
     let endpoint_config = serde_json::json!({
@@ -212,7 +399,7 @@ async fn test_endpoint_reasoning_default_reaches_standard_provider(pool: sqlx::P
         .await
         .unwrap();
 
-    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     let target = targets.targets.get("regular-private").unwrap();
@@ -232,6 +419,53 @@ async fn test_endpoint_reasoning_default_reaches_standard_provider(pool: sqlx::P
     );
 }
 
+#[sqlx::test(fixtures(path = "fixtures", scripts("cache_base")))]
+async fn test_deployment_system_prompt_reaches_pool(pool: sqlx::PgPool) {
+    sqlx::query("UPDATE deployed_models SET system_prompt = 'You are a support agent.', system_prompt_mode = 'merge' WHERE alias = 'regular-private'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE deployed_models SET system_prompt = '   ' WHERE alias = 'regular-public'")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
+        .await
+        .unwrap();
+    let system_prompt = targets.targets.get("regular-private").unwrap().system_prompt().cloned();
+    assert_eq!(
+        system_prompt,
+        Some(SystemPromptConfig {
+            content: "You are a support agent.".to_string(),
+            mode: SystemPromptMode::Merge,
+        })
+    );
+    // A blank prompt injects nothing
+    assert!(targets.targets.get("regular-public").unwrap().system_prompt().is_none());
+}
+
+#[sqlx::test(fixtures(path = "fixtures", scripts("cache_base")))]
+async fn test_deployment_max_n_reaches_pool(pool: sqlx::PgPool) {
+    sqlx::query("UPDATE deployed_models SET max_n = 4, max_n_mode = 'clamp' WHERE alias = 'regular-private'")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        targets.targets.get("regular-private").unwrap().n_limit().copied(),
+        Some(NLimitConfig {
+            max: 4,
+            mode: NLimitMode::Clamp,
+        })
+    );
+    // Without max_n, n is not capped
+    assert!(targets.targets.get("regular-public").unwrap().n_limit().is_none());
+}
+
 #[sqlx::test(fixtures(path = "fixtures", scripts("cache_base")))]
 async fn test_chat_override_preserves_endpoint_responses_default(pool: sqlx::PgPool) {
     let endpoint_config = serde_json::json!({
@@ -278,7 +512,7 @@ async fn test_chat_override_preserves_endpoint_responses_default(pool: sqlx::PgP
         .await
         .unwrap();
 
-    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     let target = targets.targets.get("regular-private").unwrap();
@@ -347,7 +581,7 @@ async fn test_disabling_one_reasoning_surface_preserves_the_other(pool: sqlx::Pg
         .await
         .unwrap();
 
-    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     let target = targets.targets.get("regular-private").unwrap();
@@ -394,7 +628,7 @@ async fn test_disabling_both_reasoning_surfaces_removes_provider_config(pool: sq
         .await
         .unwrap();
 
-    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     let target = targets.targets.get("regular-private").unwrap();
@@ -420,7 +654,7 @@ async fn test_token_budget_multi_write_survives_provider_sync(pool: sqlx::PgPool
         .await
         .unwrap();
 
-    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     let target = targets.targets.get("regular-private").unwrap();
@@ -491,7 +725,7 @@ async fn test_composite_components_keep_distinct_effective_reasoning_translation
         .await
         .unwrap();
 
-    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     let target = targets.targets.get("composite-priority").unwrap();
@@ -529,7 +763,7 @@ async fn test_cache_shape_zero_data_retention_label_reflects_owner(pool: sqlx::P
         .await
         .unwrap();
 
-    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
 
@@ -549,9 +783,30 @@ async fn test_cache_shape_zero_data_retention_label_reflects_owner(pool: sqlx::P
     );
 }
 
+#[sqlx::test(fixtures(path = "fixtures", scripts("cache_base")))]
+async fn test_cache_shape_max_priority_label(pool: sqlx::PgPool) {
+    // Keys allowed a raised priority carry it as a "max_priority" label, which
+    // onwards checks the X-Priority header against. Default keys carry none.
+    sqlx::query("UPDATE api_keys SET max_priority = 4 WHERE secret = $1")
+        .bind(KEY_A_SECRET)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
+        .await
+        .unwrap();
+
+    let key_a_labels = targets.key_labels.get(KEY_A_SECRET).expect("user A's key should carry labels");
+    assert_eq!(key_a_labels.get(onwards::priority::MAX_PRIORITY_LABEL), Some(&"4".to_string()));
+
+    let key_b_labels = targets.key_labels.get(KEY_B_SECRET).expect("user B's key should carry labels");
+    assert_eq!(key_b_labels.get(onwards::priority::MAX_PRIORITY_LABEL), None);
+}
+
 #[sqlx::test(fixtures(path = "fixtures", scripts("cache_base", "cache_tariff_metered", "cache_balance_user_a_positive")))]
 async fn test_cache_shape_metered_model_requires_positive_balance(pool: sqlx::PgPool) {
-    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     let metered = targets.targets.get("metered-public").expect("metered-public should exist");
@@ -574,7 +829,7 @@ async fn test_balance_change_toggles_paid_access_on_reload(pool: sqlx::PgPool) {
     let tiers = RateLimitTiersConfig::default();
 
     // Baseline: user A has positive balance, so their key is in the metered pool.
-    let targets = super::load_targets_from_db(&pool, &[], false, &tiers).await.unwrap();
+    let targets = super::load_targets_from_db(&pool, &[], false, &tiers, None, None).await.unwrap();
     assert!(pool_has_key(targets.targets.get("metered-public").unwrap().value(), KEY_A_SECRET));
 
     // Deplete user A in the read model, as a usage fold would; the next
@@ -585,7 +840,7 @@ async fn test_balance_change_toggles_paid_access_on_reload(pool: sqlx::PgPool) {
         .await
         .unwrap();
 
-    let targets = super::load_targets_from_db(&pool, &[], false, &tiers).await.unwrap();
+    let targets = super::load_targets_from_db(&pool, &[], false, &tiers, None, None).await.unwrap();
     assert!(
         !pool_has_key(targets.targets.get("metered-public").unwrap().value(), KEY_A_SECRET),
         "depleted user must lose paid-model access"
@@ -606,7 +861,7 @@ async fn test_balance_change_toggles_paid_access_on_reload(pool: sqlx::PgPool) {
         .await
         .unwrap();
 
-    let targets = super::load_targets_from_db(&pool, &[], false, &tiers).await.unwrap();
+    let targets = super::load_targets_from_db(&pool, &[], false, &tiers, None, None).await.unwrap();
     assert!(
         pool_has_key(targets.targets.get("metered-public").unwrap().value(), KEY_A_SECRET),
         "restored user regains paid-model access"
@@ -642,7 +897,7 @@ async fn test_spend_cap_toggles_scope_access_on_reload(pool: sqlx::PgPool) {
     };
 
     // Under the cap: both scope keys are eligible for the paid pool.
-    let targets = super::load_targets_from_db(&pool, &[], false, &tiers).await.unwrap();
+    let targets = super::load_targets_from_db(&pool, &[], false, &tiers, None, None).await.unwrap();
     let metered = targets.targets.get("metered-public").unwrap();
     assert!(pool_has_key(metered.value(), KEY_A_SECRET));
     assert!(pool_has_key(metered.value(), &child_secret), "child shares the scope's eligibility");
@@ -653,7 +908,7 @@ async fn test_spend_cap_toggles_scope_access_on_reload(pool: sqlx::PgPool) {
         .execute(&pool)
         .await
         .unwrap();
-    let targets = super::load_targets_from_db(&pool, &[], false, &tiers).await.unwrap();
+    let targets = super::load_targets_from_db(&pool, &[], false, &tiers, None, None).await.unwrap();
     let metered = targets.targets.get("metered-public").unwrap();
     assert!(!pool_has_key(metered.value(), KEY_A_SECRET), "exhausted scope loses the paid pool");
     assert!(!pool_has_key(metered.value(), &child_secret), "the child is yanked with its root");
@@ -668,7 +923,7 @@ async fn test_spend_cap_toggles_scope_access_on_reload(pool: sqlx::PgPool) {
         .execute(&pool)
         .await
         .unwrap();
-    let targets = super::load_targets_from_db(&pool, &[], false, &tiers).await.unwrap();
+    let targets = super::load_targets_from_db(&pool, &[], false, &tiers, None, None).await.unwrap();
     assert!(
         !pool_has_key(targets.targets.get("metered-public").unwrap().value(), KEY_A_SECRET),
         "one-off cap must not self-heal"
@@ -681,7 +936,7 @@ async fn test_spend_cap_toggles_scope_access_on_reload(pool: sqlx::PgPool) {
         .execute(&pool)
         .await
         .unwrap();
-    let targets = super::load_targets_from_db(&pool, &[], false, &tiers).await.unwrap();
+    let targets = super::load_targets_from_db(&pool, &[], false, &tiers, None, None).await.unwrap();
     let metered = targets.targets.get("metered-public").unwrap();
     assert!(pool_has_key(metered.value(), KEY_A_SECRET), "rolled window readmits the root");
     assert!(pool_has_key(metered.value(), &child_secret), "rolled window readmits the child");
@@ -691,7 +946,7 @@ async fn test_spend_cap_toggles_scope_access_on_reload(pool: sqlx::PgPool) {
 async fn test_cache_shape_batch_escalation_access_for_private_alias(pool: sqlx::PgPool) {
     let alias = "escalation-private".to_string();
 
-    let without_escalation = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let without_escalation = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     let pool_without = without_escalation.targets.get(&alias).expect("target should exist");
@@ -703,9 +958,16 @@ async fn test_cache_shape_batch_escalation_access_for_private_alias(pool: sqlx::
     assert!(pool_has_key(pool_without.value(), SYSTEM_KEY_SECRET));
     assert!(!pool_has_key(pool_without.value(), KEY_BATCH_SECRET));
 
-    let with_escalation = super::load_targets_from_db(&pool, std::slice::from_ref(&alias), false, &RateLimitTiersConfig::default())
-        .await
-        .unwrap();
+    let with_escalation = super::load_targets_from_db(
+        &pool,
+        std::slice::from_ref(&alias),
+        false,
+        &RateLimitTiersConfig::default(),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
     let pool_with = with_escalation.targets.get(&alias).expect("target should exist");
     assert_eq!(pool_keys_len(pool_with.value()), 2, "with escalation batch key should be added");
     assert!(pool_has_key(pool_with.value(), SYSTEM_KEY_SECRET));
@@ -714,7 +976,7 @@ async fn test_cache_shape_batch_escalation_access_for_private_alias(pool: sqlx::
 
 #[sqlx::test(fixtures(path = "fixtures", scripts("cache_base")))]
 async fn test_cache_shape_composite_pool_strategy_and_fallback(pool: sqlx::PgPool) {
-    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     let composite = targets.targets.get("composite-priority").expect("composite-priority should exist");
@@ -754,6 +1016,9 @@ async fn test_cache_shape_composite_pool_strategy_and_fallback(pool: sqlx::PgPoo
     assert_eq!(providers[1].target.onwards_model.as_deref(), Some("component-a-model"));
     assert_eq!(providers[0].weight, 30);
     assert_eq!(providers[1].weight, 70);
+    // Providers are named by component alias so `x-dwctl-route-to` can pin them.
+    assert_eq!(providers[0].target.name.as_deref(), Some("component-b"));
+    assert_eq!(providers[1].target.name.as_deref(), Some("component-a"));
     assert!(providers[0].target.sanitize_response);
     assert!(providers[1].target.sanitize_response);
 
@@ -771,7 +1036,7 @@ async fn test_cache_shape_null_composite_uses_application_fallback_status_defaul
         .await
         .unwrap();
 
-    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     let composite = targets.targets.get("composite-priority").expect("composite-priority should exist");
@@ -802,7 +1067,7 @@ async fn test_cache_shape_composite_backoff_round_trips(pool: sqlx::PgPool) {
     .await
     .unwrap();
 
-    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     let composite = targets.targets.get("composite-priority").expect("composite-priority should exist");
@@ -820,13 +1085,13 @@ async fn test_cache_shape_composite_backoff_round_trips(pool: sqlx::PgPool) {
 async fn test_cache_shape_composite_batch_escalation_access(pool: sqlx::PgPool) {
     let alias = "composite-priority".to_string();
 
-    let without_escalation = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let without_escalation = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     let pool_without = without_escalation.targets.get(&alias).expect("target should exist");
     assert!(!pool_has_key(pool_without.value(), KEY_BATCH_SECRET));
 
-    let with_escalation = super::load_targets_from_db(&pool, &[alias], false, &RateLimitTiersConfig::default())
+    let with_escalation = super::load_targets_from_db(&pool, &[alias], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     let pool_with = with_escalation.targets.get("composite-priority").expect("target should exist");
@@ -835,7 +1100,7 @@ async fn test_cache_shape_composite_batch_escalation_access(pool: sqlx::PgPool)
 
 #[sqlx::test(fixtures(path = "fixtures", scripts("cache_base", "cache_components_all_disabled")))]
 async fn test_cache_shape_composite_with_all_components_disabled(pool: sqlx::PgPool) {
-    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     let pool_entry = targets
@@ -850,7 +1115,7 @@ async fn test_cache_shape_composite_with_all_components_disabled(pool: sqlx::PgP
 
 #[sqlx::test(fixtures(path = "fixtures", scripts("cache_base", "cache_regular_public_extra_group_assignment")))]
 async fn test_cache_shape_duplicate_access_paths_do_not_duplicate_keys(pool: sqlx::PgPool) {
-    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     let public = targets.targets.get("regular-public").expect("regular-public should exist");
@@ -863,12 +1128,12 @@ async fn test_cache_shape_duplicate_access_paths_do_not_duplicate_keys(pool: sql
 
 #[sqlx::test(fixtures(path = "fixtures", scripts("cache_base")))]
 async fn test_cache_shape_strict_mode_flag_propagates(pool: sqlx::PgPool) {
-    let strict_targets = super::load_targets_from_db(&pool, &[], true, &RateLimitTiersConfig::default())
+    let strict_targets = super::load_targets_from_db(&pool, &[], true, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     assert!(strict_targets.strict_mode, "strict_mode=true should propagate to Targets");
 
-    let lax_targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let lax_targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     assert!(!lax_targets.strict_mode, "strict_mode=false should propagate to Targets");
@@ -876,7 +1141,7 @@ async fn test_cache_shape_strict_mode_flag_propagates(pool: sqlx::PgPool) {
 
 #[sqlx::test(fixtures(path = "fixtures", scripts("cache_base", "cache_user_b_in_private_group")))]
 async fn test_cache_shape_overlapping_group_memberships_expand_access(pool: sqlx::PgPool) {
-    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     let private = targets.targets.get("regular-private").expect("regular-private should exist");
@@ -894,7 +1159,7 @@ async fn test_cache_shape_overlapping_group_memberships_expand_access(pool: sqlx
 
 #[sqlx::test(fixtures(path = "fixtures", scripts("cache_base", "cache_delete_regular_public")))]
 async fn test_cache_shape_deleted_regular_model_is_excluded(pool: sqlx::PgPool) {
-    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     assert!(
@@ -905,7 +1170,7 @@ async fn test_cache_shape_deleted_regular_model_is_excluded(pool: sqlx::PgPool)
 
 #[sqlx::test(fixtures(path = "fixtures", scripts("cache_base", "cache_delete_component_a_model")))]
 async fn test_cache_shape_deleted_component_model_is_excluded_from_composite(pool: sqlx::PgPool) {
-    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     let composite = targets.targets.get("composite-priority").expect("composite-priority should exist");
@@ -918,9 +1183,61 @@ async fn test_cache_shape_deleted_component_model_is_excluded_from_composite(poo
     assert_eq!(providers[0].target.onwards_model.as_deref(), Some("component-b-model"));
 }
 
+/// The (input, output) pricing headers `pool` returns to a key with `purpose`
+fn pricing_headers_for(pool: &ProviderPool, purpose: &str) -> Option<(String, String)> {
+    let labels = std::collections::HashMap::from([("purpose".to_string(), purpose.to_string())]);
+    let headers = pool.labeled_response_headers(&labels)?;
+    Some((headers["Input-Price-Per-Token"].clone(), headers["Output-Price-Per-Token"].clone()))
+}
+
+#[sqlx::test(fixtures(path = "fixtures", scripts("cache_base", "cache_tariff_metered")))]
+async fn test_pricing_headers_follow_key_purpose_tariffs(pool: sqlx::PgPool) {
+    // The fixture gives metered-public a default tariff (no purpose). Add a playground
+    // tariff, and a batch tariff that only applies to batches with a 24h window.
+    sqlx::query(
+        "INSERT INTO model_tariffs (deployed_model_id, name, input_price_per_token, output_price_per_token, api_key_purpose, completion_window)
+         VALUES ('40000000-0000-0000-0000-000000000003', 'playground', 0.0000005, 0.000001, 'playground', NULL),
+                ('40000000-0000-0000-0000-000000000003', 'batch-24h', 0.0000001, 0.0000002, 'batch', '24h')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
+        .await
+        .unwrap();
+    let metered = targets.targets.get("metered-public").unwrap();
+    let prices = |input: &str, output: &str| Some((input.to_string(), output.to_string()));
+    assert_eq!(pricing_headers_for(metered.value(), "playground"), prices("0.0000005", "0.000001"));
+    // Without a realtime tariff, realtime and non-batch batch-key requests pay the default
+    assert_eq!(pricing_headers_for(metered.value(), "realtime"), prices("0.000001", "0.000002"));
+    assert_eq!(pricing_headers_for(metered.value(), "batch"), prices("0.000001", "0.000002"));
+
+    // A realtime tariff takes over from the default for purposes without their own
+    sqlx::query(
+        "INSERT INTO model_tariffs (deployed_model_id, name, input_price_per_token, output_price_per_token, api_key_purpose)
+         VALUES ('40000000-0000-0000-0000-000000000003', 'realtime', 0.000003, 0.000006, 'realtime')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
+        .await
+        .unwrap();
+    let metered = targets.targets.get("metered-public").unwrap();
+    assert_eq!(pricing_headers_for(metered.value(), "realtime"), prices("0.000003", "0.000006"));
+    assert_eq!(pricing_headers_for(metered.value(), "batch"), prices("0.000003", "0.000006"));
+    assert_eq!(pricing_headers_for(metered.value(), "playground"), prices("0.0000005", "0.000001"));
+
+    // Free models report no prices
+    let public = targets.targets.get("regular-public").unwrap();
+    assert_eq!(pricing_headers_for(public.value(), "realtime"), None);
+}
+
 #[sqlx::test(fixtures(path = "fixtures", scripts("cache_base", "cache_traffic_routing_rules")))]
 async fn test_cache_shape_regular_model_routing_rules(pool: sqlx::PgPool) {
-    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     let regular_private = targets.targets.get("regular-private").expect("regular-private should exist");
@@ -940,7 +1257,7 @@ async fn test_cache_shape_regular_model_routing_rules(pool: sqlx::PgPool) {
 
 #[sqlx::test(fixtures(path = "fixtures", scripts("cache_base", "cache_traffic_routing_rules")))]
 async fn test_cache_shape_composite_model_routing_rules(pool: sqlx::PgPool) {
-    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     let composite = targets.targets.get("composite-priority").expect("composite-priority should exist");
@@ -957,6 +1274,101 @@ async fn test_cache_shape_composite_model_routing_rules(pool: sqlx::PgPool) {
     assert_eq!(rules[1].match_labels.get("purpose"), Some(&"realtime".to_string()));
     assert!(matches!(rules[1].action, RoutingAction::Deny));
 }
+
+/// Endpoint regions label the composite's providers, and a region preference
+/// (from a prefer_region rule or the client hint header) steers selection to
+/// the matching component ahead of the priority order.
+#[sqlx::test(fixtures(path = "fixtures", scripts("cache_base")))]
+async fn test_cache_shape_composite_region_preference(pool: sqlx::PgPool) {
+    for (endpoint_id, region) in [
+        ("30000000-0000-0000-0000-000000000001", "eu-west"),
+        ("30000000-0000-0000-0000-000000000002", "us-east"),
+    ] {
+        sqlx::query("UPDATE inference_endpoints SET region = $1 WHERE id = $2::uuid")
+            .bind(region)
+            .bind(endpoint_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    let composite_id = uuid::Uuid::parse_str("50000000-0000-0000-0000-000000000001").unwrap();
+    let mut conn = pool.acquire().await.unwrap();
+    crate::db::handlers::Deployments::new(&mut conn)
+        .set_traffic_rules(
+            composite_id,
+            &[(
+                crate::db::models::api_keys::ApiKeyPurpose::Realtime,
+                crate::db::models::deployments::TrafficRuleAction::PreferRegion("eu-west".to_string()),
+            )],
+        )
+        .await
+        .unwrap();
+
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
+        .await
+        .unwrap();
+    let composite = targets.targets.get("composite-priority").expect("composite-priority should exist");
+    let composite_pool = composite.value();
+
+    let providers = composite_pool.providers();
+    assert_eq!(providers[0].target.name.as_deref(), Some("component-b"));
+    assert_eq!(providers[0].target.region.as_deref(), Some("us-east"));
+    assert_eq!(providers[1].target.name.as_deref(), Some("component-a"));
+    assert_eq!(providers[1].target.region.as_deref(), Some("eu-west"));
+
+    let rules = composite_pool.routing_rules();
+    assert_eq!(rules.len(), 1);
+    match &rules[0].action {
+        RoutingAction::PreferRegion { region } => assert_eq!(region, "eu-west"),
+        _ => panic!("expected prefer_region rule for realtime"),
+    }
+
+    // Priority order serves component-b first; preferring eu-west serves component-a
+    let (_, target, _) = composite_pool.select().unwrap();
+    assert_eq!(target.name.as_deref(), Some("component-b"));
+    let (_, target, _) = composite_pool.preferring_region("eu-west").select().unwrap();
+    assert_eq!(target.name.as_deref(), Some("component-a"));
+}
+
+/// An endpoint's max_concurrency gives every provider on that endpoint the same
+/// limit, keyed by endpoint, so onwards counts their requests together.
+#[sqlx::test(fixtures(path = "fixtures", scripts("cache_base")))]
+async fn test_endpoint_max_concurrency_is_shared_by_its_deployments(pool: sqlx::PgPool) {
+    sqlx::query("UPDATE inference_endpoints SET max_concurrency = 4 WHERE id = '30000000-0000-0000-0000-000000000001'")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
+        .await
+        .unwrap();
+    let limit_for = |alias: &str| {
+        targets.targets.get(alias).unwrap().value().providers()[0]
+            .target
+            .endpoint_concurrency_limit
+            .clone()
+    };
+
+    let expected = Some(EndpointConcurrencyLimit {
+        endpoint: "30000000-0000-0000-0000-000000000001".to_string(),
+        max_concurrent_requests: 4,
+    });
+    assert_eq!(limit_for("regular-public"), expected);
+    assert_eq!(limit_for("metered-public"), expected);
+    // Deployments on other endpoints are unaffected
+    assert_eq!(limit_for("regular-private"), None);
+
+    // Composite components carry their own endpoint's limit
+    let composite = targets.targets.get("composite-priority").unwrap();
+    for provider in composite.value().providers() {
+        let expected = match provider.target.name.as_deref() {
+            Some("component-a") => expected.clone(),
+            _ => None,
+        };
+        assert_eq!(provider.target.endpoint_concurrency_limit, expected);
+    }
+}
+
 #[sqlx::test(fixtures(path = "fixtures", scripts("cache_base", "cache_component_b_invalid_endpoint")))]
 #[ignore = "Known limitation: invalid component endpoint cannot be isolated because regular target loading panics on invalid endpoint URLs"]
 async fn test_known_issue_composite_invalid_component_endpoint_should_be_skipped(pool: sqlx::PgPool) {
@@ -970,7 +1382,7 @@ async fn test_known_issue_composite_invalid_component_endpoint_should_be_skipped
     // - Regular-model path uses Url::parse(...).expect(...), which panics on invalid DB URL.
     // - Because endpoints are shared across deployments in this fixture, regular loading panics
     //   before we can assert composite skip behavior.
-    let _ = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let _ = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
 }
@@ -980,7 +1392,7 @@ async fn test_composite_unmetered_access_matches_regular_model_policy(pool: sqlx
     // For unmetered aliases (no active non-zero tariff), group-authorized keys are allowed
     // even when user balance is non-positive. Composite and regular aliases follow the same
     // key visibility policy.
-    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default())
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
     let composite = targets.targets.get("composite-priority").expect("composite-priority should exist");
@@ -1011,7 +1423,7 @@ async fn test_unverified_key_gets_tier_limiter_verified_key_does_not(pool: sqlx:
         }),
     };
 
-    let targets = super::load_targets_from_db(&pool, &[], false, &tiers).await.unwrap();
+    let targets = super::load_targets_from_db(&pool, &[], false, &tiers, None, None).await.unwrap();
 
     // The unverified user's key has a limiter, and it enforces burst = 3:
     // three immediate checks pass, the fourth is throttled. All four run
@@ -1034,7 +1446,7 @@ async fn test_unverified_key_gets_tier_limiter_verified_key_does_not(pool: sqlx:
         .await
         .unwrap();
 
-    let targets = super::load_targets_from_db(&pool, &[], false, &tiers).await.unwrap();
+    let targets = super::load_targets_from_db(&pool, &[], false, &tiers, None, None).await.unwrap();
     assert!(
         targets.key_rate_limiters.get(KEY_A_SECRET).is_none(),
         "verified user with an unset verified tier should have no limiter"
@@ -1057,7 +1469,7 @@ async fn test_system_key_is_immune_to_rate_limit_tiers(pool: sqlx::PgPool) {
         unverified: Some(restrictive),
     };
 
-    let targets = super::load_targets_from_db(&pool, &[], false, &tiers).await.unwrap();
+    let targets = super::load_targets_from_db(&pool, &[], false, &tiers, None, None).await.unwrap();
 
     assert!(
         targets.key_rate_limiters.get(SYSTEM_KEY_SECRET).is_none(),
@@ -1065,6 +1477,60 @@ async fn test_system_key_is_immune_to_rate_limit_tiers(pool: sqlx::PgPool) {
     );
 }
 
+/// A user override outranks the default of the user's group, so an admin can
+/// give one member higher limits than the group grants.
+#[sqlx::test(fixtures(path = "fixtures", scripts("cache_base")))]
+async fn test_user_override_raises_limits_above_group_default(pool: sqlx::PgPool) {
+    // User A (KEY_A_SECRET, no per-key limit) is the only member of cache-private-a
+    sqlx::query(
+        "INSERT INTO group_rate_limits (group_id, requests_per_second, burst_size, concurrency_limit)
+         VALUES ('00000000-0000-0000-0000-000000000aa1', 1, 2, 2)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        targets.key_concurrency_limiters.get(KEY_A_SECRET).unwrap().limit(),
+        Some(2),
+        "group member gets the group's concurrency default"
+    );
+    {
+        let limiter = targets.key_rate_limiters.get(KEY_A_SECRET).unwrap();
+        assert!(limiter.check().is_ok());
+        assert!(limiter.check().is_ok());
+        assert!(limiter.check().is_err(), "3rd request exceeds the group's burst of 2");
+    }
+    // Keys of users outside the group are unaffected
+    assert!(targets.key_rate_limiters.get(KEY_B_SECRET).is_none());
+    assert!(targets.key_concurrency_limiters.get(KEY_B_SECRET).is_none());
+
+    sqlx::query(
+        "INSERT INTO user_rate_limits (user_id, requests_per_second, burst_size, concurrency_limit)
+         VALUES ('00000000-0000-0000-0000-0000000000a1', 100, 5, 10)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        targets.key_concurrency_limiters.get(KEY_A_SECRET).unwrap().limit(),
+        Some(10),
+        "user override raises concurrency above the group default"
+    );
+    let limiter = targets.key_rate_limiters.get(KEY_A_SECRET).unwrap();
+    for i in 1..=5 {
+        assert!(limiter.check().is_ok(), "request {i} is within the override's burst of 5");
+    }
+    assert!(limiter.check().is_err(), "6th request exceeds the override's burst of 5");
+}
+
 /// Endpoint defaults are consumed directly by the Onwards provider cache, so
 /// changing one must wake the listener even when no deployment row changes.
 #[sqlx::test(fixtures(path = "fixtures", scripts("cache_base")))]
@@ -1134,6 +1600,15 @@ async fn test_onwards_config_reloads_on_tariff_change(pool: sqlx::PgPool) {
             auth_header_name: Some("Authorization".to_string()),
             auth_header_prefix: Some("Bearer ".to_string()),
             reasoning_translation: None,
+            bedrock: None,
+            region: None,
+            body_transform: None,
+            auto_sync_interval_seconds: None,
+            alias_template: None,
+            max_concurrency: None,
+            tls: None,
+            additional_api_keys: Vec::new(),
+            api_key_rotation: None,
         })
         .await
         .unwrap();
@@ -1156,6 +1631,10 @@ async fn test_onwards_config_reloads_on_tariff_change(pool: sqlx::PgPool) {
             burst_size: None,
             capacity: None,
             batch_capacity: None,
+            per_key_capacity: None,
+            max_cost_per_request: None,
+            input_modalities: None,
+            output_modalities: None,
             throughput: None,
             provider_pricing: None,
             // Composite model fields (regular model = not composite)
@@ -1174,6 +1653,19 @@ async fn test_onwards_config_reloads_on_tariff_change(pool: sqlx::PgPool) {
             backoff_max_total_ms: None,
             sanitize_responses: true,
             trusted: false,
+            allow_public: false,
+            require_approval: false,
+            rewrite_response_model: false,
+            disable_logging: false,
+            warmup: false,
+            system_prompt: None,
+            system_prompt_mode: Default::default(),
+            max_n: None,
+            max_n_mode: Default::default(),
+            credit_exhaustion_mode: Default::default(),
+            max_overdraft: None,
+            max_prompt_length: None,
+            max_prompt_length_unit: Default::default(),
             open_responses_adapter: true,
             reasoning_translation_overrides: None,
             allowed_batch_completion_windows: None,
@@ -1357,6 +1849,15 @@ async fn test_batch_api_key_access_to_composite_escalation_target(pool: sqlx::Pg
             auth_header_name: Some("Authorization".to_string()),
             auth_header_prefix: Some("Bearer ".to_string()),
             reasoning_translation: None,
+            bedrock: None,
+            region: None,
+            body_transform: None,
+            auto_sync_interval_seconds: None,
+            alias_template: None,
+            max_concurrency: None,
+            tls: None,
+            additional_api_keys: Vec::new(),
+            api_key_rotation: None,
         })
         .await
         .unwrap();
@@ -1379,6 +1880,10 @@ async fn test_batch_api_key_access_to_composite_escalation_target(pool: sqlx::Pg
             burst_size: None,
             capacity: None,
             batch_capacity: None,
+            per_key_capacity: None,
+            max_cost_per_request: None,
+            input_modalities: None,
+            output_modalities: None,
             throughput: None,
             provider_pricing: None,
             is_composite: false,
@@ -1398,6 +1903,19 @@ async fn test_batch_api_key_access_to_composite_escalation_target(pool: sqlx::Pg
             metadata: None,
             sanitize_responses: true,
             trusted: false,
+            allow_public: false,
+            require_approval: false,
+            rewrite_response_model: false,
+            disable_logging: false,
+            warmup: false,
+            system_prompt: None,
+            system_prompt_mode: Default::default(),
+            max_n: None,
+            max_n_mode: Default::default(),
+            credit_exhaustion_mode: Default::default(),
+            max_overdraft: None,
+            max_prompt_length: None,
+            max_prompt_length_unit: Default::default(),
             open_responses_adapter: true,
             reasoning_translation_overrides: None,
         })
@@ -1423,6 +1941,10 @@ async fn test_batch_api_key_access_to_composite_escalation_target(pool: sqlx::Pg
             burst_size: None,
             capacity: None,
             batch_capacity: None,
+            per_key_capacity: None,
+            max_cost_per_request: None,
+            input_modalities: None,
+            output_modalities: None,
             throughput: None,
             provider_pricing: None,
             is_composite: true,
@@ -1442,6 +1964,19 @@ async fn test_batch_api_key_access_to_composite_escalation_target(pool: sqlx::Pg
             metadata: None,
             sanitize_responses: true,
             trusted: false,
+            allow_public: false,
+            require_approval: false,
+            rewrite_response_model: false,
+            disable_logging: false,
+            warmup: false,
+            system_prompt: None,
+            system_prompt_mode: Default::default(),
+            max_n: None,
+            max_n_mode: Default::default(),
+            credit_exhaustion_mode: Default::default(),
+            max_overdraft: None,
+            max_prompt_length: None,
+            max_prompt_length_unit: Default::default(),
             open_responses_adapter: true,
             reasoning_translation_overrides: None,
         })
@@ -1476,6 +2011,9 @@ async fn test_batch_api_key_access_to_composite_escalation_target(pool: sqlx::Pg
             created_by: test_user.id,
             spend_limit: None,
             spend_limit_interval: None,
+            allowed_model_ids: None,
+            denied_model_ids: None,
+            max_priority: 0,
         })
         .await
         .unwrap();
@@ -1483,7 +2021,7 @@ async fn test_batch_api_key_access_to_composite_escalation_target(pool: sqlx::Pg
 
     // Load targets with composite alias in escalation_models
     let escalation_models = vec![composite_alias.clone()];
-    let targets = super::load_targets_from_db(&pool, &escalation_models, false, &RateLimitTiersConfig::default())
+    let targets = super::load_targets_from_db(&pool, &escalation_models, false, &RateLimitTiersConfig::default(), None, None)
         .await
         .unwrap();
 
@@ -1516,6 +2054,7 @@ async fn test_onwards_config_reconnects_after_connection_loss(pool: sqlx::PgPool
     let config = SyncConfig {
         status_tx: Some(status_tx),
         fallback_interval_milliseconds: 10000,
+        ..Default::default()
     };
     let shutdown_token = CancellationToken::new();
     let mut sync_handle = tokio::spawn({
@@ -1568,7 +2107,10 @@ async fn test_onwards_config_reconnects_after_connection_loss(pool: sqlx::PgPool
 
     println!("Waiting for Reconnecting status...");
     let status = status_rx.recv().await;
-    assert_eq!(status, Some(super::SyncStatus::Reconnecting), "Should receive Reconnecting");
+    assert!(
+        matches!(status, Some(super::SyncStatus::Reconnecting { .. })),
+        "Should receive Reconnecting, got {status:?}"
+    );
 
     // Wait up to 7 seconds for successful reconnection (5s delay + 2s buffer)
     let reconnected = timeout(Duration::from_secs(7), async {
@@ -1610,6 +2152,7 @@ async fn test_fallback_sync_triggers_without_notifications(pool: sqlx::PgPool) {
     let config = SyncConfig {
         status_tx: Some(status_tx),
         fallback_interval_milliseconds: 20,
+        ..Default::default()
     };
 
     let shutdown_token = CancellationToken::new();
@@ -1650,6 +2193,86 @@ async fn test_fallback_sync_triggers_without_notifications(pool: sqlx::PgPool) {
     let _ = timeout(Duration::from_secs(1), sync_handle).await;
 }
 
+/// Listener reconnects back off exponentially while the database is unreachable,
+/// reporting each delay in the `Reconnecting` status.
+#[sqlx::test]
+#[test_log::test]
+async fn test_listener_reconnect_backs_off_while_db_unreachable(pool: sqlx::PgPool) {
+    let (mut sync, _initial_targets, _stream) = super::OnwardsConfigSync::new(pool.clone())
+        .await
+        .expect("Failed to create OnwardsConfigSync");
+
+    // Point the listener at a port nothing listens on, so every connect fails
+    let unreachable = pool.connect_options().as_ref().clone().port(1);
+    sync.db = sqlx::postgres::PgPoolOptions::new()
+        .acquire_timeout(Duration::from_millis(200))
+        .connect_lazy_with(unreachable);
+
+    let (status_tx, mut status_rx) = mpsc::channel(10);
+    let config = SyncConfig {
+        status_tx: Some(status_tx),
+        reconnect_initial_delay_milliseconds: 10,
+        reconnect_max_delay_milliseconds: 10_000,
+        ..Default::default()
+    };
+    let shutdown_token = CancellationToken::new();
+    let sync_handle = tokio::spawn({
+        let shutdown = shutdown_token.clone();
+        async move { sync.start(config, shutdown).await }
+    });
+
+    let mut delays = Vec::new();
+    while delays.len() < 4 {
+        let status = timeout(Duration::from_secs(5), status_rx.recv())
+            .await
+            .expect("Timeout waiting for sync status");
+        match status {
+            Some(super::SyncStatus::Connecting) => {}
+            Some(super::SyncStatus::Reconnecting { delay }) => delays.push(delay),
+            other => panic!("Unexpected status while the database is unreachable: {other:?}"),
+        }
+    }
+
+    // 10ms doubling with at most 20% jitter: each delay exceeds the last
+    assert!(delays[0] >= Duration::from_millis(10), "delays: {delays:?}");
+    for pair in delays.windows(2) {
+        assert!(pair[1] > pair[0], "Backoff should increase across failures: {delays:?}");
+    }
+
+    shutdown_token.cancel();
+    let result = timeout(Duration::from_secs(2), sync_handle).await;
+    assert!(result.is_ok(), "Sync task should stop on shutdown while backing off");
+}
+
+#[test]
+fn test_reconnect_backoff_caps_and_resets() {
+    let config = SyncConfig {
+        reconnect_initial_delay_milliseconds: 100,
+        reconnect_max_delay_milliseconds: 1_000,
+        ..Default::default()
+    };
+    let mut backoff = super::ReconnectBackoff::new(&config);
+
+    let first = backoff.next_delay();
+    assert!(first >= Duration::from_millis(100) && first <= Duration::from_millis(120));
+    for _ in 0..10 {
+        assert!(
+            backoff.next_delay() <= Duration::from_millis(1_000),
+            "Delay must not exceed the max"
+        );
+    }
+    assert_eq!(backoff.next_delay(), Duration::from_millis(1_000));
+
+    // A connection that drops straight away keeps backing off
+    backoff.connected(std::time::Instant::now());
+    assert_eq!(backoff.next_delay(), Duration::from_millis(1_000));
+
+    // One that stayed healthy starts the sequence over
+    backoff.connected(std::time::Instant::now() - super::HEALTHY_CONNECTION);
+    let after_reset = backoff.next_delay();
+    assert!(after_reset >= Duration::from_millis(100) && after_reset <= Duration::from_millis(120));
+}
+
 #[cfg(test)]
 mod resolve_key_rate_limit_tests {
     use super::*;
@@ -1697,6 +2320,69 @@ mod resolve_key_rate_limit_tests {
         assert!(super::super::resolve_key_rate_limit(None, None, true, &t).is_none());
     }
 
+    fn key(requests_per_second: Option<f32>, owner_limits: super::super::OwnerRateLimits) -> super::super::OnwardsApiKey {
+        super::super::OnwardsApiKey {
+            id: uuid::Uuid::new_v4(),
+            secret: "sk-test".to_string(),
+            purpose: "realtime".to_string(),
+            requests_per_second,
+            burst_size: None,
+            user_verified: false,
+            zero_data_retention: false,
+            max_priority: 0,
+            owner_limits,
+        }
+    }
+
+    #[test]
+    fn limit_precedence_is_user_then_key_then_group_then_tier() {
+        let t = tiers(None, Some((1.0, None)));
+        let group = super::super::OwnerRateLimits {
+            group_requests_per_second: Some(5.0),
+            group_concurrency_limit: Some(2),
+            ..Default::default()
+        };
+        let user = super::super::OwnerRateLimits {
+            user_requests_per_second: Some(50.0),
+            user_concurrency_limit: Some(20),
+            ..group
+        };
+
+        // Tier only
+        let (rl, cl) = super::super::resolve_key_limits(&key(None, Default::default()), &t);
+        assert_eq!(rl.unwrap().requests_per_second, NonZeroU32::new(1).unwrap());
+        assert!(cl.is_none());
+
+        // Group default beats the tier
+        let (rl, cl) = super::super::resolve_key_limits(&key(None, group), &t);
+        assert_eq!(rl.unwrap().requests_per_second, NonZeroU32::new(5).unwrap());
+        assert_eq!(cl.unwrap().max_concurrent_requests, 2);
+
+        // The key's own rate beats the group default
+        let (rl, _) = super::super::resolve_key_limits(&key(Some(10.0), group), &t);
+        assert_eq!(rl.unwrap().requests_per_second, NonZeroU32::new(10).unwrap());
+
+        // The user override beats everything
+        let (rl, cl) = super::super::resolve_key_limits(&key(Some(10.0), user), &t);
+        assert_eq!(rl.unwrap().requests_per_second, NonZeroU32::new(50).unwrap());
+        assert_eq!(cl.unwrap().max_concurrent_requests, 20);
+    }
+
+    #[test]
+    fn system_key_ignores_owner_limits() {
+        let t = tiers(Some((1.0, None)), Some((1.0, None)));
+        let mut system = key(
+            None,
+            super::super::OwnerRateLimits {
+                user_requests_per_second: Some(1.0),
+                user_concurrency_limit: Some(1),
+                ..Default::default()
+            },
+        );
+        system.id = uuid::Uuid::nil();
+        assert!(matches!(super::super::resolve_key_limits(&system, &t), (None, None)));
+    }
+
     #[test]
     fn only_one_tier_configured_other_tier_unrestricted() {
         let t = tiers(None, Some((5.0, None)));
@@ -1707,3 +2393,94 @@ mod resolve_key_rate_limit_tests {
         assert_eq!(rl.requests_per_second, NonZeroU32::new(5).unwrap());
     }
 }
+
+/// Resolves every reference to a fixed secret.
+struct FixedResolver(&'static str);
+
+#[async_trait::async_trait]
+impl crate::secrets::SecretResolver for FixedResolver {
+    async fn resolve(&self, _reference: &crate::secrets::SecretReference) -> Result<String, crate::secrets::SecretError> {
+        Ok(self.0.to_string())
+    }
+}
+
+#[sqlx::test(fixtures(path = "fixtures", scripts("cache_base")))]
+async fn test_endpoint_api_key_references_resolve_at_sync(pool: sqlx::PgPool) {
+    sqlx::query(
+        "UPDATE inference_endpoints SET api_key = 'vault:providers/default#api_key' WHERE id = '30000000-0000-0000-0000-000000000001'",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    sqlx::query("UPDATE inference_endpoints SET api_key = 'sk-inline' WHERE id = '30000000-0000-0000-0000-000000000002'")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let secrets = crate::secrets::SecretStore::with_resolver(std::sync::Arc::new(FixedResolver("sk-from-vault")), Duration::from_secs(60));
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, Some(&secrets))
+        .await
+        .unwrap();
+    let public = targets.targets.get("regular-public").unwrap();
+    assert_eq!(public.value().providers()[0].target.onwards_key.as_deref(), Some("sk-from-vault"));
+    let private = targets.targets.get("regular-private").unwrap();
+    assert_eq!(private.value().providers()[0].target.onwards_key.as_deref(), Some("sk-inline"));
+
+    // Without a secret store, inline keys still work but models behind a reference are skipped
+    let targets = super::load_targets_from_db(&pool, &[], false, &RateLimitTiersConfig::default(), None, None)
+        .await
+        .unwrap();
+    assert!(targets.targets.get("regular-public").is_none());
+    let private = targets.targets.get("regular-private").unwrap();
+    assert_eq!(private.value().providers()[0].target.onwards_key.as_deref(), Some("sk-inline"));
+}
+
+/// The drift check notices a live routing table that no longer matches the
+/// database, repairs it, and tells the other replicas to resync.
+#[sqlx::test(fixtures(path = "fixtures", scripts("cache_base")))]
+#[test_log::test]
+async fn test_drift_check_detects_and_repairs_stale_cache(pool: sqlx::PgPool) {
+    use crate::config::ONWARDS_CONFIG_CHANGED_CHANNEL;
+    use sqlx::postgres::PgListener;
+
+    let (sync, live, stream) = super::OnwardsConfigSync::new(pool.clone())
+        .await
+        .expect("Failed to create OnwardsConfigSync");
+    live.receive_updates(stream).await.unwrap();
+    let checker = sync.drift_checker(live.clone());
+    assert!(checker.check().await.unwrap().is_empty(), "a fresh cache should match the database");
+
+    // Stale cache, as after missed notifications: one alias gone, one that
+    // was deleted still routed, and one routed to the wrong provider
+    let (_, public_pool) = live.targets.remove("regular-public").unwrap();
+    live.targets.insert("regular-gone".to_string(), public_pool.clone());
+    live.targets.insert("regular-private".to_string(), public_pool);
+
+    let mut listener = PgListener::connect_with(&pool).await.unwrap();
+    listener.listen(ONWARDS_CONFIG_CHANGED_CHANNEL).await.unwrap();
+
+    let drift = checker.check().await.unwrap();
+    assert_eq!(drift.missing, vec!["regular-public"]);
+    assert_eq!(drift.unexpected, vec!["regular-gone"]);
+    assert_eq!(drift.changed, vec!["regular-private"]);
+    assert!(!drift.key_limits_changed);
+
+    let notification = timeout(Duration::from_secs(5), listener.recv())
+        .await
+        .expect("drift repair should notify the other replicas")
+        .unwrap();
+    assert!(notification.payload().starts_with("drift_repair:"));
+
+    // The resync reaches the live routing table through the proxy's update stream
+    timeout(Duration::from_secs(5), async {
+        while live.targets.contains_key("regular-gone") || !live.targets.contains_key("regular-public") {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("live routing table should be repaired");
+    assert!(
+        checker.check().await.unwrap().is_empty(),
+        "repaired cache should match the database"
+    );
+}
@@ -26,36 +26,52 @@
 //! export OTEL_EXPORTER_OTLP_ENDPOINT="https://otlp-gateway.example.com/otlp"
 //! export OTEL_EXPORTER_OTLP_HEADERS="Authorization=Basic%20<token>"
 //! ```
+//!
+//! Console log format and verbosity come from the `log` config section. `log.filter` takes
+//! `RUST_LOG`-style directives, so one module can be tuned independently:
+//!
+//! ```yaml
+//! log:
+//!   format: json
+//!   filter: "info,dwctl::sync::onwards_config=debug"
+//! ```
+//!
+//! The `RUST_LOG` environment variable, when set, overrides `log.filter`.
 
 use opentelemetry::trace::TracerProvider as _;
 use opentelemetry_otlp::{Protocol, WithExportConfig, WithHttpConfig};
 pub use opentelemetry_sdk::trace::SdkTracerProvider;
 use std::collections::HashMap;
-use tracing::info;
-use tracing_subscriber::EnvFilter;
+use tracing::{Subscriber, info};
 use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
 use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+use crate::config::{LogConfig, LogFormat};
 
 /// Initialize tracing with optional OpenTelemetry support
 ///
 /// This function sets up tracing-subscriber with:
-/// - Console output (fmt layer)
+/// - Console output (fmt layer) in the configured format
 /// - OpenTelemetry OTLP export (only if `enable_otel_export` is true and configured via environment variables)
 ///
 /// Parameters:
 /// - `enable_otel_export`: If true, attempts to configure OTLP export using environment variables
+/// - `log`: Console format and filter directives (`RUST_LOG` overrides the filter when set)
 ///
 /// Returns the tracer provider if OTLP export was successfully enabled. The caller should
 /// store this and call `provider.shutdown()` before application exit to flush pending spans.
-pub fn init_telemetry(enable_otel_export: bool) -> anyhow::Result<Option<SdkTracerProvider>> {
-    let env_filter = EnvFilter::new(std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string()));
+pub fn init_telemetry(enable_otel_export: bool, log: &LogConfig) -> anyhow::Result<Option<SdkTracerProvider>> {
+    let directives = std::env::var("RUST_LOG").unwrap_or_else(|_| log.filter.clone());
+    let env_filter = build_env_filter(&directives)?;
 
     if enable_otel_export {
         let (tracer, provider) = create_otlp_tracer()?;
 
         tracing_subscriber::registry()
             .with(env_filter)
-            .with(tracing_subscriber::fmt::layer().compact())
+            .with(fmt_layer(log.format))
             .with(tracing_opentelemetry::layer().with_tracer(tracer))
             .try_init()?;
 
@@ -65,7 +81,7 @@ pub fn init_telemetry(enable_otel_export: bool) -> anyhow::Result<Option<SdkTrac
         // OTLP export disabled - use only console logging
         tracing_subscriber::registry()
             .with(env_filter)
-            .with(tracing_subscriber::fmt::layer().compact())
+            .with(fmt_layer(log.format))
             .try_init()?;
 
         info!("Telemetry initialized (OTLP export disabled)");
@@ -74,6 +90,29 @@ pub fn init_telemetry(enable_otel_export: bool) -> anyhow::Result<Option<SdkTrac
     Ok(None)
 }
 
+/// Parse `RUST_LOG`-style filter directives, rejecting malformed ones instead of
+/// silently ignoring them (which is what `EnvFilter::new` does).
+pub fn build_env_filter(directives: &str) -> anyhow::Result<EnvFilter> {
+    Ok(EnvFilter::builder().parse(directives)?)
+}
+
+/// Console output layer for the configured format
+fn fmt_layer<S>(format: LogFormat) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    match format {
+        LogFormat::Compact => tracing_subscriber::fmt::layer().compact().boxed(),
+        LogFormat::Pretty => tracing_subscriber::fmt::layer().pretty().boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(false)
+            .boxed(),
+    }
+}
+
 /// Create an OpenTelemetry tracer with OTLP exporter
 ///
 /// This respects standard OpenTelemetry environment variables for configuration.
@@ -151,6 +190,42 @@ fn create_otlp_tracer() -> anyhow::Result<(opentelemetry_sdk::trace::Tracer, Sdk
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Records the target of every event that passes the filter
+    struct CaptureTargets(Arc<Mutex<Vec<String>>>);
+
+    impl<S: Subscriber> Layer<S> for CaptureTargets {
+        fn on_event(&self, event: &tracing::Event<'_>, _ctx: tracing_subscriber::layer::Context<'_, S>) {
+            self.0.lock().unwrap().push(event.metadata().target().to_string());
+        }
+    }
+
+    #[test]
+    fn filter_directive_tunes_one_module_independently() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = tracing_subscriber::registry()
+            .with(build_env_filter("warn,dwctl::sync::onwards_config=debug").unwrap())
+            .with(CaptureTargets(seen.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!(target: "dwctl::sync::onwards_config", "kept: module raised to debug");
+            tracing::trace!(target: "dwctl::sync::onwards_config", "dropped: below debug");
+            tracing::debug!(target: "dwctl::api::handlers", "dropped: default is warn");
+            tracing::warn!(target: "dwctl::api::handlers", "kept: at default level");
+        });
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec!["dwctl::sync::onwards_config".to_string(), "dwctl::api::handlers".to_string()]
+        );
+    }
+
+    #[test]
+    fn invalid_filter_directive_is_rejected() {
+        assert!(build_env_filter("info,dwctl=loud").is_err());
+        assert!(build_env_filter("info,dwctl::sync::onwards_config=debug").is_ok());
+    }
 
     #[test]
     fn otlp_tracer_builds_with_http_client() {
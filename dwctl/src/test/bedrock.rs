@@ -0,0 +1,136 @@
+//! End-to-end tests for Bedrock endpoints.
+//!
+//! A Bedrock endpoint is created through the admin API with AWS credentials,
+//! its models come from `model_filter`, and onwards SigV4-signs requests to a
+//! wiremock upstream standing in for Bedrock's OpenAI-compatible API.
+
+use crate::api::models::users::Role;
+use crate::test::utils::{add_auth_headers, create_test_admin_user, create_test_config, create_test_user};
+use sqlx::PgPool;
+
+const BEDROCK_MODEL_ID: &str = "anthropic.claude-3-haiku-20240307-v1:0";
+
+#[sqlx::test]
+async fn bedrock_requests_are_sigv4_signed(pool: PgPool) {
+    let mock = wiremock::MockServer::start().await;
+    wiremock::Mock::given(wiremock::matchers::method("POST"))
+        .and(wiremock::matchers::path("/openai/v1/chat/completions"))
+        .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "chatcmpl-1", "object": "chat.completion", "created": 1, "model": BEDROCK_MODEL_ID,
+            "choices": [ { "index": 0, "message": { "role": "assistant", "content": "Hello from Bedrock" }, "finish_reason": "stop" } ],
+            "usage": { "prompt_tokens": 10, "completion_tokens": 3, "total_tokens": 13 }
+        })))
+        .mount(&mock)
+        .await;
+
+    let mut config = create_test_config();
+    config.background_services.onwards_sync.enabled = true;
+    let app = crate::Application::new_with_pool(config, Some(pool.clone()), None)
+        .await
+        .expect("app");
+    let (server, bg) = app.into_test_server();
+
+    let admin = create_test_admin_user(&pool, Role::PlatformManager).await;
+    let h = add_auth_headers(&admin);
+
+    let resp = server
+        .post("/admin/api/v1/endpoints")
+        .add_header(&h[0].0, &h[0].1)
+        .add_header(&h[1].0, &h[1].1)
+        .json(&serde_json::json!({
+            "name": "bedrock",
+            "url": format!("{}/openai/v1", mock.uri()),
+            "protocol": "bedrock",
+            "bedrock": { "region": "us-east-1", "access_key_id": "AKIDEXAMPLE", "secret_access_key": "bedrock-secret" },
+            "model_filter": [BEDROCK_MODEL_ID],
+            "alias_mapping": { BEDROCK_MODEL_ID: "claude-haiku" }
+        }))
+        .await;
+    assert_eq!(resp.status_code(), 201, "{}", resp.text());
+    let endpoint: serde_json::Value = resp.json();
+    assert_eq!(endpoint["protocol"], "bedrock");
+    assert_eq!(endpoint["bedrock"]["access_key_id"], "AKIDEXAMPLE");
+    assert!(!resp.text().contains("bedrock-secret"), "secret must not be returned");
+
+    // Model discovery serves the configured Bedrock model IDs
+    let deployment_id: uuid::Uuid = sqlx::query_scalar("SELECT id FROM deployed_models WHERE alias = 'claude-haiku'")
+        .fetch_one(&pool)
+        .await
+        .expect("deployment created from model_filter");
+
+    let group_id = "00000000-0000-0000-0000-000000000000";
+    let assoc = server
+        .post(&format!("/admin/api/v1/groups/{group_id}/models/{deployment_id}"))
+        .add_header(&h[0].0, &h[0].1)
+        .add_header(&h[1].0, &h[1].1)
+        .await;
+    assert!(assoc.status_code().is_success(), "{}", assoc.text());
+
+    let user = create_test_user(&pool, Role::StandardUser).await;
+    let key: serde_json::Value = server
+        .post(&format!("/admin/api/v1/users/{}/api-keys", user.id))
+        .add_header(&h[0].0, &h[0].1)
+        .add_header(&h[1].0, &h[1].1)
+        .json(&serde_json::json!({ "purpose": "realtime", "name": "bedrock e2e key" }))
+        .await
+        .json();
+    let api_key = key["key"].as_str().unwrap().to_string();
+
+    bg.sync_onwards_config(&pool).await.unwrap();
+
+    let resp = server
+        .post("/ai/v1/chat/completions")
+        .add_header("Authorization", &format!("Bearer {api_key}"))
+        .json(&serde_json::json!({
+            "model": "claude-haiku",
+            "messages": [ { "role": "user", "content": "hi" } ]
+        }))
+        .await;
+    assert_eq!(resp.status_code(), 200, "{}", resp.text());
+
+    let requests = mock.received_requests().await.unwrap();
+    let upstream = requests
+        .iter()
+        .find(|r| r.url.path() == "/openai/v1/chat/completions")
+        .expect("request forwarded to Bedrock");
+
+    let authorization = upstream.headers.get("authorization").unwrap().to_str().unwrap();
+    assert!(
+        authorization.starts_with("AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/"),
+        "unexpected authorization: {authorization}"
+    );
+    assert!(authorization.contains("/us-east-1/bedrock/aws4_request"));
+    assert!(!authorization.contains(&api_key), "caller's key must not reach Bedrock");
+    assert!(upstream.headers.contains_key("x-amz-date"));
+
+    let body: serde_json::Value = serde_json::from_slice(&upstream.body).unwrap();
+    assert_eq!(body["model"], BEDROCK_MODEL_ID);
+}
+
+#[sqlx::test]
+async fn bedrock_endpoint_requires_credentials_and_models(pool: PgPool) {
+    let app = crate::Application::new_with_pool(create_test_config(), Some(pool.clone()), None)
+        .await
+        .expect("app");
+    let (server, _bg) = app.into_test_server();
+    let admin = create_test_admin_user(&pool, Role::PlatformManager).await;
+    let h = add_auth_headers(&admin);
+
+    let credentials = serde_json::json!({ "region": "us-east-1", "access_key_id": "AKIDEXAMPLE", "secret_access_key": "secret" });
+    for body in [
+        // No credentials
+        serde_json::json!({ "name": "b1", "url": "https://bedrock.example.com", "protocol": "bedrock", "model_filter": [BEDROCK_MODEL_ID] }),
+        // No model IDs to serve
+        serde_json::json!({ "name": "b2", "url": "https://bedrock.example.com", "protocol": "bedrock", "bedrock": credentials }),
+        // Credentials on a non-Bedrock endpoint
+        serde_json::json!({ "name": "b3", "url": "https://bedrock.example.com", "bedrock": credentials }),
+    ] {
+        let resp = server
+            .post("/admin/api/v1/endpoints")
+            .add_header(&h[0].0, &h[0].1)
+            .add_header(&h[1].0, &h[1].1)
+            .json(&body)
+            .await;
+        assert_eq!(resp.status_code(), 400, "{body}: {}", resp.text());
+    }
+}
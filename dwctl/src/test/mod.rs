@@ -1,8 +1,10 @@
 pub mod anthropic;
+pub mod bedrock;
 pub mod cache_classifier;
 pub mod databases;
 pub mod multi_step_executor;
 pub mod responses;
+pub mod shadow_traffic;
 pub mod sigterm_drain;
 pub mod sla;
 pub mod strict_mode;
@@ -23,7 +25,7 @@ use outlet_postgres::RequestFilter;
 use sqlx::PgPool;
 use sqlx_pool_router::{DbPools, PoolProvider};
 use tracing::info;
-use utils::{add_auth_headers, create_test_admin_user, create_test_config, create_test_user};
+use utils::{add_auth_headers, await_chat_status, create_test_admin_user, create_test_config, create_test_user, setup_proxied_model};
 use uuid::Uuid;
 use wiremock::matchers::{body_partial_json, method, path};
 
@@ -547,6 +549,124 @@ async fn ai_models_supports_optional_group_and_realtime_filters(pool: PgPool) {
     assert_eq!(invalid_capabilities_response.status_code(), 400);
 }
 
+#[sqlx::test]
+async fn ai_models_include_pricing_matches_key_purpose_tariff(pool: PgPool) {
+    use crate::db::handlers::{Tariffs, api_keys::ApiKeys};
+    use crate::db::models::api_keys::{ApiKeyCreateDBRequest, ApiKeyPurpose};
+    use crate::db::models::tariffs::TariffCreateDBRequest;
+    use rust_decimal::Decimal;
+    use utils::{
+        add_deployment_to_group, add_user_to_group, create_test_api_key_for_user, create_test_endpoint, create_test_group,
+        create_test_model,
+    };
+
+    let (server, _bg_services) = utils::create_test_app(pool.clone(), false).await;
+    let admin_user = create_test_admin_user(&pool, Role::PlatformManager).await;
+    let user = create_test_user(&pool, Role::StandardUser).await;
+    let group = create_test_group(&pool).await;
+    add_user_to_group(&pool, user.id, group.id).await;
+    let endpoint_id = create_test_endpoint(&pool, "pricing-endpoint", admin_user.id).await;
+
+    let purpose_priced = create_test_model(&pool, "pricing-purpose", "pricing-purpose", endpoint_id, admin_user.id).await;
+    let default_priced = create_test_model(&pool, "pricing-default", "pricing-default", endpoint_id, admin_user.id).await;
+    let unpriced = create_test_model(&pool, "pricing-none", "pricing-none", endpoint_id, admin_user.id).await;
+    let inaccessible = create_test_model(&pool, "pricing-hidden", "pricing-hidden", endpoint_id, admin_user.id).await;
+    for deployment_id in [purpose_priced, default_priced, unpriced] {
+        add_deployment_to_group(&pool, deployment_id, group.id, admin_user.id).await;
+    }
+
+    let mut conn = pool.acquire().await.unwrap();
+    let tariffs = [
+        (purpose_priced, Some(ApiKeyPurpose::Realtime), 1, 2),
+        (purpose_priced, Some(ApiKeyPurpose::Playground), 3, 4),
+        (default_priced, None, 5, 6),
+        (inaccessible, Some(ApiKeyPurpose::Realtime), 7, 8),
+    ];
+    for (deployed_model_id, api_key_purpose, input, output) in tariffs {
+        Tariffs::new(&mut conn)
+            .create(&TariffCreateDBRequest {
+                deployed_model_id,
+                name: format!("tariff-{input}"),
+                input_price_per_token: Decimal::new(input, 6),
+                output_price_per_token: Decimal::new(output, 6),
+                api_key_purpose,
+                completion_window: None,
+                valid_from: None,
+            })
+            .await
+            .unwrap();
+    }
+    let realtime_key = create_test_api_key_for_user(&pool, user.id).await;
+    let playground_request = ApiKeyCreateDBRequest::new(
+        user.id,
+        user.id,
+        crate::api::models::api_keys::ApiKeyCreate {
+            name: "pricing playground key".to_string(),
+            description: None,
+            purpose: Some(ApiKeyPurpose::Playground),
+            requests_per_second: None,
+            burst_size: None,
+            member_id: None,
+            spend_limit: None,
+            spend_limit_interval: None,
+            allowed_model_ids: None,
+            denied_model_ids: None,
+            max_priority: None,
+        },
+    );
+    let playground_key = ApiKeys::new(&mut conn).create(&playground_request).await.unwrap();
+    drop(conn);
+
+    let list_models = |secret: String, query: &'static str| {
+        server
+            .get(&format!("/ai/v1/models{query}"))
+            .add_header("authorization", format!("Bearer {secret}"))
+    };
+    let pricing_of = |models: &serde_json::Value, alias: &str| {
+        models["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|model| model["id"] == alias)
+            .map(|model| model["pricing"].clone())
+    };
+
+    // Pricing is opt-in, keeping the OpenAI model shape by default
+    let default_models: serde_json::Value = list_models(realtime_key.secret.clone(), "").await.json();
+    assert_eq!(pricing_of(&default_models, "pricing-purpose"), Some(serde_json::Value::Null));
+
+    let realtime_models: serde_json::Value = list_models(realtime_key.secret.clone(), "?include_pricing=true").await.json();
+    assert_eq!(
+        pricing_of(&realtime_models, "pricing-purpose"),
+        Some(serde_json::json!({"input_price_per_token": "0.000001", "output_price_per_token": "0.000002"}))
+    );
+    assert_eq!(
+        pricing_of(&realtime_models, "pricing-default"),
+        Some(serde_json::json!({"input_price_per_token": "0.000005", "output_price_per_token": "0.000006"}))
+    );
+    assert_eq!(pricing_of(&realtime_models, "pricing-none"), Some(serde_json::Value::Null));
+    assert_eq!(
+        pricing_of(&realtime_models, "pricing-hidden"),
+        None,
+        "inaccessible models are not listed"
+    );
+    assert!(
+        !realtime_models.to_string().contains("0.000007"),
+        "inaccessible pricing must not leak"
+    );
+
+    // A playground key sees its own purpose's tariff
+    let playground_models: serde_json::Value = list_models(playground_key.secret.clone(), "?include_pricing=true").await.json();
+    assert_eq!(
+        pricing_of(&playground_models, "pricing-purpose"),
+        Some(serde_json::json!({"input_price_per_token": "0.000003", "output_price_per_token": "0.000004"}))
+    );
+
+    list_models(realtime_key.secret, "?include_pricing=maybe")
+        .await
+        .assert_status_bad_request();
+}
+
 async fn assert_usage_recorded(fixture: &StreamingFixture, expected_uri: &str, prompt_tokens: i64, completion_tokens: i64) {
     let mut tries = 0;
     // The batcher flush folds the balance in the same transaction that
@@ -706,6 +826,249 @@ async fn test_e2e_ai_proxy_streaming_completions_with_fusillade_header(pool: PgP
     cleanup_fixture(fixture).await;
 }
 
+#[sqlx::test]
+#[test_log::test]
+async fn test_e2e_ai_proxy_rerank_is_logged_and_billed_per_document(pool: PgPool) {
+    let mock_server = wiremock::MockServer::start().await;
+
+    wiremock::Mock::given(method("POST"))
+        .and(path("/v1/rerank"))
+        .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "rerank-123",
+            "model": "bge-reranker-v2-m3",
+            "results": [{"index": 1, "relevance_score": 0.98}],
+            "usage": {"total_tokens": 27}
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let fixture = setup_streaming_fixture(
+        &pool,
+        format!("{}/v1", mock_server.uri()),
+        "bge-reranker-v2-m3",
+        "test-reranker",
+        None,
+    )
+    .await;
+    sqlx::query("UPDATE deployed_models SET type = 'RERANKER' WHERE alias = $1")
+        .bind("test-reranker")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let models_response = fixture
+        .server
+        .get("/ai/v1/models")
+        .add_header("authorization", format!("Bearer {}", fixture.api_key))
+        .await;
+    let models: serde_json::Value = models_response.json();
+    let reranker = models["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|model| model["id"] == "test-reranker")
+        .expect("reranker should be listed");
+    assert_eq!(reranker["capabilities"], serde_json::json!(["rerank"]));
+
+    let inference_response = fixture
+        .server
+        .post("/ai/v1/rerank")
+        .add_header("authorization", format!("Bearer {}", fixture.api_key))
+        .json(&serde_json::json!({
+            "model": "test-reranker",
+            "query": "What is the capital of France?",
+            "documents": ["Berlin is in Germany.", "Paris is the capital of France.", "Madrid is in Spain."],
+            "top_n": 1
+        }))
+        .await;
+
+    assert_eq!(inference_response.status_code().as_u16(), 200);
+    let body: serde_json::Value = inference_response.json();
+    assert_eq!(body["results"][0]["index"], 1);
+    // All three documents were scored even though top_n returned one.
+    assert_usage_recorded(&fixture, "http://localhost/rerank", 3, 0).await;
+    cleanup_fixture(fixture).await;
+}
+
+#[sqlx::test]
+#[test_log::test]
+async fn test_e2e_ai_proxy_moderation_is_logged_and_billed_per_request(pool: PgPool) {
+    let mock_server = wiremock::MockServer::start().await;
+
+    wiremock::Mock::given(method("POST"))
+        .and(path("/v1/moderations"))
+        .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "modr-123",
+            "model": "llama-guard-4-12b",
+            "results": [
+                {"flagged": false, "categories": {"violence": false}, "category_scores": {"violence": 0.01}},
+                {"flagged": true, "categories": {"violence": true}, "category_scores": {"violence": 0.97}}
+            ]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let fixture = setup_streaming_fixture(
+        &pool,
+        format!("{}/v1", mock_server.uri()),
+        "llama-guard-4-12b",
+        "test-moderation",
+        None,
+    )
+    .await;
+    sqlx::query("UPDATE deployed_models SET type = 'MODERATION' WHERE alias = $1")
+        .bind("test-moderation")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let models_response = fixture
+        .server
+        .get("/ai/v1/models")
+        .add_header("authorization", format!("Bearer {}", fixture.api_key))
+        .await;
+    let models: serde_json::Value = models_response.json();
+    let moderation_model = models["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|model| model["id"] == "test-moderation")
+        .expect("moderation model should be listed");
+    assert_eq!(moderation_model["capabilities"], serde_json::json!(["moderation"]));
+
+    let inference_response = fixture
+        .server
+        .post("/ai/v1/moderations")
+        .add_header("authorization", format!("Bearer {}", fixture.api_key))
+        .json(&serde_json::json!({
+            "model": "test-moderation",
+            "input": ["What is the capital of France?", "I want to hurt them."]
+        }))
+        .await;
+
+    assert_eq!(inference_response.status_code().as_u16(), 200);
+    let body: serde_json::Value = inference_response.json();
+    assert_eq!(body["results"][1]["flagged"], true);
+    // Two inputs, one billed unit.
+    assert_usage_recorded(&fixture, "http://localhost/moderations", 1, 0).await;
+    cleanup_fixture(fixture).await;
+}
+
+#[sqlx::test]
+#[test_log::test]
+async fn test_e2e_ai_proxy_n_is_clamped_and_billed_for_every_completion(pool: PgPool) {
+    let mock_server = wiremock::MockServer::start().await;
+
+    // Only answers if the client's n was lowered to the cap
+    wiremock::Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(body_partial_json(serde_json::json!({"n": 2})))
+        .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "chatcmpl-1", "object": "chat.completion", "created": 1, "model": "gpt-4",
+            "choices": [
+                {"index": 0, "message": {"role": "assistant", "content": "Hi"}, "finish_reason": "stop"},
+                {"index": 1, "message": {"role": "assistant", "content": "Hello"}, "finish_reason": "stop"}
+            ],
+            "usage": {"prompt_tokens": 5, "completion_tokens": 20, "total_tokens": 25}
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let fixture = setup_streaming_fixture(&pool, format!("{}/v1", mock_server.uri()), "gpt-4", "test-model", None).await;
+    sqlx::query("UPDATE deployed_models SET max_n = 2, max_n_mode = 'clamp' WHERE alias = $1")
+        .bind("test-model")
+        .execute(&pool)
+        .await
+        .unwrap();
+    fixture
+        .bg_services
+        .sync_onwards_config(&pool)
+        .await
+        .expect("Failed to sync onwards config");
+
+    let response = fixture
+        .server
+        .post("/ai/v1/chat/completions")
+        .add_header("authorization", format!("Bearer {}", fixture.api_key))
+        .json(&serde_json::json!({
+            "model": "test-model",
+            "n": 5,
+            "messages": [{"role": "user", "content": "Hello"}]
+        }))
+        .await;
+    assert_eq!(response.status_code().as_u16(), 200);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["choices"].as_array().unwrap().len(), 2);
+
+    // The usage covers both completions, so both are billed
+    assert_usage_recorded(&fixture, "http://localhost/chat/completions", 5, 20).await;
+    cleanup_fixture(fixture).await;
+}
+
+#[sqlx::test]
+#[test_log::test]
+async fn test_e2e_request_id_is_echoed_forwarded_and_logged(pool: PgPool) {
+    let mock_server = wiremock::MockServer::start().await;
+
+    // Only answers if the client's request id reached the upstream
+    wiremock::Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(wiremock::matchers::header("x-request-id", "client-req-123"))
+        .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "chatcmpl-1", "object": "chat.completion", "created": 1, "model": "gpt-4",
+            "choices": [{"index": 0, "message": {"role": "assistant", "content": "Hi"}, "finish_reason": "stop"}],
+            "usage": {"prompt_tokens": 5, "completion_tokens": 1, "total_tokens": 6}
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let fixture = setup_streaming_fixture(&pool, format!("{}/v1", mock_server.uri()), "gpt-4", "test-model", None).await;
+
+    let response = fixture
+        .server
+        .post("/ai/v1/chat/completions")
+        .add_header("authorization", format!("Bearer {}", fixture.api_key))
+        .add_header("x-request-id", "client-req-123")
+        .json(&serde_json::json!({
+            "model": "test-model",
+            "messages": [{"role": "user", "content": "Hello"}]
+        }))
+        .await;
+    assert_eq!(response.status_code().as_u16(), 200);
+    assert_eq!(response.header("x-request-id"), "client-req-123");
+
+    let mut stored = None;
+    for _ in 0..100 {
+        stored = sqlx::query_scalar::<_, String>("SELECT request_id FROM http_analytics WHERE request_id IS NOT NULL")
+            .fetch_optional(&pool)
+            .await
+            .unwrap();
+        if stored.is_some() {
+            break;
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+    }
+    assert_eq!(stored.as_deref(), Some("client-req-123"));
+
+    // Without a supplied id, admin responses carry a generated one
+    let response = fixture
+        .server
+        .get("/admin/api/v1/users/current")
+        .add_header(&fixture.admin_headers[0].0, &fixture.admin_headers[0].1)
+        .add_header(&fixture.admin_headers[1].0, &fixture.admin_headers[1].1)
+        .await;
+    assert_eq!(response.status_code(), 200);
+    let generated = response.header("x-request-id");
+    assert!(
+        Uuid::parse_str(generated.to_str().unwrap()).is_ok(),
+        "expected a generated UUID, got {generated:?}"
+    );
+
+    cleanup_fixture(fixture).await;
+}
+
 // Removed: `test_e2e_ai_proxy_streaming_responses_with_fusillade_header`.
 //
 // The original test proxied a streaming `/v1/responses` request to a
@@ -905,7 +1268,8 @@ async fn test_e2e_traffic_routing_rules(pool: PgPool) {
     // Small delay to let the config watcher task finish updating DashMaps
     tokio::time::sleep(std::time::Duration::from_millis(50)).await;
 
-    // Poll with batch key until deny rule takes effect
+    // Poll with batch key until deny rule takes effect. With the default
+    // `unauthorized_models: not_found` the denied key is told the model doesn't exist.
     let mut deny_status = 0u16;
     for i in 0..50 {
         let resp = server
@@ -914,13 +1278,15 @@ async fn test_e2e_traffic_routing_rules(pool: PgPool) {
             .json(&chat_body)
             .await;
         deny_status = resp.status_code().as_u16();
-        if deny_status == 403 {
+        if deny_status == 404 {
+            let body: serde_json::Value = resp.json();
+            assert_eq!(body["error"]["code"], "model_not_found");
             break;
         }
         assert!(i < 49, "Deny rule never took effect, last status: {deny_status}");
         tokio::time::sleep(std::time::Duration::from_millis(10)).await;
     }
-    assert_eq!(deny_status, 403, "Batch key should be denied");
+    assert_eq!(deny_status, 404, "Batch key should be denied");
 
     // Realtime key should still work (not affected by batch deny rule)
     let realtime_resp = server
@@ -1019,6 +1385,212 @@ async fn test_e2e_traffic_routing_rules(pool: PgPool) {
     bg_services.shutdown().await;
 }
 
+/// End-to-end test: a sanitize traffic rule overrides the model's sanitize_responses
+/// setting per API key purpose. Realtime (external) keys get sanitized responses,
+/// playground (internal) keys get the raw upstream response for the same model.
+#[sqlx::test]
+#[test_log::test]
+async fn test_e2e_sanitize_traffic_rule_by_purpose(pool: PgPool) {
+    let mock_server = wiremock::MockServer::start().await;
+    wiremock::Mock::given(wiremock::matchers::method("POST"))
+        .and(wiremock::matchers::path("/v1/chat/completions"))
+        .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "chatcmpl-sanitize-test",
+            "object": "chat.completion",
+            "created": 1677652288,
+            "model": "sanitize-model",
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": "Hello" },
+                "finish_reason": "stop"
+            }],
+            "usage": { "prompt_tokens": 5, "completion_tokens": 1, "total_tokens": 6 },
+            "internal_metadata": { "node": "gpu-7" }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let mut config = create_test_config();
+    config.background_services.onwards_sync.enabled = true;
+    let app = crate::Application::new_with_pool(config, Some(pool.clone()), None)
+        .await
+        .expect("Failed to create application");
+    let (server, bg_services) = app.into_test_server();
+
+    // The model itself doesn't sanitize; the rule turns it on for realtime keys only
+    let proxied = setup_proxied_model(
+        &server,
+        &bg_services,
+        &pool,
+        &mock_server,
+        serde_json::json!({}),
+        serde_json::json!({
+            "alias": "sanitize-model",
+            "sanitize_responses": false,
+            "traffic_routing_rules": [
+                { "api_key_purpose": "realtime", "action": { "type": "sanitize", "enabled": true } }
+            ]
+        }),
+    )
+    .await;
+    let model: crate::api::models::deployments::DeployedModelResponse =
+        serde_json::from_value(proxied.model).expect("Failed to parse deployment");
+    let rules = model.traffic_routing_rules.expect("sanitize rule should be returned");
+    assert!(matches!(
+        rules[0].action,
+        crate::api::models::deployments::TrafficRoutingAction::Sanitize { enabled: true }
+    ));
+
+    let admin_headers = &proxied.admin_headers;
+    let response = server
+        .post("/admin/api/v1/transactions")
+        .add_header(&admin_headers[0].0, &admin_headers[0].1)
+        .add_header(&admin_headers[1].0, &admin_headers[1].1)
+        .json(&serde_json::json!({
+            "user_id": proxied.user.id,
+            "transaction_type": "admin_grant",
+            "amount": 1000,
+            "source_id": proxied.admin.id,
+            "description": "Credits for sanitize rule test"
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201, "Failed to grant credits");
+
+    let playground_key: String =
+        sqlx::query_scalar("SELECT secret FROM api_keys WHERE user_id = $1 AND purpose = 'playground' AND hidden = true")
+            .bind(proxied.user.id)
+            .fetch_one(&pool)
+            .await
+            .expect("Playground hidden key should exist");
+
+    let chat_body = serde_json::json!({
+        "model": "sanitize-model",
+        "messages": [{"role": "user", "content": "test"}]
+    });
+    let mut responses = Vec::new();
+    for key in [&proxied.api_key, &playground_key] {
+        responses.push(await_chat_status(&server, key, &chat_body, 200).await.json::<serde_json::Value>());
+    }
+
+    assert!(
+        responses[0].get("internal_metadata").is_none(),
+        "realtime key should get a sanitized response: {}",
+        responses[0]
+    );
+    assert_eq!(responses[0]["choices"][0]["message"]["content"], "Hello");
+    assert_eq!(
+        responses[1]["internal_metadata"]["node"], "gpu-7",
+        "playground key should get the raw response: {}",
+        responses[1]
+    );
+
+    bg_services.shutdown().await;
+}
+
+#[sqlx::test]
+async fn test_e2e_rewrite_response_model_reports_requested_alias(pool: PgPool) {
+    let mock_server = wiremock::MockServer::start().await;
+    wiremock::Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(body_partial_json(serde_json::json!({ "stream": true })))
+        .respond_with(
+            wiremock::ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_string(concat!(
+                    "data: {\"id\":\"c1\",\"object\":\"chat.completion.chunk\",\"created\":1,\"model\":\"provider/underlying-model\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hi\"},\"finish_reason\":null}]}\n\n",
+                    "data: [DONE]\n\n",
+                )),
+        )
+        .mount(&mock_server)
+        .await;
+    wiremock::Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "chatcmpl-rewrite-test",
+            "object": "chat.completion",
+            "created": 1677652288,
+            "model": "provider/underlying-model",
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": "Hello" },
+                "finish_reason": "stop"
+            }],
+            "usage": { "prompt_tokens": 5, "completion_tokens": 1, "total_tokens": 6 },
+            "internal_metadata": { "node": "gpu-7" }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let mut config = create_test_config();
+    config.background_services.onwards_sync.enabled = true;
+    let app = crate::Application::new_with_pool(config, Some(pool.clone()), None)
+        .await
+        .expect("Failed to create application");
+    let (server, bg_services) = app.into_test_server();
+
+    // Rewriting is independent of sanitization: other upstream fields survive
+    let proxied = setup_proxied_model(
+        &server,
+        &bg_services,
+        &pool,
+        &mock_server,
+        serde_json::json!({}),
+        serde_json::json!({
+            "model_name": "provider/underlying-model",
+            "alias": "friendly-alias",
+            "sanitize_responses": false,
+            "rewrite_response_model": true,
+            "allow_public": true
+        }),
+    )
+    .await;
+    assert_eq!(proxied.model["rewrite_response_model"], true);
+    let api_key = proxied.api_key;
+    wait_for_model(&server, &api_key, "friendly-alias").await;
+
+    let response = server
+        .post("/ai/v1/chat/completions")
+        .add_header("authorization", format!("Bearer {api_key}"))
+        .json(&serde_json::json!({
+            "model": "friendly-alias",
+            "messages": [{"role": "user", "content": "test"}]
+        }))
+        .await;
+    assert_eq!(response.status_code(), 200, "{}", response.text());
+    let body: serde_json::Value = response.json();
+    assert_eq!(
+        body["model"], "friendly-alias",
+        "response model should be the requested alias: {body}"
+    );
+    assert_eq!(
+        body["internal_metadata"]["node"], "gpu-7",
+        "other fields should pass through: {body}"
+    );
+
+    let response = server
+        .post("/ai/v1/chat/completions")
+        .add_header("authorization", format!("Bearer {api_key}"))
+        .json(&serde_json::json!({
+            "model": "friendly-alias",
+            "stream": true,
+            "messages": [{"role": "user", "content": "test"}]
+        }))
+        .await;
+    assert_eq!(response.status_code(), 200, "{}", response.text());
+    let text = response.text();
+    assert!(
+        text.contains("\"model\":\"friendly-alias\""),
+        "stream chunks should carry the alias: {text}"
+    );
+    assert!(
+        !text.contains("provider/underlying-model"),
+        "upstream model name should not leak: {text}"
+    );
+    assert!(text.contains("data: [DONE]"));
+
+    bg_services.shutdown().await;
+}
+
 #[sqlx::test]
 #[test_log::test]
 async fn test_database_seeding_behavior(pool: PgPool) {
@@ -1069,7 +1641,9 @@ async fn test_database_seeding_behavior(pool: PgPool) {
     assert_eq!(initial_seeded, Some(false), "Initial seeded flag should be false");
 
     // First call should seed both endpoints and API key
-    super::seed_database(&sources, &pool).await.expect("First seeding should succeed");
+    super::seed_database(&sources, None, &pool)
+        .await
+        .expect("First seeding should succeed");
 
     // Verify endpoints were created
     let endpoint_count =
@@ -1107,7 +1681,7 @@ async fn test_database_seeding_behavior(pool: PgPool) {
         .expect("Should be able to update API key");
 
     // Second call should skip all seeding (because seeded flag is true)
-    super::seed_database(&sources, &pool)
+    super::seed_database(&sources, None, &pool)
         .await
         .expect("Second seeding should succeed but skip");
 
@@ -1132,6 +1706,75 @@ async fn test_database_seeding_behavior(pool: PgPool) {
     assert_eq!(final_count, Some(2), "Should still have 2 endpoints");
 }
 
+#[sqlx::test]
+#[test_log::test]
+async fn test_database_seeding_uses_configured_system_api_key(pool: PgPool) {
+    let secret = "sk-configured-system-key-0123456789abcdef";
+    super::seed_database(&[], Some(secret), &pool)
+        .await
+        .expect("Seeding should succeed");
+
+    let seeded_secret = sqlx::query_scalar!("SELECT secret FROM api_keys WHERE id = '00000000-0000-0000-0000-000000000000'")
+        .fetch_one(&pool)
+        .await
+        .expect("Should be able to get API key secret");
+    assert_eq!(seeded_secret, secret, "Configured secret should be used instead of a generated one");
+}
+
+#[sqlx::test]
+#[test_log::test]
+async fn test_database_seeding_alias_collision_is_atomic(pool: PgPool) {
+    use crate::config::{DefaultModel, ModelSource};
+    use url::Url;
+
+    // An existing deployment already owns the alias one of the default models wants
+    let admin = create_test_admin_user(&pool, Role::PlatformManager).await;
+    utils::create_test_deployment(&pool, admin.id, "taken-model", "taken-model").await;
+
+    let sources = vec![ModelSource {
+        name: "seed-endpoint".to_string(),
+        url: Url::parse("http://localhost:8001").unwrap(),
+        api_key: None,
+        sync_interval: std::time::Duration::from_secs(10),
+        default_models: Some(vec![
+            DefaultModel {
+                name: "fresh-model".to_string(),
+                add_to_everyone_group: true,
+            },
+            DefaultModel {
+                name: "taken-model".to_string(),
+                add_to_everyone_group: true,
+            },
+        ]),
+    }];
+
+    let err = super::seed_database(&sources, None, &pool)
+        .await
+        .expect_err("Seeding should fail on an alias collision");
+    let message = err.to_string();
+    assert!(message.contains("taken-model"), "Error should name the colliding alias: {message}");
+    assert!(message.contains("seed-endpoint"), "Error should name the model source: {message}");
+
+    // Nothing from the partial seed is kept, and the flag stays unset so the next startup retries
+    let seeded = sqlx::query_scalar!("SELECT value FROM system_config WHERE key = 'endpoints_seeded'")
+        .fetch_one(&pool)
+        .await
+        .expect("Should be able to query seeded flag");
+    assert!(!seeded, "Seeded flag must not be set after a failed seed");
+
+    let endpoints: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM inference_endpoints WHERE name = 'seed-endpoint'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(endpoints, 0, "Seeded endpoint should be rolled back");
+
+    let fresh: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM deployed_models WHERE alias = 'fresh-model'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(fresh, 0, "Models seeded before the collision should be rolled back");
+}
+
 #[sqlx::test]
 #[test_log::test]
 async fn test_request_logging_enabled(pool: PgPool) {
@@ -1264,6 +1907,99 @@ async fn test_outlet_suppresses_zdr_bodies(pool: PgPool) {
     );
 }
 
+/// Requests to a deployment with `disable_logging` set are logged without
+/// bodies, while requests to other deployments are logged in full.
+#[sqlx::test]
+#[test_log::test]
+async fn test_outlet_omits_bodies_of_logging_disabled_deployments(pool: PgPool) {
+    use bytes::Bytes;
+    use outlet::{RequestData, RequestHandler, ResponseData};
+    use std::collections::HashMap;
+    use std::time::{Duration, SystemTime};
+
+    // Outlet tables applied directly, as in `test_outlet_suppresses_zdr_bodies`.
+    for migration in outlet_postgres::migrator().iter() {
+        sqlx::raw_sql(migration.sql.as_ref())
+            .execute(&pool)
+            .await
+            .expect("apply outlet migration");
+    }
+
+    let admin = create_test_admin_user(&pool, Role::PlatformManager).await;
+    crate::test::utils::create_test_deployment(&pool, admin.id, "sensitive-model", "sensitive").await;
+    crate::test::utils::create_test_deployment(&pool, admin.id, "ordinary-model", "ordinary").await;
+    sqlx::query("UPDATE deployed_models SET disable_logging = true WHERE alias = 'sensitive'")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let handler =
+        outlet_postgres::PostgresHandler::<DbPools, serde_json::Value, serde_json::Value>::from_pool_provider(DbPools::new(pool.clone()))
+            .await
+            .expect("build PostgresHandler");
+    let handler = crate::request_logging::opt_out::LoggingOptOutScrubber::new(handler, pool.clone(), Default::default());
+
+    fn request(correlation_id: u64, model: &str) -> RequestData {
+        RequestData {
+            correlation_id,
+            timestamp: SystemTime::now(),
+            method: axum::http::Method::POST,
+            uri: "/ai/v1/chat/completions".parse().unwrap(),
+            headers: HashMap::new(),
+            body: Some(Bytes::from(format!(r#"{{"model":"{model}","secret":"prompt"}}"#))),
+            trace_id: None,
+            span_id: None,
+        }
+    }
+    fn response(correlation_id: u64) -> ResponseData {
+        ResponseData {
+            extensions: Default::default(),
+            correlation_id,
+            timestamp: SystemTime::now(),
+            status: axum::http::StatusCode::OK,
+            headers: HashMap::new(),
+            body: Some(Bytes::from_static(br#"{"secret":"reply"}"#)),
+            duration_to_first_byte: Duration::from_millis(1),
+            duration: Duration::from_millis(2),
+        }
+    }
+
+    handler.handle_request(request(1, "sensitive")).await;
+    handler.handle_response(request(1, "sensitive"), response(1)).await;
+    handler.handle_request(request(2, "ordinary")).await;
+    handler.handle_response(request(2, "ordinary"), response(2)).await;
+
+    let body_of = |table: &str, id: i64| {
+        let sql = format!("SELECT body FROM {table} WHERE correlation_id = $1");
+        let pool = pool.clone();
+        async move {
+            sqlx::query_scalar::<_, Option<serde_json::Value>>(&sql)
+                .bind(id)
+                .fetch_one(&pool)
+                .await
+                .expect("query body")
+        }
+    };
+
+    // The opted-out request is still logged, just without its bodies.
+    assert!(
+        body_of("http_requests", 1).await.is_none(),
+        "opted-out request body must not be logged"
+    );
+    assert!(
+        body_of("http_responses", 1).await.is_none(),
+        "opted-out response body must not be logged"
+    );
+    assert!(
+        body_of("http_requests", 2).await.is_some(),
+        "ordinary request body should be logged"
+    );
+    assert!(
+        body_of("http_responses", 2).await.is_some(),
+        "ordinary response body should be logged"
+    );
+}
+
 #[sqlx::test]
 #[test_log::test]
 async fn test_request_logging_disabled(pool: PgPool) {
@@ -1344,6 +2080,42 @@ async fn test_request_logging_disabled(pool: PgPool) {
     }
 }
 
+#[sqlx::test(migrations = false)]
+#[test_log::test]
+async fn test_migrate_applies_migrations_and_skip_migrations_bypasses_them(pool: PgPool) {
+    let mut skip_config = create_test_config();
+    skip_config.skip_migrations = true;
+
+    // With migrations skipped nothing is applied, and startup refuses to run
+    // against a database that is behind
+    let error = crate::Application::new_with_pool(skip_config.clone(), Some(pool.clone()), None)
+        .await
+        .err()
+        .expect("startup should fail while migrations are pending");
+    assert!(error.to_string().contains("dwctl migrate"), "unexpected error: {error}");
+    let migrations_table_exists: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(!migrations_table_exists, "skip_migrations must not apply any migrations");
+
+    // `dwctl migrate` applies every migration
+    crate::migrate(&create_test_config(), Some(pool.clone()))
+        .await
+        .expect("migrate should succeed");
+    let applied: Vec<i64> = sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success ORDER BY version")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    let expected: Vec<i64> = crate::migrator().iter().map(|migration| migration.version).collect();
+    assert_eq!(applied, expected);
+
+    // ...after which the server starts without running them itself
+    crate::Application::new_with_pool(skip_config, Some(pool.clone()), None)
+        .await
+        .expect("startup should succeed once migrations are applied");
+}
+
 #[sqlx::test]
 #[test_log::test]
 async fn test_dedicated_databases_for_components(pool: PgPool) {
@@ -1367,6 +2139,8 @@ async fn test_dedicated_databases_for_components(pool: PgPool) {
         replica_url: None,
         pool: PoolSettings::default(),
         replica_pool: None,
+        analytics_replica_url: None,
+        analytics_pool: None,
         fusillade: ComponentDb::Dedicated {
             url: test_dbs.fusillade_url.clone(),
             replica_url: None,
@@ -1708,12 +2482,15 @@ mod openapi_access_control {
             ApiKeyCreate {
                 name: format!("{purpose:?} key"),
                 description: None,
-                purpose,
+                purpose: Some(purpose),
                 requests_per_second: None,
                 burst_size: None,
                 member_id: None,
                 spend_limit: None,
                 spend_limit_interval: None,
+                allowed_model_ids: None,
+                denied_model_ids: None,
+                max_priority: None,
             },
         ))
         .await
@@ -2512,3 +3289,118 @@ async fn test_read_pool_enforces_readonly(pool: PgPool) {
 
     assert!(result.is_ok(), "Write operation on write pool should succeed");
 }
+
+/// Heavy analytics reads go to the analytics pool rather than the primary.
+///
+/// The analytics pool here is read-only, as a replica would be, so the
+/// handlers succeed only if they don't write; closing it then breaks exactly
+/// these handlers, which shows they don't fall back to the primary.
+#[sqlx::test]
+async fn test_analytics_handlers_use_analytics_pool(pool: PgPool) {
+    let mut config = create_test_config();
+    config.enable_analytics = false; // Disable to avoid spawning background batcher task
+
+    let analytics_pool = sqlx::postgres::PgPoolOptions::new()
+        .max_connections(2)
+        .connect_with(
+            pool.connect_options()
+                .as_ref()
+                .clone()
+                .options([("default_transaction_read_only", "on")]),
+        )
+        .await
+        .expect("Failed to create analytics pool");
+
+    let request_manager = std::sync::Arc::new(fusillade_arsenal::PostgresRequestManager::new(
+        DbPools::new(pool.clone()),
+        Default::default(),
+    ));
+    let limiters = crate::limits::Limiters::new(&config.limits);
+    let shared_config = crate::SharedConfig::new(config);
+    underway::run_migrations(&pool).await.expect("Failed to run underway migrations");
+    let task_state = crate::tasks::TaskState {
+        request_manager: request_manager.clone(),
+        dwctl_pool: pool.clone(),
+        config: shared_config.clone(),
+        encryption_key: None,
+        ingest_file_job: std::sync::Arc::new(std::sync::OnceLock::new()),
+        activate_batch_job: std::sync::Arc::new(std::sync::OnceLock::new()),
+        create_batch_job: std::sync::Arc::new(std::sync::OnceLock::new()),
+        cascade_batch_state_job: std::sync::Arc::new(std::sync::OnceLock::new()),
+    };
+    let task_runner = std::sync::Arc::new(
+        crate::tasks::TaskRunner::new(
+            pool.clone(),
+            task_state,
+            &crate::config::TaskWorkersConfig {
+                create_batch_workers: 0,
+                cascade_batch_state_workers: 0,
+                purge_user_data_workers: 0,
+                response_writer_batch_size: 0,
+            },
+        )
+        .await
+        .expect("Failed to create task runner"),
+    );
+    let response_store = std::sync::Arc::new(crate::inference::store::FusilladeResponseStore::new(request_manager.clone()));
+    let mut app_state = AppState::builder()
+        .db(DbPools::new(pool.clone()))
+        .config(shared_config)
+        .analytics_db(analytics_pool.clone())
+        .request_manager(request_manager)
+        .task_runner(task_runner)
+        .limiters(limiters)
+        .response_store(response_store)
+        .image_normalizer(std::sync::Arc::new(crate::image_normalizer::DisabledNormalizer)
+            as std::sync::Arc<dyn crate::image_normalizer::ImageNormalizer>)
+        .build();
+
+    let onwards_router = axum::Router::new();
+    let router = super::build_router(&mut app_state, onwards_router, None, None, None, false, None)
+        .await
+        .expect("Failed to build router");
+    let server = axum_test::TestServer::new(router).expect("Failed to create test server");
+
+    let admin = create_test_admin_user(&pool, Role::PlatformManager).await;
+    let headers = add_auth_headers(&admin);
+    let analytics_paths = [
+        "/admin/api/v1/requests".to_string(),
+        "/admin/api/v1/requests/aggregate".to_string(),
+        "/admin/api/v1/requests/aggregate-by-user?model=gpt-4".to_string(),
+        format!("/admin/api/v1/transactions?user_id={}", admin.id),
+        "/admin/api/v1/transactions?all=true".to_string(),
+        "/admin/api/v1/transactions/export".to_string(),
+    ];
+
+    for path in &analytics_paths {
+        let response = server
+            .get(path)
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .await;
+        assert_eq!(
+            response.status_code(),
+            200,
+            "{path} failed on the read-only analytics pool: {}",
+            response.text()
+        );
+    }
+
+    analytics_pool.close().await;
+    for path in &analytics_paths {
+        let response = server
+            .get(path)
+            .add_header(&headers[0].0, &headers[0].1)
+            .add_header(&headers[1].0, &headers[1].1)
+            .await;
+        assert_eq!(response.status_code(), 500, "{path} didn't read from the analytics pool");
+    }
+
+    // Other handlers still use the main pools
+    server
+        .get("/admin/api/v1/users/current")
+        .add_header(&headers[0].0, &headers[0].1)
+        .add_header(&headers[1].0, &headers[1].1)
+        .await
+        .assert_status_ok();
+}
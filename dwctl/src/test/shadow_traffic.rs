@@ -0,0 +1,182 @@
+//! Shadow traffic end to end: a model with a shadow sends a copy of each
+//! sampled request to the shadow model's endpoint, while the caller's response,
+//! the request log and billing only ever reflect the primary model.
+
+use crate::api::models::users::Role;
+use crate::test::utils::{add_auth_headers, await_chat_status, create_test_admin_user, create_test_config, create_test_user};
+use sqlx::PgPool;
+
+const PRIMARY_ALIAS: &str = "shadow-primary";
+const SHADOW_ALIAS: &str = "shadow-candidate";
+
+fn completion(model: &str, content: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": format!("chatcmpl-{model}"),
+        "object": "chat.completion",
+        "created": 1_677_652_288,
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "message": { "role": "assistant", "content": content },
+            "finish_reason": "stop"
+        }],
+        "usage": { "prompt_tokens": 5, "completion_tokens": 3, "total_tokens": 8 }
+    })
+}
+
+async fn mock_upstream(model: &str, content: &str) -> wiremock::MockServer {
+    let server = wiremock::MockServer::start().await;
+    wiremock::Mock::given(wiremock::matchers::method("POST"))
+        .and(wiremock::matchers::path("/v1/chat/completions"))
+        .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(completion(model, content)))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[sqlx::test]
+async fn shadow_copies_are_sent_but_not_returned_logged_or_billed(pool: PgPool) {
+    let primary_upstream = mock_upstream("primary-upstream", "from the primary").await;
+    let shadow_upstream = mock_upstream("candidate-upstream", "from the shadow").await;
+
+    let mut config = create_test_config();
+    config.background_services.onwards_sync.enabled = true;
+    let app = crate::Application::new_with_pool(config, Some(pool.clone()), None)
+        .await
+        .expect("Failed to create application");
+    let (server, bg_services) = app.into_test_server();
+
+    let admin_user = create_test_admin_user(&pool, Role::PlatformManager).await;
+    let admin_headers = add_auth_headers(&admin_user);
+    let user = create_test_user(&pool, Role::StandardUser).await;
+    let user_headers = add_auth_headers(&user);
+
+    let group: crate::api::models::groups::GroupResponse = server
+        .post("/admin/api/v1/groups")
+        .add_header(&admin_headers[0].0, &admin_headers[0].1)
+        .add_header(&admin_headers[1].0, &admin_headers[1].1)
+        .json(&serde_json::json!({ "name": "shadow-group", "description": "Shadow traffic test" }))
+        .await
+        .json();
+    server
+        .post(&format!("/admin/api/v1/groups/{}/users/{}", group.id, user.id))
+        .add_header(&admin_headers[0].0, &admin_headers[0].1)
+        .add_header(&admin_headers[1].0, &admin_headers[1].1)
+        .await;
+    server
+        .post("/admin/api/v1/transactions")
+        .add_header(&admin_headers[0].0, &admin_headers[0].1)
+        .add_header(&admin_headers[1].0, &admin_headers[1].1)
+        .json(&serde_json::json!({
+            "user_id": user.id,
+            "transaction_type": "admin_grant",
+            "amount": 1000,
+            "source_id": admin_user.id,
+            "description": "Credits for shadow traffic test"
+        }))
+        .await;
+
+    let mut model_ids = Vec::new();
+    for (name, upstream, model_name, alias) in [
+        ("Primary Endpoint", &primary_upstream, "primary-upstream", PRIMARY_ALIAS),
+        ("Shadow Endpoint", &shadow_upstream, "candidate-upstream", SHADOW_ALIAS),
+    ] {
+        let endpoint: crate::api::models::inference_endpoints::InferenceEndpointResponse = server
+            .post("/admin/api/v1/endpoints")
+            .add_header(&admin_headers[0].0, &admin_headers[0].1)
+            .add_header(&admin_headers[1].0, &admin_headers[1].1)
+            .json(&serde_json::json!({ "name": name, "url": format!("{}/v1", upstream.uri()) }))
+            .await
+            .json();
+        let model: crate::api::models::deployments::DeployedModelResponse = server
+            .post("/admin/api/v1/models")
+            .add_header(&admin_headers[0].0, &admin_headers[0].1)
+            .add_header(&admin_headers[1].0, &admin_headers[1].1)
+            .json(&serde_json::json!({
+                "type": "standard",
+                "model_name": model_name,
+                "alias": alias,
+                "hosted_on": endpoint.id,
+                "tariffs": [{
+                    "name": "default",
+                    "input_price_per_token": "0.001",
+                    "output_price_per_token": "0.003",
+                    "api_key_purpose": "realtime"
+                }]
+            }))
+            .await
+            .json();
+        model_ids.push(model.id);
+    }
+
+    // Only the primary is granted to the user; the shadow needs no access of its own
+    server
+        .post(&format!("/admin/api/v1/groups/{}/models/{}", group.id, model_ids[0]))
+        .add_header(&admin_headers[0].0, &admin_headers[0].1)
+        .add_header(&admin_headers[1].0, &admin_headers[1].1)
+        .await;
+    server
+        .put(&format!("/admin/api/v1/models/{}/shadow", model_ids[0]))
+        .add_header(&admin_headers[0].0, &admin_headers[0].1)
+        .add_header(&admin_headers[1].0, &admin_headers[1].1)
+        .json(&serde_json::json!({ "shadow_model_id": model_ids[1], "fraction": 1.0 }))
+        .await
+        .assert_status_ok();
+
+    let key: crate::api::models::api_keys::ApiKeyResponse = server
+        .post(&format!("/admin/api/v1/users/{}/api-keys", user.id))
+        .add_header(&user_headers[0].0, &user_headers[0].1)
+        .add_header(&user_headers[1].0, &user_headers[1].1)
+        .json(&serde_json::json!({ "name": "Shadow Key", "purpose": "realtime" }))
+        .await
+        .json();
+
+    bg_services.sync_onwards_config(&pool).await.expect("Failed to sync onwards config");
+
+    let body = serde_json::json!({
+        "model": PRIMARY_ALIAS,
+        "messages": [{ "role": "user", "content": "hello" }]
+    });
+    let completion: serde_json::Value = await_chat_status(&server, &key.key, &body, 200).await.json();
+    assert_eq!(completion["choices"][0]["message"]["content"], "from the primary");
+
+    // The copy reaches the shadow's endpoint, addressed to the shadow model
+    let mut shadow_requests = Vec::new();
+    for _ in 0..200 {
+        shadow_requests = shadow_upstream.received_requests().await.unwrap_or_default();
+        if !shadow_requests.is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(25)).await;
+    }
+    assert_eq!(shadow_requests.len(), 1, "exactly one copy should be sent");
+    let copy: serde_json::Value = serde_json::from_slice(&shadow_requests[0].body).unwrap();
+    assert_eq!(copy["model"], "candidate-upstream");
+    assert_eq!(copy["messages"], body["messages"]);
+    assert_eq!(primary_upstream.received_requests().await.unwrap().len(), 1);
+
+    // Only the primary request is logged and billed
+    let mut usage: Vec<(String,)> = Vec::new();
+    for _ in 0..200 {
+        usage = sqlx::query_as("SELECT amount::text FROM credits_transactions WHERE user_id = $1 AND transaction_type = 'usage'")
+            .bind(user.id)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        if !usage.is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(25)).await;
+    }
+    assert_eq!(usage.len(), 1, "the user should be billed once: {usage:?}");
+    assert_eq!(
+        usage[0].0.parse::<rust_decimal::Decimal>().unwrap(),
+        rust_decimal::Decimal::new(14, 3)
+    );
+
+    let logged: Vec<(Option<String>,)> = sqlx::query_as("SELECT model FROM http_analytics WHERE status_code = 200")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(logged, vec![(Some(PRIMARY_ALIAS.to_string()),)]);
+}
@@ -216,6 +216,8 @@ pub fn create_test_config() -> crate::config::Config {
                 ..Default::default()
             },
             replica_pool: None,
+            analytics_replica_url: None,
+            analytics_pool: None,
             fusillade: crate::config::ComponentDb::Schema {
                 name: "fusillade".to_string(),
                 pool: PoolSettings {
@@ -251,6 +253,7 @@ pub fn create_test_config() -> crate::config::Config {
             },
         },
         slow_statement_threshold_ms: 1000,
+        skip_migrations: false,
         host: "127.0.0.1".to_string(),
         port: 0,
         dashboard_url: "http://localhost:3001".to_string(),
@@ -264,6 +267,11 @@ pub fn create_test_config() -> crate::config::Config {
             sync_interval: std::time::Duration::from_secs(60),
             default_models: None,
         }],
+        // Keep retries against unreachable test endpoints fast
+        endpoint_sync: crate::config::EndpointSyncConfig {
+            retry_initial_delay_milliseconds: 10,
+            ..Default::default()
+        },
         metadata: crate::config::Metadata::default(),
         payment: None,
         auth: crate::config::AuthConfig {
@@ -286,12 +294,15 @@ pub fn create_test_config() -> crate::config::Config {
             security: SecurityConfig::default(),
             default_user_roles: vec![crate::api::models::users::Role::StandardUser],
             rate_limits: crate::config::RateLimitTiersConfig::default(),
+            api_key_manager_role: None,
+            api_key_purposes: crate::config::ApiKeyPurposesConfig::default(),
         },
         enable_metrics: false,
         enable_request_logging: false,
         enable_analytics: true,
         analytics: crate::config::AnalyticsConfig::default(),
         enable_otel_export: false,
+        log: crate::config::LogConfig::default(),
         credits: crate::config::CreditsConfig::default(),
         batches: BatchConfig {
             enabled: true,
@@ -302,8 +313,24 @@ pub fn create_test_config() -> crate::config::Config {
             onwards_sync: OnwardsSyncConfig {
                 enabled: false,
                 fallback_interval_milliseconds: 10000,
+                ..Default::default()
+            },
+            probe_scheduler: ProbeSchedulerConfig {
+                enabled: false,
+                ..Default::default()
+            },
+            endpoint_auto_sync: crate::config::EndpointAutoSyncConfig {
+                enabled: false,
+                ..Default::default()
+            },
+            deployment_warmup: crate::config::DeploymentWarmupConfig {
+                enabled: false,
+                ..Default::default()
+            },
+            balance_checkpoints: crate::config::BalanceCheckpointConfig {
+                enabled: false,
+                ..Default::default()
             },
-            probe_scheduler: ProbeSchedulerConfig { enabled: false },
             batch_daemon: DaemonConfig {
                 enabled: DaemonEnabled::Never,
                 ..Default::default()
@@ -348,6 +375,7 @@ pub fn create_test_config() -> crate::config::Config {
         connections: Default::default(),
         responses: Default::default(),
         image_normalizer: Default::default(),
+        secrets: Default::default(),
         openapi: Default::default(),
         cache: Default::default(),
         keystore: None,
@@ -499,6 +527,7 @@ pub async fn get_system_user(pool: &mut PgConnection) -> UserResponse {
         auto_topup_monthly_limit: None,
         user_type: "individual".to_string(),
         zero_data_retention: false,
+        timezone: "UTC".to_string(),
         organizations: None,
         active_organization_id: None,
         onboarding_redirect_url: None,
@@ -523,12 +552,15 @@ pub async fn create_test_api_key_for_user(pool: &PgPool, user_id: UserId) -> Api
         ApiKeyCreate {
             name: format!("Test API Key {}", Uuid::new_v4().simple()),
             description: Some("Test description".to_string()),
-            purpose: ApiKeyPurpose::Realtime,
+            purpose: Some(ApiKeyPurpose::Realtime),
             requests_per_second: None,
             burst_size: None,
             member_id: None,
             spend_limit: None,
             spend_limit_interval: None,
+            allowed_model_ids: None,
+            denied_model_ids: None,
+            max_priority: None,
         },
     );
 
@@ -688,6 +720,7 @@ pub async fn create_test_org(pool: &PgPool, created_by: UserId) -> UserResponse
         auto_topup_monthly_limit: None,
         user_type: org.user_type,
         zero_data_retention: org.zero_data_retention,
+        timezone: org.timezone,
         organizations: None,
         active_organization_id: None,
         onboarding_redirect_url: None,
@@ -721,3 +754,111 @@ pub async fn create_test_model(pool: &PgPool, model_name: &str, alias: &str, end
     .expect("Failed to create test model");
     deployment_id
 }
+
+/// A model proxied end to end to a mock upstream, built by [`setup_proxied_model`].
+pub struct ProxiedModel {
+    pub admin: UserResponse,
+    pub admin_headers: Vec<(String, String)>,
+    /// A standard user holding `api_key`
+    pub user: UserResponse,
+    /// The deployment, as the admin API returned it
+    pub model: serde_json::Value,
+    /// A realtime key of `user`
+    pub api_key: String,
+}
+
+/// Create an endpoint for `upstream` and a model on it through the admin API,
+/// grant the model to the everyone group, give a new standard user a realtime
+/// key, and sync onwards.
+///
+/// `endpoint` and `model` are extra fields for the create requests; `model`
+/// must name an `alias`, which is also the model name unless it sets one. Wait
+/// for onwards to route the model with [`await_chat_status`].
+pub async fn setup_proxied_model(
+    server: &TestServer,
+    bg_services: &crate::BackgroundServices,
+    pool: &PgPool,
+    upstream: &wiremock::MockServer,
+    endpoint: serde_json::Value,
+    model: serde_json::Value,
+) -> ProxiedModel {
+    let admin = create_test_admin_user(pool, Role::PlatformManager).await;
+    let admin_headers = add_auth_headers(&admin);
+    let user = create_test_user(pool, Role::StandardUser).await;
+    let alias = model["alias"].as_str().expect("model needs an alias").to_string();
+
+    let mut endpoint_body = serde_json::json!({ "name": format!("{alias} endpoint"), "url": format!("{}/v1", upstream.uri()) });
+    endpoint_body
+        .as_object_mut()
+        .unwrap()
+        .extend(endpoint.as_object().cloned().unwrap_or_default());
+    let response = server
+        .post("/admin/api/v1/endpoints")
+        .add_header(&admin_headers[0].0, &admin_headers[0].1)
+        .add_header(&admin_headers[1].0, &admin_headers[1].1)
+        .json(&endpoint_body)
+        .await;
+    assert_eq!(response.status_code(), 201, "Failed to create endpoint: {}", response.text());
+    let endpoint: serde_json::Value = response.json();
+
+    let mut model_body = serde_json::json!({ "type": "standard", "model_name": alias, "hosted_on": endpoint["id"] });
+    model_body
+        .as_object_mut()
+        .unwrap()
+        .extend(model.as_object().cloned().unwrap_or_default());
+    let response = server
+        .post("/admin/api/v1/models")
+        .add_header(&admin_headers[0].0, &admin_headers[0].1)
+        .add_header(&admin_headers[1].0, &admin_headers[1].1)
+        .json(&model_body)
+        .await;
+    assert_eq!(response.status_code(), 200, "Failed to create model: {}", response.text());
+    let model: serde_json::Value = response.json();
+
+    let response = server
+        .post(&format!(
+            "/admin/api/v1/groups/00000000-0000-0000-0000-000000000000/models/{}",
+            model["id"].as_str().unwrap()
+        ))
+        .add_header(&admin_headers[0].0, &admin_headers[0].1)
+        .add_header(&admin_headers[1].0, &admin_headers[1].1)
+        .await;
+    assert_eq!(response.status_code(), 204, "Failed to add model to the everyone group");
+
+    let response = server
+        .post(&format!("/admin/api/v1/users/{}/api-keys", user.id))
+        .add_header(&admin_headers[0].0, &admin_headers[0].1)
+        .add_header(&admin_headers[1].0, &admin_headers[1].1)
+        .json(&serde_json::json!({ "purpose": "realtime", "name": format!("{alias} key") }))
+        .await;
+    assert_eq!(response.status_code(), 201, "Failed to create API key");
+    let api_key = response.json::<serde_json::Value>()["key"].as_str().unwrap().to_string();
+
+    bg_services.sync_onwards_config(pool).await.expect("Failed to sync onwards config");
+
+    ProxiedModel {
+        admin,
+        admin_headers,
+        user,
+        model,
+        api_key,
+    }
+}
+
+/// POST the chat completion `body` with `api_key` until it is answered with
+/// `status`, giving onwards time to apply a config sync. Panics after 50 tries.
+pub async fn await_chat_status(server: &TestServer, api_key: &str, body: &serde_json::Value, status: u16) -> axum_test::TestResponse {
+    for i in 0..50 {
+        let response = server
+            .post("/ai/v1/chat/completions")
+            .add_header("authorization", format!("Bearer {api_key}"))
+            .json(body)
+            .await;
+        if response.status_code().as_u16() == status {
+            return response;
+        }
+        assert!(i < 49, "expected {status}, got {}: {}", response.status_code(), response.text());
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    unreachable!()
+}
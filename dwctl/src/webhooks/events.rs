@@ -4,6 +4,10 @@
 //! Events are categorised into scopes:
 //! - **Own**: Events about the webhook owner's own resources (e.g., batch completion)
 //! - **Platform**: Platform-wide events visible to PlatformManagers (e.g., user creation)
+//!
+//! API key lifecycle events are platform events that a key's owner can also
+//! subscribe to from an own-scoped webhook, to automate provisioning around
+//! their own keys.
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -47,6 +51,12 @@ pub enum WebhookEventType {
     /// A new API key was created
     #[serde(rename = "api_key.created")]
     ApiKeyCreated,
+    /// An API key's secret was replaced
+    #[serde(rename = "api_key.rotated")]
+    ApiKeyRotated,
+    /// An API key was deleted
+    #[serde(rename = "api_key.deleted")]
+    ApiKeyDeleted,
 }
 
 impl WebhookEventType {
@@ -54,9 +64,25 @@ impl WebhookEventType {
     pub fn scope(&self) -> WebhookScope {
         match self {
             Self::BatchCompleted | Self::BatchFailed => WebhookScope::Own,
-            Self::UserCreated | Self::BatchCreated | Self::ApiKeyCreated => WebhookScope::Platform,
+            Self::UserCreated | Self::BatchCreated | Self::ApiKeyCreated | Self::ApiKeyRotated | Self::ApiKeyDeleted => {
+                WebhookScope::Platform
+            }
         }
     }
+
+    /// Whether this is an API key lifecycle event.
+    pub fn is_api_key_event(&self) -> bool {
+        matches!(self, Self::ApiKeyCreated | Self::ApiKeyRotated | Self::ApiKeyDeleted)
+    }
+
+    /// Whether a webhook with the given scope may subscribe to this event type.
+    ///
+    /// Every event can be subscribed to from its own scope. API key events can
+    /// also be subscribed to from an own-scoped webhook, which then receives
+    /// them for the webhook owner's keys.
+    pub fn subscribable_from(&self, scope: WebhookScope) -> bool {
+        self.scope() == scope || (scope == WebhookScope::Own && self.is_api_key_event())
+    }
 }
 
 impl std::fmt::Display for WebhookEventType {
@@ -67,6 +93,8 @@ impl std::fmt::Display for WebhookEventType {
             Self::UserCreated => write!(f, "user.created"),
             Self::BatchCreated => write!(f, "batch.created"),
             Self::ApiKeyCreated => write!(f, "api_key.created"),
+            Self::ApiKeyRotated => write!(f, "api_key.rotated"),
+            Self::ApiKeyDeleted => write!(f, "api_key.deleted"),
         }
     }
 }
@@ -81,6 +109,8 @@ impl std::str::FromStr for WebhookEventType {
             "user.created" => Ok(Self::UserCreated),
             "batch.created" => Ok(Self::BatchCreated),
             "api_key.created" => Ok(Self::ApiKeyCreated),
+            "api_key.rotated" => Ok(Self::ApiKeyRotated),
+            "api_key.deleted" => Ok(Self::ApiKeyDeleted),
             _ => Err(format!("Unknown event type: {}", s)),
         }
     }
@@ -167,18 +197,20 @@ impl WebhookEvent {
         }
     }
 
-    /// Create a webhook event for a new API key creation.
+    /// Create a webhook event for an API key being created, rotated or deleted.
     ///
     /// `user_id` is the key owner (may be an org), `created_by` is the human who created it.
-    pub fn api_key_created(key_id: Uuid, user_id: UserId, created_by: UserId, name: &str) -> Self {
+    /// The key's secret is never included.
+    pub fn api_key(event_type: WebhookEventType, key_id: Uuid, user_id: UserId, created_by: UserId, name: &str, purpose: &str) -> Self {
         Self {
-            event_type: WebhookEventType::ApiKeyCreated.to_string(),
+            event_type: event_type.to_string(),
             timestamp: Utc::now(),
             data: serde_json::json!({
                 "api_key_id": key_id,
                 "user_id": user_id,
                 "created_by": created_by,
                 "name": name,
+                "purpose": purpose,
             }),
         }
     }
@@ -200,6 +232,14 @@ mod tests {
             "api_key.created".parse::<WebhookEventType>().unwrap(),
             WebhookEventType::ApiKeyCreated
         );
+        assert_eq!(
+            "api_key.rotated".parse::<WebhookEventType>().unwrap(),
+            WebhookEventType::ApiKeyRotated
+        );
+        assert_eq!(
+            "api_key.deleted".parse::<WebhookEventType>().unwrap(),
+            WebhookEventType::ApiKeyDeleted
+        );
         assert!("invalid".parse::<WebhookEventType>().is_err());
     }
 
@@ -210,6 +250,22 @@ mod tests {
         assert_eq!(WebhookEventType::UserCreated.scope(), WebhookScope::Platform);
         assert_eq!(WebhookEventType::BatchCreated.scope(), WebhookScope::Platform);
         assert_eq!(WebhookEventType::ApiKeyCreated.scope(), WebhookScope::Platform);
+        assert_eq!(WebhookEventType::ApiKeyRotated.scope(), WebhookScope::Platform);
+        assert_eq!(WebhookEventType::ApiKeyDeleted.scope(), WebhookScope::Platform);
+    }
+
+    #[test]
+    fn test_api_key_events_subscribable_from_own_scope() {
+        for event_type in [
+            WebhookEventType::ApiKeyCreated,
+            WebhookEventType::ApiKeyRotated,
+            WebhookEventType::ApiKeyDeleted,
+        ] {
+            assert!(event_type.subscribable_from(WebhookScope::Own));
+            assert!(event_type.subscribable_from(WebhookScope::Platform));
+        }
+        assert!(!WebhookEventType::UserCreated.subscribable_from(WebhookScope::Own));
+        assert!(!WebhookEventType::BatchCompleted.subscribable_from(WebhookScope::Platform));
     }
 
     #[test]
@@ -270,7 +326,14 @@ mod tests {
         let key_id = Uuid::nil();
         let user_id = Uuid::nil();
         let created_by = Uuid::parse_str("11111111-1111-1111-1111-111111111111").unwrap();
-        let event = WebhookEvent::api_key_created(key_id, user_id, created_by, "My Test Key");
+        let event = WebhookEvent::api_key(
+            WebhookEventType::ApiKeyCreated,
+            key_id,
+            user_id,
+            created_by,
+            "My Test Key",
+            "realtime",
+        );
 
         let json = serde_json::to_string(&event).unwrap();
         assert!(json.contains("api_key.created"));
@@ -280,6 +343,8 @@ mod tests {
         assert_eq!(data["name"], "My Test Key");
         assert_eq!(data["user_id"], user_id.to_string());
         assert_eq!(data["created_by"], created_by.to_string());
+        assert_eq!(data["purpose"], "realtime");
+        assert!(data.get("secret").is_none());
     }
 
     #[test]
@@ -26,10 +26,9 @@ use uuid::Uuid;
 use super::{ArchiveOutcome, DaemonStorage, ModelFilter, ModelFilterState, Storage};
 use crate::PostgresStorageConfig;
 use crate::batch::{
-    Batch, BatchErrorDetails, BatchErrorItem, BatchId, BatchInput, BatchNotification,
-    BatchOutputItem, BatchResponseDetails, BatchStatus, File, FileContentItem, FileId,
-    FileMetadata, FileStreamItem, FileStreamResult, ListBatchesFilter, OutputFileType,
-    RequestTemplateInput, TemplateId,
+    Batch, BatchErrorItem, BatchId, BatchInput, BatchNotification, BatchOutputItem,
+    BatchResponseDetails, BatchStatus, File, FileContentItem, FileId, FileMetadata, FileStreamItem,
+    FileStreamResult, ListBatchesFilter, OutputFileType, RequestTemplateInput, TemplateId,
 };
 use crate::daemon::{
     AnyDaemonRecord, DaemonData, DaemonRecord, DaemonState, DaemonStatus, Dead, Initializing,
@@ -1219,6 +1218,42 @@ impl<P: PoolProvider> Storage for PostgresRequestManager<P> {
         Ok(count)
     }
 
+    async fn count_owner_active_batches(&self, owner: &str, strict: bool) -> Result<i64> {
+        let executor = if strict {
+            self.write_executor()
+        } else {
+            self.read_executor()
+        };
+
+        // Seeks idx_batches_created_by; the terminal-state columns are a
+        // residual over one creditor's batches.
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM batches
+            WHERE created_by = $1
+              AND completed_at IS NULL
+              AND failed_at IS NULL
+              AND cancelled_at IS NULL
+              AND cancelling_at IS NULL
+              AND deleted_at IS NULL
+              AND (expires_at IS NULL OR expires_at > NOW())
+            "#,
+            owner,
+        )
+        .fetch_one(executor)
+        .await
+        .map_err(|e| {
+            FusilladeError::Other(anyhow!(
+                "Failed to count active batches for creditor {}: {}",
+                owner,
+                e
+            ))
+        })?;
+
+        Ok(count)
+    }
+
     async fn get_pending_request_counts_by_model_and_window(
         &self,
         windows: &[(String, Option<i64>, i64)], // (label, start_secs, end_secs)
@@ -6763,7 +6798,7 @@ impl<P: PoolProvider> PostgresRequestManager<P> {
                             custom_id: row.custom_id,
                             response: BatchResponseDetails {
                                 status_code: row.response_status.unwrap_or(200),
-                                request_id: None, // Could be extracted from response if available
+                                request_id: Some(row.id.to_string()),
                                 body: response_body,
                             },
                             error: None,
@@ -6914,15 +6949,8 @@ impl<P: PoolProvider> PostgresRequestManager<P> {
                         last_failed_at = failed_at;
                         last_id = id;
 
-                        let error_item = BatchErrorItem {
-                            id: format!("batch_req_{}", id),
-                            custom_id,
-                            response: None,
-                            error: BatchErrorDetails {
-                                code: None, // Could parse from error field if structured
-                                message: error.unwrap_or_else(|| "Unknown error".to_string()),
-                            },
-                        };
+                        let error_item =
+                            BatchErrorItem::from_stored_error(id, custom_id, error.as_deref());
 
                         if tx
                             .send(Ok(FileContentItem::Error(error_item)))
@@ -13691,7 +13719,10 @@ mod tests {
         match error_item {
             FileContentItem::Error(error) => {
                 assert_eq!(error.custom_id, Some("req-3".to_string()));
-                assert_eq!(error.error.message, "Rate limit exceeded");
+                assert_eq!(
+                    error.error.as_ref().map(|e| e.message.as_str()),
+                    Some("Rate limit exceeded")
+                );
                 assert!(error.response.is_none());
                 assert!(error.id.starts_with("batch_req_"));
             }
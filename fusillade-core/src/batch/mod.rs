@@ -244,16 +244,75 @@ pub struct BatchOutputItem {
 }
 
 /// Batch error item - represents a failed request in OpenAI format.
+///
+/// As in OpenAI's error files, a request the upstream answered with an error
+/// status carries that `response` and a null `error`; a request that never got
+/// a response (timeout, network error, terminated batch) has a null
+/// `response` and an `error`.
 #[derive(Debug, Clone, Serialize, serde::Deserialize)]
 pub struct BatchErrorItem {
     /// Request ID
     pub id: String,
     /// Custom ID from the original request
     pub custom_id: Option<String>,
-    /// Response (should be null for errors)
-    pub response: Option<serde_json::Value>,
-    /// Error details
-    pub error: BatchErrorDetails,
+    /// The upstream's error response, if it sent one
+    pub response: Option<BatchResponseDetails>,
+    /// Error details, if there was no upstream response
+    pub error: Option<BatchErrorDetails>,
+}
+
+impl BatchErrorItem {
+    /// Build the error line for request `request_id` from its stored error,
+    /// a serialized [`FailureReason`](crate::request::FailureReason). Errors
+    /// stored in any other form are reported as their raw message.
+    pub fn from_stored_error(
+        request_id: Uuid,
+        custom_id: Option<String>,
+        stored_error: Option<&str>,
+    ) -> Self {
+        use crate::request::FailureReason;
+
+        let (response, error) = match stored_error.map(serde_json::from_str::<FailureReason>) {
+            Some(Ok(
+                FailureReason::RetriableHttpStatus { status, body }
+                | FailureReason::NonRetriableHttpStatus { status, body },
+            )) => (
+                Some(BatchResponseDetails {
+                    status_code: i16::try_from(status).unwrap_or(i16::MAX),
+                    request_id: Some(request_id.to_string()),
+                    body: serde_json::from_str(&body).unwrap_or(serde_json::Value::String(body)),
+                }),
+                None,
+            ),
+            Some(Ok(reason)) => (
+                None,
+                Some(BatchErrorDetails {
+                    code: Some(reason.metric_label().to_string()),
+                    message: reason.to_error_message(),
+                }),
+            ),
+            Some(Err(_)) => (
+                None,
+                Some(BatchErrorDetails {
+                    code: None,
+                    message: stored_error.unwrap_or_default().to_string(),
+                }),
+            ),
+            None => (
+                None,
+                Some(BatchErrorDetails {
+                    code: None,
+                    message: "Unknown error".to_string(),
+                }),
+            ),
+        };
+        Self {
+            id: format!("batch_req_{}", request_id),
+            custom_id,
+            response,
+            error,
+        }
+    }
 }
 
 /// Response details for a batch output item.
@@ -592,3 +651,57 @@ pub struct ModelTemplateStats {
     /// Total size of all request bodies in bytes
     pub total_body_bytes: i64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::FailureReason;
+
+    #[test]
+    fn error_item_matches_openai_error_lines() {
+        let id = Uuid::new_v4();
+
+        // An upstream error response is reported as the response, not an error
+        let http = serde_json::to_string(&FailureReason::NonRetriableHttpStatus {
+            status: 400,
+            body: r#"{"error":{"message":"bad"}}"#.to_string(),
+        })
+        .unwrap();
+        let line = serde_json::to_value(BatchErrorItem::from_stored_error(
+            id,
+            Some("req-1".to_string()),
+            Some(&http),
+        ))
+        .unwrap();
+        assert_eq!(
+            line,
+            serde_json::json!({
+                "id": format!("batch_req_{id}"),
+                "custom_id": "req-1",
+                "response": {
+                    "status_code": 400,
+                    "request_id": id.to_string(),
+                    "body": {"error": {"message": "bad"}}
+                },
+                "error": null
+            })
+        );
+
+        // A request with no upstream response gets an error code and message
+        let timeout = serde_json::to_string(&FailureReason::Timeout {
+            error: "deadline".to_string(),
+        })
+        .unwrap();
+        let line =
+            serde_json::to_value(BatchErrorItem::from_stored_error(id, None, Some(&timeout)))
+                .unwrap();
+        assert_eq!(line["response"], serde_json::Value::Null);
+        assert_eq!(line["error"]["code"], "timeout");
+        assert_eq!(line["error"]["message"], "Request timed out: deadline");
+
+        // Errors not stored as a failure reason keep their raw message
+        let line = BatchErrorItem::from_stored_error(id, None, Some("Rate limit exceeded"));
+        assert_eq!(line.error.unwrap().message, "Rate limit exceeded");
+        assert!(line.response.is_none());
+    }
+}
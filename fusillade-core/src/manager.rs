@@ -510,6 +510,17 @@ pub trait Storage: Send + Sync {
         cutoff: DateTime<Utc>,
         strict: bool,
     ) -> Result<i64>;
+
+    /// Count a creditor's active batches: not yet completed, failed, cancelled,
+    /// being cancelled, deleted or expired.
+    ///
+    /// Used by the control layer to cap how many batches one creditor can have
+    /// queued at once. Served by `idx_batches_created_by`.
+    ///
+    /// - `owner`: the batch `created_by` — the creditor id.
+    /// - `strict`: set `true` to read from the write pool and avoid read lag, so
+    ///   a just-created batch is reflected immediately (required for enforcement).
+    async fn count_owner_active_batches(&self, owner: &str, strict: bool) -> Result<i64>;
     ///
     /// Cancel one or more individual pending or in-progress requests.
     ///
@@ -2481,8 +2481,8 @@ mod service_tier {
     }
 }
 
-/// Tests for the per-creditor rolling-window count queries that back the
-/// control layer's unverified upload-volume cap (COR-481).
+/// Tests for the per-creditor count queries that back the control layer's
+/// unverified upload-volume cap (COR-481) and active-batch limit.
 mod unverified_volume_counts {
     use super::*;
 
@@ -2661,4 +2661,59 @@ mod unverified_volume_counts {
             "created_at < cutoff must be excluded"
         );
     }
+
+    #[sqlx::test(migrator = "fusillade_arsenal::MIGRATOR")]
+    #[test_log::test]
+    async fn test_count_owner_active_batches(pool: sqlx::PgPool) {
+        let manager = PostgresStore::with_client(
+            TestDbPools::new(pool.clone()).await.unwrap(),
+            Arc::new(MockHttpClient::new()),
+        );
+
+        seed_batch(&manager, "user-a", "24h", 1).await;
+        seed_batch(&manager, "user-a", "1h", 1).await;
+        seed_batch(&manager, "user-b", "24h", 1).await;
+
+        assert_eq!(
+            manager
+                .count_owner_active_batches("user-a", true)
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            manager
+                .count_owner_active_batches("user-b", true)
+                .await
+                .unwrap(),
+            1,
+            "other creditors must not bleed into the count"
+        );
+
+        // Cancelled batches no longer count as active.
+        let batches = manager
+            .list_batches(fusillade::batch::ListBatchesFilter {
+                created_by: Some("user-a".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        manager.cancel_batch(batches[0].id).await.unwrap();
+        assert_eq!(
+            manager
+                .count_owner_active_batches("user-a", true)
+                .await
+                .unwrap(),
+            1,
+            "cancelled batches must be excluded"
+        );
+
+        assert_eq!(
+            manager
+                .count_owner_active_batches("nobody", true)
+                .await
+                .unwrap(),
+            0
+        );
+    }
 }
@@ -0,0 +1,489 @@
+//! Declarative per-provider request and response body transforms.
+//!
+//! Some upstreams need small adjustments to OpenAI-shaped bodies: a renamed
+//! field, an injected system prompt, or a parameter they reject. A
+//! [`BodyTransformConfig`] attached to a provider lists field operations
+//! applied to the JSON request body before it is forwarded, and to the JSON
+//! response body before it is returned. Operations are data, never code.
+//!
+//! Fields are addressed with absolute JSON pointers (e.g. `/stream_options/include_usage`).
+//! Request bodies that are not JSON objects (e.g. multipart uploads) and
+//! streaming responses pass through untouched.
+//!
+//! A config can also name the function calling shape the provider accepts
+//! (`tool_calling`); see [`crate::tool_calling`]. That translation runs before
+//! the request operations, so they see the provider's shape.
+
+use axum::body::Body;
+use axum::http::{HeaderValue, Response, header};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+use tracing::{debug, warn};
+
+use crate::tool_calling::{self, ToolCallingStyle};
+
+const MAX_PATH_DEPTH: usize = 8;
+
+/// Field operations applied to a provider's request and response bodies, in order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BodyTransformConfig {
+    /// Applied to the JSON request body before it is sent upstream
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub request: Vec<BodyTransformOp>,
+    /// Applied to non-streaming JSON 2xx response bodies
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub response: Vec<BodyTransformOp>,
+    /// Function calling shape the provider accepts. Chat requests in the other
+    /// shape are translated, and their responses translated back.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calling: Option<ToolCallingStyle>,
+}
+
+/// A single declarative edit to a JSON body.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case", deny_unknown_fields)]
+pub enum BodyTransformOp {
+    /// Set `path` to `value`, replacing any existing value
+    Set { path: String, value: Value },
+    /// Set `path` to `value` only when the field is absent
+    SetDefault { path: String, value: Value },
+    /// Remove the field at `path` if present
+    Remove { path: String },
+    /// Move the field at `from` to `to` if present
+    Rename { from: String, to: String },
+    /// Insert `value` at the start of the array at `path` if present
+    /// (e.g. a system message into `/messages`)
+    Prepend { path: String, value: Value },
+}
+
+/// A transform that is misconfigured or cannot be applied to a body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BodyTransformError(String);
+
+impl BodyTransformError {
+    pub(crate) fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+
+    pub fn message(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for BodyTransformError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for BodyTransformError {}
+
+impl BodyTransformConfig {
+    /// Check that every operation addresses a valid JSON pointer.
+    pub fn validate(&self) -> Result<(), BodyTransformError> {
+        for op in self.request.iter().chain(&self.response) {
+            op.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Apply the tool calling translation and then the request operations to a
+    /// JSON body. Returns `Ok(None)` when there is nothing to do or the body is
+    /// not a JSON object.
+    pub fn apply_request(&self, body: &[u8]) -> Result<Option<Vec<u8>>, BodyTransformError> {
+        if self.tool_calling.is_none() {
+            return apply_to_bytes(&self.request, body);
+        }
+        let Ok(mut value) = serde_json::from_slice::<Value>(body) else {
+            return Ok(None);
+        };
+        if !value.is_object() {
+            return Ok(None);
+        }
+        let translate_to = self.tool_calling.filter(|provider| {
+            tool_calling::request_style(&value).is_some_and(|client| client != *provider)
+        });
+        if translate_to.is_none() && self.request.is_empty() {
+            return Ok(None);
+        }
+        if let Some(provider) = translate_to {
+            tool_calling::translate_request(&mut value, provider)?;
+        }
+        for op in &self.request {
+            op.apply(&mut value)?;
+        }
+        serde_json::to_vec(&value)
+            .map(Some)
+            .map_err(|error| BodyTransformError::new(error.to_string()))
+    }
+
+    /// The function calling shape a request uses when it differs from the
+    /// provider's, i.e. the shape its response must be translated back to.
+    pub fn tool_call_translation(&self, body: &[u8]) -> Option<ToolCallingStyle> {
+        let provider = self.tool_calling?;
+        let value = serde_json::from_slice::<Value>(body).ok()?;
+        tool_calling::request_style(&value).filter(|client| *client != provider)
+    }
+
+    /// Apply the response operations to a buffered, non-streaming JSON
+    /// response. Compressed bodies are decoded and returned uncompressed.
+    /// Failures are logged and leave the response unchanged.
+    pub async fn apply_response(&self, response: &mut Response<Body>) {
+        if self.response.is_empty() {
+            return;
+        }
+        let is_json = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| ct.contains("application/json"));
+        if !is_json {
+            return;
+        }
+
+        let content_encoding = response
+            .headers()
+            .get(header::CONTENT_ENCODING)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_lowercase());
+        let bytes =
+            match axum::body::to_bytes(std::mem::take(response.body_mut()), usize::MAX).await {
+                Ok(bytes) => bytes,
+                Err(_) => return,
+            };
+        let Some(decoded) = crate::response_id::decode_body(&bytes, content_encoding.as_deref())
+        else {
+            debug!("Failed to decompress response for body transform, passing through");
+            *response.body_mut() = Body::from(bytes);
+            return;
+        };
+
+        match apply_to_bytes(&self.response, &decoded) {
+            Ok(Some(transformed)) => {
+                let content_length = transformed.len();
+                *response.body_mut() = Body::from(transformed);
+                response.headers_mut().remove(header::CONTENT_ENCODING);
+                response.headers_mut().remove(header::TRANSFER_ENCODING);
+                response
+                    .headers_mut()
+                    .insert(header::CONTENT_LENGTH, HeaderValue::from(content_length));
+            }
+            Ok(None) => *response.body_mut() = Body::from(bytes),
+            Err(error) => {
+                warn!(%error, "Response body transform failed, passing response through");
+                *response.body_mut() = Body::from(bytes);
+            }
+        }
+    }
+}
+
+impl BodyTransformOp {
+    fn validate(&self) -> Result<(), BodyTransformError> {
+        match self {
+            Self::Set { path, .. }
+            | Self::SetDefault { path, .. }
+            | Self::Remove { path }
+            | Self::Prepend { path, .. } => pointer_segments(path).map(|_| ()),
+            Self::Rename { from, to } => {
+                let from_segments = pointer_segments(from)?;
+                let to_segments = pointer_segments(to)?;
+                if to_segments.starts_with(&from_segments)
+                    || from_segments.starts_with(&to_segments)
+                {
+                    return Err(BodyTransformError::new(format!(
+                        "rename from '{from}' to '{to}' must not nest one path inside the other"
+                    )));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn apply(&self, body: &mut Value) -> Result<(), BodyTransformError> {
+        match self {
+            Self::Set { path, value } => set(body, path, value.clone(), true),
+            Self::SetDefault { path, value } => set(body, path, value.clone(), false),
+            Self::Remove { path } => take(body, path).map(|_| ()),
+            Self::Rename { from, to } => match take(body, from)? {
+                Some(value) => set(body, to, value, true),
+                None => Ok(()),
+            },
+            Self::Prepend { path, value } => {
+                let (parent, key) = parent_mut(body, path, false)?;
+                match parent.and_then(|parent| parent.get_mut(&key)) {
+                    Some(Value::Array(items)) => {
+                        items.insert(0, value.clone());
+                        Ok(())
+                    }
+                    Some(_) => Err(BodyTransformError::new(format!("'{path}' is not an array"))),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+fn apply_to_bytes(
+    ops: &[BodyTransformOp],
+    body: &[u8],
+) -> Result<Option<Vec<u8>>, BodyTransformError> {
+    if ops.is_empty() {
+        return Ok(None);
+    }
+    let Ok(mut value) = serde_json::from_slice::<Value>(body) else {
+        return Ok(None);
+    };
+    if !value.is_object() {
+        return Ok(None);
+    }
+    for op in ops {
+        op.apply(&mut value)?;
+    }
+    serde_json::to_vec(&value)
+        .map(Some)
+        .map_err(|error| BodyTransformError::new(error.to_string()))
+}
+
+fn pointer_segments(path: &str) -> Result<Vec<String>, BodyTransformError> {
+    let Some(rest) = path.strip_prefix('/').filter(|rest| !rest.is_empty()) else {
+        return Err(BodyTransformError::new(format!(
+            "'{path}' must be an absolute JSON pointer"
+        )));
+    };
+    let segments: Vec<String> = rest
+        .split('/')
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect();
+    if segments.iter().any(String::is_empty) {
+        return Err(BodyTransformError::new(format!(
+            "'{path}' must not contain empty segments"
+        )));
+    }
+    if segments.len() > MAX_PATH_DEPTH {
+        return Err(BodyTransformError::new(format!(
+            "'{path}' must not exceed {MAX_PATH_DEPTH} path segments"
+        )));
+    }
+    Ok(segments)
+}
+
+/// Walk to the object holding the last segment of `path`. Missing
+/// intermediate objects are created when `create` is set; otherwise the
+/// parent is `None`.
+fn parent_mut<'a>(
+    body: &'a mut Value,
+    path: &str,
+    create: bool,
+) -> Result<(Option<&'a mut Map<String, Value>>, String), BodyTransformError> {
+    let mut segments = pointer_segments(path)?;
+    let key = segments
+        .pop()
+        .expect("pointer_segments returns at least one segment");
+    let mut current = body;
+    for segment in segments {
+        let object = current.as_object_mut().ok_or_else(|| {
+            BodyTransformError::new(format!("'{path}' crosses a non-object field"))
+        })?;
+        current = if create {
+            object
+                .entry(segment)
+                .or_insert_with(|| Value::Object(Map::new()))
+        } else {
+            match object.get_mut(&segment) {
+                Some(next) => next,
+                None => return Ok((None, key)),
+            }
+        };
+    }
+    let object = current
+        .as_object_mut()
+        .ok_or_else(|| BodyTransformError::new(format!("'{path}' crosses a non-object field")))?;
+    Ok((Some(object), key))
+}
+
+fn set(
+    body: &mut Value,
+    path: &str,
+    value: Value,
+    replace: bool,
+) -> Result<(), BodyTransformError> {
+    let (parent, key) = parent_mut(body, path, true)?;
+    let parent = parent.expect("parent is always created");
+    if replace || !parent.contains_key(&key) {
+        parent.insert(key, value);
+    }
+    Ok(())
+}
+
+fn take(body: &mut Value, path: &str) -> Result<Option<Value>, BodyTransformError> {
+    let (parent, key) = parent_mut(body, path, false)?;
+    Ok(parent.and_then(|parent| parent.remove(&key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse(value: Value) -> BodyTransformConfig {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn apply(config: &BodyTransformConfig, body: Value) -> Value {
+        let bytes = config
+            .apply_request(&serde_json::to_vec(&body).unwrap())
+            .unwrap()
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn test_request_ops_applied_in_order() {
+        let config = parse(json!({
+            "request": [
+                { "op": "rename", "from": "/max_completion_tokens", "to": "/max_tokens" },
+                { "op": "remove", "path": "/logit_bias" },
+                { "op": "set", "path": "/stream_options/include_usage", "value": true },
+                { "op": "set_default", "path": "/temperature", "value": 0.2 },
+                { "op": "set_default", "path": "/top_p", "value": 0.9 },
+                { "op": "prepend", "path": "/messages", "value": { "role": "system", "content": "Be brief." } }
+            ]
+        }));
+        config.validate().unwrap();
+
+        let body = apply(
+            &config,
+            json!({
+                "model": "m",
+                "messages": [{ "role": "user", "content": "hi" }],
+                "max_completion_tokens": 100,
+                "logit_bias": { "1": 5 },
+                "top_p": 0.5
+            }),
+        );
+
+        assert_eq!(
+            body,
+            json!({
+                "model": "m",
+                "messages": [
+                    { "role": "system", "content": "Be brief." },
+                    { "role": "user", "content": "hi" }
+                ],
+                "max_tokens": 100,
+                "stream_options": { "include_usage": true },
+                "temperature": 0.2,
+                "top_p": 0.5
+            })
+        );
+    }
+
+    #[test]
+    fn test_missing_fields_are_skipped() {
+        let config = parse(json!({
+            "request": [
+                { "op": "remove", "path": "/a/b" },
+                { "op": "rename", "from": "/missing", "to": "/other" },
+                { "op": "prepend", "path": "/messages", "value": 1 }
+            ]
+        }));
+
+        assert_eq!(
+            apply(&config, json!({ "prompt": "hi" })),
+            json!({ "prompt": "hi" })
+        );
+    }
+
+    #[test]
+    fn test_non_object_bodies_pass_through() {
+        let config = parse(json!({ "request": [{ "op": "remove", "path": "/a" }] }));
+
+        assert_eq!(config.apply_request(b"not json").unwrap(), None);
+        assert_eq!(config.apply_request(b"[1, 2]").unwrap(), None);
+        assert_eq!(
+            BodyTransformConfig::default().apply_request(b"{}").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_type_mismatches_are_errors() {
+        let config = parse(json!({
+            "request": [{ "op": "prepend", "path": "/messages", "value": 1 }]
+        }));
+        let error = config.apply_request(br#"{"messages": "hi"}"#).unwrap_err();
+        assert!(error.message().contains("not an array"));
+
+        let config = parse(json!({
+            "request": [{ "op": "set", "path": "/prompt/text", "value": 1 }]
+        }));
+        let error = config.apply_request(br#"{"prompt": "hi"}"#).unwrap_err();
+        assert!(error.message().contains("non-object"));
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_paths() {
+        for op in [
+            json!({ "op": "remove", "path": "model" }),
+            json!({ "op": "remove", "path": "/" }),
+            json!({ "op": "remove", "path": "/a//b" }),
+            json!({ "op": "remove", "path": "/a/b/c/d/e/f/g/h/i" }),
+            json!({ "op": "rename", "from": "/a", "to": "/a/b" }),
+        ] {
+            let config = parse(json!({ "response": [op] }));
+            assert!(
+                config.validate().is_err(),
+                "{:?} should be rejected",
+                config
+            );
+        }
+
+        let unknown: Result<BodyTransformConfig, _> =
+            serde_json::from_value(json!({ "request": [{ "op": "eval", "path": "/a" }] }));
+        assert!(unknown.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_response_transform_rewrites_json_body() {
+        let config = parse(json!({
+            "response": [
+                { "op": "remove", "path": "/provider_metadata" },
+                { "op": "rename", "from": "/usage/input_tokens", "to": "/usage/prompt_tokens" }
+            ]
+        }));
+        let mut response = Response::builder()
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                r#"{"id":"x","provider_metadata":{"a":1},"usage":{"input_tokens":3}}"#,
+            ))
+            .unwrap();
+
+        config.apply_response(&mut response).await;
+
+        let content_length = response.headers()[header::CONTENT_LENGTH].clone();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(content_length, bytes.len().to_string().as_str());
+        let body: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body, json!({ "id": "x", "usage": { "prompt_tokens": 3 } }));
+    }
+
+    #[tokio::test]
+    async fn test_response_transform_skips_non_json() {
+        let config = parse(json!({ "response": [{ "op": "remove", "path": "/id" }] }));
+        let mut response = Response::builder()
+            .header(header::CONTENT_TYPE, "text/event-stream")
+            .body(Body::from("data: {\"id\":\"x\"}\n\n"))
+            .unwrap();
+
+        config.apply_response(&mut response).await;
+
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&bytes[..], b"data: {\"id\":\"x\"}\n\n");
+    }
+}
@@ -0,0 +1,307 @@
+//! Per-endpoint circuit breaking
+//!
+//! When [`AppState::with_circuit_breaker`](crate::AppState::with_circuit_breaker)
+//! is configured, every upstream URL gets a breaker. After
+//! `failure_threshold` consecutive failures (connection errors, timeouts or
+//! 5xx responses) within `window_secs`, the breaker opens and requests to that
+//! endpoint are rejected without being sent for `cooldown_secs`. A pool with
+//! fallback enabled moves straight on to its next provider; otherwise the
+//! caller gets a 503. Once the cooldown has passed a single probe request is
+//! let through: success closes the breaker, failure opens it again.
+//!
+//! Breakers live in the app state rather than in the targets, so they survive
+//! config reloads.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+/// Thresholds shared by every endpoint's breaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures that open the breaker.
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    /// Failures further apart than this (in seconds) are not counted together.
+    #[serde(default = "default_window_secs")]
+    pub window_secs: u64,
+    /// How long (in seconds) an open breaker rejects requests before a probe is let through.
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: default_failure_threshold(),
+            window_secs: default_window_secs(),
+            cooldown_secs: default_cooldown_secs(),
+        }
+    }
+}
+
+fn default_failure_threshold() -> u32 {
+    5
+}
+
+fn default_window_secs() -> u64 {
+    30
+}
+
+fn default_cooldown_secs() -> u64 {
+    30
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BreakerState {
+    /// Requests flow; `failures` consecutive failures since `window_start`.
+    Closed {
+        failures: u32,
+        window_start: Instant,
+    },
+    /// Requests are rejected until `until`.
+    Open { until: Instant },
+    /// One probe request is in flight since `probe_started`.
+    HalfOpen { probe_started: Instant },
+}
+
+/// The breaker for a single endpoint.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(BreakerState::Closed {
+                failures: 0,
+                window_start: Instant::now(),
+            }),
+        }
+    }
+
+    fn cooldown(&self) -> Duration {
+        Duration::from_secs(self.config.cooldown_secs)
+    }
+
+    /// Whether a request may be sent to the endpoint now. Once the cooldown
+    /// has passed this admits one probe; the caller must then report its
+    /// outcome with [`record_success`](Self::record_success) or
+    /// [`record_failure`](Self::record_failure).
+    pub fn try_acquire(&self) -> bool {
+        self.try_acquire_at(Instant::now())
+    }
+
+    pub fn record_success(&self) {
+        self.record_success_at(Instant::now())
+    }
+
+    pub fn record_failure(&self) {
+        self.record_failure_at(Instant::now())
+    }
+
+    /// Whether the breaker is currently rejecting requests.
+    pub fn is_open(&self) -> bool {
+        matches!(
+            *self.state.lock().unwrap(),
+            BreakerState::Open { until } if Instant::now() < until
+        )
+    }
+
+    fn try_acquire_at(&self, now: Instant) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match *state {
+            BreakerState::Closed { .. } => true,
+            BreakerState::Open { until } if now < until => false,
+            // A probe that never reported back (e.g. the client disconnected)
+            // must not hold the breaker half-open forever.
+            BreakerState::HalfOpen { probe_started }
+                if now.duration_since(probe_started) < self.cooldown() =>
+            {
+                false
+            }
+            BreakerState::Open { .. } | BreakerState::HalfOpen { .. } => {
+                *state = BreakerState::HalfOpen { probe_started: now };
+                true
+            }
+        }
+    }
+
+    fn record_success_at(&self, now: Instant) {
+        let mut state = self.state.lock().unwrap();
+        // Stragglers sent before the breaker opened don't close it early
+        if matches!(*state, BreakerState::Open { .. }) {
+            return;
+        }
+        *state = BreakerState::Closed {
+            failures: 0,
+            window_start: now,
+        };
+    }
+
+    fn record_failure_at(&self, now: Instant) {
+        let mut state = self.state.lock().unwrap();
+        let (failures, window_start) = match *state {
+            BreakerState::Open { .. } => return,
+            BreakerState::HalfOpen { .. } => {
+                *state = BreakerState::Open {
+                    until: now + self.cooldown(),
+                };
+                return;
+            }
+            BreakerState::Closed {
+                failures,
+                window_start,
+            } if now.duration_since(window_start)
+                <= Duration::from_secs(self.config.window_secs) =>
+            {
+                (failures + 1, window_start)
+            }
+            BreakerState::Closed { .. } => (1, now),
+        };
+
+        *state = if failures >= self.config.failure_threshold {
+            metrics::counter!("onwards_circuit_breaker_opened_total").increment(1);
+            BreakerState::Open {
+                until: now + self.cooldown(),
+            }
+        } else {
+            BreakerState::Closed {
+                failures,
+                window_start,
+            }
+        };
+    }
+}
+
+/// Breakers for every endpoint, keyed by upstream URL.
+#[derive(Debug, Clone)]
+pub struct CircuitBreakers {
+    config: CircuitBreakerConfig,
+    breakers: Arc<DashMap<String, Arc<CircuitBreaker>>>,
+}
+
+impl CircuitBreakers {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            breakers: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// The breaker for `url`, created closed on first use.
+    pub fn for_endpoint(&self, url: &str) -> Arc<CircuitBreaker> {
+        if let Some(breaker) = self.breakers.get(url) {
+            return Arc::clone(breaker.value());
+        }
+        Arc::clone(
+            self.breakers
+                .entry(url.to_string())
+                .or_insert_with(|| Arc::new(CircuitBreaker::new(self.config)))
+                .value(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker() -> CircuitBreaker {
+        CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 3,
+            window_secs: 10,
+            cooldown_secs: 5,
+        })
+    }
+
+    #[test]
+    fn test_opens_after_consecutive_failures() {
+        let breaker = breaker();
+        let now = Instant::now();
+
+        breaker.record_failure_at(now);
+        breaker.record_failure_at(now);
+        assert!(breaker.try_acquire_at(now));
+
+        breaker.record_failure_at(now);
+        assert!(!breaker.try_acquire_at(now));
+        assert!(!breaker.try_acquire_at(now + Duration::from_secs(4)));
+    }
+
+    #[test]
+    fn test_success_resets_failure_count() {
+        let breaker = breaker();
+        let now = Instant::now();
+
+        breaker.record_failure_at(now);
+        breaker.record_failure_at(now);
+        breaker.record_success_at(now);
+        breaker.record_failure_at(now);
+        breaker.record_failure_at(now);
+        assert!(breaker.try_acquire_at(now));
+    }
+
+    #[test]
+    fn test_failures_outside_window_are_not_counted_together() {
+        let breaker = breaker();
+        let now = Instant::now();
+
+        breaker.record_failure_at(now);
+        breaker.record_failure_at(now);
+        breaker.record_failure_at(now + Duration::from_secs(11));
+        assert!(breaker.try_acquire_at(now + Duration::from_secs(11)));
+    }
+
+    #[test]
+    fn test_half_open_admits_one_probe() {
+        let breaker = breaker();
+        let now = Instant::now();
+        for _ in 0..3 {
+            breaker.record_failure_at(now);
+        }
+
+        let after_cooldown = now + Duration::from_secs(5);
+        assert!(breaker.try_acquire_at(after_cooldown));
+        assert!(!breaker.try_acquire_at(after_cooldown));
+
+        // A successful probe closes the breaker
+        breaker.record_success_at(after_cooldown);
+        assert!(breaker.try_acquire_at(after_cooldown));
+        assert!(breaker.try_acquire_at(after_cooldown));
+    }
+
+    #[test]
+    fn test_failed_probe_reopens() {
+        let breaker = breaker();
+        let now = Instant::now();
+        for _ in 0..3 {
+            breaker.record_failure_at(now);
+        }
+
+        let after_cooldown = now + Duration::from_secs(5);
+        assert!(breaker.try_acquire_at(after_cooldown));
+        breaker.record_failure_at(after_cooldown);
+        assert!(!breaker.try_acquire_at(after_cooldown + Duration::from_secs(4)));
+        assert!(breaker.try_acquire_at(after_cooldown + Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_breakers_are_per_endpoint() {
+        let breakers = CircuitBreakers::new(CircuitBreakerConfig {
+            failure_threshold: 1,
+            ..Default::default()
+        });
+
+        breakers
+            .for_endpoint("https://a.example.com/")
+            .record_failure();
+        assert!(breakers.for_endpoint("https://a.example.com/").is_open());
+        assert!(!breakers.for_endpoint("https://b.example.com/").is_open());
+    }
+}
@@ -7,12 +7,15 @@ use std::time::Duration;
 
 use async_trait::async_trait;
 use axum::response::IntoResponse;
-use hyper_util::{client::legacy::Client, rt::TokioExecutor};
+use hyper_util::{
+    client::legacy::{Client, connect::HttpConnector},
+    rt::TokioExecutor,
+};
 
-pub type HyperClient = Client<
-    hyper_tls::HttpsConnector<hyper_util::client::legacy::connect::HttpConnector>,
-    axum::body::Body,
->;
+use crate::target::HttpPoolConfig;
+use crate::tls::TlsConfig;
+
+pub type HyperClient = Client<hyper_tls::HttpsConnector<HttpConnector>, axum::body::Body>;
 
 #[async_trait]
 pub trait HttpClient: std::fmt::Debug {
@@ -45,7 +48,36 @@ pub fn create_hyper_client(
     pool_max_idle_per_host: usize,
     pool_idle_timeout_secs: u64,
 ) -> HyperClient {
-    let mut http_connector = hyper_util::client::legacy::connect::HttpConnector::new();
+    create_hyper_client_with_config(&HttpPoolConfig {
+        max_idle_per_host: pool_max_idle_per_host,
+        idle_timeout_secs: pool_idle_timeout_secs,
+        ..Default::default()
+    })
+}
+
+/// Create a hyper HTTP client from an [`HttpPoolConfig`]
+///
+/// With `http2_prior_knowledge` set, connections are opened straight as HTTP/2
+/// (no upgrade or ALPN), with adaptive flow control windows so large responses
+/// aren't throttled by the default 64KB window.
+pub fn create_hyper_client_with_config(config: &HttpPoolConfig) -> HyperClient {
+    let https = hyper_tls::HttpsConnector::new_with_connector(http_connector());
+    build_client(config, https)
+}
+
+/// Create a hyper HTTP client from an [`HttpPoolConfig`] that connects with
+/// the given TLS settings, e.g. to trust a private CA.
+pub fn create_hyper_client_with_tls(
+    config: &HttpPoolConfig,
+    tls: &TlsConfig,
+) -> Result<HyperClient, native_tls::Error> {
+    let connector = tokio_native_tls::TlsConnector::from(tls.connector()?);
+    let https = hyper_tls::HttpsConnector::from((http_connector(), connector));
+    Ok(build_client(config, https))
+}
+
+fn http_connector() -> HttpConnector {
+    let mut http_connector = HttpConnector::new();
 
     // Allow HTTPS URIs (HttpConnector enforces HTTP-only by default)
     http_connector.enforce_http(false);
@@ -55,19 +87,26 @@ pub fn create_hyper_client(
     // failures (Linux default). This keeps conntrack entries alive and detects
     // zombies within ~105s.
     http_connector.set_keepalive(Some(Duration::from_secs(60)));
+    http_connector
+}
 
-    let https = hyper_tls::HttpsConnector::new_with_connector(http_connector);
-
+fn build_client(
+    config: &HttpPoolConfig,
+    https: hyper_tls::HttpsConnector<HttpConnector>,
+) -> HyperClient {
     tracing::info!(
-        "Creating HTTP client with connection pool: max_idle_per_host={}, idle_timeout={}s, tcp_keepalive=60s",
-        pool_max_idle_per_host,
-        pool_idle_timeout_secs
+        "Creating HTTP client with connection pool: max_idle_per_host={}, idle_timeout={}s, tcp_keepalive=60s, http2_prior_knowledge={}",
+        config.max_idle_per_host,
+        config.idle_timeout_secs,
+        config.http2_prior_knowledge
     );
 
     Client::builder(TokioExecutor::new())
-        .pool_idle_timeout(Duration::from_secs(pool_idle_timeout_secs))
-        .pool_max_idle_per_host(pool_max_idle_per_host)
+        .pool_idle_timeout(Duration::from_secs(config.idle_timeout_secs))
+        .pool_max_idle_per_host(config.max_idle_per_host)
         .pool_timer(hyper_util::rt::TokioTimer::new())
+        .http2_only(config.http2_prior_knowledge)
+        .http2_adaptive_window(config.http2_prior_knowledge)
         .build(https)
 }
 
@@ -134,4 +173,66 @@ mod tests {
         }
         // If it somehow succeeds, that's also fine (means HTTPS worked)
     }
+
+    #[tokio::test]
+    async fn test_http2_prior_knowledge_streams_response() {
+        use axum::body::Bytes;
+        use http_body_util::BodyExt;
+        use hyper_util::rt::TokioIo;
+
+        // An h2c-only upstream: it rejects anything that isn't HTTP/2 from the
+        // first byte, and streams its response as separate SSE events.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let service = hyper::service::service_fn(
+                |req: hyper::Request<hyper::body::Incoming>| async move {
+                    assert_eq!(req.version(), hyper::Version::HTTP_2);
+                    let events = [
+                        "data: {\"n\":1}\n\n",
+                        "data: {\"n\":2}\n\n",
+                        "data: [DONE]\n\n",
+                    ]
+                    .map(|event| Ok::<_, std::convert::Infallible>(Bytes::from(event)));
+                    let body = axum::body::Body::from_stream(futures_util::stream::iter(events));
+                    Ok::<_, std::convert::Infallible>(
+                        hyper::Response::builder()
+                            .header("content-type", "text/event-stream")
+                            .body(body)
+                            .unwrap(),
+                    )
+                },
+            );
+            hyper::server::conn::http2::Builder::new(TokioExecutor::new())
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+                .unwrap();
+        });
+
+        let client = create_hyper_client_with_config(&HttpPoolConfig {
+            http2_prior_knowledge: true,
+            ..Default::default()
+        });
+        let request = axum::extract::Request::builder()
+            .uri(format!("http://{addr}/v1/chat/completions"))
+            .method("POST")
+            .body(axum::body::Body::from(r#"{"stream":true}"#))
+            .unwrap();
+
+        let response = client.request(request).await.unwrap();
+        assert_eq!(response.version(), hyper::Version::HTTP_2);
+
+        let mut body = response.into_body();
+        let mut received = Vec::new();
+        while let Some(frame) = body.frame().await {
+            if let Ok(data) = frame.unwrap().into_data() {
+                received.extend_from_slice(&data);
+            }
+        }
+        assert_eq!(
+            String::from_utf8(received).unwrap(),
+            "data: {\"n\":1}\n\ndata: {\"n\":2}\n\ndata: [DONE]\n\n"
+        );
+    }
 }
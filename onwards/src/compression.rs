@@ -0,0 +1,134 @@
+//! Gzip on upstream connections
+//!
+//! When [`HttpPoolConfig::gzip`](crate::target::HttpPoolConfig::gzip) is set,
+//! requests from callers that expressed no encoding preference ask the upstream
+//! for gzip. The compressed response is decoded here, chunk by chunk, before
+//! anything else sees it, so streaming, response rewriting and callers all get
+//! the plain body. Callers that sent their own `Accept-Encoding` are left alone.
+
+use std::io::Write;
+
+use axum::body::{Body, Bytes};
+use axum::http::header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH};
+use axum::http::{HeaderMap, HeaderValue};
+use axum::response::Response;
+use flate2::write::GzDecoder;
+use futures_util::{Stream, StreamExt};
+
+/// Ask the upstream for gzip unless the caller already chose encodings.
+/// Returns whether the header was added, i.e. whether the response is ours to decode.
+pub fn request_gzip(headers: &mut HeaderMap) -> bool {
+    if headers.contains_key(ACCEPT_ENCODING) {
+        return false;
+    }
+    headers.insert(ACCEPT_ENCODING, HeaderValue::from_static("gzip"));
+    true
+}
+
+/// Decompress a gzip-encoded response as it streams. Other responses are
+/// returned unchanged.
+pub fn decode_gzip_response(response: Response) -> Response {
+    let is_gzip = response
+        .headers()
+        .get(CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.trim().eq_ignore_ascii_case("gzip"));
+    if !is_gzip {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    parts.headers.remove(CONTENT_ENCODING);
+    parts.headers.remove(CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from_stream(gunzip(body.into_data_stream())))
+}
+
+/// Decode a gzip byte stream, yielding whatever each chunk decompresses to so
+/// SSE events are forwarded as soon as they arrive.
+fn gunzip<S>(chunks: S) -> impl Stream<Item = Result<Bytes, std::io::Error>> + Send
+where
+    S: Stream<Item = Result<Bytes, axum::Error>> + Send + 'static,
+{
+    async_stream::try_stream! {
+        let mut chunks = Box::pin(chunks);
+        let mut decoder = GzDecoder::new(Vec::new());
+        while let Some(chunk) = chunks.next().await {
+            let chunk = chunk.map_err(std::io::Error::other)?;
+            decoder.write_all(&chunk)?;
+            decoder.flush()?;
+            let decoded = std::mem::take(decoder.get_mut());
+            if !decoded.is_empty() {
+                yield Bytes::from(decoded);
+            }
+        }
+        let rest = decoder.finish()?;
+        if !rest.is_empty() {
+            yield Bytes::from(rest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_request_gzip_respects_caller_preference() {
+        let mut headers = HeaderMap::new();
+        assert!(request_gzip(&mut headers));
+        assert_eq!(headers.get(ACCEPT_ENCODING).unwrap(), "gzip");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT_ENCODING, HeaderValue::from_static("br"));
+        assert!(!request_gzip(&mut headers));
+        assert_eq!(headers.get(ACCEPT_ENCODING).unwrap(), "br");
+    }
+
+    #[tokio::test]
+    async fn test_decode_gzip_response_across_chunk_boundaries() {
+        let events = "data: {\"id\":1}\n\ndata: {\"id\":2}\n\ndata: [DONE]\n\n".repeat(20);
+        let compressed = gzip(events.as_bytes());
+
+        // Split the compressed body into small chunks, so the gzip header and
+        // deflate blocks straddle chunk boundaries.
+        let chunks: Vec<Result<Vec<u8>, std::io::Error>> =
+            compressed.chunks(7).map(|c| Ok(c.to_vec())).collect();
+        let response = Response::builder()
+            .header(CONTENT_ENCODING, "gzip")
+            .header(CONTENT_LENGTH, compressed.len())
+            .body(Body::from_stream(futures_util::stream::iter(chunks)))
+            .unwrap();
+
+        let response = decode_gzip_response(response);
+        assert!(response.headers().get(CONTENT_ENCODING).is_none());
+        assert!(response.headers().get(CONTENT_LENGTH).is_none());
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, events.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_decode_gzip_response_ignores_other_encodings() {
+        let response = Response::builder()
+            .header(CONTENT_ENCODING, "br")
+            .body(Body::from("not gzip"))
+            .unwrap();
+
+        let response = decode_gzip_response(response);
+        assert_eq!(response.headers().get(CONTENT_ENCODING).unwrap(), "br");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, "not gzip");
+    }
+}
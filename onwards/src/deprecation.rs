@@ -0,0 +1,162 @@
+//! Warnings for deprecated chat completions request fields.
+//!
+//! Requests using a deprecated field are still forwarded as sent (or
+//! translated, where a provider's `tool_calling` setting asks for it; see
+//! [`crate::tool_calling`]). With a deprecation header set on the app state,
+//! the response also carries that header listing each deprecated field with
+//! its replacement, so clients can find what to migrate without reading
+//! their provider's changelog. The value is a structured field list:
+//!
+//! ```text
+//! "functions";replacement="tools", "max_tokens";replacement="max_completion_tokens"
+//! ```
+
+use axum::http::HeaderValue;
+use serde_json::Value;
+
+/// A deprecated request field and what replaces it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deprecation {
+    pub field: &'static str,
+    pub replacement: &'static str,
+}
+
+const FUNCTIONS: Deprecation = Deprecation {
+    field: "functions",
+    replacement: "tools",
+};
+const FUNCTION_CALL: Deprecation = Deprecation {
+    field: "function_call",
+    replacement: "tool_choice",
+};
+const MAX_TOKENS: Deprecation = Deprecation {
+    field: "max_tokens",
+    replacement: "max_completion_tokens",
+};
+const MESSAGE_FUNCTION_CALL: Deprecation = Deprecation {
+    field: "messages[].function_call",
+    replacement: "messages[].tool_calls",
+};
+const FUNCTION_ROLE: Deprecation = Deprecation {
+    field: "messages[].role=function",
+    replacement: "messages[].role=tool",
+};
+
+/// The deprecated fields a request body uses. Only chat completions requests
+/// are checked: `max_tokens` is current on the legacy completions API.
+pub fn detect(path: &str, body: &[u8]) -> Vec<Deprecation> {
+    if !path.trim_end_matches('/').ends_with("/chat/completions") {
+        return Vec::new();
+    }
+    let Ok(Value::Object(object)) = serde_json::from_slice::<Value>(body) else {
+        return Vec::new();
+    };
+
+    let mut found: Vec<Deprecation> = [
+        (object.contains_key("functions"), FUNCTIONS),
+        (object.contains_key("function_call"), FUNCTION_CALL),
+        (object.contains_key("max_tokens"), MAX_TOKENS),
+    ]
+    .into_iter()
+    .filter_map(|(present, deprecation)| present.then_some(deprecation))
+    .collect();
+
+    if let Some(messages) = object.get("messages").and_then(Value::as_array) {
+        if messages
+            .iter()
+            .any(|message| message.get("function_call").is_some())
+        {
+            found.push(MESSAGE_FUNCTION_CALL);
+        }
+        if messages
+            .iter()
+            .any(|message| message.get("role").and_then(Value::as_str) == Some("function"))
+        {
+            found.push(FUNCTION_ROLE);
+        }
+    }
+    found
+}
+
+/// The deprecation header value for `deprecations`, or `None` if there are none.
+pub fn header_value(deprecations: &[Deprecation]) -> Option<HeaderValue> {
+    if deprecations.is_empty() {
+        return None;
+    }
+    let value = deprecations
+        .iter()
+        .map(|d| format!("\"{}\";replacement=\"{}\"", d.field, d.replacement))
+        .collect::<Vec<_>>()
+        .join(", ");
+    HeaderValue::from_str(&value).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn detect_json(path: &str, body: Value) -> Vec<&'static str> {
+        detect(path, &serde_json::to_vec(&body).unwrap())
+            .into_iter()
+            .map(|d| d.field)
+            .collect()
+    }
+
+    #[test]
+    fn test_detects_legacy_function_calling_and_max_tokens() {
+        let fields = detect_json(
+            "/v1/chat/completions",
+            json!({
+                "model": "m",
+                "messages": [
+                    {"role": "user", "content": "Weather?"},
+                    {"role": "assistant", "content": null, "function_call": {"name": "get_weather", "arguments": "{}"}},
+                    {"role": "function", "name": "get_weather", "content": "sunny"}
+                ],
+                "functions": [{"name": "get_weather"}],
+                "function_call": "auto",
+                "max_tokens": 64
+            }),
+        );
+        assert_eq!(
+            fields,
+            vec![
+                "functions",
+                "function_call",
+                "max_tokens",
+                "messages[].function_call",
+                "messages[].role=function"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_current_fields_and_other_paths_have_no_warnings() {
+        assert!(
+            detect_json(
+                "/v1/chat/completions",
+                json!({
+                    "messages": [{"role": "tool", "tool_call_id": "call_1", "content": "sunny"}],
+                    "tools": [{"type": "function", "function": {"name": "get_weather"}}],
+                    "max_completion_tokens": 64
+                })
+            )
+            .is_empty()
+        );
+        // max_tokens is the current field on the completions API
+        assert!(
+            detect_json("/v1/completions", json!({"prompt": "Hi", "max_tokens": 64})).is_empty()
+        );
+        assert!(detect("/v1/chat/completions", b"not json").is_empty());
+    }
+
+    #[test]
+    fn test_header_value_lists_replacements() {
+        assert!(header_value(&[]).is_none());
+        assert_eq!(
+            header_value(&[FUNCTIONS, MAX_TOKENS]).unwrap(),
+            "\"functions\";replacement=\"tools\", \"max_tokens\";replacement=\"max_completion_tokens\""
+        );
+    }
+}
@@ -190,12 +190,45 @@ impl Drop for InflightGuard {
 /// count and inflight gauge are decremented when the response body finishes, not
 /// when the handler returns — critical for streaming responses where the body
 /// outlives the handler.
+///
+/// Dropping the stream before it finishes means the client went away: the
+/// upstream body is dropped with it, which aborts the upstream connection so
+/// the provider stops generating, and the cancellation is counted in
+/// `onwards_streams_cancelled_total`.
 struct GuardedStream<S> {
     inner: S,
+    finished: bool,
     _guard: ConcurrencyGuard,
+    _endpoint_guard: Option<ConcurrencyGuard>,
     _inflight_guard: InflightGuard,
 }
 
+impl<S> GuardedStream<S> {
+    fn new(
+        inner: S,
+        guard: ConcurrencyGuard,
+        endpoint_guard: Option<ConcurrencyGuard>,
+        inflight_guard: InflightGuard,
+    ) -> Self {
+        Self {
+            inner,
+            finished: false,
+            _guard: guard,
+            _endpoint_guard: endpoint_guard,
+            _inflight_guard: inflight_guard,
+        }
+    }
+}
+
+impl<S> Drop for GuardedStream<S> {
+    fn drop(&mut self) {
+        if !self.finished {
+            debug!("Response body dropped before completion; cancelling upstream request");
+            metrics::counter!("onwards_streams_cancelled_total").increment(1);
+        }
+    }
+}
+
 impl<S, E> futures_util::Stream for GuardedStream<S>
 where
     S: futures_util::Stream<Item = Result<bytes::Bytes, E>> + Unpin,
@@ -206,7 +239,11 @@ where
         mut self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Option<Self::Item>> {
-        std::pin::Pin::new(&mut self.inner).poll_next(cx)
+        let poll = std::pin::Pin::new(&mut self.inner).poll_next(cx);
+        if let std::task::Poll::Ready(None) = poll {
+            self.finished = true;
+        }
+        poll
     }
 }
 
@@ -263,6 +300,35 @@ fn withhold_trace_context(headers: &mut HeaderMap) {
     headers.remove("tracestate");
 }
 
+/// Join an incoming request path onto a provider's base URL. A leading part
+/// of the path that the base URL already ends with (e.g. `v1/`) is not
+/// repeated. Returns `None` if the result is not a valid URL.
+pub(crate) fn upstream_url(target: &Target, path_and_query: &str) -> Option<String> {
+    let request_path = path_and_query.strip_prefix('/').unwrap_or(path_and_query);
+    let target_path = target.url.path().trim_end_matches('/');
+
+    let path_to_join = if !target_path.is_empty() && target_path != "/" {
+        let target_path_no_slash = &target_path[1..];
+        if let Some(rest) = request_path.strip_prefix(target_path_no_slash) {
+            if rest.is_empty() || rest.starts_with('/') {
+                rest.strip_prefix('/').unwrap_or(rest)
+            } else {
+                request_path
+            }
+        } else {
+            request_path
+        }
+    } else {
+        request_path
+    };
+
+    target
+        .url
+        .join(path_to_join)
+        .ok()
+        .map(|url| url.to_string())
+}
+
 /// Filters and modifies headers before forwarding to upstream
 ///
 /// This function implements RFC 7230 compliant proxy behavior by:
@@ -271,7 +337,7 @@ fn withhold_trace_context(headers: &mut HeaderMap) {
 /// - Removing browser-specific context headers (sec-*, origin, referer)
 /// - Adding upstream authentication if configured
 /// - Adding X-Forwarded-* headers for transparency
-fn filter_headers_for_upstream(headers: &mut HeaderMap, target: &Target) {
+pub(crate) fn filter_headers_for_upstream(headers: &mut HeaderMap, target: &Target) {
     // Headers to remove: hop-by-hop (RFC 7230), auth, browser context, and routing headers
     const HEADERS_TO_STRIP: &[&str] = &[
         // RFC 7230 hop-by-hop headers (MUST remove per spec)
@@ -323,21 +389,7 @@ fn filter_headers_for_upstream(headers: &mut HeaderMap, target: &Target) {
 
     // Add Authorization header if target requires authentication to upstream
     if let Some(key) = &target.onwards_key {
-        let header_name_str = target
-            .upstream_auth_header_name
-            .as_deref()
-            .unwrap_or("Authorization");
-        let header_name = HeaderName::from_bytes(header_name_str.as_bytes()).unwrap();
-        let prefix = target
-            .upstream_auth_header_prefix
-            .as_deref()
-            .unwrap_or("Bearer ");
-        let header_value = format!("{}{}", prefix, key);
-        debug!(
-            "Adding {} header for upstream {}",
-            header_name_str, target.url
-        );
-        headers.insert(header_name, header_value.parse().unwrap());
+        set_upstream_auth(headers, target, key);
     } else {
         debug!(
             "No upstream authentication configured for target {}",
@@ -351,9 +403,28 @@ fn filter_headers_for_upstream(headers: &mut HeaderMap, target: &Target) {
     headers.insert("x-forwarded-proto", "https".parse().unwrap());
 }
 
+/// Set the target's upstream auth header to send `key`
+fn set_upstream_auth(headers: &mut HeaderMap, target: &Target, key: &str) {
+    let header_name_str = target
+        .upstream_auth_header_name
+        .as_deref()
+        .unwrap_or("Authorization");
+    let header_name = HeaderName::from_bytes(header_name_str.as_bytes()).unwrap();
+    let prefix = target
+        .upstream_auth_header_prefix
+        .as_deref()
+        .unwrap_or("Bearer ");
+    let header_value = format!("{}{}", prefix, key);
+    debug!(
+        "Adding {} header for upstream {}",
+        header_name_str, target.url
+    );
+    headers.insert(header_name, header_value.parse().unwrap());
+}
+
 /// The main handler responsible for forwarding requests to targets
 /// TODO(fergus): Better error messages beyond raw status codes.
-pub async fn target_message_handler<T: HttpClient>(
+pub async fn target_message_handler<T: HttpClient + Clone + Send + Sync + 'static>(
     State(state): State<AppState<T>>,
     mut req: axum::extract::Request,
 ) -> Result<Response, OnwardsErrorResponse> {
@@ -506,6 +577,8 @@ pub async fn target_message_handler<T: HttpClient>(
     // Evaluate routing rules against key labels (after auth, before rate limiting).
     // Rules on the pool are matched against the authenticated key's labels.
     // Note: routing rules are NOT re-evaluated on the redirect target pool.
+    let mut sanitize_override: Option<bool> = None;
+    let mut preferred_region: Option<String> = None;
     if !pool.routing_rules().is_empty()
         && let Some(token) = bearer_token
     {
@@ -548,11 +621,136 @@ pub async fn target_message_handler<T: HttpClient>(
                             return Err(OnwardsErrorResponse::bad_gateway());
                         }
                     }
+                    RoutingAction::Sanitize { enabled } => {
+                        debug!(
+                            "Routing rule setting sanitize_response={} for model '{}' with labels {:?}",
+                            enabled, model_name, labels
+                        );
+                        sanitize_override = Some(enabled);
+                    }
+                    RoutingAction::PreferRegion { region } => {
+                        debug!(
+                            "Routing rule preferring region '{}' for model '{}' with labels {:?}",
+                            region, model_name, labels
+                        );
+                        preferred_region = Some(region);
+                    }
                 }
             }
         // If no bearer token, no labels to match — rules are skipped (allow by default)
     }
 
+    // Pin the request to a single named provider when the route-to header is
+    // present. Runs after routing rules so a redirect is pinned within the
+    // pool it resolved to. The caller must be authorized for the pinned
+    // provider's own pool as well as the requested model.
+    if let Some(header_name) = state.route_to_header.as_deref()
+        && let Some(header_value) = req.headers().get(header_name)
+    {
+        let Ok(provider_name) = header_value.to_str() else {
+            record_response_status(400);
+            return Err(OnwardsErrorResponse::bad_request(
+                "Route-to header must be a valid model name.",
+                None,
+            ));
+        };
+
+        let authorized = match state.targets.targets.get(provider_name) {
+            Some(provider_pool) => match provider_pool.keys() {
+                Some(keys) => {
+                    bearer_token.is_some_and(|token| auth::validate_bearer_token(keys, token))
+                }
+                None => true,
+            },
+            None => false,
+        };
+        if !authorized {
+            debug!(
+                "Route-to target '{}' is not accessible to the caller",
+                provider_name
+            );
+            record_response_status(403);
+            return Err(OnwardsErrorResponse::forbidden());
+        }
+
+        pool = match pool.pinned_to(provider_name) {
+            Some(pinned) => pinned,
+            None => {
+                record_response_status(400);
+                return Err(OnwardsErrorResponse::bad_request(
+                    &format!(
+                        "'{}' is not a component of model '{}'.",
+                        provider_name, model_name
+                    ),
+                    None,
+                ));
+            }
+        };
+        debug!(
+            "Pinned request for model '{}' to provider '{}'",
+            model_name, provider_name
+        );
+    }
+
+    // Prefer providers in the client's region, falling back to the rest of the
+    // pool. A region hint header from the client takes precedence over a
+    // region preferred by a routing rule.
+    let region_hint = state
+        .region_preference_header
+        .as_deref()
+        .and_then(|header_name| req.headers().get(header_name))
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|region| !region.is_empty())
+        .map(str::to_string);
+    if let Some(region) = region_hint.or(preferred_region) {
+        debug!("Preferring region '{}' for model '{}'", region, model_name);
+        pool = pool.preferring_region(&region);
+    }
+
+    // The request's priority, capped by what the caller's key allows
+    let priority = match state
+        .priority_header
+        .as_deref()
+        .and_then(|header_name| req.headers().get(header_name))
+    {
+        Some(value) => {
+            let allowed = crate::priority::allowed_priority(
+                bearer_token
+                    .and_then(|token| state.targets.key_labels.get(token))
+                    .as_deref(),
+            );
+            match value
+                .to_str()
+                .map_err(|_| crate::priority::PriorityError::Invalid)
+                .and_then(|value| crate::priority::parse_priority(value, allowed))
+            {
+                Ok(priority) => priority,
+                Err(crate::priority::PriorityError::Invalid) => {
+                    record_response_status(400);
+                    return Err(OnwardsErrorResponse::bad_request(
+                        &format!(
+                            "Priority must be an integer from 0 to {}.",
+                            crate::priority::MAX_PRIORITY
+                        ),
+                        None,
+                    ));
+                }
+                Err(crate::priority::PriorityError::NotAllowed { requested, allowed }) => {
+                    record_response_status(400);
+                    return Err(OnwardsErrorResponse::bad_request(
+                        &format!(
+                            "Priority {} exceeds the maximum of {} allowed for this API key.",
+                            requested, allowed
+                        ),
+                        None,
+                    ));
+                }
+            }
+        }
+        None => 0,
+    };
+
     let canonical_reasoning = if let Some(reasoning) = req
         .extensions()
         .get::<crate::reasoning::CanonicalReasoningRequest>()
@@ -581,6 +779,30 @@ pub async fn target_message_handler<T: HttpClient>(
         }
     }
 
+    // Cap the number of completions before the request counts against any limit
+    if let Some(n_limit) = pool.n_limit() {
+        match crate::n_limit::enforce(n_limit, &body_bytes) {
+            Ok(Some(bytes)) => {
+                debug!(
+                    "Clamped n to {} for model: {}",
+                    n_limit.max, model_name
+                );
+                body_bytes = bytes;
+            }
+            Ok(None) => {}
+            Err(exceeded) => {
+                record_response_status(400);
+                return Err(OnwardsErrorResponse::bad_request(
+                    &format!(
+                        "n of {} exceeds the maximum of {} completions per request for this model.",
+                        exceeded.requested, exceeded.max
+                    ),
+                    Some("n"),
+                ));
+            }
+        }
+    }
+
     // Check if pool has no providers (e.g., composite model with no enabled components).
     // This runs after routing rules so that redirects get a chance to replace the pool.
     if pool.is_empty() {
@@ -610,9 +832,58 @@ pub async fn target_message_handler<T: HttpClient>(
         }
     }
 
-    // Acquire pool-level concurrency permit
-    let (_pool_concurrency_guard, _key_concurrency_guard) = {
+    // When queueing is enabled, wait for a turn at the pool's capacity rather
+    // than being turned away. The turn is held until a provider slot is taken,
+    // so later arrivals can't claim the capacity first.
+    let mut queue_turn = match state.request_queue.as_ref() {
+        Some(queue_config) => {
+            match pool
+                .slot_queue()
+                .wait_turn(&model_name, priority, queue_config, || {
+                    pool.has_capacity()
+                })
+                .await
+            {
+                Some(turn) => Some(turn),
+                None => {
+                    debug!(
+                        "Timed out waiting for capacity for model: {}",
+                        model_name
+                    );
+                    record_response_status(429);
+                    return Err(OnwardsErrorResponse::concurrency_limited());
+                }
+            }
+        }
+        None => None,
+    };
+    let queue_wait = state.queue_wait_header.as_deref().and_then(|header_name| {
+        let waited = queue_turn.as_ref()?.waited()?;
+        Some((
+            HeaderName::from_bytes(header_name.as_bytes()).ok()?,
+            HeaderValue::from(waited.as_millis() as u64),
+        ))
+    });
+
+    // Acquire per-key share, pool-level and per-key concurrency permits
+    let (_key_share_guard, _pool_concurrency_guard, _key_concurrency_guard) = {
         let _concurrency_span = tracing::info_span!("onwards.acquire_concurrency", otel.name = "onwards.acquire_concurrency", model = %model_name).entered();
+        // Checked before the pool permit so a key already holding its share
+        // never takes a pool slot another key could use
+        let key_share_guard = match (pool.per_key_concurrency_limiter(), bearer_token) {
+            (Some(limiter), Some(token)) => match limiter.try_acquire(token) {
+                Some(guard) => Some(guard),
+                None => {
+                    debug!(
+                        "Per-key share of pool capacity exceeded for model: {}",
+                        model_name
+                    );
+                    return Err(OnwardsErrorResponse::concurrency_limited());
+                }
+            },
+            _ => None,
+        };
+
         let pool_guard = if let Some(limiter) = pool.pool_concurrency_limiter() {
             match limiter.try_acquire() {
                 Some(guard) => Some(guard),
@@ -644,9 +915,15 @@ pub async fn target_message_handler<T: HttpClient>(
         } else {
             None
         };
-        (pool_guard, key_guard)
+        (key_share_guard, pool_guard, key_guard)
     };
 
+    // Response headers selected by the caller's key labels (e.g. per-purpose pricing)
+    let labeled_response_headers = bearer_token.and_then(|token| {
+        let labels = state.targets.key_labels.get(token)?;
+        pool.labeled_response_headers(labels.value()).cloned()
+    });
+
     // Extract path info once (used for each provider attempt)
     let path_and_query = req
         .uri()
@@ -656,9 +933,50 @@ pub async fn target_message_handler<T: HttpClient>(
         .to_string();
 
     // Prepare original headers and method for potential retries
-    let original_headers = req.headers().clone();
+    let mut original_headers = req.headers().clone();
+    // The route-to header has been consumed above; don't leak it upstream.
+    if let Some(header_name) = state.route_to_header.as_deref() {
+        original_headers.remove(header_name);
+    }
+    if let Some(header_name) = state.region_preference_header.as_deref() {
+        original_headers.remove(header_name);
+    }
+    if let Some(header_name) = state.priority_header.as_deref() {
+        original_headers.remove(header_name);
+    }
     let method = req.method().clone();
 
+    // Note deprecated fields as the client sent them, before any rewriting
+    let deprecation_warning = state.deprecation_header.as_deref().and_then(|header_name| {
+        let deprecations = crate::deprecation::detect(&canonical_request_path, &body_bytes);
+        for deprecation in &deprecations {
+            metrics::counter!("onwards_deprecated_fields_total", "field" => deprecation.field)
+                .increment(1);
+        }
+        let value = crate::deprecation::header_value(&deprecations)?;
+        Some((HeaderName::from_bytes(header_name.as_bytes()).ok()?, value))
+    });
+
+    // Inject the pool's system prompt before the body is copied or forwarded
+    if let Some(system_prompt) = pool.system_prompt()
+        && let Some(bytes) = crate::system_prompt::inject(system_prompt, &body_bytes)
+    {
+        debug!("Injected system prompt for model: {}", model_name);
+        body_bytes = bytes;
+    }
+
+    // Copy a sample of admitted requests to the pool's shadow alias. The copy
+    // runs in the background and never affects this request.
+    if let Some(shadow) = pool.shadow() {
+        crate::shadow::maybe_dispatch(&state, shadow, || crate::shadow::ShadowRequest {
+            model: model_name.clone(),
+            method: method.clone(),
+            path_and_query: path_and_query.clone(),
+            headers: original_headers.clone(),
+            body: body_bytes.clone(),
+        });
+    }
+
     // Track last error for fallback scenarios
     let mut last_error: Option<OnwardsErrorResponse> = None;
 
@@ -672,6 +990,8 @@ pub async fn target_message_handler<T: HttpClient>(
     let pool_max_attempts = pool.fallback_max_attempts();
     for (_idx, target, connection_guard) in pool.select_iter() {
         any_attempted = true;
+        // This request holds a provider slot now; let the next waiter in
+        drop(queue_turn.take());
         attempt_number += 1;
 
         let attempt_span = tracing::info_span!(
@@ -706,6 +1026,9 @@ pub async fn target_message_handler<T: HttpClient>(
             .get::<OriginalModel>()
             .map(|m| m.0.to_string());
 
+        // A matching sanitize routing rule overrides the provider's own setting
+        let sanitize_response = sanitize_override.unwrap_or(target.sanitize_response);
+
         let action = async {
 
         // Check provider-level rate limit (skip to next if configured for rate limit fallback)
@@ -722,6 +1045,45 @@ pub async fn target_message_handler<T: HttpClient>(
             }
         }
 
+        // Take a slot on the provider's endpoint, shared with the other pools
+        // it serves. A full endpoint is treated like a provider at capacity.
+        let endpoint_guard = match target.endpoint_concurrency_limit.as_ref() {
+            Some(limit) => match state.endpoint_concurrency.try_acquire(limit) {
+                Some(guard) => Some(guard),
+                None => {
+                    debug!(
+                        "Endpoint concurrency limit reached for '{}', skipping provider: {:?}",
+                        limit.endpoint, target.url
+                    );
+                    tracing::Span::current().record("onwards.fallback", "endpoint_concurrency_limited");
+                    if pool.fallback_enabled() {
+                        return LoopAction::Continue(Some(OnwardsErrorResponse::concurrency_limited()));
+                    } else {
+                        return LoopAction::Done(Err(OnwardsErrorResponse::concurrency_limited()));
+                    }
+                }
+            },
+            None => None,
+        };
+
+        // Fail fast while the endpoint's circuit breaker is open
+        let breaker = state
+            .circuit_breakers
+            .as_ref()
+            .map(|breakers| breakers.for_endpoint(target.url.as_str()));
+        if let Some(ref breaker) = breaker
+            && !breaker.try_acquire()
+        {
+            debug!("Circuit breaker open, skipping provider: {:?}", target.url);
+            tracing::Span::current().record("onwards.fallback", "circuit_open");
+            if pool.fallback_enabled() {
+                return LoopAction::Continue(Some(OnwardsErrorResponse::service_unavailable()));
+            } else {
+                record_response_status(503);
+                return LoopAction::Done(Err(OnwardsErrorResponse::service_unavailable()));
+            }
+        }
+
         // Clone response headers for later use
         let response_headers = target.response_headers.clone();
 
@@ -788,28 +1150,25 @@ pub async fn target_message_handler<T: HttpClient>(
             };
         }
 
-        // Build the upstream URI for this target
-        let request_path = path_and_query.strip_prefix('/').unwrap_or(&path_and_query);
-        let target_path = target.url.path().trim_end_matches('/');
-
-        let path_to_join = if !target_path.is_empty() && target_path != "/" {
-            let target_path_no_slash = &target_path[1..];
-            if let Some(rest) = request_path.strip_prefix(target_path_no_slash) {
-                if rest.is_empty() || rest.starts_with('/') {
-                    rest.strip_prefix('/').unwrap_or(rest)
-                } else {
-                    request_path
+        let mut client_tool_calling = None;
+        if let Some(body_transform) = target.body_transform.as_ref() {
+            client_tool_calling = body_transform.tool_call_translation(&attempt_body);
+            match body_transform.apply_request(&attempt_body) {
+                Ok(Some(bytes)) => attempt_body = axum::body::Bytes::from(bytes),
+                Ok(None) => {}
+                Err(error) => {
+                    return LoopAction::Done(Err(OnwardsErrorResponse::bad_request(
+                        &format!("Request body could not be adapted for this model: {error}"),
+                        None,
+                    )))
                 }
-            } else {
-                request_path
             }
-        } else {
-            request_path
-        };
+        }
 
-        let upstream_uri = match target.url.join(path_to_join) {
-            Ok(url) => url.to_string(),
-            Err(_) => return LoopAction::Done(Err(OnwardsErrorResponse::internal())),
+        // Build the upstream URI for this target
+        let upstream_uri = match upstream_url(target, &path_and_query) {
+            Some(url) => url,
+            None => return LoopAction::Done(Err(OnwardsErrorResponse::internal())),
         };
         let upstream_uri_parsed = match Uri::try_from(&upstream_uri) {
             Ok(uri) => uri,
@@ -846,6 +1205,21 @@ pub async fn target_message_handler<T: HttpClient>(
         // Filter headers for upstream forwarding
         filter_headers_for_upstream(&mut attempt_headers, target);
 
+        // Send one of the provider's rotated keys instead of `onwards_key`
+        let rotated_key = target.key_rotation.as_ref().and_then(|rotation| {
+            let key = state.key_rotations.select(rotation)?;
+            set_upstream_auth(&mut attempt_headers, target, &key);
+            Some((rotation, key))
+        });
+
+        // Ask for a gzip response if enabled; it's decoded as soon as it arrives
+        let requested_gzip = state
+            .targets
+            .http_pool_config
+            .as_ref()
+            .is_some_and(|p| p.gzip)
+            && crate::compression::request_gzip(&mut attempt_headers);
+
         // Apply W3C trace-context policy for this upstream, gated on the
         // per-target propagate_trace_context flag (defaults to the resolved
         // trusted value).
@@ -870,6 +1244,18 @@ pub async fn target_message_handler<T: HttpClient>(
             withhold_trace_context(&mut attempt_headers);
         }
 
+        // Sign last, once the body and headers are final
+        if let Some(sigv4) = target.sigv4.as_ref() {
+            crate::sigv4::sign_request(
+                sigv4,
+                &method,
+                &upstream_uri_parsed,
+                &mut attempt_headers,
+                &attempt_body,
+                std::time::SystemTime::now(),
+            );
+        }
+
         // Build the request
         let attempt_req = axum::extract::Request::builder()
             .method(method.clone())
@@ -899,7 +1285,7 @@ pub async fn target_message_handler<T: HttpClient>(
         let request_result = async {
             if let Some(timeout_secs) = target.request_timeout_secs {
                 let timeout_duration = std::time::Duration::from_secs(timeout_secs);
-                match tokio::time::timeout(timeout_duration, state.http_client.request(attempt_req))
+                match tokio::time::timeout(timeout_duration, state.send_upstream(target, attempt_req))
                     .await
                 {
                     Err(_) => {
@@ -914,7 +1300,7 @@ pub async fn target_message_handler<T: HttpClient>(
                 }
             } else {
                 // No timeout configured
-                state.http_client.request(attempt_req).await.map_err(UpstreamOutcome::Error)
+                state.send_upstream(target, attempt_req).await.map_err(UpstreamOutcome::Error)
             }
         }
         .instrument(upstream_span.clone())
@@ -923,6 +1309,9 @@ pub async fn target_message_handler<T: HttpClient>(
         // Handle request errors
         let mut response = match request_result {
             Err(UpstreamOutcome::Timeout) => {
+                if let Some(ref breaker) = breaker {
+                    breaker.record_failure();
+                }
                 upstream_span.record("http.response.status_code", 504_u16);
                 tracing::Span::current().record("onwards.fallback", "timeout");
                 if pool.fallback_enabled() {
@@ -937,6 +1326,9 @@ pub async fn target_message_handler<T: HttpClient>(
                     "Error forwarding request to target url {}: {}",
                     upstream_uri, e
                 );
+                if let Some(ref breaker) = breaker {
+                    breaker.record_failure();
+                }
                 tracing::Span::current().record("onwards.fallback", "network_error");
                 // Only continue to next provider if fallback is enabled
                 if pool.fallback_enabled() {
@@ -945,6 +1337,7 @@ pub async fn target_message_handler<T: HttpClient>(
                     return LoopAction::Done(Err(OnwardsErrorResponse::bad_gateway()));
                 }
             }
+            Ok(response) if requested_gzip => crate::compression::decode_gzip_response(response),
             Ok(response) => response,
         };
 
@@ -952,6 +1345,23 @@ pub async fn target_message_handler<T: HttpClient>(
         upstream_span.record("http.response.status_code", status);
         tracing::Span::current().record("http.response.status_code", status);
 
+        if let Some(ref breaker) = breaker {
+            if (500..600).contains(&status) {
+                breaker.record_failure();
+            } else {
+                breaker.record_success();
+            }
+        }
+        if let Some((rotation, key)) = rotated_key.as_ref()
+            && crate::key_rotation::evicts_key(status)
+        {
+            debug!(
+                "Upstream key answered with {}, taking it out of rotation: {:?}",
+                status, target.url
+            );
+            state.key_rotations.evict(rotation, key);
+        }
+
         // Check if we should fallback based on status code
         if pool.should_fallback_on_status(status) {
             debug!(
@@ -965,7 +1375,7 @@ pub async fn target_message_handler<T: HttpClient>(
         // Sanitize error responses when sanitize_response is enabled.
         // Replace upstream error bodies with generic messages to prevent
         // information leakage (provider names, URLs, internal model names).
-        if target.sanitize_response && !(200..300).contains(&status) {
+        if sanitize_response && !(200..300).contains(&status) {
             // Drain the original error body (buffering up to 64 KiB) so we can
             // record its length, but do NOT log its content. ZDR: provider error
             // bodies can echo prompt/response content, so only the status,
@@ -1247,7 +1657,7 @@ pub async fn target_message_handler<T: HttpClient>(
         // so we skip buffering here to avoid double-wrapping
         let needs_sse_buffering = !state.targets.strict_mode
             && state.response_transform_fn.is_some()
-            && target.sanitize_response
+            && sanitize_response
             && (200..300).contains(&status);
 
         // Wrap SSE streams with buffering to ensure complete events (delimited by \n\n).
@@ -1266,7 +1676,7 @@ pub async fn target_message_handler<T: HttpClient>(
         // Per-target opt-in via sanitize_response flag, only for 2xx responses
         // Skip if strict mode is enabled - strict handlers do their own sanitization
         if let Some(ref transform_fn) = state.response_transform_fn
-            && target.sanitize_response
+            && sanitize_response
             && (200..300).contains(&status)
             && !state.targets.strict_mode
         {
@@ -1381,6 +1791,32 @@ pub async fn target_message_handler<T: HttpClient>(
             }
         }
 
+        // Report the requested model name rather than the upstream one. Strict
+        // mode handlers always do this, as does sanitization on chat completions.
+        if target.rewrite_response_model
+            && (200..300).contains(&status)
+            && !state.targets.strict_mode
+            && let Some(model) = original_model.clone()
+        {
+            crate::model_rewrite::rewrite_response_model(&mut response, model).await;
+        }
+
+        // Apply the provider's declarative response edits to JSON 2xx bodies
+        if let Some(body_transform) = target.body_transform.as_ref()
+            && (200..300).contains(&status)
+            && !is_sse
+        {
+            body_transform.apply_response(&mut response).await;
+        }
+
+        // Return tool calls in the shape the client sent, after the edits above
+        // (which address the provider's shape)
+        if let Some(client_style) = client_tool_calling
+            && (200..300).contains(&status)
+        {
+            crate::tool_calling::translate_response(&mut response, client_style).await;
+        }
+
         // Override the response `id` field for /responses and /chat/completions
         // requests when the caller supplied a response ID via the configured
         // header. Both response bodies expose a top-level `id` we can rewrite.
@@ -1410,6 +1846,27 @@ pub async fn target_message_handler<T: HttpClient>(
                 "Added custom response headers"
             );
         }
+        if let Some(headers) = labeled_response_headers.as_ref() {
+            for (key, value) in headers.iter() {
+                if let (Ok(header_name), Ok(header_value)) =
+                    (key.parse::<HeaderName>(), value.parse::<HeaderValue>())
+                {
+                    response.headers_mut().insert(header_name, header_value);
+                }
+            }
+            trace!(
+                model = %model_name,
+                headers = ?headers,
+                "Added labeled response headers"
+            );
+        }
+
+        if let Some((header_name, value)) = deprecation_warning.clone() {
+            response.headers_mut().insert(header_name, value);
+        }
+        if let Some((header_name, value)) = queue_wait.clone() {
+            response.headers_mut().insert(header_name, value);
+        }
 
         record_response_status(response.status().as_u16());
         debug!(
@@ -1431,11 +1888,12 @@ pub async fn target_message_handler<T: HttpClient>(
         // are decremented when the body stream completes, not when the handler returns.
         // Critical for streaming responses where the body outlives the handler.
         let (parts, body) = response.into_parts();
-        let guarded = GuardedStream {
-            inner: body.into_data_stream(),
-            _guard: connection_guard,
-            _inflight_guard: inflight_guard.take().expect("inflight_guard taken once on success path"),
-        };
+        let guarded = GuardedStream::new(
+            body.into_data_stream(),
+            connection_guard,
+            endpoint_guard,
+            inflight_guard.take().expect("inflight_guard taken once on success path"),
+        );
         let response = Response::from_parts(parts, axum::body::Body::from_stream(guarded));
 
         LoopAction::Done(Ok(response))
@@ -2394,9 +2852,19 @@ mod tests {
             response_transform_fn: None,
             streaming_header: None,
             response_id_header: None,
+            route_to_header: None,
+            region_preference_header: None,
+            priority_header: None,
+            deprecation_header: None,
+            request_queue: None,
+            queue_wait_header: None,
             tool_executor: std::sync::Arc::new(crate::NoOpToolExecutor),
             response_store: std::sync::Arc::new(crate::NoOpResponseStore),
             body_limit: crate::DEFAULT_BODY_LIMIT,
+            circuit_breakers: None,
+            endpoint_concurrency: crate::target::EndpointConcurrencyLimiters::default(),
+            tls_clients: crate::tls::TlsClients::default(),
+            key_rotations: crate::key_rotation::KeyRotations::default(),
         };
 
         // Create a simple POST request
@@ -2466,20 +2934,28 @@ mod tests {
         propagate_trace_context: Option<bool>,
     ) -> Target {
         Target {
+            name: None,
+            region: None,
             url: "https://api.example.com/".parse().unwrap(),
             keys: None,
             onwards_key: None,
             onwards_model: None,
             limiter: None,
+            endpoint_concurrency_limit: None,
             upstream_auth_header_name: None,
             upstream_auth_header_prefix: None,
             response_headers: None,
             sanitize_response: false,
+            rewrite_response_model: false,
             open_responses: None,
             request_timeout_secs: None,
             trusted,
             propagate_trace_context,
             reasoning_translation: None,
+            sigv4: None,
+            tls: None,
+            key_rotation: None,
+            body_transform: None,
         }
     }
 
@@ -0,0 +1,279 @@
+//! Rotating a provider's requests between several upstream API keys.
+//!
+//! Providers often rate-limit per key, so spreading a provider's traffic over
+//! several keys raises the throughput it accepts. A target with a
+//! [`KeyRotationConfig`] sends one of its keys with every request in place of
+//! `onwards_key`: each in turn ([`KeyRotationStrategy::RoundRobin`]) or the
+//! one used longest ago ([`KeyRotationStrategy::LeastRecentlyUsed`]).
+//!
+//! A key whose request is answered with a 401 or 429 is taken out of rotation
+//! for `cooldown_secs`, and evictions are counted in
+//! `onwards_upstream_key_evictions_total`. While every key is out, the one due
+//! back soonest is used rather than failing the request.
+//!
+//! Rotation state lives in [`KeyRotations`] (in the app state), keyed by the
+//! key set, so it survives config reloads.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+/// Default time a key answered with a 401 or 429 is left out of rotation
+pub const DEFAULT_COOLDOWN_SECS: u64 = 60;
+
+/// How the next key is picked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyRotationStrategy {
+    /// Each key in turn
+    #[default]
+    RoundRobin,
+    /// The key used longest ago
+    LeastRecentlyUsed,
+}
+
+/// Upstream keys to rotate between for a provider
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct KeyRotationConfig {
+    pub keys: Vec<String>,
+    #[serde(default)]
+    pub strategy: KeyRotationStrategy,
+    /// How long (in seconds) a key answered with a 401 or 429 is left out
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+}
+
+fn default_cooldown_secs() -> u64 {
+    DEFAULT_COOLDOWN_SECS
+}
+
+// Manual Debug so the keys are never logged.
+impl fmt::Debug for KeyRotationConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyRotationConfig")
+            .field("keys", &self.keys.len())
+            .field("strategy", &self.strategy)
+            .field("cooldown_secs", &self.cooldown_secs)
+            .finish()
+    }
+}
+
+/// Whether a response status takes the key that got it out of rotation
+pub fn evicts_key(status: u16) -> bool {
+    matches!(status, 401 | 429)
+}
+
+#[derive(Debug, Default)]
+struct KeyState {
+    last_used: Option<Instant>,
+    evicted_until: Option<Instant>,
+}
+
+/// Rotation state for one key set, indexed like its keys
+#[derive(Debug)]
+struct KeyRing {
+    keys: Vec<KeyState>,
+    /// Where round-robin looks for the next key
+    next: usize,
+}
+
+impl KeyRing {
+    fn new(len: usize) -> Self {
+        Self {
+            keys: (0..len).map(|_| KeyState::default()).collect(),
+            next: 0,
+        }
+    }
+
+    /// Index of the key to use at `now`. The ring must not be empty.
+    fn select_at(&mut self, strategy: KeyRotationStrategy, now: Instant) -> usize {
+        let len = self.keys.len();
+        let in_rotation = |idx: &usize| {
+            self.keys[*idx]
+                .evicted_until
+                .is_none_or(|until| now >= until)
+        };
+        let selected = match strategy {
+            KeyRotationStrategy::RoundRobin => (0..len)
+                .map(|offset| (self.next + offset) % len)
+                .find(in_rotation),
+            KeyRotationStrategy::LeastRecentlyUsed => (0..len)
+                .filter(in_rotation)
+                .min_by_key(|idx| self.keys[*idx].last_used),
+        };
+        // Every key is out of rotation: use the one due back soonest
+        let idx = selected.unwrap_or_else(|| {
+            (0..len)
+                .min_by_key(|idx| self.keys[*idx].evicted_until)
+                .unwrap_or(0)
+        });
+        self.next = (idx + 1) % len;
+        self.keys[idx].last_used = Some(now);
+        idx
+    }
+}
+
+/// Rotation state for every key set, shared by clones
+#[derive(Debug, Clone, Default)]
+pub struct KeyRotations {
+    rings: Arc<DashMap<Vec<String>, Arc<Mutex<KeyRing>>>>,
+}
+
+impl KeyRotations {
+    /// The key to send with the next request to a provider with `config`, or
+    /// `None` if it has no keys.
+    pub fn select(&self, config: &KeyRotationConfig) -> Option<String> {
+        if config.keys.is_empty() {
+            return None;
+        }
+        let idx = self
+            .ring(config)
+            .lock()
+            .unwrap()
+            .select_at(config.strategy, Instant::now());
+        Some(config.keys[idx].clone())
+    }
+
+    /// Take `key` out of rotation for the config's cooldown.
+    pub fn evict(&self, config: &KeyRotationConfig, key: &str) {
+        let Some(idx) = config.keys.iter().position(|k| k == key) else {
+            return;
+        };
+        let until = Instant::now() + Duration::from_secs(config.cooldown_secs);
+        self.ring(config).lock().unwrap().keys[idx].evicted_until = Some(until);
+        metrics::counter!("onwards_upstream_key_evictions_total").increment(1);
+    }
+
+    fn ring(&self, config: &KeyRotationConfig) -> Arc<Mutex<KeyRing>> {
+        if let Some(ring) = self.rings.get(&config.keys) {
+            return Arc::clone(ring.value());
+        }
+        Arc::clone(
+            self.rings
+                .entry(config.keys.clone())
+                .or_insert_with(|| Arc::new(Mutex::new(KeyRing::new(config.keys.len()))))
+                .value(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::target::{Target, Targets};
+    use crate::{AppState, build_router};
+    use axum_test::TestServer;
+    use serde_json::json;
+    use wiremock::matchers::header;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn keys(strategy: KeyRotationStrategy) -> KeyRotationConfig {
+        KeyRotationConfig {
+            keys: vec!["key-a".into(), "key-b".into(), "key-c".into()],
+            strategy,
+            cooldown_secs: 60,
+        }
+    }
+
+    #[test]
+    fn test_least_recently_used_skips_evicted_keys() {
+        let mut ring = KeyRing::new(3);
+        let now = Instant::now();
+        let strategy = KeyRotationStrategy::LeastRecentlyUsed;
+
+        // Unused keys go first, in order
+        assert_eq!(ring.select_at(strategy, now), 0);
+        assert_eq!(ring.select_at(strategy, now + Duration::from_secs(1)), 1);
+        ring.keys[2].evicted_until = Some(now + Duration::from_secs(10));
+        assert_eq!(ring.select_at(strategy, now + Duration::from_secs(2)), 0);
+        assert_eq!(ring.select_at(strategy, now + Duration::from_secs(3)), 1);
+
+        // The evicted key is back once its cooldown has passed
+        assert_eq!(ring.select_at(strategy, now + Duration::from_secs(10)), 2);
+    }
+
+    #[test]
+    fn test_soonest_returning_key_used_when_all_evicted() {
+        let mut ring = KeyRing::new(2);
+        let now = Instant::now();
+        ring.keys[0].evicted_until = Some(now + Duration::from_secs(20));
+        ring.keys[1].evicted_until = Some(now + Duration::from_secs(10));
+        for strategy in [
+            KeyRotationStrategy::RoundRobin,
+            KeyRotationStrategy::LeastRecentlyUsed,
+        ] {
+            assert_eq!(ring.select_at(strategy, now), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rotates_keys_and_evicts_rate_limited_key() {
+        let upstream = MockServer::start().await;
+        Mock::given(header("authorization", "Bearer key-b"))
+            .respond_with(ResponseTemplate::new(429))
+            .mount(&upstream)
+            .await;
+        Mock::given(wiremock::matchers::any())
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"id": "ok"})))
+            .mount(&upstream)
+            .await;
+
+        let targets_map = Arc::new(DashMap::new());
+        targets_map.insert(
+            "rotated".to_string(),
+            Target::builder()
+                .url(format!("{}/v1/", upstream.uri()).parse().unwrap())
+                .onwards_key("key-a".to_string())
+                .key_rotation(keys(KeyRotationStrategy::RoundRobin))
+                .build()
+                .into_pool(),
+        );
+        let targets = Targets {
+            targets: targets_map,
+            key_rate_limiters: Arc::new(DashMap::new()),
+            key_concurrency_limiters: Arc::new(DashMap::new()),
+            key_labels: Arc::new(DashMap::new()),
+            strict_mode: false,
+            http_pool_config: None,
+        };
+        let server = TestServer::new(build_router(AppState::new(targets))).unwrap();
+
+        let mut statuses = Vec::new();
+        for _ in 0..6 {
+            let response = server
+                .post("/v1/chat/completions")
+                .json(&json!({"model": "rotated", "messages": []}))
+                .await;
+            statuses.push(response.status_code().as_u16());
+        }
+        assert_eq!(statuses, [200, 429, 200, 200, 200, 200]);
+
+        // key-b is left out once it has been rate limited
+        let sent: Vec<_> = upstream
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|request| {
+                request.headers["authorization"]
+                    .to_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(
+            sent,
+            [
+                "Bearer key-a",
+                "Bearer key-b",
+                "Bearer key-c",
+                "Bearer key-a",
+                "Bearer key-c",
+                "Bearer key-a"
+            ]
+        );
+    }
+}
@@ -46,23 +46,36 @@ use std::sync::Arc;
 use tracing::{info, instrument};
 
 pub mod auth;
+pub mod body_transform;
+pub mod circuit_breaker;
 pub mod client;
+pub mod compression;
 pub mod config;
+pub mod deprecation;
 pub mod errors;
 pub mod handlers;
+pub mod key_rotation;
 pub mod load_balancer;
+pub mod model_rewrite;
 pub mod models;
+mod n_limit;
+pub mod priority;
 pub mod reasoning;
 pub mod response_id;
 #[cfg(feature = "multi-step")]
 pub mod response_loop;
 pub mod response_sanitizer;
+mod shadow;
+pub mod sigv4;
 pub mod sse;
 #[cfg(feature = "multi-step")]
 pub mod streaming;
 pub mod strict;
+mod system_prompt;
 pub mod target;
 pub mod telemetry;
+pub mod tls;
+pub mod tool_calling;
 pub mod traits;
 
 use client::{HttpClient, HyperClient};
@@ -226,6 +239,33 @@ pub struct AppState<T: HttpClient> {
     /// correlate responses with pre-created tracking records without needing to
     /// patch the response body after the fact.
     pub response_id_header: Option<String>,
+    /// Header name that pins a request to a named provider within the target
+    /// pool, bypassing load balancing (e.g. to compare one component of a
+    /// composite model in an evaluation harness). The caller's key must also
+    /// be authorized for the pool registered under that provider's name.
+    /// Defaults to `None` (header ignored).
+    pub route_to_header: Option<String>,
+    /// Header name carrying the client's preferred region (e.g.
+    /// `x-region-preference`). Providers in that region are tried first,
+    /// falling back to other regions. Overrides a region preferred by a
+    /// routing rule. Defaults to `None` (header ignored).
+    pub region_preference_header: Option<String>,
+    /// Header name carrying the request's priority (e.g. `x-priority`), from
+    /// 0 to [`priority::MAX_PRIORITY`] and capped by the `max_priority` label
+    /// of the caller's key. Only affects requests that are queued. Defaults to
+    /// `None` (header ignored, every request has priority 0).
+    pub priority_header: Option<String>,
+    /// Response header listing the deprecated request fields a request used
+    /// and their replacements (e.g. `openai-deprecation`); see
+    /// [`deprecation`]. Defaults to `None` (no warnings).
+    pub deprecation_header: Option<String>,
+    /// Queue requests that find their pool at capacity, instead of rejecting
+    /// them immediately. Defaults to `None` (no queueing).
+    pub request_queue: Option<priority::QueueConfig>,
+    /// Response header set to the milliseconds a request spent queued for
+    /// capacity (e.g. `x-queue-wait-ms`), on requests that queued. Defaults
+    /// to `None` (no header).
+    pub queue_wait_header: Option<String>,
     pub tool_executor: Arc<dyn ToolExecutor>,
     pub response_store: Arc<dyn ResponseStore>,
     /// Maximum request body size in bytes, enforced by both routers. Without
@@ -233,6 +273,19 @@ pub struct AppState<T: HttpClient> {
     /// `DefaultBodyLimit`, which rejects large (e.g. long-context or base64
     /// image) payloads with a 413. Defaults to [`DEFAULT_BODY_LIMIT`].
     pub body_limit: usize,
+    /// Per-endpoint circuit breakers. Breakers are kept here rather than on
+    /// the targets so that their state survives config reloads. Defaults to
+    /// `None` (no circuit breaking).
+    pub circuit_breakers: Option<circuit_breaker::CircuitBreakers>,
+    /// Concurrent request counters for providers sharing an endpoint limit
+    /// (see [`target::EndpointConcurrencyLimit`]). Kept here rather than on
+    /// the targets so in-flight requests stay counted across config reloads.
+    pub endpoint_concurrency: target::EndpointConcurrencyLimiters,
+    /// HTTP clients for providers with custom TLS settings (see [`tls`]).
+    pub tls_clients: tls::TlsClients,
+    /// Rotation state for providers with several upstream keys (see
+    /// [`key_rotation`]), kept here so it survives config reloads.
+    pub key_rotations: key_rotation::KeyRotations,
 }
 
 /// Default maximum request body size (32 MB).
@@ -257,9 +310,19 @@ impl<T: HttpClient> std::fmt::Debug for AppState<T> {
             )
             .field("streaming_header", &self.streaming_header)
             .field("response_id_header", &self.response_id_header)
+            .field("route_to_header", &self.route_to_header)
+            .field("region_preference_header", &self.region_preference_header)
+            .field("priority_header", &self.priority_header)
+            .field("deprecation_header", &self.deprecation_header)
+            .field("request_queue", &self.request_queue)
+            .field("queue_wait_header", &self.queue_wait_header)
             .field("tool_executor", &"<dyn ToolExecutor>")
             .field("response_store", &"<dyn ResponseStore>")
             .field("body_limit", &self.body_limit)
+            .field("circuit_breakers", &self.circuit_breakers)
+            .field("endpoint_concurrency", &self.endpoint_concurrency)
+            .field("tls_clients", &self.tls_clients)
+            .field("key_rotations", &self.key_rotations)
             .finish()
     }
 }
@@ -267,13 +330,8 @@ impl<T: HttpClient> std::fmt::Debug for AppState<T> {
 impl AppState<HyperClient> {
     /// Create a new AppState with the default Hyper client
     pub fn new(targets: target::Targets) -> Self {
-        let (max_idle, timeout) = targets
-            .http_pool_config
-            .as_ref()
-            .map(|p| (p.max_idle_per_host, p.idle_timeout_secs))
-            .unwrap_or((100, 90));
-
-        let http_client = client::create_hyper_client(max_idle, timeout);
+        let pool_config = targets.http_pool_config.clone().unwrap_or_default();
+        let http_client = client::create_hyper_client_with_config(&pool_config);
         Self {
             http_client,
             targets,
@@ -281,21 +339,26 @@ impl AppState<HyperClient> {
             response_transform_fn: None,
             streaming_header: None,
             response_id_header: None,
+            route_to_header: None,
+            region_preference_header: None,
+            priority_header: None,
+            deprecation_header: None,
+            request_queue: None,
+            queue_wait_header: None,
             tool_executor: Arc::new(NoOpToolExecutor),
             response_store: Arc::new(NoOpResponseStore),
             body_limit: DEFAULT_BODY_LIMIT,
+            circuit_breakers: None,
+            endpoint_concurrency: target::EndpointConcurrencyLimiters::default(),
+            tls_clients: tls::TlsClients::new(pool_config),
+            key_rotations: key_rotation::KeyRotations::default(),
         }
     }
 
     /// Create a new AppState with the default Hyper client and a body transformation function
     pub fn with_transform(targets: target::Targets, body_transform_fn: BodyTransformFn) -> Self {
-        let (max_idle, timeout) = targets
-            .http_pool_config
-            .as_ref()
-            .map(|p| (p.max_idle_per_host, p.idle_timeout_secs))
-            .unwrap_or((100, 90));
-
-        let http_client = client::create_hyper_client(max_idle, timeout);
+        let pool_config = targets.http_pool_config.clone().unwrap_or_default();
+        let http_client = client::create_hyper_client_with_config(&pool_config);
         Self {
             http_client,
             targets,
@@ -303,9 +366,19 @@ impl AppState<HyperClient> {
             response_transform_fn: None,
             streaming_header: None,
             response_id_header: None,
+            route_to_header: None,
+            region_preference_header: None,
+            priority_header: None,
+            deprecation_header: None,
+            request_queue: None,
+            queue_wait_header: None,
             tool_executor: Arc::new(NoOpToolExecutor),
             response_store: Arc::new(NoOpResponseStore),
             body_limit: DEFAULT_BODY_LIMIT,
+            circuit_breakers: None,
+            endpoint_concurrency: target::EndpointConcurrencyLimiters::default(),
+            tls_clients: tls::TlsClients::new(pool_config),
+            key_rotations: key_rotation::KeyRotations::default(),
         }
     }
 }
@@ -320,9 +393,19 @@ impl<T: HttpClient> AppState<T> {
             response_transform_fn: None,
             streaming_header: None,
             response_id_header: None,
+            route_to_header: None,
+            region_preference_header: None,
+            priority_header: None,
+            deprecation_header: None,
+            request_queue: None,
+            queue_wait_header: None,
             tool_executor: Arc::new(NoOpToolExecutor),
             response_store: Arc::new(NoOpResponseStore),
             body_limit: DEFAULT_BODY_LIMIT,
+            circuit_breakers: None,
+            endpoint_concurrency: target::EndpointConcurrencyLimiters::default(),
+            tls_clients: tls::TlsClients::default(),
+            key_rotations: key_rotation::KeyRotations::default(),
         }
     }
 
@@ -339,9 +422,19 @@ impl<T: HttpClient> AppState<T> {
             response_transform_fn: None,
             streaming_header: None,
             response_id_header: None,
+            route_to_header: None,
+            region_preference_header: None,
+            priority_header: None,
+            deprecation_header: None,
+            request_queue: None,
+            queue_wait_header: None,
             tool_executor: Arc::new(NoOpToolExecutor),
             response_store: Arc::new(NoOpResponseStore),
             body_limit: DEFAULT_BODY_LIMIT,
+            circuit_breakers: None,
+            endpoint_concurrency: target::EndpointConcurrencyLimiters::default(),
+            tls_clients: tls::TlsClients::default(),
+            key_rotations: key_rotation::KeyRotations::default(),
         }
     }
 
@@ -358,6 +451,42 @@ impl<T: HttpClient> AppState<T> {
         self
     }
 
+    /// Set the header name used to pin a request to a named provider in its pool.
+    pub fn with_route_to_header(mut self, header: impl Into<String>) -> Self {
+        self.route_to_header = Some(header.into());
+        self
+    }
+
+    /// Set the header name carrying the client's preferred provider region.
+    pub fn with_region_preference_header(mut self, header: impl Into<String>) -> Self {
+        self.region_preference_header = Some(header.into());
+        self
+    }
+
+    /// Set the header name carrying the request's priority.
+    pub fn with_priority_header(mut self, header: impl Into<String>) -> Self {
+        self.priority_header = Some(header.into());
+        self
+    }
+
+    /// Set the response header that warns about deprecated request fields.
+    pub fn with_deprecation_header(mut self, header: impl Into<String>) -> Self {
+        self.deprecation_header = Some(header.into());
+        self
+    }
+
+    /// Queue requests that find their pool at capacity for up to `config.max_wait`.
+    pub fn with_request_queue(mut self, config: priority::QueueConfig) -> Self {
+        self.request_queue = Some(config);
+        self
+    }
+
+    /// Set the response header reporting how long a request queued for capacity.
+    pub fn with_queue_wait_header(mut self, header: impl Into<String>) -> Self {
+        self.queue_wait_header = Some(header.into());
+        self
+    }
+
     /// Set the response transformation function (builder pattern)
     pub fn with_response_transform(mut self, transform_fn: ResponseTransformFn) -> Self {
         self.response_transform_fn = Some(transform_fn);
@@ -382,6 +511,25 @@ impl<T: HttpClient> AppState<T> {
         self.body_limit = limit;
         self
     }
+
+    /// Enable per-endpoint circuit breaking (builder pattern).
+    pub fn with_circuit_breaker(mut self, config: circuit_breaker::CircuitBreakerConfig) -> Self {
+        self.circuit_breakers = Some(circuit_breaker::CircuitBreakers::new(config));
+        self
+    }
+
+    /// Send a request upstream to `target`, through a client built from its
+    /// TLS settings if it has any.
+    pub(crate) async fn send_upstream(
+        &self,
+        target: &target::Target,
+        req: axum::extract::Request,
+    ) -> Result<axum::response::Response, Box<dyn std::error::Error + Send + Sync>> {
+        match target.tls.as_ref() {
+            Some(tls) => HttpClient::request(&self.tls_clients.get(tls)?, req).await,
+            None => self.http_client.request(req).await,
+        }
+    }
 }
 
 /// Extract the model name from a request
@@ -676,6 +824,28 @@ pub mod test_utils {
             }
         }
 
+        /// Create a mock that returns `body` gzip-compressed, with `content-encoding: gzip`.
+        pub fn new_gzip(status: StatusCode, body: &str) -> Self {
+            use std::io::Write;
+
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(body.as_bytes()).unwrap();
+            let compressed = encoder.finish().unwrap();
+            Self {
+                requests: Arc::new(Mutex::new(Vec::new())),
+                response_builder: Arc::new(move || {
+                    axum::response::Response::builder()
+                        .status(status)
+                        .header("content-type", "application/json")
+                        .header("content-encoding", "gzip")
+                        .body(axum::body::Body::from(compressed.clone()))
+                        .unwrap()
+                }),
+                custom_headers: Arc::new(Mutex::new(Vec::new())),
+            }
+        }
+
         pub fn new_streaming(status: StatusCode, chunks: Vec<String>) -> Self {
             Self {
                 requests: Arc::new(Mutex::new(Vec::new())),
@@ -702,6 +872,52 @@ pub mod test_utils {
             }
         }
 
+        /// Create a mock whose streaming response sends `chunks` and then stays
+        /// open, like a provider still generating. The returned flag is set once
+        /// the upstream body is dropped, i.e. once the proxy has cancelled it.
+        pub fn new_streaming_until_dropped(
+            status: StatusCode,
+            chunks: Vec<String>,
+        ) -> (Self, Arc<std::sync::atomic::AtomicBool>) {
+            struct SetOnDrop(Arc<std::sync::atomic::AtomicBool>);
+            impl Drop for SetOnDrop {
+                fn drop(&mut self) {
+                    self.0.store(true, std::sync::atomic::Ordering::SeqCst);
+                }
+            }
+
+            let dropped = Arc::new(std::sync::atomic::AtomicBool::new(false));
+            let flag = Arc::clone(&dropped);
+            let client = Self {
+                requests: Arc::new(Mutex::new(Vec::new())),
+                custom_headers: Arc::new(Mutex::new(Vec::new())),
+                response_builder: Arc::new(move || {
+                    use axum::body::Body;
+                    use futures_util::{StreamExt, stream};
+
+                    let guard = SetOnDrop(Arc::clone(&flag));
+                    let stream = stream::iter(
+                        chunks
+                            .clone()
+                            .into_iter()
+                            .map(|chunk| Ok::<_, std::io::Error>(chunk.into_bytes())),
+                    )
+                    .chain(stream::pending())
+                    .map(move |chunk| {
+                        let _ = &guard;
+                        chunk
+                    });
+
+                    axum::response::Response::builder()
+                        .status(status)
+                        .header("content-type", "text/event-stream")
+                        .body(Body::from_stream(stream))
+                        .unwrap()
+                }),
+            };
+            (client, dropped)
+        }
+
         /// Create a mock that returns a different streaming response for each
         /// successive call. Useful for testing tool loops where the first call
         /// returns `tool_calls` and the second returns `stop`.
@@ -1014,6 +1230,73 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_gzip_upstream_responses_are_decoded() {
+        let targets_map = Arc::new(DashMap::new());
+        targets_map.insert(
+            "text-embedding".to_string(),
+            pool(
+                target::Target::builder()
+                    .url("https://api.example.com".parse().unwrap())
+                    .build(),
+            ),
+        );
+
+        let targets = target::Targets {
+            targets: targets_map,
+            key_rate_limiters: Arc::new(DashMap::new()),
+            key_concurrency_limiters: Arc::new(DashMap::new()),
+            key_labels: Arc::new(DashMap::new()),
+            strict_mode: false,
+            http_pool_config: Some(target::HttpPoolConfig {
+                gzip: true,
+                ..Default::default()
+            }),
+        };
+
+        let upstream_body = r#"{"object":"list","data":[{"object":"embedding","index":0,"embedding":[0.1,0.2]}],"usage":{"prompt_tokens":3,"total_tokens":3}}"#;
+        let mock_client = MockHttpClient::new_gzip(StatusCode::OK, upstream_body);
+        let app_state = AppState::with_client(targets, mock_client.clone());
+        let router = build_router(app_state);
+        let server = TestServer::new(router).unwrap();
+
+        // A caller without Accept-Encoding: gzip is requested upstream and the
+        // response is decoded before it is returned.
+        let response = server
+            .post("/v1/embeddings")
+            .json(&json!({"model": "text-embedding", "input": "hello"}))
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.headers().get("content-encoding").is_none());
+        assert_eq!(response.text(), upstream_body);
+
+        let requests = mock_client.get_requests();
+        assert!(
+            requests[0]
+                .headers
+                .iter()
+                .any(|(name, value)| name == "accept-encoding" && value == "gzip")
+        );
+
+        // A caller that chose its own encodings gets the upstream response as-is.
+        let response = server
+            .post("/v1/embeddings")
+            .add_header("accept-encoding", "gzip, br")
+            .json(&json!({"model": "text-embedding", "input": "hello"}))
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(response.headers().get("content-encoding").unwrap(), "gzip");
+        let requests = mock_client.get_requests();
+        assert!(
+            requests[1]
+                .headers
+                .iter()
+                .any(|(name, value)| name == "accept-encoding" && value == "gzip, br")
+        );
+    }
+
     #[tokio::test]
     async fn test_multiple_targets_routing() {
         // Create targets with multiple models
@@ -1196,6 +1479,41 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_circuit_breaker_fails_fast_after_repeated_failures() {
+        // Both providers keep failing. Once each has failed twice its breaker
+        // opens, so the next request skips both without reaching the upstream.
+        let mock = MockHttpClient::new(StatusCode::INTERNAL_SERVER_ERROR, r#"{"error":"down"}"#);
+        let app_state =
+            AppState::with_client(fallback_targets("gpt-4", 2, vec![500]), mock.clone())
+                .with_circuit_breaker(circuit_breaker::CircuitBreakerConfig {
+                    failure_threshold: 2,
+                    window_secs: 60,
+                    cooldown_secs: 60,
+                });
+        let server = TestServer::new(build_router(app_state)).unwrap();
+        let request = json!({
+            "model": "gpt-4",
+            "messages": [{"role": "user", "content": "Hello"}]
+        });
+
+        for _ in 0..2 {
+            let response = server.post("/v1/chat/completions").json(&request).await;
+            assert_eq!(response.status_code(), 502);
+        }
+        assert_eq!(mock.get_requests().len(), 4);
+
+        let response = server.post("/v1/chat/completions").json(&request).await;
+        assert_eq!(response.status_code(), 503);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["error"]["code"], "service_unavailable");
+        assert_eq!(
+            mock.get_requests().len(),
+            4,
+            "open breakers must reject without calling the upstream"
+        );
+    }
+
     /// Retry on an upstream 429. Used by the embedded-error tests below.
     fn embedded_error_targets(alias: &str, n: usize) -> target::Targets {
         fallback_targets(alias, n, vec![429])
@@ -1486,6 +1804,67 @@ mod tests {
         );
     }
 
+    /// A client that disconnects mid-stream drops the response body; that must
+    /// drop the upstream body too, so the provider connection is aborted rather
+    /// than left generating tokens nobody will read.
+    #[tokio::test]
+    async fn test_client_disconnect_cancels_upstream_stream() {
+        use futures_util::StreamExt;
+        use tower::ServiceExt;
+
+        let (mock, upstream_dropped) = MockHttpClient::new_streaming_until_dropped(
+            StatusCode::OK,
+            vec!["data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n".to_string()],
+        );
+        let targets_map = Arc::new(DashMap::new());
+        targets_map.insert(
+            "gpt-4".to_string(),
+            pool(
+                target::Target::builder()
+                    .url("https://api.openai.com".parse().unwrap())
+                    .build(),
+            ),
+        );
+        let targets = target::Targets {
+            targets: targets_map,
+            key_rate_limiters: Arc::new(DashMap::new()),
+            key_concurrency_limiters: Arc::new(DashMap::new()),
+            key_labels: Arc::new(DashMap::new()),
+            strict_mode: false,
+            http_pool_config: None,
+        };
+        let router = build_router(AppState::with_client(targets, mock));
+
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri("/v1/chat/completions")
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from(
+                json!({
+                    "model": "gpt-4", "stream": true,
+                    "messages": [{"role": "user", "content": "Hello"}]
+                })
+                .to_string(),
+            ))
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let mut body = response.into_body().into_data_stream();
+        let first = body.next().await.unwrap().unwrap();
+        assert!(String::from_utf8_lossy(&first).contains("Hel"));
+        assert!(
+            !upstream_dropped.load(std::sync::atomic::Ordering::SeqCst),
+            "upstream must stay open while the client is reading"
+        );
+
+        drop(body);
+        assert!(
+            upstream_dropped.load(std::sync::atomic::Ordering::SeqCst),
+            "dropping the client body must cancel the upstream stream"
+        );
+    }
+
     #[tokio::test]
     async fn test_request_and_response_details() {
         // Create a target
@@ -1643,6 +2022,76 @@ mod tests {
         assert_eq!(auth_header, Some(&"Bearer header-key".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_sigv4_target_signs_upstream_request() {
+        let targets_map = Arc::new(DashMap::new());
+        targets_map.insert(
+            "claude".to_string(),
+            pool(
+                Target::builder()
+                    .url(
+                        "https://bedrock-runtime.us-east-1.amazonaws.com/openai/"
+                            .parse()
+                            .unwrap(),
+                    )
+                    .onwards_model("anthropic.claude-3-haiku-20240307-v1:0".to_string())
+                    .sigv4(crate::sigv4::SigV4Config {
+                        region: "us-east-1".to_string(),
+                        service: "bedrock".to_string(),
+                        access_key_id: "AKIDEXAMPLE".to_string(),
+                        secret_access_key: "secret".to_string(),
+                    })
+                    .build(),
+            ),
+        );
+
+        let targets = Targets {
+            targets: targets_map,
+            key_rate_limiters: Arc::new(DashMap::new()),
+            key_concurrency_limiters: Arc::new(DashMap::new()),
+            key_labels: Arc::new(DashMap::new()),
+            strict_mode: false,
+            http_pool_config: None,
+        };
+
+        let mock_client = MockHttpClient::new(StatusCode::OK, r#"{"success": true}"#);
+        let app_state = AppState::with_client(targets, mock_client.clone());
+        let server = TestServer::new(build_router(app_state)).unwrap();
+
+        let response = server
+            .post("/v1/chat/completions")
+            .add_header("authorization", "Bearer caller-key")
+            .json(&json!({
+                "model": "claude",
+                "messages": [{"role": "user", "content": "Test"}]
+            }))
+            .await;
+        assert_eq!(response.status_code(), 200);
+
+        let requests = mock_client.get_requests();
+        assert_eq!(requests.len(), 1);
+        let header = |name: &str| {
+            requests[0]
+                .headers
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.clone())
+        };
+
+        // The caller's bearer token is replaced by a SigV4 signature
+        let auth_header = header("authorization").expect("authorization header");
+        assert!(auth_header.starts_with("AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/"));
+        assert!(auth_header.contains("/us-east-1/bedrock/aws4_request"));
+        assert!(auth_header.contains("SignedHeaders=content-type;host;x-amz-date"));
+        assert!(header("x-amz-date").is_some());
+
+        let forwarded_body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(
+            forwarded_body["model"],
+            "anthropic.claude-3-haiku-20240307-v1:0"
+        );
+    }
+
     #[tokio::test]
     async fn test_models_endpoint_returns_proper_model_list() {
         // Create multiple targets
@@ -2200,31 +2649,31 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_per_key_concurrency_limiting() {
+    async fn test_endpoint_concurrency_limit_is_shared_across_pools() {
         use std::rc::Rc;
 
-        // Create a target without concurrency limit
+        // Two models served by the same endpoint, which takes one request at a time
+        let shared = target::EndpointConcurrencyLimit {
+            endpoint: "shared-endpoint".to_string(),
+            max_concurrent_requests: 1,
+        };
         let targets_map = Arc::new(DashMap::new());
-        targets_map.insert(
-            "test-model".to_string(),
-            pool(
-                Target::builder()
-                    .url("https://api.example.com".parse().unwrap())
-                    .build(),
-            ),
-        );
-
-        // Set up per-key concurrency limiter
-        let key_concurrency_limiters = Arc::new(DashMap::new());
-        key_concurrency_limiters.insert(
-            "sk-limited-key".to_string(),
-            ConcurrencyLimiter::with_limit(1),
-        );
+        for (alias, model) in [("model-a", "a"), ("model-b", "b"), ("model-c", "c")] {
+            let builder = Target::builder()
+                .url("https://api.example.com".parse().unwrap())
+                .onwards_model(model.to_string());
+            let target = if alias == "model-c" {
+                builder.build()
+            } else {
+                builder.endpoint_concurrency_limit(shared.clone()).build()
+            };
+            targets_map.insert(alias.to_string(), target.into_pool());
+        }
 
         let targets = Targets {
             targets: targets_map,
             key_rate_limiters: Arc::new(DashMap::new()),
-            key_concurrency_limiters,
+            key_concurrency_limiters: Arc::new(DashMap::new()),
             key_labels: Arc::new(DashMap::new()),
             strict_mode: false,
             http_pool_config: None,
@@ -2233,50 +2682,229 @@ mod tests {
         let mock_client =
             test_utils::TriggeredMockHttpClient::new(StatusCode::OK, r#"{"success": true}"#);
         let app_state = AppState::with_client(targets, mock_client.clone());
+        let endpoint_concurrency = app_state.endpoint_concurrency.clone();
         let router = build_router(app_state);
         let server = Rc::new(TestServer::new(router).unwrap());
 
         let local = tokio::task::LocalSet::new();
         local
             .run_until(async move {
-                // Start first request with the limited key (it will block waiting for trigger)
+                // A request to model-a holds the endpoint's only slot
                 let server_clone = Rc::clone(&server);
                 let handle1 = tokio::task::spawn_local(async move {
                     server_clone
                         .post("/v1/chat/completions")
-                        .add_header(
-                            axum::http::HeaderName::from_static("authorization"),
-                            axum::http::HeaderValue::from_static("Bearer sk-limited-key"),
-                        )
-                        .json(&json!({"model": "test-model", "messages": []}))
+                        .json(&json!({"model": "model-a", "messages": []}))
                         .await
                 });
-
-                // Give it a moment to start and acquire the permit
                 tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                assert_eq!(endpoint_concurrency.active("shared-endpoint"), 1);
 
-                // Second request with same key should be rejected
+                // model-b has no limit of its own, but shares the endpoint
                 let response2 = server
                     .post("/v1/chat/completions")
-                    .add_header(
-                        axum::http::HeaderName::from_static("authorization"),
-                        axum::http::HeaderValue::from_static("Bearer sk-limited-key"),
-                    )
-                    .json(&json!({"model": "test-model", "messages": []}))
+                    .json(&json!({"model": "model-b", "messages": []}))
                     .await;
-
-                // Second request should be rejected (per-key concurrency limit exceeded)
                 assert_eq!(response2.status_code(), 429);
                 let body: serde_json::Value = response2.json();
                 assert_eq!(body["error"]["code"], "concurrency_limit_exceeded");
 
-                // Complete the first request
-                mock_client.complete_request(0);
-                let response1 = handle1.await.unwrap();
-                assert_eq!(response1.status_code(), 200);
-
-                // Only 1 request made it to the mock client
-                assert_eq!(mock_client.get_requests().len(), 1);
+                // Providers without the endpoint limit are unaffected
+                let server_clone = Rc::clone(&server);
+                let handle3 = tokio::task::spawn_local(async move {
+                    server_clone
+                        .post("/v1/chat/completions")
+                        .json(&json!({"model": "model-c", "messages": []}))
+                        .await
+                });
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                assert_eq!(mock_client.get_requests().len(), 2);
+
+                mock_client.complete_all();
+                assert_eq!(handle1.await.unwrap().status_code(), 200);
+                assert_eq!(handle3.await.unwrap().status_code(), 200);
+                assert_eq!(endpoint_concurrency.active("shared-endpoint"), 0);
+
+                // Once the slot is released, model-b gets through
+                let server_clone = Rc::clone(&server);
+                let handle4 = tokio::task::spawn_local(async move {
+                    server_clone
+                        .post("/v1/chat/completions")
+                        .json(&json!({"model": "model-b", "messages": []}))
+                        .await
+                });
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                mock_client.complete_all();
+                assert_eq!(handle4.await.unwrap().status_code(), 200);
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_per_key_concurrency_limiting() {
+        use std::rc::Rc;
+
+        // Create a target without concurrency limit
+        let targets_map = Arc::new(DashMap::new());
+        targets_map.insert(
+            "test-model".to_string(),
+            pool(
+                Target::builder()
+                    .url("https://api.example.com".parse().unwrap())
+                    .build(),
+            ),
+        );
+
+        // Set up per-key concurrency limiter
+        let key_concurrency_limiters = Arc::new(DashMap::new());
+        key_concurrency_limiters.insert(
+            "sk-limited-key".to_string(),
+            ConcurrencyLimiter::with_limit(1),
+        );
+
+        let targets = Targets {
+            targets: targets_map,
+            key_rate_limiters: Arc::new(DashMap::new()),
+            key_concurrency_limiters,
+            key_labels: Arc::new(DashMap::new()),
+            strict_mode: false,
+            http_pool_config: None,
+        };
+
+        let mock_client =
+            test_utils::TriggeredMockHttpClient::new(StatusCode::OK, r#"{"success": true}"#);
+        let app_state = AppState::with_client(targets, mock_client.clone());
+        let router = build_router(app_state);
+        let server = Rc::new(TestServer::new(router).unwrap());
+
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async move {
+                // Start first request with the limited key (it will block waiting for trigger)
+                let server_clone = Rc::clone(&server);
+                let handle1 = tokio::task::spawn_local(async move {
+                    server_clone
+                        .post("/v1/chat/completions")
+                        .add_header(
+                            axum::http::HeaderName::from_static("authorization"),
+                            axum::http::HeaderValue::from_static("Bearer sk-limited-key"),
+                        )
+                        .json(&json!({"model": "test-model", "messages": []}))
+                        .await
+                });
+
+                // Give it a moment to start and acquire the permit
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+                // Second request with same key should be rejected
+                let response2 = server
+                    .post("/v1/chat/completions")
+                    .add_header(
+                        axum::http::HeaderName::from_static("authorization"),
+                        axum::http::HeaderValue::from_static("Bearer sk-limited-key"),
+                    )
+                    .json(&json!({"model": "test-model", "messages": []}))
+                    .await;
+
+                // Second request should be rejected (per-key concurrency limit exceeded)
+                assert_eq!(response2.status_code(), 429);
+                let body: serde_json::Value = response2.json();
+                assert_eq!(body["error"]["code"], "concurrency_limit_exceeded");
+
+                // Complete the first request
+                mock_client.complete_request(0);
+                let response1 = handle1.await.unwrap();
+                assert_eq!(response1.status_code(), 200);
+
+                // Only 1 request made it to the mock client
+                assert_eq!(mock_client.get_requests().len(), 1);
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_per_key_share_of_pool_capacity() {
+        use std::rc::Rc;
+
+        // Pool capacity of 4, of which any one key may hold at most 2
+        let targets_map = Arc::new(DashMap::new());
+        let target = Target::builder()
+            .url("https://api.example.com".parse().unwrap())
+            .build();
+        targets_map.insert(
+            "shared-model".to_string(),
+            ProviderPool::with_config(
+                vec![Provider::new(target, 1)],
+                None,
+                None,
+                Some(ConcurrencyLimiter::with_limit(4)),
+                None,
+                target::LoadBalanceStrategy::default(),
+                false,
+                Vec::new(),
+            )
+            .with_per_key_concurrency_limiter(Some(target::KeyedConcurrencyLimiter::with_limit(2))),
+        );
+
+        let targets = Targets {
+            targets: targets_map,
+            key_rate_limiters: Arc::new(DashMap::new()),
+            key_concurrency_limiters: Arc::new(DashMap::new()),
+            key_labels: Arc::new(DashMap::new()),
+            strict_mode: false,
+            http_pool_config: None,
+        };
+
+        let mock_client =
+            test_utils::TriggeredMockHttpClient::new(StatusCode::OK, r#"{"success": true}"#);
+        let app_state = AppState::with_client(targets, mock_client.clone());
+        let router = build_router(app_state);
+        let server = Rc::new(TestServer::new(router).unwrap());
+
+        let send = |key: &'static str| {
+            let server = Rc::clone(&server);
+            async move {
+                server
+                    .post("/v1/chat/completions")
+                    .add_header(
+                        axum::http::HeaderName::from_static("authorization"),
+                        axum::http::HeaderValue::from_str(&format!("Bearer {key}")).unwrap(),
+                    )
+                    .json(&json!({"model": "shared-model", "messages": []}))
+                    .await
+            }
+        };
+
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async move {
+                // The greedy key fills its share of the pool...
+                let greedy1 = tokio::task::spawn_local(send("sk-greedy"));
+                let greedy2 = tokio::task::spawn_local(send("sk-greedy"));
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+                // ...and is refused more, although the pool still has free slots
+                let response = send("sk-greedy").await;
+                assert_eq!(response.status_code(), 429);
+                let body: serde_json::Value = response.json();
+                assert_eq!(body["error"]["code"], "concurrency_limit_exceeded");
+
+                // Another key still gets its fair share
+                let other1 = tokio::task::spawn_local(send("sk-other"));
+                let other2 = tokio::task::spawn_local(send("sk-other"));
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                assert_eq!(mock_client.get_requests().len(), 4);
+
+                mock_client.complete_all();
+                for handle in [greedy1, greedy2, other1, other2] {
+                    assert_eq!(handle.await.unwrap().status_code(), 200);
+                }
+
+                // Completed requests release the key's share
+                let response = tokio::task::spawn_local(send("sk-greedy"));
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                mock_client.complete_all();
+                assert_eq!(response.await.unwrap().status_code(), 200);
             })
             .await;
     }
@@ -3179,105 +3807,519 @@ mod tests {
             assert!(response.maybe_header("Input-Price-Per-Token").is_none());
             assert_eq!(response.header("Output-Price-Per-Token"), "0.00008");
         }
-    }
-
-    mod load_balancing {
-        use super::*;
-        use crate::load_balancer::{Provider, ProviderPool};
 
         #[tokio::test]
-        async fn test_load_balancing_with_multiple_providers() {
-            // Create a pool with two providers
-            let providers = vec![
-                Provider::new(
-                    Target::builder()
-                        .url("https://api.provider1.com".parse().unwrap())
-                        .onwards_key("key1".to_string())
-                        .build(),
-                    1,
-                ),
-                Provider::new(
-                    Target::builder()
-                        .url("https://api.provider2.com".parse().unwrap())
-                        .onwards_key("key2".to_string())
-                        .build(),
-                    1,
-                ),
-            ];
-            let pool = ProviderPool::new(providers);
+        async fn test_labeled_pricing_headers_follow_key_labels() {
+            use crate::target::LabeledResponseHeaders;
+
+            let purpose_prices =
+                |purpose: &str, input: &str, output: &str| LabeledResponseHeaders {
+                    match_labels: HashMap::from([("purpose".to_string(), purpose.to_string())]),
+                    headers: HashMap::from([
+                        ("Input-Price-Per-Token".to_string(), input.to_string()),
+                        ("Output-Price-Per-Token".to_string(), output.to_string()),
+                    ]),
+                };
 
             let targets_map = Arc::new(DashMap::new());
-            targets_map.insert("test-model".to_string(), pool);
+            targets_map.insert(
+                "tiered-model".to_string(),
+                pool(
+                    Target::builder()
+                        .url("https://api.example.com".parse().unwrap())
+                        .response_headers(HashMap::from([(
+                            "Input-Price-Per-Token".to_string(),
+                            "0.00003".to_string(),
+                        )]))
+                        .build(),
+                )
+                .with_labeled_response_headers(vec![
+                    purpose_prices("batch", "0.00001", "0.00002"),
+                    purpose_prices("realtime", "0.00003", "0.00006"),
+                ]),
+            );
+            let key_labels = Arc::new(DashMap::new());
+            key_labels.insert(
+                "batch-key".to_string(),
+                HashMap::from([("purpose".to_string(), "batch".to_string())]),
+            );
+            key_labels.insert(
+                "realtime-key".to_string(),
+                HashMap::from([("purpose".to_string(), "realtime".to_string())]),
+            );
 
             let targets = Targets {
                 targets: targets_map,
                 key_rate_limiters: Arc::new(DashMap::new()),
                 key_concurrency_limiters: Arc::new(DashMap::new()),
-                key_labels: Arc::new(DashMap::new()),
+                key_labels,
                 strict_mode: false,
                 http_pool_config: None,
             };
 
             let mock_client = MockHttpClient::new(StatusCode::OK, r#"{"success": true}"#);
-            let app_state = AppState::with_client(targets, mock_client.clone());
-            let router = build_router(app_state);
-            let server = TestServer::new(router).unwrap();
+            let app_state = AppState::with_client(targets, mock_client);
+            let server = TestServer::new(build_router(app_state)).unwrap();
 
-            // Make multiple requests - they should be routed to one of the providers
-            for _ in 0..5 {
-                let response = server
-                    .post("/v1/chat/completions")
-                    .json(&json!({
-                        "model": "test-model",
-                        "messages": [{"role": "user", "content": "Hello"}]
-                    }))
-                    .await;
+            let request = |token: Option<&'static str>| {
+                let mut request = server.post("/v1/chat/completions").json(&json!({
+                    "model": "tiered-model",
+                    "messages": [{"role": "user", "content": "Hello"}]
+                }));
+                if let Some(token) = token {
+                    request = request.add_header("authorization", format!("Bearer {token}"));
+                }
+                request
+            };
 
-                assert_eq!(response.status_code(), 200);
-            }
+            // Each key gets the prices matching its purpose, overriding the provider's own
+            let response = request(Some("batch-key")).await;
+            assert_eq!(response.status_code(), 200);
+            assert_eq!(response.header("Input-Price-Per-Token"), "0.00001");
+            assert_eq!(response.header("Output-Price-Per-Token"), "0.00002");
 
-            // Verify requests were made (at least one should go to each provider over multiple runs)
-            let requests = mock_client.get_requests();
-            assert_eq!(requests.len(), 5);
+            let response = request(Some("realtime-key")).await;
+            assert_eq!(response.header("Input-Price-Per-Token"), "0.00003");
+            assert_eq!(response.header("Output-Price-Per-Token"), "0.00006");
+
+            // Without matching labels only the provider's headers are added
+            let response = request(None).await;
+            assert_eq!(response.header("Input-Price-Per-Token"), "0.00003");
+            assert!(response.maybe_header("Output-Price-Per-Token").is_none());
         }
+    }
 
-        #[tokio::test]
-        async fn test_load_balancing_with_weighted_providers() {
-            // Create a pool with providers having different weights
-            let providers = vec![
-                Provider::new(
+    mod shadow_traffic {
+        use super::*;
+        use crate::auth::ConstantTimeString;
+        use crate::target::ShadowConfig;
+        use std::collections::HashSet;
+
+        fn targets_with_shadow(fraction: f64) -> Targets {
+            let keys = |key: &str| HashSet::from([ConstantTimeString::from(key.to_string())]);
+            let targets_map = Arc::new(DashMap::new());
+            targets_map.insert(
+                "prod-model".to_string(),
+                pool(
                     Target::builder()
-                        .url("https://api.high-weight.com".parse().unwrap())
-                        .onwards_key("key-high".to_string())
+                        .url("https://api.primary.com/v1/".parse().unwrap())
+                        .keys(keys("user-key"))
                         .build(),
-                    3,
-                ), // Higher weight = more traffic
-                Provider::new(
+                )
+                .with_shadow(Some(ShadowConfig {
+                    target: "candidate".to_string(),
+                    fraction,
+                })),
+            );
+            // The caller's key isn't valid on the shadow alias itself
+            targets_map.insert(
+                "candidate".to_string(),
+                pool(
                     Target::builder()
-                        .url("https://api.low-weight.com".parse().unwrap())
-                        .onwards_key("key-low".to_string())
+                        .url("https://api.shadow.com/v1/".parse().unwrap())
+                        .keys(keys("other-key"))
+                        .onwards_key("shadow-upstream-key".to_string())
+                        .onwards_model("candidate-upstream".to_string())
                         .build(),
-                    1,
                 ),
-            ];
-            let pool = ProviderPool::new(providers);
-
-            let targets_map = Arc::new(DashMap::new());
-            targets_map.insert("weighted-model".to_string(), pool);
-
-            let targets = Targets {
+            );
+            Targets {
                 targets: targets_map,
                 key_rate_limiters: Arc::new(DashMap::new()),
                 key_concurrency_limiters: Arc::new(DashMap::new()),
                 key_labels: Arc::new(DashMap::new()),
                 strict_mode: false,
                 http_pool_config: None,
-            };
-
-            let mock_client = MockHttpClient::new(StatusCode::OK, r#"{"success": true}"#);
-            let app_state = AppState::with_client(targets, mock_client.clone());
-            let router = build_router(app_state);
-            let server = TestServer::new(router).unwrap();
+            }
+        }
+
+        async fn send(server: &TestServer) -> axum_test::TestResponse {
+            server
+                .post("/v1/chat/completions")
+                .add_header("authorization", "Bearer user-key")
+                .json(&json!({
+                    "model": "prod-model",
+                    "messages": [{"role": "user", "content": "Hello"}]
+                }))
+                .await
+        }
+
+        #[tokio::test]
+        async fn test_shadow_copies_request_without_changing_response() {
+            let mock_client = MockHttpClient::new(StatusCode::OK, r#"{"id": "primary"}"#);
+            let app_state = AppState::with_client(targets_with_shadow(1.0), mock_client.clone());
+            let server = TestServer::new(build_router(app_state)).unwrap();
+
+            let response = send(&server).await;
+            assert_eq!(response.status_code(), 200);
+            assert_eq!(
+                response.json::<serde_json::Value>(),
+                json!({"id": "primary"})
+            );
+
+            // The copy is sent in the background
+            for _ in 0..100 {
+                if mock_client.get_requests().len() >= 2 {
+                    break;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+            let requests = mock_client.get_requests();
+            assert_eq!(requests.len(), 2, "primary request plus one shadow copy");
+
+            let primary = requests
+                .iter()
+                .find(|r| r.uri.starts_with("https://api.primary.com"))
+                .expect("primary request sent");
+            let primary_body: serde_json::Value = serde_json::from_slice(&primary.body).unwrap();
+            assert_eq!(primary_body["model"], "prod-model");
+
+            let copy = requests
+                .iter()
+                .find(|r| r.uri.starts_with("https://api.shadow.com"))
+                .expect("shadow copy sent");
+            assert_eq!(copy.uri, "https://api.shadow.com/v1/chat/completions");
+            let copy_body: serde_json::Value = serde_json::from_slice(&copy.body).unwrap();
+            assert_eq!(copy_body["model"], "candidate-upstream");
+            assert_eq!(copy_body["messages"], primary_body["messages"]);
+            // The copy carries the shadow's upstream credentials, not the caller's key
+            assert!(copy.headers.iter().any(|(name, value)| {
+                name.eq_ignore_ascii_case("authorization") && value == "Bearer shadow-upstream-key"
+            }));
+            assert!(
+                !copy
+                    .headers
+                    .iter()
+                    .any(|(_, value)| value.contains("user-key"))
+            );
+        }
+
+        #[tokio::test]
+        async fn test_shadow_skips_unsampled_and_rejected_requests() {
+            let mock_client = MockHttpClient::new(StatusCode::OK, r#"{"id": "primary"}"#);
+            let app_state = AppState::with_client(targets_with_shadow(0.0), mock_client.clone());
+            let server = TestServer::new(build_router(app_state)).unwrap();
+            assert_eq!(send(&server).await.status_code(), 200);
+
+            // A request the primary alias rejects is never copied
+            let mock_client_all = MockHttpClient::new(StatusCode::OK, r#"{"id": "primary"}"#);
+            let app_state =
+                AppState::with_client(targets_with_shadow(1.0), mock_client_all.clone());
+            let server_all = TestServer::new(build_router(app_state)).unwrap();
+            let response = server_all
+                .post("/v1/chat/completions")
+                .add_header("authorization", "Bearer wrong-key")
+                .json(&json!({"model": "prod-model", "messages": []}))
+                .await;
+            assert_eq!(response.status_code(), 403);
+
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            assert_eq!(mock_client.get_requests().len(), 1);
+            assert!(mock_client_all.get_requests().is_empty());
+        }
+    }
+
+    mod system_prompt_injection {
+        use super::*;
+        use crate::target::{SystemPromptConfig, SystemPromptMode};
+
+        #[tokio::test]
+        async fn test_forwarded_request_carries_system_prompt() {
+            let targets_map = Arc::new(DashMap::new());
+            targets_map.insert(
+                "assistant".to_string(),
+                pool(
+                    Target::builder()
+                        .url("https://api.example.com/v1/".parse().unwrap())
+                        .build(),
+                )
+                .with_system_prompt(Some(SystemPromptConfig {
+                    content: "You are a support agent.".to_string(),
+                    mode: SystemPromptMode::Merge,
+                })),
+            );
+            let targets = Targets {
+                targets: targets_map,
+                key_rate_limiters: Arc::new(DashMap::new()),
+                key_concurrency_limiters: Arc::new(DashMap::new()),
+                key_labels: Arc::new(DashMap::new()),
+                strict_mode: false,
+                http_pool_config: None,
+            };
+            let mock_client = MockHttpClient::new(StatusCode::OK, r#"{"id": "ok"}"#);
+            let app_state = AppState::with_client(targets, mock_client.clone());
+            let server = TestServer::new(build_router(app_state)).unwrap();
+
+            let response = server
+                .post("/v1/chat/completions")
+                .json(&json!({
+                    "model": "assistant",
+                    "messages": [
+                        {"role": "system", "content": "Reply in one line."},
+                        {"role": "user", "content": "Hello"}
+                    ]
+                }))
+                .await;
+            assert_eq!(response.status_code(), 200);
+
+            let requests = mock_client.get_requests();
+            assert_eq!(requests.len(), 1);
+            let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+            assert_eq!(
+                body["messages"],
+                json!([
+                    {"role": "system", "content": "You are a support agent.\n\nReply in one line."},
+                    {"role": "user", "content": "Hello"}
+                ])
+            );
+        }
+    }
+
+    mod request_priority {
+        use super::*;
+        use crate::priority::{MAX_PRIORITY_LABEL, QueueConfig};
+        use std::collections::HashMap;
+
+        fn targets() -> Targets {
+            let targets_map = Arc::new(DashMap::new());
+            targets_map.insert(
+                "assistant".to_string(),
+                pool(
+                    Target::builder()
+                        .url("https://api.example.com/v1/".parse().unwrap())
+                        .build(),
+                ),
+            );
+            let key_labels = Arc::new(DashMap::new());
+            key_labels.insert(
+                "interactive-key".to_string(),
+                HashMap::from([(MAX_PRIORITY_LABEL.to_string(), "3".to_string())]),
+            );
+            Targets {
+                targets: targets_map,
+                key_rate_limiters: Arc::new(DashMap::new()),
+                key_concurrency_limiters: Arc::new(DashMap::new()),
+                key_labels,
+                strict_mode: false,
+                http_pool_config: None,
+            }
+        }
+
+        #[tokio::test]
+        async fn test_priority_header_is_capped_by_key() {
+            let mock_client = MockHttpClient::new(StatusCode::OK, r#"{"id": "ok"}"#);
+            let app_state = AppState::with_client(targets(), mock_client.clone())
+                .with_priority_header("x-priority")
+                .with_request_queue(QueueConfig::default());
+            let server = TestServer::new(build_router(app_state)).unwrap();
+            let body = json!({
+                "model": "assistant",
+                "messages": [{"role": "user", "content": "Hello"}]
+            });
+
+            let send = |key: &'static str, priority: &'static str| {
+                server
+                    .post("/v1/chat/completions")
+                    .add_header("authorization", format!("Bearer {key}"))
+                    .add_header("x-priority", priority)
+                    .json(&body)
+            };
+
+            assert_eq!(send("interactive-key", "3").await.status_code(), 200);
+            assert_eq!(send("interactive-key", "4").await.status_code(), 400);
+            assert_eq!(send("interactive-key", "urgent").await.status_code(), 400);
+            // Keys without the label may only use the default priority
+            assert_eq!(send("other-key", "0").await.status_code(), 200);
+            assert_eq!(send("other-key", "1").await.status_code(), 400);
+
+            let requests = mock_client.get_requests();
+            assert_eq!(requests.len(), 2);
+            assert!(
+                requests
+                    .iter()
+                    .all(|r| !r.headers.iter().any(|(k, _)| k == "x-priority")),
+                "priority header must not be forwarded upstream"
+            );
+        }
+    }
+
+    mod n_limit_enforcement {
+        use super::*;
+        use crate::target::{NLimitConfig, NLimitMode};
+
+        fn server_with_n_limit(mode: NLimitMode) -> (TestServer, MockHttpClient) {
+            let targets_map = Arc::new(DashMap::new());
+            targets_map.insert(
+                "sampler".to_string(),
+                pool(
+                    Target::builder()
+                        .url("https://api.example.com/v1/".parse().unwrap())
+                        .build(),
+                )
+                .with_n_limit(Some(NLimitConfig { max: 4, mode })),
+            );
+            let targets = Targets {
+                targets: targets_map,
+                key_rate_limiters: Arc::new(DashMap::new()),
+                key_concurrency_limiters: Arc::new(DashMap::new()),
+                key_labels: Arc::new(DashMap::new()),
+                strict_mode: false,
+                http_pool_config: None,
+            };
+            let mock_client = MockHttpClient::new(StatusCode::OK, r#"{"id": "ok"}"#);
+            let app_state = AppState::with_client(targets, mock_client.clone());
+            (
+                TestServer::new(build_router(app_state)).unwrap(),
+                mock_client,
+            )
+        }
+
+        fn request_body(n: u64) -> serde_json::Value {
+            json!({
+                "model": "sampler",
+                "n": n,
+                "messages": [{"role": "user", "content": "Hello"}]
+            })
+        }
+
+        #[tokio::test]
+        async fn test_n_above_cap_is_clamped_before_forwarding() {
+            let (server, mock_client) = server_with_n_limit(NLimitMode::Clamp);
+
+            let response = server
+                .post("/v1/chat/completions")
+                .json(&request_body(50))
+                .await;
+            assert_eq!(response.status_code(), 200);
+
+            let requests = mock_client.get_requests();
+            assert_eq!(requests.len(), 1);
+            let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+            assert_eq!(body["n"], 4);
+        }
+
+        #[tokio::test]
+        async fn test_n_above_cap_is_rejected() {
+            let (server, mock_client) = server_with_n_limit(NLimitMode::Reject);
+
+            let response = server
+                .post("/v1/chat/completions")
+                .json(&request_body(50))
+                .await;
+            assert_eq!(response.status_code(), 400);
+            let body: serde_json::Value = response.json();
+            assert_eq!(body["error"]["param"], "n");
+            assert!(mock_client.get_requests().is_empty());
+
+            // An n within the cap is forwarded as sent
+            let response = server
+                .post("/v1/chat/completions")
+                .json(&request_body(4))
+                .await;
+            assert_eq!(response.status_code(), 200);
+            let requests = mock_client.get_requests();
+            let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+            assert_eq!(body["n"], 4);
+        }
+    }
+
+    mod load_balancing {
+        use super::*;
+        use crate::load_balancer::{Provider, ProviderPool};
+
+        #[tokio::test]
+        async fn test_load_balancing_with_multiple_providers() {
+            // Create a pool with two providers
+            let providers = vec![
+                Provider::new(
+                    Target::builder()
+                        .url("https://api.provider1.com".parse().unwrap())
+                        .onwards_key("key1".to_string())
+                        .build(),
+                    1,
+                ),
+                Provider::new(
+                    Target::builder()
+                        .url("https://api.provider2.com".parse().unwrap())
+                        .onwards_key("key2".to_string())
+                        .build(),
+                    1,
+                ),
+            ];
+            let pool = ProviderPool::new(providers);
+
+            let targets_map = Arc::new(DashMap::new());
+            targets_map.insert("test-model".to_string(), pool);
+
+            let targets = Targets {
+                targets: targets_map,
+                key_rate_limiters: Arc::new(DashMap::new()),
+                key_concurrency_limiters: Arc::new(DashMap::new()),
+                key_labels: Arc::new(DashMap::new()),
+                strict_mode: false,
+                http_pool_config: None,
+            };
+
+            let mock_client = MockHttpClient::new(StatusCode::OK, r#"{"success": true}"#);
+            let app_state = AppState::with_client(targets, mock_client.clone());
+            let router = build_router(app_state);
+            let server = TestServer::new(router).unwrap();
+
+            // Make multiple requests - they should be routed to one of the providers
+            for _ in 0..5 {
+                let response = server
+                    .post("/v1/chat/completions")
+                    .json(&json!({
+                        "model": "test-model",
+                        "messages": [{"role": "user", "content": "Hello"}]
+                    }))
+                    .await;
+
+                assert_eq!(response.status_code(), 200);
+            }
+
+            // Verify requests were made (at least one should go to each provider over multiple runs)
+            let requests = mock_client.get_requests();
+            assert_eq!(requests.len(), 5);
+        }
+
+        #[tokio::test]
+        async fn test_load_balancing_with_weighted_providers() {
+            // Create a pool with providers having different weights
+            let providers = vec![
+                Provider::new(
+                    Target::builder()
+                        .url("https://api.high-weight.com".parse().unwrap())
+                        .onwards_key("key-high".to_string())
+                        .build(),
+                    3,
+                ), // Higher weight = more traffic
+                Provider::new(
+                    Target::builder()
+                        .url("https://api.low-weight.com".parse().unwrap())
+                        .onwards_key("key-low".to_string())
+                        .build(),
+                    1,
+                ),
+            ];
+            let pool = ProviderPool::new(providers);
+
+            let targets_map = Arc::new(DashMap::new());
+            targets_map.insert("weighted-model".to_string(), pool);
+
+            let targets = Targets {
+                targets: targets_map,
+                key_rate_limiters: Arc::new(DashMap::new()),
+                key_concurrency_limiters: Arc::new(DashMap::new()),
+                key_labels: Arc::new(DashMap::new()),
+                strict_mode: false,
+                http_pool_config: None,
+            };
+
+            let mock_client = MockHttpClient::new(StatusCode::OK, r#"{"success": true}"#);
+            let app_state = AppState::with_client(targets, mock_client.clone());
+            let router = build_router(app_state);
+            let server = TestServer::new(router).unwrap();
 
             // Make enough requests that a 3:1 weighted random pool should
             // reliably favor the high-weight provider without making the test
@@ -3286,37 +4328,317 @@ mod tests {
                 let response = server
                     .post("/v1/chat/completions")
                     .json(&json!({
-                        "model": "weighted-model",
+                        "model": "weighted-model",
+                        "messages": [{"role": "user", "content": "Hello"}]
+                    }))
+                    .await;
+
+                assert_eq!(response.status_code(), 200);
+            }
+
+            let requests = mock_client.get_requests();
+            assert_eq!(requests.len(), 100);
+
+            // Count requests to each provider (URI is the full path, check if it contains the host)
+            let high_weight_count = requests
+                .iter()
+                .filter(|r| r.uri.contains("api.high-weight.com"))
+                .count();
+            let low_weight_count = requests
+                .iter()
+                .filter(|r| r.uri.contains("api.low-weight.com"))
+                .count();
+
+            // With weights 3:1, high-weight should get roughly 3x more traffic
+            // Allow for statistical variance - high weight should have at least more requests
+            assert!(
+                high_weight_count > low_weight_count,
+                "Expected high-weight provider ({}) to receive more requests than low-weight ({})",
+                high_weight_count,
+                low_weight_count
+            );
+        }
+
+        /// Two named providers behind a keyed "composite" alias, with each
+        /// provider also registered as its own keyed alias.
+        fn route_to_targets() -> Targets {
+            let composite_keys: auth::KeySet =
+                ["caller-key".to_string().into()].into_iter().collect();
+            let component_a_keys: auth::KeySet =
+                ["caller-key".to_string().into()].into_iter().collect();
+            let component_b_keys: auth::KeySet =
+                ["other-key".to_string().into()].into_iter().collect();
+
+            let named_target = |name: &str, url: &str| {
+                Target::builder()
+                    .name(name.to_string())
+                    .url(url.parse().unwrap())
+                    .build()
+            };
+
+            let composite = ProviderPool::with_config(
+                vec![
+                    Provider::new(named_target("component-a", "https://api.a.com"), 1),
+                    Provider::new(named_target("component-b", "https://api.b.com"), 1000),
+                ],
+                Some(composite_keys),
+                None,
+                None,
+                None,
+                Default::default(),
+                false,
+                Vec::new(),
+            );
+            let component_a = ProviderPool::with_config(
+                vec![Provider::new(
+                    named_target("component-a", "https://api.a.com"),
+                    1,
+                )],
+                Some(component_a_keys),
+                None,
+                None,
+                None,
+                Default::default(),
+                false,
+                Vec::new(),
+            );
+            let component_b = ProviderPool::with_config(
+                vec![Provider::new(
+                    named_target("component-b", "https://api.b.com"),
+                    1,
+                )],
+                Some(component_b_keys),
+                None,
+                None,
+                None,
+                Default::default(),
+                false,
+                Vec::new(),
+            );
+
+            let targets_map = Arc::new(DashMap::new());
+            targets_map.insert("composite".to_string(), composite);
+            targets_map.insert("component-a".to_string(), component_a);
+            targets_map.insert("component-b".to_string(), component_b);
+
+            Targets {
+                targets: targets_map,
+                key_rate_limiters: Arc::new(DashMap::new()),
+                key_concurrency_limiters: Arc::new(DashMap::new()),
+                key_labels: Arc::new(DashMap::new()),
+                strict_mode: false,
+                http_pool_config: None,
+            }
+        }
+
+        #[tokio::test]
+        async fn test_route_to_header_pins_named_provider() {
+            let mock_client = MockHttpClient::new(StatusCode::OK, r#"{"success": true}"#);
+            let app_state = AppState::with_client(route_to_targets(), mock_client.clone())
+                .with_route_to_header("x-route-to");
+            let server = TestServer::new(build_router(app_state)).unwrap();
+
+            // component-b carries almost all of the weight, so without pinning
+            // the low-weight provider would rarely be chosen.
+            for _ in 0..10 {
+                let response = server
+                    .post("/v1/chat/completions")
+                    .add_header("authorization", "Bearer caller-key")
+                    .add_header("x-route-to", "component-a")
+                    .json(&json!({
+                        "model": "composite",
+                        "messages": [{"role": "user", "content": "Hello"}]
+                    }))
+                    .await;
+                assert_eq!(response.status_code(), 200);
+            }
+
+            let requests = mock_client.get_requests();
+            assert_eq!(requests.len(), 10);
+            assert!(requests.iter().all(|r| r.uri.contains("api.a.com")));
+            assert!(
+                requests
+                    .iter()
+                    .all(|r| !r.headers.iter().any(|(k, _)| k == "x-route-to")),
+                "route-to header must not be forwarded upstream"
+            );
+        }
+
+        #[tokio::test]
+        async fn test_route_to_header_rejects_unauthorized_target() {
+            let mock_client = MockHttpClient::new(StatusCode::OK, r#"{"success": true}"#);
+            let app_state = AppState::with_client(route_to_targets(), mock_client.clone())
+                .with_route_to_header("x-route-to");
+            let server = TestServer::new(build_router(app_state)).unwrap();
+
+            // The caller can use the composite but not component-b directly.
+            let response = server
+                .post("/v1/chat/completions")
+                .add_header("authorization", "Bearer caller-key")
+                .add_header("x-route-to", "component-b")
+                .json(&json!({
+                    "model": "composite",
+                    "messages": [{"role": "user", "content": "Hello"}]
+                }))
+                .await;
+            assert_eq!(response.status_code(), 403);
+
+            // Unknown targets are rejected the same way.
+            let response = server
+                .post("/v1/chat/completions")
+                .add_header("authorization", "Bearer caller-key")
+                .add_header("x-route-to", "does-not-exist")
+                .json(&json!({
+                    "model": "composite",
+                    "messages": [{"role": "user", "content": "Hello"}]
+                }))
+                .await;
+            assert_eq!(response.status_code(), 403);
+
+            assert!(mock_client.get_requests().is_empty());
+        }
+
+        #[tokio::test]
+        async fn test_route_to_header_ignored_when_not_configured() {
+            let mock_client = MockHttpClient::new(StatusCode::OK, r#"{"success": true}"#);
+            let app_state = AppState::with_client(route_to_targets(), mock_client.clone());
+            let server = TestServer::new(build_router(app_state)).unwrap();
+
+            let response = server
+                .post("/v1/chat/completions")
+                .add_header("authorization", "Bearer caller-key")
+                .add_header("x-route-to", "component-b")
+                .json(&json!({
+                    "model": "composite",
+                    "messages": [{"role": "user", "content": "Hello"}]
+                }))
+                .await;
+            assert_eq!(response.status_code(), 200);
+        }
+
+        /// A two-region pool behind "multi-region", where us-east carries almost
+        /// all of the weight. Keys labelled `region=eu` prefer eu-west by rule.
+        fn multi_region_targets() -> Targets {
+            use crate::target::{RoutingAction, RoutingRule};
+            use std::collections::HashMap;
+
+            let regional_target = |region: &str, url: &str| {
+                Target::builder()
+                    .region(region.to_string())
+                    .url(url.parse().unwrap())
+                    .build()
+            };
+            let pool = ProviderPool::with_config(
+                vec![
+                    Provider::new(regional_target("eu-west", "https://eu.example.com"), 1),
+                    Provider::new(regional_target("us-east", "https://us.example.com"), 1000),
+                ],
+                None,
+                None,
+                None,
+                None,
+                Default::default(),
+                false,
+                vec![RoutingRule {
+                    match_labels: HashMap::from([("region".to_string(), "eu".to_string())]),
+                    action: RoutingAction::PreferRegion {
+                        region: "eu-west".to_string(),
+                    },
+                }],
+            );
+
+            let targets_map = Arc::new(DashMap::new());
+            targets_map.insert("multi-region".to_string(), pool);
+            let key_labels = Arc::new(DashMap::new());
+            key_labels.insert(
+                "eu-key".to_string(),
+                HashMap::from([("region".to_string(), "eu".to_string())]),
+            );
+
+            Targets {
+                targets: targets_map,
+                key_rate_limiters: Arc::new(DashMap::new()),
+                key_concurrency_limiters: Arc::new(DashMap::new()),
+                key_labels,
+                strict_mode: false,
+                http_pool_config: None,
+            }
+        }
+
+        #[tokio::test]
+        async fn test_region_preference_header_steers_to_matching_provider() {
+            let mock_client = MockHttpClient::new(StatusCode::OK, r#"{"success": true}"#);
+            let app_state = AppState::with_client(multi_region_targets(), mock_client.clone())
+                .with_region_preference_header("x-region-preference");
+            let server = TestServer::new(build_router(app_state)).unwrap();
+
+            // Without the hint, the low-weight eu-west provider would rarely be chosen
+            for _ in 0..10 {
+                let response = server
+                    .post("/v1/chat/completions")
+                    .add_header("x-region-preference", "eu-west")
+                    .json(&json!({
+                        "model": "multi-region",
                         "messages": [{"role": "user", "content": "Hello"}]
                     }))
                     .await;
-
                 assert_eq!(response.status_code(), 200);
             }
 
             let requests = mock_client.get_requests();
-            assert_eq!(requests.len(), 100);
-
-            // Count requests to each provider (URI is the full path, check if it contains the host)
-            let high_weight_count = requests
-                .iter()
-                .filter(|r| r.uri.contains("api.high-weight.com"))
-                .count();
-            let low_weight_count = requests
-                .iter()
-                .filter(|r| r.uri.contains("api.low-weight.com"))
-                .count();
-
-            // With weights 3:1, high-weight should get roughly 3x more traffic
-            // Allow for statistical variance - high weight should have at least more requests
+            assert_eq!(requests.len(), 10);
+            assert!(requests.iter().all(|r| r.uri.contains("eu.example.com")));
             assert!(
-                high_weight_count > low_weight_count,
-                "Expected high-weight provider ({}) to receive more requests than low-weight ({})",
-                high_weight_count,
-                low_weight_count
+                requests
+                    .iter()
+                    .all(|r| !r.headers.iter().any(|(k, _)| k == "x-region-preference")),
+                "region preference header must not be forwarded upstream"
             );
         }
 
+        #[tokio::test]
+        async fn test_region_preference_falls_back_cross_region() {
+            let mock_client = MockHttpClient::new(StatusCode::OK, r#"{"success": true}"#);
+            let app_state = AppState::with_client(multi_region_targets(), mock_client.clone())
+                .with_region_preference_header("x-region-preference");
+            let server = TestServer::new(build_router(app_state)).unwrap();
+
+            // No provider is in ap-south, so the request is served from another region
+            let response = server
+                .post("/v1/chat/completions")
+                .add_header("x-region-preference", "ap-south")
+                .json(&json!({
+                    "model": "multi-region",
+                    "messages": [{"role": "user", "content": "Hello"}]
+                }))
+                .await;
+            assert_eq!(response.status_code(), 200);
+            assert_eq!(mock_client.get_requests().len(), 1);
+        }
+
+        #[tokio::test]
+        async fn test_prefer_region_routing_rule_by_key_label() {
+            let mock_client = MockHttpClient::new(StatusCode::OK, r#"{"success": true}"#);
+            let app_state = AppState::with_client(multi_region_targets(), mock_client.clone());
+            let server = TestServer::new(build_router(app_state)).unwrap();
+
+            for _ in 0..10 {
+                let response = server
+                    .post("/v1/chat/completions")
+                    .add_header("authorization", "Bearer eu-key")
+                    .json(&json!({
+                        "model": "multi-region",
+                        "messages": [{"role": "user", "content": "Hello"}]
+                    }))
+                    .await;
+                assert_eq!(response.status_code(), 200);
+            }
+
+            let requests = mock_client.get_requests();
+            assert_eq!(requests.len(), 10);
+            assert!(requests.iter().all(|r| r.uri.contains("eu.example.com")));
+        }
+
         #[tokio::test]
         async fn test_single_provider_pool_behaves_like_single_target() {
             // A pool with a single provider should work identically to the old behavior
@@ -3414,6 +4736,229 @@ mod tests {
         assert!(upstream_body.get("reasoning_effort").is_none());
     }
 
+    #[tokio::test]
+    async fn test_provider_body_transform_applies_to_request_and_response() {
+        let body_transform = serde_json::from_value(json!({
+            "request": [
+                {"op": "remove", "path": "/logit_bias"},
+                {"op": "rename", "from": "/max_completion_tokens", "to": "/max_tokens"},
+                {"op": "prepend", "path": "/messages", "value": {"role": "system", "content": "Answer in English."}}
+            ],
+            "response": [
+                {"op": "remove", "path": "/provider_metadata"}
+            ]
+        }))
+        .unwrap();
+        let targets_map = Arc::new(DashMap::new());
+        targets_map.insert(
+            "quirky".to_string(),
+            pool(
+                Target::builder()
+                    .url("https://quirky.example.com".parse().unwrap())
+                    .body_transform(body_transform)
+                    .build(),
+            ),
+        );
+        targets_map.insert(
+            "plain".to_string(),
+            pool(
+                Target::builder()
+                    .url("https://plain.example.com".parse().unwrap())
+                    .build(),
+            ),
+        );
+        let targets = Targets {
+            targets: targets_map,
+            key_rate_limiters: Arc::new(DashMap::new()),
+            key_concurrency_limiters: Arc::new(DashMap::new()),
+            key_labels: Arc::new(DashMap::new()),
+            strict_mode: false,
+            http_pool_config: None,
+        };
+        let mock_client = MockHttpClient::new(
+            StatusCode::OK,
+            r#"{"id":"chatcmpl-1","object":"chat.completion","provider_metadata":{"node":"a"}}"#,
+        );
+        let app_state = AppState::with_client(targets, mock_client.clone());
+        let server = TestServer::new(build_router(app_state)).unwrap();
+
+        let request = |model: &str| {
+            json!({
+                "model": model,
+                "messages": [{"role": "user", "content": "Hello"}],
+                "max_completion_tokens": 64,
+                "logit_bias": {"50256": -100}
+            })
+        };
+
+        let response = server
+            .post("/v1/chat/completions")
+            .json(&request("quirky"))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let body: serde_json::Value = response.json();
+        assert!(body.get("provider_metadata").is_none());
+        assert_eq!(body["id"], "chatcmpl-1");
+
+        let requests = mock_client.get_requests();
+        let upstream_body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert!(upstream_body.get("logit_bias").is_none());
+        assert!(upstream_body.get("max_completion_tokens").is_none());
+        assert_eq!(upstream_body["max_tokens"], 64);
+        assert_eq!(upstream_body["messages"][0]["role"], "system");
+        assert_eq!(upstream_body["messages"][1]["content"], "Hello");
+
+        // Providers without a transform forward and return bodies unchanged
+        let response = server
+            .post("/v1/chat/completions")
+            .json(&request("plain"))
+            .await;
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["provider_metadata"]["node"], "a");
+        let requests = mock_client.get_requests();
+        let upstream_body: serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+        assert_eq!(upstream_body["max_completion_tokens"], 64);
+        assert_eq!(upstream_body["messages"].as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_legacy_functions_request_is_translated_for_tools_only_provider() {
+        let targets_map = Arc::new(DashMap::new());
+        targets_map.insert(
+            "tools-only".to_string(),
+            pool(
+                Target::builder()
+                    .url("https://tools-only.example.com".parse().unwrap())
+                    .body_transform(
+                        serde_json::from_value(json!({"tool_calling": "tools"})).unwrap(),
+                    )
+                    .build(),
+            ),
+        );
+        let targets = Targets {
+            targets: targets_map,
+            key_rate_limiters: Arc::new(DashMap::new()),
+            key_concurrency_limiters: Arc::new(DashMap::new()),
+            key_labels: Arc::new(DashMap::new()),
+            strict_mode: false,
+            http_pool_config: None,
+        };
+        let mock_client = MockHttpClient::new(
+            StatusCode::OK,
+            r#"{"id":"chatcmpl-1","object":"chat.completion","choices":[{"index":0,"message":{"role":"assistant","content":null,"tool_calls":[{"id":"call_up","type":"function","function":{"name":"get_weather","arguments":"{\"city\":\"Paris\"}"}}]},"finish_reason":"tool_calls"}]}"#,
+        );
+        let app_state = AppState::with_client(targets, mock_client.clone());
+        let server = TestServer::new(build_router(app_state)).unwrap();
+
+        let response = server
+            .post("/v1/chat/completions")
+            .json(&json!({
+                "model": "tools-only",
+                "messages": [{"role": "user", "content": "Weather in Paris?"}],
+                "functions": [{"name": "get_weather", "parameters": {"type": "object"}}],
+                "function_call": "auto"
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        // The provider receives tools
+        let requests = mock_client.get_requests();
+        let upstream_body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert!(upstream_body.get("functions").is_none());
+        assert!(upstream_body.get("function_call").is_none());
+        assert_eq!(upstream_body["tools"][0]["type"], "function");
+        assert_eq!(upstream_body["tools"][0]["function"]["name"], "get_weather");
+        assert_eq!(upstream_body["tool_choice"], "auto");
+        assert_eq!(upstream_body["parallel_tool_calls"], false);
+
+        // The client gets a legacy function call back
+        let body: serde_json::Value = response.json();
+        let choice = &body["choices"][0];
+        assert_eq!(choice["finish_reason"], "function_call");
+        assert!(choice["message"].get("tool_calls").is_none());
+        assert_eq!(choice["message"]["function_call"]["name"], "get_weather");
+        assert_eq!(
+            choice["message"]["function_call"]["arguments"],
+            r#"{"city":"Paris"}"#
+        );
+
+        // Clients already using tools are forwarded and answered unchanged
+        let response = server
+            .post("/v1/chat/completions")
+            .json(&json!({
+                "model": "tools-only",
+                "messages": [{"role": "user", "content": "Weather in Paris?"}],
+                "tools": [{"type": "function", "function": {"name": "get_weather"}}]
+            }))
+            .await;
+        let requests = mock_client.get_requests();
+        let upstream_body: serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+        assert!(upstream_body.get("parallel_tool_calls").is_none());
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["choices"][0]["finish_reason"], "tool_calls");
+        assert_eq!(
+            body["choices"][0]["message"]["tool_calls"][0]["id"],
+            "call_up"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_deprecated_fields_add_warning_header() {
+        let targets_map = Arc::new(DashMap::new());
+        targets_map.insert(
+            "gpt-4".to_string(),
+            pool(
+                Target::builder()
+                    .url("https://api.openai.com".parse().unwrap())
+                    .build(),
+            ),
+        );
+        let targets = Targets {
+            targets: targets_map,
+            key_rate_limiters: Arc::new(DashMap::new()),
+            key_concurrency_limiters: Arc::new(DashMap::new()),
+            key_labels: Arc::new(DashMap::new()),
+            strict_mode: false,
+            http_pool_config: None,
+        };
+        let mock_client = MockHttpClient::new(StatusCode::OK, r#"{"object":"chat.completion"}"#);
+        let app_state = AppState::with_client(targets, mock_client.clone())
+            .with_deprecation_header("openai-deprecation");
+        let server = TestServer::new(build_router(app_state)).unwrap();
+
+        let response = server
+            .post("/v1/chat/completions")
+            .json(&json!({
+                "model": "gpt-4",
+                "messages": [{"role": "user", "content": "Weather in Paris?"}],
+                "functions": [{"name": "get_weather", "parameters": {"type": "object"}}],
+                "max_tokens": 64
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(
+            response.headers()["openai-deprecation"],
+            "\"functions\";replacement=\"tools\", \"max_tokens\";replacement=\"max_completion_tokens\""
+        );
+
+        // The request is still forwarded as sent
+        let requests = mock_client.get_requests();
+        let upstream_body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(upstream_body["max_tokens"], 64);
+        assert_eq!(upstream_body["functions"][0]["name"], "get_weather");
+
+        // Requests using current fields get no warning
+        let response = server
+            .post("/v1/chat/completions")
+            .json(&json!({
+                "model": "gpt-4",
+                "messages": [{"role": "user", "content": "Hello"}],
+                "max_completion_tokens": 64
+            }))
+            .await;
+        assert!(response.headers().get("openai-deprecation").is_none());
+    }
+
     #[tokio::test]
     async fn test_missing_output_limit_returns_422_before_upstream_request() {
         let reasoning_translation = serde_json::from_value(json!({
@@ -4135,6 +5680,94 @@ mod tests {
             assert_eq!(body["custom_provider_field"], "should be preserved");
         }
 
+        #[tokio::test]
+        async fn test_sanitize_routing_rule_overrides_target_by_key_label() {
+            use crate::target::{LoadBalanceStrategy, RoutingAction, RoutingRule};
+            use std::collections::HashMap;
+
+            // Target doesn't sanitize; a rule enables it for external keys only
+            let target = Target::builder()
+                .url("https://api.openai.com".parse().unwrap())
+                .onwards_key("sk-test".to_string())
+                .sanitize_response(false)
+                .build();
+            let targets_map = Arc::new(DashMap::new());
+            targets_map.insert(
+                "gpt-4".to_string(),
+                ProviderPool::with_config(
+                    vec![Provider::new(target, 1)],
+                    None,
+                    None,
+                    None,
+                    None,
+                    LoadBalanceStrategy::default(),
+                    false,
+                    vec![RoutingRule {
+                        match_labels: HashMap::from([(
+                            "purpose".to_string(),
+                            "external".to_string(),
+                        )]),
+                        action: RoutingAction::Sanitize { enabled: true },
+                    }],
+                ),
+            );
+
+            let key_labels = Arc::new(DashMap::new());
+            key_labels.insert(
+                "external-key".to_string(),
+                HashMap::from([("purpose".to_string(), "external".to_string())]),
+            );
+            key_labels.insert(
+                "internal-key".to_string(),
+                HashMap::from([("purpose".to_string(), "internal".to_string())]),
+            );
+
+            let targets = Targets {
+                targets: targets_map,
+                key_rate_limiters: Arc::new(DashMap::new()),
+                key_concurrency_limiters: Arc::new(DashMap::new()),
+                key_labels,
+                strict_mode: false,
+                http_pool_config: None,
+            };
+
+            let mock_response = r#"{
+                "id": "chatcmpl-123",
+                "object": "chat.completion",
+                "created": 1677652288,
+                "model": "gpt-4",
+                "choices": [{
+                    "index": 0,
+                    "message": {"role": "assistant", "content": "Hello!"},
+                    "finish_reason": "stop"
+                }],
+                "usage": {"prompt_tokens": 9, "completion_tokens": 2, "total_tokens": 11},
+                "internal_metadata": "upstream-node-7"
+            }"#;
+
+            let mock_client = MockHttpClient::new(StatusCode::OK, mock_response);
+            let app_state = AppState::with_client(targets, mock_client)
+                .with_response_transform(create_openai_sanitizer());
+            let server = TestServer::new(build_router(app_state)).unwrap();
+
+            let request = |key: &'static str| {
+                server
+                    .post("/v1/chat/completions")
+                    .add_header("authorization", format!("Bearer {key}"))
+                    .json(&json!({
+                        "model": "gpt-4",
+                        "messages": [{"role": "user", "content": "Hello"}]
+                    }))
+            };
+
+            let external: serde_json::Value = request("external-key").await.json();
+            assert!(external.get("internal_metadata").is_none());
+            assert!(external.get("choices").is_some());
+
+            let internal: serde_json::Value = request("internal-key").await.json();
+            assert_eq!(internal["internal_metadata"], "upstream-node-7");
+        }
+
         #[tokio::test]
         async fn test_sanitization_only_applies_to_chat_completions() {
             let targets_map = Arc::new(DashMap::new());
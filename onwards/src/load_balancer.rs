@@ -8,9 +8,11 @@
 //! Pool-level configuration (keys, rate limits) is shared across all providers.
 
 use crate::auth::KeySet;
+use crate::priority::SlotQueue;
 use crate::target::{
-    ConcurrencyGuard, ConcurrencyLimiter, FallbackConfig, LoadBalanceStrategy, RateLimiter,
-    RoutingAction, RoutingRule, Target,
+    ConcurrencyGuard, ConcurrencyLimiter, FallbackConfig, KeyedConcurrencyLimiter,
+    LabeledResponseHeaders, LoadBalanceStrategy, NLimitConfig, RateLimiter, RoutingAction,
+    RoutingRule, ShadowConfig, SystemPromptConfig, Target,
 };
 use rand::Rng;
 use std::collections::{HashMap, HashSet};
@@ -27,6 +29,8 @@ pub struct ProviderPool {
     pool_limiter: Option<Arc<dyn RateLimiter>>,
     /// Pool-level concurrency limiter (applies to all requests to this alias)
     pool_concurrency_limiter: Option<ConcurrencyLimiter>,
+    /// Per-key concurrency limiter (caps each API key's share of this alias)
+    per_key_concurrency_limiter: Option<KeyedConcurrencyLimiter>,
     /// Fallback configuration for retrying failed requests
     fallback: Option<FallbackConfig>,
     /// Load balancing strategy
@@ -40,6 +44,19 @@ pub struct ProviderPool {
     trusted: bool,
     /// Routing rules evaluated against key labels before processing
     routing_rules: Vec<RoutingRule>,
+    /// Response headers chosen by the calling key's labels
+    labeled_response_headers: Vec<LabeledResponseHeaders>,
+    /// Shadow alias that receives copies of a sample of this pool's requests
+    shadow: Option<ShadowConfig>,
+    /// System prompt injected into this pool's chat requests
+    system_prompt: Option<SystemPromptConfig>,
+    /// Cap on the number of completions a request may ask for
+    n_limit: Option<NLimitConfig>,
+    /// Region to select providers from first, set per request by
+    /// [`ProviderPool::preferring_region`]
+    preferred_region: Option<String>,
+    /// Requests waiting for capacity in this pool, when queueing is enabled
+    slot_queue: SlotQueue,
 }
 
 /// A single provider within a pool
@@ -86,10 +103,17 @@ impl ProviderPool {
             keys: None,
             pool_limiter: None,
             pool_concurrency_limiter: None,
+            per_key_concurrency_limiter: None,
             fallback: None,
             strategy: LoadBalanceStrategy::default(),
             trusted: false,
             routing_rules: Vec::new(),
+            labeled_response_headers: Vec::new(),
+            shadow: None,
+            system_prompt: None,
+            n_limit: None,
+            preferred_region: None,
+            slot_queue: SlotQueue::default(),
         }
     }
 
@@ -110,13 +134,53 @@ impl ProviderPool {
             keys,
             pool_limiter,
             pool_concurrency_limiter,
+            per_key_concurrency_limiter: None,
             fallback,
             strategy,
             trusted,
             routing_rules,
+            labeled_response_headers: Vec::new(),
+            shadow: None,
+            system_prompt: None,
+            n_limit: None,
+            preferred_region: None,
+            slot_queue: SlotQueue::default(),
         }
     }
 
+    /// Cap how many concurrent requests any one key may hold on this pool
+    pub fn with_per_key_concurrency_limiter(
+        mut self,
+        limiter: Option<KeyedConcurrencyLimiter>,
+    ) -> Self {
+        self.per_key_concurrency_limiter = limiter;
+        self
+    }
+
+    /// Add response headers that apply when the calling key's labels match
+    pub fn with_labeled_response_headers(mut self, headers: Vec<LabeledResponseHeaders>) -> Self {
+        self.labeled_response_headers = headers;
+        self
+    }
+
+    /// Copy a sample of this pool's requests to a shadow alias
+    pub fn with_shadow(mut self, shadow: Option<ShadowConfig>) -> Self {
+        self.shadow = shadow;
+        self
+    }
+
+    /// Inject a system prompt into this pool's chat requests
+    pub fn with_system_prompt(mut self, system_prompt: Option<SystemPromptConfig>) -> Self {
+        self.system_prompt = system_prompt;
+        self
+    }
+
+    /// Cap the number of completions (`n`) this pool's requests may ask for
+    pub fn with_n_limit(mut self, n_limit: Option<NLimitConfig>) -> Self {
+        self.n_limit = n_limit;
+        self
+    }
+
     /// Create a pool with a single provider
     pub fn single(target: Target, weight: u32) -> Self {
         Self::new(vec![Provider::new(target, weight)])
@@ -175,6 +239,32 @@ impl ProviderPool {
             return None;
         }
 
+        // Try the preferred region first by also excluding every provider
+        // outside it; if none of those is eligible, fall back to the whole pool.
+        if let Some(region) = self.preferred_region.as_deref() {
+            let mut in_region_exclude = exclude.clone();
+            in_region_exclude.extend(
+                self.providers
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, p)| p.target.region.as_deref() != Some(region))
+                    .map(|(idx, _)| idx),
+            );
+            if in_region_exclude.len() < self.providers.len()
+                && let Some(selected) = self.select_with_strategy(&in_region_exclude)
+            {
+                return Some(selected);
+            }
+        }
+
+        self.select_with_strategy(exclude)
+    }
+
+    /// Internal: select with the pool's load balancing strategy
+    fn select_with_strategy(
+        &self,
+        exclude: &HashSet<usize>,
+    ) -> Option<(usize, &Target, ConcurrencyGuard)> {
         match self.strategy {
             LoadBalanceStrategy::Priority => self.select_priority(exclude),
             LoadBalanceStrategy::WeightedRandom => self.select_least_connections(exclude),
@@ -302,6 +392,11 @@ impl ProviderPool {
         self.pool_concurrency_limiter.as_ref()
     }
 
+    /// Get the per-key concurrency limiter, if configured
+    pub fn per_key_concurrency_limiter(&self) -> Option<&KeyedConcurrencyLimiter> {
+        self.per_key_concurrency_limiter.as_ref()
+    }
+
     /// Get the fallback configuration
     pub fn fallback(&self) -> Option<&FallbackConfig> {
         self.fallback.as_ref()
@@ -372,6 +467,82 @@ impl ProviderPool {
         })
     }
 
+    /// Find the extra response headers for a key with these labels.
+    /// Returns the first matching entry's headers, or None if no entry matches.
+    pub fn labeled_response_headers(
+        &self,
+        key_labels: &HashMap<String, String>,
+    ) -> Option<&HashMap<String, String>> {
+        self.labeled_response_headers.iter().find_map(|entry| {
+            let matches = entry
+                .match_labels
+                .iter()
+                .all(|(k, v)| key_labels.get(k).is_some_and(|kv| kv == v));
+            matches.then_some(&entry.headers)
+        })
+    }
+
+    /// The shadow alias this pool copies requests to, if any
+    pub fn shadow(&self) -> Option<&ShadowConfig> {
+        self.shadow.as_ref()
+    }
+
+    /// The system prompt injected into this pool's chat requests, if any
+    pub fn system_prompt(&self) -> Option<&SystemPromptConfig> {
+        self.system_prompt.as_ref()
+    }
+
+    /// The cap on the number of completions a request may ask for, if any
+    pub fn n_limit(&self) -> Option<&NLimitConfig> {
+        self.n_limit.as_ref()
+    }
+
+    /// Narrow this pool to the single provider named `name`.
+    ///
+    /// Returns a copy of the pool containing only that provider, so load
+    /// balancing is bypassed while pool-level settings (keys, limiters,
+    /// fallback) still apply. The provider's connection counter is shared
+    /// with the original, keeping active-connection tracking accurate.
+    /// Returns `None` if no provider in the pool has that name.
+    pub fn pinned_to(&self, name: &str) -> Option<ProviderPool> {
+        let provider = self
+            .providers
+            .iter()
+            .find(|p| p.target.name.as_deref() == Some(name))?;
+        Some(Self {
+            providers: vec![provider.clone()],
+            ..self.clone()
+        })
+    }
+
+    /// Get the queue of requests waiting for capacity in this pool
+    pub fn slot_queue(&self) -> &SlotQueue {
+        &self.slot_queue
+    }
+
+    /// Whether the pool could take another request: its own concurrency limit
+    /// isn't reached and at least one provider has a free slot.
+    pub fn has_capacity(&self) -> bool {
+        !self
+            .pool_concurrency_limiter
+            .as_ref()
+            .is_some_and(|limiter| limiter.at_capacity())
+            && self.providers.iter().any(|p| !p.limiter.at_capacity())
+    }
+
+    /// Return a copy of this pool that selects providers in `region` first.
+    ///
+    /// Same-region providers are tried before any others, in the pool's usual
+    /// strategy order; once they are exhausted or at capacity, selection falls
+    /// back to providers in other regions. Connection counters are shared with
+    /// the original pool. A region no provider is in leaves selection unchanged.
+    pub fn preferring_region(&self, region: &str) -> ProviderPool {
+        Self {
+            preferred_region: Some(region.to_string()),
+            ..self.clone()
+        }
+    }
+
     /// Adopt active connection counters from an old pool into this (new) pool.
     ///
     /// Matches providers by (url, onwards_key, onwards_model) identity. Where a
@@ -404,6 +575,17 @@ impl ProviderPool {
         ) {
             new_limiter.adopt_active_counter(old_limiter);
         }
+
+        // Likewise each key's share of the pool
+        if let (Some(new_limiter), Some(old_limiter)) = (
+            &mut self.per_key_concurrency_limiter,
+            &old.per_key_concurrency_limiter,
+        ) {
+            new_limiter.adopt_active_counters(old_limiter);
+        }
+
+        // Requests already waiting stay ahead of those arriving after the reload
+        self.slot_queue = old.slot_queue.clone();
     }
 }
 
@@ -492,6 +674,57 @@ mod tests {
         assert_eq!(target.url.as_str(), "https://api.example.com/");
     }
 
+    #[test]
+    fn test_preferring_region_tries_same_region_first_then_falls_back() {
+        let regional = |region: &str, url: &str| {
+            Target::builder()
+                .region(region.to_string())
+                .url(url.parse().unwrap())
+                .build()
+        };
+        let providers = vec![
+            Provider::new(regional("us-east", "https://us1.example.com"), 100),
+            Provider::new(regional("eu-west", "https://eu.example.com"), 1),
+            Provider::new(regional("us-east", "https://us2.example.com"), 100),
+        ];
+        let pool = ProviderPool::with_config(
+            providers,
+            None,
+            None,
+            None,
+            None,
+            LoadBalanceStrategy::Priority,
+            false,
+            Vec::new(),
+        )
+        .preferring_region("eu-west");
+
+        let order: Vec<usize> = pool.select_iter().map(|(idx, _, _)| idx).collect();
+        assert_eq!(order, vec![1, 0, 2]);
+
+        // Weighted selection stays in the preferred region despite its low weight
+        let pool = ProviderPool::new(vec![
+            Provider::new(regional("us-east", "https://us1.example.com"), 1000),
+            Provider::new(regional("eu-west", "https://eu.example.com"), 1),
+        ])
+        .preferring_region("eu-west");
+        for _ in 0..20 {
+            let (idx, _, _guard) = pool.select().unwrap();
+            assert_eq!(idx, 1);
+        }
+
+        // A full preferred region falls back cross-region
+        let pool = ProviderPool::new(vec![
+            Provider::with_concurrency_limit(regional("eu-west", "https://eu.example.com"), 1, 1),
+            Provider::new(regional("us-east", "https://us1.example.com"), 1),
+        ])
+        .preferring_region("eu-west");
+        let (first, _, _held) = pool.select().unwrap();
+        assert_eq!(first, 0);
+        let (second, _, _guard) = pool.select().unwrap();
+        assert_eq!(second, 1);
+    }
+
     #[test]
     fn test_empty_pool_returns_none() {
         let pool = ProviderPool::new(vec![]);
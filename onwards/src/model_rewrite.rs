@@ -0,0 +1,211 @@
+//! Rewriting the response `model` field back to the requested model.
+//!
+//! Providers report the model that actually served a request, which for an
+//! alias or a composite pool is the underlying model name rather than the name
+//! the client asked for. When a target sets `rewrite_response_model`, the
+//! top-level `model` of JSON responses (and `response.model` of Responses API
+//! stream events) is replaced with the requested model. Unlike full response
+//! sanitization, every other field is left untouched.
+
+use axum::body::{Body, Bytes};
+use axum::http::{HeaderValue, Response, header};
+use futures_util::StreamExt;
+use serde_json::Value;
+use tracing::debug;
+
+use crate::response_id::decode_body;
+use crate::sse::SseBufferedStream;
+
+/// Rewrite the `model` field of a 2xx response to `model`. Streaming responses
+/// are rewritten event by event; JSON responses are buffered, decoded and
+/// returned uncompressed. Bodies that are not JSON pass through unchanged.
+pub async fn rewrite_response_model(response: &mut Response<Body>, model: String) {
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    let is_sse = content_type.contains("text/event-stream");
+    let is_json = content_type.contains("application/json");
+
+    if is_sse {
+        let body = std::mem::take(response.body_mut()).into_data_stream();
+        let rewritten = SseBufferedStream::new(body).map(move |chunk| {
+            chunk.map(|chunk| rewrite_sse_chunk(&chunk, &model).unwrap_or(chunk))
+        });
+        *response.body_mut() = Body::from_stream(rewritten);
+        return;
+    }
+
+    if !is_json {
+        return;
+    }
+
+    let content_encoding = response
+        .headers()
+        .get(header::CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_lowercase());
+    let bytes = match axum::body::to_bytes(std::mem::take(response.body_mut()), usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(_) => return,
+    };
+    let Some(decoded) = decode_body(&bytes, content_encoding.as_deref()) else {
+        debug!("Failed to decompress response for model rewrite, passing through");
+        *response.body_mut() = Body::from(bytes);
+        return;
+    };
+
+    match rewrite_json(&decoded, &model) {
+        Some(rewritten) => {
+            let content_length = rewritten.len();
+            *response.body_mut() = Body::from(rewritten);
+            response.headers_mut().remove(header::CONTENT_ENCODING);
+            response.headers_mut().remove(header::TRANSFER_ENCODING);
+            response
+                .headers_mut()
+                .insert(header::CONTENT_LENGTH, HeaderValue::from(content_length));
+        }
+        None => *response.body_mut() = Body::from(bytes),
+    }
+}
+
+/// Replace the model name in a parsed body. Returns whether anything changed.
+fn rewrite_value(value: &mut Value, model: &str) -> bool {
+    let mut changed = false;
+    for pointer in ["/model", "/response/model"] {
+        if let Some(target) = value.pointer_mut(pointer)
+            && target.as_str().is_some_and(|current| current != model)
+        {
+            *target = Value::String(model.to_string());
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Rewrite a JSON body. Returns `None` when the body is not JSON or already
+/// names the requested model.
+fn rewrite_json(body: &[u8], model: &str) -> Option<Vec<u8>> {
+    let mut value: Value = serde_json::from_slice(body).ok()?;
+    if !rewrite_value(&mut value, model) {
+        return None;
+    }
+    serde_json::to_vec(&value).ok()
+}
+
+/// Rewrite every `data:` line of one or more complete SSE events. Lines that
+/// are not JSON (e.g. `[DONE]`, comments) are preserved byte for byte. Returns
+/// `None` when nothing changed.
+pub fn rewrite_sse_chunk(chunk: &[u8], model: &str) -> Option<Bytes> {
+    let text = std::str::from_utf8(chunk).ok()?;
+    let mut changed = false;
+    let lines: Vec<String> = text
+        .split('\n')
+        .map(|line| {
+            let (content, cr) = match line.strip_suffix('\r') {
+                Some(content) => (content, "\r"),
+                None => (line, ""),
+            };
+            let Some(data) = content.strip_prefix("data:") else {
+                return line.to_string();
+            };
+            match rewrite_json(data.trim_start().as_bytes(), model) {
+                Some(rewritten) => {
+                    changed = true;
+                    format!("data: {}{cr}", String::from_utf8_lossy(&rewritten))
+                }
+                None => line.to_string(),
+            }
+        })
+        .collect();
+
+    changed.then(|| Bytes::from(lines.join("\n")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    fn json_response(body: &str) -> Response<Body> {
+        Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    async fn body_json(response: Response<Body>) -> Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rewrites_json_model_and_keeps_other_fields() {
+        let mut response = json_response(
+            r#"{"id":"chatcmpl-1","model":"provider/llama-3-70b","provider":"x","choices":[]}"#,
+        );
+
+        rewrite_response_model(&mut response, "my-alias".to_string()).await;
+
+        let body = body_json(response).await;
+        assert_eq!(body["model"], "my-alias");
+        assert_eq!(body["provider"], "x", "non-model fields are preserved");
+        assert_eq!(body["id"], "chatcmpl-1");
+    }
+
+    #[test]
+    fn test_rewrites_responses_api_nested_model() {
+        let chunk = b"event: response.created\ndata: {\"type\":\"response.created\",\"response\":{\"id\":\"resp_1\",\"model\":\"upstream\"}}\n\n";
+
+        let rewritten = rewrite_sse_chunk(chunk, "my-alias").unwrap();
+        let text = std::str::from_utf8(&rewritten).unwrap();
+
+        assert!(text.starts_with("event: response.created\n"));
+        assert!(text.contains("\"model\":\"my-alias\""));
+        assert!(text.ends_with("\n\n"));
+    }
+
+    #[test]
+    fn test_sse_chunk_preserves_done_and_framing() {
+        let chunk =
+            b"data: {\"id\":\"c\",\"model\":\"upstream\",\"choices\":[]}\n\ndata: [DONE]\n\n";
+
+        let rewritten = rewrite_sse_chunk(chunk, "my-alias").unwrap();
+        let text = std::str::from_utf8(&rewritten).unwrap();
+
+        let events: Vec<&str> = text.split("\n\n").collect();
+        assert_eq!(events.len(), 3, "event framing is preserved: {text}");
+        let first: Value = serde_json::from_str(events[0].strip_prefix("data: ").unwrap()).unwrap();
+        assert_eq!(first["model"], "my-alias");
+        assert_eq!(first["id"], "c");
+        assert_eq!(events[1], "data: [DONE]");
+        assert_eq!(events[2], "");
+    }
+
+    #[test]
+    fn test_sse_chunk_unchanged_when_model_already_matches() {
+        let chunk = b"data: {\"model\":\"my-alias\"}\n\n";
+        assert!(rewrite_sse_chunk(chunk, "my-alias").is_none());
+        assert!(rewrite_sse_chunk(b"data: [DONE]\n\n", "my-alias").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_non_json_response_passes_through() {
+        let mut response = Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "text/plain")
+            .body(Body::from("model: upstream"))
+            .unwrap();
+
+        rewrite_response_model(&mut response, "my-alias".to_string()).await;
+
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&bytes[..], b"model: upstream");
+    }
+}
@@ -0,0 +1,103 @@
+//! Capping the number of completions a request asks for.
+//!
+//! The `n` parameter of chat and legacy completions requests asks the provider
+//! for several completions of the same prompt, each generated (and billed)
+//! separately. When a pool sets `n_limit`, a request whose `n` is above the
+//! cap is either rejected with a 400 or has `n` lowered to the cap before it is
+//! forwarded, depending on the pool's `mode`. Requests without an integer `n`
+//! pass through unchanged.
+
+use axum::body::Bytes;
+use serde_json::Value;
+
+use crate::target::{NLimitConfig, NLimitMode};
+
+/// A request asked for more completions than the pool allows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct NLimitExceeded {
+    pub requested: u64,
+    pub max: u32,
+}
+
+/// Check a request body's `n` against `config`. Returns the rewritten body
+/// when `n` was clamped, `None` when the body can be forwarded as it is, or
+/// an error when the request must be rejected.
+pub(crate) fn enforce(config: &NLimitConfig, body: &[u8]) -> Result<Option<Bytes>, NLimitExceeded> {
+    let Ok(mut value) = serde_json::from_slice::<Value>(body) else {
+        return Ok(None);
+    };
+    let Some(requested) = value.get("n").and_then(Value::as_u64) else {
+        return Ok(None);
+    };
+    if requested <= u64::from(config.max) {
+        return Ok(None);
+    }
+
+    match config.mode {
+        NLimitMode::Reject => Err(NLimitExceeded {
+            requested,
+            max: config.max,
+        }),
+        NLimitMode::Clamp => {
+            value["n"] = Value::from(config.max);
+            Ok(serde_json::to_vec(&value).ok().map(Bytes::from))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(mode: NLimitMode) -> NLimitConfig {
+        NLimitConfig { max: 4, mode }
+    }
+
+    fn enforce_json(mode: NLimitMode, body: Value) -> Result<Option<Value>, NLimitExceeded> {
+        enforce(&config(mode), &serde_json::to_vec(&body).unwrap())
+            .map(|bytes| bytes.map(|bytes| serde_json::from_slice(&bytes).unwrap()))
+    }
+
+    #[test]
+    fn test_n_within_cap_is_unchanged() {
+        for mode in [NLimitMode::Reject, NLimitMode::Clamp] {
+            assert_eq!(enforce_json(mode, json!({"model": "m", "n": 4})), Ok(None));
+            assert_eq!(enforce_json(mode, json!({"model": "m"})), Ok(None));
+        }
+    }
+
+    #[test]
+    fn test_reject_mode_rejects_n_above_cap() {
+        assert_eq!(
+            enforce_json(NLimitMode::Reject, json!({"model": "m", "n": 50})),
+            Err(NLimitExceeded {
+                requested: 50,
+                max: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_clamp_mode_lowers_n_to_cap() {
+        let body = enforce_json(
+            NLimitMode::Clamp,
+            json!({"model": "m", "n": 50, "messages": [{"role": "user", "content": "Hi"}]}),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            body,
+            json!({"model": "m", "n": 4, "messages": [{"role": "user", "content": "Hi"}]})
+        );
+    }
+
+    #[test]
+    fn test_non_integer_n_and_invalid_json_pass_through() {
+        assert_eq!(
+            enforce_json(NLimitMode::Reject, json!({"model": "m", "n": "50"})),
+            Ok(None)
+        );
+        assert_eq!(enforce(&config(NLimitMode::Reject), b"not json"), Ok(None));
+    }
+}
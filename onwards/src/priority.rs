@@ -0,0 +1,470 @@
+//! Request priority and the per-pool queue for concurrency slots.
+//!
+//! By default a request that finds its pool at capacity is rejected with a
+//! 429 straight away. With a [`QueueConfig`] set on the app state, it instead
+//! waits up to `max_wait` for a slot in the pool's [`SlotQueue`]. Waiters are
+//! admitted one at a time, highest priority first and in arrival order within
+//! a priority, whenever the pool has capacity again.
+//!
+//! A request's priority comes from the priority header (0 to [`MAX_PRIORITY`],
+//! default 0) and may not exceed the `max_priority` label of the caller's key,
+//! which also defaults to 0. To stop a steady stream of high-priority requests
+//! starving the rest, a waiter's priority rises by one for every `aging` it has
+//! waited, so an earlier low-priority request eventually overtakes newer
+//! high-priority ones.
+//!
+//! Queueing is reported per model so capacity can be sized from it:
+//!
+//! - `dwctl_deployment_queue_depth{model}` - requests waiting for a turn
+//! - `dwctl_deployment_queue_wait_seconds{model, outcome}` - how long queued
+//!   requests waited, with `outcome` either `admitted` or `timed_out`
+//!
+//! Requests admitted without waiting aren't counted in either.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use metrics::{Gauge, gauge, histogram};
+use tokio::sync::Notify;
+
+/// Highest priority a request can ask for
+pub const MAX_PRIORITY: u8 = 9;
+
+/// Key label holding the highest priority the key may request
+pub const MAX_PRIORITY_LABEL: &str = "max_priority";
+
+/// How often a waiter re-checks pool capacity. Concurrency slots are released
+/// by guards that don't know about the queue, so waiters poll for them.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Gauge of requests waiting in a model's queue
+pub const QUEUE_DEPTH_GAUGE: &str = "dwctl_deployment_queue_depth";
+
+/// Histogram of how long queued requests waited for a turn
+pub const QUEUE_WAIT_HISTOGRAM: &str = "dwctl_deployment_queue_wait_seconds";
+
+/// Settings for queueing requests that find their pool at capacity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueConfig {
+    /// Longest a request waits for a slot before it is rejected with a 429
+    pub max_wait: Duration,
+    /// Wait after which a queued request's priority rises by one. Zero
+    /// disables aging.
+    pub aging: Duration,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            max_wait: Duration::from_secs(30),
+            aging: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Why a priority header was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PriorityError {
+    /// The header isn't an integer between 0 and [`MAX_PRIORITY`]
+    Invalid,
+    /// The key may not request this priority
+    NotAllowed { requested: u8, allowed: u8 },
+}
+
+/// The highest priority a key may request, from its labels
+pub fn allowed_priority(labels: Option<&HashMap<String, String>>) -> u8 {
+    labels
+        .and_then(|labels| labels.get(MAX_PRIORITY_LABEL))
+        .and_then(|value| value.parse::<u8>().ok())
+        .map_or(0, |priority| priority.min(MAX_PRIORITY))
+}
+
+/// Parse a priority header value and check it against the key's allowance.
+pub fn parse_priority(value: &str, allowed: u8) -> Result<u8, PriorityError> {
+    let requested = value
+        .trim()
+        .parse::<u8>()
+        .ok()
+        .filter(|priority| *priority <= MAX_PRIORITY)
+        .ok_or(PriorityError::Invalid)?;
+    if requested > allowed {
+        return Err(PriorityError::NotAllowed { requested, allowed });
+    }
+    Ok(requested)
+}
+
+/// A request waiting in a [`SlotQueue`]
+#[derive(Debug)]
+struct Waiter {
+    seq: u64,
+    priority: u8,
+    enqueued_at: Instant,
+}
+
+impl Waiter {
+    /// Priority plus one for every `aging` waited
+    fn effective_priority(&self, now: Instant, aging: Duration) -> f64 {
+        let aged = if aging.is_zero() {
+            0.0
+        } else {
+            now.duration_since(self.enqueued_at).as_secs_f64() / aging.as_secs_f64()
+        };
+        f64::from(self.priority) + aged
+    }
+}
+
+#[derive(Debug, Default)]
+struct QueueState {
+    waiters: Vec<Waiter>,
+    next_seq: u64,
+    /// Requests admitted that haven't yet taken their slot
+    admitted: usize,
+}
+
+impl QueueState {
+    /// The waiter to admit next: highest effective priority, then earliest
+    fn head(&self, now: Instant, aging: Duration) -> Option<u64> {
+        self.waiters
+            .iter()
+            .max_by(|a, b| {
+                a.effective_priority(now, aging)
+                    .total_cmp(&b.effective_priority(now, aging))
+                    .then(b.seq.cmp(&a.seq))
+            })
+            .map(|waiter| waiter.seq)
+    }
+
+    fn remove(&mut self, seq: u64) -> bool {
+        let before = self.waiters.len();
+        self.waiters.retain(|waiter| waiter.seq != seq);
+        self.waiters.len() != before
+    }
+}
+
+/// Orders requests waiting for a pool's concurrency slots.
+///
+/// Clones share the same queue, so it survives config reloads along with the
+/// pool's concurrency counters.
+#[derive(Debug, Clone, Default)]
+pub struct SlotQueue {
+    state: Arc<Mutex<QueueState>>,
+    notify: Arc<Notify>,
+}
+
+impl SlotQueue {
+    /// Wait for this request's turn at a slot. `has_capacity` reports whether
+    /// the pool could take another request. Returns `None` if no turn came
+    /// within `config.max_wait`. `model` labels the queue metrics.
+    ///
+    /// The returned [`QueueTurn`] holds back later waiters until it is
+    /// dropped, so drop it once the request has taken its slot.
+    pub async fn wait_turn(
+        &self,
+        model: &str,
+        priority: u8,
+        config: &QueueConfig,
+        has_capacity: impl Fn() -> bool,
+    ) -> Option<QueueTurn> {
+        let seq = {
+            let mut state = self.state.lock().unwrap();
+            // Nobody is ahead of us, so take the slot straight away
+            if state.waiters.is_empty() && state.admitted == 0 && has_capacity() {
+                state.admitted += 1;
+                return Some(self.turn(None));
+            }
+            let seq = state.next_seq;
+            state.next_seq += 1;
+            state.waiters.push(Waiter {
+                seq,
+                priority,
+                enqueued_at: Instant::now(),
+            });
+            seq
+        };
+        // Leaves the queue if the request is dropped while waiting
+        let _waiting = Waiting::new(self, seq, model);
+        let enqueued_at = Instant::now();
+        let deadline = enqueued_at + config.max_wait;
+        let record_wait = |outcome: &'static str| {
+            histogram!(QUEUE_WAIT_HISTOGRAM, "model" => model.to_string(), "outcome" => outcome)
+                .record(enqueued_at.elapsed().as_secs_f64());
+        };
+
+        loop {
+            let notified = self.notify.notified();
+            {
+                let mut state = self.state.lock().unwrap();
+                let now = Instant::now();
+                if state.admitted == 0
+                    && state.head(now, config.aging) == Some(seq)
+                    && has_capacity()
+                {
+                    state.remove(seq);
+                    state.admitted += 1;
+                    record_wait("admitted");
+                    return Some(self.turn(Some(now.duration_since(enqueued_at))));
+                }
+                if now >= deadline {
+                    record_wait("timed_out");
+                    return None;
+                }
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let _ = tokio::time::timeout(remaining.min(POLL_INTERVAL), notified).await;
+        }
+    }
+
+    /// Number of requests waiting for a turn
+    pub fn waiting(&self) -> usize {
+        self.state.lock().unwrap().waiters.len()
+    }
+
+    fn turn(&self, waited: Option<Duration>) -> QueueTurn {
+        QueueTurn {
+            queue: self.clone(),
+            waited,
+        }
+    }
+}
+
+/// Removes a waiter from the queue when its request stops waiting
+struct Waiting<'a> {
+    queue: &'a SlotQueue,
+    seq: u64,
+    depth: Gauge,
+}
+
+impl<'a> Waiting<'a> {
+    /// Counts the waiter in the model's queue depth until it is dropped
+    fn new(queue: &'a SlotQueue, seq: u64, model: &str) -> Self {
+        let depth = gauge!(QUEUE_DEPTH_GAUGE, "model" => model.to_string());
+        depth.increment(1.0);
+        Self { queue, seq, depth }
+    }
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.depth.decrement(1.0);
+        if self.queue.state.lock().unwrap().remove(self.seq) {
+            self.queue.notify.notify_waiters();
+        }
+    }
+}
+
+/// A request's turn at a slot; the next waiter is admitted once it is dropped.
+#[derive(Debug)]
+pub struct QueueTurn {
+    queue: SlotQueue,
+    waited: Option<Duration>,
+}
+
+impl QueueTurn {
+    /// How long the request queued for its turn; `None` if it didn't queue
+    pub fn waited(&self) -> Option<Duration> {
+        self.waited
+    }
+}
+
+impl Drop for QueueTurn {
+    fn drop(&mut self) {
+        self.queue.state.lock().unwrap().admitted -= 1;
+        self.queue.notify.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::target::ConcurrencyLimiter;
+
+    #[test]
+    fn test_parse_priority() {
+        assert_eq!(parse_priority("3", 5), Ok(3));
+        assert_eq!(parse_priority(" 0 ", 0), Ok(0));
+        assert_eq!(
+            parse_priority("6", 5),
+            Err(PriorityError::NotAllowed {
+                requested: 6,
+                allowed: 5
+            })
+        );
+        assert_eq!(parse_priority("10", 9), Err(PriorityError::Invalid));
+        assert_eq!(parse_priority("-1", 9), Err(PriorityError::Invalid));
+        assert_eq!(parse_priority("high", 9), Err(PriorityError::Invalid));
+    }
+
+    #[test]
+    fn test_allowed_priority_from_labels() {
+        assert_eq!(allowed_priority(None), 0);
+        let mut labels = HashMap::new();
+        assert_eq!(allowed_priority(Some(&labels)), 0);
+        labels.insert(MAX_PRIORITY_LABEL.to_string(), "5".to_string());
+        assert_eq!(allowed_priority(Some(&labels)), 5);
+        labels.insert(MAX_PRIORITY_LABEL.to_string(), "50".to_string());
+        assert_eq!(allowed_priority(Some(&labels)), MAX_PRIORITY);
+    }
+
+    #[test]
+    fn test_aging_lets_earlier_waiters_overtake() {
+        let start = Instant::now();
+        let aging = Duration::from_secs(1);
+        let mut state = QueueState::default();
+        state.waiters.push(Waiter {
+            seq: 0,
+            priority: 0,
+            enqueued_at: start,
+        });
+        state.waiters.push(Waiter {
+            seq: 1,
+            priority: 2,
+            enqueued_at: start,
+        });
+        assert_eq!(state.head(start, aging), Some(1));
+
+        // A newer high-priority waiter is behind one that has aged past it
+        state.waiters.retain(|waiter| waiter.seq == 0);
+        let later = start + Duration::from_secs(3);
+        state.waiters.push(Waiter {
+            seq: 2,
+            priority: 2,
+            enqueued_at: later,
+        });
+        assert_eq!(state.head(later, aging), Some(0));
+
+        // Without aging, priority alone decides
+        assert_eq!(state.head(later, Duration::ZERO), Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_high_priority_waiter_overtakes_earlier_low_priority() {
+        let limiter = ConcurrencyLimiter::with_limit(1);
+        let queue = SlotQueue::default();
+        let config = QueueConfig {
+            max_wait: Duration::from_secs(5),
+            aging: Duration::from_secs(60),
+        };
+        let has_capacity = {
+            let limiter = limiter.clone();
+            move || !limiter.at_capacity()
+        };
+
+        // One request holds the only slot
+        let held = limiter.try_acquire().unwrap();
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let spawn_waiter = |name: &'static str, priority: u8| {
+            let (queue, limiter, order, has_capacity) = (
+                queue.clone(),
+                limiter.clone(),
+                order.clone(),
+                has_capacity.clone(),
+            );
+            tokio::spawn(async move {
+                let turn = queue
+                    .wait_turn("model", priority, &config, has_capacity)
+                    .await?;
+                let guard = limiter.try_acquire()?;
+                drop(turn);
+                order.lock().unwrap().push(name);
+                // Hold the slot briefly, as a request would
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                drop(guard);
+                Some(())
+            })
+        };
+
+        let low = spawn_waiter("low", 0);
+        while queue.waiting() < 1 {
+            tokio::task::yield_now().await;
+        }
+        let high = spawn_waiter("high", 5);
+        while queue.waiting() < 2 {
+            tokio::task::yield_now().await;
+        }
+
+        drop(held);
+        assert_eq!(high.await.unwrap(), Some(()));
+        assert_eq!(low.await.unwrap(), Some(()));
+        assert_eq!(*order.lock().unwrap(), vec!["high", "low"]);
+        assert_eq!(queue.waiting(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_queue_metrics_while_saturated() {
+        let recorder = metrics_exporter_prometheus::PrometheusBuilder::new().build_recorder();
+        let metrics_handle = recorder.handle();
+        // The test runtime is single-threaded, so waiters record into this recorder.
+        let _guard = metrics::set_default_local_recorder(&recorder);
+
+        let limiter = ConcurrencyLimiter::with_limit(1);
+        let queue = SlotQueue::default();
+        let config = QueueConfig {
+            max_wait: Duration::from_secs(5),
+            aging: Duration::from_secs(1),
+        };
+        let held = limiter.try_acquire().unwrap();
+
+        let waiters: Vec<_> = (0..2)
+            .map(|_| {
+                let (queue, limiter) = (queue.clone(), limiter.clone());
+                tokio::spawn(async move {
+                    let has_capacity = {
+                        let limiter = limiter.clone();
+                        move || !limiter.at_capacity()
+                    };
+                    let turn = queue
+                        .wait_turn("saturated", 0, &config, has_capacity)
+                        .await?;
+                    let _slot = limiter.try_acquire()?;
+                    turn.waited()
+                })
+            })
+            .collect();
+        while queue.waiting() < 2 {
+            tokio::task::yield_now().await;
+        }
+        let rendered = metrics_handle.render();
+        assert!(
+            rendered.contains(r#"dwctl_deployment_queue_depth{model="saturated"} 2"#),
+            "queue depth should count both waiters:\n{rendered}"
+        );
+
+        drop(held);
+        for waiter in waiters {
+            assert!(waiter.await.unwrap().is_some());
+        }
+        let rendered = metrics_handle.render();
+        assert!(
+            rendered.contains(r#"dwctl_deployment_queue_depth{model="saturated"} 0"#),
+            "queue depth should drain:\n{rendered}"
+        );
+        assert!(
+            rendered.contains(
+                r#"dwctl_deployment_queue_wait_seconds_count{model="saturated",outcome="admitted"} 2"#
+            ),
+            "missing queue wait metric:\n{rendered}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_waiter_gives_up_after_max_wait() {
+        let queue = SlotQueue::default();
+        let config = QueueConfig {
+            max_wait: Duration::from_millis(30),
+            aging: Duration::from_secs(1),
+        };
+        assert!(
+            queue
+                .wait_turn("model", 0, &config, || false)
+                .await
+                .is_none()
+        );
+        assert_eq!(queue.waiting(), 0);
+
+        // With capacity and nobody waiting, a turn is immediate
+        let turn = queue.wait_turn("model", 0, &config, || true).await.unwrap();
+        assert_eq!(turn.waited(), None);
+    }
+}
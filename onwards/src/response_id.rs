@@ -40,6 +40,23 @@ pub fn path_supports_id_override(path_and_query: &str) -> bool {
     path.ends_with("/responses") || path.ends_with("/chat/completions")
 }
 
+/// Decode a response body according to its `Content-Encoding` (gzip or
+/// brotli; anything else is returned as-is). Returns `None` if decompression
+/// fails.
+pub(crate) fn decode_body(bytes: &[u8], content_encoding: Option<&str>) -> Option<Vec<u8>> {
+    let mut buf = Vec::new();
+    match content_encoding {
+        Some("gzip") => flate2::read::GzDecoder::new(bytes)
+            .read_to_end(&mut buf)
+            .ok()?,
+        Some("br") | Some("brotli") => brotli::Decompressor::new(bytes, 4096)
+            .read_to_end(&mut buf)
+            .ok()?,
+        _ => return Some(bytes.to_vec()),
+    };
+    Some(buf)
+}
+
 /// Patch the `id` field in a JSON response body with the given override.
 ///
 /// Handles `Content-Encoding: gzip` and `Content-Encoding: br` transparently:
@@ -72,32 +89,10 @@ pub async fn patch_response_body_id(response: &mut Response<Body>, override_id:
     };
 
     // Decompress if needed.
-    let decompressed = match content_encoding.as_deref() {
-        Some("gzip") => {
-            let mut decoder = flate2::read::GzDecoder::new(&bytes[..]);
-            let mut buf = Vec::new();
-            if decoder.read_to_end(&mut buf).is_ok() {
-                buf
-            } else {
-                debug!("Failed to gzip-decompress response for ID patching, passing through");
-                *response.body_mut() = Body::from(bytes);
-                return;
-            }
-        }
-        Some("br") | Some("brotli") => {
-            let mut buf = Vec::new();
-            if brotli::Decompressor::new(&bytes[..], 4096)
-                .read_to_end(&mut buf)
-                .is_ok()
-            {
-                buf
-            } else {
-                debug!("Failed to brotli-decompress response for ID patching, passing through");
-                *response.body_mut() = Body::from(bytes);
-                return;
-            }
-        }
-        _ => bytes.to_vec(),
+    let Some(decompressed) = decode_body(&bytes, content_encoding.as_deref()) else {
+        debug!("Failed to decompress response for ID patching, passing through");
+        *response.body_mut() = Body::from(bytes);
+        return;
     };
 
     // Parse, patch, rewrite.
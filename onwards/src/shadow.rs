@@ -0,0 +1,213 @@
+//! Shadow traffic: copies of a sample of an alias's requests sent to another
+//! alias, with the copies' responses discarded.
+//!
+//! A copy is sent from a background task once the primary request has passed
+//! auth, routing rules and limits, so it never delays or changes the caller's
+//! response. The shadow pool's own keys and pool-level limits don't apply to
+//! copies; its providers' concurrency limits do, and a copy is dropped rather
+//! than queued when no provider has capacity.
+//!
+//! Copies are recorded apart from the caller's traffic: the
+//! `onwards_shadow_requests_total` counter (by outcome) and the
+//! `onwards_shadow_request_duration_seconds` histogram, both labelled with the
+//! primary and shadow aliases, and a log line on the `onwards::shadow` target.
+//! A copy never passes back through the caller's middleware, so embedders that
+//! log or bill in front of onwards don't see it.
+
+use crate::AppState;
+use crate::client::HttpClient;
+use crate::handlers::{filter_headers_for_upstream, upstream_url};
+use crate::load_balancer::ProviderPool;
+use crate::target::ShadowConfig;
+use axum::body::Bytes;
+use axum::http::{
+    HeaderMap, Method, Uri,
+    header::{CONTENT_LENGTH, TRANSFER_ENCODING},
+};
+use http_body_util::BodyExt;
+use rand::Rng;
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+/// A request to copy to a shadow alias, captured from the primary request
+pub(crate) struct ShadowRequest {
+    pub model: String,
+    pub method: Method,
+    pub path_and_query: String,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+/// Sample the request against the shadow's fraction and, if it is selected,
+/// send a copy to the shadow alias in the background.
+pub(crate) fn maybe_dispatch<T: HttpClient + Clone + Send + Sync + 'static>(
+    state: &AppState<T>,
+    shadow: &ShadowConfig,
+    request: impl FnOnce() -> ShadowRequest,
+) {
+    if shadow.fraction <= 0.0 || rand::rng().random::<f64>() >= shadow.fraction {
+        return;
+    }
+    let request = request();
+    let Some(pool) = state.targets.targets.get(&shadow.target).map(|p| p.clone()) else {
+        debug!(
+            "Shadow target '{}' for model '{}' not found",
+            shadow.target, request.model
+        );
+        record(&request.model, &shadow.target, "not_found", None);
+        return;
+    };
+
+    let state = state.clone();
+    let shadow_alias = shadow.target.clone();
+    tokio::spawn(async move {
+        send_copy(&state, &pool, &shadow_alias, request).await;
+    });
+}
+
+/// Send one copy to a provider of the shadow pool and record its outcome.
+/// The response body is read to the end, so the recorded latency covers the
+/// whole generation, then discarded.
+async fn send_copy<T: HttpClient>(
+    state: &AppState<T>,
+    pool: &ProviderPool,
+    shadow_alias: &str,
+    request: ShadowRequest,
+) {
+    let model = request.model.as_str();
+    let Some((_idx, target, _connection_guard)) = pool.select_iter().next() else {
+        record(model, shadow_alias, "no_capacity", None);
+        return;
+    };
+
+    // Address the copy to the shadow model, as its own callers would
+    let mut body = request.body;
+    if !body.is_empty()
+        && let Ok(mut value) = serde_json::from_slice::<serde_json::Value>(&body)
+        && let Some(object) = value.as_object_mut()
+        && object.contains_key("model")
+    {
+        let upstream_model = target.onwards_model.as_deref().unwrap_or(shadow_alias);
+        object.insert(
+            "model".to_string(),
+            serde_json::Value::String(upstream_model.to_string()),
+        );
+        if let Ok(bytes) = serde_json::to_vec(&value) {
+            body = Bytes::from(bytes);
+        }
+    }
+    if let Some(transform) = target.body_transform.as_ref() {
+        match transform.apply_request(&body) {
+            Ok(Some(bytes)) => body = Bytes::from(bytes),
+            Ok(None) => {}
+            Err(_) => {
+                record(model, shadow_alias, "invalid_request", None);
+                return;
+            }
+        }
+    }
+
+    let Some(uri) =
+        upstream_url(target, &request.path_and_query).and_then(|url| Uri::try_from(url).ok())
+    else {
+        record(model, shadow_alias, "invalid_request", None);
+        return;
+    };
+
+    let mut headers = request.headers;
+    if let Some(host) = uri.host() {
+        let host_value = match uri.port_u16() {
+            Some(port) => format!("{host}:{port}"),
+            None => host.to_string(),
+        };
+        if let Ok(value) = host_value.parse() {
+            headers.insert("host", value);
+        }
+    }
+    headers.insert(CONTENT_LENGTH, body.len().into());
+    headers.remove(TRANSFER_ENCODING);
+    headers.remove("traceparent");
+    headers.remove("tracestate");
+    filter_headers_for_upstream(&mut headers, target);
+    if let Some(sigv4) = target.sigv4.as_ref() {
+        crate::sigv4::sign_request(
+            sigv4,
+            &request.method,
+            &uri,
+            &mut headers,
+            &body,
+            std::time::SystemTime::now(),
+        );
+    }
+
+    let mut upstream_request = axum::extract::Request::new(axum::body::Body::from(body));
+    *upstream_request.method_mut() = request.method;
+    *upstream_request.uri_mut() = uri;
+    *upstream_request.headers_mut() = headers;
+
+    let started = Instant::now();
+    let response = match target.request_timeout_secs {
+        Some(secs) => {
+            match tokio::time::timeout(
+                Duration::from_secs(secs),
+                state.send_upstream(target, upstream_request),
+            )
+            .await
+            {
+                Ok(result) => result.map_err(|_| "error"),
+                Err(_) => Err("timeout"),
+            }
+        }
+        None => state
+            .send_upstream(target, upstream_request)
+            .await
+            .map_err(|_| "error"),
+    };
+
+    let outcome = match response {
+        Ok(response) => {
+            let status = response.status();
+            match response.into_body().collect().await {
+                Ok(_) if status.is_success() => "success",
+                Ok(_) => status_class(status.as_u16()),
+                Err(_) => "error",
+            }
+        }
+        Err(outcome) => outcome,
+    };
+    record(model, shadow_alias, outcome, Some(started.elapsed()));
+}
+
+fn status_class(status: u16) -> &'static str {
+    match status {
+        400..=499 => "4xx",
+        500..=599 => "5xx",
+        _ => "other",
+    }
+}
+
+fn record(model: &str, shadow: &str, outcome: &'static str, elapsed: Option<Duration>) {
+    metrics::counter!(
+        "onwards_shadow_requests_total",
+        "model" => model.to_string(),
+        "shadow" => shadow.to_string(),
+        "outcome" => outcome,
+    )
+    .increment(1);
+    if let Some(elapsed) = elapsed {
+        metrics::histogram!(
+            "onwards_shadow_request_duration_seconds",
+            "model" => model.to_string(),
+            "shadow" => shadow.to_string(),
+        )
+        .record(elapsed.as_secs_f64());
+    }
+    info!(
+        target: "onwards::shadow",
+        model,
+        shadow,
+        outcome,
+        latency_ms = elapsed.map(|e| e.as_millis() as u64),
+        "Shadow request completed"
+    );
+}
@@ -0,0 +1,331 @@
+//! AWS Signature Version 4 request signing.
+//!
+//! Providers such as AWS Bedrock authenticate with SigV4 rather than a bearer
+//! token. A target with a [`SigV4Config`] has each outbound request signed just
+//! before it is sent, after the body, `Host` and trace headers are final.
+//!
+//! Only the headers onwards controls are signed (`host`, `x-amz-date`, and
+//! `content-type` when present), so intermediaries that add or rewrite other
+//! headers don't invalidate the signature.
+
+use axum::http::{HeaderMap, HeaderValue, Method, Uri};
+use hmac::{Hmac, KeyInit, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+type HmacSha256 = Hmac<Sha256>;
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+fn default_service() -> String {
+    "bedrock".to_string()
+}
+
+/// Credentials and scope for signing requests to an AWS service.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SigV4Config {
+    /// AWS region, e.g. `us-east-1`
+    pub region: String,
+    /// Service name in the credential scope. Defaults to `bedrock`.
+    #[serde(default = "default_service")]
+    pub service: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
+// Manual Debug so the secret key is never logged.
+impl fmt::Debug for SigV4Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigV4Config")
+            .field("region", &self.region)
+            .field("service", &self.service)
+            .field("access_key_id", &self.access_key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Sign a request in place, adding `x-amz-date` and `authorization` headers.
+///
+/// `headers` must already contain the final `host` header.
+pub fn sign_request(
+    config: &SigV4Config,
+    method: &Method,
+    uri: &Uri,
+    headers: &mut HeaderMap,
+    body: &[u8],
+    now: SystemTime,
+) {
+    let (date, timestamp) = format_timestamp(now);
+    headers.insert(
+        "x-amz-date",
+        HeaderValue::from_str(&timestamp).expect("timestamp is a valid header value"),
+    );
+
+    let mut signed: Vec<(&str, String)> = ["content-type", "host", "x-amz-date"]
+        .into_iter()
+        .filter_map(|name| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| (name, v.trim().to_string()))
+        })
+        .collect();
+    signed.sort_by(|a, b| a.0.cmp(b.0));
+
+    let canonical_headers: String = signed
+        .iter()
+        .map(|(name, value)| format!("{name}:{value}\n"))
+        .collect();
+    let signed_headers = signed
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";");
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        method.as_str(),
+        canonical_uri(uri.path()),
+        canonical_query(uri.query().unwrap_or("")),
+        canonical_headers,
+        signed_headers,
+        hex::encode(Sha256::digest(body)),
+    );
+
+    let scope = format!("{date}/{}/{}/aws4_request", config.region, config.service);
+    let string_to_sign = format!(
+        "{ALGORITHM}\n{timestamp}\n{scope}\n{}",
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let k_date = hmac(
+        format!("AWS4{}", config.secret_access_key).as_bytes(),
+        date.as_bytes(),
+    );
+    let k_region = hmac(&k_date, config.region.as_bytes());
+    let k_service = hmac(&k_region, config.service.as_bytes());
+    let k_signing = hmac(&k_service, b"aws4_request");
+    let signature = hex::encode(hmac(&k_signing, string_to_sign.as_bytes()));
+
+    let authorization = format!(
+        "{ALGORITHM} Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+        config.access_key_id
+    );
+    headers.insert(
+        "authorization",
+        HeaderValue::from_str(&authorization).expect("authorization is a valid header value"),
+    );
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Percent-encode everything except RFC 3986 unreserved characters.
+fn uri_encode(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(hex) = bytes.get(i + 1..i + 3)
+            && let Ok(byte) = u8::from_str_radix(&String::from_utf8_lossy(hex), 16)
+        {
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Non-S3 services encode each path segment twice: once on the wire (already
+/// done in `path`) and once more in the canonical request.
+fn canonical_uri(path: &str) -> String {
+    if path.is_empty() {
+        return "/".to_string();
+    }
+    path.split('/')
+        .map(uri_encode)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn canonical_query(query: &str) -> String {
+    let mut params: Vec<(String, String)> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (
+                uri_encode(&percent_decode(name)),
+                uri_encode(&percent_decode(value)),
+            )
+        })
+        .collect();
+    params.sort();
+    params
+        .into_iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Returns `(YYYYMMDD, YYYYMMDDTHHMMSSZ)` in UTC.
+fn format_timestamp(now: SystemTime) -> (String, String) {
+    let secs = now
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    let (hour, minute, second) = (rem / 3600, (rem % 3600) / 60, rem % 60);
+
+    // Civil date from days since the epoch (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    let date = format!("{year:04}{month:02}{day:02}");
+    let timestamp = format!("{date}T{hour:02}{minute:02}{second:02}Z");
+    (date, timestamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn example_config() -> SigV4Config {
+        SigV4Config {
+            region: "us-east-1".to_string(),
+            service: "service".to_string(),
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+        }
+    }
+
+    /// 2015-08-30T12:36:00Z, the timestamp used throughout the AWS SigV4 test suite
+    fn example_time() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_440_938_160)
+    }
+
+    #[test]
+    fn test_timestamp_format() {
+        let (date, timestamp) = format_timestamp(example_time());
+        assert_eq!(date, "20150830");
+        assert_eq!(timestamp, "20150830T123600Z");
+    }
+
+    #[test]
+    fn test_get_vanilla_matches_aws_test_suite() {
+        let mut headers = HeaderMap::new();
+        headers.insert("host", HeaderValue::from_static("example.amazonaws.com"));
+
+        sign_request(
+            &example_config(),
+            &Method::GET,
+            &Uri::from_static("https://example.amazonaws.com/"),
+            &mut headers,
+            b"",
+            example_time(),
+        );
+
+        assert_eq!(headers["x-amz-date"], "20150830T123600Z");
+        assert_eq!(
+            headers["authorization"],
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+    }
+
+    #[test]
+    fn test_get_vanilla_query_order_matches_aws_test_suite() {
+        let mut headers = HeaderMap::new();
+        headers.insert("host", HeaderValue::from_static("example.amazonaws.com"));
+
+        sign_request(
+            &example_config(),
+            &Method::GET,
+            &Uri::from_static("https://example.amazonaws.com/?Param2=value2&Param1=value1"),
+            &mut headers,
+            b"",
+            example_time(),
+        );
+
+        assert!(headers["authorization"].to_str().unwrap().ends_with(
+            "Signature=b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500"
+        ));
+    }
+
+    #[test]
+    fn test_post_with_content_type_matches_aws_test_suite() {
+        let mut headers = HeaderMap::new();
+        headers.insert("host", HeaderValue::from_static("example.amazonaws.com"));
+        headers.insert(
+            "content-type",
+            HeaderValue::from_static("application/x-www-form-urlencoded"),
+        );
+
+        sign_request(
+            &example_config(),
+            &Method::POST,
+            &Uri::from_static("https://example.amazonaws.com/"),
+            &mut headers,
+            b"Param1=value1",
+            example_time(),
+        );
+
+        let authorization = headers["authorization"].to_str().unwrap();
+        assert!(authorization.contains("SignedHeaders=content-type;host;x-amz-date"));
+        assert!(authorization.ends_with(
+            "Signature=ff11897932ad3f4e8b18135d722051e5ac45fc38421b1da7b9d196a0fe09473a"
+        ));
+    }
+
+    #[test]
+    fn test_canonical_uri_double_encodes_segments() {
+        assert_eq!(canonical_uri(""), "/");
+        assert_eq!(
+            canonical_uri("/openai/v1/chat/completions"),
+            "/openai/v1/chat/completions"
+        );
+        assert_eq!(
+            canonical_uri("/model/anthropic.claude-v2%3A1/invoke"),
+            "/model/anthropic.claude-v2%253A1/invoke"
+        );
+    }
+
+    #[test]
+    fn test_debug_redacts_secret() {
+        let debug = format!("{:?}", example_config());
+        assert!(debug.contains("AKIDEXAMPLE"));
+        assert!(!debug.contains("wJalrXUtnFEMI"));
+    }
+}
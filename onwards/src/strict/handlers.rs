@@ -20,6 +20,8 @@ use super::schemas::completions::{
     normalize_completion_chunk_value, normalize_completion_response_value,
 };
 use super::schemas::embeddings::{EmbeddingsRequest, EmbeddingsResponse};
+use super::schemas::moderations::{ModerationsRequest, ModerationsResponse};
+use super::schemas::rerank::{RerankRequest, RerankResponse};
 use super::schemas::responses::{
     ResponsesRequest, ResponsesResponse, ResponsesStreamingEvent, generated_response_id,
     normalize_responses_response_value, normalize_responses_streaming_event_value,
@@ -425,6 +427,96 @@ pub async fn embeddings_handler<T: HttpClient + Clone + Send + Sync + 'static>(
     }
 }
 
+/// Handler for POST /v1/rerank
+///
+/// Validates the request against the Rerank schema, then forwards to the
+/// upstream provider's `/v1/rerank` endpoint.
+pub async fn rerank_handler<T: HttpClient + Clone + Send + Sync + 'static>(
+    State(state): State<AppState<T>>,
+    headers: HeaderMap,
+    Json(request): Json<RerankRequest>,
+) -> Response {
+    let original_model = request.model.clone();
+
+    debug!(
+        model = %original_model,
+        documents = request.documents.len(),
+        "Rerank request validated"
+    );
+
+    let body_bytes = match serde_json::to_vec(&request) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!(error = %e, "Failed to serialize rerank request");
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "server_error",
+                "Failed to process request",
+            );
+        }
+    };
+
+    let resolved_model =
+        extract_model_from_request(&headers, &body_bytes).unwrap_or(original_model.clone());
+    let ForwardResult {
+        response,
+        trusted,
+        internal_error,
+    } = forward_request(state, headers, "/rerank", body_bytes).await;
+
+    if response.status().is_success() {
+        sanitize_rerank_response(response, resolved_model).await
+    } else if trusted || internal_error {
+        debug!(model = %resolved_model, "Bypassing error sanitization for trusted provider");
+        response
+    } else {
+        sanitize_error_response(response).await
+    }
+}
+
+/// Handler for POST /v1/moderations
+///
+/// Validates the request against the Moderations schema, then forwards to the
+/// upstream provider's `/v1/moderations` endpoint.
+pub async fn moderations_handler<T: HttpClient + Clone + Send + Sync + 'static>(
+    State(state): State<AppState<T>>,
+    headers: HeaderMap,
+    Json(request): Json<ModerationsRequest>,
+) -> Response {
+    let original_model = request.model.clone();
+
+    debug!(model = %original_model, "Moderations request validated");
+
+    let body_bytes = match serde_json::to_vec(&request) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!(error = %e, "Failed to serialize moderations request");
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "server_error",
+                "Failed to process request",
+            );
+        }
+    };
+
+    let resolved_model =
+        extract_model_from_request(&headers, &body_bytes).unwrap_or(original_model.clone());
+    let ForwardResult {
+        response,
+        trusted,
+        internal_error,
+    } = forward_request(state, headers, "/moderations", body_bytes).await;
+
+    if response.status().is_success() {
+        sanitize_moderations_response(response, resolved_model).await
+    } else if trusted || internal_error {
+        debug!(model = %resolved_model, "Bypassing error sanitization for trusted provider");
+        response
+    } else {
+        sanitize_error_response(response).await
+    }
+}
+
 /// Handler for POST /v1/completions
 ///
 /// Validates the request against the legacy Completions schema, then forwards
@@ -567,8 +659,12 @@ fn should_use_adapter<T: HttpClient + Clone + Send + Sync + 'static>(
             debug!(model = %model, "Routing rule denied, defaulting to passthrough");
             false
         }
-        None => {
-            debug!(model = %model, "Pool is empty with no matching routing rules, cannot determine adapter setting");
+        Some(
+            crate::target::RoutingAction::Sanitize { .. }
+            | crate::target::RoutingAction::PreferRegion { .. },
+        )
+        | None => {
+            debug!(model = %model, "Pool is empty with no redirecting routing rule, cannot determine adapter setting");
             false
         }
     }
@@ -1931,6 +2027,126 @@ async fn sanitize_embeddings_response(mut response: Response, original_model: St
     }
 }
 
+/// Sanitize rerank response
+///
+/// Deserializes the response through our strict schema (drops extra fields),
+/// rewrites the model field, and re-serializes.
+async fn sanitize_rerank_response(mut response: Response, original_model: String) -> Response {
+    let body_bytes =
+        match axum::body::to_bytes(std::mem::take(response.body_mut()), usize::MAX).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                error!(error = %e, "Failed to read rerank response body");
+                return error_response(
+                    StatusCode::BAD_GATEWAY,
+                    "api_error",
+                    "Failed to read upstream response",
+                );
+            }
+        };
+
+    let mut rerank_response: RerankResponse = match serde_json::from_slice(&body_bytes) {
+        Ok(resp) => resp,
+        Err(e) => {
+            error!(
+                error = %e,
+                response_len = body_bytes.len(), // ZDR: length only, never response body content
+                "Failed to deserialize rerank response from provider, returning standard error"
+            );
+            return error_response(StatusCode::BAD_GATEWAY, "api_error", "Bad gateway");
+        }
+    };
+
+    rerank_response.model = original_model;
+
+    match serde_json::to_vec(&rerank_response) {
+        Ok(sanitized_bytes) => {
+            let content_length = sanitized_bytes.len();
+            *response.body_mut() = Body::from(sanitized_bytes);
+            response
+                .headers_mut()
+                .remove(axum::http::header::TRANSFER_ENCODING);
+            response.headers_mut().insert(
+                header::CONTENT_LENGTH,
+                header::HeaderValue::from(content_length),
+            );
+            debug!("Sanitized rerank response");
+            response
+        }
+        Err(e) => {
+            error!(
+                error = %e,
+                "Failed to serialize sanitized rerank response, returning standard error"
+            );
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "api_error",
+                "Internal server error",
+            )
+        }
+    }
+}
+
+/// Sanitize moderations response
+///
+/// Deserializes the response through our strict schema (drops extra fields),
+/// rewrites the model field, and re-serializes.
+async fn sanitize_moderations_response(mut response: Response, original_model: String) -> Response {
+    let body_bytes =
+        match axum::body::to_bytes(std::mem::take(response.body_mut()), usize::MAX).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                error!(error = %e, "Failed to read moderations response body");
+                return error_response(
+                    StatusCode::BAD_GATEWAY,
+                    "api_error",
+                    "Failed to read upstream response",
+                );
+            }
+        };
+
+    let mut moderations_response: ModerationsResponse = match serde_json::from_slice(&body_bytes) {
+        Ok(resp) => resp,
+        Err(e) => {
+            error!(
+                error = %e,
+                response_len = body_bytes.len(), // ZDR: length only, never response body content
+                "Failed to deserialize moderations response from provider, returning standard error"
+            );
+            return error_response(StatusCode::BAD_GATEWAY, "api_error", "Bad gateway");
+        }
+    };
+
+    moderations_response.model = original_model;
+
+    match serde_json::to_vec(&moderations_response) {
+        Ok(sanitized_bytes) => {
+            let content_length = sanitized_bytes.len();
+            *response.body_mut() = Body::from(sanitized_bytes);
+            response
+                .headers_mut()
+                .remove(axum::http::header::TRANSFER_ENCODING);
+            response.headers_mut().insert(
+                header::CONTENT_LENGTH,
+                header::HeaderValue::from(content_length),
+            );
+            debug!("Sanitized moderations response");
+            response
+        }
+        Err(e) => {
+            error!(
+                error = %e,
+                "Failed to serialize sanitized moderations response, returning standard error"
+            );
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "api_error",
+                "Internal server error",
+            )
+        }
+    }
+}
+
 /// Sanitize responses API response
 ///
 /// Deserializes the response through our strict schema (drops extra fields),
@@ -3447,6 +3663,216 @@ mod tests {
         assert!(!body_str.contains("text-embedding-3-small-internal"));
     }
 
+    /// Test that rerank requests reach the upstream /rerank path and the response
+    /// is sanitized like embeddings
+    #[tokio::test]
+    async fn test_strict_rerank_forwards_and_sanitizes() {
+        let targets = Arc::new(DashMap::new());
+        targets.insert(
+            "bge-reranker".to_string(),
+            Target::builder()
+                .url("https://api.example.com/v1/".parse().unwrap())
+                .onwards_key("sk-test".to_string())
+                .onwards_model("bge-reranker-v2-m3-internal".to_string())
+                .build()
+                .into_pool(),
+        );
+
+        let targets = Targets {
+            targets,
+            key_rate_limiters: Arc::new(DashMap::new()),
+            key_concurrency_limiters: Arc::new(DashMap::new()),
+            key_labels: Arc::new(DashMap::new()),
+            strict_mode: true,
+            http_pool_config: None,
+        };
+
+        let mock_response = r#"{
+            "id": "rerank-123",
+            "model": "bge-reranker-v2-m3-internal",
+            "results": [
+                {"index": 1, "relevance_score": 0.97},
+                {"index": 0, "relevance_score": 0.01}
+            ],
+            "usage": {"total_tokens": 42},
+            "provider": "custom-reranker"
+        }"#;
+
+        let mock_client = MockHttpClient::new(StatusCode::OK, mock_response);
+        let state = AppState::with_client(targets, mock_client.clone());
+        let router = crate::strict::build_strict_router(state);
+
+        let request_body = r#"{"model":"bge-reranker","query":"capital of France","documents":["Berlin","Paris"]}"#;
+        let request = Request::builder()
+            .method("POST")
+            .uri("/rerank")
+            .header("content-type", "application/json")
+            .body(Body::from(request_body))
+            .unwrap();
+
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body_str = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(body_str.contains("\"model\":\"bge-reranker\""));
+        assert!(body_str.contains("\"relevance_score\":0.97"));
+        assert!(!body_str.contains("internal"));
+        assert!(!body_str.contains("provider"));
+
+        let requests = mock_client.get_requests();
+        assert_eq!(requests.len(), 1);
+        assert!(
+            requests[0].uri.ends_with("/v1/rerank"),
+            "{}",
+            requests[0].uri
+        );
+    }
+
+    /// Test that malformed rerank requests are rejected before reaching the upstream
+    #[tokio::test]
+    async fn test_strict_rerank_rejects_missing_documents() {
+        let targets = Arc::new(DashMap::new());
+        targets.insert(
+            "bge-reranker".to_string(),
+            Target::builder()
+                .url("https://api.example.com/v1/".parse().unwrap())
+                .build()
+                .into_pool(),
+        );
+
+        let targets = Targets {
+            targets,
+            key_rate_limiters: Arc::new(DashMap::new()),
+            key_concurrency_limiters: Arc::new(DashMap::new()),
+            key_labels: Arc::new(DashMap::new()),
+            strict_mode: true,
+            http_pool_config: None,
+        };
+
+        let mock_client = MockHttpClient::new(StatusCode::OK, "{}");
+        let state = AppState::with_client(targets, mock_client.clone());
+        let router = crate::strict::build_strict_router(state);
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/rerank")
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"model":"bge-reranker","query":"capital"}"#))
+            .unwrap();
+
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(mock_client.get_requests().is_empty());
+    }
+
+    /// Test that moderation requests reach the upstream /moderations path and the
+    /// response is sanitized
+    #[tokio::test]
+    async fn test_strict_moderations_forwards_and_sanitizes() {
+        let targets = Arc::new(DashMap::new());
+        targets.insert(
+            "llama-guard".to_string(),
+            Target::builder()
+                .url("https://api.example.com/v1/".parse().unwrap())
+                .onwards_key("sk-test".to_string())
+                .onwards_model("llama-guard-4-12b-internal".to_string())
+                .build()
+                .into_pool(),
+        );
+
+        let targets = Targets {
+            targets,
+            key_rate_limiters: Arc::new(DashMap::new()),
+            key_concurrency_limiters: Arc::new(DashMap::new()),
+            key_labels: Arc::new(DashMap::new()),
+            strict_mode: true,
+            http_pool_config: None,
+        };
+
+        let mock_response = r#"{
+            "id": "modr-123",
+            "model": "llama-guard-4-12b-internal",
+            "results": [{
+                "flagged": true,
+                "categories": {"violence": true},
+                "category_scores": {"violence": 0.93}
+            }],
+            "provider": "custom-guard"
+        }"#;
+
+        let mock_client = MockHttpClient::new(StatusCode::OK, mock_response);
+        let state = AppState::with_client(targets, mock_client.clone());
+        let router = crate::strict::build_strict_router(state);
+
+        let request_body = r#"{"model":"llama-guard","input":"I want to hurt them."}"#;
+        let request = Request::builder()
+            .method("POST")
+            .uri("/moderations")
+            .header("content-type", "application/json")
+            .body(Body::from(request_body))
+            .unwrap();
+
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body_str = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(body_str.contains("\"model\":\"llama-guard\""));
+        assert!(body_str.contains("\"flagged\":true"));
+        assert!(!body_str.contains("internal"));
+        assert!(!body_str.contains("provider"));
+
+        let requests = mock_client.get_requests();
+        assert_eq!(requests.len(), 1);
+        assert!(
+            requests[0].uri.ends_with("/v1/moderations"),
+            "{}",
+            requests[0].uri
+        );
+    }
+
+    /// Test that moderation requests without input are rejected before reaching the upstream
+    #[tokio::test]
+    async fn test_strict_moderations_rejects_missing_input() {
+        let targets = Arc::new(DashMap::new());
+        targets.insert(
+            "llama-guard".to_string(),
+            Target::builder()
+                .url("https://api.example.com/v1/".parse().unwrap())
+                .build()
+                .into_pool(),
+        );
+
+        let targets = Targets {
+            targets,
+            key_rate_limiters: Arc::new(DashMap::new()),
+            key_concurrency_limiters: Arc::new(DashMap::new()),
+            key_labels: Arc::new(DashMap::new()),
+            strict_mode: true,
+            http_pool_config: None,
+        };
+
+        let mock_client = MockHttpClient::new(StatusCode::OK, "{}");
+        let state = AppState::with_client(targets, mock_client.clone());
+        let router = crate::strict::build_strict_router(state);
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/moderations")
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"model":"llama-guard"}"#))
+            .unwrap();
+
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(mock_client.get_requests().is_empty());
+    }
+
     /// Test that error responses return standard messages (no third-party details)
     #[tokio::test]
     async fn test_strict_error_returns_standard_message() {
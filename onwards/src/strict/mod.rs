@@ -148,6 +148,8 @@ pub use schemas::responses::{ResponsesRequest, ResponsesResponse, ResponsesStrea
 /// - `POST /v1/completions` - Legacy text completions (proxied to upstream /v1/completions)
 /// - `POST /v1/responses` - Open Responses API (validated, optional adapter)
 /// - `POST /v1/embeddings` - Embeddings API with schema validation
+/// - `POST /v1/rerank` - Rerank API with schema validation
+/// - `POST /v1/moderations` - Moderations API with schema validation
 /// - `GET /v1/models` - List available models
 /// - `GET /models` - List available models (alias)
 ///
@@ -187,6 +189,10 @@ pub fn build_strict_router<T: HttpClient + Clone + Send + Sync + 'static>(
         .route("/responses", post(handlers::responses_handler::<T>))
         // Embeddings
         .route("/embeddings", post(handlers::embeddings_handler::<T>))
+        // Rerank
+        .route("/rerank", post(handlers::rerank_handler::<T>))
+        // Moderations
+        .route("/moderations", post(handlers::moderations_handler::<T>))
         // Without this layer the `Json` extractors above fall back to Axum's
         // 2 MB default and reject larger payloads with a 413.
         .layer(DefaultBodyLimit::max(state.body_limit))
@@ -7,5 +7,7 @@
 pub mod chat_completions;
 pub mod completions;
 pub mod embeddings;
+pub mod moderations;
+pub mod rerank;
 pub mod responses;
 pub mod utils;
@@ -0,0 +1,120 @@
+//! Moderations API schemas
+//!
+//! These schemas match the OpenAI Moderations API specification.
+//! See: https://platform.openai.com/docs/api-reference/moderations
+//!
+//! Unlike OpenAI, `model` is required: it is what the request is routed on.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Request body for POST /v1/moderations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModerationsRequest {
+    /// The moderation model to use
+    pub model: String,
+
+    /// Input to classify - a string, an array of strings, or an array of
+    /// multimodal parts (`{"type": "text", ...}` / `{"type": "image_url", ...}`)
+    pub input: ModerationInput,
+}
+
+/// Input for moderations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ModerationInput {
+    Single(String),
+    Multiple(Vec<ModerationInputItem>),
+}
+
+/// A single moderation input - a string or a multimodal content part
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ModerationInputItem {
+    Text(String),
+    Part(serde_json::Map<String, serde_json::Value>),
+}
+
+/// Response from POST /v1/moderations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModerationsResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub model: String,
+    pub results: Vec<ModerationResult>,
+}
+
+/// Classification of a single input
+///
+/// Category names vary by model (e.g. `harassment/threatening`), so they are
+/// kept as maps rather than fixed fields.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModerationResult {
+    pub flagged: bool,
+    #[serde(default)]
+    pub categories: BTreeMap<String, bool>,
+    #[serde(default)]
+    pub category_scores: BTreeMap<String, f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category_applied_input_types: Option<BTreeMap<String, Vec<String>>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_string_input() {
+        let json = r#"{"model": "omni-moderation-latest", "input": "I want to hurt them."}"#;
+
+        let request: ModerationsRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.model, "omni-moderation-latest");
+        assert!(matches!(request.input, ModerationInput::Single(_)));
+    }
+
+    #[test]
+    fn test_deserialize_multimodal_input() {
+        let json = r#"{
+            "model": "omni-moderation-latest",
+            "input": [
+                {"type": "text", "text": "...text to classify goes here..."},
+                {"type": "image_url", "image_url": {"url": "https://example.com/image.png"}}
+            ]
+        }"#;
+
+        let request: ModerationsRequest = serde_json::from_str(json).unwrap();
+        match request.input {
+            ModerationInput::Multiple(items) => {
+                assert_eq!(items.len(), 2);
+                assert!(matches!(items[1], ModerationInputItem::Part(_)));
+            }
+            other => panic!("expected multiple inputs, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_reject_missing_input() {
+        let json = r#"{"model": "omni-moderation-latest"}"#;
+        assert!(serde_json::from_str::<ModerationsRequest>(json).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_response() {
+        let json = r#"{
+            "id": "modr-123",
+            "model": "omni-moderation-2024-09-26",
+            "results": [{
+                "flagged": true,
+                "categories": {"violence": true, "harassment/threatening": false},
+                "category_scores": {"violence": 0.93, "harassment/threatening": 0.02},
+                "category_applied_input_types": {"violence": ["text"]}
+            }]
+        }"#;
+
+        let response: ModerationsResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.results.len(), 1);
+        assert!(response.results[0].flagged);
+        assert_eq!(response.results[0].categories.get("violence"), Some(&true));
+    }
+}
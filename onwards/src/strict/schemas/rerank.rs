@@ -0,0 +1,122 @@
+//! Rerank API schemas
+//!
+//! OpenAI has no rerank endpoint, so these follow the de facto shape shared by
+//! Cohere, Jina, vLLM and TEI: a query plus a list of documents in, a list of
+//! scored document indices out.
+
+use serde::{Deserialize, Serialize};
+
+/// Request body for POST /v1/rerank
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RerankRequest {
+    /// The reranker model to use
+    pub model: String,
+
+    /// The query the documents are scored against
+    pub query: String,
+
+    /// Documents to score - plain strings or objects (e.g. `{"text": "..."}`)
+    pub documents: Vec<RerankDocument>,
+
+    /// Only return the `top_n` highest scoring documents
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_n: Option<u32>,
+
+    /// Whether to echo the documents back in the results
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_documents: Option<bool>,
+
+    /// User identifier for abuse tracking
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+}
+
+/// A document to rerank - a string or a structured object
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RerankDocument {
+    Text(String),
+    Object(serde_json::Map<String, serde_json::Value>),
+}
+
+/// Response from POST /v1/rerank
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RerankResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub model: String,
+    pub results: Vec<RerankResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<RerankUsage>,
+}
+
+/// A single scored document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RerankResult {
+    pub index: u32,
+    pub relevance_score: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub document: Option<RerankDocument>,
+}
+
+/// Usage information for rerank
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RerankUsage {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_tokens: Option<u32>,
+    pub total_tokens: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_string_documents() {
+        let json = r#"{
+            "model": "bge-reranker-v2-m3",
+            "query": "What is the capital of France?",
+            "documents": ["Paris is the capital of France.", "Berlin is in Germany."],
+            "top_n": 1
+        }"#;
+
+        let request: RerankRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.model, "bge-reranker-v2-m3");
+        assert_eq!(request.documents.len(), 2);
+        assert!(matches!(request.documents[0], RerankDocument::Text(_)));
+        assert_eq!(request.top_n, Some(1));
+    }
+
+    #[test]
+    fn test_deserialize_object_documents() {
+        let json = r#"{
+            "model": "bge-reranker-v2-m3",
+            "query": "capital",
+            "documents": [{"text": "Paris"}]
+        }"#;
+
+        let request: RerankRequest = serde_json::from_str(json).unwrap();
+        assert!(matches!(request.documents[0], RerankDocument::Object(_)));
+    }
+
+    #[test]
+    fn test_reject_missing_query() {
+        let json = r#"{"model": "bge-reranker-v2-m3", "documents": ["Paris"]}"#;
+        assert!(serde_json::from_str::<RerankRequest>(json).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_response_without_usage() {
+        let json = r#"{
+            "id": "rerank-123",
+            "model": "bge-reranker-v2-m3",
+            "results": [{"index": 1, "relevance_score": 0.98}, {"index": 0, "relevance_score": 0.02}]
+        }"#;
+
+        let response: RerankResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.results.len(), 2);
+        assert_eq!(response.results[0].index, 1);
+        assert!(response.usage.is_none());
+    }
+}
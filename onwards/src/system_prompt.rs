@@ -0,0 +1,161 @@
+//! Injecting a pool's system prompt into chat requests.
+//!
+//! When a pool sets `system_prompt`, the prompt is added to the `messages`
+//! array of each request before it is forwarded, so every provider in the pool
+//! (and any shadow copy) sees it. A request with no system message gets the
+//! prompt as a new first message. When the client sent its own system (or
+//! `developer`) message, the pool's `mode` decides: `prepend` inserts the
+//! prompt as a separate message ahead of it, `merge` adds the prompt to the
+//! start of the client's first system message. Bodies without a `messages`
+//! array, such as embeddings, pass through unchanged.
+
+use axum::body::Bytes;
+use serde_json::{Value, json};
+
+use crate::target::{SystemPromptConfig, SystemPromptMode};
+
+/// Roles treated as the system message when merging
+const SYSTEM_ROLES: &[&str] = &["system", "developer"];
+
+/// Inject `config`'s prompt into a request body. Returns the rewritten body,
+/// or `None` if the body has no `messages` array to inject into.
+pub(crate) fn inject(config: &SystemPromptConfig, body: &[u8]) -> Option<Bytes> {
+    let mut value: Value = serde_json::from_slice(body).ok()?;
+    let messages = value.get_mut("messages")?.as_array_mut()?;
+
+    let existing = messages.iter().position(|message| {
+        message
+            .get("role")
+            .and_then(Value::as_str)
+            .is_some_and(|role| SYSTEM_ROLES.contains(&role))
+    });
+
+    match (config.mode, existing) {
+        (SystemPromptMode::Merge, Some(index)) => {
+            let content = messages[index]
+                .as_object_mut()?
+                .entry("content")
+                .or_insert(Value::Null);
+            *content = match content.take() {
+                Value::String(text) if !text.is_empty() => {
+                    Value::String(format!("{}\n\n{}", config.content, text))
+                }
+                // Content parts: the prompt becomes the first text part
+                Value::Array(mut parts) => {
+                    parts.insert(0, json!({"type": "text", "text": config.content}));
+                    Value::Array(parts)
+                }
+                _ => Value::String(config.content.clone()),
+            };
+        }
+        _ => messages.insert(0, json!({"role": "system", "content": config.content})),
+    }
+
+    serde_json::to_vec(&value).ok().map(Bytes::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(mode: SystemPromptMode) -> SystemPromptConfig {
+        SystemPromptConfig {
+            content: "Answer in French.".to_string(),
+            mode,
+        }
+    }
+
+    fn inject_json(mode: SystemPromptMode, body: Value) -> Option<Value> {
+        inject(&config(mode), &serde_json::to_vec(&body).unwrap())
+            .map(|bytes| serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[test]
+    fn test_injects_system_message_when_none_exists() {
+        for mode in [SystemPromptMode::Prepend, SystemPromptMode::Merge] {
+            let body = inject_json(
+                mode,
+                json!({"model": "m", "messages": [{"role": "user", "content": "Hello"}]}),
+            )
+            .unwrap();
+            assert_eq!(
+                body["messages"],
+                json!([
+                    {"role": "system", "content": "Answer in French."},
+                    {"role": "user", "content": "Hello"}
+                ]),
+                "mode {mode:?}"
+            );
+            assert_eq!(body["model"], "m");
+        }
+    }
+
+    #[test]
+    fn test_prepend_keeps_client_system_message_separate() {
+        let body = inject_json(
+            SystemPromptMode::Prepend,
+            json!({"messages": [
+                {"role": "system", "content": "Be brief."},
+                {"role": "user", "content": "Hello"}
+            ]}),
+        )
+        .unwrap();
+        assert_eq!(
+            body["messages"],
+            json!([
+                {"role": "system", "content": "Answer in French."},
+                {"role": "system", "content": "Be brief."},
+                {"role": "user", "content": "Hello"}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_merge_combines_with_client_system_message() {
+        let body = inject_json(
+            SystemPromptMode::Merge,
+            json!({"messages": [
+                {"role": "user", "content": "Hello"},
+                {"role": "system", "content": "Be brief."}
+            ]}),
+        )
+        .unwrap();
+        assert_eq!(
+            body["messages"],
+            json!([
+                {"role": "user", "content": "Hello"},
+                {"role": "system", "content": "Answer in French.\n\nBe brief."}
+            ])
+        );
+
+        // Content parts and developer messages are merged too
+        let body = inject_json(
+            SystemPromptMode::Merge,
+            json!({"messages": [
+                {"role": "developer", "content": [{"type": "text", "text": "Be brief."}]},
+                {"role": "user", "content": "Hello"}
+            ]}),
+        )
+        .unwrap();
+        assert_eq!(
+            body["messages"][0]["content"],
+            json!([
+                {"type": "text", "text": "Answer in French."},
+                {"type": "text", "text": "Be brief."}
+            ])
+        );
+        assert_eq!(body["messages"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_bodies_without_messages_are_unchanged() {
+        assert!(
+            inject_json(
+                SystemPromptMode::Prepend,
+                json!({"model": "m", "input": "text"})
+            )
+            .is_none()
+        );
+        assert!(inject(&config(SystemPromptMode::Merge), b"not json").is_none());
+    }
+}
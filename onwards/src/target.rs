@@ -14,8 +14,12 @@
 //! Pool-level configuration (keys, rate_limit) applies to all providers in the pool.
 //! Provider-level configuration (url, onwards_key, weight) is specific to each provider.
 use crate::auth::KeySet;
+use crate::body_transform::BodyTransformConfig;
+use crate::key_rotation::KeyRotationConfig;
 use crate::load_balancer::{Provider, ProviderPool};
 use crate::reasoning::ReasoningTranslationConfig;
+use crate::sigv4::SigV4Config;
+use crate::tls::TlsConfig;
 use anyhow::anyhow;
 use async_trait::async_trait;
 use bon::Builder;
@@ -42,15 +46,38 @@ pub struct ConcurrencyLimitParameters {
     pub max_concurrent_requests: usize,
 }
 
+/// A concurrency limit shared by every provider on the same upstream endpoint,
+/// across all pools (e.g. a provider account limit covering several models).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EndpointConcurrencyLimit {
+    /// Identifies the endpoint; providers with the same value share the limit
+    pub endpoint: String,
+    pub max_concurrent_requests: usize,
+}
+
 /// Provider-specific configuration for a single upstream provider.
 /// This is used within a pool to configure individual providers.
 #[derive(Debug, Clone, Serialize, Deserialize, Builder)]
 pub struct ProviderSpec {
+    /// Identifier for this provider within its pool (e.g. the component
+    /// model alias of a composite). Lets callers pin a request to this
+    /// provider via the route-to header. Defaults to unnamed.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Region this provider is served from (e.g. "eu-west-1"). Requests that
+    /// prefer a region are sent to providers in that region first, falling
+    /// back to other regions. Defaults to no region.
+    #[serde(default)]
+    pub region: Option<String>,
     pub url: Url,
     pub onwards_key: Option<String>,
     pub onwards_model: Option<String>,
     pub rate_limit: Option<RateLimitParameters>,
     pub concurrency_limit: Option<ConcurrencyLimitParameters>,
+    /// Limit on concurrent requests to this provider's endpoint, shared with
+    /// every other provider (in any pool) naming the same endpoint.
+    #[serde(default)]
+    pub endpoint_concurrency_limit: Option<EndpointConcurrencyLimit>,
     #[serde(default)]
     pub upstream_auth_header_name: Option<String>,
     #[serde(default)]
@@ -71,6 +98,11 @@ pub struct ProviderSpec {
     #[serde(default)]
     pub sanitize_response: bool,
 
+    /// Rewrite the response `model` field to the model the client requested,
+    /// leaving the rest of the body untouched. Defaults to false.
+    #[serde(default)]
+    pub rewrite_response_model: bool,
+
     /// Open Responses API configuration
     #[serde(default)]
     pub open_responses: Option<OpenResponsesConfig>,
@@ -102,6 +134,24 @@ pub struct ProviderSpec {
     /// Translate canonical OpenAI reasoning controls into this provider's request shape.
     #[serde(default)]
     pub reasoning_translation: Option<ReasoningTranslationConfig>,
+
+    /// Sign upstream requests with AWS SigV4 (e.g. for Bedrock) instead of
+    /// sending `onwards_key` as a bearer token.
+    #[serde(default)]
+    pub sigv4: Option<SigV4Config>,
+
+    /// Custom CA and client certificate for connecting to this provider.
+    /// Defaults to the shared client (system roots, no client certificate).
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
+
+    /// Upstream keys to rotate between, sent in place of `onwards_key`.
+    #[serde(default)]
+    pub key_rotation: Option<KeyRotationConfig>,
+
+    /// Declarative edits applied to request and response bodies for this provider.
+    #[serde(default)]
+    pub body_transform: Option<BodyTransformConfig>,
 }
 
 /// Configuration for Open Responses API behavior
@@ -189,6 +239,63 @@ impl FallbackConfig {
     }
 }
 
+/// Shadow traffic: copy a sample of an alias's requests to another alias and
+/// discard the copies' responses, so a new deployment can be compared against
+/// live traffic without affecting callers.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ShadowConfig {
+    /// Alias of the pool the copies are sent to
+    pub target: String,
+    /// Fraction of requests copied, from 0.0 (none) to 1.0 (all)
+    pub fraction: f64,
+}
+
+/// A system prompt injected into the `messages` of every chat request sent
+/// to an alias, before it is forwarded.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SystemPromptConfig {
+    /// Text of the system prompt
+    pub content: String,
+    /// What to do when the request already has a system message
+    #[serde(default)]
+    pub mode: SystemPromptMode,
+}
+
+/// How an injected system prompt is combined with a system message the
+/// client sent. Requests without one always get the prompt as a new first message.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemPromptMode {
+    /// Insert the prompt as a separate system message before all others
+    #[default]
+    Prepend,
+    /// Add the prompt to the start of the client's first system message
+    Merge,
+}
+
+/// A cap on the number of completions (`n`) a request to an alias may ask
+/// for. Each completion is generated and billed separately, so an uncapped
+/// `n` multiplies a request's cost.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NLimitConfig {
+    /// Largest `n` allowed
+    pub max: u32,
+    /// What to do with a request asking for more
+    #[serde(default)]
+    pub mode: NLimitMode,
+}
+
+/// How a request whose `n` is above the cap is handled.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NLimitMode {
+    /// Reject the request with a 400
+    #[default]
+    Reject,
+    /// Lower `n` to the cap and forward the request
+    Clamp,
+}
+
 /// Jitter strategy applied to retry backoff delays.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -293,6 +400,12 @@ pub struct PoolSpec {
     #[serde(default)]
     pub concurrency_limit: Option<ConcurrencyLimitParameters>,
 
+    /// Per-key concurrency limit on this alias: the most concurrent requests
+    /// any one API key may hold, so a single key can't take the whole pool's
+    /// capacity and starve other callers
+    #[serde(default)]
+    pub per_key_concurrency_limit: Option<ConcurrencyLimitParameters>,
+
     /// Default response headers for all providers in this pool
     #[serde(default)]
     pub response_headers: Option<HashMap<String, String>>,
@@ -329,6 +442,26 @@ pub struct PoolSpec {
     #[serde(default)]
     pub routing_rules: Vec<RoutingRule>,
 
+    /// Response headers chosen by the calling key's labels (e.g. pricing that
+    /// depends on the key's purpose). First matching entry wins and is added on
+    /// top of the provider's `response_headers`.
+    #[serde(default)]
+    #[builder(default)]
+    pub labeled_response_headers: Vec<LabeledResponseHeaders>,
+
+    /// Copy a sample of this alias's requests to a shadow alias. The copies'
+    /// responses are discarded and only their latency and status recorded.
+    #[serde(default)]
+    pub shadow: Option<ShadowConfig>,
+
+    /// System prompt injected into chat requests to this alias.
+    #[serde(default)]
+    pub system_prompt: Option<SystemPromptConfig>,
+
+    /// Cap on the number of completions (`n`) a request may ask for.
+    #[serde(default)]
+    pub n_limit: Option<NLimitConfig>,
+
     /// The list of providers to load balance across
     pub providers: Vec<ProviderSpec>,
 }
@@ -363,6 +496,12 @@ pub struct TargetSpec {
     #[builder(default)]
     pub sanitize_response: bool,
 
+    /// Rewrite the response `model` field to the model the client requested.
+    /// Defaults to false.
+    #[serde(default)]
+    #[builder(default)]
+    pub rewrite_response_model: bool,
+
     /// Open Responses API configuration
     #[serde(default)]
     pub open_responses: Option<OpenResponsesConfig>,
@@ -392,6 +531,24 @@ pub struct TargetSpec {
     /// Translate canonical OpenAI reasoning controls into this provider's request shape.
     #[serde(default)]
     pub reasoning_translation: Option<ReasoningTranslationConfig>,
+
+    /// Sign upstream requests with AWS SigV4 (e.g. for Bedrock) instead of
+    /// sending `onwards_key` as a bearer token.
+    #[serde(default)]
+    pub sigv4: Option<SigV4Config>,
+
+    /// Custom CA and client certificate for connecting to this provider.
+    /// Defaults to the shared client (system roots, no client certificate).
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
+
+    /// Upstream keys to rotate between, sent in place of `onwards_key`.
+    #[serde(default)]
+    pub key_rotation: Option<KeyRotationConfig>,
+
+    /// Declarative edits applied to request and response bodies for this provider.
+    #[serde(default)]
+    pub body_transform: Option<BodyTransformConfig>,
 }
 
 fn default_weight() -> u32 {
@@ -416,6 +573,7 @@ pub struct PoolConfig {
     pub keys: Option<KeySet>,
     pub rate_limit: Option<RateLimitParameters>,
     pub concurrency_limit: Option<ConcurrencyLimitParameters>,
+    pub per_key_concurrency_limit: Option<ConcurrencyLimitParameters>,
     pub response_headers: Option<HashMap<String, String>>,
     pub fallback: Option<FallbackConfig>,
     pub strategy: LoadBalanceStrategy,
@@ -423,6 +581,10 @@ pub struct PoolConfig {
     pub open_responses: Option<OpenResponsesConfig>,
     pub trusted: bool,
     pub routing_rules: Vec<RoutingRule>,
+    pub labeled_response_headers: Vec<LabeledResponseHeaders>,
+    pub shadow: Option<ShadowConfig>,
+    pub system_prompt: Option<SystemPromptConfig>,
+    pub n_limit: Option<NLimitConfig>,
     pub providers: Vec<ProviderSpec>,
 }
 
@@ -434,6 +596,7 @@ impl TargetSpecOrList {
                 keys: pool.keys,
                 rate_limit: pool.rate_limit,
                 concurrency_limit: pool.concurrency_limit,
+                per_key_concurrency_limit: pool.per_key_concurrency_limit,
                 response_headers: pool.response_headers,
                 fallback: pool.fallback,
                 strategy: pool.strategy,
@@ -441,6 +604,10 @@ impl TargetSpecOrList {
                 open_responses: pool.open_responses,
                 trusted: pool.trusted,
                 routing_rules: pool.routing_rules,
+                labeled_response_headers: pool.labeled_response_headers,
+                shadow: pool.shadow,
+                system_prompt: pool.system_prompt,
+                n_limit: pool.n_limit,
                 providers: pool.providers,
             }),
             TargetSpecOrList::List(list) => {
@@ -460,16 +627,20 @@ impl TargetSpecOrList {
                 let providers = list
                     .into_iter()
                     .map(|t| ProviderSpec {
+                        name: None,
+                        region: None,
                         url: t.url,
                         onwards_key: t.onwards_key,
                         onwards_model: t.onwards_model,
                         rate_limit: t.rate_limit,
                         concurrency_limit: t.concurrency_limit,
+                        endpoint_concurrency_limit: None,
                         upstream_auth_header_name: t.upstream_auth_header_name,
                         upstream_auth_header_prefix: t.upstream_auth_header_prefix,
                         response_headers: t.response_headers,
                         weight: t.weight,
                         sanitize_response: t.sanitize_response,
+                        rewrite_response_model: t.rewrite_response_model,
                         open_responses: t.open_responses,
                         request_timeout_secs: t.request_timeout_secs,
                         trusted: None, // pool-level trusted handles this for legacy format
@@ -479,12 +650,17 @@ impl TargetSpecOrList {
                         // an unset value still inherits the resolved trusted value.
                         propagate_trace_context: t.propagate_trace_context,
                         reasoning_translation: t.reasoning_translation,
+                        sigv4: t.sigv4,
+                        tls: t.tls,
+                        key_rotation: t.key_rotation,
+                        body_transform: t.body_transform,
                     })
                     .collect();
                 Ok(PoolConfig {
                     keys,
                     rate_limit: None,
                     concurrency_limit: None,
+                    per_key_concurrency_limit: None,
                     response_headers: None,
                     fallback: None,
                     strategy: LoadBalanceStrategy::default(),
@@ -492,6 +668,10 @@ impl TargetSpecOrList {
                     open_responses: None,
                     trusted,
                     routing_rules: Vec::new(),
+                    labeled_response_headers: Vec::new(),
+                    shadow: None,
+                    system_prompt: None,
+                    n_limit: None,
                     providers,
                 })
             }
@@ -502,16 +682,20 @@ impl TargetSpecOrList {
                 let open_responses = spec.open_responses.clone();
                 let trusted = spec.trusted;
                 let provider = ProviderSpec {
+                    name: None,
+                    region: None,
                     url: spec.url,
                     onwards_key: spec.onwards_key,
                     onwards_model: spec.onwards_model,
                     rate_limit: spec.rate_limit,
                     concurrency_limit: spec.concurrency_limit,
+                    endpoint_concurrency_limit: None,
                     upstream_auth_header_name: spec.upstream_auth_header_name,
                     upstream_auth_header_prefix: spec.upstream_auth_header_prefix,
                     response_headers: spec.response_headers,
                     weight: spec.weight,
                     sanitize_response: false, // Will be OR'd with pool-level setting
+                    rewrite_response_model: spec.rewrite_response_model,
                     open_responses: open_responses.clone(),
                     request_timeout_secs: spec.request_timeout_secs,
                     trusted: None, // pool-level trusted handles this for single-provider format
@@ -520,11 +704,16 @@ impl TargetSpecOrList {
                     // YAML form would be silently dropped.
                     propagate_trace_context: spec.propagate_trace_context,
                     reasoning_translation: spec.reasoning_translation,
+                    sigv4: spec.sigv4,
+                    tls: spec.tls,
+                    key_rotation: spec.key_rotation,
+                    body_transform: spec.body_transform,
                 };
                 Ok(PoolConfig {
                     keys,
                     rate_limit: None,
                     concurrency_limit: None,
+                    per_key_concurrency_limit: None,
                     response_headers: None,
                     fallback: None,
                     strategy: LoadBalanceStrategy::default(),
@@ -532,6 +721,10 @@ impl TargetSpecOrList {
                     open_responses,
                     trusted,
                     routing_rules: Vec::new(),
+                    labeled_response_headers: Vec::new(),
+                    shadow: None,
+                    system_prompt: None,
+                    n_limit: None,
                     providers: vec![provider],
                 })
             }
@@ -556,6 +749,8 @@ fn normalize_url(mut url: Url) -> Url {
 impl From<TargetSpec> for Target {
     fn from(value: TargetSpec) -> Self {
         Target {
+            name: None,
+            region: None,
             url: normalize_url(value.url),
             keys: value.keys,
             onwards_key: value.onwards_key,
@@ -566,15 +761,21 @@ impl From<TargetSpec> for Target {
                         .allow_burst(rl.burst_size.unwrap_or(rl.requests_per_second)),
                 )) as Arc<dyn RateLimiter>
             }),
+            endpoint_concurrency_limit: value.endpoint_concurrency_limit,
             upstream_auth_header_name: value.upstream_auth_header_name,
             upstream_auth_header_prefix: value.upstream_auth_header_prefix,
             response_headers: value.response_headers,
             sanitize_response: value.sanitize_response,
+            rewrite_response_model: value.rewrite_response_model,
             open_responses: value.open_responses,
             request_timeout_secs: value.request_timeout_secs,
             trusted: None,
             propagate_trace_context: value.propagate_trace_context,
             reasoning_translation: value.reasoning_translation,
+            sigv4: value.sigv4,
+            tls: value.tls,
+            key_rotation: value.key_rotation,
+            body_transform: value.body_transform,
         }
     }
 }
@@ -582,6 +783,8 @@ impl From<TargetSpec> for Target {
 impl From<ProviderSpec> for Target {
     fn from(value: ProviderSpec) -> Self {
         Target {
+            name: value.name,
+            region: value.region,
             url: normalize_url(value.url),
             keys: None, // Provider-level targets don't have keys; keys are at pool level
             onwards_key: value.onwards_key,
@@ -592,15 +795,21 @@ impl From<ProviderSpec> for Target {
                         .allow_burst(rl.burst_size.unwrap_or(rl.requests_per_second)),
                 )) as Arc<dyn RateLimiter>
             }),
+            endpoint_concurrency_limit: value.endpoint_concurrency_limit,
             upstream_auth_header_name: value.upstream_auth_header_name,
             upstream_auth_header_prefix: value.upstream_auth_header_prefix,
             response_headers: value.response_headers,
             sanitize_response: value.sanitize_response,
+            rewrite_response_model: value.rewrite_response_model,
             open_responses: value.open_responses,
             request_timeout_secs: value.request_timeout_secs,
             trusted: value.trusted,
             propagate_trace_context: value.propagate_trace_context,
             reasoning_translation: value.reasoning_translation,
+            sigv4: value.sigv4,
+            tls: value.tls,
+            key_rotation: value.key_rotation,
+            body_transform: value.body_transform,
         }
     }
 }
@@ -624,11 +833,17 @@ impl RateLimiter for DefaultDirectRateLimiter {
 #[derive(Debug)]
 pub struct ConcurrencyGuard {
     active: Arc<AtomicUsize>,
+    /// The [`KeyedConcurrencyLimiter`] counters and key to evict the counter
+    /// from once it is released.
+    evict: Option<(KeyedCounters, String)>,
 }
 
 impl Drop for ConcurrencyGuard {
     fn drop(&mut self) {
         self.active.fetch_sub(1, Ordering::Release);
+        if let Some((counters, key)) = &self.evict {
+            counters.remove_if(key, |_, active| active.load(Ordering::Acquire) == 0);
+        }
     }
 }
 
@@ -682,6 +897,7 @@ impl ConcurrencyLimiter {
             {
                 return Some(ConcurrencyGuard {
                     active: Arc::clone(&self.active),
+                    evict: None,
                 });
             }
         }
@@ -716,6 +932,117 @@ impl ConcurrencyLimiter {
     }
 }
 
+type KeyedCounters = Arc<DashMap<String, Arc<AtomicUsize>>>;
+
+/// Enforces a concurrency limit separately for each key sharing a pool.
+///
+/// A key's counter is created on its first request and removed once its last
+/// request finishes, so only keys with requests in flight take up memory.
+/// Counters are shared between clones, so in-flight guards stay accounted for
+/// across config reloads.
+#[derive(Debug, Clone)]
+pub struct KeyedConcurrencyLimiter {
+    active: KeyedCounters,
+    limit: usize,
+}
+
+impl KeyedConcurrencyLimiter {
+    /// Create a limiter allowing each key at most `limit` concurrent requests.
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            active: Arc::new(DashMap::new()),
+            limit,
+        }
+    }
+
+    /// Try to acquire a concurrency slot for `key`.
+    /// Returns a guard on success, None if the key is at its limit.
+    pub fn try_acquire(&self, key: &str) -> Option<ConcurrencyGuard> {
+        let acquire = |active: &Arc<AtomicUsize>| {
+            ConcurrencyLimiter {
+                active: Arc::clone(active),
+                limit: Some(self.limit),
+            }
+            .try_acquire()
+        };
+        // The slot is taken while holding the entry's lock, so a released
+        // counter can't be evicted between being looked up and incremented.
+        let guard = match self.active.get_mut(key) {
+            Some(active) => acquire(&active),
+            None => acquire(self.active.entry(key.to_string()).or_default().value()),
+        };
+        match guard {
+            Some(mut guard) => {
+                guard.evict = Some((Arc::clone(&self.active), key.to_string()));
+                Some(guard)
+            }
+            None => {
+                // Only a zero limit refuses a key with nothing in flight
+                self.active
+                    .remove_if(key, |_, active| active.load(Ordering::Acquire) == 0);
+                None
+            }
+        }
+    }
+
+    /// Get the number of active requests held by `key`.
+    pub fn active(&self, key: &str) -> usize {
+        self.active
+            .get(key)
+            .map_or(0, |active| active.load(Ordering::Acquire))
+    }
+
+    /// Get the per-key concurrency limit.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Adopt the per-key active counters from another limiter, keeping the
+    /// limit from `self` (see [`ConcurrencyLimiter::adopt_active_counter`]).
+    pub fn adopt_active_counters(&mut self, old: &KeyedConcurrencyLimiter) {
+        self.active = Arc::clone(&old.active);
+    }
+}
+
+/// Counts concurrent requests per upstream endpoint, for providers with an
+/// [`EndpointConcurrencyLimit`].
+///
+/// The limit comes from each provider's config, so it follows config reloads,
+/// while the counters live here (in the app state) and keep in-flight
+/// requests accounted for.
+#[derive(Debug, Clone, Default)]
+pub struct EndpointConcurrencyLimiters {
+    active: Arc<DashMap<String, Arc<AtomicUsize>>>,
+}
+
+impl EndpointConcurrencyLimiters {
+    /// Try to acquire a slot on the endpoint named by `limit`.
+    /// Returns a guard on success, None if the endpoint is at its limit.
+    pub fn try_acquire(&self, limit: &EndpointConcurrencyLimit) -> Option<ConcurrencyGuard> {
+        let active = match self.active.get(&limit.endpoint) {
+            Some(active) => Arc::clone(&active),
+            None => Arc::clone(
+                self.active
+                    .entry(limit.endpoint.clone())
+                    .or_default()
+                    .value(),
+            ),
+        };
+        ConcurrencyLimiter {
+            active,
+            limit: Some(limit.max_concurrent_requests),
+        }
+        .try_acquire()
+    }
+
+    /// Get the number of active requests to `endpoint`.
+    pub fn active(&self, endpoint: &str) -> usize {
+        self.active
+            .get(endpoint)
+            .map_or(0, |active| active.load(Ordering::Acquire))
+    }
+}
+
 /// A target represents a destination for requests, specified by its URL.
 ///
 /// ## Validating incoming requests
@@ -728,11 +1055,17 @@ impl ConcurrencyLimiter {
 #[derive(Debug, Clone, Builder)]
 #[builder(derive(Clone))]
 pub struct Target {
+    /// Provider name within its pool, used to pin requests via the route-to header
+    pub name: Option<String>,
+    /// Region the provider is served from, used for region-preferring selection
+    pub region: Option<String>,
     pub url: Url,
     pub keys: Option<KeySet>,
     pub onwards_key: Option<String>,
     pub onwards_model: Option<String>,
     pub limiter: Option<Arc<dyn RateLimiter>>,
+    /// Concurrency limit shared with other providers on the same endpoint
+    pub endpoint_concurrency_limit: Option<EndpointConcurrencyLimit>,
     pub upstream_auth_header_name: Option<String>,
     pub upstream_auth_header_prefix: Option<String>,
     /// Custom headers to include in responses (e.g., pricing, metadata)
@@ -740,6 +1073,9 @@ pub struct Target {
     /// Enable response sanitization to enforce strict OpenAI schema compliance
     #[builder(default)]
     pub sanitize_response: bool,
+    /// Rewrite the response `model` field to the requested model
+    #[builder(default)]
+    pub rewrite_response_model: bool,
     /// Open Responses API configuration
     pub open_responses: Option<OpenResponsesConfig>,
     pub request_timeout_secs: Option<u64>,
@@ -751,6 +1087,14 @@ pub struct Target {
     pub propagate_trace_context: Option<bool>,
     /// Provider-specific translation for canonical OpenAI reasoning controls.
     pub reasoning_translation: Option<ReasoningTranslationConfig>,
+    /// AWS SigV4 signing for upstream requests.
+    pub sigv4: Option<SigV4Config>,
+    /// TLS settings; requests are sent through a client built from them.
+    pub tls: Option<TlsConfig>,
+    /// Upstream keys rotated between in place of `onwards_key`.
+    pub key_rotation: Option<KeyRotationConfig>,
+    /// Declarative request/response body edits for this provider.
+    pub body_transform: Option<BodyTransformConfig>,
 }
 
 impl Target {
@@ -783,7 +1127,7 @@ pub struct KeyDefinition {
     pub labels: HashMap<String, String>,
 }
 
-/// A rule that matches on key labels and takes an action (deny or redirect).
+/// A rule that matches on key labels and takes an action (deny, redirect, sanitize, or prefer a region).
 /// Rules are evaluated in order; first match wins.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RoutingRule {
@@ -801,6 +1145,20 @@ pub enum RoutingAction {
     Deny,
     /// Redirect to another model alias
     Redirect { target: String },
+    /// Serve the request normally, overriding the target's `sanitize_response` setting
+    Sanitize { enabled: bool },
+    /// Serve the request from providers in `region` when any are available,
+    /// falling back to the rest of the pool
+    PreferRegion { region: String },
+}
+
+/// Response headers added when the calling key's labels match
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabeledResponseHeaders {
+    /// All label conditions must match for these headers to apply
+    pub match_labels: HashMap<String, String>,
+    /// Headers to add, replacing provider headers of the same name
+    pub headers: HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Builder)]
@@ -824,6 +1182,27 @@ pub struct HttpPoolConfig {
     /// 90s balances connection reuse with avoiding stale connections.
     #[serde(default = "default_pool_idle_timeout_secs")]
     pub idle_timeout_secs: u64,
+    /// Speak HTTP/2 to every upstream without negotiating it first ("prior
+    /// knowledge"). All upstreams must then accept HTTP/2, e.g. cleartext h2c.
+    /// Lets many small requests share one connection.
+    #[serde(default)]
+    pub http2_prior_knowledge: bool,
+    /// Ask upstreams for gzip-compressed responses when the caller didn't send
+    /// `Accept-Encoding`, and decompress them before they are returned, so the
+    /// caller sees the same body either way.
+    #[serde(default)]
+    pub gzip: bool,
+}
+
+impl Default for HttpPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_idle_per_host: default_pool_max_idle_per_host(),
+            idle_timeout_secs: default_pool_idle_timeout_secs(),
+            http2_prior_knowledge: false,
+            gzip: false,
+        }
+    }
 }
 
 fn default_pool_max_idle_per_host() -> usize {
@@ -1048,6 +1427,16 @@ impl Targets {
                         )
                     })?;
                 }
+                if let Some(config) = provider.body_transform.as_ref() {
+                    config.validate().map_err(|error| {
+                        anyhow!(
+                            "Invalid body transform for target '{}' provider {}: {}",
+                            name,
+                            index,
+                            error
+                        )
+                    })?;
+                }
             }
 
             // Merge global keys with pool-level keys
@@ -1073,6 +1462,9 @@ impl Targets {
             let pool_concurrency_limiter = pool_config
                 .concurrency_limit
                 .map(|cl| ConcurrencyLimiter::with_limit(cl.max_concurrent_requests));
+            let per_key_concurrency_limiter = pool_config
+                .per_key_concurrency_limit
+                .map(|cl| KeyedConcurrencyLimiter::with_limit(cl.max_concurrent_requests));
 
             // Convert provider specs to providers
             // Pool-level sanitize_response enables sanitization for all providers
@@ -1105,7 +1497,12 @@ impl Targets {
                 pool_config.strategy,
                 pool_config.trusted,
                 pool_config.routing_rules,
-            );
+            )
+            .with_per_key_concurrency_limiter(per_key_concurrency_limiter)
+            .with_labeled_response_headers(pool_config.labeled_response_headers)
+            .with_shadow(pool_config.shadow)
+            .with_system_prompt(pool_config.system_prompt)
+            .with_n_limit(pool_config.n_limit);
             debug!(
                 "Created provider pool '{}' with {} provider(s), fallback enabled: {}, strategy: {:?}",
                 name,
@@ -1951,6 +2348,34 @@ mod tests {
         assert!(guard.is_some());
     }
 
+    #[test]
+    fn test_keyed_concurrency_limiter_evicts_released_keys() {
+        let limiter = KeyedConcurrencyLimiter::with_limit(2);
+
+        let guard1 = limiter.try_acquire("sk-a").unwrap();
+        let guard2 = limiter.try_acquire("sk-a").unwrap();
+        assert!(limiter.try_acquire("sk-a").is_none());
+        let other = limiter.try_acquire("sk-b").unwrap();
+        assert_eq!(limiter.active("sk-a"), 2);
+
+        // A key stays tracked until its last request finishes
+        drop(guard1);
+        assert_eq!(limiter.active("sk-a"), 1);
+        assert!(limiter.active.contains_key("sk-a"));
+        drop(guard2);
+        drop(other);
+        assert!(limiter.active.is_empty());
+
+        // An evicted key starts again from zero
+        let _guard = limiter.try_acquire("sk-a").unwrap();
+        assert_eq!(limiter.active("sk-a"), 1);
+
+        // A refused key isn't kept either
+        let closed = KeyedConcurrencyLimiter::with_limit(0);
+        assert!(closed.try_acquire("sk-a").is_none());
+        assert!(closed.active.is_empty());
+    }
+
     #[test]
     fn test_per_key_concurrency_limiting_configured() {
         use std::collections::HashMap;
@@ -2283,6 +2708,7 @@ mod tests {
             keys: None,
             rate_limit: None,
             concurrency_limit: None,
+            per_key_concurrency_limit: None,
             response_headers: None,
             fallback: None,
             strategy: LoadBalanceStrategy::default(),
@@ -2290,22 +2716,34 @@ mod tests {
             open_responses: None,
             trusted: true,
             routing_rules: Vec::new(),
+            labeled_response_headers: Vec::new(),
+            shadow: None,
+            system_prompt: None,
+            n_limit: None,
             providers: vec![ProviderSpec {
+                name: None,
+                region: None,
                 url: "https://api.example.com".parse().unwrap(),
                 onwards_key: None,
                 onwards_model: None,
                 rate_limit: None,
                 concurrency_limit: None,
+                endpoint_concurrency_limit: None,
                 upstream_auth_header_name: None,
                 upstream_auth_header_prefix: None,
                 response_headers: None,
                 weight: 1,
                 sanitize_response: false,
+                rewrite_response_model: false,
                 open_responses: None,
                 request_timeout_secs: None,
                 trusted: None,
                 propagate_trace_context: None,
                 reasoning_translation: None,
+                sigv4: None,
+                tls: None,
+                key_rotation: None,
+                body_transform: None,
             }],
         };
 
@@ -0,0 +1,200 @@
+//! Per-provider TLS settings.
+//!
+//! The shared HTTP client trusts only the system roots and presents no client
+//! certificate, so it can't reach providers behind a private CA or providers
+//! that require mutual TLS. A target with a [`TlsConfig`] is sent through its
+//! own client built from those settings instead.
+//!
+//! Clients are cached in [`TlsClients`] (in the app state) by their settings,
+//! so their connection pools survive config reloads and providers with the
+//! same settings share one.
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+
+use crate::client::{HyperClient, create_hyper_client_with_tls};
+use crate::target::HttpPoolConfig;
+
+/// TLS settings for connecting to a provider. Certificates and keys are PEM text.
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// CA certificates to trust in addition to the system roots
+    #[serde(default)]
+    pub ca_bundle: Option<String>,
+    /// Client certificate chain presented for mutual TLS
+    #[serde(default)]
+    pub client_cert: Option<String>,
+    /// PKCS#8 private key for `client_cert`
+    #[serde(default)]
+    pub client_key: Option<String>,
+}
+
+// Manual Debug so the private key is never logged.
+impl fmt::Debug for TlsConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsConfig")
+            .field("ca_bundle", &self.ca_bundle)
+            .field("client_cert", &self.client_cert)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TlsConfig {
+    /// Build a TLS connector that trusts `ca_bundle` and presents the client
+    /// certificate, when set.
+    pub fn connector(&self) -> Result<native_tls::TlsConnector, native_tls::Error> {
+        let mut builder = native_tls::TlsConnector::builder();
+        if let Some(ca_bundle) = &self.ca_bundle {
+            for cert in native_tls::Certificate::stack_from_pem(ca_bundle.as_bytes())? {
+                builder.add_root_certificate(cert);
+            }
+        }
+        if let (Some(cert), Some(key)) = (&self.client_cert, &self.client_key) {
+            builder.identity(native_tls::Identity::from_pkcs8(
+                cert.as_bytes(),
+                key.as_bytes(),
+            )?);
+        }
+        builder.build()
+    }
+}
+
+/// HTTP clients for providers with a [`TlsConfig`], keyed by their settings.
+///
+/// Clients are created on first use and kept for the life of the app state.
+#[derive(Debug, Clone, Default)]
+pub struct TlsClients {
+    pool_config: HttpPoolConfig,
+    clients: Arc<DashMap<TlsConfig, HyperClient>>,
+}
+
+impl TlsClients {
+    /// Clients are created with the connection pool settings in `pool_config`.
+    pub fn new(pool_config: HttpPoolConfig) -> Self {
+        Self {
+            pool_config,
+            clients: Arc::default(),
+        }
+    }
+
+    /// Get the client for `tls`, creating it if this is its first use.
+    pub fn get(&self, tls: &TlsConfig) -> Result<HyperClient, native_tls::Error> {
+        if let Some(client) = self.clients.get(tls) {
+            return Ok(client.clone());
+        }
+        let client = create_hyper_client_with_tls(&self.pool_config, tls)?;
+        Ok(self.clients.entry(tls.clone()).or_insert(client).clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::target::{Target, Targets};
+    use crate::{AppState, build_router};
+    use axum_test::TestServer;
+    use hyper_util::rt::TokioIo;
+    use serde_json::json;
+    use std::net::SocketAddr;
+
+    const CA: &str = include_str!("test_fixtures/tls/ca.pem");
+    const SERVER_CERT: &str = include_str!("test_fixtures/tls/server.pem");
+    const SERVER_KEY: &str = include_str!("test_fixtures/tls/server-key.pem");
+
+    /// Serve HTTPS with a certificate signed by the test CA
+    async fn spawn_private_ca_server() -> SocketAddr {
+        let identity =
+            native_tls::Identity::from_pkcs8(SERVER_CERT.as_bytes(), SERVER_KEY.as_bytes())
+                .unwrap();
+        let acceptor =
+            tokio_native_tls::TlsAcceptor::from(native_tls::TlsAcceptor::new(identity).unwrap());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let acceptor = acceptor.clone();
+                tokio::spawn(async move {
+                    // Handshakes from clients that don't trust the CA fail here
+                    let Ok(stream) = acceptor.accept(stream).await else {
+                        return;
+                    };
+                    let service = hyper::service::service_fn(
+                        |_req: hyper::Request<hyper::body::Incoming>| async {
+                            Ok::<_, std::convert::Infallible>(hyper::Response::new(
+                                axum::body::Body::from(r#"{"success": true}"#),
+                            ))
+                        },
+                    );
+                    let _ = hyper::server::conn::http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_provider_behind_private_ca() {
+        let addr = spawn_private_ca_server().await;
+        let url: url::Url = format!("https://127.0.0.1:{}/v1/", addr.port())
+            .parse()
+            .unwrap();
+        let tls = TlsConfig {
+            ca_bundle: Some(CA.to_string()),
+            client_cert: None,
+            client_key: None,
+        };
+
+        let targets_map = Arc::new(DashMap::new());
+        targets_map.insert(
+            "private".to_string(),
+            Target::builder()
+                .url(url.clone())
+                .tls(tls)
+                .build()
+                .into_pool(),
+        );
+        targets_map.insert(
+            "untrusted".to_string(),
+            Target::builder().url(url).build().into_pool(),
+        );
+        let targets = Targets {
+            targets: targets_map,
+            key_rate_limiters: Arc::new(DashMap::new()),
+            key_concurrency_limiters: Arc::new(DashMap::new()),
+            key_labels: Arc::new(DashMap::new()),
+            strict_mode: false,
+            http_pool_config: None,
+        };
+        let server = TestServer::new(build_router(AppState::new(targets))).unwrap();
+
+        // Only the target that trusts the CA completes the handshake
+        for (model, status) in [("private", 200), ("untrusted", 502)] {
+            let response = server
+                .post("/v1/chat/completions")
+                .json(&json!({"model": model, "messages": []}))
+                .await;
+            assert_eq!(response.status_code(), status, "model {model}");
+        }
+    }
+
+    #[test]
+    fn test_connector_loads_client_identity() {
+        let tls = TlsConfig {
+            ca_bundle: None,
+            client_cert: Some(SERVER_CERT.to_string()),
+            client_key: Some(SERVER_KEY.to_string()),
+        };
+        assert!(tls.connector().is_ok());
+
+        let invalid_key = TlsConfig {
+            client_key: Some("not a key".to_string()),
+            ..tls
+        };
+        assert!(invalid_key.connector().is_err());
+    }
+}
@@ -0,0 +1,599 @@
+//! Translation between legacy function calling and tool calling.
+//!
+//! Chat completions have two shapes for function calling: the legacy
+//! `functions`/`function_call` fields and the current `tools`/`tool_choice`
+//! fields. Some providers accept only one of them. When a provider's
+//! [`BodyTransformConfig`](crate::body_transform::BodyTransformConfig) sets
+//! `tool_calling`, requests in the other shape are translated before they are
+//! forwarded, and the response (JSON or SSE) is translated back so the client
+//! sees the shape it sent.
+//!
+//! Legacy function calling allows at most one call per assistant turn, so
+//! requests translated to tools set `parallel_tool_calls: false`, and a tools
+//! conversation with several calls in one turn can't be sent to a provider
+//! that only accepts functions.
+
+use std::collections::HashMap;
+
+use axum::body::{Body, Bytes};
+use axum::http::{HeaderValue, Response, header};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::body_transform::BodyTransformError;
+use crate::response_id::decode_body;
+use crate::sse::SseBufferedStream;
+
+/// The function calling shape of a chat completions request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCallingStyle {
+    /// `tools`, `tool_choice`, assistant `tool_calls` and `tool` messages
+    Tools,
+    /// `functions`, `function_call`, assistant `function_call` and `function` messages
+    Functions,
+}
+
+/// The function calling shape a chat completions body uses, if any. Bodies
+/// without `messages` (e.g. Responses API requests) have no style.
+pub fn request_style(body: &Value) -> Option<ToolCallingStyle> {
+    let object = body.as_object()?;
+    let messages = object.get("messages")?.as_array()?;
+    let has_role = |role: &str| {
+        messages
+            .iter()
+            .any(|message| message.get("role").and_then(Value::as_str) == Some(role))
+    };
+    let has_field = |field: &str| messages.iter().any(|message| message.get(field).is_some());
+
+    if object.contains_key("functions")
+        || object.contains_key("function_call")
+        || has_field("function_call")
+        || has_role("function")
+    {
+        Some(ToolCallingStyle::Functions)
+    } else if object.contains_key("tools")
+        || object.contains_key("tool_choice")
+        || has_field("tool_calls")
+        || has_role("tool")
+    {
+        Some(ToolCallingStyle::Tools)
+    } else {
+        None
+    }
+}
+
+/// Rewrite a chat completions request body into the `to` shape.
+pub fn translate_request(body: &mut Value, to: ToolCallingStyle) -> Result<(), BodyTransformError> {
+    let Some(object) = body.as_object_mut() else {
+        return Ok(());
+    };
+    match to {
+        ToolCallingStyle::Tools => functions_to_tools(object),
+        ToolCallingStyle::Functions => tools_to_functions(object),
+    }
+}
+
+fn functions_to_tools(object: &mut Map<String, Value>) -> Result<(), BodyTransformError> {
+    if object.contains_key("tools") || object.contains_key("tool_choice") {
+        return Err(BodyTransformError::new(
+            "requests must not mix 'functions' with 'tools'",
+        ));
+    }
+
+    if let Some(functions) = object.remove("functions") {
+        let Value::Array(functions) = functions else {
+            return Err(BodyTransformError::new("'functions' is not an array"));
+        };
+        let tools = functions
+            .into_iter()
+            .map(|function| json!({ "type": "function", "function": function }))
+            .collect();
+        object.insert("tools".to_string(), Value::Array(tools));
+        object
+            .entry("parallel_tool_calls")
+            .or_insert(Value::Bool(false));
+    }
+
+    if let Some(function_call) = object.remove("function_call") {
+        let tool_choice = match function_call {
+            Value::String(mode) => Value::String(mode),
+            Value::Object(named) => match named.get("name") {
+                Some(name) => json!({ "type": "function", "function": { "name": name } }),
+                None => {
+                    return Err(BodyTransformError::new(
+                        "'function_call' must name a function",
+                    ));
+                }
+            },
+            _ => {
+                return Err(BodyTransformError::new(
+                    "'function_call' must be a string or an object",
+                ));
+            }
+        };
+        object.insert("tool_choice".to_string(), tool_choice);
+    }
+
+    // Legacy history has no call ids, so each assistant call gets one and the
+    // following function result points at the latest call of that name
+    let mut call_ids: HashMap<String, String> = HashMap::new();
+    for (index, message) in messages_mut(object)?.iter_mut().enumerate() {
+        let Some(message) = message.as_object_mut() else {
+            continue;
+        };
+        if let Some(call) = message.remove("function_call") {
+            let id = format!("call_{index}");
+            if let Some(name) = call.get("name").and_then(Value::as_str) {
+                call_ids.insert(name.to_string(), id.clone());
+            }
+            message.insert(
+                "tool_calls".to_string(),
+                json!([{ "id": id, "type": "function", "function": call }]),
+            );
+        } else if message.get("role").and_then(Value::as_str) == Some("function") {
+            let name = message
+                .remove("name")
+                .and_then(|name| name.as_str().map(str::to_string))
+                .ok_or_else(|| BodyTransformError::new("function messages must have a 'name'"))?;
+            let id = call_ids.get(&name).ok_or_else(|| {
+                BodyTransformError::new(format!(
+                    "function message '{name}' does not follow a call to that function"
+                ))
+            })?;
+            message.insert("role".to_string(), Value::String("tool".to_string()));
+            message.insert("tool_call_id".to_string(), Value::String(id.clone()));
+        }
+    }
+    Ok(())
+}
+
+fn tools_to_functions(object: &mut Map<String, Value>) -> Result<(), BodyTransformError> {
+    if let Some(tools) = object.remove("tools") {
+        let Value::Array(tools) = tools else {
+            return Err(BodyTransformError::new("'tools' is not an array"));
+        };
+        let functions = tools
+            .into_iter()
+            .map(|tool| match tool {
+                Value::Object(mut tool)
+                    if tool.get("type").and_then(Value::as_str) == Some("function") =>
+                {
+                    tool.remove("function").ok_or_else(|| {
+                        BodyTransformError::new("function tools must have a 'function'")
+                    })
+                }
+                _ => Err(BodyTransformError::new(
+                    "only function tools can be sent as legacy functions",
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        object.insert("functions".to_string(), Value::Array(functions));
+    }
+    object.remove("parallel_tool_calls");
+
+    if let Some(tool_choice) = object.remove("tool_choice") {
+        let function_call = match tool_choice {
+            Value::String(mode) if mode == "none" || mode == "auto" => Value::String(mode),
+            Value::Object(named) => match named.get("function").and_then(|f| f.get("name")) {
+                Some(name) => json!({ "name": name }),
+                None => {
+                    return Err(BodyTransformError::new(
+                        "'tool_choice' must name a function",
+                    ));
+                }
+            },
+            other => {
+                return Err(BodyTransformError::new(format!(
+                    "'tool_choice' {other} has no legacy 'function_call' equivalent"
+                )));
+            }
+        };
+        object.insert("function_call".to_string(), function_call);
+    }
+
+    let mut call_names: HashMap<String, Value> = HashMap::new();
+    for message in messages_mut(object)?.iter_mut() {
+        let Some(message) = message.as_object_mut() else {
+            continue;
+        };
+        if let Some(tool_calls) = message.remove("tool_calls") {
+            let Value::Array(mut tool_calls) = tool_calls else {
+                return Err(BodyTransformError::new("'tool_calls' is not an array"));
+            };
+            if tool_calls.len() > 1 {
+                return Err(BodyTransformError::new(
+                    "assistant messages with several tool calls cannot be sent as legacy function calls",
+                ));
+            }
+            if let Some(mut call) = tool_calls.pop() {
+                let function = call
+                    .get_mut("function")
+                    .map(Value::take)
+                    .ok_or_else(|| BodyTransformError::new("tool calls must have a 'function'"))?;
+                if let Some(id) = call.get("id").and_then(Value::as_str)
+                    && let Some(name) = function.get("name")
+                {
+                    call_names.insert(id.to_string(), name.clone());
+                }
+                message.insert("function_call".to_string(), function);
+            }
+        } else if message.get("role").and_then(Value::as_str) == Some("tool") {
+            let id = message
+                .remove("tool_call_id")
+                .and_then(|id| id.as_str().map(str::to_string))
+                .ok_or_else(|| {
+                    BodyTransformError::new("tool messages must have a 'tool_call_id'")
+                })?;
+            let name = call_names.get(&id).ok_or_else(|| {
+                BodyTransformError::new(format!(
+                    "tool message '{id}' does not answer an earlier tool call"
+                ))
+            })?;
+            message.insert("role".to_string(), Value::String("function".to_string()));
+            message.insert("name".to_string(), name.clone());
+        }
+    }
+    Ok(())
+}
+
+fn messages_mut(object: &mut Map<String, Value>) -> Result<&mut Vec<Value>, BodyTransformError> {
+    object
+        .get_mut("messages")
+        .and_then(Value::as_array_mut)
+        .ok_or_else(|| BodyTransformError::new("'messages' is not an array"))
+}
+
+/// Translate a 2xx chat completions response (JSON or SSE) from the provider's
+/// shape back to the client's shape `to`. Compressed JSON bodies are decoded
+/// and returned uncompressed; anything else passes through unchanged.
+pub async fn translate_response(response: &mut Response<Body>, to: ToolCallingStyle) {
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    let is_sse = content_type.contains("text/event-stream");
+    let is_json = content_type.contains("application/json");
+
+    if is_sse {
+        let body = std::mem::take(response.body_mut()).into_data_stream();
+        let translated = SseBufferedStream::new(body)
+            .map(move |chunk| chunk.map(|chunk| translate_sse_chunk(&chunk, to).unwrap_or(chunk)));
+        *response.body_mut() = Body::from_stream(translated);
+        return;
+    }
+
+    if !is_json {
+        return;
+    }
+
+    let content_encoding = response
+        .headers()
+        .get(header::CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_lowercase());
+    let bytes = match axum::body::to_bytes(std::mem::take(response.body_mut()), usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(_) => return,
+    };
+    let Some(decoded) = decode_body(&bytes, content_encoding.as_deref()) else {
+        debug!("Failed to decompress response for tool call translation, passing through");
+        *response.body_mut() = Body::from(bytes);
+        return;
+    };
+
+    match translate_json(&decoded, to) {
+        Some(translated) => {
+            let content_length = translated.len();
+            *response.body_mut() = Body::from(translated);
+            response.headers_mut().remove(header::CONTENT_ENCODING);
+            response.headers_mut().remove(header::TRANSFER_ENCODING);
+            response
+                .headers_mut()
+                .insert(header::CONTENT_LENGTH, HeaderValue::from(content_length));
+        }
+        None => *response.body_mut() = Body::from(bytes),
+    }
+}
+
+/// Translate the `message` (or streamed `delta`) of every choice. Returns
+/// whether anything changed.
+fn translate_value(value: &mut Value, to: ToolCallingStyle) -> bool {
+    let Some(choices) = value.get_mut("choices").and_then(Value::as_array_mut) else {
+        return false;
+    };
+    let mut changed = false;
+    for choice in choices.iter_mut().filter_map(Value::as_object_mut) {
+        for key in ["message", "delta"] {
+            if let Some(message) = choice.get_mut(key).and_then(Value::as_object_mut) {
+                changed |= translate_message(message, to, key == "delta");
+            }
+        }
+        let (from_reason, to_reason) = match to {
+            ToolCallingStyle::Tools => ("function_call", "tool_calls"),
+            ToolCallingStyle::Functions => ("tool_calls", "function_call"),
+        };
+        if let Some(reason) = choice.get_mut("finish_reason")
+            && reason.as_str() == Some(from_reason)
+        {
+            *reason = Value::String(to_reason.to_string());
+            changed = true;
+        }
+    }
+    changed
+}
+
+fn translate_message(message: &mut Map<String, Value>, to: ToolCallingStyle, delta: bool) -> bool {
+    match to {
+        ToolCallingStyle::Functions => {
+            if !matches!(message.get("tool_calls"), Some(Value::Array(_))) {
+                return false;
+            }
+            let Some(Value::Array(mut tool_calls)) = message.remove("tool_calls") else {
+                return false;
+            };
+            // Streamed deltas carry the call index; only the first call has a
+            // legacy equivalent
+            tool_calls.retain(|call| call.get("index").and_then(Value::as_u64).unwrap_or(0) == 0);
+            if tool_calls.len() > 1 {
+                warn!(
+                    count = tool_calls.len(),
+                    "Provider returned several tool calls for a legacy function call request, keeping the first"
+                );
+            }
+            if let Some(function) = tool_calls
+                .into_iter()
+                .next()
+                .and_then(|mut call| call.get_mut("function").map(Value::take))
+            {
+                message.insert("function_call".to_string(), function);
+            }
+            true
+        }
+        ToolCallingStyle::Tools => {
+            let Some(function) = message.remove("function_call") else {
+                return false;
+            };
+            let mut call = Map::new();
+            if delta {
+                call.insert("index".to_string(), Value::from(0));
+            }
+            // A streamed call is named in its first delta only, which is also
+            // where the id belongs
+            if !delta || function.get("name").is_some() {
+                call.insert(
+                    "id".to_string(),
+                    Value::String(format!("call_{}", Uuid::new_v4().simple())),
+                );
+                call.insert("type".to_string(), Value::String("function".to_string()));
+            }
+            call.insert("function".to_string(), function);
+            message.insert(
+                "tool_calls".to_string(),
+                Value::Array(vec![Value::Object(call)]),
+            );
+            true
+        }
+    }
+}
+
+fn translate_json(body: &[u8], to: ToolCallingStyle) -> Option<Vec<u8>> {
+    let mut value: Value = serde_json::from_slice(body).ok()?;
+    if !translate_value(&mut value, to) {
+        return None;
+    }
+    serde_json::to_vec(&value).ok()
+}
+
+/// Translate every `data:` line of one or more complete SSE events. Lines that
+/// are not JSON (e.g. `[DONE]`) are preserved byte for byte. Returns `None`
+/// when nothing changed.
+fn translate_sse_chunk(chunk: &[u8], to: ToolCallingStyle) -> Option<Bytes> {
+    let text = std::str::from_utf8(chunk).ok()?;
+    let mut changed = false;
+    let lines: Vec<String> = text
+        .split('\n')
+        .map(|line| {
+            let (content, cr) = match line.strip_suffix('\r') {
+                Some(content) => (content, "\r"),
+                None => (line, ""),
+            };
+            let Some(data) = content.strip_prefix("data:") else {
+                return line.to_string();
+            };
+            match translate_json(data.trim_start().as_bytes(), to) {
+                Some(translated) => {
+                    changed = true;
+                    format!("data: {}{cr}", String::from_utf8_lossy(&translated))
+                }
+                None => line.to_string(),
+            }
+        })
+        .collect();
+
+    changed.then(|| Bytes::from(lines.join("\n")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn legacy_request() -> Value {
+        json!({
+            "model": "m",
+            "messages": [
+                { "role": "user", "content": "Weather in Paris?" },
+                { "role": "assistant", "content": null, "function_call": { "name": "get_weather", "arguments": "{\"city\":\"Paris\"}" } },
+                { "role": "function", "name": "get_weather", "content": "18C" }
+            ],
+            "functions": [{ "name": "get_weather", "parameters": { "type": "object" } }],
+            "function_call": { "name": "get_weather" }
+        })
+    }
+
+    #[test]
+    fn test_request_style_detection() {
+        assert_eq!(
+            request_style(&legacy_request()),
+            Some(ToolCallingStyle::Functions)
+        );
+        assert_eq!(
+            request_style(&json!({ "messages": [], "tools": [] })),
+            Some(ToolCallingStyle::Tools)
+        );
+        assert_eq!(
+            request_style(&json!({ "messages": [{ "role": "user", "content": "hi" }] })),
+            None
+        );
+        // Responses API bodies are never translated
+        assert_eq!(request_style(&json!({ "input": "hi", "tools": [] })), None);
+    }
+
+    #[test]
+    fn test_functions_request_translates_to_tools() {
+        let mut body = legacy_request();
+        translate_request(&mut body, ToolCallingStyle::Tools).unwrap();
+
+        assert_eq!(
+            body,
+            json!({
+                "model": "m",
+                "messages": [
+                    { "role": "user", "content": "Weather in Paris?" },
+                    { "role": "assistant", "content": null, "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": { "name": "get_weather", "arguments": "{\"city\":\"Paris\"}" }
+                    }] },
+                    { "role": "tool", "tool_call_id": "call_1", "content": "18C" }
+                ],
+                "tools": [{ "type": "function", "function": { "name": "get_weather", "parameters": { "type": "object" } } }],
+                "tool_choice": { "type": "function", "function": { "name": "get_weather" } },
+                "parallel_tool_calls": false
+            })
+        );
+    }
+
+    #[test]
+    fn test_tools_request_round_trips_through_functions() {
+        let mut body = legacy_request();
+        translate_request(&mut body, ToolCallingStyle::Tools).unwrap();
+        translate_request(&mut body, ToolCallingStyle::Functions).unwrap();
+
+        assert_eq!(body, legacy_request());
+    }
+
+    #[test]
+    fn test_untranslatable_requests_are_errors() {
+        let mut body = json!({
+            "messages": [],
+            "tools": [{ "type": "web_search" }]
+        });
+        let error = translate_request(&mut body, ToolCallingStyle::Functions).unwrap_err();
+        assert!(error.message().contains("only function tools"));
+
+        let mut body = json!({ "messages": [], "tools": [], "tool_choice": "required" });
+        let error = translate_request(&mut body, ToolCallingStyle::Functions).unwrap_err();
+        assert!(error.message().contains("no legacy"));
+
+        let mut body = json!({
+            "messages": [{ "role": "assistant", "tool_calls": [
+                { "id": "a", "type": "function", "function": { "name": "f", "arguments": "{}" } },
+                { "id": "b", "type": "function", "function": { "name": "g", "arguments": "{}" } }
+            ] }]
+        });
+        let error = translate_request(&mut body, ToolCallingStyle::Functions).unwrap_err();
+        assert!(error.message().contains("several tool calls"));
+
+        let mut body = json!({ "messages": [{ "role": "function", "name": "f", "content": "x" }] });
+        let error = translate_request(&mut body, ToolCallingStyle::Tools).unwrap_err();
+        assert!(error.message().contains("does not follow a call"));
+    }
+
+    #[test]
+    fn test_tool_call_response_translates_to_function_call() {
+        let mut body = json!({
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": null, "tool_calls": [{
+                    "id": "call_x", "type": "function",
+                    "function": { "name": "get_weather", "arguments": "{}" }
+                }] },
+                "finish_reason": "tool_calls"
+            }]
+        });
+
+        assert!(translate_value(&mut body, ToolCallingStyle::Functions));
+        assert_eq!(
+            body["choices"][0],
+            json!({
+                "index": 0,
+                "message": { "role": "assistant", "content": null, "function_call": { "name": "get_weather", "arguments": "{}" } },
+                "finish_reason": "function_call"
+            })
+        );
+    }
+
+    #[test]
+    fn test_function_call_response_translates_to_tool_calls() {
+        let mut body = json!({
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "function_call": { "name": "get_weather", "arguments": "{}" } },
+                "finish_reason": "function_call"
+            }]
+        });
+
+        assert!(translate_value(&mut body, ToolCallingStyle::Tools));
+        let call = &body["choices"][0]["message"]["tool_calls"][0];
+        assert!(call["id"].as_str().unwrap().starts_with("call_"));
+        assert_eq!(call["type"], "function");
+        assert_eq!(call["function"]["name"], "get_weather");
+        assert!(body["choices"][0]["message"].get("function_call").is_none());
+        assert_eq!(body["choices"][0]["finish_reason"], "tool_calls");
+
+        let mut plain =
+            json!({ "choices": [{ "message": { "content": "hi" }, "finish_reason": "stop" }] });
+        assert!(!translate_value(&mut plain, ToolCallingStyle::Tools));
+    }
+
+    #[test]
+    fn test_streamed_deltas_are_translated() {
+        let chunk = b"data: {\"choices\":[{\"index\":0,\"delta\":{\"function_call\":{\"name\":\"f\",\"arguments\":\"\"}}}]}\n\ndata: {\"choices\":[{\"index\":0,\"delta\":{\"function_call\":{\"arguments\":\"{}\"}}}]}\n\ndata: [DONE]\n\n";
+        let translated = translate_sse_chunk(chunk, ToolCallingStyle::Tools).unwrap();
+        let text = std::str::from_utf8(&translated).unwrap();
+        let events: Vec<Value> = text
+            .lines()
+            .filter_map(|line| line.strip_prefix("data: "))
+            .filter_map(|data| serde_json::from_str(data).ok())
+            .collect();
+
+        let first = &events[0]["choices"][0]["delta"]["tool_calls"][0];
+        assert_eq!(first["index"], 0);
+        assert_eq!(first["function"]["name"], "f");
+        assert!(first["id"].is_string());
+        let second = &events[1]["choices"][0]["delta"]["tool_calls"][0];
+        assert_eq!(second["function"]["arguments"], "{}");
+        assert!(second.get("id").is_none());
+        assert!(text.ends_with("data: [DONE]\n\n"));
+
+        let chunk = b"data: {\"choices\":[{\"index\":0,\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\"{}\"}}]},\"finish_reason\":\"tool_calls\"}]}\n\n";
+        let translated = translate_sse_chunk(chunk, ToolCallingStyle::Functions).unwrap();
+        let data: Value = serde_json::from_str(
+            std::str::from_utf8(&translated)
+                .unwrap()
+                .trim()
+                .strip_prefix("data: ")
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            data["choices"][0],
+            json!({ "index": 0, "delta": { "function_call": { "arguments": "{}" } }, "finish_reason": "function_call" })
+        );
+    }
+}
@@ -28,7 +28,9 @@
 -- again - a clean ledger makes every statement a no-op.
 --
 -- Keep this script around: re-running step 1 is also the way to reconcile
--- balances after any manual surgery on credits_transactions.
+-- balances after any manual surgery on credits_transactions. The leader's
+-- balance_checkpoints job (background_services.balance_checkpoints) heals
+-- users with recent ledger activity automatically, but never the rest.
 --
 -- HOW TO RUN: interactively in psql, statement by statement - NOT as a
 -- single "psql -f" pass. Step 2's batch statement must be repeated until it